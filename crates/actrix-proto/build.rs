@@ -4,7 +4,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // - supervisor.proto: SupervisorService (Node calls Supervisor)
     // - supervised.proto: SupervisedService (Supervisor calls Node)
     // - keyserver.proto: KeyServer service (imports common.proto)
+    // - authz.proto: SignalingAuthz service (Signaling calls external policy engine)
+    // 同时生成 FileDescriptorSet，供 gRPC 反射服务使用
+    let descriptor_path =
+        std::path::PathBuf::from(std::env::var("OUT_DIR")?).join("actrix_descriptor.bin");
+
     tonic_prost_build::configure()
+        .file_descriptor_set_path(descriptor_path)
         .build_server(true)
         .build_client(true)
         .compile_protos(
@@ -13,6 +19,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/supervisor.proto",
                 "proto/supervised.proto",
                 "proto/keyserver.proto",
+                "proto/authz.proto",
             ],
             &["proto/"],
         )?;
@@ -22,6 +29,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=proto/supervisor.proto");
     println!("cargo:rerun-if-changed=proto/supervised.proto");
     println!("cargo:rerun-if-changed=proto/keyserver.proto");
+    println!("cargo:rerun-if-changed=proto/authz.proto");
 
     Ok(())
 }
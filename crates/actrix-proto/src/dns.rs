@@ -0,0 +1,187 @@
+//! DNS re-resolution for dependency endpoints.
+//!
+//! Clients that talk to KS, AIS or the supervisor usually connect once and then keep
+//! the HTTP/2 (or pooled HTTP/1) connection for the lifetime of the process, so a
+//! failover performed by updating DNS records never reaches them while the old
+//! address is still reachable. [`DnsWatch`] re-resolves the endpoint host in the
+//! background, honouring the record TTL (clamped to
+//! [`MIN_REFRESH_INTERVAL`]..=[`MAX_REFRESH_INTERVAL`]), and lets the client find out
+//! when the resolved address set changed so it can rebuild its connection.
+//!
+//! Endpoints whose host is an IP literal have nothing to re-resolve and get no watch.
+//!
+//! ```ignore
+//! let mut dns = DnsWatch::spawn("https://ks.example.com:50052");
+//! // before each request
+//! if dns.as_mut().is_some_and(|dns| dns.take_change()) {
+//!     // reconnect
+//! }
+//! ```
+
+use hickory_resolver::TokioAsyncResolver;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Lower bound for the re-resolution interval (also used after a failed lookup).
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Upper bound for the re-resolution interval, regardless of the record TTL.
+pub const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Background re-resolution of an endpoint host.
+///
+/// The resolution task stops when the watch is dropped.
+#[derive(Debug)]
+pub struct DnsWatch {
+    host: String,
+    addresses: watch::Receiver<Vec<IpAddr>>,
+    task: JoinHandle<()>,
+}
+
+impl DnsWatch {
+    /// Starts watching the host of `endpoint` (a URI such as `https://ks:50052`).
+    ///
+    /// Returns `None` when the host is an IP literal or the endpoint cannot be parsed.
+    /// Must be called within a Tokio runtime.
+    pub fn spawn(endpoint: &str) -> Option<Self> {
+        let host = endpoint_host(endpoint)?;
+
+        let resolver = match TokioAsyncResolver::tokio_from_system_conf() {
+            Ok(resolver) => resolver,
+            Err(e) => {
+                warn!(
+                    "Failed to load system DNS configuration, using defaults: {}",
+                    e
+                );
+                TokioAsyncResolver::tokio(Default::default(), Default::default())
+            }
+        };
+
+        let (tx, addresses) = watch::channel(Vec::new());
+        let task = tokio::spawn(resolve_loop(resolver, host.clone(), tx));
+        debug!("Watching DNS for dependency host {}", host);
+
+        Some(Self {
+            host,
+            addresses,
+            task,
+        })
+    }
+
+    /// The watched host name.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The most recently resolved addresses (empty until the first lookup succeeds).
+    pub fn addresses(&self) -> Vec<IpAddr> {
+        self.addresses.borrow().clone()
+    }
+
+    /// Returns `true` once per change of the resolved address set.
+    ///
+    /// The first successful lookup is the baseline and is not reported as a change,
+    /// since the client's initial connection already used the current records.
+    pub fn take_change(&mut self) -> bool {
+        if !self.addresses.has_changed().unwrap_or(false) {
+            return false;
+        }
+        self.addresses.borrow_and_update();
+        true
+    }
+}
+
+impl Drop for DnsWatch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Extracts the host of `endpoint`, or `None` for IP literals and unparsable URIs.
+fn endpoint_host(endpoint: &str) -> Option<String> {
+    let uri: tonic::codegen::http::Uri = endpoint.parse().ok()?;
+    let host = uri.host()?.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() || host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    Some(host.to_string())
+}
+
+/// Time until the next lookup for a record valid until `valid_until`.
+fn refresh_interval(valid_until: Instant, now: Instant) -> Duration {
+    valid_until
+        .saturating_duration_since(now)
+        .clamp(MIN_REFRESH_INTERVAL, MAX_REFRESH_INTERVAL)
+}
+
+async fn resolve_loop(resolver: TokioAsyncResolver, host: String, tx: watch::Sender<Vec<IpAddr>>) {
+    loop {
+        let next = match resolver.lookup_ip(host.as_str()).await {
+            Ok(lookup) => {
+                let mut resolved: Vec<IpAddr> = lookup.iter().collect();
+                resolved.sort();
+                resolved.dedup();
+
+                tx.send_if_modified(|current| {
+                    if *current == resolved || resolved.is_empty() {
+                        return false;
+                    }
+                    let baseline = current.is_empty();
+                    if !baseline {
+                        info!("DNS for {} changed: {:?} -> {:?}", host, current, resolved);
+                    }
+                    *current = resolved;
+                    !baseline
+                });
+
+                refresh_interval(lookup.valid_until(), Instant::now())
+            }
+            Err(e) => {
+                debug!("DNS lookup for {} failed: {}", host, e);
+                MIN_REFRESH_INTERVAL
+            }
+        };
+
+        if tx.is_closed() {
+            break;
+        }
+        tokio::time::sleep(next).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_host() {
+        assert_eq!(
+            endpoint_host("https://ks.example.com:50052").as_deref(),
+            Some("ks.example.com")
+        );
+        assert_eq!(
+            endpoint_host("http://localhost:8443").as_deref(),
+            Some("localhost")
+        );
+        assert_eq!(endpoint_host("http://127.0.0.1:50052"), None);
+        assert_eq!(endpoint_host("http://[::1]:50052"), None);
+        assert_eq!(endpoint_host("not a uri"), None);
+    }
+
+    #[test]
+    fn test_refresh_interval_respects_ttl_within_bounds() {
+        let now = Instant::now();
+        assert_eq!(
+            refresh_interval(now + Duration::from_secs(60), now),
+            Duration::from_secs(60)
+        );
+        assert_eq!(refresh_interval(now, now), MIN_REFRESH_INTERVAL);
+        assert_eq!(
+            refresh_interval(now + Duration::from_secs(86400), now),
+            MAX_REFRESH_INTERVAL
+        );
+    }
+}
@@ -6,6 +6,8 @@
 //!
 //! - [`supervisor::v1`]: Supervisor service definitions (SupervisorService and SupervisedService)
 //! - [`ks::v1`]: Key Server service definitions
+//! - [`authz::v1`]: External signaling authorization service definitions
+//! - [`dns`]: DNS re-resolution for dependency endpoints shared by the service clients
 //!
 //! # Usage
 //!
@@ -54,24 +56,48 @@ pub mod ks {
     }
 }
 
+/// External signaling authorization protocol definitions.
+///
+/// Contains `SignalingAuthz` service, implemented by an external policy engine
+/// and called by the signaling server on register, discovery and relay.
+pub mod authz {
+    pub mod v1 {
+        tonic::include_proto!("authz.v1");
+    }
+}
+
+pub mod dns;
+
+/// Encoded `FileDescriptorSet` of all Actrix protos, registered with gRPC reflection.
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("actrix_descriptor");
+
 // ============================================================================
 // Re-exports: Common Types (from supervisor.v1)
 // ============================================================================
 
 pub use supervisor::v1::{
     // Enums
+    ConfigChange,
     ConfigType,
     // Shared message types
     Directive,
     DirectiveType,
     // Authentication
+    NodeCapabilities,
     NonceCredential,
     RealmInfo,
+    RealmQuotaInfo,
+    RealmRateLimitInfo,
+    RealmUsage,
     ResourceType,
     ServiceAdvertisement,
     ServiceAdvertisementStatus,
+    ServiceHealth,
     ServiceStatus,
     SystemMetrics,
+    UdpSocketStats,
+    UpdateBundle,
+    UpdateBundleKind,
 };
 
 // ============================================================================
@@ -82,12 +108,15 @@ pub use supervisor::v1::{
     // Health check (aliased to avoid collision with ks::v1)
     HealthCheckRequest as SupervisorHealthCheckRequest,
     HealthCheckResponse as SupervisorHealthCheckResponse,
+    // Reporting
+    RealmLifecycleEvent,
     // Registration
     RegisterNodeRequest,
     RegisterNodeResponse,
-    // Reporting
     ReportRequest,
     ReportResponse,
+    // Directive stream
+    WatchDirectivesRequest,
     // Client and server
     supervisor_service_client::SupervisorServiceClient,
     supervisor_service_server::{SupervisorService, SupervisorServiceServer},
@@ -98,11 +127,21 @@ pub use supervisor::v1::{
 // ============================================================================
 
 pub use supervisor::v1::{
+    // Signaling connection management
+    BroadcastServerNoticeRequest,
+    BroadcastServerNoticeResponse,
+    ConnectedActor,
+    ConnectedService,
     // Realm management
+    CreateRealmApiKeyRequest,
+    CreateRealmApiKeyResponse,
     CreateRealmRequest,
     CreateRealmResponse,
     DeleteRealmRequest,
     DeleteRealmResponse,
+    DisconnectActorRequest,
+    DisconnectActorResponse,
+    DryRunReport,
     // Configuration management
     GetConfigRequest,
     GetConfigResponse,
@@ -111,10 +150,26 @@ pub use supervisor::v1::{
     GetNodeInfoResponse,
     GetRealmRequest,
     GetRealmResponse,
+    // Service registry
+    GetServiceSpecHistoryRequest,
+    GetServiceSpecHistoryResponse,
+    ListConnectionsRequest,
+    ListConnectionsResponse,
+    ListRealmApiKeysRequest,
+    ListRealmApiKeysResponse,
     ListRealmsRequest,
     ListRealmsResponse,
+    // Diagnostics
+    LogEntry,
+    RealmApiKeyInfo,
+    RevokeRealmApiKeyRequest,
+    RevokeRealmApiKeyResponse,
+    ServiceSpecVersion,
+    SetServiceEnabledRequest,
+    SetServiceEnabledResponse,
     ShutdownRequest,
     ShutdownResponse,
+    StreamLogsRequest,
     UpdateConfigRequest,
     UpdateConfigResponse,
     UpdateRealmRequest,
@@ -141,3 +196,15 @@ pub use ks::v1::{
 // here because the ks crate defines its own native Rust types with the same
 // names for HTTP/JSON API usage. For gRPC usage, access them via:
 //   use actrix_proto::ks::v1::{GenerateKeyRequest, ...};
+
+// ============================================================================
+// Re-exports: SignalingAuthz Service
+// ============================================================================
+
+pub use authz::v1::{
+    AuthorizeRequest,
+    AuthorizeResponse,
+    // Client and server
+    signaling_authz_client::SignalingAuthzClient,
+    signaling_authz_server::{SignalingAuthz, SignalingAuthzServer},
+};
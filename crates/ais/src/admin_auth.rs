@@ -0,0 +1,71 @@
+//! AIS 管理端点认证
+//!
+//! `/revoke`、`/reserve-batch` 等管理端点与 KS 相同，使用 `actrix_shared_key` 签名的
+//! nonce 凭证认证，每个端点使用各自的签名 payload 并共享防重放存储。
+//! GET 端点通过 `credential` 查询参数传递 JSON 编码的凭证。
+
+use axum::http::StatusCode;
+use nonce_auth::{CredentialVerifier, NonceCredential, NonceError, storage::NonceStorage};
+use std::sync::Arc;
+
+/// 管理端点认证器
+#[derive(Clone)]
+pub struct AdminAuth {
+    nonce_storage: Arc<dyn NonceStorage + Send + Sync>,
+    psk: String,
+}
+
+impl AdminAuth {
+    pub fn new(nonce_storage: Arc<dyn NonceStorage + Send + Sync>, psk: impl Into<String>) -> Self {
+        Self {
+            nonce_storage,
+            psk: psk.into(),
+        }
+    }
+
+    /// 解析 `credential` 查询参数中 JSON 编码的凭证
+    pub fn parse_query_credential(
+        credential: Option<&str>,
+    ) -> Result<NonceCredential, (StatusCode, String)> {
+        let credential = credential.ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                "Missing credential query parameter".to_string(),
+            )
+        })?;
+        serde_json::from_str(credential).map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                "Invalid credential query parameter".to_string(),
+            )
+        })
+    }
+
+    /// 验证凭证，失败时返回 HTTP 状态码与错误信息
+    pub async fn verify(
+        &self,
+        credential: &NonceCredential,
+        request_payload: &str,
+    ) -> Result<(), (StatusCode, String)> {
+        CredentialVerifier::new(self.nonce_storage.clone())
+            .with_secret(self.psk.as_bytes())
+            .verify(credential, request_payload.as_bytes())
+            .await
+            .map_err(|e| match e {
+                NonceError::DuplicateNonce => {
+                    (StatusCode::UNAUTHORIZED, "Nonce already used".to_string())
+                }
+                NonceError::TimestampOutOfWindow => (
+                    StatusCode::UNAUTHORIZED,
+                    "Request timestamp out of range".to_string(),
+                ),
+                NonceError::InvalidSignature => {
+                    (StatusCode::UNAUTHORIZED, "Invalid signature".to_string())
+                }
+                e => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Authentication error: {e}"),
+                ),
+            })
+    }
+}
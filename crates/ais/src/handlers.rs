@@ -1,40 +1,146 @@
 //! AIS (Actor Identity Service) HTTP Handler
 
-use crate::{issuer::AIdIssuer, ratelimit::ip_rate_limiter};
+use crate::{
+    admin_auth::AdminAuth,
+    issuer::AIdIssuer,
+    psk_rotation::{PskRotationState, create_psk_rotation_router},
+    ratelimit::{RegisterRateLimiter, ip_rate_limiter},
+    revocation::{RevocationState, create_revocation_router},
+    turn_credential::{TurnCredentialState, create_turn_credential_router},
+};
 use actr_protocol::{ErrorResponse, RegisterRequest, RegisterResponse, register_response};
 use actrix_common::aid::AidError;
-use axum::{Router, body::Bytes, extract::State, response::Json, routing::post};
+use actrix_common::realm::Realm as RealmEntity;
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    response::Json,
+    routing::post,
+};
+use nonce_auth::NonceCredential;
 use prost::Message;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::sync::Arc;
-use tracing::{debug, error};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, warn};
+
+/// 单次批量预留序列号的上限
+pub const MAX_RESERVE_BATCH_SIZE: u64 = 10_000;
+
+/// 批量预留请求的签名 payload
+pub fn reserve_batch_payload(realm_id: u32, count: u64) -> String {
+    format!("reserve_batch:{realm_id}:{count}")
+}
+
+/// `POST /ais/reserve-batch` 请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReserveBatchRequest {
+    /// Realm ID
+    pub realm_id: u32,
+    /// 预留个数（1 ~ [`MAX_RESERVE_BATCH_SIZE`]）
+    pub count: u64,
+    /// 备注（如批次号、产线）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// 签名凭证，payload 为 [`reserve_batch_payload`]
+    pub credential: NonceCredential,
+}
+
+/// `/ais/stats` 默认统计天数
+pub const DEFAULT_STATS_DAYS: u64 = 7;
+
+/// `/ais/stats` 最多统计天数
+pub const MAX_STATS_DAYS: u64 = 90;
+
+/// `/ais/actors` 默认返回条数
+pub const DEFAULT_ACTORS_LIMIT: u32 = 100;
+
+/// `/ais/actors` 最多返回条数
+pub const MAX_ACTORS_LIMIT: u32 = 1000;
+
+/// 统计查询的签名 payload
+pub const STATS_PAYLOAD: &str = "stats";
+
+/// Actor 列表查询的签名 payload
+pub fn list_actors_payload(realm_id: u32) -> String {
+    format!("list_actors:{realm_id}")
+}
+
+/// `GET /ais/stats` 查询参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatsQuery {
+    /// 统计最近几天（含今天，UTC），默认 [`DEFAULT_STATS_DAYS`]
+    pub days: Option<u64>,
+    /// JSON 编码的签名凭证，payload 为 [`STATS_PAYLOAD`]
+    pub credential: Option<String>,
+}
+
+/// `GET /ais/actors` 查询参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActorsQuery {
+    /// Realm ID
+    pub realm_id: u32,
+    /// 返回条数，默认 [`DEFAULT_ACTORS_LIMIT`]
+    pub limit: Option<u32>,
+    /// JSON 编码的签名凭证，payload 为 [`list_actors_payload`]
+    pub credential: Option<String>,
+}
 
 /// AIS 服务状态
 #[derive(Clone)]
 pub struct AISState {
     pub issuer: Arc<AIdIssuer>,
+    /// 管理端点认证
+    pub auth: AdminAuth,
+    /// 注册请求的租户级限流
+    pub register_limiter: Arc<RegisterRateLimiter>,
 }
 
 impl AISState {
-    pub fn new(issuer: AIdIssuer) -> Self {
+    pub fn new(issuer: AIdIssuer, auth: AdminAuth) -> Self {
         Self {
             issuer: Arc::new(issuer),
+            auth,
+            register_limiter: Arc::new(RegisterRateLimiter::default()),
         }
     }
+
+    /// 设置注册请求的租户级限流器
+    pub fn with_register_limiter(mut self, register_limiter: RegisterRateLimiter) -> Self {
+        self.register_limiter = Arc::new(register_limiter);
+        self
+    }
 }
 
-/// 创建 AIS 服务的路由
+/// 创建 AIS 服务的路由（含凭证吊销、PSK 轮替与 TURN 凭证换取端点）
 ///
 /// 应用限流中间件：
 /// - IP 级别：100 req/min（防止单个 IP 的 DoS 攻击）
-pub fn create_router(state: AISState) -> Router {
+///
+/// `/register` 另按 Realm 与厂商限流（见 [`RegisterRateLimiter`]）
+pub fn create_router(
+    state: AISState,
+    revocation: RevocationState,
+    psk_rotation: PskRotationState,
+    turn_credential: TurnCredentialState,
+) -> Router {
     Router::new()
         .route("/register", post(register_actr))
+        .route("/reserve-batch", post(reserve_batch))
         .route("/health", axum::routing::get(health_check))
         .route("/rotate-key", post(rotate_key))
+        .route("/rotate-signing-key", post(rotate_signing_key))
         .route("/current-key", axum::routing::get(get_current_key))
-        .layer(ip_rate_limiter())
+        .route("/stats", axum::routing::get(get_stats))
+        .route("/actors", axum::routing::get(list_actors))
         .with_state(state)
+        .merge(create_revocation_router(revocation))
+        .merge(create_psk_rotation_router(psk_rotation))
+        .merge(create_turn_credential_router(turn_credential))
+        .layer(ip_rate_limiter())
 }
 
 /// ActrId 注册处理器 - 严格按照 proto 定义返回 RegisterResponse
@@ -60,6 +166,45 @@ async fn register_actr(State(state): State<AISState>, body: Bytes) -> Bytes {
         request.realm.realm_id, request.actr_type.manufacturer, request.actr_type.name
     );
 
+    // 已停用、终止或到期的 Realm 不再签发凭证
+    if let Err(message) = RealmEntity::validate_realm(request.realm.realm_id).await {
+        warn!("Rejected register request: {}", message);
+        return encode_result(RegisterResponse {
+            result: Some(register_response::Result::Error(ErrorResponse {
+                code: 403, // Forbidden
+                message: format!("Realm validation failed: {message}"),
+            })),
+        });
+    }
+
+    // 租户级限流：一个租户的批量开通不影响其他租户
+    if let Err(message) = state
+        .register_limiter
+        .check(request.realm.realm_id, &request.actr_type.manufacturer)
+    {
+        return encode_result(RegisterResponse {
+            result: Some(register_response::Result::Error(ErrorResponse {
+                code: 429, // Too Many Requests
+                message,
+            })),
+        });
+    }
+
+    // Supervisor 下发的 Realm 注册配额
+    if let Err(message) = state
+        .issuer
+        .check_registration_quota(request.realm.realm_id)
+        .await
+    {
+        warn!("Rejected register request: {}", message);
+        return encode_result(RegisterResponse {
+            result: Some(register_response::Result::Error(ErrorResponse {
+                code: 403, // Forbidden
+                message,
+            })),
+        });
+    }
+
     // 调用 issuer 签发 credential
     let result = match state.issuer.issue_credential(&request).await {
         Ok(response) => {
@@ -87,6 +232,61 @@ async fn register_actr(State(state): State<AISState>, body: Bytes) -> Bytes {
     encode_result(result)
 }
 
+/// 批量预留序列号
+///
+/// 供设备厂商出厂前预分配 ActrId：只分配序列号、不签发凭证，预留段持久化到 AIS 存储
+async fn reserve_batch(
+    State(state): State<AISState>,
+    Json(request): Json<ReserveBatchRequest>,
+) -> (StatusCode, Json<Value>) {
+    if let Err((status, message)) = state
+        .auth
+        .verify(
+            &request.credential,
+            &reserve_batch_payload(request.realm_id, request.count),
+        )
+        .await
+    {
+        warn!(
+            "Rejected serial reservation for realm {}: {}",
+            request.realm_id, message
+        );
+        return admin_error(status, message);
+    }
+
+    if request.count == 0 || request.count > MAX_RESERVE_BATCH_SIZE {
+        return admin_error(
+            StatusCode::BAD_REQUEST,
+            format!("count must be between 1 and {MAX_RESERVE_BATCH_SIZE}"),
+        );
+    }
+
+    match state
+        .issuer
+        .reserve_serials(request.realm_id, request.count, request.note)
+        .await
+    {
+        Ok((reservation, serial_numbers)) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "success",
+                "reservation": reservation,
+                "serial_numbers": serial_numbers
+            })),
+        ),
+        Err(e) => {
+            error!(
+                "Failed to reserve serial numbers for realm {}: {}",
+                request.realm_id, e
+            );
+            admin_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Serial reservation failed: {e}"),
+            )
+        }
+    }
+}
+
 /// 健康检查端点
 ///
 /// 执行以下检查：
@@ -123,20 +323,147 @@ async fn health_check(State(state): State<AISState>) -> Json<Value> {
     checks["ks_service"] = json!(ks_status);
 
     // 检查密钥缓存状态
-    let cache_status = match state.issuer.check_key_cache_health().await {
-        Ok(info) => json!({"status": "ok", "key_id": info.key_id, "expires_in": info.expires_in}),
+    let (cache_ok, cache_status) = key_cache_status(&state.issuer).await;
+    if !cache_ok {
+        checks["status"] = json!("degraded");
+    }
+    checks["key_cache"] = cache_status;
+
+    Json(checks)
+}
+
+/// 密钥缓存状态（含当前加密与签名 key_id），返回是否正常
+async fn key_cache_status(issuer: &AIdIssuer) -> (bool, Value) {
+    match issuer.check_key_cache_health().await {
+        Ok(info) => (
+            true,
+            json!({
+                "status": "ok",
+                "key_id": info.key_id,
+                "expires_in": info.expires_in,
+                "signing_key_id": info.signing_key_id,
+                "signing_expires_in": info.signing_expires_in
+            }),
+        ),
         Err(e) => {
             error!("Key cache health check failed: {}", e);
-            checks["status"] = json!("degraded");
-            json!({"status": "failed", "error": e.to_string()})
+            (false, json!({"status": "failed", "error": e.to_string()}))
         }
+    }
+}
+
+fn admin_error(status: StatusCode, message: String) -> (StatusCode, Json<Value>) {
+    (
+        status,
+        Json(json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+}
+
+/// 注册统计
+///
+/// 返回最近 `days` 天（UTC）按 Realm、按天的注册数，以及当前密钥与缓存状态，供运维看板使用
+async fn get_stats(
+    State(state): State<AISState>,
+    Query(query): Query<StatsQuery>,
+) -> (StatusCode, Json<Value>) {
+    let verified = match AdminAuth::parse_query_credential(query.credential.as_deref()) {
+        Ok(credential) => state.auth.verify(&credential, STATS_PAYLOAD).await,
+        Err(e) => Err(e),
     };
-    checks["key_cache"] = cache_status;
+    if let Err((status, message)) = verified {
+        warn!("Rejected AIS stats query: {}", message);
+        return admin_error(status, message);
+    }
 
-    Json(checks)
+    let days = query.days.unwrap_or(DEFAULT_STATS_DAYS);
+    if days == 0 || days > MAX_STATS_DAYS {
+        return admin_error(
+            StatusCode::BAD_REQUEST,
+            format!("days must be between 1 and {MAX_STATS_DAYS}"),
+        );
+    }
+
+    // 从 (days - 1) 天前的 UTC 零点开始统计
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let since = (now - now % 86400).saturating_sub((days - 1) * 86400);
+
+    let registrations = match state.issuer.registration_stats(since).await {
+        Ok(registrations) => registrations,
+        Err(e) => {
+            error!("Failed to query registration stats: {}", e);
+            return admin_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    };
+    let total: u64 = registrations.iter().map(|r| r.count).sum();
+    let (_, key_cache) = key_cache_status(&state.issuer).await;
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "since": since,
+            "days": days,
+            "total_registrations": total,
+            "registrations": registrations,
+            "key_cache": key_cache
+        })),
+    )
 }
 
-/// 手动触发密钥轮替
+/// 查询指定 Realm 最近注册的 Actor
+async fn list_actors(
+    State(state): State<AISState>,
+    Query(query): Query<ActorsQuery>,
+) -> (StatusCode, Json<Value>) {
+    let verified = match AdminAuth::parse_query_credential(query.credential.as_deref()) {
+        Ok(credential) => {
+            state
+                .auth
+                .verify(&credential, &list_actors_payload(query.realm_id))
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err((status, message)) = verified {
+        warn!(
+            "Rejected AIS actor query for realm {}: {}",
+            query.realm_id, message
+        );
+        return admin_error(status, message);
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_ACTORS_LIMIT);
+    if limit == 0 || limit > MAX_ACTORS_LIMIT {
+        return admin_error(
+            StatusCode::BAD_REQUEST,
+            format!("limit must be between 1 and {MAX_ACTORS_LIMIT}"),
+        );
+    }
+
+    match state.issuer.list_registrations(query.realm_id, limit).await {
+        Ok(actors) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "success",
+                "realm_id": query.realm_id,
+                "count": actors.len(),
+                "actors": actors
+            })),
+        ),
+        Err(e) => {
+            error!("Failed to list actors for realm {}: {}", query.realm_id, e);
+            admin_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    }
+}
+
+/// 手动触发加密密钥轮替
 ///
 /// 立即从 KS 生成新密钥并更新缓存
 /// 返回新的 key_id
@@ -157,14 +484,43 @@ async fn rotate_key(State(state): State<AISState>) -> Json<Value> {
     }
 }
 
-/// 获取当前使用的密钥 ID
+/// 手动触发签名密钥轮替
+///
+/// 与加密密钥相互独立，返回新的签名 key_id
+async fn rotate_signing_key(State(state): State<AISState>) -> Json<Value> {
+    match state.issuer.rotate_signing_key().await {
+        Ok(new_key_id) => Json(json!({
+            "status": "success",
+            "message": "Signing key rotated successfully",
+            "new_signing_key_id": new_key_id
+        })),
+        Err(e) => {
+            error!("Failed to rotate signing key: {}", e);
+            Json(json!({
+                "status": "error",
+                "message": format!("Signing key rotation failed: {}", e)
+            }))
+        }
+    }
+}
+
+/// 获取当前使用的加密密钥与签名密钥 ID
 ///
 /// 用于监控和调试
 async fn get_current_key(State(state): State<AISState>) -> Json<Value> {
-    match state.issuer.get_current_key_id().await {
-        Ok(key_id) => Json(json!({
+    let key_ids = match state.issuer.get_current_key_id().await {
+        Ok(key_id) => state
+            .issuer
+            .get_current_signing_key_id()
+            .await
+            .map(|signing_key_id| (key_id, signing_key_id)),
+        Err(e) => Err(e),
+    };
+    match key_ids {
+        Ok((key_id, signing_key_id)) => Json(json!({
             "status": "success",
-            "key_id": key_id
+            "key_id": key_id,
+            "signing_key_id": signing_key_id
         })),
         Err(e) => {
             error!("Failed to get current key: {}", e);
@@ -177,7 +533,7 @@ async fn get_current_key(State(state): State<AISState>) -> Json<Value> {
 }
 
 /// 编码 RegisterResponse 为 protobuf 字节
-fn encode_result(result: RegisterResponse) -> Bytes {
+pub(crate) fn encode_result(result: RegisterResponse) -> Bytes {
     let mut buf = Vec::new();
     if let Err(err) = result.encode(&mut buf) {
         error!("Failed to encode RegisterResponse: {}", err);
@@ -210,6 +566,10 @@ fn aid_error_to_error_response(err: AidError) -> ErrorResponse {
         AidError::Base64DecodeError(_) => 400,
         AidError::HexDecodeError(_) => 400,
         AidError::Expired => 401,
+        AidError::SignatureInvalid(_) => 401,
+        AidError::KeyRevoked(_) => 401,
+        AidError::CredentialRevoked(_) => 401,
+        AidError::PskSuperseded(_) => 401,
         AidError::RealmError(_) => 403, // Forbidden
 
         // 服务端错误 (5xx)
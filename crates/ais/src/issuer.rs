@@ -3,13 +3,20 @@
 //! # 职责
 //!
 //! 负责处理 `RegisterRequest` 并生成 `RegisterResponse`，包括：
-//! - 序列号分配（Snowflake 算法）
-//! - Token 加密（ECIES）
+//! - 序列号分配（Snowflake 算法），以及不签发凭证的批量预留
+//! - Token 签名（ECDSA）与加密（ECIES）
 //! - PSK 生成（客户端保管）
 //! - 密钥生命周期管理（从 KS 获取、缓存、刷新）
 //!
 //! # 密钥管理策略
 //!
+//! 签发器维护两个相互独立的密钥槽位，均由 KS 生成：
+//! - **加密密钥**：ECIES 加密 Token，key_id 写入 `AIdCredential.token_key_id`
+//! - **签名密钥**：签名 Token 明文，key_id 写入 credential 元数据
+//!   （见 [`actrix_common::aid::SignedToken`]）
+//!
+//! 两个槽位各自刷新、各自按独立周期轮替，可单独手动轮替。
+//!
 //! ## 初始化阶段
 //!
 //! 1. 尝试从本地 SQLite 加载缓存的密钥
@@ -21,6 +28,9 @@
 //! - 检查频率：每 10 分钟
 //! - 刷新触发：距离过期时间 < 10 分钟
 //! - 容忍时间：过期后 24 小时内仍可使用
+//! - 定期轮替：加密密钥与签名密钥分别按各自的轮替间隔进行
+//! - KS 轮替：订阅 KS `WatchKeyRotations`，当前槽位的密钥被 KS 转为仅验证时立即切换到新密钥
+//! - KS 吊销：订阅 KS `WatchKeyRevocations`，当前槽位的密钥被吊销时立即从 KS 获取新密钥
 //!
 //! ## 错误处理
 //!
@@ -56,8 +66,10 @@
 //! ```
 
 use crate::ks_client_wrapper::KsClientWrapper;
-use crate::sn::{AIdSerialNumberIssuer, SerialNumber};
-use crate::storage::{KeyRecord, KeyStorage};
+use crate::sn;
+use crate::storage::{
+    DailyRegistrations, KeyRecord, KeyStorage, RegistrationRecord, SerialReservation,
+};
 
 // ========== 常量配置 ==========
 
@@ -66,17 +78,25 @@ use crate::storage::{KeyRecord, KeyStorage};
 /// 后台任务每隔此时间检查一次密钥是否需要刷新
 const KEY_REFRESH_CHECK_INTERVAL_SECS: u64 = 600; // 10 分钟
 
+/// KS 轮替/吊销事件订阅断开后的重连间隔（秒）
+const KEY_ROTATION_WATCH_RETRY_SECS: u64 = 5;
+
 /// 默认 PSK 长度（字节）
 ///
 /// 生成的预共享密钥长度，用于 Actor 与 Signaling Server 的连接认证
 const DEFAULT_PSK_LENGTH: usize = 32; // 256-bit
 use actr_protocol::{
-    AIdCredential, ActrId, ActrType, ErrorResponse, Realm, RegisterRequest, RegisterResponse,
-    register_response,
+    AIdCredential, ActrId, ActrIdExt, ActrType, ErrorResponse, Realm, RegisterRequest,
+    RegisterResponse, register_response,
+};
+use actrix_common::aid::{
+    AIdCredentialValidator, AidError, CredentialMetadata, IdentityClaims, KeyUsage, SignedToken,
 };
-use actrix_common::aid::{AidError, IdentityClaims};
+use actrix_common::config::ais::AisSerialNumberConfig;
+use actrix_common::realm::RealmQuota;
+use actrix_common::storage::SqliteLocation;
 use base64::prelude::*;
-use ecies::{PublicKey, encrypt};
+use ecies::{PublicKey, SecretKey, decrypt, encrypt};
 use prost::bytes::Bytes;
 use prost_types::Timestamp;
 use rand::RngCore;
@@ -94,15 +114,31 @@ pub struct IssuerConfig {
     pub signaling_heartbeat_interval_secs: u32,
     /// 密钥缓存刷新间隔（秒，默认 1 小时）
     pub key_refresh_interval_secs: u64,
-    /// 密钥存储数据库文件路径
-    pub key_storage_file: std::path::PathBuf,
+    /// 密钥存储数据库位置（文件或内存数据库）
+    pub key_storage: SqliteLocation,
     /// 是否启用定期密钥轮替
     pub enable_periodic_rotation: bool,
-    /// 密钥轮替间隔（秒，默认 24 小时）
+    /// 加密密钥轮替间隔（秒，默认 24 小时）
     ///
     /// 仅当 enable_periodic_rotation = true 时生效
     /// 到达此间隔后会主动生成新密钥，即使旧密钥未过期
     pub key_rotation_interval_secs: u64,
+    /// 签名密钥轮替间隔（秒，默认 7 天）
+    ///
+    /// 仅当 enable_periodic_rotation = true 时生效，与加密密钥的轮替相互独立
+    pub signing_key_rotation_interval_secs: u64,
+    /// 序列号生成器配置（node_id 与时钟回拨策略）
+    pub serial_number: AisSerialNumberConfig,
+}
+
+impl IssuerConfig {
+    /// 指定用途密钥的轮替间隔
+    fn rotation_interval_secs(&self, usage: KeyUsage) -> u64 {
+        match usage {
+            KeyUsage::Encryption => self.key_rotation_interval_secs,
+            KeyUsage::Signing => self.signing_key_rotation_interval_secs,
+        }
+    }
 }
 
 impl Default for IssuerConfig {
@@ -111,14 +147,16 @@ impl Default for IssuerConfig {
             token_ttl_secs: 3600,                  // 1 小时
             signaling_heartbeat_interval_secs: 30, // 30 秒
             key_refresh_interval_secs: 3600,       // 1 小时
-            key_storage_file: std::path::PathBuf::from("ais_keys.db"),
+            key_storage: SqliteLocation::File("ais_keys.db".into()),
             enable_periodic_rotation: false,   // 默认禁用定期轮替
             key_rotation_interval_secs: 86400, // 24 小时
+            signing_key_rotation_interval_secs: 7 * 86400, // 7 天
+            serial_number: AisSerialNumberConfig::default(),
         }
     }
 }
 
-/// 密钥缓存
+/// 加密密钥缓存
 struct KeyCache {
     key_id: u32,
     public_key: PublicKey,
@@ -128,35 +166,81 @@ struct KeyCache {
     tolerance_seconds: u64,
 }
 
+/// 签名密钥缓存（私钥仅保存在内存中）
+struct SigningKeyCache {
+    key_id: u32,
+    secret_key: SecretKey,
+    expires_at: u64,
+}
+
 /// AId Token 签发器 - 专注于签发新的 Actor Identity Token
 pub struct AIdIssuer {
     ks_client: KsClientWrapper,
     key_storage: Arc<KeyStorage>,
     key_cache: Arc<RwLock<Option<KeyCache>>>,
+    signing_key_cache: Arc<RwLock<Option<SigningKeyCache>>>,
     config: IssuerConfig,
 }
 
+/// 进程内的 AIS 签发器（供就绪探针使用）
+static REGISTERED_ISSUER: std::sync::RwLock<Option<Arc<AIdIssuer>>> = std::sync::RwLock::new(None);
+
 impl AIdIssuer {
+    /// 登记进程内的签发器（AIS 路由创建时调用）
+    pub fn register(issuer: Arc<AIdIssuer>) {
+        *REGISTERED_ISSUER
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(issuer);
+    }
+
+    /// 获取进程内已登记的签发器（AIS 未启动时为 None）
+    pub fn registered() -> Option<Arc<AIdIssuer>> {
+        REGISTERED_ISSUER
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
     /// 创建新的 AIdIssuer
     pub async fn new(ks_client: KsClientWrapper, config: IssuerConfig) -> Result<Self, AidError> {
-        let key_storage = KeyStorage::new(&config.key_storage_file)
+        let key_storage = KeyStorage::new(config.key_storage.clone())
             .await
             .map_err(|e| {
                 AidError::GenerationFailed(format!("Failed to create key storage: {e}"))
             })?;
 
+        // 应用序列号配置，并从持久化的高水位恢复，避免重启后在回退的时钟上重复签发
+        sn::configure(&config.serial_number).map_err(|e| {
+            AidError::GenerationFailed(format!("Invalid serial number config: {e}"))
+        })?;
+        if let Some(high_water_ms) = key_storage.get_sn_high_water().await.map_err(|e| {
+            AidError::GenerationFailed(format!("Failed to load serial number state: {e}"))
+        })? {
+            sn::restore_high_water(high_water_ms);
+            debug!("Restored serial number high water mark: {high_water_ms}");
+        }
+        info!(
+            "Serial number generator using worker_id={}",
+            sn::worker_id()
+        );
+
         let issuer = Self {
             ks_client,
             key_storage: Arc::new(key_storage),
             key_cache: Arc::new(RwLock::new(None)),
+            signing_key_cache: Arc::new(RwLock::new(None)),
             config,
         };
 
-        // 初始化时加载或获取密钥
+        // 初始化时加载或获取加密密钥与签名密钥
         issuer.ensure_key_loaded().await?;
+        issuer.ensure_signing_key_loaded().await?;
 
-        // 启动后台密钥刷新任务
+        // 启动后台密钥刷新任务与 KS 轮替事件订阅
         issuer.spawn_key_refresh_task();
+        issuer.spawn_key_rotation_watch_task();
+        issuer.spawn_key_revocation_watch_task();
+        issuer.spawn_sn_persist_task();
 
         Ok(issuer)
     }
@@ -222,6 +306,56 @@ impl AIdIssuer {
         Ok(())
     }
 
+    /// 确保签名密钥已加载
+    ///
+    /// 存储中只记录签名密钥的 key_id，私钥按 key_id 从 KS 重新获取
+    async fn ensure_signing_key_loaded(&self) -> Result<(), AidError> {
+        if self.signing_key_cache.read().await.is_some() {
+            debug!("Signing key already in cache");
+            return Ok(());
+        }
+
+        let record = self
+            .key_storage
+            .get_key(KeyUsage::Signing)
+            .await
+            .map_err(|e| {
+                AidError::GenerationFailed(format!("Failed to get signing key from storage: {e}"))
+            })?;
+        let expired = self
+            .key_storage
+            .is_key_expired_beyond_tolerance(KeyUsage::Signing)
+            .await
+            .map_err(|e| AidError::GenerationFailed(e.to_string()))?;
+
+        if let Some(record) = record.filter(|_| !expired) {
+            match self.ks_client.fetch_secret_key(record.key_id).await {
+                Ok((secret_key, expires_at, _)) => {
+                    debug!("Loaded signing key from KS: key_id={}", record.key_id);
+                    *self.signing_key_cache.write().await = Some(SigningKeyCache {
+                        key_id: record.key_id,
+                        secret_key,
+                        expires_at,
+                    });
+                    return Ok(());
+                }
+                Err(e) => warn!(
+                    "Failed to fetch stored signing key {} from KS: {}, generating new key",
+                    record.key_id, e
+                ),
+            }
+        } else {
+            info!("No usable stored signing key, fetching from KS");
+        }
+
+        Self::refresh_signing_key_internal(
+            &self.ks_client,
+            &self.key_storage,
+            &self.signing_key_cache,
+        )
+        .await
+    }
+
     /// 从 KS 刷新密钥
     async fn refresh_key_from_ks(&self) -> Result<(), AidError> {
         info!("Fetching new key from KS");
@@ -238,7 +372,7 @@ impl AIdIssuer {
         Ok(())
     }
 
-    /// 手动触发密钥轮替
+    /// 手动触发加密密钥轮替
     ///
     /// 立即从 KS 生成新密钥并更新缓存
     /// 返回新的 key_id
@@ -263,7 +397,28 @@ impl AIdIssuer {
         Ok(key_id)
     }
 
-    /// 获取当前使用的 key_id
+    /// 手动触发签名密钥轮替
+    ///
+    /// 与加密密钥相互独立，返回新的签名 key_id
+    pub async fn rotate_signing_key(&self) -> Result<u32, AidError> {
+        info!("Manual signing key rotation triggered");
+
+        Self::refresh_signing_key_internal(
+            &self.ks_client,
+            &self.key_storage,
+            &self.signing_key_cache,
+        )
+        .await?;
+
+        let key_id = self.get_current_signing_key_id().await?;
+        info!(
+            "Manual signing key rotation completed, new key_id: {}",
+            key_id
+        );
+        Ok(key_id)
+    }
+
+    /// 获取当前使用的加密 key_id
     pub async fn get_current_key_id(&self) -> Result<u32, AidError> {
         let cache = self.key_cache.read().await;
         cache
@@ -272,11 +427,62 @@ impl AIdIssuer {
             .ok_or_else(|| AidError::GenerationFailed("No key loaded".to_string()))
     }
 
+    /// 获取当前使用的签名 key_id
+    pub async fn get_current_signing_key_id(&self) -> Result<u32, AidError> {
+        let cache = self.signing_key_cache.read().await;
+        cache
+            .as_ref()
+            .map(|c| c.key_id)
+            .ok_or_else(|| AidError::GenerationFailed("No signing key loaded".to_string()))
+    }
+
+    /// 批量预留序列号（不签发凭证）
+    ///
+    /// 预留段写入本地存储，返回预留记录与段内全部序列号
+    pub async fn reserve_serials(
+        &self,
+        realm_id: u32,
+        count: u64,
+        note: Option<String>,
+    ) -> Result<(SerialReservation, Vec<u64>), AidError> {
+        let range = sn::issue_range(realm_id, count)
+            .await
+            .map_err(|e| AidError::GenerationFailed(e.to_string()))?;
+        // 预留可能把时间戳推进到未来，立即持久化高水位
+        if let Err(e) = self
+            .key_storage
+            .update_sn_high_water(sn::high_water_ms())
+            .await
+        {
+            warn!("Failed to persist serial number high water mark: {e}");
+        }
+        let reservation = self
+            .key_storage
+            .record_reservation(
+                realm_id,
+                range.first().value(),
+                range.last().value(),
+                range.len(),
+                note,
+            )
+            .await
+            .map_err(|e| {
+                AidError::GenerationFailed(format!("Failed to persist serial reservation: {e}"))
+            })?;
+
+        info!(
+            "Reserved {} serial numbers for realm {}: {}..={}",
+            reservation.count, realm_id, reservation.first_serial, reservation.last_serial
+        );
+        Ok((reservation, range.iter().map(|sn| sn.value()).collect()))
+    }
+
     /// 启动后台密钥刷新任务
     fn spawn_key_refresh_task(&self) {
         let ks_client = self.ks_client.clone();
         let key_storage = self.key_storage.clone();
         let key_cache = self.key_cache.clone();
+        let signing_key_cache = self.signing_key_cache.clone();
         let config = self.config.clone();
 
         tokio::spawn(async move {
@@ -286,68 +492,360 @@ impl AIdIssuer {
             loop {
                 interval.tick().await;
 
-                let mut should_rotate = false;
-
-                // 检查是否需要刷新（密钥即将过期）
-                let should_refresh = match key_storage.should_refresh().await {
-                    Ok(should) => should,
-                    Err(e) => {
-                        error!("Failed to check key refresh status: {}", e);
-                        continue;
+                // 加密密钥与签名密钥分别检查、分别轮替
+                for usage in [KeyUsage::Encryption, KeyUsage::Signing] {
+                    match Self::should_rotate(&key_storage, &config, usage).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            debug!("{} key rotation not needed yet", usage.as_str());
+                            continue;
+                        }
+                        Err(e) => {
+                            error!(
+                                "Failed to check {} key rotation status: {}",
+                                usage.as_str(),
+                                e
+                            );
+                            continue;
+                        }
                     }
-                };
 
-                if should_refresh {
-                    info!("Key expiring soon, rotation triggered");
-                    should_rotate = true;
-                }
-
-                // 检查是否需要定期轮替
-                if config.enable_periodic_rotation && !should_rotate {
-                    match Self::should_periodic_rotate(&key_storage, &config).await {
-                        Ok(true) => {
-                            info!("Periodic rotation interval reached, rotation triggered");
-                            should_rotate = true;
+                    debug!("Background {} key rotation triggered", usage.as_str());
+
+                    let result = match usage {
+                        KeyUsage::Encryption => {
+                            Self::refresh_key_internal(
+                                &ks_client,
+                                &key_storage,
+                                &key_cache,
+                                &config,
+                            )
+                            .await
+                        }
+                        KeyUsage::Signing => {
+                            Self::refresh_signing_key_internal(
+                                &ks_client,
+                                &key_storage,
+                                &signing_key_cache,
+                            )
+                            .await
                         }
-                        Ok(false) => {}
+                    };
+
+                    match result {
+                        Ok(()) => info!("Background {} key rotation successful", usage.as_str()),
                         Err(e) => {
-                            error!("Failed to check periodic rotation status: {}", e);
-                            continue;
+                            warn!(
+                                "Background {} key rotation failed: {}, will retry later",
+                                usage.as_str(),
+                                e
+                            );
                         }
                     }
                 }
+            }
+        });
 
-                if !should_rotate {
-                    debug!("Key rotation not needed yet");
-                    continue;
+        info!("Background key refresh task started");
+    }
+
+    /// 启动 KS 轮替事件订阅任务
+    ///
+    /// 当前加密或签名槽位的密钥被 KS 轮替为仅验证时，切换到事件中的新密钥；
+    /// 订阅断开后按固定间隔重连
+    fn spawn_key_rotation_watch_task(&self) {
+        let ks_client = self.ks_client.clone();
+        let key_storage = self.key_storage.clone();
+        let key_cache = self.key_cache.clone();
+        let signing_key_cache = self.signing_key_cache.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match ks_client.watch_key_rotations().await {
+                    Ok(mut watch) => loop {
+                        match watch.next().await {
+                            Ok(Some(rotation)) => {
+                                if let Err(e) = Self::apply_key_rotation(
+                                    &ks_client,
+                                    &key_storage,
+                                    &key_cache,
+                                    &signing_key_cache,
+                                    &rotation,
+                                )
+                                .await
+                                {
+                                    warn!(
+                                        "Failed to apply KS key rotation to key_id {}: {}",
+                                        rotation.key_id, e
+                                    );
+                                }
+                            }
+                            Ok(None) => {
+                                debug!("KS key rotation stream closed");
+                                break;
+                            }
+                            Err(e) => {
+                                warn!("KS key rotation stream error: {}", e);
+                                break;
+                            }
+                        }
+                    },
+                    Err(e) => debug!("Failed to subscribe to KS key rotations: {}", e),
                 }
 
-                debug!("Background key rotation triggered");
+                tokio::time::sleep(Duration::from_secs(KEY_ROTATION_WATCH_RETRY_SECS)).await;
+            }
+        });
 
-                // 轮替密钥
-                match Self::refresh_key_internal(&ks_client, &key_storage, &key_cache, &config)
-                    .await
-                {
-                    Ok(()) => info!("Background key rotation successful"),
-                    Err(e) => {
-                        warn!("Background key rotation failed: {}, will retry later", e);
-                    }
+        info!("KS key rotation watch task started");
+    }
+
+    /// 处理 KS 轮替事件：仅当被轮替的旧密钥正是当前槽位的密钥时切换
+    async fn apply_key_rotation(
+        ks_client: &KsClientWrapper,
+        key_storage: &KeyStorage,
+        key_cache: &RwLock<Option<KeyCache>>,
+        signing_key_cache: &RwLock<Option<SigningKeyCache>>,
+        rotation: &ks::KeyRotation,
+    ) -> Result<(), AidError> {
+        let Some(previous_key_id) = rotation.previous_key_id else {
+            return Ok(());
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let current_encryption = key_cache
+            .read()
+            .await
+            .as_ref()
+            .map(|cache| (cache.key_id, cache.tolerance_seconds));
+        if let Some((key_id, tolerance_seconds)) = current_encryption
+            && key_id == previous_key_id
+        {
+            let public_key_bytes = BASE64_STANDARD.decode(&rotation.public_key).map_err(|e| {
+                AidError::GenerationFailed(format!("Invalid base64 public key: {e}"))
+            })?;
+            let public_key = PublicKey::parse_slice(&public_key_bytes, None).map_err(|e| {
+                AidError::GenerationFailed(format!("Failed to parse public key: {e}"))
+            })?;
+
+            *key_cache.write().await = Some(KeyCache {
+                key_id: rotation.key_id,
+                public_key,
+                expires_at: rotation.expires_at,
+                tolerance_seconds,
+            });
+            key_storage
+                .update_current_key(&KeyRecord {
+                    key_id: rotation.key_id,
+                    public_key: rotation.public_key.clone(),
+                    fetched_at: now,
+                    expires_at: rotation.expires_at,
+                    tolerance_seconds,
+                })
+                .await
+                .map_err(|e| AidError::GenerationFailed(format!("Failed to save key: {e}")))?;
+
+            info!(
+                "Encryption key {} rotated by KS, switched to key_id {}",
+                previous_key_id, rotation.key_id
+            );
+        }
+
+        let current_signing = signing_key_cache
+            .read()
+            .await
+            .as_ref()
+            .map(|cache| cache.key_id);
+        if current_signing == Some(previous_key_id) {
+            let (secret_key, expires_at, tolerance_seconds) = ks_client
+                .fetch_secret_key(rotation.key_id)
+                .await
+                .map_err(|e| AidError::GenerationFailed(format!("KS unavailable: {e}")))?;
+
+            *signing_key_cache.write().await = Some(SigningKeyCache {
+                key_id: rotation.key_id,
+                secret_key,
+                expires_at,
+            });
+            key_storage
+                .update_key(
+                    KeyUsage::Signing,
+                    &KeyRecord {
+                        key_id: rotation.key_id,
+                        public_key: rotation.public_key.clone(),
+                        fetched_at: now,
+                        expires_at,
+                        tolerance_seconds,
+                    },
+                )
+                .await
+                .map_err(|e| {
+                    AidError::GenerationFailed(format!("Failed to save signing key: {e}"))
+                })?;
+
+            info!(
+                "Signing key {} rotated by KS, switched to key_id {}",
+                previous_key_id, rotation.key_id
+            );
+        }
+
+        Ok(())
+    }
+
+    /// 启动 KS 吊销事件订阅任务
+    ///
+    /// 当前加密或签名槽位的密钥被吊销时立即替换，避免继续签发无法通过验证的凭证；
+    /// 订阅断开后按固定间隔重连（重连时 KS 会重新推送已吊销的密钥）
+    fn spawn_key_revocation_watch_task(&self) {
+        let ks_client = self.ks_client.clone();
+        let key_storage = self.key_storage.clone();
+        let key_cache = self.key_cache.clone();
+        let signing_key_cache = self.signing_key_cache.clone();
+        let config = self.config.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match ks_client.watch_key_revocations().await {
+                    Ok(mut watch) => loop {
+                        match watch.next().await {
+                            Ok(Some(revocation)) => {
+                                if let Err(e) = Self::apply_key_revocation(
+                                    &ks_client,
+                                    &key_storage,
+                                    &key_cache,
+                                    &signing_key_cache,
+                                    &config,
+                                    revocation.key_id,
+                                )
+                                .await
+                                {
+                                    warn!(
+                                        "Failed to replace revoked key_id {}: {}",
+                                        revocation.key_id, e
+                                    );
+                                }
+                            }
+                            Ok(None) => {
+                                debug!("KS key revocation stream closed");
+                                break;
+                            }
+                            Err(e) => {
+                                warn!("KS key revocation stream error: {}", e);
+                                break;
+                            }
+                        }
+                    },
+                    Err(e) => debug!("Failed to subscribe to KS key revocations: {}", e),
                 }
+
+                tokio::time::sleep(Duration::from_secs(KEY_ROTATION_WATCH_RETRY_SECS)).await;
             }
         });
 
-        info!("Background key refresh task started");
+        info!("KS key revocation watch task started");
+    }
+
+    /// 启动序列号高水位持久化任务
+    fn spawn_sn_persist_task(&self) {
+        let key_storage = self.key_storage.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(sn::HIGH_WATER_PERSIST_INTERVAL);
+            let mut persisted = 0;
+
+            loop {
+                interval.tick().await;
+
+                let high_water_ms = sn::high_water_ms();
+                if high_water_ms <= persisted {
+                    continue;
+                }
+                match key_storage.update_sn_high_water(high_water_ms).await {
+                    Ok(()) => persisted = high_water_ms,
+                    Err(e) => warn!("Failed to persist serial number high water mark: {}", e),
+                }
+            }
+        });
+
+        debug!("Serial number high water persist task started");
+    }
+
+    /// 处理 KS 吊销事件：被吊销的密钥正是当前槽位的密钥时重新生成
+    async fn apply_key_revocation(
+        ks_client: &KsClientWrapper,
+        key_storage: &KeyStorage,
+        key_cache: &RwLock<Option<KeyCache>>,
+        signing_key_cache: &RwLock<Option<SigningKeyCache>>,
+        config: &IssuerConfig,
+        revoked_key_id: u32,
+    ) -> Result<(), AidError> {
+        let current_encryption = key_cache.read().await.as_ref().map(|cache| cache.key_id);
+        if current_encryption == Some(revoked_key_id) {
+            warn!(
+                "Encryption key {} revoked by KS, fetching a new key",
+                revoked_key_id
+            );
+            Self::refresh_key_internal(ks_client, key_storage, key_cache, config).await?;
+        }
+
+        let current_signing = signing_key_cache
+            .read()
+            .await
+            .as_ref()
+            .map(|cache| cache.key_id);
+        if current_signing == Some(revoked_key_id) {
+            warn!(
+                "Signing key {} revoked by KS, fetching a new key",
+                revoked_key_id
+            );
+            Self::refresh_signing_key_internal(ks_client, key_storage, signing_key_cache).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 检查指定用途的密钥是否需要轮替（即将过期，或到达定期轮替间隔）
+    async fn should_rotate(
+        key_storage: &KeyStorage,
+        config: &IssuerConfig,
+        usage: KeyUsage,
+    ) -> Result<bool, AidError> {
+        let should_refresh = key_storage
+            .should_refresh_key(usage)
+            .await
+            .map_err(|e| AidError::GenerationFailed(e.to_string()))?;
+        if should_refresh {
+            info!("{} key expiring soon, rotation triggered", usage.as_str());
+            return Ok(true);
+        }
+
+        if config.enable_periodic_rotation
+            && Self::should_periodic_rotate(key_storage, config, usage).await?
+        {
+            info!(
+                "{} key periodic rotation interval reached, rotation triggered",
+                usage.as_str()
+            );
+            return Ok(true);
+        }
+
+        Ok(false)
     }
 
     /// 检查是否需要定期轮替密钥
     ///
-    /// 根据 fetched_at 时间和配置的 key_rotation_interval_secs 判断
+    /// 根据 fetched_at 时间和该用途配置的轮替间隔判断
     async fn should_periodic_rotate(
         key_storage: &KeyStorage,
         config: &IssuerConfig,
+        usage: KeyUsage,
     ) -> Result<bool, AidError> {
         let current_key = key_storage
-            .get_current_key()
+            .get_key(usage)
             .await
             .map_err(|e| AidError::GenerationFailed(format!("Failed to get current key: {e}")))?;
 
@@ -363,7 +861,7 @@ impl AIdIssuer {
 
         let time_since_fetched = now.saturating_sub(key_record.fetched_at);
 
-        Ok(time_since_fetched >= config.key_rotation_interval_secs)
+        Ok(time_since_fetched >= config.rotation_interval_secs(usage))
     }
 
     /// 内部密钥刷新方法（供后台任务使用）
@@ -411,6 +909,51 @@ impl AIdIssuer {
         Ok(())
     }
 
+    /// 内部签名密钥刷新方法
+    ///
+    /// 由 KS 生成新密钥对并立即获取私钥；存储中只记录 key_id 与公钥
+    async fn refresh_signing_key_internal(
+        ks_client: &KsClientWrapper,
+        key_storage: &KeyStorage,
+        signing_key_cache: &RwLock<Option<SigningKeyCache>>,
+    ) -> Result<(), AidError> {
+        let (key_id, public_key, expires_at, tolerance_seconds) = ks_client
+            .generate_key()
+            .await
+            .map_err(|e| AidError::GenerationFailed(format!("KS unavailable: {e}")))?;
+        let (secret_key, _, _) = ks_client
+            .fetch_secret_key(key_id)
+            .await
+            .map_err(|e| AidError::GenerationFailed(format!("KS unavailable: {e}")))?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        *signing_key_cache.write().await = Some(SigningKeyCache {
+            key_id,
+            secret_key,
+            expires_at,
+        });
+
+        let record = KeyRecord {
+            key_id,
+            public_key: BASE64_STANDARD.encode(public_key.serialize_compressed()),
+            fetched_at: now,
+            expires_at,
+            tolerance_seconds,
+        };
+
+        key_storage
+            .update_key(KeyUsage::Signing, &record)
+            .await
+            .map_err(|e| AidError::GenerationFailed(format!("Failed to save signing key: {e}")))?;
+
+        debug!("Signing key refreshed: key_id={}", key_id);
+        Ok(())
+    }
+
     /// 处理 register 请求并签发 credential
     pub async fn issue_credential(
         &self,
@@ -436,10 +979,46 @@ impl AIdIssuer {
     ) -> Result<register_response::RegisterOk, AidError> {
         // 确保有可用的密钥
         self.ensure_key_loaded().await?;
+        self.ensure_signing_key_loaded().await?;
 
         // 生成 ActrId
-        let actr_id = self.generate_actr_id(&request.actr_type, &request.realm)?;
+        let actr_id = self
+            .generate_actr_id(&request.actr_type, &request.realm)
+            .await?;
+
+        let register_ok = self.sign_credential(actr_id, 0).await?;
+
+        // 记录注册（失败不影响签发）
+        let record = RegistrationRecord {
+            realm_id: register_ok.actr_id.realm.realm_id,
+            serial_number: register_ok.actr_id.serial_number,
+            manufacturer: register_ok.actr_id.r#type.manufacturer.clone(),
+            name: register_ok.actr_id.r#type.name.clone(),
+            registered_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            expires_at: register_ok
+                .credential_expires_at
+                .as_ref()
+                .map_or(0, |t| t.seconds as u64),
+        };
+        if let Err(e) = self.key_storage.record_registration(&record).await {
+            warn!(
+                "Failed to record registration for serial_number {}: {}",
+                record.serial_number, e
+            );
+        }
 
+        Ok(register_ok)
+    }
+
+    /// 为 ActrId 生成新 PSK 并签发 credential
+    async fn sign_credential(
+        &self,
+        actr_id: ActrId,
+        psk_epoch: u32,
+    ) -> Result<register_response::RegisterOk, AidError> {
         // 生成过期时间
         let expr_time = self.calculate_expiry_time();
 
@@ -447,7 +1026,8 @@ impl AIdIssuer {
         let psk = self.generate_psk()?;
 
         // 创建 Claims（包含 PSK）
-        let claims = IdentityClaims::from_actr_id(&actr_id, expr_time, psk.clone());
+        let claims = IdentityClaims::from_actr_id(&actr_id, expr_time, psk.clone())
+            .with_psk_epoch(psk_epoch);
 
         // 从缓存获取密钥
         let (key_id, public_key) = {
@@ -458,8 +1038,21 @@ impl AIdIssuer {
             (cache.key_id, cache.public_key)
         };
 
+        // 使用签名密钥签名，元数据记录两个槽位的 key_id
+        let signed_token = {
+            let cache = self.signing_key_cache.read().await;
+            let cache = cache.as_ref().ok_or_else(|| {
+                AidError::GenerationFailed("No signing key available".to_string())
+            })?;
+            let metadata = CredentialMetadata {
+                encryption_key_id: key_id,
+                signing_key_id: cache.key_id,
+            };
+            SignedToken::sign(&claims, metadata, &cache.secret_key)?
+        };
+
         // 生成加密的 credential
-        let encrypted_token = self.encrypt_claims(&claims, &public_key)?;
+        let encrypted_token = self.encrypt_token(&signed_token, &public_key)?;
 
         // 创建 AIdCredential
         let credential = AIdCredential {
@@ -482,10 +1075,77 @@ impl AIdIssuer {
         })
     }
 
+    /// 验证本 AIS 签发的 credential（解密 + 验签 + 有效期与 Realm），返回身份声明
+    ///
+    /// 解密与验签所需的私钥从 KS 获取；签名密钥为当前槽位时直接使用缓存
+    pub async fn verify_credential(
+        &self,
+        credential: &AIdCredential,
+        realm_id: u32,
+    ) -> Result<IdentityClaims, AidError> {
+        let (encryption_key, _, _) = self
+            .ks_client
+            .fetch_secret_key(credential.token_key_id)
+            .await
+            .map_err(|e| AidError::GenerationFailed(format!("KS service error: {e}")))?;
+
+        // 先解密读取签名密钥 ID；旧格式 Token 没有签名
+        let decrypted = decrypt(&encryption_key.serialize(), &credential.encrypted_token)
+            .map_err(|e| AidError::DecryptionFailed(format!("Decryption error: {e}")))?;
+        let verifying_key = match serde_json::from_slice::<SignedToken>(&decrypted) {
+            Ok(token) => Some(self.verifying_key(token.metadata.signing_key_id).await?),
+            Err(_) => None,
+        };
+
+        AIdCredentialValidator::check_with_keys(
+            credential,
+            realm_id,
+            &encryption_key,
+            verifying_key.as_ref(),
+        )
+    }
+
+    /// 获取签名密钥对应的验签公钥
+    async fn verifying_key(&self, signing_key_id: u32) -> Result<PublicKey, AidError> {
+        if let Some(cache) = self.signing_key_cache.read().await.as_ref()
+            && cache.key_id == signing_key_id
+        {
+            return Ok(PublicKey::from_secret_key(&cache.secret_key));
+        }
+
+        let (secret_key, _, _) = self
+            .ks_client
+            .fetch_secret_key(signing_key_id)
+            .await
+            .map_err(|e| AidError::GenerationFailed(format!("KS service error: {e}")))?;
+        Ok(PublicKey::from_secret_key(&secret_key))
+    }
+
+    /// 轮替 PSK：为已验证的身份声明签发新 PSK 与新 credential，PSK 代数加 1
+    ///
+    /// 调用方负责验证持有旧 PSK 的证明（见 [`crate::psk_rotation`]）
+    pub async fn rotate_psk(
+        &self,
+        claims: &IdentityClaims,
+    ) -> Result<register_response::RegisterOk, AidError> {
+        let actr_id = ActrId::from_string_repr(&claims.actor_id)
+            .map_err(|e| AidError::GenerationFailed(format!("Invalid actor id in claims: {e}")))?;
+
+        self.ensure_key_loaded().await?;
+        self.ensure_signing_key_loaded().await?;
+        self.sign_credential(actr_id, claims.psk_epoch + 1).await
+    }
+
     /// 生成 ActrId
-    fn generate_actr_id(&self, actr_type: &ActrType, realm: &Realm) -> Result<ActrId, AidError> {
-        // 使用 Snowflake 算法生成序列号
-        let serial_number = SerialNumber::sn(realm.realm_id);
+    async fn generate_actr_id(
+        &self,
+        actr_type: &ActrType,
+        realm: &Realm,
+    ) -> Result<ActrId, AidError> {
+        // 使用 Snowflake 算法生成序列号（按配置处理时钟回拨）
+        let serial_number = sn::issue(realm.realm_id)
+            .await
+            .map_err(|e| AidError::GenerationFailed(e.to_string()))?;
 
         Ok(ActrId {
             realm: *realm,
@@ -503,14 +1163,14 @@ impl AIdIssuer {
             + self.config.token_ttl_secs
     }
 
-    /// 加密 Claims 为 credential
-    fn encrypt_claims(
+    /// 加密签名 Token 为 credential
+    fn encrypt_token(
         &self,
-        claims: &IdentityClaims,
+        token: &SignedToken,
         public_key: &PublicKey,
     ) -> Result<Vec<u8>, AidError> {
-        // 序列化 claims
-        let claims_bytes = serde_json::to_vec(claims)
+        // 序列化签名 Token
+        let claims_bytes = serde_json::to_vec(token)
             .map_err(|e| AidError::GenerationFailed(format!("Serialization error: {e}")))?;
 
         // 将 PublicKey 转换为字节
@@ -538,6 +1198,66 @@ impl AIdIssuer {
             .map_err(|e| AidError::GenerationFailed(format!("Database unhealthy: {e}")))
     }
 
+    /// 按 Realm 与日期统计 `since`（Unix timestamp）之后的注册数
+    pub async fn registration_stats(
+        &self,
+        since: u64,
+    ) -> Result<Vec<DailyRegistrations>, AidError> {
+        self.key_storage
+            .registration_stats(since)
+            .await
+            .map_err(|e| {
+                AidError::GenerationFailed(format!("Failed to query registration stats: {e}"))
+            })
+    }
+
+    /// 检查 Realm 的注册配额（Supervisor 下发的 `max_registrations`）
+    ///
+    /// 读取配额或统计注册数失败时放行
+    pub async fn check_registration_quota(&self, realm_id: u32) -> Result<(), String> {
+        let quota = match RealmQuota::load_for_realm(realm_id).await {
+            Ok(quota) => quota,
+            Err(e) => {
+                warn!("Failed to load quota for realm {}: {}", realm_id, e);
+                return Ok(());
+            }
+        };
+        if quota.max_registrations.is_none_or(|max| max == 0) {
+            return Ok(());
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        match self
+            .key_storage
+            .count_active_registrations(realm_id, now)
+            .await
+        {
+            Ok(registered) => quota.check_registrations(realm_id, registered),
+            Err(e) => {
+                warn!(
+                    "Failed to count registrations for realm {}: {}",
+                    realm_id, e
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// 查询指定 Realm 最近注册的 Actor
+    pub async fn list_registrations(
+        &self,
+        realm_id: u32,
+        limit: u32,
+    ) -> Result<Vec<RegistrationRecord>, AidError> {
+        self.key_storage
+            .list_registrations(realm_id, limit)
+            .await
+            .map_err(|e| AidError::GenerationFailed(format!("Failed to query registrations: {e}")))
+    }
+
     /// 检查 KS 服务健康状态
     ///
     /// 通过尝试获取当前密钥来验证 KS 服务可用性
@@ -565,6 +1285,17 @@ impl AIdIssuer {
         }
     }
 
+    /// 检查 KS 是否可达
+    ///
+    /// 仅调用 KS 的 HealthCheck，不获取或生成密钥，适合高频调用（如就绪探针）
+    pub async fn check_ks_reachable(&self) -> Result<(), AidError> {
+        self.ks_client
+            .health_check()
+            .await
+            .map(|_| ())
+            .map_err(|e| AidError::GenerationFailed(format!("KS service unreachable: {e}")))
+    }
+
     /// 检查密钥缓存健康状态
     pub async fn check_key_cache_health(&self) -> Result<KeyCacheInfo, AidError> {
         let cache = self.key_cache.read().await;
@@ -579,17 +1310,28 @@ impl AIdIssuer {
 
         let expires_in = cache.expires_at.saturating_sub(now);
 
+        let signing_cache = self.signing_key_cache.read().await;
+        let signing_cache = signing_cache
+            .as_ref()
+            .ok_or_else(|| AidError::GenerationFailed("No signing key in cache".to_string()))?;
+
         Ok(KeyCacheInfo {
             key_id: cache.key_id,
             expires_in,
+            signing_key_id: signing_cache.key_id,
+            signing_expires_in: signing_cache.expires_at.saturating_sub(now),
         })
     }
 }
 
 /// 密钥缓存健康信息
 pub struct KeyCacheInfo {
+    /// 加密密钥 ID
     pub key_id: u32,
     pub expires_in: u64,
+    /// 签名密钥 ID
+    pub signing_key_id: u32,
+    pub signing_expires_in: u64,
 }
 
 #[cfg(test)]
@@ -37,6 +37,18 @@ impl KsClientWrapper {
         client.fetch_secret_key(key_id).await
     }
 
+    /// 订阅 KS 密钥轮替事件
+    pub async fn watch_key_rotations(&self) -> Result<ks::KeyRotationWatch, ks::KsError> {
+        let mut client = self.inner.write().await;
+        client.watch_key_rotations().await
+    }
+
+    /// 订阅 KS 密钥吊销事件
+    pub async fn watch_key_revocations(&self) -> Result<ks::KeyRevocationWatch, ks::KsError> {
+        let mut client = self.inner.write().await;
+        client.watch_key_revocations().await
+    }
+
     /// 健康检查
     pub async fn health_check(&self) -> Result<String, ks::KsError> {
         let mut client = self.inner.write().await;
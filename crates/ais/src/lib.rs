@@ -6,6 +6,10 @@
 //! - ActrId 注册：为新 Actor 分配全局唯一的序列号
 //! - 凭证签发：生成加密的 AIdCredential Token
 //! - PSK 生成：为 Actor 与 Signaling Server 的连接生成预共享密钥
+//! - 序列号预留：`/reserve-batch` 为出厂预置批量分配连续序列号，不签发凭证
+//! - 凭证吊销：按序列号吊销凭证，并向 Signaling 下发吊销过滤器（见 [`revocation`]）
+//! - PSK 轮替：Actor 凭持有旧 PSK 的证明换取新 PSK，序列号不变（见 [`psk_rotation`]）
+//! - TURN 凭证：以 AIdCredential 换取绑定 Realm、随 Token 过期的 TURN 凭证（见 [`turn_credential`]）
 //!
 //! # 架构设计
 //!
@@ -79,23 +83,50 @@
 //! # 配置选项
 //!
 //! 参见 [`actrix_common::config::AisConfig`] 获取完整配置说明。
+//!
+//! # 开发用模拟实现
+//!
+//! 启用 `mock` feature 后，`mock` 模块提供不依赖 KS 的 `/register`，
+//! 使用确定性的模拟密钥即时签发凭证，仅用于本地开发。
 
+pub mod admin_auth;
 pub mod handlers;
 pub mod issuer;
 pub mod ks_client_wrapper;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod psk_rotation;
 pub mod ratelimit;
+pub mod revocation;
 mod sn;
 mod storage;
+pub mod turn_credential;
 
 pub use issuer::{AIdIssuer, IssuerConfig, KeyCacheInfo};
+pub use revocation::{RevocationRecord, RevocationStore, RevokeCredentialRequest};
+pub use storage::{DailyRegistrations, RegistrationRecord, SerialReservation};
 
+use crate::admin_auth::AdminAuth;
 use crate::handlers::{AISState, create_router};
 use crate::ks_client_wrapper::create_ks_client;
+use crate::psk_rotation::PskRotationState;
+use crate::ratelimit::RegisterRateLimiter;
+use crate::revocation::RevocationState;
+use crate::turn_credential::TurnCredentialState;
+use actrix_common::NonceStore;
 use actrix_common::config::AisConfig;
 use anyhow::{Context, Result};
 use axum::Router;
+use std::sync::Arc;
 use tracing::info;
 
+/// 注册 AIS 指标（序列号生成器的时钟漂移与回拨统计、租户级限流统计）
+pub fn register_ais_metrics(registry: &prometheus::Registry) -> Result<(), prometheus::Error> {
+    sn::register_metrics(registry)?;
+    ratelimit::register_metrics(registry)?;
+    Ok(())
+}
+
 /// 创建 AIS 路由器，遵循项目的 HttpRouterService 架构
 pub async fn create_ais_router(
     config: &AisConfig,
@@ -120,9 +151,11 @@ pub async fn create_ais_router(
         token_ttl_secs: config.server.token_ttl_secs,
         signaling_heartbeat_interval_secs: config.server.signaling_heartbeat_interval_secs,
         key_refresh_interval_secs: 3600, // 1 小时
-        key_storage_file: global_config.sqlite_path.join("ais_keys.db"),
-        enable_periodic_rotation: false, // 默认禁用，可通过配置文件开启
-        key_rotation_interval_secs: 86400, // 24 小时
+        key_storage: global_config.sqlite_location("ais_keys.db"),
+        enable_periodic_rotation: config.server.enable_periodic_rotation,
+        key_rotation_interval_secs: config.server.encryption_key_rotation_interval_secs,
+        signing_key_rotation_interval_secs: config.server.signing_key_rotation_interval_secs,
+        serial_number: config.serial_number.clone(),
     };
 
     // 创建 AId Token 签发器
@@ -130,10 +163,56 @@ pub async fn create_ais_router(
         .await
         .context("Failed to create AIS issuer")?;
 
-    let state = AISState::new(issuer);
+    // 管理端点使用 actrix_shared_key 签名的 nonce 凭证认证
+    let nonce_storage = NonceStore::from_config(
+        &global_config.nonce_storage_config(),
+        &global_config.sqlite_path,
+    )
+    .await
+    .context("Failed to create nonce storage")?;
+    let auth = AdminAuth::new(
+        Arc::new(nonce_storage),
+        global_config.get_actrix_shared_key(),
+    );
+
+    let state = AISState::new(issuer, auth.clone())
+        .with_register_limiter(RegisterRateLimiter::new(&config.rate_limit));
+    AIdIssuer::register(state.issuer.clone());
+
+    // 创建凭证吊销存储
+    let revocation_store = RevocationStore::new(
+        global_config.sqlite_location("ais_revocations.db"),
+        &config.revocation,
+    )
+    .await
+    .context("Failed to create AIS revocation storage")?;
+    let psk_rotation = PskRotationState {
+        issuer: state.issuer.clone(),
+        store: revocation_store.clone(),
+        shared_key: global_config.get_actrix_shared_key().to_string(),
+        config: config.psk_rotation.clone(),
+    };
+    let turn_credential = TurnCredentialState {
+        issuer: state.issuer.clone(),
+        store: revocation_store.clone(),
+        shared_key: global_config.get_actrix_shared_key().to_string(),
+        urls: if global_config.is_turn_enabled() {
+            vec![global_config.turn_url()]
+        } else {
+            Vec::new()
+        },
+        turn_realm: global_config
+            .is_turn_enabled()
+            .then(|| global_config.turn.realm.clone()),
+    };
+    let revocation = RevocationState {
+        store: revocation_store,
+        auth,
+        default_retention_secs: config.server.token_ttl_secs,
+    };
 
     // 创建路由器
-    let router = create_router(state);
+    let router = create_router(state, revocation, psk_rotation, turn_credential);
 
     info!("AIS router created successfully");
     Ok(router)
@@ -0,0 +1,219 @@
+//! 开发用 AIS 模拟实现（`mock` feature）
+//!
+//! `/register` 直接使用模拟 KS 的确定性密钥（`ks::mock`）签发凭证：不访问 KS，
+//! 不加载或持久化密钥，收到请求即返回。签发的凭证与真实 AIS 格式相同（签名 + ECIES 加密），
+//! 连接模拟 KS 的 Signaling 可以正常验证。
+//!
+//! 模拟密钥不具备任何保密性，只能用于本地开发。
+//!
+//! `/revocations` 始终返回空的吊销过滤器，模拟 AIS 不支持吊销。
+
+use crate::handlers::encode_result;
+use crate::sn::{AIdSerialNumberIssuer, SerialNumber};
+use actr_protocol::{
+    AIdCredential, ActrId, ErrorResponse, RegisterRequest, RegisterResponse, register_response,
+};
+use actrix_common::aid::{
+    AidError, CredentialMetadata, IdentityClaims, RevocationFilter, RevocationListResponse,
+    SignedToken,
+};
+use actrix_common::config::AisConfig;
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    response::Json,
+    routing::{get, post},
+};
+use prost::Message;
+use prost::bytes::Bytes as ProstBytes;
+use prost_types::Timestamp;
+use rand::RngCore;
+use serde_json::{Value, json};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// 模拟凭证使用的加密密钥 ID（对应 `ks::mock::mock_secret_key`）
+pub const MOCK_ENCRYPTION_KEY_ID: u32 = 1;
+
+/// 模拟凭证使用的签名密钥 ID
+pub const MOCK_SIGNING_KEY_ID: u32 = 2;
+
+/// PSK 长度（与真实 AIS 一致）
+const MOCK_PSK_LENGTH: usize = 32;
+
+#[derive(Debug, Clone)]
+struct MockAisState {
+    token_ttl_secs: u64,
+    signaling_heartbeat_interval_secs: u32,
+}
+
+/// 创建模拟 AIS 路由（挂载到 `/ais`）
+pub fn create_mock_router(config: &AisConfig) -> Router {
+    warn!("⚠️  Using mock AIS: credentials are issued with publicly derivable keys");
+
+    let state = MockAisState {
+        token_ttl_secs: config.server.token_ttl_secs,
+        signaling_heartbeat_interval_secs: config.server.signaling_heartbeat_interval_secs,
+    };
+
+    Router::new()
+        .route("/register", post(register_actr))
+        .route("/health", get(health_check))
+        .route("/revocations", get(get_revocations))
+        .with_state(state)
+}
+
+async fn register_actr(State(state): State<MockAisState>, body: Bytes) -> Bytes {
+    let result = match RegisterRequest::decode(body) {
+        Ok(request) => match issue_mock_credential(&request, &state) {
+            Ok(register_ok) => register_response::Result::Success(register_ok),
+            Err(err) => register_response::Result::Error(ErrorResponse {
+                code: 500,
+                message: err.to_string(),
+            }),
+        },
+        Err(err) => register_response::Result::Error(ErrorResponse {
+            code: 400,
+            message: format!("Invalid protobuf: {err}"),
+        }),
+    };
+
+    encode_result(RegisterResponse {
+        result: Some(result),
+    })
+}
+
+async fn health_check() -> Json<Value> {
+    Json(json!({
+        "service": "ais",
+        "version": env!("CARGO_PKG_VERSION"),
+        "status": "healthy",
+        "mock": true
+    }))
+}
+
+async fn get_revocations() -> Json<RevocationListResponse> {
+    Json(RevocationListResponse {
+        count: 0,
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        filter: RevocationFilter::default(),
+        psk_rotations: Vec::new(),
+    })
+}
+
+/// 使用模拟密钥签发凭证
+fn issue_mock_credential(
+    request: &RegisterRequest,
+    state: &MockAisState,
+) -> Result<register_response::RegisterOk, AidError> {
+    let actr_id = ActrId {
+        realm: request.realm,
+        serial_number: SerialNumber::sn(request.realm.realm_id)
+            .map_err(|e| AidError::GenerationFailed(e.to_string()))?
+            .value(),
+        r#type: request.actr_type.clone(),
+    };
+
+    let expr_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + state.token_ttl_secs;
+
+    let mut psk = vec![0u8; MOCK_PSK_LENGTH];
+    rand::thread_rng().fill_bytes(&mut psk);
+
+    let claims = IdentityClaims::from_actr_id(&actr_id, expr_time, psk.clone());
+    let metadata = CredentialMetadata {
+        encryption_key_id: MOCK_ENCRYPTION_KEY_ID,
+        signing_key_id: MOCK_SIGNING_KEY_ID,
+    };
+    let signed_token = SignedToken::sign(
+        &claims,
+        metadata,
+        &ks::mock::mock_secret_key(MOCK_SIGNING_KEY_ID),
+    )?;
+
+    let token_bytes = serde_json::to_vec(&signed_token)?;
+    let encrypted_token = ecies::encrypt(
+        &ks::mock::mock_public_key(MOCK_ENCRYPTION_KEY_ID).serialize(),
+        &token_bytes,
+    )
+    .map_err(|e| AidError::GenerationFailed(format!("Encryption error: {e}")))?;
+
+    debug!(
+        "Mock AIS issued credential: realm={}, serial_number={}",
+        actr_id.realm.realm_id, actr_id.serial_number
+    );
+
+    Ok(register_response::RegisterOk {
+        actr_id,
+        credential: AIdCredential {
+            encrypted_token: ProstBytes::from(encrypted_token),
+            token_key_id: MOCK_ENCRYPTION_KEY_ID,
+        },
+        psk: Some(ProstBytes::from(psk)),
+        credential_expires_at: Some(Timestamp {
+            seconds: expr_time as i64,
+            nanos: 0,
+        }),
+        signaling_heartbeat_interval_secs: state.signaling_heartbeat_interval_secs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::{ActrType, Realm};
+    use actrix_common::aid::AIdCredentialValidator;
+    use ecies::PublicKey;
+
+    #[test]
+    fn test_mock_credential_verifies_with_mock_keys() {
+        let request = RegisterRequest {
+            realm: Realm { realm_id: 1001 },
+            actr_type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: "device".to_string(),
+                version: None,
+            },
+            service: None,
+            service_spec: None,
+            acl: None,
+            ws_address: None,
+        };
+        let state = MockAisState {
+            token_ttl_secs: 3600,
+            signaling_heartbeat_interval_secs: 30,
+        };
+
+        let register_ok = issue_mock_credential(&request, &state).unwrap();
+        assert_eq!(register_ok.actr_id.realm.realm_id, 1001);
+
+        let verifying_key =
+            PublicKey::from_secret_key(&ks::mock::mock_secret_key(MOCK_SIGNING_KEY_ID));
+        let claims = AIdCredentialValidator::check_with_keys(
+            &register_ok.credential,
+            1001,
+            &ks::mock::mock_secret_key(MOCK_ENCRYPTION_KEY_ID),
+            Some(&verifying_key),
+        )
+        .unwrap();
+        assert_eq!(claims.psk, register_ok.psk.unwrap().to_vec());
+
+        // Realm 不匹配时拒绝
+        assert!(
+            AIdCredentialValidator::check_with_keys(
+                &register_ok.credential,
+                1002,
+                &ks::mock::mock_secret_key(MOCK_ENCRYPTION_KEY_ID),
+                Some(&verifying_key),
+            )
+            .is_err()
+        );
+    }
+}
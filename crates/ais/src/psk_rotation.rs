@@ -0,0 +1,384 @@
+//! AIS PSK 轮替
+//!
+//! # 功能
+//!
+//! 长期运行的 Actor 可以在不重新注册的情况下更换 PSK（序列号保持不变）：
+//!
+//! 1. `POST /ais/rotate-psk/challenge`：获取一次性挑战。挑战无状态，由
+//!    `actrix_shared_key` 做 HMAC 保护并携带过期时间
+//! 2. `POST /ais/rotate-psk`：提交当前凭证、挑战与证明
+//!    `Base64(HMAC-SHA256(当前 PSK, "rotate-psk:" + challenge))`（见 [`psk_proof`]）
+//!
+//! 验证通过后 AIS 签发 PSK 代数加 1 的新凭证与新 PSK，并记录轮替
+//! （[`crate::revocation::RevocationStore::record_psk_rotation`]）。轮替记录随吊销过滤器
+//! 下发给 Signaling：上一代凭证只在重叠窗口（`psk_rotation.overlap_secs`）内继续有效，
+//! 之后被拒绝；更早的代数立即失效。
+//!
+//! 同一代数的并发轮替只有一个成功，其余返回 409，客户端应使用成功的那次结果。
+//!
+//! 轮替记录与吊销记录使用同一存储：配置 `[services.ais.revocation.redis]` 后写入 Redis，
+//! 多个 AIS 实例共享当前代数，跨实例的并发轮替同样只有一个成功。
+
+use crate::issuer::AIdIssuer;
+use crate::revocation::RevocationStore;
+use actr_protocol::AIdCredential;
+use actrix_common::aid::{AidError, PskRotation};
+use actrix_common::config::ais::AisPskRotationConfig;
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use prost::Message;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 挑战 MAC 的域分隔前缀
+const CHALLENGE_DOMAIN: &[u8] = b"actrix-psk-challenge\n";
+
+/// 挑战中随机数长度（字节）
+const CHALLENGE_NONCE_LEN: usize = 16;
+
+/// 挑战 MAC 截断长度（字节）
+const CHALLENGE_MAC_LEN: usize = 16;
+
+/// 挑战总长度：过期时间 (8) + 随机数 + MAC
+const CHALLENGE_LEN: usize = 8 + CHALLENGE_NONCE_LEN + CHALLENGE_MAC_LEN;
+
+/// PSK 证明的签名前缀
+const PROOF_PREFIX: &str = "rotate-psk:";
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 计算持有 PSK 的证明：`Base64(HMAC-SHA256(psk, "rotate-psk:" + challenge))`
+pub fn psk_proof(psk: &[u8], challenge: &str) -> String {
+    let mut mac = HmacSha256::new_from_slice(psk).expect("HMAC accepts keys of any length");
+    mac.update(PROOF_PREFIX.as_bytes());
+    mac.update(challenge.as_bytes());
+    BASE64_STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// 常量时间校验 PSK 证明
+fn verify_psk_proof(psk: &[u8], challenge: &str, proof: &str) -> bool {
+    let Ok(proof) = BASE64_STANDARD.decode(proof) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(psk).expect("HMAC accepts keys of any length");
+    mac.update(PROOF_PREFIX.as_bytes());
+    mac.update(challenge.as_bytes());
+    mac.verify_slice(&proof).is_ok()
+}
+
+fn challenge_mac(shared_key: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(shared_key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(CHALLENGE_DOMAIN);
+    mac.update(body);
+    mac
+}
+
+/// 生成过期时间为 `expires_at` 的挑战
+fn issue_challenge(shared_key: &str, expires_at: u64) -> String {
+    let mut raw = Vec::with_capacity(CHALLENGE_LEN);
+    raw.extend_from_slice(&expires_at.to_be_bytes());
+    let mut nonce = [0u8; CHALLENGE_NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    raw.extend_from_slice(&nonce);
+
+    let tag = challenge_mac(shared_key, &raw).finalize().into_bytes();
+    raw.extend_from_slice(&tag[..CHALLENGE_MAC_LEN]);
+    BASE64_URL_SAFE_NO_PAD.encode(raw)
+}
+
+/// 校验挑战的 MAC 与有效期
+fn verify_challenge(
+    shared_key: &str,
+    challenge: &str,
+    now: u64,
+    ttl_secs: u64,
+) -> Result<(), String> {
+    let raw = BASE64_URL_SAFE_NO_PAD
+        .decode(challenge)
+        .map_err(|_| "Malformed challenge".to_string())?;
+    if raw.len() != CHALLENGE_LEN {
+        return Err("Malformed challenge".to_string());
+    }
+
+    let (body, tag) = raw.split_at(8 + CHALLENGE_NONCE_LEN);
+    challenge_mac(shared_key, body)
+        .verify_truncated_left(tag)
+        .map_err(|_| "Invalid challenge".to_string())?;
+
+    let mut expires_at = [0u8; 8];
+    expires_at.copy_from_slice(&body[..8]);
+    let expires_at = u64::from_be_bytes(expires_at);
+    if now > expires_at {
+        return Err("Challenge expired".to_string());
+    }
+    // 拒绝超出配置 TTL 的挑战（配置缩短后旧挑战立即失效）
+    if expires_at > now + ttl_secs {
+        return Err("Invalid challenge".to_string());
+    }
+    Ok(())
+}
+
+/// PSK 轮替请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotatePskRequest {
+    /// 凭证所属 Realm
+    pub realm_id: u32,
+    /// 当前凭证（Base64 编码的 protobuf `AIdCredential`）
+    pub credential: String,
+    /// `/rotate-psk/challenge` 返回的挑战
+    pub challenge: String,
+    /// 持有当前 PSK 的证明（见 [`psk_proof`]）
+    pub proof: String,
+}
+
+/// PSK 轮替路由状态
+#[derive(Clone)]
+pub struct PskRotationState {
+    pub issuer: Arc<AIdIssuer>,
+    pub store: RevocationStore,
+    /// 挑战 MAC 密钥（actrix_shared_key）
+    pub shared_key: String,
+    pub config: AisPskRotationConfig,
+}
+
+/// 创建 PSK 轮替路由（挂载到 `/ais`）
+pub fn create_psk_rotation_router(state: PskRotationState) -> Router {
+    Router::new()
+        .route("/rotate-psk/challenge", post(create_challenge))
+        .route("/rotate-psk", post(rotate_psk))
+        .with_state(state)
+}
+
+fn error_response(status: StatusCode, message: String) -> (StatusCode, Json<Value>) {
+    (
+        status,
+        Json(json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+}
+
+/// 签发挑战
+async fn create_challenge(State(state): State<PskRotationState>) -> Json<Value> {
+    let expires_at = now_secs() + state.config.challenge_ttl_secs;
+    Json(json!({
+        "status": "success",
+        "challenge": issue_challenge(&state.shared_key, expires_at),
+        "expires_at": expires_at
+    }))
+}
+
+/// 轮替 PSK
+async fn rotate_psk(
+    State(state): State<PskRotationState>,
+    Json(request): Json<RotatePskRequest>,
+) -> (StatusCode, Json<Value>) {
+    let credential = match BASE64_STANDARD
+        .decode(&request.credential)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| AIdCredential::decode(bytes.as_slice()).map_err(|e| e.to_string()))
+    {
+        Ok(credential) => credential,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Invalid credential: {e}"));
+        }
+    };
+
+    let now = now_secs();
+    if let Err(message) = verify_challenge(
+        &state.shared_key,
+        &request.challenge,
+        now,
+        state.config.challenge_ttl_secs,
+    ) {
+        warn!("Rejected PSK rotation: {}", message);
+        return error_response(StatusCode::UNAUTHORIZED, message);
+    }
+
+    let claims = match state
+        .issuer
+        .verify_credential(&credential, request.realm_id)
+        .await
+    {
+        Ok(claims) => claims,
+        Err(AidError::GenerationFailed(msg)) => {
+            error!("Failed to verify credential for PSK rotation: {}", msg);
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, msg);
+        }
+        Err(e) => {
+            warn!("Rejected PSK rotation with invalid credential: {}", e);
+            return error_response(StatusCode::UNAUTHORIZED, e.to_string());
+        }
+    };
+
+    if !verify_psk_proof(&claims.psk, &request.challenge, &request.proof) {
+        warn!(
+            "Rejected PSK rotation for {}: invalid proof",
+            claims.actor_id
+        );
+        return error_response(StatusCode::UNAUTHORIZED, "Invalid PSK proof".to_string());
+    }
+
+    let Some(serial_number) = claims.serial_number() else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Credential has no serial number".to_string(),
+        );
+    };
+
+    match state.store.get(serial_number).await {
+        Ok(Some(record)) if record.expires_at >= now => {
+            warn!(
+                "Rejected PSK rotation for revoked serial_number {}",
+                serial_number
+            );
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                AidError::CredentialRevoked(serial_number).to_string(),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to query revocation record: {}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    }
+
+    match state.store.psk_epoch(serial_number).await {
+        Ok(Some(current)) if current > claims.psk_epoch => {
+            warn!(
+                "Rejected PSK rotation for serial_number {}: epoch {} superseded by {}",
+                serial_number, claims.psk_epoch, current
+            );
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                AidError::PskSuperseded(serial_number).to_string(),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to query PSK rotation: {}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    }
+
+    let register_ok = match state.issuer.rotate_psk(&claims).await {
+        Ok(register_ok) => register_ok,
+        Err(e) => {
+            error!("Failed to issue rotated credential: {}", e);
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, e.to_string());
+        }
+    };
+
+    let psk_epoch = claims.psk_epoch + 1;
+    let previous_valid_until = (now + state.config.overlap_secs).min(claims.expr_time);
+    let rotation = PskRotation {
+        serial_number,
+        psk_epoch,
+        previous_valid_until,
+    };
+    match state
+        .store
+        .record_psk_rotation(&rotation, claims.expr_time.max(previous_valid_until))
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            warn!(
+                "Concurrent PSK rotation for serial_number {} (epoch {})",
+                serial_number, psk_epoch
+            );
+            return error_response(
+                StatusCode::CONFLICT,
+                "PSK rotation already in progress".to_string(),
+            );
+        }
+        Err(e) => {
+            error!("Failed to record PSK rotation: {}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    }
+
+    info!(
+        "Rotated PSK of serial_number {} to epoch {} (previous valid until {})",
+        serial_number, psk_epoch, previous_valid_until
+    );
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "serial_number": serial_number,
+            "psk_epoch": psk_epoch,
+            "credential": BASE64_STANDARD.encode(register_ok.credential.encode_to_vec()),
+            "psk": register_ok.psk.as_ref().map(|psk| BASE64_STANDARD.encode(psk)),
+            "credential_expires_at": register_ok.credential_expires_at.map(|t| t.seconds),
+            "previous_valid_until": previous_valid_until
+        })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARED_KEY: &str = "test-shared-key";
+
+    #[test]
+    fn test_challenge_roundtrip() {
+        let now = now_secs();
+        let challenge = issue_challenge(SHARED_KEY, now + 60);
+        assert!(verify_challenge(SHARED_KEY, &challenge, now, 60).is_ok());
+
+        // 过期
+        assert_eq!(
+            verify_challenge(SHARED_KEY, &challenge, now + 61, 60),
+            Err("Challenge expired".to_string())
+        );
+        // 超出 TTL
+        assert!(verify_challenge(SHARED_KEY, &challenge, now, 30).is_err());
+        // 其他密钥签发
+        assert!(verify_challenge("other-key", &challenge, now, 60).is_err());
+    }
+
+    #[test]
+    fn test_challenge_tampering() {
+        let now = now_secs();
+        let challenge = issue_challenge(SHARED_KEY, now + 60);
+        let mut raw = BASE64_URL_SAFE_NO_PAD.decode(&challenge).unwrap();
+
+        // 篡改过期时间
+        raw[7] ^= 1;
+        let tampered = BASE64_URL_SAFE_NO_PAD.encode(&raw);
+        assert_eq!(
+            verify_challenge(SHARED_KEY, &tampered, now, 60),
+            Err("Invalid challenge".to_string())
+        );
+
+        assert!(verify_challenge(SHARED_KEY, "not-a-challenge", now, 60).is_err());
+    }
+
+    #[test]
+    fn test_psk_proof() {
+        let psk = [7u8; 32];
+        let proof = psk_proof(&psk, "challenge");
+        assert!(verify_psk_proof(&psk, "challenge", &proof));
+        assert!(!verify_psk_proof(&psk, "other-challenge", &proof));
+        assert!(!verify_psk_proof(&[8u8; 32], "challenge", &proof));
+        assert!(!verify_psk_proof(&psk, "challenge", "!!!"));
+    }
+}
@@ -2,15 +2,46 @@
 //!
 //! 限流策略：
 //! - **IP 级别**：每个 IP 最多 100 req/min（突发 100 请求）
+//! - **Realm 级别**：同一 Realm 的注册共享配额（`services.ais.rate_limit.realm`）
+//! - **厂商级别**：同一 Realm 内同一厂商的注册共享配额（`services.ais.rate_limit.manufacturer`）
 //!
-//! 使用 tower-governor v0.8 实现限流，防止 DoS 攻击和资源耗尽
+//! IP 级别使用 tower-governor v0.8 中间件实现，防止 DoS 攻击和资源耗尽；
+//! Realm 与厂商级别需要解析 RegisterRequest，由 `/register` 处理器调用 [`RegisterRateLimiter`]。
 
+use actrix_common::config::ais::{AisRateLimitConfig, RegisterRateLimit};
 use axum::body::Body;
 use governor::middleware::NoOpMiddleware;
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use lazy_static::lazy_static;
+use prometheus::{IntCounterVec, Opts};
+use std::hash::Hash;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use tower_governor::{
     GovernorLayer, governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor,
 };
+use tracing::warn;
+
+/// 单层限流器跟踪的键数量上限，超过后清理已回满的令牌桶
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+lazy_static! {
+    static ref AIS_REGISTER_RATE_LIMITED: IntCounterVec = IntCounterVec::new(
+        Opts::new(
+            "actrix_ais_register_rate_limited_total",
+            "Total number of register requests rejected by tenant rate limits"
+        )
+        .namespace("actrix"),
+        &["scope"]
+    )
+    .unwrap();
+}
+
+/// 注册租户级限流指标
+pub(crate) fn register_metrics(registry: &prometheus::Registry) -> Result<(), prometheus::Error> {
+    registry.register(Box::new(AIS_REGISTER_RATE_LIMITED.clone()))?;
+    Ok(())
+}
 
 /// IP 级别限流配置
 ///
@@ -33,6 +64,84 @@ pub fn ip_rate_limiter() -> GovernorLayer<SmartIpKeyExtractor, NoOpMiddleware, B
     GovernorLayer::new(governor_conf)
 }
 
+/// 注册请求的租户级限流器（Realm 层与厂商层）
+pub struct RegisterRateLimiter {
+    realm: Option<DefaultKeyedRateLimiter<u32>>,
+    manufacturer: Option<DefaultKeyedRateLimiter<(u32, String)>>,
+}
+
+impl Default for RegisterRateLimiter {
+    fn default() -> Self {
+        Self::new(&AisRateLimitConfig::default())
+    }
+}
+
+impl RegisterRateLimiter {
+    /// 按配置创建，未启用的层不限流
+    pub fn new(config: &AisRateLimitConfig) -> Self {
+        Self {
+            realm: keyed_limiter(&config.realm),
+            manufacturer: keyed_limiter(&config.manufacturer),
+        }
+    }
+
+    /// 检查注册请求是否在配额内，超限时返回错误信息
+    ///
+    /// 先检查 Realm 层再检查厂商层；被 Realm 层拒绝的请求不消耗厂商层配额
+    pub fn check(&self, realm_id: u32, manufacturer: &str) -> Result<(), String> {
+        if let Some(ref limiter) = self.realm {
+            shrink_if_needed(limiter);
+            if limiter.check_key(&realm_id).is_err() {
+                warn!("Realm {} exceeded AIS register rate limit", realm_id);
+                AIS_REGISTER_RATE_LIMITED
+                    .with_label_values(&["realm"])
+                    .inc();
+                return Err(format!("Register rate limit exceeded for realm {realm_id}"));
+            }
+        }
+
+        if let Some(ref limiter) = self.manufacturer {
+            shrink_if_needed(limiter);
+            if limiter
+                .check_key(&(realm_id, manufacturer.to_string()))
+                .is_err()
+            {
+                warn!(
+                    "Manufacturer {} in realm {} exceeded AIS register rate limit",
+                    manufacturer, realm_id
+                );
+                AIS_REGISTER_RATE_LIMITED
+                    .with_label_values(&["manufacturer"])
+                    .inc();
+                return Err(format!(
+                    "Register rate limit exceeded for manufacturer {manufacturer} in realm {realm_id}"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn keyed_limiter<K: Hash + Eq + Clone>(
+    config: &RegisterRateLimit,
+) -> Option<DefaultKeyedRateLimiter<K>> {
+    if !config.enabled {
+        return None;
+    }
+    let per_minute = NonZeroU32::new(config.per_minute)?;
+    let burst_size = NonZeroU32::new(config.burst_size)?;
+    Some(RateLimiter::keyed(
+        Quota::per_minute(per_minute).allow_burst(burst_size),
+    ))
+}
+
+fn shrink_if_needed<K: Hash + Eq + Clone>(limiter: &DefaultKeyedRateLimiter<K>) {
+    if limiter.len() > MAX_TRACKED_KEYS {
+        limiter.retain_recent();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,4 +151,48 @@ mod tests {
         let _limiter = ip_rate_limiter();
         // 如果能创建成功，说明配置正确
     }
+
+    fn limit(burst_size: u32) -> RegisterRateLimit {
+        RegisterRateLimit {
+            enabled: true,
+            per_minute: 1,
+            burst_size,
+        }
+    }
+
+    #[test]
+    fn test_realm_limit_is_isolated_per_realm() {
+        let limiter = RegisterRateLimiter::new(&AisRateLimitConfig {
+            realm: limit(2),
+            ..Default::default()
+        });
+
+        assert!(limiter.check(1, "acme").is_ok());
+        assert!(limiter.check(1, "globex").is_ok());
+        assert!(limiter.check(1, "acme").is_err());
+        // 其他 Realm 不受影响
+        assert!(limiter.check(2, "acme").is_ok());
+    }
+
+    #[test]
+    fn test_manufacturer_limit_is_scoped_to_realm() {
+        let limiter = RegisterRateLimiter::new(&AisRateLimitConfig {
+            manufacturer: limit(1),
+            ..Default::default()
+        });
+
+        assert!(limiter.check(1, "acme").is_ok());
+        assert!(limiter.check(1, "acme").is_err());
+        // 同一 Realm 的其他厂商与其他 Realm 的同名厂商不受影响
+        assert!(limiter.check(1, "globex").is_ok());
+        assert!(limiter.check(2, "acme").is_ok());
+    }
+
+    #[test]
+    fn test_disabled_limits_allow_everything() {
+        let limiter = RegisterRateLimiter::default();
+        for _ in 0..1000 {
+            assert!(limiter.check(1, "acme").is_ok());
+        }
+    }
 }
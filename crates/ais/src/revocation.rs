@@ -0,0 +1,859 @@
+//! AIS 凭证吊销
+//!
+//! # 功能
+//!
+//! 按序列号吊销已签发的 AIdCredential，使被攻破的 Actor 在 Token 过期前即被拒绝：
+//!
+//! - `POST /ais/revoke`：管理端点（见 [`crate::admin_auth`]），签名 payload 为
+//!   `revoke:{serial_number}`
+//! - `GET /ais/revocations`：返回未过期吊销记录构成的布隆过滤器
+//!   （[`RevocationListResponse`]），Signaling 定期拉取并交给凭证验证器
+//!
+//! # 数据模型
+//!
+//! ```sql
+//! CREATE TABLE revoked_credentials (
+//!     serial_number INTEGER PRIMARY KEY,
+//!     reason TEXT,
+//!     revoked_at INTEGER NOT NULL,            -- Unix timestamp
+//!     expires_at INTEGER NOT NULL             -- Unix timestamp，此后凭证自然过期，记录可删除
+//! )
+//! ```
+//!
+//! PSK 轮替记录（见 [`crate::psk_rotation`]）保存在同一数据库中，随吊销过滤器一并下发：
+//!
+//! ```sql
+//! CREATE TABLE psk_rotations (
+//!     serial_number INTEGER PRIMARY KEY,
+//!     psk_epoch INTEGER NOT NULL,             -- 最新的 PSK 代数
+//!     rotated_at INTEGER NOT NULL,            -- Unix timestamp
+//!     previous_valid_until INTEGER NOT NULL,  -- Unix timestamp，上一代凭证的重叠窗口结束
+//!     expires_at INTEGER NOT NULL             -- Unix timestamp，此后旧凭证均已过期，记录可删除
+//! )
+//! ```
+//!
+//! 配置 Redis 后吊销记录同时写入有序集合 `{key_prefix}revoked`（score 为 expires_at），
+//! PSK 轮替记录同时写入哈希 `{key_prefix}psk_rotations`（字段为序列号，值为
+//! `psk_epoch:previous_valid_until:expires_at`）。代数比较与写入由 Lua 脚本原子完成，
+//! 不同 AIS 实例上对同一代数的并发轮替也只有一个成功；过滤器与当前代数由本地 SQLite
+//! 与 Redis 的记录合并得出，多个 AIS 实例共享吊销与轮替结果。
+
+use crate::admin_auth::AdminAuth;
+use actrix_common::aid::{PskRotation, RevocationFilter, RevocationListResponse};
+use actrix_common::config::ais::AisRevocationConfig;
+use actrix_common::storage::SqliteLocation;
+use anyhow::{Context, Result};
+use axum::{
+    Router,
+    extract::{Json, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use nonce_auth::NonceCredential;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::sqlite::SqlitePool;
+use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
+
+/// 吊销请求的签名 payload
+pub fn revoke_payload(serial_number: u64) -> String {
+    format!("revoke:{serial_number}")
+}
+
+/// 吊销记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationRecord {
+    /// 被吊销凭证的序列号
+    pub serial_number: u64,
+    /// 吊销原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// 吊销时间（Unix timestamp）
+    pub revoked_at: u64,
+    /// 记录过期时间（Unix timestamp）
+    pub expires_at: u64,
+}
+
+/// `POST /ais/revoke` 请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevokeCredentialRequest {
+    /// 要吊销的序列号
+    pub serial_number: u64,
+    /// 吊销原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// 记录保留到的时间（Unix timestamp），默认当前时间加 Token 有效期
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    /// 签名凭证，payload 为 [`revoke_payload`]
+    pub credential: NonceCredential,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 吊销记录存储（SQLite，可选 Redis）
+#[derive(Clone)]
+pub struct RevocationStore {
+    pool: SqlitePool,
+    false_positive_rate: f64,
+    #[cfg(feature = "redis")]
+    redis: Option<RedisRevocations>,
+}
+
+impl RevocationStore {
+    /// 创建或打开吊销记录存储
+    pub async fn new(
+        location: impl Into<SqliteLocation>,
+        config: &AisRevocationConfig,
+    ) -> Result<Self> {
+        let pool = location
+            .into()
+            .connect(5)
+            .await
+            .context("Failed to connect to SQLite")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS revoked_credentials (
+                serial_number INTEGER PRIMARY KEY,
+                reason TEXT,
+                revoked_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to create revoked_credentials table")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS psk_rotations (
+                serial_number INTEGER PRIMARY KEY,
+                psk_epoch INTEGER NOT NULL,
+                rotated_at INTEGER NOT NULL,
+                previous_valid_until INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to create psk_rotations table")?;
+
+        #[cfg(feature = "redis")]
+        let redis = match &config.redis {
+            Some(redis) => Some(RedisRevocations::connect(&redis.url, &redis.key_prefix).await?),
+            None => None,
+        };
+        #[cfg(not(feature = "redis"))]
+        if config.redis.is_some() {
+            anyhow::bail!(
+                "Redis revocation storage not enabled. Compile with --features ais-redis"
+            );
+        }
+
+        info!(
+            "Revocation storage initialized (false_positive_rate={})",
+            config.false_positive_rate
+        );
+        Ok(Self {
+            pool,
+            false_positive_rate: config.false_positive_rate,
+            #[cfg(feature = "redis")]
+            redis,
+        })
+    }
+
+    /// 吊销序列号
+    ///
+    /// 重复吊销保留最早的吊销时间，过期时间取两者较晚者；原因为空时保留原有原因
+    pub async fn revoke(
+        &self,
+        serial_number: u64,
+        reason: Option<String>,
+        expires_at: u64,
+    ) -> Result<RevocationRecord> {
+        sqlx::query(
+            "INSERT INTO revoked_credentials (serial_number, reason, revoked_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(serial_number) DO UPDATE SET
+                reason = COALESCE(excluded.reason, reason),
+                expires_at = MAX(expires_at, excluded.expires_at)",
+        )
+        .bind(serial_number as i64)
+        .bind(&reason)
+        .bind(now_secs() as i64)
+        .bind(expires_at as i64)
+        .execute(&self.pool)
+        .await
+        .context("Failed to insert revocation record")?;
+
+        let record = self
+            .get(serial_number)
+            .await?
+            .context("Revocation record missing after insert")?;
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            redis.revoke(serial_number, record.expires_at).await?;
+        }
+
+        self.cleanup_expired().await?;
+        Ok(record)
+    }
+
+    /// 查询本地吊销记录
+    pub async fn get(&self, serial_number: u64) -> Result<Option<RevocationRecord>> {
+        let row = sqlx::query_as::<_, (i64, Option<String>, i64, i64)>(
+            "SELECT serial_number, reason, revoked_at, expires_at
+             FROM revoked_credentials WHERE serial_number = ?1",
+        )
+        .bind(serial_number as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to query revocation record")?;
+
+        Ok(row.map(
+            |(serial_number, reason, revoked_at, expires_at)| RevocationRecord {
+                serial_number: serial_number as u64,
+                reason,
+                revoked_at: revoked_at as u64,
+                expires_at: expires_at as u64,
+            },
+        ))
+    }
+
+    /// 未过期的已吊销序列号（本地与 Redis 合并去重）
+    pub async fn active_serials(&self) -> Result<Vec<u64>> {
+        let rows = sqlx::query_as::<_, (i64,)>(
+            "SELECT serial_number FROM revoked_credentials WHERE expires_at >= ?1",
+        )
+        .bind(now_secs() as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query revocation records")?;
+
+        #[allow(unused_mut)]
+        let mut serials: BTreeSet<u64> = rows.into_iter().map(|(s,)| s as u64).collect();
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            // Redis 不可用时仍下发本地记录
+            match redis.active_serials().await {
+                Ok(shared) => serials.extend(shared),
+                Err(e) => warn!("Failed to read revocations from Redis: {}", e),
+            }
+        }
+
+        Ok(serials.into_iter().collect())
+    }
+
+    /// 记录 PSK 轮替
+    ///
+    /// 仅当新代数大于已有记录时写入；返回 false 表示该代数已被并发的轮替占用。
+    /// 配置 Redis 时以 Redis 中的记录为准，本实例的 SQLite 同步保存一份
+    pub async fn record_psk_rotation(
+        &self,
+        rotation: &PskRotation,
+        expires_at: u64,
+    ) -> Result<bool> {
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            if !redis.record_psk_rotation(rotation, expires_at).await? {
+                return Ok(false);
+            }
+            self.record_local_psk_rotation(rotation, expires_at).await?;
+            return Ok(true);
+        }
+
+        self.record_local_psk_rotation(rotation, expires_at).await
+    }
+
+    async fn record_local_psk_rotation(
+        &self,
+        rotation: &PskRotation,
+        expires_at: u64,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO psk_rotations (serial_number, psk_epoch, rotated_at, previous_valid_until, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(serial_number) DO UPDATE SET
+                psk_epoch = excluded.psk_epoch,
+                rotated_at = excluded.rotated_at,
+                previous_valid_until = excluded.previous_valid_until,
+                expires_at = MAX(expires_at, excluded.expires_at)
+             WHERE excluded.psk_epoch > psk_epoch",
+        )
+        .bind(rotation.serial_number as i64)
+        .bind(rotation.psk_epoch as i64)
+        .bind(now_secs() as i64)
+        .bind(rotation.previous_valid_until as i64)
+        .bind(expires_at as i64)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record PSK rotation")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 序列号当前的 PSK 代数（无轮替记录时为 None，本地与 Redis 取较大者）
+    pub async fn psk_epoch(&self, serial_number: u64) -> Result<Option<u32>> {
+        let row = sqlx::query_as::<_, (i64,)>(
+            "SELECT psk_epoch FROM psk_rotations WHERE serial_number = ?1",
+        )
+        .bind(serial_number as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to query PSK rotation")?;
+        let local = row.map(|(psk_epoch,)| psk_epoch as u32);
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            return Ok(local.max(redis.psk_epoch(serial_number).await?));
+        }
+
+        Ok(local)
+    }
+
+    /// 旧凭证尚未过期的 PSK 轮替记录（本地与 Redis 合并，同一序列号取最新代数）
+    pub async fn active_psk_rotations(&self) -> Result<Vec<PskRotation>> {
+        #[allow(unused_mut)]
+        let mut rotations = self.active_local_psk_rotations().await?;
+
+        #[cfg(feature = "redis")]
+        if let Some(redis) = &self.redis {
+            // Redis 不可用时仍下发本地记录
+            match redis.active_psk_rotations().await {
+                Ok(shared) => {
+                    let mut merged: std::collections::BTreeMap<u64, PskRotation> = rotations
+                        .into_iter()
+                        .map(|rotation| (rotation.serial_number, rotation))
+                        .collect();
+                    for rotation in shared {
+                        match merged.get(&rotation.serial_number) {
+                            Some(existing) if existing.psk_epoch >= rotation.psk_epoch => {}
+                            _ => {
+                                merged.insert(rotation.serial_number, rotation);
+                            }
+                        }
+                    }
+                    rotations = merged.into_values().collect();
+                }
+                Err(e) => warn!("Failed to read PSK rotations from Redis: {}", e),
+            }
+        }
+
+        Ok(rotations)
+    }
+
+    async fn active_local_psk_rotations(&self) -> Result<Vec<PskRotation>> {
+        let rows = sqlx::query_as::<_, (i64, i64, i64)>(
+            "SELECT serial_number, psk_epoch, previous_valid_until
+             FROM psk_rotations WHERE expires_at >= ?1 ORDER BY serial_number",
+        )
+        .bind(now_secs() as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query PSK rotations")?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(serial_number, psk_epoch, previous_valid_until)| PskRotation {
+                    serial_number: serial_number as u64,
+                    psk_epoch: psk_epoch as u32,
+                    previous_valid_until: previous_valid_until as u64,
+                },
+            )
+            .collect())
+    }
+
+    /// 删除已过期的吊销记录与 PSK 轮替记录，返回删除条数
+    pub async fn cleanup_expired(&self) -> Result<u64> {
+        let now = now_secs() as i64;
+        let result = sqlx::query("DELETE FROM revoked_credentials WHERE expires_at < ?1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("Failed to cleanup revocation records")?;
+        let rotations = sqlx::query("DELETE FROM psk_rotations WHERE expires_at < ?1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .context("Failed to cleanup PSK rotation records")?;
+        if rotations.rows_affected() > 0 {
+            debug!(
+                "Removed {} expired PSK rotation records",
+                rotations.rows_affected()
+            );
+        }
+
+        if result.rows_affected() > 0 {
+            debug!(
+                "Removed {} expired revocation records",
+                result.rows_affected()
+            );
+        }
+        Ok(result.rows_affected())
+    }
+
+    /// 生成下发给 Signaling 的吊销列表
+    pub async fn revocation_list(&self) -> Result<RevocationListResponse> {
+        let serials = self.active_serials().await?;
+        Ok(RevocationListResponse {
+            count: serials.len(),
+            generated_at: now_secs(),
+            filter: RevocationFilter::from_serials(&serials, self.false_positive_rate),
+            psk_rotations: self.active_psk_rotations().await?,
+        })
+    }
+}
+
+/// 仅当新代数大于已有记录时写入 PSK 轮替，过期时间取两者较晚者；返回是否写入
+#[cfg(feature = "redis")]
+const RECORD_PSK_ROTATION_SCRIPT: &str = r#"
+local current = redis.call('HGET', KEYS[1], ARGV[1])
+local expires_at = tonumber(ARGV[4])
+if current then
+    local epoch, _, current_expires_at = string.match(current, '^(%d+):(%d+):(%d+)$')
+    if epoch and tonumber(epoch) >= tonumber(ARGV[2]) then
+        return 0
+    end
+    if current_expires_at and tonumber(current_expires_at) > expires_at then
+        expires_at = tonumber(current_expires_at)
+    end
+end
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2] .. ':' .. ARGV[3] .. ':' .. string.format('%d', expires_at))
+return 1
+"#;
+
+/// Redis 中共享的吊销记录与 PSK 轮替记录
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+struct RedisRevocations {
+    conn: redis::aio::ConnectionManager,
+    key: String,
+    psk_key: String,
+}
+
+/// 解析 Redis 中的 PSK 轮替记录：`psk_epoch:previous_valid_until:expires_at`
+#[cfg(feature = "redis")]
+fn parse_redis_psk_rotation(serial_number: u64, value: &str) -> Option<(PskRotation, u64)> {
+    let mut parts = value.split(':').map(str::parse::<u64>);
+    let (Some(Ok(psk_epoch)), Some(Ok(previous_valid_until)), Some(Ok(expires_at)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some((
+        PskRotation {
+            serial_number,
+            psk_epoch: u32::try_from(psk_epoch).ok()?,
+            previous_valid_until,
+        },
+        expires_at,
+    ))
+}
+
+#[cfg(feature = "redis")]
+impl RedisRevocations {
+    async fn connect(url: &str, key_prefix: &str) -> Result<Self> {
+        let client = redis::Client::open(url).context("Invalid Redis URL")?;
+        let conn = client
+            .get_connection_manager()
+            .await
+            .context("Failed to connect to Redis")?;
+        Ok(Self {
+            conn,
+            key: format!("{key_prefix}revoked"),
+            psk_key: format!("{key_prefix}psk_rotations"),
+        })
+    }
+
+    async fn record_psk_rotation(&self, rotation: &PskRotation, expires_at: u64) -> Result<bool> {
+        let mut conn = self.conn.clone();
+        let written: i64 = redis::cmd("EVAL")
+            .arg(RECORD_PSK_ROTATION_SCRIPT)
+            .arg(1)
+            .arg(&self.psk_key)
+            .arg(rotation.serial_number)
+            .arg(rotation.psk_epoch)
+            .arg(rotation.previous_valid_until)
+            .arg(expires_at)
+            .query_async(&mut conn)
+            .await
+            .context("Failed to write PSK rotation to Redis")?;
+        Ok(written == 1)
+    }
+
+    async fn psk_epoch(&self, serial_number: u64) -> Result<Option<u32>> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = redis::cmd("HGET")
+            .arg(&self.psk_key)
+            .arg(serial_number)
+            .query_async(&mut conn)
+            .await
+            .context("Failed to read PSK rotation from Redis")?;
+        Ok(value
+            .as_deref()
+            .and_then(|value| parse_redis_psk_rotation(serial_number, value))
+            .map(|(rotation, _)| rotation.psk_epoch))
+    }
+
+    /// 旧凭证尚未过期的轮替记录，顺带删除已过期的记录
+    async fn active_psk_rotations(&self) -> Result<Vec<PskRotation>> {
+        let mut conn = self.conn.clone();
+        let now = now_secs();
+        let entries: Vec<(u64, String)> = redis::cmd("HGETALL")
+            .arg(&self.psk_key)
+            .query_async(&mut conn)
+            .await?;
+
+        let mut rotations = Vec::new();
+        let mut expired = Vec::new();
+        for (serial_number, value) in entries {
+            match parse_redis_psk_rotation(serial_number, &value) {
+                Some((rotation, expires_at)) if expires_at >= now => rotations.push(rotation),
+                _ => expired.push(serial_number),
+            }
+        }
+        if !expired.is_empty() {
+            let _: i64 = redis::cmd("HDEL")
+                .arg(&self.psk_key)
+                .arg(&expired)
+                .query_async(&mut conn)
+                .await?;
+        }
+        Ok(rotations)
+    }
+
+    async fn revoke(&self, serial_number: u64, expires_at: u64) -> Result<()> {
+        let mut conn = self.conn.clone();
+        // GT：已存在时只延长过期时间
+        let _: i64 = redis::cmd("ZADD")
+            .arg(&self.key)
+            .arg("GT")
+            .arg(expires_at)
+            .arg(serial_number)
+            .query_async(&mut conn)
+            .await
+            .context("Failed to write revocation to Redis")?;
+        Ok(())
+    }
+
+    async fn active_serials(&self) -> Result<Vec<u64>> {
+        let mut conn = self.conn.clone();
+        let now = now_secs();
+        let _: i64 = redis::cmd("ZREMRANGEBYSCORE")
+            .arg(&self.key)
+            .arg("-inf")
+            .arg(format!("({now}"))
+            .query_async(&mut conn)
+            .await?;
+        let serials: Vec<u64> = redis::cmd("ZRANGEBYSCORE")
+            .arg(&self.key)
+            .arg(now)
+            .arg("+inf")
+            .query_async(&mut conn)
+            .await?;
+        Ok(serials)
+    }
+}
+
+/// 吊销端点状态
+#[derive(Clone)]
+pub struct RevocationState {
+    pub store: RevocationStore,
+    pub auth: AdminAuth,
+    /// 未指定 expires_at 时记录的保留时长（秒），取 Token 有效期
+    pub default_retention_secs: u64,
+}
+
+/// 创建吊销相关路由（挂载到 `/ais`）
+pub fn create_revocation_router(state: RevocationState) -> Router {
+    Router::new()
+        .route("/revoke", post(revoke_credential))
+        .route("/revocations", get(get_revocations))
+        .with_state(state)
+}
+
+fn error_response(status: StatusCode, message: String) -> (StatusCode, Json<Value>) {
+    (
+        status,
+        Json(json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+}
+
+/// 吊销凭证
+async fn revoke_credential(
+    State(state): State<RevocationState>,
+    Json(request): Json<RevokeCredentialRequest>,
+) -> (StatusCode, Json<Value>) {
+    if let Err((status, message)) = state
+        .auth
+        .verify(&request.credential, &revoke_payload(request.serial_number))
+        .await
+    {
+        warn!(
+            "Rejected revocation request for serial_number {}: {}",
+            request.serial_number, message
+        );
+        return error_response(status, message);
+    }
+
+    let expires_at = request
+        .expires_at
+        .unwrap_or_else(|| now_secs() + state.default_retention_secs);
+    match state
+        .store
+        .revoke(request.serial_number, request.reason, expires_at)
+        .await
+    {
+        Ok(record) => {
+            warn!(
+                "Revoked credentials of serial_number {} until {} (reason: {})",
+                record.serial_number,
+                record.expires_at,
+                record.reason.as_deref().unwrap_or("-")
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "success",
+                    "revocation": record
+                })),
+            )
+        }
+        Err(e) => {
+            error!(
+                "Failed to revoke serial_number {}: {}",
+                request.serial_number, e
+            );
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Revocation failed: {e}"),
+            )
+        }
+    }
+}
+
+/// 获取吊销过滤器
+async fn get_revocations(
+    State(state): State<RevocationState>,
+) -> Result<Json<RevocationListResponse>, (StatusCode, Json<Value>)> {
+    match state.store.revocation_list().await {
+        Ok(list) => {
+            debug!(
+                "Returning revocation filter: {} entries, {} bytes",
+                list.count,
+                list.filter.size_bytes()
+            );
+            Ok(Json(list))
+        }
+        Err(e) => {
+            error!("Failed to build revocation list: {}", e);
+            Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to build revocation list: {e}"),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use nonce_auth::{CredentialBuilder, storage::MemoryStorage};
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+    use tower::ServiceExt;
+
+    async fn create_store(dir: &Path) -> RevocationStore {
+        RevocationStore::new(
+            dir.join("ais_revocations.db"),
+            &AisRevocationConfig::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_revoke_and_filter() {
+        let temp_dir = tempdir().unwrap();
+        let store = create_store(temp_dir.path()).await;
+        let now = now_secs();
+
+        let record = store
+            .revoke(
+                0xfed02d3f000000,
+                Some("compromised".to_string()),
+                now + 3600,
+            )
+            .await
+            .unwrap();
+        assert_eq!(record.reason.as_deref(), Some("compromised"));
+        assert_eq!(record.expires_at, now + 3600);
+
+        // 重复吊销：保留原因，延长过期时间
+        let record = store
+            .revoke(0xfed02d3f000000, None, now + 7200)
+            .await
+            .unwrap();
+        assert_eq!(record.reason.as_deref(), Some("compromised"));
+        assert_eq!(record.expires_at, now + 7200);
+
+        store.revoke(42, None, now + 60).await.unwrap();
+
+        let list = store.revocation_list().await.unwrap();
+        assert_eq!(list.count, 2);
+        assert!(list.filter.contains(0xfed02d3f000000));
+        assert!(list.filter.contains(42));
+        assert!(!list.filter.contains(43));
+    }
+
+    #[tokio::test]
+    async fn test_expired_records_are_dropped() {
+        let temp_dir = tempdir().unwrap();
+        let store = create_store(temp_dir.path()).await;
+        let now = now_secs();
+
+        store.revoke(1, None, now - 10).await.unwrap();
+        store.revoke(2, None, now + 3600).await.unwrap();
+
+        assert_eq!(store.active_serials().await.unwrap(), vec![2]);
+        assert!(store.get(1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_psk_rotation_records() {
+        let temp_dir = tempdir().unwrap();
+        let store = create_store(temp_dir.path()).await;
+        let now = now_secs();
+        let rotation = |psk_epoch| PskRotation {
+            serial_number: 7,
+            psk_epoch,
+            previous_valid_until: now + 300,
+        };
+
+        assert_eq!(store.psk_epoch(7).await.unwrap(), None);
+        assert!(
+            store
+                .record_psk_rotation(&rotation(1), now + 3600)
+                .await
+                .unwrap()
+        );
+        // 同一代数的并发轮替只有一个成功
+        assert!(
+            !store
+                .record_psk_rotation(&rotation(1), now + 3600)
+                .await
+                .unwrap()
+        );
+        assert!(
+            store
+                .record_psk_rotation(&rotation(2), now + 3600)
+                .await
+                .unwrap()
+        );
+        assert_eq!(store.psk_epoch(7).await.unwrap(), Some(2));
+
+        // 旧凭证均已过期的记录不再下发
+        store
+            .record_psk_rotation(
+                &PskRotation {
+                    serial_number: 8,
+                    psk_epoch: 1,
+                    previous_valid_until: now - 20,
+                },
+                now - 10,
+            )
+            .await
+            .unwrap();
+
+        let list = store.revocation_list().await.unwrap();
+        assert_eq!(list.psk_rotations, vec![rotation(2)]);
+
+        store.cleanup_expired().await.unwrap();
+        assert_eq!(store.psk_epoch(8).await.unwrap(), None);
+    }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    fn test_parse_redis_psk_rotation() {
+        assert_eq!(
+            parse_redis_psk_rotation(7, "2:1700000300:1700003600"),
+            Some((
+                PskRotation {
+                    serial_number: 7,
+                    psk_epoch: 2,
+                    previous_valid_until: 1700000300,
+                },
+                1700003600
+            ))
+        );
+        assert_eq!(parse_redis_psk_rotation(7, "2:1700000300"), None);
+        assert_eq!(parse_redis_psk_rotation(7, "2:1700000300:1:4"), None);
+        assert_eq!(parse_redis_psk_rotation(7, "x:1:1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_endpoint() {
+        let temp_dir = tempdir().unwrap();
+        let psk = "test-psk";
+        let router = create_revocation_router(RevocationState {
+            store: create_store(temp_dir.path()).await,
+            auth: AdminAuth::new(Arc::new(MemoryStorage::new()), psk),
+            default_retention_secs: 3600,
+        });
+
+        let revoke = |secret: &str| {
+            let request = RevokeCredentialRequest {
+                serial_number: 7,
+                reason: Some("lost device".to_string()),
+                expires_at: None,
+                credential: CredentialBuilder::new(secret.as_bytes())
+                    .sign(revoke_payload(7).as_bytes())
+                    .unwrap(),
+            };
+            Request::builder()
+                .method("POST")
+                .uri("/revoke")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&request).unwrap()))
+                .unwrap()
+        };
+
+        let response = router.clone().oneshot(revoke("wrong-psk")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router.clone().oneshot(revoke(psk)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/revocations")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: RevocationListResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list.count, 1);
+        assert!(list.filter.contains(7));
+    }
+}
@@ -28,22 +28,35 @@
 //! - Timestamp (41 bits): 相对于 2023-01-01 的毫秒数
 //!   - 可表示范围: ~69.7 年 (2**41 / 1000 / 3600 / 24 / 365)
 //! - Worker ID (5 bits): 节点标识 (0-31)
-//!   - 基于 hostname + PID 哈希生成
+//!   - 由 `services.ais.serial_number.node_id` 指定，未配置时基于 hostname + PID 哈希生成
 //! - Sequence (8 bits): 同毫秒内的序列号 (0-255)
 //!   - 单毫秒最多 256 个 ID
 //! ```
 //!
 //! # 特性
 //!
-//! - **全局唯一**: 分布式环境下保证唯一性（假设 worker_id 不冲突）
+//! - **全局唯一**: 分布式环境下保证唯一性（多实例部署需为每个实例配置不同的 node_id）
 //! - **时间排序**: ID 大致按生成时间递增
 //! - **高性能**: 单节点支持 256K IDs/秒 (256 * 1000)
 //! - **无中心化**: 无需中心协调服务
 //!
 //! # 时钟回拨处理
 //!
-//! - **小幅回拨**: 使用上次时间戳 + 递增序列号
-//! - **序列号耗尽**: 强制推进时间戳（可能导致与真实时钟偏差）
+//! 生成器记录已观察到的最大墙钟时间（高水位），当前时钟低于高水位即视为回拨：
+//!
+//! - **抖动**: 不超过 1 毫秒的回拨沿用上次时间戳 + 递增序列号
+//! - **回拨**: `sn()` / `reserve()` 返回 [`SNError::ClockRegression`]；[`issue`] / [`issue_range`]
+//!   按配置的 [`ClockRegressionPolicy`] 等待时钟追上或直接拒绝
+//! - **序列号耗尽**: 强制推进时间戳（可能导致与真实时钟偏差，见 `actrix_ais_sn_lead_milliseconds`）
+//!
+//! 高水位由签发器定期写入本地存储，重启时通过 [`restore_high_water`] 恢复，
+//! 避免进程重启后在回退的时钟上重复签发。
+//!
+//! # 批量预留
+//!
+//! [`AIdSerialNumberIssuer::reserve`] 一次性占用 N 个连续的 (timestamp, sequence) 槽位，
+//! 返回 [`SerialRange`]。预留可能把内部时间戳推进到未来（每 256 个序列号推进 1 毫秒），
+//! 之后的 `sn()` 按时钟回拨处理，从预留段之后继续分配，不会与预留段重复。
 //!
 //! # 示例
 //!
@@ -51,8 +64,8 @@
 //! use ais::sn::{SerialNumber, AIdSerialNumberIssuer};
 //!
 //! // 生成序列号
-//! let sn1 = SerialNumber::sn(1);  // realm_id = 1 (当前未使用)
-//! let sn2 = SerialNumber::sn(1);
+//! let sn1 = SerialNumber::sn(1)?;  // realm_id = 1 (当前未使用)
+//! let sn2 = SerialNumber::sn(1)?;
 //!
 //! // 序列号自动递增
 //! assert!(sn2.value() > sn1.value());
@@ -66,9 +79,12 @@
 //! ⚠️ **realm_id 参数当前未使用**：序列号全局唯一，不按 realm 隔离。
 //! 如需 realm 隔离，考虑将 realm_id 嵌入 worker_id 的高位。
 
+use actrix_common::config::ais::{AisSerialNumberConfig, ClockRegressionPolicy};
+use lazy_static::lazy_static;
+use prometheus::{IntCounterVec, IntGauge, Opts};
 use std::sync::OnceLock;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 /// The number of bits used for the serial number.
 ///
@@ -99,6 +115,46 @@ pub const MAX_SEQUENCE: u64 = (1 << SEQUENCE_BITS) - 1; // 255
 static SNOWFLAKE_STATE: AtomicU64 = AtomicU64::new(0);
 static WORKER_ID: OnceLock<u64> = OnceLock::new();
 
+/// 已观察到的最大墙钟时间（相对 CUSTOM_EPOCH 的毫秒数）
+static LAST_WALL_MS: AtomicU64 = AtomicU64::new(0);
+
+/// 时钟回拨策略（未调用 [`configure`] 时使用默认配置）
+static SETTINGS: OnceLock<AisSerialNumberConfig> = OnceLock::new();
+
+/// 不视为回拨的时钟抖动（毫秒）
+const CLOCK_JITTER_TOLERANCE_MS: u64 = 1;
+
+/// 高水位持久化间隔
+///
+/// 恢复时把内部时间戳推进到持久化值之后一个间隔，覆盖最后一次持久化之后签发的序列号
+pub const HIGH_WATER_PERSIST_INTERVAL: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    static ref SN_CLOCK_DRIFT: IntGauge = IntGauge::with_opts(
+        Opts::new("actrix_ais_sn_clock_drift_milliseconds", "Clock regression observed by the serial number generator in milliseconds (0 when the clock is monotonic)")
+            .namespace("actrix")
+    ).unwrap();
+
+    static ref SN_LEAD: IntGauge = IntGauge::with_opts(
+        Opts::new("actrix_ais_sn_lead_milliseconds", "How far the serial number timestamp is ahead of the wall clock in milliseconds")
+            .namespace("actrix")
+    ).unwrap();
+
+    static ref SN_CLOCK_REGRESSIONS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_ais_sn_clock_regressions_total", "Total number of serial number requests that hit a clock regression")
+            .namespace("actrix"),
+        &["action"]
+    ).unwrap();
+}
+
+/// 注册序列号生成器指标
+pub(crate) fn register_metrics(registry: &prometheus::Registry) -> Result<(), prometheus::Error> {
+    registry.register(Box::new(SN_CLOCK_DRIFT.clone()))?;
+    registry.register(Box::new(SN_LEAD.clone()))?;
+    registry.register(Box::new(SN_CLOCK_REGRESSIONS.clone()))?;
+    Ok(())
+}
+
 /// 从 AtomicU64 中解码 timestamp 和 sequence
 #[inline]
 fn decode_state(state: u64) -> (u64, u64) {
@@ -113,6 +169,130 @@ fn encode_state(timestamp: u64, sequence: u64) -> u64 {
     (timestamp << 8) | (sequence & 0xFF)
 }
 
+/// 应用序列号生成器配置
+///
+/// 须在首次签发前调用；worker_id 一经确定不可更改，配置的 node_id 与已生效的值不同时返回错误
+pub fn configure(config: &AisSerialNumberConfig) -> Result<(), SNError> {
+    if let Some(node_id) = config.node_id {
+        let node_id = u64::from(node_id);
+        if node_id > MAX_WORKER_ID {
+            return Err(SNError::NodeIdConflict {
+                configured: node_id,
+                active: None,
+            });
+        }
+        let active = *WORKER_ID.get_or_init(|| node_id);
+        if active != node_id {
+            return Err(SNError::NodeIdConflict {
+                configured: node_id,
+                active: Some(active),
+            });
+        }
+    }
+    let _ = SETTINGS.set(config.clone());
+    Ok(())
+}
+
+fn settings() -> &'static AisSerialNumberConfig {
+    SETTINGS.get_or_init(AisSerialNumberConfig::default)
+}
+
+/// 当前使用的 worker_id
+pub fn worker_id() -> u64 {
+    init_worker_id()
+}
+
+/// 当前墙钟（相对 CUSTOM_EPOCH 的毫秒数）
+fn now_millis() -> u64 {
+    (SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64)
+        .saturating_sub(CUSTOM_EPOCH)
+}
+
+/// 读取当前墙钟并检查是否回拨，同时更新墙钟高水位
+///
+/// 读取时钟后被抢占的线程可能看到其他线程更晚的时间戳，检测到回拨时重新读取一次
+fn check_clock() -> Result<u64, SNError> {
+    let now = now_millis();
+    if detect_regression(&LAST_WALL_MS, now).is_ok() {
+        return Ok(now);
+    }
+    let now = now_millis();
+    detect_regression(&LAST_WALL_MS, now).map(|()| now)
+}
+
+fn detect_regression(high_water: &AtomicU64, now: u64) -> Result<(), SNError> {
+    let observed = high_water.fetch_max(now, Ordering::AcqRel);
+    let drift_ms = observed.saturating_sub(now);
+    SN_CLOCK_DRIFT.set(drift_ms as i64);
+    if drift_ms > CLOCK_JITTER_TOLERANCE_MS {
+        return Err(SNError::ClockRegression { drift_ms });
+    }
+    Ok(())
+}
+
+/// 记录内部时间戳领先墙钟的幅度
+fn record_lead(timestamp: u64, now: u64) {
+    SN_LEAD.set(timestamp.saturating_sub(now) as i64);
+}
+
+/// 已签发序列号的时间戳高水位（Unix 毫秒），供持久化
+pub fn high_water_ms() -> u64 {
+    let (timestamp, _) = decode_state(SNOWFLAKE_STATE.load(Ordering::Acquire));
+    timestamp.max(LAST_WALL_MS.load(Ordering::Acquire)) + CUSTOM_EPOCH
+}
+
+/// 从持久化的高水位（Unix 毫秒）恢复
+///
+/// 之后签发的序列号时间戳不早于 `high_water_ms + HIGH_WATER_PERSIST_INTERVAL`；
+/// 当前时钟低于高水位时按时钟回拨处理
+pub fn restore_high_water(high_water_ms: u64) {
+    let timestamp = high_water_ms.saturating_sub(CUSTOM_EPOCH);
+    LAST_WALL_MS.fetch_max(timestamp, Ordering::AcqRel);
+    let floor = timestamp + HIGH_WATER_PERSIST_INTERVAL.as_millis() as u64;
+    SNOWFLAKE_STATE.fetch_max(encode_state(floor, 0), Ordering::AcqRel);
+}
+
+/// 按配置的时钟回拨策略签发序列号
+pub async fn issue(realm_id: u32) -> Result<SerialNumber, SNError> {
+    with_regression_policy(|| SerialNumber::sn(realm_id)).await
+}
+
+/// 按配置的时钟回拨策略预留序列号
+pub async fn issue_range(realm_id: u32, count: u64) -> Result<SerialRange, SNError> {
+    with_regression_policy(|| SerialNumber::reserve(realm_id, count)).await
+}
+
+async fn with_regression_policy<T>(
+    generate: impl Fn() -> Result<T, SNError>,
+) -> Result<T, SNError> {
+    let settings = settings();
+    let deadline = Instant::now() + Duration::from_millis(settings.max_clock_wait_ms);
+    let mut waited = false;
+    loop {
+        match generate() {
+            Err(SNError::ClockRegression { drift_ms })
+                if settings.clock_regression == ClockRegressionPolicy::Wait
+                    && drift_ms <= settings.max_clock_wait_ms
+                    && Instant::now() < deadline =>
+            {
+                if !waited {
+                    waited = true;
+                    SN_CLOCK_REGRESSIONS.with_label_values(&["wait"]).inc();
+                }
+                tokio::time::sleep(Duration::from_millis(drift_ms)).await;
+            }
+            Err(e @ SNError::ClockRegression { .. }) => {
+                SN_CLOCK_REGRESSIONS.with_label_values(&["reject"]).inc();
+                return Err(e);
+            }
+            result => return result,
+        }
+    }
+}
+
 /// 初始化 worker_id（只执行一次）
 fn init_worker_id() -> u64 {
     *WORKER_ID.get_or_init(|| {
@@ -136,6 +316,13 @@ fn init_worker_id() -> u64 {
 pub enum SNError {
     /// The provided value exceeds the 54-bit limit.
     ValueOverflow(u64),
+    /// The wall clock moved backwards past the last observed timestamp.
+    ClockRegression { drift_ms: u64 },
+    /// The configured node id is out of range or differs from the one already in use.
+    NodeIdConflict {
+        configured: u64,
+        active: Option<u64>,
+    },
 }
 
 impl std::fmt::Display for SNError {
@@ -144,6 +331,26 @@ impl std::fmt::Display for SNError {
             SNError::ValueOverflow(val) => {
                 write!(f, "serial number value {val} exceeds the 54-bit limit")
             }
+            SNError::ClockRegression { drift_ms } => {
+                write!(
+                    f,
+                    "clock moved backwards by {drift_ms}ms, refusing to issue serial numbers"
+                )
+            }
+            SNError::NodeIdConflict {
+                configured,
+                active: Some(active),
+            } => write!(
+                f,
+                "serial number node id {configured} conflicts with node id {active} already in use"
+            ),
+            SNError::NodeIdConflict {
+                configured,
+                active: None,
+            } => write!(
+                f,
+                "serial number node id {configured} exceeds the maximum of {MAX_WORKER_ID}"
+            ),
         }
     }
 }
@@ -190,22 +397,83 @@ impl TryFrom<u64> for SerialNumber {
     }
 }
 
+/// 一段连续预留的序列号
+///
+/// 段内序列号使用同一 worker_id，按 Snowflake 顺序连续（跨毫秒时数值不连续）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialRange {
+    first: SerialNumber,
+    last: SerialNumber,
+}
+
+/// 序列号对应的 (timestamp, sequence) 槽位
+#[inline]
+fn slot_of(value: u64) -> u64 {
+    encode_state(value >> TIMESTAMP_SHIFT, value & MAX_SEQUENCE)
+}
+
+/// 由槽位与 worker_id 构造序列号
+#[inline]
+fn serial_of(slot: u64, worker_id: u64) -> SerialNumber {
+    let (timestamp, sequence) = decode_state(slot);
+    SerialNumber(
+        ((timestamp << TIMESTAMP_SHIFT) | (worker_id << WORKER_ID_SHIFT) | sequence)
+            & SerialNumber::MAX_VALUE,
+    )
+}
+
+impl SerialRange {
+    /// 由首尾序列号还原范围（需使用同一 worker_id，且 first 不晚于 last）
+    pub fn new(first: SerialNumber, last: SerialNumber) -> Option<Self> {
+        let worker_of = |sn: SerialNumber| (sn.value() >> WORKER_ID_SHIFT) & MAX_WORKER_ID;
+        if worker_of(first) != worker_of(last) || slot_of(first.value()) > slot_of(last.value()) {
+            return None;
+        }
+        Some(Self { first, last })
+    }
+
+    /// 第一个序列号
+    pub fn first(&self) -> SerialNumber {
+        self.first
+    }
+
+    /// 最后一个序列号
+    pub fn last(&self) -> SerialNumber {
+        self.last
+    }
+
+    /// 序列号个数
+    pub fn len(&self) -> u64 {
+        slot_of(self.last.value()) - slot_of(self.first.value()) + 1
+    }
+
+    /// 范围至少包含一个序列号，恒为 false
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// 按顺序遍历范围内的序列号
+    pub fn iter(&self) -> impl Iterator<Item = SerialNumber> {
+        let worker_id = (self.first.value() >> WORKER_ID_SHIFT) & MAX_WORKER_ID;
+        (slot_of(self.first.value())..=slot_of(self.last.value()))
+            .map(move |slot| serial_of(slot, worker_id))
+    }
+}
+
 pub trait AIdSerialNumberIssuer {
-    fn sn(realm_id: u32) -> SerialNumber;
+    /// 签发序列号，时钟回拨时返回 [`SNError::ClockRegression`]
+    fn sn(realm_id: u32) -> Result<SerialNumber, SNError>;
+
+    /// 预留 `count` 个连续序列号（`count` 为 0 时按 1 处理）
+    fn reserve(realm_id: u32, count: u64) -> Result<SerialRange, SNError>;
 }
 
 impl AIdSerialNumberIssuer for SerialNumber {
-    fn sn(_realm_id: u32) -> SerialNumber {
+    fn sn(_realm_id: u32) -> Result<SerialNumber, SNError> {
         let worker_id = init_worker_id();
 
-        // Get current timestamp in milliseconds
-        let current_millis = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
-
-        // Ensure timestamp is relative to our custom epoch
-        let mut timestamp = current_millis.saturating_sub(CUSTOM_EPOCH);
+        // Current timestamp relative to our custom epoch
+        let mut timestamp = check_clock()?;
 
         // Lock-free CAS loop
         loop {
@@ -213,7 +481,8 @@ impl AIdSerialNumberIssuer for SerialNumber {
             let (last_timestamp, last_sequence) = decode_state(old_state);
 
             let (new_timestamp, new_sequence) = if timestamp < last_timestamp {
-                // Clock went backwards - use last timestamp and increment sequence
+                // Timestamp borrowed ahead (jitter, exhausted sequence or reservation) -
+                // use last timestamp and increment sequence
                 if last_sequence < MAX_SEQUENCE {
                     (last_timestamp, last_sequence + 1)
                 } else {
@@ -243,43 +512,73 @@ impl AIdSerialNumberIssuer for SerialNumber {
                 Ordering::Relaxed,
             ) {
                 Ok(_) => {
+                    record_lead(new_timestamp, timestamp);
+
                     // Success! Build the final serial number
                     let sn_value = (new_timestamp << TIMESTAMP_SHIFT)
                         | (worker_id << WORKER_ID_SHIFT)
                         | new_sequence;
 
-                    return Self::new(sn_value & Self::MAX_VALUE)
-                        .expect("BUG: Masked value should always be within 54-bit range");
+                    return Ok(Self::new(sn_value & Self::MAX_VALUE)
+                        .expect("BUG: Masked value should always be within 54-bit range"));
                 }
                 Err(_) => {
                     // CAS failed, another thread modified the state
-                    // Retry with updated timestamp
-                    timestamp = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_millis() as u64
-                        - CUSTOM_EPOCH;
+                    // Retry with updated timestamp (never earlier than the one already checked)
+                    timestamp = timestamp.max(now_millis());
                     continue;
                 }
             }
         }
     }
+
+    fn reserve(_realm_id: u32, count: u64) -> Result<SerialRange, SNError> {
+        let worker_id = init_worker_id();
+        let count = count.max(1);
+
+        let now = check_clock()?;
+        let now_slot = encode_state(now, 0);
+
+        // 状态编码与槽位一致：从当前时间与上次分配之后的较晚者开始，一次占用 count 个槽位
+        let mut old_state = SNOWFLAKE_STATE.load(Ordering::Relaxed);
+        loop {
+            let first_slot = now_slot.max(old_state + 1);
+            let last_slot = first_slot + count - 1;
+            match SNOWFLAKE_STATE.compare_exchange_weak(
+                old_state,
+                last_slot,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    record_lead(decode_state(last_slot).0, now);
+                    return Ok(SerialRange {
+                        first: serial_of(first_slot, worker_id),
+                        last: serial_of(last_slot, worker_id),
+                    });
+                }
+                Err(current) => old_state = current,
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::sn::{
-        AIdSerialNumberIssuer, MAX_SEQUENCE, MAX_WORKER_ID, SerialNumber, TIMESTAMP_BITS,
-        TIMESTAMP_SHIFT, WORKER_ID_SHIFT,
+        AIdSerialNumberIssuer, CUSTOM_EPOCH, MAX_SEQUENCE, MAX_WORKER_ID, SNError, SerialNumber,
+        SerialRange, TIMESTAMP_BITS, TIMESTAMP_SHIFT, WORKER_ID_SHIFT, detect_regression,
+        high_water_ms, restore_high_water,
     };
     use std::collections::HashSet;
+    use std::sync::atomic::AtomicU64;
     use std::thread;
 
     #[test]
     fn test_serial_number_creation() {
-        let sn1 = SerialNumber::sn(1);
-        let sn2 = SerialNumber::sn(1);
-        let sn3 = SerialNumber::sn(2);
+        let sn1 = SerialNumber::sn(1).unwrap();
+        let sn2 = SerialNumber::sn(1).unwrap();
+        let sn3 = SerialNumber::sn(2).unwrap();
 
         // Different calls should produce different serial numbers
         assert_ne!(sn1.value(), sn2.value());
@@ -294,12 +593,12 @@ mod tests {
 
     #[test]
     fn test_monotonic_generation() {
-        let mut _previous = SerialNumber::sn(1).value();
+        let mut _previous = SerialNumber::sn(1).unwrap().value();
 
         // Generate several serial numbers and check they generally increase
         // (allowing for some timestamp variations)
         for _ in 0..100 {
-            let current = SerialNumber::sn(1).value();
+            let current = SerialNumber::sn(1).unwrap().value();
             // In Snowflake, newer IDs should generally be larger (timestamp component)
             // But we can't guarantee strict monotonicity due to sequence wraparound
             assert!(current <= SerialNumber::MAX_VALUE);
@@ -313,7 +612,7 @@ mod tests {
 
         // Rapidly generate many serial numbers
         for i in 0..1000 {
-            let sn = SerialNumber::sn(i % 10); // Different realms
+            let sn = SerialNumber::sn(i % 10).unwrap(); // Different realms
             assert!(
                 generated.insert(sn.value()),
                 "Duplicate serial number generated: {}",
@@ -331,7 +630,7 @@ mod tests {
                 thread::spawn(move || {
                     let mut local_set = HashSet::new();
                     for j in 0..100 {
-                        let sn = SerialNumber::sn((i * 100 + j) as u32);
+                        let sn = SerialNumber::sn((i * 100 + j) as u32).unwrap();
                         local_set.insert(sn.value());
                     }
                     local_set
@@ -355,7 +654,7 @@ mod tests {
 
     #[test]
     fn test_bit_layout_extraction() {
-        let sn = SerialNumber::sn(42);
+        let sn = SerialNumber::sn(42).unwrap();
         let value = sn.value();
 
         // Extract components (reverse of the generation process)
@@ -370,4 +669,67 @@ mod tests {
 
         println!("SN: {value}, Timestamp: {timestamp}, Worker: {worker_id}, Sequence: {sequence}");
     }
+
+    #[test]
+    fn test_reserve_batch() {
+        let range = SerialNumber::reserve(1, 1000).unwrap();
+        assert_eq!(range.len(), 1000);
+
+        let serials: Vec<u64> = range.iter().map(|sn| sn.value()).collect();
+        assert_eq!(serials.len(), 1000);
+        assert_eq!(serials[0], range.first().value());
+        assert_eq!(serials[999], range.last().value());
+        // 严格递增且互不重复
+        assert!(serials.windows(2).all(|w| w[0] < w[1]));
+
+        // 之后分配的序列号不落入预留段
+        let next = SerialNumber::sn(1).unwrap().value();
+        assert!(next > range.last().value());
+        assert!(!serials.contains(&next));
+
+        let next_range = SerialNumber::reserve(1, 10).unwrap();
+        assert!(next_range.first().value() > next);
+    }
+
+    #[test]
+    fn test_serial_range_roundtrip() {
+        let range = SerialNumber::reserve(7, 300).unwrap();
+        assert_eq!(SerialRange::new(range.first(), range.last()), Some(range));
+        assert!(SerialRange::new(range.last(), range.first()).is_none());
+
+        // count 为 0 按 1 处理
+        let single = SerialNumber::reserve(7, 0).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single.first(), single.last());
+    }
+
+    #[test]
+    fn test_detect_clock_regression() {
+        let high_water = AtomicU64::new(0);
+        assert!(detect_regression(&high_water, 1000).is_ok());
+        assert!(detect_regression(&high_water, 1005).is_ok());
+        // 1 毫秒以内的抖动不视为回拨
+        assert!(detect_regression(&high_water, 1004).is_ok());
+        assert_eq!(
+            detect_regression(&high_water, 900),
+            Err(SNError::ClockRegression { drift_ms: 105 })
+        );
+        // 回拨不降低高水位，时钟追上后恢复签发
+        assert!(detect_regression(&high_water, 1003).is_err());
+        assert!(detect_regression(&high_water, 1005).is_ok());
+    }
+
+    #[test]
+    fn test_restore_high_water_is_monotonic() {
+        let before = high_water_ms();
+        assert!(before >= CUSTOM_EPOCH);
+
+        // 恢复较早的高水位不会回退生成器状态
+        restore_high_water(before.saturating_sub(10_000));
+        assert!(high_water_ms() >= before);
+
+        let sn = SerialNumber::sn(1).unwrap().value();
+        assert!((sn >> TIMESTAMP_SHIFT) + CUSTOM_EPOCH >= before.saturating_sub(10_000));
+        assert!(high_water_ms() >= (sn >> TIMESTAMP_SHIFT) + CUSTOM_EPOCH);
+    }
 }
@@ -16,6 +16,47 @@
 //! )
 //! ```
 //!
+//! 签名密钥存放在结构相同的 `current_signing_key` 表中，与加密密钥独立刷新和轮替。
+//! 签名私钥不落盘，启动时按 key_id 从 KS 重新获取。
+//!
+//! 批量预留的序列号段记录在 `serial_reservations` 表中（见 [`SerialReservation`]）：
+//!
+//! ```sql
+//! CREATE TABLE serial_reservations (
+//!     id INTEGER PRIMARY KEY AUTOINCREMENT,
+//!     realm_id INTEGER NOT NULL,
+//!     first_serial INTEGER NOT NULL,
+//!     last_serial INTEGER NOT NULL,
+//!     count INTEGER NOT NULL,
+//!     note TEXT,
+//!     reserved_at INTEGER NOT NULL            -- Unix timestamp
+//! )
+//! ```
+//!
+//! 成功签发的注册记录写入 `registrations` 表（见 [`RegistrationRecord`]），供 `/stats` 与
+//! `/actors` 查询：
+//!
+//! ```sql
+//! CREATE TABLE registrations (
+//!     id INTEGER PRIMARY KEY AUTOINCREMENT,
+//!     realm_id INTEGER NOT NULL,
+//!     serial_number INTEGER NOT NULL,
+//!     manufacturer TEXT NOT NULL,
+//!     name TEXT NOT NULL,
+//!     registered_at INTEGER NOT NULL,         -- Unix timestamp
+//!     expires_at INTEGER NOT NULL             -- Unix timestamp（凭证过期时间）
+//! )
+//! ```
+//!
+//! 序列号生成器的时间戳高水位记录在单行表 `sn_state` 中，重启后据此拒绝回退的时钟：
+//!
+//! ```sql
+//! CREATE TABLE sn_state (
+//!     id INTEGER PRIMARY KEY CHECK (id = 1),
+//!     high_water_ms INTEGER NOT NULL          -- Unix timestamp (毫秒)
+//! )
+//! ```
+//!
 //! # 刷新策略
 //!
 //! - **提前刷新**：在密钥过期前 10 分钟触发刷新
@@ -49,11 +90,12 @@
 //! # }
 //! ```
 
+use actrix_common::aid::KeyUsage;
+use actrix_common::storage::SqliteLocation;
 use anyhow::{Context, Result};
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
-use std::path::Path;
-use std::str::FromStr;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use sqlx::sqlite::SqlitePool;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 
 // ========== 常量配置 ==========
@@ -78,6 +120,54 @@ pub struct KeyRecord {
     pub tolerance_seconds: u64,
 }
 
+/// 预留的序列号段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SerialReservation {
+    /// 记录 ID
+    pub id: i64,
+    /// Realm ID
+    pub realm_id: u32,
+    /// 第一个序列号
+    pub first_serial: u64,
+    /// 最后一个序列号
+    pub last_serial: u64,
+    /// 序列号个数
+    pub count: u64,
+    /// 备注（如批次号、产线）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// 预留时间（Unix timestamp）
+    pub reserved_at: u64,
+}
+
+/// 注册记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RegistrationRecord {
+    /// Realm ID
+    pub realm_id: u32,
+    /// ActrId 序列号
+    pub serial_number: u64,
+    /// ActrType 厂商
+    pub manufacturer: String,
+    /// ActrType 名称
+    pub name: String,
+    /// 注册时间（Unix timestamp）
+    pub registered_at: u64,
+    /// 凭证过期时间（Unix timestamp）
+    pub expires_at: u64,
+}
+
+/// 单个 Realm 单日的注册数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyRegistrations {
+    /// Realm ID
+    pub realm_id: u32,
+    /// 日期（UTC，`YYYY-MM-DD`）
+    pub day: String,
+    /// 注册数
+    pub count: u64,
+}
+
 /// 密钥存储（使用 sqlx 连接池）
 #[derive(Clone)]
 pub struct KeyStorage {
@@ -88,50 +178,118 @@ impl KeyStorage {
     /// 创建或打开密钥存储
     ///
     /// 使用 sqlx 连接池，配置：
-    /// - 最大连接数：10
+    /// - 最大连接数：10（内存数据库为单个常驻连接）
     /// - WAL 模式：提升并发读性能（4x）
     /// - 同步模式：NORMAL（平衡性能和安全）
-    pub async fn new<P: AsRef<Path>>(db_file: P) -> Result<Self> {
-        // 创建连接选项并启用 WAL 模式
-        let options =
-            SqliteConnectOptions::from_str(&format!("sqlite:{}", db_file.as_ref().display()))
-                .context("Failed to parse SQLite URL")?
-                .create_if_missing(true)
-                .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-                .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
-                .busy_timeout(Duration::from_secs(5));
-
-        // 创建连接池
-        let pool = SqlitePoolOptions::new()
-            .max_connections(10)
-            .connect_with(options)
+    pub async fn new(location: impl Into<SqliteLocation>) -> Result<Self> {
+        let pool = location
+            .into()
+            .connect(10)
             .await
             .context("Failed to connect to SQLite")?;
 
-        // 初始化数据库表结构
+        // 初始化数据库表结构（加密密钥与签名密钥各一张单行表）
+        for usage in [KeyUsage::Encryption, KeyUsage::Signing] {
+            let table = Self::table(usage);
+            sqlx::query(&format!(
+                "CREATE TABLE IF NOT EXISTS {table} (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    key_id INTEGER NOT NULL,
+                    public_key TEXT NOT NULL,
+                    fetched_at INTEGER NOT NULL,
+                    expires_at INTEGER NOT NULL,
+                    tolerance_seconds INTEGER NOT NULL
+                )"
+            ))
+            .execute(&pool)
+            .await
+            .with_context(|| format!("Failed to create {table} table"))?;
+        }
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS serial_reservations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                realm_id INTEGER NOT NULL,
+                first_serial INTEGER NOT NULL,
+                last_serial INTEGER NOT NULL,
+                count INTEGER NOT NULL,
+                note TEXT,
+                reserved_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to create serial_reservations table")?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_serial_reservations_realm
+             ON serial_reservations (realm_id)",
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to create serial_reservations index")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS registrations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                realm_id INTEGER NOT NULL,
+                serial_number INTEGER NOT NULL,
+                manufacturer TEXT NOT NULL,
+                name TEXT NOT NULL,
+                registered_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to create registrations table")?;
+
         sqlx::query(
-            "CREATE TABLE IF NOT EXISTS current_key (
+            "CREATE INDEX IF NOT EXISTS idx_registrations_realm_time
+             ON registrations (realm_id, registered_at)",
+        )
+        .execute(&pool)
+        .await
+        .context("Failed to create registrations index")?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS sn_state (
                 id INTEGER PRIMARY KEY CHECK (id = 1),
-                key_id INTEGER NOT NULL,
-                public_key TEXT NOT NULL,
-                fetched_at INTEGER NOT NULL,
-                expires_at INTEGER NOT NULL,
-                tolerance_seconds INTEGER NOT NULL
+                high_water_ms INTEGER NOT NULL
             )",
         )
         .execute(&pool)
         .await
-        .context("Failed to create current_key table")?;
+        .context("Failed to create sn_state table")?;
 
         info!("Key storage initialized with sqlx (max_connections=10, WAL mode enabled)");
         Ok(Self { pool })
     }
 
-    /// 获取当前密钥
+    /// 各用途密钥对应的表名
+    fn table(usage: KeyUsage) -> &'static str {
+        match usage {
+            KeyUsage::Encryption => "current_key",
+            KeyUsage::Signing => "current_signing_key",
+        }
+    }
+
+    /// 获取当前加密密钥
     pub async fn get_current_key(&self) -> Result<Option<KeyRecord>> {
-        let row = sqlx::query_as::<_, (i64, String, i64, i64, i64)>(
-            "SELECT key_id, public_key, fetched_at, expires_at, tolerance_seconds FROM current_key WHERE id = 1",
-        )
+        self.get_key(KeyUsage::Encryption).await
+    }
+
+    /// 更新当前加密密钥
+    pub async fn update_current_key(&self, record: &KeyRecord) -> Result<()> {
+        self.update_key(KeyUsage::Encryption, record).await
+    }
+
+    /// 获取指定用途的当前密钥
+    pub async fn get_key(&self, usage: KeyUsage) -> Result<Option<KeyRecord>> {
+        let row = sqlx::query_as::<_, (i64, String, i64, i64, i64)>(&format!(
+            "SELECT key_id, public_key, fetched_at, expires_at, tolerance_seconds FROM {} WHERE id = 1",
+            Self::table(usage)
+        ))
         .fetch_optional(&self.pool)
         .await
         .context("Failed to query current key")?;
@@ -148,20 +306,23 @@ impl KeyStorage {
 
         if let Some(ref key) = record {
             debug!(
-                "Retrieved key record: key_id={}, expires_at={}",
-                key.key_id, key.expires_at
+                "Retrieved {} key record: key_id={}, expires_at={}",
+                usage.as_str(),
+                key.key_id,
+                key.expires_at
             );
         }
 
         Ok(record)
     }
 
-    /// 更新当前密钥
-    pub async fn update_current_key(&self, record: &KeyRecord) -> Result<()> {
-        sqlx::query(
-            "INSERT OR REPLACE INTO current_key (id, key_id, public_key, fetched_at, expires_at, tolerance_seconds)
+    /// 更新指定用途的当前密钥
+    pub async fn update_key(&self, usage: KeyUsage, record: &KeyRecord) -> Result<()> {
+        sqlx::query(&format!(
+            "INSERT OR REPLACE INTO {} (id, key_id, public_key, fetched_at, expires_at, tolerance_seconds)
              VALUES (1, ?1, ?2, ?3, ?4, ?5)",
-        )
+            Self::table(usage)
+        ))
         .bind(record.key_id as i64)
         .bind(&record.public_key)
         .bind(record.fetched_at as i64)
@@ -172,20 +333,27 @@ impl KeyStorage {
         .context("Failed to update current key")?;
 
         debug!(
-            "Updated current key: key_id={}, expires_at={}",
-            record.key_id, record.expires_at
+            "Updated current {} key: key_id={}, expires_at={}",
+            usage.as_str(),
+            record.key_id,
+            record.expires_at
         );
 
         Ok(())
     }
 
-    /// 检查密钥是否需要刷新
+    /// 检查加密密钥是否需要刷新
+    pub async fn should_refresh(&self) -> Result<bool> {
+        self.should_refresh_key(KeyUsage::Encryption).await
+    }
+
+    /// 检查指定用途的密钥是否需要刷新
     ///
     /// 返回 true 如果：
     /// - 没有密钥
     /// - 密钥将在 10 分钟内过期
-    pub async fn should_refresh(&self) -> Result<bool> {
-        let key = match self.get_current_key().await? {
+    pub async fn should_refresh_key(&self, usage: KeyUsage) -> Result<bool> {
+        let key = match self.get_key(usage).await? {
             Some(key) => key,
             None => {
                 debug!("No current key found, refresh needed");
@@ -218,11 +386,17 @@ impl KeyStorage {
         }
     }
 
-    /// 检查密钥是否已完全过期（超过容忍时间）
+    /// 检查加密密钥是否已完全过期（超过容忍时间）
+    pub async fn is_expired_beyond_tolerance(&self) -> Result<bool> {
+        self.is_key_expired_beyond_tolerance(KeyUsage::Encryption)
+            .await
+    }
+
+    /// 检查指定用途的密钥是否已完全过期（超过容忍时间）
     ///
     /// 返回 true 如果密钥过期超过 24 小时
-    pub async fn is_expired_beyond_tolerance(&self) -> Result<bool> {
-        let key = match self.get_current_key().await? {
+    pub async fn is_key_expired_beyond_tolerance(&self, usage: KeyUsage) -> Result<bool> {
+        let key = match self.get_key(usage).await? {
             Some(key) => key,
             None => return Ok(true),
         };
@@ -245,6 +419,188 @@ impl KeyStorage {
         Ok(expired_beyond)
     }
 
+    /// 记录预留的序列号段，返回带 ID 的记录
+    pub async fn record_reservation(
+        &self,
+        realm_id: u32,
+        first_serial: u64,
+        last_serial: u64,
+        count: u64,
+        note: Option<String>,
+    ) -> Result<SerialReservation> {
+        let reserved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let result = sqlx::query(
+            "INSERT INTO serial_reservations (realm_id, first_serial, last_serial, count, note, reserved_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(realm_id as i64)
+        .bind(first_serial as i64)
+        .bind(last_serial as i64)
+        .bind(count as i64)
+        .bind(&note)
+        .bind(reserved_at as i64)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record serial reservation")?;
+
+        let reservation = SerialReservation {
+            id: result.last_insert_rowid(),
+            realm_id,
+            first_serial,
+            last_serial,
+            count,
+            note,
+            reserved_at,
+        };
+        debug!(
+            "Recorded serial reservation {}: realm={}, count={}",
+            reservation.id, realm_id, count
+        );
+        Ok(reservation)
+    }
+
+    /// 查询指定 Realm 的预留记录（按预留时间升序）
+    pub async fn list_reservations(&self, realm_id: u32) -> Result<Vec<SerialReservation>> {
+        let rows = sqlx::query_as::<_, (i64, i64, i64, i64, i64, Option<String>, i64)>(
+            "SELECT id, realm_id, first_serial, last_serial, count, note, reserved_at
+             FROM serial_reservations WHERE realm_id = ?1 ORDER BY id",
+        )
+        .bind(realm_id as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query serial reservations")?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, realm_id, first_serial, last_serial, count, note, reserved_at)| {
+                    SerialReservation {
+                        id,
+                        realm_id: realm_id as u32,
+                        first_serial: first_serial as u64,
+                        last_serial: last_serial as u64,
+                        count: count as u64,
+                        note,
+                        reserved_at: reserved_at as u64,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// 记录一次成功的注册
+    pub async fn record_registration(&self, record: &RegistrationRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO registrations (realm_id, serial_number, manufacturer, name, registered_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(record.realm_id as i64)
+        .bind(record.serial_number as i64)
+        .bind(&record.manufacturer)
+        .bind(&record.name)
+        .bind(record.registered_at as i64)
+        .bind(record.expires_at as i64)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record registration")?;
+        Ok(())
+    }
+
+    /// 统计指定 Realm 在 `now` 时凭证仍未过期的注册数（同一序列号只计一次）
+    pub async fn count_active_registrations(&self, realm_id: u32, now: u64) -> Result<u64> {
+        let (count,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(DISTINCT serial_number) FROM registrations
+             WHERE realm_id = ?1 AND expires_at > ?2",
+        )
+        .bind(realm_id as i64)
+        .bind(now as i64)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to count registrations")?;
+        Ok(count as u64)
+    }
+
+    /// 按 Realm 与日期（UTC）统计 `since` 之后的注册数
+    pub async fn registration_stats(&self, since: u64) -> Result<Vec<DailyRegistrations>> {
+        let rows = sqlx::query_as::<_, (i64, String, i64)>(
+            "SELECT realm_id, date(registered_at, 'unixepoch') AS day, COUNT(*)
+             FROM registrations WHERE registered_at >= ?1
+             GROUP BY realm_id, day ORDER BY day, realm_id",
+        )
+        .bind(since as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query registration stats")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(realm_id, day, count)| DailyRegistrations {
+                realm_id: realm_id as u32,
+                day,
+                count: count as u64,
+            })
+            .collect())
+    }
+
+    /// 查询指定 Realm 最近注册的 Actor（按注册时间降序）
+    pub async fn list_registrations(
+        &self,
+        realm_id: u32,
+        limit: u32,
+    ) -> Result<Vec<RegistrationRecord>> {
+        let rows = sqlx::query_as::<_, (i64, i64, String, String, i64, i64)>(
+            "SELECT realm_id, serial_number, manufacturer, name, registered_at, expires_at
+             FROM registrations WHERE realm_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )
+        .bind(realm_id as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to query registrations")?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(realm_id, serial_number, manufacturer, name, registered_at, expires_at)| {
+                    RegistrationRecord {
+                        realm_id: realm_id as u32,
+                        serial_number: serial_number as u64,
+                        manufacturer,
+                        name,
+                        registered_at: registered_at as u64,
+                        expires_at: expires_at as u64,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// 读取序列号生成器的时间戳高水位（Unix 毫秒）
+    pub async fn get_sn_high_water(&self) -> Result<Option<u64>> {
+        let row = sqlx::query_as::<_, (i64,)>("SELECT high_water_ms FROM sn_state WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await
+            .context("Failed to query sn_state")?;
+        Ok(row.map(|(high_water_ms,)| high_water_ms as u64))
+    }
+
+    /// 更新序列号生成器的时间戳高水位（只增不减）
+    pub async fn update_sn_high_water(&self, high_water_ms: u64) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO sn_state (id, high_water_ms) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET high_water_ms = MAX(high_water_ms, excluded.high_water_ms)",
+        )
+        .bind(high_water_ms as i64)
+        .execute(&self.pool)
+        .await
+        .context("Failed to update sn_state")?;
+        Ok(())
+    }
+
     /// 健康检查：执行简单的数据库查询验证连接池
     pub async fn health_check(&self) -> Result<()> {
         sqlx::query("SELECT 1")
@@ -319,6 +675,37 @@ mod tests {
         assert!(storage.should_refresh().await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_signing_key_slot_is_independent() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage = KeyStorage::new(temp_file.path()).await.unwrap();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let record = KeyRecord {
+            key_id: 7,
+            public_key: "signing_key".to_string(),
+            fetched_at: now,
+            expires_at: now + 3600,
+            tolerance_seconds: 24 * 3600,
+        };
+        storage
+            .update_key(KeyUsage::Signing, &record)
+            .await
+            .unwrap();
+
+        // 加密密钥槽位不受影响
+        assert!(storage.get_current_key().await.unwrap().is_none());
+        assert!(storage.should_refresh().await.unwrap());
+
+        let signing = storage.get_key(KeyUsage::Signing).await.unwrap().unwrap();
+        assert_eq!(signing.key_id, 7);
+        assert!(!storage.should_refresh_key(KeyUsage::Signing).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_expired_beyond_tolerance() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -343,4 +730,101 @@ mod tests {
         // 应该超出容忍时间
         assert!(storage.is_expired_beyond_tolerance().await.unwrap());
     }
+
+    #[tokio::test]
+    async fn test_serial_reservations() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage = KeyStorage::new(temp_file.path()).await.unwrap();
+
+        let first = storage
+            .record_reservation(1, 100, 355, 256, Some("batch-A".to_string()))
+            .await
+            .unwrap();
+        storage
+            .record_reservation(2, 400, 409, 10, None)
+            .await
+            .unwrap();
+        let second = storage
+            .record_reservation(1, 500, 509, 10, None)
+            .await
+            .unwrap();
+
+        let reservations = storage.list_reservations(1).await.unwrap();
+        assert_eq!(reservations, vec![first, second]);
+        assert_eq!(reservations[0].note.as_deref(), Some("batch-A"));
+        assert_eq!(storage.list_reservations(2).await.unwrap().len(), 1);
+        assert!(storage.list_reservations(3).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_registration_stats_and_listing() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage = KeyStorage::new(temp_file.path()).await.unwrap();
+
+        // 2024-01-01 00:00:00 UTC
+        let day1 = 1_704_067_200;
+        let day2 = day1 + 86400;
+        for (realm_id, serial_number, registered_at) in [
+            (1, 10, day1),
+            (1, 11, day1 + 60),
+            (2, 20, day1),
+            (1, 12, day2),
+        ] {
+            storage
+                .record_registration(&RegistrationRecord {
+                    realm_id,
+                    serial_number,
+                    manufacturer: "acme".to_string(),
+                    name: "sensor".to_string(),
+                    registered_at,
+                    expires_at: registered_at + 3600,
+                })
+                .await
+                .unwrap();
+        }
+
+        let stats = storage.registration_stats(day1).await.unwrap();
+        let summary: Vec<_> = stats
+            .iter()
+            .map(|s| (s.realm_id, s.day.as_str(), s.count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "2024-01-01", 2),
+                (2, "2024-01-01", 1),
+                (1, "2024-01-02", 1)
+            ]
+        );
+        assert_eq!(storage.registration_stats(day2).await.unwrap().len(), 1);
+
+        let actors = storage.list_registrations(1, 2).await.unwrap();
+        let serials: Vec<_> = actors.iter().map(|a| a.serial_number).collect();
+        assert_eq!(serials, vec![12, 11]);
+        assert!(storage.list_registrations(3, 10).await.unwrap().is_empty());
+
+        // 凭证已过期的注册不计入配额
+        assert_eq!(
+            storage
+                .count_active_registrations(1, day2 + 60)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(storage.count_active_registrations(1, 0).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sn_high_water_only_moves_forward() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage = KeyStorage::new(temp_file.path()).await.unwrap();
+        assert_eq!(storage.get_sn_high_water().await.unwrap(), None);
+
+        storage.update_sn_high_water(2000).await.unwrap();
+        storage.update_sn_high_water(1000).await.unwrap();
+        assert_eq!(storage.get_sn_high_water().await.unwrap(), Some(2000));
+
+        storage.update_sn_high_water(3000).await.unwrap();
+        assert_eq!(storage.get_sn_high_water().await.unwrap(), Some(3000));
+    }
 }
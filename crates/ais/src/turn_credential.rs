@@ -0,0 +1,163 @@
+//! AIdCredential 换取 TURN 凭证
+//!
+//! `POST /ais/turn-credential`：Actor 提交当前 AIdCredential，AIS 校验凭证、吊销与 PSK 代数后
+//! 签发限定范围的 TURN 凭证（见 [`actrix_common::aid::turn_credential`]）。
+//! 凭证绑定 Realm 与序列号，过期时间与 AId Token 一致；TURN 服务以 `actrix_shared_key`
+//! 重新计算密码校验，无需另行分发 TURN 凭证。
+
+use crate::issuer::AIdIssuer;
+use crate::revocation::RevocationStore;
+use actr_protocol::AIdCredential;
+use actrix_common::aid::{AidError, ScopedTurnUser};
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use base64::prelude::*;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, warn};
+
+/// TURN 凭证换取请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnCredentialRequest {
+    /// 凭证所属 Realm
+    pub realm_id: u32,
+    /// 当前凭证（Base64 编码的 protobuf `AIdCredential`）
+    pub credential: String,
+}
+
+/// TURN 凭证换取路由状态
+#[derive(Clone)]
+pub struct TurnCredentialState {
+    pub issuer: Arc<AIdIssuer>,
+    pub store: RevocationStore,
+    /// 密码 MAC 密钥（actrix_shared_key）
+    pub shared_key: String,
+    /// 本节点启用 TURN 时对外发布的 TURN URL 与认证域，随凭证返回
+    pub urls: Vec<String>,
+    pub turn_realm: Option<String>,
+}
+
+/// 创建 TURN 凭证换取路由（挂载到 `/ais`）
+pub fn create_turn_credential_router(state: TurnCredentialState) -> Router {
+    Router::new()
+        .route("/turn-credential", post(issue_turn_credential))
+        .with_state(state)
+}
+
+fn error_response(status: StatusCode, message: String) -> (StatusCode, Json<Value>) {
+    (
+        status,
+        Json(json!({
+            "status": "error",
+            "message": message
+        })),
+    )
+}
+
+/// 签发 TURN 凭证
+async fn issue_turn_credential(
+    State(state): State<TurnCredentialState>,
+    Json(request): Json<TurnCredentialRequest>,
+) -> (StatusCode, Json<Value>) {
+    let credential = match BASE64_STANDARD
+        .decode(&request.credential)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| AIdCredential::decode(bytes.as_slice()).map_err(|e| e.to_string()))
+    {
+        Ok(credential) => credential,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Invalid credential: {e}"));
+        }
+    };
+
+    let claims = match state
+        .issuer
+        .verify_credential(&credential, request.realm_id)
+        .await
+    {
+        Ok(claims) => claims,
+        Err(AidError::GenerationFailed(msg)) => {
+            error!("Failed to verify credential for TURN credential: {}", msg);
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, msg);
+        }
+        Err(e) => {
+            warn!(
+                "Rejected TURN credential request with invalid credential: {}",
+                e
+            );
+            return error_response(StatusCode::UNAUTHORIZED, e.to_string());
+        }
+    };
+
+    let Some(serial_number) = claims.serial_number() else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "Credential has no serial number".to_string(),
+        );
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    match state.store.get(serial_number).await {
+        Ok(Some(record)) if record.expires_at >= now => {
+            warn!(
+                "Rejected TURN credential request for revoked serial_number {}",
+                serial_number
+            );
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                AidError::CredentialRevoked(serial_number).to_string(),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to query revocation record: {}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    }
+    match state.store.psk_epoch(serial_number).await {
+        Ok(Some(current)) if current > claims.psk_epoch => {
+            warn!(
+                "Rejected TURN credential request for serial_number {}: epoch {} superseded by {}",
+                serial_number, claims.psk_epoch, current
+            );
+            return error_response(
+                StatusCode::UNAUTHORIZED,
+                AidError::PskSuperseded(serial_number).to_string(),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to query PSK rotation: {}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+        }
+    }
+
+    let turn = ScopedTurnUser {
+        expires_at: claims.expr_time,
+        realm_id: claims.realm_id,
+        serial_number,
+    }
+    .issue(&state.shared_key);
+
+    debug!(
+        "Issued TURN credential for serial_number {} in realm {} (expires at {})",
+        serial_number, claims.realm_id, turn.expires_at
+    );
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "username": turn.username,
+            "password": turn.password,
+            "expires_at": turn.expires_at,
+            "ttl_secs": turn.expires_at.saturating_sub(now),
+            "urls": state.urls,
+            "realm": state.turn_realm
+        })),
+    )
+}
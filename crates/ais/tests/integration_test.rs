@@ -2,13 +2,14 @@
 //!
 //! 在测试进程内启动临时 KS gRPC 服务，验证 AIS 的签发与校验链路。
 
-use actr_protocol::{ActrType, Realm, RegisterRequest, register_response};
+use actr_protocol::{AIdCredential, ActrType, Realm, RegisterRequest, register_response};
 use actrix_common::aid::credential::validator::AIdCredentialValidator;
 use actrix_common::config::ks::KsClientConfig;
+use actrix_common::storage::NonceStore;
 use ais::issuer::{AIdIssuer, IssuerConfig};
 use ais::ks_client_wrapper::create_ks_client;
+use ecies::PublicKey;
 use ks::{GrpcClient, GrpcClientConfig, KeyStorage, KsServiceConfig, create_grpc_service};
-use nonce_auth::storage::MemoryStorage;
 use std::net::TcpListener;
 use std::path::Path;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -57,7 +58,7 @@ async fn start_embedded_ks(
 
     let service = create_grpc_service(
         storage,
-        MemoryStorage::new(),
+        NonceStore::memory(),
         psk.to_string(),
         service_config.tolerance_seconds,
     );
@@ -141,9 +142,11 @@ fn default_issuer_config(temp_dir: &TempDir) -> IssuerConfig {
         token_ttl_secs: 3600,
         signaling_heartbeat_interval_secs: 30,
         key_refresh_interval_secs: 3600,
-        key_storage_file: temp_dir.path().join("issuer_keys.db"),
+        key_storage: temp_dir.path().join("issuer_keys.db").into(),
         enable_periodic_rotation: false,
         key_rotation_interval_secs: 86400,
+        signing_key_rotation_interval_secs: 7 * 86400,
+        serial_number: Default::default(),
     }
 }
 
@@ -312,6 +315,125 @@ async fn test_issuer_rotate_key_updates_current_key() {
     assert_eq!(cache.key_id, rotated);
 }
 
+async fn issue_signed_credential(issuer: &AIdIssuer, request: &RegisterRequest) -> AIdCredential {
+    let response = issuer
+        .issue_credential(request)
+        .await
+        .expect("Failed to issue credential");
+    match response.result.expect("Response should contain result") {
+        register_response::Result::Success(ok) => ok.credential,
+        register_response::Result::Error(err) => panic!("Expected success but got error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_signing_key_rotates_independently_of_encryption_key() {
+    let env = setup_test_environment().await;
+
+    let ks_client = create_ks_client(&env.ks_config, &env.shared_key)
+        .await
+        .expect("Failed to create KS gRPC client");
+    let issuer = AIdIssuer::new(ks_client, default_issuer_config(&env.issuer_temp_dir))
+        .await
+        .expect("Failed to create issuer");
+    let encryption_key = issuer.get_current_key_id().await.expect("encryption key");
+    let signing_before = issuer
+        .get_current_signing_key_id()
+        .await
+        .expect("signing key before rotate");
+    assert_ne!(
+        encryption_key, signing_before,
+        "encryption and signing keys must be distinct"
+    );
+
+    let request = RegisterRequest {
+        actr_type: ActrType {
+            manufacturer: "test-manufacturer".to_string(),
+            name: "signed-device".to_string(),
+            version: None,
+        },
+        realm: Realm { realm_id: 1002 },
+        service: None,
+        service_spec: None,
+        acl: None,
+        ws_address: None,
+    };
+    let before_rotation = issue_signed_credential(&issuer, &request).await;
+
+    let signing_after = issuer
+        .rotate_signing_key()
+        .await
+        .expect("rotate signing key");
+    assert_ne!(signing_before, signing_after);
+    assert_eq!(
+        issuer.get_current_key_id().await.expect("encryption key"),
+        encryption_key,
+        "rotating the signing key must not touch the encryption key"
+    );
+
+    let after_rotation = issue_signed_credential(&issuer, &request).await;
+    assert_eq!(after_rotation.token_key_id, encryption_key);
+
+    // 验证器按元数据中的 signing_key_id 取签名公钥：轮替前后签发的 credential 各自可验证
+    let mut ks = GrpcClient::new(&GrpcClientConfig {
+        endpoint: env.ks_config.endpoint.clone(),
+        actrix_shared_key: env.shared_key.clone(),
+        timeout_seconds: 2,
+        enable_tls: false,
+        tls_domain: None,
+        ca_cert: None,
+        client_cert: None,
+        client_key: None,
+    })
+    .await
+    .expect("create KS client");
+    let (encryption_secret, _, _) = ks
+        .fetch_secret_key(encryption_key)
+        .await
+        .expect("fetch encryption key");
+    let (old_signing_secret, _, _) = ks
+        .fetch_secret_key(signing_before)
+        .await
+        .expect("fetch old signing key");
+    let (new_signing_secret, _, _) = ks
+        .fetch_secret_key(signing_after)
+        .await
+        .expect("fetch new signing key");
+    let old_signing = PublicKey::from_secret_key(&old_signing_secret);
+    let new_signing = PublicKey::from_secret_key(&new_signing_secret);
+
+    AIdCredentialValidator::check_with_keys(
+        &before_rotation,
+        1002,
+        &encryption_secret,
+        Some(&old_signing),
+    )
+    .expect("credential signed before rotation should validate");
+    AIdCredentialValidator::check_with_keys(
+        &after_rotation,
+        1002,
+        &encryption_secret,
+        Some(&new_signing),
+    )
+    .expect("credential signed after rotation should validate");
+    assert!(
+        AIdCredentialValidator::check_with_keys(
+            &before_rotation,
+            1002,
+            &encryption_secret,
+            Some(&new_signing),
+        )
+        .is_err(),
+        "credential must not verify against a different signing key"
+    );
+
+    let cache = issuer
+        .check_key_cache_health()
+        .await
+        .expect("key cache should be healthy");
+    assert_eq!(cache.signing_key_id, signing_after);
+}
+
 #[tokio::test]
 async fn test_issuer_creation_fails_with_wrong_shared_key() {
     let env = setup_test_environment().await;
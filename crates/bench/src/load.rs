@@ -0,0 +1,235 @@
+//! 信令压测场景：固定规模的 register/ping/route/relay 循环，以及逐级加压探测最大连接数
+
+use crate::signaling::{Fleet, SimActor};
+use crate::stats::{Recorder, Summary};
+use anyhow::Result;
+use futures_util::StreamExt;
+use futures_util::stream;
+use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// 每轮循环执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Op {
+    Ping,
+    Route,
+    Relay,
+}
+
+/// 固定规模压测参数
+pub struct LoadOptions {
+    pub actors: usize,
+    pub connect_concurrency: usize,
+    pub duration: Duration,
+    pub interval: Duration,
+    pub ops: Vec<Op>,
+}
+
+/// 固定规模压测结果
+#[derive(Debug, Serialize)]
+pub struct LoadReport {
+    pub actors: usize,
+    pub connected: usize,
+    pub register: Summary,
+    pub ping: Summary,
+    pub route: Summary,
+    pub relay_sent: u64,
+    pub relay: Summary,
+}
+
+/// 逐级加压参数
+pub struct RampOptions {
+    pub step: usize,
+    pub max: usize,
+    pub connect_concurrency: usize,
+    /// 每级 ping p99 超过此值即视为不可持续
+    pub p99_limit: Duration,
+}
+
+/// 单级加压结果
+#[derive(Debug, Serialize)]
+pub struct RampStep {
+    pub connections: usize,
+    pub register: Summary,
+    pub ping: Summary,
+    pub sustained: bool,
+}
+
+/// 逐级加压结果
+#[derive(Debug, Serialize)]
+pub struct RampReport {
+    /// 最后一个所有连接注册成功、ping 无错误且 p99 未超限的级别
+    pub max_sustained_connections: usize,
+    pub steps: Vec<RampStep>,
+}
+
+/// 并发建立 `count` 个连接，失败计入 `recorder` 的错误数
+async fn connect_many(
+    fleet: &Arc<Fleet>,
+    count: usize,
+    concurrency: usize,
+    recorder: &Recorder,
+) -> Vec<SimActor> {
+    let results: Vec<_> = stream::iter(0..count)
+        .map(|_| SimActor::connect(fleet))
+        .buffer_unordered(concurrency.max(1))
+        .collect()
+        .await;
+
+    let mut actors = Vec::with_capacity(count);
+    for result in results {
+        match result {
+            Ok((actor, elapsed)) => {
+                recorder.record(elapsed);
+                actors.push(actor);
+            }
+            Err(_) => recorder.record_error(),
+        }
+    }
+    actors
+}
+
+/// 固定规模压测：建立连接后每个 Actor 按间隔循环执行所选操作
+pub async fn run_load(fleet: Arc<Fleet>, options: LoadOptions) -> Result<LoadReport> {
+    let register = Recorder::default();
+    let connect_started = Instant::now();
+    let actors = connect_many(
+        &fleet,
+        options.actors,
+        options.connect_concurrency,
+        &register,
+    )
+    .await;
+    let register = register.summary(connect_started.elapsed());
+    let connected = actors.len();
+    if connected == 0 {
+        anyhow::bail!("no actor registered ({} errors)", register.errors);
+    }
+
+    let actors: Arc<Vec<SimActor>> = Arc::new(actors);
+    let ping = Arc::new(Recorder::default());
+    let route = Arc::new(Recorder::default());
+    let ops = Arc::new(options.ops);
+    let started = Instant::now();
+    let deadline = started + options.duration;
+
+    let mut tasks = Vec::with_capacity(connected);
+    for index in 0..connected {
+        let (fleet, actors, ping, route, ops) = (
+            fleet.clone(),
+            actors.clone(),
+            ping.clone(),
+            route.clone(),
+            ops.clone(),
+        );
+        let interval = options.interval;
+        // 错开各 Actor 的首轮，避免同时发起请求
+        let offset = interval.mul_f64(index as f64 / connected as f64);
+        tasks.push(tokio::spawn(async move {
+            tokio::time::sleep(offset).await;
+            let actor = &actors[index];
+            let peer = actors[(index + 1) % actors.len()].actr_id().clone();
+            while Instant::now() < deadline {
+                let round = Instant::now();
+                for op in ops.iter() {
+                    match op {
+                        Op::Ping => match actor.ping().await {
+                            Ok(elapsed) => ping.record(elapsed),
+                            Err(_) => ping.record_error(),
+                        },
+                        Op::Route => match actor.route(&fleet.actr_type).await {
+                            Ok(elapsed) => route.record(elapsed),
+                            Err(_) => route.record_error(),
+                        },
+                        Op::Relay => {
+                            if actor.relay(&fleet, &peer).await.is_err() {
+                                fleet.relay.record_error();
+                            }
+                        }
+                    }
+                }
+                tokio::time::sleep(interval.saturating_sub(round.elapsed())).await;
+            }
+        }));
+    }
+    for task in tasks {
+        task.await?;
+    }
+    // 等待在途中继送达
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let elapsed = started.elapsed();
+
+    let report = LoadReport {
+        actors: options.actors,
+        connected,
+        register,
+        ping: ping.summary(elapsed),
+        route: route.summary(elapsed),
+        relay_sent: fleet.relay_sent.load(Ordering::Relaxed),
+        relay: fleet.relay.summary(elapsed),
+    };
+
+    if let Ok(actors) = Arc::try_unwrap(actors) {
+        for actor in actors {
+            actor.close().await;
+        }
+    }
+    Ok(report)
+}
+
+/// 逐级加压：每级新增 `step` 个连接并保持，所有已保持连接各 ping 一次，直到出现错误或 p99 超限
+pub async fn run_ramp(fleet: Arc<Fleet>, options: RampOptions) -> Result<RampReport> {
+    let mut held: Vec<SimActor> = Vec::new();
+    let mut steps = Vec::new();
+    let mut max_sustained = 0;
+
+    while held.len() < options.max {
+        let count = options.step.min(options.max - held.len());
+        let register = Recorder::default();
+        let started = Instant::now();
+        let actors = connect_many(&fleet, count, options.connect_concurrency, &register).await;
+        let register = register.summary(started.elapsed());
+        held.extend(actors);
+
+        let ping = Recorder::default();
+        let started = Instant::now();
+        let results: Vec<_> = stream::iter(held.iter())
+            .map(|actor| actor.ping())
+            .buffer_unordered(options.connect_concurrency.max(1))
+            .collect()
+            .await;
+        for result in results {
+            match result {
+                Ok(elapsed) => ping.record(elapsed),
+                Err(_) => ping.record_error(),
+            }
+        }
+        let ping = ping.summary(started.elapsed());
+
+        let sustained = register.errors == 0
+            && ping.errors == 0
+            && ping.p99_ms <= options.p99_limit.as_secs_f64() * 1000.0;
+        if sustained {
+            max_sustained = held.len();
+        }
+        steps.push(RampStep {
+            connections: held.len(),
+            register,
+            ping,
+            sustained,
+        });
+        if !sustained {
+            break;
+        }
+    }
+
+    for actor in held {
+        actor.close().await;
+    }
+    Ok(RampReport {
+        max_sustained_connections: max_sustained,
+        steps,
+    })
+}
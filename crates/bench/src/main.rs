@@ -0,0 +1,260 @@
+//! End-to-end load harness for actrix
+//!
+//! Drives a running node the way real actors do, so regressions in the
+//! signaling and STUN hot paths show up as latency percentiles.
+//!
+//! # Usage
+//!
+//! Register 200 actors and loop ping / route / relay for 60 seconds:
+//! ```bash
+//! cargo run --release -p actrix-bench -- signaling \
+//!   --url ws://127.0.0.1:8080/signaling/ws --actors 200 --duration 60
+//! ```
+//!
+//! Find the largest number of connections the node sustains:
+//! ```bash
+//! cargo run --release -p actrix-bench -- connections \
+//!   --url ws://127.0.0.1:8080/signaling/ws --step 500 --max 20000
+//! ```
+//!
+//! Flood the STUN listener with Binding requests:
+//! ```bash
+//! cargo run --release -p actrix-bench -- stun --target 127.0.0.1:3478 --sockets 32 --rate 500
+//! ```
+
+mod load;
+mod signaling;
+mod stats;
+mod stun;
+
+use actr_protocol::ActrType;
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+use signaling::Fleet;
+use stats::Summary;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(name = "bench", about = "End-to-end load harness for actrix")]
+struct Cli {
+    /// Print the report as JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Register actors and loop ping / route / relay against the signaling service
+    Signaling {
+        #[command(flatten)]
+        target: SignalingTarget,
+        /// Number of simulated actors
+        #[arg(long, default_value_t = 100)]
+        actors: usize,
+        /// Load duration in seconds
+        #[arg(long, default_value_t = 30)]
+        duration: u64,
+        /// Interval between rounds per actor, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+        /// Operations per round (comma separated)
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "ping,route,relay"
+        )]
+        ops: Vec<load::Op>,
+    },
+    /// Add connections step by step until registration or ping fails or p99 exceeds the limit
+    Connections {
+        #[command(flatten)]
+        target: SignalingTarget,
+        /// Connections added per step
+        #[arg(long, default_value_t = 100)]
+        step: usize,
+        /// Stop after this many connections
+        #[arg(long, default_value_t = 10_000)]
+        max: usize,
+        /// Ping p99 limit in milliseconds for a step to count as sustained
+        #[arg(long, default_value_t = 500)]
+        p99_limit_ms: u64,
+    },
+    /// Flood a STUN listener with Binding requests
+    Stun {
+        /// STUN server address
+        #[arg(long, default_value = "127.0.0.1:3478")]
+        target: SocketAddr,
+        /// Number of client sockets
+        #[arg(long, default_value_t = 16)]
+        sockets: usize,
+        /// Requests per second per socket
+        #[arg(long, default_value_t = 1000)]
+        rate: u32,
+        /// Flood duration in seconds
+        #[arg(long, default_value_t = 10)]
+        duration: u64,
+        /// Response timeout in milliseconds; later responses count as lost
+        #[arg(long, default_value_t = 1000)]
+        timeout_ms: u64,
+    },
+}
+
+/// Signaling endpoint and actor identity shared by the signaling scenarios
+#[derive(Args)]
+struct SignalingTarget {
+    /// Signaling WebSocket URL
+    #[arg(long, default_value = "ws://127.0.0.1:8080/signaling/ws")]
+    url: String,
+    /// Realm the simulated actors register in
+    #[arg(long, default_value_t = 1001)]
+    realm: u32,
+    /// ActrType manufacturer of the simulated actors
+    #[arg(long, default_value = "bench")]
+    manufacturer: String,
+    /// ActrType name of the simulated actors
+    #[arg(long, default_value = "load")]
+    name: String,
+    /// Maximum concurrent connection attempts and in-flight ramp pings
+    #[arg(long, default_value_t = 64)]
+    connect_concurrency: usize,
+    /// Request timeout in milliseconds
+    #[arg(long, default_value_t = 5000)]
+    timeout_ms: u64,
+}
+
+impl SignalingTarget {
+    fn fleet(&self) -> Arc<Fleet> {
+        let actr_type = ActrType {
+            manufacturer: self.manufacturer.clone(),
+            name: self.name.clone(),
+            version: None,
+        };
+        Arc::new(Fleet::new(
+            self.url.clone(),
+            self.realm,
+            actr_type,
+            Duration::from_millis(self.timeout_ms),
+        ))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Signaling {
+            target,
+            actors,
+            duration,
+            interval_ms,
+            ops,
+        } => {
+            let options = load::LoadOptions {
+                actors,
+                connect_concurrency: target.connect_concurrency,
+                duration: Duration::from_secs(duration),
+                interval: Duration::from_millis(interval_ms),
+                ops,
+            };
+            let report = load::run_load(target.fleet(), options).await?;
+            print_report(cli.json, &report, |report| {
+                println!("actors: {}/{} connected", report.connected, report.actors);
+                print_header();
+                print_summary("register", &report.register);
+                print_summary("ping", &report.ping);
+                print_summary("route", &report.route);
+                print_summary("relay", &report.relay);
+                println!(
+                    "relay: {} sent, {} delivered",
+                    report.relay_sent, report.relay.count
+                );
+            })
+        }
+        Command::Connections {
+            target,
+            step,
+            max,
+            p99_limit_ms,
+        } => {
+            let options = load::RampOptions {
+                step,
+                max,
+                connect_concurrency: target.connect_concurrency,
+                p99_limit: Duration::from_millis(p99_limit_ms),
+            };
+            let report = load::run_ramp(target.fleet(), options).await?;
+            print_report(cli.json, &report, |report| {
+                print_header();
+                for step in &report.steps {
+                    let mark = if step.sustained { "ok" } else { "FAIL" };
+                    println!("-- {} connections [{mark}]", step.connections);
+                    print_summary("register", &step.register);
+                    print_summary("ping", &step.ping);
+                }
+                println!(
+                    "max sustained connections: {}",
+                    report.max_sustained_connections
+                );
+            })
+        }
+        Command::Stun {
+            target,
+            sockets,
+            rate,
+            duration,
+            timeout_ms,
+        } => {
+            let options = stun::StunOptions {
+                target,
+                sockets,
+                rate,
+                duration: Duration::from_secs(duration),
+                timeout: Duration::from_millis(timeout_ms),
+            };
+            let report = stun::run(options).await?;
+            print_report(cli.json, &report, |report| {
+                print_header();
+                print_summary("binding", &report.binding);
+                println!("stun: {} sent, {} lost", report.sent, report.lost);
+            })
+        }
+    }
+}
+
+fn print_report<T: Serialize>(json: bool, report: &T, human: impl FnOnce(&T)) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(report)?);
+    } else {
+        human(report);
+    }
+    Ok(())
+}
+
+fn print_header() {
+    println!(
+        "{:<10} {:>9} {:>7} {:>10} {:>9} {:>9} {:>9} {:>9}",
+        "op", "count", "errors", "rate/s", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+}
+
+fn print_summary(op: &str, summary: &Summary) {
+    println!(
+        "{:<10} {:>9} {:>7} {:>10.1} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+        op,
+        summary.count,
+        summary.errors,
+        summary.rate_per_sec,
+        summary.p50_ms,
+        summary.p90_ms,
+        summary.p99_ms,
+        summary.max_ms
+    );
+}
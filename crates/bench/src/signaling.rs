@@ -0,0 +1,328 @@
+//! 模拟 Actor：经 WebSocket 注册并执行 ping / 路由查询 / 中继
+
+use crate::stats::Recorder;
+use actr_protocol::acl_rule::{Permission, Principal};
+use actr_protocol::{
+    Acl, AclRule, ActrId, ActrRelay, ActrType, PeerToSignaling, Realm, RegisterRequest,
+    RegisterResponse, SignalingEnvelope, actr_relay, actr_to_signaling, peer_to_signaling,
+    register_response, route_candidates_response, signaling_envelope, signaling_to_actr,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use prost::Message;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use uuid::Uuid;
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type WsWrite = SplitSink<WsStream, WsMessage>;
+type WsRead = SplitStream<WsStream>;
+type Pending = Arc<Mutex<HashMap<String, oneshot::Sender<SignalingEnvelope>>>>;
+
+/// 中继消息中携带发送时间的 ufrag 前缀
+const RELAY_STAMP_PREFIX: &str = "bench:";
+
+/// 所有模拟 Actor 共享的参数与统计
+pub struct Fleet {
+    pub url: String,
+    pub realm_id: u32,
+    pub actr_type: ActrType,
+    pub timeout: Duration,
+    /// 中继延迟的时间基准（发送端写入、接收端计算）
+    pub epoch: Instant,
+    pub relay: Recorder,
+    pub relay_sent: AtomicU64,
+}
+
+impl Fleet {
+    pub fn new(url: String, realm_id: u32, actr_type: ActrType, timeout: Duration) -> Self {
+        Self {
+            url,
+            realm_id,
+            actr_type,
+            timeout,
+            epoch: Instant::now(),
+            relay: Recorder::default(),
+            relay_sent: AtomicU64::new(0),
+        }
+    }
+
+    /// 同类型 Actor 之间互相放行，路由查询与中继才不会被 ACL 拒绝
+    fn acl(&self) -> Acl {
+        Acl {
+            rules: vec![AclRule {
+                principals: vec![Principal {
+                    realm: Some(Realm {
+                        realm_id: self.realm_id,
+                    }),
+                    actr_type: Some(self.actr_type.clone()),
+                }],
+                permission: Permission::Allow as i32,
+            }],
+        }
+    }
+}
+
+/// 一个已注册的模拟 Actor
+pub struct SimActor {
+    registration: register_response::RegisterOk,
+    write: tokio::sync::Mutex<WsWrite>,
+    pending: Pending,
+    reader: JoinHandle<()>,
+    timeout: Duration,
+}
+
+impl SimActor {
+    /// 建立连接并注册，返回 Actor 与注册耗时（含 WebSocket 握手）
+    pub async fn connect(fleet: &Arc<Fleet>) -> Result<(Self, Duration)> {
+        let started = Instant::now();
+        let (ws, _) = tokio::time::timeout(fleet.timeout, connect_async(&fleet.url))
+            .await
+            .context("WebSocket connect timed out")?
+            .context("WebSocket connect failed")?;
+        let (mut write, mut read) = ws.split();
+
+        let register = RegisterRequest {
+            actr_type: fleet.actr_type.clone(),
+            realm: Realm {
+                realm_id: fleet.realm_id,
+            },
+            service: None,
+            service_spec: None,
+            acl: Some(fleet.acl()),
+            ws_address: None,
+        };
+        let (_, envelope) =
+            make_envelope(signaling_envelope::Flow::PeerToServer(PeerToSignaling {
+                payload: Some(peer_to_signaling::Payload::RegisterRequest(register)),
+            }));
+        send(&mut write, &envelope).await?;
+
+        let response = tokio::time::timeout(fleet.timeout, recv(&mut read))
+            .await
+            .context("register timed out")??;
+        let registration = match server_payload(response)? {
+            signaling_to_actr::Payload::RegisterResponse(RegisterResponse {
+                result: Some(register_response::Result::Success(ok)),
+            }) => ok,
+            other => bail!("register rejected: {other:?}"),
+        };
+        let elapsed = started.elapsed();
+
+        let pending: Pending = Arc::default();
+        let reader = tokio::spawn(read_loop(read, pending.clone(), fleet.clone()));
+        Ok((
+            Self {
+                registration,
+                write: tokio::sync::Mutex::new(write),
+                pending,
+                reader,
+                timeout: fleet.timeout,
+            },
+            elapsed,
+        ))
+    }
+
+    pub fn actr_id(&self) -> &ActrId {
+        &self.registration.actr_id
+    }
+
+    /// 发送心跳并等待 Pong
+    pub async fn ping(&self) -> Result<Duration> {
+        let payload = actr_to_signaling::Payload::Ping(actr_protocol::Ping {
+            availability: 100,
+            mailbox_backlog: 0.0,
+            power_reserve: 100.0,
+            ..Default::default()
+        });
+        let started = Instant::now();
+        match server_payload(self.request(payload).await?)? {
+            signaling_to_actr::Payload::Pong(_) => Ok(started.elapsed()),
+            other => bail!("unexpected ping response: {other:?}"),
+        }
+    }
+
+    /// 查询同类型 Actor 的路由候选
+    pub async fn route(&self, target_type: &ActrType) -> Result<Duration> {
+        let payload = actr_to_signaling::Payload::RouteCandidatesRequest(
+            actr_protocol::RouteCandidatesRequest {
+                target_type: target_type.clone(),
+                client_fingerprint: String::new(),
+                criteria: Some(
+                    actr_protocol::route_candidates_request::NodeSelectionCriteria {
+                        candidate_count: 8,
+                        ranking_factors: vec![],
+                        minimal_dependency_requirement: None,
+                        minimal_health_requirement: None,
+                    },
+                ),
+                client_location: None,
+            },
+        );
+        let started = Instant::now();
+        match server_payload(self.request(payload).await?)? {
+            signaling_to_actr::Payload::RouteCandidatesResponse(response) => {
+                match response.result {
+                    Some(route_candidates_response::Result::Success(_)) => Ok(started.elapsed()),
+                    other => bail!("route candidates failed: {other:?}"),
+                }
+            }
+            other => bail!("unexpected route response: {other:?}"),
+        }
+    }
+
+    /// 向目标 Actor 中继一条 ICE candidate，延迟由接收端的读取任务记录
+    pub async fn relay(&self, fleet: &Fleet, target: &ActrId) -> Result<()> {
+        let stamp = fleet.epoch.elapsed().as_nanos();
+        let relay = ActrRelay {
+            source: self.registration.actr_id.clone(),
+            credential: self.registration.credential.clone(),
+            target: target.clone(),
+            payload: Some(actr_relay::Payload::IceCandidate(
+                actr_protocol::IceCandidate {
+                    candidate: "candidate:1 1 udp 2122252543 127.0.0.1 9 typ host".into(),
+                    sdp_mid: Some("0".into()),
+                    sdp_mline_index: Some(0),
+                    username_fragment: Some(format!("{RELAY_STAMP_PREFIX}{stamp}")),
+                },
+            )),
+        };
+        let (_, envelope) = make_envelope(signaling_envelope::Flow::ActrRelay(relay));
+        send(&mut *self.write.lock().await, &envelope).await?;
+        fleet.relay_sent.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 关闭连接
+    pub async fn close(self) {
+        let _ = self.write.lock().await.send(WsMessage::Close(None)).await;
+        self.reader.abort();
+    }
+
+    async fn request(&self, payload: actr_to_signaling::Payload) -> Result<SignalingEnvelope> {
+        let (envelope_id, envelope) = make_envelope(signaling_envelope::Flow::ActrToServer(
+            actr_protocol::ActrToSignaling {
+                source: self.registration.actr_id.clone(),
+                credential: self.registration.credential.clone(),
+                payload: Some(payload),
+            },
+        ));
+        let (tx, rx) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("pending lock poisoned")
+            .insert(envelope_id.clone(), tx);
+
+        let result = async {
+            send(&mut *self.write.lock().await, &envelope).await?;
+            tokio::time::timeout(self.timeout, rx)
+                .await
+                .context("request timed out")?
+                .context("connection closed")
+        }
+        .await;
+        if result.is_err() {
+            self.pending
+                .lock()
+                .expect("pending lock poisoned")
+                .remove(&envelope_id);
+        }
+        result
+    }
+}
+
+/// 读取任务：按 reply_for 分发响应，记录带时间戳中继消息的延迟
+async fn read_loop(mut read: WsRead, pending: Pending, fleet: Arc<Fleet>) {
+    while let Some(Ok(message)) = read.next().await {
+        let data = match message {
+            WsMessage::Binary(data) => data,
+            WsMessage::Close(_) => break,
+            _ => continue,
+        };
+        let Ok(envelope) = SignalingEnvelope::decode(&data[..]) else {
+            continue;
+        };
+
+        if let Some(signaling_envelope::Flow::ActrRelay(ref relay)) = envelope.flow {
+            if let Some(actr_relay::Payload::IceCandidate(ref candidate)) = relay.payload
+                && let Some(stamp) = candidate
+                    .username_fragment
+                    .as_deref()
+                    .and_then(|ufrag| ufrag.strip_prefix(RELAY_STAMP_PREFIX))
+                    .and_then(|nanos| nanos.parse::<u64>().ok())
+            {
+                let sent = Duration::from_nanos(stamp);
+                fleet
+                    .relay
+                    .record(fleet.epoch.elapsed().saturating_sub(sent));
+            }
+            continue;
+        }
+
+        if let Some(reply_for) = envelope.reply_for.clone()
+            && let Some(tx) = pending
+                .lock()
+                .expect("pending lock poisoned")
+                .remove(&reply_for)
+        {
+            let _ = tx.send(envelope);
+        }
+    }
+    // 连接断开：丢弃等待者，request 返回 "connection closed"
+    pending.lock().expect("pending lock poisoned").clear();
+}
+
+fn make_envelope(flow: signaling_envelope::Flow) -> (String, SignalingEnvelope) {
+    let envelope_id = Uuid::new_v4().to_string();
+    let envelope = SignalingEnvelope {
+        envelope_version: 1,
+        envelope_id: envelope_id.clone(),
+        timestamp: prost_types::Timestamp {
+            seconds: chrono::Utc::now().timestamp(),
+            nanos: 0,
+        },
+        reply_for: None,
+        traceparent: None,
+        tracestate: None,
+        flow: Some(flow),
+    };
+    (envelope_id, envelope)
+}
+
+async fn send(write: &mut WsWrite, envelope: &SignalingEnvelope) -> Result<()> {
+    write
+        .send(WsMessage::Binary(envelope.encode_to_vec().into()))
+        .await
+        .context("WebSocket send failed")
+}
+
+async fn recv(read: &mut WsRead) -> Result<SignalingEnvelope> {
+    loop {
+        match read.next().await {
+            Some(Ok(WsMessage::Binary(data))) => {
+                return SignalingEnvelope::decode(&data[..]).context("decode envelope");
+            }
+            Some(Ok(WsMessage::Close(frame))) => bail!("connection closed: {frame:?}"),
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e).context("WebSocket receive failed"),
+            None => bail!("connection closed"),
+        }
+    }
+}
+
+fn server_payload(envelope: SignalingEnvelope) -> Result<signaling_to_actr::Payload> {
+    match envelope.flow {
+        Some(signaling_envelope::Flow::ServerToActr(message)) => message
+            .payload
+            .ok_or_else(|| anyhow!("empty server payload")),
+        other => bail!("unexpected flow: {other:?}"),
+    }
+}
@@ -0,0 +1,119 @@
+//! 延迟采样与百分位统计
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// 单项操作的延迟采样（多任务共享）
+#[derive(Debug, Default)]
+pub struct Recorder {
+    samples_us: Mutex<Vec<u64>>,
+    errors: AtomicU64,
+}
+
+impl Recorder {
+    pub fn record(&self, latency: Duration) {
+        let micros = latency.as_micros().min(u64::MAX as u128) as u64;
+        self.samples_us
+            .lock()
+            .expect("recorder lock poisoned")
+            .push(micros);
+    }
+
+    pub fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 汇总采样，`elapsed` 为采样持续时间，用于计算吞吐
+    pub fn summary(&self, elapsed: Duration) -> Summary {
+        let mut samples = self
+            .samples_us
+            .lock()
+            .expect("recorder lock poisoned")
+            .clone();
+        samples.sort_unstable();
+        Summary::from_sorted(&samples, self.errors.load(Ordering::Relaxed), elapsed)
+    }
+}
+
+/// 延迟统计结果（毫秒）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Summary {
+    pub count: u64,
+    pub errors: u64,
+    pub rate_per_sec: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Summary {
+    fn from_sorted(samples: &[u64], errors: u64, elapsed: Duration) -> Self {
+        if samples.is_empty() {
+            return Self {
+                errors,
+                ..Default::default()
+            };
+        }
+        let to_ms = |micros: u64| micros as f64 / 1000.0;
+        let total: u64 = samples.iter().sum();
+        let secs = elapsed.as_secs_f64();
+        Self {
+            count: samples.len() as u64,
+            errors,
+            rate_per_sec: if secs > 0.0 {
+                samples.len() as f64 / secs
+            } else {
+                0.0
+            },
+            mean_ms: to_ms(total) / samples.len() as f64,
+            p50_ms: to_ms(percentile(samples, 50.0)),
+            p90_ms: to_ms(percentile(samples, 90.0)),
+            p99_ms: to_ms(percentile(samples, 99.0)),
+            max_ms: to_ms(samples[samples.len() - 1]),
+        }
+    }
+}
+
+/// 最近秩法百分位，`sorted` 必须非空且已升序排列
+fn percentile(sorted: &[u64], pct: f64) -> u64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_nearest_rank() {
+        let recorder = Recorder::default();
+        for ms in 1..=100 {
+            recorder.record(Duration::from_millis(ms));
+        }
+        recorder.record_error();
+
+        let summary = recorder.summary(Duration::from_secs(10));
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.rate_per_sec, 10.0);
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p90_ms, 90.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert_eq!(summary.mean_ms, 50.5);
+    }
+
+    #[test]
+    fn test_empty_summary_keeps_errors() {
+        let recorder = Recorder::default();
+        recorder.record_error();
+        let summary = recorder.summary(Duration::from_secs(1));
+        assert_eq!(summary.count, 0);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(summary.p99_ms, 0.0);
+    }
+}
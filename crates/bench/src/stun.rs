@@ -0,0 +1,134 @@
+//! STUN Binding 请求压测
+
+use crate::stats::{Recorder, Summary};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use webrtc_stun::agent::TransactionId;
+use webrtc_stun::message::{BINDING_REQUEST, BINDING_SUCCESS, Message};
+
+/// STUN 压测参数
+pub struct StunOptions {
+    pub target: SocketAddr,
+    pub sockets: usize,
+    /// 每个套接字每秒发送的请求数
+    pub rate: u32,
+    pub duration: Duration,
+    pub timeout: Duration,
+}
+
+/// STUN 压测结果
+#[derive(Debug, Serialize)]
+pub struct StunReport {
+    pub sent: u64,
+    /// 超时未收到响应的请求（计入 binding.errors）
+    pub lost: u64,
+    pub binding: Summary,
+}
+
+type InFlight = Arc<Mutex<HashMap<[u8; 12], Instant>>>;
+
+pub async fn run(options: StunOptions) -> Result<StunReport> {
+    let recorder = Arc::new(Recorder::default());
+    let started = Instant::now();
+
+    let mut tasks = Vec::with_capacity(options.sockets);
+    for _ in 0..options.sockets {
+        let bind: SocketAddr = if options.target.is_ipv6() {
+            "[::]:0".parse()?
+        } else {
+            "0.0.0.0:0".parse()?
+        };
+        let socket = Arc::new(UdpSocket::bind(bind).await.context("bind UDP socket")?);
+        socket
+            .connect(options.target)
+            .await
+            .context("connect UDP socket")?;
+        tasks.push(tokio::spawn(flood(
+            socket,
+            options.rate,
+            options.duration,
+            options.timeout,
+            recorder.clone(),
+        )));
+    }
+
+    let mut sent = 0;
+    let mut lost = 0;
+    for task in tasks {
+        let (task_sent, task_lost) = task.await??;
+        sent += task_sent;
+        lost += task_lost;
+    }
+
+    Ok(StunReport {
+        sent,
+        lost,
+        binding: recorder.summary(started.elapsed()),
+    })
+}
+
+/// 单个套接字：按固定速率发送 Binding 请求，另起任务接收响应
+async fn flood(
+    socket: Arc<UdpSocket>,
+    rate: u32,
+    duration: Duration,
+    timeout: Duration,
+    recorder: Arc<Recorder>,
+) -> Result<(u64, u64)> {
+    let in_flight: InFlight = Arc::default();
+    let receiver = tokio::spawn(receive(socket.clone(), in_flight.clone(), recorder.clone()));
+
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / rate.max(1) as f64));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let deadline = Instant::now() + duration;
+    let mut sent = 0;
+    while Instant::now() < deadline {
+        ticker.tick().await;
+        let mut request = Message::new();
+        request.build(&[Box::<TransactionId>::default(), Box::new(BINDING_REQUEST)])?;
+        in_flight
+            .lock()
+            .expect("in-flight lock poisoned")
+            .insert(request.transaction_id.0, Instant::now());
+        if socket.send(&request.raw).await.is_err() {
+            in_flight
+                .lock()
+                .expect("in-flight lock poisoned")
+                .remove(&request.transaction_id.0);
+            recorder.record_error();
+            continue;
+        }
+        sent += 1;
+    }
+
+    // 等待最后一批响应
+    tokio::time::sleep(timeout).await;
+    receiver.abort();
+    let lost = in_flight.lock().expect("in-flight lock poisoned").len() as u64;
+    for _ in 0..lost {
+        recorder.record_error();
+    }
+    Ok((sent, lost))
+}
+
+async fn receive(socket: Arc<UdpSocket>, in_flight: InFlight, recorder: Arc<Recorder>) {
+    let mut buf = vec![0u8; 1500];
+    while let Ok(len) = socket.recv(&mut buf).await {
+        let mut response = Message::new();
+        if response.write(&buf[..len]).is_err() || response.typ != BINDING_SUCCESS {
+            continue;
+        }
+        let sent_at = in_flight
+            .lock()
+            .expect("in-flight lock poisoned")
+            .remove(&response.transaction_id.0);
+        if let Some(sent_at) = sent_at {
+            recorder.record(sent_at.elapsed());
+        }
+    }
+}
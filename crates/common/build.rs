@@ -29,8 +29,25 @@ fn main() {
             std::process::exit(1);
         }
     }
+
+    // Field metadata for `config::schema` (`actrix config generate`)
+    let schema_output_path = Path::new(&env::var("OUT_DIR").unwrap()).join("config_schema.rs");
+    let extra_sources: Vec<_> = KS_CONFIG_SOURCES
+        .iter()
+        .map(|source| Path::new(&manifest_dir).join(source))
+        .collect();
+    for source in &extra_sources {
+        println!("cargo:rerun-if-changed={}", source.display());
+    }
+    if let Err(e) = generate_schema_metadata(&config_dir, &extra_sources, &schema_output_path) {
+        eprintln!("Failed to generate configuration schema metadata: {e}");
+        std::process::exit(1);
+    }
 }
 
+/// Configuration types defined in the KS crate (`services.ks`)
+const KS_CONFIG_SOURCES: &[&str] = &["../ks/src/config.rs", "../ks/src/storage/config.rs"];
+
 fn generate_config_template(
     config_dir: &Path,
     output_path: &Path,
@@ -341,6 +358,7 @@ fn get_default_value_for_field_with_context(field_name: &str, section_prefix: &s
         "key" => "\"certificates/server.key\"".to_string(),
         "relay_port_range" => "\"49152-65535\"".to_string(),
         "realm" => "\"actor-rtc.local\"".to_string(),
+        "allowed_realm_ids" => "[]".to_string(),
 
         // Supervisor config
         "node_id" => "\"\"".to_string(),
@@ -360,6 +378,294 @@ fn get_default_value_for_field(field_name: &str) -> String {
     get_default_value_for_field_with_context(field_name, "")
 }
 
+/// Generate static field metadata (`CONFIG_STRUCTS` / `CONFIG_ENUMS`) for runtime schema export
+fn generate_schema_metadata(
+    config_dir: &Path,
+    extra_sources: &[std::path::PathBuf],
+    output_path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut structs = parse_all_config_modules(config_dir)?;
+    let mut enums = HashMap::new();
+    let mut sources = config_source_files(config_dir)?;
+    for source in extra_sources {
+        if source.is_file() {
+            parse_file_structs(source, &mut structs)?;
+            sources.push(source.clone());
+        }
+    }
+    for source in &sources {
+        parse_file_enums(source, &mut enums)?;
+    }
+
+    let mut struct_names: Vec<&String> = structs.keys().collect();
+    struct_names.sort();
+    let mut enum_names: Vec<&String> = enums.keys().collect();
+    enum_names.sort();
+
+    let mut out = String::new();
+    out.push_str("// Generated by build.rs from the configuration structs. Do not edit.\n\n");
+    out.push_str("pub(crate) static CONFIG_STRUCTS: &[ConfigStruct] = &[\n");
+    for name in struct_names {
+        let item = &structs[name];
+        let container_default = serde_flags(&item.attrs).default;
+        out.push_str(&format!(
+            "    ConfigStruct {{ name: {:?}, doc: {:?}, fields: &[\n",
+            name,
+            doc_string(&item.attrs)
+        ));
+        if let Fields::Named(fields) = &item.fields {
+            for field in &fields.named {
+                let flags = serde_flags(&field.attrs);
+                if flags.skip {
+                    continue;
+                }
+                let name = flags
+                    .rename
+                    .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string());
+                let (kind, optional) = match option_inner(&field.ty) {
+                    Some(inner) => (field_kind(inner, &structs, &enums), true),
+                    None => (field_kind(&field.ty, &structs, &enums), false),
+                };
+                out.push_str(&format!(
+                    "        ConfigField {{ name: {:?}, doc: {:?}, kind: {}, optional: {}, has_default: {} }},\n",
+                    name,
+                    doc_string(&field.attrs),
+                    kind,
+                    optional,
+                    container_default || flags.default
+                ));
+            }
+        }
+        out.push_str("    ] },\n");
+    }
+    out.push_str("];\n\n");
+
+    out.push_str("pub(crate) static CONFIG_ENUMS: &[ConfigEnum] = &[\n");
+    for name in enum_names {
+        let (doc, variants) = &enums[name];
+        out.push_str(&format!(
+            "    ConfigEnum {{ name: {name:?}, doc: {doc:?}, variants: &{variants:?} }},\n"
+        ));
+    }
+    out.push_str("];\n");
+
+    fs::write(output_path, out)?;
+    Ok(())
+}
+
+/// All `.rs` files in the config directory and its submodule directories
+fn config_source_files(
+    config_dir: &Path,
+) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(config_dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            for sub_entry in fs::read_dir(&path)? {
+                let sub_path = sub_entry?.path();
+                if sub_path.extension().is_some_and(|ext| ext == "rs") {
+                    files.push(sub_path);
+                }
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Parse unit-only enums (serialized as strings) with their serde names
+fn parse_file_enums(
+    path: &Path,
+    enums: &mut HashMap<String, (String, Vec<String>)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    let Ok(syntax_tree) = syn::parse_str::<syn::File>(&content) else {
+        return Ok(());
+    };
+    for item in syntax_tree.items {
+        let syn::Item::Enum(enum_item) = item else {
+            continue;
+        };
+        if !enum_item
+            .variants
+            .iter()
+            .all(|variant| matches!(variant.fields, Fields::Unit))
+        {
+            continue;
+        }
+        let rename_all = serde_flags(&enum_item.attrs).rename_all;
+        let variants = enum_item
+            .variants
+            .iter()
+            .filter_map(|variant| {
+                let flags = serde_flags(&variant.attrs);
+                if flags.skip {
+                    return None;
+                }
+                Some(flags.rename.unwrap_or_else(|| {
+                    rename_variant(&variant.ident.to_string(), rename_all.as_deref())
+                }))
+            })
+            .collect();
+        enums.insert(
+            enum_item.ident.to_string(),
+            (doc_string(&enum_item.attrs), variants),
+        );
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct SerdeFlags {
+    default: bool,
+    skip: bool,
+    rename: Option<String>,
+    rename_all: Option<String>,
+}
+
+fn serde_flags(attrs: &[Attribute]) -> SerdeFlags {
+    let mut flags = SerdeFlags::default();
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                flags.default = true;
+                if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<syn::LitStr>()?;
+                }
+            } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                flags.skip = true;
+            } else if meta.path.is_ident("rename") {
+                flags.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.path.is_ident("rename_all") {
+                flags.rename_all = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+            Ok(())
+        });
+    }
+    flags
+}
+
+fn rename_variant(variant: &str, rename_all: Option<&str>) -> String {
+    let snake = || {
+        let mut out = String::new();
+        for (i, c) in variant.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        }
+        out
+    };
+    match rename_all {
+        Some("lowercase") => variant.to_lowercase(),
+        Some("UPPERCASE") => variant.to_uppercase(),
+        Some("snake_case") => snake(),
+        Some("SCREAMING_SNAKE_CASE") => snake().to_uppercase(),
+        Some("kebab-case") => snake().replace('_', "-"),
+        _ => variant.to_string(),
+    }
+}
+
+/// Doc comment lines joined with newlines
+fn doc_string(attrs: &[Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_args(ty, "Option").and_then(|args| args.first().copied())
+}
+
+fn generic_args<'a>(ty: &'a Type, ident: &str) -> Option<Vec<&'a Type>> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != ident {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    Some(
+        args.args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+/// `FieldKind` expression for a field type
+fn field_kind(
+    ty: &Type,
+    structs: &HashMap<String, syn::ItemStruct>,
+    enums: &HashMap<String, (String, Vec<String>)>,
+) -> String {
+    let Type::Path(type_path) = ty else {
+        return "FieldKind::Any".to_string();
+    };
+    let ident = type_path_to_string(type_path);
+    match ident.as_str() {
+        "String" | "PathBuf" | "IpAddr" | "Ipv4Addr" | "Ipv6Addr" | "SocketAddr" | "Url" => {
+            "FieldKind::String".to_string()
+        }
+        "bool" => "FieldKind::Boolean".to_string(),
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+        | "isize" => "FieldKind::Integer".to_string(),
+        "f32" | "f64" => "FieldKind::Number".to_string(),
+        "Option" | "Box" | "Arc" => match generic_args(ty, &ident).and_then(|a| a.first().copied())
+        {
+            Some(inner) => field_kind(inner, structs, enums),
+            None => "FieldKind::Any".to_string(),
+        },
+        "Vec" | "VecDeque" | "HashSet" | "BTreeSet" => {
+            match generic_args(ty, &ident).and_then(|a| a.first().copied()) {
+                Some(inner) => format!("FieldKind::Array(&{})", field_kind(inner, structs, enums)),
+                None => "FieldKind::Any".to_string(),
+            }
+        }
+        "HashMap" | "BTreeMap" => match generic_args(ty, &ident).and_then(|a| a.get(1).copied()) {
+            Some(value) => format!("FieldKind::Map(&{})", field_kind(value, structs, enums)),
+            None => "FieldKind::Any".to_string(),
+        },
+        name if structs.contains_key(name) => format!("FieldKind::Struct({name:?})"),
+        name if enums.contains_key(name) => format!("FieldKind::Enum({name:?})"),
+        _ => "FieldKind::Any".to_string(),
+    }
+}
+
 trait AttributeValue {
     fn value(&self) -> Result<TokenStream, syn::Error>;
 }
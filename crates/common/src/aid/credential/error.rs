@@ -26,6 +26,18 @@ pub enum AidError {
     #[error("JSON serialization error: {0}")]
     JsonSerializationError(#[from] serde_json::Error),
 
+    #[error("Token signature verification failed: {0}")]
+    SignatureInvalid(String),
+
+    #[error("Key {0} has been revoked")]
+    KeyRevoked(u32),
+
+    #[error("Credential of serial number {0} has been revoked")]
+    CredentialRevoked(u64),
+
+    #[error("Credential PSK of serial number {0} has been rotated")]
+    PskSuperseded(u64),
+
     #[error("Token decryption failed: {0}")]
     DecryptionFailed(String),
 
@@ -1,23 +1,60 @@
 //! AId Credential 验证器
 //!
 //! 负责验证和解密 AId Token，专注于验证职责
+//!
+//! 加密密钥（`token_key_id`）用于解密 Token，签名密钥（credential 元数据中的
+//! `signing_key_id`）用于验签，两者均按 key_id 从本地缓存或 KS 获取。
+//!
+//! 全局实例订阅 KS 密钥轮替事件：旧密钥被转为仅验证后移除其本地缓存，
+//! 下次验证时从 KS 重新获取宽限期截止时间。
+//!
+//! 同时订阅 KS 密钥吊销事件，维护已吊销密钥的黑名单：使用黑名单中的密钥加密或
+//! 签名的凭证立即被拒绝（`AidError::KeyRevoked`），不再等待本地缓存到期。
+//!
+//! 单个 Actor 的凭证吊销由 AIS 以 [`RevocationFilter`] 下发，调用方通过
+//! [`AIdCredentialValidator::set_revocation_filter`] 更新；序列号命中过滤器的凭证
+//! 被拒绝（`AidError::CredentialRevoked`）。
+//!
+//! PSK 轮替记录随吊销列表下发，通过 [`AIdCredentialValidator::set_psk_rotations`] 更新：
+//! 轮替后上一代凭证只在重叠窗口内有效，更早的凭证被拒绝（`AidError::PskSuperseded`）。
 
 use super::error::AidError;
 use crate::aid::identity_claims::IdentityClaims;
 use crate::aid::key_cache::KeyCache;
+use crate::aid::revocation::{PskRotation, RevocationFilter};
+use crate::aid::signed_token::SignedToken;
 use crate::config::ks::KsClientConfig;
 use actr_protocol::AIdCredential;
-use ecies::{SecretKey, decrypt};
+use ecies::{PublicKey, SecretKey, decrypt};
 use ks::GrpcClient;
 use once_cell::sync::OnceCell;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use tracing::{debug, error};
+use tracing::{debug, error, info, warn};
+
+/// KS 轮替/吊销事件订阅断开后的重连间隔（秒）
+const KEY_ROTATION_WATCH_RETRY_SECS: u64 = 5;
 
 /// AId Token 验证器 - 提供静态方法验证和解密 Token
 pub struct AIdCredentialValidator {
     key_cache: Arc<KeyCache>,
     ks_client: Arc<RwLock<GrpcClient>>,
+    /// 已吊销的密钥 ID
+    revoked_keys: Arc<std::sync::RwLock<HashSet<u32>>>,
+    /// AIS 下发的已吊销凭证序列号
+    revoked_credentials: std::sync::RwLock<RevocationFilter>,
+    /// AIS 下发的 PSK 轮替记录（按序列号）
+    psk_rotations: std::sync::RwLock<HashMap<u64, PskRotation>>,
+}
+
+/// 解密后的 Token 明文
+enum DecryptedToken {
+    /// 带签名与元数据的 Token
+    Signed(SignedToken),
+    /// 升级前签发的未签名 Token
+    Legacy(IdentityClaims),
 }
 
 static VALIDATOR_INSTANCE: OnceCell<Arc<AIdCredentialValidator>> = OnceCell::new();
@@ -54,6 +91,9 @@ impl AIdCredentialValidator {
         Ok(Self {
             key_cache,
             ks_client,
+            revoked_keys: Arc::new(std::sync::RwLock::new(HashSet::new())),
+            revoked_credentials: std::sync::RwLock::new(RevocationFilter::default()),
+            psk_rotations: std::sync::RwLock::new(HashMap::new()),
         })
     }
 
@@ -63,10 +103,185 @@ impl AIdCredentialValidator {
         actrix_shared_key: &str,
         sqlite_path: &std::path::Path,
     ) -> Result<(), AidError> {
-        let validator = Self::new(ks_client_config, actrix_shared_key, sqlite_path).await?;
+        let validator =
+            Arc::new(Self::new(ks_client_config, actrix_shared_key, sqlite_path).await?);
         VALIDATOR_INSTANCE
-            .set(Arc::new(validator))
+            .set(validator.clone())
             .map_err(|_| AidError::DecryptionFailed("Validator already initialized".to_string()))?;
+        validator.spawn_key_rotation_watch_task();
+        validator.spawn_key_revocation_watch_task();
+        Ok(())
+    }
+
+    /// 启动 KS 轮替事件订阅任务，按事件移除旧密钥的本地缓存
+    fn spawn_key_rotation_watch_task(&self) {
+        let key_cache = self.key_cache.clone();
+        let ks_client = self.ks_client.clone();
+
+        tokio::spawn(async move {
+            loop {
+                // 仅在建立订阅时持有客户端锁
+                let watch = ks_client.write().await.watch_key_rotations().await;
+                match watch {
+                    Ok(mut watch) => loop {
+                        match watch.next().await {
+                            Ok(Some(rotation)) => {
+                                let Some(previous_key_id) = rotation.previous_key_id else {
+                                    continue;
+                                };
+                                match key_cache.remove_key(previous_key_id).await {
+                                    Ok(true) => info!(
+                                        "Evicted cached key {} rotated by KS (verify-only until {})",
+                                        previous_key_id, rotation.verify_until
+                                    ),
+                                    Ok(false) => {}
+                                    Err(e) => warn!(
+                                        "Failed to evict cached key {}: {}",
+                                        previous_key_id, e
+                                    ),
+                                }
+                            }
+                            Ok(None) => {
+                                debug!("KS key rotation stream closed");
+                                break;
+                            }
+                            Err(e) => {
+                                warn!("KS key rotation stream error: {}", e);
+                                break;
+                            }
+                        }
+                    },
+                    Err(e) => debug!("Failed to subscribe to KS key rotations: {}", e),
+                }
+
+                tokio::time::sleep(Duration::from_secs(KEY_ROTATION_WATCH_RETRY_SECS)).await;
+            }
+        });
+    }
+
+    /// 启动 KS 吊销事件订阅任务，将吊销的密钥加入黑名单并移除其本地缓存
+    ///
+    /// 每次（重新）订阅时 KS 先推送全部已吊销的密钥，断线期间的吊销不会遗漏
+    fn spawn_key_revocation_watch_task(&self) {
+        let key_cache = self.key_cache.clone();
+        let ks_client = self.ks_client.clone();
+        let revoked_keys = self.revoked_keys.clone();
+
+        tokio::spawn(async move {
+            loop {
+                // 仅在建立订阅时持有客户端锁
+                let watch = ks_client.write().await.watch_key_revocations().await;
+                match watch {
+                    Ok(mut watch) => loop {
+                        match watch.next().await {
+                            Ok(Some(revocation)) => {
+                                let key_id = revocation.key_id;
+                                if revoked_keys.write().unwrap().insert(key_id) {
+                                    warn!(
+                                        "KS revoked key {} at {}, rejecting credentials using it",
+                                        key_id, revocation.revoked_at
+                                    );
+                                }
+                                if let Err(e) = key_cache.remove_key(key_id).await {
+                                    warn!("Failed to evict cached key {}: {}", key_id, e);
+                                }
+                            }
+                            Ok(None) => {
+                                debug!("KS key revocation stream closed");
+                                break;
+                            }
+                            Err(e) => {
+                                warn!("KS key revocation stream error: {}", e);
+                                break;
+                            }
+                        }
+                    },
+                    Err(e) => debug!("Failed to subscribe to KS key revocations: {}", e),
+                }
+
+                tokio::time::sleep(Duration::from_secs(KEY_ROTATION_WATCH_RETRY_SECS)).await;
+            }
+        });
+    }
+
+    /// 确认密钥未被吊销
+    fn ensure_not_revoked(&self, key_id: u32) -> Result<(), AidError> {
+        if self.revoked_keys.read().unwrap().contains(&key_id) {
+            return Err(AidError::KeyRevoked(key_id));
+        }
+        Ok(())
+    }
+
+    /// 更新全局验证器的凭证吊销过滤器
+    ///
+    /// 新过滤器整体替换旧过滤器，之后的验证立即生效
+    pub fn set_revocation_filter(filter: RevocationFilter) -> Result<(), AidError> {
+        let validator = Self::get_instance()?;
+        *validator.revoked_credentials.write().unwrap() = filter;
+        Ok(())
+    }
+
+    /// 序列号是否命中全局验证器的吊销过滤器（验证器未初始化时为 false）
+    ///
+    /// 供缓存了认证结果的调用方（如 TURN 认证缓存）在每次命中时复查
+    pub fn is_serial_revoked(serial_number: u64) -> bool {
+        VALIDATOR_INSTANCE.get().is_some_and(|validator| {
+            validator
+                .revoked_credentials
+                .read()
+                .unwrap()
+                .contains(serial_number)
+        })
+    }
+
+    /// 确认凭证的序列号未被 AIS 吊销
+    fn ensure_credential_not_revoked(&self, claims: &IdentityClaims) -> Result<(), AidError> {
+        if let Some(serial_number) = claims.serial_number()
+            && self
+                .revoked_credentials
+                .read()
+                .unwrap()
+                .contains(serial_number)
+        {
+            return Err(AidError::CredentialRevoked(serial_number));
+        }
+        Ok(())
+    }
+
+    /// 更新全局验证器的 PSK 轮替记录
+    ///
+    /// 新记录整体替换旧记录，之后的验证立即生效
+    pub fn set_psk_rotations(rotations: Vec<PskRotation>) -> Result<(), AidError> {
+        let validator = Self::get_instance()?;
+        *validator.psk_rotations.write().unwrap() = rotations
+            .into_iter()
+            .map(|rotation| (rotation.serial_number, rotation))
+            .collect();
+        Ok(())
+    }
+
+    /// 确认凭证的 PSK 未被轮替替换（或仍在重叠窗口内）
+    fn ensure_psk_current(&self, claims: &IdentityClaims) -> Result<(), AidError> {
+        let Some(serial_number) = claims.serial_number() else {
+            return Ok(());
+        };
+        let Some(rotation) = self
+            .psk_rotations
+            .read()
+            .unwrap()
+            .get(&serial_number)
+            .copied()
+        else {
+            return Ok(());
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if !rotation.accepts(claims.psk_epoch, now) {
+            return Err(AidError::PskSuperseded(serial_number));
+        }
         Ok(())
     }
 
@@ -79,7 +294,7 @@ impl AIdCredentialValidator {
         })
     }
 
-    /// 检查 credential (解密 + 验证有效性 + 容忍期检测)
+    /// 检查 credential (解密 + 验签 + 验证有效性 + 容忍期检测)
     ///
     /// 使用 AIdCredential 进行验证，并返回密钥是否在容忍期
     ///
@@ -89,20 +304,17 @@ impl AIdCredentialValidator {
     ///
     /// # Returns
     /// * `Ok((Claims, in_tolerance_period))` - 验证成功，返回解密后的身份声明和容忍期状态
+    ///   （加密密钥或签名密钥任一处于容忍期即为 true）
     /// * `Err(AidError)` - 验证失败，包含具体错误信息
     pub async fn check(
         credential: &AIdCredential,
         realm_id: u32,
     ) -> Result<(IdentityClaims, bool), AidError> {
         let validator = Self::get_instance()?;
-        let (secret_key, in_tolerance) = validator
-            .get_secret_key_with_tolerance(credential.token_key_id)
-            .await?;
-        let claims = Self::check_with_key(credential, realm_id, &secret_key)?;
-        Ok((claims, in_tolerance))
+        validator.check_credential(credential, realm_id).await
     }
 
-    /// Synchronously checks a credential (decryption + validity verification)
+    /// Synchronously checks a credential (decryption + signature + validity verification)
     ///
     /// This synchronous method is intended for use in sync contexts (such as TURN authentication).
     pub fn check_sync(
@@ -112,47 +324,128 @@ impl AIdCredentialValidator {
         let validator = Self::get_instance()?;
 
         // Use block_in_place to execute async operations without blocking the entire runtime
-        let (secret_key, _in_tolerance) = tokio::task::block_in_place(|| {
+        let (claims, _in_tolerance) = tokio::task::block_in_place(|| {
             let handle = tokio::runtime::Handle::try_current().map_err(|_| {
                 AidError::DecryptionFailed("Not in tokio runtime context".to_string())
             })?;
 
-            handle.block_on(async {
-                validator
-                    .get_secret_key_with_tolerance(credential.token_key_id)
-                    .await
-            })
+            handle.block_on(validator.check_credential(credential, realm_id))
         })?;
 
-        Self::check_with_key(credential, realm_id, &secret_key)
+        Ok(claims)
+    }
+
+    /// 解密 credential，按需获取签名密钥并验签
+    async fn check_credential(
+        &self,
+        credential: &AIdCredential,
+        realm_id: u32,
+    ) -> Result<(IdentityClaims, bool), AidError> {
+        let (encryption_key, encryption_in_tolerance) = self
+            .get_secret_key_with_tolerance(credential.token_key_id)
+            .await?;
+
+        let (claims, signing_in_tolerance) = match Self::decrypt_token(credential, &encryption_key)?
+        {
+            DecryptedToken::Signed(token) => {
+                let (signing_key, in_tolerance) = self
+                    .get_secret_key_with_tolerance(token.metadata.signing_key_id)
+                    .await?;
+                let verifying_key = PublicKey::from_secret_key(&signing_key);
+                let claims = Self::verify_signed_token(credential, &token, &verifying_key)?;
+                (claims, in_tolerance)
+            }
+            DecryptedToken::Legacy(claims) => {
+                debug!(
+                    "Accepting unsigned legacy credential (key_id={})",
+                    credential.token_key_id
+                );
+                (claims, false)
+            }
+        };
+
+        Self::validate_claims(&claims, realm_id)?;
+        self.ensure_credential_not_revoked(&claims)?;
+        self.ensure_psk_current(&claims)?;
+        Ok((claims, encryption_in_tolerance || signing_in_tolerance))
     }
 
-    /// 使用提供的密钥检查 credential (解密 + 验证有效性)
+    /// 使用提供的密钥检查 credential (解密 + 验签 + 验证有效性)
     ///
-    /// # Arguments  
+    /// # Arguments
     /// * `credential` - 来自 actor-rtc-proto 的 AIdCredential
     /// * `realm_id` - 期望的 Realm ID
-    /// * `secret_key` - 用于解密的密钥
+    /// * `encryption_key` - 用于解密的加密私钥（对应 `token_key_id`）
+    /// * `verifying_key` - 签名公钥（对应 credential 元数据中的 `signing_key_id`）；
+    ///   为 None 时仅接受未签名的旧格式 Token
     ///
     /// # Returns
     /// * `Ok(Claims)` - 验证成功，返回解密后的身份声明
     /// * `Err(AidError)` - 验证失败，包含具体错误信息
-    pub fn check_with_key(
+    pub fn check_with_keys(
         credential: &AIdCredential,
         realm_id: u32,
-        secret_key: &SecretKey,
+        encryption_key: &SecretKey,
+        verifying_key: Option<&PublicKey>,
     ) -> Result<IdentityClaims, AidError> {
+        let claims = match (
+            Self::decrypt_token(credential, encryption_key)?,
+            verifying_key,
+        ) {
+            (DecryptedToken::Signed(token), Some(verifying_key)) => {
+                Self::verify_signed_token(credential, &token, verifying_key)?
+            }
+            (DecryptedToken::Signed(token), None) => {
+                return Err(AidError::SignatureInvalid(format!(
+                    "Missing verifying key for signing key {}",
+                    token.metadata.signing_key_id
+                )));
+            }
+            (DecryptedToken::Legacy(claims), _) => claims,
+        };
+
+        Self::validate_claims(&claims, realm_id)?;
+        Ok(claims)
+    }
+
+    /// 解密 Token 明文，区分签名格式与旧格式
+    fn decrypt_token(
+        credential: &AIdCredential,
+        encryption_key: &SecretKey,
+    ) -> Result<DecryptedToken, AidError> {
         // 将 SecretKey 转换为字节
-        let secret_key_bytes = secret_key.serialize();
+        let secret_key_bytes = encryption_key.serialize();
 
         // 解密
         let decrypted_bytes = decrypt(&secret_key_bytes, &credential.encrypted_token)
             .map_err(|e| AidError::DecryptionFailed(format!("Decryption error: {e}")))?;
 
-        // 反序列化
+        // 反序列化：优先按签名格式解析，失败时回退到旧格式
+        if let Ok(token) = serde_json::from_slice::<SignedToken>(&decrypted_bytes) {
+            return Ok(DecryptedToken::Signed(token));
+        }
         let claims: IdentityClaims = serde_json::from_slice(&decrypted_bytes)
             .map_err(|e| AidError::DecryptionFailed(format!("Deserialization error: {e}")))?;
+        Ok(DecryptedToken::Legacy(claims))
+    }
 
+    /// 验证签名，并确认元数据中的加密密钥与 credential 一致
+    fn verify_signed_token(
+        credential: &AIdCredential,
+        token: &SignedToken,
+        verifying_key: &PublicKey,
+    ) -> Result<IdentityClaims, AidError> {
+        if token.metadata.encryption_key_id != credential.token_key_id {
+            return Err(AidError::SignatureInvalid(format!(
+                "Encryption key mismatch: metadata={}, credential={}",
+                token.metadata.encryption_key_id, credential.token_key_id
+            )));
+        }
+        token.verify(verifying_key)
+    }
+
+    /// 验证 claims 有效期与 Realm
+    fn validate_claims(claims: &IdentityClaims, realm_id: u32) -> Result<(), AidError> {
         // 验证 credential 是否过期
         if claims.is_expired() {
             return Err(AidError::Expired);
@@ -163,7 +456,7 @@ impl AIdCredentialValidator {
             return Err(AidError::DecryptionFailed("Realm ID mismatch".to_string()));
         }
 
-        Ok(claims)
+        Ok(())
     }
 
     /// 根据 key_id 获取对应的密钥和容忍期状态
@@ -179,6 +472,9 @@ impl AIdCredentialValidator {
     ) -> Result<(SecretKey, bool), AidError> {
         debug!("Fetching secret key for key_id: {}", key_id);
 
+        // 0. 已吊销的密钥直接拒绝，不使用缓存
+        self.ensure_not_revoked(key_id)?;
+
         // 1. 首先尝试从 SQLite 缓存获取
         match self.key_cache.get_cached_key(key_id).await? {
             Some((secret_key, expires_at, tolerance_seconds)) => {
@@ -198,9 +494,17 @@ impl AIdCredentialValidator {
         // 2. 从 KS 服务获取密钥、过期时间和容忍期秒数
         let (secret_key, expires_at, tolerance_seconds) = {
             let mut client = self.ks_client.write().await;
-            client.fetch_secret_key(key_id).await.map_err(|e| {
-                error!("Failed to fetch secret key {} from KS: {}", key_id, e);
-                AidError::DecryptionFailed(format!("KS error: {e}"))
+            client.fetch_secret_key(key_id).await.map_err(|e| match e {
+                // 订阅尚未推送到的吊销，在此补入黑名单
+                ks::KsError::KeyRevoked(key_id) => {
+                    warn!("KS reports key {} as revoked", key_id);
+                    self.revoked_keys.write().unwrap().insert(key_id);
+                    AidError::KeyRevoked(key_id)
+                }
+                e => {
+                    error!("Failed to fetch secret key {} from KS: {}", key_id, e);
+                    AidError::DecryptionFailed(format!("KS error: {e}"))
+                }
             })?
         };
 
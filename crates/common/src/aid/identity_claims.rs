@@ -23,6 +23,12 @@ pub struct IdentityClaims {
     /// Pre-shared key (PSK) for TURN authentication
     /// 256-bit (32 bytes) pre-shared key used for TURN server authentication
     pub psk: Vec<u8>,
+
+    /// PSK 代数：注册时为 0，每次通过 `/ais/rotate-psk` 轮替加 1
+    ///
+    /// 轮替前签发的 Token 不含该字段，按 0 处理
+    #[serde(default)]
+    pub psk_epoch: u32,
 }
 
 impl IdentityClaims {
@@ -33,6 +39,7 @@ impl IdentityClaims {
             actor_id,
             expr_time,
             psk,
+            psk_epoch: 0,
         }
     }
 
@@ -44,9 +51,16 @@ impl IdentityClaims {
             actor_id: actr_id.to_string_repr(),
             expr_time,
             psk,
+            psk_epoch: 0,
         }
     }
 
+    /// 设置 PSK 代数
+    pub fn with_psk_epoch(mut self, psk_epoch: u32) -> Self {
+        self.psk_epoch = psk_epoch;
+        self
+    }
+
     /// 检查 Token 是否过期
     pub fn is_expired(&self) -> bool {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -56,6 +70,12 @@ impl IdentityClaims {
             .as_secs();
         now > self.expr_time
     }
+
+    /// 从 actor_id 中解析序列号（`@` 之前的十六进制部分）
+    pub fn serial_number(&self) -> Option<u64> {
+        let (serial_hex, _) = self.actor_id.split_once('@')?;
+        u64::from_str_radix(serial_hex, 16).ok()
+    }
 }
 
 #[cfg(test)]
@@ -97,4 +117,32 @@ mod tests {
         let expired_claims = IdentityClaims::new(1, "123@1/acme:test:1".to_string(), now - 1, psk);
         assert!(expired_claims.is_expired());
     }
+
+    #[test]
+    fn test_serial_number() {
+        let claims = IdentityClaims::new(
+            12345,
+            "fed02d3f000000@12345/apple:user:1".to_string(),
+            0,
+            vec![],
+        );
+        assert_eq!(claims.serial_number(), Some(0xfed02d3f000000));
+
+        let invalid = IdentityClaims::new(1, "not-an-actor-id".to_string(), 0, vec![]);
+        assert_eq!(invalid.serial_number(), None);
+    }
+
+    #[test]
+    fn test_psk_epoch_defaults_to_zero() {
+        let legacy: IdentityClaims = serde_json::from_str(
+            r#"{"realm_id":1,"actor_id":"1@1/acme:test","expr_time":0,"psk":[1,2]}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.psk_epoch, 0);
+
+        let rotated = legacy.with_psk_epoch(3);
+        let json = serde_json::to_string(&rotated).unwrap();
+        let decoded: IdentityClaims = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.psk_epoch, 3);
+    }
 }
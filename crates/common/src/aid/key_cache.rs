@@ -205,6 +205,17 @@ impl KeyCache {
         Ok(())
     }
 
+    /// 移除指定密钥的缓存（密钥被 KS 轮替后，下次使用时重新获取有效期）
+    pub async fn remove_key(&self, key_id: u32) -> Result<bool, AidError> {
+        let result = sqlx::query("DELETE FROM key_cache WHERE key_id = ?1")
+            .bind(key_id as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| AidError::DecryptionFailed(format!("Failed to remove cached key: {e}")))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// 清理过期的缓存密钥
     pub async fn cleanup_expired_keys(&self) -> Result<u32, AidError> {
         let now = SystemTime::now()
@@ -380,4 +391,19 @@ mod tests {
         assert_eq!(cleaned, 1);
         assert_eq!(cache.get_cached_key_count().await.unwrap(), 0);
     }
+
+    #[tokio::test]
+    async fn test_remove_key() {
+        let temp_dir = tempdir().unwrap();
+        let cache_path = temp_dir.path().join("test_cache.db");
+        let cache = KeyCache::new(&cache_path).await.unwrap();
+
+        let (secret_key, _) = ecies::utils::generate_keypair();
+        cache.cache_key(1, &secret_key, 0, 0).await.unwrap();
+
+        assert!(cache.remove_key(1).await.unwrap());
+        assert!(cache.get_cached_key(1).await.unwrap().is_none());
+        // 重复移除不报错
+        assert!(!cache.remove_key(1).await.unwrap());
+    }
 }
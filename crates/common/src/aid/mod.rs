@@ -5,7 +5,13 @@
 pub mod credential;
 pub mod identity_claims;
 pub mod key_cache;
+pub mod revocation;
+pub mod signed_token;
+pub mod turn_credential;
 
 pub use credential::{AIdCredential, AIdCredentialValidator, AidError};
 pub use identity_claims::IdentityClaims;
 pub use key_cache::KeyCache;
+pub use revocation::{PskRotation, RevocationFilter, RevocationListResponse};
+pub use signed_token::{CredentialMetadata, KeyUsage, SignedToken};
+pub use turn_credential::{ScopedTurnUser, TurnCredential};
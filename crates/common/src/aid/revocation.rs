@@ -0,0 +1,227 @@
+//! 凭证吊销过滤器
+//!
+//! AIS 将未过期的已吊销序列号打包为布隆过滤器下发，Signaling / TURN 的验证器据此在
+//! Token 过期前拒绝被吊销的 Actor。过滤器只会误判"已吊销"、不会漏判，误判率由 AIS
+//! 配置控制；被误判的 Actor 重新注册即可获得新的序列号。
+//!
+//! 哈希采用固定的 splitmix64 双重哈希，保证不同进程、不同版本间结果一致。
+//!
+//! 吊销列表同时携带 PSK 轮替记录（[`PskRotation`]）：Actor 轮替 PSK 后，旧代数的凭证
+//! 只在重叠窗口内继续有效，之后按被替换处理。
+
+use base64::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// 过滤器的最小位数
+const MIN_BITS: u64 = 64;
+
+/// 哈希函数个数上限
+const MAX_HASHES: u32 = 30;
+
+/// 已吊销序列号的布隆过滤器
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationFilter {
+    /// 位数
+    num_bits: u64,
+    /// 哈希函数个数
+    num_hashes: u32,
+    /// 位数组（Base64 编码传输）
+    #[serde(
+        serialize_with = "serialize_bits",
+        deserialize_with = "deserialize_bits"
+    )]
+    bits: Vec<u8>,
+}
+
+impl Default for RevocationFilter {
+    fn default() -> Self {
+        Self::with_capacity(0, 1.0)
+    }
+}
+
+impl RevocationFilter {
+    /// 按预期条目数与误判率创建空过滤器
+    pub fn with_capacity(expected_items: usize, false_positive_rate: f64) -> Self {
+        let rate = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+        let items = expected_items.max(1) as f64;
+
+        let num_bits = ((-items * rate.ln() / (ln2 * ln2)).ceil() as u64)
+            .max(MIN_BITS)
+            .next_multiple_of(8);
+        let num_hashes = ((num_bits as f64 / items * ln2).round() as u32).clamp(1, MAX_HASHES);
+
+        Self {
+            num_bits,
+            num_hashes,
+            bits: vec![0; (num_bits / 8) as usize],
+        }
+    }
+
+    /// 由序列号列表构建过滤器
+    pub fn from_serials(serials: &[u64], false_positive_rate: f64) -> Self {
+        let mut filter = Self::with_capacity(serials.len(), false_positive_rate);
+        for &serial_number in serials {
+            filter.insert(serial_number);
+        }
+        filter
+    }
+
+    /// 加入序列号
+    pub fn insert(&mut self, serial_number: u64) {
+        for index in self.indexes(serial_number) {
+            self.bits[(index / 8) as usize] |= 1 << (index % 8);
+        }
+    }
+
+    /// 序列号是否（可能）已吊销
+    pub fn contains(&self, serial_number: u64) -> bool {
+        // 反序列化得到的位数组长度与 num_bits 不一致时视为空过滤器
+        if self.num_bits == 0 || self.bits.len() as u64 * 8 != self.num_bits {
+            return false;
+        }
+        self.indexes(serial_number)
+            .all(|index| self.bits[(index / 8) as usize] & (1 << (index % 8)) != 0)
+    }
+
+    /// 位数组字节数
+    pub fn size_bytes(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn indexes(&self, serial_number: u64) -> impl Iterator<Item = u64> + '_ {
+        let h1 = splitmix64(serial_number);
+        let h2 = splitmix64(h1) | 1;
+        (0..self.num_hashes as u64)
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn serialize_bits<S: Serializer>(bits: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&BASE64_STANDARD.encode(bits))
+}
+
+fn deserialize_bits<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    BASE64_STANDARD
+        .decode(encoded)
+        .map_err(serde::de::Error::custom)
+}
+
+/// 单个 Actor 最近一次 PSK 轮替
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PskRotation {
+    /// 序列号
+    pub serial_number: u64,
+    /// 轮替后的 PSK 代数
+    pub psk_epoch: u32,
+    /// 上一代凭证的有效截止时间（Unix timestamp，重叠窗口结束）
+    pub previous_valid_until: u64,
+}
+
+impl PskRotation {
+    /// 给定代数的凭证在 `now` 时刻是否仍可使用
+    ///
+    /// 当前及更新的代数始终接受；上一代只在重叠窗口内接受；更早的代数一律拒绝
+    pub fn accepts(&self, psk_epoch: u32, now: u64) -> bool {
+        psk_epoch >= self.psk_epoch
+            || (psk_epoch + 1 == self.psk_epoch && now <= self.previous_valid_until)
+    }
+}
+
+/// AIS `/ais/revocations` 的响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevocationListResponse {
+    /// 未过期的吊销条目数
+    pub count: usize,
+    /// 生成时间（Unix timestamp）
+    pub generated_at: u64,
+    /// 吊销过滤器
+    pub filter: RevocationFilter,
+    /// 旧凭证尚未过期的 PSK 轮替记录
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub psk_rotations: Vec<PskRotation>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_false_negatives() {
+        let serials: Vec<u64> = (0..1000)
+            .map(|i| 0xfed0_2d3f_0000_0000 + i * 7919)
+            .collect();
+        let filter = RevocationFilter::from_serials(&serials, 1e-6);
+        assert!(serials.iter().all(|&serial| filter.contains(serial)));
+    }
+
+    #[test]
+    fn test_false_positive_rate() {
+        let serials: Vec<u64> = (0..1000).collect();
+        let filter = RevocationFilter::from_serials(&serials, 1e-3);
+        let false_positives = (1_000_000..1_100_000u64)
+            .filter(|&serial| filter.contains(serial))
+            .count();
+        // 期望约 100 个误判，留足余量
+        assert!(false_positives < 300, "false positives: {false_positives}");
+    }
+
+    #[test]
+    fn test_empty_filter() {
+        let filter = RevocationFilter::default();
+        assert!(!filter.contains(0));
+        assert!(!filter.contains(42));
+        assert_eq!(filter.size_bytes(), 8);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let filter = RevocationFilter::from_serials(&[1, 2, 3], 1e-6);
+        let json = serde_json::to_string(&filter).unwrap();
+        let decoded: RevocationFilter = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, filter);
+        assert!(decoded.contains(2));
+        assert!(!decoded.contains(4));
+    }
+
+    #[test]
+    fn test_psk_rotation_overlap_window() {
+        let rotation = PskRotation {
+            serial_number: 1,
+            psk_epoch: 2,
+            previous_valid_until: 1000,
+        };
+        assert!(rotation.accepts(2, 5000));
+        assert!(rotation.accepts(3, 5000));
+        // 上一代只在重叠窗口内有效
+        assert!(rotation.accepts(1, 1000));
+        assert!(!rotation.accepts(1, 1001));
+        assert!(!rotation.accepts(0, 500));
+
+        // 旧版 AIS 的响应不含轮替记录
+        let list: RevocationListResponse = serde_json::from_str(
+            r#"{"count":0,"generated_at":0,"filter":{"num_bits":64,"num_hashes":1,"bits":"AAAAAAAAAAA="}}"#,
+        )
+        .unwrap();
+        assert!(list.psk_rotations.is_empty());
+    }
+
+    #[test]
+    fn test_inconsistent_filter_matches_nothing() {
+        for json in [
+            r#"{"num_bits":1024,"num_hashes":3,"bits":"AAAA"}"#,
+            r#"{"num_bits":0,"num_hashes":3,"bits":""}"#,
+        ] {
+            let filter: RevocationFilter = serde_json::from_str(json).unwrap();
+            assert!(!filter.contains(1));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! 签名 Token 封装
+//!
+//! AIdCredential 的 `encrypted_token` 解密后为 [`SignedToken`]（JSON）：
+//! - `claims`：JSON 编码的 [`IdentityClaims`]
+//! - `metadata`：[`CredentialMetadata`]，记录加密与签名分别使用的密钥
+//! - `signature`：签名密钥对 claims 与 metadata 的 ECDSA (secp256k1) 签名
+//!
+//! 加密密钥（`token_key_id`）与签名密钥相互独立，各自按独立周期轮替。
+//! ECIES 只需公钥即可加密，签名保证 Token 确实由持有签名私钥的 AIS 签发。
+//!
+//! 升级前签发的 Token 解密后直接是 [`IdentityClaims`]（无签名），
+//! 由验证器按旧格式兼容处理，直到其自然过期。
+
+use crate::aid::credential::error::AidError;
+use crate::aid::identity_claims::IdentityClaims;
+use base64::prelude::*;
+use ecies::{PublicKey, SecretKey};
+use libsecp256k1::{Message, Signature};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 签名摘要的域分隔前缀
+const SIGNING_DOMAIN: &[u8] = b"actrix-aid-token-v1\n";
+
+/// 密钥用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyUsage {
+    /// 加密 Token（ECIES）
+    Encryption,
+    /// 签名 Token（ECDSA）
+    Signing,
+}
+
+impl KeyUsage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Encryption => "encryption",
+            Self::Signing => "signing",
+        }
+    }
+}
+
+/// Credential 元数据：记录各用途使用的密钥
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialMetadata {
+    /// 加密密钥 ID（与 `AIdCredential.token_key_id` 一致）
+    pub encryption_key_id: u32,
+    /// 签名密钥 ID
+    pub signing_key_id: u32,
+}
+
+/// 带签名的 Token 明文
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedToken {
+    /// JSON 编码的 IdentityClaims（签名覆盖原始字节，避免重新序列化导致差异）
+    pub claims: String,
+    pub metadata: CredentialMetadata,
+    /// Base64 编码的 64 字节压缩签名
+    pub signature: String,
+}
+
+impl SignedToken {
+    /// 使用签名密钥签名 claims
+    pub fn sign(
+        claims: &IdentityClaims,
+        metadata: CredentialMetadata,
+        signing_key: &SecretKey,
+    ) -> Result<Self, AidError> {
+        let claims = serde_json::to_string(claims)?;
+        let message = Self::signing_message(&claims, &metadata);
+        let (signature, _) = libsecp256k1::sign(&message, signing_key);
+
+        Ok(Self {
+            claims,
+            metadata,
+            signature: BASE64_STANDARD.encode(signature.serialize()),
+        })
+    }
+
+    /// 使用签名公钥验证签名，成功时返回 claims
+    pub fn verify(&self, verifying_key: &PublicKey) -> Result<IdentityClaims, AidError> {
+        let signature_bytes = BASE64_STANDARD.decode(&self.signature)?;
+        let signature = Signature::parse_standard_slice(&signature_bytes)
+            .map_err(|e| AidError::SignatureInvalid(format!("Malformed signature: {e:?}")))?;
+
+        let message = Self::signing_message(&self.claims, &self.metadata);
+        if !libsecp256k1::verify(&message, &signature, verifying_key) {
+            return Err(AidError::SignatureInvalid(format!(
+                "Signature does not match signing key {}",
+                self.metadata.signing_key_id
+            )));
+        }
+
+        Ok(serde_json::from_str(&self.claims)?)
+    }
+
+    /// 计算签名摘要：SHA-256(domain || encryption_key_id || signing_key_id || claims)
+    fn signing_message(claims: &str, metadata: &CredentialMetadata) -> Message {
+        let mut hasher = Sha256::new();
+        hasher.update(SIGNING_DOMAIN);
+        hasher.update(metadata.encryption_key_id.to_be_bytes());
+        hasher.update(metadata.signing_key_id.to_be_bytes());
+        hasher.update(claims.as_bytes());
+        Message::parse(&hasher.finalize().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims() -> IdentityClaims {
+        IdentityClaims::new(1, "1a2b@1/acme:echo".to_string(), u64::MAX, vec![7u8; 32])
+    }
+
+    fn metadata() -> CredentialMetadata {
+        CredentialMetadata {
+            encryption_key_id: 10,
+            signing_key_id: 20,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_roundtrip() {
+        let (signing_key, verifying_key) = ecies::utils::generate_keypair();
+        let token = SignedToken::sign(&claims(), metadata(), &signing_key).unwrap();
+
+        let verified = token.verify(&verifying_key).unwrap();
+        assert_eq!(verified.actor_id, "1a2b@1/acme:echo");
+        assert_eq!(token.metadata, metadata());
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering_and_wrong_key() {
+        let (signing_key, verifying_key) = ecies::utils::generate_keypair();
+        let (_, other_key) = ecies::utils::generate_keypair();
+        let token = SignedToken::sign(&claims(), metadata(), &signing_key).unwrap();
+
+        assert!(matches!(
+            token.verify(&other_key),
+            Err(AidError::SignatureInvalid(_))
+        ));
+
+        let mut tampered = token.clone();
+        tampered.claims = tampered.claims.replace("\"realm_id\":1", "\"realm_id\":2");
+        assert!(tampered.verify(&verifying_key).is_err());
+
+        let mut swapped = token;
+        swapped.metadata.encryption_key_id = 11;
+        assert!(swapped.verify(&verifying_key).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! 由 AIdCredential 换取的限定范围 TURN 凭证
+//!
+//! Actor 持有效的 AIdCredential 调用 `POST /ais/turn-credential` 获得 TURN 用户名与密码，
+//! 无需另行分发 TURN 凭证：
+//!
+//! - 用户名：`{expires_at}:{realm_id}:{serial_number}`，绑定 Realm 与 Actor，
+//!   过期时间与 AId Token 一致
+//! - 密码：`Base64(HMAC-SHA256(actrix_shared_key, "actrix-turn-credential\n" + 用户名))`
+//!
+//! TURN 服务用同一个 `actrix_shared_key` 重新计算密码并校验过期时间与 Realm，
+//! 因此签发凭证的 AIS 与 TURN 节点必须共享该密钥。
+
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 密码 MAC 的域分隔前缀
+const PASSWORD_DOMAIN: &[u8] = b"actrix-turn-credential\n";
+
+/// 签发给 Actor 的 TURN 凭证
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnCredential {
+    pub username: String,
+    pub password: String,
+    /// 过期时间（Unix 秒），与换取时使用的 AId Token 一致
+    pub expires_at: u64,
+}
+
+/// 限定范围 TURN 用户名携带的信息
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopedTurnUser {
+    pub expires_at: u64,
+    pub realm_id: u32,
+    pub serial_number: u64,
+}
+
+impl ScopedTurnUser {
+    /// 解析 `{expires_at}:{realm_id}:{serial_number}` 格式的用户名，其他格式返回 None
+    pub fn parse(username: &str) -> Option<Self> {
+        let mut parts = username.split(':');
+        let user = Self {
+            expires_at: parts.next()?.parse().ok()?,
+            realm_id: parts.next()?.parse().ok()?,
+            serial_number: parts.next()?.parse().ok()?,
+        };
+        parts.next().is_none().then_some(user)
+    }
+
+    pub fn username(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.expires_at, self.realm_id, self.serial_number
+        )
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// 签发该用户的 TURN 凭证
+    pub fn issue(&self, shared_key: &str) -> TurnCredential {
+        let username = self.username();
+        TurnCredential {
+            password: turn_password(shared_key, &username),
+            username,
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+/// 计算用户名对应的 TURN 密码
+pub fn turn_password(shared_key: &str, username: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(shared_key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(PASSWORD_DOMAIN);
+    mac.update(username.as_bytes());
+    BASE64_STANDARD.encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_username_roundtrip() {
+        let user = ScopedTurnUser {
+            expires_at: 1_700_000_000,
+            realm_id: 1001,
+            serial_number: 42,
+        };
+        let credential = user.issue("shared-key");
+        assert_eq!(credential.username, "1700000000:1001:42");
+        assert_eq!(credential.expires_at, 1_700_000_000);
+        assert_eq!(ScopedTurnUser::parse(&credential.username), Some(user));
+
+        assert_eq!(
+            credential.password,
+            turn_password("shared-key", &credential.username)
+        );
+        assert_ne!(
+            credential.password,
+            turn_password("other-key", &credential.username)
+        );
+        assert_ne!(
+            credential.password,
+            turn_password("shared-key", "1700000000:1002:42")
+        );
+
+        assert!(!user.is_expired(1_699_999_999));
+        assert!(user.is_expired(1_700_000_000));
+    }
+
+    #[test]
+    fn test_parse_rejects_other_formats() {
+        assert_eq!(ScopedTurnUser::parse("invalid-claims-format"), None);
+        assert_eq!(ScopedTurnUser::parse("1:2"), None);
+        assert_eq!(ScopedTurnUser::parse("1:2:3:4"), None);
+        assert_eq!(ScopedTurnUser::parse("1:x:3"), None);
+    }
+}
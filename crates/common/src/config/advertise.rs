@@ -0,0 +1,123 @@
+//! 对外宣告端点配置
+//!
+//! 节点位于云负载均衡、端口转发 NAT 或 NAT64 网关之后时，客户端可达的地址与本机绑定地址不同。
+//! 这里的覆盖项只改变发布出去的端点——服务发现文档，以及上报给 Supervisor 的 Signaling、
+//! AIS、KS 与 STUN/TURN 服务信息——不改变实际监听的地址。未配置的项沿用 `bind` 中的值。
+//!
+//! TURN 端点的主机名与端口已分别由 `turn.advertised_hostname`、`turn.advertised_port` 覆盖。
+
+use serde::{Deserialize, Serialize};
+use std::net::Ipv6Addr;
+use url::Url;
+
+/// 对外宣告端点配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdvertiseConfig {
+    /// HTTP(S) 服务对外基础 URL（如 "https://edge.example.com"），可带路径前缀
+    ///
+    /// 未配置时由主 HTTP 监听器（dev 环境优先 `bind.http`，其余环境为 `bind.https`）的
+    /// 域名与端口推导
+    #[serde(default)]
+    pub public_url: Option<String>,
+
+    /// Signaling WebSocket 地址（如 "wss://ws.example.com/signaling/ws"）
+    ///
+    /// 未配置时为 `public_url` 对应的 ws/wss 地址加 `{ws_path}/ws`（`services.signaling.server.ws_path`）
+    #[serde(default)]
+    pub signaling_ws_url: Option<String>,
+
+    /// STUN 对外主机名或 IP 地址，未配置时为 `bind.ice.domain_name`
+    #[serde(default)]
+    pub stun_host: Option<String>,
+
+    /// STUN 对外端口，未配置时为 `bind.ice.port`
+    #[serde(default)]
+    pub stun_port: Option<u16>,
+}
+
+impl AdvertiseConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref url) = self.public_url {
+            check_url("public_url", url, &["http", "https"])?;
+        }
+        if let Some(ref url) = self.signaling_ws_url {
+            check_url("signaling_ws_url", url, &["ws", "wss"])?;
+        }
+        if let Some(ref host) = self.stun_host {
+            let bare = host
+                .strip_prefix('[')
+                .and_then(|host| host.strip_suffix(']'))
+                .unwrap_or(host);
+            let valid = !bare.is_empty()
+                && !bare.chars().any(|c| c.is_whitespace() || c == '/')
+                && (!bare.contains(':') || bare.parse::<Ipv6Addr>().is_ok());
+            if !valid {
+                return Err(format!(
+                    "stun_host '{host}' must be a hostname or IP address without a port"
+                ));
+            }
+        }
+        if self.stun_port == Some(0) {
+            return Err("stun_port must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 校验对外 URL：协议在允许列表内、包含主机，且不带查询参数
+fn check_url(field: &str, value: &str, schemes: &[&str]) -> Result<(), String> {
+    let valid = Url::parse(value).is_ok_and(|url| {
+        schemes.contains(&url.scheme())
+            && url.host_str().is_some()
+            && url.query().is_none()
+            && url.fragment().is_none()
+    });
+    if !valid {
+        return Err(format!(
+            "{field} '{value}' must be a {} URL with a host and no query",
+            schemes.join("/")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(AdvertiseConfig::default().validate().is_ok());
+
+        let config = AdvertiseConfig {
+            public_url: Some("https://edge.example.com/actrix".to_string()),
+            signaling_ws_url: Some("wss://ws.example.com:443/signaling/ws".to_string()),
+            stun_host: Some("2001:db8::1".to_string()),
+            stun_port: Some(3478),
+        };
+        assert!(config.validate().is_ok());
+
+        let invalid = [
+            AdvertiseConfig {
+                public_url: Some("wss://edge.example.com".to_string()),
+                ..Default::default()
+            },
+            AdvertiseConfig {
+                signaling_ws_url: Some("https://edge.example.com/signaling/ws".to_string()),
+                ..Default::default()
+            },
+            AdvertiseConfig {
+                stun_host: Some("stun.example.com:3478".to_string()),
+                ..Default::default()
+            },
+            AdvertiseConfig {
+                stun_port: Some(0),
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert!(config.validate().is_err(), "{config:?}");
+        }
+    }
+}
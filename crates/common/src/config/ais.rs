@@ -16,6 +16,22 @@ pub struct AisConfig {
     /// AIS 的依赖服务配置
     #[serde(default)]
     pub dependencies: AisDependencies,
+
+    /// 凭证吊销配置
+    #[serde(default)]
+    pub revocation: AisRevocationConfig,
+
+    /// 序列号生成器配置
+    #[serde(default)]
+    pub serial_number: AisSerialNumberConfig,
+
+    /// 注册请求的租户级限流配置
+    #[serde(default)]
+    pub rate_limit: AisRateLimitConfig,
+
+    /// PSK 轮替配置
+    #[serde(default)]
+    pub psk_rotation: AisPskRotationConfig,
 }
 
 /// AIS 服务器配置
@@ -32,6 +48,20 @@ pub struct AisServerConfig {
     /// 生成的 AIdCredential 的过期时间
     #[serde(default = "default_token_ttl_secs")]
     pub token_ttl_secs: u64,
+
+    /// 是否启用定期密钥轮替
+    ///
+    /// 启用后加密密钥与签名密钥分别按各自的间隔轮替；未启用时仅在密钥即将过期时刷新
+    #[serde(default)]
+    pub enable_periodic_rotation: bool,
+
+    /// 加密密钥轮替间隔（秒）
+    #[serde(default = "default_encryption_key_rotation_interval_secs")]
+    pub encryption_key_rotation_interval_secs: u64,
+
+    /// 签名密钥轮替间隔（秒）
+    #[serde(default = "default_signing_key_rotation_interval_secs")]
+    pub signing_key_rotation_interval_secs: u64,
 }
 
 /// AIS 依赖的外部服务
@@ -46,11 +76,261 @@ pub struct AisDependencies {
     pub ks: Option<KsClientConfig>,
 }
 
+/// 凭证吊销配置
+///
+/// 吊销记录写入 `{sqlite_path}/ais_revocations.db`；配置 Redis 后同时写入 Redis，
+/// 多个 AIS 实例下发的过滤器包含彼此的吊销记录。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AisRevocationConfig {
+    /// 下发给 Signaling 的布隆过滤器误判率
+    #[serde(default = "default_false_positive_rate")]
+    pub false_positive_rate: f64,
+
+    /// Redis 配置（需要编译时启用 `ais-redis` feature）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis: Option<RedisRevocationConfig>,
+}
+
+/// Redis 吊销记录配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RedisRevocationConfig {
+    /// 连接地址，如 `redis://127.0.0.1:6379/0`
+    pub url: String,
+
+    /// 键前缀，吊销记录保存在有序集合 `{key_prefix}revoked` 中，
+    /// PSK 轮替记录保存在哈希 `{key_prefix}psk_rotations` 中
+    #[serde(default = "default_redis_key_prefix")]
+    pub key_prefix: String,
+}
+
+impl Default for AisRevocationConfig {
+    fn default() -> Self {
+        Self {
+            false_positive_rate: default_false_positive_rate(),
+            redis: None,
+        }
+    }
+}
+
+impl AisRevocationConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.false_positive_rate > 0.0 && self.false_positive_rate < 1.0) {
+            return Err(format!(
+                "false_positive_rate must be between 0 and 1 (exclusive), got {}",
+                self.false_positive_rate
+            ));
+        }
+        if let Some(ref redis) = self.redis
+            && redis.url.trim().is_empty()
+        {
+            return Err("redis.url cannot be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 序列号（Snowflake）生成器配置
+///
+/// 多个 AIS 实例（如主主部署）同时签发时，必须为每个实例配置不同的 `node_id`，
+/// 否则依赖 hostname + PID 哈希，存在冲突的可能。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AisSerialNumberConfig {
+    /// 节点标识（0-31），写入序列号的 worker_id 位
+    ///
+    /// 未配置时基于 hostname + PID 哈希生成
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<u8>,
+
+    /// 检测到时钟回拨时的处理策略
+    #[serde(default)]
+    pub clock_regression: ClockRegressionPolicy,
+
+    /// `wait` 策略下等待时钟追上的最长时间（毫秒），回拨超过该值时拒绝签发
+    #[serde(default = "default_max_clock_wait_ms")]
+    pub max_clock_wait_ms: u64,
+}
+
+/// 时钟回拨处理策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockRegressionPolicy {
+    /// 等待时钟追上上次签发的时间戳后继续签发
+    #[default]
+    Wait,
+    /// 立即拒绝签发
+    Reject,
+}
+
+/// 序列号 worker_id 的最大值（5 bits）
+pub const MAX_SERIAL_NODE_ID: u8 = 31;
+
+impl Default for AisSerialNumberConfig {
+    fn default() -> Self {
+        Self {
+            node_id: None,
+            clock_regression: ClockRegressionPolicy::default(),
+            max_clock_wait_ms: default_max_clock_wait_ms(),
+        }
+    }
+}
+
+impl AisSerialNumberConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(node_id) = self.node_id
+            && node_id > MAX_SERIAL_NODE_ID
+        {
+            return Err(format!(
+                "node_id must be between 0 and {MAX_SERIAL_NODE_ID}, got {node_id}"
+            ));
+        }
+        if self.clock_regression == ClockRegressionPolicy::Wait && self.max_clock_wait_ms == 0 {
+            return Err(
+                "max_clock_wait_ms must be greater than 0 when clock_regression is 'wait'"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 默认最长等待时钟追上：5 秒
+fn default_max_clock_wait_ms() -> u64 {
+    5000
+}
+
+/// PSK 轮替配置
+///
+/// 客户端通过 `/ais/rotate-psk` 证明持有旧 PSK 后获得新 PSK 与新凭证；
+/// 旧凭证在重叠窗口内继续有效，便于客户端切换期间的在途请求。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AisPskRotationConfig {
+    /// 轮替后旧凭证继续有效的时长（秒）
+    #[serde(default = "default_psk_overlap_secs")]
+    pub overlap_secs: u64,
+
+    /// 轮替挑战的有效期（秒）
+    #[serde(default = "default_psk_challenge_ttl_secs")]
+    pub challenge_ttl_secs: u64,
+}
+
+impl Default for AisPskRotationConfig {
+    fn default() -> Self {
+        Self {
+            overlap_secs: default_psk_overlap_secs(),
+            challenge_ttl_secs: default_psk_challenge_ttl_secs(),
+        }
+    }
+}
+
+impl AisPskRotationConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.challenge_ttl_secs == 0 {
+            return Err("challenge_ttl_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 默认重叠窗口：5 分钟
+fn default_psk_overlap_secs() -> u64 {
+    300
+}
+
+/// 默认挑战有效期：60 秒
+fn default_psk_challenge_ttl_secs() -> u64 {
+    60
+}
+
+/// 注册请求的租户级限流配置
+///
+/// 在 IP 限流之外按 RegisterRequest 中的 realm_id、(realm_id, manufacturer) 分层限流，
+/// 避免单个租户的批量开通占满 AIS。两层均通过才签发。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AisRateLimitConfig {
+    /// 每个 Realm 的注册限额
+    #[serde(default = "default_realm_register_limit")]
+    pub realm: RegisterRateLimit,
+
+    /// 每个 Realm 内每个厂商的注册限额
+    #[serde(default = "default_manufacturer_register_limit")]
+    pub manufacturer: RegisterRateLimit,
+}
+
+/// 单层注册限额（令牌桶）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RegisterRateLimit {
+    /// 是否启用该层限流
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 稳态速率：每分钟允许的注册数
+    pub per_minute: u32,
+
+    /// 突发允许的注册数
+    pub burst_size: u32,
+}
+
+impl Default for AisRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            realm: default_realm_register_limit(),
+            manufacturer: default_manufacturer_register_limit(),
+        }
+    }
+}
+
+impl AisRateLimitConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, limit) in [("realm", &self.realm), ("manufacturer", &self.manufacturer)] {
+            if limit.enabled && (limit.per_minute == 0 || limit.burst_size == 0) {
+                return Err(format!(
+                    "{name}.per_minute and {name}.burst_size must be greater than 0"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 默认 Realm 限额：600 次/分钟，突发 200（默认未启用）
+fn default_realm_register_limit() -> RegisterRateLimit {
+    RegisterRateLimit {
+        enabled: false,
+        per_minute: 600,
+        burst_size: 200,
+    }
+}
+
+/// 默认厂商限额：300 次/分钟，突发 100（默认未启用）
+fn default_manufacturer_register_limit() -> RegisterRateLimit {
+    RegisterRateLimit {
+        enabled: false,
+        per_minute: 300,
+        burst_size: 100,
+    }
+}
+
+/// 默认误判率：百万分之一
+fn default_false_positive_rate() -> f64 {
+    1e-6
+}
+
+fn default_redis_key_prefix() -> String {
+    "actrix:ais:".to_string()
+}
+
 impl Default for AisServerConfig {
     fn default() -> Self {
         Self {
             signaling_heartbeat_interval_secs: default_signaling_heartbeat_interval_secs(),
             token_ttl_secs: default_token_ttl_secs(),
+            enable_periodic_rotation: false,
+            encryption_key_rotation_interval_secs: default_encryption_key_rotation_interval_secs(),
+            signing_key_rotation_interval_secs: default_signing_key_rotation_interval_secs(),
         }
     }
 }
@@ -65,6 +345,16 @@ fn default_token_ttl_secs() -> u64 {
     3600
 }
 
+/// 默认加密密钥轮替间隔：24 小时
+fn default_encryption_key_rotation_interval_secs() -> u64 {
+    86400
+}
+
+/// 默认签名密钥轮替间隔：7 天
+fn default_signing_key_rotation_interval_secs() -> u64 {
+    7 * 86400
+}
+
 impl AisConfig {
     /// 获取 KS 客户端配置
     ///
@@ -83,20 +373,8 @@ impl AisConfig {
 
         // 回退：检查是否启用了本地 KS 服务
         if global_config.is_ks_enabled() && global_config.services.ks.is_some() {
-            // 自动生成指向本地 KS 的客户端配置
-            // gRPC 使用独立端口 50052（HTTP router 使用 8443/8080）
-            let grpc_port = 50052;
-            let grpc_protocol = "http"; // 默认不启用 TLS，可通过配置开启
-
-            return Some(KsClientConfig {
-                endpoint: format!("{grpc_protocol}://127.0.0.1:{grpc_port}"),
-                timeout_seconds: 30,
-                enable_tls: false,
-                tls_domain: None,
-                ca_cert: None,
-                client_cert: None,
-                client_key: None,
-            });
+            // 自动生成指向本地 KS gRPC 监听地址（services.ks.grpc.bind）的客户端配置
+            return global_config.local_ks_client_config();
         }
 
         None
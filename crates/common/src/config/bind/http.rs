@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 
 /// HTTP 服务绑定配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,6 +15,12 @@ pub struct HttpBindConfig {
     /// 在 NAT 环境中，这通常是路由器的公网 IP。
     pub advertised_ip: String,
 
+    /// 公网 IPv6 地址（可选）
+    ///
+    /// 双栈部署时对经 IPv6 连接的客户端宣告的地址，未配置时始终宣告 `advertised_ip`。
+    #[serde(default)]
+    pub advertised_ipv6: Option<String>,
+
     /// 绑定 IP 地址
     ///
     /// 服务实际绑定的网络接口 IP 地址。
@@ -24,6 +31,21 @@ pub struct HttpBindConfig {
     ///
     /// HTTP 服务监听的端口号。标准 HTTP 端口为 80。
     pub port: u16,
+
+    /// 重定向到 HTTPS
+    ///
+    /// 同时配置了 `bind.https` 且主服务运行在 HTTPS 上（非 dev 环境）时，
+    /// HTTP 监听器不再提供明文 API，而是将所有请求 308 重定向到 HTTPS，
+    /// 并为 HTTPS 响应附加 HSTS 头。
+    #[serde(default)]
+    pub redirect_to_https: bool,
+}
+
+impl HttpBindConfig {
+    /// 监听的 socket 地址
+    pub fn socket_addr(&self) -> Result<SocketAddr, String> {
+        super::bind_socket_addr("bind.http", &self.ip, self.port)
+    }
 }
 
 impl Default for HttpBindConfig {
@@ -31,8 +53,10 @@ impl Default for HttpBindConfig {
         Self {
             domain_name: "localhost".to_string(),
             advertised_ip: "127.0.0.1".to_string(),
+            advertised_ipv6: None,
             ip: "0.0.0.0".to_string(),
             port: 8080,
+            redirect_to_https: false,
         }
     }
 }
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 
 /// HTTPS 服务绑定配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,6 +14,12 @@ pub struct HttpsBindConfig {
     /// 服务对外宣告的 IP 地址，客户端将使用此地址连接。
     pub advertised_ip: String,
 
+    /// 公网 IPv6 地址（可选）
+    ///
+    /// 双栈部署时对经 IPv6 连接的客户端宣告的地址，未配置时始终宣告 `advertised_ip`。
+    #[serde(default)]
+    pub advertised_ipv6: Option<String>,
+
     /// 绑定 IP 地址
     ///
     /// 服务实际绑定的网络接口 IP 地址。
@@ -34,11 +41,19 @@ pub struct HttpsBindConfig {
     pub key: String,
 }
 
+impl HttpsBindConfig {
+    /// 监听的 socket 地址
+    pub fn socket_addr(&self) -> Result<SocketAddr, String> {
+        super::bind_socket_addr("bind.https", &self.ip, self.port)
+    }
+}
+
 impl Default for HttpsBindConfig {
     fn default() -> Self {
         Self {
             domain_name: "localhost".to_string(),
             advertised_ip: "127.0.0.1".to_string(),
+            advertised_ipv6: None,
             ip: "0.0.0.0".to_string(),
             port: 8443,
             cert: "certificates/server.crt".to_string(),
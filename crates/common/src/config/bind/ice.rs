@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 
 /// ICE 服务绑定配置
 ///
@@ -12,13 +13,70 @@ pub struct IceBindConfig {
 
     /// 绑定 IP 地址
     ///
-    /// UDP 服务绑定的网络接口 IP 地址。
+    /// UDP 服务绑定的网络接口 IP 地址。`"::"` 绑定双栈套接字，同时接收 IPv4 与 IPv6 流量。
     pub ip: String,
 
+    /// IPv6 绑定地址（可选）
+    ///
+    /// `ip` 为 IPv4 地址时额外绑定的 IPv6 地址：配置 `turn.advertised_ipv6` 时 TURN 使用
+    /// （未配置时默认 "::"），仅运行 STUN 时配置此项即额外监听 IPv6。
+    /// 该套接字仅接收 IPv6 流量，不与 `ip` 上的 IPv4 绑定冲突。
+    #[serde(default)]
+    pub ipv6: Option<String>,
+
     /// 绑定端口
     ///
     /// STUN/TURN 服务监听的 UDP 端口。标准端口为 3478。
     pub port: u16,
+
+    /// UDP 接收工作线程数
+    ///
+    /// 大于 1 时以 SO_REUSEPORT 在同一地址上打开多个套接字，由内核按四元组分流，
+    /// 每个套接字独立运行接收循环；0 表示按 CPU 核数自动选择。默认 1（单套接字）。
+    /// 仅 Unix 平台支持，其他平台固定为 1。
+    #[serde(default = "default_workers")]
+    pub workers: usize,
+}
+
+/// 工作线程数上限
+pub const MAX_ICE_WORKERS: usize = 256;
+
+fn default_workers() -> usize {
+    1
+}
+
+impl IceBindConfig {
+    /// 主监听地址（`ip` 为 `::` 时为双栈套接字）
+    pub fn socket_addr(&self) -> Result<SocketAddr, String> {
+        super::bind_socket_addr("bind.ice", &self.ip, self.port)
+    }
+
+    /// 额外的仅 IPv6 监听地址，`ip` 已是 IPv6 地址时不需要（返回 None）
+    pub fn ipv6_socket_addr(&self) -> Result<Option<SocketAddr>, String> {
+        if self.socket_addr()?.is_ipv6() {
+            return Ok(None);
+        }
+        let ip = self.ipv6.as_deref().unwrap_or("::");
+        let addr = super::bind_socket_addr("bind.ice.ipv6", ip, self.port)?;
+        if !addr.is_ipv6() {
+            return Err(format!("bind.ice.ipv6 must be an IPv6 address: {ip}"));
+        }
+        Ok(Some(addr))
+    }
+
+    /// 实际使用的接收套接字数量
+    pub fn effective_workers(&self) -> usize {
+        if !cfg!(unix) {
+            return 1;
+        }
+        match self.workers {
+            0 => std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(MAX_ICE_WORKERS),
+            n => n.min(MAX_ICE_WORKERS),
+        }
+    }
 }
 
 impl Default for IceBindConfig {
@@ -26,7 +84,9 @@ impl Default for IceBindConfig {
         Self {
             domain_name: "localhost".to_string(),
             ip: "0.0.0.0".to_string(),
+            ipv6: None,
             port: 3478,
+            workers: default_workers(),
         }
     }
 }
@@ -6,6 +6,7 @@ pub use crate::config::bind::http::HttpBindConfig;
 pub use crate::config::bind::https::HttpsBindConfig;
 pub use crate::config::bind::ice::IceBindConfig;
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 
 /// 网络绑定配置
 ///
@@ -38,3 +39,69 @@ impl Default for BindConfig {
         }
     }
 }
+
+/// 由绑定 IP 与端口构造监听地址，IPv6 地址可带方括号（`"::"` 与 `"[::]"` 等价）
+pub fn bind_socket_addr(section: &str, ip: &str, port: u16) -> Result<SocketAddr, String> {
+    let bare = ip
+        .strip_prefix('[')
+        .and_then(|ip| ip.strip_suffix(']'))
+        .unwrap_or(ip);
+    let ip: IpAddr = bare
+        .parse()
+        .map_err(|_| format!("{section}.ip is not a valid IP address: {ip}"))?;
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// 宣告地址的地址族是否能经由绑定地址到达，不一致时返回说明
+///
+/// 绑定 IPv4 地址（包括 `0.0.0.0`）只接收 IPv4 流量，绑定具体的 IPv6 地址只接收 IPv6 流量，
+/// 绑定 `::` 为双栈，接受两种地址族。任一地址无法解析时不做判断，由格式校验报告。
+pub fn address_family_mismatch(
+    bind_field: &str,
+    bind_ip: &str,
+    advertised_field: &str,
+    advertised_ip: &str,
+) -> Option<String> {
+    let bind = bind_socket_addr(bind_field, bind_ip, 0).ok()?.ip();
+    let advertised: IpAddr = advertised_ip.parse().ok()?;
+    let reachable = match bind {
+        IpAddr::V4(_) => advertised.is_ipv4(),
+        IpAddr::V6(ip) if ip.is_unspecified() => true,
+        IpAddr::V6(_) => advertised.is_ipv6(),
+    };
+    (!reachable).then(|| {
+        let (family, bind_family) = if advertised.is_ipv6() {
+            ("IPv6", "IPv4")
+        } else {
+            ("IPv4", "IPv6")
+        };
+        format!(
+            "{advertised_field} '{advertised_ip}' is {family} but {bind_field} '{bind_ip}' only accepts {bind_family} traffic; bind \"::\" for dual-stack"
+        )
+    })
+}
+
+/// URL 中的主机部分：IPv6 字面量需要加方括号
+pub fn url_host(host: &str) -> String {
+    if host.parse::<Ipv6Addr>().is_ok() {
+        format!("[{host}]")
+    } else {
+        host.to_string()
+    }
+}
+
+/// 按客户端连接的地址族选择对外宣告的地址
+///
+/// 客户端经 IPv6 连接（IPv4 映射地址除外）且配置了 IPv6 宣告地址时返回 `ipv6`，
+/// 否则返回 `ipv4`；客户端地址未知时按 IPv4 处理。
+pub fn advertised_ip_for<'a>(
+    ipv4: &'a str,
+    ipv6: Option<&'a str>,
+    client_ip: Option<IpAddr>,
+) -> &'a str {
+    let client_is_ipv6 = matches!(client_ip, Some(IpAddr::V6(ip)) if ip.to_ipv4_mapped().is_none());
+    match ipv6 {
+        Some(ipv6) if client_is_ipv6 => ipv6,
+        _ => ipv4,
+    }
+}
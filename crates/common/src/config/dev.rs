@@ -0,0 +1,89 @@
+//! 开发调试配置
+//!
+//! 仅用于本地开发与测试，`env = "prod"` 时启用会被配置校验拒绝。
+
+use serde::{Deserialize, Serialize};
+
+/// 开发调试配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DevConfig {
+    /// 模拟 KS 与 AIS 依赖（需以 `dev-mock` feature 构建，且 `env = "dev"`）
+    ///
+    /// 启用后，未在 `enable` 中开启的 KS 与 AIS 由进程内模拟实现代替：
+    /// 确定性密钥、不校验凭证、即时签发 AId，使仅启用 Signaling 的节点无需任何密钥基础设施即可运行
+    #[serde(default)]
+    pub mock_dependencies: bool,
+
+    /// 局域网直连模式（需 `env = "dev"`）
+    ///
+    /// 启用后 Signaling 中继的 SDP / ICE candidate 只保留 host candidate，
+    /// 并将 mDNS 主机名（`*.local`）替换为发送方在局域网内的 IP，
+    /// 使同一局域网内的 Actor 无需 STUN / TURN 与 mDNS 解析即可直连，便于完全离线开发
+    #[serde(default)]
+    pub lan_discovery: bool,
+
+    /// 弱网模拟
+    #[serde(default)]
+    pub network_emulation: NetworkEmulationConfig,
+}
+
+/// 弱网模拟配置
+///
+/// 为 Signaling 中继与 STUN 响应注入人为延迟、抖动与丢包，
+/// 便于客户端开发者在本地复现弱网行为，无需外部流量整形工具。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkEmulationConfig {
+    /// 是否启用弱网模拟
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 固定附加延迟（毫秒）
+    #[serde(default)]
+    pub latency_ms: u64,
+
+    /// 延迟抖动（毫秒），实际延迟在 `latency_ms ± jitter_ms` 内均匀分布
+    #[serde(default)]
+    pub jitter_ms: u64,
+
+    /// 丢包率，取值 0.0 ~ 1.0
+    #[serde(default)]
+    pub drop_rate: f64,
+
+    /// 是否作用于 Signaling 中继消息
+    #[serde(default = "default_true")]
+    pub signaling_relay: bool,
+
+    /// 是否作用于 STUN 响应
+    #[serde(default = "default_true")]
+    pub stun: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for NetworkEmulationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            latency_ms: 0,
+            jitter_ms: 0,
+            drop_rate: 0.0,
+            signaling_relay: default_true(),
+            stun: default_true(),
+        }
+    }
+}
+
+impl NetworkEmulationConfig {
+    /// 校验弱网模拟配置
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.drop_rate) {
+            return Err(format!(
+                "drop_rate must be between 0.0 and 1.0, got {}",
+                self.drop_rate
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,668 @@
+//! HTTP 服务公共配置
+//!
+//! 作用于 `bind.http` / `bind.https` 上合并后的 HTTP 路由（AIS、KS、Signaling 及管理端点）：
+//! - `route_prefixes`：AIS 与 KS 的挂载前缀（Signaling 沿用 `services.signaling.server.ws_path`），
+//!   便于挂载到改写路径的反向代理之后
+//! - `trusted_proxies`：受信任的反向代理，来自这些地址的请求以 `X-Forwarded-For` / `X-Real-IP`
+//!   作为客户端地址，限流与按地址族选择宣告地址才能在 nginx/HAProxy 之后正常工作
+//! - `cors`：跨域访问策略，可按服务（`/ais`、`/ks`、`/signaling`）分别配置允许的来源，
+//!   便于浏览器中的 Web 应用直接调用这些 HTTP 端点
+//! - `security_headers`：为所有响应附加标准安全头（`X-Content-Type-Options` 等）
+//! - `max_body_bytes` / `request_timeout_secs` / `max_concurrent_requests`：请求体大小、
+//!   处理超时与并发上限，防止慢速攻击与超大 protobuf 请求体耗尽 AIS/KS 资源
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+/// 可单独配置 CORS 来源的服务
+pub const CORS_SERVICES: [&str; 3] = ["ais", "ks", "signaling"];
+
+/// 主 HTTP 路由上由节点自身占用的路径，服务前缀不能与之重叠
+pub const RESERVED_ROUTE_PREFIXES: [&str; 4] = ["/admin", "/healthz", "/readyz", "/.well-known"];
+
+/// HTTP 服务公共配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    /// 请求体大小上限（字节），超出时返回 413
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// 请求处理超时（秒），包括读取请求体，超时返回 408；0 表示不限制
+    ///
+    /// WebSocket 升级完成后的长连接与 `POST /admin/snapshot` 快照导出不受此限制
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// 同时处理的请求数上限，超出的请求直接返回 503；0 表示不限制
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+
+    /// AIS 与 KS 的路由前缀
+    #[serde(default)]
+    pub route_prefixes: RoutePrefixConfig,
+
+    /// 受信任的反向代理地址（IP 或 CIDR，如 "10.0.0.0/8"、"::1"）
+    ///
+    /// 套接字对端属于其中之一时，客户端地址取自 `X-Forwarded-For`（自右向左跳过受信任代理后的
+    /// 第一个地址），没有该头时取 `X-Real-IP`；为空时始终使用套接字对端地址
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+
+    /// CORS 配置
+    #[serde(default)]
+    pub cors: CorsConfig,
+
+    /// 安全响应头配置
+    #[serde(default)]
+    pub security_headers: SecurityHeadersConfig,
+}
+
+/// AIS 与 KS 的路由前缀配置
+///
+/// Signaling 的前缀由 `services.signaling.server.ws_path` 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutePrefixConfig {
+    /// AIS 路由前缀
+    #[serde(default = "default_ais_prefix")]
+    pub ais: String,
+
+    /// KS 路由前缀
+    #[serde(default = "default_ks_prefix")]
+    pub ks: String,
+}
+
+/// 已解析的各 HTTP 路由服务前缀（由 `ActrixConfig::route_prefixes` 生成）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutePrefixes {
+    pub ais: String,
+    pub ks: String,
+    pub signaling: String,
+}
+
+/// CORS 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// 是否处理跨域请求，关闭后不返回任何 CORS 头
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// 默认允许的来源（如 "https://app.example.com"），`"*"` 表示任意来源
+    ///
+    /// 未在 `services` 中单独配置的路径使用此列表
+    #[serde(default = "default_allowed_origins")]
+    pub allowed_origins: Vec<String>,
+
+    /// 按服务覆盖允许的来源，键为 `ais`、`ks` 或 `signaling`，空列表表示禁止跨域访问该服务
+    #[serde(default)]
+    pub services: HashMap<String, Vec<String>>,
+
+    /// 是否允许携带凭据（Cookie、Authorization），不能与 `"*"` 同时使用
+    #[serde(default)]
+    pub allow_credentials: bool,
+
+    /// 预检请求结果缓存时间（秒）
+    #[serde(default = "default_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+/// 安全响应头配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityHeadersConfig {
+    /// 是否附加 `X-Content-Type-Options`、`X-Frame-Options`、`Referrer-Policy`
+    /// 与 `Content-Security-Policy`（处理器已设置的头不会被覆盖）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// `Content-Security-Policy` 取值，空字符串表示不发送
+    #[serde(default = "default_content_security_policy")]
+    pub content_security_policy: String,
+}
+
+fn default_max_body_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_concurrent_requests() -> usize {
+    1024
+}
+
+fn default_ais_prefix() -> String {
+    "/ais".to_string()
+}
+
+fn default_ks_prefix() -> String {
+    "/ks".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_allowed_origins() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_max_age_secs() -> u64 {
+    600
+}
+
+fn default_content_security_policy() -> String {
+    "default-src 'none'; frame-ancestors 'none'".to_string()
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: default_max_body_bytes(),
+            request_timeout_secs: default_request_timeout_secs(),
+            max_concurrent_requests: default_max_concurrent_requests(),
+            route_prefixes: RoutePrefixConfig::default(),
+            trusted_proxies: Vec::new(),
+            cors: CorsConfig::default(),
+            security_headers: SecurityHeadersConfig::default(),
+        }
+    }
+}
+
+impl Default for RoutePrefixConfig {
+    fn default() -> Self {
+        Self {
+            ais: default_ais_prefix(),
+            ks: default_ks_prefix(),
+        }
+    }
+}
+
+impl Default for RoutePrefixes {
+    fn default() -> Self {
+        Self {
+            ais: default_ais_prefix(),
+            ks: default_ks_prefix(),
+            signaling: "/signaling".to_string(),
+        }
+    }
+}
+
+/// 路径是否位于前缀之下（按路径段匹配，`/aisx` 不属于 `/ais`）
+pub fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+impl RoutePrefixes {
+    /// (服务名, 前缀)，服务名与 [`CORS_SERVICES`] 一致
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("ais", self.ais.as_str()),
+            ("ks", self.ks.as_str()),
+            ("signaling", self.signaling.as_str()),
+        ]
+        .into_iter()
+    }
+
+    /// 请求路径所属的服务
+    pub fn service_for_path(&self, path: &str) -> Option<&'static str> {
+        self.iter()
+            .find(|(_, prefix)| path_has_prefix(path, prefix))
+            .map(|(service, _)| service)
+    }
+
+    /// 验证前缀格式，且各前缀之间、与 `reserved` 中的路径之间互不重叠
+    pub fn validate(&self, reserved: &[&str]) -> Result<(), String> {
+        for (service, prefix) in self.iter() {
+            let valid = prefix.len() > 1
+                && prefix.starts_with('/')
+                && !prefix.ends_with('/')
+                && !prefix.contains("//")
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "/-._~".contains(c));
+            if !valid {
+                return Err(format!(
+                    "{service} route prefix '{prefix}' must start with '/', must not end with '/' and may only contain letters, digits and '-._~'"
+                ));
+            }
+        }
+        let prefixes: Vec<_> = self.iter().collect();
+        for (i, (service, prefix)) in prefixes.iter().enumerate() {
+            let others = prefixes[i + 1..]
+                .iter()
+                .map(|(other, path)| (format!("{other} route prefix"), *path))
+                .chain(
+                    reserved
+                        .iter()
+                        .map(|path| ("reserved path".to_string(), *path)),
+                );
+            for (other, path) in others {
+                if path_has_prefix(prefix, path) || path_has_prefix(path, prefix) {
+                    return Err(format!(
+                        "{service} route prefix '{prefix}' collides with {other} '{path}'"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allowed_origins: default_allowed_origins(),
+            services: HashMap::new(),
+            allow_credentials: false,
+            max_age_secs: default_max_age_secs(),
+        }
+    }
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            content_security_policy: default_content_security_policy(),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// 请求路径对应的允许来源：匹配服务路由前缀时使用该服务的配置，否则使用默认列表
+    pub fn origins_for_path(&self, prefixes: &RoutePrefixes, path: &str) -> &[String] {
+        prefixes
+            .service_for_path(path)
+            .and_then(|service| self.services.get(service))
+            .unwrap_or(&self.allowed_origins)
+    }
+
+    /// 来源是否被允许访问该路径
+    pub fn is_origin_allowed(&self, prefixes: &RoutePrefixes, path: &str, origin: &str) -> bool {
+        self.origins_for_path(prefixes, path)
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        for name in self.services.keys() {
+            if !CORS_SERVICES.contains(&name.as_str()) {
+                return Err(format!(
+                    "unknown service '{name}' in cors.services, expected one of: {}",
+                    CORS_SERVICES.join(", ")
+                ));
+            }
+        }
+        for origin in self
+            .allowed_origins
+            .iter()
+            .chain(self.services.values().flatten())
+        {
+            if origin == "*" {
+                if self.allow_credentials {
+                    return Err(
+                        "cors.allow_credentials cannot be combined with '*' origins".to_string()
+                    );
+                }
+                continue;
+            }
+            let valid = url::Url::parse(origin).is_ok_and(|url| {
+                matches!(url.scheme(), "http" | "https")
+                    && url.host_str().is_some()
+                    && url.path() == "/"
+                    && !origin.ends_with('/')
+                    && url.query().is_none()
+            });
+            if !valid {
+                return Err(format!(
+                    "cors origin '{origin}' must be '*' or scheme://host[:port] without a path"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SecurityHeadersConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .content_security_policy
+            .chars()
+            .any(|c| !c.is_ascii() || c.is_ascii_control())
+        {
+            return Err(
+                "security_headers.content_security_policy must be printable ASCII".to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// IP 网段（CIDR），单个地址视为全长前缀
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// 地址是否属于该网段（IPv4 映射的 IPv6 地址按 IPv4 匹配）
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr = IpAddr::from_str(addr)
+            .map_err(|_| format!("invalid IP address or CIDR '{s}'"))?
+            .to_canonical();
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid prefix length in '{s}'"))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// 受信任的反向代理列表（由 `http.trusted_proxies` 解析）
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpCidr>);
+
+impl TrustedProxies {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|cidr| cidr.contains(ip))
+    }
+
+    /// 按代理头解析客户端地址
+    ///
+    /// 对端不受信任时直接返回对端地址；否则自右向左遍历 `X-Forwarded-For`（多个头按出现顺序拼接），
+    /// 返回第一个不受信任的地址，遇到无法解析的条目时停止并返回最后一个已确认的地址。
+    /// 没有 `X-Forwarded-For` 时使用 `X-Real-IP`
+    pub fn client_ip<'a>(
+        &self,
+        peer: IpAddr,
+        forwarded_for: impl IntoIterator<Item = &'a str>,
+        real_ip: Option<&str>,
+    ) -> IpAddr {
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+        let hops: Vec<&str> = forwarded_for
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|hop| !hop.is_empty())
+            .collect();
+        if hops.is_empty() {
+            return real_ip.and_then(parse_forwarded_ip).unwrap_or(peer);
+        }
+        let mut client = peer;
+        for hop in hops.iter().rev() {
+            let Some(ip) = parse_forwarded_ip(hop) else {
+                break;
+            };
+            client = ip;
+            if !self.is_trusted(ip) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// 解析代理头中的地址，允许附带端口（`1.2.3.4:80`、`[::1]:80`）
+fn parse_forwarded_ip(value: &str) -> Option<IpAddr> {
+    let value = value.trim();
+    IpAddr::from_str(value)
+        .or_else(|_| SocketAddr::from_str(value).map(|addr| addr.ip()))
+        .ok()
+        .map(|ip| ip.to_canonical())
+}
+
+impl HttpConfig {
+    /// 解析受信任的反向代理列表
+    pub fn trusted_proxies(&self) -> Result<TrustedProxies, String> {
+        self.trusted_proxies
+            .iter()
+            .map(|proxy| proxy.parse())
+            .collect::<Result<_, _>>()
+            .map(TrustedProxies)
+    }
+
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_body_bytes == 0 {
+            return Err("max_body_bytes must be greater than 0".to_string());
+        }
+        self.trusted_proxies()
+            .map_err(|e| format!("trusted_proxies: {e}"))?;
+        self.cors.validate()?;
+        self.security_headers.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origins_for_path() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://console.example.com".to_string()],
+            services: HashMap::from([
+                (
+                    "ais".to_string(),
+                    vec!["https://app.example.com".to_string()],
+                ),
+                ("ks".to_string(), Vec::new()),
+            ]),
+            ..Default::default()
+        };
+
+        let prefixes = RoutePrefixes::default();
+        let allowed = |path, origin| config.is_origin_allowed(&prefixes, path, origin);
+
+        assert!(allowed("/ais/register", "https://app.example.com"));
+        assert!(!allowed("/ais/register", "https://console.example.com"));
+        // 空列表禁止跨域访问 KS
+        assert!(!allowed("/ks/keys", "https://app.example.com"));
+        // 其他路径使用默认列表，前缀需按路径段匹配
+        assert!(allowed("/signaling/ws", "https://console.example.com"));
+        assert!(allowed("/aisx", "https://console.example.com"));
+        assert!(CorsConfig::default().is_origin_allowed(
+            &prefixes,
+            "/readyz",
+            "http://localhost:3000"
+        ));
+
+        // 自定义前缀按配置匹配
+        let prefixes = RoutePrefixes {
+            ais: "/edge/identity".to_string(),
+            ..Default::default()
+        };
+        assert!(config.is_origin_allowed(
+            &prefixes,
+            "/edge/identity/register",
+            "https://app.example.com"
+        ));
+        assert!(config.is_origin_allowed(
+            &prefixes,
+            "/ais/register",
+            "https://console.example.com"
+        ));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(HttpConfig::default().validate().is_ok());
+        let no_body = HttpConfig {
+            max_body_bytes: 0,
+            ..Default::default()
+        };
+        assert!(no_body.validate().is_err());
+
+        let unknown_service = CorsConfig {
+            services: HashMap::from([("turn".to_string(), Vec::new())]),
+            ..Default::default()
+        };
+        assert!(unknown_service.validate().unwrap_err().contains("turn"));
+
+        let credentials_with_wildcard = CorsConfig {
+            allow_credentials: true,
+            ..Default::default()
+        };
+        assert!(credentials_with_wildcard.validate().is_err());
+
+        for origin in ["app.example.com", "https://app.example.com/", "ftp://x.com"] {
+            let config = CorsConfig {
+                allowed_origins: vec![origin.to_string()],
+                ..Default::default()
+            };
+            assert!(config.validate().is_err(), "{origin}");
+        }
+
+        let config = CorsConfig {
+            allowed_origins: vec!["http://localhost:3000".to_string()],
+            allow_credentials: true,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_route_prefixes_validate() {
+        assert!(
+            RoutePrefixes::default()
+                .validate(&RESERVED_ROUTE_PREFIXES)
+                .is_ok()
+        );
+
+        let nested = RoutePrefixes {
+            ais: "/edge/ais".to_string(),
+            ks: "/edge/ks".to_string(),
+            signaling: "/edge".to_string(),
+        };
+        assert!(nested.validate(&[]).unwrap_err().contains("collides"));
+
+        let reserved = RoutePrefixes {
+            ks: "/admin/ks".to_string(),
+            ..Default::default()
+        };
+        assert!(
+            reserved
+                .validate(&RESERVED_ROUTE_PREFIXES)
+                .unwrap_err()
+                .contains("reserved path '/admin'")
+        );
+
+        for prefix in ["", "/", "ais", "/ais/", "/a//b", "/{id}"] {
+            let config = RoutePrefixes {
+                ais: prefix.to_string(),
+                ..Default::default()
+            };
+            assert!(config.validate(&[]).is_err(), "{prefix}");
+        }
+
+        // 按路径段判断重叠，/ais 与 /aisx 可以共存
+        let siblings = RoutePrefixes {
+            ks: "/aisx".to_string(),
+            ..Default::default()
+        };
+        assert!(siblings.validate(&RESERVED_ROUTE_PREFIXES).is_ok());
+    }
+
+    #[test]
+    fn test_trusted_proxies_client_ip() {
+        let config = HttpConfig {
+            trusted_proxies: vec!["10.0.0.0/8".to_string(), "::1".to_string()],
+            ..Default::default()
+        };
+        let proxies = config.trusted_proxies().unwrap();
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        // 不受信任的对端忽略代理头
+        assert_eq!(
+            proxies.client_ip(ip("203.0.113.9"), ["198.51.100.1"], None),
+            ip("203.0.113.9")
+        );
+        // 自右向左跳过受信任代理，伪造的最左侧条目不被采用
+        assert_eq!(
+            proxies.client_ip(
+                ip("10.0.0.2"),
+                ["1.1.1.1, 198.51.100.7:5060", "10.1.2.3"],
+                None
+            ),
+            ip("198.51.100.7")
+        );
+        // IPv4 映射地址按 IPv4 匹配
+        assert_eq!(
+            proxies.client_ip(ip("::ffff:10.0.0.2"), ["[2001:db8::5]:443"], None),
+            ip("2001:db8::5")
+        );
+        // 无法解析的条目终止遍历
+        assert_eq!(
+            proxies.client_ip(ip("::1"), ["198.51.100.1, garbage, 10.0.0.3"], None),
+            ip("10.0.0.3")
+        );
+        // 没有 X-Forwarded-For 时使用 X-Real-IP
+        assert_eq!(
+            proxies.client_ip(ip("::1"), [], Some("198.51.100.2")),
+            ip("198.51.100.2")
+        );
+        assert_eq!(proxies.client_ip(ip("::1"), [], None), ip("::1"));
+        assert!(TrustedProxies::default().is_empty());
+    }
+
+    #[test]
+    fn test_trusted_proxies_validate() {
+        for proxy in ["10.0.0.0/33", "10.0.0.0/x", "example.com", "::/129"] {
+            let config = HttpConfig {
+                trusted_proxies: vec![proxy.to_string()],
+                ..Default::default()
+            };
+            assert!(
+                config.validate().unwrap_err().contains("trusted_proxies"),
+                "{proxy}"
+            );
+        }
+        let cidr: IpCidr = "0.0.0.0/0".parse().unwrap();
+        assert!(cidr.contains("192.0.2.1".parse().unwrap()));
+        assert!(!cidr.contains("2001:db8::1".parse().unwrap()));
+    }
+}
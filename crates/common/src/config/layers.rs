@@ -0,0 +1,195 @@
+//! 分层配置
+//!
+//! 配置按以下顺序合并，后者覆盖前者：
+//! 1. 结构体默认值（`#[serde(default)]`）
+//! 2. TOML 配置文件
+//! 3. 环境变量 `ACTRIX__SECTION__FIELD`：去掉前缀后以 `__` 分隔路径并转为小写，
+//!    例如 `ACTRIX__BIND__HTTP__PORT=9000` 对应 `bind.http.port`
+//! 4. 命令行 `--set key=value`，例如 `--set services.signaling.server.ws_path=/ws`
+//!
+//! 覆盖值按 TOML 字面量解析（`9000`、`true`、`["a", "b"]`），目标字段为字符串时保留原文；
+//! 路径中不存在的配置段会自动创建。覆盖在 [`ActrixConfig::from_file`](super::ActrixConfig::from_file)
+//! 中应用，配置热加载同样生效。
+
+use super::schema::{FieldKind, PathLookup, lookup_path};
+use std::sync::{LazyLock, Mutex};
+
+/// 环境变量覆盖前缀
+pub const ENV_PREFIX: &str = "ACTRIX__";
+
+/// 环境变量路径分隔符
+const ENV_SEPARATOR: &str = "__";
+
+/// 单个配置覆盖
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOverride {
+    /// 点分路径
+    pub path: String,
+    /// 原始值
+    pub value: String,
+    /// 来源（用于错误信息）
+    pub source: String,
+}
+
+impl ConfigOverride {
+    /// 解析 `--set key=value`
+    pub fn parse_set(arg: &str) -> Result<Self, String> {
+        let (path, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("--set {arg}: expected key=value"))?;
+        let path = path.trim();
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return Err(format!("--set {arg}: invalid key '{path}'"));
+        }
+        Ok(Self {
+            path: path.to_string(),
+            value: value.to_string(),
+            source: format!("--set {path}"),
+        })
+    }
+}
+
+/// 进程级命令行覆盖（启动时设置一次，配置热加载时复用）
+static CLI_OVERRIDES: LazyLock<Mutex<Vec<ConfigOverride>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// 设置命令行覆盖
+pub fn set_cli_overrides(overrides: Vec<ConfigOverride>) {
+    *CLI_OVERRIDES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = overrides;
+}
+
+fn cli_overrides() -> Vec<ConfigOverride> {
+    CLI_OVERRIDES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// 从环境变量中提取覆盖，按变量名排序以保证结果稳定
+pub fn env_overrides(vars: impl IntoIterator<Item = (String, String)>) -> Vec<ConfigOverride> {
+    let mut overrides: Vec<ConfigOverride> = vars
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let path = name.strip_prefix(ENV_PREFIX)?;
+            let segments: Vec<String> = path.split(ENV_SEPARATOR).map(str::to_lowercase).collect();
+            if segments.iter().any(String::is_empty) {
+                return None;
+            }
+            Some(ConfigOverride {
+                path: segments.join("."),
+                value,
+                source: name,
+            })
+        })
+        .collect();
+    overrides.sort_by(|a, b| a.source.cmp(&b.source));
+    overrides
+}
+
+/// 在配置文件内容上应用环境变量与命令行覆盖
+pub fn apply_layers(table: &mut toml::Table) -> Result<(), String> {
+    for layer in env_overrides(std::env::vars())
+        .iter()
+        .chain(&cli_overrides())
+    {
+        apply_override(table, layer)?;
+    }
+    Ok(())
+}
+
+/// 应用单个覆盖
+pub fn apply_override(table: &mut toml::Table, layer: &ConfigOverride) -> Result<(), String> {
+    let segments: Vec<&str> = layer.path.split('.').collect();
+    let kind = match lookup_path(&segments) {
+        PathLookup::Field(kind) => Some(kind),
+        PathLookup::Unknown { section, field } => {
+            return Err(format!(
+                "{}: unknown configuration field '{field}' in {section}",
+                layer.source
+            ));
+        }
+        PathLookup::Opaque => None,
+    };
+    let value = parse_value(&layer.value, kind);
+
+    let (last, parents) = segments
+        .split_last()
+        .ok_or_else(|| format!("{}: empty key", layer.source))?;
+    let mut current = table;
+    for segment in parents {
+        let entry = current
+            .entry(segment.to_string())
+            .or_insert(toml::Value::Table(toml::Table::new()));
+        current = entry.as_table_mut().ok_or_else(|| {
+            format!(
+                "{}: '{segment}' is not a table and cannot contain '{}'",
+                layer.source, layer.path
+            )
+        })?;
+    }
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+/// 字符串字段保留原文，其他字段按 TOML 字面量解析，解析失败时作为字符串
+fn parse_value(raw: &str, kind: Option<&FieldKind>) -> toml::Value {
+    if matches!(kind, Some(FieldKind::String | FieldKind::Enum(_))) {
+        return toml::Value::String(raw.to_string());
+    }
+    toml::from_str::<toml::Table>(&format!("value = {raw}"))
+        .ok()
+        .and_then(|mut parsed| parsed.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(arg: &str) -> ConfigOverride {
+        ConfigOverride::parse_set(arg).unwrap()
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let overrides = env_overrides([
+            ("ACTRIX__BIND__HTTP__PORT".to_string(), "9000".to_string()),
+            ("ACTRIX_PATH".to_string(), "/opt/actrix".to_string()),
+            ("ACTRIX__BAD____PATH".to_string(), "x".to_string()),
+            ("ACTRIX__NAME".to_string(), "edge-1".to_string()),
+        ]);
+        let paths: Vec<&str> = overrides.iter().map(|o| o.path.as_str()).collect();
+        assert_eq!(paths, vec!["bind.http.port", "name"]);
+    }
+
+    #[test]
+    fn test_apply_override_types_and_tables() {
+        let mut table: toml::Table = toml::from_str("name = \"from-file\"").unwrap();
+
+        apply_override(&mut table, &set("bind.http.port=9000")).unwrap();
+        apply_override(&mut table, &set("name=12345")).unwrap();
+        apply_override(&mut table, &set("services.signaling.server.ws_path=/ws")).unwrap();
+
+        assert_eq!(table["bind"]["http"]["port"].as_integer(), Some(9000));
+        // 字符串字段保留原文
+        assert_eq!(table["name"].as_str(), Some("12345"));
+        assert_eq!(
+            table["services"]["signaling"]["server"]["ws_path"].as_str(),
+            Some("/ws")
+        );
+    }
+
+    #[test]
+    fn test_apply_override_rejects_unknown_or_conflicting_paths() {
+        let mut table: toml::Table = toml::from_str("name = \"node\"").unwrap();
+
+        let err = apply_override(&mut table, &set("bind.htp.port=1")).unwrap_err();
+        assert!(err.contains("htp") && err.contains("bind"));
+
+        assert!(apply_override(&mut table, &set("name.inner=1")).is_err());
+        assert!(ConfigOverride::parse_set("no-equals").is_err());
+        assert!(ConfigOverride::parse_set("a..b=1").is_err());
+    }
+}
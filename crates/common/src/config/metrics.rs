@@ -0,0 +1,188 @@
+//! Prometheus 指标导出配置
+//!
+//! 默认在主 HTTP 路由上挂载 `/metrics`；配置 `bind` 后改为在独立的管理端口上导出，
+//! 仅运行 STUN/TURN 的节点也可以借此暴露指标。配置 `bearer_token` 后抓取请求需要携带
+//! `Authorization: Bearer <token>`。
+//!
+//! 启用 `otlp` 后（需要编译时启用 `opentelemetry` feature），同一批指标还会按固定周期
+//! 推送到 OTLP 后端，供不抓取 Prometheus 的部署使用。
+
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// Prometheus 指标导出配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// 是否导出指标
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// 指标路径
+    #[serde(default = "default_path")]
+    pub path: String,
+
+    /// 独立的管理监听地址（如 "127.0.0.1:9090"）
+    ///
+    /// 未配置时挂载到主 HTTP 路由；配置后仅在该地址上以明文 HTTP 导出
+    #[serde(default)]
+    pub bind: Option<String>,
+
+    /// 抓取认证 token，未配置时不认证
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+
+    /// OTLP 指标推送配置
+    #[serde(default)]
+    pub otlp: OtlpMetricsConfig,
+}
+
+/// OTLP 指标推送配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtlpMetricsConfig {
+    /// 是否推送指标（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// OTLP gRPC 端点，未配置时使用 `observability.tracing.endpoint`
+    #[serde(default)]
+    pub endpoint: Option<String>,
+
+    /// 推送间隔（秒）
+    #[serde(default = "default_export_interval_secs")]
+    pub export_interval_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_path() -> String {
+    "/metrics".to_string()
+}
+
+fn default_export_interval_secs() -> u64 {
+    60
+}
+
+impl Default for OtlpMetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            export_interval_secs: default_export_interval_secs(),
+        }
+    }
+}
+
+impl OtlpMetricsConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.export_interval_secs == 0 {
+            return Err("otlp.export_interval_secs must be greater than 0".to_string());
+        }
+        if let Some(endpoint) = &self.endpoint
+            && !endpoint.starts_with("http://")
+            && !endpoint.starts_with("https://")
+        {
+            return Err(format!(
+                "otlp.endpoint '{endpoint}' must start with http:// or https://"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            path: default_path(),
+            bind: None,
+            bearer_token: None,
+            otlp: OtlpMetricsConfig::default(),
+        }
+    }
+}
+
+impl MetricsConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.path.starts_with('/') {
+            return Err(format!("path '{}' must start with '/'", self.path));
+        }
+        if let Some(bind) = &self.bind
+            && bind.parse::<SocketAddr>().is_err()
+        {
+            return Err(format!("bind '{bind}' is not a valid socket address"));
+        }
+        if let Some(token) = &self.bearer_token
+            && token.trim().is_empty()
+        {
+            return Err("bearer_token cannot be empty when set".to_string());
+        }
+        self.otlp.validate()
+    }
+
+    /// 解析独立监听地址（未配置或无效时返回 None）
+    pub fn bind_addr(&self) -> Option<SocketAddr> {
+        self.bind.as_deref().and_then(|bind| bind.parse().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mounts_on_main_router() {
+        let config = MetricsConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.path, "/metrics");
+        assert!(config.bind_addr().is_none());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = MetricsConfig {
+            bind: Some("127.0.0.1:9090".to_string()),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.bind_addr(), Some("127.0.0.1:9090".parse().unwrap()));
+
+        config.bind = Some("localhost".to_string());
+        assert!(config.validate().is_err());
+
+        config.bind = None;
+        config.path = "metrics".to_string();
+        assert!(config.validate().is_err());
+
+        config.path = "/metrics".to_string();
+        config.bearer_token = Some("  ".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_otlp() {
+        let mut config = MetricsConfig::default();
+        assert!(!config.otlp.enabled);
+
+        // 未启用时不校验
+        config.otlp.export_interval_secs = 0;
+        assert!(config.validate().is_ok());
+
+        config.otlp.enabled = true;
+        assert!(config.validate().is_err());
+
+        config.otlp.export_interval_secs = 30;
+        config.otlp.endpoint = Some("otel-collector:4317".to_string());
+        assert!(config.validate().is_err());
+
+        config.otlp.endpoint = Some("http://otel-collector:4317".to_string());
+        assert!(config.validate().is_ok());
+    }
+}
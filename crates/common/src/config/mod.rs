@@ -3,25 +3,47 @@
 //! 本模块是 Actor-RTC 辅助服务配置的"单一真理之源"。
 //! 所有配置项的定义、文档、默认值都在这里统一管理。
 
+pub mod advertise;
 pub mod ais;
 pub mod bind;
+pub mod dev;
+pub mod http;
 pub mod ks;
+pub mod layers;
+pub mod metrics;
+pub mod nonce;
+pub mod reload;
+pub mod schema;
+pub mod secrets;
 pub mod services;
+pub mod shutdown;
 pub mod signaling;
+pub mod storage;
 pub mod supervisor;
 pub mod tracing;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub mod turn;
 
+pub use crate::config::advertise::AdvertiseConfig;
 pub use crate::config::ais::AisConfig;
 pub use crate::config::bind::BindConfig;
+pub use crate::config::dev::DevConfig;
+pub use crate::config::http::{HttpConfig, RoutePrefixes};
+use crate::config::ks::KsClientConfig;
+pub use crate::config::metrics::{MetricsConfig, OtlpMetricsConfig};
+pub use crate::config::nonce::NonceStorageConfig;
+pub use crate::config::reload::ReloadConfig;
 pub use crate::config::services::ServicesConfig;
+pub use crate::config::shutdown::ShutdownConfig;
 pub use crate::config::signaling::SignalingConfig;
-pub use crate::config::supervisor::SupervisorConfig;
+pub use crate::config::storage::StorageMode;
+pub use crate::config::supervisor::{SelfUpdateConfig, SupervisorConfig};
 pub use crate::config::tracing::TracingConfig;
 pub use crate::config::turn::TurnConfig;
+use crate::storage::SqliteLocation;
 use ::ks::storage::StorageBackend;
 use std::path::{Path, PathBuf};
+use url::Url;
 
 /// Actor-RTC 辅助服务的主配置结构体
 ///
@@ -85,6 +107,19 @@ pub struct ActrixConfig {
     /// 定义各种网络服务的绑定地址和端口配置。
     pub bind: BindConfig,
 
+    /// 对外宣告端点配置（可选）
+    ///
+    /// 位于负载均衡、端口转发 NAT 或 NAT64 之后时，覆盖服务发现文档与 Supervisor 上报中
+    /// 发布的 HTTP 基础 URL、Signaling WebSocket 地址与 STUN 端点，与绑定地址相互独立。
+    #[serde(default)]
+    pub advertise: AdvertiseConfig,
+
+    /// HTTP 服务公共配置（可选）
+    ///
+    /// 作用于合并后的 HTTP 路由，包括请求体大小、超时与并发上限，按服务配置的 CORS 来源与安全响应头。
+    #[serde(default)]
+    pub http: HttpConfig,
+
     /// TURN 服务特定配置
     ///
     /// TURN 中继服务的专用配置，包括公网地址、端口范围、认证域等。
@@ -120,6 +155,17 @@ pub struct ActrixConfig {
     )]
     pub sqlite_path: PathBuf,
 
+    /// 存储模式
+    ///
+    /// - "sqlite": 主数据库、服务注册表缓存与 nonce 存储写入 `sqlite_path`（默认）
+    /// - "memory": 以上数据仅保存在进程内存中，不创建 `sqlite_path`，重启后丢失。
+    ///   nonce_storage.backend = "sqlite" 时自动改用内存后端，redis 后端不受影响。
+    ///   AIS 密钥与吊销记录、KS sqlite 密钥存储与审计日志同样改用内存数据库
+    ///   （见 [`ActrixConfig::sqlite_location`] 与 [`ActrixConfig::ks_service_config`]）；
+    ///   KS 公钥缓存（`ks_cache.db`）不受影响
+    #[serde(default)]
+    pub storage: StorageMode,
+
     /// Actrix 内部服务通信共享密钥
     ///
     /// 用于 Actrix 各服务之间的内部通信认证，如 AIS 与 KS 之间的通信。
@@ -132,11 +178,36 @@ pub struct ActrixConfig {
     /// - 字段名保留 actrix_shared_key 以保持向后兼容
     pub actrix_shared_key: String,
 
+    /// Nonce 存储配置
+    ///
+    /// 选择 KS、Supervisord 等服务防重放校验使用的 nonce 存储后端，默认 SQLite。
+    #[serde(default)]
+    pub nonce_storage: NonceStorageConfig,
+
     /// 可观测性配置（日志 + 追踪）
     ///
     /// 将日志和 OpenTelemetry 追踪配置合并到统一的 observability 段，便于统一管理。
     #[serde(default)]
     pub observability: ObservabilityConfig,
+
+    /// 配置热加载（可选）
+    ///
+    /// SIGHUP 与配置文件监视触发重新加载。运行时生效的字段：`observability.filter_level`、
+    /// `services.signaling.server.rate_limit`、`turn.allowed_realm_ids`，其余字段需要重启。
+    #[serde(default)]
+    pub reload: ReloadConfig,
+
+    /// 优雅关闭配置（可选）
+    ///
+    /// 收到关闭信号后等待服务任务退出的期限，超时后强制中止剩余任务并汇总记录。
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+
+    /// 开发调试配置（可选）
+    ///
+    /// 包含弱网模拟等仅用于本地开发的功能，生产环境 (`env = "prod"`) 中不允许启用。
+    #[serde(default)]
+    pub dev: DevConfig,
 }
 
 /// 可观测性配置
@@ -157,6 +228,12 @@ pub struct ObservabilityConfig {
     /// 需要编译时启用 `opentelemetry` feature。
     #[serde(default)]
     pub tracing: TracingConfig,
+
+    /// Prometheus 指标导出配置
+    ///
+    /// 控制 `/metrics` 端点的路径、认证以及是否使用独立的管理端口。
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 /// 日志配置
@@ -183,6 +260,32 @@ pub struct LogConfig {
     /// 当 output = "file" 时有效
     #[serde(default = "default_log_path")]
     pub path: String,
+
+    /// 单个日志文件的大小上限（MB），0 表示不按大小轮转
+    ///
+    /// 以下轮转与保留选项仅在 rotate = true 时有效；任一选项启用后，当前日志写入 `actrix.log`，
+    /// 跨天或超过大小上限时归档为带时间戳的文件
+    #[serde(default)]
+    pub max_file_size_mb: u64,
+
+    /// 保留的归档日志文件数，0 表示不限制
+    #[serde(default)]
+    pub max_files: usize,
+
+    /// 归档日志文件的保留天数，0 表示不限制
+    #[serde(default)]
+    pub max_age_days: u64,
+
+    /// 是否使用 gzip 压缩归档日志文件
+    #[serde(default)]
+    pub compress: bool,
+}
+
+impl LogConfig {
+    /// 是否启用了大小轮转、保留策略或压缩（否则沿用按天轮转）
+    pub fn has_retention_policy(&self) -> bool {
+        self.max_file_size_mb > 0 || self.max_files > 0 || self.max_age_days > 0 || self.compress
+    }
 }
 
 impl Default for ObservabilityConfig {
@@ -191,6 +294,7 @@ impl Default for ObservabilityConfig {
             log: LogConfig::default(),
             filter_level: default_filter_level(),
             tracing: TracingConfig::default(),
+            metrics: MetricsConfig::default(),
         }
     }
 }
@@ -201,6 +305,10 @@ impl Default for LogConfig {
             output: default_log_output(),
             rotate: false,
             path: default_log_path(),
+            max_file_size_mb: 0,
+            max_files: 0,
+            max_age_days: 0,
+            compress: false,
         }
     }
 }
@@ -246,13 +354,20 @@ impl Default for ActrixConfig {
             group: None,
             pid: Some("logs/actrix.pid".to_string()),
             bind: BindConfig::default(),
+            advertise: AdvertiseConfig::default(),
+            http: HttpConfig::default(),
             turn: TurnConfig::default(),
             location_tag: "default-location".to_string(),
             supervisor: None,
             services: ServicesConfig::default(),
             sqlite_path: PathBuf::from("database"),
+            storage: StorageMode::default(),
             actrix_shared_key: "XDDYE8d+yMfdXcdWMrXprcUk2uzjnmoX6nCfFw1gGIg=".to_string(),
+            nonce_storage: NonceStorageConfig::default(),
             observability: ObservabilityConfig::default(),
+            reload: ReloadConfig::default(),
+            shutdown: ShutdownConfig::default(),
+            dev: DevConfig::default(),
         }
     }
 }
@@ -296,6 +411,73 @@ impl ActrixConfig {
         self.enable & ENABLE_KS != 0
     }
 
+    /// 检查是否使用模拟的 KS 与 AIS 依赖（仅开发环境）
+    pub fn is_dev_mock_enabled(&self) -> bool {
+        self.env == "dev" && self.dev.mock_dependencies
+    }
+
+    /// 本地 KS gRPC 监听地址（`services.ks.grpc.bind`，未配置 KS 时为默认地址）
+    pub fn ks_grpc_bind(&self) -> ::ks::KsGrpcBindConfig {
+        self.services
+            .ks
+            .as_ref()
+            .map(|ks| ks.grpc.bind.clone())
+            .unwrap_or_default()
+    }
+
+    /// 指向本地 KS gRPC 监听器的客户端配置
+    ///
+    /// 本地 KS 启用双向 TLS 时返回 None：客户端证书无法自动推导，需显式配置 dependencies.ks
+    pub fn local_ks_client_config(&self) -> Option<KsClientConfig> {
+        if self
+            .services
+            .ks
+            .as_ref()
+            .is_some_and(|ks| ks.grpc_tls_config().is_some())
+        {
+            return None;
+        }
+        let addr = self.ks_grpc_bind().local_addr().ok()?;
+        Some(KsClientConfig {
+            endpoint: format!("http://{addr}"),
+            ..Default::default()
+        })
+    }
+
+    /// KS gRPC 监听地址与其他 TCP 监听器（HTTP / HTTPS / Supervisord）冲突时返回说明
+    pub fn ks_grpc_listener_conflict(&self) -> Option<String> {
+        let ks = self.ks_grpc_bind();
+        let mut listeners = Vec::new();
+        if let Some(http) = &self.bind.http {
+            listeners.push(("bind.http", http.ip.clone(), http.port));
+        }
+        if let Some(https) = &self.bind.https {
+            listeners.push(("bind.https", https.ip.clone(), https.port));
+        }
+        if let Some(supervisor) = &self.supervisor {
+            listeners.push((
+                "supervisor.supervisord",
+                supervisor.supervisord.ip.clone(),
+                supervisor.supervisord.port,
+            ));
+        }
+
+        listeners
+            .into_iter()
+            .find(|(_, ip, port)| *port == ks.port && listeners_overlap(ip, &ks.ip))
+            .map(|(name, ip, port)| {
+                format!(
+                    "services.ks.grpc.bind {}:{} conflicts with {name} listener {ip}:{port}",
+                    ks.ip, ks.port
+                )
+            })
+    }
+
+    /// 检查是否启用了局域网直连模式（仅开发环境）
+    pub fn is_lan_discovery_enabled(&self) -> bool {
+        self.env == "dev" && self.dev.lan_discovery
+    }
+
     /// 检查是否启用了 ICE 服务（STUN 或 TURN）
     pub fn is_ice_enabled(&self) -> bool {
         self.is_stun_enabled() || self.is_turn_enabled()
@@ -308,6 +490,50 @@ impl ActrixConfig {
         })
     }
 
+    /// TURN 中继端口范围包含 ICE 监听端口（UDP）时返回冲突描述
+    ///
+    /// `ephemeral` 为系统临时端口范围，仅用于计算建议的安全范围
+    pub fn relay_port_listener_conflict(&self, ephemeral: Option<(u16, u16)>) -> Option<String> {
+        let relay = self.turn.relay_port_bounds().ok()?;
+        let port = self.bind.ice.port;
+        if !turn::port_ranges_overlap(relay, (port, port)) {
+            return None;
+        }
+        Some(format!(
+            "TURN relay_port_range {}-{} contains the ICE listener port bind.ice.port = {}{}",
+            relay.0,
+            relay.1,
+            port,
+            self.relay_port_suggestion(ephemeral)
+        ))
+    }
+
+    /// TURN 中继端口范围与系统临时端口范围重叠时返回冲突描述
+    ///
+    /// 重叠时出站连接占用的临时端口会导致中继地址分配偶发失败
+    pub fn relay_port_ephemeral_conflict(&self, ephemeral: Option<(u16, u16)>) -> Option<String> {
+        let relay = self.turn.relay_port_bounds().ok()?;
+        let ephemeral_range = ephemeral?;
+        if !turn::port_ranges_overlap(relay, ephemeral_range) {
+            return None;
+        }
+        Some(format!(
+            "TURN relay_port_range {}-{} overlaps the system ephemeral port range {}-{} (net.ipv4.ip_local_port_range){}",
+            relay.0,
+            relay.1,
+            ephemeral_range.0,
+            ephemeral_range.1,
+            self.relay_port_suggestion(ephemeral)
+        ))
+    }
+
+    fn relay_port_suggestion(&self, ephemeral: Option<(u16, u16)>) -> String {
+        match turn::suggest_relay_port_range(ephemeral, &[self.bind.ice.port]) {
+            Some((start, end)) => format!("; suggested relay_port_range = \"{start}-{end}\""),
+            None => String::new(),
+        }
+    }
+
     /// 获取 PID 文件路径，如果没有配置则使用默认值
     pub fn get_pid_path(&self) -> Option<String> {
         self.pid.clone().or_else(|| {
@@ -326,6 +552,140 @@ impl ActrixConfig {
         &self.actrix_shared_key
     }
 
+    /// 获取实际生效的 nonce 存储配置
+    ///
+    /// 内存存储模式下 sqlite 后端改用进程内存储，避免写入 `nonce.db`
+    pub fn nonce_storage_config(&self) -> NonceStorageConfig {
+        let mut config = self.nonce_storage.clone();
+        if self.storage.is_memory() && config.backend == nonce::NonceBackend::Sqlite {
+            config.backend = nonce::NonceBackend::Memory;
+        }
+        config
+    }
+
+    /// 服务私有 SQLite 数据库的位置
+    ///
+    /// 内存存储模式下使用内存数据库，否则为 `sqlite_path` 下的 `file_name`
+    pub fn sqlite_location(&self, file_name: &str) -> SqliteLocation {
+        if self.storage.is_memory() {
+            SqliteLocation::Memory
+        } else {
+            SqliteLocation::File(self.sqlite_path.join(file_name))
+        }
+    }
+
+    /// 获取实际生效的 KS 服务配置（未配置 KS 时为 None）
+    ///
+    /// 内存存储模式下 sqlite 密钥存储与 sqlite 审计日志改用内存数据库，
+    /// 避免写入 `ks_keys.db` 与 `ks_audit.db`
+    pub fn ks_service_config(&self) -> Option<::ks::KsServiceConfig> {
+        let mut config = self.services.ks.clone()?;
+        if self.storage.is_memory() {
+            if let Some(sqlite) = config.storage.sqlite.as_mut() {
+                sqlite.in_memory = true;
+            }
+            if config.audit.sink == ::ks::config::AuditSinkType::Sqlite {
+                config.audit.sink = ::ks::config::AuditSinkType::Memory;
+            }
+        }
+        Some(config)
+    }
+
+    /// 对外发布的 HTTP(S) 基础 URL
+    ///
+    /// 优先使用 `advertise.public_url`，否则由主 HTTP 监听器（dev 环境优先 `bind.http`，
+    /// 其余环境为 `bind.https`）的域名与端口推导
+    pub fn public_url(&self) -> Result<Url, String> {
+        if let Some(ref url) = self.advertise.public_url {
+            return Url::parse(url)
+                .map_err(|e| format!("Invalid advertise.public_url '{url}': {e}"));
+        }
+        let is_dev = self.env.to_lowercase() == "dev";
+        let (scheme, domain_name, port) = match (&self.bind.http, &self.bind.https) {
+            (Some(http), _) if is_dev => ("http", &http.domain_name, http.port),
+            (_, Some(https)) => ("https", &https.domain_name, https.port),
+            _ if is_dev => return Err("No HTTP or HTTPS binding configuration found".to_string()),
+            _ => {
+                return Err(
+                    "HTTPS binding configuration is required for production environment"
+                        .to_string(),
+                );
+            }
+        };
+        Url::parse(&format!(
+            "{scheme}://{}:{port}",
+            bind::url_host(domain_name)
+        ))
+        .map_err(|e| format!("Failed to parse {} URL: {e}", scheme.to_uppercase()))
+    }
+
+    /// 各 HTTP 路由服务的挂载前缀
+    ///
+    /// AIS 与 KS 取自 `http.route_prefixes`，Signaling 取自 `services.signaling.server.ws_path`
+    pub fn route_prefixes(&self) -> RoutePrefixes {
+        let mut prefixes = RoutePrefixes {
+            ais: self.http.route_prefixes.ais.clone(),
+            ks: self.http.route_prefixes.ks.clone(),
+            ..Default::default()
+        };
+        if let Some(ref signaling) = self.services.signaling {
+            prefixes.signaling = signaling.server.ws_path.clone();
+        }
+        prefixes
+    }
+
+    /// 对外发布的 Signaling WebSocket 地址
+    ///
+    /// 优先使用 `advertise.signaling_ws_url`，否则为 `public_url` 对应的 ws/wss 地址加
+    /// `{ws_path}/ws`
+    pub fn signaling_ws_url(&self, public_url: &Url) -> String {
+        if let Some(ref url) = self.advertise.signaling_ws_url {
+            return url.clone();
+        }
+        let http_base = public_url.as_str().trim_end_matches('/');
+        let ws_base = if public_url.scheme() == "https" {
+            http_base.replacen("https", "wss", 1)
+        } else {
+            http_base.replacen("http", "ws", 1)
+        };
+        format!("{ws_base}{}/ws", self.route_prefixes().signaling)
+    }
+
+    /// 对外发布的 STUN 端点（主机, 端口），默认取 `bind.ice` 的域名与端口
+    pub fn stun_endpoint(&self) -> (&str, u16) {
+        (
+            self.advertise
+                .stun_host
+                .as_deref()
+                .unwrap_or(&self.bind.ice.domain_name),
+            self.advertise.stun_port.unwrap_or(self.bind.ice.port),
+        )
+    }
+
+    /// 对外发布的 TURN 端点（主机, 端口）：`turn.advertised_hostname`（默认 `bind.ice.domain_name`）
+    /// 与 `turn.advertised_port`
+    pub fn turn_endpoint(&self) -> (&str, u16) {
+        (
+            self.turn
+                .advertised_hostname
+                .as_deref()
+                .unwrap_or(&self.bind.ice.domain_name),
+            self.turn.advertised_port,
+        )
+    }
+
+    /// 对外发布的 STUN URL
+    pub fn stun_url(&self) -> String {
+        let (host, port) = self.stun_endpoint();
+        format!("stun:{}:{port}", bind::url_host(host))
+    }
+
+    /// 对外发布的 TURN URL（UDP）
+    pub fn turn_url(&self) -> String {
+        let (host, port) = self.turn_endpoint();
+        format!("turn:{}:{port}?transport=udp", bind::url_host(host))
+    }
+
     /// 获取追踪配置
     ///
     /// 返回 OpenTelemetry 追踪配置的引用
@@ -369,6 +729,8 @@ impl ActrixConfig {
     }
 
     /// 从文件加载配置
+    ///
+    /// 依次叠加环境变量与命令行覆盖（见 [`layers`]），再解析机密字段引用
     pub fn from_file<P: AsRef<std::path::Path>>(
         path: P,
     ) -> Result<Self, Box<dyn std::error::Error>> {
@@ -387,12 +749,39 @@ impl ActrixConfig {
         // Read file content
         let content = std::fs::read_to_string(path_ref)?;
 
-        // Parse TOML content
-        let config: ActrixConfig = toml::from_str(&content)?;
+        // Parse TOML content, then apply environment and command-line overrides
+        let mut table: toml::Table = toml::from_str(&content)?;
+        layers::apply_layers(&mut table)?;
+        let mut config: ActrixConfig = toml::Value::Table(table).try_into()?;
+        config.resolve_secrets()?;
 
         Ok(config)
     }
 
+    /// 解析机密字段中的 `${VAR}` 与 `file:` 引用（见 [`secrets`]）
+    pub fn resolve_secrets(&mut self) -> Result<(), String> {
+        self.actrix_shared_key =
+            secrets::resolve_secret("actrix_shared_key", &self.actrix_shared_key)?;
+        if let Some(ks) = self.services.ks.as_mut()
+            && let Some(kek) = ks.kek.as_mut()
+        {
+            *kek = secrets::resolve_secret("services.ks.kek", kek)?;
+        }
+        if let Some(signaling) = self.services.signaling.as_mut()
+            && let Some(kek) = signaling.server.registry_encryption.kek.as_mut()
+        {
+            *kek =
+                secrets::resolve_secret("services.signaling.server.registry_encryption.kek", kek)?;
+        }
+        if let Some(supervisor) = self.supervisor.as_mut() {
+            supervisor.client.shared_secret = secrets::resolve_secret(
+                "supervisor.client.shared_secret",
+                &supervisor.client.shared_secret,
+            )?;
+        }
+        Ok(())
+    }
+
     /// 从 TOML 字符串加载配置
     pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(content)
@@ -459,6 +848,12 @@ impl ActrixConfig {
             ));
         }
 
+        if self.observability.log.has_retention_policy()
+            && !(self.observability.log.output == "file" && self.observability.log.rotate)
+        {
+            errors.push("Warning: observability.log.max_file_size_mb / max_files / max_age_days / compress only take effect with output = \"file\" and rotate = true".to_string());
+        }
+
         // 验证 actrix_shared_key
         if self.actrix_shared_key.contains("default") || self.actrix_shared_key.contains("change") {
             errors.push("Security warning: actrix_shared_key appears to be a default value. Please change it!".to_string());
@@ -477,11 +872,68 @@ impl ActrixConfig {
             errors.push("SQLite database path cannot be empty".to_string());
         }
 
+        // 验证 HTTP 公共配置
+        if let Err(e) = self.http.validate() {
+            errors.push(format!("HTTP configuration error (http): {e}"));
+        }
+
         // 验证追踪配置
         if let Err(e) = self.observability.tracing.validate() {
             errors.push(format!("Tracing configuration error: {e}"));
         }
 
+        // 验证指标导出配置
+        if let Err(e) = self.observability.metrics.validate() {
+            errors.push(format!(
+                "Metrics configuration error (observability.metrics): {e}"
+            ));
+        }
+
+        // 验证弱网模拟配置（仅限开发环境）
+        if self.dev.network_emulation.enabled {
+            if self.env == "prod" {
+                errors.push(
+                    "dev.network_emulation must not be enabled in prod environment".to_string(),
+                );
+            }
+            if let Err(e) = self.dev.network_emulation.validate() {
+                errors.push(format!("Network emulation configuration error: {e}"));
+            }
+        }
+
+        if self.dev.mock_dependencies && self.env != "dev" {
+            errors.push("dev.mock_dependencies requires env = \"dev\"".to_string());
+        }
+        if self.dev.lan_discovery && self.env != "dev" {
+            errors.push("dev.lan_discovery requires env = \"dev\"".to_string());
+        }
+
+        if let Err(e) = self.reload.validate() {
+            errors.push(format!("Reload configuration error: {e}"));
+        }
+
+        if let Err(e) = self.shutdown.validate() {
+            errors.push(format!("Shutdown configuration error: {e}"));
+        }
+
+        if let Err(e) = self.advertise.validate() {
+            errors.push(format!("Advertise configuration error: {e}"));
+        }
+
+        // 验证 nonce 存储配置
+        if let Err(e) = self.nonce_storage.validate() {
+            errors.push(format!("Nonce storage configuration error: {e}"));
+        }
+        if self.env == "prod" && self.nonce_storage.backend == nonce::NonceBackend::Memory {
+            errors.push(
+                "nonce_storage.backend = \"memory\" must not be used in prod environment"
+                    .to_string(),
+            );
+        }
+        if self.env == "prod" && self.storage.is_memory() {
+            errors.push("storage = \"memory\" must not be used in prod environment".to_string());
+        }
+
         // 验证 TURN 配置（如果启用）
         if self.is_turn_enabled() {
             if self.turn.advertised_ip.trim().is_empty() {
@@ -490,6 +942,14 @@ impl ActrixConfig {
             if self.turn.realm.trim().is_empty() {
                 errors.push("TURN realm is required when TURN is enabled".to_string());
             }
+            if let Err(e) = self.turn.relay_port_bounds() {
+                errors.push(e);
+            }
+            if let Some(conflict) =
+                self.relay_port_listener_conflict(turn::system_ephemeral_port_range())
+            {
+                errors.push(conflict);
+            }
             // 验证 advertised_ip 格式
             if self.turn.advertised_ip.parse::<std::net::IpAddr>().is_err() {
                 errors.push(format!(
@@ -497,6 +957,112 @@ impl ActrixConfig {
                     self.turn.advertised_ip
                 ));
             }
+            if let Some(ref ipv6) = self.turn.advertised_ipv6
+                && ipv6.parse::<std::net::Ipv6Addr>().is_err()
+            {
+                errors.push(format!(
+                    "Invalid TURN advertised_ipv6 '{ipv6}', must be a valid IPv6 address"
+                ));
+            }
+            if let Some(mismatch) = bind::address_family_mismatch(
+                "bind.ice.ip",
+                &self.bind.ice.ip,
+                "turn.advertised_ip",
+                &self.turn.advertised_ip,
+            ) {
+                errors.push(mismatch);
+            }
+            // 双栈套接字只能宣告一个地址，IPv6 宣告地址需要独立的 IPv6 套接字
+            if self.turn.advertised_ipv6.is_some()
+                && self.bind.ice.socket_addr().is_ok_and(|addr| addr.is_ipv6())
+            {
+                errors.push(format!(
+                    "turn.advertised_ipv6 requires an IPv4 bind.ice.ip (the IPv6 relay listens on bind.ice.ipv6), but bind.ice.ip is '{}'",
+                    self.bind.ice.ip
+                ));
+            }
+            if self
+                .turn
+                .advertised_hostname
+                .as_deref()
+                .is_some_and(|hostname| hostname.trim().is_empty())
+            {
+                errors.push("TURN advertised_hostname cannot be empty when set".to_string());
+            }
+        }
+
+        // 验证 ICE 接收工作线程数与绑定地址
+        if self.is_ice_enabled() {
+            if let Err(e) = self.bind.ice.socket_addr() {
+                errors.push(e);
+            }
+            if let Some(ref ipv6) = self.bind.ice.ipv6
+                && ipv6.parse::<std::net::Ipv6Addr>().is_err()
+            {
+                errors.push(format!(
+                    "Invalid bind.ice.ipv6 '{ipv6}', must be a valid IPv6 address"
+                ));
+            }
+            if self.bind.ice.workers > bind::ice::MAX_ICE_WORKERS {
+                errors.push(format!(
+                    "bind.ice.workers = {} exceeds the maximum of {}",
+                    self.bind.ice.workers,
+                    bind::ice::MAX_ICE_WORKERS
+                ));
+            }
+            if self.bind.ice.workers != 1 && !cfg!(unix) {
+                errors.push("Warning: bind.ice.workers requires SO_REUSEPORT (Unix only), falling back to a single socket".to_string());
+            }
+        }
+
+        // 验证 HTTP/HTTPS 的绑定地址、IPv6 宣告地址格式，以及宣告地址与绑定地址的地址族
+        let http_binds = [
+            self.bind.http.as_ref().map(|c| {
+                (
+                    "bind.http",
+                    c.socket_addr(),
+                    &c.ip,
+                    &c.advertised_ip,
+                    c.advertised_ipv6.as_ref(),
+                )
+            }),
+            self.bind.https.as_ref().map(|c| {
+                (
+                    "bind.https",
+                    c.socket_addr(),
+                    &c.ip,
+                    &c.advertised_ip,
+                    c.advertised_ipv6.as_ref(),
+                )
+            }),
+        ];
+        for (section, addr, ip, advertised_ip, ipv6) in http_binds.into_iter().flatten() {
+            if let Err(e) = addr {
+                errors.push(e);
+            }
+            if let Some(ipv6) = ipv6
+                && ipv6.parse::<std::net::Ipv6Addr>().is_err()
+            {
+                errors.push(format!(
+                    "Invalid {section}.advertised_ipv6 '{ipv6}', must be a valid IPv6 address"
+                ));
+            }
+            let advertised = [
+                ("advertised_ip", Some(advertised_ip)),
+                ("advertised_ipv6", ipv6),
+            ];
+            for (field, value) in advertised {
+                if let Some(value) = value
+                    && let Some(mismatch) = bind::address_family_mismatch(
+                        &format!("{section}.ip"),
+                        ip,
+                        &format!("{section}.{field}"),
+                        value,
+                    )
+                {
+                    errors.push(mismatch);
+                }
+            }
         }
 
         // 验证 KS 配置（如果启用）
@@ -513,6 +1079,52 @@ impl ActrixConfig {
                             );
                         }
                     }
+                    StorageBackend::Etcd => match ks.storage.etcd {
+                        None => errors.push(
+                            "KS is configured to use etcd but etcd config is missing".to_string(),
+                        ),
+                        Some(ref etcd) if etcd.endpoints.is_empty() => errors
+                            .push("KS etcd storage requires at least one endpoint".to_string()),
+                        Some(_) => {}
+                    },
+                }
+
+                // 验证 KEK 提供方
+                if let Some(::ks::KekProviderConfig::Pkcs11(ref pkcs11)) = ks.kek_provider
+                    && let Err(e) = pkcs11.validate()
+                {
+                    errors.push(format!("Invalid KS KEK provider: {e}"));
+                }
+
+                // 验证 gRPC 监听地址
+                if let Err(e) = ks.grpc.bind.validate() {
+                    errors.push(format!("Invalid KS gRPC bind configuration: {e}"));
+                }
+                if ks.grpc.bind.tls.is_some() && ks.grpc_tls.is_some() {
+                    errors.push(
+                        "services.ks.grpc.bind.tls and legacy services.ks.grpc_tls are both configured; keep only grpc.bind.tls"
+                            .to_string(),
+                    );
+                }
+                if let Some(conflict) = self.ks_grpc_listener_conflict() {
+                    errors.push(conflict);
+                }
+
+                // 验证 gRPC 双向 TLS
+                if let Some(tls) = ks.grpc_tls_config() {
+                    if let Err(e) = tls.validate() {
+                        errors.push(format!("Invalid KS gRPC TLS configuration: {e}"));
+                    } else if tls.allowed_client_sans.is_empty() {
+                        errors.push(
+                            "Warning: KS grpc_tls.allowed_client_sans is empty; any certificate issued by client_ca (with or without SANs) can access key material"
+                                .to_string(),
+                        );
+                    }
+                }
+
+                // 验证后台密钥调度
+                if let Err(e) = ks.rotation.validate() {
+                    errors.push(format!("Invalid KS rotation configuration: {e}"));
                 }
             } else {
                 // KS 位掩码已设置但 services.ks 配置缺失
@@ -533,6 +1145,18 @@ impl ActrixConfig {
                             .to_string(),
                     );
                 }
+                if let Err(e) = ais.revocation.validate() {
+                    errors.push(format!("Invalid AIS revocation configuration: {e}"));
+                }
+                if let Err(e) = ais.serial_number.validate() {
+                    errors.push(format!("Invalid AIS serial number configuration: {e}"));
+                }
+                if let Err(e) = ais.rate_limit.validate() {
+                    errors.push(format!("Invalid AIS rate limit configuration: {e}"));
+                }
+                if let Err(e) = ais.psk_rotation.validate() {
+                    errors.push(format!("Invalid AIS PSK rotation configuration: {e}"));
+                }
             } else {
                 // AIS 位掩码已设置但 services.ais 配置缺失
                 errors.push(
@@ -546,6 +1170,7 @@ impl ActrixConfig {
             if let Some(ref signaling) = self.services.signaling {
                 if signaling.dependencies.ks.is_none()
                     && !(self.is_ks_enabled() && self.services.ks.is_some())
+                    && !self.is_dev_mock_enabled()
                 {
                     errors.push(
                     "Signaling dependencies.ks is not configured; enable KS (bitmask + services.ks) to use local defaults"
@@ -555,12 +1180,45 @@ impl ActrixConfig {
 
                 if signaling.dependencies.ais.is_none()
                     && !(self.is_ais_enabled() && self.services.ais.is_some())
+                    && !self.is_dev_mock_enabled()
                 {
                     errors.push(
                     "Signaling dependencies.ais is not configured; enable AIS (bitmask + services.ais) to use local defaults"
                         .to_string(),
                 );
                 }
+
+                if let Err(e) = signaling.server.authz_hook.validate() {
+                    errors.push(format!("Signaling authz_hook configuration error: {e}"));
+                }
+                if let Err(e) = signaling.server.resumption.validate() {
+                    errors.push(format!("Signaling resumption configuration error: {e}"));
+                }
+                if let Err(e) = signaling.server.rate_limit.validate() {
+                    errors.push(format!("Signaling rate_limit configuration error: {e}"));
+                }
+                if let Err(e) = signaling.server.load_shedding.validate() {
+                    errors.push(format!("Signaling load_shedding configuration error: {e}"));
+                }
+                if let Err(e) = signaling.server.duplicate_identity.validate() {
+                    errors.push(format!(
+                        "Signaling duplicate_identity configuration error: {e}"
+                    ));
+                }
+                if let Err(e) = signaling.server.compression.validate() {
+                    errors.push(format!("Signaling compression configuration error: {e}"));
+                }
+                if let Err(e) = signaling.storage.validate() {
+                    errors.push(format!("Signaling storage configuration error: {e}"));
+                }
+                if signaling.server.registry_encryption.enabled
+                    && signaling.get_registry_kek_source(self).is_none()
+                {
+                    errors.push(
+                        "Signaling registry_encryption is enabled but no KEK is configured; set registry_encryption.kek_env/kek_file/kek or configure a KEK for services.ks"
+                            .to_string(),
+                    );
+                }
             } else {
                 errors.push(
                     "Signaling service is enabled (ENABLE_SIGNALING bit is set) but services.signaling configuration is missing"
@@ -569,6 +1227,27 @@ impl ActrixConfig {
             }
         }
 
+        // 验证 HTTP 路由服务前缀：互不重叠，且不占用节点自身的管理与探针路径
+        let mut reserved = http::RESERVED_ROUTE_PREFIXES.to_vec();
+        let metrics = &self.observability.metrics;
+        if metrics.enabled && metrics.bind.is_none() {
+            reserved.push(metrics.path.as_str());
+        }
+        if let Err(e) = self.route_prefixes().validate(&reserved) {
+            errors.push(format!("HTTP route prefix error: {e}"));
+        }
+
+        // HTTP → HTTPS 重定向需要 HTTPS 监听器
+        if let Some(ref http) = self.bind.http
+            && http.redirect_to_https
+            && self.bind.https.is_none()
+        {
+            errors.push(
+                "bind.http.redirect_to_https is enabled but bind.https is not configured"
+                    .to_string(),
+            );
+        }
+
         // 生产环境额外检查
         if self.env == "prod" {
             // 生产环境应使用 HTTPS
@@ -608,6 +1287,15 @@ impl ActrixConfig {
     }
 }
 
+/// 两个绑定 IP 是否可能占用同一地址（相同，或任一为通配地址）
+fn listeners_overlap(a: &str, b: &str) -> bool {
+    let unspecified = |ip: &str| {
+        ip.parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_unspecified())
+    };
+    a == b || unspecified(a) || unspecified(b)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -643,6 +1331,519 @@ mod tests {
         assert_eq!(parsed_config.actrix_shared_key, config.actrix_shared_key);
     }
 
+    #[test]
+    fn test_turn_allowed_realm_ids() {
+        let config = ActrixConfig::default();
+        assert!(config.turn.allowed_realm_ids.is_empty());
+
+        let toml_str = config
+            .to_toml()
+            .unwrap()
+            .replace("allowed_realm_ids = []", "allowed_realm_ids = [1001, 1002]");
+        let parsed = ActrixConfig::from_toml(&toml_str).unwrap();
+        assert_eq!(parsed.turn.allowed_realm_ids, vec![1001, 1002]);
+    }
+
+    #[test]
+    fn test_turn_dual_stack_advertised_ip() {
+        let mut config = ActrixConfig::default();
+        let v4_client = Some("203.0.113.7".parse().unwrap());
+        let v6_client = Some("2001:db8::7".parse().unwrap());
+        let mapped_client = Some("::ffff:203.0.113.7".parse().unwrap());
+
+        // 未配置 IPv6 时始终宣告 IPv4 地址
+        assert_eq!(config.turn.advertised_ip_for(v6_client), "127.0.0.1");
+
+        config.turn.advertised_ipv6 = Some("2001:db8::1".to_string());
+        assert_eq!(config.turn.advertised_ip_for(v4_client), "127.0.0.1");
+        assert_eq!(config.turn.advertised_ip_for(v6_client), "2001:db8::1");
+        assert_eq!(config.turn.advertised_ip_for(mapped_client), "127.0.0.1");
+        assert_eq!(config.turn.advertised_ip_for(None), "127.0.0.1");
+
+        config.enable = ENABLE_TURN;
+        let ipv6_errors = |config: &ActrixConfig| {
+            config
+                .validate()
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.contains("ipv6"))
+                .collect::<Vec<_>>()
+        };
+        assert!(ipv6_errors(&config).is_empty());
+
+        config.turn.advertised_ipv6 = Some("127.0.0.2".to_string());
+        config.bind.ice.ipv6 = Some("not-an-ip".to_string());
+        let errors = ipv6_errors(&config);
+        assert!(errors.iter().any(|e| e.contains("TURN advertised_ipv6")));
+        assert!(errors.iter().any(|e| e.contains("bind.ice.ipv6")));
+    }
+
+    #[test]
+    fn test_bind_address_families() {
+        let family_errors = |config: &ActrixConfig| {
+            config
+                .validate()
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.contains("dual-stack") || e.contains("requires an IPv4"))
+                .collect::<Vec<_>>()
+        };
+        let mut config = ActrixConfig {
+            enable: ENABLE_TURN,
+            ..Default::default()
+        };
+        assert!(family_errors(&config).is_empty());
+
+        // IPv4 监听器无法接收经 IPv6 宣告地址到达的流量
+        let https = config.bind.https.as_mut().unwrap();
+        https.advertised_ipv6 = Some("2001:db8::10".to_string());
+        assert!(
+            family_errors(&config)
+                .iter()
+                .any(|e| e.contains("bind.https.advertised_ipv6"))
+        );
+        // 双栈绑定（带或不带方括号）接受两种地址族
+        config.bind.https.as_mut().unwrap().ip = "[::]".to_string();
+        assert!(family_errors(&config).is_empty());
+        assert_eq!(
+            config.bind.https.as_ref().unwrap().socket_addr().unwrap(),
+            "[::]:8443".parse().unwrap()
+        );
+
+        // 具体的 IPv6 绑定地址不能宣告 IPv4 地址
+        config.bind.ice.ip = "2001:db8::20".to_string();
+        assert!(
+            family_errors(&config)
+                .iter()
+                .any(|e| e.contains("turn.advertised_ip"))
+        );
+
+        // 双栈 ICE 套接字不能再单独宣告 IPv6 中继地址
+        config.bind.ice.ip = "::".to_string();
+        config.turn.advertised_ipv6 = Some("2001:db8::1".to_string());
+        assert!(
+            family_errors(&config)
+                .iter()
+                .any(|e| e.contains("requires an IPv4 bind.ice.ip"))
+        );
+        assert_eq!(config.bind.ice.ipv6_socket_addr().unwrap(), None);
+
+        config.bind.ice.ip = "0.0.0.0".to_string();
+        assert!(family_errors(&config).is_empty());
+        assert_eq!(
+            config.bind.ice.ipv6_socket_addr().unwrap(),
+            Some("[::]:3478".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_advertised_endpoints() {
+        let mut config = ActrixConfig::default();
+        assert_eq!(
+            config.public_url().unwrap().as_str(),
+            "http://localhost:8080/"
+        );
+        assert_eq!(config.stun_url(), "stun:localhost:3478");
+
+        // 负载均衡之后：发布的地址与绑定地址不同
+        config.advertise.public_url = Some("https://edge.example.com/actrix".to_string());
+        config.advertise.stun_port = Some(13478);
+        config.turn.advertised_port = 443;
+        config.turn.advertised_hostname = Some("turn.example.com".to_string());
+        let public_url = config.public_url().unwrap();
+        assert_eq!(
+            config.signaling_ws_url(&public_url),
+            "wss://edge.example.com/actrix/signaling/ws"
+        );
+        let mut signaling = SignalingConfig::default();
+        signaling.server.ws_path = "/rtc".to_string();
+        config.services.signaling = Some(signaling);
+        assert_eq!(
+            config.signaling_ws_url(&public_url),
+            "wss://edge.example.com/actrix/rtc/ws"
+        );
+        config.services.signaling = None;
+        assert_eq!(config.stun_url(), "stun:localhost:13478");
+        assert_eq!(config.turn_url(), "turn:turn.example.com:443?transport=udp");
+
+        config.env = "prod".to_string();
+        config.advertise.public_url = None;
+        config.bind.https = None;
+        assert!(config.public_url().is_err());
+    }
+
+    #[test]
+    fn test_signaling_traffic_stats_defaults() {
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [traffic_stats]
+            max_tracked_types = 32
+            "#,
+        )
+        .unwrap();
+        assert!(server.traffic_stats.enabled);
+        assert_eq!(server.traffic_stats.max_tracked_types, 32);
+        assert_eq!(server.traffic_stats.half_life_secs, 300);
+    }
+
+    #[test]
+    fn test_signaling_connection_limits() {
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [limits]
+            max_envelope_bytes = 65536
+            outbound_overflow_policy = "drop_newest"
+            sequence_numbers = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(server.limits.max_envelope_bytes, 65536);
+        assert_eq!(server.limits.max_outbound_queue, 256);
+        assert_eq!(
+            server.limits.outbound_overflow_policy,
+            signaling::OutboundOverflowPolicy::DropNewest
+        );
+        assert_eq!(server.limits.outbound_send_timeout_ms, 1000);
+        assert!(server.limits.sequence_numbers);
+        assert!(!signaling::ConnectionLimitsConfig::default().sequence_numbers);
+    }
+
+    #[test]
+    fn test_signaling_realm_rate_limit() {
+        let server = signaling::SignalingServerConfig::default();
+        assert!(!server.rate_limit.realm.enabled);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [rate_limit.realm]
+            enabled = true
+            relays_per_second = 50
+            "#,
+        )
+        .unwrap();
+        assert!(server.rate_limit.realm.enabled);
+        assert_eq!(server.rate_limit.realm.relays_per_second, 50);
+        assert_eq!(server.rate_limit.realm.registrations_per_minute, 600);
+        assert_eq!(server.rate_limit.realm.discovery_per_second, 200);
+        assert!(server.rate_limit.connection.enabled);
+        assert!(server.rate_limit.validate().is_ok());
+
+        let mut invalid = server.rate_limit.clone();
+        invalid.message.burst_size = 0;
+        assert!(invalid.validate().is_err());
+
+        // 未启用的限流器不校验
+        invalid.message.enabled = false;
+        assert!(invalid.validate().is_ok());
+    }
+
+    #[test]
+    fn test_signaling_spec_history() {
+        let server = signaling::SignalingServerConfig::default();
+        assert_eq!(server.spec_history.max_versions, 20);
+        assert_eq!(server.spec_history.max_age_secs, 30 * 24 * 3600);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [spec_history]
+            max_versions = 5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(server.spec_history.max_versions, 5);
+        assert_eq!(server.spec_history.max_age_secs, 30 * 24 * 3600);
+    }
+
+    #[test]
+    fn test_signaling_spec_notice() {
+        let server = signaling::SignalingServerConfig::default();
+        assert!(server.spec_notice.enabled);
+        assert_eq!(server.spec_notice.dependent_ttl_secs, 3600);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [spec_notice]
+            enabled = false
+            "#,
+        )
+        .unwrap();
+        assert!(!server.spec_notice.enabled);
+        assert_eq!(server.spec_notice.dependent_ttl_secs, 3600);
+    }
+
+    #[test]
+    fn test_signaling_sdp_validation() {
+        let server = signaling::SignalingServerConfig::default();
+        assert!(!server.sdp_validation.enabled);
+        assert_eq!(server.sdp_validation.max_sdp_bytes, 64 * 1024);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [sdp_validation]
+            enabled = true
+            strip_private_candidates = true
+            allowed_codecs = ["opus", "VP8"]
+
+            [[sdp_validation.realm_codecs]]
+            realm_id = 7
+            codecs = ["opus"]
+            "#,
+        )
+        .unwrap();
+        let sdp = &server.sdp_validation;
+        assert!(sdp.enabled);
+        assert!(sdp.strip_private_candidates);
+        assert_eq!(sdp.codecs_for_realm(7), ["opus"]);
+        assert_eq!(sdp.codecs_for_realm(8), ["opus", "VP8"]);
+    }
+
+    #[test]
+    fn test_signaling_authz_hook() {
+        let server = signaling::SignalingServerConfig::default();
+        assert!(!server.authz_hook.enabled);
+        assert!(server.authz_hook.validate().is_ok());
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [authz_hook]
+            enabled = true
+            protocol = "grpc"
+            endpoint = "http://authz:50061"
+            actions = ["relay"]
+            "#,
+        )
+        .unwrap();
+        let hook = &server.authz_hook;
+        assert_eq!(hook.protocol, signaling::AuthzHookProtocol::Grpc);
+        assert_eq!(hook.timeout_ms, 500);
+        assert!(!hook.fail_open);
+        assert!(hook.validate().is_ok());
+
+        let mut invalid = hook.clone();
+        invalid.actions.push("subscribe".to_string());
+        assert!(invalid.validate().is_err());
+        invalid.actions.clear();
+        invalid.endpoint.clear();
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_signaling_resumption() {
+        let server = signaling::SignalingServerConfig::default();
+        assert!(!server.resumption.enabled);
+        assert_eq!(server.resumption.window_secs, 60);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [resumption]
+            enabled = true
+            window_secs = 30
+            "#,
+        )
+        .unwrap();
+        assert_eq!(server.resumption.window_secs, 30);
+        assert_eq!(server.resumption.max_queued_messages, 256);
+        assert!(server.resumption.validate().is_ok());
+
+        let mut invalid = server.resumption.clone();
+        invalid.window_secs = 0;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_signaling_storage() {
+        let signaling: SignalingConfig = toml::from_str(
+            r#"
+            [server]
+            ws_path = "/signaling"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            signaling.storage.backend,
+            signaling::RegistryStorageBackend::Sqlite
+        );
+        assert!(signaling.storage.validate().is_ok());
+
+        let signaling: SignalingConfig = toml::from_str(
+            r#"
+            [server]
+            ws_path = "/signaling"
+
+            [storage]
+            backend = "redis"
+
+            [storage.redis]
+            url = "redis://127.0.0.1:6379/0"
+            "#,
+        )
+        .unwrap();
+        let redis = signaling.storage.redis.as_ref().unwrap();
+        assert_eq!(redis.key_prefix, "actrix:registry:");
+        assert!(signaling.storage.validate().is_ok());
+
+        let mut invalid = signaling.storage.clone();
+        invalid.redis = None;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_signaling_compression() {
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [compression]
+            enabled = true
+            threshold_bytes = 4096
+            "#,
+        )
+        .unwrap();
+        assert!(server.compression.enabled);
+        assert_eq!(server.compression.threshold_bytes, 4096);
+        assert_eq!(server.compression.level, 6);
+        assert!(server.compression.validate().is_ok());
+
+        let mut invalid = server.compression.clone();
+        invalid.level = 10;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_signaling_load_shedding() {
+        let server = signaling::SignalingServerConfig::default();
+        assert!(!server.load_shedding.enabled);
+        assert_eq!(server.load_shedding.queue_depth_threshold, 10_000);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [load_shedding]
+            enabled = true
+            cpu_threshold_percent = 90.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(server.load_shedding.cpu_threshold_percent, 90.0);
+        assert_eq!(server.load_shedding.recovery_ratio, 0.8);
+        assert!(server.load_shedding.validate().is_ok());
+
+        let mut invalid = server.load_shedding.clone();
+        invalid.recovery_ratio = 1.5;
+        assert!(invalid.validate().is_err());
+
+        let mut invalid = server.load_shedding.clone();
+        invalid.cpu_threshold_percent = 0.0;
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_signaling_duplicate_identity() {
+        use signaling::DuplicateIdentityPolicy;
+
+        let server = signaling::SignalingServerConfig::default();
+        assert_eq!(
+            server.duplicate_identity.policy_for_realm(1),
+            (DuplicateIdentityPolicy::Replace, 2)
+        );
+        assert!(server.duplicate_identity.same_fingerprint_replaces);
+
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [duplicate_identity]
+            policy = "reject_new"
+            max_connections = 3
+
+            [[duplicate_identity.realm_overrides]]
+            realm_id = 1001
+            policy = "allow_multiple"
+
+            [[duplicate_identity.realm_overrides]]
+            realm_id = 1002
+            policy = "allow_multiple"
+            max_connections = 5
+            "#,
+        )
+        .unwrap();
+        let config = &server.duplicate_identity;
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.policy_for_realm(1),
+            (DuplicateIdentityPolicy::RejectNew, 3)
+        );
+        assert_eq!(
+            config.policy_for_realm(1001),
+            (DuplicateIdentityPolicy::AllowMultiple, 3)
+        );
+        assert_eq!(
+            config.policy_for_realm(1002),
+            (DuplicateIdentityPolicy::AllowMultiple, 5)
+        );
+
+        let mut invalid = config.clone();
+        invalid.realm_overrides[1].max_connections = Some(0);
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_signaling_registry_encryption() {
+        let mut config = ActrixConfig::default();
+        let mut signaling = signaling::SignalingConfig::default();
+        assert!(signaling.get_registry_kek_source(&config).is_none());
+
+        // 启用但未配置 KEK 时不回退到未启用加密的 KS
+        signaling.server.registry_encryption.enabled = true;
+        assert!(signaling.get_registry_kek_source(&config).is_none());
+
+        // 回退到本地 KS 的 KEK
+        config.services.ks = Some(::ks::KsServiceConfig {
+            kek_env: Some("ACTRIX_KS_KEK".to_string()),
+            ..Default::default()
+        });
+        assert!(matches!(
+            signaling.get_registry_kek_source(&config),
+            Some(::ks::KekSource::Environment(env)) if env == "ACTRIX_KS_KEK"
+        ));
+
+        // 单独配置的 KEK 优先
+        let server: signaling::SignalingServerConfig = toml::from_str(
+            r#"
+            ws_path = "/signaling"
+
+            [registry_encryption]
+            enabled = true
+            kek_file = "/etc/actrix/registry.kek"
+            "#,
+        )
+        .unwrap();
+        signaling.server = server;
+        assert!(matches!(
+            signaling.get_registry_kek_source(&config),
+            Some(::ks::KekSource::File(path)) if path == "/etc/actrix/registry.kek"
+        ));
+    }
+
     #[test]
     fn test_actrix_shared_key() {
         let config = ActrixConfig::default();
@@ -657,6 +1858,26 @@ mod tests {
         assert_eq!(custom_config.get_actrix_shared_key(), "custom-shared-key");
     }
 
+    #[test]
+    fn test_from_file_resolves_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("shared_key");
+        std::fs::write(&secret, "mounted-shared-key\n").unwrap();
+
+        let config = ActrixConfig {
+            actrix_shared_key: format!("file:{}", secret.display()),
+            ..ActrixConfig::default()
+        };
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, config.to_toml().unwrap()).unwrap();
+
+        let loaded = ActrixConfig::from_file(&path).unwrap();
+        assert_eq!(loaded.get_actrix_shared_key(), "mounted-shared-key");
+
+        std::fs::remove_file(&secret).unwrap();
+        assert!(ActrixConfig::from_file(&path).is_err());
+    }
+
     #[test]
     fn test_service_flags() {
         let mut config = ActrixConfig {
@@ -670,6 +1891,7 @@ mod tests {
         config.services.signaling = Some(SignalingConfig {
             server: signaling::SignalingServerConfig::default(),
             dependencies: signaling::SignalingDependencies::default(),
+            storage: signaling::RegistryStorageConfig::default(),
         });
         assert!(!config.is_signaling_enabled());
 
@@ -681,6 +1903,7 @@ mod tests {
         config.services.signaling = Some(SignalingConfig {
             server: signaling::SignalingServerConfig::default(),
             dependencies: signaling::SignalingDependencies::default(),
+            storage: signaling::RegistryStorageConfig::default(),
         });
         assert!(config.is_signaling_enabled());
 
@@ -693,6 +1916,10 @@ mod tests {
         config.services.ais = Some(AisConfig {
             server: ais::AisServerConfig::default(),
             dependencies: ais::AisDependencies::default(),
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
         assert!(!config.is_ais_enabled());
 
@@ -704,6 +1931,10 @@ mod tests {
         config.services.ais = Some(AisConfig {
             server: ais::AisServerConfig::default(),
             dependencies: ais::AisDependencies::default(),
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
         assert!(config.is_ais_enabled());
 
@@ -758,6 +1989,10 @@ mod tests {
         config.services.ais = Some(AisConfig {
             server: ais::AisServerConfig::default(),
             dependencies: ais::AisDependencies { ks: None }, // 未配置 KS
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
 
         // 应该能获取到自动生成的 KS 配置
@@ -786,6 +2021,10 @@ mod tests {
                     client_key: None,
                 }),
             },
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
 
         let ks_config = config
@@ -803,6 +2042,10 @@ mod tests {
         config.services.ais = Some(AisConfig {
             server: ais::AisServerConfig::default(),
             dependencies: ais::AisDependencies { ks: None },
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
         config.enable = ENABLE_AIS; // Enable AIS via bitmask
 
@@ -815,6 +2058,61 @@ mod tests {
         assert!(ks_config.is_none());
     }
 
+    #[test]
+    fn test_ks_grpc_bind_config() {
+        let mut config = ActrixConfig {
+            enable: ENABLE_KS | ENABLE_AIS,
+            ..ActrixConfig::default()
+        };
+        let mut ks = KsServiceConfig::default();
+        ks.grpc.bind.ip = "0.0.0.0".to_string();
+        ks.grpc.bind.port = 6052;
+        config.services.ks = Some(ks);
+        config.services.ais = Some(AisConfig::default());
+
+        // 自动默认依赖跟随配置的监听端口，通配地址替换为回环地址
+        let ks_config = config
+            .services
+            .ais
+            .as_ref()
+            .unwrap()
+            .get_ks_client_config(&config)
+            .unwrap();
+        assert_eq!(ks_config.endpoint, "http://127.0.0.1:6052");
+        assert!(config.ks_grpc_listener_conflict().is_none());
+
+        // 与 HTTP 监听器端口冲突
+        let http_port = config.bind.http.as_ref().unwrap().port;
+        config.services.ks.as_mut().unwrap().grpc.bind.port = http_port;
+        let conflict = config.ks_grpc_listener_conflict().unwrap();
+        assert!(conflict.contains("bind.http"));
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("conflicts with bind.http"))
+        );
+
+        // 新旧 TLS 配置不能同时存在；启用 TLS 后不再自动推导依赖
+        let tls = ::ks::KsGrpcTlsConfig {
+            cert: "/etc/actrix/ks.pem".to_string(),
+            key: "/etc/actrix/ks.key".to_string(),
+            client_ca: "/etc/actrix/ca.pem".to_string(),
+            allowed_client_sans: vec!["ais.actrix.internal".to_string()],
+        };
+        let ks = config.services.ks.as_mut().unwrap();
+        ks.grpc.bind.port = 6052;
+        ks.grpc.bind.tls = Some(tls.clone());
+        ks.grpc_tls = Some(tls);
+        assert!(config.local_ks_client_config().is_none());
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("grpc.bind.tls and legacy services.ks.grpc_tls"))
+        );
+    }
+
     #[test]
     fn test_signaling_auto_ks_config() {
         let mut config = ActrixConfig {
@@ -834,6 +2132,7 @@ mod tests {
                 ks: None,
                 ais: None,
             },
+            storage: signaling::RegistryStorageConfig::default(),
         });
 
         // Signaling 应该能获取到自动生成的 KS 配置
@@ -900,6 +2199,7 @@ mod tests {
                 ks: None,
                 ais: None,
             },
+            storage: signaling::RegistryStorageConfig::default(),
         });
         config.services.ks = None; // No local KS
 
@@ -934,18 +2234,30 @@ mod tests {
                     client_key: None,
                 }),
             },
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
         config.services.ks = Some(KsServiceConfig {
             storage: ::ks::storage::StorageConfig {
                 backend: ::ks::storage::StorageBackend::Sqlite,
                 key_ttl_seconds: 3600,
-                sqlite: Some(::ks::storage::SqliteConfig {}),
+                sqlite: Some(::ks::storage::SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         });
 
         // Should not have bitmask consistency errors (may have other validation errors)
@@ -968,6 +2280,7 @@ mod tests {
         config.services.signaling = Some(SignalingConfig {
             server: signaling::SignalingServerConfig::default(),
             dependencies: signaling::SignalingDependencies::default(),
+            storage: signaling::RegistryStorageConfig::default(),
         });
         config.services.ks = None;
         config.services.ais = None;
@@ -996,6 +2309,7 @@ mod tests {
         config.services.signaling = Some(SignalingConfig {
             server: signaling::SignalingServerConfig::default(),
             dependencies: signaling::SignalingDependencies::default(),
+            storage: signaling::RegistryStorageConfig::default(),
         });
         config.services.ks = Some(KsServiceConfig {
             ..Default::default()
@@ -1003,9 +2317,476 @@ mod tests {
         config.services.ais = Some(AisConfig {
             server: ais::AisServerConfig::default(),
             dependencies: ais::AisDependencies::default(),
+            revocation: Default::default(),
+            serial_number: Default::default(),
+            rate_limit: Default::default(),
+            psk_rotation: Default::default(),
         });
 
         let result = config.validate();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_dev_network_emulation_config() {
+        let config: DevConfig = toml::from_str(
+            r#"
+            [network_emulation]
+            enabled = true
+            latency_ms = 120
+            jitter_ms = 30
+            drop_rate = 0.05
+            stun = false
+            "#,
+        )
+        .expect("config should parse");
+
+        let emulation = &config.network_emulation;
+        assert!(emulation.enabled);
+        assert_eq!(emulation.latency_ms, 120);
+        assert_eq!(emulation.jitter_ms, 30);
+        assert_eq!(emulation.drop_rate, 0.05);
+        assert!(emulation.signaling_relay);
+        assert!(!emulation.stun);
+        assert!(!ActrixConfig::default().dev.network_emulation.enabled);
+    }
+
+    #[test]
+    fn test_validate_route_prefixes() {
+        let route_errors = |config: &ActrixConfig| -> Vec<String> {
+            config
+                .validate()
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.contains("route prefix"))
+                .collect()
+        };
+        let mut config = ActrixConfig::default();
+        assert!(route_errors(&config).is_empty());
+
+        config.http.route_prefixes.ais = "/signaling/ais".to_string();
+        assert_eq!(route_errors(&config).len(), 1);
+
+        config.http.route_prefixes.ais = "/api/ais".to_string();
+        config.http.route_prefixes.ks = "/admin/ks".to_string();
+        assert!(route_errors(&config)[0].contains("reserved path '/admin'"));
+
+        config.http.route_prefixes.ks = "/api/ks".to_string();
+        let mut signaling = SignalingConfig::default();
+        signaling.server.ws_path = "/api/signaling".to_string();
+        config.services.signaling = Some(signaling);
+        assert!(route_errors(&config).is_empty());
+        assert_eq!(config.route_prefixes().signaling, "/api/signaling");
+    }
+
+    #[test]
+    fn test_dev_network_emulation_rejected_in_prod() {
+        let mut config = ActrixConfig::default();
+        config.dev.network_emulation.enabled = true;
+        config.dev.network_emulation.drop_rate = 1.5;
+
+        let errors = config.validate().err().unwrap_or_default();
+        assert!(errors.iter().any(|e| e.contains("drop_rate")));
+        assert!(!errors.iter().any(|e| e.contains("prod environment")));
+
+        config.env = "prod".to_string();
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("dev.network_emulation must not be enabled"))
+        );
+    }
+
+    #[test]
+    fn test_dev_lan_discovery() {
+        let dev: DevConfig = toml::from_str("lan_discovery = true").unwrap();
+        assert!(dev.lan_discovery);
+        assert!(!DevConfig::default().lan_discovery);
+
+        let mut config = ActrixConfig {
+            dev,
+            ..Default::default()
+        };
+        assert!(config.is_lan_discovery_enabled());
+
+        config.env = "test".to_string();
+        assert!(!config.is_lan_discovery_enabled());
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("dev.lan_discovery requires env"))
+        );
+    }
+
+    #[test]
+    fn test_reload_config() {
+        let config = ActrixConfig::default();
+        assert!(!config.reload.watch);
+        assert_eq!(config.reload.watch_interval_secs, 5);
+
+        let reload: ReloadConfig = toml::from_str("watch = true\nwatch_interval_secs = 0").unwrap();
+        let config = ActrixConfig {
+            reload,
+            ..Default::default()
+        };
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("Reload configuration error"))
+        );
+    }
+
+    #[test]
+    fn test_log_retention_config() {
+        let log: LogConfig = toml::from_str(
+            "output = \"file\"\nrotate = true\nmax_file_size_mb = 100\nmax_files = 7\ncompress = true",
+        )
+        .unwrap();
+        assert!(log.has_retention_policy());
+        assert_eq!(log.max_age_days, 0);
+        assert!(!LogConfig::default().has_retention_policy());
+
+        // 未启用轮转时给出警告
+        let mut config = ActrixConfig::default();
+        config.observability.log.max_files = 7;
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("Warning:") && e.contains("max_files"))
+        );
+    }
+
+    #[test]
+    fn test_redirect_to_https_requires_https() {
+        let mut config = ActrixConfig::default();
+        config.bind.http.as_mut().unwrap().redirect_to_https = true;
+        let has_redirect_error = |config: &ActrixConfig| {
+            config
+                .validate()
+                .err()
+                .unwrap_or_default()
+                .iter()
+                .any(|e| e.contains("redirect_to_https"))
+        };
+        assert!(!has_redirect_error(&config));
+
+        config.bind.https = None;
+        assert!(has_redirect_error(&config));
+    }
+
+    #[test]
+    fn test_dev_mock_dependencies() {
+        let dev: DevConfig = toml::from_str("mock_dependencies = true").unwrap();
+        assert!(dev.mock_dependencies);
+        assert!(!DevConfig::default().mock_dependencies);
+
+        let mut config = ActrixConfig {
+            enable: ENABLE_SIGNALING,
+            dev,
+            ..Default::default()
+        };
+        config.bind.https = None;
+        config.services.signaling = Some(SignalingConfig::default());
+        assert!(config.is_dev_mock_enabled());
+
+        // 模拟依赖代替本地 KS / AIS，无需显式配置 dependencies
+        let errors = config.validate().err().unwrap_or_default();
+        assert!(!errors.iter().any(|e| e.contains("dependencies.ks")));
+        assert!(!errors.iter().any(|e| e.contains("dependencies.ais")));
+
+        let signaling = config.services.signaling.clone().unwrap();
+        assert_eq!(
+            signaling.get_ks_client_config(&config).unwrap().endpoint,
+            "http://127.0.0.1:50052"
+        );
+        assert_eq!(
+            signaling.get_ais_client_config(&config).unwrap().endpoint,
+            format!(
+                "http://127.0.0.1:{}",
+                config.bind.http.as_ref().unwrap().port
+            )
+        );
+
+        config.env = "prod".to_string();
+        assert!(!config.is_dev_mock_enabled());
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("dev.mock_dependencies requires env"))
+        );
+        assert!(errors.iter().any(|e| e.contains("dependencies.ks")));
+    }
+
+    #[test]
+    fn test_ais_key_rotation_config() {
+        let server: ais::AisServerConfig = toml::from_str(
+            r#"
+            enable_periodic_rotation = true
+            signing_key_rotation_interval_secs = 3600
+            "#,
+        )
+        .unwrap();
+
+        assert!(server.enable_periodic_rotation);
+        assert_eq!(server.encryption_key_rotation_interval_secs, 86400);
+        assert_eq!(server.signing_key_rotation_interval_secs, 3600);
+        assert!(!ais::AisServerConfig::default().enable_periodic_rotation);
+    }
+
+    #[test]
+    fn test_ais_revocation_config() {
+        let revocation: ais::AisRevocationConfig = toml::from_str(
+            r#"
+            false_positive_rate = 0.0001
+            [redis]
+            url = "redis://127.0.0.1:6379/1"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(revocation.false_positive_rate, 0.0001);
+        assert_eq!(revocation.redis.as_ref().unwrap().key_prefix, "actrix:ais:");
+        assert!(revocation.validate().is_ok());
+
+        let defaults = ais::AisRevocationConfig::default();
+        assert_eq!(defaults.false_positive_rate, 1e-6);
+        assert!(defaults.redis.is_none());
+
+        for rate in [0.0, 1.0, -0.5] {
+            let invalid = ais::AisRevocationConfig {
+                false_positive_rate: rate,
+                redis: None,
+            };
+            assert!(invalid.validate().is_err());
+        }
+
+        let ais_client: signaling::AisClientConfig =
+            toml::from_str(r#"endpoint = "http://127.0.0.1:8080""#).unwrap();
+        assert_eq!(ais_client.revocation_refresh_interval_secs, 30);
+    }
+
+    #[test]
+    fn test_ais_psk_rotation_config() {
+        let psk_rotation: ais::AisPskRotationConfig = toml::from_str("overlap_secs = 0").unwrap();
+        assert_eq!(psk_rotation.overlap_secs, 0);
+        assert_eq!(psk_rotation.challenge_ttl_secs, 60);
+        assert!(psk_rotation.validate().is_ok());
+
+        let invalid = ais::AisPskRotationConfig {
+            challenge_ttl_secs: 0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_ais_rate_limit_config() {
+        let rate_limit: ais::AisRateLimitConfig = toml::from_str(
+            r#"
+            [manufacturer]
+            enabled = true
+            per_minute = 60
+            burst_size = 10
+            "#,
+        )
+        .unwrap();
+        assert!(!rate_limit.realm.enabled);
+        assert_eq!(rate_limit.realm.per_minute, 600);
+        assert!(rate_limit.manufacturer.enabled);
+        assert_eq!(rate_limit.manufacturer.burst_size, 10);
+        assert!(rate_limit.validate().is_ok());
+
+        let mut invalid = ais::AisRateLimitConfig::default();
+        invalid.realm.enabled = true;
+        invalid.realm.burst_size = 0;
+        assert!(invalid.validate().is_err());
+
+        // 未启用的层不校验
+        invalid.realm.enabled = false;
+        assert!(invalid.validate().is_ok());
+    }
+
+    #[test]
+    fn test_ais_serial_number_config() {
+        let serial_number: ais::AisSerialNumberConfig = toml::from_str(
+            r#"
+            node_id = 3
+            clock_regression = "reject"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(serial_number.node_id, Some(3));
+        assert_eq!(
+            serial_number.clock_regression,
+            ais::ClockRegressionPolicy::Reject
+        );
+        assert_eq!(serial_number.max_clock_wait_ms, 5000);
+        assert!(serial_number.validate().is_ok());
+
+        let defaults = ais::AisSerialNumberConfig::default();
+        assert!(defaults.node_id.is_none());
+        assert_eq!(defaults.clock_regression, ais::ClockRegressionPolicy::Wait);
+
+        let invalid = ais::AisSerialNumberConfig {
+            node_id: Some(32),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        let invalid = ais::AisSerialNumberConfig {
+            max_clock_wait_ms: 0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_nonce_storage_config() {
+        let config: NonceStorageConfig = toml::from_str(
+            r#"
+            backend = "redis"
+            [redis]
+            url = "redis://127.0.0.1:6379/0"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.backend, nonce::NonceBackend::Redis);
+        assert_eq!(config.redis.as_ref().unwrap().key_prefix, "actrix:nonce:");
+        assert!(config.validate().is_ok());
+        // Redis 依赖键 TTL，不启动过期清理
+        assert_eq!(config.cleanup_interval(), None);
+        assert_eq!(
+            ActrixConfig::default().nonce_storage.backend,
+            nonce::NonceBackend::Sqlite
+        );
+        assert_eq!(
+            ActrixConfig::default().nonce_storage.cleanup_interval(),
+            Some(std::time::Duration::from_secs(300))
+        );
+
+        let missing_redis: NonceStorageConfig = toml::from_str(r#"backend = "redis""#).unwrap();
+        assert!(missing_redis.validate().is_err());
+
+        let mut config = ActrixConfig::default();
+        config.nonce_storage.backend = nonce::NonceBackend::Memory;
+        config.env = "prod".to_string();
+        let errors = config.validate().unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("nonce_storage.backend = \"memory\""))
+        );
+    }
+    #[test]
+    fn test_relay_port_range_conflicts() {
+        let mut config = ActrixConfig::default();
+        assert_eq!(config.turn.relay_port_bounds(), Ok((49152, 65535)));
+
+        // ICE 监听端口落在中继范围内
+        config.bind.ice.port = 50000;
+        let conflict = config.relay_port_listener_conflict(None).unwrap();
+        assert!(conflict.contains("bind.ice.port = 50000"));
+        assert!(conflict.contains("suggested relay_port_range = \"1024-49999\""));
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .iter()
+                .any(|e| e.contains("bind.ice.port"))
+        );
+
+        // 与 Linux 默认临时端口范围重叠，建议避开临时端口与监听端口
+        config.bind.ice.port = 3478;
+        assert!(config.relay_port_listener_conflict(None).is_none());
+        let conflict = config
+            .relay_port_ephemeral_conflict(Some((32768, 60999)))
+            .unwrap();
+        assert!(conflict.contains("32768-60999"));
+        assert!(conflict.contains("suggested relay_port_range = \"3479-32767\""));
+
+        config.turn.relay_port_range = "61000-65535".to_string();
+        assert!(
+            config
+                .relay_port_ephemeral_conflict(Some((32768, 60999)))
+                .is_none()
+        );
+
+        config.turn.relay_port_range = "65535-1024".to_string();
+        assert!(config.turn.relay_port_bounds().is_err());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_suggest_relay_port_range() {
+        assert_eq!(
+            turn::suggest_relay_port_range(Some((32768, 60999)), &[3478]),
+            Some((3479, 32767))
+        );
+        assert_eq!(
+            turn::suggest_relay_port_range(Some((1024, 65535)), &[]),
+            None
+        );
+        assert_eq!(
+            turn::suggest_relay_port_range(None, &[]),
+            Some((1024, 65535))
+        );
+        assert_eq!(
+            turn::parse_port_range(" 40000 - 40100 "),
+            Some((40000, 40100))
+        );
+        assert_eq!(turn::parse_port_range("0-100"), None);
+    }
+
+    #[test]
+    fn test_memory_storage_mode() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            storage: StorageMode,
+        }
+        let wrapper: Wrapper = toml::from_str(r#"storage = "memory""#).unwrap();
+        assert_eq!(wrapper.storage, StorageMode::Memory);
+        assert_eq!(ActrixConfig::default().storage, StorageMode::Sqlite);
+
+        let config = ActrixConfig {
+            storage: StorageMode::Memory,
+            ..ActrixConfig::default()
+        };
+
+        // sqlite nonce 后端改用内存，redis 后端保持不变
+        assert_eq!(
+            config.nonce_storage_config().backend,
+            nonce::NonceBackend::Memory
+        );
+        let mut redis = config.clone();
+        redis.nonce_storage.backend = nonce::NonceBackend::Redis;
+        assert_eq!(
+            redis.nonce_storage_config().backend,
+            nonce::NonceBackend::Redis
+        );
+
+        // 服务私有数据库与 KS sqlite 存储、审计日志改用内存数据库
+        assert_eq!(
+            config.sqlite_location("ais_keys.db"),
+            SqliteLocation::Memory
+        );
+        assert_eq!(
+            ActrixConfig::default().sqlite_location("ais_keys.db"),
+            SqliteLocation::File(ActrixConfig::default().sqlite_path.join("ais_keys.db"))
+        );
+        let mut ks = config.clone();
+        ks.services.ks = Some(Default::default());
+        let ks_config = ks.ks_service_config().unwrap();
+        assert!(ks_config.storage.sqlite.unwrap().in_memory);
+        assert_eq!(ks_config.audit.sink, ::ks::config::AuditSinkType::Memory);
+
+        let mut prod = config;
+        prod.env = "prod".to_string();
+        let errors = prod.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.contains("storage = \"memory\"")));
+    }
 }
@@ -0,0 +1,94 @@
+//! Nonce 存储配置
+//!
+//! 统一选择防重放 nonce 的存储后端，KS、Supervisord 等服务共用同一份配置。
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Nonce 存储配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NonceStorageConfig {
+    /// 存储后端类型
+    ///
+    /// - "sqlite": `{sqlite_path}/nonce.db`（默认）
+    /// - "memory": 进程内存储，重启后丢失，仅适用于开发与测试
+    /// - "redis": 多节点共享，需要编译时启用 `nonce-redis` feature
+    #[serde(default)]
+    pub backend: NonceBackend,
+
+    /// Redis 配置（当 backend = "redis" 时必需）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis: Option<RedisNonceConfig>,
+
+    /// 过期 nonce 清理间隔（秒），0 表示不清理
+    ///
+    /// 仅对 sqlite / memory 后端生效，Redis 后端由键 TTL 自动过期
+    #[serde(default = "default_cleanup_interval_secs")]
+    pub cleanup_interval_secs: u64,
+}
+
+impl Default for NonceStorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: NonceBackend::Sqlite,
+            redis: None,
+            cleanup_interval_secs: default_cleanup_interval_secs(),
+        }
+    }
+}
+
+fn default_cleanup_interval_secs() -> u64 {
+    300
+}
+
+/// Nonce 存储后端类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NonceBackend {
+    /// 进程内存储
+    Memory,
+    /// SQLite 数据库
+    #[default]
+    Sqlite,
+    /// Redis
+    Redis,
+}
+
+/// Redis nonce 存储配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisNonceConfig {
+    /// 连接地址，如 `redis://127.0.0.1:6379/0`
+    pub url: String,
+
+    /// 键前缀，实际键为 `{key_prefix}{context}:{nonce}`
+    #[serde(default = "default_redis_key_prefix")]
+    pub key_prefix: String,
+}
+
+fn default_redis_key_prefix() -> String {
+    "actrix:nonce:".to_string()
+}
+
+impl NonceStorageConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.backend == NonceBackend::Redis {
+            let redis = self
+                .redis
+                .as_ref()
+                .ok_or_else(|| "redis config is required when backend = \"redis\"".to_string())?;
+            if redis.url.trim().is_empty() {
+                return Err("redis.url cannot be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// 过期清理间隔，Redis 后端或 `cleanup_interval_secs = 0` 时返回 None
+    pub fn cleanup_interval(&self) -> Option<Duration> {
+        if self.backend == NonceBackend::Redis || self.cleanup_interval_secs == 0 {
+            return None;
+        }
+        Some(Duration::from_secs(self.cleanup_interval_secs))
+    }
+}
@@ -0,0 +1,41 @@
+//! 配置热加载
+//!
+//! 进程始终响应 SIGHUP 重新加载配置文件；启用 `watch` 后还会定期检查配置文件的修改时间。
+//! 新配置校验通过后只应用可在运行时安全生效的字段，其余字段的变化仅提示需要重启。
+
+use serde::{Deserialize, Serialize};
+
+/// 配置热加载配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// 是否监视配置文件变化（SIGHUP 不受此开关影响）
+    #[serde(default)]
+    pub watch: bool,
+
+    /// 检查配置文件修改时间的间隔（秒）
+    #[serde(default = "default_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+}
+
+fn default_watch_interval_secs() -> u64 {
+    5
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            watch_interval_secs: default_watch_interval_secs(),
+        }
+    }
+}
+
+impl ReloadConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.watch && self.watch_interval_secs == 0 {
+            return Err("watch_interval_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,420 @@
+//! 配置 Schema 导出与默认配置生成
+//!
+//! 字段元数据（名称、类型、文档注释、可选/默认）由 build.rs 从配置结构体源码提取，
+//! 与 [`ActrixConfig::default`] 的取值结合，生成：
+//! - [`default_config_toml`]：带完整注释的默认配置 TOML，可选配置段以注释形式给出
+//! - [`json_schema`]：JSON Schema（draft-07），可用于编辑器补全与 CI 校验
+//!
+//! 供 `actrix config generate` 使用。
+
+use super::ActrixConfig;
+use serde_json::{Map, Value, json};
+use std::fmt::Write as _;
+
+/// 嵌套层级上限（防止类型自引用导致无限递归）
+const MAX_DEPTH: usize = 16;
+
+/// 配置结构体
+#[derive(Debug)]
+pub(crate) struct ConfigStruct {
+    name: &'static str,
+    doc: &'static str,
+    fields: &'static [ConfigField],
+}
+
+/// 配置字段
+#[derive(Debug)]
+pub(crate) struct ConfigField {
+    name: &'static str,
+    doc: &'static str,
+    kind: FieldKind,
+    /// `Option<T>`
+    optional: bool,
+    /// 带 `#[serde(default)]`，可以省略
+    has_default: bool,
+}
+
+/// 以字符串表示的枚举
+#[derive(Debug)]
+pub(crate) struct ConfigEnum {
+    name: &'static str,
+    doc: &'static str,
+    variants: &'static [&'static str],
+}
+
+/// 字段类型
+#[derive(Debug)]
+pub(crate) enum FieldKind {
+    String,
+    Boolean,
+    Integer,
+    Number,
+    Struct(&'static str),
+    Enum(&'static str),
+    Array(&'static FieldKind),
+    Map(&'static FieldKind),
+    /// 无法从源码确定的类型
+    Any,
+}
+
+include!(concat!(env!("OUT_DIR"), "/config_schema.rs"));
+
+fn find_struct(name: &str) -> Option<&'static ConfigStruct> {
+    CONFIG_STRUCTS.iter().find(|s| s.name == name)
+}
+
+fn find_enum(name: &str) -> Option<&'static ConfigEnum> {
+    CONFIG_ENUMS.iter().find(|e| e.name == name)
+}
+
+/// 按配置路径查找字段的结果
+#[derive(Debug)]
+pub(crate) enum PathLookup {
+    /// 已知字段及其类型
+    Field(&'static FieldKind),
+    /// 所在配置段已知，但没有该字段
+    Unknown { section: String, field: String },
+    /// 路径经过类型未知的字段或映射表，无法校验
+    Opaque,
+}
+
+/// 查找点分路径（如 `bind.http.port`）对应的字段
+pub(crate) fn lookup_path(path: &[&str]) -> PathLookup {
+    let Some(mut item) = find_struct("ActrixConfig") else {
+        return PathLookup::Opaque;
+    };
+    let mut section = String::new();
+    for (index, segment) in path.iter().enumerate() {
+        let Some(field) = item.fields.iter().find(|f| f.name == *segment) else {
+            return PathLookup::Unknown {
+                section: if section.is_empty() {
+                    "<root>".to_string()
+                } else {
+                    section
+                },
+                field: segment.to_string(),
+            };
+        };
+        if index + 1 == path.len() {
+            return PathLookup::Field(&field.kind);
+        }
+        match &field.kind {
+            FieldKind::Struct(name) => match find_struct(name) {
+                Some(inner) => item = inner,
+                None => return PathLookup::Opaque,
+            },
+            _ => return PathLookup::Opaque,
+        }
+        if !section.is_empty() {
+            section.push('.');
+        }
+        section.push_str(segment);
+    }
+    PathLookup::Opaque
+}
+
+fn default_values() -> toml::Table {
+    ActrixConfig::default()
+        .to_toml()
+        .ok()
+        .and_then(|content| toml::from_str::<toml::Table>(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 生成带注释的默认配置 TOML
+pub fn default_config_toml() -> String {
+    let mut out = String::new();
+    out.push_str("# Actrix configuration\n");
+    out.push_str("# Generated by `actrix config generate` from the configuration structs.\n");
+    out.push_str("# Commented-out entries are optional or have no default value.\n\n");
+    if let Some(root) = find_struct("ActrixConfig") {
+        render_struct(&mut out, root, Some(&default_values()), "", false, 0);
+    }
+    out
+}
+
+/// 生成 JSON Schema
+pub fn json_schema() -> Value {
+    let defaults = to_json(&toml::Value::Table(default_values()));
+    let mut schema = match find_struct("ActrixConfig") {
+        Some(root) => struct_schema(root, Some(&defaults), 0),
+        None => json!({ "type": "object" }),
+    };
+    if let Some(object) = schema.as_object_mut() {
+        object.insert(
+            "$schema".to_string(),
+            json!("http://json-schema.org/draft-07/schema#"),
+        );
+        object.insert("title".to_string(), json!("ActrixConfig"));
+    }
+    schema
+}
+
+fn struct_schema(item: &ConfigStruct, defaults: Option<&Value>, depth: usize) -> Value {
+    if depth > MAX_DEPTH {
+        return json!({});
+    }
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in item.fields {
+        let default = defaults.and_then(|d| d.get(field.name));
+        let mut schema = kind_schema(&field.kind, default, depth + 1);
+        if let Some(object) = schema.as_object_mut() {
+            if !field.doc.is_empty() {
+                object.insert("description".to_string(), json!(field.doc));
+            }
+            if let Some(default) = default
+                && !matches!(field.kind, FieldKind::Struct(_))
+            {
+                object.insert("default".to_string(), default.clone());
+            }
+        }
+        if !field.optional && !field.has_default {
+            required.push(json!(field.name));
+        }
+        properties.insert(field.name.to_string(), schema);
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+    });
+    if !item.doc.is_empty() {
+        schema["description"] = json!(item.doc);
+    }
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+fn kind_schema(kind: &FieldKind, default: Option<&Value>, depth: usize) -> Value {
+    match kind {
+        FieldKind::String => json!({ "type": "string" }),
+        FieldKind::Boolean => json!({ "type": "boolean" }),
+        FieldKind::Integer => json!({ "type": "integer" }),
+        FieldKind::Number => json!({ "type": "number" }),
+        FieldKind::Enum(name) => match find_enum(name) {
+            Some(item) => {
+                let mut schema = json!({ "type": "string", "enum": item.variants });
+                if !item.doc.is_empty() {
+                    schema["description"] = json!(item.doc);
+                }
+                schema
+            }
+            None => json!({ "type": "string" }),
+        },
+        FieldKind::Array(inner) => json!({
+            "type": "array",
+            "items": kind_schema(inner, None, depth + 1),
+        }),
+        FieldKind::Map(inner) => json!({
+            "type": "object",
+            "additionalProperties": kind_schema(inner, None, depth + 1),
+        }),
+        FieldKind::Struct(name) => match find_struct(name) {
+            Some(item) => struct_schema(item, default, depth),
+            None => json!({ "type": "object" }),
+        },
+        FieldKind::Any => json!({}),
+    }
+}
+
+fn to_json(value: &toml::Value) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// 输出一个配置段的字段
+///
+/// 先输出标量字段，再输出子配置段（TOML 要求表内键位于子表之前）；
+/// `commented` 为 true 或没有默认值的配置段整体以注释形式输出
+fn render_struct(
+    out: &mut String,
+    item: &ConfigStruct,
+    defaults: Option<&toml::Table>,
+    prefix: &str,
+    commented: bool,
+    depth: usize,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let comment = if commented { "# " } else { "" };
+
+    for field in item.fields.iter().filter(|f| !is_section(&f.kind)) {
+        push_doc(out, field.doc, commented);
+        match defaults.and_then(|d| d.get(field.name)) {
+            Some(value) => {
+                let _ = writeln!(out, "{comment}{} = {}", field.name, inline(value));
+            }
+            None => {
+                let _ = writeln!(out, "# {} = {}", field.name, placeholder(&field.kind));
+            }
+        }
+    }
+
+    for field in item.fields.iter().filter(|f| is_section(&f.kind)) {
+        let path = format!("{prefix}{}", field.name);
+        let default = defaults.and_then(|d| d.get(field.name));
+        out.push('\n');
+        push_doc(out, field.doc, commented);
+        match &field.kind {
+            FieldKind::Struct(name) => {
+                let Some(inner) = find_struct(name) else {
+                    continue;
+                };
+                let table = default.and_then(toml::Value::as_table);
+                let section_commented = commented || table.is_none();
+                let _ = writeln!(out, "{}[{path}]", if section_commented { "# " } else { "" });
+                render_struct(
+                    out,
+                    inner,
+                    table,
+                    &format!("{path}."),
+                    section_commented,
+                    depth + 1,
+                );
+            }
+            FieldKind::Array(FieldKind::Struct(name)) => {
+                let Some(inner) = find_struct(name) else {
+                    continue;
+                };
+                let entries: Vec<&toml::Table> = default
+                    .and_then(toml::Value::as_array)
+                    .map(|items| items.iter().filter_map(toml::Value::as_table).collect())
+                    .unwrap_or_default();
+                if entries.is_empty() || commented {
+                    let _ = writeln!(out, "# [[{path}]]");
+                    render_struct(out, inner, None, &format!("{path}."), true, depth + 1);
+                } else {
+                    for entry in entries {
+                        let _ = writeln!(out, "[[{path}]]");
+                        render_struct(
+                            out,
+                            inner,
+                            Some(entry),
+                            &format!("{path}."),
+                            false,
+                            depth + 1,
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_section(kind: &FieldKind) -> bool {
+    match kind {
+        FieldKind::Struct(name) => find_struct(name).is_some(),
+        FieldKind::Array(FieldKind::Struct(name)) => find_struct(name).is_some(),
+        _ => false,
+    }
+}
+
+fn push_doc(out: &mut String, doc: &str, commented: bool) {
+    for line in doc.lines() {
+        let line = line.trim_end();
+        match (commented, line.is_empty()) {
+            (true, true) => out.push_str("# #\n"),
+            (true, false) => {
+                let _ = writeln!(out, "# # {line}");
+            }
+            (false, true) => out.push_str("#\n"),
+            (false, false) => {
+                let _ = writeln!(out, "# {line}");
+            }
+        }
+    }
+}
+
+/// 行内 TOML 表示（数组与表展开为行内形式）
+fn inline(value: &toml::Value) -> String {
+    match value {
+        toml::Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(inline).collect::<Vec<_>>().join(", ")
+        ),
+        toml::Value::Table(table) if table.is_empty() => "{}".to_string(),
+        toml::Value::Table(table) => format!(
+            "{{ {} }}",
+            table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", inline_key(key), inline(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        scalar => scalar.to_string(),
+    }
+}
+
+fn inline_key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+/// 没有默认值的字段的示例值
+fn placeholder(kind: &FieldKind) -> String {
+    match kind {
+        FieldKind::Boolean => "false".to_string(),
+        FieldKind::Integer => "0".to_string(),
+        FieldKind::Number => "0.0".to_string(),
+        FieldKind::Enum(name) => find_enum(name)
+            .and_then(|item| item.variants.first())
+            .map_or_else(|| "\"\"".to_string(), |variant| format!("\"{variant}\"")),
+        FieldKind::Array(_) => "[]".to_string(),
+        FieldKind::Map(_) | FieldKind::Struct(_) => "{}".to_string(),
+        FieldKind::String | FieldKind::Any => "\"\"".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_toml_parses_back() {
+        let generated = default_config_toml();
+        assert!(generated.contains("\n[bind]\n"));
+        assert!(generated.contains("# [supervisor]"));
+
+        let parsed = ActrixConfig::from_toml(&generated).unwrap();
+        let default = ActrixConfig::default();
+        assert_eq!(parsed.enable, default.enable);
+        assert_eq!(parsed.name, default.name);
+        assert_eq!(parsed.actrix_shared_key, default.actrix_shared_key);
+        assert!(parsed.supervisor.is_none());
+    }
+
+    #[test]
+    fn test_json_schema_describes_fields() {
+        let schema = json_schema();
+        assert_eq!(schema["title"], "ActrixConfig");
+
+        let properties = &schema["properties"];
+        assert_eq!(properties["enable"]["type"], "integer");
+        assert_eq!(properties["bind"]["type"], "object");
+        assert!(properties["bind"]["properties"]["http"].is_object());
+        assert_eq!(properties["storage"]["type"], "string");
+        assert!(
+            properties["storage"]["enum"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("memory"))
+        );
+        assert!(
+            schema["required"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("name"))
+        );
+    }
+}
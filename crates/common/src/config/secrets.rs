@@ -0,0 +1,126 @@
+//! 机密字段的环境变量插值与文件引用
+//!
+//! 以下字段在 [`ActrixConfig::from_file`](super::ActrixConfig::from_file) 加载后解析，
+//! 便于 Docker/K8s 通过环境变量或挂载的 Secret 文件注入，而不必写入 TOML：
+//! - `actrix_shared_key`
+//! - `services.ks.kek`
+//! - `services.signaling.server.registry_encryption.kek`
+//! - `supervisor.client.shared_secret`
+//!
+//! 支持两种写法：
+//! - `file:/run/secrets/actrix_shared_key`：读取文件内容（去除首尾空白）
+//! - `${ACTRIX_SHARED_KEY}`：替换为环境变量的值，可与普通文本混用，`$${` 表示字面量 `${`
+//!
+//! 文件路径中同样可以使用 `${VAR}`。引用的环境变量未设置或文件不可读时加载失败。
+
+use std::path::Path;
+
+/// 文件引用前缀
+pub const FILE_PREFIX: &str = "file:";
+
+/// 解析单个机密字段
+pub fn resolve_secret(field: &str, value: &str) -> Result<String, String> {
+    resolve_with(field, value, |name| std::env::var(name).ok())
+}
+
+fn resolve_with(
+    field: &str,
+    value: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let interpolated = interpolate(value, &lookup).map_err(|e| format!("{field}: {e}"))?;
+    match interpolated.strip_prefix(FILE_PREFIX) {
+        Some(path) => read_secret_file(Path::new(path.trim())).map_err(|e| format!("{field}: {e}")),
+        None => Ok(interpolated),
+    }
+}
+
+/// 替换 `${VAR}`，`$${` 转义为字面量 `${`
+fn interpolate(value: &str, lookup: &impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix("$${") {
+            output.push_str("${");
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("${") {
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("unterminated variable reference in '{value}'"))?;
+            let name = &after[..end];
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!("invalid environment variable name '{name}'"));
+            }
+            let resolved =
+                lookup(name).ok_or_else(|| format!("environment variable {name} is not set"))?;
+            output.push_str(&resolved);
+            rest = &after[end + 1..];
+        } else {
+            output.push('$');
+            rest = &tail[1..];
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn read_secret_file(path: &Path) -> Result<String, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read secret file {}: {e}", path.display()))?;
+    let secret = content.trim();
+    if secret.is_empty() {
+        return Err(format!("secret file {} is empty", path.display()));
+    }
+    Ok(secret.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "ACTRIX_SHARED_KEY" => Some("s3cret".to_string()),
+            "SECRETS_DIR" => Some("/nonexistent".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_interpolation() {
+        assert_eq!(
+            resolve_with("key", "${ACTRIX_SHARED_KEY}", lookup).unwrap(),
+            "s3cret"
+        );
+        assert_eq!(
+            resolve_with("key", "pre-${ACTRIX_SHARED_KEY}-$${LITERAL}$5", lookup).unwrap(),
+            "pre-s3cret-${LITERAL}$5"
+        );
+        // 没有引用时原样保留
+        assert_eq!(resolve_with("key", "plain", lookup).unwrap(), "plain");
+
+        let err = resolve_with("actrix_shared_key", "${MISSING}", lookup).unwrap_err();
+        assert!(err.contains("actrix_shared_key") && err.contains("MISSING"));
+        assert!(resolve_with("key", "${UNTERMINATED", lookup).is_err());
+        assert!(resolve_with("key", "${BAD-NAME}", lookup).is_err());
+    }
+
+    #[test]
+    fn test_file_indirection() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared_key");
+        std::fs::write(&path, "from-file\n").unwrap();
+
+        let value = format!("file:{}", path.display());
+        assert_eq!(resolve_with("key", &value, lookup).unwrap(), "from-file");
+
+        // 文件路径中可以使用环境变量
+        let err = resolve_with("key", "file:${SECRETS_DIR}/key", lookup).unwrap_err();
+        assert!(err.contains("/nonexistent/key"));
+
+        std::fs::write(&path, "  \n").unwrap();
+        assert!(resolve_with("key", &value, lookup).is_err());
+    }
+}
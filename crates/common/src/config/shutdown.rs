@@ -0,0 +1,36 @@
+//! 优雅关闭配置
+
+use serde::{Deserialize, Serialize};
+
+/// 优雅关闭配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// 收到关闭信号后等待服务任务退出的最长时间（秒），超时后强制中止剩余任务
+    ///
+    /// 应小于进程管理器的停止超时（systemd `TimeoutStopSec`、K8s
+    /// `terminationGracePeriodSeconds`），否则进程可能在汇总日志输出前被强制终止
+    #[serde(default = "default_grace_period_secs")]
+    pub grace_period_secs: u64,
+}
+
+fn default_grace_period_secs() -> u64 {
+    30
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: default_grace_period_secs(),
+        }
+    }
+}
+
+impl ShutdownConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.grace_period_secs == 0 {
+            return Err("grace_period_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
@@ -16,17 +16,541 @@ pub struct SignalingConfig {
     /// Signaling 的依赖服务配置
     #[serde(default)]
     pub dependencies: SignalingDependencies,
+
+    /// 服务注册表持久化存储
+    #[serde(default)]
+    pub storage: RegistryStorageConfig,
+}
+
+/// 服务注册表持久化存储配置
+///
+/// 内存中的 ServiceRegistry 是主存储，持久化层用于重启恢复与兼容性协商时查询 proto spec
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RegistryStorageConfig {
+    /// 存储后端类型
+    ///
+    /// - "sqlite": `{sqlite_path}/signaling_cache.db`（默认，`storage = "memory"` 时为内存数据库）
+    /// - "redis": 多个 Signaling 节点共享，需要编译时启用 `signaling-redis` feature
+    #[serde(default)]
+    pub backend: RegistryStorageBackend,
+
+    /// Redis 配置（当 backend = "redis" 时必需）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis: Option<RedisRegistryConfig>,
+}
+
+/// 服务注册表存储后端类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryStorageBackend {
+    /// SQLite 数据库
+    #[default]
+    Sqlite,
+    /// Redis
+    Redis,
+}
+
+/// Redis 注册表存储配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisRegistryConfig {
+    /// 连接地址，如 `redis://127.0.0.1:6379/0`
+    pub url: String,
+
+    /// 键前缀，同一 Redis 中的多套部署以不同前缀隔离
+    #[serde(default = "default_redis_registry_key_prefix")]
+    pub key_prefix: String,
+}
+
+fn default_redis_registry_key_prefix() -> String {
+    "actrix:registry:".to_string()
+}
+
+impl RegistryStorageConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.backend == RegistryStorageBackend::Redis {
+            let redis = self
+                .redis
+                .as_ref()
+                .ok_or_else(|| "redis config is required when backend = \"redis\"".to_string())?;
+            if redis.url.trim().is_empty() {
+                return Err("redis.url cannot be empty".to_string());
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Signaling 服务器配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SignalingServerConfig {
-    /// WebSocket 路径
+    /// Signaling 路由前缀，WebSocket 端点为 `{ws_path}/ws`，管理接口位于 `{ws_path}/admin`
     pub ws_path: String,
 
     /// 速率限制配置
     #[serde(default)]
     pub rate_limit: RateLimitConfig,
+
+    /// 按 ActrType 的流量统计配置
+    #[serde(default)]
+    pub traffic_stats: TrafficStatsConfig,
+
+    /// WebSocket 连接认证配置
+    #[serde(default)]
+    pub auth: WsAuthConfig,
+
+    /// Envelope 重放保护配置
+    #[serde(default)]
+    pub replay_protection: ReplayProtectionConfig,
+
+    /// 单连接消息大小与发送队列限制
+    #[serde(default)]
+    pub limits: ConnectionLimitsConfig,
+
+    /// ServiceSpec 历史版本保留策略
+    #[serde(default)]
+    pub spec_history: SpecHistoryConfig,
+
+    /// ServiceSpec 不兼容变更主动通知
+    #[serde(default)]
+    pub spec_notice: SpecNoticeConfig,
+
+    /// 中继 SDP 校验与清洗
+    #[serde(default)]
+    pub sdp_validation: SdpValidationConfig,
+
+    /// 外部授权钩子
+    #[serde(default)]
+    pub authz_hook: AuthzHookConfig,
+
+    /// 断线重连会话恢复
+    #[serde(default)]
+    pub resumption: ResumptionConfig,
+
+    /// 过载降级
+    #[serde(default)]
+    pub load_shedding: LoadSheddingConfig,
+
+    /// 同一 ActrId 重复连接的处理策略
+    #[serde(default)]
+    pub duplicate_identity: DuplicateIdentityConfig,
+
+    /// 服务注册表存储加密
+    #[serde(default)]
+    pub registry_encryption: RegistryEncryptionConfig,
+
+    /// WebSocket 消息压缩
+    #[serde(default)]
+    pub compression: CompressionConfig,
+
+    /// 路由候选排序时参与亲和匹配的实例元数据键（如 `["region"]`）
+    ///
+    /// 元数据由 Actor 在 ServiceSpec 标签中以 `key=value` 声明；候选实例在这些键上与请求方取值相同时优先，
+    /// 为空时不做元数据亲和排序
+    #[serde(default)]
+    pub metadata_affinity_keys: Vec<String>,
+}
+
+/// WebSocket 消息压缩配置
+///
+/// 启用后与在握手时提供 `actrix.deflate` 子协议的客户端协商按消息 deflate 压缩，
+/// 超过 `threshold_bytes` 且压缩后更小的消息才会压缩（如较大的 ServiceSpec）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompressionConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 触发压缩的最小消息大小（字节）
+    #[serde(default = "default_compression_threshold_bytes")]
+    pub threshold_bytes: usize,
+
+    /// deflate 压缩级别（0-9）
+    #[serde(default = "default_compression_level")]
+    pub level: u32,
+}
+
+/// 服务注册表存储加密配置
+///
+/// 启用后注册表 SQLite 中的 ACL 与 ServiceSpec（含 proto 内容与历史版本）以 AES-256-GCM
+/// 加密存储，与 KS 私钥加密共用 `KeyEncryptor`。未单独配置 KEK 时使用本地 KS 的 KEK。
+/// 加载时透明解密，已有的明文数据仍可读取，可用 `encrypt-registry` 命令迁移
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RegistryEncryptionConfig {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// KEK - 直接配置（64 字符十六进制或 44 字符 Base64）
+    #[serde(default)]
+    pub kek: Option<String>,
+
+    /// KEK 环境变量名称
+    #[serde(default)]
+    pub kek_env: Option<String>,
+
+    /// KEK 文件路径
+    #[serde(default)]
+    pub kek_file: Option<String>,
+
+    /// 硬件保护的 KEK 提供方（优先于 kek / kek_env / kek_file）
+    #[serde(default)]
+    pub kek_provider: Option<::ks::KekProviderConfig>,
+}
+
+/// 同一 ActrId 重复连接的处理策略配置
+///
+/// 通过 URL 身份重连或首条认证消息绑定身份时，若该 Actor 已有在线连接则按策略处理。
+/// 每个连接按客户端 IP 与 User-Agent 计算指纹，指纹相同视为同一客户端重连
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateIdentityConfig {
+    /// 默认策略
+    #[serde(default)]
+    pub policy: DuplicateIdentityPolicy,
+
+    /// `allow_multiple` 策略下每个 Actor 的最大连接数，超出时断开最早的连接
+    #[serde(default = "default_duplicate_identity_max_connections")]
+    pub max_connections: usize,
+
+    /// `reject_new` 与 `allow_multiple` 策略下，新连接先替换指纹相同的已有连接（客户端断线重连）
+    #[serde(default = "default_true")]
+    pub same_fingerprint_replaces: bool,
+
+    /// 按 Realm 覆盖的策略
+    #[serde(default)]
+    pub realm_overrides: Vec<RealmDuplicateIdentityPolicy>,
+}
+
+/// 重复连接处理策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateIdentityPolicy {
+    /// 断开旧连接，保留新连接
+    #[default]
+    Replace,
+    /// 拒绝新连接，保留旧连接
+    RejectNew,
+    /// 允许同时在线，最多 `max_connections` 个
+    AllowMultiple,
+}
+
+/// 单个 Realm 的重复连接处理策略
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RealmDuplicateIdentityPolicy {
+    pub realm_id: u32,
+    pub policy: DuplicateIdentityPolicy,
+    /// 未设置时使用默认的 `max_connections`
+    #[serde(default)]
+    pub max_connections: Option<usize>,
+}
+
+/// 过载降级配置
+///
+/// 周期采样 CPU 使用率与全部连接的待发送消息总数，任一越过阈值即进入降级状态：
+/// 服务发现、ServiceSpec 查询与管理端统计查询返回 503，注册、凭证校验与中继
+/// （呼叫建立）照常处理。两项指标均回落到阈值 × `recovery_ratio` 以下后退出降级
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LoadSheddingConfig {
+    /// 是否启用过载降级
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 进入降级的 CPU 使用率阈值（百分比）
+    #[serde(default = "default_shed_cpu_threshold_percent")]
+    pub cpu_threshold_percent: f32,
+
+    /// 进入降级的待发送消息总数阈值（所有连接出站队列之和）
+    #[serde(default = "default_shed_queue_depth_threshold")]
+    pub queue_depth_threshold: usize,
+
+    /// 退出降级的回落比例（0, 1]，用于避免在阈值附近反复切换
+    #[serde(default = "default_shed_recovery_ratio")]
+    pub recovery_ratio: f32,
+
+    /// 采样间隔（毫秒）
+    #[serde(default = "default_shed_sample_interval_ms")]
+    pub sample_interval_ms: u64,
+}
+
+/// 断线重连会话恢复配置
+///
+/// 注册成功后下发恢复 token。连接断开后在 `window_secs` 内保留该 Actor 的 Presence 订阅，
+/// 并缓存发往它的消息；客户端携带 URL 身份与恢复 token 重连时原样恢复
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResumptionConfig {
+    /// 是否启用会话恢复
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 断开后会话保留时长（秒）
+    #[serde(default = "default_resumption_window_secs")]
+    pub window_secs: u64,
+
+    /// 断开期间每个 Actor 最多缓存的消息数，超出时丢弃最旧的消息
+    #[serde(default = "default_resumption_max_queued_messages")]
+    pub max_queued_messages: usize,
+}
+
+/// 外部授权钩子配置
+///
+/// 启用后注册、服务发现与中继除内置 ACL 外还需经外部策略服务（OPA、自建权限中心等）批准，
+/// 任一方拒绝即拒绝
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuthzHookConfig {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 调用协议
+    #[serde(default)]
+    pub protocol: AuthzHookProtocol,
+
+    /// 策略服务地址
+    ///
+    /// - http：完整 URL，如 `http://opa:8181/v1/data/actrix/allow`
+    /// - grpc：实现 `authz.v1.SignalingAuthz` 的服务地址，如 `http://authz:50061`
+    #[serde(default)]
+    pub endpoint: String,
+
+    /// HTTP 请求携带的 Bearer Token（可选）
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+
+    /// 单次调用超时（毫秒）
+    #[serde(default = "default_authz_timeout_ms")]
+    pub timeout_ms: u64,
+
+    /// 策略服务不可用（超时、网络错误、响应无法解析）时是否放行
+    #[serde(default)]
+    pub fail_open: bool,
+
+    /// 决策缓存时长（秒），0 表示不缓存
+    #[serde(default = "default_authz_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+
+    /// 需要调用钩子的操作（register / discover / relay）
+    #[serde(default = "default_authz_actions")]
+    pub actions: Vec<String>,
+}
+
+/// 外部授权钩子调用协议
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthzHookProtocol {
+    /// HTTP POST JSON（兼容 OPA Data API）
+    #[default]
+    Http,
+    /// gRPC `authz.v1.SignalingAuthz`
+    Grpc,
+}
+
+impl AuthzHookConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.endpoint.trim().is_empty() {
+            return Err("endpoint is required when authz_hook is enabled".to_string());
+        }
+        if let Some(action) = self
+            .actions
+            .iter()
+            .find(|a| !["register", "discover", "relay"].contains(&a.as_str()))
+        {
+            return Err(format!(
+                "unknown action '{action}', must be one of: register, discover, relay"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 中继 SDP 校验与清洗配置
+///
+/// 启用后中继的 SDP offer/answer 与 trickle ICE candidate 会先经过解析：
+/// 格式错误的 SDP 被拒绝，不允许的 candidate 与编解码器被剥离
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SdpValidationConfig {
+    /// 是否启用（默认关闭，SDP 原样转发）
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 单个 SDP 的最大字节数
+    #[serde(default = "default_max_sdp_bytes")]
+    pub max_sdp_bytes: usize,
+
+    /// 需要剥离的 candidate 类型（host / srflx / prflx / relay）
+    #[serde(default)]
+    pub blocked_candidate_types: Vec<String>,
+
+    /// 是否剥离私有地址的 candidate（RFC 1918、CGNAT、链路本地、回环、IPv6 ULA）
+    #[serde(default)]
+    pub strip_private_candidates: bool,
+
+    /// 默认编解码器白名单（按 rtpmap 名称，大小写不敏感；空表示不限制）
+    #[serde(default)]
+    pub allowed_codecs: Vec<String>,
+
+    /// 按 Realm 覆盖的编解码器白名单
+    #[serde(default)]
+    pub realm_codecs: Vec<RealmCodecAllowlist>,
+}
+
+/// 单个 Realm 的编解码器白名单
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RealmCodecAllowlist {
+    pub realm_id: u32,
+    pub codecs: Vec<String>,
+}
+
+impl SdpValidationConfig {
+    /// 获取 Realm 生效的编解码器白名单（未单独配置时使用默认白名单）
+    pub fn codecs_for_realm(&self, realm_id: u32) -> &[String] {
+        self.realm_codecs
+            .iter()
+            .find(|r| r.realm_id == realm_id)
+            .map(|r| r.codecs.as_slice())
+            .unwrap_or(&self.allowed_codecs)
+    }
+}
+
+/// ServiceSpec 不兼容变更主动通知配置
+///
+/// 服务以新 fingerprint 重新注册且分析结果为破坏性变更时，
+/// 主动通知近期通过 RouteCandidates 依赖该服务的在线客户端
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpecNoticeConfig {
+    /// 是否启用主动通知
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// 依赖关系的保留时长（秒）：客户端超过该时长未再请求路由时不再通知
+    #[serde(default = "default_spec_notice_dependent_ttl_secs")]
+    pub dependent_ttl_secs: u64,
+}
+
+/// ServiceSpec 历史版本保留策略
+///
+/// 每个服务名按 fingerprint 记录发布过的规格，供客户端固定或对比旧版本契约
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpecHistoryConfig {
+    /// 每个服务最多保留的版本数，超出时淘汰最早的版本
+    #[serde(default = "default_spec_history_max_versions")]
+    pub max_versions: usize,
+
+    /// 版本最后一次被发布后的保留时长（秒），0 表示不按时间淘汰
+    ///
+    /// 最新版本始终保留
+    #[serde(default = "default_spec_history_max_age_secs")]
+    pub max_age_secs: u64,
+}
+
+/// 单连接消息大小与发送队列限制
+///
+/// 防止单个慢速或恶意客户端耗尽服务器内存
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConnectionLimitsConfig {
+    /// 单个入站 envelope 的最大字节数，超出时回复 413 EnvelopeError 并丢弃
+    ///
+    /// 超过该值 2 倍的 WebSocket 消息在传输层直接断开连接
+    #[serde(default = "default_max_envelope_bytes")]
+    pub max_envelope_bytes: usize,
+
+    /// 每个连接发送队列的最大消息数
+    #[serde(default = "default_max_outbound_queue")]
+    pub max_outbound_queue: usize,
+
+    /// 发送队列已满时的处理策略
+    #[serde(default)]
+    pub outbound_overflow_policy: OutboundOverflowPolicy,
+
+    /// `wait` 策略下等待队列空位的最长时间（毫秒），超时后断开连接
+    #[serde(default = "default_outbound_send_timeout_ms")]
+    pub outbound_send_timeout_ms: u64,
+
+    /// 是否为出站 envelope 标记单连接递增序号（写入 `tracestate` 的 `actrix-seq`）
+    ///
+    /// 客户端据此发现丢失的消息，并重新发起服务发现或 Presence 订阅
+    #[serde(default)]
+    pub sequence_numbers: bool,
+}
+
+/// 发送队列已满时的处理策略
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboundOverflowPolicy {
+    /// 等待队列空位（背压），超时后断开连接
+    #[default]
+    Wait,
+    /// 丢弃新消息，保留连接
+    DropNewest,
+    /// 立即断开连接
+    Disconnect,
+}
+
+/// Envelope 重放保护配置
+///
+/// payload 类型名使用 snake_case，如 `ping`、`unregister_request`、`actr_relay`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReplayProtectionConfig {
+    /// 是否启用时间戳新鲜度校验与 nonce 跟踪
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// envelope 时间戳允许的最大时长（秒）
+    #[serde(default = "default_max_envelope_age_secs")]
+    pub max_age_secs: u64,
+
+    /// envelope 时间戳允许超前服务器时间的最大值（秒）
+    #[serde(default = "default_max_future_skew_secs")]
+    pub max_future_skew_secs: u64,
+
+    /// 不做新鲜度校验的 payload 类型
+    #[serde(default)]
+    pub exempt_payloads: Vec<String>,
+
+    /// 需要 nonce 跟踪（envelope_id 不可重复）的敏感 payload 类型
+    #[serde(default = "default_nonce_payloads")]
+    pub nonce_payloads: Vec<String>,
+}
+
+/// WebSocket 连接认证配置
+///
+/// 重连时的身份凭证可通过以下方式提供（优先级从高到低）：
+/// 1. `Authorization: Bearer <base64 token>` 请求头
+/// 2. URL 查询参数 `token`（会泄漏到日志/代理，仅为兼容保留）
+/// 3. 连接建立后发送的第一条携带 credential 的 ActrToSignaling 消息
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WsAuthConfig {
+    /// 是否允许通过 URL 查询参数 `token` 传递凭证
+    #[serde(default = "default_true")]
+    pub allow_query_token: bool,
+
+    /// WSS 连接上是否强制要求 TLS 通道绑定证明
+    ///
+    /// 启用后，通过 URL/请求头携带凭证的 WSS 连接必须提供
+    /// `X-Actr-Channel-Binding` 证明，否则拒绝升级
+    #[serde(default)]
+    pub require_channel_binding: bool,
+}
+
+/// 按 ActrType 聚合的流量统计配置
+///
+/// 用于容量规划，统计结果通过 admin API (`/admin/traffic`) 暴露
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrafficStatsConfig {
+    /// 是否启用流量统计
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// 最多跟踪的 ActrType 数量，超出时淘汰近期流量最低的类型
+    #[serde(default = "default_max_tracked_types")]
+    pub max_tracked_types: usize,
+
+    /// 速率衰减半衰期（秒）
+    #[serde(default = "default_traffic_half_life_secs")]
+    pub half_life_secs: u64,
 }
 
 /// 速率限制配置
@@ -39,6 +563,10 @@ pub struct RateLimitConfig {
     /// 消息速率限制配置
     #[serde(default)]
     pub message: MessageRateLimit,
+
+    /// Realm 级聚合速率限制配置
+    #[serde(default)]
+    pub realm: RealmRateLimit,
 }
 
 /// 连接速率限制配置
@@ -77,6 +605,33 @@ pub struct MessageRateLimit {
     pub burst_size: u32,
 }
 
+/// Realm 级聚合速率限制配置
+///
+/// 同一 Realm 下所有连接共享配额，避免单个租户占满共享节点。
+/// 此处为节点默认值，可被 Realm 元数据中的 `ratelimit.*` 配置项覆盖，0 表示不限制。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RealmRateLimit {
+    /// 是否启用 Realm 级速率限制
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 每个 Realm 每分钟允许的注册数
+    #[serde(default = "default_realm_registrations_per_minute")]
+    pub registrations_per_minute: u32,
+
+    /// 每个 Realm 每秒允许的中继消息数
+    #[serde(default = "default_realm_relays_per_second")]
+    pub relays_per_second: u32,
+
+    /// 每个 Realm 每秒允许的服务发现请求数
+    #[serde(default = "default_realm_discovery_per_second")]
+    pub discovery_per_second: u32,
+
+    /// Realm 元数据中限额的缓存时间（秒）
+    #[serde(default = "default_realm_limits_cache_secs")]
+    pub cache_ttl_secs: u64,
+}
+
 // 默认值函数
 fn default_true() -> bool {
     true
@@ -102,6 +657,125 @@ fn default_message_burst() -> u32 {
     50
 }
 
+fn default_realm_registrations_per_minute() -> u32 {
+    600
+}
+
+fn default_realm_relays_per_second() -> u32 {
+    1000
+}
+
+fn default_realm_discovery_per_second() -> u32 {
+    200
+}
+
+fn default_realm_limits_cache_secs() -> u64 {
+    30
+}
+
+fn default_max_tracked_types() -> usize {
+    256
+}
+
+fn default_traffic_half_life_secs() -> u64 {
+    300
+}
+
+fn default_max_envelope_age_secs() -> u64 {
+    300
+}
+
+fn default_max_future_skew_secs() -> u64 {
+    30
+}
+
+fn default_max_envelope_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_max_outbound_queue() -> usize {
+    256
+}
+
+fn default_outbound_send_timeout_ms() -> u64 {
+    1000
+}
+
+fn default_spec_history_max_versions() -> usize {
+    20
+}
+
+fn default_spec_history_max_age_secs() -> u64 {
+    30 * 24 * 3600
+}
+
+fn default_spec_notice_dependent_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_max_sdp_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_authz_timeout_ms() -> u64 {
+    500
+}
+
+fn default_authz_cache_ttl_secs() -> u64 {
+    5
+}
+
+fn default_authz_actions() -> Vec<String> {
+    vec![
+        "register".to_string(),
+        "discover".to_string(),
+        "relay".to_string(),
+    ]
+}
+
+fn default_resumption_window_secs() -> u64 {
+    60
+}
+
+fn default_resumption_max_queued_messages() -> usize {
+    256
+}
+
+fn default_compression_threshold_bytes() -> usize {
+    1024
+}
+
+fn default_compression_level() -> u32 {
+    6
+}
+
+fn default_shed_cpu_threshold_percent() -> f32 {
+    85.0
+}
+
+fn default_shed_queue_depth_threshold() -> usize {
+    10_000
+}
+
+fn default_shed_recovery_ratio() -> f32 {
+    0.8
+}
+
+fn default_shed_sample_interval_ms() -> u64 {
+    1000
+}
+
+fn default_duplicate_identity_max_connections() -> usize {
+    2
+}
+
+fn default_nonce_payloads() -> Vec<String> {
+    vec![
+        "unregister_request".to_string(),
+        "credential_update_request".to_string(),
+    ]
+}
+
 /// Signaling 依赖的外部服务
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct SignalingDependencies {
@@ -127,21 +801,296 @@ pub struct SignalingDependencies {
 pub struct AisClientConfig {
     /// AIS 服务端点 URL
     pub endpoint: String,
+    /// AIS 的路由前缀（对端 `http.route_prefixes.ais`）
+    #[serde(default = "default_ais_route_prefix")]
+    pub route_prefix: String,
     /// 请求超时时间（秒）
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
+    /// 从 AIS 拉取凭证吊销过滤器的间隔（秒），0 表示不拉取
+    #[serde(default = "default_revocation_refresh_interval_secs")]
+    pub revocation_refresh_interval_secs: u64,
 }
 
 fn default_timeout() -> u64 {
     30
 }
 
+fn default_revocation_refresh_interval_secs() -> u64 {
+    30
+}
+
+fn default_ais_route_prefix() -> String {
+    "/ais".to_string()
+}
+
 impl Default for SignalingServerConfig {
     fn default() -> Self {
         Self {
             ws_path: "/signaling".to_string(),
             rate_limit: RateLimitConfig::default(),
+            traffic_stats: TrafficStatsConfig::default(),
+            auth: WsAuthConfig::default(),
+            replay_protection: ReplayProtectionConfig::default(),
+            limits: ConnectionLimitsConfig::default(),
+            spec_history: SpecHistoryConfig::default(),
+            spec_notice: SpecNoticeConfig::default(),
+            sdp_validation: SdpValidationConfig::default(),
+            authz_hook: AuthzHookConfig::default(),
+            resumption: ResumptionConfig::default(),
+            load_shedding: LoadSheddingConfig::default(),
+            duplicate_identity: DuplicateIdentityConfig::default(),
+            registry_encryption: RegistryEncryptionConfig::default(),
+            compression: CompressionConfig::default(),
+            metadata_affinity_keys: Vec::new(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.level > 9 {
+            return Err("level must be between 0 and 9".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_bytes: default_compression_threshold_bytes(),
+            level: default_compression_level(),
+        }
+    }
+}
+
+impl RegistryEncryptionConfig {
+    /// 获取单独配置的 KEK 来源
+    ///
+    /// 优先级与 KS 一致: kek_provider > kek_file > kek_env > kek
+    pub fn get_kek_source(&self) -> Option<::ks::KekSource> {
+        if let Some(::ks::KekProviderConfig::Pkcs11(config)) = &self.kek_provider {
+            return Some(::ks::KekSource::Pkcs11(config.clone()));
+        }
+        if let Some(path) = &self.kek_file {
+            return Some(::ks::KekSource::File(path.clone()));
+        }
+        if let Some(env_var) = &self.kek_env {
+            return Some(::ks::KekSource::Environment(env_var.clone()));
+        }
+        self.kek.clone().map(::ks::KekSource::Direct)
+    }
+}
+
+impl DuplicateIdentityConfig {
+    /// 获取 Realm 生效的策略与最大连接数（未单独配置时使用默认值）
+    pub fn policy_for_realm(&self, realm_id: u32) -> (DuplicateIdentityPolicy, usize) {
+        match self.realm_overrides.iter().find(|r| r.realm_id == realm_id) {
+            Some(realm) => (
+                realm.policy,
+                realm.max_connections.unwrap_or(self.max_connections),
+            ),
+            None => (self.policy, self.max_connections),
+        }
+    }
+
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_connections == 0 {
+            return Err("max_connections must be greater than 0".to_string());
+        }
+        if let Some(realm) = self
+            .realm_overrides
+            .iter()
+            .find(|r| r.max_connections == Some(0))
+        {
+            return Err(format!(
+                "realm_overrides max_connections for realm {} must be greater than 0",
+                realm.realm_id
+            ));
         }
+        Ok(())
+    }
+}
+
+impl Default for DuplicateIdentityConfig {
+    fn default() -> Self {
+        Self {
+            policy: DuplicateIdentityPolicy::default(),
+            max_connections: default_duplicate_identity_max_connections(),
+            same_fingerprint_replaces: default_true(),
+            realm_overrides: Vec::new(),
+        }
+    }
+}
+
+impl ResumptionConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.window_secs == 0 {
+            return Err("window_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for ResumptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: default_resumption_window_secs(),
+            max_queued_messages: default_resumption_max_queued_messages(),
+        }
+    }
+}
+
+impl LoadSheddingConfig {
+    /// 验证配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if !(self.cpu_threshold_percent > 0.0 && self.cpu_threshold_percent <= 100.0) {
+            return Err("cpu_threshold_percent must be in (0, 100]".to_string());
+        }
+        if self.queue_depth_threshold == 0 {
+            return Err("queue_depth_threshold must be greater than 0".to_string());
+        }
+        if !(self.recovery_ratio > 0.0 && self.recovery_ratio <= 1.0) {
+            return Err("recovery_ratio must be in (0, 1]".to_string());
+        }
+        if self.sample_interval_ms == 0 {
+            return Err("sample_interval_ms must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for LoadSheddingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cpu_threshold_percent: default_shed_cpu_threshold_percent(),
+            queue_depth_threshold: default_shed_queue_depth_threshold(),
+            recovery_ratio: default_shed_recovery_ratio(),
+            sample_interval_ms: default_shed_sample_interval_ms(),
+        }
+    }
+}
+
+impl Default for AuthzHookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            protocol: AuthzHookProtocol::default(),
+            endpoint: String::new(),
+            bearer_token: None,
+            timeout_ms: default_authz_timeout_ms(),
+            fail_open: false,
+            cache_ttl_secs: default_authz_cache_ttl_secs(),
+            actions: default_authz_actions(),
+        }
+    }
+}
+
+impl Default for SdpValidationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_sdp_bytes: default_max_sdp_bytes(),
+            blocked_candidate_types: Vec::new(),
+            strip_private_candidates: false,
+            allowed_codecs: Vec::new(),
+            realm_codecs: Vec::new(),
+        }
+    }
+}
+
+impl Default for SpecNoticeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            dependent_ttl_secs: default_spec_notice_dependent_ttl_secs(),
+        }
+    }
+}
+
+impl Default for SpecHistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_versions: default_spec_history_max_versions(),
+            max_age_secs: default_spec_history_max_age_secs(),
+        }
+    }
+}
+
+impl Default for ConnectionLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_envelope_bytes: default_max_envelope_bytes(),
+            max_outbound_queue: default_max_outbound_queue(),
+            outbound_overflow_policy: OutboundOverflowPolicy::default(),
+            outbound_send_timeout_ms: default_outbound_send_timeout_ms(),
+            sequence_numbers: false,
+        }
+    }
+}
+
+impl Default for ReplayProtectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_secs: default_max_envelope_age_secs(),
+            max_future_skew_secs: default_max_future_skew_secs(),
+            exempt_payloads: Vec::new(),
+            nonce_payloads: default_nonce_payloads(),
+        }
+    }
+}
+
+impl Default for WsAuthConfig {
+    fn default() -> Self {
+        Self {
+            allow_query_token: default_true(),
+            require_channel_binding: false,
+        }
+    }
+}
+
+impl Default for TrafficStatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_true(),
+            max_tracked_types: default_max_tracked_types(),
+            half_life_secs: default_traffic_half_life_secs(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// 验证配置有效性（Realm 限额为 0 表示不限制，不做校验）
+    pub fn validate(&self) -> Result<(), String> {
+        let connection = &self.connection;
+        if connection.enabled
+            && (connection.per_minute == 0
+                || connection.burst_size == 0
+                || connection.max_concurrent_per_ip == 0)
+        {
+            return Err(
+                "connection.per_minute, connection.burst_size and connection.max_concurrent_per_ip must be greater than 0"
+                    .to_string(),
+            );
+        }
+        let message = &self.message;
+        if message.enabled && (message.per_second == 0 || message.burst_size == 0) {
+            return Err(
+                "message.per_second and message.burst_size must be greater than 0".to_string(),
+            );
+        }
+        Ok(())
     }
 }
 
@@ -166,6 +1115,18 @@ impl Default for MessageRateLimit {
     }
 }
 
+impl Default for RealmRateLimit {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            registrations_per_minute: default_realm_registrations_per_minute(),
+            relays_per_second: default_realm_relays_per_second(),
+            discovery_per_second: default_realm_discovery_per_second(),
+            cache_ttl_secs: default_realm_limits_cache_secs(),
+        }
+    }
+}
+
 impl SignalingConfig {
     /// 获取 KS 客户端配置
     ///
@@ -182,27 +1143,39 @@ impl SignalingConfig {
             return Some(ks_config.clone());
         }
 
-        // 回退：检查是否启用了本地 KS 服务
-        if global_config.is_ks_enabled() && global_config.services.ks.is_some() {
-            // 自动生成指向本地 KS 的客户端配置
-            // gRPC 使用独立端口 50052（HTTP router 使用 8443/8080）
-            let grpc_port = 50052;
-            let grpc_protocol = "http"; // 默认不启用 TLS，可通过配置开启
-
-            return Some(KsClientConfig {
-                endpoint: format!("{grpc_protocol}://127.0.0.1:{grpc_port}"),
-                timeout_seconds: 30,
-                enable_tls: false,
-                tls_domain: None,
-                ca_cert: None,
-                client_cert: None,
-                client_key: None,
-            });
+        // 回退：检查是否启用了本地 KS 服务（或开发用模拟 KS）
+        if (global_config.is_ks_enabled() && global_config.services.ks.is_some())
+            || global_config.is_dev_mock_enabled()
+        {
+            // 自动生成指向本地 KS gRPC 监听地址（services.ks.grpc.bind）的客户端配置
+            return global_config.local_ks_client_config();
         }
 
         None
     }
 
+    /// 获取服务注册表加密使用的 KEK 来源
+    ///
+    /// 未启用加密时返回 None；启用时优先使用 `registry_encryption` 中单独配置的 KEK，
+    /// 否则回退到本地 KS 服务的 KEK
+    pub fn get_registry_kek_source(
+        &self,
+        global_config: &super::ActrixConfig,
+    ) -> Option<::ks::KekSource> {
+        let encryption = &self.server.registry_encryption;
+        if !encryption.enabled {
+            return None;
+        }
+
+        encryption.get_kek_source().or_else(|| {
+            global_config
+                .services
+                .ks
+                .as_ref()
+                .and_then(|ks| ks.get_kek_source())
+        })
+    }
+
     /// 获取 AIS 客户端配置
     ///
     /// 支持智能默认：
@@ -218,8 +1191,10 @@ impl SignalingConfig {
             return Some(ais_config.clone());
         }
 
-        // 回退：检查是否启用了本地 AIS 服务
-        if global_config.is_ais_enabled() && global_config.services.ais.is_some() {
+        // 回退：检查是否启用了本地 AIS 服务（或开发用模拟 AIS）
+        if (global_config.is_ais_enabled() && global_config.services.ais.is_some())
+            || global_config.is_dev_mock_enabled()
+        {
             // 自动生成指向本地 AIS 的客户端配置
             // AIS 作为 HTTP router service 共享同一个 HTTP/HTTPS 端口
             let port = global_config
@@ -238,7 +1213,9 @@ impl SignalingConfig {
 
             return Some(AisClientConfig {
                 endpoint: format!("{protocol}://127.0.0.1:{port}"),
+                route_prefix: global_config.http.route_prefixes.ais.clone(),
                 timeout_seconds: 30,
+                revocation_refresh_interval_secs: default_revocation_refresh_interval_secs(),
             });
         }
 
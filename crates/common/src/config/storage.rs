@@ -0,0 +1,27 @@
+//! 存储模式配置
+//!
+//! 选择主数据库、服务注册表持久化缓存与 nonce 存储落盘还是仅保存在进程内存中。
+
+use serde::{Deserialize, Serialize};
+
+/// 存储模式
+///
+/// - "sqlite": 数据库文件存放在 `sqlite_path` 下（默认）
+/// - "memory": 保存在进程内存中，不写文件系统，重启后丢失，
+///   适用于 CI 与短生命周期的演示容器
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageMode {
+    /// SQLite 数据库文件
+    #[default]
+    Sqlite,
+    /// 进程内存储
+    Memory,
+}
+
+impl StorageMode {
+    /// 是否为内存模式
+    pub fn is_memory(&self) -> bool {
+        *self == StorageMode::Memory
+    }
+}
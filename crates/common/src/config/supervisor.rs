@@ -15,6 +15,22 @@ pub struct SupervisorConfig {
     #[serde(default = "default_health_check_interval")]
     pub health_check_interval_secs: u64,
 
+    /// 最大重连等待时间（秒）
+    ///
+    /// 与 Supervisor 断开后按带抖动的指数退避重连，等待时间从 1 秒开始翻倍直至该上限
+    #[serde(default = "default_max_reconnect_delay")]
+    pub max_reconnect_delay_secs: u64,
+
+    /// Supervisor 不可达期间最多缓存的状态报告数（0 表示不缓存）
+    #[serde(default = "default_report_buffer_size")]
+    pub report_buffer_size: usize,
+
+    /// 系统指标采样间隔（秒）
+    ///
+    /// 间隔内的多次上报复用同一次采样，CPU 使用率为两次采样之间的平均值
+    #[serde(default = "default_metrics_sample_interval")]
+    pub metrics_sample_interval_secs: u64,
+
     /// 是否启用 TLS
     #[serde(default)]
     pub enable_tls: bool,
@@ -54,6 +70,25 @@ pub struct SupervisorConfig {
     /// Supervisor 客户端配置（主动注册 + 上报）
     #[serde(default)]
     pub client: SupervisorClientConfig,
+
+    /// 自更新配置，未配置时忽略 UPDATE 指令
+    #[serde(default)]
+    pub update: Option<SelfUpdateConfig>,
+}
+
+/// 自更新配置（UPDATE 指令）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SelfUpdateConfig {
+    /// 更新包签名公钥（hex 编码的 32 字节 Ed25519 公钥）
+    pub public_key: String,
+
+    /// 更新包暂存目录（同时记录各类型已安装的版本，用于拒绝旧版本重放）
+    #[serde(default = "default_update_staging_dir")]
+    pub staging_dir: String,
+
+    /// 更新包大小上限（字节）
+    #[serde(default = "default_max_bundle_bytes")]
+    pub max_bundle_bytes: u64,
 }
 
 /// Supervisord gRPC 服务配置
@@ -101,6 +136,12 @@ pub struct SupervisorClientConfig {
     /// 示例：http://supervisor.example.com:50051
     pub endpoint: String,
 
+    /// 备用 Supervisor 端点（按顺序尝试）
+    ///
+    /// 当前端点不可达时切换到下一个通过健康检查的端点，并保持在该端点直到它再次失败
+    #[serde(default)]
+    pub fallback_endpoints: Vec<String>,
+
     /// Shared secret (hex encoded for HMAC signatures)
     ///
     /// Shared secret used for nonce-auth authentication.
@@ -121,10 +162,30 @@ fn default_health_check_interval() -> u64 {
     30
 }
 
+fn default_max_reconnect_delay() -> u64 {
+    60
+}
+
+fn default_report_buffer_size() -> usize {
+    120
+}
+
+fn default_metrics_sample_interval() -> u64 {
+    15
+}
+
 fn default_max_clock_skew() -> u64 {
     300 // 5 minutes
 }
 
+fn default_update_staging_dir() -> String {
+    "database/updates".to_string()
+}
+
+fn default_max_bundle_bytes() -> u64 {
+    256 * 1024 * 1024
+}
+
 fn default_node_name() -> String {
     String::from("actrix-node")
 }
@@ -147,6 +208,9 @@ impl Default for SupervisorConfig {
             connect_timeout_secs: default_connect_timeout(),
             status_report_interval_secs: default_status_interval(),
             health_check_interval_secs: default_health_check_interval(),
+            max_reconnect_delay_secs: default_max_reconnect_delay(),
+            report_buffer_size: default_report_buffer_size(),
+            metrics_sample_interval_secs: default_metrics_sample_interval(),
             enable_tls: false,
             tls_domain: None,
             client_cert: None,
@@ -155,6 +219,7 @@ impl Default for SupervisorConfig {
             max_clock_skew_secs: default_max_clock_skew(),
             supervisord: SupervisordConfig::default(),
             client: SupervisorClientConfig::default(),
+            update: None,
         }
     }
 }
@@ -216,6 +281,14 @@ impl SupervisorConfig {
                 .push("supervisor.client.endpoint must start with http:// or https://".to_string());
         }
 
+        for endpoint in &client.fallback_endpoints {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                errors.push(format!(
+                    "supervisor.client.fallback_endpoints entry '{endpoint}' must start with http:// or https://"
+                ));
+            }
+        }
+
         let secret = &client.shared_secret;
         if secret.trim().is_empty() {
             errors
@@ -250,6 +323,33 @@ impl SupervisorConfig {
             errors.push("supervisor.supervisord.port must be greater than 0".to_string());
         }
 
+        if let Some(update) = &self.update {
+            let key_valid = hex::decode(update.public_key.trim())
+                .map(|key| key.len() == 32)
+                .unwrap_or(false);
+            if !key_valid {
+                errors.push(
+                    "supervisor.update.public_key must be 32 bytes (64 hex characters)".to_string(),
+                );
+            }
+            if update.staging_dir.trim().is_empty() {
+                errors.push("supervisor.update.staging_dir cannot be empty".to_string());
+            }
+            if update.max_bundle_bytes == 0 {
+                errors
+                    .push("supervisor.update.max_bundle_bytes must be greater than 0".to_string());
+            }
+        }
+
+        if self.max_reconnect_delay_secs == 0 {
+            errors.push("supervisor.max_reconnect_delay_secs must be greater than 0".to_string());
+        }
+
+        if self.metrics_sample_interval_secs == 0 {
+            errors
+                .push("supervisor.metrics_sample_interval_secs must be greater than 0".to_string());
+        }
+
         if self.enable_tls && self.tls_domain.is_none() {
             errors.push("tls_domain is required when enable_tls is true".to_string());
         }
@@ -301,6 +401,7 @@ impl Default for SupervisorClientConfig {
         Self {
             node_id: String::new(),
             endpoint: "http://localhost:50051".to_string(),
+            fallback_endpoints: Vec::new(),
             shared_secret: String::new(),
         }
     }
@@ -319,6 +420,7 @@ mod tests {
             client: SupervisorClientConfig {
                 node_id: "test-node".to_string(),
                 endpoint: "http://localhost:50051".to_string(),
+                fallback_endpoints: Vec::new(),
                 shared_secret: valid_secret(),
             },
             supervisord: SupervisordConfig::default(),
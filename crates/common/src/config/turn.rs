@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 
 /// TURN 服务配置
 ///
@@ -11,6 +12,20 @@ pub struct TurnConfig {
     /// 必须是真实可路由的公网 IP，不能使用 "0.0.0.0"。
     pub advertised_ip: String,
 
+    /// 公网 IPv6 地址（可选）
+    ///
+    /// 配置后 TURN 额外在 IPv6 上监听（绑定地址见 `bind.ice.ipv6`），
+    /// 经 IPv6 连接的客户端获得该地址上的中继地址与 TURN 端点。
+    #[serde(default)]
+    pub advertised_ipv6: Option<String>,
+
+    /// 对外宣告的主机名（可选）
+    ///
+    /// 同时解析到 IPv4 与 IPv6 地址的域名。配置后服务发现文档优先返回基于主机名的
+    /// TURN 端点，再附上与客户端地址族匹配的 IP 端点。
+    #[serde(default)]
+    pub advertised_hostname: Option<String>,
+
     /// 公网端口
     ///
     /// TURN 服务对外宣告的端口号，通常与绑定端口相同。
@@ -21,21 +36,115 @@ pub struct TurnConfig {
     /// TURN 服务用于数据中继的 UDP 端口范围。
     /// 格式：开始端口-结束端口，如 "49152-65535"。
     /// 范围越大，可支持的并发中继会话越多。
+    /// 不应与系统临时端口范围或其他监听端口重叠，否则中继地址分配会偶发失败。
     pub relay_port_range: String,
 
     /// TURN 认证域
     ///
     /// TURN 服务的认证域名，用于 TURN 协议的认证机制。
     pub realm: String,
+
+    /// 允许使用中继的 Realm ID 列表
+    ///
+    /// 将 TURN 中继资源限定给指定租户。凭证中的 realm 不在列表内的分配请求会被拒绝。
+    /// 留空表示不限制（任何有效 Realm 的凭证均可使用）。
+    #[serde(default)]
+    pub allowed_realm_ids: Vec<u32>,
 }
 
 impl Default for TurnConfig {
     fn default() -> Self {
         Self {
             advertised_ip: "127.0.0.1".to_string(),
+            advertised_ipv6: None,
+            advertised_hostname: None,
             advertised_port: 3478,
             relay_port_range: "49152-65535".to_string(),
             realm: "actor-rtc.local".to_string(),
+            allowed_realm_ids: Vec::new(),
         }
     }
 }
+
+/// 中继端口范围建议的下限（避开特权端口）
+const MIN_RELAY_PORT: u16 = 1024;
+
+/// Linux 系统临时端口范围配置文件
+const EPHEMERAL_PORT_RANGE_FILE: &str = "/proc/sys/net/ipv4/ip_local_port_range";
+
+impl TurnConfig {
+    /// 解析中继端口范围，返回 (起始端口, 结束端口)
+    pub fn relay_port_bounds(&self) -> Result<(u16, u16), String> {
+        parse_port_range(&self.relay_port_range).ok_or_else(|| {
+            format!(
+                "Invalid TURN relay_port_range '{}', expected \"<start>-<end>\" with 0 < start <= end",
+                self.relay_port_range
+            )
+        })
+    }
+
+    /// 按客户端连接的地址族选择宣告给该客户端的 TURN 地址
+    pub fn advertised_ip_for(&self, client_ip: Option<IpAddr>) -> &str {
+        crate::config::bind::advertised_ip_for(
+            &self.advertised_ip,
+            self.advertised_ipv6.as_deref(),
+            client_ip,
+        )
+    }
+}
+
+/// 解析 "start-end" 格式的端口范围
+pub fn parse_port_range(value: &str) -> Option<(u16, u16)> {
+    let (start, end) = value.split_once('-')?;
+    let start: u16 = start.trim().parse().ok()?;
+    let end: u16 = end.trim().parse().ok()?;
+    (start > 0 && start <= end).then_some((start, end))
+}
+
+/// 读取系统临时端口范围（仅 Linux，读取失败或其他平台返回 None）
+pub fn system_ephemeral_port_range() -> Option<(u16, u16)> {
+    let content = std::fs::read_to_string(EPHEMERAL_PORT_RANGE_FILE).ok()?;
+    let mut parts = content.split_whitespace();
+    let start = parts.next()?.parse().ok()?;
+    let end = parts.next()?.parse().ok()?;
+    Some((start, end))
+}
+
+/// 两个闭区间端口范围是否重叠
+pub fn port_ranges_overlap(a: (u16, u16), b: (u16, u16)) -> bool {
+    a.0 <= b.1 && b.0 <= a.1
+}
+
+/// 建议一个不与临时端口范围及监听端口冲突的中继端口范围
+///
+/// 在 1024-65535 中取最大的空闲区间，没有空闲区间时返回 None
+pub fn suggest_relay_port_range(
+    ephemeral: Option<(u16, u16)>,
+    listener_ports: &[u16],
+) -> Option<(u16, u16)> {
+    let mut occupied: Vec<(u16, u16)> = listener_ports.iter().map(|&port| (port, port)).collect();
+    occupied.extend(ephemeral);
+    occupied.sort_unstable();
+
+    let mut best: Option<(u16, u16)> = None;
+    let mut consider = |start: u32, end: u32| {
+        if start > end || start < MIN_RELAY_PORT as u32 {
+            return;
+        }
+        let candidate = (start as u16, end as u16);
+        if best.is_none_or(|(s, e)| end - start > (e - s) as u32) {
+            best = Some(candidate);
+        }
+    };
+
+    let mut next_free = MIN_RELAY_PORT as u32;
+    for (start, end) in occupied {
+        if start as u32 > next_free {
+            consider(next_free, start as u32 - 1);
+        }
+        next_free = next_free.max(end as u32 + 1);
+    }
+    consider(next_free, u16::MAX as u32);
+
+    best
+}
@@ -21,7 +21,7 @@ pub use error::{
 };
 pub use monitoring::{ServiceCollector, ServiceInfo, ServiceState, ServiceType};
 pub use realm::{ActorAcl, Realm, RealmError};
-pub use storage::SqliteNonceStorage;
+pub use storage::{NonceStore, SqliteNonceStorage};
 pub use types::{ActrId, PeerId, RealmId};
 pub use util::TlsConfigurer;
 
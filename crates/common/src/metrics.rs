@@ -4,7 +4,7 @@
 
 use lazy_static::lazy_static;
 use prometheus::{
-    HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    Gauge, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
 };
 use std::sync::Once;
 use std::time::Instant;
@@ -98,6 +98,19 @@ lazy_static! {
         &["pool", "state"]
     ).unwrap();
 
+    /// 优雅关闭期限内未退出、被强制中止的任务（按任务名）
+    pub static ref SHUTDOWN_ABORTED_TASKS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_shutdown_aborted_tasks_total", "Total number of tasks aborted after the shutdown grace period")
+            .namespace("actrix"),
+        &["task"]
+    ).unwrap();
+
+    /// 最近一次优雅关闭耗时（秒）
+    pub static ref SHUTDOWN_DURATION: Gauge = Gauge::new(
+        "actrix_shutdown_duration_seconds",
+        "Duration of the last graceful shutdown in seconds"
+    ).unwrap();
+
     // ========== 安全指标 ==========
 
     /// 速率限制触发次数
@@ -137,6 +150,15 @@ lazy_static! {
         &["reason"]
     ).unwrap();
 
+    // ========== STUN 特定指标 ==========
+
+    /// STUN 请求处理结果（binding_success / binding_error / non_binding / malformed / dropped）
+    pub static ref STUN_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_stun_requests_total", "Total number of STUN requests by result")
+            .namespace("actrix"),
+        &["result"]
+    ).unwrap();
+
     // ========== TURN 特定指标 ==========
 
     /// TURN 分配请求
@@ -158,6 +180,107 @@ lazy_static! {
             .namespace("actrix"),
         &["direction"]
     ).unwrap();
+
+    // ========== Signaling 特定指标 ==========
+
+    /// Actor 上报的 ICE 选中候选对（ConnectionReport）
+    pub static ref ICE_CONNECTION_REPORTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_ice_connection_reports_total", "Total number of ICE connection reports by selected candidate pair")
+            .namespace("actrix"),
+        &["realm_id", "local_candidate_type", "remote_candidate_type", "relayed"]
+    ).unwrap();
+
+    /// ICE 选中候选对的 RTT（秒）
+    pub static ref ICE_CONNECTION_RTT: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("actrix_ice_connection_rtt_seconds", "RTT of the selected ICE candidate pair in seconds")
+            .namespace("actrix")
+            .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.2, 0.5, 1.0]),
+        &["realm_id", "relayed"]
+    ).unwrap();
+
+    /// 外部授权钩子决策次数
+    pub static ref SIGNALING_AUTHZ_DECISIONS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_signaling_authz_decisions_total", "Total number of external authorization hook decisions")
+            .namespace("actrix"),
+        &["action", "decision"]
+    ).unwrap();
+
+    /// 断线重连会话恢复结果（resumed / rejected / expired）
+    pub static ref SIGNALING_SESSION_RESUMPTIONS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_signaling_session_resumptions_total", "Total number of signaling session resumption outcomes")
+            .namespace("actrix"),
+        &["result"]
+    ).unwrap();
+
+    /// 信令服务是否处于过载降级状态（1 = 降级中）
+    pub static ref SIGNALING_LOAD_SHEDDING: IntGauge = IntGauge::new(
+        "actrix_signaling_load_shedding",
+        "Whether the signaling server is currently shedding low-priority requests"
+    ).unwrap();
+
+    /// 过载降级期间被拒绝的低优先级请求（按请求类型）
+    pub static ref SIGNALING_SHED_REQUESTS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_signaling_shed_requests_total", "Total number of low-priority requests rejected while load shedding")
+            .namespace("actrix"),
+        &["kind"]
+    ).unwrap();
+
+    /// 信令限流器跟踪的令牌桶数量（limiter: connection / message / realm）
+    pub static ref SIGNALING_RATE_LIMIT_BUCKETS: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("actrix_signaling_rate_limit_buckets", "Number of token buckets tracked by each signaling rate limiter")
+            .namespace("actrix"),
+        &["limiter"]
+    ).unwrap();
+
+    /// 信令限流器中令牌已耗尽的令牌桶数量
+    pub static ref SIGNALING_RATE_LIMIT_EXHAUSTED_BUCKETS: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("actrix_signaling_rate_limit_exhausted_buckets", "Number of signaling rate limiter token buckets with no tokens left")
+            .namespace("actrix"),
+        &["limiter"]
+    ).unwrap();
+
+    /// 各 Realm 令牌桶的剩余令牌数（operation: register / relay / discovery）
+    pub static ref SIGNALING_REALM_RATE_LIMIT_TOKENS: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("actrix_signaling_realm_rate_limit_tokens", "Remaining tokens in each realm rate limit bucket")
+            .namespace("actrix"),
+        &["realm_id", "operation"]
+    ).unwrap();
+
+    /// 被 Realm 级限流拒绝的请求（按 Realm 与操作）
+    pub static ref SIGNALING_REALM_RATE_LIMITED: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_signaling_realm_rate_limited_total", "Total number of signaling requests rejected by realm rate limits")
+            .namespace("actrix"),
+        &["realm_id", "operation"]
+    ).unwrap();
+
+    /// 信令服务是否处于排空状态（1 = 排空中，新注册被重定向）
+    pub static ref SIGNALING_DRAINING: IntGauge = IntGauge::new(
+        "actrix_signaling_draining",
+        "Whether the signaling server is draining and redirecting new registrations"
+    ).unwrap();
+
+    /// 信令 WebSocket 压缩字节数（direction: outbound / inbound，stage: original / compressed）
+    pub static ref SIGNALING_COMPRESSION_BYTES: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_signaling_compression_bytes_total", "Total bytes of compressed signaling messages before and after compression")
+            .namespace("actrix"),
+        &["direction", "stage"]
+    ).unwrap();
+
+    /// 信令 WebSocket 单条消息压缩比（压缩后 / 压缩前）
+    pub static ref SIGNALING_COMPRESSION_RATIO: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("actrix_signaling_compression_ratio", "Compressed to original size ratio of signaling messages")
+            .namespace("actrix")
+            .buckets(vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]),
+        &["direction"]
+    ).unwrap();
+
+    /// 信令 envelope 单条消息编解码耗时（operation: encode / decode）
+    pub static ref SIGNALING_ENVELOPE_CODEC_SECONDS: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("actrix_signaling_envelope_codec_seconds", "Time spent encoding or decoding a single signaling envelope in seconds")
+            .namespace("actrix")
+            .buckets(vec![0.000001, 0.000005, 0.00001, 0.000025, 0.00005, 0.0001, 0.00025, 0.0005, 0.001, 0.005]),
+        &["operation"]
+    ).unwrap();
 }
 
 /// 注册所有指标到全局 Registry
@@ -185,6 +308,8 @@ pub fn register_metrics() -> Result<(), prometheus::Error> {
             REGISTRY.register(Box::new(CACHE_HITS.clone()))?;
             REGISTRY.register(Box::new(CACHE_MISSES.clone()))?;
             REGISTRY.register(Box::new(DB_CONNECTIONS.clone()))?;
+            REGISTRY.register(Box::new(SHUTDOWN_ABORTED_TASKS.clone()))?;
+            REGISTRY.register(Box::new(SHUTDOWN_DURATION.clone()))?;
 
             // 安全指标
             REGISTRY.register(Box::new(RATE_LIMIT_EXCEEDED.clone()))?;
@@ -195,11 +320,30 @@ pub fn register_metrics() -> Result<(), prometheus::Error> {
             REGISTRY.register(Box::new(KEYS_GENERATED.clone()))?;
             REGISTRY.register(Box::new(KEY_ROTATIONS.clone()))?;
 
+            // STUN 特定指标
+            REGISTRY.register(Box::new(STUN_REQUESTS.clone()))?;
+
             // TURN 特定指标
             REGISTRY.register(Box::new(TURN_ALLOCATIONS.clone()))?;
             REGISTRY.register(Box::new(TURN_ACTIVE_SESSIONS.clone()))?;
             REGISTRY.register(Box::new(TURN_BYTES_RELAYED.clone()))?;
 
+            // Signaling 特定指标
+            REGISTRY.register(Box::new(ICE_CONNECTION_REPORTS.clone()))?;
+            REGISTRY.register(Box::new(ICE_CONNECTION_RTT.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_AUTHZ_DECISIONS.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_SESSION_RESUMPTIONS.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_LOAD_SHEDDING.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_SHED_REQUESTS.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_RATE_LIMIT_BUCKETS.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_RATE_LIMIT_EXHAUSTED_BUCKETS.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_REALM_RATE_LIMIT_TOKENS.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_REALM_RATE_LIMITED.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_DRAINING.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_COMPRESSION_BYTES.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_COMPRESSION_RATIO.clone()))?;
+            REGISTRY.register(Box::new(SIGNALING_ENVELOPE_CODEC_SECONDS.clone()))?;
+
             Ok::<(), prometheus::Error>(())
         })();
 
@@ -3,6 +3,7 @@
 //! Defines the basic information structure for services
 
 use crate::config::ActrixConfig;
+use crate::config::bind::url_host;
 use crate::monitoring::{ServiceState, service_type::ServiceType};
 use actrix_proto::{ResourceType, ServiceStatus as ProtoServiceStatus};
 use serde::{Deserialize, Serialize};
@@ -31,107 +32,24 @@ impl ServiceInfo {
         description: Option<String>,
         config: &ActrixConfig,
     ) -> Self {
+        // Published endpoints honour the [advertise] / turn.advertised_* overrides
         let (port_info, domain_name) = match service_type {
-            ServiceType::Signaling => {
-                let (port_info, domain_name) = if config.env == "dev" {
-                    // Development environment prefers HTTP
-                    if let Some(ref http_config) = config.bind.http {
-                        (
-                            http_config.port.to_string(),
-                            format!("ws://{}", http_config.domain_name),
-                        )
-                    } else if let Some(ref https_config) = config.bind.https {
-                        (
-                            https_config.port.to_string(),
-                            format!("wss://{}", https_config.domain_name),
-                        )
-                    } else {
-                        ("0".to_string(), "ws://localhost".to_string())
-                    }
-                } else {
-                    // Production environment uses HTTPS
-                    if let Some(ref https_config) = config.bind.https {
-                        (
-                            https_config.port.to_string(),
-                            format!("wss://{}", https_config.domain_name),
-                        )
-                    } else {
-                        ("0".to_string(), "wss://localhost".to_string())
-                    }
-                };
-                (port_info, domain_name)
-            }
+            ServiceType::Signaling => config
+                .public_url()
+                .ok()
+                .and_then(|public_url| Url::parse(&config.signaling_ws_url(&public_url)).ok())
+                .map_or_else(|| placeholder(config, "ws", "wss"), |url| url_parts(&url)),
+            ServiceType::Ais | ServiceType::Ks => config.public_url().map_or_else(
+                |_| placeholder(config, "http", "https"),
+                |url| url_parts(&url),
+            ),
             ServiceType::Turn => {
-                let (port_info, domain_name) = (
-                    config.bind.ice.port.to_string(),
-                    format!("turn:{}", config.bind.ice.domain_name),
-                );
-                (port_info, domain_name)
+                let (host, port) = config.turn_endpoint();
+                (port.to_string(), format!("turn:{}", url_host(host)))
             }
             ServiceType::Stun => {
-                let (port_info, domain_name) = (
-                    config.bind.ice.port.to_string(),
-                    format!("stun:{}", config.bind.ice.domain_name),
-                );
-                (port_info, domain_name)
-            }
-            ServiceType::Ais => {
-                let (port_info, domain_name) = if config.env == "dev" {
-                    // Development environment prefers HTTP
-                    if let Some(ref http_config) = config.bind.http {
-                        (
-                            http_config.port.to_string(),
-                            format!("http://{}", http_config.domain_name),
-                        )
-                    } else if let Some(ref https_config) = config.bind.https {
-                        (
-                            https_config.port.to_string(),
-                            format!("https://{}", https_config.domain_name),
-                        )
-                    } else {
-                        ("0".to_string(), "http://localhost".to_string())
-                    }
-                } else {
-                    // Production environment uses HTTPS
-                    if let Some(ref https_config) = config.bind.https {
-                        (
-                            https_config.port.to_string(),
-                            format!("https://{}", https_config.domain_name),
-                        )
-                    } else {
-                        ("0".to_string(), "https://localhost".to_string())
-                    }
-                };
-                (port_info, domain_name)
-            }
-            ServiceType::Ks => {
-                let (port_info, domain_name) = if config.env == "dev" {
-                    // Development environment prefers HTTP
-                    if let Some(ref http_config) = config.bind.http {
-                        (
-                            http_config.port.to_string(),
-                            format!("http://{}", http_config.domain_name),
-                        )
-                    } else if let Some(ref https_config) = config.bind.https {
-                        (
-                            https_config.port.to_string(),
-                            format!("https://{}", https_config.domain_name),
-                        )
-                    } else {
-                        ("0".to_string(), "http://localhost".to_string())
-                    }
-                } else {
-                    // Production environment uses HTTPS
-                    if let Some(ref https_config) = config.bind.https {
-                        (
-                            https_config.port.to_string(),
-                            format!("https://{}", https_config.domain_name),
-                        )
-                    } else {
-                        ("0".to_string(), "https://localhost".to_string())
-                    }
-                };
-                (port_info, domain_name)
+                let (host, port) = config.stun_endpoint();
+                (port.to_string(), format!("stun:{}", url_host(host)))
             }
         };
         Self {
@@ -181,6 +99,28 @@ impl ServiceInfo {
     }
 }
 
+/// Split a published URL into (port, "scheme://host")
+fn url_parts(url: &Url) -> (String, String) {
+    (
+        url.port_or_known_default().unwrap_or(0).to_string(),
+        format!(
+            "{}://{}",
+            url.scheme(),
+            url.host_str().unwrap_or("localhost")
+        ),
+    )
+}
+
+/// Placeholder endpoint when no HTTP listener is configured
+fn placeholder(config: &ActrixConfig, dev_scheme: &str, scheme: &str) -> (String, String) {
+    let scheme = if config.env == "dev" {
+        dev_scheme
+    } else {
+        scheme
+    };
+    ("0".to_string(), format!("{scheme}://localhost"))
+}
+
 /// Convert ServiceInfo to proto ServiceStatus
 impl From<&ServiceInfo> for ProtoServiceStatus {
     fn from(service_info: &ServiceInfo) -> Self {
@@ -43,6 +43,11 @@ impl ServiceCollector {
         self.inner.write().await.insert(key, value);
     }
 
+    /// Remove a service info entry (e.g. when a service is disabled at runtime)
+    pub async fn remove(&self, key: &str) -> Option<ServiceInfo> {
+        self.inner.write().await.remove(key)
+    }
+
     /// Get all service statuses as proto ServiceStatus
     ///
     /// Converts all ServiceInfo entries to ServiceStatus using the From trait.
@@ -4,10 +4,13 @@
 
 use actrix_proto::ResourceType;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumString};
 
 /// Service type enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, Display, PartialEq, Eq)]
+///
+/// Parses case-insensitively from the variant name (e.g. `"signaling"`).
+#[derive(Debug, Clone, Serialize, Deserialize, Display, EnumString, PartialEq, Eq, Hash)]
+#[strum(ascii_case_insensitive)]
 pub enum ServiceType {
     Stun,
     Turn,
@@ -35,3 +38,49 @@ impl From<&ServiceType> for ResourceType {
         Self::from(service_type.clone())
     }
 }
+
+/// Convert ResourceType to ServiceType (`RESOURCE_TYPE_UNSPECIFIED` has no service)
+impl TryFrom<ResourceType> for ServiceType {
+    type Error = ResourceType;
+
+    fn try_from(resource_type: ResourceType) -> Result<Self, Self::Error> {
+        match resource_type {
+            ResourceType::Stun => Ok(ServiceType::Stun),
+            ResourceType::Turn => Ok(ServiceType::Turn),
+            ResourceType::Signaling => Ok(ServiceType::Signaling),
+            ResourceType::Ais => Ok(ServiceType::Ais),
+            ResourceType::Ks => Ok(ServiceType::Ks),
+            other => Err(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_service_type() {
+        assert_eq!(
+            "signaling".parse::<ServiceType>(),
+            Ok(ServiceType::Signaling)
+        );
+        assert_eq!("TURN".parse::<ServiceType>(), Ok(ServiceType::Turn));
+        assert!("admin".parse::<ServiceType>().is_err());
+    }
+
+    #[test]
+    fn test_resource_type_roundtrip() {
+        for service_type in [
+            ServiceType::Stun,
+            ServiceType::Turn,
+            ServiceType::Signaling,
+            ServiceType::Ais,
+            ServiceType::Ks,
+        ] {
+            let resource_type = ResourceType::from(&service_type);
+            assert_eq!(ServiceType::try_from(resource_type), Ok(service_type));
+        }
+        assert!(ServiceType::try_from(ResourceType::Unspecified).is_err());
+    }
+}
@@ -1,7 +1,30 @@
 //! Actor 访问控制列表
 //!
 //! 定义了 Actor 的权限控制数据结构
+//!
+//! 规则在注册时写入，也可以通过 [`AclUpdate`] 在运行时整体替换或增量修改某个类型的入站规则；
+//! 发现与中继每次都从数据库读取规则，更新立即生效。
+//!
+//! # 来源主体
+//! 规则的 `from_type` 是一个主体模式（见 [`AclPrincipal`]），`to_type` 是具体的目标类型：
+//! - `acme:client:1.2`：精确类型（含版本）；不含 `:` 的旧式类型名同样按精确匹配
+//! - `acme:client`：该类型的任意版本
+//! - `acme:*`：该 manufacturer 下的任意类型
+//! - `#trusted`：属于 `trusted` 组的任意 Actor
+//! - `*`：任意 Actor
+//!
+//! # 优先级
+//! 对同一目标类型，先找出与来源匹配的全部规则，只取其中最具体一级：
+//! 精确类型 > 类型 > manufacturer 通配 > 标签组 > 任意。
+//! 同一级内只要有一条 DENY 即拒绝（deny 优先于 allow），全部为 ALLOW 才允许；
+//! 没有任何规则匹配时拒绝。结果与规则的存储顺序无关。
+//!
+//! # 标签组
+//! 组成员由 Realm 管理员分配（见 [`ActorAcl::set_group_members`]），成员是类型模式
+//! （精确类型、`manufacturer:name` 或 `manufacturer:*`）。成员关系只保存在服务端：
+//! Actor 在 ServiceSpec 中自报的标签不参与 `#tag` 匹配，否则任何 Actor 都能自行加入组。
 use anyhow::Result;
+use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +35,239 @@ const ANONYMOUS_ACTOR_TYPE: &str = "ANONCLNT";
 const VOICE_ACTOR_TYPE: &str = "VOICE";
 const CHAT_ACTOR_TYPE: &str = "CHAT";
 
+/// 类型名最大长度
+const MAX_TYPE_LEN: usize = 256;
+
+/// 单次更新的最大规则数
+const MAX_UPDATE_RULES: usize = 1024;
+
+/// 单个标签组的最大成员数
+const MAX_GROUP_MEMBERS: usize = 1024;
+
+/// 校验规则中的 Actor 类型名
+fn validate_type(field: &str, value: &str) -> Result<(), RealmError> {
+    if value.is_empty() {
+        return Err(RealmError::ValidationError(format!(
+            "{field} must not be empty"
+        )));
+    }
+    if value.len() > MAX_TYPE_LEN {
+        return Err(RealmError::ValidationError(format!(
+            "{field} exceeds {MAX_TYPE_LEN} bytes"
+        )));
+    }
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(RealmError::ValidationError(format!(
+            "{field} must not contain whitespace or control characters: {value:?}"
+        )));
+    }
+    Ok(())
+}
+
+/// 校验主体模式（`from_type`）
+fn validate_principal(field: &str, value: &str) -> Result<(), RealmError> {
+    validate_type(field, value)?;
+    AclPrincipal::parse(value).map(|_| ())
+}
+
+/// 校验目标类型（`to_type`），目标必须是具体类型
+fn validate_target(field: &str, value: &str) -> Result<(), RealmError> {
+    validate_type(field, value)?;
+    if value.contains('*') || value.starts_with('#') {
+        return Err(RealmError::ValidationError(format!(
+            "{field} must be a concrete actor type: {value}"
+        )));
+    }
+    Ok(())
+}
+
+/// 校验标签组成员：只能是类型模式，不能是 `*` 或另一个组
+fn validate_group_member(field: &str, value: &str) -> Result<(), RealmError> {
+    validate_type(field, value)?;
+    match AclPrincipal::parse(value)? {
+        AclPrincipal::Any | AclPrincipal::Tag(_) => Err(RealmError::ValidationError(format!(
+            "{field} must be an actor type pattern: {value}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// 规则来源主体，由 `from_type` 解析
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclPrincipal<'a> {
+    /// `*`
+    Any,
+    /// `#tag`
+    Tag(&'a str),
+    /// `manufacturer:*`
+    Manufacturer(&'a str),
+    /// `manufacturer:name`，匹配任意版本
+    Type(&'a str),
+    /// `manufacturer:name:version` 或不含 `:` 的旧式类型名
+    Exact(&'a str),
+}
+
+impl<'a> AclPrincipal<'a> {
+    /// 解析主体模式
+    pub fn parse(pattern: &'a str) -> Result<Self, RealmError> {
+        let invalid = || RealmError::ValidationError(format!("invalid ACL principal: {pattern}"));
+
+        if pattern == "*" {
+            return Ok(Self::Any);
+        }
+        if let Some(tag) = pattern.strip_prefix('#') {
+            if tag.is_empty() || tag.contains(['#', '*']) {
+                return Err(invalid());
+            }
+            return Ok(Self::Tag(tag));
+        }
+        if let Some(manufacturer) = pattern.strip_suffix(":*") {
+            if manufacturer.is_empty() || manufacturer.contains([':', '*']) {
+                return Err(invalid());
+            }
+            return Ok(Self::Manufacturer(manufacturer));
+        }
+        if pattern.contains(['*', '#']) || pattern.split(':').any(str::is_empty) {
+            return Err(invalid());
+        }
+        match pattern.matches(':').count() {
+            1 => Ok(Self::Type(pattern)),
+            _ => Ok(Self::Exact(pattern)),
+        }
+    }
+
+    /// 优先级，数值越大越具体
+    pub fn level(&self) -> u8 {
+        match self {
+            Self::Any => 0,
+            Self::Tag(_) => 1,
+            Self::Manufacturer(_) => 2,
+            Self::Type(_) => 3,
+            Self::Exact(_) => 4,
+        }
+    }
+
+    /// 是否匹配来源 Actor（`actor_type` 为 `manufacturer:name[:version]`）
+    pub fn matches(&self, actor_type: &str, tags: &[String]) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Tag(tag) => tags.iter().any(|t| t == tag),
+            Self::Manufacturer(manufacturer) => actor_type
+                .split_once(':')
+                .is_some_and(|(m, _)| m == *manufacturer),
+            Self::Type(type_name) => {
+                actor_type == *type_name
+                    || actor_type
+                        .strip_prefix(*type_name)
+                        .is_some_and(|rest| rest.starts_with(':'))
+            }
+            Self::Exact(exact) => actor_type == *exact,
+        }
+    }
+}
+
+/// 按优先级规则计算来源对目标类型的访问权限
+///
+/// `rules` 为目标类型的入站规则；没有规则匹配时返回 None
+pub fn evaluate(rules: &[ActorAcl], from_type: &str, from_tags: &[String]) -> Option<bool> {
+    // (最具体的匹配级别, 该级别的访问结果)
+    let mut best: Option<(u8, bool)> = None;
+    for rule in rules {
+        let principal = match AclPrincipal::parse(&rule.from_type) {
+            Ok(principal) => principal,
+            Err(_) => {
+                tracing::warn!(
+                    rowid = ?rule.rowid,
+                    from_type = %rule.from_type,
+                    "Skipping ACL rule with invalid principal"
+                );
+                continue;
+            }
+        };
+        if !principal.matches(from_type, from_tags) {
+            continue;
+        }
+        let level = principal.level();
+        best = match best {
+            Some((best_level, access)) if best_level > level => Some((best_level, access)),
+            // 同一级内 deny 优先
+            Some((best_level, access)) if best_level == level => {
+                Some((level, access && rule.access))
+            }
+            _ => Some((level, rule.access)),
+        };
+    }
+    best.map(|(_, access)| access)
+}
+
+/// 运行时 ACL 更新方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AclUpdateMode {
+    /// 用 `rules` 整体替换目标类型的全部入站规则
+    #[default]
+    Replace,
+    /// 逐条新增或更新 `rules`，并删除 `remove` 中的来源类型
+    Patch,
+}
+
+/// 入站规则：`from_type` 对目标类型的访问权限
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InboundAclRule {
+    pub from_type: String,
+    pub access: bool,
+}
+
+/// 运行时 ACL 更新请求
+///
+/// 作用于某个目标类型的入站规则（`from_type -> to_type`），与注册时 ACL 的语义一致
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AclUpdate {
+    #[serde(default)]
+    pub mode: AclUpdateMode,
+    #[serde(default)]
+    pub rules: Vec<InboundAclRule>,
+    /// 要删除的来源类型，仅 Patch 模式可用
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl AclUpdate {
+    /// 校验更新内容
+    pub fn validate(&self) -> Result<(), RealmError> {
+        if self.mode == AclUpdateMode::Replace && !self.remove.is_empty() {
+            return Err(RealmError::ValidationError(
+                "remove is only allowed in patch mode".to_string(),
+            ));
+        }
+        if self.rules.len() + self.remove.len() > MAX_UPDATE_RULES {
+            return Err(RealmError::ValidationError(format!(
+                "too many rules in one update (max {MAX_UPDATE_RULES})"
+            )));
+        }
+
+        let mut seen = HashSet::new();
+        for rule in &self.rules {
+            validate_principal("from_type", &rule.from_type)?;
+            if !seen.insert(rule.from_type.as_str()) {
+                return Err(RealmError::ValidationError(format!(
+                    "duplicate rule for from_type {}",
+                    rule.from_type
+                )));
+            }
+        }
+        for from_type in &self.remove {
+            validate_principal("remove", from_type)?;
+            if seen.contains(from_type.as_str()) {
+                return Err(RealmError::ValidationError(format!(
+                    "from_type {from_type} is both updated and removed"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Actor 访问控制列表
 ///
 /// 管理不同类型 Actor 之间的访问权限
@@ -182,9 +438,172 @@ impl ActorAcl {
         self.access
     }
 
+    /// 校验规则的来源与目标类型
+    pub fn validate(&self) -> Result<(), RealmError> {
+        validate_principal("from_type", &self.from_type)?;
+        validate_target("to_type", &self.to_type)
+    }
+
+    /// 获取目标类型的全部入站规则
+    pub async fn get_inbound(realm_id: u32, to_type: &str) -> Result<Vec<Self>, RealmError> {
+        let db = get_database();
+        let pool = db.get_pool();
+
+        let acls = sqlx::query_as::<_, ActorAcl>(
+            "SELECT rowid, realm_id, from_type, to_type, access FROM actoracl
+             WHERE realm_id = ? AND to_type = ? ORDER BY from_type",
+        )
+        .bind(realm_id)
+        .bind(to_type)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(acls)
+    }
+
+    /// 在一个事务内对目标类型的入站规则应用更新，返回更新后的全部入站规则
+    pub async fn apply_update(
+        realm_id: u32,
+        to_type: &str,
+        update: &AclUpdate,
+    ) -> Result<Vec<Self>, RealmError> {
+        validate_target("to_type", to_type)?;
+        update.validate()?;
+
+        let db = get_database();
+        let mut tx = db.get_pool().begin().await?;
+
+        if update.mode == AclUpdateMode::Replace {
+            sqlx::query("DELETE FROM actoracl WHERE realm_id = ? AND to_type = ?")
+                .bind(realm_id)
+                .bind(to_type)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for from_type in &update.remove {
+            sqlx::query(
+                "DELETE FROM actoracl WHERE realm_id = ? AND from_type = ? AND to_type = ?",
+            )
+            .bind(realm_id)
+            .bind(from_type)
+            .bind(to_type)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for rule in &update.rules {
+            let access = if rule.access { 1 } else { 0 };
+            let updated = sqlx::query(
+                "UPDATE actoracl SET access = ? WHERE realm_id = ? AND from_type = ? AND to_type = ?",
+            )
+            .bind(access)
+            .bind(realm_id)
+            .bind(&rule.from_type)
+            .bind(to_type)
+            .execute(&mut *tx)
+            .await?;
+
+            if updated.rows_affected() == 0 {
+                sqlx::query(
+                    "INSERT INTO actoracl (realm_id, from_type, to_type, access) VALUES (?, ?, ?, ?)",
+                )
+                .bind(realm_id)
+                .bind(&rule.from_type)
+                .bind(to_type)
+                .bind(access)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Self::get_inbound(realm_id, to_type).await
+    }
+
+    /// 获取标签组的成员（按字典序）
+    pub async fn get_group_members(realm_id: u32, tag: &str) -> Result<Vec<String>, RealmError> {
+        let db = get_database();
+        let pool = db.get_pool();
+
+        let members = sqlx::query_scalar::<_, String>(
+            "SELECT member FROM actoracl_group WHERE realm_id = ? AND tag = ? ORDER BY member",
+        )
+        .bind(realm_id)
+        .bind(tag)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// 整体替换标签组的成员，返回替换后的成员；`members` 为空时删除该组
+    pub async fn set_group_members(
+        realm_id: u32,
+        tag: &str,
+        members: &[String],
+    ) -> Result<Vec<String>, RealmError> {
+        validate_principal("tag", &format!("#{tag}"))?;
+        if members.len() > MAX_GROUP_MEMBERS {
+            return Err(RealmError::ValidationError(format!(
+                "too many group members (max {MAX_GROUP_MEMBERS})"
+            )));
+        }
+        for member in members {
+            validate_group_member("member", member)?;
+        }
+
+        let db = get_database();
+        let mut tx = db.get_pool().begin().await?;
+        sqlx::query("DELETE FROM actoracl_group WHERE realm_id = ? AND tag = ?")
+            .bind(realm_id)
+            .bind(tag)
+            .execute(&mut *tx)
+            .await?;
+        for member in members {
+            sqlx::query(
+                "INSERT OR IGNORE INTO actoracl_group (realm_id, tag, member) VALUES (?, ?, ?)",
+            )
+            .bind(realm_id)
+            .bind(tag)
+            .bind(member)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Self::get_group_members(realm_id, tag).await
+    }
+
+    /// 来源类型所属的标签组
+    pub async fn groups_of(realm_id: u32, from_type: &str) -> Result<Vec<String>, RealmError> {
+        let db = get_database();
+        let pool = db.get_pool();
+
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT tag, member FROM actoracl_group WHERE realm_id = ?",
+        )
+        .bind(realm_id)
+        .fetch_all(pool)
+        .await?;
+
+        let mut groups: Vec<String> = rows
+            .into_iter()
+            .filter(|(_, member)| {
+                AclPrincipal::parse(member).is_ok_and(|pattern| pattern.matches(from_type, &[]))
+            })
+            .map(|(tag, _)| tag)
+            .collect();
+        groups.sort();
+        groups.dedup();
+        Ok(groups)
+    }
+
     /// Check if discovery is allowed between two actor types
     ///
-    /// Used for Presence notification filtering and service discovery
+    /// Used for Presence notification filtering and service discovery.
+    /// Rules are evaluated with the precedence described in the module docs;
+    /// `#tag` rules match by the realm's admin-assigned groups.
     ///
     /// # Arguments
     ///
@@ -201,16 +620,23 @@ impl ActorAcl {
         from_type: &str,
         to_type: &str,
     ) -> Result<bool, RealmError> {
-        match Self::get_by_types(realm_id, from_type, to_type).await? {
-            Some(acl) => {
+        let rules = Self::get_inbound(realm_id, to_type).await?;
+        // 只有存在组规则时才需要查询组成员
+        let groups = if rules.iter().any(|rule| rule.from_type.starts_with('#')) {
+            Self::groups_of(realm_id, from_type).await?
+        } else {
+            Vec::new()
+        };
+        match evaluate(&rules, from_type, &groups) {
+            Some(access) => {
                 tracing::debug!(
                     realm_id = %realm_id,
                     from_type = %from_type,
                     to_type = %to_type,
-                    access = acl.access(),
+                    access = access,
                     "ACL rule found"
                 );
-                Ok(acl.access())
+                Ok(access)
             }
             None => {
                 // Default policy: deny if no rule exists
@@ -361,4 +787,301 @@ mod tests {
 
         Ok(())
     }
+
+    fn rule(from_type: &str, access: bool) -> InboundAclRule {
+        InboundAclRule {
+            from_type: from_type.to_string(),
+            access,
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_apply_update_replace_and_patch() -> anyhow::Result<()> {
+        setup_test_db().await?;
+        let realm_id = rand::random::<u32>();
+        Realm::new(realm_id, format!("acl-update-{realm_id}"))
+            .save()
+            .await?;
+        let to_type = "acme:echo";
+
+        // 注册时写入的规则
+        ActorAcl::new(realm_id, "acme:old".to_string(), to_type.to_string(), true)
+            .save()
+            .await?;
+        // 其他类型的入站规则不受影响
+        ActorAcl::new(
+            realm_id,
+            "acme:old".to_string(),
+            "acme:other".to_string(),
+            true,
+        )
+        .save()
+        .await?;
+
+        let replace = AclUpdate {
+            mode: AclUpdateMode::Replace,
+            rules: vec![rule("acme:client", true), rule("acme:guest", false)],
+            remove: vec![],
+        };
+        let rules = ActorAcl::apply_update(realm_id, to_type, &replace).await?;
+        let from_types: Vec<&str> = rules.iter().map(|r| r.from_type.as_str()).collect();
+        assert_eq!(from_types, vec!["acme:client", "acme:guest"]);
+        assert!(!ActorAcl::can_discover(realm_id, "acme:old", to_type).await?);
+        assert!(ActorAcl::can_discover(realm_id, "acme:old", "acme:other").await?);
+
+        let patch = AclUpdate {
+            mode: AclUpdateMode::Patch,
+            rules: vec![rule("acme:guest", true), rule("acme:admin", true)],
+            remove: vec!["acme:client".to_string()],
+        };
+        let rules = ActorAcl::apply_update(realm_id, to_type, &patch).await?;
+        assert_eq!(rules.len(), 2);
+        assert!(ActorAcl::can_discover(realm_id, "acme:guest", to_type).await?);
+        assert!(ActorAcl::can_discover(realm_id, "acme:admin", to_type).await?);
+        assert!(!ActorAcl::can_discover(realm_id, "acme:client", to_type).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_group_rules_use_assigned_members() -> anyhow::Result<()> {
+        setup_test_db().await?;
+        let realm_id = rand::random::<u32>();
+        Realm::new(realm_id, format!("acl-group-{realm_id}"))
+            .save()
+            .await?;
+        let to_type = "acme:vault";
+        ActorAcl::apply_update(
+            realm_id,
+            to_type,
+            &AclUpdate {
+                rules: vec![rule("#trusted", true)],
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        // 未分配到组时，无论 Actor 自报什么标签都不匹配
+        assert!(!ActorAcl::can_discover(realm_id, "acme:client:1", to_type).await?);
+
+        let members = ActorAcl::set_group_members(
+            realm_id,
+            "trusted",
+            &["acme:client".to_string(), "partner:*".to_string()],
+        )
+        .await?;
+        assert_eq!(members, vec!["acme:client", "partner:*"]);
+        assert!(ActorAcl::can_discover(realm_id, "acme:client:1", to_type).await?);
+        assert!(ActorAcl::can_discover(realm_id, "partner:x", to_type).await?);
+        assert!(!ActorAcl::can_discover(realm_id, "acme:server", to_type).await?);
+        assert_eq!(
+            ActorAcl::groups_of(realm_id, "acme:client:1").await?,
+            vec!["trusted"]
+        );
+
+        // 清空成员即删除组
+        ActorAcl::set_group_members(realm_id, "trusted", &[]).await?;
+        assert!(!ActorAcl::can_discover(realm_id, "acme:client:1", to_type).await?);
+
+        for bad in ["*", "#other", "acme: x"] {
+            assert!(
+                ActorAcl::set_group_members(realm_id, "trusted", &[bad.to_string()])
+                    .await
+                    .is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+        assert!(
+            ActorAcl::set_group_members(realm_id, "a*b", &[])
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_acl_update_validation() {
+        let valid = AclUpdate {
+            mode: AclUpdateMode::Patch,
+            rules: vec![rule("acme:client", true)],
+            remove: vec!["acme:guest".to_string()],
+        };
+        assert!(valid.validate().is_ok());
+
+        let remove_in_replace = AclUpdate {
+            mode: AclUpdateMode::Replace,
+            ..valid.clone()
+        };
+        assert!(remove_in_replace.validate().is_err());
+
+        let duplicate = AclUpdate {
+            rules: vec![rule("acme:client", true), rule("acme:client", false)],
+            ..Default::default()
+        };
+        assert!(duplicate.validate().is_err());
+
+        let conflicting = AclUpdate {
+            remove: vec!["acme:client".to_string()],
+            ..valid.clone()
+        };
+        assert!(conflicting.validate().is_err());
+
+        for bad in ["", "acme: client", &"x".repeat(MAX_TYPE_LEN + 1)] {
+            let update = AclUpdate {
+                rules: vec![rule(bad, true)],
+                ..Default::default()
+            };
+            assert!(update.validate().is_err(), "{bad:?} should be rejected");
+        }
+    }
+
+    fn acl(from_type: &str, access: bool) -> ActorAcl {
+        ActorAcl::new(1, from_type.to_string(), "acme:echo".to_string(), access)
+    }
+
+    #[test]
+    fn test_principal_parse() {
+        assert_eq!(AclPrincipal::parse("*").unwrap(), AclPrincipal::Any);
+        assert_eq!(
+            AclPrincipal::parse("#trusted").unwrap(),
+            AclPrincipal::Tag("trusted")
+        );
+        assert_eq!(
+            AclPrincipal::parse("acme:*").unwrap(),
+            AclPrincipal::Manufacturer("acme")
+        );
+        assert_eq!(
+            AclPrincipal::parse("acme:client").unwrap(),
+            AclPrincipal::Type("acme:client")
+        );
+        assert_eq!(
+            AclPrincipal::parse("acme:client:1.2").unwrap(),
+            AclPrincipal::Exact("acme:client:1.2")
+        );
+        assert_eq!(
+            AclPrincipal::parse(ANONYMOUS_ACTOR_TYPE).unwrap(),
+            AclPrincipal::Exact(ANONYMOUS_ACTOR_TYPE)
+        );
+        for bad in ["#", "a*:b", "acme:c*", ":*", "acme::x", "#a#b", "*:*"] {
+            assert!(
+                AclPrincipal::parse(bad).is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_precedence() {
+        let trusted = vec!["trusted".to_string()];
+        let rules = vec![
+            acl("*", false),
+            acl("#trusted", true),
+            acl("acme:*", false),
+            acl("acme:client", true),
+            acl("acme:client:2", false),
+        ];
+
+        // 精确版本优先于类型规则
+        assert_eq!(evaluate(&rules, "acme:client:2", &[]), Some(false));
+        assert_eq!(evaluate(&rules, "acme:client:1", &[]), Some(true));
+        // manufacturer 通配优先于标签组
+        assert_eq!(evaluate(&rules, "acme:server", &trusted), Some(false));
+        assert_eq!(evaluate(&rules, "other:server", &trusted), Some(true));
+        assert_eq!(evaluate(&rules, "other:server", &[]), Some(false));
+        // 没有规则匹配
+        assert_eq!(evaluate(&[acl("acme:*", true)], "other:x", &[]), None);
+        // 同一级 deny 优先
+        let conflicting = vec![acl("#trusted", true), acl("#beta", false)];
+        let tags = vec!["trusted".to_string(), "beta".to_string()];
+        assert_eq!(evaluate(&conflicting, "other:x", &tags), Some(false));
+    }
+
+    /// 规范实现：找出全部匹配规则，取最具体一级，该级全部 ALLOW 才允许
+    fn reference_evaluate(rules: &[ActorAcl], from_type: &str, tags: &[String]) -> Option<bool> {
+        let matched: Vec<(u8, bool)> = rules
+            .iter()
+            .filter_map(|rule| {
+                let principal = AclPrincipal::parse(&rule.from_type).ok()?;
+                principal
+                    .matches(from_type, tags)
+                    .then(|| (principal.level(), rule.access))
+            })
+            .collect();
+        let top = matched.iter().map(|(level, _)| *level).max()?;
+        Some(
+            matched
+                .iter()
+                .filter(|(level, _)| *level == top)
+                .all(|(_, access)| *access),
+        )
+    }
+
+    #[test]
+    fn test_evaluate_properties() {
+        use rand::seq::SliceRandom;
+        use rand::{Rng, SeedableRng, rngs::StdRng};
+
+        const PATTERNS: &[&str] = &[
+            "*",
+            "#trusted",
+            "#beta",
+            "acme:*",
+            "other:*",
+            "acme:client",
+            "acme:server",
+            "acme:client:1",
+            "acme:client:2",
+            "other:client",
+        ];
+        const SUBJECTS: &[&str] = &[
+            "acme:client",
+            "acme:client:1",
+            "acme:client:2",
+            "acme:server:1",
+            "other:client",
+            "third:x",
+        ];
+        const TAGS: &[&str] = &["trusted", "beta"];
+
+        let mut rng = StdRng::seed_from_u64(0x5eed_ac1);
+        for _ in 0..2000 {
+            let mut rules: Vec<ActorAcl> = (0..rng.gen_range(0..8))
+                .map(|_| acl(PATTERNS.choose(&mut rng).unwrap(), rng.r#gen()))
+                .collect();
+            let subject = *SUBJECTS.choose(&mut rng).unwrap();
+            let tags: Vec<String> = TAGS
+                .iter()
+                .filter(|_| rng.r#gen())
+                .map(|t| t.to_string())
+                .collect();
+
+            // 与规范实现一致
+            let result = evaluate(&rules, subject, &tags);
+            assert_eq!(result, reference_evaluate(&rules, subject, &tags));
+
+            // 与规则顺序无关
+            rules.shuffle(&mut rng);
+            assert_eq!(evaluate(&rules, subject, &tags), result);
+
+            // 不匹配的规则不影响结果
+            let unrelated = acl("nobody:*", !result.unwrap_or(false));
+            rules.push(unrelated);
+            assert_eq!(evaluate(&rules, subject, &tags), result);
+
+            // 在不低于当前最高匹配级别处追加 DENY，结果必为拒绝
+            let pattern = *PATTERNS.choose(&mut rng).unwrap();
+            let principal = AclPrincipal::parse(pattern).unwrap();
+            let top = rules
+                .iter()
+                .filter_map(|r| AclPrincipal::parse(&r.from_type).ok())
+                .filter(|p| p.matches(subject, &tags))
+                .map(|p| p.level())
+                .max();
+            if principal.matches(subject, &tags) && top.is_none_or(|top| principal.level() >= top) {
+                rules.push(acl(pattern, false));
+                assert_eq!(evaluate(&rules, subject, &tags), Some(false));
+            }
+        }
+    }
 }
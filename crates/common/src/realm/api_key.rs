@@ -0,0 +1,361 @@
+//! Realm API Key
+//!
+//! Realm 范围的 API Key，由 Supervisor 或节点管理 API 为租户签发，
+//! 允许租户自己的工具通过 HTTP 管理接口查询本 Realm 的在线状态、用量并管理 ACL，
+//! 无需持有全局的 `actrix_shared_key`。
+//!
+//! # Token 格式
+//! `rk_<key_id>_<secret>`：`key_id` 为 16 位十六进制公开标识，`secret` 为 32 字节随机数的十六进制。
+//! 数据库只保存 token 的 SHA-256 摘要，明文仅在签发时返回一次。
+
+use chrono::Utc;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+use strum::{Display, EnumString};
+
+use super::error::RealmError;
+use super::model::Realm;
+use crate::storage::db::get_database;
+use crate::util::constant_time_eq;
+
+/// Token 前缀
+const TOKEN_PREFIX: &str = "rk_";
+
+/// Realm API Key 权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RealmApiScope {
+    /// 查询在线 Actor
+    Presence,
+    /// 查询用量（连接数、注册服务、ICE 上报）
+    Usage,
+    /// 查看与管理 ACL 规则
+    Acl,
+}
+
+impl RealmApiScope {
+    /// 全部权限范围
+    pub fn all() -> Vec<Self> {
+        vec![Self::Presence, Self::Usage, Self::Acl]
+    }
+
+    /// 解析权限范围列表，空列表表示全部
+    pub fn parse_list<S: AsRef<str>>(scopes: &[S]) -> Result<Vec<Self>, RealmError> {
+        if scopes.is_empty() {
+            return Ok(Self::all());
+        }
+        let mut parsed = Vec::with_capacity(scopes.len());
+        for scope in scopes {
+            let scope = Self::from_str(scope.as_ref().trim()).map_err(|_| {
+                RealmError::ValidationError(format!("Unknown API key scope: {}", scope.as_ref()))
+            })?;
+            if !parsed.contains(&scope) {
+                parsed.push(scope);
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Realm API Key 记录（不含明文 token）
+#[derive(Debug, Clone, Serialize)]
+pub struct RealmApiKey {
+    #[serde(skip)]
+    pub rowid: Option<i64>,
+    /// 公开标识，用于列出与吊销
+    pub key_id: String,
+    pub realm_id: u32,
+    /// 备注名称
+    pub name: String,
+    pub scopes: Vec<RealmApiScope>,
+    /// 创建时间 (Unix 秒)
+    pub created_at: i64,
+    /// 过期时间 (Unix 秒)，None 表示不过期
+    pub expires_at: Option<i64>,
+    /// 最近一次使用时间 (Unix 秒)
+    pub last_used_at: Option<i64>,
+    pub revoked: bool,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::sqlite::SqliteRow> for RealmApiKey {
+    fn from_row(row: &'r sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        let scopes: String = row.try_get("scopes")?;
+        Ok(Self {
+            rowid: row.try_get("rowid")?,
+            key_id: row.try_get("key_id")?,
+            realm_id: row
+                .try_get::<i64, _>("realm_id")?
+                .try_into()
+                .unwrap_or_default(),
+            name: row.try_get("name")?,
+            scopes: scopes
+                .split(',')
+                .filter_map(|s| RealmApiScope::from_str(s).ok())
+                .collect(),
+            created_at: row.try_get("created_at")?,
+            expires_at: row.try_get("expires_at")?,
+            last_used_at: row.try_get("last_used_at")?,
+            revoked: row.try_get::<i64, _>("revoked")? != 0,
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str = "SELECT rowid, key_id, realm_id, name, scopes, created_at, expires_at, last_used_at, revoked FROM realm_api_key";
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    hex::encode(buf)
+}
+
+/// 从 token 中取出 key_id
+fn parse_key_id(token: &str) -> Option<&str> {
+    let rest = token.strip_prefix(TOKEN_PREFIX)?;
+    let (key_id, secret) = rest.split_once('_')?;
+    (!key_id.is_empty() && !secret.is_empty()).then_some(key_id)
+}
+
+impl RealmApiKey {
+    /// 为 Realm 签发新的 API Key，返回记录与明文 token（仅此一次）
+    ///
+    /// Realm 必须存在
+    pub async fn issue(
+        realm_id: u32,
+        name: &str,
+        scopes: Vec<RealmApiScope>,
+        expires_at: Option<i64>,
+    ) -> Result<(Self, String), RealmError> {
+        if Realm::get_by_realm_id(realm_id).await?.is_none() {
+            return Err(RealmError::NotFound);
+        }
+        if scopes.is_empty() {
+            return Err(RealmError::ValidationError(
+                "API key must have at least one scope".to_string(),
+            ));
+        }
+
+        let key_id = random_hex(8);
+        let token = format!("{TOKEN_PREFIX}{key_id}_{}", random_hex(32));
+        let mut key = Self {
+            rowid: None,
+            key_id,
+            realm_id,
+            name: name.to_string(),
+            scopes,
+            created_at: Utc::now().timestamp(),
+            expires_at,
+            last_used_at: None,
+            revoked: false,
+        };
+
+        let db = get_database();
+        let result = sqlx::query(
+            "INSERT INTO realm_api_key (key_id, realm_id, name, key_hash, scopes, created_at, expires_at, revoked)
+             VALUES (?, ?, ?, ?, ?, ?, ?, 0)",
+        )
+        .bind(&key.key_id)
+        .bind(key.realm_id)
+        .bind(&key.name)
+        .bind(hash_token(&token))
+        .bind(key.scopes_string())
+        .bind(key.created_at)
+        .bind(key.expires_at)
+        .execute(db.get_pool())
+        .await?;
+
+        key.rowid = Some(result.last_insert_rowid());
+        Ok((key, token))
+    }
+
+    /// 校验 token，成功时返回对应的 API Key 并记录使用时间
+    ///
+    /// 已吊销、已过期或所属 Realm 不再活跃的 Key 返回 None
+    pub async fn authenticate(token: &str) -> Result<Option<Self>, RealmError> {
+        let Some(key_id) = parse_key_id(token) else {
+            return Ok(None);
+        };
+
+        let db = get_database();
+        let pool = db.get_pool();
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT key_hash FROM realm_api_key WHERE key_id = ?")
+                .bind(key_id)
+                .fetch_optional(pool)
+                .await?;
+        let Some((stored_hash,)) = row else {
+            return Ok(None);
+        };
+        if !constant_time_eq(stored_hash.as_bytes(), hash_token(token).as_bytes()) {
+            return Ok(None);
+        }
+
+        let Some(key) = Self::get(key_id).await? else {
+            return Ok(None);
+        };
+        if !key.is_usable() {
+            return Ok(None);
+        }
+        match Realm::get_by_realm_id(key.realm_id).await? {
+            Some(realm) if realm.is_active() => {}
+            _ => return Ok(None),
+        }
+
+        sqlx::query("UPDATE realm_api_key SET last_used_at = ? WHERE key_id = ?")
+            .bind(Utc::now().timestamp())
+            .bind(key_id)
+            .execute(pool)
+            .await?;
+
+        Ok(Some(key))
+    }
+
+    /// 按 key_id 获取
+    pub async fn get(key_id: &str) -> Result<Option<Self>, RealmError> {
+        let db = get_database();
+        let key = sqlx::query_as::<_, Self>(&format!("{SELECT_COLUMNS} WHERE key_id = ?"))
+            .bind(key_id)
+            .fetch_optional(db.get_pool())
+            .await?;
+        Ok(key)
+    }
+
+    /// 列出 Realm 的全部 API Key（含已吊销）
+    pub async fn list_by_realm(realm_id: u32) -> Result<Vec<Self>, RealmError> {
+        let db = get_database();
+        let keys = sqlx::query_as::<_, Self>(&format!(
+            "{SELECT_COLUMNS} WHERE realm_id = ? ORDER BY created_at, rowid"
+        ))
+        .bind(realm_id)
+        .fetch_all(db.get_pool())
+        .await?;
+        Ok(keys)
+    }
+
+    /// 吊销 Realm 下的 API Key
+    pub async fn revoke(realm_id: u32, key_id: &str) -> Result<(), RealmError> {
+        let db = get_database();
+        let result =
+            sqlx::query("UPDATE realm_api_key SET revoked = 1 WHERE realm_id = ? AND key_id = ?")
+                .bind(realm_id)
+                .bind(key_id)
+                .execute(db.get_pool())
+                .await?;
+        if result.rows_affected() == 0 {
+            return Err(RealmError::KeyNotExist);
+        }
+        Ok(())
+    }
+
+    /// 删除 Realm 的全部 API Key（删除 Realm 时调用）
+    pub async fn delete_by_realm(realm_id: u32) -> Result<u64, RealmError> {
+        let db = get_database();
+        let result = sqlx::query("DELETE FROM realm_api_key WHERE realm_id = ?")
+            .bind(realm_id)
+            .execute(db.get_pool())
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// 是否拥有指定权限范围
+    pub fn has_scope(&self, scope: RealmApiScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// 未吊销且未过期
+    pub fn is_usable(&self) -> bool {
+        !self.revoked
+            && self
+                .expires_at
+                .is_none_or(|expires_at| Utc::now().timestamp() <= expires_at)
+    }
+
+    fn scopes_string(&self) -> String {
+        self.scopes
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_utils::utils::setup_test_db;
+    use serial_test::serial;
+
+    #[test]
+    fn test_scope_parsing() {
+        assert_eq!(
+            RealmApiScope::parse_list(&["acl", "presence", "acl"]).unwrap(),
+            vec![RealmApiScope::Acl, RealmApiScope::Presence]
+        );
+        assert_eq!(
+            RealmApiScope::parse_list::<&str>(&[]).unwrap(),
+            RealmApiScope::all()
+        );
+        assert!(RealmApiScope::parse_list(&["admin"]).is_err());
+        assert_eq!(parse_key_id("rk_abcd_secret"), Some("abcd"));
+        assert_eq!(parse_key_id("Bearer something"), None);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_issue_authenticate_and_revoke() -> anyhow::Result<()> {
+        setup_test_db().await?;
+
+        let realm_id = rand::random::<u32>();
+        Realm::new(realm_id, "tenant".to_string()).save().await?;
+
+        assert!(matches!(
+            RealmApiKey::issue(realm_id.wrapping_add(1), "x", RealmApiScope::all(), None).await,
+            Err(RealmError::NotFound)
+        ));
+
+        let (key, token) =
+            RealmApiKey::issue(realm_id, "ci", vec![RealmApiScope::Presence], None).await?;
+        assert!(token.starts_with(&format!("rk_{}_", key.key_id)));
+
+        let authenticated = RealmApiKey::authenticate(&token).await?.unwrap();
+        assert_eq!(authenticated.realm_id, realm_id);
+        assert!(authenticated.has_scope(RealmApiScope::Presence));
+        assert!(!authenticated.has_scope(RealmApiScope::Acl));
+
+        // 篡改 secret 不能通过
+        let last = if token.ends_with('0') { '1' } else { '0' };
+        let forged = format!("{}{last}", &token[..token.len() - 1]);
+        assert!(RealmApiKey::authenticate(&forged).await?.is_none());
+
+        let listed = RealmApiKey::list_by_realm(realm_id).await?;
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].last_used_at.is_some());
+
+        // 已过期的 Key 不能使用
+        let (_, expired_token) = RealmApiKey::issue(
+            realm_id,
+            "expired",
+            RealmApiScope::all(),
+            Some(Utc::now().timestamp() - 1),
+        )
+        .await?;
+        assert!(RealmApiKey::authenticate(&expired_token).await?.is_none());
+
+        RealmApiKey::revoke(realm_id, &key.key_id).await?;
+        assert!(RealmApiKey::authenticate(&token).await?.is_none());
+        assert!(matches!(
+            RealmApiKey::revoke(realm_id, "missing").await,
+            Err(RealmError::KeyNotExist)
+        ));
+
+        assert_eq!(RealmApiKey::delete_by_realm(realm_id).await?, 2);
+        Ok(())
+    }
+}
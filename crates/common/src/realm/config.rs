@@ -156,6 +156,19 @@ impl RealmConfig {
         self.value = value;
     }
 
+    pub async fn delete_by_realm_and_key(realm_rowid: i64, key: &str) -> Result<u64, RealmError> {
+        let db = get_database();
+        let pool = db.get_pool();
+
+        let result = sqlx::query("DELETE FROM realmconfig WHERE realm_rowid = ? AND key = ?")
+            .bind(realm_rowid)
+            .bind(key)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     pub async fn delete_by_realm(realm_rowid: i64) -> Result<u64, RealmError> {
         let db = get_database();
         let pool = db.get_pool();
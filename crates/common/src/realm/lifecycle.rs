@@ -0,0 +1,204 @@
+//! Realm 生命周期管理
+//!
+//! Realm 状态流转：`Normal` →（到期或 Supervisor 停用）→ `Suspended` → `Terminated`。
+//! 注册、中继等入口统一通过 [`Realm::validate_realm`] 拒绝非 Normal 或已过期的 Realm；
+//! [`RealmLifecycleManager`] 定期把已到期但仍为 Normal 的 Realm 落库为 Suspended，
+//! 使状态与 `expires_at` 保持一致，并将状态变更记录为事件，随下一次状态报告上报给 Supervisor。
+
+use super::error::RealmError;
+use super::model::{Realm, RealmStatus};
+use chrono::Utc;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// 默认到期检查间隔
+pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 等待上报的事件上限，超出时丢弃最早的事件
+const MAX_PENDING_EVENTS: usize = 1024;
+
+/// 到期停用事件的原因
+pub const REASON_EXPIRED: &str = "expired";
+
+/// Realm 状态变更事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RealmLifecycleEvent {
+    pub realm_id: u32,
+    /// 变更前状态
+    pub previous: RealmStatus,
+    /// 变更后状态
+    pub current: RealmStatus,
+    /// 变更原因，如 [`REASON_EXPIRED`]
+    pub reason: String,
+    /// 变更时间（Unix timestamp，秒）
+    pub timestamp: i64,
+}
+
+static PENDING_EVENTS: Mutex<VecDeque<RealmLifecycleEvent>> = Mutex::new(VecDeque::new());
+
+/// 记录一条待上报的状态变更事件
+pub fn record_event(event: RealmLifecycleEvent) {
+    let mut pending = PENDING_EVENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if pending.len() >= MAX_PENDING_EVENTS {
+        warn!(
+            "Realm 生命周期事件积压超过 {}，丢弃最早的事件",
+            MAX_PENDING_EVENTS
+        );
+        pending.pop_front();
+    }
+    pending.push_back(event);
+}
+
+/// 取出全部待上报事件
+pub fn take_events() -> Vec<RealmLifecycleEvent> {
+    PENDING_EVENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .drain(..)
+        .collect()
+}
+
+/// 上报失败时放回事件，保持原有顺序排在新事件之前
+pub fn requeue_events(events: Vec<RealmLifecycleEvent>) {
+    let mut pending = PENDING_EVENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for event in events.into_iter().rev() {
+        if pending.len() >= MAX_PENDING_EVENTS {
+            break;
+        }
+        pending.push_front(event);
+    }
+}
+
+/// 后台 Realm 到期检查任务
+pub struct RealmLifecycleManager {
+    interval: Duration,
+}
+
+impl Default for RealmLifecycleManager {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHECK_INTERVAL)
+    }
+}
+
+impl RealmLifecycleManager {
+    /// 创建到期检查任务，每隔 `interval` 检查一次
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+
+    /// 将 `now` 时已到期的 Normal Realm 置为 Suspended，返回被停用的 Realm ID
+    pub async fn suspend_expired(now: i64) -> Result<Vec<u32>, RealmError> {
+        let mut suspended = Vec::new();
+        for mut realm in Realm::list_expired(now).await? {
+            realm.set_status(RealmStatus::Suspended);
+            realm.save().await?;
+
+            info!("⏰ Realm {} 已到期，状态置为 Suspended", realm.realm_id);
+            record_event(RealmLifecycleEvent {
+                realm_id: realm.realm_id,
+                previous: RealmStatus::Normal,
+                current: RealmStatus::Suspended,
+                reason: REASON_EXPIRED.to_string(),
+                timestamp: now,
+            });
+            suspended.push(realm.realm_id);
+        }
+        Ok(suspended)
+    }
+
+    /// 启动后台检查（启动时立即检查一次），收到关闭信号后退出
+    pub fn spawn(self, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("Realm 到期检查已启动: interval={:?}", self.interval);
+
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        info!("Realm 到期检查收到关闭信号");
+                        break;
+                    }
+                    _ = ticker.tick() => {
+                        if let Err(e) = Self::suspend_expired(Utc::now().timestamp()).await {
+                            error!("Realm 到期检查失败: {}", e);
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_utils::utils::setup_test_db;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn test_suspend_expired() -> anyhow::Result<()> {
+        setup_test_db().await?;
+        let now = Utc::now().timestamp();
+
+        let expired_id = rand::random::<u32>();
+        Realm::new(expired_id, format!("expired-{expired_id}"))
+            .with_expires_at(now - 60)
+            .save()
+            .await?;
+        let active_id = rand::random::<u32>();
+        Realm::new(active_id, format!("active-{active_id}"))
+            .with_expires_at(now + 3600)
+            .save()
+            .await?;
+        take_events();
+
+        let suspended = RealmLifecycleManager::suspend_expired(now).await?;
+        assert!(suspended.contains(&expired_id));
+        assert!(!suspended.contains(&active_id));
+
+        let expired = Realm::get_by_realm_id(expired_id).await?.unwrap();
+        assert_eq!(expired.status(), RealmStatus::Suspended);
+        let active = Realm::get_by_realm_id(active_id).await?.unwrap();
+        assert_eq!(active.status(), RealmStatus::Normal);
+
+        // 已停用的 Realm 不会重复产生事件
+        let events = take_events();
+        assert!(events.iter().any(|e| e.realm_id == expired_id
+            && e.current == RealmStatus::Suspended
+            && e.reason == REASON_EXPIRED));
+        RealmLifecycleManager::suspend_expired(now).await?;
+        assert!(take_events().iter().all(|e| e.realm_id != expired_id));
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_requeue_keeps_order() {
+        take_events();
+        let event = |realm_id| RealmLifecycleEvent {
+            realm_id,
+            previous: RealmStatus::Normal,
+            current: RealmStatus::Suspended,
+            reason: REASON_EXPIRED.to_string(),
+            timestamp: 0,
+        };
+        record_event(event(1));
+        let failed = take_events();
+        record_event(event(2));
+        requeue_events(failed);
+
+        let ids: Vec<u32> = take_events().iter().map(|e| e.realm_id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+}
@@ -4,21 +4,36 @@
 //!
 //! 按照概念独立性原则组织，每个概念都有独立的文件：
 //! - `model.rs` - 核心 Realm 数据结构
+//! - `api_key.rs` - Realm 范围的租户 API Key
+//! - `lifecycle.rs` - Realm 到期停用与状态变更事件
+//! - `usage.rs` - Realm 用量计量
 //! - `repository.rs` - 数据库操作
+//! - `rate_limit.rs` - Realm 级速率限额
+//! - `quota.rs` - Realm 级配额（并发 Actor 数、注册数）
 //! - `validation.rs` - 业务规则验证
 
 // 子模块
 pub mod acl;
+pub mod api_key;
 pub mod config;
 pub mod error;
+pub mod lifecycle;
 pub mod model;
+pub mod quota;
+pub mod rate_limit;
 pub mod repository;
 pub mod service_type;
+pub mod usage;
 pub mod validation;
 
 // 公共API导出
-pub use acl::ActorAcl;
+pub use acl::{AclUpdate, AclUpdateMode, ActorAcl, InboundAclRule};
+pub use api_key::{RealmApiKey, RealmApiScope};
 pub use config::RealmConfig;
 pub use error::RealmError;
+pub use lifecycle::{RealmLifecycleEvent, RealmLifecycleManager};
 pub use model::{Realm, RealmStatus};
+pub use quota::RealmQuota;
+pub use rate_limit::RealmRateLimits;
 pub use service_type::ServiceType;
+pub use usage::RealmUsageSnapshot;
@@ -0,0 +1,161 @@
+//! Realm 级配额
+//!
+//! 配额由 Supervisor 在创建或更新 Realm 时下发，以键值对形式存储在 Realm 元数据
+//! （`realmconfig` 表）中：
+//! - Signaling 注册时检查 `max_concurrent_actors`（当前在线 Actor 数）
+//! - AIS 签发凭证时检查 `max_registrations`（凭证未过期的注册数）
+
+use super::config::RealmConfig;
+use super::error::RealmError;
+use super::model::Realm;
+
+/// 最大并发 Actor 数的配置键
+pub const REALM_MAX_CONCURRENT_ACTORS_KEY: &str = "quota.max_concurrent_actors";
+/// 最大注册数的配置键
+pub const REALM_MAX_REGISTRATIONS_KEY: &str = "quota.max_registrations";
+
+/// Realm 元数据中的配额
+///
+/// `None` 与 `Some(0)` 均表示不限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RealmQuota {
+    pub max_concurrent_actors: Option<u32>,
+    pub max_registrations: Option<u32>,
+}
+
+impl RealmQuota {
+    /// 是否未设置任何配额
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 从 Realm 配置项读取配额
+    pub async fn load(realm_rowid: i64) -> Result<Self, RealmError> {
+        let configs = RealmConfig::get_by_realm(realm_rowid).await?;
+        Ok(Self::from_configs(&configs))
+    }
+
+    /// 按 realm_id 读取配额，Realm 不存在时返回空配额
+    pub async fn load_for_realm(realm_id: u32) -> Result<Self, RealmError> {
+        match Realm::get_by_realm_id(realm_id)
+            .await?
+            .and_then(|realm| realm.rowid)
+        {
+            Some(rowid) => Self::load(rowid).await,
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 写入配额：有值的键被创建或更新，`None` 的键被删除
+    pub async fn save(&self, realm_rowid: i64) -> Result<(), RealmError> {
+        for (key, value) in self.entries() {
+            match value {
+                Some(value) => {
+                    let value = value.to_string();
+                    match RealmConfig::get_by_realm_and_key(realm_rowid, key).await? {
+                        Some(mut config) => {
+                            config.set_value(value);
+                            config.save().await?;
+                        }
+                        None => {
+                            RealmConfig::new(realm_rowid, key.to_string(), value)
+                                .save()
+                                .await?;
+                        }
+                    }
+                }
+                None => {
+                    RealmConfig::delete_by_realm_and_key(realm_rowid, key).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 检查并发 Actor 配额，`active` 为当前在线 Actor 数
+    pub fn check_concurrent_actors(&self, realm_id: u32, active: u64) -> Result<(), String> {
+        check_limit(self.max_concurrent_actors, active)
+            .map_err(|max| format!("Realm {realm_id} reached its concurrent actor quota ({max})"))
+    }
+
+    /// 检查注册配额，`registered` 为凭证未过期的注册数
+    pub fn check_registrations(&self, realm_id: u32, registered: u64) -> Result<(), String> {
+        check_limit(self.max_registrations, registered)
+            .map_err(|max| format!("Realm {realm_id} reached its registration quota ({max})"))
+    }
+
+    fn entries(&self) -> [(&'static str, Option<u32>); 2] {
+        [
+            (REALM_MAX_CONCURRENT_ACTORS_KEY, self.max_concurrent_actors),
+            (REALM_MAX_REGISTRATIONS_KEY, self.max_registrations),
+        ]
+    }
+
+    fn from_configs(configs: &[RealmConfig]) -> Self {
+        let get = |key: &str| {
+            configs
+                .iter()
+                .find(|config| config.key() == key)
+                .and_then(|config| config.value().trim().parse::<u32>().ok())
+        };
+
+        Self {
+            max_concurrent_actors: get(REALM_MAX_CONCURRENT_ACTORS_KEY),
+            max_registrations: get(REALM_MAX_REGISTRATIONS_KEY),
+        }
+    }
+}
+
+/// 已用量达到上限时返回上限
+fn check_limit(limit: Option<u32>, used: u64) -> Result<(), u32> {
+    match limit {
+        Some(max) if max > 0 && used >= u64::from(max) => Err(max),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_utils::utils::setup_test_db;
+    use serial_test::serial;
+
+    #[test]
+    fn test_check_limits() {
+        let quota = RealmQuota {
+            max_concurrent_actors: Some(2),
+            max_registrations: Some(0),
+        };
+        assert!(quota.check_concurrent_actors(1, 1).is_ok());
+        assert!(quota.check_concurrent_actors(1, 2).is_err());
+        // 0 表示不限制
+        assert!(quota.check_registrations(1, 10_000).is_ok());
+        assert!(
+            RealmQuota::default()
+                .check_concurrent_actors(1, 10_000)
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_save_and_load_round_trip() -> anyhow::Result<()> {
+        setup_test_db().await?;
+
+        let realm_id = rand::random::<u32>();
+        let mut realm = Realm::new(realm_id, "quota_realm".to_string());
+        let realm_rowid = realm.save().await?;
+
+        let quota = RealmQuota {
+            max_concurrent_actors: Some(100),
+            max_registrations: None,
+        };
+        quota.save(realm_rowid).await?;
+        assert_eq!(RealmQuota::load_for_realm(realm_id).await?, quota);
+
+        RealmQuota::default().save(realm_rowid).await?;
+        assert!(RealmQuota::load(realm_rowid).await?.is_empty());
+
+        Ok(())
+    }
+}
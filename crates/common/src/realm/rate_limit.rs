@@ -0,0 +1,150 @@
+//! Realm 级速率限额
+//!
+//! 限额以键值对形式存储在 Realm 元数据（`realmconfig` 表）中，
+//! 由 Signaling 读取，用于覆盖节点默认的 Realm 聚合限额。
+
+use super::config::RealmConfig;
+use super::error::RealmError;
+use super::model::Realm;
+
+/// 每分钟注册数的配置键
+pub const REALM_REGISTRATIONS_PER_MINUTE_KEY: &str = "ratelimit.registrations_per_minute";
+/// 每秒中继消息数的配置键
+pub const REALM_RELAYS_PER_SECOND_KEY: &str = "ratelimit.relays_per_second";
+/// 每秒服务发现请求数的配置键
+pub const REALM_DISCOVERY_PER_SECOND_KEY: &str = "ratelimit.discovery_per_second";
+
+/// Realm 元数据中的速率限额
+///
+/// `None` 表示沿用节点默认值，`Some(0)` 表示不限制
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RealmRateLimits {
+    pub registrations_per_minute: Option<u32>,
+    pub relays_per_second: Option<u32>,
+    pub discovery_per_second: Option<u32>,
+}
+
+impl RealmRateLimits {
+    /// 是否未设置任何覆盖值
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 从 Realm 配置项读取限额
+    pub async fn load(realm_rowid: i64) -> Result<Self, RealmError> {
+        let configs = RealmConfig::get_by_realm(realm_rowid).await?;
+        Ok(Self::from_configs(&configs))
+    }
+
+    /// 按 realm_id 读取限额，Realm 不存在时返回空限额
+    pub async fn load_for_realm(realm_id: u32) -> Result<Self, RealmError> {
+        match Realm::get_by_realm_id(realm_id)
+            .await?
+            .and_then(|realm| realm.rowid)
+        {
+            Some(rowid) => Self::load(rowid).await,
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// 写入限额：有值的键被创建或更新，`None` 的键被删除
+    pub async fn save(&self, realm_rowid: i64) -> Result<(), RealmError> {
+        for (key, value) in self.entries() {
+            match value {
+                Some(value) => {
+                    let value = value.to_string();
+                    match RealmConfig::get_by_realm_and_key(realm_rowid, key).await? {
+                        Some(mut config) => {
+                            config.set_value(value);
+                            config.save().await?;
+                        }
+                        None => {
+                            RealmConfig::new(realm_rowid, key.to_string(), value)
+                                .save()
+                                .await?;
+                        }
+                    }
+                }
+                None => {
+                    RealmConfig::delete_by_realm_and_key(realm_rowid, key).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> [(&'static str, Option<u32>); 3] {
+        [
+            (
+                REALM_REGISTRATIONS_PER_MINUTE_KEY,
+                self.registrations_per_minute,
+            ),
+            (REALM_RELAYS_PER_SECOND_KEY, self.relays_per_second),
+            (REALM_DISCOVERY_PER_SECOND_KEY, self.discovery_per_second),
+        ]
+    }
+
+    fn from_configs(configs: &[RealmConfig]) -> Self {
+        let get = |key: &str| {
+            configs
+                .iter()
+                .find(|config| config.key() == key)
+                .and_then(|config| config.value().trim().parse::<u32>().ok())
+        };
+
+        Self {
+            registrations_per_minute: get(REALM_REGISTRATIONS_PER_MINUTE_KEY),
+            relays_per_second: get(REALM_RELAYS_PER_SECOND_KEY),
+            discovery_per_second: get(REALM_DISCOVERY_PER_SECOND_KEY),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_utils::utils::setup_test_db;
+    use serial_test::serial;
+
+    #[test]
+    fn test_from_configs_ignores_invalid_values() {
+        let configs = vec![
+            RealmConfig::new(1, REALM_RELAYS_PER_SECOND_KEY.to_string(), "25".to_string()),
+            RealmConfig::new(
+                1,
+                REALM_DISCOVERY_PER_SECOND_KEY.to_string(),
+                "abc".to_string(),
+            ),
+            RealmConfig::new(1, "realm.enabled".to_string(), "true".to_string()),
+        ];
+
+        let limits = RealmRateLimits::from_configs(&configs);
+        assert_eq!(limits.relays_per_second, Some(25));
+        assert_eq!(limits.discovery_per_second, None);
+        assert_eq!(limits.registrations_per_minute, None);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_save_and_load_round_trip() -> anyhow::Result<()> {
+        setup_test_db().await?;
+
+        let realm_id = rand::random::<u32>();
+        let mut realm = Realm::new(realm_id, "rate_limit_realm".to_string());
+        let realm_rowid = realm.save().await?;
+
+        let limits = RealmRateLimits {
+            registrations_per_minute: Some(30),
+            relays_per_second: Some(0),
+            discovery_per_second: None,
+        };
+        limits.save(realm_rowid).await?;
+        assert_eq!(RealmRateLimits::load_for_realm(realm_id).await?, limits);
+
+        // 清除覆盖值后回到默认
+        RealmRateLimits::default().save(realm_rowid).await?;
+        assert!(RealmRateLimits::load(realm_rowid).await?.is_empty());
+
+        Ok(())
+    }
+}
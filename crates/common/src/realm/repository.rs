@@ -5,7 +5,7 @@
 use chrono::Utc;
 
 use super::error::RealmError;
-use super::model::Realm;
+use super::model::{Realm, RealmStatus};
 use crate::storage::db::get_database;
 
 /// Realm 数据库操作实现
@@ -97,6 +97,12 @@ impl Realm {
             .execute(pool)
             .await?;
 
+        // 同时清理该 Realm 的 API Key
+        sqlx::query("DELETE FROM realm_api_key WHERE realm_id = ?")
+            .bind(realm_id)
+            .execute(pool)
+            .await?;
+
         Ok(result.rows_affected())
     }
 
@@ -148,6 +154,23 @@ impl Realm {
     pub async fn list() -> Result<Vec<Self>, RealmError> {
         Self::get_all().await
     }
+
+    /// 查询在 `now`（Unix timestamp）时已到期但状态仍为 Normal 的 Realm
+    pub async fn list_expired(now: i64) -> Result<Vec<Self>, RealmError> {
+        let db = get_database();
+        let pool = db.get_pool();
+
+        let realms = sqlx::query_as::<_, Realm>(
+            "SELECT rowid, realm_id, name, status, expires_at, created_at, updated_at
+             FROM realm WHERE status = ? AND expires_at IS NOT NULL AND expires_at < ?",
+        )
+        .bind(RealmStatus::Normal.to_string())
+        .bind(now)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(realms)
+    }
 }
 
 #[cfg(test)]
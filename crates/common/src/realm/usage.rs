@@ -0,0 +1,151 @@
+//! Realm 用量计量
+//!
+//! 信令与 TURN 在热路径上按 Realm 累加用量，Supervit 客户端在每次状态报告中
+//! 通过 [`snapshot`] 读取并随 `SystemMetrics.realm_usage` 上报，供平台按租户计费与告警。
+//!
+//! 除活跃 Actor 数外均为进程启动以来的累计值，Supervisor 按相邻两次报告求差得到区间用量。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// 单个 Realm 的用量计数器
+#[derive(Debug, Default)]
+struct RealmCounters {
+    active_actors: AtomicI64,
+    registrations: AtomicU64,
+    relay_messages: AtomicU64,
+    turn_bytes: AtomicU64,
+}
+
+/// 单个 Realm 的用量快照
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RealmUsageSnapshot {
+    pub realm_id: u32,
+    /// 当前在线的 Actor 数
+    pub active_actors: u64,
+    /// 累计注册次数
+    pub registrations: u64,
+    /// 累计信令中继消息数
+    pub relay_messages: u64,
+    /// 累计 TURN 中继字节数
+    pub turn_bytes: u64,
+}
+
+static USAGE: LazyLock<RwLock<HashMap<u32, Arc<RealmCounters>>>> = LazyLock::new(Default::default);
+
+fn counters(realm_id: u32) -> Arc<RealmCounters> {
+    if let Some(counters) = USAGE
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&realm_id)
+    {
+        return counters.clone();
+    }
+    USAGE
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(realm_id)
+        .or_default()
+        .clone()
+}
+
+/// Actor 上线（同一 Actor 的首个连接建立身份时调用）
+pub fn record_actor_connected(realm_id: u32) {
+    counters(realm_id)
+        .active_actors
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// Actor 下线（同一 Actor 的最后一个连接清理时调用）
+pub fn record_actor_disconnected(realm_id: u32) {
+    counters(realm_id)
+        .active_actors
+        .fetch_sub(1, Ordering::Relaxed);
+}
+
+/// 注册成功
+pub fn record_registration(realm_id: u32) {
+    counters(realm_id)
+        .registrations
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// 信令中继消息
+pub fn record_relay_message(realm_id: u32) {
+    counters(realm_id)
+        .relay_messages
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// TURN 中继字节数
+pub fn record_turn_bytes(realm_id: u32, bytes: u64) {
+    counters(realm_id)
+        .turn_bytes
+        .fetch_add(bytes, Ordering::Relaxed);
+}
+
+/// 当前在线的 Actor 数（用于并发 Actor 配额检查）
+pub fn active_actors(realm_id: u32) -> u64 {
+    USAGE
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(&realm_id)
+        .map_or(0, |counters| {
+            counters.active_actors.load(Ordering::Relaxed).max(0) as u64
+        })
+}
+
+/// 读取全部 Realm 的用量，按 Realm ID 排序
+pub fn snapshot() -> Vec<RealmUsageSnapshot> {
+    let mut usage: Vec<RealmUsageSnapshot> = USAGE
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .map(|(realm_id, counters)| RealmUsageSnapshot {
+            realm_id: *realm_id,
+            active_actors: counters.active_actors.load(Ordering::Relaxed).max(0) as u64,
+            registrations: counters.registrations.load(Ordering::Relaxed),
+            relay_messages: counters.relay_messages.load(Ordering::Relaxed),
+            turn_bytes: counters.turn_bytes.load(Ordering::Relaxed),
+        })
+        .collect();
+    usage.sort_by_key(|u| u.realm_id);
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage_of(realm_id: u32) -> RealmUsageSnapshot {
+        snapshot()
+            .into_iter()
+            .find(|u| u.realm_id == realm_id)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_counters_accumulate_per_realm() {
+        let realm_id = rand::random::<u32>();
+        let other = realm_id.wrapping_add(1);
+
+        record_registration(realm_id);
+        record_registration(realm_id);
+        record_actor_connected(realm_id);
+        record_actor_connected(realm_id);
+        record_actor_disconnected(realm_id);
+        record_relay_message(realm_id);
+        record_turn_bytes(realm_id, 1200);
+        record_turn_bytes(other, 10);
+
+        let usage = usage_of(realm_id);
+        assert_eq!(usage.registrations, 2);
+        assert_eq!(usage.active_actors, 1);
+        assert_eq!(usage.relay_messages, 1);
+        assert_eq!(usage.turn_bytes, 1200);
+        assert_eq!(usage_of(other).turn_bytes, 10);
+        assert_eq!(active_actors(realm_id), 1);
+        assert_eq!(active_actors(other), 0);
+    }
+}
@@ -4,10 +4,89 @@
 
 use anyhow::Result;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 
+/// 服务私有 SQLite 数据库的位置
+///
+/// `storage = "memory"` 时各服务的数据库（AIS 密钥与吊销记录等）改用 `sqlite::memory:`，
+/// 不在 `sqlite_path` 下创建文件，见 [`crate::config::ActrixConfig::sqlite_location`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SqliteLocation {
+    /// 数据库文件
+    File(PathBuf),
+    /// 进程内存数据库，进程退出后数据丢失
+    Memory,
+}
+
+impl SqliteLocation {
+    /// 是否为内存数据库
+    pub fn is_memory(&self) -> bool {
+        matches!(self, Self::Memory)
+    }
+
+    /// 创建连接池
+    ///
+    /// 文件数据库启用 WAL 模式；每个 `:memory:` 连接都是独立的数据库，
+    /// 因此内存数据库的连接池固定为单个常驻连接
+    pub async fn connect(&self, max_connections: u32) -> Result<SqlitePool> {
+        let pool = match self {
+            Self::File(file) => {
+                let options =
+                    SqliteConnectOptions::from_str(&format!("sqlite:{}", file.display()))?
+                        .create_if_missing(true)
+                        .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+                        .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+                        .busy_timeout(Duration::from_secs(5));
+                SqlitePoolOptions::new()
+                    .max_connections(max_connections)
+                    .connect_with(options)
+                    .await?
+            }
+            Self::Memory => {
+                let options = SqliteConnectOptions::from_str("sqlite::memory:")?
+                    .busy_timeout(Duration::from_secs(5));
+                SqlitePoolOptions::new()
+                    .max_connections(1)
+                    .min_connections(1)
+                    .idle_timeout(None)
+                    .max_lifetime(None)
+                    .connect_with(options)
+                    .await?
+            }
+        };
+        Ok(pool)
+    }
+}
+
+impl From<PathBuf> for SqliteLocation {
+    fn from(file: PathBuf) -> Self {
+        Self::File(file)
+    }
+}
+
+impl From<&Path> for SqliteLocation {
+    fn from(file: &Path) -> Self {
+        Self::File(file.to_path_buf())
+    }
+}
+
+impl From<&PathBuf> for SqliteLocation {
+    fn from(file: &PathBuf) -> Self {
+        Self::File(file.clone())
+    }
+}
+
+impl std::fmt::Display for SqliteLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(file) => write!(f, "{}", file.display()),
+            Self::Memory => f.write_str(":memory:"),
+        }
+    }
+}
+
 /// 数据库管理器
 #[derive(Clone)]
 pub struct Database {
@@ -44,6 +123,16 @@ impl Database {
         Ok(db)
     }
 
+    /// 创建内存数据库实例（不写文件系统，进程退出后数据丢失）
+    pub async fn new_in_memory() -> Result<Self> {
+        let pool = SqliteLocation::Memory.connect(1).await?;
+
+        let db = Self { pool };
+        db.initialize_schema().await?;
+
+        Ok(db)
+    }
+
     /// 初始化数据库表结构
     async fn initialize_schema(&self) -> Result<()> {
         // 创建 Realm 表
@@ -87,6 +176,36 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        // 创建 ACL 标签组成员表（由 Realm 管理员分配）
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS actoracl_group (
+                realm_id INTEGER NOT NULL,
+                tag TEXT NOT NULL,
+                member TEXT NOT NULL,
+                PRIMARY KEY (realm_id, tag, member)
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // 创建 Realm API Key 表（仅保存 token 摘要）
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS realm_api_key (
+                rowid INTEGER PRIMARY KEY AUTOINCREMENT,
+                key_id TEXT NOT NULL UNIQUE,
+                realm_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                key_hash TEXT NOT NULL,
+                scopes TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER,
+                last_used_at INTEGER,
+                revoked INTEGER NOT NULL DEFAULT 0
+            )",
+        )
+        .execute(&self.pool)
+        .await?;
+
         // 创建索引
         sqlx::query(
             "CREATE INDEX IF NOT EXISTS idx_realm_realm_id
@@ -109,6 +228,13 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_realm_api_key_realm_id
+             ON realm_api_key(realm_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
@@ -138,11 +264,20 @@ pub async fn set_db_path(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// 使用内存数据库初始化全局数据库（`storage = "memory"`）
+pub async fn set_in_memory_db() -> Result<()> {
+    let database = Database::new_in_memory().await?;
+    GLOBAL_DATABASE
+        .set(database)
+        .map_err(|_| anyhow::anyhow!("Database already initialized"))?;
+    Ok(())
+}
+
 /// 获取全局数据库实例
 pub fn get_database() -> &'static Database {
     GLOBAL_DATABASE
         .get()
-        .expect("Database not initialized. Call set_db_path first.")
+        .expect("Database not initialized. Call set_db_path or set_in_memory_db first.")
 }
 
 /// 检查数据库是否已初始化
@@ -5,5 +5,5 @@
 pub mod db;
 pub mod nonce;
 
-pub use db::{Database, is_database_initialized};
-pub use nonce::SqliteNonceStorage;
+pub use db::{Database, SqliteLocation, is_database_initialized};
+pub use nonce::{NonceStore, SqliteNonceStorage};
@@ -0,0 +1,196 @@
+//! Nonce 存储后端选择
+//!
+//! 根据 [`NonceStorageConfig`] 创建对应后端，KS、Supervisord 以及测试统一通过此处获取
+//! nonce 存储，避免各服务各自硬编码 `MemoryStorage` / `SqliteNonceStorage`。
+
+use anyhow::Result;
+use nonce_auth::NonceError;
+use nonce_auth::storage::{MemoryStorage, NonceEntry, NonceStorage, StorageStats};
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+use super::SqliteNonceStorage;
+#[cfg(feature = "nonce-redis")]
+use super::redis_nonce_storage::RedisNonceStorage;
+use crate::config::nonce::{NonceBackend, NonceStorageConfig};
+
+/// 按配置选定的 nonce 存储
+///
+/// 与 KS 的 `KeyStorage` 一样使用 enum 封装后端，可直接作为泛型参数传给
+/// `create_grpc_service` / `create_ks_state`，也可包装为 `Arc<dyn NonceStorage>`。
+pub enum NonceStore {
+    /// 进程内存储（开发与测试）
+    Memory(MemoryStorage),
+    /// SQLite 存储
+    Sqlite(SqliteNonceStorage),
+    /// Redis 存储
+    #[cfg(feature = "nonce-redis")]
+    Redis(RedisNonceStorage),
+}
+
+impl NonceStore {
+    /// 从配置创建 nonce 存储
+    ///
+    /// # Arguments
+    /// * `config` - nonce 存储配置
+    /// * `sqlite_path` - SQLite 目录（backend = "sqlite" 时使用，来自 ActrixConfig.sqlite_path）
+    ///
+    /// # Errors
+    /// - 缺少 Redis 配置或未启用 `nonce-redis` feature
+    /// - 后端初始化失败
+    pub async fn from_config<P: AsRef<Path>>(
+        config: &NonceStorageConfig,
+        sqlite_path: P,
+    ) -> Result<Self> {
+        let store = match config.backend {
+            NonceBackend::Memory => Self::memory(),
+            NonceBackend::Sqlite => Self::Sqlite(SqliteNonceStorage::new_async(sqlite_path).await?),
+            NonceBackend::Redis => {
+                let redis = config.redis.as_ref().ok_or_else(|| {
+                    anyhow::anyhow!("Missing nonce_storage.redis config for redis backend")
+                })?;
+                #[cfg(feature = "nonce-redis")]
+                {
+                    Self::Redis(RedisNonceStorage::connect(&redis.url, &redis.key_prefix).await?)
+                }
+                #[cfg(not(feature = "nonce-redis"))]
+                {
+                    let _ = redis;
+                    anyhow::bail!(
+                        "Redis nonce backend not enabled. Compile with --features nonce-redis"
+                    );
+                }
+            }
+        };
+
+        info!("Nonce storage initialized: {}", store.backend_name());
+        Ok(store)
+    }
+
+    /// 创建进程内存储
+    pub fn memory() -> Self {
+        Self::Memory(MemoryStorage::new())
+    }
+
+    /// 后端名称
+    pub fn backend_name(&self) -> &'static str {
+        match self {
+            Self::Memory(_) => "memory",
+            Self::Sqlite(_) => "sqlite",
+            #[cfg(feature = "nonce-redis")]
+            Self::Redis(_) => "redis",
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NonceStorage for NonceStore {
+    async fn get(
+        &self,
+        nonce: &str,
+        context: Option<&str>,
+    ) -> Result<Option<NonceEntry>, NonceError> {
+        match self {
+            Self::Memory(s) => NonceStorage::get(s, nonce, context).await,
+            Self::Sqlite(s) => NonceStorage::get(s, nonce, context).await,
+            #[cfg(feature = "nonce-redis")]
+            Self::Redis(s) => NonceStorage::get(s, nonce, context).await,
+        }
+    }
+
+    async fn set(
+        &self,
+        nonce: &str,
+        context: Option<&str>,
+        ttl: Duration,
+    ) -> Result<(), NonceError> {
+        match self {
+            Self::Memory(s) => s.set(nonce, context, ttl).await,
+            Self::Sqlite(s) => s.set(nonce, context, ttl).await,
+            #[cfg(feature = "nonce-redis")]
+            Self::Redis(s) => s.set(nonce, context, ttl).await,
+        }
+    }
+
+    async fn exists(&self, nonce: &str, context: Option<&str>) -> Result<bool, NonceError> {
+        match self {
+            Self::Memory(s) => s.exists(nonce, context).await,
+            Self::Sqlite(s) => s.exists(nonce, context).await,
+            #[cfg(feature = "nonce-redis")]
+            Self::Redis(s) => s.exists(nonce, context).await,
+        }
+    }
+
+    async fn cleanup_expired(&self, current_time: i64) -> Result<usize, NonceError> {
+        match self {
+            Self::Memory(s) => s.cleanup_expired(current_time).await,
+            Self::Sqlite(s) => s.cleanup_expired(current_time).await,
+            #[cfg(feature = "nonce-redis")]
+            Self::Redis(s) => s.cleanup_expired(current_time).await,
+        }
+    }
+
+    async fn get_stats(&self) -> Result<StorageStats, NonceError> {
+        match self {
+            Self::Memory(s) => s.get_stats().await,
+            Self::Sqlite(s) => s.get_stats().await,
+            #[cfg(feature = "nonce-redis")]
+            Self::Redis(s) => s.get_stats().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn assert_replay_rejected(store: &NonceStore) {
+        store
+            .set("nonce-1", Some("ctx"), Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(store.exists("nonce-1", Some("ctx")).await.unwrap());
+        assert!(!store.exists("nonce-1", Some("other")).await.unwrap());
+        assert!(matches!(
+            store
+                .set("nonce-1", Some("ctx"), Duration::from_secs(60))
+                .await,
+            Err(NonceError::DuplicateNonce)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_backend_selected_from_config() {
+        let temp_dir = tempdir().unwrap();
+
+        let sqlite = NonceStore::from_config(&NonceStorageConfig::default(), temp_dir.path())
+            .await
+            .unwrap();
+        assert_eq!(sqlite.backend_name(), "sqlite");
+        assert!(temp_dir.path().join("nonce.db").exists());
+        assert_replay_rejected(&sqlite).await;
+
+        let memory = NonceStore::from_config(
+            &NonceStorageConfig {
+                backend: NonceBackend::Memory,
+                ..Default::default()
+            },
+            temp_dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(memory.backend_name(), "memory");
+        assert_replay_rejected(&memory).await;
+    }
+
+    #[tokio::test]
+    async fn test_redis_backend_requires_config() {
+        let config = NonceStorageConfig {
+            backend: NonceBackend::Redis,
+            ..Default::default()
+        };
+        assert!(NonceStore::from_config(&config, ".").await.is_err());
+    }
+}
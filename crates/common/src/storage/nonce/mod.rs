@@ -3,7 +3,15 @@
 //! 提供 Nonce 的存储和管理功能，防止重放攻击
 
 pub mod db_nonce_entry;
+pub mod factory;
+#[cfg(feature = "nonce-redis")]
+pub mod redis_nonce_storage;
 pub mod sqlite_nonce_storage;
+pub mod sweeper;
 
 pub use db_nonce_entry::DbNonceEntry;
+pub use factory::NonceStore;
+#[cfg(feature = "nonce-redis")]
+pub use redis_nonce_storage::RedisNonceStorage;
 pub use sqlite_nonce_storage::SqliteNonceStorage;
+pub use sweeper::NonceSweeper;
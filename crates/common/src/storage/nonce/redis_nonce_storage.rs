@@ -0,0 +1,136 @@
+//! Redis Nonce 存储实现
+//!
+//! 多个节点共享同一 Redis 时，nonce 防重放在节点间生效。
+//! 使用 `SET NX PX` 原子写入，过期由 Redis TTL 自动处理。
+
+use anyhow::Result;
+use nonce_auth::NonceError;
+use nonce_auth::storage::{NonceEntry, NonceStorage, StorageStats};
+use redis::aio::ConnectionManager;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 无 context 时键中使用的占位符
+const NO_CONTEXT: &str = "-";
+
+/// 基于 Redis 的 NonceStorage 实现
+pub struct RedisNonceStorage {
+    conn: ConnectionManager,
+    key_prefix: String,
+}
+
+impl RedisNonceStorage {
+    /// 连接 Redis 并创建存储实例
+    pub async fn connect(url: &str, key_prefix: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        let conn = client.get_connection_manager().await?;
+
+        Ok(Self {
+            conn,
+            key_prefix: key_prefix.to_string(),
+        })
+    }
+
+    fn key(&self, nonce: &str, context: Option<&str>) -> String {
+        format!(
+            "{}{}:{}",
+            self.key_prefix,
+            context.unwrap_or(NO_CONTEXT),
+            nonce
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl NonceStorage for RedisNonceStorage {
+    async fn get(
+        &self,
+        nonce: &str,
+        context: Option<&str>,
+    ) -> Result<Option<NonceEntry>, NonceError> {
+        let mut conn = self.conn.clone();
+        let created_at: Option<i64> = redis::cmd("GET")
+            .arg(self.key(nonce, context))
+            .query_async(&mut conn)
+            .await
+            .map_err(NonceError::from_storage_error)?;
+
+        Ok(created_at.map(|created_at| NonceEntry {
+            nonce: nonce.to_string(),
+            context: context.map(str::to_string),
+            created_at,
+        }))
+    }
+
+    async fn set(
+        &self,
+        nonce: &str,
+        context: Option<&str>,
+        ttl: Duration,
+    ) -> Result<(), NonceError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let mut conn = self.conn.clone();
+        let reply: Option<String> = redis::cmd("SET")
+            .arg(self.key(nonce, context))
+            .arg(now)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut conn)
+            .await
+            .map_err(NonceError::from_storage_error)?;
+
+        // NX 条件不满足时 Redis 返回 nil
+        match reply {
+            Some(_) => Ok(()),
+            None => Err(NonceError::DuplicateNonce),
+        }
+    }
+
+    async fn exists(&self, nonce: &str, context: Option<&str>) -> Result<bool, NonceError> {
+        let mut conn = self.conn.clone();
+        let count: u64 = redis::cmd("EXISTS")
+            .arg(self.key(nonce, context))
+            .query_async(&mut conn)
+            .await
+            .map_err(NonceError::from_storage_error)?;
+        Ok(count > 0)
+    }
+
+    async fn cleanup_expired(&self, _current_time: i64) -> Result<usize, NonceError> {
+        // 过期键由 Redis TTL 自动删除
+        Ok(0)
+    }
+
+    async fn get_stats(&self) -> Result<StorageStats, NonceError> {
+        let mut conn = self.conn.clone();
+        let pattern = format!("{}*", self.key_prefix);
+        let mut cursor = 0u64;
+        let mut total = 0usize;
+
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(1000)
+                .query_async(&mut conn)
+                .await
+                .map_err(NonceError::from_storage_error)?;
+            total += keys.len();
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        Ok(StorageStats {
+            total_records: total,
+            backend_info: format!("Redis (prefix {})", self.key_prefix),
+        })
+    }
+}
@@ -0,0 +1,113 @@
+//! Nonce 过期清理
+//!
+//! SQLite / 内存后端不会主动删除过期 nonce，需要后台任务定期调用
+//! `cleanup_expired`；Redis 后端依赖键 TTL，无需清理。
+//!
+//! 清理任务只持有存储的弱引用，使用该存储的服务释放后任务自动退出。
+
+use nonce_auth::storage::NonceStorage;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 后台 nonce 过期清理任务
+pub struct NonceSweeper {
+    storage: Weak<dyn NonceStorage + Send + Sync>,
+    interval: Duration,
+}
+
+impl NonceSweeper {
+    /// 创建清理任务，每隔 `interval` 清理一次
+    pub fn new(storage: &Arc<dyn NonceStorage + Send + Sync>, interval: Duration) -> Self {
+        Self {
+            storage: Arc::downgrade(storage),
+            interval,
+        }
+    }
+
+    /// 启动后台清理，存储被释放后退出
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("🧹 Nonce 过期清理已启动: interval={:?}", self.interval);
+
+            let mut ticker = tokio::time::interval(self.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // 第一次 tick 立即完成，跳过以免启动时与初始化争用
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+                let Some(storage) = self.storage.upgrade() else {
+                    debug!("Nonce 存储已释放，过期清理退出");
+                    break;
+                };
+                sweep(storage.as_ref(), unix_now()).await;
+            }
+        })
+    }
+}
+
+/// 清理 `current_time` 之前过期的 nonce，返回删除条数
+async fn sweep(storage: &(dyn NonceStorage + Send + Sync), current_time: i64) -> usize {
+    match storage.cleanup_expired(current_time).await {
+        Ok(0) => 0,
+        Ok(removed) => {
+            info!("清理过期 nonce {} 条", removed);
+            removed
+        }
+        Err(e) => {
+            warn!("清理过期 nonce 失败: {}", e);
+            0
+        }
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::nonce::SqliteNonceStorage;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_sweep_removes_expired() {
+        let temp_dir = tempdir().unwrap();
+        let storage: Arc<dyn NonceStorage + Send + Sync> = Arc::new(
+            SqliteNonceStorage::new_async(temp_dir.path())
+                .await
+                .unwrap(),
+        );
+        storage
+            .set("nonce-1", Some("ctx"), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert_eq!(sweep(storage.as_ref(), unix_now()).await, 0);
+        assert_eq!(sweep(storage.as_ref(), unix_now() + 120).await, 1);
+        assert!(!storage.exists("nonce-1", Some("ctx")).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_exits_when_storage_dropped() {
+        let temp_dir = tempdir().unwrap();
+        let storage: Arc<dyn NonceStorage + Send + Sync> = Arc::new(
+            SqliteNonceStorage::new_async(temp_dir.path())
+                .await
+                .unwrap(),
+        );
+        let handle = NonceSweeper::new(&storage, Duration::from_millis(10)).spawn();
+
+        drop(storage);
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
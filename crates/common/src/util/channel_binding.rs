@@ -0,0 +1,85 @@
+//! TLS 通道绑定
+//!
+//! 基于 RFC 9266 的 `tls-exporter` 通道绑定：TLS 握手完成后，服务端与客户端
+//! 各自从会话导出相同的 32 字节密钥材料。客户端用凭证中的 PSK 对其做
+//! HMAC-SHA256 作为证明，服务端校验后即可确认 token 未被转移到其他 TLS 连接上重放。
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// RFC 9266 定义的 exporter label
+pub const TLS_EXPORTER_LABEL: &[u8] = b"EXPORTER-Channel-Binding";
+
+/// 导出密钥材料长度（字节）
+pub const TLS_EXPORTER_LEN: usize = 32;
+
+/// 单个 TLS 连接的通道绑定值
+///
+/// 由 HTTPS 接入层在握手完成后导出，并作为请求扩展 (request extension) 注入，
+/// 仅在 TLS 连接上存在。
+#[derive(Clone, PartialEq, Eq)]
+pub struct TlsChannelBinding([u8; TLS_EXPORTER_LEN]);
+
+impl std::fmt::Debug for TlsChannelBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密钥材料
+        f.write_str("TlsChannelBinding(..)")
+    }
+}
+
+impl TlsChannelBinding {
+    /// 从原始导出值创建
+    pub fn new(exporter: [u8; TLS_EXPORTER_LEN]) -> Self {
+        Self(exporter)
+    }
+
+    /// 从已完成握手的服务端连接导出绑定值
+    pub fn from_connection(conn: &rustls::ServerConnection) -> Result<Self, rustls::Error> {
+        let exporter =
+            conn.export_keying_material([0u8; TLS_EXPORTER_LEN], TLS_EXPORTER_LABEL, None)?;
+        Ok(Self(exporter))
+    }
+
+    /// 计算绑定证明：`HMAC-SHA256(psk, exporter)`
+    pub fn proof(&self, psk: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(psk).expect("HMAC accepts keys of any length");
+        mac.update(&self.0);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// 以常量时间校验客户端提交的绑定证明
+    pub fn verify(&self, psk: &[u8], proof: &[u8]) -> bool {
+        let mut mac = HmacSha256::new_from_slice(psk).expect("HMAC accepts keys of any length");
+        mac.update(&self.0);
+        mac.verify_slice(proof).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proof_roundtrip() {
+        let binding = TlsChannelBinding::new([7u8; TLS_EXPORTER_LEN]);
+        let psk = b"0123456789abcdef0123456789abcdef";
+
+        let proof = binding.proof(psk);
+        assert_eq!(proof.len(), 32);
+        assert!(binding.verify(psk, &proof));
+    }
+
+    #[test]
+    fn test_proof_rejected_on_other_channel_or_key() {
+        let binding = TlsChannelBinding::new([7u8; TLS_EXPORTER_LEN]);
+        let other_channel = TlsChannelBinding::new([8u8; TLS_EXPORTER_LEN]);
+        let psk = b"0123456789abcdef0123456789abcdef";
+
+        let proof = binding.proof(psk);
+        assert!(!other_channel.verify(psk, &proof));
+        assert!(!binding.verify(b"another-psk", &proof));
+        assert!(!binding.verify(psk, &proof[..16]));
+    }
+}
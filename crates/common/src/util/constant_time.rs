@@ -0,0 +1,21 @@
+//! 常量时间比较
+//!
+//! 用于比较 token、口令摘要等秘密值，比较耗时不随首个不同字节的位置变化，避免计时侧信道。
+
+/// 常量时间比较两个字节串是否相等（长度不同时直接返回 false）
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret-longer"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
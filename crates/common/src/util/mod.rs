@@ -1,10 +1,16 @@
 //! TLS 配置模块
 //!
-//! 提供 TLS 相关配置和加密提供者管理功能
+//! 提供 TLS 相关配置、加密提供者管理和通道绑定功能
 
+pub mod channel_binding;
 pub mod config;
+pub mod constant_time;
+pub mod network_emulation;
 
 #[cfg(test)]
 pub mod test_utils;
 
+pub use channel_binding::TlsChannelBinding;
 pub use config::TlsConfigurer;
+pub use constant_time::constant_time_eq;
+pub use network_emulation::NetworkEmulator;
@@ -0,0 +1,90 @@
+//! 弱网模拟
+//!
+//! 按 [`NetworkEmulationConfig`] 在发送前注入延迟、抖动与丢包，仅用于开发调试。
+
+use crate::config::dev::NetworkEmulationConfig;
+use rand::Rng;
+use std::time::Duration;
+
+/// 弱网模拟器
+#[derive(Debug, Clone)]
+pub struct NetworkEmulator {
+    latency_ms: u64,
+    jitter_ms: u64,
+    drop_rate: f64,
+}
+
+impl NetworkEmulator {
+    /// 根据配置创建模拟器，未启用时返回 None
+    pub fn from_config(config: &NetworkEmulationConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            latency_ms: config.latency_ms,
+            jitter_ms: config.jitter_ms,
+            drop_rate: config.drop_rate.clamp(0.0, 1.0),
+        })
+    }
+
+    /// 按配置等待一段模拟延迟；返回 false 表示该消息应被丢弃
+    pub async fn impair(&self) -> bool {
+        let (delay, keep) = self.sample();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        keep
+    }
+
+    /// 采样一次延迟与是否保留
+    fn sample(&self) -> (Duration, bool) {
+        let mut rng = rand::thread_rng();
+        let keep = self.drop_rate <= 0.0 || rng.gen_range(0.0..1.0) >= self.drop_rate;
+
+        let jitter = if self.jitter_ms > 0 {
+            rng.gen_range(-(self.jitter_ms as i64)..=self.jitter_ms as i64)
+        } else {
+            0
+        };
+        let delay_ms = (self.latency_ms as i64 + jitter).max(0) as u64;
+
+        (Duration::from_millis(delay_ms), keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(latency_ms: u64, jitter_ms: u64, drop_rate: f64) -> NetworkEmulationConfig {
+        NetworkEmulationConfig {
+            enabled: true,
+            latency_ms,
+            jitter_ms,
+            drop_rate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_disabled_returns_none() {
+        assert!(NetworkEmulator::from_config(&NetworkEmulationConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_delay_within_jitter_bounds() {
+        let emulator = NetworkEmulator::from_config(&config(100, 20, 0.0)).unwrap();
+        for _ in 0..100 {
+            let (delay, keep) = emulator.sample();
+            assert!(keep);
+            assert!((80..=120).contains(&(delay.as_millis() as u64)));
+        }
+    }
+
+    #[test]
+    fn test_drop_rate_extremes() {
+        let always_drop = NetworkEmulator::from_config(&config(0, 0, 1.0)).unwrap();
+        let never_drop = NetworkEmulator::from_config(&config(0, 0, 0.0)).unwrap();
+        for _ in 0..50 {
+            assert!(!always_drop.sample().1);
+            assert!(never_drop.sample().1);
+        }
+    }
+}
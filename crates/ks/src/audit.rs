@@ -0,0 +1,678 @@
+//! KS 审计日志
+//!
+//! 启用 `[services.ks.audit]` 后，每次密钥生成、私钥读取、轮替与吊销都会追加一条
+//! [`AuditEntry`]，记录调用方身份（mTLS 证书 SAN，未启用 mTLS 时为 `psk`）、来源地址与
+//! 请求凭证的 nonce，供合规审查密钥材料的访问情况，可通过 HTTP `/ks/audit` 查询。
+//!
+//! 写入目标：
+//! - `sqlite`：`ks_audit.db`，触发器拒绝 UPDATE / DELETE，只允许追加
+//! - `memory`：与 `sqlite` 相同，但使用进程内存数据库，不写文件
+//! - `file`：以 JSON Lines 追加写入
+//!
+//! 审计记录写入失败时请求返回错误，不会出现未被记录的密钥访问。
+
+use crate::config::{AuditSinkType, KsAuditConfig};
+use crate::error::{KsError, KsResult};
+use crate::mtls::ClientIdentity;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::info;
+
+/// 单次查询默认返回的条数
+pub const DEFAULT_AUDIT_QUERY_LIMIT: usize = 100;
+
+/// 单次查询最多返回的条数
+pub const MAX_AUDIT_QUERY_LIMIT: usize = 1000;
+
+/// 未启用 mTLS 时的调用方身份（仅通过 PSK 签名认证）
+pub const PSK_CALLER: &str = "psk";
+
+/// 查询审计日志请求的签名 payload
+pub const AUDIT_QUERY_PAYLOAD: &str = "query_audit";
+
+/// 审计的操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    GenerateKey,
+    GetSecretKey,
+    RotateKey,
+    RevokeKey,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::GenerateKey => "generate_key",
+            AuditAction::GetSecretKey => "get_secret_key",
+            AuditAction::RotateKey => "rotate_key",
+            AuditAction::RevokeKey => "revoke_key",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = KsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "generate_key" => Ok(AuditAction::GenerateKey),
+            "get_secret_key" => Ok(AuditAction::GetSecretKey),
+            "rotate_key" => Ok(AuditAction::RotateKey),
+            "revoke_key" => Ok(AuditAction::RevokeKey),
+            _ => Err(KsError::InvalidRequest(format!(
+                "Unknown audit action: {s}"
+            ))),
+        }
+    }
+}
+
+/// 一条审计记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// 记录时间 (Unix 秒)
+    pub timestamp: u64,
+    pub action: AuditAction,
+    /// 操作的密钥（生成与轮替时为新密钥）
+    pub key_id: u32,
+    /// 轮替时被替换的旧密钥
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_key_id: Option<u32>,
+    /// 调用方身份
+    pub caller: String,
+    /// 调用方地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_addr: Option<String>,
+    /// 请求凭证的 nonce
+    pub nonce: String,
+    /// 请求来源："http" 或 "grpc"
+    pub transport: String,
+}
+
+impl AuditEntry {
+    /// 设置轮替时被替换的旧密钥
+    pub fn with_previous_key_id(mut self, previous_key_id: Option<u32>) -> Self {
+        self.previous_key_id = previous_key_id;
+        self
+    }
+}
+
+/// 请求的调用方信息，在处理请求前提取
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditContext {
+    pub caller: String,
+    pub remote_addr: Option<String>,
+    pub transport: &'static str,
+}
+
+impl AuditContext {
+    /// 从 gRPC 请求提取（mTLS 身份由 [`crate::ClientIdentityInterceptor`] 写入扩展）
+    pub fn from_grpc<T>(request: &tonic::Request<T>) -> Self {
+        Self {
+            caller: Self::caller(request.extensions().get::<ClientIdentity>()),
+            remote_addr: request.remote_addr().map(|addr| addr.to_string()),
+            transport: "grpc",
+        }
+    }
+
+    /// 从 HTTP 请求扩展提取
+    pub fn from_http(extensions: &axum::http::Extensions) -> Self {
+        Self {
+            caller: Self::caller(extensions.get::<ClientIdentity>()),
+            remote_addr: extensions
+                .get::<axum::extract::ConnectInfo<SocketAddr>>()
+                .map(|info| info.0.to_string()),
+            transport: "http",
+        }
+    }
+
+    fn caller(identity: Option<&ClientIdentity>) -> String {
+        identity
+            .map(|identity| identity.san.clone())
+            .unwrap_or_else(|| PSK_CALLER.to_string())
+    }
+
+    /// 生成该调用方的审计记录
+    pub fn entry(&self, action: AuditAction, key_id: u32, nonce: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            action,
+            key_id,
+            previous_key_id: None,
+            caller: self.caller.clone(),
+            remote_addr: self.remote_addr.clone(),
+            nonce: nonce.to_string(),
+            transport: self.transport.to_string(),
+        }
+    }
+}
+
+/// 审计日志查询条件
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditQuery {
+    pub action: Option<AuditAction>,
+    pub key_id: Option<u32>,
+    pub caller: Option<String>,
+    /// 起始时间 (Unix 秒，含)
+    pub since: Option<u64>,
+    /// 截止时间 (Unix 秒，含)
+    pub until: Option<u64>,
+    /// 返回条数，默认 [`DEFAULT_AUDIT_QUERY_LIMIT`]，最多 [`MAX_AUDIT_QUERY_LIMIT`]
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    /// 从 HTTP 查询参数解析
+    pub fn from_params(params: &HashMap<String, String>) -> KsResult<Self> {
+        fn parse<T: FromStr>(params: &HashMap<String, String>, name: &str) -> KsResult<Option<T>> {
+            params
+                .get(name)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| KsError::InvalidRequest(format!("Invalid {name} parameter")))
+                })
+                .transpose()
+        }
+
+        Ok(Self {
+            action: params
+                .get("action")
+                .map(|action| action.parse())
+                .transpose()?,
+            key_id: parse(params, "key_id")?,
+            caller: params.get("caller").cloned(),
+            since: parse(params, "since")?,
+            until: parse(params, "until")?,
+            limit: parse(params, "limit")?,
+        })
+    }
+
+    fn effective_limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_AUDIT_QUERY_LIMIT)
+            .clamp(1, MAX_AUDIT_QUERY_LIMIT)
+    }
+
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        self.action.is_none_or(|action| entry.action == action)
+            && self.key_id.is_none_or(|key_id| entry.key_id == key_id)
+            && self
+                .caller
+                .as_ref()
+                .is_none_or(|caller| &entry.caller == caller)
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp <= until)
+    }
+}
+
+/// 审计日志查询结果（最新的记录在前）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditQueryResponse {
+    pub entries: Vec<AuditEntry>,
+}
+
+enum AuditSink {
+    Sqlite(SqlitePool),
+    File { path: PathBuf, lock: Mutex<()> },
+}
+
+/// 追加写入的审计日志，未启用时记录与查询均为空操作
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    sink: Option<Arc<AuditSink>>,
+}
+
+impl std::fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sink = match self.sink.as_deref() {
+            None => "disabled",
+            Some(AuditSink::Sqlite(_)) => "sqlite",
+            Some(AuditSink::File { .. }) => "file",
+        };
+        f.debug_struct("AuditLog").field("sink", &sink).finish()
+    }
+}
+
+impl AuditLog {
+    /// 不记录审计日志
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// 按配置创建，`db_path` 为 sqlite_path 目录
+    pub async fn from_config(config: &KsAuditConfig, db_path: &Path) -> KsResult<Self> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+
+        match config.sink {
+            AuditSinkType::Sqlite => Self::open_sqlite(&db_path.join("ks_audit.db")).await,
+            AuditSinkType::Memory => Self::open_memory().await,
+            AuditSinkType::File => {
+                let path = config
+                    .file_path
+                    .as_ref()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| db_path.join("ks_audit.log"));
+                Self::open_file(path).await
+            }
+        }
+    }
+
+    /// 写入 SQLite 数据库
+    pub async fn open_sqlite(file: &Path) -> KsResult<Self> {
+        let log = Self::open_pool(file, false).await?;
+        info!("KS audit log enabled: sqlite {}", file.display());
+        Ok(log)
+    }
+
+    /// 写入进程内存 SQLite 数据库（同一进程内的 KS 服务共享）
+    pub async fn open_memory() -> KsResult<Self> {
+        let log = Self::open_pool(Path::new("ks_audit.db"), true).await?;
+        info!("KS audit log enabled: in-memory sqlite");
+        Ok(log)
+    }
+
+    async fn open_pool(file: &Path, in_memory: bool) -> KsResult<Self> {
+        let pool = crate::storage::sqlite::connect(file, in_memory, 4)
+            .await
+            .map_err(|e| KsError::Internal(format!("Failed to connect to audit database: {e}")))?;
+
+        for statement in [
+            r#"
+            CREATE TABLE IF NOT EXISTS audit_log (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                key_id INTEGER NOT NULL,
+                previous_key_id INTEGER,
+                caller TEXT NOT NULL,
+                remote_addr TEXT,
+                nonce TEXT NOT NULL,
+                transport TEXT NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_key_id ON audit_log(key_id)",
+            "CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log(timestamp)",
+            r#"
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END
+            "#,
+            r#"
+            CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+            BEGIN SELECT RAISE(ABORT, 'audit log is append-only'); END
+            "#,
+        ] {
+            sqlx::query(statement)
+                .execute(&pool)
+                .await
+                .map_err(|e| KsError::Internal(format!("Failed to initialize audit log: {e}")))?;
+        }
+
+        Ok(Self {
+            sink: Some(Arc::new(AuditSink::Sqlite(pool))),
+        })
+    }
+
+    /// 以 JSON Lines 追加写入文件
+    pub async fn open_file(path: PathBuf) -> KsResult<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| {
+                KsError::Internal(format!("Failed to create audit log directory: {e}"))
+            })?;
+        }
+
+        info!("KS audit log enabled: file {}", path.display());
+        Ok(Self {
+            sink: Some(Arc::new(AuditSink::File {
+                path,
+                lock: Mutex::new(()),
+            })),
+        })
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// 追加一条记录
+    pub async fn record(&self, entry: AuditEntry) -> KsResult<()> {
+        let Some(sink) = self.sink.as_deref() else {
+            return Ok(());
+        };
+
+        match sink {
+            AuditSink::Sqlite(pool) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO audit_log
+                        (timestamp, action, key_id, previous_key_id, caller, remote_addr, nonce, transport)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(entry.timestamp as i64)
+                .bind(entry.action.as_str())
+                .bind(entry.key_id as i64)
+                .bind(entry.previous_key_id.map(i64::from))
+                .bind(&entry.caller)
+                .bind(&entry.remote_addr)
+                .bind(&entry.nonce)
+                .bind(&entry.transport)
+                .execute(pool)
+                .await
+                .map_err(|e| KsError::Internal(format!("Failed to write audit log: {e}")))?;
+            }
+            AuditSink::File { path, lock } => {
+                let mut line = serde_json::to_vec(&entry)?;
+                line.push(b'\n');
+
+                let _guard = lock.lock().await;
+                let mut options = tokio::fs::OpenOptions::new();
+                options.create(true).append(true);
+                #[cfg(unix)]
+                options.mode(0o600);
+                let mut file = options
+                    .open(path)
+                    .await
+                    .map_err(|e| KsError::Internal(format!("Failed to open audit log: {e}")))?;
+                file.write_all(&line)
+                    .await
+                    .map_err(|e| KsError::Internal(format!("Failed to write audit log: {e}")))?;
+                file.flush()
+                    .await
+                    .map_err(|e| KsError::Internal(format!("Failed to write audit log: {e}")))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 查询记录，最新的在前
+    pub async fn query(&self, query: &AuditQuery) -> KsResult<Vec<AuditEntry>> {
+        let Some(sink) = self.sink.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let limit = query.effective_limit();
+
+        match sink {
+            AuditSink::Sqlite(pool) => {
+                let rows = sqlx::query_as::<
+                    _,
+                    (
+                        i64,
+                        String,
+                        i64,
+                        Option<i64>,
+                        String,
+                        Option<String>,
+                        String,
+                        String,
+                    ),
+                >(
+                    r#"
+                    SELECT timestamp, action, key_id, previous_key_id, caller, remote_addr, nonce, transport
+                    FROM audit_log
+                    WHERE (?1 IS NULL OR action = ?1)
+                      AND (?2 IS NULL OR key_id = ?2)
+                      AND (?3 IS NULL OR caller = ?3)
+                      AND (?4 IS NULL OR timestamp >= ?4)
+                      AND (?5 IS NULL OR timestamp <= ?5)
+                    ORDER BY id DESC
+                    LIMIT ?6
+                    "#,
+                )
+                .bind(query.action.map(|action| action.as_str()))
+                .bind(query.key_id.map(i64::from))
+                .bind(&query.caller)
+                .bind(query.since.map(|since| since as i64))
+                .bind(query.until.map(|until| until as i64))
+                .bind(limit as i64)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| KsError::Internal(format!("Failed to query audit log: {e}")))?;
+
+                rows.into_iter()
+                    .map(
+                        |(
+                            timestamp,
+                            action,
+                            key_id,
+                            previous_key_id,
+                            caller,
+                            remote_addr,
+                            nonce,
+                            transport,
+                        )| {
+                            Ok(AuditEntry {
+                                timestamp: timestamp as u64,
+                                action: action.parse()?,
+                                key_id: key_id as u32,
+                                previous_key_id: previous_key_id.map(|id| id as u32),
+                                caller,
+                                remote_addr,
+                                nonce,
+                                transport,
+                            })
+                        },
+                    )
+                    .collect()
+            }
+            AuditSink::File { path, lock } => {
+                let content = {
+                    let _guard = lock.lock().await;
+                    match tokio::fs::read_to_string(path).await {
+                        Ok(content) => content,
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                        Err(e) => {
+                            return Err(KsError::Internal(format!(
+                                "Failed to read audit log: {e}"
+                            )));
+                        }
+                    }
+                };
+
+                let mut entries = Vec::new();
+                for line in content.lines().rev().filter(|line| !line.trim().is_empty()) {
+                    let entry: AuditEntry = serde_json::from_str(line)?;
+                    if query.matches(&entry) {
+                        entries.push(entry);
+                        if entries.len() == limit {
+                            break;
+                        }
+                    }
+                }
+                Ok(entries)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn context(caller: &str) -> AuditContext {
+        AuditContext {
+            caller: caller.to_string(),
+            remote_addr: Some("10.0.0.1:5000".to_string()),
+            transport: "grpc",
+        }
+    }
+
+    async fn record_sample(log: &AuditLog) {
+        let ais = context("ais.actrix.internal");
+        let signaling = context("signaling.actrix.internal");
+        log.record(ais.entry(AuditAction::GenerateKey, 1, "n1"))
+            .await
+            .unwrap();
+        log.record(signaling.entry(AuditAction::GetSecretKey, 1, "n2"))
+            .await
+            .unwrap();
+        log.record(
+            ais.entry(AuditAction::RotateKey, 2, "n3")
+                .with_previous_key_id(Some(1)),
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn assert_queries(log: &AuditLog) {
+        let all = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        // 最新的记录在前
+        assert_eq!(all[0].action, AuditAction::RotateKey);
+        assert_eq!(all[0].previous_key_id, Some(1));
+        assert_eq!(all[2].nonce, "n1");
+        assert_eq!(all[2].remote_addr.as_deref(), Some("10.0.0.1:5000"));
+
+        let by_key = AuditQuery {
+            key_id: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(log.query(&by_key).await.unwrap().len(), 2);
+
+        let by_caller = AuditQuery {
+            action: Some(AuditAction::GetSecretKey),
+            caller: Some("signaling.actrix.internal".to_string()),
+            ..Default::default()
+        };
+        let reads = log.query(&by_caller).await.unwrap();
+        assert_eq!(reads.len(), 1);
+        assert_eq!(reads[0].nonce, "n2");
+
+        let limited = AuditQuery {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(log.query(&limited).await.unwrap().len(), 1);
+
+        let future = AuditQuery {
+            since: Some(u64::MAX / 2),
+            ..Default::default()
+        };
+        assert!(log.query(&future).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_audit_log() {
+        let temp_dir = tempdir().unwrap();
+        let config = KsAuditConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let log = AuditLog::from_config(&config, temp_dir.path())
+            .await
+            .unwrap();
+        assert!(log.is_enabled());
+
+        record_sample(&log).await;
+        assert_queries(&log).await;
+
+        // 只允许追加
+        let Some(AuditSink::Sqlite(pool)) = log.sink.as_deref() else {
+            panic!("Expected sqlite sink");
+        };
+        assert!(
+            sqlx::query("DELETE FROM audit_log")
+                .execute(pool)
+                .await
+                .is_err()
+        );
+        assert!(
+            sqlx::query("UPDATE audit_log SET caller = 'forged'")
+                .execute(pool)
+                .await
+                .is_err()
+        );
+        assert_eq!(log.query(&AuditQuery::default()).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_memory_audit_log() {
+        let temp_dir = tempdir().unwrap();
+        let config = KsAuditConfig {
+            enabled: true,
+            sink: AuditSinkType::Memory,
+            file_path: None,
+        };
+        let log = AuditLog::from_config(&config, temp_dir.path())
+            .await
+            .unwrap();
+
+        record_sample(&log).await;
+        assert_queries(&log).await;
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_file_audit_log() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("audit").join("ks_audit.log");
+        let config = KsAuditConfig {
+            enabled: true,
+            sink: AuditSinkType::File,
+            file_path: Some(path.display().to_string()),
+        };
+        let log = AuditLog::from_config(&config, temp_dir.path())
+            .await
+            .unwrap();
+
+        // 尚未写入时查询为空
+        assert!(log.query(&AuditQuery::default()).await.unwrap().is_empty());
+
+        record_sample(&log).await;
+        assert_queries(&log).await;
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_audit_log() {
+        let log = AuditLog::from_config(&KsAuditConfig::default(), Path::new("/nonexistent"))
+            .await
+            .unwrap();
+        assert!(!log.is_enabled());
+        record_sample(&log).await;
+        assert!(log.query(&AuditQuery::default()).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_from_params() {
+        let params: HashMap<String, String> = [
+            ("action", "revoke_key"),
+            ("key_id", "7"),
+            ("since", "100"),
+            ("limit", "5000"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let query = AuditQuery::from_params(&params).unwrap();
+        assert_eq!(query.action, Some(AuditAction::RevokeKey));
+        assert_eq!(query.key_id, Some(7));
+        assert_eq!(query.since, Some(100));
+        assert_eq!(query.effective_limit(), MAX_AUDIT_QUERY_LIMIT);
+
+        let invalid: HashMap<String, String> =
+            [("action".to_string(), "delete_key".to_string())].into();
+        assert!(AuditQuery::from_params(&invalid).is_err());
+        let invalid: HashMap<String, String> = [("key_id".to_string(), "abc".to_string())].into();
+        assert!(AuditQuery::from_params(&invalid).is_err());
+    }
+}
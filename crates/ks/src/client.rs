@@ -64,7 +64,10 @@ impl Client {
         let credential = CredentialBuilder::new(self.actrix_shared_key.as_bytes())
             .sign(request_data.as_bytes())?;
 
-        let request = GenerateKeyRequest { credential };
+        let request = GenerateKeyRequest {
+            credential,
+            algorithm: None,
+        };
 
         debug!("Requesting key generation from KS at {}", url);
 
@@ -3,8 +3,10 @@
 //! KS 服务用于生成和管理加密密钥，为其他服务提供密钥生成和公钥查询功能
 
 use crate::crypto::KekSource;
+use crate::pkcs11::Pkcs11KekConfig;
 use crate::storage::StorageConfig;
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 /// KS 服务配置
 ///
@@ -23,6 +25,13 @@ pub struct KsServiceConfig {
     #[serde(default = "default_tolerance")]
     pub tolerance_seconds: u64,
 
+    /// 密钥轮替宽限期 (秒)
+    ///
+    /// RotateKey 后旧密钥转为仅验证，在此期间内仍可获取私钥用于验证已签发的凭证
+    /// 默认: 86400 (24小时)，可在单次轮替请求中覆盖
+    #[serde(default = "default_rotation_grace")]
+    pub rotation_grace_seconds: u64,
+
     /// KEK (Key Encryption Key) - 直接配置
     ///
     /// 用于加密存储的私钥。支持两种格式：
@@ -46,30 +55,295 @@ pub struct KsServiceConfig {
     /// 文件权限应设置为 600 (仅所有者可读写)
     #[serde(default)]
     pub kek_file: Option<String>,
+
+    /// 硬件保护的 KEK 提供方
+    ///
+    /// 配置后优先于 kek / kek_env / kek_file，KEK 不离开 HSM
+    /// 例如：`[services.ks.kek_provider]` 下 `type = "pkcs11"`
+    #[serde(default)]
+    pub kek_provider: Option<KekProviderConfig>,
+
+    /// gRPC 监听器配置
+    #[serde(default)]
+    pub grpc: KsGrpcConfig,
+
+    /// gRPC 监听器双向 TLS（旧配置位置，新配置请使用 `grpc.bind.tls`）
+    ///
+    /// 与 `grpc.bind.tls` 不能同时配置，见 [`KsServiceConfig::grpc_tls_config`]
+    #[serde(default)]
+    pub grpc_tls: Option<KsGrpcTlsConfig>,
+
+    /// 密钥访问审计日志（见 [`crate::audit`]）
+    #[serde(default)]
+    pub audit: KsAuditConfig,
+
+    /// 密钥预生成、定时轮替与过期清理（见 [`crate::scheduler`]）
+    #[serde(default)]
+    pub rotation: KsRotationConfig,
+}
+
+/// KS 后台密钥调度配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KsRotationConfig {
+    /// 定时轮替的 cron 表达式（UTC，5 段格式或 `@daily` 等简写，见 [`crate::cron`]）
+    ///
+    /// 未配置时不自动轮替
+    #[serde(default)]
+    pub schedule: Option<String>,
+
+    /// 预生成密钥池大小，0 表示不预生成
+    ///
+    /// GenerateKey 优先从池中取出已生成的密钥，降低请求延迟
+    #[serde(default)]
+    pub pool_size: usize,
+
+    /// 池中密钥的最长存放时间 (秒)，超过后丢弃并重新生成
+    /// 默认: 300 (5分钟)
+    #[serde(default = "default_pool_max_age")]
+    pub pool_max_age_seconds: u64,
+
+    /// 过期密钥清理间隔 (秒)，0 表示不清理
+    /// 默认: 3600 (1小时)
+    #[serde(default = "default_cleanup_interval")]
+    pub cleanup_interval_seconds: u64,
+}
+
+fn default_pool_max_age() -> u64 {
+    300
+}
+
+fn default_cleanup_interval() -> u64 {
+    3600
+}
+
+impl Default for KsRotationConfig {
+    fn default() -> Self {
+        Self {
+            schedule: None,
+            pool_size: 0,
+            pool_max_age_seconds: default_pool_max_age(),
+            cleanup_interval_seconds: default_cleanup_interval(),
+        }
+    }
+}
+
+impl KsRotationConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(schedule) = &self.schedule {
+            schedule
+                .parse::<crate::cron::CronSchedule>()
+                .map_err(|e| format!("rotation.schedule: {e}"))?;
+        }
+        if self.pool_size > 0 && self.pool_max_age_seconds == 0 {
+            return Err(
+                "rotation.pool_max_age_seconds must be greater than 0 when pool_size is set"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// KS 审计日志配置
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct KsAuditConfig {
+    /// 是否记录审计日志
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 写入目标
+    #[serde(default)]
+    pub sink: AuditSinkType,
+
+    /// `sink = "file"` 时的文件路径，默认为 sqlite_path 下的 ks_audit.log
+    #[serde(default)]
+    pub file_path: Option<String>,
+}
+
+/// 审计日志写入目标
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSinkType {
+    /// sqlite_path 下的 ks_audit.db（只允许追加）
+    #[default]
+    Sqlite,
+    /// 进程内存 SQLite 数据库（只允许追加，重启后丢失），全局 `storage = "memory"` 时
+    /// `sqlite` 自动改用此目标
+    Memory,
+    /// JSON Lines 文件
+    File,
+}
+
+/// KS gRPC 监听器配置
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct KsGrpcConfig {
+    /// 监听地址
+    #[serde(default)]
+    pub bind: KsGrpcBindConfig,
+}
+
+/// KS gRPC 监听地址
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KsGrpcBindConfig {
+    /// 绑定 IP 地址
+    /// 默认: "127.0.0.1"（仅本机访问）
+    #[serde(default = "default_grpc_ip")]
+    pub ip: String,
+
+    /// 绑定端口
+    /// 默认: 50052
+    #[serde(default = "default_grpc_port")]
+    pub port: u16,
+
+    /// 双向 TLS（见 [`crate::mtls`]）
+    ///
+    /// 未配置时 gRPC 使用明文连接，仅依赖 PSK 签名认证
+    #[serde(default)]
+    pub tls: Option<KsGrpcTlsConfig>,
+}
+
+fn default_grpc_ip() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_grpc_port() -> u16 {
+    50052
+}
+
+impl Default for KsGrpcBindConfig {
+    fn default() -> Self {
+        Self {
+            ip: default_grpc_ip(),
+            port: default_grpc_port(),
+            tls: None,
+        }
+    }
+}
+
+impl KsGrpcBindConfig {
+    /// 监听的 socket 地址
+    pub fn socket_addr(&self) -> Result<SocketAddr, String> {
+        let ip: IpAddr = self
+            .ip
+            .parse()
+            .map_err(|_| format!("grpc.bind.ip is not a valid IP address: {}", self.ip))?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
+
+    /// 本机客户端连接的地址，通配地址替换为对应的回环地址
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        let addr = self.socket_addr()?;
+        let ip = match addr.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            ip => ip,
+        };
+        Ok(SocketAddr::new(ip, addr.port()))
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<(), String> {
+        self.socket_addr()?;
+        if self.port == 0 {
+            return Err("grpc.bind.port must not be 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// KS gRPC 双向 TLS 配置
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KsGrpcTlsConfig {
+    /// 服务端证书（PEM）路径
+    pub cert: String,
+
+    /// 服务端私钥（PEM）路径
+    pub key: String,
+
+    /// 用于校验客户端证书的 CA（PEM）路径
+    pub client_ca: String,
+
+    /// 允许访问的客户端证书 SAN（DNS 名称或 URI），例如
+    /// `["ais.actrix.internal", "signaling.actrix.internal", "supervisor.actrix.internal"]`
+    ///
+    /// 为空时接受 client_ca 签发的任意证书（包括没有 SAN 的证书，见 [`crate::mtls::ClientIdentity`]）
+    #[serde(default)]
+    pub allowed_client_sans: Vec<String>,
+}
+
+impl KsGrpcTlsConfig {
+    /// 校验配置
+    pub fn validate(&self) -> Result<(), String> {
+        for (field, value) in [
+            ("cert", &self.cert),
+            ("key", &self.key),
+            ("client_ca", &self.client_ca),
+        ] {
+            if value.trim().is_empty() {
+                return Err(format!("grpc_tls.{field} must not be empty"));
+            }
+        }
+        if self
+            .allowed_client_sans
+            .iter()
+            .any(|san| san.trim().is_empty())
+        {
+            return Err("grpc_tls.allowed_client_sans must not contain empty entries".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// KEK 提供方
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum KekProviderConfig {
+    /// PKCS#11 令牌（HSM、SoftHSM 等），需要启用 `kek-pkcs11` feature
+    Pkcs11(Pkcs11KekConfig),
 }
 
 fn default_tolerance() -> u64 {
     3600
 }
 
+fn default_rotation_grace() -> u64 {
+    86400
+}
+
 impl Default for KsServiceConfig {
     fn default() -> Self {
         Self {
             storage: Default::default(),
             tolerance_seconds: default_tolerance(),
+            rotation_grace_seconds: default_rotation_grace(),
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: KsGrpcConfig::default(),
+            grpc_tls: None,
+            audit: KsAuditConfig::default(),
+            rotation: KsRotationConfig::default(),
         }
     }
 }
 
 impl KsServiceConfig {
+    /// 生效的 gRPC 双向 TLS 配置：优先 `grpc.bind.tls`，其次旧的 `grpc_tls`
+    pub fn grpc_tls_config(&self) -> Option<&KsGrpcTlsConfig> {
+        self.grpc.bind.tls.as_ref().or(self.grpc_tls.as_ref())
+    }
+
     /// 获取 KEK 源
     ///
-    /// 优先级: kek_file > kek_env > kek
+    /// 优先级: kek_provider > kek_file > kek_env > kek
     /// 如果都未配置，返回 None（使用无加密模式）
     pub fn get_kek_source(&self) -> Option<KekSource> {
+        if let Some(KekProviderConfig::Pkcs11(config)) = &self.kek_provider {
+            return Some(KekSource::Pkcs11(config.clone()));
+        }
+
         if let Some(path) = &self.kek_file {
             return Some(KekSource::File(path.clone()));
         }
@@ -95,6 +369,7 @@ mod tests {
     fn test_default_ks_service_config() {
         let config = KsServiceConfig::default();
         assert_eq!(config.storage.backend, StorageBackend::Sqlite);
+        assert_eq!(config.rotation_grace_seconds, 86400);
     }
 
     #[test]
@@ -103,13 +378,21 @@ mod tests {
             storage: StorageConfig {
                 backend: StorageBackend::Sqlite,
                 key_ttl_seconds: 7200,
-                sqlite: Some(SqliteConfig {}),
+                sqlite: Some(SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         };
 
         let toml = toml::to_string(&config).unwrap();
@@ -156,4 +439,153 @@ mod tests {
             _ => panic!("Expected File KEK source"),
         }
     }
+
+    #[test]
+    fn test_parse_pkcs11_kek_provider() {
+        let config: KsServiceConfig = toml::from_str(
+            r#"
+            kek_file = "/path/to/kek"
+
+            [kek_provider]
+            type = "pkcs11"
+            module_path = "/usr/lib/softhsm/libsofthsm2.so"
+            token_label = "actrix"
+            key_label = "actrix-ks-kek"
+            pin_env = "ACTRIX_KS_HSM_PIN"
+            "#,
+        )
+        .unwrap();
+
+        // kek_provider 优先于本地 KEK
+        match config.get_kek_source() {
+            Some(KekSource::Pkcs11(p)) => {
+                assert_eq!(p.token_label.as_deref(), Some("actrix"));
+                assert_eq!(p.key_label, "actrix-ks-kek");
+                assert_eq!(p.slot_id, None);
+            }
+            _ => panic!("Expected Pkcs11 KEK source"),
+        }
+    }
+
+    #[test]
+    fn test_parse_grpc_tls() {
+        let config: KsServiceConfig = toml::from_str(
+            r#"
+            [grpc_tls]
+            cert = "/etc/actrix/ks.pem"
+            key = "/etc/actrix/ks.key"
+            client_ca = "/etc/actrix/ca.pem"
+            allowed_client_sans = ["ais.actrix.internal", "spiffe://actrix/signaling"]
+            "#,
+        )
+        .unwrap();
+
+        let tls = config.grpc_tls.unwrap();
+        assert_eq!(tls.client_ca, "/etc/actrix/ca.pem");
+        assert_eq!(tls.allowed_client_sans.len(), 2);
+        assert!(tls.validate().is_ok());
+
+        let mut invalid = tls.clone();
+        invalid.client_ca = String::new();
+        assert!(invalid.validate().is_err());
+
+        assert!(KsServiceConfig::default().grpc_tls.is_none());
+        assert!(KsServiceConfig::default().grpc_tls_config().is_none());
+    }
+
+    #[test]
+    fn test_parse_audit() {
+        let config: KsServiceConfig = toml::from_str(
+            r#"
+            [audit]
+            enabled = true
+            sink = "file"
+            file_path = "/var/log/actrix/ks_audit.log"
+            "#,
+        )
+        .unwrap();
+        assert!(config.audit.enabled);
+        assert_eq!(config.audit.sink, AuditSinkType::File);
+
+        let default = KsServiceConfig::default().audit;
+        assert!(!default.enabled);
+        assert_eq!(default.sink, AuditSinkType::Sqlite);
+    }
+
+    #[test]
+    fn test_parse_rotation() {
+        let config: KsServiceConfig = toml::from_str(
+            r#"
+            [rotation]
+            schedule = "0 3 * * 0"
+            pool_size = 4
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.rotation.schedule.as_deref(), Some("0 3 * * 0"));
+        assert_eq!(config.rotation.pool_size, 4);
+        assert_eq!(config.rotation.pool_max_age_seconds, 300);
+        assert_eq!(config.rotation.cleanup_interval_seconds, 3600);
+        assert!(config.rotation.validate().is_ok());
+
+        let default = KsServiceConfig::default().rotation;
+        assert!(default.schedule.is_none());
+        assert_eq!(default.pool_size, 0);
+
+        let invalid = KsRotationConfig {
+            schedule: Some("every day".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_grpc_bind() {
+        let default = KsServiceConfig::default().grpc.bind;
+        assert_eq!(
+            default.socket_addr().unwrap().to_string(),
+            "127.0.0.1:50052"
+        );
+        assert!(default.validate().is_ok());
+
+        let config: KsServiceConfig = toml::from_str(
+            r#"
+            [grpc.bind]
+            ip = "0.0.0.0"
+            port = 6052
+
+            [grpc.bind.tls]
+            cert = "/etc/actrix/ks.pem"
+            key = "/etc/actrix/ks.key"
+            client_ca = "/etc/actrix/ca.pem"
+            "#,
+        )
+        .unwrap();
+        let bind = &config.grpc.bind;
+        assert_eq!(bind.socket_addr().unwrap().to_string(), "0.0.0.0:6052");
+        assert_eq!(bind.local_addr().unwrap().to_string(), "127.0.0.1:6052");
+        assert_eq!(
+            config.grpc_tls_config().map(|tls| tls.cert.as_str()),
+            Some("/etc/actrix/ks.pem")
+        );
+
+        let ipv6 = KsGrpcBindConfig {
+            ip: "::".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(ipv6.local_addr().unwrap().to_string(), "[::1]:50052");
+
+        for invalid in [
+            KsGrpcBindConfig {
+                ip: "localhost".to_string(),
+                ..Default::default()
+            },
+            KsGrpcBindConfig {
+                port: 0,
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
 }
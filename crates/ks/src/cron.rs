@@ -0,0 +1,264 @@
+//! 定时轮替使用的 cron 表达式
+//!
+//! 标准 5 段格式（UTC）：`分 时 日 月 周`，每段支持 `*`、数字、`a-b` 范围、`a,b` 列表与
+//! `/n` 步长；周的取值为 0-7（0 与 7 均为周日）。另支持 `@hourly`、`@daily`（`@midnight`）、
+//! `@weekly`、`@monthly`、`@yearly`（`@annually`）简写。
+//!
+//! 与常见 cron 实现一致，日与周同时受限时满足其一即触发。
+
+use chrono::{DateTime, Datelike, Days, Duration, NaiveDate, TimeZone, Timelike, Utc};
+use std::str::FromStr;
+
+/// 查找下一次触发时间的最大跨度（覆盖仅在闰年 2 月 29 日触发的表达式）
+const MAX_SEARCH_DAYS: u64 = 366 * 8;
+
+/// 解析后的 cron 表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// 日段不是 `*`
+    day_of_month_restricted: bool,
+    /// 周段不是 `*`
+    day_of_week_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "cron expression '{s}' must have 5 fields (minute hour day month weekday)"
+            ));
+        };
+
+        // 7 与 0 都表示周日
+        let mut days_of_week = parse_field(day_of_week, "weekday", 0, 7)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, "minute", 0, 59)?,
+            hours: parse_field(hour, "hour", 0, 23)?,
+            days_of_month: parse_field(day_of_month, "day", 1, 31)?,
+            months: parse_field(month, "month", 1, 12)?,
+            days_of_week,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+}
+
+/// 解析单个字段为位图（第 n 位表示取值 n）
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<u64, String> {
+    let invalid = || format!("Invalid cron {name} field '{field}'");
+    let mut bits = 0u64;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse().map_err(|_| invalid())?,
+                end.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let value: u32 = range.parse().map_err(|_| invalid())?;
+            // `a/n` 表示从 a 开始到最大值
+            (value, if item.contains('/') { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!(
+                "Invalid cron {name} field '{field}': values must be within {min}-{max}"
+            ));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+impl CronSchedule {
+    /// 严格晚于 `after` 的下一次触发时间（精确到分钟）
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut next = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = next.checked_add_days(Days::new(MAX_SEARCH_DAYS))?;
+
+        while next < limit {
+            if !contains(self.months, next.month()) {
+                let (year, month) = if next.month() == 12 {
+                    (next.year() + 1, 1)
+                } else {
+                    (next.year(), next.month() + 1)
+                };
+                next = start_of_day(NaiveDate::from_ymd_opt(year, month, 1)?)?;
+                continue;
+            }
+
+            if !self.day_matches(next) {
+                next = start_of_day(next.date_naive().checked_add_days(Days::new(1))?)?;
+                continue;
+            }
+
+            if !contains(self.hours, next.hour()) {
+                next = next.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+
+            if !contains(self.minutes, next.minute()) {
+                next += Duration::minutes(1);
+                continue;
+            }
+
+            return Some(next);
+        }
+
+        None
+    }
+
+    fn day_matches(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month = contains(self.days_of_month, time.day());
+        let day_of_week = contains(self.days_of_week, time.weekday().num_days_from_sunday());
+
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+}
+
+fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn start_of_day(date: NaiveDate) -> Option<DateTime<Utc>> {
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> DateTime<Utc> {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(
+            next("0 3 * * *", "2025-01-01T02:59:30Z"),
+            at("2025-01-01T03:00:00Z")
+        );
+        // 严格晚于给定时间
+        assert_eq!(
+            next("0 3 * * *", "2025-01-01T03:00:00Z"),
+            at("2025-01-02T03:00:00Z")
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2025-01-01T10:07:00Z"),
+            at("2025-01-01T10:15:00Z")
+        );
+        assert_eq!(
+            next("30 9-17/4 * * *", "2025-01-01T13:31:00Z"),
+            at("2025-01-01T17:30:00Z")
+        );
+        // 跨年
+        assert_eq!(
+            next("@monthly", "2025-12-15T00:00:00Z"),
+            at("2026-01-01T00:00:00Z")
+        );
+        // 闰年 2 月 29 日
+        assert_eq!(
+            next("0 0 29 2 *", "2025-03-01T00:00:00Z"),
+            at("2028-02-29T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_day_of_week() {
+        // 2025-01-01 为周三；7 与 0 都表示周日
+        assert_eq!(
+            next("0 0 * * 7", "2025-01-01T00:00:00Z"),
+            at("2025-01-05T00:00:00Z")
+        );
+        assert_eq!(
+            next("@weekly", "2025-01-01T00:00:00Z"),
+            at("2025-01-05T00:00:00Z")
+        );
+        assert_eq!(
+            next("0 8 * * 1-5", "2025-01-03T09:00:00Z"),
+            at("2025-01-06T08:00:00Z")
+        );
+        // 日与周同时受限时满足其一即可
+        assert_eq!(
+            next("0 0 15 * 5", "2025-01-01T00:00:00Z"),
+            at("2025-01-03T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "@every_minute",
+        ] {
+            assert!(
+                expression.parse::<CronSchedule>().is_err(),
+                "{expression:?} should be rejected"
+            );
+        }
+        // 不可能的日期找不到触发时间
+        let never: CronSchedule = "0 0 31 2 *".parse().unwrap();
+        assert!(never.next_after(at("2025-01-01T00:00:00Z")).is_none());
+    }
+}
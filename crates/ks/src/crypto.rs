@@ -1,17 +1,21 @@
 //! KS 密钥加密模块
 //!
-//! 使用 AES-256-GCM 对存储的私钥进行加密保护
+//! 使用 AES-256-GCM 对存储的私钥进行加密保护。KEK 可以是本地密钥（配置/环境变量/文件），
+//! 也可以是 HSM 中不可导出的密钥（PKCS#11，见 [`crate::pkcs11`]）
 
 // Allow deprecated generic-array::from_slice until aes-gcm upgrades
 #![allow(deprecated)]
 
 use crate::error::{KsError, KsResult};
+use crate::pkcs11::Pkcs11KekConfig;
 use aes_gcm::{
     Aes256Gcm, Nonce,
     aead::{Aead, KeyInit, OsRng},
 };
 use base64::prelude::*;
 use rand::RngCore;
+#[cfg(feature = "kek-pkcs11")]
+use std::sync::Arc;
 use tracing::{debug, info};
 
 /// KEK (Key Encryption Key) 来源
@@ -23,6 +27,40 @@ pub enum KekSource {
     Environment(String),
     /// 从文件路径读取 KEK
     File(String),
+    /// 使用 PKCS#11 令牌中的 KEK（加解密在令牌内完成）
+    Pkcs11(Pkcs11KekConfig),
+}
+
+/// 实际执行 AES-256-GCM 的 KEK
+#[derive(Clone)]
+enum KekCipher {
+    /// 内存中的本地 KEK
+    Local(Aes256Gcm),
+    /// PKCS#11 令牌中的 KEK
+    #[cfg(feature = "kek-pkcs11")]
+    Pkcs11(Arc<crate::pkcs11::Pkcs11Kek>),
+}
+
+impl KekCipher {
+    fn encrypt(&self, nonce_bytes: &[u8; 12], plaintext: &[u8]) -> KsResult<Vec<u8>> {
+        match self {
+            KekCipher::Local(cipher) => cipher
+                .encrypt(Nonce::from_slice(nonce_bytes), plaintext)
+                .map_err(|e| KsError::Crypto(format!("Encryption failed: {e}"))),
+            #[cfg(feature = "kek-pkcs11")]
+            KekCipher::Pkcs11(kek) => kek.encrypt(nonce_bytes, plaintext),
+        }
+    }
+
+    fn decrypt(&self, nonce_bytes: &[u8; 12], ciphertext: &[u8]) -> KsResult<Vec<u8>> {
+        match self {
+            KekCipher::Local(cipher) => cipher
+                .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+                .map_err(|e| KsError::Crypto(format!("Decryption failed: {e}"))),
+            #[cfg(feature = "kek-pkcs11")]
+            KekCipher::Pkcs11(kek) => kek.decrypt(nonce_bytes, ciphertext),
+        }
+    }
 }
 
 /// 密钥加密器
@@ -31,7 +69,7 @@ pub enum KekSource {
 /// 加密格式: base64(nonce[12] || ciphertext || tag[16])
 #[derive(Clone)]
 pub struct KeyEncryptor {
-    cipher: Option<Aes256Gcm>,
+    cipher: Option<KekCipher>,
 }
 
 impl std::fmt::Debug for KeyEncryptor {
@@ -70,6 +108,7 @@ impl KeyEncryptor {
                     KsError::Config(format!("Failed to read KEK from file {path}: {e}"))
                 })?
             }
+            KekSource::Pkcs11(config) => return Self::from_pkcs11(config),
         };
 
         Self::from_kek(&kek)
@@ -110,10 +149,28 @@ impl KeyEncryptor {
 
         info!("KEK loaded successfully");
         Ok(Self {
-            cipher: Some(cipher),
+            cipher: Some(KekCipher::Local(cipher)),
+        })
+    }
+
+    /// 使用 PKCS#11 令牌中的 KEK 创建加密器
+    #[cfg(feature = "kek-pkcs11")]
+    pub fn from_pkcs11(config: &Pkcs11KekConfig) -> KsResult<Self> {
+        let kek = crate::pkcs11::Pkcs11Kek::open(config)?;
+        Ok(Self {
+            cipher: Some(KekCipher::Pkcs11(Arc::new(kek))),
         })
     }
 
+    /// 使用 PKCS#11 令牌中的 KEK 创建加密器（未启用 `kek-pkcs11` feature）
+    #[cfg(not(feature = "kek-pkcs11"))]
+    pub fn from_pkcs11(config: &Pkcs11KekConfig) -> KsResult<Self> {
+        config.validate().map_err(KsError::Config)?;
+        Err(KsError::Config(
+            "PKCS#11 KEK provider requires KS built with the `kek-pkcs11` feature".to_string(),
+        ))
+    }
+
     /// 加密私钥
     ///
     /// 如果未配置 KEK，直接返回原始私钥（向后兼容）
@@ -129,20 +186,7 @@ impl KeyEncryptor {
             }
         };
 
-        // 生成随机 nonce (12 字节)
-        let mut nonce_bytes = [0u8; 12];
-        OsRng.fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
-
-        // 加密
-        let ciphertext = cipher
-            .encrypt(nonce, secret_key.as_bytes())
-            .map_err(|e| KsError::Crypto(format!("Encryption failed: {e}")))?;
-
-        // 组合: nonce || ciphertext (包含 tag)
-        let mut encrypted = Vec::with_capacity(12 + ciphertext.len());
-        encrypted.extend_from_slice(&nonce_bytes);
-        encrypted.extend_from_slice(&ciphertext);
+        let encrypted = Self::seal_with(cipher, secret_key.as_bytes())?;
 
         // Base64 编码
         Ok(BASE64_STANDARD.encode(&encrypted))
@@ -166,24 +210,63 @@ impl KeyEncryptor {
             .decode(encrypted_key)
             .map_err(|e| KsError::Crypto(format!("Invalid encrypted key format: {e}")))?;
 
-        if encrypted_bytes.len() < 12 + 16 {
+        let plaintext = Self::open_with(cipher, &encrypted_bytes)?;
+
+        String::from_utf8(plaintext)
+            .map_err(|e| KsError::Crypto(format!("Invalid UTF-8 after decryption: {e}")))
+    }
+
+    /// 加密任意字节（供 KS 之外的组件加密存储字段，如 Signaling 服务注册表）
+    ///
+    /// 未配置 KEK 时直接返回原始数据
+    ///
+    /// 加密格式: nonce[12] || ciphertext || tag[16]（不做 Base64 编码）
+    pub fn encrypt_bytes(&self, plaintext: &[u8]) -> KsResult<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => Self::seal_with(cipher, plaintext),
+            None => Ok(plaintext.to_vec()),
+        }
+    }
+
+    /// 解密 [`Self::encrypt_bytes`] 的输出
+    ///
+    /// 未配置 KEK 时直接返回原始数据
+    pub fn decrypt_bytes(&self, encrypted: &[u8]) -> KsResult<Vec<u8>> {
+        match &self.cipher {
+            Some(cipher) => Self::open_with(cipher, encrypted),
+            None => Ok(encrypted.to_vec()),
+        }
+    }
+
+    fn seal_with(cipher: &KekCipher, plaintext: &[u8]) -> KsResult<Vec<u8>> {
+        // 生成随机 nonce (12 字节)
+        let mut nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce_bytes);
+
+        // 加密
+        let ciphertext = cipher.encrypt(&nonce_bytes, plaintext)?;
+
+        // 组合: nonce || ciphertext (包含 tag)
+        let mut encrypted = Vec::with_capacity(12 + ciphertext.len());
+        encrypted.extend_from_slice(&nonce_bytes);
+        encrypted.extend_from_slice(&ciphertext);
+        Ok(encrypted)
+    }
+
+    fn open_with(cipher: &KekCipher, encrypted: &[u8]) -> KsResult<Vec<u8>> {
+        if encrypted.len() < 12 + 16 {
             return Err(KsError::Crypto(format!(
                 "Invalid encrypted key size: expected at least 28 bytes, got {}",
-                encrypted_bytes.len()
+                encrypted.len()
             )));
         }
 
         // 分离 nonce 和 ciphertext
-        let (nonce_bytes, ciphertext) = encrypted_bytes.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
+        let (nonce_bytes, ciphertext) = encrypted.split_at(12);
+        let nonce_bytes: [u8; 12] = nonce_bytes.try_into().unwrap();
 
         // 解密
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| KsError::Crypto(format!("Decryption failed: {e}")))?;
-
-        String::from_utf8(plaintext)
-            .map_err(|e| KsError::Crypto(format!("Invalid UTF-8 after decryption: {e}")))
+        cipher.decrypt(&nonce_bytes, ciphertext)
     }
 
     /// 是否启用了加密
@@ -251,6 +334,21 @@ mod tests {
         assert_eq!(decrypted, original);
     }
 
+    #[test]
+    fn test_encrypt_bytes() {
+        let encryptor = KeyEncryptor::from_kek(&KeyEncryptor::generate_kek()).unwrap();
+
+        let original = [0u8, 1, 2, 255];
+        let encrypted = encryptor.encrypt_bytes(&original).unwrap();
+        assert_eq!(encrypted.len(), 12 + original.len() + 16);
+        assert_eq!(encryptor.decrypt_bytes(&encrypted).unwrap(), original);
+        assert!(encryptor.decrypt_bytes(&encrypted[..20]).is_err());
+
+        let plain = KeyEncryptor::no_encryption();
+        assert_eq!(plain.encrypt_bytes(&original).unwrap(), original);
+        assert_eq!(plain.decrypt_bytes(&original).unwrap(), original);
+    }
+
     #[test]
     fn test_invalid_kek_length() {
         let result = KeyEncryptor::from_kek("too-short");
@@ -352,6 +450,20 @@ mod tests {
         assert!(encryptor.is_enabled());
     }
 
+    #[cfg(not(feature = "kek-pkcs11"))]
+    #[test]
+    fn test_pkcs11_requires_feature() {
+        let result = KeyEncryptor::from_kek_source(&KekSource::Pkcs11(Pkcs11KekConfig {
+            module_path: "/usr/lib/softhsm/libsofthsm2.so".to_string(),
+            slot_id: Some(0),
+            token_label: None,
+            key_label: "actrix-ks-kek".to_string(),
+            pin_env: Some("ACTRIX_KS_HSM_PIN".to_string()),
+            pin_file: None,
+        }));
+        assert!(result.unwrap_err().to_string().contains("kek-pkcs11"));
+    }
+
     #[test]
     fn test_generate_kek_format() {
         let kek = KeyEncryptor::generate_kek();
@@ -35,6 +35,10 @@ pub enum KsError {
     #[error("Key not found: key_id={0}")]
     KeyNotFound(u32),
 
+    /// 密钥已被吊销
+    #[error("Key revoked: key_id={0}")]
+    KeyRevoked(u32),
+
     /// 内部服务器错误
     #[error("Internal server error: {0}")]
     Internal(String),
@@ -80,6 +84,7 @@ impl IntoResponse for KsError {
                 // 生产环境不泄露具体的 key_id
                 (StatusCode::NOT_FOUND, "Resource not found".to_string())
             }
+            KsError::KeyRevoked(_) => (StatusCode::GONE, "Resource revoked".to_string()),
             KsError::Database(_) | KsError::Internal(_) | KsError::Crypto(_) => {
                 // 不向客户端暴露内部错误详情
                 tracing::error!("Internal error: {:?}", self);
@@ -1,8 +1,17 @@
 //! KS gRPC 客户端
 
 use crate::error::KsError;
+use crate::revocation::KeyRevocation;
+use crate::rotation::KeyRotation;
+use crate::types::{
+    KeyAlgorithm, MAX_BATCH_KEY_IDS, RevokeKeyResponse, RotateKeyResponse, batch_request_payload,
+    generate_key_payload,
+};
+use actrix_proto::dns::DnsWatch;
 use actrix_proto::ks::v1::{
-    GenerateKeyRequest, GetSecretKeyRequest, HealthCheckRequest, key_server_client::KeyServerClient,
+    GenerateKeyRequest, GetPublicKeysRequest, GetSecretKeyRequest, GetSecretKeysRequest,
+    HealthCheckRequest, KeyRevocationEvent, KeyRotationEvent, RevokeKeyRequest, RotateKeyRequest,
+    WatchKeyRevocationsRequest, WatchKeyRotationsRequest, key_server_client::KeyServerClient,
 };
 use actrix_proto::supervisor::v1::NonceCredential;
 use base64::prelude::*;
@@ -10,8 +19,9 @@ use ecies::{PublicKey, SecretKey};
 use nonce_auth::CredentialBuilder;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use tonic::Streaming;
 use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// KS gRPC 客户端配置
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -43,15 +53,93 @@ pub struct GrpcClientConfig {
     pub client_key: Option<String>,
 }
 
+/// 密钥轮替事件订阅（`WatchKeyRotations` 服务端流）
+pub struct KeyRotationWatch {
+    stream: Streaming<KeyRotationEvent>,
+}
+
+impl KeyRotationWatch {
+    /// 等待下一个轮替事件；服务端关闭流时返回 None
+    pub async fn next(&mut self) -> Result<Option<KeyRotation>, KsError> {
+        let event = self
+            .stream
+            .message()
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC WatchKeyRotations failed: {e}")))?;
+
+        Ok(event.map(|event| KeyRotation {
+            key_id: event.key_id,
+            algorithm: event.algorithm().into(),
+            public_key: event.public_key,
+            expires_at: event.expires_at,
+            previous_key_id: event.previous_key_id,
+            verify_until: event.verify_until,
+            rotated_at: event.rotated_at,
+        }))
+    }
+}
+
+/// 密钥吊销事件订阅（`WatchKeyRevocations` 服务端流）
+///
+/// 订阅后先收到当前已吊销的密钥，再收到新的吊销事件
+pub struct KeyRevocationWatch {
+    stream: Streaming<KeyRevocationEvent>,
+}
+
+impl KeyRevocationWatch {
+    /// 等待下一个吊销事件；服务端关闭流时返回 None
+    pub async fn next(&mut self) -> Result<Option<KeyRevocation>, KsError> {
+        let event = self
+            .stream
+            .message()
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC WatchKeyRevocations failed: {e}")))?;
+
+        Ok(event.map(|event| KeyRevocation {
+            key_id: event.key_id,
+            revoked_at: event.revoked_at,
+        }))
+    }
+}
+
+/// 批量获取密钥的结果
+#[derive(Debug, Clone)]
+pub struct KeyBatch<K> {
+    /// 可用的密钥: (key_id, 密钥, expires_at, tolerance_seconds)
+    pub keys: Vec<(u32, K, u64, u64)>,
+    /// 不存在或已超过容忍期的密钥 ID
+    pub missing_key_ids: Vec<u32>,
+    /// 已吊销的密钥 ID
+    pub revoked_key_ids: Vec<u32>,
+}
+
 /// KS gRPC 客户端
+///
+/// endpoint 为域名时在后台按 TTL 重新解析，解析结果变化后在下一次请求前重建连接，
+/// 使通过 DNS 完成的故障切换无需重启即可生效
 pub struct GrpcClient {
     client: KeyServerClient<Channel>,
     actrix_shared_key: String,
+    config: GrpcClientConfig,
+    dns: Option<DnsWatch>,
 }
 
 impl GrpcClient {
     /// 创建新的 KS gRPC 客户端
     pub async fn new(config: &GrpcClientConfig) -> Result<Self, KsError> {
+        let channel = Self::connect_channel(config).await?;
+        let client = KeyServerClient::new(channel);
+
+        Ok(Self {
+            client,
+            actrix_shared_key: config.actrix_shared_key.clone(),
+            config: config.clone(),
+            dns: DnsWatch::spawn(&config.endpoint),
+        })
+    }
+
+    /// 建立到 KS 的 gRPC 连接
+    async fn connect_channel(config: &GrpcClientConfig) -> Result<Channel, KsError> {
         let mut endpoint = Endpoint::from_shared(config.endpoint.clone())
             .map_err(|e| KsError::Internal(format!("Invalid endpoint: {e}")))?
             .timeout(Duration::from_secs(config.timeout_seconds))
@@ -66,17 +154,30 @@ impl GrpcClient {
             info!("TLS enabled for KS gRPC client");
         }
 
-        let channel = endpoint
+        endpoint
             .connect()
             .await
-            .map_err(|e| KsError::Internal(format!("Failed to connect to KS: {e}")))?;
-
-        let client = KeyServerClient::new(channel);
+            .map_err(|e| KsError::Internal(format!("Failed to connect to KS: {e}")))
+    }
 
-        Ok(Self {
-            client,
-            actrix_shared_key: config.actrix_shared_key.clone(),
-        })
+    /// 获取 gRPC 客户端；endpoint 的 DNS 解析结果变化时先重建连接
+    ///
+    /// 重连失败时继续使用现有连接，等待下一次解析变化
+    async fn client(&mut self) -> &mut KeyServerClient<Channel> {
+        if let Some(dns) = self.dns.as_mut()
+            && dns.take_change()
+        {
+            info!(
+                "KS endpoint {} now resolves to {:?}, reconnecting",
+                dns.host(),
+                dns.addresses()
+            );
+            match Self::connect_channel(&self.config).await {
+                Ok(channel) => self.client = KeyServerClient::new(channel),
+                Err(e) => warn!("Failed to reconnect to KS after DNS change: {}", e),
+            }
+        }
+        &mut self.client
     }
 
     /// 构建 TLS 配置
@@ -132,71 +233,100 @@ impl GrpcClient {
         Ok(tls_config)
     }
 
-    /// 从 KS 服务生成新的密钥对
+    /// 从 KS 服务生成新的密钥对（secp256k1）
     pub async fn generate_key(&mut self) -> Result<(u32, PublicKey, u64, u64), KsError> {
-        let request_data = "generate_key";
+        let (key_id, algorithm, public_key, expires_at, tolerance_seconds) =
+            self.request_key(None).await?;
+        ensure_secp256k1(key_id, algorithm)?;
+        let public_key = decode_public_key(&public_key)?;
 
-        // 创建 nonce credential
-        let nonce_credential = CredentialBuilder::new(self.actrix_shared_key.as_bytes())
-            .sign(request_data.as_bytes())?;
+        Ok((key_id, public_key, expires_at, tolerance_seconds))
+    }
 
-        // 转换为 protobuf NonceCredential
-        let credential = NonceCredential {
-            timestamp: nonce_credential.timestamp,
-            nonce: nonce_credential.nonce,
-            signature: nonce_credential.signature,
-        };
+    /// 按指定算法生成新的密钥对
+    ///
+    /// 返回 (key_id, 公钥 Base64, expires_at, tolerance_seconds)，公钥编码见 [`KeyAlgorithm`]
+    pub async fn generate_key_with_algorithm(
+        &mut self,
+        algorithm: KeyAlgorithm,
+    ) -> Result<(u32, String, u64, u64), KsError> {
+        let (key_id, generated, public_key, expires_at, tolerance_seconds) =
+            self.request_key(Some(algorithm)).await?;
+        if generated != algorithm {
+            return Err(KsError::Crypto(format!(
+                "KS generated a {generated} key for key_id {key_id}, expected {algorithm}"
+            )));
+        }
+
+        Ok((key_id, public_key, expires_at, tolerance_seconds))
+    }
 
-        let request = tonic::Request::new(GenerateKeyRequest { credential });
+    /// 发送 GenerateKey 请求
+    ///
+    /// 返回 (key_id, algorithm, 公钥 Base64, expires_at, tolerance_seconds)
+    async fn request_key(
+        &mut self,
+        algorithm: Option<KeyAlgorithm>,
+    ) -> Result<(u32, KeyAlgorithm, String, u64, u64), KsError> {
+        let credential = self.sign_credential(&generate_key_payload(algorithm))?;
+
+        let request = tonic::Request::new(GenerateKeyRequest {
+            credential,
+            algorithm: algorithm
+                .map(|algorithm| actrix_proto::ks::v1::KeyAlgorithm::from(algorithm) as i32),
+        });
 
         debug!("Requesting key generation from KS via gRPC");
 
         let response = self
-            .client
+            .client()
+            .await
             .generate_key(request)
             .await
             .map_err(|e| KsError::Internal(format!("gRPC GenerateKey failed: {e}")))?;
 
         let resp = response.into_inner();
+        // 旧版 KS 不返回算法，均为 secp256k1
+        let algorithm = KeyAlgorithm::from(resp.algorithm());
 
-        // 解码公钥
-        let public_key_bytes = BASE64_STANDARD
-            .decode(&resp.public_key)
-            .map_err(|e| KsError::Crypto(format!("Failed to decode public key: {e}")))?;
-
-        if public_key_bytes.len() == 33 {
-            let public_key_array: [u8; 33] = public_key_bytes
-                .try_into()
-                .map_err(|_| KsError::Crypto("Invalid public key length".to_string()))?;
-            let public_key = PublicKey::parse_compressed(&public_key_array).map_err(|e| {
-                KsError::Crypto(format!("Failed to parse compressed public key: {e}"))
-            })?;
-
-            info!(
-                "Successfully generated key pair with key_id {} via gRPC, expires_at: {}, tolerance_seconds: {}",
-                resp.key_id, resp.expires_at, resp.tolerance_seconds
-            );
-            Ok((
-                resp.key_id,
-                public_key,
-                resp.expires_at,
-                resp.tolerance_seconds,
-            ))
-        } else {
-            Err(KsError::Crypto(format!(
-                "Unsupported public key length: {}",
-                public_key_bytes.len()
-            )))
-        }
+        info!(
+            "Successfully generated {} key pair with key_id {} via gRPC, expires_at: {}, tolerance_seconds: {}",
+            algorithm, resp.key_id, resp.expires_at, resp.tolerance_seconds
+        );
+        Ok((
+            resp.key_id,
+            algorithm,
+            resp.public_key,
+            resp.expires_at,
+            resp.tolerance_seconds,
+        ))
     }
 
     /// 从 KS 服务获取私钥、过期时间和容忍期秒数
     ///
-    /// 返回 (SecretKey, expires_at, tolerance_seconds)
+    /// 返回 (SecretKey, expires_at, tolerance_seconds)，仅适用于 secp256k1 密钥
     pub async fn fetch_secret_key(
         &mut self,
         key_id: u32,
     ) -> Result<(SecretKey, u64, u64), KsError> {
+        let (algorithm, secret_key, expires_at, tolerance_seconds) =
+            self.fetch_raw_secret_key(key_id).await?;
+        ensure_secp256k1(key_id, algorithm)?;
+
+        Ok((
+            decode_secret_key(&secret_key)?,
+            expires_at,
+            tolerance_seconds,
+        ))
+    }
+
+    /// 获取任意算法的私钥
+    ///
+    /// 返回 (algorithm, 私钥 Base64, expires_at, tolerance_seconds)，私钥编码见 [`KeyAlgorithm`]
+    pub async fn fetch_raw_secret_key(
+        &mut self,
+        key_id: u32,
+    ) -> Result<(KeyAlgorithm, String, u64, u64), KsError> {
         let request_data = format!("get_secret_key:{key_id}");
 
         // 创建 nonce credential
@@ -215,30 +345,235 @@ impl GrpcClient {
         debug!("Fetching secret key {} from KS via gRPC", key_id);
 
         let response = self
-            .client
+            .client()
+            .await
             .get_secret_key(request)
             .await
-            .map_err(|e| KsError::Internal(format!("gRPC GetSecretKey failed: {e}")))?;
+            .map_err(|e| match e.code() {
+                tonic::Code::PermissionDenied => KsError::KeyRevoked(key_id),
+                _ => KsError::Internal(format!("gRPC GetSecretKey failed: {e}")),
+            })?;
 
         let resp = response.into_inner();
 
-        // 解码私钥
-        let secret_key_bytes = BASE64_STANDARD
-            .decode(&resp.secret_key)
-            .map_err(|e| KsError::Crypto(format!("Failed to decode secret key: {e}")))?;
+        info!(
+            "Successfully fetched secret key {} from KS via gRPC, expires_at: {}, tolerance: {}s",
+            key_id, resp.expires_at, resp.tolerance_seconds
+        );
+        Ok((
+            resp.algorithm().into(),
+            resp.secret_key,
+            resp.expires_at,
+            resp.tolerance_seconds,
+        ))
+    }
 
-        let secret_key_array: [u8; 32] = secret_key_bytes.try_into().map_err(|_| {
-            KsError::Crypto("Invalid secret key length, expected 32 bytes".to_string())
-        })?;
+    /// 批量获取公钥
+    ///
+    /// 用于启动时预热本地密钥缓存，单次最多 [`MAX_BATCH_KEY_IDS`] 个 key_id
+    pub async fn fetch_public_keys(
+        &mut self,
+        key_ids: &[u32],
+    ) -> Result<KeyBatch<PublicKey>, KsError> {
+        let credential =
+            self.sign_credential(&batch_request_payload("get_public_keys", key_ids))?;
 
-        let secret_key = SecretKey::parse(&secret_key_array)
-            .map_err(|e| KsError::Crypto(format!("Failed to parse secret key: {e}")))?;
+        let resp = self
+            .client()
+            .await
+            .get_public_keys(tonic::Request::new(GetPublicKeysRequest {
+                key_ids: key_ids.to_vec(),
+                credential,
+            }))
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC GetPublicKeys failed: {e}")))?
+            .into_inner();
+
+        let keys = resp
+            .keys
+            .into_iter()
+            .map(|entry| {
+                ensure_secp256k1(entry.key_id, entry.algorithm().into())?;
+                Ok((
+                    entry.key_id,
+                    decode_public_key(&entry.public_key)?,
+                    entry.expires_at,
+                    entry.tolerance_seconds,
+                ))
+            })
+            .collect::<Result<Vec<_>, KsError>>()?;
+
+        debug!(
+            "Fetched {} public keys from KS via gRPC ({} missing, {} revoked)",
+            keys.len(),
+            resp.missing_key_ids.len(),
+            resp.revoked_key_ids.len()
+        );
+        Ok(KeyBatch {
+            keys,
+            missing_key_ids: resp.missing_key_ids,
+            revoked_key_ids: resp.revoked_key_ids,
+        })
+    }
+
+    /// 批量获取私钥
+    ///
+    /// 可用性规则与 [`Self::fetch_secret_key`] 相同，单次最多 [`MAX_BATCH_KEY_IDS`] 个 key_id
+    pub async fn fetch_secret_keys(
+        &mut self,
+        key_ids: &[u32],
+    ) -> Result<KeyBatch<SecretKey>, KsError> {
+        let credential =
+            self.sign_credential(&batch_request_payload("get_secret_keys", key_ids))?;
+
+        let resp = self
+            .client()
+            .await
+            .get_secret_keys(tonic::Request::new(GetSecretKeysRequest {
+                key_ids: key_ids.to_vec(),
+                credential,
+            }))
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC GetSecretKeys failed: {e}")))?
+            .into_inner();
+
+        let keys = resp
+            .keys
+            .into_iter()
+            .map(|entry| {
+                ensure_secp256k1(entry.key_id, entry.algorithm().into())?;
+                Ok((
+                    entry.key_id,
+                    decode_secret_key(&entry.secret_key)?,
+                    entry.expires_at,
+                    entry.tolerance_seconds,
+                ))
+            })
+            .collect::<Result<Vec<_>, KsError>>()?;
 
         info!(
-            "Successfully fetched secret key {} from KS via gRPC, expires_at: {}, tolerance: {}s",
-            key_id, resp.expires_at, resp.tolerance_seconds
+            "Fetched {} secret keys from KS via gRPC ({} missing, {} revoked)",
+            keys.len(),
+            resp.missing_key_ids.len(),
+            resp.revoked_key_ids.len()
         );
-        Ok((secret_key, resp.expires_at, resp.tolerance_seconds))
+        Ok(KeyBatch {
+            keys,
+            missing_key_ids: resp.missing_key_ids,
+            revoked_key_ids: resp.revoked_key_ids,
+        })
+    }
+
+    /// 轮替密钥
+    ///
+    /// 生成新的 Active 密钥，并将 `previous_key_id`（缺省为最近创建的 Active 密钥）
+    /// 转为仅验证，`grace_seconds` 缺省使用服务端配置
+    pub async fn rotate_key(
+        &mut self,
+        previous_key_id: Option<u32>,
+        grace_seconds: Option<u64>,
+    ) -> Result<RotateKeyResponse, KsError> {
+        let request_data = match previous_key_id {
+            Some(key_id) => format!("rotate_key:{key_id}"),
+            None => "rotate_key".to_string(),
+        };
+        let credential = self.sign_credential(&request_data)?;
+
+        let request = tonic::Request::new(RotateKeyRequest {
+            credential,
+            previous_key_id,
+            grace_seconds,
+        });
+
+        let resp = self
+            .client()
+            .await
+            .rotate_key(request)
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC RotateKey failed: {e}")))?
+            .into_inner();
+
+        info!(
+            "Rotated key via gRPC: new key_id {}, previous key_id {:?} verify-only until {}",
+            resp.key_id, resp.previous_key_id, resp.verify_until
+        );
+        Ok(RotateKeyResponse {
+            key_id: resp.key_id,
+            algorithm: resp.algorithm().into(),
+            public_key: resp.public_key,
+            expires_at: resp.expires_at,
+            tolerance_seconds: resp.tolerance_seconds,
+            previous_key_id: resp.previous_key_id,
+            verify_until: resp.verify_until,
+        })
+    }
+
+    /// 订阅密钥轮替事件
+    pub async fn watch_key_rotations(&mut self) -> Result<KeyRotationWatch, KsError> {
+        let credential = self.sign_credential("watch_key_rotations")?;
+
+        let stream = self
+            .client()
+            .await
+            .watch_key_rotations(tonic::Request::new(WatchKeyRotationsRequest { credential }))
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC WatchKeyRotations failed: {e}")))?
+            .into_inner();
+
+        debug!("Subscribed to KS key rotation events");
+        Ok(KeyRotationWatch { stream })
+    }
+
+    /// 吊销密钥
+    pub async fn revoke_key(&mut self, key_id: u32) -> Result<RevokeKeyResponse, KsError> {
+        let credential = self.sign_credential(&format!("revoke_key:{key_id}"))?;
+
+        let resp = self
+            .client()
+            .await
+            .revoke_key(tonic::Request::new(RevokeKeyRequest { credential, key_id }))
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC RevokeKey failed: {e}")))?
+            .into_inner();
+
+        info!(
+            "Revoked key via gRPC: key_id {} at {}",
+            resp.key_id, resp.revoked_at
+        );
+        Ok(RevokeKeyResponse {
+            key_id: resp.key_id,
+            revoked_at: resp.revoked_at,
+        })
+    }
+
+    /// 订阅密钥吊销事件
+    pub async fn watch_key_revocations(&mut self) -> Result<KeyRevocationWatch, KsError> {
+        let credential = self.sign_credential("watch_key_revocations")?;
+
+        let stream = self
+            .client()
+            .await
+            .watch_key_revocations(tonic::Request::new(WatchKeyRevocationsRequest {
+                credential,
+            }))
+            .await
+            .map_err(|e| KsError::Internal(format!("gRPC WatchKeyRevocations failed: {e}")))?
+            .into_inner();
+
+        debug!("Subscribed to KS key revocation events");
+        Ok(KeyRevocationWatch { stream })
+    }
+
+    /// 为请求数据签发 nonce credential
+    fn sign_credential(&self, request_data: &str) -> Result<NonceCredential, KsError> {
+        let nonce_credential = CredentialBuilder::new(self.actrix_shared_key.as_bytes())
+            .sign(request_data.as_bytes())?;
+
+        Ok(NonceCredential {
+            timestamp: nonce_credential.timestamp,
+            nonce: nonce_credential.nonce,
+            signature: nonce_credential.signature,
+        })
     }
 
     /// 健康检查
@@ -246,7 +581,8 @@ impl GrpcClient {
         let request = tonic::Request::new(HealthCheckRequest {});
 
         let response = self
-            .client
+            .client()
+            .await
             .health_check(request)
             .await
             .map_err(|e| KsError::Internal(format!("gRPC HealthCheck failed: {e}")))?;
@@ -256,6 +592,44 @@ impl GrpcClient {
     }
 }
 
+/// 解码为 ecies 密钥前确认密钥算法，避免把其他曲线的密钥按 secp256k1 解析
+fn ensure_secp256k1(key_id: u32, algorithm: KeyAlgorithm) -> Result<(), KsError> {
+    if algorithm != KeyAlgorithm::Secp256k1 {
+        return Err(KsError::Crypto(format!(
+            "Key {key_id} is a {algorithm} key, expected secp256k1"
+        )));
+    }
+    Ok(())
+}
+
+/// 解码 Base64 编码的压缩公钥
+fn decode_public_key(public_key: &str) -> Result<PublicKey, KsError> {
+    let public_key_bytes = BASE64_STANDARD
+        .decode(public_key)
+        .map_err(|e| KsError::Crypto(format!("Failed to decode public key: {e}")))?;
+
+    let public_key_array: [u8; 33] = public_key_bytes.try_into().map_err(|bytes: Vec<u8>| {
+        KsError::Crypto(format!("Unsupported public key length: {}", bytes.len()))
+    })?;
+
+    PublicKey::parse_compressed(&public_key_array)
+        .map_err(|e| KsError::Crypto(format!("Failed to parse compressed public key: {e}")))
+}
+
+/// 解码 Base64 编码的私钥
+fn decode_secret_key(secret_key: &str) -> Result<SecretKey, KsError> {
+    let secret_key_bytes = BASE64_STANDARD
+        .decode(secret_key)
+        .map_err(|e| KsError::Crypto(format!("Failed to decode secret key: {e}")))?;
+
+    let secret_key_array: [u8; 32] = secret_key_bytes
+        .try_into()
+        .map_err(|_| KsError::Crypto("Invalid secret key length, expected 32 bytes".to_string()))?;
+
+    SecretKey::parse(&secret_key_array)
+        .map_err(|e| KsError::Crypto(format!("Failed to parse secret key: {e}")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,20 @@
 //! KS gRPC 服务实现
 
-use crate::{error::KsError, storage::KeyStorage};
+use crate::{
+    audit::{AuditAction, AuditContext, AuditLog},
+    error::KsError,
+    scheduler::KeyPool,
+    storage::KeyStorage,
+    types::{
+        KeyAlgorithm as StoredKeyAlgorithm, KeyRecord, KeyStatus, MAX_BATCH_KEY_IDS,
+        batch_request_payload, generate_key_payload,
+    },
+};
 use nonce_auth::{CredentialVerifier, NonceError, storage::NonceStorage};
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::{Stream, wrappers::ReceiverStream};
 use tonic::{Request, Response, Status};
 use tracing::{debug, info, warn};
 
@@ -18,6 +30,28 @@ pub struct KsGrpcService {
     pub nonce_storage: Arc<dyn NonceStorage + Send + Sync>,
     pub psk: String,
     pub tolerance_seconds: u64,
+    /// 轮替时旧密钥的默认宽限期（秒）
+    pub rotation_grace_seconds: u64,
+    /// 密钥访问审计日志
+    pub audit: AuditLog,
+    /// 预生成密钥池（未启用预生成时为空池）
+    pub key_pool: KeyPool,
+}
+
+/// 轮替事件推送流
+type KeyRotationStream = Pin<Box<dyn Stream<Item = Result<KeyRotationEvent, Status>> + Send>>;
+
+/// 吊销事件推送流
+type KeyRevocationStream = Pin<Box<dyn Stream<Item = Result<KeyRevocationEvent, Status>> + Send>>;
+
+/// 批量查询中单个密钥的可用性
+enum KeyLookup {
+    /// 可用，附带对外报告的 (expires_at, tolerance_seconds)
+    Available(KeyRecord, u64, u64),
+    /// 不存在或已超过容忍期
+    Missing,
+    /// 已吊销
+    Revoked,
 }
 
 impl KsGrpcService {
@@ -33,9 +67,55 @@ impl KsGrpcService {
             nonce_storage: Arc::new(nonce_storage),
             psk,
             tolerance_seconds,
+            rotation_grace_seconds: crate::config::KsServiceConfig::default()
+                .rotation_grace_seconds,
+            audit: AuditLog::disabled(),
+            key_pool: KeyPool::default(),
         }
     }
 
+    /// 设置轮替时旧密钥的默认宽限期（秒）
+    pub fn with_rotation_grace_seconds(mut self, rotation_grace_seconds: u64) -> Self {
+        self.rotation_grace_seconds = rotation_grace_seconds;
+        self
+    }
+
+    /// 设置审计日志
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// 设置预生成密钥池（见 [`crate::scheduler::KeyScheduler::pool`]）
+    pub fn with_key_pool(mut self, key_pool: KeyPool) -> Self {
+        self.key_pool = key_pool;
+        self
+    }
+
+    /// 防重放使用的 nonce 存储
+    pub fn nonce_storage(&self) -> Arc<dyn NonceStorage + Send + Sync> {
+        self.nonce_storage.clone()
+    }
+
+    /// 追加审计记录，写入失败时拒绝请求
+    async fn record_audit(
+        &self,
+        context: &AuditContext,
+        action: AuditAction,
+        key_id: u32,
+        previous_key_id: Option<u32>,
+        nonce: &str,
+    ) -> Result<(), Status> {
+        self.audit
+            .record(
+                context
+                    .entry(action, key_id, nonce)
+                    .with_previous_key_id(previous_key_id),
+            )
+            .await
+            .map_err(|e| Status::internal(format!("Failed to write audit log: {e}")))
+    }
+
     /// 验证请求的 nonce 凭证
     async fn verify_credential(
         &self,
@@ -67,6 +147,53 @@ impl KsGrpcService {
 
         Ok(())
     }
+
+    /// 校验批量请求的 key_id 列表并去重（保持请求顺序）
+    fn dedup_key_ids(key_ids: &[u32]) -> Result<Vec<u32>, Status> {
+        if key_ids.len() > MAX_BATCH_KEY_IDS {
+            return Err(Status::invalid_argument(format!(
+                "Too many key_ids: {} (max {MAX_BATCH_KEY_IDS})",
+                key_ids.len()
+            )));
+        }
+
+        let mut unique = Vec::with_capacity(key_ids.len());
+        for key_id in key_ids {
+            if !unique.contains(key_id) {
+                unique.push(*key_id);
+            }
+        }
+        Ok(unique)
+    }
+
+    /// 按 GetSecretKey 的规则判断密钥是否可用
+    async fn lookup_key(&self, key_id: u32) -> Result<KeyLookup, Status> {
+        let Some(record) = self
+            .storage
+            .get_key_record(key_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get key record: {e}")))?
+        else {
+            return Ok(KeyLookup::Missing);
+        };
+
+        if record.status == KeyStatus::Revoked {
+            return Ok(KeyLookup::Revoked);
+        }
+
+        let (expires_at, tolerance_seconds) = record.effective_expiry(self.tolerance_seconds);
+        if expires_at > 0 {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            if expires_at + tolerance_seconds < now {
+                return Ok(KeyLookup::Missing);
+            }
+        }
+
+        Ok(KeyLookup::Available(record, expires_at, tolerance_seconds))
+    }
 }
 
 #[tonic::async_trait]
@@ -78,18 +205,31 @@ impl KeyServer for KsGrpcService {
     ) -> Result<Response<GenerateKeyResponse>, Status> {
         info!("Received gRPC GenerateKey request");
 
+        let audit_context = AuditContext::from_grpc(&request);
         let req = request.into_inner();
 
+        // 未知的算法值直接拒绝，避免静默回退为默认算法
+        let algorithm = req
+            .algorithm
+            .map(|value| {
+                KeyAlgorithm::try_from(value)
+                    .map(StoredKeyAlgorithm::from)
+                    .map_err(|_| {
+                        Status::invalid_argument(format!("Unknown key algorithm: {value}"))
+                    })
+            })
+            .transpose()?;
+
         // 验证凭证（proto2 required 字段直接是结构体类型）
-        let request_data = "generate_key";
-        self.verify_credential(&req.credential, request_data)
+        let request_data = generate_key_payload(algorithm);
+        self.verify_credential(&req.credential, &request_data)
             .await
             .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
 
-        // 生成密钥对
+        // 优先取出预生成的密钥，池为空时直接生成
         let key_pair = self
-            .storage
-            .generate_and_store_key()
+            .key_pool
+            .take_or_generate(&self.storage, algorithm.unwrap_or_default())
             .await
             .map_err(|e| Status::internal(format!("Failed to generate key: {e}")))?;
 
@@ -101,13 +241,26 @@ impl KeyServer for KsGrpcService {
             .map_err(|e| Status::internal(format!("Failed to get key record: {e}")))?
             .ok_or_else(|| Status::internal("Failed to get key record after creation"))?;
 
-        info!("Generated key pair with key_id: {}", key_pair.key_id);
+        self.record_audit(
+            &audit_context,
+            AuditAction::GenerateKey,
+            key_pair.key_id,
+            None,
+            &req.credential.nonce,
+        )
+        .await?;
+
+        info!(
+            "Generated {} key pair with key_id: {}",
+            key_pair.algorithm, key_pair.key_id
+        );
 
         let response = GenerateKeyResponse {
             key_id: key_pair.key_id,
             public_key: key_pair.public_key,
             expires_at: key_record.expires_at,
             tolerance_seconds: self.tolerance_seconds,
+            algorithm: Some(KeyAlgorithm::from(key_pair.algorithm) as i32),
         };
 
         Ok(Response::new(response))
@@ -118,6 +271,7 @@ impl KeyServer for KsGrpcService {
         &self,
         request: Request<GetSecretKeyRequest>,
     ) -> Result<Response<GetSecretKeyResponse>, Status> {
+        let audit_context = AuditContext::from_grpc(&request);
         let req = request.into_inner();
         let key_id = req.key_id;
 
@@ -137,23 +291,31 @@ impl KeyServer for KsGrpcService {
             .map_err(|e| Status::internal(format!("Failed to get key record: {e}")))?
             .ok_or_else(|| Status::not_found(format!("Key not found: {key_id}")))?;
 
-        // 检查密钥是否超过容忍期
-        let tolerance_seconds = self.tolerance_seconds;
+        // 已吊销的密钥不再提供私钥，与过期区分以便验证方将其列入黑名单
+        if key_record.status == KeyStatus::Revoked {
+            warn!("Rejected GetSecretKey for revoked key {}", key_id);
+            return Err(Status::permission_denied(format!(
+                "Key {key_id} has been revoked"
+            )));
+        }
+
+        // 检查密钥是否超过容忍期（仅验证的密钥以宽限期为准）
+        let (expires_at, tolerance_seconds) = key_record.effective_expiry(self.tolerance_seconds);
 
-        if key_record.expires_at > 0 {
+        if expires_at > 0 {
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs();
 
             // 检查是否超过了过期时间 + 容忍期
-            if key_record.expires_at + tolerance_seconds < now {
+            if expires_at + tolerance_seconds < now {
                 warn!("Key {} has expired beyond tolerance period", key_id);
                 return Err(Status::not_found(format!("Key {key_id} has expired")));
             }
 
             // 记录是否在容忍期内
-            if key_record.expires_at < now {
+            if expires_at < now {
                 warn!("Key {} is in tolerance period", key_id);
             }
         }
@@ -166,21 +328,333 @@ impl KeyServer for KsGrpcService {
             .map_err(|e| Status::internal(format!("Failed to get secret key: {e}")))?
             .ok_or_else(|| Status::not_found(format!("Secret key not found: {key_id}")))?;
 
+        self.record_audit(
+            &audit_context,
+            AuditAction::GetSecretKey,
+            key_id,
+            None,
+            &req.credential.nonce,
+        )
+        .await?;
+
         info!(
             "Found secret key for key_id: {}, expires_at: {}",
-            key_id, key_record.expires_at
+            key_id, expires_at
         );
 
         let response = GetSecretKeyResponse {
             key_id,
             secret_key,
-            expires_at: key_record.expires_at,
+            expires_at,
             tolerance_seconds,
+            algorithm: Some(KeyAlgorithm::from(key_record.algorithm) as i32),
         };
 
         Ok(Response::new(response))
     }
 
+    /// 轮替密钥
+    async fn rotate_key(
+        &self,
+        request: Request<RotateKeyRequest>,
+    ) -> Result<Response<RotateKeyResponse>, Status> {
+        let audit_context = AuditContext::from_grpc(&request);
+        let req = request.into_inner();
+        info!(
+            "Received gRPC RotateKey request, previous_key_id: {:?}",
+            req.previous_key_id
+        );
+
+        let request_data = match req.previous_key_id {
+            Some(key_id) => format!("rotate_key:{key_id}"),
+            None => "rotate_key".to_string(),
+        };
+        self.verify_credential(&req.credential, &request_data)
+            .await
+            .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
+
+        let grace_seconds = req.grace_seconds.unwrap_or(self.rotation_grace_seconds);
+        let rotation =
+            crate::rotation::rotate_key(&self.storage, req.previous_key_id, grace_seconds)
+                .await
+                .map_err(|e| match e {
+                    KsError::KeyNotFound(key_id) => {
+                        Status::not_found(format!("Key not found: {key_id}"))
+                    }
+                    KsError::InvalidRequest(message) => Status::failed_precondition(message),
+                    e => Status::internal(format!("Failed to rotate key: {e}")),
+                })?;
+
+        self.record_audit(
+            &audit_context,
+            AuditAction::RotateKey,
+            rotation.key_id,
+            rotation.previous_key_id,
+            &req.credential.nonce,
+        )
+        .await?;
+
+        Ok(Response::new(RotateKeyResponse {
+            key_id: rotation.key_id,
+            public_key: rotation.public_key,
+            expires_at: rotation.expires_at,
+            tolerance_seconds: self.tolerance_seconds,
+            previous_key_id: rotation.previous_key_id,
+            verify_until: rotation.verify_until,
+            algorithm: Some(KeyAlgorithm::from(rotation.algorithm) as i32),
+        }))
+    }
+
+    type WatchKeyRotationsStream = KeyRotationStream;
+
+    /// 订阅密钥轮替事件
+    async fn watch_key_rotations(
+        &self,
+        request: Request<WatchKeyRotationsRequest>,
+    ) -> Result<Response<Self::WatchKeyRotationsStream>, Status> {
+        let req = request.into_inner();
+        self.verify_credential(&req.credential, "watch_key_rotations")
+            .await
+            .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
+
+        info!("Key rotation watcher subscribed");
+
+        let mut events = crate::rotation::subscribe();
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            loop {
+                let rotation = match events.recv().await {
+                    Ok(rotation) => rotation,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Key rotation watcher lagged, {} events skipped", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let event = KeyRotationEvent {
+                    key_id: rotation.key_id,
+                    public_key: rotation.public_key,
+                    expires_at: rotation.expires_at,
+                    previous_key_id: rotation.previous_key_id,
+                    verify_until: rotation.verify_until,
+                    rotated_at: rotation.rotated_at,
+                    algorithm: Some(KeyAlgorithm::from(rotation.algorithm) as i32),
+                };
+                // 订阅方断开后退出
+                if tx.send(Ok(event)).await.is_err() {
+                    debug!("Key rotation watcher disconnected");
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    /// 吊销密钥
+    async fn revoke_key(
+        &self,
+        request: Request<RevokeKeyRequest>,
+    ) -> Result<Response<RevokeKeyResponse>, Status> {
+        let audit_context = AuditContext::from_grpc(&request);
+        let req = request.into_inner();
+        let key_id = req.key_id;
+        info!("Received gRPC RevokeKey request for key_id: {}", key_id);
+
+        let request_data = format!("revoke_key:{key_id}");
+        self.verify_credential(&req.credential, &request_data)
+            .await
+            .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
+
+        let revocation = crate::revocation::revoke_key(&self.storage, key_id)
+            .await
+            .map_err(|e| match e {
+                KsError::KeyNotFound(key_id) => {
+                    Status::not_found(format!("Key not found: {key_id}"))
+                }
+                KsError::InvalidRequest(message) => Status::failed_precondition(message),
+                e => Status::internal(format!("Failed to revoke key: {e}")),
+            })?;
+
+        self.record_audit(
+            &audit_context,
+            AuditAction::RevokeKey,
+            revocation.key_id,
+            None,
+            &req.credential.nonce,
+        )
+        .await?;
+
+        Ok(Response::new(RevokeKeyResponse {
+            key_id: revocation.key_id,
+            revoked_at: revocation.revoked_at,
+        }))
+    }
+
+    type WatchKeyRevocationsStream = KeyRevocationStream;
+
+    /// 订阅密钥吊销事件
+    async fn watch_key_revocations(
+        &self,
+        request: Request<WatchKeyRevocationsRequest>,
+    ) -> Result<Response<Self::WatchKeyRevocationsStream>, Status> {
+        let req = request.into_inner();
+        self.verify_credential(&req.credential, "watch_key_revocations")
+            .await
+            .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
+
+        // 先订阅再读取快照，避免两者之间发生的吊销被遗漏（重复推送由订阅方去重）
+        let mut events = crate::revocation::subscribe();
+        let revoked = crate::revocation::revoked_keys(&self.storage)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to list revoked keys: {e}")))?;
+
+        info!(
+            "Key revocation watcher subscribed, {} revoked keys in snapshot",
+            revoked.len()
+        );
+
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            for revocation in revoked {
+                let event = KeyRevocationEvent {
+                    key_id: revocation.key_id,
+                    revoked_at: revocation.revoked_at,
+                };
+                if tx.send(Ok(event)).await.is_err() {
+                    debug!("Key revocation watcher disconnected");
+                    return;
+                }
+            }
+            loop {
+                let revocation = match events.recv().await {
+                    Ok(revocation) => revocation,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Key revocation watcher lagged, {} events skipped", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let event = KeyRevocationEvent {
+                    key_id: revocation.key_id,
+                    revoked_at: revocation.revoked_at,
+                };
+                // 订阅方断开后退出
+                if tx.send(Ok(event)).await.is_err() {
+                    debug!("Key revocation watcher disconnected");
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    /// 批量获取公钥
+    async fn get_public_keys(
+        &self,
+        request: Request<GetPublicKeysRequest>,
+    ) -> Result<Response<GetPublicKeysResponse>, Status> {
+        let req = request.into_inner();
+        info!(
+            "Received gRPC GetPublicKeys request for {} key_ids",
+            req.key_ids.len()
+        );
+
+        let request_data = batch_request_payload("get_public_keys", &req.key_ids);
+        self.verify_credential(&req.credential, &request_data)
+            .await
+            .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
+
+        let mut response = GetPublicKeysResponse::default();
+        for key_id in Self::dedup_key_ids(&req.key_ids)? {
+            match self.lookup_key(key_id).await? {
+                KeyLookup::Available(record, expires_at, tolerance_seconds) => {
+                    response.keys.push(PublicKeyEntry {
+                        key_id,
+                        public_key: record.public_key,
+                        expires_at,
+                        tolerance_seconds,
+                        algorithm: Some(KeyAlgorithm::from(record.algorithm) as i32),
+                    });
+                }
+                KeyLookup::Missing => response.missing_key_ids.push(key_id),
+                KeyLookup::Revoked => response.revoked_key_ids.push(key_id),
+            }
+        }
+
+        debug!(
+            "Returning {} public keys ({} missing, {} revoked)",
+            response.keys.len(),
+            response.missing_key_ids.len(),
+            response.revoked_key_ids.len()
+        );
+        Ok(Response::new(response))
+    }
+
+    /// 批量获取私钥
+    async fn get_secret_keys(
+        &self,
+        request: Request<GetSecretKeysRequest>,
+    ) -> Result<Response<GetSecretKeysResponse>, Status> {
+        let audit_context = AuditContext::from_grpc(&request);
+        let req = request.into_inner();
+        info!(
+            "Received gRPC GetSecretKeys request for {} key_ids",
+            req.key_ids.len()
+        );
+
+        let request_data = batch_request_payload("get_secret_keys", &req.key_ids);
+        self.verify_credential(&req.credential, &request_data)
+            .await
+            .map_err(|e| Status::unauthenticated(format!("Authentication failed: {e}")))?;
+
+        let mut response = GetSecretKeysResponse::default();
+        for key_id in Self::dedup_key_ids(&req.key_ids)? {
+            match self.lookup_key(key_id).await? {
+                KeyLookup::Available(record, expires_at, tolerance_seconds) => {
+                    let secret_key =
+                        self.storage.get_secret_key(key_id).await.map_err(|e| {
+                            Status::internal(format!("Failed to get secret key: {e}"))
+                        })?;
+                    match secret_key {
+                        Some(secret_key) => {
+                            self.record_audit(
+                                &audit_context,
+                                AuditAction::GetSecretKey,
+                                key_id,
+                                None,
+                                &req.credential.nonce,
+                            )
+                            .await?;
+                            response.keys.push(SecretKeyEntry {
+                                key_id,
+                                secret_key,
+                                expires_at,
+                                tolerance_seconds,
+                                algorithm: Some(KeyAlgorithm::from(record.algorithm) as i32),
+                            });
+                        }
+                        None => response.missing_key_ids.push(key_id),
+                    }
+                }
+                KeyLookup::Missing => response.missing_key_ids.push(key_id),
+                KeyLookup::Revoked => {
+                    warn!("Skipped revoked key {} in GetSecretKeys", key_id);
+                    response.revoked_key_ids.push(key_id);
+                }
+            }
+        }
+
+        info!(
+            "Returning {} secret keys ({} missing, {} revoked)",
+            response.keys.len(),
+            response.missing_key_ids.len(),
+            response.revoked_key_ids.len()
+        );
+        Ok(Response::new(response))
+    }
+
     /// 健康检查
     async fn health_check(
         &self,
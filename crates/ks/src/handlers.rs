@@ -1,14 +1,21 @@
 //! KS HTTP 处理器
 
 use crate::{
+    audit::{
+        AUDIT_QUERY_PAYLOAD, AuditAction, AuditContext, AuditLog, AuditQuery, AuditQueryResponse,
+    },
     crypto::KeyEncryptor,
     error::KsError,
     storage::KeyStorage,
-    types::{GenerateKeyRequest, GenerateKeyResponse, GetSecretKeyRequest, GetSecretKeyResponse},
+    types::{
+        GenerateKeyRequest, GenerateKeyResponse, GetSecretKeyRequest, GetSecretKeyResponse,
+        KeyStatus, RevokeKeyRequest, RevokeKeyResponse, RotateKeyRequest, RotateKeyResponse,
+    },
 };
 use axum::{
     Router,
     extract::{Json, Path, Query, State},
+    http::Extensions,
     routing::{get, post},
 };
 use lazy_static::lazy_static;
@@ -56,6 +63,7 @@ pub fn register_ks_metrics(registry: &prometheus::Registry) -> Result<(), promet
     registry.register(Box::new(KS_REQUEST_DURATION.clone()))?;
     registry.register(Box::new(KS_REQUESTS_TOTAL.clone()))?;
     registry.register(Box::new(KS_AUTH_FAILURES.clone()))?;
+    crate::scheduler::register_metrics(registry)?;
     Ok(())
 }
 
@@ -71,6 +79,10 @@ pub struct KSState {
     pub psk: String,
     /// 容忍期（秒）
     pub tolerance_seconds: u64,
+    /// 轮替时旧密钥的默认宽限期（秒）
+    pub rotation_grace_seconds: u64,
+    /// 密钥访问审计日志
+    pub audit: AuditLog,
     /// 请求计数器（用于惰性清理触发）
     request_counter: Arc<AtomicU32>,
 }
@@ -87,10 +99,25 @@ impl KSState {
             nonce_storage: Arc::new(nonce_storage),
             psk,
             tolerance_seconds,
+            rotation_grace_seconds: crate::config::KsServiceConfig::default()
+                .rotation_grace_seconds,
+            audit: AuditLog::disabled(),
             request_counter: Arc::new(AtomicU32::new(0)),
         }
     }
 
+    /// 设置轮替时旧密钥的默认宽限期（秒）
+    pub fn with_rotation_grace_seconds(mut self, rotation_grace_seconds: u64) -> Self {
+        self.rotation_grace_seconds = rotation_grace_seconds;
+        self
+    }
+
+    /// 设置审计日志
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = audit;
+        self
+    }
+
     /// 惰性清理：在请求时检查是否需要清理过期密钥
     ///
     /// 触发条件：
@@ -194,19 +221,26 @@ pub async fn create_ks_state<N: NonceStorage + Send + Sync + 'static>(
     let key_storage =
         KeyStorage::from_config(&service_config.storage, encryptor, sqlite_path).await?;
 
+    let audit = AuditLog::from_config(&service_config.audit, sqlite_path).await?;
+
     Ok(KSState::new(
         key_storage,
         nonce_storage,
         actrix_shared_key.to_string(),
         service_config.tolerance_seconds,
-    ))
+    )
+    .with_rotation_grace_seconds(service_config.rotation_grace_seconds)
+    .with_audit(audit))
 }
 
 /// 创建 KS 服务的路由
 pub fn create_router(state: KSState) -> Router {
     Router::new()
         .route("/generate", post(generate_key_handler))
+        .route("/rotate", post(rotate_key_handler))
+        .route("/revoke", post(revoke_key_handler))
         .route("/secret/{key_id}", get(get_secret_key_handler))
+        .route("/audit", get(query_audit_handler))
         .route("/health", get(health_check_handler))
         .with_state(state)
 }
@@ -225,6 +259,7 @@ pub struct ServiceStats {
 
 async fn generate_key_handler(
     State(app_state): State<KSState>,
+    extensions: Extensions,
     Json(request): Json<GenerateKeyRequest>,
 ) -> Result<Json<GenerateKeyResponse>, KsError> {
     let start_time = Instant::now();
@@ -258,8 +293,11 @@ async fn generate_key_handler(
     }
     verify_result?;
 
-    // 生成并存储密钥
-    let key_pair = app_state.storage.generate_and_store_key().await?;
+    // 按请求的算法生成并存储密钥
+    let key_pair = app_state
+        .storage
+        .generate_and_store_key_with(request.algorithm.unwrap_or_default())
+        .await?;
 
     // 获取密钥记录以获取正确的过期时间
     let key_record = app_state
@@ -268,14 +306,26 @@ async fn generate_key_handler(
         .await?
         .ok_or_else(|| KsError::Internal("Failed to get key record after creation".into()))?;
 
+    app_state
+        .audit
+        .record(AuditContext::from_http(&extensions).entry(
+            AuditAction::GenerateKey,
+            key_pair.key_id,
+            &request.credential.nonce,
+        ))
+        .await?;
+
     // 惰性清理：在生成新密钥时检查是否需要清理过期密钥
     app_state.maybe_cleanup_expired_keys().await;
 
     // 记录密钥生成指标
-    KS_KEYS_GENERATED.with_label_values(&["ecies"]).inc();
+    KS_KEYS_GENERATED
+        .with_label_values(&[key_pair.algorithm.as_str()])
+        .inc();
 
     let response = GenerateKeyResponse {
         key_id: key_pair.key_id,
+        algorithm: key_pair.algorithm,
         public_key: key_pair.public_key,
         expires_at: key_record.expires_at,
         tolerance_seconds: app_state.tolerance_seconds,
@@ -294,9 +344,225 @@ async fn generate_key_handler(
     Ok(Json(response))
 }
 
+async fn rotate_key_handler(
+    State(app_state): State<KSState>,
+    extensions: Extensions,
+    Json(request): Json<RotateKeyRequest>,
+) -> Result<Json<RotateKeyResponse>, KsError> {
+    let start_time = Instant::now();
+    info!(
+        "Received key rotation request, previous_key_id: {:?}",
+        request.previous_key_id
+    );
+
+    // 验证凭据
+    let request_data = request.request_payload();
+    if let Err(e) = app_state
+        .verify_credential(&request.credential, &request_data)
+        .await
+    {
+        let reason = match e {
+            KsError::ReplayAttack(_) => "replay_attack",
+            KsError::Authentication(_) => "invalid_signature",
+            _ => "unknown",
+        };
+        KS_AUTH_FAILURES.with_label_values(&["ks", reason]).inc();
+
+        let duration = start_time.elapsed().as_secs_f64();
+        KS_REQUEST_DURATION
+            .with_label_values(&["ks", "POST", "/rotate", "401"])
+            .observe(duration);
+        KS_REQUESTS_TOTAL
+            .with_label_values(&["ks", "POST", "/rotate", "401"])
+            .inc();
+
+        return Err(e);
+    }
+
+    let grace_seconds = request
+        .grace_seconds
+        .unwrap_or(app_state.rotation_grace_seconds);
+    let rotation = match crate::rotation::rotate_key(
+        &app_state.storage,
+        request.previous_key_id,
+        grace_seconds,
+    )
+    .await
+    {
+        Ok(rotation) => rotation,
+        Err(e) => {
+            let status = match e {
+                KsError::KeyNotFound(_) => "404",
+                KsError::InvalidRequest(_) => "400",
+                _ => "500",
+            };
+            let duration = start_time.elapsed().as_secs_f64();
+            KS_REQUEST_DURATION
+                .with_label_values(&["ks", "POST", "/rotate", status])
+                .observe(duration);
+            KS_REQUESTS_TOTAL
+                .with_label_values(&["ks", "POST", "/rotate", status])
+                .inc();
+            return Err(e);
+        }
+    };
+
+    app_state
+        .audit
+        .record(
+            AuditContext::from_http(&extensions)
+                .entry(
+                    AuditAction::RotateKey,
+                    rotation.key_id,
+                    &request.credential.nonce,
+                )
+                .with_previous_key_id(rotation.previous_key_id),
+        )
+        .await?;
+
+    KS_KEYS_GENERATED.with_label_values(&["ecies"]).inc();
+
+    let duration = start_time.elapsed().as_secs_f64();
+    KS_REQUEST_DURATION
+        .with_label_values(&["ks", "POST", "/rotate", "200"])
+        .observe(duration);
+    KS_REQUESTS_TOTAL
+        .with_label_values(&["ks", "POST", "/rotate", "200"])
+        .inc();
+
+    Ok(Json(RotateKeyResponse {
+        key_id: rotation.key_id,
+        algorithm: rotation.algorithm,
+        public_key: rotation.public_key,
+        expires_at: rotation.expires_at,
+        tolerance_seconds: app_state.tolerance_seconds,
+        previous_key_id: rotation.previous_key_id,
+        verify_until: rotation.verify_until,
+    }))
+}
+
+async fn revoke_key_handler(
+    State(app_state): State<KSState>,
+    extensions: Extensions,
+    Json(request): Json<RevokeKeyRequest>,
+) -> Result<Json<RevokeKeyResponse>, KsError> {
+    let start_time = Instant::now();
+    info!(
+        "Received key revocation request for key_id: {}",
+        request.key_id
+    );
+
+    // 验证凭据
+    let request_data = request.request_payload();
+    if let Err(e) = app_state
+        .verify_credential(&request.credential, &request_data)
+        .await
+    {
+        let reason = match e {
+            KsError::ReplayAttack(_) => "replay_attack",
+            KsError::Authentication(_) => "invalid_signature",
+            _ => "unknown",
+        };
+        KS_AUTH_FAILURES.with_label_values(&["ks", reason]).inc();
+
+        let duration = start_time.elapsed().as_secs_f64();
+        KS_REQUEST_DURATION
+            .with_label_values(&["ks", "POST", "/revoke", "401"])
+            .observe(duration);
+        KS_REQUESTS_TOTAL
+            .with_label_values(&["ks", "POST", "/revoke", "401"])
+            .inc();
+
+        return Err(e);
+    }
+
+    let revocation = match crate::revocation::revoke_key(&app_state.storage, request.key_id).await {
+        Ok(revocation) => revocation,
+        Err(e) => {
+            let status = match e {
+                KsError::KeyNotFound(_) => "404",
+                KsError::InvalidRequest(_) => "400",
+                _ => "500",
+            };
+            let duration = start_time.elapsed().as_secs_f64();
+            KS_REQUEST_DURATION
+                .with_label_values(&["ks", "POST", "/revoke", status])
+                .observe(duration);
+            KS_REQUESTS_TOTAL
+                .with_label_values(&["ks", "POST", "/revoke", status])
+                .inc();
+            return Err(e);
+        }
+    };
+
+    app_state
+        .audit
+        .record(AuditContext::from_http(&extensions).entry(
+            AuditAction::RevokeKey,
+            revocation.key_id,
+            &request.credential.nonce,
+        ))
+        .await?;
+
+    let duration = start_time.elapsed().as_secs_f64();
+    KS_REQUEST_DURATION
+        .with_label_values(&["ks", "POST", "/revoke", "200"])
+        .observe(duration);
+    KS_REQUESTS_TOTAL
+        .with_label_values(&["ks", "POST", "/revoke", "200"])
+        .inc();
+
+    Ok(Json(RevokeKeyResponse {
+        key_id: revocation.key_id,
+        revoked_at: revocation.revoked_at,
+    }))
+}
+
+/// 从查询参数解析请求凭证
+///
+/// 兼容三种格式：
+/// 1) credential 为 JSON 字符串（ks::client 使用）
+/// 2) 展开字段：credential.timestamp/nonce/signature
+/// 3) 方括号字段：credential[timestamp]/[nonce]/[signature]
+fn credential_from_query(
+    params: &HashMap<String, String>,
+) -> Result<nonce_auth::NonceCredential, KsError> {
+    if let Some(credential_json) = params.get("credential") {
+        return serde_json::from_str(credential_json).map_err(|_| {
+            KsError::InvalidRequest("Invalid credential query parameter".to_string())
+        });
+    }
+
+    let timestamp = params
+        .get("credential.timestamp")
+        .or_else(|| params.get("credential[timestamp]"))
+        .ok_or_else(|| KsError::InvalidRequest("Missing credential timestamp".to_string()))
+        .and_then(|v| {
+            u64::from_str(v)
+                .map_err(|_| KsError::InvalidRequest("Invalid credential timestamp".to_string()))
+        })?;
+    let nonce = params
+        .get("credential.nonce")
+        .or_else(|| params.get("credential[nonce]"))
+        .cloned()
+        .ok_or_else(|| KsError::InvalidRequest("Missing credential nonce".to_string()))?;
+    let signature = params
+        .get("credential.signature")
+        .or_else(|| params.get("credential[signature]"))
+        .cloned()
+        .ok_or_else(|| KsError::InvalidRequest("Missing credential signature".to_string()))?;
+
+    Ok(nonce_auth::NonceCredential {
+        timestamp,
+        nonce,
+        signature,
+    })
+}
+
 async fn get_secret_key_handler(
     State(app_state): State<KSState>,
     Path(key_id): Path<u32>,
+    extensions: Extensions,
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<Json<GetSecretKeyResponse>, KsError> {
     let start_time = Instant::now();
@@ -324,43 +590,7 @@ async fn get_secret_key_handler(
         ));
     }
 
-    // Query compatibility:
-    // 1) credential as JSON string (used by ks::client)
-    // 2) flattened fields: credential.timestamp/nonce/signature
-    // 3) bracket fields: credential[timestamp]/[nonce]/[signature]
-    let credential: nonce_auth::NonceCredential = if let Some(credential_json) =
-        params.get("credential")
-    {
-        serde_json::from_str(credential_json).map_err(|_| {
-            KsError::InvalidRequest("Invalid credential query parameter".to_string())
-        })?
-    } else {
-        let timestamp = params
-            .get("credential.timestamp")
-            .or_else(|| params.get("credential[timestamp]"))
-            .ok_or_else(|| KsError::InvalidRequest("Missing credential timestamp".to_string()))
-            .and_then(|v| {
-                u64::from_str(v).map_err(|_| {
-                    KsError::InvalidRequest("Invalid credential timestamp".to_string())
-                })
-            })?;
-        let nonce = params
-            .get("credential.nonce")
-            .or_else(|| params.get("credential[nonce]"))
-            .cloned()
-            .ok_or_else(|| KsError::InvalidRequest("Missing credential nonce".to_string()))?;
-        let signature = params
-            .get("credential.signature")
-            .or_else(|| params.get("credential[signature]"))
-            .cloned()
-            .ok_or_else(|| KsError::InvalidRequest("Missing credential signature".to_string()))?;
-
-        nonce_auth::NonceCredential {
-            timestamp,
-            nonce,
-            signature,
-        }
-    };
+    let credential = credential_from_query(&params)?;
 
     let request = GetSecretKeyRequest {
         key_id: request_key_id,
@@ -398,18 +628,33 @@ async fn get_secret_key_handler(
     // 获取完整的密钥记录
     match app_state.storage.get_key_record(key_id).await? {
         Some(key_record) => {
-            // 检查密钥是否超过容忍期
-            if key_record.expires_at > 0 {
+            // 已吊销的密钥不再提供私钥
+            if key_record.status == KeyStatus::Revoked {
+                warn!("Rejected secret key request for revoked key {}", key_id);
+                let duration = start_time.elapsed().as_secs_f64();
+                KS_REQUEST_DURATION
+                    .with_label_values(&["ks", "GET", "/secret", "410"])
+                    .observe(duration);
+                KS_REQUESTS_TOTAL
+                    .with_label_values(&["ks", "GET", "/secret", "410"])
+                    .inc();
+                return Err(KsError::KeyRevoked(key_id));
+            }
+
+            // 检查密钥是否超过容忍期（仅验证的密钥以宽限期为准）
+            let (expires_at, tolerance_seconds) =
+                key_record.effective_expiry(app_state.tolerance_seconds);
+            if expires_at > 0 {
                 let now = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_secs();
 
                 // 检查是否超过了过期时间 + 容忍期
-                if key_record.expires_at + app_state.tolerance_seconds < now {
+                if expires_at + tolerance_seconds < now {
                     warn!(
                         "Key {} has expired beyond tolerance period. Expires at: {}, Tolerance: {}s, Now: {}",
-                        key_id, key_record.expires_at, app_state.tolerance_seconds, now
+                        key_id, expires_at, tolerance_seconds, now
                     );
                     let duration = start_time.elapsed().as_secs_f64();
                     KS_REQUEST_DURATION
@@ -422,10 +667,10 @@ async fn get_secret_key_handler(
                 }
 
                 // 记录是否在容忍期内（用于日志）
-                if key_record.expires_at < now {
+                if expires_at < now {
                     warn!(
                         "Key {} is in tolerance period (expired at: {}, now: {})",
-                        key_id, key_record.expires_at, now
+                        key_id, expires_at, now
                     );
                 }
             }
@@ -437,11 +682,21 @@ async fn get_secret_key_handler(
                 .await?
                 .ok_or_else(|| KsError::KeyNotFound(key_id))?;
 
+            app_state
+                .audit
+                .record(AuditContext::from_http(&extensions).entry(
+                    AuditAction::GetSecretKey,
+                    key_id,
+                    &request.credential.nonce,
+                ))
+                .await?;
+
             let response = GetSecretKeyResponse {
                 key_id,
+                algorithm: key_record.algorithm,
                 secret_key,
-                expires_at: key_record.expires_at,
-                tolerance_seconds: app_state.tolerance_seconds,
+                expires_at,
+                tolerance_seconds,
             };
 
             // 记录成功的请求指标
@@ -455,7 +710,7 @@ async fn get_secret_key_handler(
 
             info!(
                 "Returned secret key for key_id: {}, expires_at: {}",
-                key_id, key_record.expires_at
+                key_id, expires_at
             );
             Ok(Json(response))
         }
@@ -473,6 +728,41 @@ async fn get_secret_key_handler(
     }
 }
 
+/// 查询审计日志
+///
+/// 过滤参数：action、key_id、caller、since、until、limit；
+/// 凭证格式与 `/secret` 相同，签名 payload 为 `query_audit`
+async fn query_audit_handler(
+    State(app_state): State<KSState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<AuditQueryResponse>, KsError> {
+    let credential = credential_from_query(&params)?;
+    if let Err(e) = app_state
+        .verify_credential(&credential, AUDIT_QUERY_PAYLOAD)
+        .await
+    {
+        let reason = match e {
+            KsError::ReplayAttack(_) => "replay_attack",
+            KsError::Authentication(_) => "invalid_signature",
+            _ => "unknown",
+        };
+        KS_AUTH_FAILURES.with_label_values(&["ks", reason]).inc();
+        return Err(e);
+    }
+
+    if !app_state.audit.is_enabled() {
+        return Err(KsError::InvalidRequest(
+            "KS audit log is not enabled".to_string(),
+        ));
+    }
+
+    let query = AuditQuery::from_params(&params)?;
+    let entries = app_state.audit.query(&query).await?;
+    debug!("Returning {} audit entries", entries.len());
+
+    Ok(Json(AuditQueryResponse { entries }))
+}
+
 async fn health_check_handler(
     State(app_state): State<KSState>,
 ) -> Result<Json<serde_json::Value>, KsError> {
@@ -497,10 +787,12 @@ async fn health_check_handler(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::KeyAlgorithm;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
     };
+    use base64::prelude::*;
     use nonce_auth::{CredentialBuilder, NonceCredential, storage::MemoryStorage};
     use serde_json;
     use tempfile::tempdir;
@@ -512,13 +804,21 @@ mod tests {
             storage: crate::storage::StorageConfig {
                 backend: crate::storage::StorageBackend::Sqlite,
                 key_ttl_seconds: 3600,
-                sqlite: Some(crate::storage::SqliteConfig {}),
+                sqlite: Some(crate::storage::SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         };
 
         let psk = "test-psk".to_string();
@@ -529,6 +829,8 @@ mod tests {
 
         let router = Router::new()
             .route("/generate", post(generate_key_handler))
+            .route("/rotate", post(rotate_key_handler))
+            .route("/revoke", post(revoke_key_handler))
             .route("/secret/{key_id}", get(get_secret_key_handler))
             .route("/health", get(health_check_handler))
             .with_state(app_state);
@@ -549,13 +851,21 @@ mod tests {
             storage: crate::storage::StorageConfig {
                 backend: crate::storage::StorageBackend::Sqlite,
                 key_ttl_seconds: 3600,
-                sqlite: Some(crate::storage::SqliteConfig {}),
+                sqlite: Some(crate::storage::SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         };
 
         let nonce_storage = MemoryStorage::new();
@@ -580,13 +890,21 @@ mod tests {
             storage: crate::storage::StorageConfig {
                 backend: crate::storage::StorageBackend::Sqlite,
                 key_ttl_seconds: 3600,
-                sqlite: Some(crate::storage::SqliteConfig {}),
+                sqlite: Some(crate::storage::SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         };
 
         let nonce_storage = MemoryStorage::new();
@@ -606,13 +924,21 @@ mod tests {
             storage: crate::storage::StorageConfig {
                 backend: crate::storage::StorageBackend::Sqlite,
                 key_ttl_seconds: 3600,
-                sqlite: Some(crate::storage::SqliteConfig {}),
+                sqlite: Some(crate::storage::SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         };
 
         let nonce_storage = MemoryStorage::new();
@@ -648,7 +974,10 @@ mod tests {
         let request_data = "generate_key";
         let credential = create_credential_for_request(&psk, request_data);
 
-        let request = GenerateKeyRequest { credential };
+        let request = GenerateKeyRequest {
+            credential,
+            algorithm: None,
+        };
         let request_body = serde_json::to_value(request).unwrap();
 
         let response = app
@@ -679,6 +1008,54 @@ mod tests {
         assert_eq!(response_json.key_id, 1);
         assert!(!response_json.public_key.is_empty());
         assert_eq!(response_json.tolerance_seconds, 3600);
+        assert_eq!(response_json.algorithm, KeyAlgorithm::Secp256k1);
+    }
+
+    #[tokio::test]
+    async fn test_generate_key_with_algorithm() {
+        let (app, psk, _temp_dir) = create_test_app().await;
+
+        let generate = |credential, algorithm| {
+            let request = GenerateKeyRequest {
+                credential,
+                algorithm: Some(algorithm),
+            };
+            Request::builder()
+                .method("POST")
+                .uri("/generate")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&request).unwrap()))
+                .unwrap()
+        };
+
+        // 算法纳入签名，使用默认签名数据的请求被拒绝
+        let credential = create_credential_for_request(&psk, "generate_key");
+        let response = app
+            .clone()
+            .oneshot(generate(credential, KeyAlgorithm::Ed25519))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let credential = create_credential_for_request(&psk, "generate_key:ed25519");
+        let response = app
+            .oneshot(generate(credential, KeyAlgorithm::Ed25519))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let response_json: GenerateKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response_json.algorithm, KeyAlgorithm::Ed25519);
+        assert_eq!(
+            BASE64_STANDARD
+                .decode(&response_json.public_key)
+                .unwrap()
+                .len(),
+            32
+        );
     }
 
     #[tokio::test]
@@ -693,6 +1070,7 @@ mod tests {
 
         let invalid_request = GenerateKeyRequest {
             credential: invalid_credential,
+            algorithm: None,
         };
         let request_body = serde_json::to_value(invalid_request).unwrap();
 
@@ -710,4 +1088,227 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
+
+    #[tokio::test]
+    async fn test_rotate_key() {
+        let (app, psk, _temp_dir) = create_test_app().await;
+
+        let rotate = |previous_key_id: Option<u32>| {
+            let credential = create_credential_for_request(
+                &psk,
+                &match previous_key_id {
+                    Some(key_id) => format!("rotate_key:{key_id}"),
+                    None => "rotate_key".to_string(),
+                },
+            );
+            let request = RotateKeyRequest {
+                credential,
+                previous_key_id,
+                grace_seconds: Some(600),
+            };
+            Request::builder()
+                .method("POST")
+                .uri("/rotate")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&request).unwrap()))
+                .unwrap()
+        };
+
+        // 首次轮替没有旧密钥
+        let response = app.clone().oneshot(rotate(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let first: RotateKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(first.previous_key_id, None);
+
+        let response = app
+            .clone()
+            .oneshot(rotate(Some(first.key_id)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let second: RotateKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(second.previous_key_id, Some(first.key_id));
+        assert!(second.verify_until > 0);
+
+        // 已轮替的密钥不能再次轮替
+        let response = app.oneshot(rotate(Some(first.key_id))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_key() {
+        let (app, psk, _temp_dir) = create_test_app().await;
+
+        let credential = create_credential_for_request(&psk, "generate_key");
+        let request = Request::builder()
+            .method("POST")
+            .uri("/generate")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_string(&GenerateKeyRequest {
+                    credential,
+                    algorithm: None,
+                })
+                .unwrap(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let key: GenerateKeyResponse = serde_json::from_slice(&body).unwrap();
+
+        let revoke = |key_id: u32| {
+            let credential = create_credential_for_request(&psk, &format!("revoke_key:{key_id}"));
+            let request = RevokeKeyRequest { credential, key_id };
+            Request::builder()
+                .method("POST")
+                .uri("/revoke")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_string(&request).unwrap()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(revoke(key.key_id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let revoked: RevokeKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(revoked.key_id, key.key_id);
+
+        // 重复吊销与不存在的密钥
+        let response = app.clone().oneshot(revoke(key.key_id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = app.clone().oneshot(revoke(999)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // 吊销后不再返回私钥
+        let credential =
+            create_credential_for_request(&psk, &format!("get_secret_key:{}", key.key_id));
+        let url = reqwest::Url::parse_with_params(
+            &format!("http://localhost/secret/{}", key.key_id),
+            &[
+                ("key_id", key.key_id.to_string()),
+                ("credential", serde_json::to_string(&credential).unwrap()),
+            ],
+        )
+        .unwrap();
+        let request = Request::builder()
+            .method("GET")
+            .uri(format!("{}?{}", url.path(), url.query().unwrap()))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::GONE);
+    }
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let temp_dir = tempdir().unwrap();
+        let config = crate::config::KsServiceConfig {
+            audit: crate::config::KsAuditConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let psk = "test-psk";
+        let state = create_ks_state(&config, MemoryStorage::new(), psk, temp_dir.path())
+            .await
+            .unwrap();
+        let app = create_router(state);
+
+        let credential = create_credential_for_request(psk, "generate_key");
+        let generate_nonce = credential.nonce.clone();
+        let request = Request::builder()
+            .method("POST")
+            .uri("/generate")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_string(&GenerateKeyRequest {
+                    credential,
+                    algorithm: None,
+                })
+                .unwrap(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let key: GenerateKeyResponse = serde_json::from_slice(&body).unwrap();
+
+        let credential =
+            create_credential_for_request(psk, &format!("get_secret_key:{}", key.key_id));
+        let read_nonce = credential.nonce.clone();
+        let url = reqwest::Url::parse_with_params(
+            &format!("http://localhost/secret/{}", key.key_id),
+            &[
+                ("key_id", key.key_id.to_string()),
+                ("credential", serde_json::to_string(&credential).unwrap()),
+            ],
+        )
+        .unwrap();
+        let request = Request::builder()
+            .uri(format!("{}?{}", url.path(), url.query().unwrap()))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let query_audit = |credential: NonceCredential| {
+            let url = reqwest::Url::parse_with_params(
+                "http://localhost/audit",
+                &[
+                    ("key_id", key.key_id.to_string()),
+                    ("credential", serde_json::to_string(&credential).unwrap()),
+                ],
+            )
+            .unwrap();
+            Request::builder()
+                .uri(format!("{}?{}", url.path(), url.query().unwrap()))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(query_audit(create_credential_for_request(
+                psk,
+                AUDIT_QUERY_PAYLOAD,
+            )))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let audit: AuditQueryResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(audit.entries.len(), 2);
+        assert_eq!(audit.entries[0].action, AuditAction::GetSecretKey);
+        assert_eq!(audit.entries[0].nonce, read_nonce);
+        assert_eq!(audit.entries[1].action, AuditAction::GenerateKey);
+        assert_eq!(audit.entries[1].nonce, generate_nonce);
+        assert_eq!(audit.entries[1].caller, crate::audit::PSK_CALLER);
+        assert_eq!(audit.entries[1].transport, "http");
+
+        // 查询同样需要有效的签名
+        let response = app
+            .oneshot(query_audit(create_credential_for_request(
+                psk,
+                "generate_key",
+            )))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
 }
@@ -0,0 +1,97 @@
+//! 按算法生成密钥对
+//!
+//! 各算法的编码格式见 [`KeyAlgorithm`]，私钥与公钥均以 Base64 返回，
+//! 私钥由存储后端再经 KEK 加密后写入。
+
+use crate::types::KeyAlgorithm;
+use base64::prelude::*;
+use rand::rngs::OsRng;
+
+/// 生成密钥对，返回 (私钥 Base64, 公钥 Base64)
+pub fn generate_keypair(algorithm: KeyAlgorithm) -> (String, String) {
+    let (secret_key, public_key) = match algorithm {
+        KeyAlgorithm::Secp256k1 => {
+            let (secret_key, public_key) = ecies::utils::generate_keypair();
+            (
+                secret_key.serialize().to_vec(),
+                public_key.serialize_compressed().to_vec(),
+            )
+        }
+        KeyAlgorithm::Ed25519 => {
+            let signing_key = ed25519_dalek::SigningKey::generate(&mut OsRng);
+            (
+                signing_key.to_bytes().to_vec(),
+                signing_key.verifying_key().to_bytes().to_vec(),
+            )
+        }
+        KeyAlgorithm::P256 => {
+            let secret_key = p256::SecretKey::random(&mut OsRng);
+            let public_key = p256::elliptic_curve::sec1::ToEncodedPoint::to_encoded_point(
+                &secret_key.public_key(),
+                true,
+            );
+            (
+                secret_key.to_bytes().to_vec(),
+                public_key.as_bytes().to_vec(),
+            )
+        }
+    };
+
+    (
+        BASE64_STANDARD.encode(secret_key),
+        BASE64_STANDARD.encode(public_key),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(value: &str) -> Vec<u8> {
+        BASE64_STANDARD.decode(value).unwrap()
+    }
+
+    #[test]
+    fn test_secp256k1_keypair() {
+        let (secret_key, public_key) = generate_keypair(KeyAlgorithm::Secp256k1);
+        let secret_key = ecies::SecretKey::parse_slice(&decode(&secret_key)).unwrap();
+        let public_key: [u8; 33] = decode(&public_key).try_into().unwrap();
+        assert_eq!(
+            ecies::PublicKey::from_secret_key(&secret_key).serialize_compressed(),
+            public_key
+        );
+    }
+
+    #[test]
+    fn test_ed25519_keypair() {
+        use ed25519_dalek::{Signer, Verifier};
+
+        let (secret_key, public_key) = generate_keypair(KeyAlgorithm::Ed25519);
+        let seed: [u8; 32] = decode(&secret_key).try_into().unwrap();
+        let public_key: [u8; 32] = decode(&public_key).try_into().unwrap();
+
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&seed);
+        let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&public_key).unwrap();
+        let signature = signing_key.sign(b"actrix");
+        assert!(verifying_key.verify(b"actrix", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_p256_keypair() {
+        let (secret_key, public_key) = generate_keypair(KeyAlgorithm::P256);
+        let secret_key = p256::SecretKey::from_slice(&decode(&secret_key)).unwrap();
+        let public_key = p256::PublicKey::from_sec1_bytes(&decode(&public_key)).unwrap();
+        assert_eq!(secret_key.public_key(), public_key);
+    }
+
+    #[test]
+    fn test_keypairs_are_unique() {
+        for algorithm in [
+            KeyAlgorithm::Secp256k1,
+            KeyAlgorithm::Ed25519,
+            KeyAlgorithm::P256,
+        ] {
+            assert_ne!(generate_keypair(algorithm), generate_keypair(algorithm));
+        }
+    }
+}
@@ -1,37 +1,65 @@
 //! Key Server (KS) - 椭圆曲线密钥生成和管理服务
 //!
 //! KS 服务提供以下功能：
-//! 1. 生成椭圆曲线密钥对（使用 ECIES），返回公钥给 Issue 服务
+//! 1. 生成密钥对（secp256k1 / Ed25519 / P-256，见 [`KeyAlgorithm`]），返回公钥给 Issue 服务
 //! 2. 基于 key_id 查询私钥给验证服务
 //! 3. PSK 签名验证和防重放攻击保护
 //! 4. 多存储后端支持：SQLite, PostgreSQL
+//! 5. 密钥轮替：旧密钥在宽限期内仅供验证，并向订阅方推送轮替事件（见 [`rotation`]）
+//! 6. 密钥吊销：泄露的密钥立即失效，并向验证方推送吊销事件（见 [`revocation`]）
+//! 7. 私钥加密存储：KEK 可来自配置/环境变量/文件，或 PKCS#11 HSM（见 [`pkcs11`]）
+//! 8. gRPC 双向 TLS：按客户端证书 SAN 白名单限制访问方（见 [`mtls`]）
+//! 9. 审计日志：记录密钥生成、私钥读取、轮替与吊销的调用方与 nonce（见 [`audit`]）
+//! 10. 后台调度：预生成密钥、按 cron 表达式定时轮替、定期清理过期密钥（见 [`scheduler`]）
+//! 11. 开发用模拟实现：确定性密钥、不校验凭证（`mock` feature，见 `mock` 模块）
 
+pub mod audit;
 #[cfg(test)]
 pub mod client;
 pub mod config;
+pub mod cron;
 pub mod crypto;
 pub mod error;
 pub mod grpc_client;
 pub mod grpc_handlers;
 pub mod handlers;
+pub mod keygen;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod mtls;
+pub mod pkcs11;
+pub mod revocation;
+pub mod rotation;
+pub mod scheduler;
 pub mod storage;
 pub mod types;
 
 // Re-export commonly used items
+pub use audit::{AuditAction, AuditContext, AuditEntry, AuditLog, AuditQuery};
 #[cfg(test)]
 pub use client::{Client, ClientConfig};
-pub use config::KsServiceConfig;
+pub use config::{
+    AuditSinkType, KekProviderConfig, KsAuditConfig, KsGrpcBindConfig, KsGrpcConfig,
+    KsGrpcTlsConfig, KsRotationConfig, KsServiceConfig,
+};
 pub use crypto::{KekSource, KeyEncryptor};
 pub use error::KsError;
-pub use grpc_client::{GrpcClient, GrpcClientConfig};
+pub use grpc_client::{
+    GrpcClient, GrpcClientConfig, KeyBatch, KeyRevocationWatch, KeyRotationWatch,
+};
 pub use grpc_handlers::{KsGrpcService, create_grpc_service};
 // Re-export proto types from actrix-proto
 pub use actrix_proto::ks::v1::key_server_server::{KeyServer, KeyServerServer};
 pub use handlers::{KSState, create_ks_state, create_router, get_stats, register_ks_metrics};
+pub use mtls::{ClientIdentity, ClientIdentityInterceptor};
+pub use revocation::KeyRevocation;
+pub use rotation::KeyRotation;
+pub use scheduler::{KeyPool, KeyScheduler};
 pub use storage::{KeyStorage, StorageConfig};
 pub use types::{
-    GenerateKeyRequest, GenerateKeyResponse, GetSecretKeyRequest, GetSecretKeyResponse, KeyPair,
-    KeyRecord,
+    GenerateKeyRequest, GenerateKeyResponse, GetSecretKeyRequest, GetSecretKeyResponse,
+    KeyAlgorithm, KeyPair, KeyRecord, KeyStatus, MAX_BATCH_KEY_IDS, RevokeKeyRequest,
+    RevokeKeyResponse, RotateKeyRequest, RotateKeyResponse,
 };
 
 #[cfg(test)]
@@ -49,13 +77,21 @@ mod tests {
             storage: StorageConfig {
                 backend: StorageBackend::Sqlite,
                 key_ttl_seconds: 3600,
-                sqlite: Some(SqliteConfig {}),
+                sqlite: Some(SqliteConfig::default()),
                 postgres: None,
+                etcd: None,
+                cache: Default::default(),
             },
             kek: None,
             kek_env: None,
             kek_file: None,
+            kek_provider: None,
+            grpc: Default::default(),
+            grpc_tls: None,
+            audit: Default::default(),
+            rotation: Default::default(),
             tolerance_seconds: 3600,
+            rotation_grace_seconds: 86400,
         };
 
         // 使用内存存储进行测试（避免文件系统依赖）
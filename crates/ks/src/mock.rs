@@ -0,0 +1,400 @@
+//! 开发用 KS 模拟实现（`mock` feature）
+//!
+//! 供 `env = "dev"` 且启用 `dev.mock_dependencies` 的单节点开发环境使用：在进程内提供
+//! KeyServer gRPC 接口，不校验请求凭证、不持久化密钥。私钥由 key_id 确定性派生
+//! （[`mock_secret_key`]），重启后同一 key_id 仍对应同一密钥对，已签发的凭证继续有效。
+//!
+//! 派生规则是公开的，模拟密钥不具备任何保密性，只能用于本地开发。
+//! 模拟密钥均为 secp256k1，请求其他算法时返回 `UNIMPLEMENTED`。
+
+use actrix_proto::ks::v1::key_server_server::KeyServer;
+use actrix_proto::ks::v1::*;
+use base64::prelude::*;
+use ecies::{PublicKey, SecretKey};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+use tracing::{debug, info, warn};
+
+/// 模拟密钥的有效期（秒），每次查询都从当前时间起算
+pub const MOCK_KEY_TTL_SECS: u64 = 365 * 24 * 3600;
+
+/// GenerateKey / RotateKey 分配的第一个 key_id
+///
+/// 更小的 key_id 同样可以查询，留给直接使用派生密钥的模拟方（如模拟 AIS）
+pub const MOCK_FIRST_GENERATED_KEY_ID: u32 = 100;
+
+/// 密钥派生的域分隔前缀
+const MOCK_KEY_DOMAIN: &[u8] = b"actrix-dev-mock-ks";
+
+/// 按 key_id 确定性派生模拟私钥
+pub fn mock_secret_key(key_id: u32) -> SecretKey {
+    // 摘要超出曲线阶时（概率可忽略）递增计数器重新派生
+    (0u32..)
+        .find_map(|counter| {
+            let digest: [u8; 32] = Sha256::new()
+                .chain_update(MOCK_KEY_DOMAIN)
+                .chain_update(key_id.to_be_bytes())
+                .chain_update(counter.to_be_bytes())
+                .finalize()
+                .into();
+            SecretKey::parse(&digest).ok()
+        })
+        .expect("SHA-256 digest eventually yields a valid secret key")
+}
+
+/// 模拟私钥对应的公钥
+pub fn mock_public_key(key_id: u32) -> PublicKey {
+    PublicKey::from_secret_key(&mock_secret_key(key_id))
+}
+
+/// 轮替事件推送流（模拟实现不产生事件）
+type MockRotationStream = Pin<Box<dyn Stream<Item = Result<KeyRotationEvent, Status>> + Send>>;
+
+/// 吊销事件推送流
+type MockRevocationStream = Pin<Box<dyn Stream<Item = Result<KeyRevocationEvent, Status>> + Send>>;
+
+/// 模拟 KS gRPC 服务
+#[derive(Debug)]
+pub struct MockKeyServer {
+    /// 下一个 GenerateKey / RotateKey 分配的 key_id
+    next_key_id: AtomicU32,
+    tolerance_seconds: u64,
+    /// 已吊销的密钥：key_id -> 吊销时间
+    revoked: Mutex<BTreeMap<u32, u64>>,
+}
+
+impl Default for MockKeyServer {
+    fn default() -> Self {
+        Self::new(crate::config::KsServiceConfig::default().tolerance_seconds)
+    }
+}
+
+impl MockKeyServer {
+    pub fn new(tolerance_seconds: u64) -> Self {
+        warn!("⚠️  Using mock KS: credentials are not verified and keys are NOT secret");
+        Self {
+            next_key_id: AtomicU32::new(MOCK_FIRST_GENERATED_KEY_ID),
+            tolerance_seconds,
+            revoked: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn allocate_key_id(&self) -> u32 {
+        self.next_key_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn is_revoked(&self, key_id: u32) -> bool {
+        self.revoked.lock().unwrap().contains_key(&key_id)
+    }
+
+    fn public_key_b64(key_id: u32) -> String {
+        BASE64_STANDARD.encode(mock_public_key(key_id).serialize_compressed())
+    }
+
+    fn secret_key_b64(key_id: u32) -> String {
+        BASE64_STANDARD.encode(mock_secret_key(key_id).serialize())
+    }
+}
+
+#[tonic::async_trait]
+impl KeyServer for MockKeyServer {
+    async fn generate_key(
+        &self,
+        request: Request<GenerateKeyRequest>,
+    ) -> Result<Response<GenerateKeyResponse>, Status> {
+        let algorithm = request.into_inner().algorithm();
+        if algorithm != KeyAlgorithm::Secp256k1 {
+            return Err(Status::unimplemented(format!(
+                "Mock KS only issues secp256k1 keys, requested {}",
+                algorithm.as_str_name()
+            )));
+        }
+
+        let key_id = self.allocate_key_id();
+        info!("Mock KS generated key_id: {}", key_id);
+
+        Ok(Response::new(GenerateKeyResponse {
+            key_id,
+            public_key: Self::public_key_b64(key_id),
+            expires_at: Self::now() + MOCK_KEY_TTL_SECS,
+            tolerance_seconds: self.tolerance_seconds,
+            algorithm: Some(KeyAlgorithm::Secp256k1 as i32),
+        }))
+    }
+
+    async fn get_secret_key(
+        &self,
+        request: Request<GetSecretKeyRequest>,
+    ) -> Result<Response<GetSecretKeyResponse>, Status> {
+        let key_id = request.into_inner().key_id;
+        debug!("Mock KS GetSecretKey for key_id: {}", key_id);
+
+        if self.is_revoked(key_id) {
+            return Err(Status::permission_denied(format!(
+                "Key {key_id} has been revoked"
+            )));
+        }
+
+        Ok(Response::new(GetSecretKeyResponse {
+            key_id,
+            secret_key: Self::secret_key_b64(key_id),
+            expires_at: Self::now() + MOCK_KEY_TTL_SECS,
+            tolerance_seconds: self.tolerance_seconds,
+            algorithm: Some(KeyAlgorithm::Secp256k1 as i32),
+        }))
+    }
+
+    async fn rotate_key(
+        &self,
+        request: Request<RotateKeyRequest>,
+    ) -> Result<Response<RotateKeyResponse>, Status> {
+        let req = request.into_inner();
+        let key_id = self.allocate_key_id();
+        let now = Self::now();
+        info!(
+            "Mock KS rotated to key_id: {}, previous key_id: {:?}",
+            key_id, req.previous_key_id
+        );
+
+        Ok(Response::new(RotateKeyResponse {
+            key_id,
+            public_key: Self::public_key_b64(key_id),
+            expires_at: now + MOCK_KEY_TTL_SECS,
+            tolerance_seconds: self.tolerance_seconds,
+            previous_key_id: req.previous_key_id,
+            verify_until: req
+                .previous_key_id
+                .map(|_| now + req.grace_seconds.unwrap_or(0))
+                .unwrap_or(0),
+            algorithm: Some(KeyAlgorithm::Secp256k1 as i32),
+        }))
+    }
+
+    type WatchKeyRotationsStream = MockRotationStream;
+
+    async fn watch_key_rotations(
+        &self,
+        _request: Request<WatchKeyRotationsRequest>,
+    ) -> Result<Response<Self::WatchKeyRotationsStream>, Status> {
+        // 模拟密钥不会被转为仅验证，保持订阅但不推送事件
+        Ok(Response::new(Box::pin(tokio_stream::pending())))
+    }
+
+    async fn revoke_key(
+        &self,
+        request: Request<RevokeKeyRequest>,
+    ) -> Result<Response<RevokeKeyResponse>, Status> {
+        let key_id = request.into_inner().key_id;
+        let revoked_at = Self::now();
+
+        if self
+            .revoked
+            .lock()
+            .unwrap()
+            .insert(key_id, revoked_at)
+            .is_some()
+        {
+            return Err(Status::failed_precondition(format!(
+                "Key {key_id} has already been revoked"
+            )));
+        }
+        warn!("Mock KS revoked key_id: {}", key_id);
+
+        Ok(Response::new(RevokeKeyResponse { key_id, revoked_at }))
+    }
+
+    type WatchKeyRevocationsStream = MockRevocationStream;
+
+    async fn watch_key_revocations(
+        &self,
+        _request: Request<WatchKeyRevocationsRequest>,
+    ) -> Result<Response<Self::WatchKeyRevocationsStream>, Status> {
+        // 推送当前已吊销的密钥快照后保持订阅
+        let snapshot: Vec<_> = self
+            .revoked
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&key_id, &revoked_at)| Ok(KeyRevocationEvent { key_id, revoked_at }))
+            .collect();
+
+        Ok(Response::new(Box::pin(
+            tokio_stream::iter(snapshot).chain(tokio_stream::pending()),
+        )))
+    }
+
+    async fn get_public_keys(
+        &self,
+        request: Request<GetPublicKeysRequest>,
+    ) -> Result<Response<GetPublicKeysResponse>, Status> {
+        let expires_at = Self::now() + MOCK_KEY_TTL_SECS;
+        let mut response = GetPublicKeysResponse::default();
+        for key_id in request.into_inner().key_ids {
+            if self.is_revoked(key_id) {
+                response.revoked_key_ids.push(key_id);
+            } else {
+                response.keys.push(PublicKeyEntry {
+                    key_id,
+                    public_key: Self::public_key_b64(key_id),
+                    expires_at,
+                    tolerance_seconds: self.tolerance_seconds,
+                    algorithm: Some(KeyAlgorithm::Secp256k1 as i32),
+                });
+            }
+        }
+        Ok(Response::new(response))
+    }
+
+    async fn get_secret_keys(
+        &self,
+        request: Request<GetSecretKeysRequest>,
+    ) -> Result<Response<GetSecretKeysResponse>, Status> {
+        let expires_at = Self::now() + MOCK_KEY_TTL_SECS;
+        let mut response = GetSecretKeysResponse::default();
+        for key_id in request.into_inner().key_ids {
+            if self.is_revoked(key_id) {
+                response.revoked_key_ids.push(key_id);
+            } else {
+                response.keys.push(SecretKeyEntry {
+                    key_id,
+                    secret_key: Self::secret_key_b64(key_id),
+                    expires_at,
+                    tolerance_seconds: self.tolerance_seconds,
+                    algorithm: Some(KeyAlgorithm::Secp256k1 as i32),
+                });
+            }
+        }
+        Ok(Response::new(response))
+    }
+
+    async fn health_check(
+        &self,
+        _request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        Ok(Response::new(HealthCheckResponse {
+            status: "healthy".to_string(),
+            service: "ks".to_string(),
+            backend: "mock".to_string(),
+            key_count: self.next_key_id.load(Ordering::Relaxed) - MOCK_FIRST_GENERATED_KEY_ID,
+            timestamp: Self::now(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_proto::supervisor::v1::NonceCredential;
+
+    fn credential() -> NonceCredential {
+        NonceCredential::default()
+    }
+
+    #[test]
+    fn test_mock_keys_are_deterministic() {
+        assert_eq!(
+            mock_secret_key(1).serialize(),
+            mock_secret_key(1).serialize()
+        );
+        assert_ne!(
+            mock_secret_key(1).serialize(),
+            mock_secret_key(2).serialize()
+        );
+        assert_eq!(
+            mock_public_key(1).serialize(),
+            PublicKey::from_secret_key(&mock_secret_key(1)).serialize()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_and_get_secret_key() {
+        let server = MockKeyServer::default();
+
+        let generated = server
+            .generate_key(Request::new(GenerateKeyRequest {
+                credential: credential(),
+                algorithm: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(generated.key_id, MOCK_FIRST_GENERATED_KEY_ID);
+
+        let secret = server
+            .get_secret_key(Request::new(GetSecretKeyRequest {
+                key_id: generated.key_id,
+                credential: credential(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let secret_bytes: [u8; 32] = BASE64_STANDARD
+            .decode(secret.secret_key)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let public = PublicKey::from_secret_key(&SecretKey::parse(&secret_bytes).unwrap());
+        assert_eq!(
+            BASE64_STANDARD.encode(public.serialize_compressed()),
+            generated.public_key
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_rejects_other_algorithms() {
+        let server = MockKeyServer::default();
+        let err = server
+            .generate_key(Request::new(GenerateKeyRequest {
+                credential: credential(),
+                algorithm: Some(KeyAlgorithm::Ed25519 as i32),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_key() {
+        let server = MockKeyServer::default();
+        server
+            .revoke_key(Request::new(RevokeKeyRequest {
+                key_id: 1,
+                credential: credential(),
+            }))
+            .await
+            .unwrap();
+
+        let err = server
+            .get_secret_key(Request::new(GetSecretKeyRequest {
+                key_id: 1,
+                credential: credential(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let keys = server
+            .get_public_keys(Request::new(GetPublicKeysRequest {
+                key_ids: vec![1, 2],
+                credential: credential(),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(keys.revoked_key_ids, vec![1]);
+        assert_eq!(keys.keys.len(), 1);
+    }
+}
@@ -0,0 +1,302 @@
+//! KS gRPC 双向 TLS 与客户端身份白名单
+//!
+//! 配置 `[services.ks.grpc.bind.tls]`（或旧的 `[services.ks.grpc_tls]`）后，KS gRPC 监听器只接受由 `client_ca` 签发的客户端证书，
+//! 并要求叶证书的 SAN（DNS 名称或 URI，如 SPIFFE ID）命中 `allowed_client_sans`，
+//! 使密钥访问绑定到工作负载身份（AIS、Signaling、Supervisor）而不仅是共享密钥。
+//!
+//! 请求仍需通过 PSK 签名校验，两者同时生效。通过校验的身份以 [`ClientIdentity`]
+//! 写入请求扩展，供处理函数记录审计日志。
+
+use crate::config::KsGrpcTlsConfig;
+use crate::error::KsError;
+use std::sync::Arc;
+use tonic::service::Interceptor;
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+use tonic::{Request, Status};
+use tracing::{debug, warn};
+use x509_parser::extensions::GeneralName;
+
+/// 通过校验的客户端身份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity {
+    /// 命中白名单的 SAN
+    ///
+    /// 白名单为空时为证书的第一个 SAN；证书没有 SAN 时为 subject CN，
+    /// 两者都没有时为 [`ANY_CLIENT_IDENTITY`]
+    pub san: String,
+}
+
+/// 白名单为空且证书既没有 SAN 也没有 subject CN 时记录的身份
+pub const ANY_CLIENT_IDENTITY: &str = "<any>";
+
+/// 构建 KS gRPC 服务端 TLS 配置（要求客户端证书）
+pub fn server_tls_config(config: &KsGrpcTlsConfig) -> Result<ServerTlsConfig, KsError> {
+    let read = |path: &str, what: &str| {
+        std::fs::read(path)
+            .map_err(|e| KsError::Config(format!("Failed to read KS gRPC {what} {path}: {e}")))
+    };
+
+    let cert = read(&config.cert, "certificate")?;
+    let key = read(&config.key, "private key")?;
+    let client_ca = read(&config.client_ca, "client CA")?;
+
+    Ok(ServerTlsConfig::new()
+        .identity(Identity::from_pem(cert, key))
+        .client_ca_root(Certificate::from_pem(client_ca))
+        .client_auth_optional(false))
+}
+
+/// 提取证书中的 DNS 与 URI 类型 SAN（DNS 名称转为小写）
+pub fn certificate_sans(der: &[u8]) -> Result<Vec<String>, String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der)
+        .map_err(|e| format!("Invalid client certificate: {e}"))?;
+
+    let extension = cert
+        .subject_alternative_name()
+        .map_err(|e| format!("Invalid subjectAltName extension: {e}"))?;
+
+    Ok(extension
+        .map(|ext| {
+            ext.value
+                .general_names
+                .iter()
+                .filter_map(|name| match name {
+                    GeneralName::DNSName(dns) => Some(dns.to_ascii_lowercase()),
+                    GeneralName::URI(uri) => Some(uri.to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// 提取证书 subject 的第一个 CN
+pub fn certificate_common_name(der: &[u8]) -> Result<Option<String>, String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der)
+        .map_err(|e| format!("Invalid client certificate: {e}"))?;
+
+    Ok(cert
+        .subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .map(str::to_string))
+}
+
+/// 按客户端证书 SAN 白名单校验请求的拦截器
+#[derive(Debug, Clone)]
+pub struct ClientIdentityInterceptor {
+    allowed_sans: Arc<Vec<String>>,
+}
+
+impl ClientIdentityInterceptor {
+    /// `allowed_sans` 为空时接受 client_ca 签发的任意证书（包括没有 SAN 的证书）
+    pub fn new(allowed_sans: &[String]) -> Self {
+        // URI（SPIFFE ID 等）区分大小写，DNS 名称不区分
+        let allowed_sans = allowed_sans
+            .iter()
+            .map(|san| {
+                if san.contains("://") {
+                    san.clone()
+                } else {
+                    san.to_ascii_lowercase()
+                }
+            })
+            .collect();
+        Self {
+            allowed_sans: Arc::new(allowed_sans),
+        }
+    }
+
+    /// 校验客户端叶证书（DER）
+    pub fn authorize(&self, leaf_der: Option<&[u8]>) -> Result<ClientIdentity, Status> {
+        let der = leaf_der.ok_or_else(|| Status::unauthenticated("Client certificate required"))?;
+
+        let sans = certificate_sans(der).map_err(|e| {
+            warn!("Rejected KS gRPC client: {}", e);
+            Status::unauthenticated(e)
+        })?;
+
+        if self.allowed_sans.is_empty() {
+            let san = match sans.into_iter().next() {
+                Some(san) => san,
+                None => certificate_common_name(der)
+                    .map_err(Status::unauthenticated)?
+                    .unwrap_or_else(|| ANY_CLIENT_IDENTITY.to_string()),
+            };
+            return Ok(ClientIdentity { san });
+        }
+
+        match sans.iter().find(|san| self.allowed_sans.contains(san)) {
+            Some(san) => Ok(ClientIdentity { san: san.clone() }),
+            None => {
+                warn!(
+                    "Rejected KS gRPC client: certificate SANs {:?} not in allowlist",
+                    sans
+                );
+                Err(Status::permission_denied(
+                    "Client certificate identity is not allowed",
+                ))
+            }
+        }
+    }
+}
+
+impl Interceptor for ClientIdentityInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let peer_certs = request.peer_certs();
+        let leaf_der = peer_certs
+            .as_ref()
+            .and_then(|certs| certs.first())
+            .map(|cert| cert.as_ref());
+
+        let identity = self.authorize(leaf_der)?;
+        debug!("KS gRPC client authenticated as {}", identity.san);
+        request.extensions_mut().insert(identity);
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair, SanType};
+
+    fn certificate(sans: Vec<SanType>) -> Vec<u8> {
+        certificate_with_cn(sans, None)
+    }
+
+    fn certificate_with_cn(sans: Vec<SanType>, common_name: Option<&str>) -> Vec<u8> {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.subject_alt_names = sans;
+        params.distinguished_name = DistinguishedName::new();
+        if let Some(common_name) = common_name {
+            params
+                .distinguished_name
+                .push(DnType::CommonName, common_name);
+        }
+        let cert = params.self_signed(&KeyPair::generate().unwrap()).unwrap();
+        cert.der().to_vec()
+    }
+
+    fn dns(name: &str) -> SanType {
+        SanType::DnsName(name.try_into().unwrap())
+    }
+
+    #[test]
+    fn test_certificate_sans() {
+        let der = certificate(vec![
+            dns("AIS.actrix.internal"),
+            SanType::URI("spiffe://actrix/ais".try_into().unwrap()),
+        ]);
+        assert_eq!(
+            certificate_sans(&der).unwrap(),
+            vec!["ais.actrix.internal", "spiffe://actrix/ais"]
+        );
+
+        assert!(
+            certificate_sans(&certificate(Vec::new()))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(certificate_sans(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn test_authorize_allowlist() {
+        let interceptor = ClientIdentityInterceptor::new(&[
+            "Signaling.actrix.internal".to_string(),
+            "spiffe://actrix/supervisor".to_string(),
+        ]);
+
+        let signaling = certificate(vec![dns("signaling.actrix.internal")]);
+        assert_eq!(
+            interceptor.authorize(Some(&signaling)).unwrap().san,
+            "signaling.actrix.internal"
+        );
+
+        let supervisor = certificate(vec![SanType::URI(
+            "spiffe://actrix/supervisor".try_into().unwrap(),
+        )]);
+        assert!(interceptor.authorize(Some(&supervisor)).is_ok());
+
+        // URI 区分大小写
+        let supervisor_upper = certificate(vec![SanType::URI(
+            "spiffe://actrix/Supervisor".try_into().unwrap(),
+        )]);
+        assert_eq!(
+            interceptor
+                .authorize(Some(&supervisor_upper))
+                .unwrap_err()
+                .code(),
+            tonic::Code::PermissionDenied
+        );
+
+        let other = certificate(vec![dns("turn.actrix.internal")]);
+        assert_eq!(
+            interceptor.authorize(Some(&other)).unwrap_err().code(),
+            tonic::Code::PermissionDenied
+        );
+        assert_eq!(
+            interceptor.authorize(None).unwrap_err().code(),
+            tonic::Code::Unauthenticated
+        );
+    }
+
+    #[test]
+    fn test_authorize_empty_allowlist() {
+        let interceptor = ClientIdentityInterceptor::new(&[]);
+        let any = certificate(vec![dns("ais.actrix.internal")]);
+        assert_eq!(
+            interceptor.authorize(Some(&any)).unwrap().san,
+            "ais.actrix.internal"
+        );
+
+        // 没有 SAN 的证书同样接受，身份取 subject CN，没有 CN 时为占位身份
+        let cn_only = certificate_with_cn(Vec::new(), Some("legacy-client"));
+        assert_eq!(
+            interceptor.authorize(Some(&cn_only)).unwrap().san,
+            "legacy-client"
+        );
+        assert_eq!(
+            interceptor
+                .authorize(Some(&certificate(Vec::new())))
+                .unwrap()
+                .san,
+            ANY_CLIENT_IDENTITY
+        );
+        assert_eq!(
+            interceptor.authorize(None).unwrap_err().code(),
+            tonic::Code::Unauthenticated
+        );
+    }
+
+    #[test]
+    fn test_server_tls_config() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let key_pair = KeyPair::generate().unwrap();
+        let cert = CertificateParams::new(vec!["ks.actrix.internal".to_string()])
+            .unwrap()
+            .self_signed(&key_pair)
+            .unwrap();
+
+        let cert_path = temp_dir.path().join("ks.pem");
+        let key_path = temp_dir.path().join("ks.key");
+        std::fs::write(&cert_path, cert.pem()).unwrap();
+        std::fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+
+        let mut config = KsGrpcTlsConfig {
+            cert: cert_path.display().to_string(),
+            key: key_path.display().to_string(),
+            client_ca: cert_path.display().to_string(),
+            allowed_client_sans: Vec::new(),
+        };
+        assert!(server_tls_config(&config).is_ok());
+
+        config.client_ca = temp_dir.path().join("missing.pem").display().to_string();
+        assert!(matches!(
+            server_tls_config(&config),
+            Err(KsError::Config(_))
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+//! PKCS#11 (HSM) KEK 提供方
+//!
+//! KEK 以不可导出的 AES-256 密钥形式保存在 HSM / PKCS#11 令牌中，
+//! 私钥的加密与解密均通过 `CKM_AES_GCM` 在令牌内完成，KEK 明文不离开硬件。
+//! 密文格式与本地 KEK 相同: base64(nonce[12] || ciphertext || tag[16])。
+//!
+//! 实现需要启用 `kek-pkcs11` feature；未启用时配置 PKCS#11 KEK 会在启动时报错。
+
+use serde::{Deserialize, Serialize};
+
+/// PKCS#11 KEK 配置
+///
+/// 令牌通过 `slot_id` 或 `token_label` 选择（`slot_id` 优先），
+/// PIN 只能从环境变量或文件读取，避免明文写入配置文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pkcs11KekConfig {
+    /// PKCS#11 模块路径，例如 "/usr/lib/softhsm/libsofthsm2.so"
+    pub module_path: String,
+
+    /// 令牌所在的 slot ID
+    #[serde(default)]
+    pub slot_id: Option<u64>,
+
+    /// 令牌标签
+    #[serde(default)]
+    pub token_label: Option<String>,
+
+    /// KEK 对象标签（CKA_LABEL，需为 AES-256 密钥）
+    pub key_label: String,
+
+    /// 用户 PIN 所在的环境变量名称
+    #[serde(default)]
+    pub pin_env: Option<String>,
+
+    /// 用户 PIN 文件路径（文件权限应设置为 600）
+    #[serde(default)]
+    pub pin_file: Option<String>,
+}
+
+impl Pkcs11KekConfig {
+    /// 读取用户 PIN
+    ///
+    /// 优先级: pin_file > pin_env
+    pub fn read_pin(&self) -> Result<String, String> {
+        if let Some(path) = &self.pin_file {
+            return std::fs::read_to_string(path)
+                .map(|pin| pin.trim().to_string())
+                .map_err(|e| format!("Failed to read PKCS#11 PIN from file {path}: {e}"));
+        }
+
+        if let Some(env_var) = &self.pin_env {
+            return std::env::var(env_var).map_err(|e| {
+                format!("Failed to read PKCS#11 PIN from environment variable {env_var}: {e}")
+            });
+        }
+
+        Err("PKCS#11 KEK requires pin_env or pin_file".to_string())
+    }
+
+    /// 校验配置
+    pub fn validate(&self) -> Result<(), String> {
+        if self.module_path.trim().is_empty() {
+            return Err("kek_provider.module_path must not be empty".to_string());
+        }
+        if self.key_label.trim().is_empty() {
+            return Err("kek_provider.key_label must not be empty".to_string());
+        }
+        if self.slot_id.is_none() && self.token_label.is_none() {
+            return Err("kek_provider requires slot_id or token_label".to_string());
+        }
+        if self.pin_env.is_none() && self.pin_file.is_none() {
+            return Err("kek_provider requires pin_env or pin_file".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "kek-pkcs11")]
+pub use imp::Pkcs11Kek;
+
+#[cfg(feature = "kek-pkcs11")]
+mod imp {
+    use super::Pkcs11KekConfig;
+    use crate::error::{KsError, KsResult};
+    use cryptoki::context::{CInitializeArgs, Pkcs11};
+    use cryptoki::mechanism::{Mechanism, aead::GcmParams};
+    use cryptoki::object::{Attribute, ObjectClass, ObjectHandle};
+    use cryptoki::session::{Session, UserType};
+    use cryptoki::slot::Slot;
+    use cryptoki::types::AuthPin;
+    use std::sync::Mutex;
+    use tracing::info;
+
+    /// GCM 认证标签长度（位）
+    const GCM_TAG_BITS: u64 = 128;
+
+    /// 已登录的 PKCS#11 会话与 KEK 句柄
+    ///
+    /// PKCS#11 会话不支持并发调用，加解密时串行持有会话锁
+    pub struct Pkcs11Kek {
+        // 保持模块加载，会话依赖其生命周期
+        _context: Pkcs11,
+        session: Mutex<Session>,
+        key: ObjectHandle,
+    }
+
+    impl std::fmt::Debug for Pkcs11Kek {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Pkcs11Kek").finish_non_exhaustive()
+        }
+    }
+
+    fn pkcs11_error(context: &str, e: cryptoki::error::Error) -> KsError {
+        KsError::Crypto(format!("PKCS#11 {context} failed: {e}"))
+    }
+
+    impl Pkcs11Kek {
+        /// 加载 PKCS#11 模块、登录令牌并定位 KEK
+        pub fn open(config: &Pkcs11KekConfig) -> KsResult<Self> {
+            config.validate().map_err(KsError::Config)?;
+            let pin = config.read_pin().map_err(KsError::Config)?;
+
+            let context = Pkcs11::new(&config.module_path).map_err(|e| {
+                KsError::Config(format!(
+                    "Failed to load PKCS#11 module {}: {e}",
+                    config.module_path
+                ))
+            })?;
+            context
+                .initialize(CInitializeArgs::OsThreads)
+                .map_err(|e| pkcs11_error("initialize", e))?;
+
+            let slot = Self::find_slot(&context, config)?;
+            let session = context
+                .open_ro_session(slot)
+                .map_err(|e| pkcs11_error("open session", e))?;
+            session
+                .login(UserType::User, Some(&AuthPin::new(pin)))
+                .map_err(|e| pkcs11_error("login", e))?;
+
+            let key = session
+                .find_objects(&[
+                    Attribute::Class(ObjectClass::SECRET_KEY),
+                    Attribute::Label(config.key_label.as_bytes().to_vec()),
+                ])
+                .map_err(|e| pkcs11_error("find KEK", e))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    KsError::Config(format!(
+                        "PKCS#11 KEK with label {} not found",
+                        config.key_label
+                    ))
+                })?;
+
+            info!(
+                "PKCS#11 KEK loaded: module={}, key_label={}",
+                config.module_path, config.key_label
+            );
+            Ok(Self {
+                _context: context,
+                session: Mutex::new(session),
+                key,
+            })
+        }
+
+        /// 按 slot_id 或 token_label 选择令牌
+        fn find_slot(context: &Pkcs11, config: &Pkcs11KekConfig) -> KsResult<Slot> {
+            let slots = context
+                .get_slots_with_token()
+                .map_err(|e| pkcs11_error("list slots", e))?;
+
+            if let Some(slot_id) = config.slot_id {
+                return slots
+                    .into_iter()
+                    .find(|slot| slot.id() == slot_id)
+                    .ok_or_else(|| {
+                        KsError::Config(format!("PKCS#11 slot {slot_id} has no token"))
+                    });
+            }
+
+            let label = config.token_label.as_deref().unwrap_or_default();
+            for slot in slots {
+                let info = context
+                    .get_token_info(slot)
+                    .map_err(|e| pkcs11_error("get token info", e))?;
+                if info.label().trim() == label {
+                    return Ok(slot);
+                }
+            }
+            Err(KsError::Config(format!(
+                "PKCS#11 token with label {label} not found"
+            )))
+        }
+
+        /// 在令牌内执行 AES-GCM 加密，返回 ciphertext || tag
+        pub fn encrypt(&self, nonce: &[u8; 12], plaintext: &[u8]) -> KsResult<Vec<u8>> {
+            let mut iv = *nonce;
+            let params = GcmParams::new(&mut iv, &[], GCM_TAG_BITS.into())
+                .map_err(|e| pkcs11_error("GCM parameters", e))?;
+            let session = self.session.lock().unwrap();
+            session
+                .encrypt(&Mechanism::AesGcm(params), self.key, plaintext)
+                .map_err(|e| pkcs11_error("encrypt", e))
+        }
+
+        /// 在令牌内执行 AES-GCM 解密
+        pub fn decrypt(&self, nonce: &[u8; 12], ciphertext: &[u8]) -> KsResult<Vec<u8>> {
+            let mut iv = *nonce;
+            let params = GcmParams::new(&mut iv, &[], GCM_TAG_BITS.into())
+                .map_err(|e| pkcs11_error("GCM parameters", e))?;
+            let session = self.session.lock().unwrap();
+            session
+                .decrypt(&Mechanism::AesGcm(params), self.key, ciphertext)
+                .map_err(|e| pkcs11_error("decrypt", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Pkcs11KekConfig {
+        Pkcs11KekConfig {
+            module_path: "/usr/lib/softhsm/libsofthsm2.so".to_string(),
+            slot_id: None,
+            token_label: Some("actrix".to_string()),
+            key_label: "actrix-ks-kek".to_string(),
+            pin_env: Some("ACTRIX_TEST_PKCS11_PIN_UNSET".to_string()),
+            pin_file: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_pkcs11_config() {
+        assert!(config().validate().is_ok());
+
+        let missing_token = Pkcs11KekConfig {
+            token_label: None,
+            ..config()
+        };
+        assert!(missing_token.validate().is_err());
+
+        let missing_pin = Pkcs11KekConfig {
+            pin_env: None,
+            ..config()
+        };
+        assert!(missing_pin.validate().is_err());
+    }
+
+    #[test]
+    fn test_read_pin_prefers_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let pin_path = temp_dir.path().join("pin");
+        std::fs::write(&pin_path, "1234\n").unwrap();
+
+        let config = Pkcs11KekConfig {
+            pin_file: Some(pin_path.to_string_lossy().to_string()),
+            ..config()
+        };
+        assert_eq!(config.read_pin().unwrap(), "1234");
+
+        // 环境变量不存在时报错
+        let config = Pkcs11KekConfig {
+            pin_file: None,
+            ..config
+        };
+        assert!(config.read_pin().is_err());
+    }
+}
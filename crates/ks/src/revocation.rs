@@ -0,0 +1,140 @@
+//! 密钥吊销
+//!
+//! `RevokeKey` 立即使指定密钥失效（适用于私钥泄露），不必等待 TTL 过期：
+//! 吊销后 GetSecretKey 不再返回该私钥，密钥也不能再参与轮替。
+//!
+//! 每次吊销都会广播 [`KeyRevocation`] 事件，gRPC `WatchKeyRevocations` 先推送当前
+//! 已吊销的密钥列表，再推送新的吊销事件，验证方（Signaling 等）据此拒绝使用
+//! 该密钥签发的凭证并清理本地缓存。事件通道为进程级，与 [`crate::rotation`] 一致。
+
+use crate::error::{KsError, KsResult};
+use crate::storage::KeyStorage;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// 事件通道容量，订阅者落后超过该数量时丢失最旧的事件
+const REVOCATION_EVENT_CAPACITY: usize = 64;
+
+lazy_static! {
+    static ref REVOCATION_EVENTS: broadcast::Sender<KeyRevocation> =
+        broadcast::channel(REVOCATION_EVENT_CAPACITY).0;
+}
+
+/// 一次密钥吊销的结果（同时作为推送给订阅者的事件）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRevocation {
+    /// 被吊销的密钥 ID
+    pub key_id: u32,
+    /// 吊销时间（Unix 时间戳）
+    pub revoked_at: u64,
+}
+
+/// 订阅密钥吊销事件
+pub fn subscribe() -> broadcast::Receiver<KeyRevocation> {
+    REVOCATION_EVENTS.subscribe()
+}
+
+/// 当前已吊销的密钥（按 key_id 排序）
+pub async fn revoked_keys(storage: &KeyStorage) -> KsResult<Vec<KeyRevocation>> {
+    Ok(storage
+        .list_revoked_keys()
+        .await?
+        .into_iter()
+        .map(|(key_id, revoked_at)| KeyRevocation { key_id, revoked_at })
+        .collect())
+}
+
+/// 吊销密钥
+///
+/// # Errors
+/// - 密钥不存在（`KeyNotFound`）或已被吊销（`InvalidRequest`）
+/// - 存储错误
+pub async fn revoke_key(storage: &KeyStorage, key_id: u32) -> KsResult<KeyRevocation> {
+    if storage.get_key_record(key_id).await?.is_none() {
+        return Err(KsError::KeyNotFound(key_id));
+    }
+
+    let revoked_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    if !storage.revoke_key(key_id, revoked_at).await? {
+        return Err(KsError::InvalidRequest(format!(
+            "Key {key_id} has already been revoked"
+        )));
+    }
+
+    let revocation = KeyRevocation { key_id, revoked_at };
+
+    warn!("Revoked key: key_id={} at {}", key_id, revoked_at);
+
+    // 没有订阅者时发送失败，忽略即可
+    let _ = REVOCATION_EVENTS.send(revocation.clone());
+
+    Ok(revocation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyEncryptor;
+    use crate::rotation::rotate_key;
+    use crate::storage::{SqliteConfig, StorageBackend, StorageConfig};
+    use crate::types::KeyStatus;
+    use tempfile::tempdir;
+
+    async fn create_storage(path: &std::path::Path) -> KeyStorage {
+        let config = StorageConfig {
+            backend: StorageBackend::Sqlite,
+            key_ttl_seconds: 3600,
+            sqlite: Some(SqliteConfig::default()),
+            postgres: None,
+            etcd: None,
+            cache: Default::default(),
+        };
+        KeyStorage::from_config(&config, KeyEncryptor::no_encryption(), path)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_revoke_key_and_notify() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+        let key = storage.generate_and_store_key().await.unwrap();
+
+        let mut events = subscribe();
+        let revocation = revoke_key(&storage, key.key_id).await.unwrap();
+        assert_eq!(revocation.key_id, key.key_id);
+
+        // 其他测试也可能触发吊销，按 revoked_at 与 key_id 找到本次事件
+        loop {
+            let event = events.recv().await.unwrap();
+            if event == revocation {
+                break;
+            }
+        }
+
+        let record = storage.get_key_record(key.key_id).await.unwrap().unwrap();
+        assert_eq!(record.status, KeyStatus::Revoked);
+        assert_eq!(revoked_keys(&storage).await.unwrap(), vec![revocation]);
+
+        // 不能重复吊销，也不能再作为旧密钥参与轮替
+        assert!(matches!(
+            revoke_key(&storage, key.key_id).await,
+            Err(KsError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            rotate_key(&storage, Some(key.key_id), 600).await,
+            Err(KsError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            revoke_key(&storage, 999).await,
+            Err(KsError::KeyNotFound(999))
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+//! 密钥轮替
+//!
+//! `RotateKey` 生成新的 Active 密钥，并将旧密钥转为仅验证（`verify_only`）：
+//! 宽限期内旧密钥仍可通过 GetSecretKey 获取，用于验证轮替前签发的凭证，
+//! 宽限期结束后不再可用并随过期清理删除。
+//!
+//! 新密钥沿用旧密钥的算法（见 [`KeyAlgorithm`]），没有旧密钥时使用默认算法。
+//!
+//! 每次轮替都会广播 [`KeyRotation`] 事件，gRPC `WatchKeyRotations` 将其推送给
+//! 已订阅的消费方（AIS、Signaling），消费方据此切换签发密钥或刷新本地缓存。
+//! 事件通道为进程级，HTTP 与 gRPC 端点触发的轮替都会推送给所有订阅者。
+
+use crate::error::{KsError, KsResult};
+use crate::storage::KeyStorage;
+use crate::types::{KeyAlgorithm, KeyStatus};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tracing::info;
+
+/// 事件通道容量，订阅者落后超过该数量时丢失最旧的事件
+const ROTATION_EVENT_CAPACITY: usize = 64;
+
+lazy_static! {
+    static ref ROTATION_EVENTS: broadcast::Sender<KeyRotation> =
+        broadcast::channel(ROTATION_EVENT_CAPACITY).0;
+}
+
+/// 一次密钥轮替的结果（同时作为推送给订阅者的事件）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRotation {
+    /// 新的 Active 密钥 ID
+    pub key_id: u32,
+    /// 新密钥算法（与旧密钥相同）
+    #[serde(default)]
+    pub algorithm: KeyAlgorithm,
+    /// 新公钥（Base64 编码）
+    pub public_key: String,
+    /// 新密钥过期时间（Unix 时间戳）
+    pub expires_at: u64,
+    /// 被转为仅验证的旧密钥 ID
+    pub previous_key_id: Option<u32>,
+    /// 旧密钥宽限期截止时间（Unix 时间戳，无旧密钥时为 0）
+    pub verify_until: u64,
+    /// 轮替时间（Unix 时间戳）
+    pub rotated_at: u64,
+}
+
+/// 订阅密钥轮替事件
+pub fn subscribe() -> broadcast::Receiver<KeyRotation> {
+    ROTATION_EVENTS.subscribe()
+}
+
+/// 轮替密钥
+///
+/// # Arguments
+/// * `storage` - 密钥存储
+/// * `previous_key_id` - 要转为仅验证的旧密钥，None 时取最近创建的 Active 密钥
+/// * `grace_seconds` - 旧密钥宽限期（秒）
+///
+/// # Errors
+/// - 指定的旧密钥不存在（`KeyNotFound`）或已不是 Active（`InvalidRequest`）
+/// - 存储错误
+pub async fn rotate_key(
+    storage: &KeyStorage,
+    previous_key_id: Option<u32>,
+    grace_seconds: u64,
+) -> KsResult<KeyRotation> {
+    // 先确认旧密钥，避免生成新密钥后才发现请求无效
+    let previous_key_id = match previous_key_id {
+        Some(key_id) => Some(key_id),
+        None => storage.get_latest_active_key_id().await?,
+    };
+    let algorithm = match previous_key_id {
+        Some(key_id) => {
+            let record = storage
+                .get_key_record(key_id)
+                .await?
+                .ok_or(KsError::KeyNotFound(key_id))?;
+            if record.status != KeyStatus::Active {
+                return Err(KsError::InvalidRequest(format!(
+                    "Key {key_id} has already been rotated"
+                )));
+            }
+            record.algorithm
+        }
+        None => KeyAlgorithm::default(),
+    };
+
+    let key_pair = storage.generate_and_store_key_with(algorithm).await?;
+    let record = storage
+        .get_key_record(key_pair.key_id)
+        .await?
+        .ok_or_else(|| KsError::Internal("Failed to get key record after creation".into()))?;
+
+    let rotated_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let verify_until = match previous_key_id {
+        Some(key_id) => {
+            let verify_until = rotated_at + grace_seconds;
+            if !storage.retire_key(key_id, rotated_at, verify_until).await? {
+                // 并发轮替已先一步处理了该密钥
+                return Err(KsError::InvalidRequest(format!(
+                    "Key {key_id} has already been rotated"
+                )));
+            }
+            verify_until
+        }
+        None => 0,
+    };
+
+    let rotation = KeyRotation {
+        key_id: key_pair.key_id,
+        algorithm,
+        public_key: key_pair.public_key,
+        expires_at: record.expires_at,
+        previous_key_id,
+        verify_until,
+        rotated_at,
+    };
+
+    info!(
+        "Rotated key: new key_id={}, previous key_id={:?} verify-only until {}",
+        rotation.key_id, rotation.previous_key_id, rotation.verify_until
+    );
+
+    // 没有订阅者时发送失败，忽略即可
+    let _ = ROTATION_EVENTS.send(rotation.clone());
+
+    Ok(rotation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyEncryptor;
+    use crate::storage::{SqliteConfig, StorageBackend, StorageConfig};
+    use tempfile::tempdir;
+
+    async fn create_storage(path: &std::path::Path) -> KeyStorage {
+        let config = StorageConfig {
+            backend: StorageBackend::Sqlite,
+            key_ttl_seconds: 3600,
+            sqlite: Some(SqliteConfig::default()),
+            postgres: None,
+            etcd: None,
+            cache: Default::default(),
+        };
+        KeyStorage::from_config(&config, KeyEncryptor::no_encryption(), path)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rotate_retires_previous_key_and_notifies() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+        let old = storage.generate_and_store_key().await.unwrap();
+
+        let mut events = subscribe();
+        let rotation = rotate_key(&storage, None, 600).await.unwrap();
+        assert_eq!(rotation.previous_key_id, Some(old.key_id));
+        assert_eq!(rotation.verify_until, rotation.rotated_at + 600);
+
+        // 其他测试也可能触发轮替，按 key_id 找到本次事件
+        loop {
+            let event = events.recv().await.unwrap();
+            if event.key_id == rotation.key_id {
+                assert_eq!(event, rotation);
+                break;
+            }
+        }
+
+        let record = storage.get_key_record(old.key_id).await.unwrap().unwrap();
+        assert_eq!(record.status, KeyStatus::VerifyOnly);
+        let (expires_at, tolerance) = record.effective_expiry(3600);
+        assert_eq!(expires_at, rotation.rotated_at);
+        assert_eq!(expires_at + tolerance, rotation.verify_until);
+
+        // 已轮替的密钥不能再次指定
+        assert!(matches!(
+            rotate_key(&storage, Some(old.key_id), 600).await,
+            Err(KsError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            rotate_key(&storage, Some(999), 600).await,
+            Err(KsError::KeyNotFound(999))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rotate_without_previous_key() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+
+        let rotation = rotate_key(&storage, None, 600).await.unwrap();
+        assert_eq!(rotation.previous_key_id, None);
+        assert_eq!(rotation.verify_until, 0);
+        assert_eq!(rotation.algorithm, KeyAlgorithm::Secp256k1);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_keeps_algorithm() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+        let old = storage
+            .generate_and_store_key_with(KeyAlgorithm::Ed25519)
+            .await
+            .unwrap();
+
+        let rotation = rotate_key(&storage, Some(old.key_id), 600).await.unwrap();
+        assert_eq!(rotation.algorithm, KeyAlgorithm::Ed25519);
+        let record = storage
+            .get_key_record(rotation.key_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.algorithm, KeyAlgorithm::Ed25519);
+    }
+}
@@ -0,0 +1,523 @@
+//! 后台密钥调度
+//!
+//! [`KeyScheduler`] 按 `services.ks.rotation` 配置在后台执行三项任务：
+//! - 预生成：维持 [`KeyPool`] 中的可用密钥数量，GenerateKey 直接从池中取出，
+//!   池中密钥超过 `pool_max_age_seconds` 后丢弃（其 TTL 自生成时开始计算）
+//! - 定时轮替：按 cron 表达式（见 [`crate::cron`]）轮替所有已分发的 Active 密钥，
+//!   每次轮替都会推送 [`crate::rotation::KeyRotation`] 事件
+//! - 过期清理：定期调用 [`KeyStorage::cleanup_expired_keys`]
+//!
+//! 多个 KS 实例共享存储后端（PostgreSQL、etcd）时，应只在一个实例上配置 `schedule`，
+//! 否则同一时刻会重复轮替。
+
+use crate::config::KsRotationConfig;
+use crate::cron::CronSchedule;
+use crate::error::{KsError, KsResult};
+use crate::rotation::rotate_key;
+use crate::storage::KeyStorage;
+use crate::types::{KeyAlgorithm, KeyPair, KeyStatus};
+use chrono::Utc;
+use lazy_static::lazy_static;
+use prometheus::{IntCounter, IntCounterVec, IntGauge, Opts};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Notify, broadcast};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// 未触发补充时检查池与指标的间隔
+const POOL_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    /// 后台调度指标
+    static ref KS_KEY_POOL_SIZE: IntGauge = IntGauge::with_opts(
+        Opts::new("actrix_ks_key_pool_size", "Number of pre-generated keys in the pool")
+            .namespace("actrix")
+    ).unwrap();
+
+    static ref KS_KEY_POOL_OLDEST_AGE: IntGauge = IntGauge::with_opts(
+        Opts::new("actrix_ks_key_pool_oldest_age_seconds", "Age of the oldest pre-generated key in seconds")
+            .namespace("actrix")
+    ).unwrap();
+
+    static ref KS_ACTIVE_KEYS: IntGauge = IntGauge::with_opts(
+        Opts::new("actrix_ks_active_keys", "Number of active keys in storage")
+            .namespace("actrix")
+    ).unwrap();
+
+    static ref KS_ACTIVE_KEY_AGE: IntGauge = IntGauge::with_opts(
+        Opts::new("actrix_ks_active_key_oldest_age_seconds", "Age of the oldest active key in seconds")
+            .namespace("actrix")
+    ).unwrap();
+
+    static ref KS_SCHEDULED_ROTATIONS: IntCounterVec = IntCounterVec::new(
+        Opts::new("actrix_ks_scheduled_rotations_total", "Total number of keys rotated by the schedule")
+            .namespace("actrix"),
+        &["result"]
+    ).unwrap();
+
+    static ref KS_KEYS_CLEANED: IntCounter = IntCounter::with_opts(
+        Opts::new("actrix_ks_keys_cleaned_total", "Total number of expired keys removed by periodic cleanup")
+            .namespace("actrix")
+    ).unwrap();
+}
+
+/// 注册调度指标
+pub(crate) fn register_metrics(registry: &prometheus::Registry) -> Result<(), prometheus::Error> {
+    registry.register(Box::new(KS_KEY_POOL_SIZE.clone()))?;
+    registry.register(Box::new(KS_KEY_POOL_OLDEST_AGE.clone()))?;
+    registry.register(Box::new(KS_ACTIVE_KEYS.clone()))?;
+    registry.register(Box::new(KS_ACTIVE_KEY_AGE.clone()))?;
+    registry.register(Box::new(KS_SCHEDULED_ROTATIONS.clone()))?;
+    registry.register(Box::new(KS_KEYS_CLEANED.clone()))?;
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// 预生成密钥池
+///
+/// 池中密钥已写入存储（Active），取出后才分发给调用方。默认（未启用预生成）为空池，
+/// [`KeyPool::take_or_generate`] 直接生成新密钥。
+#[derive(Clone)]
+pub struct KeyPool {
+    keys: Arc<Mutex<VecDeque<(KeyPair, u64)>>>,
+    /// 取出密钥后通知调度器补充
+    refill: Arc<Notify>,
+    max_age_seconds: u64,
+}
+
+impl Default for KeyPool {
+    fn default() -> Self {
+        Self::new(KsRotationConfig::default().pool_max_age_seconds)
+    }
+}
+
+impl KeyPool {
+    pub fn new(max_age_seconds: u64) -> Self {
+        Self {
+            keys: Arc::new(Mutex::new(VecDeque::new())),
+            refill: Arc::new(Notify::new()),
+            max_age_seconds,
+        }
+    }
+
+    /// 池中密钥数量
+    pub fn len(&self) -> usize {
+        self.keys.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 密钥是否仍在池中（尚未分发）
+    pub fn contains(&self, key_id: u32) -> bool {
+        self.keys
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(key_pair, _)| key_pair.key_id == key_id)
+    }
+
+    /// 池中最旧密钥的存放时间（秒）
+    pub fn oldest_age(&self) -> Option<u64> {
+        self.keys
+            .lock()
+            .unwrap()
+            .front()
+            .map(|(_, created_at)| now_secs().saturating_sub(*created_at))
+    }
+
+    /// 放入新生成的密钥
+    fn push(&self, key_pair: KeyPair, created_at: u64) {
+        self.keys.lock().unwrap().push_back((key_pair, created_at));
+    }
+
+    /// 丢弃超过最长存放时间的密钥，返回丢弃数量
+    fn evict_stale(&self) -> usize {
+        let cutoff = now_secs().saturating_sub(self.max_age_seconds);
+        let mut keys = self.keys.lock().unwrap();
+        let before = keys.len();
+        keys.retain(|(_, created_at)| *created_at > cutoff);
+        before - keys.len()
+    }
+
+    /// 取出最旧的未过期密钥
+    pub fn take(&self) -> Option<KeyPair> {
+        self.evict_stale();
+        let key_pair = self.keys.lock().unwrap().pop_front().map(|(key, _)| key);
+        if key_pair.is_some() {
+            self.refill.notify_one();
+        }
+        key_pair
+    }
+
+    /// 取出池中密钥，池为空时直接生成
+    ///
+    /// 池中只预生成默认算法（secp256k1）的密钥，其他算法直接生成。
+    /// 池中密钥可能已被轮替或吊销（例如未指定旧密钥的 RotateKey 选中了池中密钥），
+    /// 只分发存储中仍为 Active 的密钥。
+    pub async fn take_or_generate(
+        &self,
+        storage: &KeyStorage,
+        algorithm: KeyAlgorithm,
+    ) -> KsResult<KeyPair> {
+        if algorithm != KeyAlgorithm::default() {
+            return storage.generate_and_store_key_with(algorithm).await;
+        }
+
+        while let Some(key_pair) = self.take() {
+            match storage.get_key_record(key_pair.key_id).await? {
+                Some(record) if record.status == KeyStatus::Active => return Ok(key_pair),
+                _ => debug!("丢弃池中已失效的密钥: key_id={}", key_pair.key_id),
+            }
+        }
+        storage.generate_and_store_key().await
+    }
+}
+
+/// 后台密钥调度器
+pub struct KeyScheduler {
+    storage: KeyStorage,
+    pool: KeyPool,
+    pool_size: usize,
+    schedule: Option<CronSchedule>,
+    cleanup_interval: Option<Duration>,
+    rotation_grace_seconds: u64,
+}
+
+impl KeyScheduler {
+    /// 创建调度器
+    ///
+    /// # Errors
+    /// cron 表达式无效时返回 `KsError::Config`
+    pub fn new(
+        storage: KeyStorage,
+        config: &KsRotationConfig,
+        rotation_grace_seconds: u64,
+    ) -> KsResult<Self> {
+        let schedule = config
+            .schedule
+            .as_deref()
+            .map(str::parse::<CronSchedule>)
+            .transpose()
+            .map_err(KsError::Config)?;
+
+        Ok(Self {
+            storage,
+            pool: KeyPool::new(config.pool_max_age_seconds),
+            pool_size: config.pool_size,
+            schedule,
+            cleanup_interval: (config.cleanup_interval_seconds > 0)
+                .then(|| Duration::from_secs(config.cleanup_interval_seconds)),
+            rotation_grace_seconds,
+        })
+    }
+
+    /// 预生成密钥池（交给 gRPC 服务的 GenerateKey 使用）
+    pub fn pool(&self) -> KeyPool {
+        self.pool.clone()
+    }
+
+    /// 启动后台任务，收到 shutdown 信号后退出
+    pub fn spawn(self, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!(
+                "🗓️  KS 密钥调度已启动: pool_size={}, schedule={}, cleanup_interval={:?}",
+                self.pool_size,
+                self.schedule.is_some(),
+                self.cleanup_interval
+            );
+
+            let mut cleanup = self.cleanup_interval.map(|interval| {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                ticker
+            });
+
+            self.refill_pool().await;
+            self.update_metrics().await;
+
+            loop {
+                let next_rotation = self.next_rotation_delay();
+
+                tokio::select! {
+                    _ = shutdown_rx.recv() => {
+                        info!("KS 密钥调度收到关闭信号");
+                        break;
+                    }
+                    _ = async { tokio::time::sleep(next_rotation.unwrap()).await },
+                        if next_rotation.is_some() =>
+                    {
+                        self.rotate_now().await;
+                    }
+                    _ = async { cleanup.as_mut().unwrap().tick().await }, if cleanup.is_some() => {
+                        self.cleanup().await;
+                    }
+                    _ = self.pool.refill.notified() => {
+                        self.refill_pool().await;
+                    }
+                    _ = tokio::time::sleep(POOL_CHECK_INTERVAL) => {
+                        self.refill_pool().await;
+                    }
+                }
+
+                self.update_metrics().await;
+            }
+        })
+    }
+
+    /// 距离下一次定时轮替的时间
+    fn next_rotation_delay(&self) -> Option<Duration> {
+        let now = Utc::now();
+        let next = self.schedule.as_ref()?.next_after(now)?;
+        (next - now).to_std().ok()
+    }
+
+    /// 丢弃过旧的池中密钥并补足到 `pool_size`
+    pub async fn refill_pool(&self) {
+        let evicted = self.pool.evict_stale();
+        if evicted > 0 {
+            debug!("丢弃 {} 个超过最长存放时间的预生成密钥", evicted);
+        }
+
+        while self.pool.len() < self.pool_size {
+            match self.storage.generate_and_store_key().await {
+                Ok(key_pair) => self.pool.push(key_pair, now_secs()),
+                Err(e) => {
+                    warn!("预生成密钥失败: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// 轮替所有已分发的 Active 密钥（池中密钥不参与）
+    ///
+    /// 返回成功轮替的数量
+    pub async fn rotate_now(&self) -> usize {
+        let key_ids: Vec<u32> = match self.storage.list_active_keys().await {
+            Ok(keys) => keys
+                .into_iter()
+                .map(|(key_id, _)| key_id)
+                .filter(|key_id| !self.pool.contains(*key_id))
+                .collect(),
+            Err(e) => {
+                error!("定时轮替失败，无法列出 Active 密钥: {}", e);
+                KS_SCHEDULED_ROTATIONS.with_label_values(&["error"]).inc();
+                return 0;
+            }
+        };
+
+        if key_ids.is_empty() {
+            debug!("定时轮替: 没有需要轮替的密钥");
+            return 0;
+        }
+
+        let mut rotated = 0;
+        for key_id in key_ids {
+            match rotate_key(&self.storage, Some(key_id), self.rotation_grace_seconds).await {
+                Ok(_) => {
+                    rotated += 1;
+                    KS_SCHEDULED_ROTATIONS.with_label_values(&["success"]).inc();
+                }
+                // 同一时刻被手动轮替或吊销
+                Err(KsError::InvalidRequest(_)) | Err(KsError::KeyNotFound(_)) => {
+                    KS_SCHEDULED_ROTATIONS.with_label_values(&["skipped"]).inc();
+                }
+                Err(e) => {
+                    error!("定时轮替密钥 {} 失败: {}", key_id, e);
+                    KS_SCHEDULED_ROTATIONS.with_label_values(&["error"]).inc();
+                }
+            }
+        }
+
+        info!("定时轮替完成: 轮替 {} 个密钥", rotated);
+        rotated
+    }
+
+    /// 清理过期密钥，返回删除数量
+    pub async fn cleanup(&self) -> u32 {
+        match self.storage.cleanup_expired_keys().await {
+            Ok(count) => {
+                if count > 0 {
+                    info!("定期清理删除了 {} 个过期密钥", count);
+                }
+                KS_KEYS_CLEANED.inc_by(count as u64);
+                count
+            }
+            Err(e) => {
+                warn!("定期清理过期密钥失败: {}", e);
+                0
+            }
+        }
+    }
+
+    /// 更新池与存储的指标
+    async fn update_metrics(&self) {
+        KS_KEY_POOL_SIZE.set(self.pool.len() as i64);
+        KS_KEY_POOL_OLDEST_AGE.set(self.pool.oldest_age().unwrap_or(0) as i64);
+
+        match self.storage.list_active_keys().await {
+            Ok(keys) => {
+                let now = now_secs();
+                // 池中密钥单独统计
+                let distributed: Vec<u64> = keys
+                    .into_iter()
+                    .filter(|(key_id, _)| !self.pool.contains(*key_id))
+                    .map(|(_, created_at)| created_at)
+                    .collect();
+                KS_ACTIVE_KEYS.set(distributed.len() as i64);
+                KS_ACTIVE_KEY_AGE.set(
+                    distributed
+                        .iter()
+                        .min()
+                        .map(|created_at| now.saturating_sub(*created_at))
+                        .unwrap_or(0) as i64,
+                );
+            }
+            Err(e) => debug!("更新密钥指标失败: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KeyEncryptor;
+    use crate::storage::{SqliteConfig, StorageBackend, StorageConfig};
+    use tempfile::tempdir;
+
+    async fn create_storage(path: &std::path::Path) -> KeyStorage {
+        let config = StorageConfig {
+            backend: StorageBackend::Sqlite,
+            key_ttl_seconds: 3600,
+            sqlite: Some(SqliteConfig::default()),
+            postgres: None,
+            etcd: None,
+            cache: Default::default(),
+        };
+        KeyStorage::from_config(&config, KeyEncryptor::no_encryption(), path)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pool_take_or_generate() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+        let config = KsRotationConfig {
+            pool_size: 2,
+            ..Default::default()
+        };
+        let scheduler = KeyScheduler::new(storage.clone(), &config, 60).unwrap();
+        let pool = scheduler.pool();
+
+        scheduler.refill_pool().await;
+        assert_eq!(pool.len(), 2);
+        assert_eq!(storage.get_key_count().await.unwrap(), 2);
+
+        // 优先取出池中密钥
+        let first = pool
+            .take_or_generate(&storage, KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert!(!pool.contains(first.key_id));
+        assert_eq!(pool.len(), 1);
+
+        // 池中密钥已被轮替时跳过
+        let pooled = pool.keys.lock().unwrap().front().unwrap().0.key_id;
+        rotate_key(&storage, Some(pooled), 60).await.unwrap();
+        let second = pool
+            .take_or_generate(&storage, KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert_ne!(second.key_id, pooled);
+        assert!(pool.is_empty());
+
+        // 池为空时直接生成
+        let generated = pool
+            .take_or_generate(&storage, KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert_ne!(generated.key_id, second.key_id);
+
+        // 其他算法不使用池
+        scheduler.refill_pool().await;
+        let ed25519 = pool
+            .take_or_generate(&storage, KeyAlgorithm::Ed25519)
+            .await
+            .unwrap();
+        assert_eq!(ed25519.algorithm, KeyAlgorithm::Ed25519);
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pool_evicts_stale_keys() {
+        let pool = KeyPool::new(60);
+        let key_pair = |key_id| KeyPair {
+            key_id,
+            algorithm: KeyAlgorithm::default(),
+            secret_key: String::new(),
+            public_key: String::new(),
+        };
+        pool.push(key_pair(1), now_secs() - 120);
+        pool.push(key_pair(2), now_secs());
+
+        assert_eq!(pool.take().map(|key| key.key_id), Some(2));
+        assert!(pool.take().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_now_skips_pooled_keys() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+        let config = KsRotationConfig {
+            schedule: Some("@daily".to_string()),
+            pool_size: 1,
+            ..Default::default()
+        };
+        let scheduler = KeyScheduler::new(storage.clone(), &config, 60).unwrap();
+        assert!(scheduler.next_rotation_delay().unwrap() <= Duration::from_secs(86400));
+
+        let distributed = storage.generate_and_store_key().await.unwrap();
+        scheduler.refill_pool().await;
+        let pooled = scheduler.pool.keys.lock().unwrap()[0].0.key_id;
+
+        assert_eq!(scheduler.rotate_now().await, 1);
+
+        let old = storage
+            .get_key_record(distributed.key_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(old.status, KeyStatus::VerifyOnly);
+        let pooled_record = storage.get_key_record(pooled).await.unwrap().unwrap();
+        assert_eq!(pooled_record.status, KeyStatus::Active);
+
+        // 轮替生成的新密钥在下一次定时轮替时参与轮替
+        assert_eq!(scheduler.rotate_now().await, 1);
+        assert_eq!(storage.list_active_keys().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_schedule() {
+        let temp_dir = tempdir().unwrap();
+        let storage = create_storage(temp_dir.path()).await;
+        let config = KsRotationConfig {
+            schedule: Some("not a schedule".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            KeyScheduler::new(storage, &config, 60),
+            Err(KsError::Config(_))
+        ));
+    }
+}
@@ -3,12 +3,12 @@
 //! 定义了所有存储后端必须实现的统一异步接口
 
 use crate::error::KsResult;
-use crate::types::{KeyPair, KeyRecord};
+use crate::types::{KeyAlgorithm, KeyPair, KeyRecord};
 use async_trait::async_trait;
 
 /// 密钥存储后端抽象接口
 ///
-/// 所有存储后端（SQLite, PostgreSQL）都需要实现此 trait
+/// 所有存储后端（SQLite, PostgreSQL, etcd）都需要实现此 trait
 /// 提供统一的异步 API 用于密钥的生成、存储、查询和管理
 #[async_trait]
 pub trait KeyStorageBackend: Send + Sync {
@@ -19,11 +19,14 @@ pub trait KeyStorageBackend: Send + Sync {
 
     /// 生成并存储新的密钥对
     ///
-    /// 自动生成椭圆曲线密钥对，存储到后端，并返回包含 key_id 的完整密钥信息
+    /// 按指定算法生成密钥对（见 [`crate::keygen`]），存储到后端，并返回包含 key_id 的完整密钥信息
+    ///
+    /// # Arguments
+    /// * `algorithm` - 密钥算法，随记录一起保存
     ///
     /// # Returns
-    /// 包含 key_id、public_key 和 secret_key 的密钥对结构
-    async fn generate_and_store_key(&self) -> KsResult<KeyPair>;
+    /// 包含 key_id、algorithm、public_key 和 secret_key 的密钥对结构
+    async fn generate_and_store_key(&self, algorithm: KeyAlgorithm) -> KsResult<KeyPair>;
 
     /// 根据 key_id 查询公钥
     ///
@@ -64,9 +67,53 @@ pub trait KeyStorageBackend: Send + Sync {
     /// 密钥总数（包括过期和未过期的）
     async fn get_key_count(&self) -> KsResult<u32>;
 
+    /// 获取最近创建的 Active 密钥 ID
+    ///
+    /// # Returns
+    /// * `Ok(Some(key_id))` - 存在 Active 密钥
+    /// * `Ok(None)` - 没有 Active 密钥
+    async fn get_latest_active_key_id(&self) -> KsResult<Option<u32>>;
+
+    /// 列出所有 Active 密钥
+    ///
+    /// # Returns
+    /// `(key_id, created_at)` 列表，按 key_id 升序
+    async fn list_active_keys(&self) -> KsResult<Vec<(u32, u64)>>;
+
+    /// 将 Active 密钥转为仅验证
+    ///
+    /// # Arguments
+    /// * `key_id` - 密钥 ID
+    /// * `retired_at` - 轮替时间（Unix 时间戳）
+    /// * `verify_until` - 宽限期截止时间（Unix 时间戳）
+    ///
+    /// # Returns
+    /// * `Ok(true)` - 已转为仅验证
+    /// * `Ok(false)` - 密钥不存在或已不是 Active
+    async fn retire_key(&self, key_id: u32, retired_at: u64, verify_until: u64) -> KsResult<bool>;
+
+    /// 吊销密钥（Active 与仅验证的密钥均可吊销）
+    ///
+    /// # Arguments
+    /// * `key_id` - 密钥 ID
+    /// * `revoked_at` - 吊销时间（Unix 时间戳）
+    ///
+    /// # Returns
+    /// * `Ok(true)` - 已吊销
+    /// * `Ok(false)` - 密钥不存在或已被吊销
+    async fn revoke_key(&self, key_id: u32, revoked_at: u64) -> KsResult<bool>;
+
+    /// 列出所有已吊销且尚未清理的密钥
+    ///
+    /// # Returns
+    /// `(key_id, revoked_at)` 列表，按 key_id 升序
+    async fn list_revoked_keys(&self) -> KsResult<Vec<(u32, u64)>>;
+
     /// 清理过期的密钥
     ///
-    /// 删除所有已过期的密钥记录（expires_at > 0 且 < 当前时间）
+    /// 删除所有已过期的密钥记录（expires_at > 0 且 < 当前时间），
+    /// 仅验证的密钥在宽限期结束前保留、结束后删除；
+    /// 吊销的密钥同样保留到过期，期间作为吊销名单下发给验证方
     ///
     /// # Returns
     /// 被清理的密钥数量
@@ -0,0 +1,230 @@
+//! 密钥读取缓存
+//!
+//! 为 [`super::KeyStorage`] 提供进程内的读穿透缓存：密钥记录与解密后的私钥按 key_id
+//! 缓存，不存在的密钥以较短的 TTL 负缓存，避免验证方反复请求无效 key_id 时穿透到后端。
+//!
+//! 写操作（生成、轮替、吊销、清理）经由 `KeyStorage` 使对应条目失效。失效会递增代数，
+//! 读取期间发生失效时本次读取结果不写回缓存，避免并发读取把失效前的旧记录重新写入。
+
+use super::config::CacheConfig;
+use crate::types::KeyRecord;
+use moka::Expiry;
+use moka::future::Cache;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 按条目是否存在选择 TTL
+struct CacheExpiry {
+    ttl: Duration,
+    negative_ttl: Duration,
+}
+
+impl<V> Expiry<u32, Option<V>> for CacheExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &u32,
+        value: &Option<V>,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(if value.is_some() {
+            self.ttl
+        } else {
+            self.negative_ttl
+        })
+    }
+
+    fn expire_after_update(
+        &self,
+        key: &u32,
+        value: &Option<V>,
+        updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        self.expire_after_create(key, value, updated_at)
+    }
+}
+
+/// 密钥记录与私钥缓存
+#[derive(Clone)]
+pub struct KeyCache {
+    records: Cache<u32, Option<KeyRecord>>,
+    secrets: Cache<u32, Option<String>>,
+    negative_caching: bool,
+    generation: Arc<AtomicU64>,
+}
+
+impl std::fmt::Debug for KeyCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出缓存的私钥
+        f.debug_struct("KeyCache")
+            .field("records", &self.records.entry_count())
+            .field("secrets", &self.secrets.entry_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyCache {
+    /// 根据配置创建缓存，未启用或 `ttl_seconds` 为 0 时返回 None
+    pub fn from_config(config: &CacheConfig) -> Option<Self> {
+        if !config.enabled || config.ttl_seconds == 0 {
+            return None;
+        }
+
+        let ttl = Duration::from_secs(config.ttl_seconds);
+        let negative_ttl = Duration::from_secs(config.negative_ttl_seconds);
+
+        Some(Self {
+            records: Cache::builder()
+                .max_capacity(config.max_capacity)
+                .expire_after(CacheExpiry { ttl, negative_ttl })
+                .build(),
+            secrets: Cache::builder()
+                .max_capacity(config.max_capacity)
+                .expire_after(CacheExpiry { ttl, negative_ttl })
+                .build(),
+            negative_caching: config.negative_ttl_seconds > 0,
+            generation: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// 当前失效代数，读取后端前获取，写回时传入
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// 缓存的密钥记录（外层 None 表示未命中）
+    pub async fn get_record(&self, key_id: u32) -> Option<Option<KeyRecord>> {
+        self.records.get(&key_id).await
+    }
+
+    /// 缓存的私钥（外层 None 表示未命中）
+    pub async fn get_secret(&self, key_id: u32) -> Option<Option<String>> {
+        self.secrets.get(&key_id).await
+    }
+
+    /// 写回密钥记录，读取期间发生过失效时忽略
+    pub async fn insert_record(&self, key_id: u32, record: Option<KeyRecord>, generation: u64) {
+        if self.should_insert(record.is_some(), generation) {
+            self.records.insert(key_id, record).await;
+            // 写入与失效并发时撤销写入
+            if self.generation() != generation {
+                self.records.invalidate(&key_id).await;
+            }
+        }
+    }
+
+    /// 写回私钥，读取期间发生过失效时忽略
+    pub async fn insert_secret(&self, key_id: u32, secret: Option<String>, generation: u64) {
+        if self.should_insert(secret.is_some(), generation) {
+            self.secrets.insert(key_id, secret).await;
+            if self.generation() != generation {
+                self.secrets.invalidate(&key_id).await;
+            }
+        }
+    }
+
+    /// 使指定密钥的缓存失效
+    pub async fn invalidate(&self, key_id: u32) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.records.invalidate(&key_id).await;
+        self.secrets.invalidate(&key_id).await;
+    }
+
+    /// 使全部缓存失效
+    pub fn invalidate_all(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.records.invalidate_all();
+        self.secrets.invalidate_all();
+    }
+
+    fn should_insert(&self, found: bool, generation: u64) -> bool {
+        (found || self.negative_caching) && self.generation() == generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KeyAlgorithm, KeyStatus};
+
+    fn record(key_id: u32) -> KeyRecord {
+        KeyRecord {
+            key_id,
+            algorithm: KeyAlgorithm::Secp256k1,
+            public_key: "public".to_string(),
+            created_at: 0,
+            expires_at: 0,
+            status: KeyStatus::Active,
+            retired_at: 0,
+            verify_until: 0,
+            revoked_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_disabled_cache() {
+        let disabled = CacheConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        assert!(KeyCache::from_config(&disabled).is_none());
+
+        let zero_ttl = CacheConfig {
+            ttl_seconds: 0,
+            ..Default::default()
+        };
+        assert!(KeyCache::from_config(&zero_ttl).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_invalidate() {
+        let cache = KeyCache::from_config(&CacheConfig::default()).unwrap();
+
+        let generation = cache.generation();
+        cache.insert_record(1, Some(record(1)), generation).await;
+        cache.insert_record(2, None, generation).await;
+        assert_eq!(cache.get_record(1).await, Some(Some(record(1))));
+        assert_eq!(cache.get_record(2).await, Some(None));
+        assert_eq!(cache.get_record(3).await, None);
+
+        cache.invalidate(1).await;
+        assert_eq!(cache.get_record(1).await, None);
+        assert_eq!(cache.get_record(2).await, Some(None));
+
+        cache.invalidate_all();
+        assert_eq!(cache.get_record(2).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_stale_read_not_written_back() {
+        let cache = KeyCache::from_config(&CacheConfig::default()).unwrap();
+
+        // 读取后端期间发生失效，旧结果不写回
+        let generation = cache.generation();
+        cache.invalidate(1).await;
+        cache
+            .insert_secret(1, Some("secret".to_string()), generation)
+            .await;
+        assert_eq!(cache.get_secret(1).await, None);
+
+        let generation = cache.generation();
+        cache
+            .insert_secret(1, Some("secret".to_string()), generation)
+            .await;
+        assert_eq!(cache.get_secret(1).await, Some(Some("secret".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_negative_caching_disabled() {
+        let cache = KeyCache::from_config(&CacheConfig {
+            negative_ttl_seconds: 0,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let generation = cache.generation();
+        cache.insert_record(1, None, generation).await;
+        assert_eq!(cache.get_record(1).await, None);
+    }
+}
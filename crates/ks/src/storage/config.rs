@@ -23,6 +23,14 @@ pub struct StorageConfig {
     /// PostgreSQL 配置（当 backend = "postgres" 时必需）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub postgres: Option<PostgresConfig>,
+
+    /// etcd 配置（当 backend = "etcd" 时必需）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etcd: Option<EtcdConfig>,
+
+    /// 读取缓存配置
+    #[serde(default)]
+    pub cache: CacheConfig,
 }
 
 impl Default for StorageConfig {
@@ -32,6 +40,8 @@ impl Default for StorageConfig {
             key_ttl_seconds: 3600,
             sqlite: Some(SqliteConfig::default()),
             postgres: None,
+            etcd: None,
+            cache: CacheConfig::default(),
         }
     }
 }
@@ -44,6 +54,8 @@ pub enum StorageBackend {
     Sqlite,
     /// PostgreSQL 数据库
     Postgres,
+    /// etcd 集群（跨数据中心高可用）
+    Etcd,
 }
 
 /// SQLite 配置
@@ -51,7 +63,13 @@ pub enum StorageBackend {
 /// 注意：数据库路径通过 KeyStorage::from_config 的 db_path 参数传入
 /// TODO: define grain config for sqlite
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct SqliteConfig {}
+pub struct SqliteConfig {
+    /// 使用进程内存数据库（`sqlite::memory:`），不创建 `ks_keys.db`，重启后密钥丢失
+    ///
+    /// 全局 `storage = "memory"` 时自动启用
+    #[serde(default)]
+    pub in_memory: bool,
+}
 
 /// PostgreSQL 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +112,109 @@ impl Default for PostgresConfig {
     }
 }
 
+/// etcd 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EtcdConfig {
+    /// 集群节点地址，例如 ["http://etcd-1:2379", "http://etcd-2:2379"]
+    pub endpoints: Vec<String>,
+
+    /// 键前缀，同一 etcd 集群中的多套 KS 以不同前缀隔离
+    #[serde(default = "default_etcd_key_prefix")]
+    pub key_prefix: String,
+
+    /// 用户名（etcd 启用认证时）
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// 密码
+    #[serde(default)]
+    pub password: Option<String>,
+
+    /// 连接超时（秒）
+    #[serde(default = "default_etcd_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// 请求超时（秒）
+    #[serde(default = "default_etcd_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+}
+
+impl Default for EtcdConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: vec!["http://localhost:2379".to_string()],
+            key_prefix: default_etcd_key_prefix(),
+            username: None,
+            password: None,
+            connect_timeout_secs: default_etcd_connect_timeout_secs(),
+            request_timeout_secs: default_etcd_request_timeout_secs(),
+        }
+    }
+}
+
+/// 读取缓存配置
+///
+/// 缓存 GetSecretKey 热路径上的密钥记录与解密后的私钥，不存在的密钥按
+/// `negative_ttl_seconds` 负缓存。本进程内的轮替与吊销会立即使对应条目失效；
+/// 多个 KS 实例共享 PostgreSQL / etcd 时，其他实例上的变更最多在 `ttl_seconds` 后可见。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// 是否启用缓存
+    #[serde(default = "default_cache_enabled")]
+    pub enabled: bool,
+
+    /// 已存在密钥的缓存时间（秒），0 表示不缓存
+    #[serde(default = "default_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+
+    /// 不存在密钥的负缓存时间（秒），0 表示不做负缓存
+    #[serde(default = "default_cache_negative_ttl_seconds")]
+    pub negative_ttl_seconds: u64,
+
+    /// 最大缓存条目数
+    #[serde(default = "default_cache_max_capacity")]
+    pub max_capacity: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_cache_enabled(),
+            ttl_seconds: default_cache_ttl_seconds(),
+            negative_ttl_seconds: default_cache_negative_ttl_seconds(),
+            max_capacity: default_cache_max_capacity(),
+        }
+    }
+}
+
+fn default_cache_enabled() -> bool {
+    true
+}
+
+fn default_cache_ttl_seconds() -> u64 {
+    30
+}
+
+fn default_cache_negative_ttl_seconds() -> u64 {
+    5
+}
+
+fn default_cache_max_capacity() -> u64 {
+    10_000
+}
+
+fn default_etcd_key_prefix() -> String {
+    "/actrix/ks/".to_string()
+}
+
+fn default_etcd_connect_timeout_secs() -> u64 {
+    5
+}
+
+fn default_etcd_request_timeout_secs() -> u64 {
+    10
+}
+
 fn default_postgres_pool_size() -> u32 {
     20
 }
@@ -119,8 +240,10 @@ mod tests {
         let config = StorageConfig {
             backend: StorageBackend::Sqlite,
             key_ttl_seconds: 7200,
-            sqlite: Some(SqliteConfig {}),
+            sqlite: Some(SqliteConfig::default()),
             postgres: None,
+            etcd: None,
+            cache: Default::default(),
         };
 
         let toml = toml::to_string(&config).unwrap();
@@ -155,4 +278,27 @@ mod tests {
         assert_eq!(postgres.password, "secret");
         assert_eq!(postgres.pool_size, 30);
     }
+
+    #[test]
+    fn test_deserialize_etcd_config() {
+        let toml_str = r#"
+            backend = "etcd"
+            key_ttl_seconds = 3600
+
+            [etcd]
+            endpoints = ["http://etcd-1:2379", "http://etcd-2:2379"]
+            username = "actrix"
+            password = "secret"
+        "#;
+
+        let config: StorageConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.backend, StorageBackend::Etcd);
+
+        let etcd = config.etcd.unwrap();
+        assert_eq!(etcd.endpoints.len(), 2);
+        assert_eq!(etcd.key_prefix, "/actrix/ks/");
+        assert_eq!(etcd.username.as_deref(), Some("actrix"));
+        assert_eq!(etcd.connect_timeout_secs, 5);
+        assert_eq!(etcd.request_timeout_secs, 10);
+    }
 }
@@ -0,0 +1,617 @@
+//! etcd 存储后端实现
+//!
+//! 密钥记录以 JSON 形式保存在 etcd 中，多个数据中心的 KS 实例共享同一个 etcd 集群，
+//! 任一实例宕机时其余实例仍可提供完整的密钥数据。
+//!
+//! # 键布局
+//!
+//! - `{key_prefix}keys/{key_id:010}`：密钥记录（零填充，字典序即 key_id 顺序）
+//! - `{key_prefix}next_key_id`：下一个可分配的 key_id
+//!
+//! key_id 分配与状态变更都通过比较 `mod_revision` 的事务完成：多个 KS 实例并发写入时
+//! 冲突的一方重新读取后重试，保证 key_id 唯一且状态转换不会互相覆盖。
+
+use crate::crypto::KeyEncryptor;
+use crate::error::{KsError, KsResult};
+use crate::keygen::generate_keypair;
+use crate::storage::backend::KeyStorageBackend;
+use crate::storage::config::EtcdConfig;
+use crate::types::{KeyAlgorithm, KeyPair, KeyRecord, KeyStatus};
+use async_trait::async_trait;
+use etcd_client::{Client, Compare, CompareOp, ConnectOptions, GetOptions, KvClient, Txn, TxnOp};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, trace};
+
+/// 事务冲突时的最大重试次数
+const MAX_TXN_RETRIES: usize = 16;
+
+/// etcd 存储后端
+#[derive(Clone)]
+pub struct EtcdBackend {
+    kv: KvClient,
+    key_prefix: String,
+    key_ttl: u64,
+    encryptor: KeyEncryptor,
+}
+
+impl std::fmt::Debug for EtcdBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EtcdBackend")
+            .field("key_prefix", &self.key_prefix)
+            .field("key_ttl", &self.key_ttl)
+            .field("encryption_enabled", &self.encryptor.is_enabled())
+            .finish()
+    }
+}
+
+/// etcd 中保存的密钥记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredKey {
+    key_id: u32,
+    public_key: String,
+    /// 私钥（配置 KEK 时为密文）
+    secret_key: String,
+    created_at: u64,
+    expires_at: u64,
+    status: String,
+    #[serde(default)]
+    retired_at: u64,
+    #[serde(default)]
+    verify_until: u64,
+    #[serde(default)]
+    revoked_at: u64,
+    /// 旧记录没有该字段，按 secp256k1 处理
+    #[serde(default)]
+    algorithm: KeyAlgorithm,
+}
+
+impl StoredKey {
+    fn status(&self) -> KeyStatus {
+        KeyStatus::from_db(&self.status)
+    }
+
+    fn to_record(&self) -> KeyRecord {
+        KeyRecord {
+            key_id: self.key_id,
+            algorithm: self.algorithm,
+            public_key: self.public_key.clone(),
+            created_at: self.created_at,
+            expires_at: self.expires_at,
+            status: self.status(),
+            retired_at: self.retired_at,
+            verify_until: self.verify_until,
+            revoked_at: self.revoked_at,
+        }
+    }
+
+    /// 清理条件与 SQLite / PostgreSQL 后端一致：仅验证的密钥在宽限期结束后删除
+    fn is_expired(&self, now: u64) -> bool {
+        (self.expires_at > 0 && self.expires_at < now && self.verify_until < now)
+            || (self.verify_until > 0 && self.verify_until < now)
+    }
+}
+
+fn etcd_error(context: &str, e: etcd_client::Error) -> KsError {
+    KsError::Internal(format!("etcd {context} failed: {e}"))
+}
+
+fn decode_record(value: &[u8]) -> KsResult<StoredKey> {
+    serde_json::from_slice(value)
+        .map_err(|e| KsError::Internal(format!("Invalid key record in etcd: {e}")))
+}
+
+fn encode_record(record: &StoredKey) -> KsResult<Vec<u8>> {
+    serde_json::to_vec(record)
+        .map_err(|e| KsError::Internal(format!("Failed to encode key record: {e}")))
+}
+
+impl EtcdBackend {
+    /// 创建新的 etcd 后端实例
+    ///
+    /// # Arguments
+    /// * `config` - etcd 配置
+    /// * `key_ttl` - 密钥有效期（秒），0 表示永不过期
+    /// * `encryptor` - 密钥加密器
+    pub async fn new(config: &EtcdConfig, key_ttl: u64, encryptor: KeyEncryptor) -> KsResult<Self> {
+        if config.endpoints.is_empty() {
+            return Err(KsError::Config("etcd endpoints must not be empty".into()));
+        }
+
+        let mut options = ConnectOptions::new()
+            .with_connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+            .with_timeout(Duration::from_secs(config.request_timeout_secs));
+        if let Some(username) = &config.username {
+            options = options.with_user(
+                username.clone(),
+                config.password.clone().unwrap_or_default(),
+            );
+        }
+
+        let client = Client::connect(config.endpoints.clone(), Some(options))
+            .await
+            .map_err(|e| KsError::Internal(format!("Failed to connect to etcd: {e}")))?;
+
+        let backend = Self {
+            kv: client.kv_client(),
+            key_prefix: config.key_prefix.clone(),
+            key_ttl,
+            encryptor,
+        };
+
+        backend.init().await?;
+
+        info!(
+            "etcd storage initialized: endpoints={:?}, key_prefix={}, key_ttl={}s, encryption={}",
+            config.endpoints,
+            config.key_prefix,
+            key_ttl,
+            backend.encryptor.is_enabled()
+        );
+
+        Ok(backend)
+    }
+
+    fn keys_prefix(&self) -> String {
+        format!("{}keys/", self.key_prefix)
+    }
+
+    fn record_key(&self, key_id: u32) -> String {
+        format!("{}keys/{key_id:010}", self.key_prefix)
+    }
+
+    fn counter_key(&self) -> String {
+        format!("{}next_key_id", self.key_prefix)
+    }
+
+    /// 读取单条记录及其 mod_revision
+    async fn load(&self, key_id: u32) -> KsResult<Option<(StoredKey, i64)>> {
+        let response = self
+            .kv
+            .clone()
+            .get(self.record_key(key_id), None)
+            .await
+            .map_err(|e| etcd_error("get key record", e))?;
+
+        match response.kvs().first() {
+            Some(kv) => Ok(Some((decode_record(kv.value())?, kv.mod_revision()))),
+            None => Ok(None),
+        }
+    }
+
+    /// 读取全部记录（按 key_id 升序）
+    async fn load_all(&self) -> KsResult<Vec<(StoredKey, i64)>> {
+        let response = self
+            .kv
+            .clone()
+            .get(self.keys_prefix(), Some(GetOptions::new().with_prefix()))
+            .await
+            .map_err(|e| etcd_error("list key records", e))?;
+
+        response
+            .kvs()
+            .iter()
+            .map(|kv| Ok((decode_record(kv.value())?, kv.mod_revision())))
+            .collect()
+    }
+
+    /// 在记录未被并发修改的前提下应用状态变更
+    ///
+    /// `apply` 返回 false 表示记录不满足变更条件；冲突时重新读取后重试
+    async fn update_record<F>(&self, key_id: u32, apply: F) -> KsResult<bool>
+    where
+        F: Fn(&mut StoredKey) -> bool + Send + Sync,
+    {
+        let key = self.record_key(key_id);
+
+        for _ in 0..MAX_TXN_RETRIES {
+            let Some((mut record, mod_revision)) = self.load(key_id).await? else {
+                return Ok(false);
+            };
+            if !apply(&mut record) {
+                return Ok(false);
+            }
+
+            let txn = Txn::new()
+                .when(vec![Compare::mod_revision(
+                    key.clone(),
+                    CompareOp::Equal,
+                    mod_revision,
+                )])
+                .and_then(vec![TxnOp::put(key.clone(), encode_record(&record)?, None)]);
+            let response = self
+                .kv
+                .clone()
+                .txn(txn)
+                .await
+                .map_err(|e| etcd_error("update key record", e))?;
+            if response.succeeded() {
+                return Ok(true);
+            }
+
+            debug!("Key {} was modified concurrently in etcd, retrying", key_id);
+        }
+
+        Err(KsError::Internal(format!(
+            "Too many concurrent updates of key {key_id} in etcd"
+        )))
+    }
+}
+
+#[async_trait]
+impl KeyStorageBackend for EtcdBackend {
+    async fn init(&self) -> KsResult<()> {
+        // etcd 无需建表，读取计数器以确认连接与访问权限
+        self.kv
+            .clone()
+            .get(self.counter_key(), None)
+            .await
+            .map_err(|e| etcd_error("read key_id counter", e))?;
+
+        debug!("etcd key prefix {} is accessible", self.key_prefix);
+        Ok(())
+    }
+
+    async fn generate_and_store_key(&self, algorithm: KeyAlgorithm) -> KsResult<KeyPair> {
+        // 按算法生成密钥对（Base64 编码）
+        let (secret_key_b64, public_key_b64) = generate_keypair(algorithm);
+
+        // 加密私钥（如果启用了 KEK）
+        let encrypted_secret_key = self.encryptor.encrypt(&secret_key_b64)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // 计算过期时间
+        let expires_at = if self.key_ttl == 0 {
+            0 // 永不过期
+        } else {
+            now + self.key_ttl
+        };
+
+        let counter_key = self.counter_key();
+        let mut kv = self.kv.clone();
+
+        for _ in 0..MAX_TXN_RETRIES {
+            let response = kv
+                .get(counter_key.clone(), None)
+                .await
+                .map_err(|e| etcd_error("read key_id counter", e))?;
+
+            // 计数器不存在时从 1 开始分配，与 SQLite / PostgreSQL 自增主键一致
+            let (key_id, guard) = match response.kvs().first() {
+                Some(counter) => {
+                    let next = counter
+                        .value_str()
+                        .ok()
+                        .and_then(|value| value.parse::<u32>().ok())
+                        .ok_or_else(|| {
+                            KsError::Internal("Invalid key_id counter in etcd".into())
+                        })?;
+                    (
+                        next,
+                        Compare::mod_revision(
+                            counter_key.clone(),
+                            CompareOp::Equal,
+                            counter.mod_revision(),
+                        ),
+                    )
+                }
+                None => (
+                    1,
+                    Compare::version(counter_key.clone(), CompareOp::Equal, 0),
+                ),
+            };
+
+            let record = StoredKey {
+                key_id,
+                public_key: public_key_b64.clone(),
+                secret_key: encrypted_secret_key.clone(),
+                created_at: now,
+                expires_at,
+                status: KeyStatus::Active.as_str().to_string(),
+                retired_at: 0,
+                verify_until: 0,
+                revoked_at: 0,
+                algorithm,
+            };
+
+            let txn = Txn::new().when(vec![guard]).and_then(vec![
+                TxnOp::put(counter_key.clone(), (key_id + 1).to_string(), None),
+                TxnOp::put(self.record_key(key_id), encode_record(&record)?, None),
+            ]);
+            let response = kv.txn(txn).await.map_err(|e| etcd_error("store key", e))?;
+
+            if response.succeeded() {
+                info!(
+                    "Generated and stored new key pair in etcd: key_id={}, expires_at={}",
+                    key_id, expires_at
+                );
+
+                return Ok(KeyPair {
+                    key_id,
+                    algorithm,
+                    secret_key: secret_key_b64,
+                    public_key: public_key_b64,
+                });
+            }
+
+            debug!(
+                "key_id {} was allocated concurrently in etcd, retrying",
+                key_id
+            );
+        }
+
+        Err(KsError::Internal(
+            "Too many concurrent key_id allocations in etcd".into(),
+        ))
+    }
+
+    async fn get_public_key(&self, key_id: u32) -> KsResult<Option<String>> {
+        let result = self
+            .load(key_id)
+            .await?
+            .map(|(record, _)| record.public_key);
+
+        if result.is_some() {
+            debug!("Found public key for key_id: {} in etcd", key_id);
+        } else {
+            debug!("No public key found for key_id: {} in etcd", key_id);
+        }
+
+        Ok(result)
+    }
+
+    async fn get_secret_key(&self, key_id: u32) -> KsResult<Option<String>> {
+        match self.load(key_id).await? {
+            Some((record, _)) => {
+                trace!("Secret key found in etcd");
+                // 解密私钥（如果启用了 KEK）
+                Ok(Some(self.encryptor.decrypt(&record.secret_key)?))
+            }
+            None => {
+                trace!("Secret key not found in etcd");
+                Ok(None)
+            }
+        }
+    }
+
+    async fn get_key_record(&self, key_id: u32) -> KsResult<Option<KeyRecord>> {
+        Ok(self
+            .load(key_id)
+            .await?
+            .map(|(record, _)| record.to_record()))
+    }
+
+    async fn get_key_count(&self) -> KsResult<u32> {
+        let response = self
+            .kv
+            .clone()
+            .get(
+                self.keys_prefix(),
+                Some(GetOptions::new().with_prefix().with_count_only()),
+            )
+            .await
+            .map_err(|e| etcd_error("count keys", e))?;
+
+        Ok(response.count() as u32)
+    }
+
+    async fn get_latest_active_key_id(&self) -> KsResult<Option<u32>> {
+        Ok(self
+            .load_all()
+            .await?
+            .into_iter()
+            .rev()
+            .find(|(record, _)| record.status() == KeyStatus::Active)
+            .map(|(record, _)| record.key_id))
+    }
+
+    async fn list_active_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        let mut keys: Vec<(u32, u64)> = self
+            .load_all()
+            .await?
+            .into_iter()
+            .filter(|(record, _)| record.status() == KeyStatus::Active)
+            .map(|(record, _)| (record.key_id, record.created_at))
+            .collect();
+        keys.sort_unstable_by_key(|(key_id, _)| *key_id);
+        Ok(keys)
+    }
+
+    async fn retire_key(&self, key_id: u32, retired_at: u64, verify_until: u64) -> KsResult<bool> {
+        self.update_record(key_id, |record| {
+            if record.status() != KeyStatus::Active {
+                return false;
+            }
+            record.status = KeyStatus::VerifyOnly.as_str().to_string();
+            record.retired_at = retired_at;
+            record.verify_until = verify_until;
+            true
+        })
+        .await
+    }
+
+    async fn revoke_key(&self, key_id: u32, revoked_at: u64) -> KsResult<bool> {
+        self.update_record(key_id, |record| {
+            if record.status() == KeyStatus::Revoked {
+                return false;
+            }
+            record.status = KeyStatus::Revoked.as_str().to_string();
+            record.revoked_at = revoked_at;
+            true
+        })
+        .await
+    }
+
+    async fn list_revoked_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        Ok(self
+            .load_all()
+            .await?
+            .into_iter()
+            .filter(|(record, _)| record.status() == KeyStatus::Revoked)
+            .map(|(record, _)| (record.key_id, record.revoked_at))
+            .collect())
+    }
+
+    async fn cleanup_expired_keys(&self) -> KsResult<u32> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut kv = self.kv.clone();
+        let mut deleted_count = 0;
+
+        for (record, mod_revision) in self.load_all().await? {
+            if !record.is_expired(now) {
+                continue;
+            }
+
+            // 记录在读取后被修改（例如另一实例刚处理过）时跳过，下一轮清理再判断
+            let key = self.record_key(record.key_id);
+            let txn = Txn::new()
+                .when(vec![Compare::mod_revision(
+                    key.clone(),
+                    CompareOp::Equal,
+                    mod_revision,
+                )])
+                .and_then(vec![TxnOp::delete(key, None)]);
+            let response = kv
+                .txn(txn)
+                .await
+                .map_err(|e| etcd_error("delete expired key", e))?;
+            if response.succeeded() {
+                deleted_count += 1;
+            }
+        }
+
+        if deleted_count > 0 {
+            info!("Cleaned up {} expired keys from etcd", deleted_count);
+        }
+
+        Ok(deleted_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_key(status: KeyStatus) -> StoredKey {
+        StoredKey {
+            key_id: 7,
+            public_key: "pub".to_string(),
+            secret_key: "secret".to_string(),
+            created_at: 1_000,
+            expires_at: 2_000,
+            status: status.as_str().to_string(),
+            retired_at: 0,
+            verify_until: 0,
+            revoked_at: 0,
+            algorithm: KeyAlgorithm::Secp256k1,
+        }
+    }
+
+    #[test]
+    fn test_stored_key_expiry_matches_sql_backends() {
+        let active = stored_key(KeyStatus::Active);
+        assert!(!active.is_expired(1_500));
+        assert!(active.is_expired(2_001));
+
+        // 仅验证的密钥在宽限期内保留
+        let verify_only = StoredKey {
+            retired_at: 1_500,
+            verify_until: 2_500,
+            ..stored_key(KeyStatus::VerifyOnly)
+        };
+        assert!(!verify_only.is_expired(2_001));
+        assert!(verify_only.is_expired(2_501));
+
+        let never_expires = StoredKey {
+            expires_at: 0,
+            ..stored_key(KeyStatus::Active)
+        };
+        assert!(!never_expires.is_expired(u64::MAX));
+    }
+
+    #[test]
+    fn test_stored_key_roundtrip() {
+        let record = StoredKey {
+            revoked_at: 1_800,
+            algorithm: KeyAlgorithm::Ed25519,
+            ..stored_key(KeyStatus::Revoked)
+        };
+        let decoded = decode_record(&encode_record(&record).unwrap()).unwrap();
+        let key_record = decoded.to_record();
+        assert_eq!(key_record.key_id, 7);
+        assert_eq!(key_record.status, KeyStatus::Revoked);
+        assert_eq!(key_record.revoked_at, 1_800);
+        assert_eq!(key_record.algorithm, KeyAlgorithm::Ed25519);
+
+        // 引入算法字段前写入的记录按 secp256k1 读取
+        let legacy = br#"{"key_id":1,"public_key":"pub","secret_key":"secret","created_at":1,"expires_at":0,"status":"active"}"#;
+        assert_eq!(
+            decode_record(legacy).unwrap().algorithm,
+            KeyAlgorithm::Secp256k1
+        );
+    }
+
+    async fn create_test_backend() -> EtcdBackend {
+        let config = EtcdConfig {
+            endpoints: vec![
+                std::env::var("ETCD_ENDPOINT")
+                    .unwrap_or_else(|_| "http://localhost:2379".to_string()),
+            ],
+            key_prefix: format!("/actrix-test/ks/{}/", uuid::Uuid::new_v4()),
+            ..EtcdConfig::default()
+        };
+
+        EtcdBackend::new(&config, 3600, KeyEncryptor::no_encryption())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore] // 需要 etcd 服务器
+    async fn test_generate_rotate_and_revoke() {
+        let backend = create_test_backend().await;
+        assert_eq!(backend.get_key_count().await.unwrap(), 0);
+
+        let first = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        let second = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert_eq!(second.key_id, first.key_id + 1);
+        assert_eq!(backend.get_key_count().await.unwrap(), 2);
+        assert_eq!(
+            backend.get_secret_key(first.key_id).await.unwrap(),
+            Some(first.secret_key)
+        );
+        assert_eq!(
+            backend.get_latest_active_key_id().await.unwrap(),
+            Some(second.key_id)
+        );
+
+        assert!(backend.retire_key(second.key_id, 10, 20).await.unwrap());
+        assert!(!backend.retire_key(second.key_id, 10, 20).await.unwrap());
+        assert_eq!(
+            backend.get_latest_active_key_id().await.unwrap(),
+            Some(first.key_id)
+        );
+
+        assert!(backend.revoke_key(first.key_id, 30).await.unwrap());
+        assert!(!backend.revoke_key(first.key_id, 30).await.unwrap());
+        assert_eq!(
+            backend.list_revoked_keys().await.unwrap(),
+            vec![(first.key_id, 30)]
+        );
+        assert_eq!(backend.get_public_key(99999).await.unwrap(), None);
+    }
+}
@@ -1,16 +1,17 @@
 //! KS 存储模块
 //!
-//! 提供多种存储后端支持：SQLite, PostgreSQL
+//! 提供多种存储后端支持：SQLite, PostgreSQL, etcd
 //!
 //! # 设计
 //!
 //! - `KeyStorageBackend` trait 定义统一的异步接口
-//! - `KeyStorage` enum 封装不同的后端实现
+//! - `KeyStorage` 以 enum 封装不同的后端实现，并在其上提供读取缓存（见 [`CacheConfig`]）
 //! - 通过 `StorageConfig` 配置选择和初始化后端
 
 use std::path::Path;
 
 pub mod backend;
+mod cache;
 pub mod config;
 
 // SQLite 始终可用（使用 rusqlite）
@@ -19,32 +20,55 @@ pub mod sqlite;
 #[cfg(feature = "backend-postgres")]
 pub mod postgres;
 
+#[cfg(feature = "backend-etcd")]
+pub mod etcd;
+
 use crate::crypto::KeyEncryptor;
 use crate::error::{KsError, KsResult};
-use crate::types::{KeyPair, KeyRecord};
+use crate::types::{KeyAlgorithm, KeyPair, KeyRecord};
 
 pub use backend::KeyStorageBackend;
-pub use config::{PostgresConfig, SqliteConfig, StorageBackend, StorageConfig};
+pub use config::{
+    CacheConfig, EtcdConfig, PostgresConfig, SqliteConfig, StorageBackend, StorageConfig,
+};
 
+use cache::KeyCache;
 use sqlite::SqliteBackend;
 
 #[cfg(feature = "backend-postgres")]
 use postgres::PostgresBackend;
 
+#[cfg(feature = "backend-etcd")]
+use etcd::EtcdBackend;
+
 /// 密钥存储统一接口
 ///
+/// 封装存储后端，`get_key_record` 与 `get_secret_key` 经读取缓存（未禁用时），
+/// 写操作使对应的缓存条目失效
+#[derive(Clone, Debug)]
+pub struct KeyStorage {
+    backend: Backend,
+    cache: Option<KeyCache>,
+}
+
+/// 存储后端
+///
 /// 使用 enum 而不是 trait object 的好处：
 /// - 零成本抽象（无虚函数调用）
 /// - 可以 Clone
 /// - 编译期类型检查
 #[derive(Clone, Debug)]
-pub enum KeyStorage {
+enum Backend {
     /// SQLite 存储后端（始终可用）
     Sqlite(Box<SqliteBackend>),
 
     /// PostgreSQL 存储后端
     #[cfg(feature = "backend-postgres")]
     Postgres(PostgresBackend),
+
+    /// etcd 存储后端
+    #[cfg(feature = "backend-etcd")]
+    Etcd(Box<EtcdBackend>),
 }
 
 impl KeyStorage {
@@ -67,6 +91,18 @@ impl KeyStorage {
         encryptor: KeyEncryptor,
         db_path: P,
     ) -> KsResult<Self> {
+        let backend = Self::create_backend(config, encryptor, db_path.as_ref()).await?;
+        Ok(Self {
+            backend,
+            cache: KeyCache::from_config(&config.cache),
+        })
+    }
+
+    async fn create_backend(
+        config: &StorageConfig,
+        encryptor: KeyEncryptor,
+        db_path: &Path,
+    ) -> KsResult<Backend> {
         match config.backend {
             StorageBackend::Sqlite => {
                 let cfg = config
@@ -74,9 +110,8 @@ impl KeyStorage {
                     .as_ref()
                     .ok_or_else(|| KsError::Config("Missing SQLite config".into()))?;
                 let backend =
-                    SqliteBackend::new(cfg, config.key_ttl_seconds, encryptor, db_path.as_ref())
-                        .await?;
-                Ok(Self::Sqlite(Box::new(backend)))
+                    SqliteBackend::new(cfg, config.key_ttl_seconds, encryptor, db_path).await?;
+                Ok(Backend::Sqlite(Box::new(backend)))
             }
 
             #[cfg(feature = "backend-postgres")]
@@ -86,83 +121,249 @@ impl KeyStorage {
                     .as_ref()
                     .ok_or_else(|| KsError::Config("Missing PostgreSQL config".into()))?;
                 let backend = PostgresBackend::new(cfg, config.key_ttl_seconds).await?;
-                Ok(Self::Postgres(backend))
+                Ok(Backend::Postgres(backend))
             }
 
             #[cfg(not(feature = "backend-postgres"))]
             StorageBackend::Postgres => Err(KsError::Config(
                 "PostgreSQL backend not enabled. Compile with --features backend-postgres".into(),
             )),
+
+            #[cfg(feature = "backend-etcd")]
+            StorageBackend::Etcd => {
+                let cfg = config
+                    .etcd
+                    .as_ref()
+                    .ok_or_else(|| KsError::Config("Missing etcd config".into()))?;
+                let backend = EtcdBackend::new(cfg, config.key_ttl_seconds, encryptor).await?;
+                Ok(Backend::Etcd(Box::new(backend)))
+            }
+
+            #[cfg(not(feature = "backend-etcd"))]
+            StorageBackend::Etcd => Err(KsError::Config(
+                "etcd backend not enabled. Compile with --features backend-etcd".into(),
+            )),
         }
     }
 
-    /// 生成并存储新的密钥对
+    /// 生成并存储新的密钥对（默认算法 secp256k1）
     pub async fn generate_and_store_key(&self) -> KsResult<KeyPair> {
-        match self {
-            Self::Sqlite(b) => b.generate_and_store_key().await,
+        self.generate_and_store_key_with(KeyAlgorithm::default())
+            .await
+    }
+
+    /// 按指定算法生成并存储新的密钥对
+    pub async fn generate_and_store_key_with(&self, algorithm: KeyAlgorithm) -> KsResult<KeyPair> {
+        let key_pair = match &self.backend {
+            Backend::Sqlite(b) => b.generate_and_store_key(algorithm).await,
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(b) => b.generate_and_store_key().await,
-        }
+            Backend::Postgres(b) => b.generate_and_store_key(algorithm).await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.generate_and_store_key(algorithm).await,
+        }?;
+
+        // 清除生成前对该 key_id 的负缓存
+        self.invalidate(key_pair.key_id).await;
+        Ok(key_pair)
     }
 
     /// 根据 key_id 查询公钥
     pub async fn get_public_key(&self, key_id: u32) -> KsResult<Option<String>> {
-        match self {
-            Self::Sqlite(b) => b.get_public_key(key_id).await,
+        match &self.backend {
+            Backend::Sqlite(b) => b.get_public_key(key_id).await,
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(b) => b.get_public_key(key_id).await,
+            Backend::Postgres(b) => b.get_public_key(key_id).await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.get_public_key(key_id).await,
         }
     }
 
     /// 根据 key_id 查询私钥
     pub async fn get_secret_key(&self, key_id: u32) -> KsResult<Option<String>> {
-        match self {
-            Self::Sqlite(b) => b.get_secret_key(key_id).await,
+        let generation = match &self.cache {
+            Some(cache) => match cache.get_secret(key_id).await {
+                Some(secret_key) => return Ok(secret_key),
+                None => cache.generation(),
+            },
+            None => 0,
+        };
+
+        let secret_key = match &self.backend {
+            Backend::Sqlite(b) => b.get_secret_key(key_id).await,
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(b) => b.get_secret_key(key_id).await,
+            Backend::Postgres(b) => b.get_secret_key(key_id).await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.get_secret_key(key_id).await,
+        }?;
+
+        if let Some(cache) = &self.cache {
+            cache
+                .insert_secret(key_id, secret_key.clone(), generation)
+                .await;
         }
+        Ok(secret_key)
     }
 
     /// 获取完整的密钥记录
     pub async fn get_key_record(&self, key_id: u32) -> KsResult<Option<KeyRecord>> {
-        match self {
-            Self::Sqlite(b) => b.get_key_record(key_id).await,
+        let generation = match &self.cache {
+            Some(cache) => match cache.get_record(key_id).await {
+                Some(record) => return Ok(record),
+                None => cache.generation(),
+            },
+            None => 0,
+        };
+
+        let record = match &self.backend {
+            Backend::Sqlite(b) => b.get_key_record(key_id).await,
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(b) => b.get_key_record(key_id).await,
+            Backend::Postgres(b) => b.get_key_record(key_id).await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.get_key_record(key_id).await,
+        }?;
+
+        if let Some(cache) = &self.cache {
+            cache
+                .insert_record(key_id, record.clone(), generation)
+                .await;
         }
+        Ok(record)
     }
 
     /// 获取密钥总数
     pub async fn get_key_count(&self) -> KsResult<u32> {
-        match self {
-            Self::Sqlite(b) => b.get_key_count().await,
+        match &self.backend {
+            Backend::Sqlite(b) => b.get_key_count().await,
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(b) => b.get_key_count().await,
+            Backend::Postgres(b) => b.get_key_count().await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.get_key_count().await,
+        }
+    }
+
+    /// 获取最近创建的 Active 密钥 ID
+    pub async fn get_latest_active_key_id(&self) -> KsResult<Option<u32>> {
+        match &self.backend {
+            Backend::Sqlite(b) => b.get_latest_active_key_id().await,
+
+            #[cfg(feature = "backend-postgres")]
+            Backend::Postgres(b) => b.get_latest_active_key_id().await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.get_latest_active_key_id().await,
+        }
+    }
+
+    /// 列出所有 Active 密钥 `(key_id, created_at)`，按 key_id 升序
+    pub async fn list_active_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        match &self.backend {
+            Backend::Sqlite(b) => b.list_active_keys().await,
+
+            #[cfg(feature = "backend-postgres")]
+            Backend::Postgres(b) => b.list_active_keys().await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.list_active_keys().await,
+        }
+    }
+
+    /// 将 Active 密钥转为仅验证
+    pub async fn retire_key(
+        &self,
+        key_id: u32,
+        retired_at: u64,
+        verify_until: u64,
+    ) -> KsResult<bool> {
+        let result = match &self.backend {
+            Backend::Sqlite(b) => b.retire_key(key_id, retired_at, verify_until).await,
+
+            #[cfg(feature = "backend-postgres")]
+            Backend::Postgres(b) => b.retire_key(key_id, retired_at, verify_until).await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.retire_key(key_id, retired_at, verify_until).await,
+        };
+
+        self.invalidate(key_id).await;
+        result
+    }
+
+    /// 吊销密钥
+    pub async fn revoke_key(&self, key_id: u32, revoked_at: u64) -> KsResult<bool> {
+        let result = match &self.backend {
+            Backend::Sqlite(b) => b.revoke_key(key_id, revoked_at).await,
+
+            #[cfg(feature = "backend-postgres")]
+            Backend::Postgres(b) => b.revoke_key(key_id, revoked_at).await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.revoke_key(key_id, revoked_at).await,
+        };
+
+        self.invalidate(key_id).await;
+        result
+    }
+
+    /// 列出所有已吊销且尚未清理的密钥
+    pub async fn list_revoked_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        match &self.backend {
+            Backend::Sqlite(b) => b.list_revoked_keys().await,
+
+            #[cfg(feature = "backend-postgres")]
+            Backend::Postgres(b) => b.list_revoked_keys().await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.list_revoked_keys().await,
         }
     }
 
     /// 清理过期的密钥
     pub async fn cleanup_expired_keys(&self) -> KsResult<u32> {
-        match self {
-            Self::Sqlite(b) => b.cleanup_expired_keys().await,
+        let deleted = match &self.backend {
+            Backend::Sqlite(b) => b.cleanup_expired_keys().await,
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(b) => b.cleanup_expired_keys().await,
+            Backend::Postgres(b) => b.cleanup_expired_keys().await,
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(b) => b.cleanup_expired_keys().await,
+        }?;
+
+        if deleted > 0
+            && let Some(cache) = &self.cache
+        {
+            cache.invalidate_all();
+        }
+        Ok(deleted)
+    }
+
+    /// 使指定密钥的读取缓存失效
+    async fn invalidate(&self, key_id: u32) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(key_id).await;
         }
     }
 
     /// 获取后端类型名称
     pub fn backend_name(&self) -> &'static str {
-        match self {
-            Self::Sqlite(_) => "SQLite",
+        match &self.backend {
+            Backend::Sqlite(_) => "SQLite",
 
             #[cfg(feature = "backend-postgres")]
-            Self::Postgres(_) => "Postgres",
+            Backend::Postgres(_) => "Postgres",
+
+            #[cfg(feature = "backend-etcd")]
+            Backend::Etcd(_) => "etcd",
         }
     }
 }
@@ -178,8 +379,10 @@ mod tests {
         let config = StorageConfig {
             backend: StorageBackend::Sqlite,
             key_ttl_seconds: 3600,
-            sqlite: Some(SqliteConfig {}),
+            sqlite: Some(SqliteConfig::default()),
             postgres: None,
+            etcd: None,
+            cache: Default::default(),
         };
 
         let storage = KeyStorage::from_config(
@@ -199,6 +402,36 @@ mod tests {
         assert_eq!(public_key, Some(key_pair.public_key));
     }
 
+    #[tokio::test]
+    async fn test_cache_invalidated_on_write() {
+        let temp_dir = tempdir().unwrap();
+        let config = StorageConfig::default();
+        let storage = KeyStorage::from_config(
+            &config,
+            crate::crypto::KeyEncryptor::no_encryption(),
+            temp_dir.path(),
+        )
+        .await
+        .unwrap();
+
+        // 生成前查询的 key_id 被负缓存，生成后应可见
+        assert_eq!(storage.get_key_record(1).await.unwrap(), None);
+        assert_eq!(storage.get_secret_key(1).await.unwrap(), None);
+        let key_pair = storage.generate_and_store_key().await.unwrap();
+        assert_eq!(key_pair.key_id, 1);
+        let record = storage.get_key_record(1).await.unwrap().unwrap();
+        assert_eq!(record.status, crate::types::KeyStatus::Active);
+        assert_eq!(
+            storage.get_secret_key(1).await.unwrap(),
+            Some(key_pair.secret_key)
+        );
+
+        // 吊销后不再返回缓存的旧记录
+        assert!(storage.revoke_key(1, 100).await.unwrap());
+        let record = storage.get_key_record(1).await.unwrap().unwrap();
+        assert_eq!(record.status, crate::types::KeyStatus::Revoked);
+    }
+
     #[tokio::test]
     async fn test_missing_backend_config() {
         let config = StorageConfig {
@@ -206,6 +439,8 @@ mod tests {
             key_ttl_seconds: 3600,
             sqlite: None, // 缺少配置
             postgres: None,
+            etcd: None,
+            cache: Default::default(),
         };
 
         let temp_dir = tempdir().unwrap();
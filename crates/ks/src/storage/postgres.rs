@@ -3,11 +3,11 @@
 //! 使用 sqlx 提供 PostgreSQL 存储支持
 
 use crate::error::{KsError, KsResult};
+use crate::keygen::generate_keypair;
 use crate::storage::backend::KeyStorageBackend;
 use crate::storage::config::PostgresConfig;
-use crate::types::{KeyPair, KeyRecord};
+use crate::types::{KeyAlgorithm, KeyPair, KeyRecord, KeyStatus};
 use async_trait::async_trait;
-use base64::prelude::*;
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, trace};
@@ -73,7 +73,12 @@ impl KeyStorageBackend for PostgresBackend {
                 public_key TEXT NOT NULL,
                 secret_key TEXT NOT NULL,
                 created_at BIGINT NOT NULL,
-                expires_at BIGINT NOT NULL
+                expires_at BIGINT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'active',
+                retired_at BIGINT NOT NULL DEFAULT 0,
+                verify_until BIGINT NOT NULL DEFAULT 0,
+                revoked_at BIGINT NOT NULL DEFAULT 0,
+                algorithm TEXT NOT NULL DEFAULT 'secp256k1'
             )
             "#,
         )
@@ -81,6 +86,21 @@ impl KeyStorageBackend for PostgresBackend {
         .await
         .map_err(|e| KsError::Internal(format!("Failed to create keys table: {e}")))?;
 
+        // 旧版本数据库补充轮替、吊销与算法相关列
+        sqlx::query(
+            r#"
+            ALTER TABLE keys
+                ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'active',
+                ADD COLUMN IF NOT EXISTS retired_at BIGINT NOT NULL DEFAULT 0,
+                ADD COLUMN IF NOT EXISTS verify_until BIGINT NOT NULL DEFAULT 0,
+                ADD COLUMN IF NOT EXISTS revoked_at BIGINT NOT NULL DEFAULT 0,
+                ADD COLUMN IF NOT EXISTS algorithm TEXT NOT NULL DEFAULT 'secp256k1'
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to migrate keys table: {e}")))?;
+
         // 创建索引以提高过期查询性能
         sqlx::query(
             "CREATE INDEX IF NOT EXISTS idx_keys_expires_at ON keys(expires_at) WHERE expires_at > 0",
@@ -93,13 +113,9 @@ impl KeyStorageBackend for PostgresBackend {
         Ok(())
     }
 
-    async fn generate_and_store_key(&self) -> KsResult<KeyPair> {
-        // 生成椭圆曲线密钥对
-        let (secret_key, public_key) = ecies::utils::generate_keypair();
-
-        // 编码为 Base64
-        let secret_key_b64 = BASE64_STANDARD.encode(secret_key.serialize());
-        let public_key_b64 = BASE64_STANDARD.encode(public_key.serialize_compressed());
+    async fn generate_and_store_key(&self, algorithm: KeyAlgorithm) -> KsResult<KeyPair> {
+        // 按算法生成密钥对（Base64 编码）
+        let (secret_key_b64, public_key_b64) = generate_keypair(algorithm);
 
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -116,8 +132,8 @@ impl KeyStorageBackend for PostgresBackend {
         // 插入密钥并获取自动生成的 key_id
         let row = sqlx::query_as::<_, (i32,)>(
             r#"
-            INSERT INTO keys (public_key, secret_key, created_at, expires_at)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO keys (public_key, secret_key, created_at, expires_at, algorithm)
+            VALUES ($1, $2, $3, $4, $5)
             RETURNING key_id
             "#,
         )
@@ -125,6 +141,7 @@ impl KeyStorageBackend for PostgresBackend {
         .bind(&secret_key_b64)
         .bind(now)
         .bind(expires_at)
+        .bind(algorithm.as_str())
         .fetch_one(&self.pool)
         .await
         .map_err(|e| KsError::Internal(format!("Failed to insert key: {e}")))?;
@@ -138,6 +155,7 @@ impl KeyStorageBackend for PostgresBackend {
 
         Ok(KeyPair {
             key_id,
+            algorithm,
             secret_key: secret_key_b64,
             public_key: public_key_b64,
         })
@@ -186,8 +204,8 @@ impl KeyStorageBackend for PostgresBackend {
     }
 
     async fn get_key_record(&self, key_id: u32) -> KsResult<Option<KeyRecord>> {
-        let result = sqlx::query_as::<_, (i32, String, i64, i64)>(
-            "SELECT key_id, public_key, created_at, expires_at FROM keys WHERE key_id = $1",
+        let result = sqlx::query_as::<_, (i32, String, i64, i64, String, i64, i64, i64, String)>(
+            "SELECT key_id, public_key, created_at, expires_at, status, retired_at, verify_until, revoked_at, algorithm FROM keys WHERE key_id = $1",
         )
         .bind(key_id as i32)
         .fetch_optional(&self.pool)
@@ -199,13 +217,28 @@ impl KeyStorageBackend for PostgresBackend {
         })?;
 
         match result {
-            Some((id, public_key, created_at, expires_at)) => {
+            Some((
+                id,
+                public_key,
+                created_at,
+                expires_at,
+                status,
+                retired_at,
+                verify_until,
+                revoked_at,
+                algorithm,
+            )) => {
                 debug!("Found key record for key_id: {} in PostgreSQL", key_id);
                 Ok(Some(KeyRecord {
                     key_id: id as u32,
+                    algorithm: KeyAlgorithm::from_db(&algorithm),
                     public_key,
                     created_at: created_at as u64,
                     expires_at: expires_at as u64,
+                    status: KeyStatus::from_db(&status),
+                    retired_at: retired_at as u64,
+                    verify_until: verify_until as u64,
+                    revoked_at: revoked_at as u64,
                 }))
             }
             None => {
@@ -224,14 +257,86 @@ impl KeyStorageBackend for PostgresBackend {
         Ok(count as u32)
     }
 
+    async fn get_latest_active_key_id(&self) -> KsResult<Option<u32>> {
+        let result = sqlx::query_scalar::<_, i32>(
+            "SELECT key_id FROM keys WHERE status = 'active' ORDER BY key_id DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to query latest active key: {e}")))?;
+
+        Ok(result.map(|key_id| key_id as u32))
+    }
+
+    async fn list_active_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        let rows = sqlx::query_as::<_, (i32, i64)>(
+            "SELECT key_id, created_at FROM keys WHERE status = $1 ORDER BY key_id",
+        )
+        .bind(KeyStatus::Active.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to list active keys: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(key_id, created_at)| (key_id as u32, created_at as u64))
+            .collect())
+    }
+
+    async fn retire_key(&self, key_id: u32, retired_at: u64, verify_until: u64) -> KsResult<bool> {
+        let result = sqlx::query(
+            "UPDATE keys SET status = $1, retired_at = $2, verify_until = $3 WHERE key_id = $4 AND status = 'active'",
+        )
+        .bind(KeyStatus::VerifyOnly.as_str())
+        .bind(retired_at as i64)
+        .bind(verify_until as i64)
+        .bind(key_id as i32)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to retire key {key_id}: {e}")))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_key(&self, key_id: u32, revoked_at: u64) -> KsResult<bool> {
+        let result = sqlx::query(
+            "UPDATE keys SET status = $1, revoked_at = $2 WHERE key_id = $3 AND status != $1",
+        )
+        .bind(KeyStatus::Revoked.as_str())
+        .bind(revoked_at as i64)
+        .bind(key_id as i32)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to revoke key {key_id}: {e}")))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_revoked_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        let rows = sqlx::query_as::<_, (i32, i64)>(
+            "SELECT key_id, revoked_at FROM keys WHERE status = $1 ORDER BY key_id",
+        )
+        .bind(KeyStatus::Revoked.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to list revoked keys: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(key_id, revoked_at)| (key_id as u32, revoked_at as u64))
+            .collect())
+    }
+
     async fn cleanup_expired_keys(&self) -> KsResult<u32> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
 
-        // 删除过期的密钥（expires_at > 0 且 < now）
-        let result = sqlx::query("DELETE FROM keys WHERE expires_at > 0 AND expires_at < $1")
+        // 删除过期的密钥（expires_at > 0 且 < now），仅验证的密钥在宽限期结束后删除
+        let result = sqlx::query(
+            "DELETE FROM keys WHERE (expires_at > 0 AND expires_at < $1 AND verify_until < $1) OR (verify_until > 0 AND verify_until < $1)",
+        )
             .bind(now)
             .execute(&self.pool)
             .await
@@ -292,7 +397,10 @@ mod tests {
         cleanup_test_data(&backend).await;
 
         // 生成密钥
-        let key_pair = backend.generate_and_store_key().await.unwrap();
+        let key_pair = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert!(key_pair.key_id > 0);
         assert!(!key_pair.public_key.is_empty());
         assert!(!key_pair.secret_key.is_empty());
@@ -328,10 +436,16 @@ mod tests {
 
         assert_eq!(backend.get_key_count().await.unwrap(), 0);
 
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
 
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 2);
 
         cleanup_test_data(&backend).await;
@@ -358,7 +472,10 @@ mod tests {
         cleanup_test_data(&backend).await;
 
         // 生成密钥
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
 
         // 等待过期
@@ -392,7 +509,10 @@ mod tests {
         let backend = PostgresBackend::new(&config, 0).await.unwrap();
         cleanup_test_data(&backend).await;
 
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
 
         // 清理不应删除永不过期的密钥
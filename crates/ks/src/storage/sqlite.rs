@@ -4,11 +4,11 @@
 
 use crate::crypto::KeyEncryptor;
 use crate::error::{KsError, KsResult};
+use crate::keygen::generate_keypair;
 use crate::storage::backend::KeyStorageBackend;
 use crate::storage::config::SqliteConfig;
-use crate::types::{KeyPair, KeyRecord};
+use crate::types::{KeyAlgorithm, KeyPair, KeyRecord, KeyStatus};
 use async_trait::async_trait;
-use base64::prelude::*;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
 use std::path::Path;
 use std::str::FromStr;
@@ -36,30 +36,18 @@ impl SqliteBackend {
     /// 创建新的 SQLite 后端实例
     ///
     /// # Arguments
-    /// * `_config` - SQLite 配置（暂时保留，未使用）
+    /// * `config` - SQLite 配置（`in_memory` 时使用内存数据库）
     /// * `key_ttl` - 密钥有效期（秒），0 表示永不过期
     /// * `encryptor` - 密钥加密器
-    /// * `db_path` - 数据库文件存储目录路径（来自 ActrixConfig.sqlite_path）
+    /// * `db_path` - 数据库文件存储目录路径（来自 ActrixConfig.sqlite_path，内存数据库时不使用）
     pub async fn new(
-        _config: &SqliteConfig,
+        config: &SqliteConfig,
         key_ttl: u64,
         encryptor: KeyEncryptor,
         db_path: &Path,
     ) -> KsResult<Self> {
         let file = db_path.join("ks_keys.db");
-
-        // 创建连接选项并启用 WAL 模式
-        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", file.display()))
-            .map_err(|e| KsError::Internal(format!("Failed to parse SQLite URL: {e}")))?
-            .create_if_missing(true)
-            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
-            .busy_timeout(std::time::Duration::from_secs(5));
-
-        // 创建连接池
-        let pool = SqlitePoolOptions::new()
-            .max_connections(10)
-            .connect_with(options)
+        let pool = connect(&file, config.in_memory, 10)
             .await
             .map_err(|e| KsError::Internal(format!("Failed to connect to SQLite: {e}")))?;
 
@@ -73,8 +61,9 @@ impl SqliteBackend {
         backend.init().await?;
 
         info!(
-            "SQLite storage initialized with sqlx: path={}, key_ttl={}s, encryption={}, WAL mode enabled",
+            "SQLite storage initialized with sqlx: path={}, in_memory={}, key_ttl={}s, encryption={}",
             file.display(),
+            config.in_memory,
             key_ttl,
             backend.encryptor.is_enabled()
         );
@@ -83,6 +72,43 @@ impl SqliteBackend {
     }
 }
 
+/// 连接 SQLite 数据库
+///
+/// 文件数据库启用 WAL 模式。`in_memory` 时不创建文件，改用以文件名命名的共享缓存内存数据库，
+/// 同一进程内的 KS HTTP 与 gRPC 服务打开同一个数据库；连接池保持一个常驻连接，
+/// 避免最后一个连接关闭时数据库被释放
+pub(crate) async fn connect(
+    file: &Path,
+    in_memory: bool,
+    max_connections: u32,
+) -> sqlx::Result<SqlitePool> {
+    if !in_memory {
+        let options = SqliteConnectOptions::from_str(&format!("sqlite:{}", file.display()))?
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+        return SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await;
+    }
+
+    let name = file
+        .file_name()
+        .map_or_else(|| "ks".into(), |name| name.to_string_lossy());
+    let options =
+        SqliteConnectOptions::from_str(&format!("sqlite:{name}?mode=memory&cache=shared"))?
+            .busy_timeout(std::time::Duration::from_secs(5));
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .min_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await
+}
+
 #[async_trait]
 impl KeyStorageBackend for SqliteBackend {
     async fn init(&self) -> KsResult<()> {
@@ -94,7 +120,12 @@ impl KeyStorageBackend for SqliteBackend {
                 public_key TEXT NOT NULL,
                 secret_key TEXT NOT NULL,
                 created_at INTEGER NOT NULL,
-                expires_at INTEGER NOT NULL
+                expires_at INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'active',
+                retired_at INTEGER NOT NULL DEFAULT 0,
+                verify_until INTEGER NOT NULL DEFAULT 0,
+                revoked_at INTEGER NOT NULL DEFAULT 0,
+                algorithm TEXT NOT NULL DEFAULT 'secp256k1'
             )
             "#,
         )
@@ -102,6 +133,30 @@ impl KeyStorageBackend for SqliteBackend {
         .await
         .map_err(|e| KsError::Internal(format!("Failed to create keys table: {e}")))?;
 
+        // 旧版本数据库补充轮替、吊销与算法相关列
+        let columns = sqlx::query_as::<_, (String,)>("SELECT name FROM pragma_table_info('keys')")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| KsError::Internal(format!("Failed to read keys table info: {e}")))?;
+        for (column, definition) in [
+            ("status", "TEXT NOT NULL DEFAULT 'active'"),
+            ("retired_at", "INTEGER NOT NULL DEFAULT 0"),
+            ("verify_until", "INTEGER NOT NULL DEFAULT 0"),
+            ("revoked_at", "INTEGER NOT NULL DEFAULT 0"),
+            ("algorithm", "TEXT NOT NULL DEFAULT 'secp256k1'"),
+        ] {
+            if !columns.iter().any(|(name,)| name == column) {
+                sqlx::query(&format!(
+                    "ALTER TABLE keys ADD COLUMN {column} {definition}"
+                ))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| {
+                    KsError::Internal(format!("Failed to add keys.{column} column: {e}"))
+                })?;
+            }
+        }
+
         // 创建索引
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_keys_expires_at ON keys(expires_at)")
             .execute(&self.pool)
@@ -112,13 +167,9 @@ impl KeyStorageBackend for SqliteBackend {
         Ok(())
     }
 
-    async fn generate_and_store_key(&self) -> KsResult<KeyPair> {
-        // 生成椭圆曲线密钥对
-        let (secret_key, public_key) = ecies::utils::generate_keypair();
-
-        // 编码为 Base64
-        let secret_key_b64 = BASE64_STANDARD.encode(secret_key.serialize());
-        let public_key_b64 = BASE64_STANDARD.encode(public_key.serialize_compressed());
+    async fn generate_and_store_key(&self, algorithm: KeyAlgorithm) -> KsResult<KeyPair> {
+        // 按算法生成密钥对（Base64 编码）
+        let (secret_key_b64, public_key_b64) = generate_keypair(algorithm);
 
         // 加密私钥（如果启用）
         let encrypted_secret_key = self.encryptor.encrypt(&secret_key_b64)?;
@@ -137,13 +188,14 @@ impl KeyStorageBackend for SqliteBackend {
 
         // 插入密钥并返回 ID（存储加密后的私钥）
         let result = sqlx::query(
-            r#"INSERT INTO keys (public_key, secret_key, created_at, expires_at)
-               VALUES (?1, ?2, ?3, ?4)"#,
+            r#"INSERT INTO keys (public_key, secret_key, created_at, expires_at, algorithm)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
         )
         .bind(&public_key_b64)
         .bind(&encrypted_secret_key)
         .bind(now)
         .bind(expires_at)
+        .bind(algorithm.as_str())
         .execute(&self.pool)
         .await
         .map_err(|e| KsError::Internal(format!("Failed to insert key: {e}")))?;
@@ -155,6 +207,7 @@ impl KeyStorageBackend for SqliteBackend {
         // 返回明文私钥（供调用方使用）
         Ok(KeyPair {
             key_id,
+            algorithm,
             secret_key: secret_key_b64,
             public_key: public_key_b64,
         })
@@ -203,8 +256,8 @@ impl KeyStorageBackend for SqliteBackend {
     }
 
     async fn get_key_record(&self, key_id: u32) -> KsResult<Option<KeyRecord>> {
-        let result = sqlx::query_as::<_, (i64, String, i64, i64)>(
-            "SELECT key_id, public_key, created_at, expires_at FROM keys WHERE key_id = ?",
+        let result = sqlx::query_as::<_, (i64, String, i64, i64, String, i64, i64, i64, String)>(
+            "SELECT key_id, public_key, created_at, expires_at, status, retired_at, verify_until, revoked_at, algorithm FROM keys WHERE key_id = ?",
         )
         .bind(key_id as i64)
         .fetch_optional(&self.pool)
@@ -215,13 +268,29 @@ impl KeyStorageBackend for SqliteBackend {
             ))
         })?;
 
-        if let Some((key_id_db, public_key, created_at, expires_at)) = result {
+        if let Some((
+            key_id_db,
+            public_key,
+            created_at,
+            expires_at,
+            status,
+            retired_at,
+            verify_until,
+            revoked_at,
+            algorithm,
+        )) = result
+        {
             debug!("Found key record for key_id: {}", key_id);
             Ok(Some(KeyRecord {
                 key_id: key_id_db as u32,
+                algorithm: KeyAlgorithm::from_db(&algorithm),
                 public_key,
                 created_at: created_at as u64,
                 expires_at: expires_at as u64,
+                status: KeyStatus::from_db(&status),
+                retired_at: retired_at as u64,
+                verify_until: verify_until as u64,
+                revoked_at: revoked_at as u64,
             }))
         } else {
             debug!("No key record found for key_id: {}", key_id);
@@ -238,13 +307,85 @@ impl KeyStorageBackend for SqliteBackend {
         Ok(result.0 as u32)
     }
 
+    async fn get_latest_active_key_id(&self) -> KsResult<Option<u32>> {
+        let result = sqlx::query_as::<_, (i64,)>(
+            "SELECT key_id FROM keys WHERE status = 'active' ORDER BY key_id DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to query latest active key: {e}")))?;
+
+        Ok(result.map(|(key_id,)| key_id as u32))
+    }
+
+    async fn list_active_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        let rows = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT key_id, created_at FROM keys WHERE status = ?1 ORDER BY key_id",
+        )
+        .bind(KeyStatus::Active.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to list active keys: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(key_id, created_at)| (key_id as u32, created_at as u64))
+            .collect())
+    }
+
+    async fn retire_key(&self, key_id: u32, retired_at: u64, verify_until: u64) -> KsResult<bool> {
+        let result = sqlx::query(
+            "UPDATE keys SET status = ?1, retired_at = ?2, verify_until = ?3 WHERE key_id = ?4 AND status = 'active'",
+        )
+        .bind(KeyStatus::VerifyOnly.as_str())
+        .bind(retired_at as i64)
+        .bind(verify_until as i64)
+        .bind(key_id as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to retire key {key_id}: {e}")))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_key(&self, key_id: u32, revoked_at: u64) -> KsResult<bool> {
+        let result = sqlx::query(
+            "UPDATE keys SET status = ?1, revoked_at = ?2 WHERE key_id = ?3 AND status != ?1",
+        )
+        .bind(KeyStatus::Revoked.as_str())
+        .bind(revoked_at as i64)
+        .bind(key_id as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to revoke key {key_id}: {e}")))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_revoked_keys(&self) -> KsResult<Vec<(u32, u64)>> {
+        let rows = sqlx::query_as::<_, (i64, i64)>(
+            "SELECT key_id, revoked_at FROM keys WHERE status = ?1 ORDER BY key_id",
+        )
+        .bind(KeyStatus::Revoked.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| KsError::Internal(format!("Failed to list revoked keys: {e}")))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(key_id, revoked_at)| (key_id as u32, revoked_at as u64))
+            .collect())
+    }
+
     async fn cleanup_expired_keys(&self) -> KsResult<u32> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
 
-        let result = sqlx::query("DELETE FROM keys WHERE expires_at > 0 AND expires_at < ?")
+        let result = sqlx::query(
+            "DELETE FROM keys WHERE (expires_at > 0 AND expires_at < ?1 AND verify_until < ?1) OR (verify_until > 0 AND verify_until < ?1)",
+        )
             .bind(now)
             .execute(&self.pool)
             .await
@@ -265,7 +406,7 @@ mod tests {
     use tempfile::tempdir;
 
     async fn create_test_backend(path: &Path) -> SqliteBackend {
-        let config = SqliteConfig {};
+        let config = SqliteConfig::default();
         SqliteBackend::new(
             &config,
             3600,
@@ -290,7 +431,10 @@ mod tests {
         let backend = create_test_backend(temp_dir.path()).await;
 
         // 生成密钥
-        let key_pair = backend.generate_and_store_key().await.unwrap();
+        let key_pair = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert!(key_pair.key_id > 0);
         assert!(!key_pair.public_key.is_empty());
         assert!(!key_pair.secret_key.is_empty());
@@ -309,6 +453,25 @@ mod tests {
         let record = record.unwrap();
         assert_eq!(record.key_id, key_pair.key_id);
         assert_eq!(record.public_key, key_pair.public_key);
+        assert_eq!(record.algorithm, KeyAlgorithm::Secp256k1);
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_algorithm() {
+        let temp_dir = tempdir().unwrap();
+        let backend = create_test_backend(temp_dir.path()).await;
+
+        for algorithm in [KeyAlgorithm::Ed25519, KeyAlgorithm::P256] {
+            let key_pair = backend.generate_and_store_key(algorithm).await.unwrap();
+            assert_eq!(key_pair.algorithm, algorithm);
+
+            let record = backend
+                .get_key_record(key_pair.key_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(record.algorithm, algorithm);
+        }
     }
 
     #[tokio::test]
@@ -333,19 +496,66 @@ mod tests {
 
         assert_eq!(backend.get_key_count().await.unwrap(), 0);
 
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
 
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 2);
     }
 
+    #[tokio::test]
+    async fn test_in_memory_backend_creates_no_files() {
+        let temp_dir = tempdir().unwrap();
+        let config = SqliteConfig { in_memory: true };
+        let backend = SqliteBackend::new(
+            &config,
+            3600,
+            crate::crypto::KeyEncryptor::no_encryption(),
+            temp_dir.path(),
+        )
+        .await
+        .unwrap();
+
+        let key_pair = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert!(
+            backend
+                .get_secret_key(key_pair.key_id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        // 同一进程内的其他实例（KS HTTP 与 gRPC）打开同一个内存数据库
+        let other = SqliteBackend::new(
+            &config,
+            3600,
+            crate::crypto::KeyEncryptor::no_encryption(),
+            temp_dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            other.get_public_key(key_pair.key_id).await.unwrap(),
+            Some(key_pair.public_key)
+        );
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
     #[tokio::test]
     async fn test_cleanup_expired_keys() {
         let temp_dir = tempdir().unwrap();
 
         // 创建 TTL 为 1 秒的后端
-        let config = SqliteConfig {};
+        let config = SqliteConfig::default();
         let backend = SqliteBackend::new(
             &config,
             1,
@@ -356,7 +566,10 @@ mod tests {
         .unwrap();
 
         // 生成密钥
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
 
         // 等待过期
@@ -373,7 +586,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
 
         // TTL 为 0（永不过期）
-        let config = SqliteConfig {};
+        let config = SqliteConfig::default();
         let backend = SqliteBackend::new(
             &config,
             0,
@@ -383,7 +596,10 @@ mod tests {
         .await
         .unwrap();
 
-        backend.generate_and_store_key().await.unwrap();
+        backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
 
         // 清理不应删除永不过期的密钥
@@ -391,4 +607,86 @@ mod tests {
         assert_eq!(cleaned, 0);
         assert_eq!(backend.get_key_count().await.unwrap(), 1);
     }
+
+    #[tokio::test]
+    async fn test_retire_key() {
+        let temp_dir = tempdir().unwrap();
+        let backend = create_test_backend(temp_dir.path()).await;
+
+        let old = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        let new = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.get_latest_active_key_id().await.unwrap(),
+            Some(new.key_id)
+        );
+
+        assert!(backend.retire_key(new.key_id, 100, 200).await.unwrap());
+        // 已是仅验证的密钥不能重复轮替
+        assert!(!backend.retire_key(new.key_id, 100, 200).await.unwrap());
+        assert!(!backend.retire_key(999, 100, 200).await.unwrap());
+
+        let record = backend.get_key_record(new.key_id).await.unwrap().unwrap();
+        assert_eq!(record.status, KeyStatus::VerifyOnly);
+        assert_eq!(record.retired_at, 100);
+        assert_eq!(record.verify_until, 200);
+        assert_eq!(
+            backend.get_latest_active_key_id().await.unwrap(),
+            Some(old.key_id)
+        );
+
+        // 宽限期已结束的仅验证密钥被清理
+        assert_eq!(backend.cleanup_expired_keys().await.unwrap(), 1);
+        assert!(backend.get_key_record(new.key_id).await.unwrap().is_none());
+        assert!(backend.get_key_record(old.key_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_key() {
+        let temp_dir = tempdir().unwrap();
+        let backend = create_test_backend(temp_dir.path()).await;
+
+        let active = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        let retired = backend
+            .generate_and_store_key(KeyAlgorithm::default())
+            .await
+            .unwrap();
+        assert!(
+            backend
+                .retire_key(retired.key_id, 100, u64::MAX / 2)
+                .await
+                .unwrap()
+        );
+        assert!(backend.list_revoked_keys().await.unwrap().is_empty());
+
+        // Active 与仅验证的密钥都可吊销，且不能重复吊销
+        assert!(backend.revoke_key(active.key_id, 300).await.unwrap());
+        assert!(backend.revoke_key(retired.key_id, 400).await.unwrap());
+        assert!(!backend.revoke_key(active.key_id, 500).await.unwrap());
+        assert!(!backend.revoke_key(999, 500).await.unwrap());
+
+        let record = backend
+            .get_key_record(active.key_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.status, KeyStatus::Revoked);
+        assert_eq!(record.revoked_at, 300);
+        assert_eq!(
+            backend.list_revoked_keys().await.unwrap(),
+            vec![(active.key_id, 300), (retired.key_id, 400)]
+        );
+
+        // 吊销的密钥不能再被选为轮替对象
+        assert_eq!(backend.get_latest_active_key_id().await.unwrap(), None);
+        assert!(!backend.retire_key(active.key_id, 100, 200).await.unwrap());
+    }
 }
@@ -1,13 +1,89 @@
 //! KS 服务数据类型定义
 
+use actrix_proto::ks::v1::KeyAlgorithm as ProtoKeyAlgorithm;
 use nonce_auth::NonceCredential;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 密钥算法
+///
+/// 编码格式（均为 Base64）：
+/// - `secp256k1`：ECIES 加密与 ECDSA 签名，私钥 32 字节，公钥为 33 字节压缩格式
+/// - `ed25519`：签名，私钥为 32 字节种子，公钥 32 字节
+/// - `p256`：ECDSA / ECDH（NIST P-256），私钥 32 字节，公钥为 33 字节 SEC1 压缩格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyAlgorithm {
+    /// 默认算法，与引入算法字段前生成的密钥相同
+    #[default]
+    Secp256k1,
+    Ed25519,
+    P256,
+}
+
+impl KeyAlgorithm {
+    /// 数据库中存储的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyAlgorithm::Secp256k1 => "secp256k1",
+            KeyAlgorithm::Ed25519 => "ed25519",
+            KeyAlgorithm::P256 => "p256",
+        }
+    }
+
+    /// 从数据库字符串解析，未知值按 secp256k1 处理（兼容旧数据）
+    pub fn from_db(value: &str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for KeyAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for KeyAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "secp256k1" => Ok(KeyAlgorithm::Secp256k1),
+            "ed25519" => Ok(KeyAlgorithm::Ed25519),
+            "p256" => Ok(KeyAlgorithm::P256),
+            other => Err(format!("Unknown key algorithm: {other}")),
+        }
+    }
+}
+
+impl From<ProtoKeyAlgorithm> for KeyAlgorithm {
+    fn from(algorithm: ProtoKeyAlgorithm) -> Self {
+        match algorithm {
+            ProtoKeyAlgorithm::Secp256k1 => KeyAlgorithm::Secp256k1,
+            ProtoKeyAlgorithm::Ed25519 => KeyAlgorithm::Ed25519,
+            ProtoKeyAlgorithm::P256 => KeyAlgorithm::P256,
+        }
+    }
+}
+
+impl From<KeyAlgorithm> for ProtoKeyAlgorithm {
+    fn from(algorithm: KeyAlgorithm) -> Self {
+        match algorithm {
+            KeyAlgorithm::Secp256k1 => ProtoKeyAlgorithm::Secp256k1,
+            KeyAlgorithm::Ed25519 => ProtoKeyAlgorithm::Ed25519,
+            KeyAlgorithm::P256 => ProtoKeyAlgorithm::P256,
+        }
+    }
+}
 
 /// 密钥对结构
 #[derive(Debug, Clone)]
 pub struct KeyPair {
     /// 密钥 ID
     pub key_id: u32,
+    /// 密钥算法
+    pub algorithm: KeyAlgorithm,
     /// 私钥（Base64 编码）
     pub secret_key: String,
     /// 公钥（Base64 编码）
@@ -19,6 +95,9 @@ pub struct KeyPair {
 pub struct GenerateKeyRequest {
     /// nonce-auth 凭证
     pub credential: NonceCredential,
+    /// 密钥算法（缺省为 secp256k1）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<KeyAlgorithm>,
 }
 
 /// 生成密钥响应
@@ -26,6 +105,9 @@ pub struct GenerateKeyRequest {
 pub struct GenerateKeyResponse {
     /// 生成的密钥 ID
     pub key_id: u32,
+    /// 密钥算法
+    #[serde(default)]
+    pub algorithm: KeyAlgorithm,
     /// 公钥（Base64 编码）
     pub public_key: String,
     /// 过期时间（Unix 时间戳）
@@ -48,6 +130,9 @@ pub struct GetSecretKeyRequest {
 pub struct GetSecretKeyResponse {
     /// 密钥 ID
     pub key_id: u32,
+    /// 密钥算法
+    #[serde(default)]
+    pub algorithm: KeyAlgorithm,
     /// 私钥（Base64 编码）
     pub secret_key: String,
     /// 过期时间（Unix 时间戳）
@@ -56,24 +141,158 @@ pub struct GetSecretKeyResponse {
     pub tolerance_seconds: u64,
 }
 
+/// 密钥状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStatus {
+    /// 正常使用
+    Active,
+    /// 已被轮替，仅在宽限期内供验证方获取私钥
+    VerifyOnly,
+    /// 已被吊销，不再对外提供私钥
+    Revoked,
+}
+
+impl KeyStatus {
+    /// 数据库中存储的字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStatus::Active => "active",
+            KeyStatus::VerifyOnly => "verify_only",
+            KeyStatus::Revoked => "revoked",
+        }
+    }
+
+    /// 从数据库字符串解析，未知值按 Active 处理（兼容旧数据）
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "verify_only" => KeyStatus::VerifyOnly,
+            "revoked" => KeyStatus::Revoked,
+            _ => KeyStatus::Active,
+        }
+    }
+}
+
 /// 存储在数据库中的密钥记录
 #[derive(Debug, Clone, PartialEq)]
 pub struct KeyRecord {
     /// 密钥 ID
     pub key_id: u32,
+    /// 密钥算法
+    pub algorithm: KeyAlgorithm,
     /// 公钥（Base64 编码）
     pub public_key: String,
     /// 创建时间戳
     pub created_at: u64,
     /// 过期时间（Unix 时间戳）
     pub expires_at: u64,
+    /// 密钥状态
+    pub status: KeyStatus,
+    /// 被轮替为仅验证的时间（Unix 时间戳，未轮替为 0）
+    pub retired_at: u64,
+    /// 仅验证宽限期截止时间（Unix 时间戳，未轮替为 0）
+    pub verify_until: u64,
+    /// 吊销时间（Unix 时间戳，未吊销为 0）
+    pub revoked_at: u64,
+}
+
+impl KeyRecord {
+    /// 对外报告的 (expires_at, tolerance_seconds)
+    ///
+    /// 仅验证的密钥以轮替时间作为过期时间、以宽限期作为容忍期，
+    /// 验证方据此在宽限期内将其视为容忍期密钥，宽限期结束后不再可用；
+    /// 宽限期不会超过密钥原本的过期时间 + 容忍期。
+    /// 吊销的密钥以吊销时间为过期时间且没有容忍期。
+    pub fn effective_expiry(&self, tolerance_seconds: u64) -> (u64, u64) {
+        match self.status {
+            KeyStatus::Active => (self.expires_at, tolerance_seconds),
+            KeyStatus::VerifyOnly => {
+                let mut verify_until = self.verify_until;
+                let mut expires_at = self.retired_at;
+                if self.expires_at > 0 {
+                    verify_until = verify_until.min(self.expires_at + tolerance_seconds);
+                    expires_at = expires_at.min(self.expires_at);
+                }
+                (expires_at, verify_until.saturating_sub(expires_at))
+            }
+            KeyStatus::Revoked => (self.revoked_at, 0),
+        }
+    }
+}
+
+/// 轮替密钥请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RotateKeyRequest {
+    /// nonce-auth 凭证
+    pub credential: NonceCredential,
+    /// 要转为仅验证的旧密钥 ID（缺省为最近创建的 Active 密钥）
+    #[serde(default)]
+    pub previous_key_id: Option<u32>,
+    /// 旧密钥的宽限期（秒，缺省使用配置的 rotation_grace_seconds）
+    #[serde(default)]
+    pub grace_seconds: Option<u64>,
+}
+
+/// 轮替密钥响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RotateKeyResponse {
+    /// 新密钥 ID
+    pub key_id: u32,
+    /// 新密钥算法（与旧密钥相同）
+    #[serde(default)]
+    pub algorithm: KeyAlgorithm,
+    /// 新公钥（Base64 编码）
+    pub public_key: String,
+    /// 新密钥过期时间（Unix 时间戳）
+    pub expires_at: u64,
+    /// 容忍时间（秒）
+    pub tolerance_seconds: u64,
+    /// 被转为仅验证的旧密钥 ID（无可轮替的旧密钥时为 None）
+    pub previous_key_id: Option<u32>,
+    /// 旧密钥宽限期截止时间（Unix 时间戳，无旧密钥时为 0）
+    pub verify_until: u64,
+}
+
+/// 吊销密钥请求
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokeKeyRequest {
+    /// nonce-auth 凭证
+    pub credential: NonceCredential,
+    /// 要吊销的密钥 ID
+    pub key_id: u32,
+}
+
+/// 吊销密钥响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokeKeyResponse {
+    /// 被吊销的密钥 ID
+    pub key_id: u32,
+    /// 吊销时间（Unix 时间戳）
+    pub revoked_at: u64,
 }
 
 impl GenerateKeyRequest {
     /// 获取用于验证的请求数据
     pub fn request_payload(&self) -> String {
-        // 为生成密钥请求，我们只需要一个固定的标识符
-        "generate_key".to_string()
+        generate_key_payload(self.algorithm)
+    }
+}
+
+impl RotateKeyRequest {
+    /// 获取用于验证的请求数据
+    pub fn request_payload(&self) -> String {
+        // 指定旧密钥时将其纳入签名，防止被篡改
+        match self.previous_key_id {
+            Some(key_id) => format!("rotate_key:{key_id}"),
+            None => "rotate_key".to_string(),
+        }
+    }
+}
+
+impl RevokeKeyRequest {
+    /// 获取用于验证的请求数据
+    pub fn request_payload(&self) -> String {
+        format!("revoke_key:{}", self.key_id)
     }
 }
 
@@ -84,3 +303,26 @@ impl GetSecretKeyRequest {
         format!("get_secret_key:{}", self.key_id)
     }
 }
+
+/// 生成密钥请求的签名数据
+///
+/// 未指定算法时为固定标识符 "generate_key"（与旧版客户端兼容），
+/// 指定算法时将其纳入签名，例如 "generate_key:ed25519"
+pub fn generate_key_payload(algorithm: Option<KeyAlgorithm>) -> String {
+    match algorithm {
+        Some(algorithm) => format!("generate_key:{algorithm}"),
+        None => "generate_key".to_string(),
+    }
+}
+
+/// 批量获取密钥时单次请求的最大 key_id 数量
+pub const MAX_BATCH_KEY_IDS: usize = 256;
+
+/// 批量获取密钥请求的签名数据
+///
+/// 格式为 "{action}:{key_ids}"，key_ids 按请求顺序以逗号连接，
+/// 例如 "get_secret_keys:1,2,3"
+pub fn batch_request_payload(action: &str, key_ids: &[u32]) -> String {
+    let key_ids: Vec<String> = key_ids.iter().map(u32::to_string).collect();
+    format!("{action}:{}", key_ids.join(","))
+}
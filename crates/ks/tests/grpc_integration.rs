@@ -1,14 +1,14 @@
 use actrix_proto::{
     ks::v1::{
-        GenerateKeyRequest, GetSecretKeyRequest, HealthCheckRequest,
+        GenerateKeyRequest, GetSecretKeyRequest, GetSecretKeysRequest, HealthCheckRequest,
         key_server_client::KeyServerClient,
     },
     supervisor::v1::NonceCredential,
 };
 use base64::Engine as _;
 use ks::{
-    GrpcClient, GrpcClientConfig, KeyEncryptor, KeyStorage, KsError, KsServiceConfig,
-    create_grpc_service,
+    GrpcClient, GrpcClientConfig, KeyAlgorithm, KeyEncryptor, KeyRevocation, KeyRevocationWatch,
+    KeyStorage, KsError, KsServiceConfig, create_grpc_service,
 };
 use nonce_auth::{CredentialBuilder, storage::MemoryStorage};
 use tempfile::TempDir;
@@ -146,6 +146,7 @@ async fn test_grpc_health_and_key_lifecycle() {
     let generated = client
         .generate_key(GenerateKeyRequest {
             credential: sign_credential(psk, "generate_key"),
+            algorithm: None,
         })
         .await
         .expect("generate key")
@@ -195,6 +196,7 @@ async fn test_grpc_generate_rejects_invalid_signature() {
     let err = client
         .generate_key(GenerateKeyRequest {
             credential: sign_credential(psk, "not-generate-key"),
+            algorithm: None,
         })
         .await
         .expect_err("generate should fail for invalid payload signature");
@@ -211,12 +213,16 @@ async fn test_grpc_generate_rejects_replay_nonce() {
     client
         .generate_key(GenerateKeyRequest {
             credential: credential.clone(),
+            algorithm: None,
         })
         .await
         .expect("first request should succeed");
 
     let replay_err = client
-        .generate_key(GenerateKeyRequest { credential })
+        .generate_key(GenerateKeyRequest {
+            credential,
+            algorithm: None,
+        })
         .await
         .expect_err("replayed nonce should be rejected");
     assert_eq!(replay_err.code(), Code::Unauthenticated);
@@ -248,6 +254,7 @@ async fn test_grpc_get_secret_key_rejects_stale_timestamp() {
     let err = client
         .generate_key(GenerateKeyRequest {
             credential: sign_credential_with_timestamp(psk, "generate_key", 0),
+            algorithm: None,
         })
         .await
         .expect_err("stale timestamp should be rejected");
@@ -263,6 +270,7 @@ async fn test_grpc_get_secret_key_expired_beyond_tolerance_returns_not_found() {
     let generated = client
         .generate_key(GenerateKeyRequest {
             credential: sign_credential(psk, "generate_key"),
+            algorithm: None,
         })
         .await
         .expect("generate key")
@@ -280,6 +288,53 @@ async fn test_grpc_get_secret_key_expired_beyond_tolerance_returns_not_found() {
     assert_eq!(err.code(), Code::NotFound);
 }
 
+#[tokio::test]
+async fn test_ks_grpc_client_key_algorithms() {
+    let psk = "test-ks-grpc-psk";
+    let server = start_grpc_server(psk, 3600, 90).await;
+
+    let mut client = GrpcClient::new(&GrpcClientConfig {
+        endpoint: server.endpoint.clone(),
+        actrix_shared_key: psk.to_string(),
+        timeout_seconds: 5,
+        enable_tls: false,
+        tls_domain: None,
+        ca_cert: None,
+        client_cert: None,
+        client_key: None,
+    })
+    .await
+    .expect("create grpc client");
+
+    let (key_id, public_key, _, _) = client
+        .generate_key_with_algorithm(KeyAlgorithm::Ed25519)
+        .await
+        .expect("generate ed25519 key");
+    let public_key = base64::engine::general_purpose::STANDARD
+        .decode(public_key)
+        .expect("decode public key");
+    assert_eq!(public_key.len(), 32);
+
+    let (algorithm, secret_key, _, _) = client
+        .fetch_raw_secret_key(key_id)
+        .await
+        .expect("fetch ed25519 secret key");
+    assert_eq!(algorithm, KeyAlgorithm::Ed25519);
+    assert!(!secret_key.is_empty());
+
+    // ecies 接口拒绝其他算法的密钥
+    assert!(matches!(
+        client.fetch_secret_key(key_id).await,
+        Err(KsError::Crypto(_))
+    ));
+
+    let (p256_key_id, _, _, _) = client
+        .generate_key_with_algorithm(KeyAlgorithm::P256)
+        .await
+        .expect("generate p256 key");
+    assert_ne!(p256_key_id, key_id);
+}
+
 #[tokio::test]
 async fn test_ks_grpc_client_end_to_end() {
     let psk = "test-ks-grpc-psk";
@@ -315,6 +370,196 @@ async fn test_ks_grpc_client_end_to_end() {
     assert_eq!(fetched_tolerance_seconds, 90);
 }
 
+#[tokio::test]
+async fn test_ks_grpc_client_batch_fetch() {
+    let psk = "test-ks-grpc-batch-psk";
+    let server = start_grpc_server(psk, 3600, 90).await;
+
+    let mut client = GrpcClient::new(&GrpcClientConfig {
+        endpoint: server.endpoint.clone(),
+        actrix_shared_key: psk.to_string(),
+        timeout_seconds: 5,
+        enable_tls: false,
+        tls_domain: None,
+        ca_cert: None,
+        client_cert: None,
+        client_key: None,
+    })
+    .await
+    .expect("create grpc client");
+
+    let (first_key_id, first_public_key, _, _) = client.generate_key().await.expect("generate key");
+    let (second_key_id, _, _, _) = client.generate_key().await.expect("generate key");
+    let (revoked_key_id, _, _, _) = client.generate_key().await.expect("generate key");
+    client.revoke_key(revoked_key_id).await.expect("revoke key");
+    let missing_key_id = 9_999_999_u32;
+
+    let key_ids = [
+        first_key_id,
+        second_key_id,
+        revoked_key_id,
+        missing_key_id,
+        first_key_id,
+    ];
+
+    let public_keys = client
+        .fetch_public_keys(&key_ids)
+        .await
+        .expect("fetch public keys");
+    let fetched_ids: Vec<u32> = public_keys.keys.iter().map(|key| key.0).collect();
+    assert_eq!(fetched_ids, vec![first_key_id, second_key_id]);
+    assert_eq!(public_keys.keys[0].1, first_public_key);
+    assert_eq!(public_keys.keys[0].3, 90);
+    assert_eq!(public_keys.missing_key_ids, vec![missing_key_id]);
+    assert_eq!(public_keys.revoked_key_ids, vec![revoked_key_id]);
+
+    let secret_keys = client
+        .fetch_secret_keys(&key_ids)
+        .await
+        .expect("fetch secret keys");
+    let fetched_ids: Vec<u32> = secret_keys.keys.iter().map(|key| key.0).collect();
+    assert_eq!(fetched_ids, vec![first_key_id, second_key_id]);
+    assert_eq!(
+        ecies::PublicKey::from_secret_key(&secret_keys.keys[0].1),
+        first_public_key
+    );
+    assert_eq!(secret_keys.missing_key_ids, vec![missing_key_id]);
+    assert_eq!(secret_keys.revoked_key_ids, vec![revoked_key_id]);
+
+    // 超过单次上限的请求被拒绝
+    let too_many: Vec<u32> = (1..=ks::MAX_BATCH_KEY_IDS as u32 + 1).collect();
+    assert!(client.fetch_secret_keys(&too_many).await.is_err());
+}
+
+#[tokio::test]
+async fn test_grpc_get_secret_keys_rejects_mismatched_signature() {
+    let psk = "test-ks-grpc-psk";
+    let server = start_grpc_server(psk, 3600, 3600).await;
+    let mut client = connect_client(&server.endpoint).await;
+
+    // 签名覆盖完整的 key_id 列表
+    let err = client
+        .get_secret_keys(GetSecretKeysRequest {
+            key_ids: vec![1, 2],
+            credential: sign_credential(psk, "get_secret_keys:1"),
+        })
+        .await
+        .expect_err("signature over different key_ids should be rejected");
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_ks_grpc_client_rotate_key_and_watch() {
+    let psk = "test-ks-grpc-rotate-psk";
+    let server = start_grpc_server(psk, 3600, 90).await;
+
+    let mut client = GrpcClient::new(&GrpcClientConfig {
+        endpoint: server.endpoint.clone(),
+        actrix_shared_key: psk.to_string(),
+        timeout_seconds: 5,
+        enable_tls: false,
+        tls_domain: None,
+        ca_cert: None,
+        client_cert: None,
+        client_key: None,
+    })
+    .await
+    .expect("create grpc client");
+
+    let (old_key_id, _, _, _) = client.generate_key().await.expect("generate key");
+    let mut watch = client
+        .watch_key_rotations()
+        .await
+        .expect("watch key rotations");
+
+    let rotated = client
+        .rotate_key(Some(old_key_id), Some(300))
+        .await
+        .expect("rotate key");
+    assert_ne!(rotated.key_id, old_key_id);
+    assert_eq!(rotated.previous_key_id, Some(old_key_id));
+
+    // 进程级事件通道，按 key_id 找到本次轮替
+    let event = loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), watch.next())
+            .await
+            .expect("rotation event timeout")
+            .expect("rotation stream error")
+            .expect("rotation stream closed");
+        if event.key_id == rotated.key_id {
+            break event;
+        }
+    };
+    assert_eq!(event.previous_key_id, Some(old_key_id));
+    assert_eq!(event.verify_until, rotated.verify_until);
+
+    // 旧密钥在宽限期内仍可获取，截止时间为宽限期结束
+    let (_secret_key, expires_at, tolerance_seconds) = client
+        .fetch_secret_key(old_key_id)
+        .await
+        .expect("fetch verify-only key");
+    assert_eq!(expires_at + tolerance_seconds, rotated.verify_until);
+
+    // 同一密钥不能重复轮替
+    assert!(client.rotate_key(Some(old_key_id), None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_ks_grpc_client_revoke_key_and_watch() {
+    let psk = "test-ks-grpc-revoke-psk";
+    let server = start_grpc_server(psk, 3600, 90).await;
+
+    let mut client = GrpcClient::new(&GrpcClientConfig {
+        endpoint: server.endpoint.clone(),
+        actrix_shared_key: psk.to_string(),
+        timeout_seconds: 5,
+        enable_tls: false,
+        tls_domain: None,
+        ca_cert: None,
+        client_cert: None,
+        client_key: None,
+    })
+    .await
+    .expect("create grpc client");
+
+    let (leaked_key_id, _, _, _) = client.generate_key().await.expect("generate key");
+    let (other_key_id, _, _, _) = client.generate_key().await.expect("generate key");
+
+    let revoked = client.revoke_key(leaked_key_id).await.expect("revoke key");
+    assert_eq!(revoked.key_id, leaked_key_id);
+
+    // 吊销后不再提供私钥，且与其他错误区分
+    assert!(matches!(
+        client.fetch_secret_key(leaked_key_id).await,
+        Err(KsError::KeyRevoked(id)) if id == leaked_key_id
+    ));
+    assert!(client.revoke_key(leaked_key_id).await.is_err());
+
+    // 订阅时先收到已吊销的密钥快照
+    let mut watch = client
+        .watch_key_revocations()
+        .await
+        .expect("watch key revocations");
+    async fn next_event(watch: &mut KeyRevocationWatch) -> KeyRevocation {
+        tokio::time::timeout(Duration::from_secs(5), watch.next())
+            .await
+            .expect("revocation event timeout")
+            .expect("revocation stream error")
+            .expect("revocation stream closed")
+    }
+    let snapshot = next_event(&mut watch).await;
+    assert_eq!(snapshot.key_id, leaked_key_id);
+    assert_eq!(snapshot.revoked_at, revoked.revoked_at);
+
+    // 进程级事件通道，按 key_id 找到本次吊销
+    client.revoke_key(other_key_id).await.expect("revoke key");
+    loop {
+        if next_event(&mut watch).await.key_id == other_key_id {
+            break;
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_ks_grpc_client_rejects_wrong_shared_secret() {
     let server = start_grpc_server("correct-secret", 3600, 60).await;
@@ -60,7 +60,10 @@ async fn generate_key_via_http(
     psk: &str,
 ) -> GenerateKeyResponse {
     let credential = sign_request(psk, "generate_key");
-    let req = GenerateKeyRequest { credential };
+    let req = GenerateKeyRequest {
+        credential,
+        algorithm: None,
+    };
     let resp = client
         .post(format!("{base_url}/generate"))
         .json(&req)
@@ -87,7 +90,10 @@ async fn test_http_health_and_key_lifecycle() {
     assert_eq!(health_json["status"], "healthy");
 
     let credential = sign_request(psk, "generate_key");
-    let generate_req = GenerateKeyRequest { credential };
+    let generate_req = GenerateKeyRequest {
+        credential,
+        algorithm: None,
+    };
     let generated = client
         .post(format!("{}/generate", server.base_url))
         .json(&generate_req)
@@ -150,6 +156,7 @@ async fn test_http_generate_rejects_invalid_signature() {
     let invalid_credential = sign_request(psk, "not-generate-key");
     let req = GenerateKeyRequest {
         credential: invalid_credential,
+        algorithm: None,
     };
     let response = client
         .post(format!("{}/generate", server.base_url))
@@ -171,6 +178,7 @@ async fn test_http_generate_replay_is_rejected() {
     let credential = sign_request(psk, "generate_key");
     let req_body = GenerateKeyRequest {
         credential: credential.clone(),
+        algorithm: None,
     };
 
     let url = format!("{}/generate", server.base_url);
@@ -191,7 +199,10 @@ async fn test_http_generate_timestamp_out_of_window() {
     let mut credential = sign_request(psk, "generate_key");
     // push timestamp far into the past to exceed default window
     credential.timestamp = 0;
-    let req_body = GenerateKeyRequest { credential };
+    let req_body = GenerateKeyRequest {
+        credential,
+        algorithm: None,
+    };
 
     let url = format!("{}/generate", server.base_url);
     let resp = client.post(&url).json(&req_body).send().await.unwrap();
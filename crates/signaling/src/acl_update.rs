@@ -0,0 +1,133 @@
+//! 运行时 ACL 更新 (UpdateAclRequest)
+//!
+//! 已注册的服务可以在不重新注册的情况下整体替换或增量修改自身类型的入站 ACL 规则，
+//! 规则写入数据库后立即作用于发现、Presence 与中继检查。
+//!
+//! # 请求方式
+//! actr-protocol 目前没有专用的 payload，客户端通过 `ActrToSignaling` 的 `Error` payload 发送：
+//! `code` 为 [`UPDATE_ACL_CODE`]，`message` 为 [`UPDATE_ACL_REQUEST_PREFIX`]
+//! 加 JSON 编码的 [`AclUpdate`]。
+//!
+//! 成功时服务器以同一 code 回复（`reply_for` 指向请求），`message` 为
+//! [`UPDATE_ACL_RESPONSE_PREFIX`] 加 JSON 编码的 [`UpdateAclResponse`]；
+//! 校验失败回复 code 400，存储失败回复 code 500。
+//!
+//! HTTP 等价接口见 [`crate::realm_admin`] 的 `/admin/realms/{realm_id}/acl/types/{to_type}`。
+
+use crate::tunnel;
+use actr_protocol::ErrorResponse;
+use actrix_common::realm::{AclUpdate, ActorAcl, InboundAclRule};
+use serde::{Deserialize, Serialize};
+
+/// 请求与响应使用的 ErrorResponse code
+pub const UPDATE_ACL_CODE: u32 = tunnel::UPDATE_ACL_CODE;
+
+/// 请求 message 前缀，其后为 JSON 编码的 [`AclUpdate`]
+pub const UPDATE_ACL_REQUEST_PREFIX: &str = tunnel::UPDATE_ACL_REQUEST.prefix;
+
+/// 响应 message 前缀，其后为 JSON 编码的 [`UpdateAclResponse`]
+pub const UPDATE_ACL_RESPONSE_PREFIX: &str = tunnel::UPDATE_ACL_RESPONSE.prefix;
+
+/// 从客户端 ErrorResponse 中解析 ACL 更新请求
+///
+/// 不是更新请求时返回 None；是更新请求但内容无效时返回 `Some(Err)`
+pub fn from_error_response(error: &ErrorResponse) -> Option<Result<AclUpdate, serde_json::Error>> {
+    tunnel::UPDATE_ACL_REQUEST.decode(error)
+}
+
+/// 编码为客户端发送的 ErrorResponse
+pub fn to_error_response(update: &AclUpdate) -> ErrorResponse {
+    tunnel::UPDATE_ACL_REQUEST.encode(update)
+}
+
+/// ACL 更新结果：更新后目标类型的全部入站规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateAclResponse {
+    pub to_type: String,
+    pub rules: Vec<InboundAclRule>,
+}
+
+impl UpdateAclResponse {
+    pub fn new(to_type: String, rules: &[ActorAcl]) -> Self {
+        Self {
+            to_type,
+            rules: rules
+                .iter()
+                .map(|rule| InboundAclRule {
+                    from_type: rule.from_type.clone(),
+                    access: rule.access,
+                })
+                .collect(),
+        }
+    }
+
+    /// 从服务器回复中解析
+    pub fn from_error_response(error: &ErrorResponse) -> Option<Result<Self, serde_json::Error>> {
+        tunnel::UPDATE_ACL_RESPONSE.decode(error)
+    }
+
+    /// 编码为服务器回复的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::UPDATE_ACL_RESPONSE.encode(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::realm::AclUpdateMode;
+
+    #[test]
+    fn test_request_roundtrip() {
+        let update = AclUpdate {
+            mode: AclUpdateMode::Patch,
+            rules: vec![InboundAclRule {
+                from_type: "acme:client".to_string(),
+                access: true,
+            }],
+            remove: vec!["acme:guest".to_string()],
+        };
+        let parsed = from_error_response(&to_error_response(&update))
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed.mode, AclUpdateMode::Patch);
+        assert_eq!(parsed.rules, update.rules);
+        assert_eq!(parsed.remove, update.remove);
+
+        // 省略 mode 时默认整体替换
+        let minimal = ErrorResponse {
+            code: UPDATE_ACL_CODE,
+            message: format!("{UPDATE_ACL_REQUEST_PREFIX}{{\"rules\":[]}}"),
+        };
+        let parsed = from_error_response(&minimal).unwrap().unwrap();
+        assert_eq!(parsed.mode, AclUpdateMode::Replace);
+    }
+
+    #[test]
+    fn test_ignores_other_messages() {
+        let other = ErrorResponse {
+            code: 500,
+            message: "UpdateAclRequest:{}".to_string(),
+        };
+        assert!(from_error_response(&other).is_none());
+
+        // 服务器回复不会被当作请求
+        let response = UpdateAclResponse {
+            to_type: "acme:echo".to_string(),
+            rules: vec![],
+        }
+        .to_error_response();
+        assert!(from_error_response(&response).is_none());
+        assert!(
+            UpdateAclResponse::from_error_response(&response)
+                .unwrap()
+                .is_ok()
+        );
+
+        let invalid = ErrorResponse {
+            code: UPDATE_ACL_CODE,
+            message: format!("{UPDATE_ACL_REQUEST_PREFIX}not json"),
+        };
+        assert!(from_error_response(&invalid).unwrap().is_err());
+    }
+}
@@ -0,0 +1,1069 @@
+//! Signaling 管理 API
+//!
+//! 面向运维的端点，挂载在 Signaling Router 的 `/admin` 下：
+//! - `GET /admin/traffic?top=N`：按 ActrType 的流量统计
+//! - `GET /admin/connection-reports?realm_id=N`：按 Realm 聚合的 ICE 连接上报（见 [`crate::connection_report`]）
+//! - `GET /admin/connections?realm_id=N`：当前连接的 Actor、注册的服务及最近一次心跳指标
+//! - `DELETE /admin/connections/{actor_id}`：强制断开指定 Actor（事件响应）
+//! - `GET /admin/discovery`：按 ActrType 分页浏览已注册服务（游标分页、名称前缀/标签/实例元数据过滤、排序）
+//! - `GET /admin/services/{service_name}/spec-history`：服务发布过的 ServiceSpec 版本（可按 fingerprint 取单个版本）
+//! - `POST /admin/notices`：向全部或筛选后的在线 Actor 广播运维通知（见 [`crate::server_notice`]）
+//! - `GET / POST / DELETE /admin/drain`：查询、进入、退出节点排空状态（见 [`crate::drain`]）
+//! - `GET / PATCH /admin/rate-limits`：查询限流配置与令牌桶水位、运行时调整限额（见 [`crate::ratelimit`]）
+//! - `/admin/realms/{realm_id}/...`：Realm API Key 管理与租户自助端点（见 [`crate::realm_admin`]）
+//!
+//! 流量、连接上报、服务浏览与 spec 历史属于统计查询，过载降级期间返回 503（见 [`crate::load_shed`]）。
+//!
+//! 除租户自助端点也接受 Realm API Key 外，所有端点需要 `Authorization: Bearer <actrix_shared_key>`。
+//! 同样的能力通过 Supervisord gRPC (`ListConnections` / `DisconnectActor` / `GetServiceSpecHistory` /
+//! `BroadcastServerNotice`) 暴露给 Supervisor，
+//! 由 [`registered_server`] 获取当前进程内的 SignalingServer。
+
+use crate::axum_router::SignalingState;
+use crate::drain::DrainRedirect;
+use crate::load_shed::StatsQuery;
+use crate::server::{SignalingServer, cleanup_client};
+use crate::server_notice::{NoticeFilter, NoticeSeverity, ServerNotice, broadcast_notice};
+use crate::service_registry::{
+    DiscoveryQuery, DiscoverySort, ServiceStatus, SpecVersion, parse_metadata_filter,
+};
+use actr_protocol::{ActrId, ActrIdExt};
+use actrix_common::config::signaling::RateLimitConfig;
+use actrix_common::util::constant_time_eq;
+use axum::{
+    Router,
+    extract::{
+        FromRequestParts, Path, Query, State,
+        ws::{CloseFrame, close_code},
+    },
+    http::{StatusCode, request::Parts},
+    response::Json,
+    routing::{delete, get, patch, post},
+};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use prost::Message as _;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
+
+/// `/admin/traffic` 默认返回的类型数量
+const DEFAULT_TRAFFIC_TOP_N: usize = 20;
+
+/// `/admin/traffic` 单次最多返回的类型数量
+const MAX_TRAFFIC_TOP_N: usize = 1000;
+
+/// `/admin/discovery` 默认每页类型数量
+const DEFAULT_DISCOVERY_PAGE_SIZE: usize = 100;
+
+/// `/admin/discovery` 每页最多类型数量
+const MAX_DISCOVERY_PAGE_SIZE: usize = 1000;
+
+/// `/admin/services/{service_name}/spec-history` 默认返回的版本数量
+const DEFAULT_SPEC_HISTORY_LIMIT: usize = 20;
+
+/// `/admin/services/{service_name}/spec-history` 单次最多返回的版本数量
+const MAX_SPEC_HISTORY_LIMIT: usize = 1000;
+
+/// 进程内的 SignalingServer（供 Supervisord gRPC 管理接口使用）
+static REGISTERED_SERVER: RwLock<Option<Arc<SignalingServer>>> = RwLock::new(None);
+
+/// 创建管理 API 路由
+pub fn admin_router() -> Router<SignalingState> {
+    Router::new()
+        .route("/admin/traffic", get(traffic_stats))
+        .route("/admin/connection-reports", get(connection_reports))
+        .route("/admin/connections", get(list_connections))
+        .route("/admin/connections/{actor_id}", delete(disconnect_actor))
+        .route("/admin/discovery", get(discovery_page_handler))
+        .route(
+            "/admin/services/{service_name}/spec-history",
+            get(spec_history_handler),
+        )
+        .route("/admin/notices", post(broadcast_notice_handler))
+        .route(
+            "/admin/drain",
+            get(drain_status_handler)
+                .post(start_drain_handler)
+                .delete(stop_drain_handler),
+        )
+        .route(
+            "/admin/rate-limits",
+            get(rate_limits_handler).patch(update_rate_limits_handler),
+        )
+        .merge(crate::realm_admin::realm_admin_router())
+}
+
+/// 注册进程内的 SignalingServer，后注册的覆盖先注册的
+pub(crate) fn register_server(server: Arc<SignalingServer>) {
+    *REGISTERED_SERVER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(server);
+}
+
+/// 注销进程内的 SignalingServer（Signaling 服务在运行时停用后调用）
+pub fn unregister_server() {
+    *REGISTERED_SERVER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+}
+
+/// 获取进程内已注册的 SignalingServer（Signaling 服务未启动时为 None）
+pub fn registered_server() -> Option<Arc<SignalingServer>> {
+    REGISTERED_SERVER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// 提供管理 API token 的路由状态
+///
+/// 节点级管理端点（快照、服务启停等）的状态实现此 trait 后即可复用 [`AdminAuth`]。
+pub trait AdminTokenSource {
+    /// 管理 token（未配置时返回 None，此时拒绝所有请求）
+    fn admin_token(&self) -> Option<&str>;
+}
+
+impl AdminTokenSource for SignalingState {
+    fn admin_token(&self) -> Option<&str> {
+        self.admin_token.as_deref()
+    }
+}
+
+impl<T: AdminTokenSource> AdminTokenSource for Arc<T> {
+    fn admin_token(&self) -> Option<&str> {
+        (**self).admin_token()
+    }
+}
+
+/// 管理 API 认证
+///
+/// 校验 `Authorization: Bearer <token>` 与配置的管理 token 一致（常量时间比较）；
+/// 未配置 token 时拒绝所有请求。
+pub struct AdminAuth;
+
+impl<S> FromRequestParts<S> for AdminAuth
+where
+    S: AdminTokenSource + Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Some(expected) = state.admin_token().filter(|t| !t.is_empty()) else {
+            return Err((StatusCode::UNAUTHORIZED, "Admin API is not configured"));
+        };
+
+        match crate::ws_auth::bearer_token(&parts.headers) {
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(AdminAuth),
+            _ => {
+                warn!("🚫 管理 API 认证失败");
+                Err((StatusCode::UNAUTHORIZED, "Invalid admin token"))
+            }
+        }
+    }
+}
+
+/// 连接中 Actor 注册的服务及最近一次心跳指标
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceSnapshot {
+    pub service_name: String,
+    /// ServiceSpec fingerprint
+    pub fingerprint: Option<String>,
+    pub status: ServiceStatus,
+    /// 最近一次心跳时间 (Unix 秒)
+    pub last_heartbeat_time_secs: u64,
+    pub availability_state: Option<i32>,
+    pub power_reserve: Option<f32>,
+    pub mailbox_backlog: Option<f32>,
+    /// 实例元数据（ServiceSpec 中 `key=value` 形式的标签）
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, String>,
+}
+
+/// 单个 WebSocket 连接的快照
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionSnapshot {
+    pub client_id: String,
+    /// ActrId 字符串表示（注册前为 None）
+    pub actor_id: Option<String>,
+    pub realm_id: Option<u32>,
+    pub client_ip: Option<String>,
+    pub webrtc_role: Option<String>,
+    /// 建立连接的时间 (Unix 秒)
+    pub connected_at: i64,
+    pub services: Vec<ServiceSnapshot>,
+}
+
+/// 列出当前连接，可按 realm 过滤（指定 realm 时不包含尚未注册的连接）
+pub async fn connection_snapshots(
+    server: &SignalingServer,
+    realm_id: Option<u32>,
+) -> Vec<ConnectionSnapshot> {
+    let registry = server.service_registry.read().await;
+
+    let mut snapshots: Vec<ConnectionSnapshot> = server
+        .clients
+        .collect(|_, client| {
+            let in_realm = realm_id.is_none_or(|realm_id| {
+                client
+                    .actor_id
+                    .as_ref()
+                    .is_some_and(|actor_id| actor_id.realm.realm_id == realm_id)
+            });
+            if !in_realm {
+                return None;
+            }
+            let services = client
+                .actor_id
+                .as_ref()
+                .map(|actor_id| {
+                    registry
+                        .services_of_actor(actor_id)
+                        .into_iter()
+                        .map(|service| ServiceSnapshot {
+                            service_name: service.service_name.clone(),
+                            fingerprint: service
+                                .service_spec
+                                .as_ref()
+                                .map(|spec| spec.fingerprint.clone()),
+                            status: service.status.clone(),
+                            last_heartbeat_time_secs: service.last_heartbeat_time_secs,
+                            availability_state: service.service_availability_state,
+                            power_reserve: service.power_reserve,
+                            mailbox_backlog: service.mailbox_backlog,
+                            metadata: service.metadata().cloned().unwrap_or_default(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(ConnectionSnapshot {
+                client_id: client.id.clone(),
+                actor_id: client.actor_id.as_ref().map(|id| id.to_string_repr()),
+                realm_id: client.actor_id.as_ref().map(|id| id.realm.realm_id),
+                client_ip: client.client_ip.map(|ip| ip.to_string()),
+                webrtc_role: client.webrtc_role.clone(),
+                connected_at: client.connected_at,
+                services,
+            })
+        })
+        .await;
+
+    snapshots.sort_by_key(|snapshot| snapshot.connected_at);
+    snapshots
+}
+
+/// 服务发现分页中的单个 ActrType
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredTypeSnapshot {
+    pub actr_type: String,
+    pub service_name: String,
+    pub fingerprint: Option<String>,
+    pub description: Option<String>,
+    /// 最新发布时间 (Unix 秒，未知为 0)
+    pub published_at: i64,
+    pub tags: Vec<String>,
+    /// 该类型的在线实例数
+    pub instances: usize,
+}
+
+/// 服务发现的一页结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryPage {
+    pub types: Vec<DiscoveredTypeSnapshot>,
+    /// 下一页游标，没有更多结果时为 None
+    pub next_cursor: Option<String>,
+}
+
+/// 按查询条件取一页服务发现结果（不做 ACL 过滤）
+pub async fn discovery_page(
+    server: &SignalingServer,
+    query: &DiscoveryQuery,
+    page_size: usize,
+) -> Result<DiscoveryPage, String> {
+    let registry = server.service_registry.read().await;
+    let mut types = registry.discover_types(query)?;
+
+    let next_cursor = (types.len() > page_size).then(|| types[page_size - 1].cursor());
+    types.truncate(page_size);
+
+    let types = types
+        .into_iter()
+        .map(|discovered| {
+            let service = discovered.services[0];
+            let spec = service.service_spec.as_ref();
+            DiscoveredTypeSnapshot {
+                actr_type: discovered.type_key.clone(),
+                service_name: service.service_name.clone(),
+                fingerprint: spec.map(|spec| spec.fingerprint.clone()),
+                description: spec.and_then(|spec| spec.description.clone()),
+                published_at: discovered.published_at,
+                tags: spec.map(|spec| spec.tags.clone()).unwrap_or_default(),
+                instances: discovered.services.len(),
+            }
+        })
+        .collect();
+
+    Ok(DiscoveryPage { types, next_cursor })
+}
+
+/// ServiceSpec 历史版本快照
+#[derive(Debug, Clone, Serialize)]
+pub struct SpecVersionSnapshot {
+    pub fingerprint: String,
+    /// 发布时间 (Unix 秒)
+    pub published_at: i64,
+    /// 首次注册该版本的时间 (Unix 秒)
+    pub first_seen_at: u64,
+    /// 最近一次注册该版本的时间 (Unix 秒)
+    pub last_seen_at: u64,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// protobuf 编码的完整 ServiceSpec（JSON 中为 base64），供客户端固定或对比旧版本契约
+    #[serde(serialize_with = "serialize_base64")]
+    pub spec: Vec<u8>,
+}
+
+impl From<&SpecVersion> for SpecVersionSnapshot {
+    fn from(version: &SpecVersion) -> Self {
+        Self {
+            fingerprint: version.fingerprint.clone(),
+            published_at: version.published_at,
+            first_seen_at: version.first_seen_at,
+            last_seen_at: version.last_seen_at,
+            description: version.spec.description.clone(),
+            tags: version.spec.tags.clone(),
+            spec: version.spec.encode_to_vec(),
+        }
+    }
+}
+
+fn serialize_base64<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&BASE64.encode(bytes))
+}
+
+/// 查询服务的 ServiceSpec 历史版本（最新在前），可按 fingerprint 只取单个版本
+pub async fn spec_history(
+    server: &SignalingServer,
+    service_name: &str,
+    fingerprint: Option<&str>,
+    limit: usize,
+) -> Vec<SpecVersionSnapshot> {
+    let registry = server.service_registry.read().await;
+    registry
+        .spec_history(service_name)
+        .into_iter()
+        .filter(|version| fingerprint.is_none_or(|fp| version.fingerprint == fp))
+        .take(limit)
+        .map(SpecVersionSnapshot::from)
+        .collect()
+}
+
+/// 强制断开指定 Actor 的连接
+///
+/// 向客户端发送 Close 帧并立即清理连接与服务注册。返回是否找到了在线连接。
+pub async fn force_disconnect(server: &SignalingServer, actor_id: &ActrId) -> bool {
+    let indexed = server.actor_id_index.with(actor_id, Clone::clone).await;
+    let client_id = match indexed {
+        Some(cid) if server.clients.contains_key(&cid).await => Some(cid),
+        _ => server
+            .clients
+            .collect(|id, client| (client.actor_id.as_ref() == Some(actor_id)).then(|| id.clone()))
+            .await
+            .pop(),
+    };
+
+    let Some(client_id) = client_id else {
+        return false;
+    };
+
+    server
+        .clients
+        .with(&client_id, |client| {
+            client.direct_sender.close(Some(CloseFrame {
+                code: close_code::POLICY,
+                reason: "Disconnected by administrator".into(),
+            }))
+        })
+        .await;
+
+    // 移除连接后发送通道关闭，发送任务在送出 Close 帧后退出
+    cleanup_client(&client_id, &server.handle()).await;
+    info!(
+        "🔌 管理员强制断开 Actor {} (client {})",
+        actor_id.to_string_repr(),
+        client_id
+    );
+    true
+}
+
+/// 断开所有连接（Signaling 服务停用时调用），返回断开的连接数
+///
+/// 向客户端发送 Going Away Close 帧，客户端可按自身策略重连到其他节点。
+pub async fn disconnect_all(server: &SignalingServer, reason: &str) -> usize {
+    let client_ids: Vec<String> = server
+        .clients
+        .collect(|client_id, client| {
+            client.direct_sender.close(Some(CloseFrame {
+                code: close_code::AWAY,
+                reason: reason.to_string().into(),
+            }));
+            Some(client_id.clone())
+        })
+        .await;
+
+    let handle = server.handle();
+    for client_id in &client_ids {
+        cleanup_client(client_id, &handle).await;
+    }
+    info!(
+        "🔌 已断开全部 {} 个 Signaling 连接: {}",
+        client_ids.len(),
+        reason
+    );
+    client_ids.len()
+}
+
+/// 运行时调整速率限制
+///
+/// `patch` 为 JSON Merge Patch（RFC 7386），合并到当前生效的配置后校验，
+/// 再经与配置热加载相同的路径（[`SignalingServer::reload_rate_limits`]）应用，已消耗的配额清零。
+/// 返回调整后生效的配置与需要重启才能启用的限流器名称；配置无效时不做任何修改。
+///
+/// 调整不写回配置文件：配置文件中的 `rate_limit` 之后发生变化并重新加载时以配置文件为准，
+/// 重启后恢复为配置文件的值。
+pub async fn update_rate_limits(
+    server: &SignalingServer,
+    patch: Value,
+) -> Result<(RateLimitConfig, Vec<&'static str>), String> {
+    let mut merged = serde_json::to_value(server.rate_limit_config())
+        .map_err(|e| format!("Failed to serialize rate limit configuration: {e}"))?;
+    merge_patch(&mut merged, patch);
+    let config: RateLimitConfig = serde_json::from_value(merged)
+        .map_err(|e| format!("Invalid rate limit configuration: {e}"))?;
+    config.validate()?;
+
+    let restart_required = server.reload_rate_limits(&config).await;
+    Ok((server.rate_limit_config(), restart_required))
+}
+
+/// 按 JSON Merge Patch 规则合并：对象逐字段合并，`null` 删除字段（恢复默认值），其余直接替换
+fn merge_patch(target: &mut Value, patch: Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+/// `/admin/traffic` 查询参数
+#[derive(Debug, Deserialize)]
+struct TrafficQuery {
+    /// 返回前 N 个类型（按近期字节速率排序）
+    top: Option<usize>,
+}
+
+/// 按 ActrType 的信令流量统计
+///
+/// 返回按近期字节速率排序的 Top-N 类型，用于容量规划
+async fn traffic_stats(
+    _auth: AdminAuth,
+    _shed: StatsQuery,
+    State(state): State<SignalingState>,
+    Query(query): Query<TrafficQuery>,
+) -> Json<Value> {
+    let Some(stats) = state.server.traffic_stats.as_ref() else {
+        return Json(json!({
+            "status": "error",
+            "message": "Traffic stats are disabled"
+        }));
+    };
+
+    let top_n = query
+        .top
+        .unwrap_or(DEFAULT_TRAFFIC_TOP_N)
+        .min(MAX_TRAFFIC_TOP_N);
+
+    Json(json!({
+        "status": "success",
+        "traffic": stats.snapshot(top_n)
+    }))
+}
+
+/// 按 Realm 聚合的 ICE 连接上报
+///
+/// 用于了解各 Realm 实际经 TURN 中继的连接比例
+async fn connection_reports(
+    _auth: AdminAuth,
+    _shed: StatsQuery,
+    State(state): State<SignalingState>,
+    Query(query): Query<ConnectionsQuery>,
+) -> Json<Value> {
+    Json(json!({
+        "status": "success",
+        "realms": state.server.connection_reports.snapshot(query.realm_id)
+    }))
+}
+
+/// `/admin/connections` 查询参数
+#[derive(Debug, Deserialize)]
+struct ConnectionsQuery {
+    /// 仅列出该 realm 的 Actor
+    realm_id: Option<u32>,
+}
+
+/// 当前连接的 Actor 列表
+async fn list_connections(
+    _auth: AdminAuth,
+    State(state): State<SignalingState>,
+    Query(query): Query<ConnectionsQuery>,
+) -> Json<Value> {
+    let connections = connection_snapshots(&state.server, query.realm_id).await;
+
+    Json(json!({
+        "status": "success",
+        "total": connections.len(),
+        "connections": connections
+    }))
+}
+
+/// `/admin/discovery` 查询参数
+#[derive(Debug, Deserialize)]
+struct DiscoveryParams {
+    realm_id: Option<u32>,
+    manufacturer: Option<String>,
+    name_prefix: Option<String>,
+    /// 逗号分隔，需全部匹配
+    tags: Option<String>,
+    /// 逗号分隔的 `key=value`，实例需全部具备
+    metadata: Option<String>,
+    #[serde(default)]
+    sort: DiscoverySort,
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
+
+/// 分页浏览已注册服务
+async fn discovery_page_handler(
+    _auth: AdminAuth,
+    _shed: StatsQuery,
+    State(state): State<SignalingState>,
+    Query(params): Query<DiscoveryParams>,
+) -> (StatusCode, Json<Value>) {
+    let metadata = match parse_metadata_filter(params.metadata.as_deref().unwrap_or_default()) {
+        Ok(metadata) => metadata,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "status": "error",
+                    "message": message
+                })),
+            );
+        }
+    };
+    let query = DiscoveryQuery {
+        realm_id: params.realm_id,
+        manufacturer: params.manufacturer,
+        name_prefix: params.name_prefix,
+        tags: params
+            .tags
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect(),
+        metadata,
+        sort: params.sort,
+        cursor: params.cursor,
+        after_type: None,
+    };
+    let page_size = params
+        .limit
+        .unwrap_or(DEFAULT_DISCOVERY_PAGE_SIZE)
+        .clamp(1, MAX_DISCOVERY_PAGE_SIZE);
+
+    match discovery_page(&state.server, &query, page_size).await {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "success",
+                "types": page.types,
+                "next_cursor": page.next_cursor
+            })),
+        ),
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "status": "error",
+                "message": message
+            })),
+        ),
+    }
+}
+
+/// `/admin/services/{service_name}/spec-history` 查询参数
+#[derive(Debug, Deserialize)]
+struct SpecHistoryParams {
+    /// 只返回指定 fingerprint 的版本
+    fingerprint: Option<String>,
+    limit: Option<usize>,
+}
+
+/// 服务的 ServiceSpec 历史版本
+async fn spec_history_handler(
+    _auth: AdminAuth,
+    _shed: StatsQuery,
+    State(state): State<SignalingState>,
+    Path(service_name): Path<String>,
+    Query(params): Query<SpecHistoryParams>,
+) -> (StatusCode, Json<Value>) {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SPEC_HISTORY_LIMIT)
+        .clamp(1, MAX_SPEC_HISTORY_LIMIT);
+    let versions = spec_history(
+        &state.server,
+        &service_name,
+        params.fingerprint.as_deref(),
+        limit,
+    )
+    .await;
+
+    if versions.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "status": "error",
+                "message": format!("No spec history for service: {service_name}")
+            })),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "service_name": service_name,
+            "versions": versions
+        })),
+    )
+}
+
+/// 强制断开指定 Actor
+async fn disconnect_actor(
+    _auth: AdminAuth,
+    State(state): State<SignalingState>,
+    Path(actor_id): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    let Ok(actor_id) = ActrId::from_string_repr(&actor_id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "status": "error",
+                "message": format!("Invalid actor_id: {actor_id}")
+            })),
+        );
+    };
+
+    if force_disconnect(&state.server, &actor_id).await {
+        (StatusCode::OK, Json(json!({ "status": "success" })))
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "status": "error",
+                "message": "Actor is not connected"
+            })),
+        )
+    }
+}
+
+/// `POST /admin/notices` 请求体
+#[derive(Debug, Deserialize)]
+struct BroadcastNoticeBody {
+    #[serde(default)]
+    severity: NoticeSeverity,
+    category: String,
+    message: String,
+    effective_at: Option<i64>,
+    #[serde(default)]
+    filter: NoticeFilter,
+}
+
+/// 广播运维通知
+async fn broadcast_notice_handler(
+    _auth: AdminAuth,
+    State(state): State<SignalingState>,
+    Json(body): Json<BroadcastNoticeBody>,
+) -> (StatusCode, Json<Value>) {
+    if body.message.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "status": "error",
+                "message": "Notice message must not be empty"
+            })),
+        );
+    }
+
+    let notice = ServerNotice::new(
+        body.severity,
+        body.category,
+        body.message,
+        body.effective_at,
+    );
+    let delivered = broadcast_notice(&state.server, &notice, &body.filter).await;
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "notice_id": notice.notice_id,
+            "delivered": delivered
+        })),
+    )
+}
+
+/// 查询节点排空状态
+async fn drain_status_handler(
+    _auth: AdminAuth,
+    State(state): State<SignalingState>,
+) -> Json<Value> {
+    Json(json!({
+        "status": "success",
+        "drain": state.server.drain.status()
+    }))
+}
+
+/// 进入排空状态（已在排空中时更新重定向提示）
+async fn start_drain_handler(
+    _auth: AdminAuth,
+    State(state): State<SignalingState>,
+    Json(redirect): Json<DrainRedirect>,
+) -> Json<Value> {
+    state.server.drain.start(redirect);
+    Json(json!({
+        "status": "success",
+        "drain": state.server.drain.status()
+    }))
+}
+
+/// 退出排空状态
+async fn stop_drain_handler(_auth: AdminAuth, State(state): State<SignalingState>) -> Json<Value> {
+    let was_draining = state.server.drain.stop();
+    Json(json!({
+        "status": "success",
+        "was_draining": was_draining
+    }))
+}
+
+/// 查询速率限制配置与令牌桶水位
+async fn rate_limits_handler(_auth: AdminAuth, State(state): State<SignalingState>) -> Json<Value> {
+    let status = state.server.rate_limit_status().await;
+    status.record_metrics();
+    Json(json!({
+        "status": "success",
+        "config": state.server.rate_limit_config(),
+        "limiters": status
+    }))
+}
+
+/// 运行时调整速率限制
+async fn update_rate_limits_handler(
+    _auth: AdminAuth,
+    State(state): State<SignalingState>,
+    Json(patch): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    match update_rate_limits(&state.server, patch).await {
+        Ok((config, restart_required)) => {
+            info!(
+                "⚙️  速率限制已通过管理 API 调整 (restart required: {:?})",
+                restart_required
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "success",
+                    "config": config,
+                    "restart_required": restart_required
+                })),
+            )
+        }
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "status": "error",
+                "message": message
+            })),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outbound::{OutboundReceiver, outbound_channel};
+    use crate::server::ClientConnection;
+    use actr_protocol::{ActrType, Realm};
+    use axum::extract::ws::Message as WsMessage;
+
+    fn actor(realm_id: u32, serial_number: u64) -> ActrId {
+        ActrId {
+            realm: Realm { realm_id },
+            serial_number,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: "echo".to_string(),
+                version: None,
+            },
+        }
+    }
+
+    async fn connect(
+        server: &SignalingServer,
+        actor_id: ActrId,
+        connected_at: i64,
+    ) -> OutboundReceiver {
+        let (tx, rx) = outbound_channel(&server.limits);
+        let client_id = format!("client-{}", actor_id.serial_number);
+        server
+            .actor_id_index
+            .insert(actor_id.clone(), client_id.clone())
+            .await;
+        server
+            .clients
+            .insert(
+                client_id.clone(),
+                ClientConnection {
+                    id: client_id,
+                    actor_id: Some(actor_id),
+                    credential: None,
+                    direct_sender: tx,
+                    client_ip: None,
+                    webrtc_role: None,
+                    connected_at,
+                    fingerprint: String::new(),
+                },
+            )
+            .await;
+        rx
+    }
+
+    #[test]
+    fn test_parse_metadata_filter() {
+        let metadata = parse_metadata_filter(" region=cn-beijing, capacity = large ,").unwrap();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata["region"], "cn-beijing");
+        assert_eq!(metadata["capacity"], "large");
+
+        assert!(parse_metadata_filter("").unwrap().is_empty());
+        assert!(parse_metadata_filter("region").is_err());
+        assert!(parse_metadata_filter("=large").is_err());
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = json!({ "a": { "b": 1, "c": 2 }, "d": 3 });
+        merge_patch(&mut target, json!({ "a": { "b": 10, "c": null }, "e": 5 }));
+        assert_eq!(target, json!({ "a": { "b": 10 }, "d": 3, "e": 5 }));
+    }
+
+    #[tokio::test]
+    async fn test_update_rate_limits() {
+        let mut server = SignalingServer::new();
+        server.message_rate_limiter = Some(Arc::new(crate::ratelimit::MessageRateLimiter::new(
+            Default::default(),
+        )));
+        let per_second = server.rate_limit_config().message.per_second;
+
+        let (config, restart_required) =
+            update_rate_limits(&server, json!({ "message": { "burst_size": 7 } }))
+                .await
+                .unwrap();
+        // 未修改的字段保持原值
+        assert_eq!(config.message.burst_size, 7);
+        assert_eq!(config.message.per_second, per_second);
+        assert!(restart_required.is_empty());
+        assert_eq!(
+            server
+                .message_rate_limiter
+                .as_ref()
+                .unwrap()
+                .config()
+                .burst_size,
+            7
+        );
+
+        // 启动时未创建的限流器需要重启
+        let (config, restart_required) =
+            update_rate_limits(&server, json!({ "realm": { "enabled": true } }))
+                .await
+                .unwrap();
+        assert_eq!(restart_required, vec!["realm"]);
+        assert!(!config.realm.enabled);
+
+        // 无效配置整体不生效
+        assert!(
+            update_rate_limits(&server, json!({ "message": { "per_second": 0 } }))
+                .await
+                .is_err()
+        );
+        assert!(
+            update_rate_limits(&server, json!({ "message": { "burst_size": "many" } }))
+                .await
+                .is_err()
+        );
+        assert_eq!(server.rate_limit_config().message.per_second, per_second);
+    }
+
+    #[tokio::test]
+    async fn test_connection_snapshots_filter_by_realm() {
+        let server = SignalingServer::new();
+        let _rx1 = connect(&server, actor(1, 10), 200).await;
+        let _rx2 = connect(&server, actor(1, 11), 100).await;
+        let _rx3 = connect(&server, actor(2, 12), 300).await;
+
+        let all = connection_snapshots(&server, None).await;
+        assert_eq!(all.len(), 3);
+        // 按连接时间排序
+        assert_eq!(all[0].client_id, "client-11");
+
+        let realm_1 = connection_snapshots(&server, Some(1)).await;
+        assert_eq!(realm_1.len(), 2);
+        assert!(realm_1.iter().all(|c| c.realm_id == Some(1)));
+    }
+
+    #[tokio::test]
+    async fn test_force_disconnect_sends_close_and_cleans_up() {
+        let server = SignalingServer::new();
+        let target = actor(1, 10);
+        let mut rx = connect(&server, target.clone(), 100).await;
+
+        assert!(force_disconnect(&server, &target).await);
+        assert!(matches!(rx.recv().await, Some(WsMessage::Close(Some(_)))));
+        // 连接已移除，发送通道关闭
+        assert!(rx.recv().await.is_none());
+        assert!(server.clients.is_empty().await);
+        assert!(server.actor_id_index.is_empty().await);
+
+        assert!(!force_disconnect(&server, &target).await);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_notice_respects_filter() {
+        let server = SignalingServer::new();
+        let mut rx1 = connect(&server, actor(1, 10), 100).await;
+        let mut rx2 = connect(&server, actor(2, 11), 100).await;
+
+        let notice = ServerNotice::new(NoticeSeverity::Warning, "quota", "Quota at 90%", None);
+        let filter = NoticeFilter {
+            realm_ids: vec![1],
+            ..Default::default()
+        };
+        assert_eq!(broadcast_notice(&server, &notice, &filter).await, 1);
+
+        let Some(WsMessage::Binary(bytes)) = rx1.recv().await else {
+            panic!("expected binary envelope");
+        };
+        let envelope = actr_protocol::SignalingEnvelope::decode(&bytes[..]).unwrap();
+        let Some(actr_protocol::signaling_envelope::Flow::ServerToActr(msg)) = envelope.flow else {
+            panic!("expected ServerToActr flow");
+        };
+        let Some(actr_protocol::signaling_to_actr::Payload::Error(error)) = msg.payload else {
+            panic!("expected ErrorResponse payload");
+        };
+        assert_eq!(error.code, crate::server_notice::SERVER_NOTICE_CODE);
+        assert!(error.message.contains(&notice.notice_id));
+        assert!(envelope.reply_for.is_none());
+
+        // realm 2 的连接未收到通知
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_millis(50), rx2.recv())
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            broadcast_notice(&server, &notice, &NoticeFilter::default()).await,
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discovery_page_cursor() {
+        let server = SignalingServer::new();
+        {
+            let mut registry = server.service_registry.write().await;
+            for (serial, name) in ["alpha", "bravo", "charlie"].iter().enumerate() {
+                let mut actor_id = actor(1, serial as u64);
+                actor_id.r#type.name = name.to_string();
+                registry
+                    .register_service(actor_id, name.to_string(), vec![], None)
+                    .unwrap();
+            }
+        }
+
+        let mut query = DiscoveryQuery::default();
+        let first = discovery_page(&server, &query, 2).await.unwrap();
+        assert_eq!(first.types.len(), 2);
+        assert_eq!(first.types[0].service_name, "alpha");
+        assert!(first.next_cursor.is_some());
+
+        query.cursor = first.next_cursor;
+        let second = discovery_page(&server, &query, 2).await.unwrap();
+        assert_eq!(second.types.len(), 1);
+        assert_eq!(second.types[0].service_name, "charlie");
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_spec_history_snapshots() {
+        let server = SignalingServer::new();
+        {
+            let mut registry = server.service_registry.write().await;
+            for (serial, fingerprint) in ["fp-1", "fp-2"].iter().enumerate() {
+                let spec = actr_protocol::ServiceSpec {
+                    name: "echo".to_string(),
+                    fingerprint: fingerprint.to_string(),
+                    description: None,
+                    protobufs: vec![],
+                    published_at: Some(100 + serial as i64),
+                    tags: vec![],
+                };
+                registry
+                    .register_service_full(
+                        actor(1, serial as u64),
+                        "echo".to_string(),
+                        vec![],
+                        None,
+                        Some(spec),
+                        None,
+                        None,
+                    )
+                    .unwrap();
+            }
+        }
+
+        let versions = spec_history(&server, "echo", None, 10).await;
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].fingerprint, "fp-2");
+        assert_eq!(versions[1].published_at, 100);
+        let decoded = actr_protocol::ServiceSpec::decode(&versions[1].spec[..]).unwrap();
+        assert_eq!(decoded.fingerprint, "fp-1");
+
+        let pinned = spec_history(&server, "echo", Some("fp-1"), 10).await;
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(spec_history(&server, "echo", None, 1).await.len(), 1);
+        assert!(spec_history(&server, "other", None, 10).await.is_empty());
+    }
+}
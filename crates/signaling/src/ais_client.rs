@@ -1,18 +1,26 @@
 //! AIS (Actor Identity Service) 客户端
 //!
-//! 用于 Signaling 服务调用 AIS 重新签发 Credential
+//! 用于 Signaling 服务调用 AIS 重新签发 Credential，并定期拉取凭证吊销过滤器
+//!
+//! endpoint 为域名时在后台按 TTL 重新解析，解析结果变化后重建 HTTP 客户端，
+//! 丢弃连接池中指向旧地址的连接
 
 use actr_protocol::{ActrType, Realm, RegisterRequest, RegisterResponse, register_response};
+use actrix_common::aid::{AIdCredentialValidator, RevocationListResponse};
+use actrix_proto::dns::DnsWatch;
 use anyhow::{Result, anyhow};
 use prost::Message;
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
-use tracing::{debug, error};
+use tracing::{debug, error, info, warn};
 
 /// AIS 客户端配置
 #[derive(Debug, Clone)]
 pub struct AisClientConfig {
     /// AIS 服务端点 URL (例如: "http://127.0.0.1:8443")
     pub endpoint: String,
+    /// AIS 路由前缀 (例如: "/ais")
+    pub route_prefix: String,
     /// 请求超时时间（秒）
     pub timeout_seconds: u64,
 }
@@ -21,6 +29,7 @@ impl Default for AisClientConfig {
     fn default() -> Self {
         Self {
             endpoint: "https://127.0.0.1:8443".to_string(),
+            route_prefix: "/ais".to_string(),
             timeout_seconds: 30,
         }
     }
@@ -29,25 +38,58 @@ impl Default for AisClientConfig {
 /// AIS 客户端
 #[derive(Debug)]
 pub struct AisClient {
-    endpoint: String,
-    client: reqwest::Client,
+    /// 端点与路由前缀拼接后的基础 URL
+    base_url: String,
+    timeout_seconds: u64,
+    client: RwLock<reqwest::Client>,
+    dns: Option<Mutex<DnsWatch>>,
 }
 
 impl AisClient {
     /// 创建新的 AIS 客户端
     pub fn new(config: &AisClientConfig) -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .danger_accept_invalid_certs(true) // 开发环境允许自签名证书
-            .build()
-            .map_err(|e| anyhow!("Failed to create HTTP client: {e}"))?;
+        let client = Self::build_http_client(config.timeout_seconds)?;
 
         Ok(Self {
-            endpoint: config.endpoint.clone(),
-            client,
+            base_url: format!(
+                "{}{}",
+                config.endpoint.trim_end_matches('/'),
+                config.route_prefix
+            ),
+            timeout_seconds: config.timeout_seconds,
+            client: RwLock::new(client),
+            dns: DnsWatch::spawn(&config.endpoint).map(Mutex::new),
         })
     }
 
+    fn build_http_client(timeout_seconds: u64) -> Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_seconds))
+            .danger_accept_invalid_certs(true) // 开发环境允许自签名证书
+            .build()
+            .map_err(|e| anyhow!("Failed to create HTTP client: {e}"))
+    }
+
+    /// 获取 HTTP 客户端；endpoint 的 DNS 解析结果变化时先重建
+    fn http_client(&self) -> reqwest::Client {
+        if let Some(dns) = &self.dns {
+            let mut dns = dns.lock().unwrap();
+            if dns.take_change() {
+                info!(
+                    "AIS endpoint {} now resolves to {:?}, rebuilding HTTP client",
+                    dns.host(),
+                    dns.addresses()
+                );
+                match Self::build_http_client(self.timeout_seconds) {
+                    Ok(client) => *self.client.write().unwrap() = client,
+                    Err(e) => warn!("Failed to rebuild AIS HTTP client: {}", e),
+                }
+            }
+        }
+        // reqwest::Client 内部为 Arc，克隆开销很小
+        self.client.read().unwrap().clone()
+    }
+
     /// 刷新 Credential（调用 AIS /register 接口）
     ///
     /// # 参数
@@ -66,7 +108,7 @@ impl AisClient {
         realm_id: u32,
         actr_type: ActrType,
     ) -> Result<RegisterResponse> {
-        let url = format!("{}/ais/register", self.endpoint);
+        let url = format!("{}/register", self.base_url);
 
         // 构造 RegisterRequest
         let request = RegisterRequest {
@@ -85,7 +127,7 @@ impl AisClient {
 
         // 发送 HTTP POST 请求
         let response = self
-            .client
+            .http_client()
             .post(&url)
             .header("Content-Type", "application/octet-stream")
             .body(request.encode_to_vec())
@@ -129,6 +171,79 @@ impl AisClient {
             None => Err(anyhow!("Empty response from AIS")),
         }
     }
+
+    /// 拉取凭证吊销列表（调用 AIS /revocations 接口）
+    pub async fn fetch_revocations(&self) -> Result<RevocationListResponse> {
+        let url = format!("{}/revocations", self.base_url);
+
+        let response = self
+            .http_client()
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("HTTP request failed: {e}"))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "<no body>".to_string());
+            return Err(anyhow!("AIS HTTP error {status}: {body}"));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to decode revocation list: {e}"))
+    }
+
+    /// 启动吊销过滤器刷新任务：按间隔从 AIS 拉取并更新凭证验证器
+    ///
+    /// 拉取失败时保留上一次的过滤器；吊销在下一次拉取后对所有连接的后续消息生效。
+    /// 同一列表中的 PSK 轮替记录一并更新，轮替前的凭证在重叠窗口结束后被拒绝
+    pub fn spawn_revocation_refresh(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut last_count = 0;
+            let mut failing = false;
+
+            loop {
+                ticker.tick().await;
+
+                let list = match self.fetch_revocations().await {
+                    Ok(list) => list,
+                    Err(e) => {
+                        // 仅在首次失败时告警，避免 AIS 不可用期间刷屏
+                        if !failing {
+                            warn!("Failed to fetch credential revocations from AIS: {}", e);
+                            failing = true;
+                        } else {
+                            debug!("Failed to fetch credential revocations from AIS: {}", e);
+                        }
+                        continue;
+                    }
+                };
+                failing = false;
+
+                if list.count != last_count {
+                    info!(
+                        "Credential revocation list updated: {} entries ({} bytes)",
+                        list.count,
+                        list.filter.size_bytes()
+                    );
+                    last_count = list.count;
+                }
+
+                if let Err(e) = AIdCredentialValidator::set_revocation_filter(list.filter) {
+                    debug!("Skipping revocation filter update: {}", e);
+                }
+                if let Err(e) = AIdCredentialValidator::set_psk_rotations(list.psk_rotations) {
+                    debug!("Skipping PSK rotation update: {}", e);
+                }
+            }
+        });
+    }
 }
 
 #[cfg(test)]
@@ -148,4 +263,15 @@ mod tests {
         let client = AisClient::new(&config);
         assert!(client.is_ok());
     }
+
+    #[test]
+    fn test_ais_client_route_prefix() {
+        let client = AisClient::new(&AisClientConfig {
+            endpoint: "http://127.0.0.1:8080/".to_string(),
+            route_prefix: "/edge/ais".to_string(),
+            timeout_seconds: 30,
+        })
+        .unwrap();
+        assert_eq!(client.base_url, "http://127.0.0.1:8080/edge/ais");
+    }
 }
@@ -0,0 +1,542 @@
+//! 外部授权钩子 (SignalingAuthzHook)
+//!
+//! 注册、服务发现与中继在内置 ACL 之外再咨询外部策略服务，企业可以接入自己的
+//! 策略引擎（OPA、自建权限中心），无需修改服务器。两者同时生效：任一方拒绝即拒绝。
+//!
+//! # 实现
+//! - [`HttpAuthzHook`]：HTTP POST JSON，兼容 OPA Data API（`{"input": ...}` / `{"result": ...}`）
+//! - [`GrpcAuthzHook`]：调用 `authz.v1.SignalingAuthz`
+//!
+//! 需要其他接入方式时实现 [`SignalingAuthzHook`] 并通过 [`AuthzGate::new`] 装配。
+
+use actr_protocol::{ActrId, ActrIdExt, ActrType};
+use actrix_common::config::signaling::{AuthzHookConfig, AuthzHookProtocol};
+use actrix_common::metrics::SIGNALING_AUTHZ_DECISIONS;
+use actrix_proto::{AuthorizeRequest, SignalingAuthzClient};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tonic::transport::{Channel, Endpoint};
+use tracing::warn;
+
+use crate::actr_type_utils::type_key;
+
+/// 决策缓存条目上限，超出时先清理过期条目
+const MAX_CACHE_ENTRIES: usize = 10_000;
+
+/// 需要授权的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthzAction {
+    /// Actor 注册
+    Register,
+    /// 服务发现与路由候选
+    Discover,
+    /// 信令中继
+    Relay,
+}
+
+impl AuthzAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Register => "register",
+            Self::Discover => "discover",
+            Self::Relay => "relay",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "register" => Some(Self::Register),
+            "discover" => Some(Self::Discover),
+            "relay" => Some(Self::Relay),
+            _ => None,
+        }
+    }
+}
+
+/// 发给策略服务的授权请求
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct AuthzRequest {
+    pub action: AuthzAction,
+    /// 发起方所在 Realm
+    pub realm_id: u32,
+    /// 发起方 ActrType key
+    pub source_type: String,
+    /// 发起方 ActrId（注册时尚未分配）
+    pub source: Option<String>,
+    /// 目标 ActrType key（注册时为空）
+    pub target_type: Option<String>,
+    /// 目标 ActrId（仅中继）
+    pub target: Option<String>,
+}
+
+impl AuthzRequest {
+    /// 注册请求
+    pub fn register(realm_id: u32, actr_type: &ActrType) -> Self {
+        Self {
+            action: AuthzAction::Register,
+            realm_id,
+            source_type: type_key(actr_type),
+            source: None,
+            target_type: None,
+            target: None,
+        }
+    }
+
+    /// 发现 `target_type` 类型的服务
+    pub fn discover(source: &ActrId, target_type: &str) -> Self {
+        Self {
+            action: AuthzAction::Discover,
+            realm_id: source.realm.realm_id,
+            source_type: type_key(&source.r#type),
+            source: Some(source.to_string_repr()),
+            target_type: Some(target_type.to_string()),
+            target: None,
+        }
+    }
+
+    /// 从 `source` 中继到 `target`
+    pub fn relay(source: &ActrId, target: &ActrId) -> Self {
+        Self {
+            action: AuthzAction::Relay,
+            realm_id: source.realm.realm_id,
+            source_type: type_key(&source.r#type),
+            source: Some(source.to_string_repr()),
+            target_type: Some(type_key(&target.r#type)),
+            target: Some(target.to_string_repr()),
+        }
+    }
+
+    /// 服务发现结果按类型生效：不携带发起方 ActrId，按类型缓存
+    fn cache_key(&self) -> Self {
+        if self.action == AuthzAction::Discover {
+            Self {
+                source: None,
+                ..self.clone()
+            }
+        } else {
+            self.clone()
+        }
+    }
+}
+
+/// 授权决策
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthzDecision {
+    Allow,
+    Deny { reason: String },
+}
+
+/// 外部授权钩子
+#[async_trait::async_trait]
+pub trait SignalingAuthzHook: Send + Sync + std::fmt::Debug {
+    /// 对单次操作做出决策；返回 Err 表示策略服务不可用
+    async fn authorize(&self, request: &AuthzRequest) -> Result<AuthzDecision>;
+}
+
+/// HTTP 授权钩子
+///
+/// 请求体为 `{"input": AuthzRequest}`，响应支持：
+/// - `{"result": true}` / `{"result": {"allow": false, "reason": "..."}}`（OPA Data API）
+/// - `{"allow": bool, "reason": "..."}`
+#[derive(Debug)]
+pub struct HttpAuthzHook {
+    endpoint: String,
+    bearer_token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl HttpAuthzHook {
+    pub fn new(endpoint: &str, bearer_token: Option<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| anyhow!("Failed to create HTTP client: {e}"))?;
+
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            bearer_token: bearer_token.filter(|t| !t.is_empty()),
+            client,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct HttpAuthzInput<'a> {
+    input: &'a AuthzRequest,
+}
+
+/// 解析 HTTP 策略服务的响应
+fn parse_http_decision(body: &serde_json::Value) -> Result<AuthzDecision> {
+    #[derive(Deserialize)]
+    struct Verdict {
+        allow: bool,
+        #[serde(default)]
+        reason: Option<String>,
+    }
+
+    let verdict = match body.get("result").unwrap_or(body) {
+        serde_json::Value::Bool(allow) => Verdict {
+            allow: *allow,
+            reason: None,
+        },
+        value @ serde_json::Value::Object(map) if map.contains_key("allow") => {
+            serde_json::from_value(value.clone())?
+        }
+        // OPA 规则未定义时返回 `{}`，按拒绝处理
+        serde_json::Value::Object(map) if map.is_empty() => Verdict {
+            allow: false,
+            reason: Some("Policy decision is undefined".to_string()),
+        },
+        _ => return Err(anyhow!("Unrecognized authorization response: {body}")),
+    };
+
+    Ok(if verdict.allow {
+        AuthzDecision::Allow
+    } else {
+        AuthzDecision::Deny {
+            reason: verdict
+                .reason
+                .unwrap_or_else(|| "Denied by authorization policy".to_string()),
+        }
+    })
+}
+
+#[async_trait::async_trait]
+impl SignalingAuthzHook for HttpAuthzHook {
+    async fn authorize(&self, request: &AuthzRequest) -> Result<AuthzDecision> {
+        let mut http_request = self
+            .client
+            .post(&self.endpoint)
+            .json(&HttpAuthzInput { input: request });
+        if let Some(ref token) = self.bearer_token {
+            http_request = http_request.bearer_auth(token);
+        }
+
+        let response = http_request
+            .send()
+            .await
+            .map_err(|e| anyhow!("HTTP request failed: {e}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("Authorization service returned HTTP {status}"));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to decode response: {e}"))?;
+        parse_http_decision(&body)
+    }
+}
+
+/// gRPC 授权钩子（`authz.v1.SignalingAuthz`）
+#[derive(Debug)]
+pub struct GrpcAuthzHook {
+    client: SignalingAuthzClient<Channel>,
+}
+
+impl GrpcAuthzHook {
+    /// 创建钩子，连接在首次调用时建立
+    pub fn new(endpoint: &str, timeout: Duration) -> Result<Self> {
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| anyhow!("Invalid authz endpoint {endpoint}: {e}"))?
+            .timeout(timeout)
+            .connect_timeout(timeout)
+            .connect_lazy();
+
+        Ok(Self {
+            client: SignalingAuthzClient::new(channel),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl SignalingAuthzHook for GrpcAuthzHook {
+    async fn authorize(&self, request: &AuthzRequest) -> Result<AuthzDecision> {
+        let response = self
+            .client
+            .clone()
+            .authorize(AuthorizeRequest {
+                action: request.action.as_str().to_string(),
+                realm_id: request.realm_id,
+                source_type: request.source_type.clone(),
+                source: request.source.clone(),
+                target_type: request.target_type.clone(),
+                target: request.target.clone(),
+            })
+            .await
+            .map_err(|e| anyhow!("gRPC call failed: {e}"))?
+            .into_inner();
+
+        Ok(if response.allow {
+            AuthzDecision::Allow
+        } else if response.reason.is_empty() {
+            AuthzDecision::Deny {
+                reason: "Denied by authorization policy".to_string(),
+            }
+        } else {
+            AuthzDecision::Deny {
+                reason: response.reason,
+            }
+        })
+    }
+}
+
+/// 授权钩子的调用入口：操作筛选、超时、故障策略与决策缓存
+#[derive(Debug)]
+pub struct AuthzGate {
+    hook: Arc<dyn SignalingAuthzHook>,
+    actions: HashSet<AuthzAction>,
+    timeout: Duration,
+    fail_open: bool,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<AuthzRequest, (AuthzDecision, Instant)>>,
+}
+
+impl AuthzGate {
+    /// 使用自定义钩子创建
+    pub fn new(hook: Arc<dyn SignalingAuthzHook>, config: &AuthzHookConfig) -> Self {
+        Self {
+            hook,
+            actions: config
+                .actions
+                .iter()
+                .filter_map(|a| AuthzAction::parse(a))
+                .collect(),
+            timeout: Duration::from_millis(config.timeout_ms.max(1)),
+            fail_open: config.fail_open,
+            cache_ttl: Duration::from_secs(config.cache_ttl_secs),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 按配置创建内置的 HTTP / gRPC 钩子
+    pub fn from_config(config: &AuthzHookConfig) -> Result<Self> {
+        let timeout = Duration::from_millis(config.timeout_ms.max(1));
+        let hook: Arc<dyn SignalingAuthzHook> = match config.protocol {
+            AuthzHookProtocol::Http => Arc::new(HttpAuthzHook::new(
+                &config.endpoint,
+                config.bearer_token.clone(),
+                timeout,
+            )?),
+            AuthzHookProtocol::Grpc => Arc::new(GrpcAuthzHook::new(&config.endpoint, timeout)?),
+        };
+        Ok(Self::new(hook, config))
+    }
+
+    /// 检查操作是否被允许，拒绝时返回原因
+    ///
+    /// 未配置该操作时直接放行；策略服务不可用时按 `fail_open` 处理
+    pub async fn check(&self, request: AuthzRequest) -> Result<(), String> {
+        if !self.actions.contains(&request.action) {
+            return Ok(());
+        }
+
+        let cache_key = request.cache_key();
+        let decision = match self.cached(&cache_key) {
+            Some(decision) => decision,
+            None => match tokio::time::timeout(self.timeout, self.hook.authorize(&request)).await {
+                Ok(Ok(decision)) => {
+                    self.store(cache_key, decision.clone());
+                    decision
+                }
+                Ok(Err(e)) => return self.unavailable(&request, &e.to_string()),
+                Err(_) => return self.unavailable(&request, "timed out"),
+            },
+        };
+
+        let action = request.action.as_str();
+        match decision {
+            AuthzDecision::Allow => {
+                SIGNALING_AUTHZ_DECISIONS
+                    .with_label_values(&[action, "allow"])
+                    .inc();
+                Ok(())
+            }
+            AuthzDecision::Deny { reason } => {
+                SIGNALING_AUTHZ_DECISIONS
+                    .with_label_values(&[action, "deny"])
+                    .inc();
+                Err(reason)
+            }
+        }
+    }
+
+    fn unavailable(&self, request: &AuthzRequest, error: &str) -> Result<(), String> {
+        let action = request.action.as_str();
+        SIGNALING_AUTHZ_DECISIONS
+            .with_label_values(&[action, "error"])
+            .inc();
+        warn!(
+            "⚠️  外部授权钩子不可用 ({} {} -> {:?}): {}, fail_open={}",
+            action, request.source_type, request.target_type, error, self.fail_open
+        );
+        if self.fail_open {
+            Ok(())
+        } else {
+            Err("Authorization service unavailable".to_string())
+        }
+    }
+
+    fn cached(&self, key: &AuthzRequest) -> Option<AuthzDecision> {
+        if self.cache_ttl.is_zero() {
+            return None;
+        }
+        let cache = self.cache.lock().expect("authz cache poisoned");
+        cache
+            .get(key)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.cache_ttl)
+            .map(|(decision, _)| decision.clone())
+    }
+
+    fn store(&self, key: AuthzRequest, decision: AuthzDecision) {
+        if self.cache_ttl.is_zero() {
+            return;
+        }
+        let mut cache = self.cache.lock().expect("authz cache poisoned");
+        if cache.len() >= MAX_CACHE_ENTRIES {
+            cache.retain(|_, (_, cached_at)| cached_at.elapsed() < self.cache_ttl);
+            if cache.len() >= MAX_CACHE_ENTRIES {
+                cache.clear();
+            }
+        }
+        cache.insert(key, (decision, Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::Realm;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 只允许 `acme:chat` 作为目标类型，并记录调用次数
+    #[derive(Debug, Default)]
+    struct ChatOnlyHook {
+        calls: AtomicUsize,
+        unavailable: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl SignalingAuthzHook for ChatOnlyHook {
+        async fn authorize(&self, request: &AuthzRequest) -> Result<AuthzDecision> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.unavailable {
+                return Err(anyhow!("connection refused"));
+            }
+            Ok(match request.target_type.as_deref() {
+                None | Some("acme:chat") => AuthzDecision::Allow,
+                Some(_) => AuthzDecision::Deny {
+                    reason: "only chat is allowed".to_string(),
+                },
+            })
+        }
+    }
+
+    fn actor(serial_number: u64, name: &str) -> ActrId {
+        ActrId {
+            realm: Realm { realm_id: 1 },
+            serial_number,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: name.to_string(),
+                version: None,
+            },
+        }
+    }
+
+    fn config(actions: &[&str]) -> AuthzHookConfig {
+        AuthzHookConfig {
+            enabled: true,
+            endpoint: "http://authz".to_string(),
+            actions: actions.iter().map(|a| a.to_string()).collect(),
+            ..AuthzHookConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gate_applies_decisions_and_caches_discovery() {
+        let hook = Arc::new(ChatOnlyHook::default());
+        let gate = AuthzGate::new(hook.clone(), &config(&["discover", "relay"]));
+
+        assert!(
+            gate.check(AuthzRequest::discover(&actor(1, "app"), "acme:chat"))
+                .await
+                .is_ok()
+        );
+        // 发现结果按类型缓存：不同发起方命中同一缓存
+        assert!(
+            gate.check(AuthzRequest::discover(&actor(2, "app"), "acme:chat"))
+                .await
+                .is_ok()
+        );
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 1);
+
+        assert_eq!(
+            gate.check(AuthzRequest::relay(&actor(1, "app"), &actor(3, "echo")))
+                .await
+                .unwrap_err(),
+            "only chat is allowed"
+        );
+
+        // 未配置的操作不调用钩子
+        assert!(
+            gate.check(AuthzRequest::register(1, &actor(1, "app").r#type))
+                .await
+                .is_ok()
+        );
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_gate_failure_policy() {
+        let hook = Arc::new(ChatOnlyHook {
+            unavailable: true,
+            ..Default::default()
+        });
+        let request = AuthzRequest::register(1, &actor(1, "app").r#type);
+
+        let fail_closed = AuthzGate::new(hook.clone(), &config(&["register"]));
+        assert!(fail_closed.check(request.clone()).await.is_err());
+
+        let fail_open = AuthzGate::new(
+            hook.clone(),
+            &AuthzHookConfig {
+                fail_open: true,
+                ..config(&["register"])
+            },
+        );
+        assert!(fail_open.check(request).await.is_ok());
+        // 失败结果不缓存
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_parse_http_decision() {
+        let allow = parse_http_decision(&serde_json::json!({"result": true})).unwrap();
+        assert_eq!(allow, AuthzDecision::Allow);
+
+        let deny = parse_http_decision(
+            &serde_json::json!({"result": {"allow": false, "reason": "blocked"}}),
+        )
+        .unwrap();
+        assert_eq!(
+            deny,
+            AuthzDecision::Deny {
+                reason: "blocked".to_string()
+            }
+        );
+
+        let plain = parse_http_decision(&serde_json::json!({"allow": true})).unwrap();
+        assert_eq!(plain, AuthzDecision::Allow);
+
+        let undefined = parse_http_decision(&serde_json::json!({})).unwrap();
+        assert!(matches!(undefined, AuthzDecision::Deny { .. }));
+
+        assert!(parse_http_decision(&serde_json::json!({"result": "yes"})).is_err());
+    }
+}
@@ -2,29 +2,37 @@
 //!
 //! 提供 SignalingServer 的 Axum Router 适配器
 
-use crate::server::{SignalingServer, SignalingServerHandle};
+use crate::server::SignalingServer;
+use crate::ws_auth;
+use actr_protocol::ActrIdExt;
 use actrix_common::aid::credential::validator::AIdCredentialValidator;
 use actrix_common::config::ActrixConfig;
+use actrix_common::config::signaling::{RegistryStorageBackend, WsAuthConfig};
+use actrix_common::util::{NetworkEmulator, TlsChannelBinding};
 use anyhow::{Context as _, Result};
 use axum::{
-    Router,
+    Extension, Router,
     extract::{
         ConnectInfo, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
+    http::HeaderMap,
     response::IntoResponse,
     routing::get,
 };
-use base64::Engine as _;
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::{collections::HashMap, str::FromStr};
 use tracing::{error, info, warn};
 
 /// Signaling Server 状态（用于 Axum State）
 #[derive(Clone)]
 pub struct SignalingState {
     pub server: Arc<SignalingServer>,
+    /// WebSocket 连接认证配置
+    pub auth: WsAuthConfig,
+    /// 管理 API 的 Bearer token（None 时管理 API 拒绝所有请求）
+    pub admin_token: Option<String>,
 }
 
 /// 创建 Signaling Axum Router
@@ -33,13 +41,17 @@ pub struct SignalingState {
 pub async fn create_signaling_router() -> Result<Router> {
     info!("Creating Signaling Axum router");
 
-    let server = SignalingServer::new();
+    let mut server = SignalingServer::new();
+    server.traffic_stats = Some(Arc::new(crate::traffic_stats::TrafficStats::default()));
     let state = SignalingState {
         server: Arc::new(server),
+        auth: WsAuthConfig::default(),
+        admin_token: None,
     };
 
     let router = Router::new()
         .route("/ws", get(websocket_handler))
+        .merge(crate::admin::admin_router())
         .with_state(state);
 
     info!("Signaling Axum router created successfully");
@@ -80,8 +92,18 @@ pub async fn create_signaling_router_with_config(config: &ActrixConfig) -> Resul
 
     // 初始化 ServiceRegistry 持久化缓存（用于重启恢复）
     let cache_ttl_secs = crate::service_registry_storage::DEFAULT_SERVICE_TTL_SECS;
-
-    if !config.sqlite_path.exists() {
+    let registry_cipher = crate::registry_encryption::RegistryCipher::from_config(config)?;
+
+    let storage_config = config
+        .services
+        .signaling
+        .as_ref()
+        .map(|signaling| signaling.storage.clone())
+        .unwrap_or_default();
+    let cache_db_file = config.sqlite_path.join("signaling_cache.db");
+    let use_sqlite_file =
+        storage_config.backend == RegistryStorageBackend::Sqlite && !config.storage.is_memory();
+    if use_sqlite_file && !config.sqlite_path.exists() {
         std::fs::create_dir_all(&config.sqlite_path).with_context(|| {
             format!(
                 "Failed to create SQLite data directory: {}",
@@ -89,20 +111,29 @@ pub async fn create_signaling_router_with_config(config: &ActrixConfig) -> Resul
             )
         })?;
     }
-    let cache_db_file = config.sqlite_path.join("signaling_cache.db");
-
-    match crate::service_registry_storage::ServiceRegistryStorage::new(
-        &cache_db_file,
+    let storage_result = crate::service_registry_storage::ServiceRegistryStorage::from_config(
+        &storage_config,
+        use_sqlite_file.then_some(cache_db_file.as_path()),
         Some(cache_ttl_secs),
     )
-    .await
-    {
+    .await;
+
+    match storage_result {
         Ok(storage) => {
-            let storage_arc = Arc::new(storage);
-            info!(
-                "✅ ServiceRegistry cache initialized at: {}",
-                cache_db_file.display()
-            );
+            let storage_arc = Arc::new(storage.with_cipher(registry_cipher));
+            if use_sqlite_file {
+                info!(
+                    "✅ ServiceRegistry cache initialized at: {}",
+                    cache_db_file.display()
+                );
+            } else if storage_config.backend == RegistryStorageBackend::Sqlite {
+                info!("✅ ServiceRegistry cache initialized in memory");
+            } else {
+                info!(
+                    "✅ ServiceRegistry cache initialized with {} backend",
+                    storage_arc.backend_name()
+                );
+            }
 
             // 设置存储到 ServiceRegistry
             {
@@ -207,6 +238,253 @@ pub async fn create_signaling_router_with_config(config: &ActrixConfig) -> Resul
         } else {
             info!("⚠️  Message rate limiting is disabled");
         }
+
+        // 初始化 Realm 聚合速率限制器
+        if rate_limit_config.realm.enabled {
+            info!(
+                "Initializing realm rate limiter: {} registrations/min, {} relays/sec, {} discovery/sec",
+                rate_limit_config.realm.registrations_per_minute,
+                rate_limit_config.realm.relays_per_second,
+                rate_limit_config.realm.discovery_per_second
+            );
+            server.realm_rate_limiter = Some(Arc::new(crate::ratelimit::RealmRateLimiter::new(
+                rate_limit_config.realm.clone(),
+            )));
+            info!("✅ Realm rate limiter initialized");
+        }
+
+        // 定期采集令牌桶水位指标
+        let connection_for_metrics = server.connection_rate_limiter.clone();
+        let message_for_metrics = server.message_rate_limiter.clone();
+        let realm_for_metrics = server.realm_rate_limiter.clone();
+        if connection_for_metrics.is_some()
+            || message_for_metrics.is_some()
+            || realm_for_metrics.is_some()
+        {
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                    crate::ratelimit::METRICS_INTERVAL_SECS,
+                ));
+                loop {
+                    interval.tick().await;
+                    crate::ratelimit::RateLimitStatus::collect(
+                        connection_for_metrics.as_deref(),
+                        message_for_metrics.as_deref(),
+                        realm_for_metrics.as_deref(),
+                    )
+                    .await
+                    .record_metrics();
+                }
+            });
+        }
+
+        // 初始化重放保护
+        let replay_config = &signaling_config.server.replay_protection;
+        if replay_config.enabled {
+            info!(
+                "Initializing replay protection: max age: {}s, max future skew: {}s, exempt: {:?}, nonce: {:?}",
+                replay_config.max_age_secs,
+                replay_config.max_future_skew_secs,
+                replay_config.exempt_payloads,
+                replay_config.nonce_payloads
+            );
+            server.replay_guard = Some(Arc::new(crate::replay::ReplayGuard::new(replay_config)));
+        }
+
+        // 单连接消息大小与发送队列限制
+        server.limits = signaling_config.server.limits.clone();
+        info!(
+            "Connection limits: max envelope: {} bytes, outbound queue: {}, overflow policy: {:?}",
+            server.limits.max_envelope_bytes,
+            server.limits.max_outbound_queue,
+            server.limits.outbound_overflow_policy
+        );
+
+        // WebSocket 消息压缩
+        let compression_config = &signaling_config.server.compression;
+        server.compressor = crate::compression::Compressor::from_config(compression_config);
+        if server.compressor.is_some() {
+            info!(
+                "WebSocket compression enabled: subprotocol: {}, threshold: {} bytes, level: {}",
+                crate::compression::SUBPROTOCOL,
+                compression_config.threshold_bytes,
+                compression_config.level
+            );
+        }
+
+        // ServiceSpec 历史版本保留策略
+        let spec_history_config = &signaling_config.server.spec_history;
+        info!(
+            "Spec history retention: max versions: {}, max age: {}s",
+            spec_history_config.max_versions, spec_history_config.max_age_secs
+        );
+        server
+            .service_registry
+            .write()
+            .await
+            .set_spec_history_config(spec_history_config.clone());
+
+        // 路由候选的元数据亲和键
+        let metadata_affinity_keys = &signaling_config.server.metadata_affinity_keys;
+        if !metadata_affinity_keys.is_empty() {
+            info!("Route metadata affinity keys: {:?}", metadata_affinity_keys);
+            server
+                .service_registry
+                .write()
+                .await
+                .set_metadata_affinity_keys(metadata_affinity_keys.clone());
+        }
+
+        // 初始化流量统计
+        let traffic_stats_config = &signaling_config.server.traffic_stats;
+        if traffic_stats_config.enabled {
+            info!(
+                "Initializing traffic stats: max tracked types: {}, half-life: {}s",
+                traffic_stats_config.max_tracked_types, traffic_stats_config.half_life_secs
+            );
+            server.traffic_stats = Some(Arc::new(crate::traffic_stats::TrafficStats::new(
+                traffic_stats_config,
+            )));
+        } else {
+            info!("⚠️  Traffic stats are disabled");
+        }
+
+        // 初始化 ServiceSpec 不兼容变更通知
+        let spec_notice_config = &signaling_config.server.spec_notice;
+        if spec_notice_config.enabled {
+            info!(
+                "Initializing spec incompatibility notices: dependent ttl: {}s",
+                spec_notice_config.dependent_ttl_secs
+            );
+            server.spec_dependencies = Some(Arc::new(
+                crate::spec_notice::SpecDependencyTracker::new(spec_notice_config),
+            ));
+        }
+
+        // 初始化中继 SDP 校验
+        let sdp_validation_config = &signaling_config.server.sdp_validation;
+        if sdp_validation_config.enabled {
+            info!(
+                "Initializing relay SDP validation: strip private candidates: {}, blocked candidate types: {:?}, default codecs: {:?}",
+                sdp_validation_config.strip_private_candidates,
+                sdp_validation_config.blocked_candidate_types,
+                sdp_validation_config.allowed_codecs
+            );
+            server.sdp_sanitizer = Some(Arc::new(crate::sdp_filter::SdpSanitizer::new(
+                sdp_validation_config,
+            )));
+        }
+
+        // 初始化外部授权钩子
+        let authz_hook_config = &signaling_config.server.authz_hook;
+        if authz_hook_config.enabled {
+            info!(
+                "Initializing external authz hook: {:?} {} (actions: {:?}, fail_open: {})",
+                authz_hook_config.protocol,
+                authz_hook_config.endpoint,
+                authz_hook_config.actions,
+                authz_hook_config.fail_open
+            );
+            server.authz_gate = Some(Arc::new(crate::authz_hook::AuthzGate::from_config(
+                authz_hook_config,
+            )?));
+        }
+
+        // 初始化断线重连会话恢复
+        let resumption_config = &signaling_config.server.resumption;
+        if resumption_config.enabled {
+            info!(
+                "Initializing session resumption: window: {}s, max queued messages: {}",
+                resumption_config.window_secs, resumption_config.max_queued_messages
+            );
+            let resumption = Arc::new(crate::resumption::ResumptionManager::new(resumption_config));
+
+            // 定期清理超出窗口的会话及其 Presence 订阅
+            let resumption_for_sweep = resumption.clone();
+            let presence_for_sweep = server.presence_manager.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                    crate::resumption::SWEEP_INTERVAL_SECS,
+                ));
+                loop {
+                    interval.tick().await;
+                    let expired = resumption_for_sweep.sweep_expired();
+                    if expired.is_empty() {
+                        continue;
+                    }
+                    let mut presence = presence_for_sweep.write().await;
+                    for actor_id in &expired {
+                        presence.unsubscribe_all(actor_id);
+                    }
+                    info!("🧹 Dropped {} expired resumable sessions", expired.len());
+                }
+            });
+
+            server.resumption = Some(resumption);
+        }
+
+        // 初始化过载降级
+        let load_shedding_config = &signaling_config.server.load_shedding;
+        if load_shedding_config.enabled {
+            info!(
+                "Initializing load shedding: cpu threshold: {}%, queue depth threshold: {}, recovery ratio: {}",
+                load_shedding_config.cpu_threshold_percent,
+                load_shedding_config.queue_depth_threshold,
+                load_shedding_config.recovery_ratio
+            );
+            let shedder = Arc::new(crate::load_shed::LoadShedder::new(load_shedding_config));
+
+            // 定期采样 CPU 与全部连接的待发送消息总数
+            let shedder_for_sample = shedder.clone();
+            let clients_for_sample = server.clients.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(shedder_for_sample.sample_interval());
+                loop {
+                    interval.tick().await;
+                    let cpu_percent = crate::load_shed::sample_cpu_percent().await;
+                    let mut queue_depth = 0;
+                    clients_for_sample
+                        .for_each(|_, client| queue_depth += client.direct_sender.queued_len())
+                        .await;
+                    shedder_for_sample.evaluate(cpu_percent, queue_depth);
+                }
+            });
+
+            server.load_shedder = Some(shedder);
+        }
+
+        // 同一 ActrId 重复连接的处理策略
+        let duplicate_identity_config = &signaling_config.server.duplicate_identity;
+        info!(
+            "Duplicate identity policy: {:?}, max connections: {}, same fingerprint replaces: {}, realm overrides: {}",
+            duplicate_identity_config.policy,
+            duplicate_identity_config.max_connections,
+            duplicate_identity_config.same_fingerprint_replaces,
+            duplicate_identity_config.realm_overrides.len()
+        );
+        server.duplicate_identity = Arc::new(
+            crate::duplicate_identity::DuplicateIdentityGuard::new(duplicate_identity_config),
+        );
+    }
+
+    // 初始化弱网模拟（仅开发环境，生产环境由配置校验拒绝）
+    let network_emulation = &config.dev.network_emulation;
+    if network_emulation.signaling_relay
+        && let Some(emulator) = NetworkEmulator::from_config(network_emulation)
+    {
+        warn!(
+            "⚠️  Signaling relay network emulation enabled: latency={}ms jitter={}ms drop_rate={}",
+            network_emulation.latency_ms, network_emulation.jitter_ms, network_emulation.drop_rate
+        );
+        server.network_emulator = Some(Arc::new(emulator));
+    }
+
+    // 局域网直连模式（仅开发环境）
+    if config.is_lan_discovery_enabled() {
+        warn!(
+            "⚠️  LAN discovery mode enabled: relayed candidates are limited to host candidates with mDNS names replaced by LAN IPs"
+        );
+        server.lan_discovery = true;
     }
 
     // 初始化 AIS 客户端（如果配置存在）
@@ -218,10 +496,23 @@ pub async fn create_signaling_router_with_config(config: &ActrixConfig) -> Resul
             );
             match crate::ais_client::AisClient::new(&crate::ais_client::AisClientConfig {
                 endpoint: ais_client_config.endpoint.clone(),
+                route_prefix: ais_client_config.route_prefix.clone(),
                 timeout_seconds: ais_client_config.timeout_seconds,
             }) {
                 Ok(ais_client) => {
-                    server.ais_client = Some(Arc::new(ais_client));
+                    let ais_client = Arc::new(ais_client);
+                    if ais_client_config.revocation_refresh_interval_secs > 0 {
+                        ais_client.clone().spawn_revocation_refresh(
+                            std::time::Duration::from_secs(
+                                ais_client_config.revocation_refresh_interval_secs,
+                            ),
+                        );
+                        info!(
+                            "Credential revocation refresh enabled (every {}s)",
+                            ais_client_config.revocation_refresh_interval_secs
+                        );
+                    }
+                    server.ais_client = Some(ais_client);
                     info!("✅ AIS client initialized successfully");
                 }
                 Err(e) => {
@@ -235,12 +526,23 @@ pub async fn create_signaling_router_with_config(config: &ActrixConfig) -> Resul
     }
 
     // 创建 Router
+    let auth = config
+        .services
+        .signaling
+        .as_ref()
+        .map(|c| c.server.auth.clone())
+        .unwrap_or_default();
+    let server = Arc::new(server);
+    crate::admin::register_server(server.clone());
     let state = SignalingState {
-        server: Arc::new(server),
+        server,
+        auth,
+        admin_token: Some(config.get_actrix_shared_key().to_string()),
     };
 
     let router = Router::new()
         .route("/ws", get(websocket_handler))
+        .merge(crate::admin::admin_router())
         .with_state(state);
 
     info!("Signaling Axum router created successfully");
@@ -253,6 +555,8 @@ async fn websocket_handler(
     State(state): State<SignalingState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    channel_binding: Option<Extension<TlsChannelBinding>>,
 ) -> impl IntoResponse {
     let client_ip = addr.ip();
 
@@ -264,49 +568,86 @@ async fn websocket_handler(
         return axum::http::StatusCode::TOO_MANY_REQUESTS.into_response();
     }
 
-    ws.on_upgrade(move |socket| handle_websocket(socket, state, client_ip, params))
+    // 升级前校验请求携带的身份（用于无注册重连）
+    let url_identity = match ws_auth::extract_identity(&headers, &params, &state.auth) {
+        Ok(identity) => identity,
+        Err(e) => {
+            warn!("🚫 IP {} WebSocket 身份解析失败: {}", client_ip, e);
+            return (e.status_code(), e.to_string()).into_response();
+        }
+    };
+    if let Some(ref identity) = url_identity
+        && let Err(e) = ws_auth::authenticate(
+            identity,
+            channel_binding.as_ref().map(|Extension(binding)| binding),
+            &state.auth,
+        )
+        .await
+    {
+        warn!(
+            "🚫 IP {} WebSocket 身份校验失败 (actor={}): {}",
+            client_ip,
+            identity.actor_id.to_string_repr(),
+            e
+        );
+        return (e.status_code(), e.to_string()).into_response();
+    }
+    let url_identity = url_identity.map(|identity| (identity.actor_id, identity.credential));
+    let resume_token = crate::resumption::resume_token(&headers, &params);
+    let fingerprint = crate::duplicate_identity::connection_fingerprint(
+        Some(client_ip),
+        headers
+            .get(axum::http::header::USER_AGENT)
+            .and_then(|value| value.to_str().ok()),
+    );
+
+    // 超过 envelope 限制但在 2 倍以内的消息由 handle_client_envelope 回复 413，
+    // 更大的消息在传输层直接断开连接，避免缓冲超大帧
+    let max_message_size = state.server.limits.max_envelope_bytes.saturating_mul(2);
+
+    // 客户端提供压缩子协议时启用按消息压缩
+    let ws = match state.server.compressor {
+        Some(_) => ws.protocols([crate::compression::SUBPROTOCOL]),
+        None => ws,
+    };
+    let compressor = state
+        .server
+        .compressor
+        .clone()
+        .filter(|_| ws.selected_protocol().is_some());
+
+    ws.max_message_size(max_message_size)
+        .on_upgrade(move |socket| {
+            handle_websocket(
+                socket,
+                state,
+                client_ip,
+                params,
+                url_identity,
+                resume_token,
+                fingerprint,
+                compressor,
+            )
+        })
 }
 
 /// WebSocket 连接处理
+#[allow(clippy::too_many_arguments)]
 async fn handle_websocket(
     socket: WebSocket,
     state: SignalingState,
     client_ip: std::net::IpAddr,
     params: HashMap<String, String>,
+    url_identity: Option<(actr_protocol::ActrId, actr_protocol::AIdCredential)>,
+    resume_token: Option<String>,
+    fingerprint: String,
+    compressor: Option<crate::compression::Compressor>,
 ) {
-    info!("📡 新 WebSocket 连接: IP={}", client_ip);
-
-    // 从 URL 获取 actor_id/token（如果提供），用于无注册重连。
-    let mut url_identity: Option<(actr_protocol::ActrId, actr_protocol::AIdCredential)> = None;
-    if let Some(actor_str) = params.get("actor_id") {
-        match actr_protocol::ActrIdExt::from_string_repr(actor_str) {
-            Ok(actor_id) => {
-                if let Some(token_b64) = params.get("token") {
-                    if let Ok(token_bytes) =
-                        base64::engine::general_purpose::STANDARD.decode(token_b64)
-                    {
-                        // 默认 key_id = 0，如果未提供则取 0
-                        let key_id = params
-                            .get("token_key_id")
-                            .and_then(|s| u32::from_str(s).ok())
-                            .unwrap_or_default();
-                        let credential = actr_protocol::AIdCredential {
-                            encrypted_token: token_bytes.into(),
-                            token_key_id: key_id,
-                        };
-                        url_identity = Some((actor_id, credential));
-                    } else {
-                        warn!("⚠️ 无法解析 token (base64) 来自 URL 参数");
-                    }
-                } else {
-                    warn!("⚠️ 提供了 actor_id 但缺少 token 参数");
-                }
-            }
-            Err(e) => {
-                error!("⚠️ 无法解析 actor_id 字符串 '{}': {}", actor_str, e);
-            }
-        }
-    }
+    info!(
+        "📡 新 WebSocket 连接: IP={}, 压缩={}",
+        client_ip,
+        compressor.is_some()
+    );
 
     // 提取 webrtc_role 参数（如果存在）
     let webrtc_role = params.get("webrtc_role").cloned();
@@ -320,16 +661,7 @@ async fn handle_websocket(
     }
 
     // 创建 SignalingServerHandle
-    let server_handle = SignalingServerHandle {
-        clients: state.server.clients.clone(),
-        actor_id_index: state.server.actor_id_index.clone(),
-        service_registry: state.server.service_registry.clone(),
-        presence_manager: state.server.presence_manager.clone(),
-        ais_client: state.server.ais_client.clone(),
-        compatibility_cache: state.server.compatibility_cache.clone(),
-        connection_rate_limiter: state.server.connection_rate_limiter.clone(),
-        message_rate_limiter: state.server.message_rate_limiter.clone(),
-    };
+    let server_handle = state.server.handle();
 
     // 调用 SignalingServer 的 WebSocket 处理函数
     if let Err(e) = crate::handle_websocket_connection(
@@ -338,6 +670,9 @@ async fn handle_websocket(
         Some(client_ip),
         url_identity,
         webrtc_role,
+        resume_token,
+        fingerprint,
+        compressor,
     )
     .await
     {
@@ -0,0 +1,84 @@
+//! SignalingEnvelope 编解码
+//!
+//! 信令热路径上的 envelope 统一经此编解码：
+//! - 解码直接以 WebSocket 帧的 `Bytes` 作为输入，不再复制到中间缓冲区
+//! - 编码写入线程本地的 `BytesMut`，以 `split().freeze()` 交出结果；
+//!   之前交出的 `Bytes` 全部释放后，下次扩容会原地回收同一块内存，
+//!   每条响应不再单独分配并反复扩容 `Vec`
+//! - 每条消息的编解码耗时记录到 `actrix_signaling_envelope_codec_seconds`
+
+use actr_protocol::SignalingEnvelope;
+use actrix_common::metrics::SIGNALING_ENVELOPE_CODEC_SECONDS;
+use bytes::{Bytes, BytesMut};
+use prost::Message as ProstMessage;
+use std::cell::RefCell;
+use std::time::Instant;
+
+/// 编码缓冲区每次扩容的最小字节数（多条小 envelope 共用一块内存）
+const ENCODE_CHUNK_SIZE: usize = 16 * 1024;
+
+thread_local! {
+    static ENCODE_BUFFER: RefCell<BytesMut> = RefCell::new(BytesMut::new());
+}
+
+/// 从 WebSocket 帧解码 envelope
+pub fn decode_envelope(data: &Bytes) -> Result<SignalingEnvelope, prost::DecodeError> {
+    let start = Instant::now();
+    let result = SignalingEnvelope::decode(data.clone());
+    SIGNALING_ENVELOPE_CODEC_SECONDS
+        .with_label_values(&["decode"])
+        .observe(start.elapsed().as_secs_f64());
+    result
+}
+
+/// 将 envelope 编码为可直接作为 Binary 帧发送的 `Bytes`
+pub fn encode_envelope(envelope: &SignalingEnvelope) -> Bytes {
+    let start = Instant::now();
+    let len = envelope.encoded_len();
+    let encoded = ENCODE_BUFFER.with_borrow_mut(|buffer| {
+        if buffer.capacity() < len {
+            buffer.reserve(len.max(ENCODE_CHUNK_SIZE));
+        }
+        envelope.encode(buffer).expect("BytesMut grows on demand");
+        buffer.split().freeze()
+    });
+    SIGNALING_ENVELOPE_CODEC_SECONDS
+        .with_label_values(&["encode"])
+        .observe(start.elapsed().as_secs_f64());
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(id: &str) -> SignalingEnvelope {
+        SignalingEnvelope {
+            envelope_version: 1,
+            envelope_id: id.to_string(),
+            reply_for: None,
+            timestamp: prost_types::Timestamp::default(),
+            traceparent: None,
+            tracestate: None,
+            flow: None,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let original = envelope("roundtrip");
+        let encoded = encode_envelope(&original);
+        assert_eq!(encoded, original.encode_to_vec());
+        assert_eq!(decode_envelope(&encoded).unwrap(), original);
+    }
+
+    #[test]
+    fn test_encodes_share_buffer() {
+        let first = encode_envelope(&envelope("first"));
+        let second = encode_envelope(&envelope("second"));
+        // 连续编码写入同一块内存的相邻位置
+        assert_eq!(second.as_ptr(), first.as_ptr().wrapping_add(first.len()));
+        assert_eq!(decode_envelope(&first).unwrap().envelope_id, "first");
+        assert_eq!(decode_envelope(&second).unwrap().envelope_id, "second");
+    }
+}
@@ -0,0 +1,196 @@
+//! WebSocket 消息压缩
+//!
+//! 较大的 ServiceSpec（含完整 proto 内容）与服务发现结果以未压缩的 Binary 帧下发。
+//! axum 的 WebSocket 实现不支持 RFC 7692 的 `permessage-deflate` 帧级扩展，
+//! 因此采用等价的按消息 deflate 压缩，在握手时通过子协议协商：
+//!
+//! 1. 客户端在 `Sec-WebSocket-Protocol` 中提供 [`SUBPROTOCOL`]
+//! 2. 启用压缩时 Signaling 选中该子协议，之后双方都可以发送压缩消息
+//! 3. 压缩消息为 [`COMPRESSED_MARKER`] 加 raw deflate 数据；protobuf 消息的首字节不可能为 0
+//!    （字段号 0 非法），因此未压缩的 envelope 无需任何前缀
+//!
+//! 只有超过 `threshold_bytes` 且压缩后更小的消息才会压缩，未协商的连接不受影响。
+
+use actrix_common::config::signaling::CompressionConfig;
+use actrix_common::metrics::{SIGNALING_COMPRESSION_BYTES, SIGNALING_COMPRESSION_RATIO};
+use axum::extract::ws::Message as WsMessage;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use std::io::{Read, Write};
+use thiserror::Error;
+
+/// 协商压缩使用的 WebSocket 子协议
+pub const SUBPROTOCOL: &str = "actrix.deflate";
+
+/// 压缩消息的首字节
+pub const COMPRESSED_MARKER: u8 = 0x00;
+
+/// 解压失败
+#[derive(Debug, Error)]
+pub enum DecompressError {
+    #[error("decompressed message exceeds {0} bytes")]
+    TooLarge(usize),
+    #[error("invalid deflate data: {0}")]
+    Invalid(#[from] std::io::Error),
+}
+
+/// 按消息 deflate 压缩器
+#[derive(Debug, Clone)]
+pub struct Compressor {
+    threshold_bytes: usize,
+    level: Compression,
+}
+
+impl Compressor {
+    /// 根据配置创建，未启用时返回 None
+    pub fn from_config(config: &CompressionConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            threshold_bytes: config.threshold_bytes,
+            level: Compression::new(config.level),
+        })
+    }
+
+    /// 压缩消息体，未达到阈值或压缩后没有变小时返回 None
+    pub fn compress(&self, data: &[u8]) -> Option<Vec<u8>> {
+        if data.len() < self.threshold_bytes {
+            return None;
+        }
+
+        let mut output = Vec::with_capacity(data.len() / 2 + 1);
+        output.push(COMPRESSED_MARKER);
+        let mut encoder = DeflateEncoder::new(output, self.level);
+        encoder.write_all(data).ok()?;
+        let compressed = encoder.finish().ok()?;
+        if compressed.len() >= data.len() {
+            return None;
+        }
+
+        record("outbound", data.len(), compressed.len());
+        Some(compressed)
+    }
+
+    /// 压缩发往客户端的 Binary 消息，其他消息原样返回
+    pub fn encode(&self, message: WsMessage) -> WsMessage {
+        match message {
+            WsMessage::Binary(data) => match self.compress(&data) {
+                Some(compressed) => WsMessage::Binary(compressed.into()),
+                None => WsMessage::Binary(data),
+            },
+            other => other,
+        }
+    }
+
+    /// 解压客户端发送的消息，未压缩的消息返回 None
+    ///
+    /// 解压后超过 `max_bytes` 时返回错误，避免压缩炸弹
+    pub fn decompress(
+        &self,
+        data: &[u8],
+        max_bytes: usize,
+    ) -> Result<Option<Vec<u8>>, DecompressError> {
+        let Some((&COMPRESSED_MARKER, body)) = data.split_first() else {
+            return Ok(None);
+        };
+
+        let mut output = Vec::new();
+        DeflateDecoder::new(body)
+            .take(max_bytes as u64 + 1)
+            .read_to_end(&mut output)?;
+        if output.len() > max_bytes {
+            return Err(DecompressError::TooLarge(max_bytes));
+        }
+
+        record("inbound", output.len(), data.len());
+        Ok(Some(output))
+    }
+}
+
+fn record(direction: &str, original: usize, compressed: usize) {
+    SIGNALING_COMPRESSION_BYTES
+        .with_label_values(&[direction, "original"])
+        .inc_by(original as u64);
+    SIGNALING_COMPRESSION_BYTES
+        .with_label_values(&[direction, "compressed"])
+        .inc_by(compressed as u64);
+    if original > 0 {
+        SIGNALING_COMPRESSION_RATIO
+            .with_label_values(&[direction])
+            .observe(compressed as f64 / original as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compressor(threshold_bytes: usize) -> Compressor {
+        Compressor::from_config(&CompressionConfig {
+            enabled: true,
+            threshold_bytes,
+            level: 6,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        assert!(Compressor::from_config(&CompressionConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_compress_roundtrip() {
+        let compressor = compressor(64);
+        let spec = "syntax = \"proto3\";\nmessage Echo { string text = 1; }\n".repeat(50);
+
+        let compressed = compressor.compress(spec.as_bytes()).unwrap();
+        assert_eq!(compressed[0], COMPRESSED_MARKER);
+        assert!(compressed.len() < spec.len());
+
+        let restored = compressor
+            .decompress(&compressed, spec.len())
+            .unwrap()
+            .unwrap();
+        assert_eq!(restored, spec.as_bytes());
+    }
+
+    #[test]
+    fn test_small_or_incompressible_messages_untouched() {
+        let compressor = compressor(64);
+        assert!(compressor.compress(&[0x0a, 0x02, 0x08, 0x01]).is_none());
+
+        // 伪随机数据压缩后不会变小
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let noise: Vec<u8> = (0..256)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 56) as u8
+            })
+            .collect();
+        assert!(compressor.compress(&noise).is_none());
+
+        // 未压缩的 envelope 原样交给上层
+        assert!(
+            compressor
+                .decompress(&[0x0a, 0x02], 1024)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_decompress_limit() {
+        let compressor = compressor(0);
+        let compressed = compressor.compress(&[b'a'; 4096]).unwrap();
+        assert!(matches!(
+            compressor.decompress(&compressed, 1024),
+            Err(DecompressError::TooLarge(1024))
+        ));
+        assert!(matches!(
+            compressor.decompress(&[COMPRESSED_MARKER, 0xff, 0xff], 1024),
+            Err(DecompressError::Invalid(_))
+        ));
+    }
+}
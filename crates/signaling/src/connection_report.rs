@@ -0,0 +1,267 @@
+//! ICE 连接质量上报 (ConnectionReport)
+//!
+//! Actor 在 ICE 完成后上报选中的候选对（本端/远端候选类型、RTT、是否经 TURN 中继），
+//! 信令服务器按 Realm 聚合，并导出为 Prometheus 指标，帮助运维了解实际需要 TURN 中继的比例。
+//!
+//! # 上报方式
+//! actr-protocol 目前没有专用的上报 payload，客户端通过 `ActrToSignaling` 的 `Error` payload 上报：
+//! `code` 为 [`CONNECTION_REPORT_CODE`]，`message` 为 [`CONNECTION_REPORT_PREFIX`]
+//! 加 JSON 编码的 [`ConnectionReport`]。服务器不回复该消息。
+
+use crate::tunnel;
+use actr_protocol::ErrorResponse;
+use actrix_common::metrics::{ICE_CONNECTION_REPORTS, ICE_CONNECTION_RTT};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 上报使用的 ErrorResponse code
+pub const CONNECTION_REPORT_CODE: u32 = tunnel::CONNECTION_REPORT.code;
+
+/// 上报 message 前缀，其后为 JSON 编码的 [`ConnectionReport`]
+pub const CONNECTION_REPORT_PREFIX: &str = tunnel::CONNECTION_REPORT.prefix;
+
+/// RTT 上报值上限（毫秒），超出视为无效样本
+const MAX_REPORTED_RTT_MS: f64 = 60_000.0;
+
+/// ICE 候选类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateType {
+    Host,
+    Srflx,
+    Prflx,
+    Relay,
+}
+
+impl CandidateType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Host => "host",
+            Self::Srflx => "srflx",
+            Self::Prflx => "prflx",
+            Self::Relay => "relay",
+        }
+    }
+}
+
+/// Actor 上报的 ICE 选中候选对信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionReport {
+    /// 对端 Actor（字符串表示，可选）
+    #[serde(default)]
+    pub peer: Option<String>,
+    /// 本端选中的候选类型
+    pub local_candidate_type: CandidateType,
+    /// 远端选中的候选类型
+    pub remote_candidate_type: CandidateType,
+    /// 选中候选对的往返时延（毫秒）
+    #[serde(default)]
+    pub rtt_ms: Option<f64>,
+    /// 中继传输协议（udp / tcp / tls），仅经 TURN 中继时有意义
+    #[serde(default)]
+    pub relay_protocol: Option<String>,
+}
+
+impl ConnectionReport {
+    /// 从客户端 ErrorResponse 中解析上报
+    ///
+    /// 不是上报消息时返回 None；是上报消息但内容无效时返回 `Some(Err)`
+    pub fn from_error_response(error: &ErrorResponse) -> Option<Result<Self, serde_json::Error>> {
+        tunnel::CONNECTION_REPORT.decode(error)
+    }
+
+    /// 编码为客户端发送的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::CONNECTION_REPORT.encode(self)
+    }
+
+    /// 是否经 TURN 中继（任一端选中 relay 候选）
+    pub fn uses_relay(&self) -> bool {
+        self.local_candidate_type == CandidateType::Relay
+            || self.remote_candidate_type == CandidateType::Relay
+    }
+
+    /// 有效的 RTT 样本
+    fn valid_rtt_ms(&self) -> Option<f64> {
+        self.rtt_ms
+            .filter(|rtt| rtt.is_finite() && (0.0..=MAX_REPORTED_RTT_MS).contains(rtt))
+    }
+}
+
+/// 单个 Realm 的聚合数据
+#[derive(Debug, Default)]
+struct RealmReports {
+    reports_total: u64,
+    relayed_total: u64,
+    rtt_sum_ms: f64,
+    rtt_samples: u64,
+    /// (本端类型, 远端类型) -> 次数
+    candidate_pairs: HashMap<(CandidateType, CandidateType), u64>,
+}
+
+/// 单个 Realm 的 ConnectionReport 汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct RealmConnectionSummary {
+    pub realm_id: u32,
+    pub reports_total: u64,
+    pub relayed_total: u64,
+    /// 经 TURN 中继的比例 (0.0 ~ 1.0)
+    pub relay_ratio: f64,
+    /// 平均 RTT（毫秒），无样本时为 None
+    pub avg_rtt_ms: Option<f64>,
+    /// 各候选对出现次数，key 为 `local/remote`（如 `srflx/relay`）
+    pub candidate_pairs: HashMap<String, u64>,
+}
+
+/// 按 Realm 聚合的 ConnectionReport 统计
+#[derive(Debug, Default)]
+pub struct ConnectionReportStats {
+    realms: Mutex<HashMap<u32, RealmReports>>,
+}
+
+impl ConnectionReportStats {
+    /// 记录一次上报，同时更新 Prometheus 指标
+    pub fn record(&self, realm_id: u32, report: &ConnectionReport) {
+        let relayed = report.uses_relay();
+        let rtt_ms = report.valid_rtt_ms();
+
+        {
+            let mut realms = self.realms.lock().expect("connection reports poisoned");
+            let realm = realms.entry(realm_id).or_default();
+            realm.reports_total += 1;
+            if relayed {
+                realm.relayed_total += 1;
+            }
+            if let Some(rtt_ms) = rtt_ms {
+                realm.rtt_sum_ms += rtt_ms;
+                realm.rtt_samples += 1;
+            }
+            *realm
+                .candidate_pairs
+                .entry((report.local_candidate_type, report.remote_candidate_type))
+                .or_default() += 1;
+        }
+
+        let realm_label = realm_id.to_string();
+        let relayed_label = if relayed { "true" } else { "false" };
+        ICE_CONNECTION_REPORTS
+            .with_label_values(&[
+                realm_label.as_str(),
+                report.local_candidate_type.as_str(),
+                report.remote_candidate_type.as_str(),
+                relayed_label,
+            ])
+            .inc();
+        if let Some(rtt_ms) = rtt_ms {
+            ICE_CONNECTION_RTT
+                .with_label_values(&[realm_label.as_str(), relayed_label])
+                .observe(rtt_ms / 1000.0);
+        }
+    }
+
+    /// 各 Realm 的汇总，按 realm_id 排序；指定 realm_id 时只返回该 Realm
+    pub fn snapshot(&self, realm_id: Option<u32>) -> Vec<RealmConnectionSummary> {
+        let realms = self.realms.lock().expect("connection reports poisoned");
+        let mut summaries: Vec<RealmConnectionSummary> = realms
+            .iter()
+            .filter(|(id, _)| realm_id.is_none_or(|wanted| wanted == **id))
+            .map(|(id, realm)| RealmConnectionSummary {
+                realm_id: *id,
+                reports_total: realm.reports_total,
+                relayed_total: realm.relayed_total,
+                relay_ratio: realm.relayed_total as f64 / realm.reports_total.max(1) as f64,
+                avg_rtt_ms: (realm.rtt_samples > 0)
+                    .then(|| realm.rtt_sum_ms / realm.rtt_samples as f64),
+                candidate_pairs: realm
+                    .candidate_pairs
+                    .iter()
+                    .map(|((local, remote), count)| {
+                        (format!("{}/{}", local.as_str(), remote.as_str()), *count)
+                    })
+                    .collect(),
+            })
+            .collect();
+        summaries.sort_by_key(|s| s.realm_id);
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(
+        local: CandidateType,
+        remote: CandidateType,
+        rtt_ms: Option<f64>,
+    ) -> ConnectionReport {
+        ConnectionReport {
+            peer: None,
+            local_candidate_type: local,
+            remote_candidate_type: remote,
+            rtt_ms,
+            relay_protocol: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_from_error_response() {
+        let original = report(CandidateType::Srflx, CandidateType::Relay, Some(42.0));
+        let parsed = ConnectionReport::from_error_response(&original.to_error_response())
+            .unwrap()
+            .unwrap();
+        assert!(parsed.uses_relay());
+        assert_eq!(parsed.rtt_ms, Some(42.0));
+
+        let unrelated = ErrorResponse {
+            code: 500,
+            message: "boom".to_string(),
+        };
+        assert!(ConnectionReport::from_error_response(&unrelated).is_none());
+
+        let malformed = ErrorResponse {
+            code: CONNECTION_REPORT_CODE,
+            message: format!("{CONNECTION_REPORT_PREFIX}{{\"local_candidate_type\":\"bogus\"}}"),
+        };
+        assert!(
+            ConnectionReport::from_error_response(&malformed)
+                .unwrap()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_stats_aggregate_per_realm() {
+        let stats = ConnectionReportStats::default();
+        stats.record(
+            1,
+            &report(CandidateType::Host, CandidateType::Host, Some(10.0)),
+        );
+        stats.record(
+            1,
+            &report(CandidateType::Relay, CandidateType::Srflx, Some(30.0)),
+        );
+        stats.record(
+            1,
+            &report(CandidateType::Srflx, CandidateType::Srflx, Some(f64::NAN)),
+        );
+        stats.record(2, &report(CandidateType::Relay, CandidateType::Relay, None));
+
+        let all = stats.snapshot(None);
+        assert_eq!(all.len(), 2);
+
+        let realm1 = &all[0];
+        assert_eq!(realm1.realm_id, 1);
+        assert_eq!(realm1.reports_total, 3);
+        assert_eq!(realm1.relayed_total, 1);
+        assert!((realm1.relay_ratio - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(realm1.avg_rtt_ms, Some(20.0));
+        assert_eq!(realm1.candidate_pairs["relay/srflx"], 1);
+
+        let realm2 = stats.snapshot(Some(2));
+        assert_eq!(realm2.len(), 1);
+        assert_eq!(realm2[0].relay_ratio, 1.0);
+        assert_eq!(realm2[0].avg_rtt_ms, None);
+    }
+}
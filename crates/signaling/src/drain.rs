@@ -0,0 +1,238 @@
+//! 节点排空 (Drain)
+//!
+//! 节点下线或维护前进入排空状态：已建立的会话照常工作（心跳、中继、凭证刷新不受影响），
+//! 新的注册请求被拒绝并携带重定向提示，客户端据此改连其他节点；节点的 `/readyz`
+//! 同时返回 503，负载均衡随之停止分配新连接。
+//! 通过管理 API (`POST /admin/drain` / `DELETE /admin/drain`) 或 Supervisor 的 `DRAIN` 指令触发。
+//!
+//! # 投递方式
+//! 与 [`crate::server_notice`] 相同，actr-protocol 的 RegisterResponse 没有重定向结果，
+//! 提示通过 `ErrorResponse` 下发：`code` 为 [`DRAIN_ERROR_CODE`]，
+//! `message` 为 [`DRAIN_REDIRECT_PREFIX`] 加 JSON 编码的 [`DrainRedirect`]。
+
+use crate::tunnel;
+use actr_protocol::ErrorResponse;
+use actrix_common::metrics::SIGNALING_DRAINING;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use tracing::{info, warn};
+
+/// 排空期间拒绝注册使用的 ErrorResponse code（307，临时重定向）
+pub const DRAIN_ERROR_CODE: u32 = tunnel::DRAIN_REDIRECT.code;
+
+/// 重定向提示 message 前缀，其后为 JSON 编码的 [`DrainRedirect`]
+pub const DRAIN_REDIRECT_PREFIX: &str = tunnel::DRAIN_REDIRECT.prefix;
+
+/// 未指定时建议客户端等待的重试时间（秒）
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 5;
+
+/// 下发给新注册请求的重定向提示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrainRedirect {
+    /// 可改连的其他节点 Signaling 地址（如 `wss://node-b.example.com/signaling/ws`），可能为空
+    #[serde(default)]
+    pub alternates: Vec<String>,
+    /// 建议的重试等待时间（秒）
+    #[serde(default = "default_retry_after_secs")]
+    pub retry_after_secs: u64,
+    /// 排空原因（如 maintenance / upgrade），由运维自定义
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+fn default_retry_after_secs() -> u64 {
+    DEFAULT_RETRY_AFTER_SECS
+}
+
+impl DrainRedirect {
+    /// 从 Supervisor `DRAIN` 指令的 payload 解析
+    ///
+    /// payload 为 JSON 编码的 [`DrainRedirect`] 时直接使用，否则按逗号分隔的备选地址列表处理；
+    /// 没有 payload 表示没有备选节点
+    pub fn from_directive_payload(payload: Option<&str>) -> Self {
+        let payload = payload.map(str::trim).unwrap_or_default();
+        if let Ok(redirect) = serde_json::from_str::<Self>(payload) {
+            return redirect;
+        }
+        Self {
+            alternates: payload
+                .split(',')
+                .map(str::trim)
+                .filter(|alternate| !alternate.is_empty())
+                .map(str::to_string)
+                .collect(),
+            retry_after_secs: DEFAULT_RETRY_AFTER_SECS,
+            reason: None,
+        }
+    }
+
+    /// 编码为下发给客户端的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::DRAIN_REDIRECT.encode(self)
+    }
+
+    /// 从 ErrorResponse 解析重定向提示（客户端侧使用）
+    pub fn from_error_response(error: &ErrorResponse) -> Option<Self> {
+        tunnel::DRAIN_REDIRECT.decode(error)?.ok()
+    }
+}
+
+/// 排空状态快照
+#[derive(Debug, Clone, Serialize)]
+pub struct DrainStatus {
+    pub draining: bool,
+    /// 进入排空的时间 (Unix 秒)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<DrainRedirect>,
+}
+
+/// 节点排空状态
+#[derive(Debug, Default)]
+pub struct DrainState {
+    current: RwLock<Option<(DrainRedirect, i64)>>,
+}
+
+impl DrainState {
+    /// 进入排空状态；已在排空中时更新重定向提示，保留进入时间
+    pub fn start(&self, redirect: DrainRedirect) {
+        let mut current = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let since = current
+            .as_ref()
+            .map(|(_, since)| *since)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+        warn!(
+            "🚧 信令节点进入排空状态: alternates={:?} retry_after={}s reason={:?}",
+            redirect.alternates, redirect.retry_after_secs, redirect.reason
+        );
+        *current = Some((redirect, since));
+        SIGNALING_DRAINING.set(1);
+    }
+
+    /// 退出排空状态，返回此前是否处于排空中
+    pub fn stop(&self) -> bool {
+        let was_draining = self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+            .is_some();
+        if was_draining {
+            info!("✅ 信令节点退出排空状态，恢复接收新注册");
+        }
+        SIGNALING_DRAINING.set(0);
+        was_draining
+    }
+
+    /// 当前是否处于排空状态
+    pub fn is_draining(&self) -> bool {
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_some()
+    }
+
+    /// 排空中时返回下发给新注册请求的重定向提示
+    pub fn redirect(&self) -> Option<DrainRedirect> {
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+            .map(|(redirect, _)| redirect.clone())
+    }
+
+    /// 当前状态快照
+    pub fn status(&self) -> DrainStatus {
+        let current = self
+            .current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        DrainStatus {
+            draining: current.is_some(),
+            since: current.as_ref().map(|(_, since)| *since),
+            redirect: current.as_ref().map(|(redirect, _)| redirect.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directive_payload() {
+        let redirect = DrainRedirect::from_directive_payload(Some(
+            " wss://a.example.com/signaling/ws, ,wss://b.example.com/signaling/ws ",
+        ));
+        assert_eq!(
+            redirect.alternates,
+            vec![
+                "wss://a.example.com/signaling/ws",
+                "wss://b.example.com/signaling/ws"
+            ]
+        );
+        assert_eq!(redirect.retry_after_secs, DEFAULT_RETRY_AFTER_SECS);
+
+        let redirect = DrainRedirect::from_directive_payload(Some(
+            r#"{"alternates":["wss://c.example.com/signaling/ws"],"retry_after_secs":30,"reason":"upgrade"}"#,
+        ));
+        assert_eq!(
+            redirect.alternates,
+            vec!["wss://c.example.com/signaling/ws"]
+        );
+        assert_eq!(redirect.retry_after_secs, 30);
+        assert_eq!(redirect.reason.as_deref(), Some("upgrade"));
+
+        assert!(
+            DrainRedirect::from_directive_payload(None)
+                .alternates
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_error_response_roundtrip() {
+        let redirect = DrainRedirect {
+            alternates: vec!["wss://b.example.com/signaling/ws".to_string()],
+            retry_after_secs: 10,
+            reason: Some("maintenance".to_string()),
+        };
+        let error = redirect.to_error_response();
+        assert_eq!(error.code, DRAIN_ERROR_CODE);
+        assert!(error.message.starts_with(DRAIN_REDIRECT_PREFIX));
+        assert_eq!(DrainRedirect::from_error_response(&error), Some(redirect));
+
+        let other = ErrorResponse {
+            code: tunnel::SERVICE_UNAVAILABLE_CODE,
+            message: "overloaded".to_string(),
+        };
+        assert_eq!(DrainRedirect::from_error_response(&other), None);
+    }
+
+    #[test]
+    fn test_drain_state() {
+        let state = DrainState::default();
+        assert!(!state.is_draining());
+        assert!(state.redirect().is_none());
+        assert!(!state.stop());
+
+        state.start(DrainRedirect::from_directive_payload(None));
+        let since = state.status().since;
+        assert!(state.is_draining());
+        assert!(since.is_some());
+
+        // 更新重定向提示不改变进入时间
+        state.start(DrainRedirect::from_directive_payload(Some(
+            "wss://b.example.com/signaling/ws",
+        )));
+        assert_eq!(state.status().since, since);
+        assert_eq!(state.redirect().unwrap().alternates.len(), 1);
+
+        assert!(state.stop());
+        assert!(!state.status().draining);
+    }
+}
@@ -0,0 +1,302 @@
+//! 同一 ActrId 的重复连接处理
+//!
+//! Actor 通过 URL 身份重连或以首条认证消息绑定身份时，若该 ActrId 已有在线连接，
+//! 按 [`DuplicateIdentityConfig`]（可按 Realm 覆盖）决定：
+//! - `replace`：断开全部旧连接，保留新连接
+//! - `reject_new`：保留旧连接，以 Close 帧（`POLICY`）拒绝新连接
+//! - `allow_multiple`：最多保留 `max_connections` 个连接，超出时断开最早的连接
+//!
+//! 每个连接按客户端 IP 与 User-Agent 计算指纹（[`connection_fingerprint`]），
+//! 启用 `same_fingerprint_replaces` 时指纹相同的新连接视为断线重连，先替换这些旧连接。
+//!
+//! 旧连接被替换时收到 Close 帧，并广播 [`IdentityReplaced`] 事件。
+
+use actr_protocol::ActrId;
+use actrix_common::config::signaling::{DuplicateIdentityConfig, DuplicateIdentityPolicy};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::IpAddr;
+use tokio::sync::broadcast;
+use tracing::info;
+
+/// 事件通道容量，订阅者落后超过该数量时丢失最旧的事件
+const REPLACEMENT_EVENT_CAPACITY: usize = 64;
+
+/// 被替换的旧连接收到的 Close 帧原因
+pub const REPLACED_CLOSE_REASON: &str = "Replaced by a newer connection";
+
+/// 被拒绝的新连接收到的 Close 帧原因
+pub const REJECTED_CLOSE_REASON: &str = "Duplicate identity rejected";
+
+/// 计算连接指纹（客户端 IP + User-Agent）
+///
+/// 非加密指纹，仅用于区分同一 Actor 的不同客户端
+pub fn connection_fingerprint(client_ip: Option<IpAddr>, user_agent: Option<&str>) -> String {
+    let mut hasher = DefaultHasher::new();
+    client_ip.hash(&mut hasher);
+    user_agent.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// 同一 Actor 的已有连接
+#[derive(Debug, Clone)]
+pub struct ExistingConnection {
+    pub client_id: String,
+    pub fingerprint: String,
+    /// 建立连接的时间 (Unix 秒)
+    pub connected_at: i64,
+}
+
+/// 重复连接的处理结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DuplicateDecision {
+    /// 接受新连接，并断开列出的旧连接
+    Admit { evict: Vec<String> },
+    /// 拒绝新连接
+    Reject,
+}
+
+/// 一次连接替换（推送给订阅者的事件）
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdentityReplaced {
+    pub actor_id: ActrId,
+    /// 被断开的旧连接
+    pub replaced_client_id: String,
+    pub replaced_fingerprint: String,
+    /// 替换它的新连接
+    pub new_client_id: String,
+    pub new_fingerprint: String,
+    /// 替换时间 (Unix 秒)
+    pub replaced_at: i64,
+}
+
+/// 重复连接策略
+#[derive(Debug)]
+pub struct DuplicateIdentityGuard {
+    config: DuplicateIdentityConfig,
+    events: broadcast::Sender<IdentityReplaced>,
+}
+
+impl Default for DuplicateIdentityGuard {
+    fn default() -> Self {
+        Self::new(&DuplicateIdentityConfig::default())
+    }
+}
+
+impl DuplicateIdentityGuard {
+    pub fn new(config: &DuplicateIdentityConfig) -> Self {
+        Self {
+            config: config.clone(),
+            events: broadcast::channel(REPLACEMENT_EVENT_CAPACITY).0,
+        }
+    }
+
+    /// 订阅连接替换事件
+    pub fn subscribe(&self) -> broadcast::Receiver<IdentityReplaced> {
+        self.events.subscribe()
+    }
+
+    /// 决定如何处理新连接与同一 Actor 的已有连接
+    pub fn resolve(
+        &self,
+        actor_id: &ActrId,
+        fingerprint: &str,
+        existing: &[ExistingConnection],
+    ) -> DuplicateDecision {
+        if existing.is_empty() {
+            return DuplicateDecision::Admit { evict: Vec::new() };
+        }
+
+        let (policy, max_connections) = self.config.policy_for_realm(actor_id.realm.realm_id);
+        let (same, mut others): (Vec<_>, Vec<_>) = existing.iter().partition(|conn| {
+            self.config.same_fingerprint_replaces && conn.fingerprint == fingerprint
+        });
+
+        match policy {
+            DuplicateIdentityPolicy::Replace => DuplicateDecision::Admit {
+                evict: existing.iter().map(|conn| conn.client_id.clone()).collect(),
+            },
+            DuplicateIdentityPolicy::RejectNew if others.is_empty() => DuplicateDecision::Admit {
+                evict: same.iter().map(|conn| conn.client_id.clone()).collect(),
+            },
+            DuplicateIdentityPolicy::RejectNew => DuplicateDecision::Reject,
+            DuplicateIdentityPolicy::AllowMultiple => {
+                let mut evict: Vec<String> =
+                    same.iter().map(|conn| conn.client_id.clone()).collect();
+
+                // 为新连接腾出位置，从最早的连接开始断开
+                others.sort_by(|a, b| {
+                    (a.connected_at, &a.client_id).cmp(&(b.connected_at, &b.client_id))
+                });
+                let excess = (others.len() + 1).saturating_sub(max_connections.max(1));
+                evict.extend(
+                    others
+                        .iter()
+                        .take(excess)
+                        .map(|conn| conn.client_id.clone()),
+                );
+
+                DuplicateDecision::Admit { evict }
+            }
+        }
+    }
+
+    /// 广播连接替换事件
+    pub fn notify_replaced(
+        &self,
+        actor_id: &ActrId,
+        replaced: &ExistingConnection,
+        new_client_id: &str,
+        new_fingerprint: &str,
+    ) {
+        info!(
+            "🔁 Actor {} 的连接 {} (指纹 {}) 被新连接 {} (指纹 {}) 替换",
+            actor_id.serial_number,
+            replaced.client_id,
+            replaced.fingerprint,
+            new_client_id,
+            new_fingerprint
+        );
+
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.events.send(IdentityReplaced {
+            actor_id: actor_id.clone(),
+            replaced_client_id: replaced.client_id.clone(),
+            replaced_fingerprint: replaced.fingerprint.clone(),
+            new_client_id: new_client_id.to_string(),
+            new_fingerprint: new_fingerprint.to_string(),
+            replaced_at: chrono::Utc::now().timestamp(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::{ActrType, Realm};
+    use actrix_common::config::signaling::RealmDuplicateIdentityPolicy;
+
+    fn actor(realm_id: u32) -> ActrId {
+        ActrId {
+            serial_number: 1,
+            realm: Realm { realm_id },
+            r#type: ActrType {
+                manufacturer: "test".to_string(),
+                name: "device".to_string(),
+                version: None,
+            },
+        }
+    }
+
+    fn conn(client_id: &str, fingerprint: &str, connected_at: i64) -> ExistingConnection {
+        ExistingConnection {
+            client_id: client_id.to_string(),
+            fingerprint: fingerprint.to_string(),
+            connected_at,
+        }
+    }
+
+    fn admit(evict: &[&str]) -> DuplicateDecision {
+        DuplicateDecision::Admit {
+            evict: evict.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_connection_fingerprint() {
+        let ip = Some("192.0.2.1".parse().unwrap());
+        let fingerprint = connection_fingerprint(ip, Some("actr-sdk/1.0"));
+        assert_eq!(fingerprint.len(), 16);
+        assert_eq!(
+            fingerprint,
+            connection_fingerprint(ip, Some("actr-sdk/1.0"))
+        );
+        assert_ne!(
+            fingerprint,
+            connection_fingerprint(ip, Some("actr-sdk/2.0"))
+        );
+        assert_ne!(
+            fingerprint,
+            connection_fingerprint(None, Some("actr-sdk/1.0"))
+        );
+    }
+
+    #[test]
+    fn test_replace_and_reject_new() {
+        let existing = [conn("old", "fp-a", 10)];
+
+        let guard = DuplicateIdentityGuard::default();
+        assert_eq!(guard.resolve(&actor(1), "fp-b", &[]), admit(&[]));
+        assert_eq!(guard.resolve(&actor(1), "fp-b", &existing), admit(&["old"]));
+
+        let mut config = DuplicateIdentityConfig {
+            policy: DuplicateIdentityPolicy::RejectNew,
+            ..Default::default()
+        };
+        let guard = DuplicateIdentityGuard::new(&config);
+        assert_eq!(
+            guard.resolve(&actor(1), "fp-b", &existing),
+            DuplicateDecision::Reject
+        );
+        // 同一客户端断线重连仍替换旧连接
+        assert_eq!(guard.resolve(&actor(1), "fp-a", &existing), admit(&["old"]));
+
+        config.same_fingerprint_replaces = false;
+        let guard = DuplicateIdentityGuard::new(&config);
+        assert_eq!(
+            guard.resolve(&actor(1), "fp-a", &existing),
+            DuplicateDecision::Reject
+        );
+    }
+
+    #[test]
+    fn test_allow_multiple_evicts_oldest() {
+        let guard = DuplicateIdentityGuard::new(&DuplicateIdentityConfig {
+            policy: DuplicateIdentityPolicy::AllowMultiple,
+            max_connections: 2,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            guard.resolve(&actor(1), "fp-c", &[conn("a", "fp-a", 10)]),
+            admit(&[])
+        );
+
+        let existing = [conn("b", "fp-b", 20), conn("a", "fp-a", 10)];
+        assert_eq!(guard.resolve(&actor(1), "fp-c", &existing), admit(&["a"]));
+        // 指纹相同的连接先被替换，不再需要断开其他连接
+        assert_eq!(guard.resolve(&actor(1), "fp-b", &existing), admit(&["b"]));
+    }
+
+    #[test]
+    fn test_realm_override() {
+        let guard = DuplicateIdentityGuard::new(&DuplicateIdentityConfig {
+            realm_overrides: vec![RealmDuplicateIdentityPolicy {
+                realm_id: 1001,
+                policy: DuplicateIdentityPolicy::AllowMultiple,
+                max_connections: Some(3),
+            }],
+            ..Default::default()
+        });
+
+        let existing = [conn("a", "fp-a", 10), conn("b", "fp-b", 20)];
+        assert_eq!(guard.resolve(&actor(1001), "fp-c", &existing), admit(&[]));
+        assert_eq!(
+            guard.resolve(&actor(1), "fp-c", &existing),
+            admit(&["a", "b"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_replaced() {
+        let guard = DuplicateIdentityGuard::default();
+        let mut events = guard.subscribe();
+
+        guard.notify_replaced(&actor(1), &conn("old", "fp-a", 10), "new", "fp-b");
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.actor_id, actor(1));
+        assert_eq!(event.replaced_client_id, "old");
+        assert_eq!(event.replaced_fingerprint, "fp-a");
+        assert_eq!(event.new_client_id, "new");
+        assert_eq!(event.new_fingerprint, "fp-b");
+    }
+}
@@ -0,0 +1,171 @@
+//! 局域网直连模式（仅开发环境）
+//!
+//! 完全离线的开发环境中没有可用的 STUN / TURN，浏览器等客户端又会用 mDNS 主机名
+//! （`*.local`）隐藏 host candidate，而 mDNS 解析在部分网络（容器、虚拟机、
+//! 禁用组播的 Wi-Fi）中并不可靠。启用 `dev.lan_discovery` 后，中继的 SDP 与
+//! trickle ICE candidate 在转发前：
+//!
+//! - 只保留 `typ host` candidate，丢弃 srflx / prflx / relay
+//! - 将 mDNS 主机名替换为 Signaling 观察到的发送方 IP（仅当该 IP 为局域网或回环地址）
+//!
+//! 对端因此直接拿到可连通的局域网地址，无需任何 ICE 服务器。
+
+use crate::sdp_filter::is_private_address;
+use std::net::IpAddr;
+
+/// 改写后的 SDP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanRewrite {
+    pub sdp: String,
+    /// mDNS 主机名被替换的 candidate 数量
+    pub rewritten: usize,
+    /// 被丢弃的非 host candidate 数量
+    pub stripped: usize,
+}
+
+/// 单个 candidate 的处理结果
+enum CandidateAction {
+    Keep,
+    Rewrite(String),
+    Drop,
+}
+
+/// 处理 `candidate:<foundation> <component> <transport> <priority> <address> <port> typ <type> ...`
+///
+/// 无法解析的 candidate 原样保留，格式校验由 [`crate::sdp_filter`] 负责
+fn process_candidate(candidate: &str, sender_ip: Option<IpAddr>) -> CandidateAction {
+    let Some(body) = candidate.strip_prefix("candidate:") else {
+        return CandidateAction::Keep;
+    };
+    let fields: Vec<&str> = body.split_whitespace().collect();
+    if fields.len() < 8 || fields[6] != "typ" {
+        return CandidateAction::Keep;
+    }
+    if !fields[7].eq_ignore_ascii_case("host") {
+        return CandidateAction::Drop;
+    }
+
+    match sender_ip {
+        Some(ip) if fields[4].ends_with(".local") && is_private_address(&ip) => {
+            let address = ip.to_string();
+            let fields: Vec<&str> = fields[..4]
+                .iter()
+                .copied()
+                .chain([address.as_str()])
+                .chain(fields[5..].iter().copied())
+                .collect();
+            CandidateAction::Rewrite(format!("candidate:{}", fields.join(" ")))
+        }
+        _ => CandidateAction::Keep,
+    }
+}
+
+/// 改写 SDP 中的 candidate，`sender_ip` 为发送方连接 Signaling 时的来源 IP
+pub fn rewrite_sdp(sdp: &str, sender_ip: Option<IpAddr>) -> LanRewrite {
+    let mut output = String::with_capacity(sdp.len());
+    let mut rewritten = 0;
+    let mut stripped = 0;
+
+    for line in sdp.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let ending = &line[content.len()..];
+        let Some(candidate) = content.strip_prefix("a=") else {
+            output.push_str(line);
+            continue;
+        };
+        match process_candidate(candidate, sender_ip) {
+            CandidateAction::Keep => output.push_str(line),
+            CandidateAction::Rewrite(candidate) => {
+                rewritten += 1;
+                output.push_str("a=");
+                output.push_str(&candidate);
+                output.push_str(ending);
+            }
+            CandidateAction::Drop => stripped += 1,
+        }
+    }
+
+    LanRewrite {
+        sdp: output,
+        rewritten,
+        stripped,
+    }
+}
+
+/// 改写 trickle ICE candidate，返回 None 表示应丢弃
+///
+/// 空字符串（end-of-candidates）原样转发
+pub fn rewrite_candidate(candidate: &str, sender_ip: Option<IpAddr>) -> Option<String> {
+    let (prefix, body) = match candidate.strip_prefix("a=") {
+        Some(body) => ("a=", body),
+        None => ("", candidate),
+    };
+    match process_candidate(body.trim(), sender_ip) {
+        CandidateAction::Keep => Some(candidate.to_string()),
+        CandidateAction::Rewrite(body) => Some(format!("{prefix}{body}")),
+        CandidateAction::Drop => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFER: &str = "v=0\r\n\
+        o=- 4611731400430051336 2 IN IP4 127.0.0.1\r\n\
+        s=-\r\n\
+        t=0 0\r\n\
+        m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+        c=IN IP4 0.0.0.0\r\n\
+        a=candidate:1 1 udp 2122260223 4b3c.local 54400 typ host generation 0\r\n\
+        a=candidate:2 1 udp 1686052607 203.0.113.7 54400 typ srflx raddr 0.0.0.0 rport 0\r\n\
+        a=candidate:3 1 udp 41885439 198.51.100.1 3478 typ relay raddr 203.0.113.7 rport 54400\r\n\
+        a=candidate:4 1 tcp 1518280447 192.168.1.10 9 typ host tcptype active\r\n\
+        a=sctp-port:5000\r\n";
+
+    #[test]
+    fn test_rewrite_sdp_keeps_host_candidates() {
+        let ip: IpAddr = "192.168.1.20".parse().unwrap();
+        let result = rewrite_sdp(OFFER, Some(ip));
+        assert_eq!(result.rewritten, 1);
+        assert_eq!(result.stripped, 2);
+        assert!(result.sdp.contains(
+            "a=candidate:1 1 udp 2122260223 192.168.1.20 54400 typ host generation 0\r\n"
+        ));
+        assert!(result.sdp.contains("192.168.1.10 9 typ host"));
+        assert!(!result.sdp.contains("typ srflx"));
+        assert!(!result.sdp.contains("typ relay"));
+        assert!(result.sdp.ends_with("a=sctp-port:5000\r\n"));
+    }
+
+    #[test]
+    fn test_mdns_kept_for_public_or_unknown_sender() {
+        let public: IpAddr = "203.0.113.9".parse().unwrap();
+        for sender_ip in [Some(public), None] {
+            let result = rewrite_sdp(OFFER, sender_ip);
+            assert_eq!(result.rewritten, 0);
+            assert!(result.sdp.contains("4b3c.local"));
+        }
+    }
+
+    #[test]
+    fn test_rewrite_trickle_candidate() {
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        assert_eq!(
+            rewrite_candidate(
+                "a=candidate:1 1 udp 2122260223 4b3c.local 54400 typ host",
+                Some(ip)
+            )
+            .as_deref(),
+            Some("a=candidate:1 1 udp 2122260223 127.0.0.1 54400 typ host")
+        );
+        assert_eq!(
+            rewrite_candidate(
+                "candidate:2 1 udp 1686052607 203.0.113.7 54400 typ srflx raddr 0.0.0.0 rport 0",
+                Some(ip)
+            ),
+            None
+        );
+        assert_eq!(rewrite_candidate("", Some(ip)).as_deref(), Some(""));
+    }
+}
@@ -11,21 +11,61 @@
 //!
 //! ## 扩展模块
 //! - [`presence`] - Presence 订阅管理
+//! - [`presence_snapshot`] - Presence 快照查询（目标类型全部实例当前的在线状态）
 //! - [`load_balancer`] - 负载均衡算法
 //! - [`geo`] - 地理位置和距离计算
+//! - [`traffic_stats`] - 按 ActrType 的流量统计
+//! - [`acl_update`] - 已注册服务运行时替换或修改自身入站 ACL
+//! - [`connection_report`] - 按 Realm 聚合的 ICE 连接上报（TURN 中继比例、RTT）
+//! - [`admin`] - 管理 API
+//! - [`realm_admin`] - Realm API Key 与租户自助管理 API
+//! - [`resumption`] - 断线重连会话恢复（恢复 token、订阅保留与消息缓存）
+//! - [`load_shed`] - 过载降级（按优先级拒绝低优先级请求）
+//! - [`drain`] - 节点排空（新注册重定向到其他节点，已建立的会话照常工作）
+//! - [`ws_auth`] - WebSocket 连接认证与 TLS 通道绑定
+//! - [`replay`] - Envelope 时间戳新鲜度与重放保护
+//! - [`outbound`] - 单连接有界发送队列、溢出策略与出站 envelope 序号
+//! - [`codec`] - 热路径 envelope 编解码（零拷贝解码、编码缓冲复用、耗时直方图）
+//! - [`sharded_map`] - 连接表与 ActrId 索引的分片并发映射
+//! - [`authz_hook`] - 外部授权钩子（注册、发现、中继接入外部策略引擎）
+//! - [`registry_encryption`] - 服务注册表 ACL 与 ServiceSpec 存储加密
+//! - [`tunnel`] - 借用 `ErrorResponse` 传输的扩展消息：保留 code、前缀与编解码
 
+pub mod acl_update;
 pub mod actr_type_utils;
+pub mod admin;
 pub mod ais_client;
+pub mod authz_hook;
+pub mod codec;
 pub mod compatibility_cache;
+pub mod compression;
+pub mod connection_report;
+pub mod drain;
+pub mod duplicate_identity;
 pub mod geo;
+pub mod lan_discovery;
 pub mod load_balancer;
+pub mod load_shed;
+pub mod outbound;
 pub mod presence;
+pub mod presence_snapshot;
 pub mod ratelimit;
+pub mod realm_admin;
+pub mod registry_encryption;
+pub mod replay;
+pub mod resumption;
+pub mod sdp_filter;
 pub mod server;
+pub mod server_notice;
 pub mod service_registry;
 pub mod service_registry_storage;
+pub mod sharded_map;
+pub mod spec_notice;
 #[cfg(feature = "opentelemetry")]
 pub mod trace;
+pub mod traffic_stats;
+pub mod tunnel;
+pub mod ws_auth;
 
 // Axum router integration
 pub mod axum_router;
@@ -36,7 +76,7 @@ pub use axum_router::{create_signaling_router, create_signaling_router_with_conf
 pub use compatibility_cache::GlobalCompatibilityCache;
 pub use load_balancer::LoadBalancer;
 pub use presence::PresenceManager;
-pub use server::{ClientConnection, SignalingServer, SignalingServerHandle};
+pub use server::{ClientConnection, ENVELOPE_VERSION, SignalingServer, SignalingServerHandle};
 pub use service_registry::{ServiceInfo, ServiceRegistry};
 
 // Export WebSocket handler
@@ -9,9 +9,13 @@
 //! - `NEAREST`: 按地理距离最近（基于 Haversine 公式）
 //! - `CLIENT_AFFINITY`: 按客户端亲和性（会话保持）
 //!
+//! # 元数据亲和
+//! 指定偏好元数据（请求方在配置的亲和键上的取值，如 `region`）时，
+//! 在上述排序因子之后按匹配的键数量降序稳定排序：匹配的实例整体优先，组内保持排序因子的顺序。
+//!
 //! # 使用示例
 //! ```ignore
-//! use signaling::load_balancer::LoadBalancer;
+//! use signaling::load_balancer::{LoadBalancer, RankOptions};
 //! use signaling::service_registry::ServiceInfo;
 //! use actr_protocol::route_candidates_request::node_selection_criteria::NodeRankingFactor;
 //!
@@ -26,7 +30,8 @@
 //!     minimal_dependency_requirement: None,
 //! });
 //!
-//! let ranked = LoadBalancer::rank_candidates(candidates, criteria, None);
+//! let options = RankOptions { criteria, ..Default::default() };
+//! let ranked = LoadBalancer::rank_candidates(candidates, options);
 //! // 返回排序后的候选 ActrId 列表
 //! ```
 
@@ -36,21 +41,36 @@ use actr_protocol::{
     ActrId, ServiceAvailabilityState, ServiceDependencyState,
     route_candidates_request::{NodeSelectionCriteria, node_selection_criteria::NodeRankingFactor},
 };
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
 /// 负载均衡器
 pub struct LoadBalancer;
 
+/// [`LoadBalancer::rank_candidates`] 的排序参数（均为可选，未提供时对应步骤跳过）
+#[derive(Clone, Copy, Default)]
+pub struct RankOptions<'a> {
+    /// 节点选择标准（包含排序因子、最小健康要求等）；未指定时返回所有候选
+    pub criteria: Option<&'a NodeSelectionCriteria>,
+    /// 客户端 ID（用于 CLIENT_AFFINITY）
+    pub client_id: Option<&'a str>,
+    /// 客户端地理坐标 (latitude, longitude)（用于 NEAREST）
+    pub client_location: Option<(f64, f64)>,
+    /// 兼容性缓存（用于 BEST_COMPATIBILITY）
+    pub compatibility_cache: Option<&'a GlobalCompatibilityCache>,
+    /// 客户端服务指纹（用于 BEST_COMPATIBILITY）
+    pub client_fingerprint: Option<&'a str>,
+    /// 偏好元数据（用于元数据亲和）
+    pub preferred_metadata: Option<&'a HashMap<String, String>>,
+}
+
 impl LoadBalancer {
     /// 根据选择标准对候选服务进行排序
     ///
     /// # 参数
     /// - `candidates`: 候选服务列表
-    /// - `criteria`: 节点选择标准（包含排序因子、最小健康要求等）
-    /// - `client_id`: 可选的客户端 ID（用于 CLIENT_AFFINITY）
-    /// - `client_location`: 可选的客户端地理坐标 (latitude, longitude)（用于 NEAREST）
-    /// - `compatibility_cache`: 可选的兼容性缓存（用于 BEST_COMPATIBILITY）
-    /// - `client_fingerprint`: 可选的客户端服务指纹（用于 BEST_COMPATIBILITY）
+    /// - `options`: 选择标准与各排序因子所需的输入（见 [`RankOptions`]）
     ///
     /// # 返回
     /// 排序后的 ActrId 列表（最多返回 candidate_count 个）
@@ -59,15 +79,20 @@ impl LoadBalancer {
     /// 1. 应用健康和依赖过滤
     /// 2. 计算兼容性分数（如果提供了 compatibility_cache 和 client_fingerprint）
     /// 3. 按排序因子依次排序
-    /// 4. 返回前 N 个候选
+    /// 4. 按元数据亲和排序
+    /// 5. 返回前 N 个候选
     pub fn rank_candidates(
         mut candidates: Vec<ServiceInfo>,
-        criteria: Option<&NodeSelectionCriteria>,
-        client_id: Option<&str>,
-        client_location: Option<(f64, f64)>,
-        compatibility_cache: Option<&GlobalCompatibilityCache>,
-        client_fingerprint: Option<&str>,
+        options: RankOptions<'_>,
     ) -> Vec<ActrId> {
+        let RankOptions {
+            criteria,
+            client_id,
+            client_location,
+            compatibility_cache,
+            client_fingerprint,
+            preferred_metadata,
+        } = options;
         if candidates.is_empty() {
             return Vec::new();
         }
@@ -77,6 +102,9 @@ impl LoadBalancer {
             Some(c) => c,
             None => {
                 info!("未指定选择标准，返回所有候选");
+                if let Some(preferred) = preferred_metadata {
+                    Self::sort_by_metadata_affinity(&mut candidates, preferred);
+                }
                 return candidates.into_iter().map(|s| s.actor_id).collect();
             }
         };
@@ -133,7 +161,12 @@ impl LoadBalancer {
             }
         }
 
-        // 5. 返回前 N 个候选
+        // 5. 按元数据亲和排序
+        if let Some(preferred) = preferred_metadata {
+            Self::sort_by_metadata_affinity(&mut candidates, preferred);
+        }
+
+        // 6. 返回前 N 个候选
         let limit = criteria.candidate_count as usize;
         candidates
             .into_iter()
@@ -379,6 +412,29 @@ impl LoadBalancer {
         });
     }
 
+    /// 按元数据亲和排序（稳定排序：与偏好元数据匹配的键越多越靠前）
+    ///
+    /// 偏好元数据为空时不改变顺序
+    pub fn sort_by_metadata_affinity(
+        candidates: &mut [ServiceInfo],
+        preferred: &HashMap<String, String>,
+    ) {
+        if preferred.is_empty() {
+            return;
+        }
+        debug!("按元数据亲和排序: preferred={:?}", preferred);
+
+        candidates.sort_by_key(|s| {
+            let matched = s.metadata().map_or(0, |metadata| {
+                preferred
+                    .iter()
+                    .filter(|(key, value)| metadata.get(*key) == Some(*value))
+                    .count()
+            });
+            Reverse(matched)
+        });
+    }
+
     /// 计算候选服务的兼容性分数
     ///
     /// 使用 CompatibilityCache 查询客户端指纹到候选服务指纹的兼容性
@@ -506,7 +562,7 @@ mod tests {
             create_test_service(2, "service-2"),
         ];
 
-        let ranked = LoadBalancer::rank_candidates(candidates, None, None, None, None, None);
+        let ranked = LoadBalancer::rank_candidates(candidates, RankOptions::default());
         assert_eq!(ranked.len(), 2);
     }
 
@@ -525,15 +581,20 @@ mod tests {
             minimal_health_requirement: None,
         };
 
-        let ranked =
-            LoadBalancer::rank_candidates(candidates, Some(&criteria), None, None, None, None);
+        let ranked = LoadBalancer::rank_candidates(
+            candidates,
+            RankOptions {
+                criteria: Some(&criteria),
+                ..Default::default()
+            },
+        );
         assert_eq!(ranked.len(), 2);
     }
 
     #[test]
     fn test_empty_candidates() {
         let candidates = vec![];
-        let ranked = LoadBalancer::rank_candidates(candidates, None, None, None, None, None);
+        let ranked = LoadBalancer::rank_candidates(candidates, RankOptions::default());
         assert_eq!(ranked.len(), 0);
     }
 
@@ -714,8 +775,13 @@ mod tests {
             minimal_dependency_requirement: None,
         };
 
-        let ranked =
-            LoadBalancer::rank_candidates(candidates, Some(&criteria), None, None, None, None);
+        let ranked = LoadBalancer::rank_candidates(
+            candidates,
+            RankOptions {
+                criteria: Some(&criteria),
+                ..Default::default()
+            },
+        );
 
         // 注意：依次调用排序，最后一个因子起主要作用（稳定排序特性）
         // 实际执行顺序：先按 power 排序，再按 backlog 排序
@@ -725,6 +791,57 @@ mod tests {
         assert_eq!(ranked[2].serial_number, 1); // backlog=0.3 最大
     }
 
+    #[test]
+    fn test_metadata_affinity_ranking() {
+        use crate::service_registry::ServiceCapabilities;
+
+        let with_tags = |serial: u64, power: f32, tags: &[&str]| {
+            let mut s = create_test_service(serial, "s");
+            s.power_reserve = Some(power);
+            s.capabilities = ServiceCapabilities::from_spec_tags(
+                &tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>(),
+            );
+            s
+        };
+        let candidates = vec![
+            with_tags(1, 0.9, &["region=us-west"]),
+            with_tags(2, 0.3, &["region=cn-beijing"]),
+            with_tags(3, 0.7, &["region=cn-beijing"]),
+            with_tags(4, 0.8, &[]),
+        ];
+        let criteria = NodeSelectionCriteria {
+            candidate_count: 3,
+            ranking_factors: vec![NodeRankingFactor::MaximumPowerReserve as i32],
+            minimal_health_requirement: None,
+            minimal_dependency_requirement: None,
+        };
+        let preferred = HashMap::from([("region".to_string(), "cn-beijing".to_string())]);
+
+        // 同区域实例整体优先，组内仍按 power_reserve 排序
+        let ranked = LoadBalancer::rank_candidates(
+            candidates.clone(),
+            RankOptions {
+                criteria: Some(&criteria),
+                preferred_metadata: Some(&preferred),
+                ..Default::default()
+            },
+        );
+        let serials: Vec<u64> = ranked.iter().map(|id| id.serial_number).collect();
+        assert_eq!(serials, vec![3, 2, 1]);
+
+        // 无偏好元数据时仅按排序因子
+        let ranked = LoadBalancer::rank_candidates(
+            candidates,
+            RankOptions {
+                criteria: Some(&criteria),
+                preferred_metadata: Some(&HashMap::new()),
+                ..Default::default()
+            },
+        );
+        let serials: Vec<u64> = ranked.iter().map(|id| id.serial_number).collect();
+        assert_eq!(serials, vec![1, 4, 3]);
+    }
+
     // ========================================================================
     // 边界情况测试
     // ========================================================================
@@ -747,8 +864,13 @@ mod tests {
             minimal_dependency_requirement: None,
         };
 
-        let ranked =
-            LoadBalancer::rank_candidates(candidates, Some(&criteria), None, None, None, None);
+        let ranked = LoadBalancer::rank_candidates(
+            candidates,
+            RankOptions {
+                criteria: Some(&criteria),
+                ..Default::default()
+            },
+        );
         assert_eq!(ranked.len(), 3); // 全部保留，顺序不变
     }
 
@@ -785,8 +907,13 @@ mod tests {
             minimal_dependency_requirement: None,
         };
 
-        let ranked =
-            LoadBalancer::rank_candidates(candidates, Some(&criteria), None, None, None, None);
+        let ranked = LoadBalancer::rank_candidates(
+            candidates,
+            RankOptions {
+                criteria: Some(&criteria),
+                ..Default::default()
+            },
+        );
         assert_eq!(ranked.len(), 0); // 全部被过滤
     }
 
@@ -831,11 +958,11 @@ mod tests {
 
         let ranked = LoadBalancer::rank_candidates(
             candidates,
-            Some(&criteria),
-            None,
-            client_location,
-            None,
-            None,
+            RankOptions {
+                criteria: Some(&criteria),
+                client_location,
+                ..Default::default()
+            },
         );
 
         // 排序结果应该是：北京(0km) < 上海(~1067km) < 深圳(~1943km)，无坐标的在最后
@@ -1057,11 +1184,12 @@ mod tests {
 
         let ranked = LoadBalancer::rank_candidates(
             candidates,
-            Some(&criteria),
-            None,
-            None,
-            Some(&cache),
-            Some("client-v2"),
+            RankOptions {
+                criteria: Some(&criteria),
+                compatibility_cache: Some(&cache),
+                client_fingerprint: Some("client-v2"),
+                ..Default::default()
+            },
         );
 
         // 应该按兼容性排序：v2(1.0) > v1(0.5)
@@ -1142,11 +1270,12 @@ mod tests {
 
         let ranked = LoadBalancer::rank_candidates(
             candidates,
-            Some(&criteria),
-            None,
-            None,
-            Some(&cache),
-            Some("client-1.0"),
+            RankOptions {
+                criteria: Some(&criteria),
+                compatibility_cache: Some(&cache),
+                client_fingerprint: Some("client-1.0"),
+                ..Default::default()
+            },
         );
 
         // 最后一个排序因子起主导作用（稳定排序）
@@ -1322,11 +1451,12 @@ mod tests {
 
         let ranked = LoadBalancer::rank_candidates(
             candidates,
-            Some(&criteria),
-            None,
-            None,
-            Some(&cache),
-            Some("client-v3"),
+            RankOptions {
+                criteria: Some(&criteria),
+                compatibility_cache: Some(&cache),
+                client_fingerprint: Some("client-v3"),
+                ..Default::default()
+            },
         );
 
         // 两者兼容性分数都是 1.0，最后按 power_reserve 排序
@@ -0,0 +1,238 @@
+//! 过载降级 (Load Shedding)
+//!
+//! 周期采样 CPU 使用率与全部连接出站队列中的待发送消息总数，任一越过阈值即进入降级状态。
+//! 降级期间按优先级取舍：
+//! - 低优先级（拒绝，返回 503）：服务发现、Presence 快照、ServiceSpec 查询、管理端与租户统计查询
+//! - 高优先级（照常处理）：注册、凭证校验与刷新、中继（呼叫建立）、心跳、订阅
+//!
+//! 两项指标均回落到阈值 × `recovery_ratio` 以下后退出降级，避免在阈值附近反复切换。
+//! 当前状态见 `actrix_signaling_load_shedding`，被拒绝的请求按类型计入
+//! `actrix_signaling_shed_requests_total`。
+
+use crate::axum_router::SignalingState;
+use crate::tunnel;
+use actr_protocol::actr_to_signaling;
+use actrix_common::config::signaling::LoadSheddingConfig;
+use actrix_common::metrics::{SIGNALING_LOAD_SHEDDING, SIGNALING_SHED_REQUESTS};
+use axum::{
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 降级期间拒绝信令请求使用的 ErrorResponse code
+pub const SHED_ERROR_CODE: u32 = tunnel::SERVICE_UNAVAILABLE_CODE;
+
+/// 降级期间拒绝请求的提示信息
+pub const SHED_ERROR_MESSAGE: &str = "Signaling server is overloaded, please retry later";
+
+/// 可被降级拒绝的低优先级请求类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShedKind {
+    /// 服务发现（DiscoveryRequest、GetPresenceSnapshotRequest）
+    Discovery,
+    /// ServiceSpec 查询（GetServiceSpecRequest）
+    ServiceSpec,
+    /// 管理端与租户自助端点的统计查询
+    Stats,
+}
+
+impl ShedKind {
+    /// 指标标签
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShedKind::Discovery => "discovery",
+            ShedKind::ServiceSpec => "service_spec",
+            ShedKind::Stats => "stats",
+        }
+    }
+
+    /// ActrToSignaling 载荷对应的低优先级类型；高优先级载荷返回 None
+    pub fn of_payload(payload: Option<&actr_to_signaling::Payload>) -> Option<Self> {
+        match payload? {
+            actr_to_signaling::Payload::DiscoveryRequest(_) => Some(ShedKind::Discovery),
+            actr_to_signaling::Payload::Error(error)
+                if error.code == crate::presence_snapshot::PRESENCE_SNAPSHOT_CODE =>
+            {
+                Some(ShedKind::Discovery)
+            }
+            actr_to_signaling::Payload::GetServiceSpecRequest(_) => Some(ShedKind::ServiceSpec),
+            _ => None,
+        }
+    }
+}
+
+/// 过载降级状态机
+#[derive(Debug)]
+pub struct LoadShedder {
+    config: LoadSheddingConfig,
+    shedding: AtomicBool,
+}
+
+impl LoadShedder {
+    pub fn new(config: &LoadSheddingConfig) -> Self {
+        SIGNALING_LOAD_SHEDDING.set(0);
+        Self {
+            config: config.clone(),
+            shedding: AtomicBool::new(false),
+        }
+    }
+
+    /// 采样间隔
+    pub fn sample_interval(&self) -> Duration {
+        Duration::from_millis(self.config.sample_interval_ms)
+    }
+
+    /// 当前是否处于降级状态
+    pub fn is_shedding(&self) -> bool {
+        self.shedding.load(Ordering::Relaxed)
+    }
+
+    /// 根据一次采样更新降级状态，返回更新后的状态
+    ///
+    /// `cpu_percent` 为 None（采样失败）时仅按队列深度判断
+    pub fn evaluate(&self, cpu_percent: Option<f32>, queue_depth: usize) -> bool {
+        let was_shedding = self.is_shedding();
+        // 降级中使用回落阈值，未降级时使用进入阈值
+        let ratio = if was_shedding {
+            self.config.recovery_ratio
+        } else {
+            1.0
+        };
+        let cpu_limit = self.config.cpu_threshold_percent * ratio;
+        let queue_limit = self.config.queue_depth_threshold as f32 * ratio;
+
+        let overloaded =
+            cpu_percent.is_some_and(|cpu| cpu >= cpu_limit) || queue_depth as f32 >= queue_limit;
+
+        if overloaded != was_shedding {
+            self.shedding.store(overloaded, Ordering::Relaxed);
+            SIGNALING_LOAD_SHEDDING.set(overloaded as i64);
+            if overloaded {
+                warn!(
+                    "🔥 信令服务过载，进入降级: cpu={:?}% queue_depth={}",
+                    cpu_percent, queue_depth
+                );
+            } else {
+                info!(
+                    "✅ 信令服务负载回落，退出降级: cpu={:?}% queue_depth={}",
+                    cpu_percent, queue_depth
+                );
+            }
+        }
+
+        overloaded
+    }
+
+    /// 降级期间拒绝低优先级请求：返回 true 表示应拒绝（同时计入指标）
+    pub fn should_shed(&self, kind: ShedKind) -> bool {
+        if !self.is_shedding() {
+            return false;
+        }
+        SIGNALING_SHED_REQUESTS
+            .with_label_values(&[kind.as_str()])
+            .inc();
+        true
+    }
+}
+
+/// 采样 CPU 使用率（百分比），失败时返回 None
+pub async fn sample_cpu_percent() -> Option<f32> {
+    match pwrzv::get_power_reserve_level_with_details_direct().await {
+        Ok((_, details)) => details.get("cpu_usage").copied(),
+        Err(e) => {
+            warn!("读取 CPU 使用率失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 统计查询端点的降级守卫
+///
+/// 放在认证提取器之后：降级期间以 503 拒绝请求并计入 [`ShedKind::Stats`]
+pub struct StatsQuery;
+
+impl FromRequestParts<SignalingState> for StatsQuery {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        _parts: &mut Parts,
+        state: &SignalingState,
+    ) -> Result<Self, Self::Rejection> {
+        match state.server.load_shedder.as_ref() {
+            Some(shedder) if shedder.should_shed(ShedKind::Stats) => {
+                Err((StatusCode::SERVICE_UNAVAILABLE, SHED_ERROR_MESSAGE))
+            }
+            _ => Ok(StatsQuery),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shedder() -> LoadShedder {
+        LoadShedder::new(&LoadSheddingConfig {
+            enabled: true,
+            cpu_threshold_percent: 80.0,
+            queue_depth_threshold: 100,
+            recovery_ratio: 0.5,
+            sample_interval_ms: 1000,
+        })
+    }
+
+    #[test]
+    fn test_enter_and_recover_with_hysteresis() {
+        let shedder = shedder();
+        assert!(!shedder.evaluate(Some(70.0), 10));
+        assert!(!shedder.should_shed(ShedKind::Discovery));
+
+        // CPU 越过阈值进入降级
+        assert!(shedder.evaluate(Some(85.0), 10));
+        assert!(shedder.should_shed(ShedKind::Discovery));
+
+        // 低于进入阈值但未低于回落阈值，保持降级
+        assert!(shedder.evaluate(Some(60.0), 10));
+
+        // 两项均低于回落阈值后退出
+        assert!(!shedder.evaluate(Some(30.0), 10));
+        assert!(!shedder.should_shed(ShedKind::Stats));
+    }
+
+    #[test]
+    fn test_queue_depth_triggers_without_cpu_sample() {
+        let shedder = shedder();
+        assert!(shedder.evaluate(None, 100));
+        assert!(shedder.evaluate(None, 60));
+        assert!(!shedder.evaluate(None, 40));
+    }
+
+    #[test]
+    fn test_only_low_priority_payloads_are_sheddable() {
+        use actr_protocol::{DiscoveryRequest, Ping};
+
+        assert_eq!(
+            ShedKind::of_payload(Some(&actr_to_signaling::Payload::DiscoveryRequest(
+                DiscoveryRequest::default()
+            ))),
+            Some(ShedKind::Discovery)
+        );
+        assert_eq!(
+            ShedKind::of_payload(Some(&actr_to_signaling::Payload::Ping(Ping::default()))),
+            None
+        );
+        assert_eq!(
+            ShedKind::of_payload(Some(&actr_to_signaling::Payload::Error(
+                crate::presence_snapshot::GetPresenceSnapshotRequest {
+                    target_type: actr_protocol::ActrType::default(),
+                }
+                .to_error_response()
+            ))),
+            Some(ShedKind::Discovery)
+        );
+        assert_eq!(ShedKind::of_payload(None), None);
+    }
+}
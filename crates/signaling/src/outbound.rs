@@ -0,0 +1,355 @@
+//! 单连接有界发送队列
+//!
+//! 每个 WebSocket 连接的出站消息经由有界队列交给发送任务，队列已满时按
+//! [`OutboundOverflowPolicy`] 处理：
+//! - `wait`：等待空位（背压），超时后断开连接
+//! - `drop_newest`：丢弃新消息，保留连接
+//! - `disconnect`：立即断开连接
+//!
+//! 断开通过通知发送任务退出实现，连接随后按正常流程清理。
+//!
+//! 启用 `sequence_numbers` 后，[`OutboundSender::send_envelope`] 在编码前将单连接递增序号写入
+//! `tracestate` 的 [`SEQUENCE_TRACESTATE_KEY`] 成员（从 1 开始），队列中只保存编码后的消息。
+//! 被丢弃的消息同样占用序号，客户端发现序号跳跃即说明有消息丢失，应重新发起服务发现或
+//! Presence 订阅。
+
+use crate::codec::encode_envelope;
+use actr_protocol::SignalingEnvelope;
+use actrix_common::config::signaling::{ConnectionLimitsConfig, OutboundOverflowPolicy};
+use axum::extract::ws::{CloseFrame, Message as WsMessage};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::{Mutex, Notify, mpsc};
+use tracing::warn;
+
+/// 出站 envelope 序号在 `tracestate` 中的成员名
+pub const SEQUENCE_TRACESTATE_KEY: &str = "actrix-seq";
+
+/// 出站消息发送失败原因
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OutboundError {
+    #[error("connection closed")]
+    Closed,
+
+    #[error("outbound queue full, message dropped")]
+    Dropped,
+
+    #[error("outbound queue full, connection closed")]
+    Overflow,
+}
+
+/// 连接发送端（可克隆，由 ClientConnection 持有）
+#[derive(Debug, Clone)]
+pub struct OutboundSender {
+    tx: mpsc::Sender<WsMessage>,
+    policy: OutboundOverflowPolicy,
+    send_timeout: Duration,
+    overflow: Arc<Notify>,
+    dropped: Arc<AtomicU64>,
+    /// 已分配的最后一个序号（None 表示未启用）；分配后持锁直到入队，保证队列顺序与序号一致
+    sequence: Option<Arc<Mutex<u64>>>,
+}
+
+/// 连接发送任务持有的接收端
+#[derive(Debug)]
+pub struct OutboundReceiver {
+    rx: mpsc::Receiver<WsMessage>,
+    overflow: Arc<Notify>,
+}
+
+/// 按配置创建单连接发送队列
+pub fn outbound_channel(config: &ConnectionLimitsConfig) -> (OutboundSender, OutboundReceiver) {
+    let (tx, rx) = mpsc::channel(config.max_outbound_queue.max(1));
+    let overflow = Arc::new(Notify::new());
+    (
+        OutboundSender {
+            tx,
+            policy: config.outbound_overflow_policy,
+            send_timeout: Duration::from_millis(config.outbound_send_timeout_ms),
+            overflow: overflow.clone(),
+            dropped: Arc::new(AtomicU64::new(0)),
+            sequence: config.sequence_numbers.then(|| Arc::new(Mutex::new(0))),
+        },
+        OutboundReceiver { rx, overflow },
+    )
+}
+
+impl OutboundSender {
+    /// 按溢出策略发送消息
+    pub async fn send(&self, message: WsMessage) -> Result<(), OutboundError> {
+        let message = match self.tx.try_send(message) {
+            Ok(()) => return Ok(()),
+            Err(mpsc::error::TrySendError::Closed(_)) => return Err(OutboundError::Closed),
+            Err(mpsc::error::TrySendError::Full(message)) => message,
+        };
+
+        match self.policy {
+            OutboundOverflowPolicy::Wait => {
+                match tokio::time::timeout(self.send_timeout, self.tx.send(message)).await {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(_)) => Err(OutboundError::Closed),
+                    Err(_) => {
+                        warn!(
+                            "🚫 发送队列 {}ms 内未腾出空位，断开慢速连接",
+                            self.send_timeout.as_millis()
+                        );
+                        self.overflow.notify_one();
+                        Err(OutboundError::Overflow)
+                    }
+                }
+            }
+            OutboundOverflowPolicy::DropNewest => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!("🚫 发送队列已满，丢弃消息 (累计丢弃 {})", dropped);
+                Err(OutboundError::Dropped)
+            }
+            OutboundOverflowPolicy::Disconnect => {
+                warn!("🚫 发送队列已满，断开连接");
+                self.overflow.notify_one();
+                Err(OutboundError::Overflow)
+            }
+        }
+    }
+
+    /// 写入下一个序号（启用时）后编码一次并按溢出策略发送
+    ///
+    /// 被丢弃的消息同样占用序号，让客户端看到缺口
+    pub async fn send_envelope(
+        &self,
+        mut envelope: SignalingEnvelope,
+    ) -> Result<(), OutboundError> {
+        let _order = match self.sequence {
+            Some(ref sequence) => {
+                let mut last = sequence.lock().await;
+                *last += 1;
+                set_envelope_sequence(&mut envelope, *last);
+                Some(last)
+            }
+            None => None,
+        };
+        self.send(WsMessage::Binary(encode_envelope(&envelope)))
+            .await
+    }
+
+    /// 为绕过队列直接写出的 envelope 写入下一个序号；未启用序号时不修改
+    ///
+    /// 仅用于连接登记前的会话恢复重放：此时没有其他发送方，写出顺序即序号顺序
+    pub async fn stamp(&self, envelope: &mut SignalingEnvelope) {
+        if let Some(ref sequence) = self.sequence {
+            let mut last = sequence.lock().await;
+            *last += 1;
+            set_envelope_sequence(envelope, *last);
+        }
+    }
+
+    /// 发送 Close 帧；队列已满时直接通知发送任务断开
+    pub fn close(&self, frame: Option<CloseFrame>) {
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.tx.try_send(WsMessage::Close(frame)) {
+            self.overflow.notify_one();
+        }
+    }
+
+    /// 因队列已满被丢弃的消息数
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 当前队列中等待发送的消息数
+    pub fn queued_len(&self) -> usize {
+        self.tx.max_capacity() - self.tx.capacity()
+    }
+}
+
+impl OutboundReceiver {
+    /// 取下一条待发送消息；所有发送端关闭或因溢出需要断开时返回 None
+    pub async fn recv(&mut self) -> Option<WsMessage> {
+        tokio::select! {
+            biased;
+            _ = self.overflow.notified() => None,
+            message = self.rx.recv() => message,
+        }
+    }
+}
+
+/// 将序号写入 envelope 的 `tracestate`（置于首位，替换已有的同名成员）
+pub fn set_envelope_sequence(envelope: &mut SignalingEnvelope, seq: u64) {
+    let mut members = vec![format!("{SEQUENCE_TRACESTATE_KEY}={seq}")];
+    if let Some(ref tracestate) = envelope.tracestate {
+        members.extend(
+            tracestate
+                .split(',')
+                .map(str::trim)
+                .filter(|member| {
+                    !member.is_empty() && member.split('=').next() != Some(SEQUENCE_TRACESTATE_KEY)
+                })
+                .map(str::to_string),
+        );
+    }
+    envelope.tracestate = Some(members.join(","));
+}
+
+/// 读取 envelope 上的出站序号
+pub fn envelope_sequence(envelope: &SignalingEnvelope) -> Option<u64> {
+    envelope
+        .tracestate
+        .as_deref()?
+        .split(',')
+        .filter_map(|member| member.trim().split_once('='))
+        .find(|(key, _)| *key == SEQUENCE_TRACESTATE_KEY)
+        .and_then(|(_, value)| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message as _;
+
+    fn config(policy: OutboundOverflowPolicy) -> ConnectionLimitsConfig {
+        ConnectionLimitsConfig {
+            max_outbound_queue: 1,
+            outbound_overflow_policy: policy,
+            outbound_send_timeout_ms: 50,
+            ..Default::default()
+        }
+    }
+
+    fn text(s: &'static str) -> WsMessage {
+        WsMessage::Text(s.into())
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_keeps_connection() {
+        let (tx, mut rx) = outbound_channel(&config(OutboundOverflowPolicy::DropNewest));
+
+        assert_eq!(tx.queued_len(), 0);
+        assert_eq!(tx.send(text("a")).await, Ok(()));
+        assert_eq!(tx.queued_len(), 1);
+        assert_eq!(tx.send(text("b")).await, Err(OutboundError::Dropped));
+        assert_eq!(tx.dropped_count(), 1);
+
+        assert!(matches!(rx.recv().await, Some(WsMessage::Text(t)) if t.as_str() == "a"));
+        assert_eq!(tx.send(text("c")).await, Ok(()));
+        assert!(matches!(rx.recv().await, Some(WsMessage::Text(t)) if t.as_str() == "c"));
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_policy_stops_receiver() {
+        let (tx, mut rx) = outbound_channel(&config(OutboundOverflowPolicy::Disconnect));
+
+        assert_eq!(tx.send(text("a")).await, Ok(()));
+        assert_eq!(tx.send(text("b")).await, Err(OutboundError::Overflow));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_policy_applies_backpressure_then_disconnects() {
+        let (tx, mut rx) = outbound_channel(&config(OutboundOverflowPolicy::Wait));
+        assert_eq!(tx.send(text("a")).await, Ok(()));
+
+        // 接收端腾出空位后，等待中的发送成功
+        let sender = tx.clone();
+        let pending = tokio::spawn(async move { sender.send(text("b")).await });
+        assert!(matches!(rx.recv().await, Some(WsMessage::Text(t)) if t.as_str() == "a"));
+        assert_eq!(pending.await.unwrap(), Ok(()));
+
+        // 接收端停滞，超时后断开
+        assert_eq!(tx.send(text("c")).await, Err(OutboundError::Overflow));
+        assert!(rx.recv().await.is_none());
+    }
+
+    fn envelope(tracestate: Option<&str>) -> SignalingEnvelope {
+        SignalingEnvelope {
+            envelope_version: 1,
+            envelope_id: "e".to_string(),
+            reply_for: None,
+            timestamp: prost_types::Timestamp::default(),
+            traceparent: None,
+            tracestate: tracestate.map(str::to_string),
+            flow: None,
+        }
+    }
+
+    fn sequence_of(message: Option<WsMessage>) -> Option<u64> {
+        let Some(WsMessage::Binary(data)) = message else {
+            panic!("expected binary message");
+        };
+        envelope_sequence(&SignalingEnvelope::decode(data.as_ref()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_sequence_numbers_expose_dropped_messages() {
+        let (tx, mut rx) = outbound_channel(&ConnectionLimitsConfig {
+            sequence_numbers: true,
+            ..config(OutboundOverflowPolicy::DropNewest)
+        });
+
+        assert_eq!(tx.send_envelope(envelope(None)).await, Ok(()));
+        assert_eq!(sequence_of(rx.recv().await), Some(1));
+
+        // 队列已满时被丢弃的消息占用序号 3，已入队的消息保留序号 2
+        assert_eq!(tx.send_envelope(envelope(Some("vendor=x"))).await, Ok(()));
+        assert_eq!(
+            tx.send_envelope(envelope(None)).await,
+            Err(OutboundError::Dropped)
+        );
+        assert_eq!(sequence_of(rx.recv().await), Some(2));
+
+        // 非 envelope 消息不占用序号
+        assert_eq!(tx.send(text("a")).await, Ok(()));
+        assert!(matches!(rx.recv().await, Some(WsMessage::Text(_))));
+        let mut replayed = envelope(None);
+        tx.stamp(&mut replayed).await;
+        assert_eq!(envelope_sequence(&replayed), Some(4));
+        assert_eq!(tx.send_envelope(envelope(None)).await, Ok(()));
+        assert_eq!(sequence_of(rx.recv().await), Some(5));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_queue_order_matches_sequence_order() {
+        let (tx, mut rx) = outbound_channel(&ConnectionLimitsConfig {
+            max_outbound_queue: 64,
+            sequence_numbers: true,
+            ..Default::default()
+        });
+
+        let senders: Vec<_> = (0..64)
+            .map(|_| {
+                let tx = tx.clone();
+                tokio::spawn(async move { tx.send_envelope(envelope(None)).await })
+            })
+            .collect();
+        for sender in senders {
+            assert_eq!(sender.await.unwrap(), Ok(()));
+        }
+        for expected in 1..=64 {
+            assert_eq!(sequence_of(rx.recv().await), Some(expected));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequence_numbers_disabled_by_default() {
+        let (tx, mut rx) = outbound_channel(&config(OutboundOverflowPolicy::Wait));
+        assert_eq!(tx.send_envelope(envelope(None)).await, Ok(()));
+        assert_eq!(sequence_of(rx.recv().await), None);
+    }
+
+    #[test]
+    fn test_set_envelope_sequence_preserves_tracestate() {
+        let mut envelope = envelope(Some("vendor=x, actrix-seq=7"));
+        set_envelope_sequence(&mut envelope, 8);
+        assert_eq!(
+            envelope.tracestate.as_deref(),
+            Some("actrix-seq=8,vendor=x")
+        );
+        assert_eq!(envelope_sequence(&envelope), Some(8));
+    }
+
+    #[tokio::test]
+    async fn test_closed_receiver() {
+        let (tx, rx) = outbound_channel(&config(OutboundOverflowPolicy::Wait));
+        drop(rx);
+        assert_eq!(tx.send(text("a")).await, Err(OutboundError::Closed));
+    }
+}
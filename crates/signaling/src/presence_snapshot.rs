@@ -0,0 +1,184 @@
+//! Presence 快照查询 (GetPresenceSnapshotRequest)
+//!
+//! 新连接的客户端查询目标 ActrType 全部实例当前的在线状态，无需等待后续的 ActrUpEvent
+//! 才能得知哪些实例可用。与服务发现相同，受 ACL 与外部授权钩子约束，且只返回同 Realm 的实例。
+//!
+//! 实例来自服务注册表：持有活跃连接的实例为 [`PresenceStatus::Online`]，
+//! 注册仍保留但连接已断开（断线重连窗口内、或从持久化存储恢复后尚未重连）的实例为
+//! [`PresenceStatus::Offline`]。
+//!
+//! # 请求方式
+//! actr-protocol 目前没有专用的 payload，客户端通过 `ActrToSignaling` 的 `Error` payload 发送：
+//! `code` 为 [`PRESENCE_SNAPSHOT_CODE`]，`message` 为 [`PRESENCE_SNAPSHOT_REQUEST_PREFIX`]
+//! 加 JSON 编码的 [`GetPresenceSnapshotRequest`]。
+//!
+//! 成功时服务器以同一 code 回复（`reply_for` 指向请求），`message` 为
+//! [`PRESENCE_SNAPSHOT_RESPONSE_PREFIX`] 加 JSON 编码的 [`GetPresenceSnapshotResponse`]；
+//! 请求无效回复 code 400，ACL 拒绝回复 code 403。
+
+use crate::tunnel;
+use actr_protocol::{ActrId, ActrType, ErrorResponse};
+use serde::{Deserialize, Serialize};
+
+/// 请求与响应使用的 ErrorResponse code
+pub const PRESENCE_SNAPSHOT_CODE: u32 = tunnel::PRESENCE_SNAPSHOT_CODE;
+
+/// 请求 message 前缀，其后为 JSON 编码的 [`GetPresenceSnapshotRequest`]
+pub const PRESENCE_SNAPSHOT_REQUEST_PREFIX: &str = tunnel::PRESENCE_SNAPSHOT_REQUEST.prefix;
+
+/// 响应 message 前缀，其后为 JSON 编码的 [`GetPresenceSnapshotResponse`]
+pub const PRESENCE_SNAPSHOT_RESPONSE_PREFIX: &str = tunnel::PRESENCE_SNAPSHOT_RESPONSE.prefix;
+
+/// Presence 快照请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPresenceSnapshotRequest {
+    /// 目标类型；指定 version 时只返回该版本的实例
+    pub target_type: ActrType,
+}
+
+impl GetPresenceSnapshotRequest {
+    /// 从客户端 ErrorResponse 中解析
+    ///
+    /// 不是快照请求时返回 None；是快照请求但内容无效时返回 `Some(Err)`
+    pub fn from_error_response(error: &ErrorResponse) -> Option<Result<Self, serde_json::Error>> {
+        tunnel::PRESENCE_SNAPSHOT_REQUEST.decode(error)
+    }
+
+    /// 编码为客户端发送的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::PRESENCE_SNAPSHOT_REQUEST.encode(self)
+    }
+}
+
+/// 实例在线状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    /// 持有活跃连接
+    Online,
+    /// 注册仍保留但连接已断开
+    Offline,
+}
+
+/// 单个实例的在线状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstancePresence {
+    pub actr_id: ActrId,
+    pub status: PresenceStatus,
+    /// 最近一次心跳时间 (Unix 秒)
+    pub last_heartbeat_secs: u64,
+}
+
+/// Presence 快照结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetPresenceSnapshotResponse {
+    pub target_type: ActrType,
+    /// 按序列号排序
+    pub instances: Vec<InstancePresence>,
+    /// 快照时间 (Unix 秒)
+    pub snapshot_at: i64,
+}
+
+impl GetPresenceSnapshotResponse {
+    pub fn new(target_type: ActrType, mut instances: Vec<InstancePresence>) -> Self {
+        instances.sort_by_key(|instance| instance.actr_id.serial_number);
+        Self {
+            target_type,
+            instances,
+            snapshot_at: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// 在线实例数量
+    pub fn online_count(&self) -> usize {
+        self.instances
+            .iter()
+            .filter(|instance| instance.status == PresenceStatus::Online)
+            .count()
+    }
+
+    /// 从服务器回复中解析
+    pub fn from_error_response(error: &ErrorResponse) -> Option<Result<Self, serde_json::Error>> {
+        tunnel::PRESENCE_SNAPSHOT_RESPONSE.decode(error)
+    }
+
+    /// 编码为服务器回复的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::PRESENCE_SNAPSHOT_RESPONSE.encode(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::Realm;
+
+    fn echo_type() -> ActrType {
+        ActrType {
+            manufacturer: "acme".to_string(),
+            name: "echo".to_string(),
+            version: None,
+        }
+    }
+
+    fn instance(serial_number: u64, status: PresenceStatus) -> InstancePresence {
+        InstancePresence {
+            actr_id: ActrId {
+                serial_number,
+                realm: Realm { realm_id: 1001 },
+                r#type: echo_type(),
+            },
+            status,
+            last_heartbeat_secs: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_request_roundtrip() {
+        let request = GetPresenceSnapshotRequest {
+            target_type: echo_type(),
+        };
+        let parsed = GetPresenceSnapshotRequest::from_error_response(&request.to_error_response())
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed, request);
+
+        let invalid = ErrorResponse {
+            code: PRESENCE_SNAPSHOT_CODE,
+            message: format!("{PRESENCE_SNAPSHOT_REQUEST_PREFIX}not json"),
+        };
+        assert!(
+            GetPresenceSnapshotRequest::from_error_response(&invalid)
+                .unwrap()
+                .is_err()
+        );
+
+        let other = ErrorResponse {
+            code: 500,
+            message: format!("{PRESENCE_SNAPSHOT_REQUEST_PREFIX}{{}}"),
+        };
+        assert!(GetPresenceSnapshotRequest::from_error_response(&other).is_none());
+    }
+
+    #[test]
+    fn test_response_roundtrip() {
+        let response = GetPresenceSnapshotResponse::new(
+            echo_type(),
+            vec![
+                instance(9, PresenceStatus::Offline),
+                instance(3, PresenceStatus::Online),
+            ],
+        );
+        assert_eq!(response.instances[0].actr_id.serial_number, 3);
+        assert_eq!(response.online_count(), 1);
+
+        let error = response.to_error_response();
+        // 服务器回复不会被当作请求
+        assert!(GetPresenceSnapshotRequest::from_error_response(&error).is_none());
+        let parsed = GetPresenceSnapshotResponse::from_error_response(&error)
+            .unwrap()
+            .unwrap();
+        assert_eq!(parsed, response);
+        assert!(error.message.contains("\"offline\""));
+    }
+}
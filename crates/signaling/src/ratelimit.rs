@@ -1,27 +1,195 @@
 //! Signaling 服务速率限制
 //!
-//! 实现双重速率限制：
+//! 实现三层速率限制：
 //! 1. **连接速率限制**：限制每个 IP 建立新 WebSocket 连接的速率
 //! 2. **消息速率限制**：限制每个连接发送消息的速率
+//! 3. **Realm 速率限制**：限制同一 Realm 内所有连接的注册、中继、服务发现总速率
 //!
-//! 使用 governor crate 实现，支持配置化
-
-use actrix_common::config::signaling::{ConnectionRateLimit, MessageRateLimit};
+//! 使用 governor crate 实现，支持配置化。
+//!
+//! 被拒绝的请求计入 `actrix_rate_limit_exceeded_total{service="signaling"}`（按限流器），
+//! Realm 级拒绝另按 Realm 与操作计入 `actrix_signaling_realm_rate_limited_total`；
+//! 令牌桶水位由 [`RateLimitStatus`] 定期采集为指标，并通过 `GET /admin/rate-limits` 查询。
+
+use actrix_common::config::signaling::{ConnectionRateLimit, MessageRateLimit, RealmRateLimit};
+use actrix_common::metrics::{
+    RATE_LIMIT_EXCEEDED, SIGNALING_RATE_LIMIT_BUCKETS, SIGNALING_RATE_LIMIT_EXHAUSTED_BUCKETS,
+    SIGNALING_REALM_RATE_LIMIT_TOKENS, SIGNALING_REALM_RATE_LIMITED,
+};
+use actrix_common::realm::RealmRateLimits;
+use governor::middleware::StateInformationMiddleware;
 use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::net::IpAddr;
 use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex, MutexGuard, RwLock as StdRwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
+/// 令牌桶水位指标的采集间隔（秒）
+pub const METRICS_INTERVAL_SECS: u64 = 15;
+
+/// 令牌桶：governor 限流器加上最近一次检查后的剩余令牌，用于估算当前水位
+#[derive(Debug)]
+struct TokenBucket {
+    limiter: DefaultDirectRateLimiter<StateInformationMiddleware>,
+    quota: Quota,
+    /// 最近一次检查后的剩余令牌
+    remaining: u32,
+    /// 最近一次检查的时间
+    checked_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(quota: Quota) -> Self {
+        Self {
+            limiter: RateLimiter::direct(quota).with_middleware(),
+            quota,
+            remaining: quota.burst_size().get(),
+            checked_at: Instant::now(),
+        }
+    }
+
+    /// 消耗一个令牌，令牌不足时返回 false
+    fn check(&mut self) -> bool {
+        let allowed = match self.limiter.check() {
+            Ok(snapshot) => {
+                self.remaining = snapshot.remaining_burst_capacity();
+                true
+            }
+            Err(_) => {
+                self.remaining = 0;
+                false
+            }
+        };
+        self.checked_at = Instant::now();
+        allowed
+    }
+
+    /// 突发容量
+    fn capacity(&self) -> u32 {
+        self.quota.burst_size().get()
+    }
+
+    /// 当前剩余令牌：最近一次检查后的剩余令牌加上此后补充的令牌，不超过突发容量
+    fn tokens(&self) -> u32 {
+        let interval = self.quota.replenish_interval().as_nanos().max(1);
+        let replenished = self.checked_at.elapsed().as_nanos() / interval;
+        (u128::from(self.remaining) + replenished).min(u128::from(self.capacity())) as u32
+    }
+}
+
+/// 一个限流器全部令牌桶的水位汇总
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BucketLevels {
+    /// 跟踪的令牌桶数量
+    pub buckets: usize,
+    /// 令牌已耗尽的令牌桶数量
+    pub exhausted: usize,
+    /// 所有令牌桶中最少的剩余令牌数（未跟踪任何令牌桶时为 None）
+    pub min_tokens: Option<u32>,
+}
+
+impl BucketLevels {
+    fn collect<'a>(buckets: impl IntoIterator<Item = &'a TokenBucket>) -> Self {
+        let mut levels = Self::default();
+        for bucket in buckets {
+            let tokens = bucket.tokens();
+            levels.buckets += 1;
+            if tokens == 0 {
+                levels.exhausted += 1;
+            }
+            levels.min_tokens = Some(levels.min_tokens.map_or(tokens, |min| min.min(tokens)));
+        }
+        levels
+    }
+}
+
+/// 单个 Realm 某项操作的令牌桶水位
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RealmBucketLevel {
+    pub realm_id: u32,
+    pub operation: RealmOperation,
+    /// 剩余令牌
+    pub tokens: u32,
+    /// 突发容量
+    pub capacity: u32,
+}
+
+/// 全部限流器的令牌桶水位（启动时未创建的限流器为 None）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RateLimitStatus {
+    pub connection: Option<BucketLevels>,
+    pub message: Option<BucketLevels>,
+    pub realm: Option<BucketLevels>,
+    /// 各 Realm 的令牌桶水位，按 Realm 与操作排序
+    pub realm_buckets: Vec<RealmBucketLevel>,
+}
+
+impl RateLimitStatus {
+    /// 采集各限流器当前的令牌桶水位
+    pub async fn collect(
+        connection: Option<&ConnectionRateLimiter>,
+        message: Option<&MessageRateLimiter>,
+        realm: Option<&RealmRateLimiter>,
+    ) -> Self {
+        let mut status = Self::default();
+        if let Some(limiter) = connection {
+            status.connection = Some(limiter.levels().await);
+        }
+        if let Some(limiter) = message {
+            status.message = Some(limiter.levels().await);
+        }
+        if let Some(limiter) = realm {
+            let (levels, realm_buckets) = limiter.levels().await;
+            status.realm = Some(levels);
+            status.realm_buckets = realm_buckets;
+        }
+        status
+    }
+
+    /// 写入令牌桶水位指标
+    pub fn record_metrics(&self) {
+        for (limiter, levels) in [
+            ("connection", &self.connection),
+            ("message", &self.message),
+            ("realm", &self.realm),
+        ] {
+            let levels = levels.clone().unwrap_or_default();
+            SIGNALING_RATE_LIMIT_BUCKETS
+                .with_label_values(&[limiter])
+                .set(levels.buckets as i64);
+            SIGNALING_RATE_LIMIT_EXHAUSTED_BUCKETS
+                .with_label_values(&[limiter])
+                .set(levels.exhausted as i64);
+        }
+
+        // 已清除的 Realm 令牌桶不再上报
+        SIGNALING_REALM_RATE_LIMIT_TOKENS.reset();
+        for bucket in &self.realm_buckets {
+            SIGNALING_REALM_RATE_LIMIT_TOKENS
+                .with_label_values(&[&bucket.realm_id.to_string(), bucket.operation.as_str()])
+                .set(i64::from(bucket.tokens));
+        }
+    }
+}
+
+/// 记录一次被限流器拒绝的请求
+fn record_rejection(limiter: &str) {
+    RATE_LIMIT_EXCEEDED
+        .with_label_values(&["signaling", limiter])
+        .inc();
+}
+
 /// 连接速率限制器（基于 IP）
 #[derive(Debug)]
 pub struct ConnectionRateLimiter {
-    /// 配置
-    config: ConnectionRateLimit,
+    /// 配置（可热加载）
+    config: StdRwLock<ConnectionRateLimit>,
     /// 每个 IP 的速率限制器
-    limiters: Arc<RwLock<HashMap<IpAddr, DefaultDirectRateLimiter>>>,
+    limiters: Arc<RwLock<HashMap<IpAddr, TokenBucket>>>,
     /// 每个 IP 的当前连接数
     connections: Arc<RwLock<HashMap<IpAddr, u32>>>,
 }
@@ -30,7 +198,7 @@ impl ConnectionRateLimiter {
     /// 创建新的连接速率限制器
     pub fn new(config: ConnectionRateLimit) -> Self {
         Self {
-            config,
+            config: StdRwLock::new(config),
             limiters: Arc::new(RwLock::new(HashMap::new())),
             connections: Arc::new(RwLock::new(HashMap::new())),
         }
@@ -40,22 +208,24 @@ impl ConnectionRateLimiter {
     ///
     /// 返回 Ok(()) 如果允许，否则返回 Err
     pub async fn check_connection(&self, ip: IpAddr) -> Result<(), String> {
-        if !self.config.enabled {
+        let config = self.config();
+        if !config.enabled {
             return Ok(());
         }
 
         // 检查并发连接数
         let connections = self.connections.read().await;
         if let Some(&count) = connections.get(&ip)
-            && count >= self.config.max_concurrent_per_ip
+            && count >= config.max_concurrent_per_ip
         {
             warn!(
                 "IP {} exceeded max concurrent connections: {}/{}",
-                ip, count, self.config.max_concurrent_per_ip
+                ip, count, config.max_concurrent_per_ip
             );
+            record_rejection("connection");
             return Err(format!(
                 "Too many concurrent connections from your IP: {}/{}",
-                count, self.config.max_concurrent_per_ip
+                count, config.max_concurrent_per_ip
             ));
         }
         drop(connections);
@@ -65,33 +235,30 @@ impl ConnectionRateLimiter {
         let limiter = limiters.entry(ip).or_insert_with(|| {
             // 每分钟 per_minute 个连接，转换为每秒
             let per_second =
-                NonZeroU32::new((self.config.per_minute as f64 / 60.0).ceil().max(1.0) as u32)
-                    .unwrap();
+                NonZeroU32::new((config.per_minute as f64 / 60.0).ceil().max(1.0) as u32).unwrap();
 
             let quota = Quota::per_second(per_second)
-                .allow_burst(NonZeroU32::new(self.config.burst_size).unwrap());
+                .allow_burst(NonZeroU32::new(config.burst_size).unwrap());
 
-            RateLimiter::direct(quota)
+            TokenBucket::new(quota)
         });
 
-        match limiter.check() {
-            Ok(_) => {
-                debug!("IP {} passed connection rate limit check", ip);
-                Ok(())
-            }
-            Err(_) => {
-                warn!("IP {} exceeded connection rate limit", ip);
-                Err(format!(
-                    "Too many connection attempts. Limit: {} connections/minute",
-                    self.config.per_minute
-                ))
-            }
+        if limiter.check() {
+            debug!("IP {} passed connection rate limit check", ip);
+            Ok(())
+        } else {
+            warn!("IP {} exceeded connection rate limit", ip);
+            record_rejection("connection");
+            Err(format!(
+                "Too many connection attempts. Limit: {} connections/minute",
+                config.per_minute
+            ))
         }
     }
 
     /// 增加连接计数
     pub async fn increment_connection(&self, ip: IpAddr) {
-        if !self.config.enabled {
+        if !self.config().enabled {
             return;
         }
 
@@ -106,7 +273,7 @@ impl ConnectionRateLimiter {
 
     /// 减少连接计数
     pub async fn decrement_connection(&self, ip: IpAddr) {
-        if !self.config.enabled {
+        if !self.config().enabled {
             return;
         }
 
@@ -122,28 +289,46 @@ impl ConnectionRateLimiter {
         }
     }
 
+    /// 当前生效的配置
+    pub fn config(&self) -> ConnectionRateLimit {
+        read_config(&self.config)
+    }
+
+    /// 运行时替换配置
+    ///
+    /// 按 IP 的限流器以新配额重建（已消耗的配额清零），当前连接计数保留
+    pub async fn update_config(&self, config: ConnectionRateLimit) {
+        write_config(&self.config, config);
+        self.limiters.write().await.clear();
+    }
+
     /// 获取统计信息
     pub async fn stats(&self) -> (usize, usize) {
         let limiters = self.limiters.read().await;
         let connections = self.connections.read().await;
         (limiters.len(), connections.len())
     }
+
+    /// 按 IP 的令牌桶水位
+    pub async fn levels(&self) -> BucketLevels {
+        BucketLevels::collect(self.limiters.read().await.values())
+    }
 }
 
 /// 消息速率限制器（基于连接 ID）
 #[derive(Debug)]
 pub struct MessageRateLimiter {
-    /// 配置
-    config: MessageRateLimit,
+    /// 配置（可热加载）
+    config: StdRwLock<MessageRateLimit>,
     /// 每个连接的速率限制器
-    limiters: Arc<RwLock<HashMap<String, DefaultDirectRateLimiter>>>,
+    limiters: Arc<RwLock<HashMap<String, TokenBucket>>>,
 }
 
 impl MessageRateLimiter {
     /// 创建新的消息速率限制器
     pub fn new(config: MessageRateLimit) -> Self {
         Self {
-            config,
+            config: StdRwLock::new(config),
             limiters: Arc::new(RwLock::new(HashMap::new())),
         }
     }
@@ -152,7 +337,8 @@ impl MessageRateLimiter {
     ///
     /// 返回 Ok(()) 如果允许，否则返回 Err
     pub async fn check_message(&self, connection_id: &str) -> Result<(), String> {
-        if !self.config.enabled {
+        let config = self.config();
+        if !config.enabled {
             return Ok(());
         }
 
@@ -160,34 +346,32 @@ impl MessageRateLimiter {
         let limiter = limiters
             .entry(connection_id.to_string())
             .or_insert_with(|| {
-                let per_second = NonZeroU32::new(self.config.per_second).unwrap();
+                let per_second = NonZeroU32::new(config.per_second).unwrap();
                 let quota = Quota::per_second(per_second)
-                    .allow_burst(NonZeroU32::new(self.config.burst_size).unwrap());
+                    .allow_burst(NonZeroU32::new(config.burst_size).unwrap());
 
-                RateLimiter::direct(quota)
+                TokenBucket::new(quota)
             });
 
-        match limiter.check() {
-            Ok(_) => {
-                debug!(
-                    "Connection {} passed message rate limit check",
-                    connection_id
-                );
-                Ok(())
-            }
-            Err(_) => {
-                warn!("Connection {} exceeded message rate limit", connection_id);
-                Err(format!(
-                    "Too many messages. Limit: {} messages/second",
-                    self.config.per_second
-                ))
-            }
+        if limiter.check() {
+            debug!(
+                "Connection {} passed message rate limit check",
+                connection_id
+            );
+            Ok(())
+        } else {
+            warn!("Connection {} exceeded message rate limit", connection_id);
+            record_rejection("message");
+            Err(format!(
+                "Too many messages. Limit: {} messages/second",
+                config.per_second
+            ))
         }
     }
 
     /// 移除连接的速率限制器（连接关闭时调用）
     pub async fn remove_connection(&self, connection_id: &str) {
-        if !self.config.enabled {
+        if !self.config().enabled {
             return;
         }
 
@@ -196,11 +380,280 @@ impl MessageRateLimiter {
         debug!("Removed rate limiter for connection {}", connection_id);
     }
 
+    /// 当前生效的配置
+    pub fn config(&self) -> MessageRateLimit {
+        read_config(&self.config)
+    }
+
+    /// 运行时替换配置，按连接的限流器以新配额重建
+    pub async fn update_config(&self, config: MessageRateLimit) {
+        write_config(&self.config, config);
+        self.limiters.write().await.clear();
+    }
+
     /// 获取统计信息
     pub async fn stats(&self) -> usize {
         let limiters = self.limiters.read().await;
         limiters.len()
     }
+
+    /// 按连接的令牌桶水位
+    pub async fn levels(&self) -> BucketLevels {
+        BucketLevels::collect(self.limiters.read().await.values())
+    }
+}
+
+/// Realm 级限流的操作类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RealmOperation {
+    /// 注册（RegisterRequest）
+    Register,
+    /// 中继（ActrRelay）
+    Relay,
+    /// 服务发现（DiscoveryRequest）
+    Discovery,
+}
+
+impl RealmOperation {
+    /// 指标标签值
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RealmOperation::Register => "register",
+            RealmOperation::Relay => "relay",
+            RealmOperation::Discovery => "discovery",
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            RealmOperation::Register => "registrations/minute",
+            RealmOperation::Relay => "relays/second",
+            RealmOperation::Discovery => "discovery requests/second",
+        }
+    }
+}
+
+/// 单个 Realm 的限流状态
+#[derive(Debug)]
+struct RealmBucket {
+    /// 生效的限额覆盖值
+    overrides: RealmRateLimits,
+    /// 覆盖值的加载时间
+    loaded_at: Instant,
+    /// 各操作的限流器，限额为 0 时不创建
+    limiters: HashMap<RealmOperation, TokenBucket>,
+}
+
+impl RealmBucket {
+    fn new() -> Self {
+        Self {
+            overrides: RealmRateLimits::default(),
+            loaded_at: Instant::now(),
+            limiters: HashMap::new(),
+        }
+    }
+}
+
+/// Realm 聚合速率限制器（同一 Realm 的所有连接共享配额）
+#[derive(Debug)]
+pub struct RealmRateLimiter {
+    /// 节点默认限额（可热加载）
+    config: StdRwLock<RealmRateLimit>,
+    /// 每个 Realm 的限流状态
+    ///
+    /// 外层写锁只在首次出现的 Realm 插入时持有；已有 Realm 在读锁下取出后各自加锁，
+    /// 不同 Realm 的检查互不阻塞
+    buckets: Arc<RwLock<HashMap<u32, Arc<StdMutex<RealmBucket>>>>>,
+}
+
+impl RealmRateLimiter {
+    /// 创建新的 Realm 速率限制器
+    pub fn new(config: RealmRateLimit) -> Self {
+        Self {
+            config: StdRwLock::new(config),
+            buckets: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// 检查 Realm 是否还有该操作的配额
+    ///
+    /// 限额覆盖值从 Realm 元数据读取并缓存 `cache_ttl_secs` 秒，读取失败时沿用节点默认值
+    pub async fn check(&self, realm_id: u32, operation: RealmOperation) -> Result<(), String> {
+        let config = self.config();
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let cache_ttl = Duration::from_secs(config.cache_ttl_secs);
+        let fresh_overrides = {
+            let buckets = self.buckets.read().await;
+            buckets
+                .get(&realm_id)
+                .is_some_and(|bucket| lock_bucket(bucket).loaded_at.elapsed() < cache_ttl)
+        };
+
+        let overrides = if fresh_overrides {
+            None
+        } else {
+            match RealmRateLimits::load_for_realm(realm_id).await {
+                Ok(overrides) => Some(overrides),
+                Err(e) => {
+                    warn!("Failed to load rate limits for realm {}: {}", realm_id, e);
+                    Some(RealmRateLimits::default())
+                }
+            }
+        };
+
+        self.check_with(realm_id, operation, overrides).await
+    }
+
+    /// 使用给定的覆盖值（None 表示沿用缓存）执行检查
+    async fn check_with(
+        &self,
+        realm_id: u32,
+        operation: RealmOperation,
+        overrides: Option<RealmRateLimits>,
+    ) -> Result<(), String> {
+        let existing = self.buckets.read().await.get(&realm_id).cloned();
+        let bucket = match existing {
+            Some(bucket) => bucket,
+            None => self
+                .buckets
+                .write()
+                .await
+                .entry(realm_id)
+                .or_insert_with(|| Arc::new(StdMutex::new(RealmBucket::new())))
+                .clone(),
+        };
+        let mut bucket = lock_bucket(&bucket);
+
+        if let Some(overrides) = overrides {
+            // 限额变化时重建限流器，未变化时保留已消耗的配额
+            if bucket.overrides != overrides {
+                debug!("Realm {} rate limits changed: {:?}", realm_id, overrides);
+                bucket.limiters.clear();
+                bucket.overrides = overrides;
+            }
+            bucket.loaded_at = Instant::now();
+        }
+
+        let limit = self.effective_limit(&bucket.overrides, operation);
+        let Some(limit) = NonZeroU32::new(limit) else {
+            return Ok(());
+        };
+
+        let limiter = bucket.limiters.entry(operation).or_insert_with(|| {
+            let quota = match operation {
+                RealmOperation::Register => Quota::per_minute(limit),
+                RealmOperation::Relay | RealmOperation::Discovery => Quota::per_second(limit),
+            };
+            TokenBucket::new(quota)
+        });
+
+        if limiter.check() {
+            return Ok(());
+        }
+
+        warn!(
+            "Realm {} exceeded {} rate limit: {}",
+            realm_id,
+            operation.describe(),
+            limit
+        );
+        record_rejection("realm");
+        SIGNALING_REALM_RATE_LIMITED
+            .with_label_values(&[&realm_id.to_string(), operation.as_str()])
+            .inc();
+        Err(format!(
+            "Realm rate limit exceeded. Limit: {} {}",
+            limit,
+            operation.describe()
+        ))
+    }
+
+    /// 计算生效限额：Realm 覆盖值优先，否则为节点默认值
+    fn effective_limit(&self, overrides: &RealmRateLimits, operation: RealmOperation) -> u32 {
+        let config = self.config();
+        match operation {
+            RealmOperation::Register => overrides
+                .registrations_per_minute
+                .unwrap_or(config.registrations_per_minute),
+            RealmOperation::Relay => overrides
+                .relays_per_second
+                .unwrap_or(config.relays_per_second),
+            RealmOperation::Discovery => overrides
+                .discovery_per_second
+                .unwrap_or(config.discovery_per_second),
+        }
+    }
+
+    /// 当前生效的节点默认限额
+    pub fn config(&self) -> RealmRateLimit {
+        read_config(&self.config)
+    }
+
+    /// 运行时替换节点默认限额，各 Realm 的限流状态与限额覆盖值重新加载
+    pub async fn update_config(&self, config: RealmRateLimit) {
+        write_config(&self.config, config);
+        self.buckets.write().await.clear();
+    }
+
+    /// 获取统计信息（已跟踪的 Realm 数）
+    pub async fn stats(&self) -> usize {
+        let buckets = self.buckets.read().await;
+        buckets.len()
+    }
+
+    /// 令牌桶水位：全部 Realm 的汇总与各 Realm 按操作的明细
+    pub async fn levels(&self) -> (BucketLevels, Vec<RealmBucketLevel>) {
+        let realms = self.buckets.read().await;
+        let buckets: Vec<(u32, MutexGuard<'_, RealmBucket>)> = realms
+            .iter()
+            .map(|(&realm_id, bucket)| (realm_id, lock_bucket(bucket)))
+            .collect();
+        let levels = BucketLevels::collect(
+            buckets
+                .iter()
+                .flat_map(|(_, bucket)| bucket.limiters.values()),
+        );
+        let mut realm_buckets: Vec<RealmBucketLevel> = buckets
+            .iter()
+            .flat_map(|(realm_id, bucket)| {
+                let realm_id = *realm_id;
+                bucket
+                    .limiters
+                    .iter()
+                    .map(move |(&operation, limiter)| RealmBucketLevel {
+                        realm_id,
+                        operation,
+                        tokens: limiter.tokens(),
+                        capacity: limiter.capacity(),
+                    })
+            })
+            .collect();
+        realm_buckets.sort_by_key(|level| (level.realm_id, level.operation));
+        (levels, realm_buckets)
+    }
+}
+
+fn lock_bucket(bucket: &StdMutex<RealmBucket>) -> MutexGuard<'_, RealmBucket> {
+    bucket
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn read_config<T: Clone>(config: &StdRwLock<T>) -> T {
+    config
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+fn write_config<T>(config: &StdRwLock<T>, value: T) {
+    *config
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = value;
 }
 
 #[cfg(test)]
@@ -249,4 +702,184 @@ mod tests {
         limiter.remove_connection(conn_id).await;
         assert_eq!(limiter.stats().await, 0);
     }
+
+    #[tokio::test]
+    async fn test_message_limiter_update_config() {
+        let limiter = MessageRateLimiter::new(MessageRateLimit {
+            enabled: true,
+            per_second: 1,
+            burst_size: 1,
+        });
+        let conn_id = "test-connection-1";
+        assert!(limiter.check_message(conn_id).await.is_ok());
+        assert!(limiter.check_message(conn_id).await.is_err());
+
+        // 新配额立即生效，已消耗的配额清零
+        limiter
+            .update_config(MessageRateLimit {
+                enabled: true,
+                per_second: 1,
+                burst_size: 3,
+            })
+            .await;
+        assert_eq!(limiter.config().burst_size, 3);
+        for _ in 0..3 {
+            assert!(limiter.check_message(conn_id).await.is_ok());
+        }
+        assert!(limiter.check_message(conn_id).await.is_err());
+
+        limiter
+            .update_config(MessageRateLimit {
+                enabled: false,
+                ..limiter.config()
+            })
+            .await;
+        assert!(limiter.check_message(conn_id).await.is_ok());
+    }
+
+    fn realm_config(relays_per_second: u32) -> RealmRateLimit {
+        RealmRateLimit {
+            enabled: true,
+            relays_per_second,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_realm_limit_is_shared_and_isolated_per_realm() {
+        let limiter = RealmRateLimiter::new(realm_config(2));
+        let none = Some(RealmRateLimits::default());
+
+        assert!(
+            limiter
+                .check_with(1, RealmOperation::Relay, none)
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_with(1, RealmOperation::Relay, None)
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_with(1, RealmOperation::Relay, None)
+                .await
+                .is_err()
+        );
+
+        // 其他 Realm 与其他操作不受影响
+        assert!(
+            limiter
+                .check_with(2, RealmOperation::Relay, none)
+                .await
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check_with(1, RealmOperation::Discovery, None)
+                .await
+                .is_ok()
+        );
+        assert_eq!(limiter.stats().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_realm_metadata_overrides_defaults() {
+        let limiter = RealmRateLimiter::new(realm_config(1));
+
+        // 覆盖值 0 表示不限制
+        let unlimited = Some(RealmRateLimits {
+            relays_per_second: Some(0),
+            ..Default::default()
+        });
+        for _ in 0..10 {
+            assert!(
+                limiter
+                    .check_with(7, RealmOperation::Relay, unlimited)
+                    .await
+                    .is_ok()
+            );
+        }
+
+        // 覆盖值变化后立即生效
+        let strict = Some(RealmRateLimits {
+            relays_per_second: Some(1),
+            ..Default::default()
+        });
+        assert!(
+            limiter
+                .check_with(7, RealmOperation::Relay, strict)
+                .await
+                .is_ok()
+        );
+        let err = limiter
+            .check_with(7, RealmOperation::Relay, strict)
+            .await
+            .unwrap_err();
+        assert!(err.contains("relays/second"));
+    }
+
+    #[tokio::test]
+    async fn test_message_bucket_levels() {
+        let limiter = MessageRateLimiter::new(MessageRateLimit {
+            enabled: true,
+            per_second: 1,
+            burst_size: 3,
+        });
+        assert_eq!(limiter.levels().await, BucketLevels::default());
+
+        assert!(limiter.check_message("conn-a").await.is_ok());
+        for _ in 0..4 {
+            let _ = limiter.check_message("conn-b").await;
+        }
+
+        let levels = limiter.levels().await;
+        assert_eq!(levels.buckets, 2);
+        assert_eq!(levels.exhausted, 1);
+        assert_eq!(levels.min_tokens, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_realm_bucket_levels_and_metrics() {
+        let limiter = RealmRateLimiter::new(realm_config(2));
+        let none = Some(RealmRateLimits::default());
+        assert!(
+            limiter
+                .check_with(42, RealmOperation::Relay, none)
+                .await
+                .is_ok()
+        );
+
+        let status = RateLimitStatus::collect(None, None, Some(&limiter)).await;
+        assert!(status.connection.is_none());
+        assert_eq!(status.realm.as_ref().unwrap().buckets, 1);
+        assert_eq!(
+            status.realm_buckets,
+            vec![RealmBucketLevel {
+                realm_id: 42,
+                operation: RealmOperation::Relay,
+                tokens: 1,
+                capacity: 2,
+            }]
+        );
+
+        status.record_metrics();
+        assert_eq!(
+            SIGNALING_REALM_RATE_LIMIT_TOKENS
+                .with_label_values(&["42", "relay"])
+                .get(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_realm_limiter_disabled() {
+        let limiter = RealmRateLimiter::new(RealmRateLimit::default());
+        for _ in 0..5 {
+            assert!(limiter.check(1, RealmOperation::Register).await.is_ok());
+        }
+        assert_eq!(limiter.stats().await, 0);
+    }
 }
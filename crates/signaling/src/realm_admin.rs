@@ -0,0 +1,594 @@
+//! Realm 自助管理 API
+//!
+//! 租户使用 Realm API Key（见 [`actrix_common::realm::RealmApiKey`]）访问本 Realm 的数据，
+//! 无需持有全局管理 token：
+//! - `GET /admin/realms/{realm_id}/presence`：本 Realm 在线 Actor（scope `presence`）
+//! - `GET /admin/realms/{realm_id}/usage`：连接数、注册服务数与 ICE 连接上报（scope `usage`）
+//! - `GET /admin/realms/{realm_id}/acl`：本 Realm 的 ACL 规则（scope `acl`）
+//! - `PUT /admin/realms/{realm_id}/acl`：新增或更新一条 `from_type -> to_type` 规则（scope `acl`）
+//! - `DELETE /admin/realms/{realm_id}/acl/{rule_id}`：删除规则（scope `acl`）
+//! - `PUT /admin/realms/{realm_id}/acl/types/{to_type}`：整体替换某类型的入站规则（scope `acl`）
+//! - `PATCH /admin/realms/{realm_id}/acl/types/{to_type}`：增量修改某类型的入站规则（scope `acl`）
+//! - `GET / PUT /admin/realms/{realm_id}/acl/groups/{tag}`：查询、整体替换 `#tag` 组的成员类型（scope `acl`）
+//!
+//! 以上端点同样接受 `actrix_shared_key`。presence 与 usage 属于统计查询，过载降级期间返回 503。Realm 的创建与
+//! Key 的签发、吊销只接受 `actrix_shared_key`（或通过 Supervisord gRPC `CreateRealm` /
+//! `CreateRealmApiKey` / `ListRealmApiKeys` / `RevokeRealmApiKey`）：
+//! - `POST /admin/realms`：创建 Realm，已存在时返回 409（供 `deploy realm create` 初始化测试与新租户）
+//! - `POST /admin/realms/{realm_id}/api-keys`
+//! - `GET /admin/realms/{realm_id}/api-keys`
+//! - `DELETE /admin/realms/{realm_id}/api-keys/{key_id}`
+
+use crate::admin::{AdminAuth, connection_snapshots};
+use crate::axum_router::SignalingState;
+use crate::load_shed::StatsQuery;
+use actrix_common::realm::{
+    AclUpdate, AclUpdateMode, ActorAcl, Realm, RealmApiKey, RealmApiScope, RealmError,
+};
+use actrix_common::util::constant_time_eq;
+use axum::{
+    Router,
+    extract::{FromRequestParts, Path, State},
+    http::{StatusCode, request::Parts},
+    response::Json,
+    routing::{delete, get, post},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::{info, warn};
+
+type ApiResult = (StatusCode, Json<Value>);
+
+/// 创建 Realm 自助管理路由（由 [`crate::admin::admin_router`] 合并）
+pub fn realm_admin_router() -> Router<SignalingState> {
+    Router::new()
+        .route("/admin/realms", post(create_realm))
+        .route(
+            "/admin/realms/{realm_id}/api-keys",
+            get(list_api_keys).post(create_api_key),
+        )
+        .route(
+            "/admin/realms/{realm_id}/api-keys/{key_id}",
+            delete(revoke_api_key),
+        )
+        .route("/admin/realms/{realm_id}/presence", get(presence))
+        .route("/admin/realms/{realm_id}/usage", get(usage))
+        .route(
+            "/admin/realms/{realm_id}/acl",
+            get(list_acl).put(upsert_acl),
+        )
+        .route("/admin/realms/{realm_id}/acl/{rule_id}", delete(delete_acl))
+        .route(
+            "/admin/realms/{realm_id}/acl/types/{to_type}",
+            get(list_inbound_acl)
+                .put(replace_inbound_acl)
+                .patch(patch_inbound_acl),
+        )
+        .route(
+            "/admin/realms/{realm_id}/acl/groups/{tag}",
+            get(list_group_members).put(replace_group_members),
+        )
+}
+
+/// Realm 自助 API 的调用方
+///
+/// 持有管理 token 的运维，或持有 Realm API Key 的租户
+#[derive(Debug)]
+pub enum RealmCaller {
+    Admin,
+    Tenant(RealmApiKey),
+}
+
+impl RealmCaller {
+    /// 检查调用方能否以指定权限范围访问该 Realm
+    pub fn require(&self, realm_id: u32, scope: RealmApiScope) -> Result<(), ApiResult> {
+        match self {
+            Self::Admin => Ok(()),
+            Self::Tenant(key) if key.realm_id != realm_id => Err(error(
+                StatusCode::FORBIDDEN,
+                "API key does not belong to this realm",
+            )),
+            Self::Tenant(key) if !key.has_scope(scope) => Err(error(
+                StatusCode::FORBIDDEN,
+                format!("API key lacks scope: {scope}"),
+            )),
+            Self::Tenant(_) => Ok(()),
+        }
+    }
+}
+
+impl FromRequestParts<SignalingState> for RealmCaller {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &SignalingState,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(token) = crate::ws_auth::bearer_token(&parts.headers) else {
+            return Err((StatusCode::UNAUTHORIZED, "Missing bearer token"));
+        };
+
+        if state
+            .admin_token
+            .as_deref()
+            .is_some_and(|expected| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+        {
+            return Ok(Self::Admin);
+        }
+
+        match RealmApiKey::authenticate(token).await {
+            Ok(Some(key)) => Ok(Self::Tenant(key)),
+            Ok(None) => {
+                warn!("🚫 Realm API Key 认证失败");
+                Err((StatusCode::UNAUTHORIZED, "Invalid API key"))
+            }
+            Err(e) => {
+                warn!("Realm API Key 校验出错: {}", e);
+                Err((StatusCode::INTERNAL_SERVER_ERROR, "API key lookup failed"))
+            }
+        }
+    }
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> ApiResult {
+    (
+        status,
+        Json(json!({
+            "status": "error",
+            "message": message.into()
+        })),
+    )
+}
+
+fn realm_error(e: RealmError) -> ApiResult {
+    match e {
+        RealmError::NotFound => error(StatusCode::NOT_FOUND, "Realm not found"),
+        RealmError::KeyNotExist => error(StatusCode::NOT_FOUND, "API key not found"),
+        RealmError::ValidationError(msg) => error(StatusCode::BAD_REQUEST, msg),
+        other => error(StatusCode::INTERNAL_SERVER_ERROR, other.to_string()),
+    }
+}
+
+/// `POST /admin/realms` 请求体
+#[derive(Debug, Deserialize)]
+struct CreateRealmBody {
+    realm_id: u32,
+    name: String,
+    /// 到期时间（Unix 秒），不填表示永不过期
+    expires_at: Option<i64>,
+}
+
+impl CreateRealmBody {
+    fn validate(&self) -> Result<(), RealmError> {
+        if self.realm_id == 0 {
+            return Err(RealmError::ValidationError(
+                "realm_id must be greater than 0".to_string(),
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(RealmError::ValidationError(
+                "name must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 创建 Realm
+async fn create_realm(_auth: AdminAuth, Json(body): Json<CreateRealmBody>) -> ApiResult {
+    if let Err(e) = body.validate() {
+        return realm_error(e);
+    }
+    match Realm::get_by_realm_id(body.realm_id).await {
+        Ok(Some(_)) => {
+            return error(
+                StatusCode::CONFLICT,
+                format!("Realm {} already exists", body.realm_id),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => return realm_error(e),
+    }
+
+    let mut realm = Realm::new(body.realm_id, body.name);
+    realm.set_expires_at(body.expires_at);
+    match realm.save().await {
+        Ok(_) => {
+            info!("🏠 已创建 Realm {} ({})", realm.realm_id, realm.name);
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "status": "success",
+                    "realm": realm
+                })),
+            )
+        }
+        Err(e) => realm_error(e),
+    }
+}
+
+/// `POST /admin/realms/{realm_id}/api-keys` 请求体
+#[derive(Debug, Deserialize)]
+struct CreateApiKeyBody {
+    name: String,
+    /// 为空时授予全部权限范围
+    #[serde(default)]
+    scopes: Vec<String>,
+    expires_at: Option<i64>,
+}
+
+/// 签发 Realm API Key，明文 token 只在响应中出现一次
+async fn create_api_key(
+    _auth: AdminAuth,
+    Path(realm_id): Path<u32>,
+    Json(body): Json<CreateApiKeyBody>,
+) -> ApiResult {
+    let scopes = match RealmApiScope::parse_list(&body.scopes) {
+        Ok(scopes) => scopes,
+        Err(e) => return realm_error(e),
+    };
+
+    match RealmApiKey::issue(realm_id, &body.name, scopes, body.expires_at).await {
+        Ok((key, token)) => {
+            info!("🔑 为 Realm {} 签发 API Key {}", realm_id, key.key_id);
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "status": "success",
+                    "key": key,
+                    "token": token
+                })),
+            )
+        }
+        Err(e) => realm_error(e),
+    }
+}
+
+/// 列出 Realm 的 API Key（不含 token）
+async fn list_api_keys(_auth: AdminAuth, Path(realm_id): Path<u32>) -> ApiResult {
+    match RealmApiKey::list_by_realm(realm_id).await {
+        Ok(keys) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "success",
+                "keys": keys
+            })),
+        ),
+        Err(e) => realm_error(e),
+    }
+}
+
+/// 吊销 Realm API Key
+async fn revoke_api_key(
+    _auth: AdminAuth,
+    Path((realm_id, key_id)): Path<(u32, String)>,
+) -> ApiResult {
+    match RealmApiKey::revoke(realm_id, &key_id).await {
+        Ok(()) => {
+            info!("🔑 已吊销 Realm {} 的 API Key {}", realm_id, key_id);
+            (StatusCode::OK, Json(json!({ "status": "success" })))
+        }
+        Err(e) => realm_error(e),
+    }
+}
+
+/// 本 Realm 在线 Actor
+async fn presence(
+    caller: RealmCaller,
+    _shed: StatsQuery,
+    State(state): State<SignalingState>,
+    Path(realm_id): Path<u32>,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Presence) {
+        return rejection;
+    }
+
+    let connections = connection_snapshots(&state.server, Some(realm_id)).await;
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "realm_id": realm_id,
+            "total": connections.len(),
+            "actors": connections
+        })),
+    )
+}
+
+/// 本 Realm 用量
+async fn usage(
+    caller: RealmCaller,
+    _shed: StatsQuery,
+    State(state): State<SignalingState>,
+    Path(realm_id): Path<u32>,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Usage) {
+        return rejection;
+    }
+
+    let connections = connection_snapshots(&state.server, Some(realm_id)).await;
+    let services: usize = connections.iter().map(|c| c.services.len()).sum();
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "realm_id": realm_id,
+            "connections": connections.len(),
+            "services": services,
+            "connection_reports": state.server.connection_reports.snapshot(Some(realm_id))
+        })),
+    )
+}
+
+/// 本 Realm 的 ACL 规则
+async fn list_acl(caller: RealmCaller, Path(realm_id): Path<u32>) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+
+    match ActorAcl::get_by_realm(realm_id).await {
+        Ok(rules) => (
+            StatusCode::OK,
+            Json(json!({
+                "status": "success",
+                "realm_id": realm_id,
+                "rules": rules
+            })),
+        ),
+        Err(e) => realm_error(e),
+    }
+}
+
+/// `PUT /admin/realms/{realm_id}/acl` 请求体
+#[derive(Debug, Deserialize)]
+struct UpsertAclBody {
+    /// 来源类型（`manufacturer:name`）
+    from_type: String,
+    /// 目标类型（`manufacturer:name`）
+    to_type: String,
+    access: bool,
+}
+
+/// 新增或更新 ACL 规则（同一 `from_type -> to_type` 只保留一条）
+async fn upsert_acl(
+    caller: RealmCaller,
+    Path(realm_id): Path<u32>,
+    Json(body): Json<UpsertAclBody>,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+    let candidate = ActorAcl::new(
+        realm_id,
+        body.from_type.clone(),
+        body.to_type.clone(),
+        body.access,
+    );
+    if let Err(e) = candidate.validate() {
+        return realm_error(e);
+    }
+
+    let existing = match ActorAcl::get_by_types(realm_id, &body.from_type, &body.to_type).await {
+        Ok(existing) => existing,
+        Err(e) => return realm_error(e),
+    };
+    let mut rule = existing.unwrap_or(candidate);
+    rule.access = body.access;
+
+    match rule.save().await {
+        Ok(rule_id) => {
+            info!(
+                "✅ Realm {} ACL 规则已更新: {} -> {} : {}",
+                realm_id,
+                rule.from_type,
+                rule.to_type,
+                if rule.access { "ALLOW" } else { "DENY" }
+            );
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "status": "success",
+                    "rule_id": rule_id
+                })),
+            )
+        }
+        Err(e) => realm_error(e),
+    }
+}
+
+/// 删除本 Realm 的 ACL 规则
+async fn delete_acl(caller: RealmCaller, Path((realm_id, rule_id)): Path<(u32, i64)>) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+
+    // 规则必须属于该 Realm，避免跨租户删除
+    match ActorAcl::get(rule_id).await {
+        Ok(Some(rule)) if rule.realm_id == realm_id => {}
+        Ok(_) => return error(StatusCode::NOT_FOUND, "ACL rule not found"),
+        Err(e) => return realm_error(e),
+    }
+
+    match ActorAcl::delete_by_id(rule_id).await {
+        Ok(_) => (StatusCode::OK, Json(json!({ "status": "success" }))),
+        Err(e) => realm_error(e),
+    }
+}
+
+fn inbound_rules(realm_id: u32, to_type: &str, rules: Vec<ActorAcl>) -> ApiResult {
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "realm_id": realm_id,
+            "to_type": to_type,
+            "rules": rules
+        })),
+    )
+}
+
+/// 某类型的入站规则
+async fn list_inbound_acl(
+    caller: RealmCaller,
+    Path((realm_id, to_type)): Path<(u32, String)>,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+
+    match ActorAcl::get_inbound(realm_id, &to_type).await {
+        Ok(rules) => inbound_rules(realm_id, &to_type, rules),
+        Err(e) => realm_error(e),
+    }
+}
+
+/// 应用 ACL 更新，`mode` 由 HTTP 方法决定
+async fn apply_inbound_update(
+    caller: RealmCaller,
+    realm_id: u32,
+    to_type: String,
+    mode: AclUpdateMode,
+    mut update: AclUpdate,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+    update.mode = mode;
+
+    match ActorAcl::apply_update(realm_id, &to_type, &update).await {
+        Ok(rules) => {
+            info!(
+                "✅ Realm {} {} 入站 ACL 已更新 ({:?}): {} 条规则",
+                realm_id,
+                to_type,
+                mode,
+                rules.len()
+            );
+            inbound_rules(realm_id, &to_type, rules)
+        }
+        Err(e) => realm_error(e),
+    }
+}
+
+/// 整体替换某类型的入站规则
+async fn replace_inbound_acl(
+    caller: RealmCaller,
+    Path((realm_id, to_type)): Path<(u32, String)>,
+    Json(update): Json<AclUpdate>,
+) -> ApiResult {
+    apply_inbound_update(caller, realm_id, to_type, AclUpdateMode::Replace, update).await
+}
+
+/// 增量修改某类型的入站规则
+async fn patch_inbound_acl(
+    caller: RealmCaller,
+    Path((realm_id, to_type)): Path<(u32, String)>,
+    Json(update): Json<AclUpdate>,
+) -> ApiResult {
+    apply_inbound_update(caller, realm_id, to_type, AclUpdateMode::Patch, update).await
+}
+
+fn group_members(realm_id: u32, tag: &str, members: Vec<String>) -> ApiResult {
+    (
+        StatusCode::OK,
+        Json(json!({
+            "status": "success",
+            "realm_id": realm_id,
+            "tag": tag,
+            "members": members
+        })),
+    )
+}
+
+/// `#tag` 组的成员类型
+async fn list_group_members(
+    caller: RealmCaller,
+    Path((realm_id, tag)): Path<(u32, String)>,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+
+    match ActorAcl::get_group_members(realm_id, &tag).await {
+        Ok(members) => group_members(realm_id, &tag, members),
+        Err(e) => realm_error(e),
+    }
+}
+
+/// `PUT /admin/realms/{realm_id}/acl/groups/{tag}` 请求体
+#[derive(Debug, Deserialize)]
+struct GroupMembersBody {
+    /// 成员类型模式（`manufacturer:name[:version]` 或 `manufacturer:*`），为空时删除该组
+    members: Vec<String>,
+}
+
+/// 整体替换 `#tag` 组的成员类型
+async fn replace_group_members(
+    caller: RealmCaller,
+    Path((realm_id, tag)): Path<(u32, String)>,
+    Json(body): Json<GroupMembersBody>,
+) -> ApiResult {
+    if let Err(rejection) = caller.require(realm_id, RealmApiScope::Acl) {
+        return rejection;
+    }
+
+    match ActorAcl::set_group_members(realm_id, &tag, &body.members).await {
+        Ok(members) => {
+            info!(
+                "✅ Realm {} ACL 组 #{} 已更新: {} 个成员",
+                realm_id,
+                tag,
+                members.len()
+            );
+            group_members(realm_id, &tag, members)
+        }
+        Err(e) => realm_error(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant(realm_id: u32, scopes: Vec<RealmApiScope>) -> RealmCaller {
+        RealmCaller::Tenant(RealmApiKey {
+            rowid: None,
+            key_id: "abcd".to_string(),
+            realm_id,
+            name: "ci".to_string(),
+            scopes,
+            created_at: 0,
+            expires_at: None,
+            last_used_at: None,
+            revoked: false,
+        })
+    }
+
+    #[test]
+    fn test_caller_scope_and_realm_checks() {
+        assert!(RealmCaller::Admin.require(7, RealmApiScope::Acl).is_ok());
+
+        let caller = tenant(7, vec![RealmApiScope::Presence]);
+        assert!(caller.require(7, RealmApiScope::Presence).is_ok());
+
+        let (status, _) = caller.require(7, RealmApiScope::Acl).unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // 其他 Realm 的数据不可访问
+        let (status, _) = caller.require(8, RealmApiScope::Presence).unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_create_realm_body_validation() {
+        let body = |realm_id: u32, name: &str| CreateRealmBody {
+            realm_id,
+            name: name.to_string(),
+            expires_at: None,
+        };
+        assert!(body(1001, "test").validate().is_ok());
+        assert!(body(0, "test").validate().is_err());
+        assert!(body(1001, " ").validate().is_err());
+    }
+}
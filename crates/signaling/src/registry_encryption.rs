@@ -0,0 +1,145 @@
+//! 服务注册表存储加密
+//!
+//! 启用 `registry_encryption` 后，注册表 SQLite 中的敏感列（ACL、ServiceSpec、proto 内容与
+//! 历史版本）以 [`ks::KeyEncryptor`] 加密后写入，KEK 与 KS 共用同一套来源（配置、环境变量、
+//! 文件或 PKCS#11）。
+//!
+//! 密文格式: `SEALED_HEADER || nonce[12] || ciphertext || tag[16]`。头部以 0x00 开头，
+//! 而合法的 protobuf 编码不会以 0x00 开头（字段号 0 无效），因此加载时可区分密文与
+//! 启用加密前写入的明文：明文原样返回，由 `encrypt-registry` 命令迁移。
+
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result, bail};
+use ks::KeyEncryptor;
+use tracing::info;
+
+/// 密文头部（固定前缀 + 格式版本）
+const SEALED_HEADER: [u8; 4] = [0x00, b'A', b'X', 0x01];
+
+/// 注册表敏感列的加解密
+#[derive(Debug, Clone)]
+pub struct RegistryCipher {
+    encryptor: KeyEncryptor,
+}
+
+impl Default for RegistryCipher {
+    fn default() -> Self {
+        Self::new(KeyEncryptor::no_encryption())
+    }
+}
+
+impl RegistryCipher {
+    pub fn new(encryptor: KeyEncryptor) -> Self {
+        Self { encryptor }
+    }
+
+    /// 按 `services.signaling.server.registry_encryption` 创建，未启用时不加密
+    pub fn from_config(config: &ActrixConfig) -> Result<Self> {
+        let Some(signaling) = &config.services.signaling else {
+            return Ok(Self::default());
+        };
+        if !signaling.server.registry_encryption.enabled {
+            return Ok(Self::default());
+        }
+
+        let source = signaling
+            .get_registry_kek_source(config)
+            .context("Registry encryption is enabled but no KEK is configured")?;
+        let encryptor = KeyEncryptor::from_kek_source(&source)
+            .map_err(|e| anyhow::anyhow!("Failed to load registry KEK: {e}"))?;
+
+        info!("🔐 ServiceRegistry 存储加密已启用");
+        Ok(Self::new(encryptor))
+    }
+
+    /// 是否启用了加密
+    pub fn is_enabled(&self) -> bool {
+        self.encryptor.is_enabled()
+    }
+
+    /// 是否为加密后的数据
+    pub fn is_sealed(blob: &[u8]) -> bool {
+        blob.starts_with(&SEALED_HEADER)
+    }
+
+    /// 加密写入的数据，未启用加密时原样返回
+    pub fn seal(&self, plaintext: Vec<u8>) -> Result<Vec<u8>> {
+        if !self.is_enabled() {
+            return Ok(plaintext);
+        }
+
+        let encrypted = self
+            .encryptor
+            .encrypt_bytes(&plaintext)
+            .map_err(|e| anyhow::anyhow!("Failed to encrypt registry data: {e}"))?;
+
+        let mut sealed = Vec::with_capacity(SEALED_HEADER.len() + encrypted.len());
+        sealed.extend_from_slice(&SEALED_HEADER);
+        sealed.extend_from_slice(&encrypted);
+        Ok(sealed)
+    }
+
+    /// 解密读取的数据，明文（启用加密前写入）原样返回
+    pub fn open(&self, blob: Vec<u8>) -> Result<Vec<u8>> {
+        if !Self::is_sealed(&blob) {
+            return Ok(blob);
+        }
+        if !self.is_enabled() {
+            bail!("Registry data is encrypted but registry_encryption is not enabled");
+        }
+
+        self.encryptor
+            .decrypt_bytes(&blob[SEALED_HEADER.len()..])
+            .map_err(|e| anyhow::anyhow!("Failed to decrypt registry data: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> RegistryCipher {
+        RegistryCipher::new(KeyEncryptor::from_kek(&KeyEncryptor::generate_kek()).unwrap())
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let cipher = enabled();
+        let plaintext = b"\x0a\x04spec".to_vec();
+
+        let sealed = cipher.seal(plaintext.clone()).unwrap();
+        assert!(RegistryCipher::is_sealed(&sealed));
+        assert_ne!(sealed, plaintext);
+        assert_eq!(cipher.open(sealed.clone()).unwrap(), plaintext);
+
+        // 启用加密前写入的明文原样返回
+        assert_eq!(cipher.open(plaintext.clone()).unwrap(), plaintext);
+
+        // KEK 不匹配或未启用加密时无法读取密文
+        assert!(enabled().open(sealed.clone()).is_err());
+        assert!(RegistryCipher::default().open(sealed).is_err());
+    }
+
+    #[test]
+    fn test_disabled_passthrough() {
+        let cipher = RegistryCipher::default();
+        assert!(!cipher.is_enabled());
+        assert_eq!(cipher.seal(b"acl".to_vec()).unwrap(), b"acl");
+        assert_eq!(cipher.open(b"acl".to_vec()).unwrap(), b"acl");
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = ActrixConfig::default();
+        assert!(!RegistryCipher::from_config(&config).unwrap().is_enabled());
+
+        let mut signaling = actrix_common::config::signaling::SignalingConfig::default();
+        signaling.server.registry_encryption.enabled = true;
+        config.services.signaling = Some(signaling.clone());
+        assert!(RegistryCipher::from_config(&config).is_err());
+
+        signaling.server.registry_encryption.kek = Some(KeyEncryptor::generate_kek());
+        config.services.signaling = Some(signaling);
+        assert!(RegistryCipher::from_config(&config).unwrap().is_enabled());
+    }
+}
@@ -0,0 +1,327 @@
+//! Envelope 重放保护
+//!
+//! 可选地强制校验 envelope 时间戳：
+//! - **新鲜度窗口**：拒绝早于 `max_age_secs` 或晚于 `max_future_skew_secs` 的 envelope，
+//!   可按 payload 类型豁免（如 `ping`）
+//! - **Nonce 跟踪**：对敏感 payload（如 `unregister_request`）记录 envelope_id，
+//!   窗口期内重复出现即视为重放
+//!
+//! 默认关闭，通过 `services.signaling.server.replay_protection.enabled` 启用
+
+use crate::tunnel;
+use actr_protocol::{SignalingEnvelope, actr_to_signaling, peer_to_signaling, signaling_envelope};
+use actrix_common::config::signaling::ReplayProtectionConfig;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// 超过该数量时清理过期 nonce
+const NONCE_PURGE_THRESHOLD: usize = 10_000;
+
+/// 重放保护拒绝原因
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("Envelope timestamp is {age_secs}s old, exceeds freshness window of {max_age_secs}s")]
+    Stale { age_secs: i64, max_age_secs: u64 },
+
+    #[error(
+        "Envelope timestamp is {ahead_secs}s in the future, exceeds allowed skew of {max_skew_secs}s"
+    )]
+    FromFuture { ahead_secs: i64, max_skew_secs: u64 },
+
+    #[error("Envelope {0} has already been processed")]
+    Replayed(String),
+}
+
+impl ReplayError {
+    /// EnvelopeError 使用的错误码
+    pub fn code(&self) -> u32 {
+        match self {
+            ReplayError::Stale { .. } | ReplayError::FromFuture { .. } => 400,
+            ReplayError::Replayed(_) => 409,
+        }
+    }
+}
+
+/// 获取 envelope 的 payload 类型名（snake_case，与配置中的名称对应）
+///
+/// 借用 `ErrorResponse` 传输的隧道消息按其保留 code 区分（见 [`crate::tunnel::code_name`]），
+/// 其余 `ErrorResponse` 为 "error"
+pub fn payload_kind(envelope: &SignalingEnvelope) -> &'static str {
+    match envelope.flow.as_ref() {
+        Some(signaling_envelope::Flow::PeerToServer(msg)) => match msg.payload.as_ref() {
+            Some(peer_to_signaling::Payload::RegisterRequest(_)) => "register_request",
+            None => "empty",
+        },
+        Some(signaling_envelope::Flow::ActrToServer(msg)) => match msg.payload.as_ref() {
+            Some(actr_to_signaling::Payload::Ping(_)) => "ping",
+            Some(actr_to_signaling::Payload::UnregisterRequest(_)) => "unregister_request",
+            Some(actr_to_signaling::Payload::CredentialUpdateRequest(_)) => {
+                "credential_update_request"
+            }
+            Some(actr_to_signaling::Payload::DiscoveryRequest(_)) => "discovery_request",
+            Some(actr_to_signaling::Payload::RouteCandidatesRequest(_)) => {
+                "route_candidates_request"
+            }
+            Some(actr_to_signaling::Payload::GetServiceSpecRequest(_)) => {
+                "get_service_spec_request"
+            }
+            Some(actr_to_signaling::Payload::SubscribeActrUpRequest(_)) => {
+                "subscribe_actr_up_request"
+            }
+            Some(actr_to_signaling::Payload::UnsubscribeActrUpRequest(_)) => {
+                "unsubscribe_actr_up_request"
+            }
+            Some(actr_to_signaling::Payload::Error(error)) => {
+                tunnel::code_name(error.code).unwrap_or("error")
+            }
+            None => "empty",
+        },
+        Some(signaling_envelope::Flow::ActrRelay(_)) => "actr_relay",
+        Some(signaling_envelope::Flow::EnvelopeError(_)) => "envelope_error",
+        _ => "unknown",
+    }
+}
+
+/// Envelope 重放保护器
+#[derive(Debug)]
+pub struct ReplayGuard {
+    max_age_secs: u64,
+    max_future_skew_secs: u64,
+    exempt_payloads: HashSet<String>,
+    nonce_payloads: HashSet<String>,
+    /// envelope_id -> 过期时间 (Unix 秒)
+    seen_nonces: Mutex<HashMap<String, i64>>,
+}
+
+impl ReplayGuard {
+    /// 根据配置创建重放保护器
+    pub fn new(config: &ReplayProtectionConfig) -> Self {
+        Self {
+            max_age_secs: config.max_age_secs,
+            max_future_skew_secs: config.max_future_skew_secs,
+            exempt_payloads: config.exempt_payloads.iter().cloned().collect(),
+            nonce_payloads: config.nonce_payloads.iter().cloned().collect(),
+            seen_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 校验 envelope，通过时记录其 nonce
+    pub async fn check(&self, envelope: &SignalingEnvelope) -> Result<(), ReplayError> {
+        self.check_at(envelope, chrono::Utc::now().timestamp())
+            .await
+    }
+
+    async fn check_at(&self, envelope: &SignalingEnvelope, now: i64) -> Result<(), ReplayError> {
+        let kind = payload_kind(envelope);
+
+        if !self.exempt_payloads.contains(kind) {
+            let sent_at = envelope.timestamp.seconds;
+            let age_secs = now - sent_at;
+            if age_secs > self.max_age_secs as i64 {
+                warn!(
+                    "🚫 Envelope {} ({}) 超出新鲜度窗口: age={}s",
+                    envelope.envelope_id, kind, age_secs
+                );
+                return Err(ReplayError::Stale {
+                    age_secs,
+                    max_age_secs: self.max_age_secs,
+                });
+            }
+            if -age_secs > self.max_future_skew_secs as i64 {
+                warn!(
+                    "🚫 Envelope {} ({}) 时间戳超前: ahead={}s",
+                    envelope.envelope_id, kind, -age_secs
+                );
+                return Err(ReplayError::FromFuture {
+                    ahead_secs: -age_secs,
+                    max_skew_secs: self.max_future_skew_secs,
+                });
+            }
+        }
+
+        if self.nonce_payloads.contains(kind) {
+            let mut seen = self.seen_nonces.lock().await;
+            if seen.len() >= NONCE_PURGE_THRESHOLD {
+                seen.retain(|_, expires_at| *expires_at > now);
+            }
+
+            if seen
+                .get(&envelope.envelope_id)
+                .is_some_and(|expires_at| *expires_at > now)
+            {
+                warn!("🚫 Envelope {} ({}) 重放被拒绝", envelope.envelope_id, kind);
+                return Err(ReplayError::Replayed(envelope.envelope_id.clone()));
+            }
+
+            // 窗口之外的重放会被新鲜度检查拒绝，nonce 只需保留一个窗口期
+            let expires_at = now + (self.max_age_secs + self.max_future_skew_secs) as i64;
+            seen.insert(envelope.envelope_id.clone(), expires_at);
+            debug!("记录 envelope nonce: {} ({})", envelope.envelope_id, kind);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::{
+        AIdCredential, ActrId, ActrToSignaling, ActrType, ErrorResponse, Ping, Realm,
+        UnregisterRequest,
+    };
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn config() -> ReplayProtectionConfig {
+        ReplayProtectionConfig {
+            enabled: true,
+            max_age_secs: 60,
+            max_future_skew_secs: 10,
+            exempt_payloads: vec!["ping".to_string()],
+            nonce_payloads: vec!["unregister_request".to_string()],
+        }
+    }
+
+    fn actr_envelope(
+        envelope_id: &str,
+        sent_at: i64,
+        payload: actr_to_signaling::Payload,
+    ) -> SignalingEnvelope {
+        let source = ActrId {
+            realm: Realm { realm_id: 1 },
+            serial_number: 1,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: "echo".to_string(),
+                version: None,
+            },
+        };
+        SignalingEnvelope {
+            envelope_version: 1,
+            envelope_id: envelope_id.to_string(),
+            reply_for: None,
+            timestamp: prost_types::Timestamp {
+                seconds: sent_at,
+                nanos: 0,
+            },
+            traceparent: None,
+            tracestate: None,
+            flow: Some(signaling_envelope::Flow::ActrToServer(ActrToSignaling {
+                source,
+                credential: AIdCredential::default(),
+                payload: Some(payload),
+            })),
+        }
+    }
+
+    fn unregister(envelope_id: &str, sent_at: i64) -> SignalingEnvelope {
+        actr_envelope(
+            envelope_id,
+            sent_at,
+            actr_to_signaling::Payload::UnregisterRequest(UnregisterRequest::default()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_freshness_window() {
+        let guard = ReplayGuard::new(&config());
+
+        assert!(
+            guard
+                .check_at(&unregister("a", NOW - 30), NOW)
+                .await
+                .is_ok()
+        );
+        assert_eq!(
+            guard.check_at(&unregister("b", NOW - 61), NOW).await,
+            Err(ReplayError::Stale {
+                age_secs: 61,
+                max_age_secs: 60
+            })
+        );
+        let err = guard
+            .check_at(&unregister("c", NOW + 11), NOW)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ReplayError::FromFuture { ahead_secs: 11, .. }
+        ));
+        assert_eq!(err.code(), 400);
+    }
+
+    #[test]
+    fn test_payload_kind_distinguishes_tunneled_messages() {
+        let error = |code| {
+            actr_envelope(
+                "e",
+                NOW,
+                actr_to_signaling::Payload::Error(ErrorResponse {
+                    code,
+                    message: String::new(),
+                }),
+            )
+        };
+        assert_eq!(
+            payload_kind(&error(tunnel::CONNECTION_REPORT_CODE)),
+            "connection_report"
+        );
+        assert_eq!(payload_kind(&error(tunnel::UPDATE_ACL_CODE)), "update_acl");
+        assert_eq!(
+            payload_kind(&error(tunnel::PRESENCE_SNAPSHOT_CODE)),
+            "presence_snapshot"
+        );
+        assert_eq!(
+            payload_kind(&error(tunnel::RESUMPTION_TOKEN_CODE)),
+            "resumption_token"
+        );
+        assert_eq!(
+            payload_kind(&error(tunnel::DRAIN_REDIRECT_CODE)),
+            "drain_redirect"
+        );
+        assert_eq!(payload_kind(&error(500)), "error");
+
+        // 每个保留 code 的类型名互不相同，也不与普通错误混淆
+        let kinds: HashSet<_> = tunnel::TUNNEL_CODES
+            .iter()
+            .map(|(code, _)| payload_kind(&error(*code)))
+            .collect();
+        assert_eq!(kinds.len(), tunnel::TUNNEL_CODES.len());
+        assert!(!kinds.contains("error"));
+    }
+
+    #[tokio::test]
+    async fn test_exempt_payload_skips_freshness() {
+        let guard = ReplayGuard::new(&config());
+        let stale_ping = actr_envelope(
+            "ping-1",
+            NOW - 3600,
+            actr_to_signaling::Payload::Ping(Ping::default()),
+        );
+        assert!(guard.check_at(&stale_ping, NOW).await.is_ok());
+        // ping 不在 nonce 列表中，重复不会被拒绝
+        assert!(guard.check_at(&stale_ping, NOW).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_nonce_replay_rejected() {
+        let guard = ReplayGuard::new(&config());
+        let envelope = unregister("unreg-1", NOW);
+
+        assert!(guard.check_at(&envelope, NOW).await.is_ok());
+        let err = guard.check_at(&envelope, NOW + 1).await.unwrap_err();
+        assert_eq!(err, ReplayError::Replayed("unreg-1".to_string()));
+        assert_eq!(err.code(), 409);
+
+        // 不同 envelope_id 不受影响
+        assert!(
+            guard
+                .check_at(&unregister("unreg-2", NOW), NOW)
+                .await
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! 断线重连会话恢复 (Resumption)
+//!
+//! URL 身份重连只会替换旧连接，断开期间发往该 Actor 的消息会丢失，
+//! 客户端也无法确认 Presence 订阅是否仍然有效。启用后：
+//! 1. 注册成功时下发恢复 token（[`RESUMPTION_TOKEN_CODE`] 通知）
+//! 2. 连接断开后在 `window_secs` 内保留该 Actor 的 Presence 订阅，并缓存发往它的中继消息与上线事件
+//! 3. 客户端携带 URL 身份与恢复 token（`X-Actr-Resume-Token` 头或 `resume_token` 参数）重连时，
+//!    在同一把连接表写锁内恢复会话并取出缓存，缓存消息先于任何新消息发出
+//!
+//! 超出窗口或主动注销的会话被丢弃，其 Presence 订阅一并清理。
+//!
+//! # 下发方式
+//! 与 [`crate::server_notice`] 相同，通过不带 `reply_for` 的 `ErrorResponse` 下发：
+//! `code` 为 [`RESUMPTION_TOKEN_CODE`]，`message` 为 [`RESUMPTION_TOKEN_PREFIX`] 加 JSON 编码的
+//! [`ResumptionNotice`]。恢复成功时再次下发该通知（`resumed = true`），
+//! 重连后未收到该通知的客户端应视为会话已丢失并重新订阅。
+
+use crate::tunnel;
+use actr_protocol::{ActrId, ErrorResponse, SignalingEnvelope};
+use actrix_common::config::signaling::ResumptionConfig;
+use actrix_common::metrics::SIGNALING_SESSION_RESUMPTIONS;
+use actrix_common::util::constant_time_eq;
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// 恢复 token 通知使用的 ErrorResponse code
+pub const RESUMPTION_TOKEN_CODE: u32 = tunnel::RESUMPTION_TOKEN.code;
+
+/// 通知 message 前缀，其后为 JSON 编码的 [`ResumptionNotice`]
+pub const RESUMPTION_TOKEN_PREFIX: &str = tunnel::RESUMPTION_TOKEN.prefix;
+
+/// 重连时携带恢复 token 的请求头
+pub const RESUME_TOKEN_HEADER: &str = "x-actr-resume-token";
+
+/// 过期会话的清理间隔（秒）
+pub const SWEEP_INTERVAL_SECS: u64 = 5;
+
+/// 下发给客户端的恢复 token 通知
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumptionNotice {
+    pub token: String,
+    /// 断开后会话保留时长（秒）
+    pub window_secs: u64,
+    /// 本次连接是否恢复了已有会话
+    pub resumed: bool,
+    /// 恢复时重放的缓存消息数
+    pub replayed: usize,
+}
+
+impl ResumptionNotice {
+    /// 编码为下发给客户端的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::RESUMPTION_TOKEN.encode(self)
+    }
+}
+
+/// 从握手请求中取出恢复 token（请求头优先）
+pub fn resume_token(headers: &HeaderMap, params: &HashMap<String, String>) -> Option<String> {
+    headers
+        .get(RESUME_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .or(params.get("resume_token").map(String::as_str))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+/// 会话恢复失败原因
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ResumeError {
+    #[error("no resumable session for actor")]
+    UnknownSession,
+    #[error("resumption token mismatch")]
+    TokenMismatch,
+    #[error("resumption window expired")]
+    Expired,
+}
+
+impl ResumeError {
+    fn metric_label(&self) -> &'static str {
+        match self {
+            Self::Expired => "expired",
+            _ => "rejected",
+        }
+    }
+}
+
+/// 断开期间保留的状态
+#[derive(Debug)]
+struct Parked {
+    deadline: Instant,
+    /// 缓存的 SignalingEnvelope（重放时写入序号后再编码）
+    queue: VecDeque<SignalingEnvelope>,
+}
+
+#[derive(Debug)]
+struct Session {
+    token: String,
+    actor_id: ActrId,
+    /// None 表示连接在线
+    parked: Option<Parked>,
+}
+
+/// Actor 会话键，与中继按 realm + serial 查找目标保持一致
+type SessionKey = (u32, u64);
+
+fn session_key(actor_id: &ActrId) -> SessionKey {
+    (actor_id.realm.realm_id, actor_id.serial_number)
+}
+
+/// 会话恢复管理器
+pub struct ResumptionManager {
+    window: Duration,
+    max_queued_messages: usize,
+    sessions: Mutex<HashMap<SessionKey, Session>>,
+}
+
+impl ResumptionManager {
+    pub fn new(config: &ResumptionConfig) -> Self {
+        Self {
+            window: Duration::from_secs(config.window_secs),
+            max_queued_messages: config.max_queued_messages,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 会话保留时长（秒）
+    pub fn window_secs(&self) -> u64 {
+        self.window.as_secs()
+    }
+
+    fn sessions(&self) -> std::sync::MutexGuard<'_, HashMap<SessionKey, Session>> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 为注册成功的 Actor 签发恢复 token，替换该 Actor 已有的会话
+    pub fn issue(&self, actor_id: &ActrId) -> String {
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+        self.sessions().insert(
+            session_key(actor_id),
+            Session {
+                token: token.clone(),
+                actor_id: actor_id.clone(),
+                parked: None,
+            },
+        );
+        token
+    }
+
+    /// 连接断开时保留会话，返回该 Actor 是否持有可恢复的会话
+    pub fn park(&self, actor_id: &ActrId) -> bool {
+        let deadline = Instant::now() + self.window;
+        match self.sessions().get_mut(&session_key(actor_id)) {
+            Some(session) => {
+                session.parked.get_or_insert_with(|| Parked {
+                    deadline,
+                    queue: VecDeque::new(),
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 缓存发往断开中 Actor 的消息，超出上限时丢弃最旧的消息
+    ///
+    /// 调用方需持有连接表读锁并确认目标不在线，保证与 [`Self::resume`] 互斥
+    pub fn enqueue(&self, actor_id: &ActrId, envelope: SignalingEnvelope) -> bool {
+        let now = Instant::now();
+        let mut sessions = self.sessions();
+        let Some(parked) = sessions
+            .get_mut(&session_key(actor_id))
+            .and_then(|session| session.parked.as_mut())
+            .filter(|parked| parked.deadline > now)
+        else {
+            return false;
+        };
+
+        if self.max_queued_messages == 0 {
+            return false;
+        }
+        while parked.queue.len() >= self.max_queued_messages {
+            parked.queue.pop_front();
+        }
+        parked.queue.push_back(envelope);
+        true
+    }
+
+    /// 恢复会话，返回断开期间缓存的消息
+    ///
+    /// 旧连接尚未清理（会话仍在线）时视为恢复成功且没有缓存消息。
+    /// 调用方需持有连接表写锁，并在释放前登记新连接；恢复失败时应调用 [`Self::remove`]，
+    /// 避免之后的过期清理误删新连接的订阅。
+    pub fn resume(
+        &self,
+        token: &str,
+        actor_id: &ActrId,
+    ) -> Result<Vec<SignalingEnvelope>, ResumeError> {
+        let result = self.try_resume(token, actor_id);
+        let label = match &result {
+            Ok(_) => "resumed",
+            Err(e) => e.metric_label(),
+        };
+        SIGNALING_SESSION_RESUMPTIONS
+            .with_label_values(&[label])
+            .inc();
+        result
+    }
+
+    fn try_resume(
+        &self,
+        token: &str,
+        actor_id: &ActrId,
+    ) -> Result<Vec<SignalingEnvelope>, ResumeError> {
+        let key = session_key(actor_id);
+        let mut sessions = self.sessions();
+        let session = sessions.get_mut(&key).ok_or(ResumeError::UnknownSession)?;
+
+        if session.actor_id != *actor_id {
+            return Err(ResumeError::UnknownSession);
+        }
+        if !constant_time_eq(session.token.as_bytes(), token.as_bytes()) {
+            return Err(ResumeError::TokenMismatch);
+        }
+
+        match session.parked.take() {
+            None => Ok(Vec::new()),
+            Some(parked) if parked.deadline > Instant::now() => Ok(parked.queue.into()),
+            Some(_) => {
+                sessions.remove(&key);
+                Err(ResumeError::Expired)
+            }
+        }
+    }
+
+    /// 当前 token（供恢复成功后重新下发）
+    pub fn token_of(&self, actor_id: &ActrId) -> Option<String> {
+        self.sessions()
+            .get(&session_key(actor_id))
+            .map(|session| session.token.clone())
+    }
+
+    /// 丢弃 Actor 的会话（主动注销）
+    pub fn remove(&self, actor_id: &ActrId) {
+        self.sessions().remove(&session_key(actor_id));
+    }
+
+    /// 清理超出保留窗口的会话，返回需要清理 Presence 订阅的 Actor
+    pub fn sweep_expired(&self) -> Vec<ActrId> {
+        let now = Instant::now();
+        let mut expired = Vec::new();
+        self.sessions().retain(|_, session| {
+            let keep = session
+                .parked
+                .as_ref()
+                .is_none_or(|parked| parked.deadline > now);
+            if !keep {
+                expired.push(session.actor_id.clone());
+            }
+            keep
+        });
+        if !expired.is_empty() {
+            SIGNALING_SESSION_RESUMPTIONS
+                .with_label_values(&["expired"])
+                .inc_by(expired.len() as u64);
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::{ActrType, Realm};
+
+    fn actor(serial_number: u64) -> ActrId {
+        ActrId {
+            realm: Realm { realm_id: 1 },
+            serial_number,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: "echo".to_string(),
+                version: None,
+            },
+        }
+    }
+
+    fn envelope(id: &str) -> SignalingEnvelope {
+        SignalingEnvelope {
+            envelope_id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn manager(window_secs: u64, max_queued_messages: usize) -> ResumptionManager {
+        ResumptionManager::new(&ResumptionConfig {
+            enabled: true,
+            window_secs,
+            max_queued_messages,
+        })
+    }
+
+    #[test]
+    fn test_park_enqueue_and_resume() {
+        let manager = manager(60, 2);
+        let a = actor(1);
+
+        // 未签发 token 的 Actor 不保留会话
+        assert!(!manager.park(&a));
+        assert!(!manager.enqueue(&a, envelope("0")));
+
+        let token = manager.issue(&a);
+        // 在线时不缓存
+        assert!(!manager.enqueue(&a, envelope("0")));
+
+        assert!(manager.park(&a));
+        assert!(manager.enqueue(&a, envelope("1")));
+        assert!(manager.enqueue(&a, envelope("2")));
+        assert!(manager.enqueue(&a, envelope("3")));
+
+        assert_eq!(manager.resume("wrong", &a), Err(ResumeError::TokenMismatch));
+        // 超出上限时丢弃最旧的消息
+        assert_eq!(
+            manager.resume(&token, &a).unwrap(),
+            vec![envelope("2"), envelope("3")]
+        );
+        // 恢复后会话回到在线状态，token 保持不变
+        assert_eq!(manager.token_of(&a), Some(token.clone()));
+        assert!(manager.resume(&token, &a).unwrap().is_empty());
+
+        assert_eq!(
+            manager.resume(&token, &actor(2)),
+            Err(ResumeError::UnknownSession)
+        );
+    }
+
+    #[test]
+    fn test_expired_sessions_are_swept() {
+        let manager = manager(0, 8);
+        let a = actor(1);
+        let token = manager.issue(&a);
+        let online = actor(2);
+        manager.issue(&online);
+
+        assert!(manager.park(&a));
+        assert!(!manager.enqueue(&a, envelope("1")));
+        assert_eq!(manager.sweep_expired(), vec![a.clone()]);
+        assert_eq!(manager.resume(&token, &a), Err(ResumeError::UnknownSession));
+        // 在线会话不受影响
+        assert!(manager.token_of(&online).is_some());
+    }
+
+    #[test]
+    fn test_resume_token_from_header_or_query() {
+        let mut headers = HeaderMap::new();
+        let mut params = HashMap::new();
+        assert_eq!(resume_token(&headers, &params), None);
+
+        params.insert("resume_token".to_string(), "from-query".to_string());
+        assert_eq!(
+            resume_token(&headers, &params).as_deref(),
+            Some("from-query")
+        );
+
+        headers.insert(RESUME_TOKEN_HEADER, "from-header".parse().unwrap());
+        assert_eq!(
+            resume_token(&headers, &params).as_deref(),
+            Some("from-header")
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! 中继 SDP 校验与清洗
+//!
+//! 中继路径默认原样转发 SDP。启用后在转发前：
+//! - 拒绝格式错误或超出大小限制的 SDP（`ErrorResponse` 400 / 413）
+//! - 剥离不允许的 ICE candidate（按类型，或私有地址 / mDNS 主机名）
+//! - 按 Realm 的编解码器白名单剥离 audio/video 段中不允许的 payload type
+//!
+//! trickle ICE candidate 使用相同的 candidate 规则，不允许的 candidate 直接丢弃。
+
+use actrix_common::config::signaling::SdpValidationConfig;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use thiserror::Error;
+
+/// SDP 校验失败原因
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SdpError {
+    #[error("SDP is {size} bytes, exceeds limit of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+
+    #[error("Malformed SDP at line {line}: {reason}")]
+    Malformed { line: usize, reason: String },
+
+    #[error("Malformed ICE candidate: {0}")]
+    MalformedCandidate(String),
+
+    #[error("No allowed codecs left in m={media} section")]
+    NoAllowedCodecs { media: String },
+}
+
+impl SdpError {
+    /// ErrorResponse 使用的错误码
+    pub fn code(&self) -> u32 {
+        match self {
+            SdpError::TooLarge { .. } => 413,
+            _ => 400,
+        }
+    }
+}
+
+/// 清洗后的 SDP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SanitizedSdp {
+    pub sdp: String,
+    /// 被剥离的 candidate 数量
+    pub stripped_candidates: usize,
+    /// 被剥离的 payload type 数量
+    pub stripped_codecs: usize,
+}
+
+/// 解析后的 ICE candidate 关键字段
+struct Candidate<'a> {
+    address: &'a str,
+    kind: &'a str,
+}
+
+/// 中继 SDP 校验器
+#[derive(Debug)]
+pub struct SdpSanitizer {
+    max_sdp_bytes: usize,
+    blocked_candidate_types: HashSet<String>,
+    strip_private_candidates: bool,
+    /// 小写的默认编解码器白名单（空表示不限制）
+    allowed_codecs: HashSet<String>,
+    realm_codecs: HashMap<u32, HashSet<String>>,
+}
+
+fn lowercase_set(values: &[String]) -> HashSet<String> {
+    values.iter().map(|v| v.to_ascii_lowercase()).collect()
+}
+
+impl SdpSanitizer {
+    /// 根据配置创建校验器
+    pub fn new(config: &SdpValidationConfig) -> Self {
+        Self {
+            max_sdp_bytes: config.max_sdp_bytes,
+            blocked_candidate_types: lowercase_set(&config.blocked_candidate_types),
+            strip_private_candidates: config.strip_private_candidates,
+            allowed_codecs: lowercase_set(&config.allowed_codecs),
+            realm_codecs: config
+                .realm_codecs
+                .iter()
+                .map(|r| (r.realm_id, lowercase_set(&r.codecs)))
+                .collect(),
+        }
+    }
+
+    /// 校验并清洗 `realm_id` 下中继的 SDP
+    pub fn sanitize(&self, realm_id: u32, sdp: &str) -> Result<SanitizedSdp, SdpError> {
+        if sdp.len() > self.max_sdp_bytes {
+            return Err(SdpError::TooLarge {
+                size: sdp.len(),
+                max: self.max_sdp_bytes,
+            });
+        }
+
+        let lines: Vec<&str> = sdp
+            .split('\n')
+            .map(|l| l.strip_suffix('\r').unwrap_or(l))
+            .collect();
+        // 末尾的换行产生一个空行
+        let lines = match lines.split_last() {
+            Some((last, rest)) if last.is_empty() => rest,
+            _ => &lines[..],
+        };
+
+        validate_lines(lines)?;
+
+        let allowed_codecs = self
+            .realm_codecs
+            .get(&realm_id)
+            .unwrap_or(&self.allowed_codecs);
+
+        let mut output = Vec::with_capacity(lines.len());
+        let mut stripped_candidates = 0;
+        let mut stripped_codecs = 0;
+
+        // 第一个 m= 之前为会话级，之后每个 m= 开始一个媒体段
+        let first_media = lines
+            .iter()
+            .position(|l| l.starts_with("m="))
+            .unwrap_or(lines.len());
+        let mut sections: Vec<(usize, &[&str])> = vec![(0, &lines[..first_media])];
+        let mut start = first_media;
+        while start < lines.len() {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|l| l.starts_with("m="))
+                .map(|p| start + 1 + p)
+                .unwrap_or(lines.len());
+            sections.push((start, &lines[start..end]));
+            start = end;
+        }
+
+        for (offset, section_lines) in sections {
+            let mut section: Vec<String> = Vec::with_capacity(section_lines.len());
+            for (i, line) in section_lines.iter().enumerate() {
+                if let Some(candidate) = line.strip_prefix("a=")
+                    && candidate.starts_with("candidate:")
+                {
+                    let parsed =
+                        parse_candidate(candidate).map_err(|reason| SdpError::Malformed {
+                            line: offset + i + 1,
+                            reason,
+                        })?;
+                    if !self.candidate_allowed(&parsed) {
+                        stripped_candidates += 1;
+                        continue;
+                    }
+                }
+                section.push(line.to_string());
+            }
+
+            if !allowed_codecs.is_empty() && section[0].starts_with("m=") {
+                stripped_codecs += filter_codecs(&mut section, allowed_codecs)?;
+            }
+            output.extend(section);
+        }
+
+        let mut sdp = output.join("\r\n");
+        sdp.push_str("\r\n");
+        Ok(SanitizedSdp {
+            sdp,
+            stripped_candidates,
+            stripped_codecs,
+        })
+    }
+
+    /// 判断 trickle ICE candidate 是否允许转发
+    ///
+    /// 空字符串（end-of-candidates）始终允许
+    pub fn allow_candidate(&self, candidate: &str) -> Result<bool, SdpError> {
+        let candidate = candidate.strip_prefix("a=").unwrap_or(candidate).trim();
+        if candidate.is_empty() {
+            return Ok(true);
+        }
+        let parsed = parse_candidate(candidate).map_err(SdpError::MalformedCandidate)?;
+        Ok(self.candidate_allowed(&parsed))
+    }
+
+    fn candidate_allowed(&self, candidate: &Candidate<'_>) -> bool {
+        if self
+            .blocked_candidate_types
+            .contains(&candidate.kind.to_ascii_lowercase())
+        {
+            return false;
+        }
+        if self.strip_private_candidates {
+            return match candidate.address.parse::<IpAddr>() {
+                Ok(ip) => !is_private_address(&ip),
+                // mDNS 主机名（*.local）只在本地网络内可解析
+                Err(_) => !candidate.address.ends_with(".local"),
+            };
+        }
+        true
+    }
+}
+
+/// 校验 SDP 的基本结构：`v=0` 开头，每行为 `<type>=<value>`，包含 `o=` 与 `s=`
+fn validate_lines(lines: &[&str]) -> Result<(), SdpError> {
+    let malformed = |line: usize, reason: &str| SdpError::Malformed {
+        line,
+        reason: reason.to_string(),
+    };
+
+    if lines.first() != Some(&"v=0") {
+        return Err(malformed(1, "SDP must start with v=0"));
+    }
+
+    for (i, line) in lines.iter().enumerate() {
+        let bytes = line.as_bytes();
+        if bytes.len() < 2 || !bytes[0].is_ascii_lowercase() || bytes[1] != b'=' {
+            return Err(malformed(i + 1, "expected <type>=<value>"));
+        }
+        if let Some(media) = line.strip_prefix("m=")
+            && media.split_whitespace().count() < 4
+        {
+            return Err(malformed(
+                i + 1,
+                "m= line requires media, port, proto and formats",
+            ));
+        }
+    }
+
+    let session_end = lines
+        .iter()
+        .position(|l| l.starts_with("m="))
+        .unwrap_or(lines.len());
+    for required in ["o=", "s="] {
+        if !lines[..session_end].iter().any(|l| l.starts_with(required)) {
+            return Err(malformed(
+                session_end,
+                &format!("missing {required} line in session section"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 解析 `candidate:<foundation> <component> <transport> <priority> <address> <port> typ <type> ...`
+fn parse_candidate(candidate: &str) -> Result<Candidate<'_>, String> {
+    let fields: Vec<&str> = candidate
+        .strip_prefix("candidate:")
+        .ok_or_else(|| "expected candidate: prefix".to_string())?
+        .split_whitespace()
+        .collect();
+    if fields.len() < 8 || fields[6] != "typ" {
+        return Err(format!("invalid candidate attribute: {candidate}"));
+    }
+    Ok(Candidate {
+        address: fields[4],
+        kind: fields[7],
+    })
+}
+
+pub(crate) fn is_private_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let octets = v4.octets();
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                // 100.64.0.0/10 (CGNAT)
+                || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                // fc00::/7 (ULA) 与 fe80::/10 (链路本地)
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// 未出现在 rtpmap 中的静态 payload type（RFC 3551）
+fn static_codec_name(payload_type: &str) -> Option<&'static str> {
+    match payload_type {
+        "0" => Some("pcmu"),
+        "8" => Some("pcma"),
+        "9" => Some("g722"),
+        _ => None,
+    }
+}
+
+/// 剥离媒体段中不在白名单内的 payload type，返回剥离数量
+///
+/// 非 RTP 媒体段（如 DataChannel）不受影响
+fn filter_codecs(section: &mut Vec<String>, allowed: &HashSet<String>) -> Result<usize, SdpError> {
+    let m_line: Vec<String> = section[0].split_whitespace().map(str::to_string).collect();
+    let media = m_line[0].trim_start_matches("m=").to_string();
+    if media != "audio" && media != "video" {
+        return Ok(0);
+    }
+
+    let codec_names: HashMap<String, String> = section
+        .iter()
+        .filter_map(|l| l.strip_prefix("a=rtpmap:"))
+        .filter_map(|l| {
+            let (pt, rest) = l.split_once(' ')?;
+            let name = rest.split('/').next()?;
+            Some((pt.to_string(), name.to_ascii_lowercase()))
+        })
+        .collect();
+
+    let (kept, removed): (Vec<&String>, Vec<&String>) = m_line[3..].iter().partition(|pt| {
+        codec_names
+            .get(pt.as_str())
+            .map(String::as_str)
+            .or_else(|| static_codec_name(pt))
+            .is_some_and(|name| allowed.contains(name))
+    });
+    if kept.is_empty() {
+        return Err(SdpError::NoAllowedCodecs { media });
+    }
+    if removed.is_empty() {
+        return Ok(0);
+    }
+
+    let removed: HashSet<&str> = removed.iter().map(|pt| pt.as_str()).collect();
+    let new_m_line = m_line[..3]
+        .iter()
+        .chain(kept.iter().copied())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    section.retain(|line| {
+        let payload_type = ["a=rtpmap:", "a=fmtp:", "a=rtcp-fb:"]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            .and_then(|rest| rest.split([' ', '\t']).next());
+        !payload_type.is_some_and(|pt| removed.contains(pt))
+    });
+    section[0] = new_m_line;
+
+    Ok(removed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::config::signaling::RealmCodecAllowlist;
+
+    const OFFER: &str = "v=0\r\n\
+        o=- 4611731400430051336 2 IN IP4 127.0.0.1\r\n\
+        s=-\r\n\
+        t=0 0\r\n\
+        m=audio 9 UDP/TLS/RTP/SAVPF 111 0 8\r\n\
+        c=IN IP4 0.0.0.0\r\n\
+        a=candidate:1 1 udp 2122260223 192.168.1.10 54400 typ host\r\n\
+        a=candidate:2 1 udp 1686052607 203.0.113.7 54400 typ srflx raddr 192.168.1.10 rport 54400\r\n\
+        a=candidate:3 1 udp 2122260223 4b3c.local 54401 typ host\r\n\
+        a=rtpmap:111 opus/48000/2\r\n\
+        a=fmtp:111 minptime=10;useinbandfec=1\r\n\
+        a=rtcp-fb:111 transport-cc\r\n\
+        m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+        a=sctp-port:5000\r\n";
+
+    fn sanitizer(config: SdpValidationConfig) -> SdpSanitizer {
+        SdpSanitizer::new(&SdpValidationConfig {
+            enabled: true,
+            ..config
+        })
+    }
+
+    #[test]
+    fn test_default_config_passes_sdp_through() {
+        let result = sanitizer(SdpValidationConfig::default())
+            .sanitize(1, OFFER)
+            .unwrap();
+        assert_eq!(result.sdp, OFFER);
+        assert_eq!(result.stripped_candidates, 0);
+        assert_eq!(result.stripped_codecs, 0);
+    }
+
+    #[test]
+    fn test_strip_private_and_blocked_candidates() {
+        let result = sanitizer(SdpValidationConfig {
+            strip_private_candidates: true,
+            ..Default::default()
+        })
+        .sanitize(1, OFFER)
+        .unwrap();
+        assert_eq!(result.stripped_candidates, 2);
+        assert!(result.sdp.contains("203.0.113.7"));
+        assert!(!result.sdp.contains("192.168.1.10 54400 typ host"));
+        assert!(!result.sdp.contains(".local"));
+
+        let result = sanitizer(SdpValidationConfig {
+            blocked_candidate_types: vec!["SRFLX".to_string()],
+            ..Default::default()
+        })
+        .sanitize(1, OFFER)
+        .unwrap();
+        assert_eq!(result.stripped_candidates, 1);
+        assert!(!result.sdp.contains("typ srflx"));
+    }
+
+    #[test]
+    fn test_codec_allowlist_per_realm() {
+        let sanitizer = sanitizer(SdpValidationConfig {
+            allowed_codecs: vec!["opus".to_string(), "PCMU".to_string()],
+            realm_codecs: vec![RealmCodecAllowlist {
+                realm_id: 7,
+                codecs: vec!["VP8".to_string()],
+            }],
+            ..Default::default()
+        });
+
+        let result = sanitizer.sanitize(1, OFFER).unwrap();
+        assert_eq!(result.stripped_codecs, 1);
+        assert!(result.sdp.contains("m=audio 9 UDP/TLS/RTP/SAVPF 111 0\r\n"));
+        assert!(result.sdp.contains("a=fmtp:111"));
+        // DataChannel 段不受白名单影响
+        assert!(result.sdp.contains("m=application 9"));
+
+        assert_eq!(
+            sanitizer.sanitize(7, OFFER),
+            Err(SdpError::NoAllowedCodecs {
+                media: "audio".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_malformed_sdp_is_rejected() {
+        let sanitizer = sanitizer(SdpValidationConfig::default());
+
+        let err = sanitizer
+            .sanitize(1, "o=- 1 1 IN IP4 0.0.0.0\r\n")
+            .unwrap_err();
+        assert_eq!(err.code(), 400);
+
+        let err = sanitizer
+            .sanitize(1, "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nbogus\r\n")
+            .unwrap_err();
+        assert!(matches!(err, SdpError::Malformed { line: 4, .. }));
+
+        let err = sanitizer
+            .sanitize(1, "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nm=audio 9\r\n")
+            .unwrap_err();
+        assert!(matches!(err, SdpError::Malformed { line: 4, .. }));
+
+        let err = sanitizer
+            .sanitize(
+                1,
+                "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nm=audio 9 RTP/AVP 0\r\na=candidate:1 1 udp\r\n",
+            )
+            .unwrap_err();
+        assert!(matches!(err, SdpError::Malformed { line: 5, .. }));
+
+        let small = SdpSanitizer::new(&SdpValidationConfig {
+            max_sdp_bytes: 16,
+            ..Default::default()
+        });
+        assert_eq!(small.sanitize(1, OFFER).unwrap_err().code(), 413);
+    }
+
+    #[test]
+    fn test_trickle_candidate_filtering() {
+        let sanitizer = sanitizer(SdpValidationConfig {
+            strip_private_candidates: true,
+            ..Default::default()
+        });
+
+        assert_eq!(sanitizer.allow_candidate(""), Ok(true));
+        assert_eq!(
+            sanitizer.allow_candidate("candidate:1 1 udp 2122252543 10.0.0.5 54400 typ host"),
+            Ok(false)
+        );
+        assert_eq!(
+            sanitizer.allow_candidate("a=candidate:1 1 udp 2122252543 fd00::1 54400 typ host"),
+            Ok(false)
+        );
+        assert_eq!(
+            sanitizer.allow_candidate("candidate:2 1 udp 1686052607 198.51.100.9 54400 typ srflx"),
+            Ok(true)
+        );
+        assert!(sanitizer.allow_candidate("candidate:1 1 udp").is_err());
+    }
+}
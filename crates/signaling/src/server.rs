@@ -18,6 +18,10 @@
 //!   - 集成 GlobalCompatibilityCache 实现实时兼容性计算
 //!   - 精确匹配快速路径优化
 //! - ✅ Presence 订阅 (`SubscribeActrUpRequest` / `ActrUpEvent`)
+//! - ✅ Presence 快照（目标类型全部实例的在线状态，见 [`crate::presence_snapshot`]）
+//! - ✅ 断线重连会话恢复（恢复 token，见 [`crate::resumption`]）
+//! - ✅ 过载降级（低优先级请求返回 503，见 [`crate::load_shed`]）
+//! - ✅ 节点排空（新注册返回重定向提示，见 [`crate::drain`]）
 //! - ✅ Credential 刷新 (`CredentialUpdateRequest` - 通过 AIS 客户端)
 //! - ✅ 负载指标存储 (`handle_ping()` - 存储到 ServiceRegistry 用于负载均衡)
 //!
@@ -38,9 +42,13 @@ use actr_protocol::{
     actr_to_signaling, peer_to_signaling, register_response, signaling_envelope, signaling_to_actr,
 };
 use actrix_common::aid::credential::validator::AIdCredentialValidator;
+use actrix_common::config::signaling::{ConnectionLimitsConfig, RateLimitConfig};
 use actrix_common::realm::Realm as RealmEntity;
+use actrix_common::realm::RealmQuota;
+use actrix_common::realm::usage;
+use actrix_common::util::NetworkEmulator;
+use bytes::Bytes;
 use futures_util::{SinkExt, StreamExt};
-use prost::Message as ProstMessage;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -48,25 +56,38 @@ use tracing::{debug, error, info, info_span, warn};
 use uuid::Uuid;
 
 // Axum WebSocket
-use axum::extract::ws::{Message as WsMessage, WebSocket};
+use axum::extract::ws::{CloseFrame, Message as WsMessage, WebSocket, close_code};
 
 use crate::actr_type_utils::type_key;
-use crate::load_balancer::LoadBalancer;
+use crate::codec::{decode_envelope, encode_envelope};
+use crate::compression::Compressor;
+use crate::duplicate_identity::{
+    DuplicateDecision, DuplicateIdentityGuard, ExistingConnection, REJECTED_CLOSE_REASON,
+    REPLACED_CLOSE_REASON,
+};
+use crate::load_balancer::{LoadBalancer, RankOptions};
+use crate::outbound::{OutboundSender, outbound_channel};
 use crate::presence::PresenceManager;
 use crate::service_registry::ServiceRegistry;
+use crate::sharded_map::ShardedMap;
 #[cfg(feature = "opentelemetry")]
-use crate::trace::{extract_trace_context, inject_trace_context};
+use crate::trace::{current_trace_context, extract_trace_context, inject_trace_context};
 use tracing::Instrument;
 #[cfg(feature = "opentelemetry")]
 use tracing::instrument;
 
+/// 服务端生成的 envelope 协议版本
+pub const ENVELOPE_VERSION: u32 = 1;
+
 /// 信令服务器状态
 #[derive(Debug)]
 pub struct SignalingServer {
-    /// 已连接的客户端
-    pub clients: Arc<RwLock<HashMap<String, ClientConnection>>>,
-    /// 通过 ActorId 查找 client_id 的索引
-    pub actor_id_index: Arc<RwLock<HashMap<ActrId, String>>>,
+    /// 已连接的客户端（按 client_id 分片）
+    pub clients: Arc<ShardedMap<String, ClientConnection>>,
+    /// 通过 ActorId 查找 client_id 的索引（按 ActrId 分片）
+    ///
+    /// 同时持有两者的锁时，先获取索引分片再获取连接分片
+    pub actor_id_index: Arc<ShardedMap<ActrId, String>>,
     /// 服务注册表
     pub service_registry: Arc<RwLock<ServiceRegistry>>,
     /// Presence 订阅管理器
@@ -79,6 +100,36 @@ pub struct SignalingServer {
     pub connection_rate_limiter: Option<Arc<crate::ratelimit::ConnectionRateLimiter>>,
     /// 消息速率限制器
     pub message_rate_limiter: Option<Arc<crate::ratelimit::MessageRateLimiter>>,
+    /// Realm 聚合速率限制器
+    pub realm_rate_limiter: Option<Arc<crate::ratelimit::RealmRateLimiter>>,
+    /// 按 ActrType 的流量统计（用于容量规划）
+    pub traffic_stats: Option<Arc<crate::traffic_stats::TrafficStats>>,
+    /// Envelope 重放保护
+    pub replay_guard: Option<Arc<crate::replay::ReplayGuard>>,
+    /// 单连接消息大小与发送队列限制
+    pub limits: ConnectionLimitsConfig,
+    /// 中继消息弱网模拟（仅开发环境）
+    pub network_emulator: Option<Arc<NetworkEmulator>>,
+    /// 局域网直连模式（仅开发环境，见 [`crate::lan_discovery`]）
+    pub lan_discovery: bool,
+    /// WebSocket 消息压缩（None 表示未启用，仅对协商了子协议的连接生效）
+    pub compressor: Option<crate::compression::Compressor>,
+    /// 服务类型依赖跟踪（用于 ServiceSpec 不兼容变更通知）
+    pub spec_dependencies: Option<Arc<crate::spec_notice::SpecDependencyTracker>>,
+    /// 中继 SDP 校验与清洗
+    pub sdp_sanitizer: Option<Arc<crate::sdp_filter::SdpSanitizer>>,
+    /// 外部授权钩子（注册、发现、中继，与内置 ACL 同时生效）
+    pub authz_gate: Option<Arc<crate::authz_hook::AuthzGate>>,
+    /// 按 Realm 聚合的 ICE 连接上报
+    pub connection_reports: Arc<crate::connection_report::ConnectionReportStats>,
+    /// 断线重连会话恢复（None 表示未启用）
+    pub resumption: Option<Arc<crate::resumption::ResumptionManager>>,
+    /// 过载降级（None 表示未启用）
+    pub load_shedder: Option<Arc<crate::load_shed::LoadShedder>>,
+    /// 同一 ActrId 重复连接的处理策略
+    pub duplicate_identity: Arc<DuplicateIdentityGuard>,
+    /// 节点排空状态（排空中新注册被重定向到其他节点）
+    pub drain: Arc<crate::drain::DrainState>,
 }
 
 /// 客户端连接信息
@@ -87,23 +138,41 @@ pub struct ClientConnection {
     pub id: String,
     pub actor_id: Option<ActrId>,
     pub credential: Option<AIdCredential>,
-    pub direct_sender: tokio::sync::mpsc::UnboundedSender<WsMessage>,
+    pub direct_sender: OutboundSender,
     pub client_ip: Option<std::net::IpAddr>,
     /// WebRTC 角色：\"answer\" 或 None (默认为 offer)
     pub webrtc_role: Option<String>,
+    /// 建立连接的时间 (Unix 秒)
+    pub connected_at: i64,
+    /// 连接指纹（客户端 IP + User-Agent，见 [`crate::duplicate_identity`]）
+    pub fingerprint: String,
 }
 
 /// 信令服务器句柄 - 用于在异步任务中操作服务器
 #[derive(Debug, Clone)]
 pub struct SignalingServerHandle {
-    pub clients: Arc<RwLock<HashMap<String, ClientConnection>>>,
-    pub actor_id_index: Arc<RwLock<HashMap<ActrId, String>>>,
+    pub clients: Arc<ShardedMap<String, ClientConnection>>,
+    pub actor_id_index: Arc<ShardedMap<ActrId, String>>,
     pub service_registry: Arc<RwLock<ServiceRegistry>>,
     pub presence_manager: Arc<RwLock<PresenceManager>>,
     pub ais_client: Option<Arc<crate::ais_client::AisClient>>,
     pub compatibility_cache: Arc<RwLock<crate::compatibility_cache::GlobalCompatibilityCache>>,
     pub connection_rate_limiter: Option<Arc<crate::ratelimit::ConnectionRateLimiter>>,
     pub message_rate_limiter: Option<Arc<crate::ratelimit::MessageRateLimiter>>,
+    pub realm_rate_limiter: Option<Arc<crate::ratelimit::RealmRateLimiter>>,
+    pub traffic_stats: Option<Arc<crate::traffic_stats::TrafficStats>>,
+    pub replay_guard: Option<Arc<crate::replay::ReplayGuard>>,
+    pub limits: ConnectionLimitsConfig,
+    pub network_emulator: Option<Arc<NetworkEmulator>>,
+    pub lan_discovery: bool,
+    pub spec_dependencies: Option<Arc<crate::spec_notice::SpecDependencyTracker>>,
+    pub sdp_sanitizer: Option<Arc<crate::sdp_filter::SdpSanitizer>>,
+    pub authz_gate: Option<Arc<crate::authz_hook::AuthzGate>>,
+    pub connection_reports: Arc<crate::connection_report::ConnectionReportStats>,
+    pub resumption: Option<Arc<crate::resumption::ResumptionManager>>,
+    pub load_shedder: Option<Arc<crate::load_shed::LoadShedder>>,
+    pub duplicate_identity: Arc<DuplicateIdentityGuard>,
+    pub drain: Arc<crate::drain::DrainState>,
 }
 impl SignalingServerHandle {
     /// 创建 SignalingEnvelope
@@ -118,7 +187,7 @@ impl SignalingServerHandle {
     ) -> SignalingEnvelope {
         #[allow(unused_mut)]
         let mut envelope = SignalingEnvelope {
-            envelope_version: 1,
+            envelope_version: ENVELOPE_VERSION,
             envelope_id: Uuid::new_v4().to_string(),
             reply_for: reply_for.map(|id| id.to_string()),
             timestamp: prost_types::Timestamp {
@@ -137,7 +206,7 @@ impl SignalingServerHandle {
     }
 
     #[cfg_attr(feature = "opentelemetry", instrument(level = "debug", skip_all))]
-    fn create_new_envelope(&self, flow: signaling_envelope::Flow) -> SignalingEnvelope {
+    pub(crate) fn create_new_envelope(&self, flow: signaling_envelope::Flow) -> SignalingEnvelope {
         self.create_envelope(flow, None)
     }
 }
@@ -151,8 +220,8 @@ impl Default for SignalingServer {
 impl SignalingServer {
     pub fn new() -> Self {
         Self {
-            clients: Arc::new(RwLock::new(HashMap::new())),
-            actor_id_index: Arc::new(RwLock::new(HashMap::new())),
+            clients: Arc::new(ShardedMap::new()),
+            actor_id_index: Arc::new(ShardedMap::new()),
             service_registry: Arc::new(RwLock::new(ServiceRegistry::new())),
             presence_manager: Arc::new(RwLock::new(PresenceManager::new())),
             ais_client: None, // 在 axum_router 中初始化
@@ -161,17 +230,124 @@ impl SignalingServer {
             )),
             connection_rate_limiter: None, // 在 axum_router 中根据配置初始化
             message_rate_limiter: None,    // 在 axum_router 中根据配置初始化
+            realm_rate_limiter: None,      // 在 axum_router 中根据配置初始化
+            traffic_stats: None,           // 在 axum_router 中根据配置初始化
+            replay_guard: None,            // 在 axum_router 中根据配置初始化
+            limits: ConnectionLimitsConfig::default(),
+            network_emulator: None,  // 在 axum_router 中根据配置初始化
+            lan_discovery: false,    // 在 axum_router 中根据配置初始化
+            compressor: None,        // 在 axum_router 中根据配置初始化
+            spec_dependencies: None, // 在 axum_router 中根据配置初始化
+            sdp_sanitizer: None,     // 在 axum_router 中根据配置初始化
+            authz_gate: None,        // 在 axum_router 中根据配置初始化
+            connection_reports: Arc::new(crate::connection_report::ConnectionReportStats::default()),
+            resumption: None,   // 在 axum_router 中根据配置初始化
+            load_shedder: None, // 在 axum_router 中根据配置初始化
+            duplicate_identity: Arc::new(DuplicateIdentityGuard::default()),
+            drain: Arc::new(crate::drain::DrainState::default()),
+        }
+    }
+
+    /// 创建用于异步任务的服务器句柄
+    pub fn handle(&self) -> SignalingServerHandle {
+        SignalingServerHandle {
+            clients: self.clients.clone(),
+            actor_id_index: self.actor_id_index.clone(),
+            service_registry: self.service_registry.clone(),
+            presence_manager: self.presence_manager.clone(),
+            ais_client: self.ais_client.clone(),
+            compatibility_cache: self.compatibility_cache.clone(),
+            connection_rate_limiter: self.connection_rate_limiter.clone(),
+            message_rate_limiter: self.message_rate_limiter.clone(),
+            realm_rate_limiter: self.realm_rate_limiter.clone(),
+            traffic_stats: self.traffic_stats.clone(),
+            replay_guard: self.replay_guard.clone(),
+            limits: self.limits.clone(),
+            network_emulator: self.network_emulator.clone(),
+            lan_discovery: self.lan_discovery,
+            spec_dependencies: self.spec_dependencies.clone(),
+            sdp_sanitizer: self.sdp_sanitizer.clone(),
+            authz_gate: self.authz_gate.clone(),
+            connection_reports: self.connection_reports.clone(),
+            resumption: self.resumption.clone(),
+            load_shedder: self.load_shedder.clone(),
+            duplicate_identity: self.duplicate_identity.clone(),
+            drain: self.drain.clone(),
+        }
+    }
+
+    /// 热加载速率限制配置
+    ///
+    /// 更新已创建的限流器，返回启动时未启用、需要重启才能启用的限流器名称
+    /// （`connection` / `message` / `realm`）
+    pub async fn reload_rate_limits(&self, config: &RateLimitConfig) -> Vec<&'static str> {
+        let mut restart_required = Vec::new();
+
+        match self.connection_rate_limiter {
+            Some(ref limiter) => limiter.update_config(config.connection.clone()).await,
+            None if config.connection.enabled => restart_required.push("connection"),
+            None => {}
+        }
+        match self.message_rate_limiter {
+            Some(ref limiter) => limiter.update_config(config.message.clone()).await,
+            None if config.message.enabled => restart_required.push("message"),
+            None => {}
+        }
+        match self.realm_rate_limiter {
+            Some(ref limiter) => limiter.update_config(config.realm.clone()).await,
+            None if config.realm.enabled => restart_required.push("realm"),
+            None => {}
+        }
+
+        info!(
+            "Signaling rate limits reloaded (restart required: {:?})",
+            restart_required
+        );
+        restart_required
+    }
+
+    /// 当前生效的速率限制配置，启动时未创建的限流器按未启用报告
+    pub fn rate_limit_config(&self) -> RateLimitConfig {
+        let mut config = RateLimitConfig::default();
+        match self.connection_rate_limiter {
+            Some(ref limiter) => config.connection = limiter.config(),
+            None => config.connection.enabled = false,
         }
+        match self.message_rate_limiter {
+            Some(ref limiter) => config.message = limiter.config(),
+            None => config.message.enabled = false,
+        }
+        match self.realm_rate_limiter {
+            Some(ref limiter) => config.realm = limiter.config(),
+            None => config.realm.enabled = false,
+        }
+        config
+    }
+
+    /// 各限流器当前的令牌桶水位
+    pub async fn rate_limit_status(&self) -> crate::ratelimit::RateLimitStatus {
+        crate::ratelimit::RateLimitStatus::collect(
+            self.connection_rate_limiter.as_deref(),
+            self.message_rate_limiter.as_deref(),
+            self.realm_rate_limiter.as_deref(),
+        )
+        .await
     }
 }
 
 /// 处理 WebSocket 连接
+///
+/// `compressor` 为握手时协商了压缩子协议的连接的压缩器（见 [`crate::compression`]）
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_websocket_connection(
     websocket: WebSocket,
     server: SignalingServerHandle,
     client_ip: Option<std::net::IpAddr>,
     url_identity: Option<(ActrId, AIdCredential)>,
     webrtc_role: Option<String>,
+    resume_token: Option<String>,
+    fingerprint: String,
+    compressor: Option<Compressor>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let client_id = Uuid::new_v4().to_string();
     info!(
@@ -183,86 +359,166 @@ pub async fn handle_websocket_connection(
     let (mut ws_sender, mut ws_receiver) = websocket.split();
 
     // 创建专用的发送通道用于点对点消息
-    let (direct_tx, mut direct_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (direct_tx, mut direct_rx) = outbound_channel(&server.limits);
 
     // 注册客户端（包含专用发送器）
-    {
-        let mut clients_guard = server.clients.write().await;
-
-        // 如果 URL 已带 actor_id，则移除已有相同 actor 的连接（避免 stale 映射）。
-        let (actor_for_entry, cred_for_entry) =
-            if let Some((actor_id, credential)) = url_identity.clone() {
-                let mut to_remove = Vec::new();
-                for (cid, conn) in clients_guard.iter() {
-                    if conn.actor_id.as_ref() == Some(&actor_id) {
-                        to_remove.push(cid.clone());
-                    }
+    let mut connection = ClientConnection {
+        id: client_id.clone(),
+        actor_id: None,
+        credential: None,
+        direct_sender: direct_tx,
+        client_ip,
+        webrtc_role: webrtc_role.clone(),
+        connected_at: chrono::Utc::now().timestamp(),
+        fingerprint,
+    };
+    // 会话恢复时重放的消息（已写入序号并编码）及其中缓存消息的条数
+    let mut resumed: Option<(ActrId, Vec<Bytes>, usize)> = None;
+    if let Some((actor_id, credential)) = url_identity {
+        // 持有该 Actor 的索引分片写锁：重复连接判定、会话恢复与登记对同一 Actor 互斥
+        let mut actor_index = server.actor_id_index.write(&actor_id).await;
+
+        // URL 已带 actor_id，按重复连接策略处理相同 actor 的已有连接（避免 stale 映射）。
+        if !admit_duplicate_identity(&client_id, &actor_id, &connection.fingerprint, &server).await
+        {
+            drop(actor_index);
+            ws_sender
+                .send(WsMessage::Close(Some(CloseFrame {
+                    code: close_code::POLICY,
+                    reason: REJECTED_CLOSE_REASON.into(),
+                })))
+                .await?;
+            return Ok(());
+        }
+
+        // 持有索引分片写锁恢复会话：断开期间的缓存在此之后不会再增加
+        if let Some(ref resumption) = server.resumption {
+            match resume_token
+                .as_deref()
+                .map(|token| resumption.resume(token, &actor_id))
+            {
+                Some(Ok(queued)) => {
+                    let replayed = queued.len();
+                    let replay =
+                        resumption_replay(&actor_id, queued, &connection.direct_sender, &server)
+                            .await;
+                    resumed = Some((actor_id.clone(), replay, replayed));
                 }
-                for cid in to_remove {
-                    clients_guard.remove(&cid);
-                    info!("🧹 Removed stale client {} for actor {:?}", cid, actor_id);
+                Some(Err(e)) => {
+                    warn!(
+                        "⚠️  Actor {} 会话恢复失败: {}",
+                        format_actor_id(&actor_id),
+                        e
+                    );
+                    resumption.remove(&actor_id);
                 }
-                (Some(actor_id), Some(credential))
-            } else {
-                (None, None)
-            };
+                None => resumption.remove(&actor_id),
+            }
+        }
 
-        clients_guard.insert(
-            client_id.clone(),
-            ClientConnection {
-                id: client_id.clone(),
-                actor_id: actor_for_entry,
-                credential: cred_for_entry,
-                direct_sender: direct_tx,
-                client_ip,
-                webrtc_role: webrtc_role.clone(),
-            },
+        connection.actor_id = Some(actor_id.clone());
+        connection.credential = Some(credential);
+        server.clients.insert(client_id.clone(), connection).await;
+        // URL 身份连接成为该 Actor 的最新连接，索引指向它
+        let realm_id = actor_id.realm.realm_id;
+        if actor_index.insert(actor_id, client_id.clone()).is_none() {
+            usage::record_actor_connected(realm_id);
+        }
+    } else {
+        server.clients.insert(client_id.clone(), connection).await;
+    }
+
+    // 压缩在序号标记之后进行
+    let inbound_compressor = compressor.clone();
+    let encode = move |message: WsMessage| match compressor {
+        Some(ref compressor) => compressor.encode(message),
+        None => message,
+    };
+
+    // 会话恢复：在发送任务启动前直接写出恢复通知与缓存消息，保证其先于新消息到达
+    if let Some((actor_id, replay, replayed)) = resumed {
+        for message in replay {
+            ws_sender.send(encode(WsMessage::Binary(message))).await?;
+        }
+        info!(
+            "♻️  Actor {} 会话已恢复，重放 {} 条缓存消息",
+            format_actor_id(&actor_id),
+            replayed
         );
     }
 
+    // 连接级 span：该连接上的所有 envelope span 在没有远端 trace context 时挂在其下
+    let connection_span = info_span!(
+        "signaling.connection",
+        client_id = %client_id,
+        client_ip = ?client_ip
+    );
+
     // 处理客户端消息的任务
     let server_for_receive = server.clone();
     let client_id_for_receive = client_id.clone();
 
-    let receive_task = tokio::spawn(async move {
-        while let Some(msg) = ws_receiver.next().await {
-            match msg {
-                Ok(WsMessage::Binary(data)) => {
-                    if let Err(e) =
-                        handle_client_envelope(&data, &client_id_for_receive, &server_for_receive)
-                            .await
-                    {
-                        error!("处理客户端信令错误: {}", e);
+    let receive_task = tokio::spawn(
+        async move {
+            while let Some(msg) = ws_receiver.next().await {
+                match msg {
+                    Ok(WsMessage::Binary(data)) => {
+                        let data = match inbound_compressor.as_ref().map(|compressor| {
+                            compressor.decompress(
+                                &data,
+                                server_for_receive
+                                    .limits
+                                    .max_envelope_bytes
+                                    .saturating_mul(2),
+                            )
+                        }) {
+                            Some(Ok(Some(decompressed))) => decompressed.into(),
+                            Some(Err(e)) => {
+                                warn!("🚫 连接 {} 压缩消息解压失败: {}", client_id_for_receive, e);
+                                break;
+                            }
+                            _ => data,
+                        };
+                        if let Err(e) = handle_client_envelope(
+                            data,
+                            &client_id_for_receive,
+                            &server_for_receive,
+                        )
+                        .await
+                        {
+                            error!("处理客户端信令错误: {}", e);
+                            break;
+                        }
+                    }
+                    Ok(WsMessage::Close(_)) => {
+                        info!("客户端 {} 主动断开连接", client_id_for_receive);
                         break;
                     }
-                }
-                Ok(WsMessage::Close(_)) => {
-                    info!("客户端 {} 主动断开连接", client_id_for_receive);
-                    break;
-                }
-                Err(e) => {
-                    error!("WebSocket 错误: {}", e);
-                    break;
-                }
-                _ => {
-                    warn!("收到非 Binary 消息，忽略");
+                    Err(e) => {
+                        error!("WebSocket 错误: {}", e);
+                        break;
+                    }
+                    _ => {
+                        warn!("收到非 Binary 消息，忽略");
+                    }
                 }
             }
-        }
 
-        // 清理客户端
-        cleanup_client(&client_id_for_receive, &server_for_receive).await;
-    });
+            // 清理客户端
+            cleanup_client(&client_id_for_receive, &server_for_receive).await;
+        }
+        .instrument(connection_span),
+    );
 
     // 处理发送消息的任务
     let send_task = tokio::spawn(async move {
         loop {
             tokio::select! {
-                // 处理点对点消息
+                // 处理点对点消息（发送队列溢出需要断开时返回 None）
                 msg = direct_rx.recv() => {
                     match msg {
                         Some(message) => {
-                            if ws_sender.send(message).await.is_err() {
+                            if ws_sender.send(encode(message)).await.is_err() {
                                 break;
                             }
                         }
@@ -288,10 +544,31 @@ pub async fn handle_websocket_connection(
 
 /// 处理客户端发送的 SignalingEnvelope
 async fn handle_client_envelope(
-    data: &[u8],
+    data: Bytes,
     client_id: &str,
     server: &SignalingServerHandle,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // 拒绝超过大小限制的 envelope（未解码，无法设置 reply_for）
+    if data.len() > server.limits.max_envelope_bytes {
+        warn!(
+            "🚫 连接 {} envelope 过大: {} bytes (limit {})",
+            client_id,
+            data.len(),
+            server.limits.max_envelope_bytes
+        );
+        let error_envelope =
+            server.create_new_envelope(signaling_envelope::Flow::EnvelopeError(ErrorResponse {
+                code: 413,
+                message: format!(
+                    "Envelope of {} bytes exceeds limit of {} bytes",
+                    data.len(),
+                    server.limits.max_envelope_bytes
+                ),
+            }));
+        send_envelope_to_client(client_id, error_envelope, server).await?;
+        return Ok(());
+    }
+
     // 检查消息速率限制
     if let Some(ref limiter) = server.message_rate_limiter
         && let Err(e) = limiter.check_message(client_id).await
@@ -308,8 +585,30 @@ async fn handle_client_envelope(
         return Ok(());
     }
 
-    // 解码 protobuf 消息
-    let envelope = SignalingEnvelope::decode(data)?;
+    // 直接从 WebSocket 帧解码 protobuf 消息
+    let envelope = decode_envelope(&data)?;
+
+    // 校验时间戳新鲜度与 nonce，拒绝重放的 envelope
+    if let Some(ref guard) = server.replay_guard
+        && let Err(e) = guard.check(&envelope).await
+    {
+        let error_envelope = server.create_envelope(
+            signaling_envelope::Flow::EnvelopeError(ErrorResponse {
+                code: e.code(),
+                message: e.to_string(),
+            }),
+            Some(&envelope.envelope_id),
+        );
+        send_envelope_to_client(client_id, error_envelope, server).await?;
+        return Ok(());
+    }
+
+    // 按目标 ActrType 记录流量
+    if let Some(ref stats) = server.traffic_stats
+        && let Some(actr_type) = envelope_traffic_type(&envelope)
+    {
+        stats.record(&actr_type, data.len());
+    }
 
     #[cfg(feature = "opentelemetry")]
     let remote_context = extract_trace_context(&envelope);
@@ -319,10 +618,11 @@ async fn handle_client_envelope(
         envelope_id = %envelope.envelope_id,
         client_id = %client_id
     );
+    // 仅当 envelope 携带有效 traceparent 时才接入远端链路，否则沿用连接 span
     #[cfg(feature = "opentelemetry")]
-    {
+    if let Some(remote_context) = remote_context {
         use tracing_opentelemetry::OpenTelemetrySpanExt;
-        let _ = span.set_parent(remote_context.clone());
+        let _ = span.set_parent(remote_context);
     }
 
     async move {
@@ -338,22 +638,8 @@ async fn handle_client_envelope(
                 handle_actr_to_server(actr_to_server, client_id, server, &envelope.envelope_id)
                     .await
             }
-            Some(signaling_envelope::Flow::ActrRelay(ref relay)) => {
-                #[cfg(feature = "opentelemetry")]
-                {
-                    handle_actr_relay(
-                        relay.clone(),
-                        client_id,
-                        server,
-                        &envelope.envelope_id,
-                        remote_context,
-                    )
-                    .await
-                }
-                #[cfg(not(feature = "opentelemetry"))]
-                {
-                    handle_actr_relay(relay.clone(), client_id, server, &envelope.envelope_id).await
-                }
+            Some(signaling_envelope::Flow::ActrRelay(relay)) => {
+                handle_actr_relay(relay, client_id, server, &envelope.envelope_id).await
             }
             Some(signaling_envelope::Flow::EnvelopeError(error)) => {
                 error!(
@@ -372,6 +658,33 @@ async fn handle_client_envelope(
     .await
 }
 
+/// 确定 envelope 流量归属的 ActrType
+///
+/// - ActrRelay：中继目标的类型
+/// - RouteCandidates / 订阅类请求：请求中的 target_type
+/// - 其他 ActrToSignaling：发送方自身的类型
+/// - RegisterRequest：待注册的类型
+fn envelope_traffic_type(envelope: &SignalingEnvelope) -> Option<String> {
+    match envelope.flow.as_ref()? {
+        signaling_envelope::Flow::ActrRelay(relay) => Some(type_key(&relay.target.r#type)),
+        signaling_envelope::Flow::ActrToServer(actr_to_server) => {
+            let actr_type = match actr_to_server.payload.as_ref() {
+                Some(actr_to_signaling::Payload::RouteCandidatesRequest(req)) => &req.target_type,
+                Some(actr_to_signaling::Payload::SubscribeActrUpRequest(req)) => &req.target_type,
+                Some(actr_to_signaling::Payload::UnsubscribeActrUpRequest(req)) => &req.target_type,
+                _ => &actr_to_server.source.r#type,
+            };
+            Some(type_key(actr_type))
+        }
+        signaling_envelope::Flow::PeerToServer(peer_to_server) => {
+            match peer_to_server.payload.as_ref()? {
+                peer_to_signaling::Payload::RegisterRequest(req) => Some(type_key(&req.actr_type)),
+            }
+        }
+        _ => None,
+    }
+}
+
 /// 处理 PeerToSignaling 流程（注册前）
 #[cfg_attr(feature = "opentelemetry", instrument(level = "debug", skip_all, fields(client_id, envelope_id = request_envelope_id)))]
 async fn handle_peer_to_server(
@@ -398,6 +711,23 @@ async fn handle_peer_to_server(
                 return Ok(());
             }
 
+            // Realm 聚合注册速率限制
+            if let Some(ref limiter) = server.realm_rate_limiter
+                && let Err(e) = limiter
+                    .check(realm_id, crate::ratelimit::RealmOperation::Register)
+                    .await
+            {
+                send_register_error(client_id, 429, &e, server, request_envelope_id).await?;
+                return Ok(());
+            }
+
+            // Supervisor 下发的 Realm 并发 Actor 配额
+            if let Err(e) = check_actor_quota(realm_id).await {
+                warn!("⚠️  RegisterRequest 超出 Realm 配额: {}", e);
+                send_register_error(client_id, 403, &e, server, request_envelope_id).await?;
+                return Ok(());
+            }
+
             handle_register_request(register_request, client_id, server, request_envelope_id)
                 .await?;
         }
@@ -408,6 +738,17 @@ async fn handle_peer_to_server(
     Ok(())
 }
 
+/// 检查 Realm 的并发 Actor 配额（读取配额失败时放行）
+async fn check_actor_quota(realm_id: u32) -> Result<(), String> {
+    match RealmQuota::load_for_realm(realm_id).await {
+        Ok(quota) => quota.check_concurrent_actors(realm_id, usage::active_actors(realm_id)),
+        Err(e) => {
+            warn!("⚠️  读取 Realm {} 配额失败: {}", realm_id, e);
+            Ok(())
+        }
+    }
+}
+
 /// 处理注册请求
 #[cfg_attr(feature = "opentelemetry", instrument(level = "debug", skip_all, fields(client_id, envelope_id = request_envelope_id)))]
 async fn handle_register_request(
@@ -439,8 +780,11 @@ async fn handle_register_request(
     }
 
     // 检查是否已经注册过
-    if let Some(client) = server.clients.read().await.get(client_id)
-        && client.actor_id.is_some()
+    if server
+        .clients
+        .with(client_id, |client| client.actor_id.is_some())
+        .await
+        .unwrap_or(false)
     {
         send_register_error(
             client_id,
@@ -453,6 +797,41 @@ async fn handle_register_request(
         return Ok(());
     }
 
+    // 节点排空中：拒绝新注册并提示改连其他节点，已建立的会话不受影响
+    if let Some(redirect) = server.drain.redirect() {
+        info!(
+            "🚧 节点排空中，重定向注册请求: type={}/{}, alternates={:?}",
+            request.actr_type.manufacturer, request.actr_type.name, redirect.alternates
+        );
+        let error = redirect.to_error_response();
+        send_register_error(
+            client_id,
+            error.code,
+            &error.message,
+            server,
+            request_envelope_id,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // 外部授权钩子
+    if let Some(ref authz) = server.authz_gate
+        && let Err(reason) = authz
+            .check(crate::authz_hook::AuthzRequest::register(
+                request.realm.realm_id,
+                &request.actr_type,
+            ))
+            .await
+    {
+        warn!(
+            "⚠️  外部授权拒绝注册: realm={}, type={}/{}: {}",
+            request.realm.realm_id, request.actr_type.manufacturer, request.actr_type.name, reason
+        );
+        send_register_error(client_id, 403, &reason, server, request_envelope_id).await?;
+        return Ok(());
+    }
+
     // 通过 AIS 分配 ActorId 和 Credential
     let ais_client = match &server.ais_client {
         Some(client) => client,
@@ -562,7 +941,7 @@ async fn handle_register_request(
             register_ok.actr_id.clone(),
             service_name,
             message_types,
-            None, // capabilities 当前不使用
+            None, // 由 ServiceSpec 标签解析实例元数据
             request.service_spec.clone(),
             request.acl.clone(),
             request.ws_address.clone(),
@@ -577,6 +956,16 @@ async fn handle_register_request(
         drop(registry);
     }
 
+    // 新 fingerprint 可能破坏已有依赖方，异步检查并通知
+    if let (Some(spec), Some(_)) = (&request.service_spec, &server.spec_dependencies) {
+        let server = server.clone();
+        let provider = register_ok.actr_id.clone();
+        let spec = spec.clone();
+        tokio::spawn(async move {
+            notify_spec_incompatibility(&server, &provider, &spec).await;
+        });
+    }
+
     // 持久化 ACL 规则到数据库
     if let Some(ref acl) = request.acl {
         use actrix_common::realm::acl::ActorAcl;
@@ -625,20 +1014,29 @@ async fn handle_register_request(
     }
 
     // 更新客户端信息和 ActorId 索引
-    // Hold clients lock until actor_id_index update completes to prevent race condition
-    // where cleanup_client removes the client between releasing clients lock and
-    // acquiring actor_id_index lock, leading to stale index entries.
+    // Hold the actor's index shard lock across both updates so cleanup_client (which takes
+    // the same shard lock before removing a registered client) cannot interleave, and only
+    // index clients that are still connected to avoid stale index entries.
     {
-        let mut clients_guard = server.clients.write().await;
-        if let Some(client) = clients_guard.get_mut(client_id) {
-            client.actor_id = Some(register_ok.actr_id.clone());
-            client.credential = Some(register_ok.credential.clone());
+        let mut actor_index = server.actor_id_index.write(&register_ok.actr_id).await;
+        let updated = server
+            .clients
+            .update(client_id, |client| {
+                client.actor_id = Some(register_ok.actr_id.clone());
+                client.credential = Some(register_ok.credential.clone());
+            })
+            .await;
+        if updated.is_some() {
+            let realm_id = register_ok.actr_id.realm.realm_id;
+            usage::record_registration(realm_id);
+            if actor_index
+                .insert(register_ok.actr_id.clone(), client_id.to_string())
+                .is_none()
+            {
+                usage::record_actor_connected(realm_id);
+            }
         }
     }
-    {
-        let mut actor_index = server.actor_id_index.write().await;
-        actor_index.insert(register_ok.actr_id.clone(), client_id.to_string());
-    }
 
     // 直接使用 AIS 返回的 register_ok（包含 psk 和 public_key）
     let response = RegisterResponse {
@@ -656,6 +1054,22 @@ async fn handle_register_request(
 
     send_envelope_to_client(client_id, response_envelope, server).await?;
 
+    // 下发断线重连恢复 token
+    if let Some(ref resumption) = server.resumption {
+        resumption.issue(&register_ok.actr_id);
+        if let Some(notice) = resumption_notice(&register_ok.actr_id, false, 0, server) {
+            let sender = server
+                .clients
+                .with(client_id, |client| client.direct_sender.clone())
+                .await;
+            if let Some(sender) = sender
+                && let Err(e) = sender.send_envelope(notice).await
+            {
+                warn!("⚠️  下发恢复 token 失败: {}", e);
+            }
+        }
+    }
+
     // 通知所有订阅了该 ActrType 的订阅者（带 ACL 过滤）
     let presence = server.presence_manager.read().await;
     let subscribers = presence
@@ -677,20 +1091,8 @@ async fn handle_register_request(
 
         // 为每个订阅者构造并发送通知
         for subscriber_id in subscribers {
-            let subscriber_client_id =
-                match resolve_client_id_by_actor_id(&subscriber_id, server).await {
-                    Ok(id) => id,
-                    Err(e) => {
-                        warn!(
-                            "⚠️  订阅者 {} 索引缺失或不一致: {}",
-                            subscriber_id.serial_number, e
-                        );
-                        continue;
-                    }
-                };
-
             let flow = signaling_envelope::Flow::ServerToActr(SignalingToActr {
-                target: subscriber_id,
+                target: subscriber_id.clone(),
                 payload: Some(signaling_to_actr::Payload::ActrUpEvent(
                     actr_up_event.clone(),
                 )),
@@ -698,6 +1100,26 @@ async fn handle_register_request(
 
             let event_envelope = server.create_new_envelope(flow);
 
+            let subscriber_client_id = match resolve_client_id_by_actor_id(&subscriber_id, server)
+                .await
+            {
+                Ok(id) => id,
+                Err(_) if queue_for_resumption(&subscriber_id, &event_envelope, server).await => {
+                    debug!(
+                        "订阅者 {} 已断开，ActrUpEvent 已缓存待恢复",
+                        subscriber_id.serial_number
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️  订阅者 {} 索引缺失或不一致: {}",
+                        subscriber_id.serial_number, e
+                    );
+                    continue;
+                }
+            };
+
             if let Err(e) =
                 send_envelope_to_client(&subscriber_client_id, event_envelope, server).await
             {
@@ -780,13 +1202,13 @@ async fn handle_actr_to_server(
     }
 
     // 验证 credential 并获取容忍期状态
-    let in_tolerance_period = match AIdCredentialValidator::check(
+    let (claims, in_tolerance_period) = match AIdCredentialValidator::check(
         &actr_to_server.credential,
         source.realm.realm_id,
     )
     .await
     {
-        Ok((_claims, in_tolerance)) => in_tolerance,
+        Ok(result) => result,
         Err(e) => {
             warn!(
                 "⚠️  Actor {} credential 验证失败: {}",
@@ -806,6 +1228,37 @@ async fn handle_actr_to_server(
         }
     };
 
+    // 未在升级请求中携带凭证的连接，以第一条通过认证的消息绑定身份（被重复连接策略拒绝时停止处理）
+    if claims.actor_id == source.to_string_repr()
+        && !bind_connection_identity(client_id, &source, &actr_to_server.credential, server).await
+    {
+        return Ok(());
+    }
+
+    // 过载降级：拒绝低优先级请求，注册、凭证与中继不受影响
+    if let Some(kind) = crate::load_shed::ShedKind::of_payload(actr_to_server.payload.as_ref())
+        && server
+            .load_shedder
+            .as_ref()
+            .is_some_and(|shedder| shedder.should_shed(kind))
+    {
+        debug!(
+            "🔥 过载降级，拒绝 {} 请求: actor={}",
+            kind.as_str(),
+            source.serial_number
+        );
+        send_error_response(
+            client_id,
+            &source,
+            crate::load_shed::SHED_ERROR_CODE,
+            crate::load_shed::SHED_ERROR_MESSAGE,
+            server,
+            Some(request_envelope_id),
+        )
+        .await?;
+        return Ok(());
+    }
+
     match actr_to_server.payload {
         Some(actr_to_signaling::Payload::Ping(ping)) => {
             handle_ping(
@@ -849,11 +1302,44 @@ async fn handle_actr_to_server(
         Some(actr_to_signaling::Payload::UnsubscribeActrUpRequest(req)) => {
             handle_unsubscribe_actr_up(source, req, client_id, server, request_envelope_id).await?;
         }
+        Some(actr_to_signaling::Payload::Error(error))
+            if error.code == crate::acl_update::UPDATE_ACL_CODE =>
+        {
+            handle_update_acl(source, &error, client_id, server, request_envelope_id).await?;
+        }
+        Some(actr_to_signaling::Payload::Error(error))
+            if error.code == crate::presence_snapshot::PRESENCE_SNAPSHOT_CODE =>
+        {
+            handle_presence_snapshot(source, &error, client_id, server, request_envelope_id)
+                .await?;
+        }
         Some(actr_to_signaling::Payload::Error(error)) => {
-            error!(
-                "收到客户端错误报告 (Actor {}): code={}, message={}",
-                source.serial_number, error.code, error.message
-            );
+            match crate::connection_report::ConnectionReport::from_error_response(&error) {
+                Some(Ok(report)) => {
+                    debug!(
+                        "📶 Actor {} ConnectionReport: {}/{} rtt={:?}ms",
+                        source.serial_number,
+                        report.local_candidate_type.as_str(),
+                        report.remote_candidate_type.as_str(),
+                        report.rtt_ms
+                    );
+                    server
+                        .connection_reports
+                        .record(source.realm.realm_id, &report);
+                }
+                Some(Err(e)) => {
+                    warn!(
+                        "Actor {} ConnectionReport 格式无效: {}",
+                        source.serial_number, e
+                    );
+                }
+                None => {
+                    error!(
+                        "收到客户端错误报告 (Actor {}): code={}, message={}",
+                        source.serial_number, error.code, error.message
+                    );
+                }
+            }
         }
         None => {
             warn!("ActrToSignaling 消息缺少 payload");
@@ -1005,62 +1491,227 @@ async fn handle_unregister(
     let response_envelope = server.create_envelope(flow, Some(request_envelope_id));
     send_envelope_to_client(client_id, response_envelope, server).await?;
 
+    // 主动注销不保留恢复会话
+    if let Some(ref resumption) = server.resumption
+        && let Some(actor_id) = server
+            .clients
+            .with(client_id, |client| client.actor_id.clone())
+            .await
+            .flatten()
+    {
+        resumption.remove(&actor_id);
+    }
+
     // 清理客户端连接
     cleanup_client(client_id, server).await;
 
     Ok(())
 }
 
-/// 通过 actor_id_index 快速解析 client_id，保持索引与 clients 同步
-async fn resolve_client_id_by_actor_id(
+/// 将尚未绑定身份的连接绑定到已认证的 Actor
+///
+/// 已绑定身份（注册或升级请求携带凭证）的连接不受影响；
+/// 同一 Actor 的已有连接按重复连接策略处理，与 URL 身份重连一致。
+///
+/// 返回 false 表示策略拒绝了该连接，连接已被关闭
+async fn bind_connection_identity(
+    client_id: &str,
     actor_id: &ActrId,
+    credential: &AIdCredential,
     server: &SignalingServerHandle,
-) -> Result<String, String> {
-    let client_id = {
-        let index_guard = server.actor_id_index.read().await;
-        index_guard.get(actor_id).cloned()
-    };
+) -> bool {
+    // 快速路径：已绑定身份的连接（绝大多数消息）只在读锁下判定，不争用索引分片写锁；
+    // 绑定只会从未绑定变为已绑定，读到已绑定即可直接返回
+    let unbound = server
+        .clients
+        .with(client_id, |client| client.actor_id.is_none())
+        .await;
+    if unbound != Some(true) {
+        return true;
+    }
 
-    let client_id = match client_id {
-        Some(id) => id,
-        None => {
-            warn!(
-                "⚠️  Actor {} 缺少 client_id 索引，可能尚未注册或已清理",
-                format_actor_id(actor_id)
-            );
-            return Err("client_id not found for actor_id".into());
+    {
+        // 需要绑定时才持有该 Actor 的索引分片写锁，与 URL 身份连接的重复判定互斥，并在锁内复查
+        let mut actor_index = server.actor_id_index.write(actor_id).await;
+        let Some(fingerprint) = server
+            .clients
+            .with(client_id, |client| {
+                client
+                    .actor_id
+                    .is_none()
+                    .then(|| client.fingerprint.clone())
+            })
+            .await
+            .flatten()
+        else {
+            return true;
+        };
+
+        if !admit_duplicate_identity(client_id, actor_id, &fingerprint, server).await {
+            // 移除连接后发送通道关闭，发送任务在送出 Close 帧后退出
+            if let Some(client) = server.clients.remove(client_id).await {
+                client.direct_sender.close(Some(CloseFrame {
+                    code: close_code::POLICY,
+                    reason: REJECTED_CLOSE_REASON.into(),
+                }));
+            }
+            return false;
         }
-    };
 
-    let exists = server.clients.read().await.contains_key(&client_id);
-    if !exists {
-        warn!(
-            "⚠️  Actor {} 索引指向不存在的客户端 {}，索引可能已过期",
-            format_actor_id(actor_id),
-            client_id
-        );
-        return Err("actor_id_index stale for actor_id".into());
+        let updated = server
+            .clients
+            .update(client_id, |client| {
+                client.actor_id = Some(actor_id.clone());
+                client.credential = Some(credential.clone());
+            })
+            .await;
+        if updated.is_some()
+            && actor_index
+                .insert(actor_id.clone(), client_id.to_string())
+                .is_none()
+        {
+            usage::record_actor_connected(actor_id.realm.realm_id);
+        }
     }
 
-    Ok(client_id)
-}
-
-fn format_actor_id(actor_id: &ActrId) -> String {
-    format!(
-        "realm={} serial={}",
-        actor_id.realm.realm_id, actor_id.serial_number
-    )
+    info!(
+        "🔐 连接 {} 通过首条认证消息绑定 Actor {}",
+        client_id,
+        format_actor_id(actor_id)
+    );
+    true
 }
 
-/// 处理 ActrRelay（WebRTC 信令中继）
-#[cfg_attr(feature = "opentelemetry", instrument(level = "debug", skip_all, fields(client_id, envelope_id = request_envelope_id)))]
-async fn handle_actr_relay(
-    relay: ActrRelay,
+/// 按重复连接策略处理同一 Actor 的已有连接（调用方持有该 Actor 的索引分片写锁）
+///
+/// 返回 false 表示新连接被拒绝；被替换的旧连接收到 Close 帧并从连接表移除
+async fn admit_duplicate_identity(
+    client_id: &str,
+    actor_id: &ActrId,
+    fingerprint: &str,
+    server: &SignalingServerHandle,
+) -> bool {
+    let existing = connections_of_actor(actor_id, Some(client_id), server).await;
+
+    match server
+        .duplicate_identity
+        .resolve(actor_id, fingerprint, &existing)
+    {
+        DuplicateDecision::Reject => {
+            warn!(
+                "🚫 Actor {} 已有 {} 个在线连接，拒绝新连接 {} (指纹 {})",
+                format_actor_id(actor_id),
+                existing.len(),
+                client_id,
+                fingerprint
+            );
+            false
+        }
+        DuplicateDecision::Admit { evict } => {
+            for replaced in existing
+                .iter()
+                .filter(|conn| evict.contains(&conn.client_id))
+            {
+                if let Some(old) = server.clients.remove(&replaced.client_id).await {
+                    old.direct_sender.close(Some(CloseFrame {
+                        code: close_code::POLICY,
+                        reason: REPLACED_CLOSE_REASON.into(),
+                    }));
+                    server.duplicate_identity.notify_replaced(
+                        actor_id,
+                        replaced,
+                        client_id,
+                        fingerprint,
+                    );
+                }
+            }
+            true
+        }
+    }
+}
+
+/// 同一 Actor 的全部连接（allow_multiple 策略下可能有多个），可排除指定连接
+///
+/// 逐个分片扫描连接表，仅在连接建立与清理时使用，调用方持有该 Actor 的索引分片写锁
+async fn connections_of_actor(
+    actor_id: &ActrId,
+    exclude: Option<&str>,
+    server: &SignalingServerHandle,
+) -> Vec<ExistingConnection> {
+    server
+        .clients
+        .collect(|id, conn| {
+            (exclude != Some(id.as_str()) && conn.actor_id.as_ref() == Some(actor_id)).then(|| {
+                ExistingConnection {
+                    client_id: conn.id.clone(),
+                    fingerprint: conn.fingerprint.clone(),
+                    connected_at: conn.connected_at,
+                }
+            })
+        })
+        .await
+}
+
+/// 通过 actor_id_index 查找 Actor 当前的在线连接（不记录日志）
+async fn lookup_client_id(actor_id: &ActrId, server: &SignalingServerHandle) -> Option<String> {
+    let client_id = server.actor_id_index.with(actor_id, Clone::clone).await?;
+    server
+        .clients
+        .contains_key(&client_id)
+        .await
+        .then_some(client_id)
+}
+
+/// 通过 actor_id_index 快速解析 client_id，保持索引与 clients 同步
+async fn resolve_client_id_by_actor_id(
+    actor_id: &ActrId,
+    server: &SignalingServerHandle,
+) -> Result<String, String> {
+    let client_id = server.actor_id_index.with(actor_id, Clone::clone).await;
+
+    let client_id = match client_id {
+        Some(id) => id,
+        None => {
+            warn!(
+                "⚠️  Actor {} 缺少 client_id 索引，可能尚未注册或已清理",
+                format_actor_id(actor_id)
+            );
+            return Err("client_id not found for actor_id".into());
+        }
+    };
+
+    let exists = server.clients.contains_key(&client_id).await;
+    if !exists {
+        warn!(
+            "⚠️  Actor {} 索引指向不存在的客户端 {}，索引可能已过期",
+            format_actor_id(actor_id),
+            client_id
+        );
+        return Err("actor_id_index stale for actor_id".into());
+    }
+
+    Ok(client_id)
+}
+
+fn format_actor_id(actor_id: &ActrId) -> String {
+    format!(
+        "realm={} serial={}",
+        actor_id.realm.realm_id, actor_id.serial_number
+    )
+}
+
+/// 处理 ActrRelay（WebRTC 信令中继）
+#[cfg_attr(feature = "opentelemetry", instrument(level = "debug", skip_all, fields(client_id, envelope_id = request_envelope_id)))]
+async fn handle_actr_relay(
+    mut relay: ActrRelay,
     client_id: &str,
     server: &SignalingServerHandle,
     request_envelope_id: &str,
-    #[cfg(feature = "opentelemetry")] remote_context: opentelemetry::Context,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // 转发时使用当前（服务端）span 的 context，使接收方的 span 成为服务端 span 的子节点
+    #[cfg(feature = "opentelemetry")]
+    let trace_context = current_trace_context();
+
     let source = relay.source.clone();
     let target = &relay.target;
     // 验证源 Actor 的 realm（存在、未过期且状态正常）
@@ -1079,6 +1730,24 @@ async fn handle_actr_relay(
         return Ok(());
     }
 
+    // Realm 聚合中继速率限制
+    if let Some(ref limiter) = server.realm_rate_limiter
+        && let Err(e) = limiter
+            .check(realm_id, crate::ratelimit::RealmOperation::Relay)
+            .await
+    {
+        send_error_response(
+            client_id,
+            &source,
+            429,
+            &e,
+            server,
+            Some(request_envelope_id),
+        )
+        .await?;
+        return Ok(());
+    }
+
     info!(
         "🔀 中继信令: {} -> {}",
         source.serial_number, target.serial_number
@@ -1134,6 +1803,28 @@ async fn handle_actr_relay(
         return Ok(());
     }
 
+    // 外部授权钩子
+    if let Some(ref authz) = server.authz_gate
+        && let Err(reason) = authz
+            .check(crate::authz_hook::AuthzRequest::relay(&source, target))
+            .await
+    {
+        warn!(
+            "⚠️  外部授权拒绝中继: {} -> {}: {}",
+            source.serial_number, target.serial_number, reason
+        );
+        send_error_response(
+            client_id,
+            &source,
+            403,
+            &reason,
+            server,
+            Some(request_envelope_id),
+        )
+        .await?;
+        return Ok(());
+    }
+
     // Validate credential and retain claims for identity verification below.
     let claims = match AIdCredentialValidator::check(&relay.credential, source.realm.realm_id).await
     {
@@ -1176,29 +1867,114 @@ async fn handle_actr_relay(
         return Ok(());
     }
 
+    // SDP 校验与清洗：拒绝格式错误的 SDP，剥离不允许的 candidate 与编解码器
+    if let Some(ref sanitizer) = server.sdp_sanitizer {
+        let result = match relay.payload.as_mut() {
+            Some(actr_relay::Payload::SessionDescription(description)) => sanitizer
+                .sanitize(realm_id, &description.sdp)
+                .map(|sanitized| {
+                    if sanitized.stripped_candidates > 0 || sanitized.stripped_codecs > 0 {
+                        debug!(
+                            "Sanitized relayed SDP: stripped {} candidates, {} codecs",
+                            sanitized.stripped_candidates, sanitized.stripped_codecs
+                        );
+                    }
+                    description.sdp = sanitized.sdp;
+                    true
+                }),
+            Some(actr_relay::Payload::IceCandidate(candidate)) => {
+                sanitizer.allow_candidate(&candidate.candidate)
+            }
+            _ => Ok(true),
+        };
+
+        match result {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!(
+                    "Dropped disallowed ICE candidate: {} -> {}",
+                    source.serial_number, target.serial_number
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                warn!(
+                    "⚠️  Actor {} 中继的 SDP 校验失败: {}",
+                    source.serial_number, e
+                );
+                send_error_response(
+                    client_id,
+                    &source,
+                    e.code(),
+                    &format!("Invalid SDP: {e}"),
+                    server,
+                    Some(request_envelope_id),
+                )
+                .await?;
+                return Ok(());
+            }
+        }
+    }
+
+    // 局域网直连：只转发 host candidate，mDNS 主机名替换为发送方的局域网 IP
+    if server.lan_discovery {
+        let sender_ip = server
+            .clients
+            .with(client_id, |c| c.client_ip)
+            .await
+            .flatten();
+        match relay.payload.as_mut() {
+            Some(actr_relay::Payload::SessionDescription(description)) => {
+                let rewrite = crate::lan_discovery::rewrite_sdp(&description.sdp, sender_ip);
+                debug!(
+                    "LAN discovery rewrote relayed SDP: {} mDNS candidates rewritten, {} non-host candidates stripped",
+                    rewrite.rewritten, rewrite.stripped
+                );
+                description.sdp = rewrite.sdp;
+            }
+            Some(actr_relay::Payload::IceCandidate(candidate)) => {
+                match crate::lan_discovery::rewrite_candidate(&candidate.candidate, sender_ip) {
+                    Some(rewritten) => candidate.candidate = rewritten,
+                    None => {
+                        debug!(
+                            "LAN discovery dropped non-host ICE candidate: {} -> {}",
+                            source.serial_number, target.serial_number
+                        );
+                        return Ok(());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 弱网模拟：转发前注入延迟，或按丢包率静默丢弃
+    if let Some(ref emulator) = server.network_emulator
+        && !emulator.impair().await
+    {
+        debug!(
+            "Network emulation dropped relay: {} -> {}",
+            source.serial_number, target.serial_number
+        );
+        return Ok(());
+    }
+
+    usage::record_relay_message(realm_id);
+
     // Role negotiation: server decides offerer/answerer and notifies both parties
     if let Some(actr_relay::Payload::RoleNegotiation(RoleNegotiation { from, to, .. })) =
         relay.payload.clone()
     {
-        let clients_guard = server.clients.read().await;
+        let from_role = webrtc_role_of(&from, server).await;
+        let to_role = webrtc_role_of(&to, server).await;
 
         // 使用 determine_webrtc_role 函数确定角色
-        let is_offerer = determine_webrtc_role(&from, &to, &clients_guard);
+        let is_offerer =
+            determine_webrtc_role(&from, &to, from_role.as_deref(), to_role.as_deref());
 
         // 判断双方是否有固定网络配置 (webrtc_role == "answer")
-        let from_fixed = clients_guard
-            .values()
-            .find(|c| c.actor_id.as_ref() == Some(&from))
-            .and_then(|c| c.webrtc_role.as_deref())
-            == Some("answer");
-
-        let to_fixed = clients_guard
-            .values()
-            .find(|c| c.actor_id.as_ref() == Some(&to))
-            .and_then(|c| c.webrtc_role.as_deref())
-            == Some("answer");
-
-        drop(clients_guard);
+        let from_fixed = from_role.as_deref() == Some("answer");
+        let to_fixed = to_role.as_deref() == Some("answer");
 
         // 发送给 from 的 RoleAssignment，remote_fixed 表示 to 的配置状态
         let new_relay = ActrRelay {
@@ -1216,7 +1992,7 @@ async fn handle_actr_relay(
             server,
             new_relay.clone(),
             #[cfg(feature = "opentelemetry")]
-            remote_context.clone(),
+            trace_context.clone(),
         )
         .await?;
 
@@ -1237,7 +2013,7 @@ async fn handle_actr_relay(
             server,
             new_relay,
             #[cfg(feature = "opentelemetry")]
-            remote_context,
+            trace_context,
         )
         .await?;
 
@@ -1245,18 +2021,7 @@ async fn handle_actr_relay(
     }
 
     // 查找目标客户端并转发其他中继消息
-    let clients_guard = server.clients.read().await;
-    let target_client_id = clients_guard.iter().find_map(|(id, client)| {
-        client.actor_id.as_ref().and_then(|actor_id| {
-            if actor_id.realm.realm_id == target.realm.realm_id
-                && actor_id.serial_number == target.serial_number
-            {
-                Some(id.clone())
-            } else {
-                None
-            }
-        })
-    });
+    let target_client_id = lookup_client_id(target, server).await;
 
     if let Some(target_client_id) = target_client_id {
         // 重新构造 envelope 并转发
@@ -1264,14 +2029,32 @@ async fn handle_actr_relay(
         #[allow(unused_mut)]
         let mut forward_envelope = server.create_new_envelope(flow);
 
-        // Inject the original trace context into the forwarded envelope to ensure end-to-end tracing
+        // Inject the relay span context into the forwarded envelope to ensure end-to-end tracing
         #[cfg(feature = "opentelemetry")]
-        inject_trace_context(&remote_context, &mut forward_envelope);
-        send_envelope_to_client(&target_client_id, forward_envelope, server).await?;
+        inject_trace_context(&trace_context, &mut forward_envelope);
+        // 目标发送队列溢出等失败只影响目标连接，不中断发送方
+        if let Err(e) = send_envelope_to_client(&target_client_id, forward_envelope, server).await {
+            warn!("⚠️ 转发到目标 Actor {} 失败: {}", target.serial_number, e);
+            return Ok(());
+        }
 
         info!("✅ 信令中继成功");
     } else {
-        warn!("⚠️ 未找到目标 Actor {}", target.serial_number);
+        let target = target.clone();
+        let flow = signaling_envelope::Flow::ActrRelay(relay);
+        #[allow(unused_mut)]
+        let mut queued_envelope = server.create_new_envelope(flow);
+        #[cfg(feature = "opentelemetry")]
+        inject_trace_context(&trace_context, &mut queued_envelope);
+
+        if queue_for_resumption(&target, &queued_envelope, server).await {
+            info!(
+                "📥 目标 Actor {} 已断开，中继消息已缓存待恢复",
+                target.serial_number
+            );
+        } else {
+            warn!("⚠️ 未找到目标 Actor {}", target.serial_number);
+        }
     }
 
     Ok(())
@@ -1296,7 +2079,8 @@ fn actor_order_key(id: &ActrId) -> (u32, u64, String, String) {
 /// # Arguments
 /// * `from` - 发起方的 ActorId
 /// * `to` - 接收方的 ActorId
-/// * `clients` - 客户端连接映射表,用于查找角色偏好
+/// * `from_role` - 发起方连接的角色偏好
+/// * `to_role` - 接收方连接的角色偏好
 ///
 /// # Returns
 /// * `true` - 发起方应该是 offerer
@@ -1304,19 +2088,9 @@ fn actor_order_key(id: &ActrId) -> (u32, u64, String, String) {
 fn determine_webrtc_role(
     from: &ActrId,
     to: &ActrId,
-    clients: &HashMap<String, ClientConnection>,
+    from_role: Option<&str>,
+    to_role: Option<&str>,
 ) -> bool {
-    // 从客户端连接中查找双方的角色偏好
-    let from_role = clients
-        .values()
-        .find(|c| c.actor_id.as_ref() == Some(from))
-        .and_then(|c| c.webrtc_role.as_deref());
-
-    let to_role = clients
-        .values()
-        .find(|c| c.actor_id.as_ref() == Some(to))
-        .and_then(|c| c.webrtc_role.as_deref());
-
     let is_offerer = if from_role == Some("answer") && to_role != Some("answer") {
         false
     } else if to_role == Some("answer") && from_role != Some("answer") {
@@ -1339,33 +2113,33 @@ async fn send_role_assignment(
     target_actor: &ActrId,
     server: &SignalingServerHandle,
     relay: ActrRelay,
-    #[cfg(feature = "opentelemetry")] remote_context: opentelemetry::Context,
+    #[cfg(feature = "opentelemetry")] trace_context: opentelemetry::Context,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let flow = signaling_envelope::Flow::ActrRelay(relay);
     #[allow(unused_mut)]
     let mut envelope = server.create_new_envelope(flow);
 
     #[cfg(feature = "opentelemetry")]
-    inject_trace_context(&remote_context, &mut envelope);
-
-    let mut buf = Vec::new();
-    envelope.encode(&mut buf)?;
+    inject_trace_context(&trace_context, &mut envelope);
 
-    let clients_guard = server.clients.read().await;
-    if let Some(client) = clients_guard.values().find(|client| {
-        client.actor_id.as_ref().is_some_and(|id| {
-            id.realm.realm_id == target_actor.realm.realm_id
-                && id.serial_number == target_actor.serial_number
-        })
-    }) {
-        debug!(
-            "send_role_assignment: 发送 envelope 到客户端 {:?}",
-            client.actor_id
-        );
-        client
-            .direct_sender
-            .send(WsMessage::Binary(buf.into()))
-            .map_err(|e| e.into())
+    // 克隆发送端后释放锁，避免背压等待期间阻塞其他连接
+    let sender = match lookup_client_id(target_actor, server).await {
+        Some(client_id) => {
+            server
+                .clients
+                .with(&client_id, |client| {
+                    debug!(
+                        "send_role_assignment: 发送 envelope 到客户端 {:?}",
+                        client.actor_id
+                    );
+                    client.direct_sender.clone()
+                })
+                .await
+        }
+        None => None,
+    };
+    if let Some(sender) = sender {
+        sender.send_envelope(envelope).await.map_err(|e| e.into())
     } else {
         warn!(
             "⚠️ send_role_assignment: 未找到目标 Actor {}",
@@ -1377,27 +2151,26 @@ async fn send_role_assignment(
 
 /// 发送 SignalingEnvelope 到客户端
 #[cfg_attr(feature = "opentelemetry", instrument(level = "debug", skip_all, fields(client_id, envelope_id = envelope.envelope_id)))]
-async fn send_envelope_to_client(
+pub(crate) async fn send_envelope_to_client(
     client_id: &str,
     #[allow(unused_mut)] mut envelope: SignalingEnvelope,
     server: &SignalingServerHandle,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let clients_guard = server.clients.read().await;
+    // 克隆发送端后释放锁，避免背压等待期间阻塞其他连接
+    let sender = server
+        .clients
+        .with(client_id, |client| client.direct_sender.clone())
+        .await;
 
-    if let Some(client) = clients_guard.get(client_id) {
+    if let Some(sender) = sender {
+        // 保留调用方已注入的 context（如中继转发），否则使用当前 span
         #[cfg(feature = "opentelemetry")]
-        {
-            use tracing_opentelemetry::OpenTelemetrySpanExt;
-            let context = tracing::Span::current().context();
-            inject_trace_context(&context, &mut envelope);
+        if envelope.traceparent.is_none() {
+            inject_trace_context(&current_trace_context(), &mut envelope);
         }
 
-        // 编码 protobuf
-        let mut buf = Vec::new();
-        envelope.encode(&mut buf)?;
-
-        // 发送 Binary 消息
-        match client.direct_sender.send(WsMessage::Binary(buf.into())) {
+        // 写入序号后编码为 Binary 消息（复用线程本地编码缓冲区）
+        match sender.send_envelope(envelope).await {
             Ok(_) => {
                 info!("✅ 成功发送 envelope 到客户端 {}", client_id);
                 Ok(())
@@ -1414,31 +2187,79 @@ async fn send_envelope_to_client(
 }
 
 /// 清理客户端连接
-async fn cleanup_client(client_id: &str, server: &SignalingServerHandle) {
-    let removed_client = {
-        let mut clients_guard = server.clients.write().await;
-        clients_guard.remove(client_id)
+pub(crate) async fn cleanup_client(client_id: &str, server: &SignalingServerHandle) {
+    // 已绑定身份的连接先锁定该 Actor 的索引分片，再移除连接并更新索引
+    let bound_actor = server
+        .clients
+        .with(client_id, |client| client.actor_id.clone())
+        .await
+        .flatten();
+    let mut actor_index = match bound_actor {
+        Some(ref actor_id) => Some(server.actor_id_index.write(actor_id).await),
+        None => None,
     };
+    let removed_client = server.clients.remove(client_id).await;
 
     if let Some(client) = removed_client {
         if let Some(actor_id) = client.actor_id {
-            info!("🧹 清理 Actor {} 的连接", actor_id.serial_number);
+            // 加锁后连接才绑定身份时改为锁定实际身份所在的分片
+            let mut actor_index = match actor_index.take() {
+                Some(guard) if bound_actor.as_ref() == Some(&actor_id) => guard,
+                stale => {
+                    drop(stale);
+                    server.actor_id_index.write(&actor_id).await
+                }
+            };
 
-            // Remove all services for this Actor from the ServiceRegistry to avoid stale ghost instances
-            server
-                .service_registry
-                .write()
+            // 同一 Actor 仍有其他连接时（allow_multiple 策略）由最新的连接接替
+            let successor = connections_of_actor(&actor_id, None, server)
                 .await
-                .unregister_actor(&actor_id);
+                .into_iter()
+                .max_by_key(|conn| conn.connected_at)
+                .map(|conn| conn.client_id);
 
-            let mut actor_index = server.actor_id_index.write().await;
-            match actor_index.remove(&actor_id) {
-                Some(mapped_client) if mapped_client != client_id => warn!(
-                    "⚠️  Actor {} 索引指向意外客户端 {}，已移除",
-                    actor_id.serial_number, mapped_client
-                ),
-                None => warn!("⚠️  Actor {} 清理时未找到索引条目", actor_id.serial_number),
-                _ => {}
+            if let Some(successor) = successor {
+                info!(
+                    "🧹 清理 Actor {} 的连接 {}，由连接 {} 接替",
+                    actor_id.serial_number, client_id, successor
+                );
+                actor_index.insert(actor_id, successor);
+            } else {
+                info!("🧹 清理 Actor {} 的连接", actor_id.serial_number);
+
+                // Remove all services for this Actor from the ServiceRegistry to avoid stale ghost instances
+                server
+                    .service_registry
+                    .write()
+                    .await
+                    .unregister_actor(&actor_id);
+
+                let removed = actor_index.remove(&actor_id);
+                if removed.is_some() {
+                    usage::record_actor_disconnected(actor_id.realm.realm_id);
+                }
+                match removed {
+                    Some(mapped_client) if mapped_client != client_id => warn!(
+                        "⚠️  Actor {} 索引指向意外客户端 {}，已移除",
+                        actor_id.serial_number, mapped_client
+                    ),
+                    None => warn!("⚠️  Actor {} 清理时未找到索引条目", actor_id.serial_number),
+                    _ => {}
+                }
+
+                // 保留可恢复会话（Presence 订阅保持不变，直到恢复或超出窗口）。
+                // 须在释放索引分片写锁前完成，否则并发的恢复可能先登记新连接，
+                // 随后被这里重新标记为断开
+                if let Some(ref resumption) = server.resumption
+                    && resumption.park(&actor_id)
+                {
+                    info!(
+                        "⏸️  Actor {} 会话已保留 {}s 等待恢复",
+                        actor_id.serial_number,
+                        resumption.window_secs()
+                    );
+                }
+                drop(actor_index);
             }
         }
 
@@ -1447,6 +2268,81 @@ async fn cleanup_client(client_id: &str, server: &SignalingServerHandle) {
             limiter.remove_connection(client_id).await;
         }
     }
+
+    if let Some(ref tracker) = server.spec_dependencies {
+        tracker.remove_client(client_id);
+    }
+}
+
+/// 目标 Actor 断开且持有可恢复会话时缓存消息，返回是否已缓存
+///
+/// 在目标索引分片读锁内确认目标不在线后入队，与重连时在写锁内取出缓存互斥
+async fn queue_for_resumption(
+    actor_id: &ActrId,
+    envelope: &SignalingEnvelope,
+    server: &SignalingServerHandle,
+) -> bool {
+    let Some(ref resumption) = server.resumption else {
+        return false;
+    };
+
+    let actor_index = server.actor_id_index.read(actor_id).await;
+    let online = match actor_index.get(actor_id) {
+        Some(client_id) => server.clients.contains_key(client_id).await,
+        None => false,
+    };
+    !online && resumption.enqueue(actor_id, envelope.clone())
+}
+
+/// Actor 当前连接的 WebRTC 角色偏好
+async fn webrtc_role_of(actor_id: &ActrId, server: &SignalingServerHandle) -> Option<String> {
+    let client_id = lookup_client_id(actor_id, server).await?;
+    server
+        .clients
+        .with(&client_id, |client| client.webrtc_role.clone())
+        .await
+        .flatten()
+}
+
+/// 构造恢复 token 通知（未启用会话恢复或没有会话时为 None）
+fn resumption_notice(
+    actor_id: &ActrId,
+    resumed: bool,
+    replayed: usize,
+    server: &SignalingServerHandle,
+) -> Option<SignalingEnvelope> {
+    let resumption = server.resumption.as_ref()?;
+    let notice = crate::resumption::ResumptionNotice {
+        token: resumption.token_of(actor_id)?,
+        window_secs: resumption.window_secs(),
+        resumed,
+        replayed,
+    };
+    let flow = signaling_envelope::Flow::ServerToActr(SignalingToActr {
+        target: actor_id.clone(),
+        payload: Some(signaling_to_actr::Payload::Error(
+            notice.to_error_response(),
+        )),
+    });
+    Some(server.create_new_envelope(flow))
+}
+
+/// 会话恢复时直接写出的消息：恢复通知在前，缓存消息在后，均已写入序号并编码
+///
+/// 需在登记新连接前调用，此时没有其他发送方，之后入队的消息序号必然更大
+async fn resumption_replay(
+    actor_id: &ActrId,
+    queued: Vec<SignalingEnvelope>,
+    sender: &OutboundSender,
+    server: &SignalingServerHandle,
+) -> Vec<Bytes> {
+    let notice = resumption_notice(actor_id, true, queued.len(), server);
+    let mut replay = Vec::with_capacity(queued.len() + 1);
+    for mut envelope in notice.into_iter().chain(queued) {
+        sender.stamp(&mut envelope).await;
+        replay.push(encode_envelope(&envelope));
+    }
+    replay
 }
 
 /// 处理 Credential 更新请求
@@ -1468,7 +2364,7 @@ async fn handle_credential_update(
         None => {
             warn!("⚠️  AIS 客户端未配置，无法刷新 Credential");
             let error_response = ErrorResponse {
-                code: 503,
+                code: crate::tunnel::SERVICE_UNAVAILABLE_CODE,
                 message: "AIS service not configured".to_string(),
             };
 
@@ -1497,16 +2393,16 @@ async fn handle_credential_update(
                     let expires_at = register_ok.credential_expires_at;
 
                     // 更新客户端连接中存储的 credential
-                    {
-                        let mut clients_guard = server.clients.write().await;
-                        if let Some(client_conn) = clients_guard.get_mut(client_id) {
+                    server
+                        .clients
+                        .update(client_id, |client_conn| {
                             client_conn.credential = Some(new_credential.clone());
                             info!(
                                 "✅ 已更新 Actor {} 的 Credential (key_id={})",
                                 source.serial_number, new_credential.token_key_id
                             );
-                        }
-                    }
+                        })
+                        .await;
 
                     // 返回成功响应（使用 RegisterResponse，因为协议中没有 CredentialUpdateResponse）
                     // 新的 PSK 已被加密到 token 中，客户端必须同步更新本地 PSK，否则 TURN 认证会失败
@@ -1604,95 +2500,145 @@ async fn handle_discovery_request(
         req.limit.unwrap_or(64)
     );
 
-    // 从 ServiceRegistry 查询所有服务
+    // Realm 聚合服务发现速率限制
+    if let Some(ref limiter) = server.realm_rate_limiter
+        && let Err(e) = limiter
+            .check(
+                source.realm.realm_id,
+                crate::ratelimit::RealmOperation::Discovery,
+            )
+            .await
+    {
+        send_error_response(
+            client_id,
+            &source,
+            429,
+            &e,
+            server,
+            Some(request_envelope_id),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // 从 ServiceRegistry 按 ActrType 查询同 realm 的服务（排序稳定，保证 limit 截取结果确定）；
+    // manufacturer 以 `?` 开头时携带名称前缀、标签、排序与翻页条件
+    let query = match crate::service_registry::DiscoveryQuery::from_actor_filter(
+        source.realm.realm_id,
+        req.manufacturer.as_deref(),
+    ) {
+        Ok(query) => query,
+        Err(e) => {
+            send_error_response(
+                client_id,
+                &source,
+                400,
+                &e,
+                server,
+                Some(request_envelope_id),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let limit = req.limit.unwrap_or(64) as usize;
+
     let registry = server.service_registry.read().await;
-    let services = registry.discover_all(req.manufacturer.as_deref());
-    let total_count = services.len(); // Save count before moving
+    let types = match registry.discover_types(&query) {
+        Ok(types) => types,
+        Err(e) => {
+            drop(registry);
+            send_error_response(
+                client_id,
+                &source,
+                400,
+                &e,
+                server,
+                Some(request_envelope_id),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let total_count = types.len();
 
-    // Apply ACL filtering (if ACL is enabled)
+    // Apply ACL filtering：ACL 规则按类型生效，每个类型只需检查一次
     use actrix_common::realm::acl::ActorAcl;
     let source_realm = source.realm.realm_id;
     let source_type = type_key(&source.r#type);
 
-    let mut acl_filtered_services = Vec::new();
-
-    // ACL always enabled: filter services based on ACL rules
-    for service in services {
-        let target_realm = service.actor_id.realm.realm_id;
-        let target_type = type_key(&service.actor_id.r#type);
+    let mut entries = Vec::new();
+    for discovered in types {
+        if entries.len() >= limit {
+            break;
+        }
 
-        // Only check ACL if in same realm
-        if source_realm == target_realm {
-            match ActorAcl::can_discover(source_realm, &source_type, &target_type).await {
-                Ok(true) => acl_filtered_services.push(service),
-                Ok(false) => {
-                    debug!(
-                        "ACL denied discovery: {} cannot discover {}",
-                        source.serial_number, service.actor_id.serial_number
-                    );
-                }
-                Err(e) => {
-                    warn!(
-                        "ACL check failed for {} -> {}: {}",
-                        source.serial_number, service.actor_id.serial_number, e
-                    );
-                }
+        match ActorAcl::can_discover(source_realm, &source_type, &discovered.type_key).await {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!(
+                    "ACL denied discovery: {} cannot discover {}",
+                    source.serial_number, discovered.type_key
+                );
+                continue;
             }
-        } else {
-            // Cross-realm discovery denied
+            Err(e) => {
+                warn!(
+                    "ACL check failed for {} -> {}: {}",
+                    source.serial_number, discovered.type_key, e
+                );
+                continue;
+            }
+        }
+
+        if let Some(ref authz) = server.authz_gate
+            && let Err(reason) = authz
+                .check(crate::authz_hook::AuthzRequest::discover(
+                    &source,
+                    &discovered.type_key,
+                ))
+                .await
+        {
             debug!(
-                "Cross-realm discovery denied: {} -> {}",
-                source_realm, target_realm
+                "Authz hook denied discovery: {} cannot discover {}: {}",
+                source.serial_number, discovered.type_key, reason
             );
+            continue;
         }
-    }
-    info!(
-        "ACL filtering: {} -> {} services",
-        total_count,
-        acl_filtered_services.len()
-    );
-
-    // 按 ActrType 聚合服务（使用 HashMap 去重）
-    use std::collections::HashMap;
-    let mut type_map: HashMap<String, actr_protocol::discovery_response::TypeEntry> =
-        HashMap::new();
 
-    for service in acl_filtered_services {
-        let type_key = type_key(&service.actor_id.r#type);
-
-        // 如果该类型还未添加，创建新条目
-        type_map.entry(type_key).or_insert_with(|| {
-            let (fingerprint, description, published_at, tags) = service
-                .service_spec
-                .as_ref()
-                .map(|spec| {
-                    (
-                        spec.fingerprint.clone(),
-                        spec.description.clone(),
-                        spec.published_at,
-                        spec.tags.clone(),
-                    )
-                })
-                .unwrap_or_else(|| ("unknown".to_string(), None, None, Vec::new()));
-
-            actr_protocol::discovery_response::TypeEntry {
-                actr_type: service.actor_id.r#type.clone(),
-                name: service.service_name.clone(),
-                description,
-                service_fingerprint: fingerprint,
-                published_at,
-                tags,
-            }
+        let service = discovered.services[0];
+        let (fingerprint, description, published_at, tags) = service
+            .service_spec
+            .as_ref()
+            .map(|spec| {
+                (
+                    spec.fingerprint.clone(),
+                    spec.description.clone(),
+                    spec.published_at,
+                    spec.tags.clone(),
+                )
+            })
+            .unwrap_or_else(|| ("unknown".to_string(), None, None, Vec::new()));
+
+        entries.push(actr_protocol::discovery_response::TypeEntry {
+            actr_type: service.actor_id.r#type.clone(),
+            name: service.service_name.clone(),
+            description,
+            service_fingerprint: fingerprint,
+            published_at,
+            tags,
         });
     }
 
-    // 转换为 Vec 并应用 limit
-    let mut entries: Vec<_> = type_map.into_values().collect();
-    let limit = req.limit.unwrap_or(64) as usize;
-    entries.truncate(limit);
-
     drop(registry);
 
+    info!(
+        "ACL filtering: {} types matched, {} returned (limit {})",
+        total_count,
+        entries.len(),
+        limit
+    );
+
     info!(
         "✅ 为 Actor {} 返回 {} 个服务类型",
         source.serial_number,
@@ -1739,6 +2685,7 @@ async fn handle_route_candidates_request(
     // 从 ServiceRegistry 查询所有匹配 target_type 的实例
     let registry = server.service_registry.read().await;
     let candidates = registry.find_by_actr_type(&req.target_type);
+    let preferred_metadata = registry.metadata_affinity(&source);
     drop(registry);
 
     let total_candidates = candidates.len();
@@ -1767,7 +2714,23 @@ async fn handle_route_candidates_request(
 
         if source_realm == target_realm {
             match ActorAcl::can_discover(source_realm, &source_type, &candidate_type_key).await {
-                Ok(true) => acl_filtered_candidates.push(candidate),
+                Ok(true) => {
+                    if let Some(ref authz) = server.authz_gate
+                        && let Err(reason) = authz
+                            .check(crate::authz_hook::AuthzRequest::discover(
+                                &source,
+                                &candidate_type_key,
+                            ))
+                            .await
+                    {
+                        debug!(
+                            "Authz hook denied route candidate: {} cannot access {}: {}",
+                            source.serial_number, candidate.actor_id.serial_number, reason
+                        );
+                    } else {
+                        acl_filtered_candidates.push(candidate);
+                    }
+                }
                 Ok(false) => {
                     debug!(
                         "ACL denied route candidate: {} cannot access {}",
@@ -1806,10 +2769,25 @@ async fn handle_route_candidates_request(
         }
     });
 
+    // 记录候选的 fingerprint，用于跟踪依赖关系
+    let candidate_fingerprints: HashMap<ActrId, String> = acl_filtered_candidates
+        .iter()
+        .filter_map(|c| {
+            c.service_spec
+                .as_ref()
+                .map(|spec| (c.actor_id.clone(), spec.fingerprint.clone()))
+        })
+        .collect();
+
     // 兼容性协商逻辑
     let (ranked_actor_ids, compatibility_info, has_exact_match, is_sub_healthy, ws_address_map) =
         if !client_fingerprint.is_empty() {
-            // 有 client_fingerprint 就启用协商模式
+            // 有 client_fingerprint 就启用协商模式；
+            // 元数据亲和：与请求方部署属性（如 region）相同的实例优先，协商按此顺序保留同级候选
+            LoadBalancer::sort_by_metadata_affinity(
+                &mut acl_filtered_candidates,
+                &preferred_metadata,
+            );
             perform_compatibility_negotiation(
                 &acl_filtered_candidates,
                 &client_fingerprint,
@@ -1821,7 +2799,7 @@ async fn handle_route_candidates_request(
             )
             .await
         } else {
-            // 非协商模式：使用原有的 LoadBalancer 排序
+            // 非协商模式：使用原有的 LoadBalancer 排序（含元数据亲和）
             let cache_guard = server.compatibility_cache.read().await;
             let compatibility_cache = Some(&*cache_guard);
 
@@ -1833,11 +2811,14 @@ async fn handle_route_candidates_request(
 
             let ranked = LoadBalancer::rank_candidates(
                 acl_filtered_candidates,
-                req.criteria.as_ref(),
-                Some(client_id),
-                client_location,
-                compatibility_cache,
-                None,
+                RankOptions {
+                    criteria: req.criteria.as_ref(),
+                    client_id: Some(client_id),
+                    client_location,
+                    compatibility_cache,
+                    preferred_metadata: Some(&preferred_metadata),
+                    ..Default::default()
+                },
             );
 
             // ws_address 通过专用参数返回，compat_info 保持为空
@@ -1852,6 +2833,25 @@ async fn handle_route_candidates_request(
         is_sub_healthy
     );
 
+    // 记录依赖关系：优先使用客户端声明的 fingerprint，否则使用首选候选的 fingerprint
+    if let Some(ref tracker) = server.spec_dependencies {
+        let fingerprint = if client_fingerprint.is_empty() {
+            ranked_actor_ids
+                .first()
+                .and_then(|id| candidate_fingerprints.get(id))
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            client_fingerprint.clone()
+        };
+        tracker.record_route(
+            &type_key(&req.target_type),
+            client_id,
+            &source,
+            &fingerprint,
+        );
+    }
+
     let ws_address_map_proto: Vec<actr_protocol::WsAddressEntry> = ws_address_map
         .into_iter()
         .map(|(id, ws)| actr_protocol::WsAddressEntry {
@@ -2160,6 +3160,127 @@ async fn perform_compatibility_negotiation(
     )
 }
 
+/// 服务以新 fingerprint 注册后，通知依赖旧 fingerprint 且存在破坏性变更的在线客户端
+async fn notify_spec_incompatibility(
+    server: &SignalingServerHandle,
+    provider: &ActrId,
+    new_spec: &actr_protocol::ServiceSpec,
+) {
+    use crate::compatibility_cache::{CompatibilityReportData, GlobalCompatibilityCache};
+    use crate::spec_notice::SpecIncompatibilityNotice;
+    use actr_version::{CompatibilityLevel, ServiceCompatibility};
+
+    let Some(ref tracker) = server.spec_dependencies else {
+        return;
+    };
+    let service_type = type_key(&provider.r#type);
+    let dependents = tracker.pending_dependents(&service_type, &new_spec.fingerprint);
+    if dependents.is_empty() {
+        return;
+    }
+
+    let storage = {
+        let registry = server.service_registry.read().await;
+        registry.get_storage()
+    };
+
+    for dependent in dependents {
+        let cache_key = GlobalCompatibilityCache::build_cache_key(
+            &service_type,
+            &dependent.fingerprint,
+            &new_spec.fingerprint,
+        );
+        let cached = server
+            .compatibility_cache
+            .write()
+            .await
+            .query(&cache_key)
+            .analysis_result;
+
+        let analysis =
+            match cached {
+                Some(analysis) => analysis,
+                None => {
+                    let old_spec = match &storage {
+                        Some(storage) => storage
+                            .get_proto_by_fingerprint(&provider.r#type, &dependent.fingerprint)
+                            .await
+                            .unwrap_or_else(|e| {
+                                warn!("获取依赖方 spec 失败: {}", e);
+                                None
+                            }),
+                        None => None,
+                    };
+                    let Some(old_spec) = old_spec else {
+                        debug!(
+                            "依赖方 fingerprint {} 无对应 spec，跳过不兼容检查",
+                            dependent.fingerprint
+                        );
+                        continue;
+                    };
+
+                    match ServiceCompatibility::analyze_compatibility(&old_spec, new_spec) {
+                        Ok(analysis) => {
+                            server.compatibility_cache.write().await.store(
+                                CompatibilityReportData {
+                                    from_fingerprint: dependent.fingerprint.clone(),
+                                    to_fingerprint: new_spec.fingerprint.clone(),
+                                    service_type: service_type.clone(),
+                                    analysis_result: analysis.clone(),
+                                },
+                            );
+                            analysis
+                        }
+                        Err(e) => {
+                            warn!("兼容性分析失败: service_type={} error={}", service_type, e);
+                            continue;
+                        }
+                    }
+                }
+            };
+
+        if !matches!(analysis.level, CompatibilityLevel::BreakingChanges) {
+            continue;
+        }
+
+        let notice = SpecIncompatibilityNotice {
+            service_type: service_type.clone(),
+            service_name: new_spec.name.clone(),
+            provider_serial_number: provider.serial_number,
+            previous_fingerprint: dependent.fingerprint.clone(),
+            new_fingerprint: new_spec.fingerprint.clone(),
+            breaking_changes: analysis
+                .breaking_changes
+                .iter()
+                .map(|c| c.description.clone())
+                .collect(),
+            issued_at: chrono::Utc::now().timestamp(),
+        };
+
+        let flow = signaling_envelope::Flow::ServerToActr(SignalingToActr {
+            target: dependent.actor_id.clone(),
+            payload: Some(signaling_to_actr::Payload::Error(
+                notice.to_error_response(),
+            )),
+        });
+        let envelope = server.create_new_envelope(flow);
+
+        match send_envelope_to_client(&dependent.client_id, envelope, server).await {
+            Ok(()) => {
+                tracker.mark_notified(&service_type, &dependent.client_id, &new_spec.fingerprint);
+                info!(
+                    "📣 已通知 Actor {} 服务 {} 存在破坏性变更 ({} -> {})",
+                    dependent.actor_id.serial_number,
+                    service_type,
+                    dependent.fingerprint,
+                    new_spec.fingerprint
+                );
+            }
+            Err(e) => warn!("⚠️  发送 SpecIncompatibilityNotice 失败: {}", e),
+        }
+    }
+}
+
 /// 将 actr-version 的 CompatibilityAnalysisResult 转换为 proto 版本
 fn convert_to_proto_analysis_result(
     result: &actr_version::CompatibilityAnalysisResult,
@@ -2342,6 +3463,248 @@ async fn handle_unsubscribe_actr_up(
     Ok(())
 }
 
+/// 处理运行时 ACL 更新请求，作用于请求方自身类型的入站规则
+async fn handle_update_acl(
+    source: ActrId,
+    error: &ErrorResponse,
+    client_id: &str,
+    server: &SignalingServerHandle,
+    request_envelope_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::acl_update::UpdateAclResponse;
+    use actrix_common::realm::{ActorAcl, RealmError};
+
+    let update = match crate::acl_update::from_error_response(error) {
+        Some(Ok(update)) => update,
+        Some(Err(e)) => {
+            warn!(
+                "Actor {} UpdateAclRequest 格式无效: {}",
+                source.serial_number, e
+            );
+            return send_error_response(
+                client_id,
+                &source,
+                400,
+                &format!("Invalid UpdateAclRequest: {e}"),
+                server,
+                Some(request_envelope_id),
+            )
+            .await;
+        }
+        None => {
+            return send_error_response(
+                client_id,
+                &source,
+                400,
+                "Invalid UpdateAclRequest: missing prefix",
+                server,
+                Some(request_envelope_id),
+            )
+            .await;
+        }
+    };
+
+    let realm_id = source.realm.realm_id;
+    let to_type = type_key(&source.r#type);
+    match ActorAcl::apply_update(realm_id, &to_type, &update).await {
+        Ok(rules) => {
+            info!(
+                "🔐 Actor {} 更新 ACL ({:?}): {} 条入站规则 -> {}",
+                source.serial_number,
+                update.mode,
+                rules.len(),
+                to_type
+            );
+            let response = UpdateAclResponse::new(to_type, &rules).to_error_response();
+            let flow = signaling_envelope::Flow::ServerToActr(SignalingToActr {
+                target: source,
+                payload: Some(signaling_to_actr::Payload::Error(response)),
+            });
+            let response_envelope = server.create_envelope(flow, Some(request_envelope_id));
+            send_envelope_to_client(client_id, response_envelope, server).await?;
+            Ok(())
+        }
+        Err(RealmError::ValidationError(msg)) => {
+            warn!("Actor {} ACL 更新校验失败: {}", source.serial_number, msg);
+            send_error_response(
+                client_id,
+                &source,
+                400,
+                &format!("Invalid ACL update: {msg}"),
+                server,
+                Some(request_envelope_id),
+            )
+            .await
+        }
+        Err(e) => {
+            error!("Actor {} ACL 更新失败: {}", source.serial_number, e);
+            send_error_response(
+                client_id,
+                &source,
+                500,
+                "Failed to update ACL",
+                server,
+                Some(request_envelope_id),
+            )
+            .await
+        }
+    }
+}
+
+/// 处理 Presence 快照请求：返回目标类型全部实例当前的在线状态
+async fn handle_presence_snapshot(
+    source: ActrId,
+    error: &ErrorResponse,
+    client_id: &str,
+    server: &SignalingServerHandle,
+    request_envelope_id: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::presence_snapshot::{
+        GetPresenceSnapshotRequest, GetPresenceSnapshotResponse, InstancePresence, PresenceStatus,
+    };
+    use actrix_common::realm::acl::ActorAcl;
+
+    let request = match GetPresenceSnapshotRequest::from_error_response(error) {
+        Some(Ok(request)) => request,
+        Some(Err(e)) => {
+            warn!(
+                "Actor {} GetPresenceSnapshotRequest 格式无效: {}",
+                source.serial_number, e
+            );
+            return send_error_response(
+                client_id,
+                &source,
+                400,
+                &format!("Invalid GetPresenceSnapshotRequest: {e}"),
+                server,
+                Some(request_envelope_id),
+            )
+            .await;
+        }
+        None => {
+            return send_error_response(
+                client_id,
+                &source,
+                400,
+                "Invalid GetPresenceSnapshotRequest: missing prefix",
+                server,
+                Some(request_envelope_id),
+            )
+            .await;
+        }
+    };
+    let target_type = request.target_type;
+    let target_type_key = type_key(&target_type);
+
+    // 与服务发现共用 Realm 聚合速率限制
+    if let Some(ref limiter) = server.realm_rate_limiter
+        && let Err(e) = limiter
+            .check(
+                source.realm.realm_id,
+                crate::ratelimit::RealmOperation::Discovery,
+            )
+            .await
+    {
+        return send_error_response(
+            client_id,
+            &source,
+            429,
+            &e,
+            server,
+            Some(request_envelope_id),
+        )
+        .await;
+    }
+
+    // ACL 按类型生效，整个快照只需检查一次
+    let mut allowed = ActorAcl::can_discover(
+        source.realm.realm_id,
+        &type_key(&source.r#type),
+        &target_type_key,
+    )
+    .await
+    .unwrap_or_else(|e| {
+        warn!(
+            "ACL check failed for {} -> {}: {}",
+            source.serial_number, target_type_key, e
+        );
+        false
+    });
+    if allowed
+        && let Some(ref authz) = server.authz_gate
+        && let Err(reason) = authz
+            .check(crate::authz_hook::AuthzRequest::discover(
+                &source,
+                &target_type_key,
+            ))
+            .await
+    {
+        debug!(
+            "Authz hook denied presence snapshot: {} cannot discover {}: {}",
+            source.serial_number, target_type_key, reason
+        );
+        allowed = false;
+    }
+    if !allowed {
+        warn!(
+            "⚠️  ACL denied presence snapshot: {} -> {}",
+            source.serial_number, target_type_key
+        );
+        return send_error_response(
+            client_id,
+            &source,
+            403,
+            "ACL policy denies presence snapshot of target type",
+            server,
+            Some(request_envelope_id),
+        )
+        .await;
+    }
+
+    let instances: Vec<(ActrId, u64)> = server
+        .service_registry
+        .read()
+        .await
+        .instances_of_type(source.realm.realm_id, &target_type)
+        .into_iter()
+        .filter(|service| service.actor_id != source)
+        .map(|service| (service.actor_id.clone(), service.last_heartbeat_time_secs))
+        .collect();
+
+    let mut presences = Vec::with_capacity(instances.len());
+    for (actr_id, last_heartbeat_secs) in instances {
+        let status = if server.actor_id_index.contains_key(&actr_id).await {
+            PresenceStatus::Online
+        } else {
+            PresenceStatus::Offline
+        };
+        presences.push(InstancePresence {
+            actr_id,
+            status,
+            last_heartbeat_secs,
+        });
+    }
+
+    let snapshot = GetPresenceSnapshotResponse::new(target_type, presences);
+    debug!(
+        "👀 Actor {} Presence 快照: {} ({}/{} 在线)",
+        source.serial_number,
+        target_type_key,
+        snapshot.online_count(),
+        snapshot.instances.len()
+    );
+
+    let flow = signaling_envelope::Flow::ServerToActr(SignalingToActr {
+        target: source,
+        payload: Some(signaling_to_actr::Payload::Error(
+            snapshot.to_error_response(),
+        )),
+    });
+    let response_envelope = server.create_envelope(flow, Some(request_envelope_id));
+    send_envelope_to_client(client_id, response_envelope, server).await?;
+    Ok(())
+}
+
 /// 发送通用错误响应
 #[cfg_attr(feature = "opentelemetry", tracing::instrument(level = "debug", skip_all, fields(client_id, reply_for = ?reply_for, target = ?target)))]
 async fn send_error_response(
@@ -2374,7 +3737,7 @@ async fn send_error_response(
 mod tests {
     use super::*;
     use actr_protocol::{ActrType, Realm};
-    use std::collections::HashMap;
+    use prost::Message as _;
 
     /// 创建测试用的 ActrId
     fn create_test_actr_id(serial: u64) -> ActrId {
@@ -2395,9 +3758,11 @@ mod tests {
             id: uuid::Uuid::new_v4().to_string(),
             actor_id: Some(actor_id),
             credential: None,
-            direct_sender: tokio::sync::mpsc::unbounded_channel().0,
+            direct_sender: outbound_channel(&ConnectionLimitsConfig::default()).0,
             client_ip: None,
             webrtc_role,
+            connected_at: 0,
+            fingerprint: String::new(),
         }
     }
 
@@ -2452,18 +3817,7 @@ mod tests {
             let from = create_test_actr_id(from_serial);
             let to = create_test_actr_id(to_serial);
 
-            // 创建 clients map
-            let mut clients = HashMap::new();
-            clients.insert(
-                format!("client_{from_serial}"),
-                create_test_client(from.clone(), from_role.map(|s| s.to_string())),
-            );
-            clients.insert(
-                format!("client_{to_serial}"),
-                create_test_client(to.clone(), to_role.map(|s| s.to_string())),
-            );
-
-            let is_offerer = determine_webrtc_role(&from, &to, &clients);
+            let is_offerer = determine_webrtc_role(&from, &to, from_role, to_role);
 
             assert_eq!(
                 is_offerer, expected,
@@ -2471,4 +3825,180 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_oversized_envelope_rejected_with_413() {
+        let mut server = SignalingServer::new();
+        server.limits.max_envelope_bytes = 8;
+        let handle = server.handle();
+
+        let (tx, mut rx) = outbound_channel(&handle.limits);
+        let mut client = create_test_client(create_test_actr_id(1), None);
+        client.direct_sender = tx;
+        let client_id = client.id.clone();
+        handle.clients.insert(client_id.clone(), client).await;
+
+        handle_client_envelope(Bytes::from_static(&[0u8; 16]), &client_id, &handle)
+            .await
+            .expect("oversized envelope should not break the connection");
+
+        let Some(WsMessage::Binary(data)) = rx.recv().await else {
+            panic!("expected binary EnvelopeError");
+        };
+        let envelope = SignalingEnvelope::decode(data.as_ref()).unwrap();
+        match envelope.flow {
+            Some(signaling_envelope::Flow::EnvelopeError(error)) => assert_eq!(error.code, 413),
+            other => panic!("unexpected flow: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_disconnected_actor_with_session_queues_messages() {
+        let mut server = SignalingServer::new();
+        server.resumption = Some(Arc::new(crate::resumption::ResumptionManager::new(
+            &actrix_common::config::signaling::ResumptionConfig {
+                enabled: true,
+                ..Default::default()
+            },
+        )));
+        let handle = server.handle();
+        let resumption = handle.resumption.clone().unwrap();
+
+        let actor_id = create_test_actr_id(1);
+        let client = create_test_client(actor_id.clone(), None);
+        let client_id = client.id.clone();
+        handle.clients.insert(client_id.clone(), client).await;
+        handle
+            .actor_id_index
+            .insert(actor_id.clone(), client_id.clone())
+            .await;
+        let token = resumption.issue(&actor_id);
+
+        let envelope =
+            handle.create_new_envelope(signaling_envelope::Flow::ServerToActr(SignalingToActr {
+                target: actor_id.clone(),
+                payload: Some(signaling_to_actr::Payload::Error(ErrorResponse {
+                    code: 0,
+                    message: "hello".to_string(),
+                })),
+            }));
+
+        // 在线时不缓存
+        assert!(!queue_for_resumption(&actor_id, &envelope, &handle).await);
+
+        // 断开后会话保留，消息进入缓存
+        cleanup_client(&client_id, &handle).await;
+        assert!(queue_for_resumption(&actor_id, &envelope, &handle).await);
+
+        let queued = resumption.resume(&token, &actor_id).unwrap();
+        assert_eq!(queued, vec![envelope.clone()]);
+
+        // 没有会话的 Actor 不缓存
+        assert!(!queue_for_resumption(&create_test_actr_id(2), &envelope, &handle).await);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_identity_allow_multiple() {
+        use actrix_common::config::signaling::{DuplicateIdentityConfig, DuplicateIdentityPolicy};
+
+        let mut server = SignalingServer::new();
+        server.duplicate_identity =
+            Arc::new(DuplicateIdentityGuard::new(&DuplicateIdentityConfig {
+                policy: DuplicateIdentityPolicy::AllowMultiple,
+                max_connections: 2,
+                ..Default::default()
+            }));
+        let handle = server.handle();
+        let mut events = handle.duplicate_identity.subscribe();
+        let actor_id = create_test_actr_id(1);
+
+        let mut ids = Vec::new();
+        for (connected_at, fingerprint) in [(10, "fp-a"), (20, "fp-b"), (30, "fp-c")] {
+            let mut client = create_test_client(actor_id.clone(), None);
+            client.actor_id = None;
+            client.connected_at = connected_at;
+            client.fingerprint = fingerprint.to_string();
+            let client_id = client.id.clone();
+            handle.clients.insert(client_id.clone(), client).await;
+            let credential = AIdCredential::default();
+            assert!(bind_connection_identity(&client_id, &actor_id, &credential, &handle).await);
+            ids.push(client_id);
+        }
+
+        // 第三个连接挤掉最早的连接
+        let event = events.recv().await.unwrap();
+        assert_eq!(event.replaced_client_id, ids[0]);
+        assert_eq!(event.new_client_id, ids[2]);
+        assert!(!handle.clients.contains_key(&ids[0]).await);
+        assert!(handle.clients.contains_key(&ids[1]).await);
+        assert!(handle.clients.contains_key(&ids[2]).await);
+        let indexed = || handle.actor_id_index.with(&actor_id, Clone::clone);
+        assert_eq!(indexed().await, Some(ids[2].clone()));
+
+        // 最新连接断开后索引由剩余连接接替
+        cleanup_client(&ids[2], &handle).await;
+        assert_eq!(indexed().await, Some(ids[1].clone()));
+
+        cleanup_client(&ids[1], &handle).await;
+        assert!(indexed().await.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_resume_racing_cleanup_never_parks_live_session() {
+        let mut server = SignalingServer::new();
+        server.resumption = Some(Arc::new(crate::resumption::ResumptionManager::new(
+            &actrix_common::config::signaling::ResumptionConfig {
+                enabled: true,
+                ..Default::default()
+            },
+        )));
+        let handle = server.handle();
+        let resumption = handle.resumption.clone().unwrap();
+        let actor_id = create_test_actr_id(1);
+
+        for _ in 0..200 {
+            let old = create_test_client(actor_id.clone(), None);
+            let old_id = old.id.clone();
+            handle.clients.insert(old_id.clone(), old).await;
+            handle
+                .actor_id_index
+                .insert(actor_id.clone(), old_id.clone())
+                .await;
+            let token = resumption.issue(&actor_id);
+
+            // 与 handle_websocket_connection 相同：在索引分片写锁内恢复并登记新连接
+            let resume = {
+                let handle = handle.clone();
+                let resumption = resumption.clone();
+                let actor_id = actor_id.clone();
+                tokio::spawn(async move {
+                    let mut actor_index = handle.actor_id_index.write(&actor_id).await;
+                    resumption
+                        .resume(&token, &actor_id)
+                        .expect("session should resume");
+                    let new = create_test_client(actor_id.clone(), None);
+                    let new_id = new.id.clone();
+                    handle.clients.insert(new_id.clone(), new).await;
+                    actor_index.insert(actor_id, new_id.clone());
+                    new_id
+                })
+            };
+            let cleanup = {
+                let handle = handle.clone();
+                tokio::spawn(async move { cleanup_client(&old_id, &handle).await })
+            };
+            let (new_id, _) = tokio::join!(resume, cleanup);
+            let new_id = new_id.unwrap();
+
+            // 新连接在线时会话不得处于保留状态（否则消息会被缓存而不是投递）
+            assert_eq!(
+                handle.actor_id_index.with(&actor_id, Clone::clone).await,
+                Some(new_id.clone())
+            );
+            assert!(!resumption.enqueue(&actor_id, SignalingEnvelope::default()));
+
+            cleanup_client(&new_id, &handle).await;
+            resumption.remove(&actor_id);
+        }
+    }
 }
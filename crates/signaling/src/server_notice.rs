@@ -0,0 +1,216 @@
+//! 运维广播通知 (ServerNotice)
+//!
+//! 节点向全部或按条件筛选的在线 Actor 推送运维通知，例如维护倒计时、弃用提醒、配额告警。
+//! 通过管理 API (`POST /admin/notices`) 或 Supervisord gRPC (`BroadcastServerNotice`) 触发。
+//!
+//! # 投递方式
+//! 与 [`crate::spec_notice`] 相同，actr-protocol 目前没有专用的通知 payload，
+//! 通知通过不带 `reply_for` 的 `ErrorResponse` 下发：`code` 为 [`SERVER_NOTICE_CODE`]，
+//! `message` 为 [`SERVER_NOTICE_PREFIX`] 加 JSON 编码的 [`ServerNotice`]。
+
+use crate::actr_type_utils::type_key;
+use crate::server::{SignalingServer, send_envelope_to_client};
+use crate::tunnel;
+use actr_protocol::{
+    ActrId, ErrorResponse, SignalingToActr, signaling_envelope, signaling_to_actr,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// 通知使用的 ErrorResponse code（100，信息性）
+pub const SERVER_NOTICE_CODE: u32 = tunnel::SERVER_NOTICE.code;
+
+/// 通知 message 前缀，其后为 JSON 编码的 [`ServerNotice`]
+pub const SERVER_NOTICE_PREFIX: &str = tunnel::SERVER_NOTICE.prefix;
+
+/// 客户端可见的通知级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoticeSeverity {
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl NoticeSeverity {
+    /// 从字符串解析（大小写不敏感）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" => Some(Self::Warning),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// 运维广播通知
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerNotice {
+    pub notice_id: String,
+    pub severity: NoticeSeverity,
+    /// 通知类别（如 maintenance / deprecation / quota），由运维自定义
+    pub category: String,
+    pub message: String,
+    /// 通知所指事件的生效时间（Unix 秒），例如维护开始时间，供客户端倒计时
+    pub effective_at: Option<i64>,
+    /// 发出时间 (Unix 秒)
+    pub issued_at: i64,
+}
+
+impl ServerNotice {
+    /// 创建新通知，分配 notice_id 与发出时间
+    pub fn new(
+        severity: NoticeSeverity,
+        category: impl Into<String>,
+        message: impl Into<String>,
+        effective_at: Option<i64>,
+    ) -> Self {
+        Self {
+            notice_id: Uuid::new_v4().to_string(),
+            severity,
+            category: category.into(),
+            message: message.into(),
+            effective_at,
+            issued_at: chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// 编码为下发给客户端的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::SERVER_NOTICE.encode(self)
+    }
+}
+
+/// 通知接收方筛选条件，各条件之间为 AND，条件为空表示不限制
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NoticeFilter {
+    /// 限定 Realm
+    #[serde(default)]
+    pub realm_ids: Vec<u32>,
+    /// 限定 ActrType（`manufacturer:name` 或带版本的 type key）
+    #[serde(default)]
+    pub actr_types: Vec<String>,
+}
+
+impl NoticeFilter {
+    /// 判断 Actor 是否为通知接收方
+    pub fn matches(&self, actor_id: &ActrId) -> bool {
+        let realm_matches =
+            self.realm_ids.is_empty() || self.realm_ids.contains(&actor_id.realm.realm_id);
+        let type_matches = self.actr_types.is_empty() || {
+            let short_key = format!("{}:{}", actor_id.r#type.manufacturer, actor_id.r#type.name);
+            let full_key = type_key(&actor_id.r#type);
+            self.actr_types
+                .iter()
+                .any(|t| *t == short_key || *t == full_key)
+        };
+        realm_matches && type_matches
+    }
+}
+
+/// 向匹配筛选条件的已注册连接广播通知，返回成功投递的连接数
+///
+/// 尚未完成注册的连接没有 ActrId，不接收通知
+pub async fn broadcast_notice(
+    server: &SignalingServer,
+    notice: &ServerNotice,
+    filter: &NoticeFilter,
+) -> usize {
+    let recipients: Vec<(String, ActrId)> = server
+        .clients
+        .collect(|_, client| {
+            client
+                .actor_id
+                .as_ref()
+                .filter(|actor_id| filter.matches(actor_id))
+                .map(|actor_id| (client.id.clone(), actor_id.clone()))
+        })
+        .await;
+
+    let handle = server.handle();
+    let mut delivered = 0;
+    for (client_id, actor_id) in recipients {
+        let flow = signaling_envelope::Flow::ServerToActr(SignalingToActr {
+            target: actor_id,
+            payload: Some(signaling_to_actr::Payload::Error(
+                notice.to_error_response(),
+            )),
+        });
+        let envelope = handle.create_new_envelope(flow);
+        let sent = send_envelope_to_client(&client_id, envelope, &handle)
+            .await
+            .is_ok();
+        if sent {
+            delivered += 1;
+        } else {
+            warn!(
+                "⚠️  ServerNotice {} 投递到 {} 失败",
+                notice.notice_id, client_id
+            );
+        }
+    }
+
+    info!(
+        "📢 ServerNotice {} ({:?}/{}) 已投递到 {} 个连接",
+        notice.notice_id, notice.severity, notice.category, delivered
+    );
+    delivered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::{ActrType, Realm};
+
+    fn actor(realm_id: u32, name: &str) -> ActrId {
+        ActrId {
+            realm: Realm { realm_id },
+            serial_number: 1,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: name.to_string(),
+                version: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_filter_matches_realm_and_type() {
+        assert!(NoticeFilter::default().matches(&actor(1, "echo")));
+
+        let filter = NoticeFilter {
+            realm_ids: vec![1],
+            actr_types: vec!["acme:echo".to_string()],
+        };
+        assert!(filter.matches(&actor(1, "echo")));
+        assert!(!filter.matches(&actor(2, "echo")));
+        assert!(!filter.matches(&actor(1, "chat")));
+    }
+
+    #[test]
+    fn test_notice_encoding_and_severity() {
+        assert_eq!(
+            NoticeSeverity::parse("WARNING"),
+            Some(NoticeSeverity::Warning)
+        );
+        assert_eq!(NoticeSeverity::parse("fatal"), None);
+
+        let notice = ServerNotice::new(
+            NoticeSeverity::Critical,
+            "maintenance",
+            "Node restarts in 5 minutes",
+            Some(1_700_000_300),
+        );
+        let response = notice.to_error_response();
+        assert_eq!(response.code, SERVER_NOTICE_CODE);
+        let decoded: ServerNotice =
+            serde_json::from_str(response.message.strip_prefix(SERVER_NOTICE_PREFIX).unwrap())
+                .unwrap();
+        assert_eq!(decoded.severity, NoticeSeverity::Critical);
+        assert_eq!(decoded.effective_at, Some(1_700_000_300));
+        assert!(response.message.contains("\"severity\":\"critical\""));
+    }
+}
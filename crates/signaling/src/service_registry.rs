@@ -7,13 +7,23 @@
 //! - **内存 HashMap**：主存储，快速查询
 //! - **SQLite 缓存**：可选，用于重启恢复
 //! - **后台写入**：不阻塞主逻辑，异步写入数据库
+//!
+//! ## 实例元数据
+//!
+//! Actor 在 ServiceSpec 标签中以 `key=value` 形式声明部署属性（如 `region=cn-beijing`、
+//! `version=1.4.2`、`capacity=large`），注册时解析为 [`ServiceCapabilities::tags`]，
+//! 可用于服务发现过滤（[`DiscoveryQuery::metadata`]）和路由候选的元数据亲和排序
+//! （见 [`ServiceRegistry::metadata_affinity`]）。
 
 use actr_protocol::{ActrId, ActrType};
 use actrix_common::RealmError;
+use actrix_common::config::signaling::SpecHistoryConfig;
 use actrix_common::realm::acl::ActorAcl;
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, warn};
@@ -27,6 +37,21 @@ pub const SERVICE_EXPIRY_THRESHOLD_SECS: u64 = 5 * 60;
 /// 清理任务执行间隔（秒）
 pub const CLEANUP_INTERVAL_SECS: u64 = 30;
 
+/// ServiceSpec 标签中元数据键与值的分隔符
+pub const METADATA_TAG_SEPARATOR: char = '=';
+
+/// 表示所在区域的元数据键，同时写入 [`ServiceCapabilities::region`]
+pub const METADATA_REGION_KEY: &str = "region";
+
+/// 每个实例最多保留的元数据条目数
+pub const MAX_METADATA_ENTRIES: usize = 32;
+
+/// 元数据键的最大长度（字节）
+pub const MAX_METADATA_KEY_LEN: usize = 64;
+
+/// 元数据值的最大长度（字节）
+pub const MAX_METADATA_VALUE_LEN: usize = 256;
+
 /// 服务能力描述
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceCapabilities {
@@ -36,10 +61,48 @@ pub struct ServiceCapabilities {
     pub version_range: Option<String>,
     /// 所在区域
     pub region: Option<String>,
-    /// 自定义标签
+    /// 自定义标签（实例元数据）
     pub tags: Option<HashMap<String, String>>,
 }
 
+impl ServiceCapabilities {
+    /// 从 ServiceSpec 标签解析实例元数据
+    ///
+    /// 只有 `key=value` 形式的标签视为元数据，其余标签忽略；键为空、超长或超出条目数的元数据被丢弃。
+    /// 没有元数据时返回 None
+    pub fn from_spec_tags(tags: &[String]) -> Option<Self> {
+        let mut metadata = HashMap::new();
+        for tag in tags {
+            let Some((key, value)) = tag.split_once(METADATA_TAG_SEPARATOR) else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if key.is_empty()
+                || key.len() > MAX_METADATA_KEY_LEN
+                || value.len() > MAX_METADATA_VALUE_LEN
+            {
+                warn!("忽略无效的元数据标签: {}", tag);
+                continue;
+            }
+            if metadata.len() >= MAX_METADATA_ENTRIES && !metadata.contains_key(key) {
+                warn!("元数据超过 {} 条，忽略: {}", MAX_METADATA_ENTRIES, tag);
+                continue;
+            }
+            metadata.insert(key.to_string(), value.to_string());
+        }
+
+        if metadata.is_empty() {
+            return None;
+        }
+        Some(Self {
+            max_concurrent_requests: None,
+            version_range: None,
+            region: metadata.get(METADATA_REGION_KEY).cloned(),
+            tags: Some(metadata),
+        })
+    }
+}
+
 /// 服务状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ServiceStatus {
@@ -99,6 +162,23 @@ pub struct ServiceInfo {
     pub ws_address: Option<String>,
 }
 
+impl ServiceInfo {
+    /// 实例元数据（注册时从 ServiceSpec 标签解析）
+    pub fn metadata(&self) -> Option<&HashMap<String, String>> {
+        self.capabilities.as_ref()?.tags.as_ref()
+    }
+
+    /// 是否具备全部指定的元数据键值（未指定时总是匹配）
+    pub fn matches_metadata(&self, required: &HashMap<String, String>) -> bool {
+        required.is_empty()
+            || self.metadata().is_some_and(|metadata| {
+                required
+                    .iter()
+                    .all(|(key, value)| metadata.get(key) == Some(value))
+            })
+    }
+}
+
 /// 服务地理位置信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceLocation {
@@ -132,6 +212,163 @@ pub struct ServiceMetrics {
     pub error_rate: f64,
 }
 
+/// 服务发现结果排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverySort {
+    /// 按 ActrType 字典序升序
+    #[default]
+    TypeAsc,
+    /// 按 ActrType 字典序降序
+    TypeDesc,
+    /// 按服务发布时间从新到旧，相同时按 ActrType 升序
+    RecentlyPublished,
+}
+
+impl std::str::FromStr for DiscoverySort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "type_asc" => Ok(Self::TypeAsc),
+            "type_desc" => Ok(Self::TypeDesc),
+            "recently_published" => Ok(Self::RecentlyPublished),
+            other => Err(format!("Unknown discovery sort: {other}")),
+        }
+    }
+}
+
+/// 服务发现查询条件（按 ActrType 聚合、基于游标分页）
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryQuery {
+    /// 仅返回该 realm 的服务
+    pub realm_id: Option<u32>,
+    /// 制造商精确匹配
+    pub manufacturer: Option<String>,
+    /// ActrType 名称前缀
+    pub name_prefix: Option<String>,
+    /// 服务规格需同时包含的标签
+    pub tags: Vec<String>,
+    /// 实例需同时具备的元数据键值
+    pub metadata: HashMap<String, String>,
+    /// 排序方式
+    pub sort: DiscoverySort,
+    /// 上一页最后一个类型的游标，返回其后的类型
+    pub cursor: Option<String>,
+    /// 上一页最后一个 ActrType（[`type_key`] 格式），返回其后的类型
+    ///
+    /// 供拿不到不透明游标的 Actor 发现请求使用（`DiscoveryResponse` 没有游标字段）
+    pub after_type: Option<String>,
+}
+
+impl DiscoveryQuery {
+    /// 由 Actor 发现请求的 `manufacturer` 字段构造查询（限定在请求方所在 realm）
+    ///
+    /// `DiscoveryRequest` 只有 manufacturer 与 limit 两个字段：普通取值仍按制造商精确匹配；
+    /// 以 `?` 开头时按 `key=value&...` 解析扩展条件：
+    /// `manufacturer`、`name_prefix`、`tags`（逗号分隔，需全部匹配）、
+    /// `metadata`（逗号分隔的 `key=value`，实例需全部具备）、`sort`（`type_asc` / `type_desc` / `recently_published`）、
+    /// `after`（上一页最后一个条目的 ActrType）。
+    pub fn from_actor_filter(realm_id: u32, filter: Option<&str>) -> Result<Self, String> {
+        let mut query = Self {
+            realm_id: Some(realm_id),
+            ..Default::default()
+        };
+        let Some(params) = filter.and_then(|filter| filter.strip_prefix('?')) else {
+            query.manufacturer = filter.map(str::to_string);
+            return Ok(query);
+        };
+
+        for param in params.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid discovery filter: {param}"))?;
+            let value = value.trim();
+            match key.trim() {
+                "manufacturer" => query.manufacturer = Some(value.to_string()),
+                "name_prefix" => query.name_prefix = Some(value.to_string()),
+                "tags" => query.tags.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_string),
+                ),
+                "metadata" => query.metadata.extend(parse_metadata_filter(value)?),
+                "sort" => query.sort = value.parse()?,
+                "after" => query.after_type = Some(value.to_string()),
+                other => return Err(format!("Unknown discovery filter: {other}")),
+            }
+        }
+        Ok(query)
+    }
+}
+
+/// 解析逗号分隔的 `key=value` 元数据过滤条件
+pub fn parse_metadata_filter(filter: &str) -> Result<HashMap<String, String>, String> {
+    filter
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(format!("Invalid metadata filter: {entry}")),
+        })
+        .collect()
+}
+
+/// 按 ActrType 聚合的服务发现结果
+#[derive(Debug)]
+pub struct DiscoveredType<'a> {
+    /// ActrType 字符串表示
+    pub type_key: String,
+    /// 该类型服务规格中最新的发布时间（未知为 0）
+    pub published_at: i64,
+    /// 该类型下所有匹配的可用服务实例
+    pub services: Vec<&'a ServiceInfo>,
+}
+
+impl DiscoveredType<'_> {
+    /// 指向该类型的分页游标（不透明字符串）
+    pub fn cursor(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}\n{}", self.published_at, self.type_key))
+    }
+}
+
+/// 解析分页游标为 (published_at, type_key)
+fn decode_discovery_cursor(cursor: &str) -> Result<(i64, String), String> {
+    let invalid = || format!("Invalid discovery cursor: {cursor}");
+    let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (published_at, type_key) = text.split_once('\n').ok_or_else(invalid)?;
+    let published_at = published_at.parse().map_err(|_| invalid())?;
+    Ok((published_at, type_key.to_string()))
+}
+
+/// 按排序方式比较两个 (published_at, type_key)
+fn cmp_discovery_order(sort: DiscoverySort, a: (i64, &str), b: (i64, &str)) -> Ordering {
+    match sort {
+        DiscoverySort::TypeAsc => a.1.cmp(b.1),
+        DiscoverySort::TypeDesc => b.1.cmp(a.1),
+        DiscoverySort::RecentlyPublished => b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)),
+    }
+}
+
+/// ServiceSpec 历史版本
+#[derive(Debug, Clone)]
+pub struct SpecVersion {
+    pub fingerprint: String,
+    /// ServiceSpec.published_at（缺失时为首次出现时间）
+    pub published_at: i64,
+    /// 首次注册该版本的时间 (Unix 秒)
+    pub first_seen_at: u64,
+    /// 最近一次注册该版本的时间 (Unix 秒)
+    pub last_seen_at: u64,
+    pub spec: actr_protocol::ServiceSpec,
+}
+
 /// 服务注册表
 #[derive(Debug, Default)]
 pub struct ServiceRegistry {
@@ -141,6 +378,12 @@ pub struct ServiceRegistry {
     message_type_index: HashMap<String, Vec<String>>,
     /// Actor ID 映射表：actor_id -> 服务列表
     actor_index: HashMap<ActrId, Vec<String>>,
+    /// ServiceSpec 历史：service_name -> 版本列表（按首次出现时间升序）
+    spec_history: HashMap<String, Vec<SpecVersion>>,
+    /// 历史版本保留策略
+    spec_history_config: SpecHistoryConfig,
+    /// 路由候选排序时参与亲和匹配的元数据键
+    metadata_affinity_keys: Vec<String>,
     /// SQLite 持久化缓存（可选）
     storage: Option<Arc<ServiceRegistryStorage>>,
 }
@@ -156,6 +399,33 @@ impl ServiceRegistry {
         self.storage = Some(storage);
     }
 
+    /// 设置 ServiceSpec 历史版本保留策略
+    pub fn set_spec_history_config(&mut self, config: SpecHistoryConfig) {
+        self.spec_history_config = config;
+    }
+
+    /// 设置路由候选排序时参与亲和匹配的元数据键（如 `region`）
+    pub fn set_metadata_affinity_keys(&mut self, keys: Vec<String>) {
+        self.metadata_affinity_keys = keys;
+    }
+
+    /// 请求方在亲和键上的元数据取值
+    ///
+    /// 路由候选排序时优先选择这些键取值相同的实例；请求方未声明的键不参与匹配
+    pub fn metadata_affinity(&self, actor_id: &ActrId) -> HashMap<String, String> {
+        let Some(metadata) = self
+            .services_of_actor(actor_id)
+            .into_iter()
+            .find_map(ServiceInfo::metadata)
+        else {
+            return HashMap::new();
+        };
+        self.metadata_affinity_keys
+            .iter()
+            .filter_map(|key| Some((key.clone(), metadata.get(key)?.clone())))
+            .collect()
+    }
+
     /// 从存储恢复服务列表（启动时调用）
     pub async fn restore_from_storage(&mut self) -> Result<usize, String> {
         let storage = match &self.storage {
@@ -198,6 +468,19 @@ impl ServiceRegistry {
                         .push(service_name);
                 }
 
+                // 恢复 ServiceSpec 历史（失败不影响服务恢复）
+                match storage.load_spec_history().await {
+                    Ok(versions) => {
+                        for (service_name, version) in versions {
+                            self.spec_history
+                                .entry(service_name)
+                                .or_default()
+                                .push(version);
+                        }
+                    }
+                    Err(e) => warn!("从缓存恢复 ServiceSpec 历史失败: {}", e),
+                }
+
                 Ok(count)
             }
             Err(e) => {
@@ -208,6 +491,8 @@ impl ServiceRegistry {
     }
 
     /// 注册服务（完整版本，支持 ServiceSpec 和 ACL）
+    ///
+    /// 未指定 `capabilities` 时从 ServiceSpec 标签解析实例元数据
     #[allow(clippy::too_many_arguments)]
     pub fn register_service_full(
         &mut self,
@@ -228,6 +513,12 @@ impl ServiceRegistry {
             ws_address
         );
 
+        let capabilities = capabilities.or_else(|| {
+            service_spec
+                .as_ref()
+                .and_then(|spec| ServiceCapabilities::from_spec_tags(&spec.tags))
+        });
+
         let service_info = ServiceInfo {
             actor_id: actor_id.clone(),
             service_name: service_name.clone(),
@@ -247,9 +538,15 @@ impl ServiceRegistry {
             ws_address,
         };
 
+        // 记录 ServiceSpec 历史版本
+        let spec_version = service_info.service_spec.as_ref().map(|spec| {
+            self.record_spec_version(&service_name, spec, service_info.last_heartbeat_time_secs)
+        });
+
         // 异步写入 SQLite 缓存（后台任务，不阻塞）
         if let Some(storage) = self.storage.clone() {
             let service_to_save = service_info.clone();
+            let history_service_name = service_name.clone();
             let actr_type = actor_id.r#type.clone();
             let service_spec_to_save = service_to_save.service_spec.clone();
             tokio::spawn(async move {
@@ -270,6 +567,23 @@ impl ServiceRegistry {
                         );
                     }
                 }
+
+                // 保存 ServiceSpec 历史版本并删除被淘汰的版本
+                if let Some((version, evicted)) = spec_version {
+                    if let Err(e) = storage
+                        .save_spec_version(&history_service_name, &version)
+                        .await
+                    {
+                        error!("保存 ServiceSpec 历史版本失败: {}", e);
+                    }
+                    if !evicted.is_empty()
+                        && let Err(e) = storage
+                            .delete_spec_versions(&history_service_name, &evicted)
+                            .await
+                    {
+                        error!("删除过期 ServiceSpec 历史版本失败: {}", e);
+                    }
+                }
             });
         }
 
@@ -296,6 +610,105 @@ impl ServiceRegistry {
         Ok(())
     }
 
+    /// 记录一次 ServiceSpec 发布并按保留策略淘汰旧版本
+    ///
+    /// 同一 fingerprint 只记录一次（刷新最近注册时间）。最新版本与仍有在线实例
+    /// 使用的版本不会被淘汰。返回需要持久化的版本和被淘汰的 fingerprint。
+    fn record_spec_version(
+        &mut self,
+        service_name: &str,
+        spec: &actr_protocol::ServiceSpec,
+        now: u64,
+    ) -> (SpecVersion, Vec<String>) {
+        let history = self
+            .spec_history
+            .entry(service_name.to_string())
+            .or_default();
+
+        let version = match history
+            .iter_mut()
+            .find(|version| version.fingerprint == spec.fingerprint)
+        {
+            Some(version) => {
+                version.last_seen_at = now;
+                version.clone()
+            }
+            None => {
+                let version = SpecVersion {
+                    fingerprint: spec.fingerprint.clone(),
+                    published_at: spec.published_at.unwrap_or(now as i64),
+                    first_seen_at: now,
+                    last_seen_at: now,
+                    spec: spec.clone(),
+                };
+                info!(
+                    "记录 ServiceSpec 新版本: {} fingerprint={}",
+                    service_name, spec.fingerprint
+                );
+                history.push(version.clone());
+                version
+            }
+        };
+
+        // 仍有在线实例使用的版本
+        let live: Vec<&str> = self
+            .services
+            .get(service_name)
+            .map(|services| {
+                services
+                    .iter()
+                    .filter_map(|s| s.service_spec.as_ref())
+                    .map(|spec| spec.fingerprint.as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let config = &self.spec_history_config;
+        let latest = history.last().map(|v| v.fingerprint.clone());
+        let is_protected = |v: &SpecVersion| {
+            v.fingerprint == spec.fingerprint
+                || Some(&v.fingerprint) == latest.as_ref()
+                || live.contains(&v.fingerprint.as_str())
+        };
+
+        let mut evicted = Vec::new();
+        if config.max_age_secs > 0 {
+            let cutoff = now.saturating_sub(config.max_age_secs);
+            history.retain(|v| {
+                let keep = v.last_seen_at >= cutoff || is_protected(v);
+                if !keep {
+                    evicted.push(v.fingerprint.clone());
+                }
+                keep
+            });
+        }
+        while history.len() > config.max_versions.max(1) {
+            let Some(index) = history.iter().position(|v| !is_protected(v)) else {
+                break;
+            };
+            evicted.push(history.remove(index).fingerprint);
+        }
+
+        if !evicted.is_empty() {
+            debug!(
+                "淘汰 {} 个 ServiceSpec 历史版本: {} {:?}",
+                evicted.len(),
+                service_name,
+                evicted
+            );
+        }
+
+        (version, evicted)
+    }
+
+    /// 获取服务的 ServiceSpec 历史版本（最新在前）
+    pub fn spec_history(&self, service_name: &str) -> Vec<&SpecVersion> {
+        self.spec_history
+            .get(service_name)
+            .map(|history| history.iter().rev().collect())
+            .unwrap_or_default()
+    }
+
     /// 注册服务（简化版本，向后兼容）
     pub fn register_service(
         &mut self,
@@ -381,6 +794,20 @@ impl ServiceRegistry {
         })
     }
 
+    /// 获取 Actor 注册的所有服务实例（用于管理 API）
+    pub fn services_of_actor(&self, actor_id: &ActrId) -> Vec<&ServiceInfo> {
+        self.actor_index
+            .get(actor_id)
+            .map(|service_names| {
+                service_names
+                    .iter()
+                    .filter_map(|service_name| self.services.get(service_name))
+                    .flat_map(|services| services.iter().filter(|s| &s.actor_id == actor_id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// 获取服务的 ACL（用于访问控制）
     pub fn get_acl(&self, actor_id: &ActrId) -> Option<&actr_protocol::Acl> {
         self.actor_index.get(actor_id).and_then(|service_names| {
@@ -814,6 +1241,117 @@ impl ServiceRegistry {
         results
     }
 
+    /// 按条件查询可用服务，按 ActrType 聚合并排序
+    ///
+    /// 返回游标之后的全部类型，由调用方截取一页（调用方可能还需按 ACL 过滤）。
+    /// 下一页游标取本页最后一个类型的 [`DiscoveredType::cursor`]。
+    pub fn discover_types(
+        &self,
+        query: &DiscoveryQuery,
+    ) -> Result<Vec<DiscoveredType<'_>>, String> {
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(decode_discovery_cursor)
+            .transpose()?;
+
+        let mut by_type: HashMap<String, Vec<&ServiceInfo>> = HashMap::new();
+        for service in self.services.values().flatten() {
+            if service.status != ServiceStatus::Available {
+                continue;
+            }
+
+            let actr_type = &service.actor_id.r#type;
+            if query
+                .realm_id
+                .is_some_and(|realm_id| service.actor_id.realm.realm_id != realm_id)
+                || query
+                    .manufacturer
+                    .as_deref()
+                    .is_some_and(|mfr| actr_type.manufacturer != mfr)
+                || query
+                    .name_prefix
+                    .as_deref()
+                    .is_some_and(|prefix| !actr_type.name.starts_with(prefix))
+            {
+                continue;
+            }
+
+            if !service.matches_metadata(&query.metadata) {
+                continue;
+            }
+
+            if !query.tags.is_empty() {
+                let tags = service
+                    .service_spec
+                    .as_ref()
+                    .map(|spec| spec.tags.as_slice())
+                    .unwrap_or_default();
+                if !query.tags.iter().all(|tag| tags.contains(tag)) {
+                    continue;
+                }
+            }
+
+            by_type
+                .entry(type_key(actr_type))
+                .or_default()
+                .push(service);
+        }
+
+        let mut types: Vec<DiscoveredType<'_>> = by_type
+            .into_iter()
+            .map(|(type_key, services)| {
+                let published_at = services
+                    .iter()
+                    .filter_map(|service| service.service_spec.as_ref()?.published_at)
+                    .max()
+                    .unwrap_or(0);
+                DiscoveredType {
+                    type_key,
+                    published_at,
+                    services,
+                }
+            })
+            .filter(|entry| {
+                cursor.as_ref().is_none_or(|(published_at, type_key)| {
+                    cmp_discovery_order(
+                        query.sort,
+                        (entry.published_at, &entry.type_key),
+                        (*published_at, type_key),
+                    ) == Ordering::Greater
+                })
+            })
+            .collect();
+
+        types.sort_by(|a, b| {
+            cmp_discovery_order(
+                query.sort,
+                (a.published_at, &a.type_key),
+                (b.published_at, &b.type_key),
+            )
+        });
+
+        if let Some(after) = query.after_type.as_deref() {
+            match types.iter().position(|entry| entry.type_key == after) {
+                Some(index) => {
+                    types.drain(..=index);
+                }
+                // 按发布时间排序时无法定位已下线的类型
+                None if query.sort == DiscoverySort::RecentlyPublished => {
+                    return Err(format!(
+                        "Discovery type {after} is no longer available; restart from the first page"
+                    ));
+                }
+                None => types.retain(|entry| {
+                    cmp_discovery_order(query.sort, (0, &entry.type_key), (0, after))
+                        == Ordering::Greater
+                }),
+            }
+        }
+
+        Ok(types)
+    }
+
     /// 按 ActrType 查询服务实例（用于负载均衡路由）
     ///
     /// # 参数
@@ -874,6 +1412,36 @@ impl ServiceRegistry {
             .collect()
     }
 
+    /// 查询 Realm 内某个 ActrType 的全部实例（每个 Actor 一条，不按服务状态过滤）
+    ///
+    /// 用于 Presence 快照；`target_type` 带 version 时只返回该版本的实例
+    pub fn instances_of_type(&self, realm_id: u32, target_type: &ActrType) -> Vec<&ServiceInfo> {
+        let target_version = normalize_version(target_type.version.clone());
+        let mut seen = HashSet::new();
+        let mut instances = Vec::new();
+
+        for service in self.services.values().flatten() {
+            let actor_type = &service.actor_id.r#type;
+            if service.actor_id.realm.realm_id != realm_id
+                || actor_type.manufacturer != target_type.manufacturer
+                || actor_type.name != target_type.name
+            {
+                continue;
+            }
+            if target_version.is_some()
+                && normalize_version(actor_type.version.clone()) != target_version
+            {
+                continue;
+            }
+            if seen.insert(&service.actor_id) {
+                instances.push(service);
+            }
+        }
+
+        instances.sort_by_key(|service| service.actor_id.serial_number);
+        instances
+    }
+
     /// Discover services by ActrType with ACL filtering
     ///
     /// Returns only services that the requester is allowed to discover
@@ -1473,6 +2041,54 @@ mod tests {
         assert_eq!(results_v2[0].actor_id.serial_number, 2);
     }
 
+    #[test]
+    fn test_instances_of_type() {
+        let mut registry = ServiceRegistry::new();
+        let worker = |serial_number: u64, realm_id: u32, version: &str| ActrId {
+            serial_number,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: "worker".to_string(),
+                version: Some(version.to_string()),
+            },
+            realm: actr_protocol::Realm { realm_id },
+        };
+
+        // 同一 Actor 的多个服务只算一个实例，非 Available 状态同样返回
+        for (actor_id, service_name) in [
+            (worker(2, 0, "1"), "work"),
+            (worker(2, 0, "1"), "admin"),
+            (worker(1, 0, "2"), "work"),
+            (worker(3, 7, "1"), "work"),
+        ] {
+            registry
+                .register_service(actor_id, service_name.to_string(), vec![], None)
+                .unwrap();
+        }
+        registry
+            .update_service_status(&worker(1, 0, "2"), "work", ServiceStatus::Busy, None)
+            .unwrap();
+        registry
+            .register_service(create_test_actor_id(4), "work".to_string(), vec![], None)
+            .unwrap();
+
+        let mut target_type = worker(0, 0, "1").r#type;
+        target_type.version = None;
+        let serials: Vec<u64> = registry
+            .instances_of_type(0, &target_type)
+            .iter()
+            .map(|service| service.actor_id.serial_number)
+            .collect();
+        assert_eq!(serials, vec![1, 2]);
+
+        target_type.version = Some("1".to_string());
+        let instances = registry.instances_of_type(0, &target_type);
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].actor_id.serial_number, 2);
+
+        assert_eq!(registry.instances_of_type(7, &target_type).len(), 1);
+    }
+
     #[test]
     fn test_find_by_actr_type_no_version_only_returns_none_version_group() {
         let mut registry = ServiceRegistry::new();
@@ -1555,4 +2171,364 @@ mod tests {
         assert_eq!(acme_only.len(), 1);
         assert_eq!(acme_only[0].actor_id.r#type.manufacturer, "acme");
     }
+
+    fn register_typed(
+        registry: &mut ServiceRegistry,
+        serial: u64,
+        name: &str,
+        published_at: Option<i64>,
+        tags: &[&str],
+    ) {
+        let actor_id = ActrId {
+            serial_number: serial,
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: name.to_string(),
+                version: None,
+            },
+            realm: actr_protocol::Realm { realm_id: 1 },
+        };
+        let spec = actr_protocol::ServiceSpec {
+            name: name.to_string(),
+            fingerprint: format!("fp-{name}"),
+            description: None,
+            protobufs: vec![],
+            published_at,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        };
+        registry
+            .register_service_full(
+                actor_id,
+                name.to_string(),
+                vec![],
+                None,
+                Some(spec),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    fn type_names(types: &[DiscoveredType<'_>]) -> Vec<String> {
+        types
+            .iter()
+            .map(|entry| entry.services[0].actor_id.r#type.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_discover_types_cursor_pagination() {
+        let mut registry = ServiceRegistry::new();
+        for (serial, name) in ["delta", "alpha", "charlie", "bravo"].iter().enumerate() {
+            register_typed(&mut registry, serial as u64, name, None, &[]);
+        }
+        // 同类型多个实例聚合为一个条目
+        register_typed(&mut registry, 10, "alpha", None, &[]);
+
+        let mut query = DiscoveryQuery::default();
+        let first = registry.discover_types(&query).unwrap();
+        assert_eq!(type_names(&first), ["alpha", "bravo", "charlie", "delta"]);
+        assert_eq!(first[0].services.len(), 2);
+
+        query.cursor = Some(first[1].cursor());
+        let rest = registry.discover_types(&query).unwrap();
+        assert_eq!(type_names(&rest), ["charlie", "delta"]);
+
+        query.cursor = Some("not-a-cursor".to_string());
+        assert!(registry.discover_types(&query).is_err());
+    }
+
+    #[test]
+    fn test_discover_types_filters_and_sort() {
+        let mut registry = ServiceRegistry::new();
+        register_typed(&mut registry, 1, "echo-old", Some(100), &["stable"]);
+        register_typed(&mut registry, 2, "echo-new", Some(300), &["stable", "beta"]);
+        register_typed(&mut registry, 3, "chat", Some(200), &["stable"]);
+
+        let query = DiscoveryQuery {
+            name_prefix: Some("echo".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            type_names(&registry.discover_types(&query).unwrap()),
+            ["echo-new", "echo-old"]
+        );
+
+        let query = DiscoveryQuery {
+            tags: vec!["stable".to_string(), "beta".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            type_names(&registry.discover_types(&query).unwrap()),
+            ["echo-new"]
+        );
+
+        let mut query = DiscoveryQuery {
+            sort: DiscoverySort::RecentlyPublished,
+            ..Default::default()
+        };
+        let by_recency = registry.discover_types(&query).unwrap();
+        assert_eq!(type_names(&by_recency), ["echo-new", "chat", "echo-old"]);
+        query.cursor = Some(by_recency[0].cursor());
+        assert_eq!(
+            type_names(&registry.discover_types(&query).unwrap()),
+            ["chat", "echo-old"]
+        );
+
+        let query = DiscoveryQuery {
+            realm_id: Some(2),
+            ..Default::default()
+        };
+        assert!(registry.discover_types(&query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_discover_types_after_type() {
+        let mut registry = ServiceRegistry::new();
+        register_typed(&mut registry, 1, "alpha", Some(100), &[]);
+        register_typed(&mut registry, 2, "bravo", Some(300), &[]);
+        register_typed(&mut registry, 3, "charlie", Some(200), &[]);
+
+        let mut query = DiscoveryQuery::default();
+        let all = registry.discover_types(&query).unwrap();
+        query.after_type = Some(all[0].type_key.clone());
+        assert_eq!(
+            type_names(&registry.discover_types(&query).unwrap()),
+            ["bravo", "charlie"]
+        );
+
+        // 已下线的类型按字典序定位
+        let gone = all[0].type_key.replace("alpha", "alpha-gone");
+        query.after_type = Some(gone.clone());
+        assert_eq!(
+            type_names(&registry.discover_types(&query).unwrap()),
+            ["bravo", "charlie"]
+        );
+
+        query.sort = DiscoverySort::RecentlyPublished;
+        query.after_type = Some(all[1].type_key.clone());
+        assert_eq!(
+            type_names(&registry.discover_types(&query).unwrap()),
+            ["charlie", "alpha"]
+        );
+        query.after_type = Some(gone);
+        assert!(registry.discover_types(&query).is_err());
+    }
+
+    #[test]
+    fn test_discovery_query_from_actor_filter() {
+        let query = DiscoveryQuery::from_actor_filter(7, Some("acme")).unwrap();
+        assert_eq!(query.realm_id, Some(7));
+        assert_eq!(query.manufacturer.as_deref(), Some("acme"));
+
+        let query = DiscoveryQuery::from_actor_filter(7, None).unwrap();
+        assert!(query.manufacturer.is_none());
+
+        let query = DiscoveryQuery::from_actor_filter(
+            7,
+            Some("?manufacturer=acme&name_prefix=echo&tags=stable, beta&sort=recently_published&after=acme:echo:1"),
+        )
+        .unwrap();
+        assert_eq!(query.realm_id, Some(7));
+        assert_eq!(query.manufacturer.as_deref(), Some("acme"));
+        assert_eq!(query.name_prefix.as_deref(), Some("echo"));
+        assert_eq!(query.tags, ["stable", "beta"]);
+        assert_eq!(query.sort, DiscoverySort::RecentlyPublished);
+        assert_eq!(query.after_type.as_deref(), Some("acme:echo:1"));
+
+        let query = DiscoveryQuery::from_actor_filter(
+            7,
+            Some("?metadata=region=cn-beijing,capacity=large"),
+        )
+        .unwrap();
+        assert_eq!(
+            query.metadata,
+            HashMap::from([
+                ("region".to_string(), "cn-beijing".to_string()),
+                ("capacity".to_string(), "large".to_string()),
+            ])
+        );
+        assert!(DiscoveryQuery::from_actor_filter(7, Some("?metadata=region")).is_err());
+
+        assert!(DiscoveryQuery::from_actor_filter(7, Some("?sort=random")).is_err());
+        assert!(DiscoveryQuery::from_actor_filter(7, Some("?realm_id=1")).is_err());
+        assert!(DiscoveryQuery::from_actor_filter(7, Some("?name_prefix")).is_err());
+    }
+
+    #[test]
+    fn test_metadata_from_spec_tags() {
+        let tags: Vec<String> = [
+            "stable",
+            "region=cn-beijing",
+            " capacity = large ",
+            "=orphan",
+            "version=1.4.2",
+        ]
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect();
+        let capabilities = ServiceCapabilities::from_spec_tags(&tags).unwrap();
+        assert_eq!(capabilities.region.as_deref(), Some("cn-beijing"));
+        let metadata = capabilities.tags.unwrap();
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata["capacity"], "large");
+        assert_eq!(metadata["version"], "1.4.2");
+
+        assert!(ServiceCapabilities::from_spec_tags(&["stable".to_string()]).is_none());
+
+        let many: Vec<String> = (0..MAX_METADATA_ENTRIES + 5)
+            .map(|i| format!("k{i}=v"))
+            .collect();
+        let metadata = ServiceCapabilities::from_spec_tags(&many)
+            .unwrap()
+            .tags
+            .unwrap();
+        assert_eq!(metadata.len(), MAX_METADATA_ENTRIES);
+    }
+
+    #[test]
+    fn test_discover_types_metadata_filter() {
+        let mut registry = ServiceRegistry::new();
+        register_typed(&mut registry, 1, "echo", None, &["region=us-west"]);
+        register_typed(&mut registry, 2, "echo", None, &["region=cn-beijing"]);
+        register_typed(&mut registry, 3, "chat", None, &["stable"]);
+
+        let query = DiscoveryQuery {
+            metadata: HashMap::from([("region".to_string(), "cn-beijing".to_string())]),
+            ..Default::default()
+        };
+        let types = registry.discover_types(&query).unwrap();
+        assert_eq!(type_names(&types), ["echo"]);
+        assert_eq!(types[0].services.len(), 1);
+        assert_eq!(types[0].services[0].actor_id.serial_number, 2);
+
+        let query = DiscoveryQuery {
+            metadata: HashMap::from([("region".to_string(), "eu-central".to_string())]),
+            ..Default::default()
+        };
+        assert!(registry.discover_types(&query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_metadata_affinity() {
+        let mut registry = ServiceRegistry::new();
+        registry.set_metadata_affinity_keys(vec!["region".to_string(), "zone".to_string()]);
+        register_typed(
+            &mut registry,
+            1,
+            "client",
+            None,
+            &["region=cn-beijing", "capacity=small"],
+        );
+        register_typed(&mut registry, 2, "plain", None, &["stable"]);
+
+        let client = registry.discover_by_service_name("client")[0]
+            .actor_id
+            .clone();
+        let affinity = registry.metadata_affinity(&client);
+        assert_eq!(
+            affinity,
+            HashMap::from([("region".to_string(), "cn-beijing".to_string())])
+        );
+
+        let plain = registry.discover_by_service_name("plain")[0]
+            .actor_id
+            .clone();
+        assert!(registry.metadata_affinity(&plain).is_empty());
+    }
+
+    fn spec(fingerprint: &str) -> actr_protocol::ServiceSpec {
+        actr_protocol::ServiceSpec {
+            name: "echo".to_string(),
+            fingerprint: fingerprint.to_string(),
+            description: None,
+            protobufs: vec![],
+            published_at: None,
+            tags: vec![],
+        }
+    }
+
+    fn fingerprints(registry: &ServiceRegistry, service_name: &str) -> Vec<String> {
+        registry
+            .spec_history(service_name)
+            .into_iter()
+            .map(|version| version.fingerprint.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_spec_history_records_each_fingerprint_once() {
+        let mut registry = ServiceRegistry::new();
+
+        let (v1, _) = registry.record_spec_version("echo", &spec("fp-1"), 100);
+        assert_eq!(v1.published_at, 100);
+        registry.record_spec_version("echo", &spec("fp-2"), 200);
+        let (again, evicted) = registry.record_spec_version("echo", &spec("fp-1"), 300);
+        assert!(evicted.is_empty());
+        assert_eq!(again.first_seen_at, 100);
+        assert_eq!(again.last_seen_at, 300);
+
+        // 最新版本在前，按首次出现排序
+        assert_eq!(fingerprints(&registry, "echo"), ["fp-2", "fp-1"]);
+        assert!(registry.spec_history("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_spec_history_retention() {
+        let mut registry = ServiceRegistry::new();
+        registry.set_spec_history_config(SpecHistoryConfig {
+            max_versions: 2,
+            max_age_secs: 1000,
+        });
+
+        registry.record_spec_version("echo", &spec("fp-1"), 100);
+        registry.record_spec_version("echo", &spec("fp-2"), 200);
+        let (_, evicted) = registry.record_spec_version("echo", &spec("fp-3"), 300);
+        assert_eq!(evicted, ["fp-1"]);
+        assert_eq!(fingerprints(&registry, "echo"), ["fp-3", "fp-2"]);
+
+        // 超过保留时长的旧版本被淘汰，最新版本保留
+        let (_, evicted) = registry.record_spec_version("echo", &spec("fp-3"), 1250);
+        assert_eq!(evicted, ["fp-2"]);
+        assert_eq!(fingerprints(&registry, "echo"), ["fp-3"]);
+    }
+
+    #[test]
+    fn test_spec_history_keeps_versions_in_use() {
+        let mut registry = ServiceRegistry::new();
+        registry.set_spec_history_config(SpecHistoryConfig {
+            max_versions: 1,
+            max_age_secs: 0,
+        });
+
+        registry
+            .register_service_full(
+                create_test_actor_id(1),
+                "echo".to_string(),
+                vec![],
+                None,
+                Some(spec("fp-1")),
+                None,
+                None,
+            )
+            .unwrap();
+        registry
+            .register_service_full(
+                create_test_actor_id(2),
+                "echo".to_string(),
+                vec![],
+                None,
+                Some(spec("fp-2")),
+                None,
+                None,
+            )
+            .unwrap();
+
+        // fp-1 仍有在线实例，不被淘汰
+        assert_eq!(fingerprints(&registry, "echo"), ["fp-2", "fp-1"]);
+
+        registry.unregister_actor(&create_test_actor_id(1));
+        registry.record_spec_version("echo", &spec("fp-2"), current_timestamp());
+        assert_eq!(fingerprints(&registry, "echo"), ["fp-2"]);
+    }
 }
@@ -0,0 +1,70 @@
+//! 服务注册表存储后端抽象接口
+//!
+//! 定义了所有存储后端必须实现的统一异步接口
+
+use super::CacheStats;
+use crate::service_registry::{ServiceInfo, SpecVersion};
+use actr_protocol::{ActrId, ActrType, ServiceSpec};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// 服务注册表存储后端抽象接口
+///
+/// 所有存储后端（SQLite, Redis）都需要实现此 trait。
+/// 记录均带 TTL，过期的服务与 proto spec 不应再被加载。
+#[async_trait]
+pub trait RegistryStorage: Send + Sync {
+    /// 保存（或更新）服务信息，并重置 TTL
+    async fn save_service(&self, service: &ServiceInfo) -> Result<()>;
+
+    /// 更新心跳时间并延长 TTL，服务不存在时忽略
+    async fn update_heartbeat(&self, actor_id: &ActrId, service_name: &str) -> Result<()>;
+
+    /// 删除服务
+    async fn delete_service(&self, actor_id: &ActrId, service_name: &str) -> Result<()>;
+
+    /// 加载所有未过期的服务（启动时恢复）
+    async fn load_all_services(&self) -> Result<Vec<ServiceInfo>>;
+
+    /// 加载指定 Actor 的所有未过期服务（心跳时发现内存中缺失注册时恢复）
+    async fn load_services_by_actor_id(&self, actor_id: &ActrId) -> Result<Vec<ServiceInfo>>;
+
+    /// 清理过期服务，返回删除的记录数
+    ///
+    /// 由存储自身处理过期的后端（如 Redis TTL）返回 0
+    async fn cleanup_expired(&self) -> Result<u64>;
+
+    /// 获取统计信息
+    async fn get_stats(&self) -> Result<CacheStats>;
+
+    /// 保存 Proto spec（用于兼容性协商），相同指纹的记录刷新 TTL
+    async fn save_proto_spec(&self, actr_type: &ActrType, service_spec: &ServiceSpec)
+    -> Result<()>;
+
+    /// 根据指纹获取 Proto spec，命中时刷新 TTL
+    ///
+    /// # Returns
+    /// * `Ok(Some(spec))` - 找到未过期的 spec
+    /// * `Ok(None)` - 不存在或已过期
+    /// * `Err(...)` - 存储错误
+    async fn get_proto_by_fingerprint(
+        &self,
+        actr_type: &ActrType,
+        fingerprint: &str,
+    ) -> Result<Option<ServiceSpec>>;
+
+    /// 清理过期的 proto specs，返回删除的记录数
+    async fn cleanup_expired_proto_specs(&self) -> Result<u64>;
+
+    /// 保存（或刷新）一个 ServiceSpec 历史版本
+    ///
+    /// 版本已存在时只更新 `last_seen_at`
+    async fn save_spec_version(&self, service_name: &str, version: &SpecVersion) -> Result<()>;
+
+    /// 删除被保留策略淘汰的历史版本
+    async fn delete_spec_versions(&self, service_name: &str, fingerprints: &[String])
+    -> Result<()>;
+
+    /// 加载全部历史版本：(service_name, version)，按首次出现时间升序
+    async fn load_spec_history(&self) -> Result<Vec<(String, SpecVersion)>>;
+}
@@ -0,0 +1,304 @@
+//! ServiceRegistry 持久化存储层
+//!
+//! ## 设计原则
+//!
+//! 1. **存储作为缓存**：不是主数据源，用于重启恢复与兼容性协商时查询 proto spec
+//! 2. **数据有 TTL**：服务与 proto spec 均带过期时间
+//! 3. **内存优先**：HashMap 是主存储，持久化存储是备份
+//!
+//! ## 数据流
+//!
+//! - 启动：存储 → HashMap（恢复缓存）
+//! - 注册：HashMap + 存储（双写）
+//! - 查询：HashMap（快速）
+//! - 心跳：HashMap + 存储（更新 TTL）
+//! - 清理：定期清理过期数据
+//!
+//! ## 存储后端
+//!
+//! - `RegistryStorage` trait 定义统一的异步接口
+//! - `ServiceRegistryStorage` 以 enum 封装不同的后端实现
+//! - 通过 `services.signaling.storage`（[`RegistryStorageConfig`]）选择后端：
+//!   - SQLite（默认）：单节点，见 [`sqlite`]
+//!   - Redis：多个 Signaling 节点共享注册表，需要编译时启用 `redis` feature
+
+use actrix_common::config::signaling::{RegistryStorageBackend, RegistryStorageConfig};
+use anyhow::{Result, bail};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod backend;
+
+// SQLite 始终可用
+pub mod sqlite;
+
+#[cfg(feature = "redis")]
+pub mod redis;
+
+use crate::registry_encryption::RegistryCipher;
+use crate::service_registry::{ServiceInfo, SpecVersion};
+use actr_protocol::{ActrId, ActrType, ServiceSpec};
+
+pub use backend::RegistryStorage;
+pub use sqlite::SqliteRegistryStorage;
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisRegistryStorage;
+
+/// 默认服务 TTL（1 小时）
+pub const DEFAULT_SERVICE_TTL_SECS: u64 = 12 * 3600; // 临时方案
+
+/// 默认 Proto spec TTL（7 天）
+pub const DEFAULT_PROTO_TTL_SECS: u64 = 604800;
+
+/// 加密迁移结果（各列加密的行数）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncryptionMigrationStats {
+    pub service_specs: u64,
+    pub acls: u64,
+    pub proto_specs: u64,
+    pub spec_history: u64,
+}
+
+/// 缓存统计信息
+#[derive(Debug, Clone)]
+pub struct CacheStats {
+    pub total_services: u64,
+    pub expired_services: u64,
+    pub valid_services: u64,
+}
+
+/// ServiceRegistry 持久化存储
+///
+/// 封装存储后端，ServiceRegistry 只依赖此类型
+#[derive(Debug)]
+pub struct ServiceRegistryStorage {
+    backend: Backend,
+}
+
+/// 存储后端
+#[derive(Debug)]
+enum Backend {
+    /// SQLite 存储后端（始终可用）
+    Sqlite(Box<SqliteRegistryStorage>),
+
+    /// Redis 存储后端
+    #[cfg(feature = "redis")]
+    Redis(Box<RedisRegistryStorage>),
+}
+
+impl ServiceRegistryStorage {
+    /// 创建 SQLite 文件存储实例
+    pub async fn new(database_file: impl AsRef<Path>, ttl_secs: Option<u64>) -> Result<Self> {
+        let backend = SqliteRegistryStorage::new(database_file, ttl_secs).await?;
+        Ok(Self {
+            backend: Backend::Sqlite(Box::new(backend)),
+        })
+    }
+
+    /// 创建 SQLite 内存存储实例（`storage = "memory"`，不写文件系统，重启后不恢复）
+    pub async fn new_in_memory(ttl_secs: Option<u64>) -> Result<Self> {
+        let backend = SqliteRegistryStorage::new_in_memory(ttl_secs).await?;
+        Ok(Self {
+            backend: Backend::Sqlite(Box::new(backend)),
+        })
+    }
+
+    /// 从配置创建存储实例
+    ///
+    /// # Arguments
+    /// * `config` - 存储配置（`services.signaling.storage`）
+    /// * `database_file` - SQLite 数据库文件，`None` 时使用内存数据库（仅 backend = "sqlite" 时使用）
+    /// * `ttl_secs` - 服务 TTL，`None` 时使用 [`DEFAULT_SERVICE_TTL_SECS`]
+    ///
+    /// # Errors
+    /// - 缺少对应后端的配置
+    /// - 后端初始化失败
+    /// - 后端功能未启用（feature flag）
+    pub async fn from_config(
+        config: &RegistryStorageConfig,
+        database_file: Option<&Path>,
+        ttl_secs: Option<u64>,
+    ) -> Result<Self> {
+        match config.backend {
+            RegistryStorageBackend::Sqlite => match database_file {
+                Some(database_file) => Self::new(database_file, ttl_secs).await,
+                None => Self::new_in_memory(ttl_secs).await,
+            },
+
+            #[cfg(feature = "redis")]
+            RegistryStorageBackend::Redis => {
+                let Some(cfg) = config.redis.as_ref() else {
+                    bail!("Missing Redis config for registry storage");
+                };
+                let backend = RedisRegistryStorage::connect(cfg, ttl_secs).await?;
+                Ok(Self {
+                    backend: Backend::Redis(Box::new(backend)),
+                })
+            }
+
+            #[cfg(not(feature = "redis"))]
+            RegistryStorageBackend::Redis => {
+                bail!("Redis registry storage not enabled. Compile with --features signaling-redis")
+            }
+        }
+    }
+
+    /// 设置敏感列加密
+    pub fn with_cipher(self, cipher: RegistryCipher) -> Self {
+        let backend = match self.backend {
+            Backend::Sqlite(b) => Backend::Sqlite(Box::new((*b).with_cipher(cipher))),
+
+            #[cfg(feature = "redis")]
+            Backend::Redis(b) => Backend::Redis(Box::new((*b).with_cipher(cipher))),
+        };
+        Self { backend }
+    }
+
+    /// 当前使用的后端名称
+    pub fn backend_name(&self) -> &'static str {
+        match &self.backend {
+            Backend::Sqlite(_) => "sqlite",
+
+            #[cfg(feature = "redis")]
+            Backend::Redis(_) => "redis",
+        }
+    }
+
+    fn backend(&self) -> &dyn RegistryStorage {
+        match &self.backend {
+            Backend::Sqlite(b) => b.as_ref(),
+
+            #[cfg(feature = "redis")]
+            Backend::Redis(b) => b.as_ref(),
+        }
+    }
+
+    /// 保存服务信息
+    pub async fn save_service(&self, service: &ServiceInfo) -> Result<()> {
+        self.backend().save_service(service).await
+    }
+
+    /// 更新心跳时间和 TTL
+    pub async fn update_heartbeat(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
+        self.backend()
+            .update_heartbeat(actor_id, service_name)
+            .await
+    }
+
+    /// 删除服务
+    pub async fn delete_service(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
+        self.backend().delete_service(actor_id, service_name).await
+    }
+
+    /// 加载所有有效服务（启动时恢复）
+    pub async fn load_all_services(&self) -> Result<Vec<ServiceInfo>> {
+        self.backend().load_all_services().await
+    }
+
+    /// 根据 ActorId 加载服务（用于心跳恢复）
+    pub async fn load_services_by_actor_id(&self, actor_id: &ActrId) -> Result<Vec<ServiceInfo>> {
+        self.backend().load_services_by_actor_id(actor_id).await
+    }
+
+    /// 清理过期数据
+    pub async fn cleanup_expired(&self) -> Result<u64> {
+        self.backend().cleanup_expired().await
+    }
+
+    /// 获取统计信息
+    pub async fn get_stats(&self) -> Result<CacheStats> {
+        self.backend().get_stats().await
+    }
+
+    /// 保存 Proto spec（用于兼容性协商）
+    pub async fn save_proto_spec(
+        &self,
+        actr_type: &ActrType,
+        service_spec: &ServiceSpec,
+    ) -> Result<()> {
+        self.backend()
+            .save_proto_spec(actr_type, service_spec)
+            .await
+    }
+
+    /// 根据指纹获取 Proto spec
+    pub async fn get_proto_by_fingerprint(
+        &self,
+        actr_type: &ActrType,
+        fingerprint: &str,
+    ) -> Result<Option<ServiceSpec>> {
+        self.backend()
+            .get_proto_by_fingerprint(actr_type, fingerprint)
+            .await
+    }
+
+    /// 清理过期的 proto specs
+    pub async fn cleanup_expired_proto_specs(&self) -> Result<u64> {
+        self.backend().cleanup_expired_proto_specs().await
+    }
+
+    /// 保存（或刷新）一个 ServiceSpec 历史版本
+    pub async fn save_spec_version(&self, service_name: &str, version: &SpecVersion) -> Result<()> {
+        self.backend()
+            .save_spec_version(service_name, version)
+            .await
+    }
+
+    /// 删除被保留策略淘汰的历史版本
+    pub async fn delete_spec_versions(
+        &self,
+        service_name: &str,
+        fingerprints: &[String],
+    ) -> Result<()> {
+        self.backend()
+            .delete_spec_versions(service_name, fingerprints)
+            .await
+    }
+
+    /// 加载全部历史版本：(service_name, version)，按首次出现时间升序
+    pub async fn load_spec_history(&self) -> Result<Vec<(String, SpecVersion)>> {
+        self.backend().load_spec_history().await
+    }
+}
+
+/// 获取当前 Unix 时间戳（秒）
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_from_config_defaults_to_sqlite() {
+        let storage =
+            ServiceRegistryStorage::from_config(&RegistryStorageConfig::default(), None, None)
+                .await
+                .unwrap();
+        assert_eq!(storage.backend_name(), "sqlite");
+        assert_eq!(storage.get_stats().await.unwrap().total_services, 0);
+    }
+
+    #[cfg(not(feature = "redis"))]
+    #[tokio::test]
+    async fn test_from_config_redis_requires_feature() {
+        use actrix_common::config::signaling::RedisRegistryConfig;
+
+        let config = RegistryStorageConfig {
+            backend: RegistryStorageBackend::Redis,
+            redis: Some(RedisRegistryConfig {
+                url: "redis://127.0.0.1:6379/0".to_string(),
+                key_prefix: "actrix:registry:".to_string(),
+            }),
+        };
+        let err = ServiceRegistryStorage::from_config(&config, None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("signaling-redis"));
+    }
+}
@@ -0,0 +1,598 @@
+//! ServiceRegistry Redis 存储后端
+//!
+//! 多个 Signaling 节点共享同一 Redis 时，任一节点重启后都能恢复完整的服务注册表，
+//! 兼容性协商也能查到其他节点注册的 proto spec。
+//!
+//! ## 键布局
+//!
+//! - `{prefix}service:{realm_id}:{serial_number}:{service_name}`：JSON 编码的服务记录，`SET EX` 写入
+//! - `{prefix}proto:{manufacturer}:{name}:{version}:{fingerprint}`：protobuf 编码的 ServiceSpec
+//! - `{prefix}spec_history:{service_name}`：Hash，field 为 fingerprint，value 为 JSON 编码的历史版本
+//!
+//! 服务与 proto spec 的过期由 Redis TTL 自动处理，`cleanup_expired*` 始终返回 0。
+//! ServiceSpec 与 ACL 经 [`RegistryCipher`] 加密后以 Base64 存入 JSON（启用 `registry_encryption` 时）。
+
+use super::backend::RegistryStorage;
+use super::{CacheStats, DEFAULT_PROTO_TTL_SECS, DEFAULT_SERVICE_TTL_SECS, current_timestamp};
+use crate::actr_type_utils::{normalize_version, type_key};
+use crate::registry_encryption::RegistryCipher;
+use crate::service_registry::{ServiceInfo, SpecVersion};
+use actr_protocol::{Acl, ActrId, ActrType, ServiceSpec};
+use actrix_common::config::signaling::RedisRegistryConfig;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::prelude::*;
+use prost::Message as ProstMessage;
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, error, info};
+
+/// SCAN 每批返回的键数量提示
+const SCAN_BATCH: usize = 1000;
+
+/// Redis 中的服务记录
+#[derive(Serialize, Deserialize)]
+struct StoredService {
+    #[serde(flatten)]
+    info: ServiceInfo,
+    /// Base64 编码的（加密后）ServiceSpec protobuf
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    service_spec_blob: Option<String>,
+    /// Base64 编码的（加密后）ACL protobuf
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    acl_blob: Option<String>,
+}
+
+/// Redis 中的 ServiceSpec 历史版本
+#[derive(Serialize, Deserialize)]
+struct StoredSpecVersion {
+    fingerprint: String,
+    published_at: i64,
+    first_seen_at: u64,
+    last_seen_at: u64,
+    /// Base64 编码的（加密后）ServiceSpec protobuf
+    spec_blob: String,
+}
+
+/// ServiceRegistry Redis 存储
+pub struct RedisRegistryStorage {
+    conn: ConnectionManager,
+    key_prefix: String,
+    /// 服务 TTL（秒）
+    default_ttl_secs: u64,
+    /// Proto specs TTL（秒），默认 604800 秒（7 天）
+    proto_ttl_secs: u64,
+    /// 敏感字段加密（默认不加密）
+    cipher: RegistryCipher,
+}
+
+impl std::fmt::Debug for RedisRegistryStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisRegistryStorage")
+            .field("key_prefix", &self.key_prefix)
+            .field("default_ttl_secs", &self.default_ttl_secs)
+            .field("proto_ttl_secs", &self.proto_ttl_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisRegistryStorage {
+    /// 连接 Redis 并创建存储实例
+    pub async fn connect(config: &RedisRegistryConfig, ttl_secs: Option<u64>) -> Result<Self> {
+        let client = redis::Client::open(config.url.as_str()).context("Invalid Redis URL")?;
+        let conn = client
+            .get_connection_manager()
+            .await
+            .context("Failed to connect to Redis")?;
+
+        let storage = Self {
+            conn,
+            key_prefix: config.key_prefix.clone(),
+            default_ttl_secs: ttl_secs.unwrap_or(DEFAULT_SERVICE_TTL_SECS),
+            proto_ttl_secs: DEFAULT_PROTO_TTL_SECS,
+            cipher: RegistryCipher::default(),
+        };
+
+        info!(
+            "✅ Redis ServiceRegistryStorage initialized with TTL={}s (prefix {})",
+            storage.default_ttl_secs, storage.key_prefix
+        );
+        Ok(storage)
+    }
+
+    /// 设置敏感字段加密
+    pub fn with_cipher(mut self, cipher: RegistryCipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    fn service_key(&self, actor_id: &ActrId, service_name: &str) -> String {
+        format!(
+            "{}service:{}:{}:{}",
+            self.key_prefix, actor_id.realm.realm_id, actor_id.serial_number, service_name
+        )
+    }
+
+    fn proto_key(&self, actr_type: &ActrType, fingerprint: &str) -> String {
+        format!(
+            "{}proto:{}:{}:{}:{}",
+            self.key_prefix,
+            actr_type.manufacturer,
+            actr_type.name,
+            normalize_version(actr_type.version.clone()).unwrap_or_default(),
+            fingerprint
+        )
+    }
+
+    fn spec_history_key(&self, service_name: &str) -> String {
+        format!("{}spec_history:{}", self.key_prefix, service_name)
+    }
+
+    /// 按模式遍历所有匹配的键
+    async fn scan_keys(&self, pattern: &str) -> Result<Vec<String>> {
+        let mut conn = self.conn.clone();
+        let mut cursor = 0u64;
+        let mut all = Vec::new();
+
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(&mut conn)
+                .await
+                .with_context(|| format!("Failed to scan registry keys: {pattern}"))?;
+            all.extend(keys);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        Ok(all)
+    }
+
+    /// 读取匹配模式的所有服务记录，无法解析的记录记录错误后跳过
+    async fn load_services(&self, pattern: &str) -> Result<Vec<ServiceInfo>> {
+        let keys = self.scan_keys(pattern).await?;
+        let mut conn = self.conn.clone();
+        let mut services = Vec::with_capacity(keys.len());
+
+        for chunk in keys.chunks(SCAN_BATCH) {
+            // 扫描与读取之间过期的键返回 nil
+            let values: Vec<Option<String>> = redis::cmd("MGET")
+                .arg(chunk)
+                .query_async(&mut conn)
+                .await
+                .context("Failed to read services from Redis")?;
+            for (key, value) in chunk.iter().zip(values) {
+                let Some(value) = value else { continue };
+                match self.decode_service(&value) {
+                    Ok(service) => services.push(service),
+                    Err(e) => error!("Failed to deserialize service {}: {:?}", key, e),
+                }
+            }
+        }
+
+        services.sort_by(|a, b| {
+            a.service_name
+                .cmp(&b.service_name)
+                .then(a.actor_id.serial_number.cmp(&b.actor_id.serial_number))
+        });
+        Ok(services)
+    }
+
+    fn encode_service(&self, service: &ServiceInfo) -> Result<String> {
+        let service_spec_blob = service
+            .service_spec
+            .as_ref()
+            .map(|spec| self.seal_base64(spec.encode_to_vec()))
+            .transpose()?;
+        let acl_blob = service
+            .acl
+            .as_ref()
+            .map(|acl| self.seal_base64(acl.encode_to_vec()))
+            .transpose()?;
+
+        Ok(serde_json::to_string(&StoredService {
+            info: service.clone(),
+            service_spec_blob,
+            acl_blob,
+        })?)
+    }
+
+    fn decode_service(&self, value: &str) -> Result<ServiceInfo> {
+        let stored: StoredService = serde_json::from_str(value)?;
+        let mut service = stored.info;
+
+        // 与 SQLite 后端一致：无法解密或解码的 BLOB 视为缺失
+        service.service_spec = stored
+            .service_spec_blob
+            .and_then(|blob| self.open_base64(&service.service_name, &blob))
+            .and_then(|bytes| ServiceSpec::decode(&bytes[..]).ok());
+        service.acl = stored
+            .acl_blob
+            .and_then(|blob| self.open_base64(&service.service_name, &blob))
+            .and_then(|bytes| Acl::decode(&bytes[..]).ok());
+        // WebSocket 地址来自实时的 RegisterRequest，不从缓存恢复
+        service.ws_address = None;
+
+        Ok(service)
+    }
+
+    fn seal_base64(&self, plaintext: Vec<u8>) -> Result<String> {
+        Ok(BASE64_STANDARD.encode(self.cipher.seal(plaintext)?))
+    }
+
+    /// 解码并解密 Base64 BLOB，失败时记录错误并视为缺失
+    fn open_base64(&self, service_name: &str, blob: &str) -> Option<Vec<u8>> {
+        BASE64_STANDARD
+            .decode(blob)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| self.cipher.open(bytes))
+            .map_err(|e| error!("Failed to decrypt registry data of {}: {}", service_name, e))
+            .ok()
+    }
+}
+
+#[async_trait]
+impl RegistryStorage for RedisRegistryStorage {
+    /// 保存服务信息
+    async fn save_service(&self, service: &ServiceInfo) -> Result<()> {
+        let value = self.encode_service(service)?;
+
+        let mut conn = self.conn.clone();
+        let _: () = redis::cmd("SET")
+            .arg(self.service_key(&service.actor_id, &service.service_name))
+            .arg(value)
+            .arg("EX")
+            .arg(self.default_ttl_secs.max(1))
+            .query_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to save service: {}", service.service_name))?;
+
+        debug!(
+            "Saved service to Redis: {} (Actor {}, expires in {}s)",
+            service.service_name, service.actor_id.serial_number, self.default_ttl_secs
+        );
+        Ok(())
+    }
+
+    /// 更新心跳时间和 TTL
+    async fn update_heartbeat(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
+        let key = self.service_key(actor_id, service_name);
+        let mut conn = self.conn.clone();
+
+        let value: Option<String> = redis::cmd("GET").arg(&key).query_async(&mut conn).await?;
+        let Some(value) = value else {
+            return Ok(());
+        };
+
+        let mut stored: StoredService = serde_json::from_str(&value)?;
+        stored.info.last_heartbeat_time_secs = current_timestamp();
+
+        // XX：期间被删除的服务不重新写入
+        let _: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(serde_json::to_string(&stored)?)
+            .arg("EX")
+            .arg(self.default_ttl_secs.max(1))
+            .arg("XX")
+            .query_async(&mut conn)
+            .await?;
+
+        debug!(
+            "Updated heartbeat: {} (Actor {}, TTL extended by {}s)",
+            service_name, actor_id.serial_number, self.default_ttl_secs
+        );
+        Ok(())
+    }
+
+    /// 删除服务
+    async fn delete_service(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
+        let mut conn = self.conn.clone();
+        let _: u64 = redis::cmd("DEL")
+            .arg(self.service_key(actor_id, service_name))
+            .query_async(&mut conn)
+            .await?;
+
+        debug!(
+            "Deleted service from Redis: {} (Actor {})",
+            service_name, actor_id.serial_number
+        );
+        Ok(())
+    }
+
+    /// 加载所有有效服务（启动时恢复）
+    async fn load_all_services(&self) -> Result<Vec<ServiceInfo>> {
+        let services = self
+            .load_services(&format!("{}service:*", self.key_prefix))
+            .await?;
+        info!("Loaded {} services from Redis", services.len());
+        Ok(services)
+    }
+
+    /// 根据 ActorId 加载服务（用于心跳恢复）
+    async fn load_services_by_actor_id(&self, actor_id: &ActrId) -> Result<Vec<ServiceInfo>> {
+        let pattern = format!(
+            "{}service:{}:{}:*",
+            self.key_prefix, actor_id.realm.realm_id, actor_id.serial_number
+        );
+        let services = self.load_services(&pattern).await?;
+
+        if !services.is_empty() {
+            debug!(
+                "Loaded {} services from Redis for Actor {}",
+                services.len(),
+                actor_id.serial_number
+            );
+        }
+        Ok(services)
+    }
+
+    /// 过期服务由 Redis TTL 自动删除
+    async fn cleanup_expired(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// 获取统计信息（过期的键已被 Redis 删除）
+    async fn get_stats(&self) -> Result<CacheStats> {
+        let total = self
+            .scan_keys(&format!("{}service:*", self.key_prefix))
+            .await?
+            .len() as u64;
+
+        Ok(CacheStats {
+            total_services: total,
+            expired_services: 0,
+            valid_services: total,
+        })
+    }
+
+    /// 保存 Proto spec（用于兼容性协商）
+    async fn save_proto_spec(
+        &self,
+        actr_type: &ActrType,
+        service_spec: &ServiceSpec,
+    ) -> Result<()> {
+        let proto_content = self.cipher.seal(service_spec.encode_to_vec())?;
+
+        let mut conn = self.conn.clone();
+        let _: () = redis::cmd("SET")
+            .arg(self.proto_key(actr_type, &service_spec.fingerprint))
+            .arg(proto_content)
+            .arg("EX")
+            .arg(self.proto_ttl_secs)
+            .query_async(&mut conn)
+            .await
+            .with_context(|| format!("Failed to save proto spec for {}", type_key(actr_type)))?;
+
+        debug!(
+            "Saved proto spec: {} fingerprint={} (expires in {}s)",
+            type_key(actr_type),
+            service_spec.fingerprint,
+            self.proto_ttl_secs
+        );
+        Ok(())
+    }
+
+    /// 根据指纹获取 Proto spec，命中时刷新 TTL
+    async fn get_proto_by_fingerprint(
+        &self,
+        actr_type: &ActrType,
+        fingerprint: &str,
+    ) -> Result<Option<ServiceSpec>> {
+        let key = self.proto_key(actr_type, fingerprint);
+        let mut conn = self.conn.clone();
+
+        let proto_content: Option<Vec<u8>> = redis::cmd("GETEX")
+            .arg(&key)
+            .arg("EX")
+            .arg(self.proto_ttl_secs)
+            .query_async(&mut conn)
+            .await?;
+
+        let Some(proto_content) = proto_content else {
+            debug!(
+                "Proto spec not found: {} fingerprint={}",
+                type_key(actr_type),
+                fingerprint
+            );
+            return Ok(None);
+        };
+
+        let proto_content = self.cipher.open(proto_content)?;
+        let service_spec = ServiceSpec::decode(&proto_content[..])
+            .with_context(|| "Failed to decode ServiceSpec")?;
+
+        debug!(
+            "Found proto spec: {} fingerprint={}",
+            type_key(actr_type),
+            fingerprint
+        );
+        Ok(Some(service_spec))
+    }
+
+    /// 过期 proto specs 由 Redis TTL 自动删除
+    async fn cleanup_expired_proto_specs(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// 保存（或刷新）一个 ServiceSpec 历史版本
+    async fn save_spec_version(&self, service_name: &str, version: &SpecVersion) -> Result<()> {
+        let key = self.spec_history_key(service_name);
+        let mut conn = self.conn.clone();
+
+        let existing: Option<String> = redis::cmd("HGET")
+            .arg(&key)
+            .arg(&version.fingerprint)
+            .query_async(&mut conn)
+            .await?;
+
+        // 已存在的版本只刷新 last_seen_at
+        let stored = match existing.and_then(|value| {
+            serde_json::from_str::<StoredSpecVersion>(&value)
+                .map_err(|e| error!("Failed to decode spec history of {}: {}", service_name, e))
+                .ok()
+        }) {
+            Some(mut stored) => {
+                stored.last_seen_at = version.last_seen_at;
+                stored
+            }
+            None => StoredSpecVersion {
+                fingerprint: version.fingerprint.clone(),
+                published_at: version.published_at,
+                first_seen_at: version.first_seen_at,
+                last_seen_at: version.last_seen_at,
+                spec_blob: self.seal_base64(version.spec.encode_to_vec())?,
+            },
+        };
+
+        let _: u64 = redis::cmd("HSET")
+            .arg(&key)
+            .arg(&version.fingerprint)
+            .arg(serde_json::to_string(&stored)?)
+            .query_async(&mut conn)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to save spec version {service_name} fingerprint={}",
+                    version.fingerprint
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// 删除被保留策略淘汰的历史版本
+    async fn delete_spec_versions(
+        &self,
+        service_name: &str,
+        fingerprints: &[String],
+    ) -> Result<()> {
+        if fingerprints.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn.clone();
+        let _: u64 = redis::cmd("HDEL")
+            .arg(self.spec_history_key(service_name))
+            .arg(fingerprints)
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// 加载全部历史版本：(service_name, version)，按首次出现时间升序
+    async fn load_spec_history(&self) -> Result<Vec<(String, SpecVersion)>> {
+        let key_prefix = self.spec_history_key("");
+        let keys = self.scan_keys(&format!("{key_prefix}*")).await?;
+        let mut conn = self.conn.clone();
+        let mut versions = Vec::new();
+
+        for key in keys {
+            let Some(service_name) = key.strip_prefix(&key_prefix) else {
+                continue;
+            };
+            let fields: HashMap<String, String> = redis::cmd("HGETALL")
+                .arg(&key)
+                .query_async(&mut conn)
+                .await?;
+
+            for value in fields.into_values() {
+                let stored: StoredSpecVersion = match serde_json::from_str(&value) {
+                    Ok(stored) => stored,
+                    Err(e) => {
+                        error!("Failed to decode spec history of {}: {}", service_name, e);
+                        continue;
+                    }
+                };
+                let Some(spec_blob) = self.open_base64(service_name, &stored.spec_blob) else {
+                    continue;
+                };
+                let spec = match ServiceSpec::decode(&spec_blob[..]) {
+                    Ok(spec) => spec,
+                    Err(e) => {
+                        error!("Failed to decode spec history of {}: {}", service_name, e);
+                        continue;
+                    }
+                };
+                versions.push((
+                    service_name.to_string(),
+                    SpecVersion {
+                        fingerprint: stored.fingerprint,
+                        published_at: stored.published_at,
+                        first_seen_at: stored.first_seen_at,
+                        last_seen_at: stored.last_seen_at,
+                        spec,
+                    },
+                ));
+            }
+        }
+
+        versions.sort_by_key(|(_, version)| version.first_seen_at);
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::Realm;
+
+    #[test]
+    fn test_stored_service_roundtrip() {
+        let actor_id = ActrId {
+            serial_number: 7,
+            realm: Realm { realm_id: 1001 },
+            r#type: ActrType {
+                manufacturer: "acme".to_string(),
+                name: "echo".to_string(),
+                version: None,
+            },
+        };
+        let service = ServiceInfo {
+            actor_id,
+            service_name: "echo".to_string(),
+            message_types: vec!["acme.Echo".to_string()],
+            capabilities: None,
+            status: crate::service_registry::ServiceStatus::Available,
+            last_heartbeat_time_secs: 1_700_000_000,
+            service_spec: Some(ServiceSpec {
+                fingerprint: "sha256:abc".to_string(),
+                ..Default::default()
+            }),
+            acl: None,
+            service_availability_state: None,
+            power_reserve: None,
+            mailbox_backlog: None,
+            worst_dependency_health_state: None,
+            protocol_compatibility_score: None,
+            geo_location: None,
+            sticky_client_ids: vec![],
+            ws_address: Some("ws://10.0.0.1:9100".to_string()),
+        };
+
+        let stored: StoredService = serde_json::from_str(
+            &serde_json::to_string(&StoredService {
+                info: service.clone(),
+                service_spec_blob: Some(
+                    BASE64_STANDARD.encode(service.service_spec.as_ref().unwrap().encode_to_vec()),
+                ),
+                acl_blob: None,
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(stored.info.service_name, "echo");
+        assert_eq!(stored.info.actor_id.serial_number, 7);
+        assert_eq!(stored.info.message_types, service.message_types);
+        assert!(stored.info.service_spec.is_none());
+        assert!(stored.service_spec_blob.is_some());
+        assert!(stored.acl_blob.is_none());
+    }
+}
@@ -1,47 +1,46 @@
-//! ServiceRegistry SQLite 持久化缓存层
+//! ServiceRegistry SQLite 存储后端
 //!
-//! ## 设计原则
+//! 单节点默认后端，数据库文件为 `{sqlite_path}/signaling_cache.db`（`storage = "memory"` 时为内存数据库）。
+//! 服务记录带 TTL，过期数据由定期清理任务删除。
 //!
-//! 1. **SQLite 作为缓存**：不是主数据源，用于重启恢复
-//! 2. **数据有 TTL**：默认 1 小时过期，自动清理
-//! 3. **内存优先**：HashMap 是主存储，SQLite 是备份
+//! ## 加密
 //!
-//! ## 数据流
-//!
-//! - 启动：SQLite → HashMap（恢复缓存）
-//! - 注册：HashMap + SQLite（双写）
-//! - 查询：HashMap（快速）
-//! - 心跳：HashMap + SQLite（更新 TTL）
-//! - 清理：定期清理过期数据
+//! ACL 与 ServiceSpec 相关列经 [`RegistryCipher`] 加密存储（启用 `registry_encryption` 时），
+//! 加载时透明解密；启用前写入的明文可通过 [`SqliteRegistryStorage::migrate_encryption`] 迁移
 
-use crate::service_registry::{ServiceCapabilities, ServiceInfo, ServiceLocation, ServiceStatus};
+use super::backend::RegistryStorage;
+use super::{
+    CacheStats, DEFAULT_PROTO_TTL_SECS, DEFAULT_SERVICE_TTL_SECS, EncryptionMigrationStats,
+    current_timestamp,
+};
+use crate::registry_encryption::RegistryCipher;
+use crate::service_registry::{
+    ServiceCapabilities, ServiceInfo, ServiceLocation, ServiceStatus, SpecVersion,
+};
 use actr_protocol::{Acl, ActrId, ServiceSpec};
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
 use prost::Message as ProstMessage;
 use serde_json;
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
-use std::{
-    path::Path,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use sqlx::sqlite::{SqliteConnection, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
 use tracing::{debug, error, info};
 
 use crate::actr_type_utils::{normalize_version, type_key};
 
-/// ServiceRegistry 持久化存储
+/// ServiceRegistry SQLite 存储
 #[derive(Debug)]
-pub struct ServiceRegistryStorage {
+pub struct SqliteRegistryStorage {
     pool: SqlitePool,
     /// TTL（秒），默认 3600 秒（1 小时）
     default_ttl_secs: u64,
     /// Proto specs TTL（秒），默认 604800 秒（7 天）
     proto_ttl_secs: u64,
+    /// 敏感列加密（默认不加密）
+    cipher: RegistryCipher,
 }
 
-/// 默认服务 TTL（1 小时）
-pub const DEFAULT_SERVICE_TTL_SECS: u64 = 12 * 3600; // 临时方案
-
-impl ServiceRegistryStorage {
+impl SqliteRegistryStorage {
     /// 创建存储实例
     pub async fn new(database_file: impl AsRef<Path>, ttl_secs: Option<u64>) -> Result<Self> {
         let db_path = database_file.as_ref();
@@ -65,18 +64,54 @@ impl ServiceRegistryStorage {
         let storage = Self {
             pool,
             default_ttl_secs: ttl_secs.unwrap_or(DEFAULT_SERVICE_TTL_SECS),
-            proto_ttl_secs: 604800, // Proto specs 默认 7 天
+            proto_ttl_secs: DEFAULT_PROTO_TTL_SECS,
+            cipher: RegistryCipher::default(),
+        };
+
+        storage.init_schema().await?;
+        info!(
+            "✅ SQLite registry storage initialized with TTL={}s",
+            storage.default_ttl_secs
+        );
+
+        Ok(storage)
+    }
+
+    /// 创建内存存储实例（`storage = "memory"`，不写文件系统，重启后不恢复）
+    ///
+    /// 每个 `:memory:` 连接都是独立的数据库，因此连接池固定为单个常驻连接
+    pub async fn new_in_memory(ttl_secs: Option<u64>) -> Result<Self> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .context("Failed to create in-memory database")?;
+
+        let storage = Self {
+            pool,
+            default_ttl_secs: ttl_secs.unwrap_or(DEFAULT_SERVICE_TTL_SECS),
+            proto_ttl_secs: DEFAULT_PROTO_TTL_SECS,
+            cipher: RegistryCipher::default(),
         };
 
         storage.init_schema().await?;
         info!(
-            "✅ ServiceRegistryStorage initialized with TTL={}s",
+            "✅ In-memory SQLite registry storage initialized with TTL={}s",
             storage.default_ttl_secs
         );
 
         Ok(storage)
     }
 
+    /// 设置敏感列加密
+    pub fn with_cipher(mut self, cipher: RegistryCipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// 初始化数据库表结构
     async fn init_schema(&self) -> Result<()> {
         sqlx::query(
@@ -156,12 +191,191 @@ impl ServiceRegistryStorage {
         .await
         .with_context(|| "Failed to create service_specs table")?;
 
+        // service_spec_history 表：按服务名记录发布过的 ServiceSpec 版本
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS service_spec_history (
+                service_name TEXT NOT NULL,
+                service_fingerprint TEXT NOT NULL,
+                published_at INTEGER NOT NULL,
+                first_seen_at INTEGER NOT NULL,
+                last_seen_at INTEGER NOT NULL,
+                spec_blob BLOB NOT NULL,
+                PRIMARY KEY (service_name, service_fingerprint)
+            );
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .with_context(|| "Failed to create service_spec_history table")?;
+
         info!("Database schema initialized");
         Ok(())
     }
 
+    /// 将数据库行转换为 ServiceInfo
+    fn row_to_service_info(&self, row: sqlx::sqlite::SqliteRow) -> Result<ServiceInfo> {
+        use sqlx::Row;
+
+        // ActorId
+        let actor_id = ActrId {
+            serial_number: row.get::<i64, _>("actor_serial_number") as u64,
+            realm: actr_protocol::Realm {
+                realm_id: row.get::<i64, _>("actor_realm_id") as u32,
+            },
+            r#type: actr_protocol::ActrType {
+                manufacturer: row.get("actor_manufacturer"),
+                name: row.get("actor_device_name"),
+                version: storage_to_version(row.get::<String, _>("actor_type_version")),
+            },
+        };
+
+        // 基本字段
+        let service_name: String = row.get("service_name");
+        let message_types: Vec<String> = serde_json::from_str(row.get("message_types"))?;
+        let status = string_to_status(row.get("status"))?;
+
+        // 可选字段
+        let capabilities: Option<ServiceCapabilities> = row
+            .get::<Option<String>, _>("capabilities_json")
+            .map(|s| serde_json::from_str(&s))
+            .transpose()?;
+
+        // ServiceSpec (protobuf BLOB)
+        let service_spec: Option<ServiceSpec> = row
+            .get::<Option<Vec<u8>>, _>("service_spec_blob")
+            .and_then(|bytes| self.open_blob(&service_name, bytes))
+            .and_then(|bytes| ServiceSpec::decode(&bytes[..]).ok());
+
+        // ACL (protobuf BLOB)
+        let acl: Option<Acl> = row
+            .get::<Option<Vec<u8>>, _>("acl_blob")
+            .and_then(|bytes| self.open_blob(&service_name, bytes))
+            .and_then(|bytes| Acl::decode(&bytes[..]).ok());
+
+        // 地理位置
+        let geo_location =
+            row.get::<Option<String>, _>("geo_region")
+                .map(|region| ServiceLocation {
+                    region,
+                    longitude: row.get("geo_longitude"),
+                    latitude: row.get("geo_latitude"),
+                });
+
+        // 粘滞客户端
+        let sticky_client_ids: Vec<String> = serde_json::from_str(row.get("sticky_client_ids"))?;
+
+        Ok(ServiceInfo {
+            actor_id,
+            service_name,
+            message_types,
+            capabilities,
+            status,
+            last_heartbeat_time_secs: row.get::<i64, _>("last_heartbeat_at") as u64,
+            service_spec,
+            acl,
+            service_availability_state: row
+                .get::<Option<i64>, _>("service_availability_state")
+                .map(|v| v as i32),
+            power_reserve: row.get::<Option<f64>, _>("power_reserve").map(|v| v as f32),
+            mailbox_backlog: row
+                .get::<Option<f64>, _>("mailbox_backlog")
+                .map(|v| v as f32),
+            worst_dependency_health_state: row
+                .get::<Option<i64>, _>("worst_dependency_health_state")
+                .map(|v| v as i32),
+            protocol_compatibility_score: row
+                .get::<Option<f64>, _>("protocol_compatibility_score")
+                .map(|v| v as f32),
+            geo_location,
+            sticky_client_ids,
+            ws_address: None, // Not persisted in SQLite cache; populated from live RegisterRequest
+        })
+    }
+
+    /// 解密服务行中的 BLOB，失败时记录错误并视为缺失
+    fn open_blob(&self, service_name: &str, bytes: Vec<u8>) -> Option<Vec<u8>> {
+        self.cipher
+            .open(bytes)
+            .map_err(|e| error!("Failed to decrypt registry data of {}: {}", service_name, e))
+            .ok()
+    }
+
+    /// 加密启用加密前写入的明文数据，已加密的行保持不变
+    ///
+    /// 所有表在同一事务内迁移，中途失败不会留下部分迁移的数据
+    pub async fn migrate_encryption(&self) -> Result<EncryptionMigrationStats> {
+        if !self.cipher.is_enabled() {
+            bail!("Registry encryption is not enabled");
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let stats = EncryptionMigrationStats {
+            service_specs: self
+                .seal_column(&mut tx, "service_registry", "service_spec_blob")
+                .await?,
+            acls: self
+                .seal_column(&mut tx, "service_registry", "acl_blob")
+                .await?,
+            proto_specs: self
+                .seal_column(&mut tx, "service_specs", "proto_content")
+                .await?,
+            spec_history: self
+                .seal_column(&mut tx, "service_spec_history", "spec_blob")
+                .await?,
+        };
+        tx.commit()
+            .await
+            .context("Failed to commit encryption migration")?;
+
+        info!(
+            "🔐 Encrypted registry data: {} service specs, {} ACLs, {} proto specs, {} history versions",
+            stats.service_specs, stats.acls, stats.proto_specs, stats.spec_history
+        );
+        Ok(stats)
+    }
+
+    /// 加密单个列中的明文，返回加密的行数
+    async fn seal_column(
+        &self,
+        conn: &mut SqliteConnection,
+        table: &str,
+        column: &str,
+    ) -> Result<u64> {
+        use sqlx::Row;
+
+        let select = format!("SELECT rowid, {column} FROM {table} WHERE {column} IS NOT NULL");
+        let rows = sqlx::query(&select)
+            .fetch_all(&mut *conn)
+            .await
+            .with_context(|| format!("Failed to read {table}.{column}"))?;
+
+        let update = format!("UPDATE {table} SET {column} = ?1 WHERE rowid = ?2");
+        let mut sealed = 0;
+        for row in rows {
+            let rowid: i64 = row.get(0);
+            let blob: Vec<u8> = row.get(1);
+            if RegistryCipher::is_sealed(&blob) {
+                continue;
+            }
+
+            sqlx::query(&update)
+                .bind(self.cipher.seal(blob)?)
+                .bind(rowid)
+                .execute(&mut *conn)
+                .await
+                .with_context(|| format!("Failed to encrypt {table}.{column}"))?;
+            sealed += 1;
+        }
+
+        Ok(sealed)
+    }
+}
+
+#[async_trait]
+impl RegistryStorage for SqliteRegistryStorage {
     /// 保存服务信息
-    pub async fn save_service(&self, service: &ServiceInfo) -> Result<()> {
+    async fn save_service(&self, service: &ServiceInfo) -> Result<()> {
         let now = current_timestamp();
         let expires_at = now + self.default_ttl_secs;
 
@@ -175,18 +389,28 @@ impl ServiceRegistryStorage {
         let sticky_client_ids_json = serde_json::to_string(&service.sticky_client_ids)?;
 
         // ServiceSpec 序列化为 protobuf bytes
-        let service_spec_blob = service.service_spec.as_ref().and_then(|spec| {
-            let mut buf = Vec::new();
-            spec.encode(&mut buf).ok()?;
-            Some(buf)
-        });
+        let service_spec_blob = service
+            .service_spec
+            .as_ref()
+            .and_then(|spec| {
+                let mut buf = Vec::new();
+                spec.encode(&mut buf).ok()?;
+                Some(buf)
+            })
+            .map(|buf| self.cipher.seal(buf))
+            .transpose()?;
 
         // ACL 序列化为 protobuf bytes
-        let acl_blob = service.acl.as_ref().and_then(|acl| {
-            let mut buf = Vec::new();
-            acl.encode(&mut buf).ok()?;
-            Some(buf)
-        });
+        let acl_blob = service
+            .acl
+            .as_ref()
+            .and_then(|acl| {
+                let mut buf = Vec::new();
+                acl.encode(&mut buf).ok()?;
+                Some(buf)
+            })
+            .map(|buf| self.cipher.seal(buf))
+            .transpose()?;
 
         // 提取 ActorId 字段
         let actor_type = &service.actor_id.r#type;
@@ -271,7 +495,7 @@ impl ServiceRegistryStorage {
     }
 
     /// 更新心跳时间和 TTL
-    pub async fn update_heartbeat(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
+    async fn update_heartbeat(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
         let now = current_timestamp();
         let expires_at = now + self.default_ttl_secs;
 
@@ -302,7 +526,7 @@ impl ServiceRegistryStorage {
     }
 
     /// 删除服务
-    pub async fn delete_service(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
+    async fn delete_service(&self, actor_id: &ActrId, service_name: &str) -> Result<()> {
         sqlx::query(
             r#"
             DELETE FROM service_registry
@@ -323,7 +547,7 @@ impl ServiceRegistryStorage {
     }
 
     /// 加载所有有效服务（启动时恢复）
-    pub async fn load_all_services(&self) -> Result<Vec<ServiceInfo>> {
+    async fn load_all_services(&self) -> Result<Vec<ServiceInfo>> {
         let now = current_timestamp();
 
         let rows = sqlx::query(
@@ -373,7 +597,7 @@ impl ServiceRegistryStorage {
     /// # Returns
     ///
     /// 该 Actor 的所有未过期服务列表
-    pub async fn load_services_by_actor_id(&self, actor_id: &ActrId) -> Result<Vec<ServiceInfo>> {
+    async fn load_services_by_actor_id(&self, actor_id: &ActrId) -> Result<Vec<ServiceInfo>> {
         let now = current_timestamp();
 
         let rows = sqlx::query(
@@ -426,7 +650,7 @@ impl ServiceRegistryStorage {
     }
 
     /// 清理过期数据
-    pub async fn cleanup_expired(&self) -> Result<u64> {
+    async fn cleanup_expired(&self) -> Result<u64> {
         let now = current_timestamp();
 
         let result = sqlx::query(
@@ -446,92 +670,8 @@ impl ServiceRegistryStorage {
         Ok(deleted_count)
     }
 
-    /// 将数据库行转换为 ServiceInfo
-    fn row_to_service_info(&self, row: sqlx::sqlite::SqliteRow) -> Result<ServiceInfo> {
-        use sqlx::Row;
-
-        // ActorId
-        let actor_id = ActrId {
-            serial_number: row.get::<i64, _>("actor_serial_number") as u64,
-            realm: actr_protocol::Realm {
-                realm_id: row.get::<i64, _>("actor_realm_id") as u32,
-            },
-            r#type: actr_protocol::ActrType {
-                manufacturer: row.get("actor_manufacturer"),
-                name: row.get("actor_device_name"),
-                version: storage_to_version(row.get::<String, _>("actor_type_version")),
-            },
-        };
-
-        // 基本字段
-        let service_name: String = row.get("service_name");
-        let message_types: Vec<String> = serde_json::from_str(row.get("message_types"))?;
-        let status = string_to_status(row.get("status"))?;
-
-        // 可选字段
-        let capabilities: Option<ServiceCapabilities> = row
-            .get::<Option<String>, _>("capabilities_json")
-            .map(|s| serde_json::from_str(&s))
-            .transpose()?;
-
-        // ServiceSpec (protobuf BLOB)
-        let service_spec: Option<ServiceSpec> = row
-            .get::<Option<Vec<u8>>, _>("service_spec_blob")
-            .map(|bytes| ServiceSpec::decode(&bytes[..]))
-            .transpose()
-            .ok()
-            .flatten();
-
-        // ACL (protobuf BLOB)
-        let acl: Option<Acl> = row
-            .get::<Option<Vec<u8>>, _>("acl_blob")
-            .map(|bytes| Acl::decode(&bytes[..]))
-            .transpose()
-            .ok()
-            .flatten();
-
-        // 地理位置
-        let geo_location =
-            row.get::<Option<String>, _>("geo_region")
-                .map(|region| ServiceLocation {
-                    region,
-                    longitude: row.get("geo_longitude"),
-                    latitude: row.get("geo_latitude"),
-                });
-
-        // 粘滞客户端
-        let sticky_client_ids: Vec<String> = serde_json::from_str(row.get("sticky_client_ids"))?;
-
-        Ok(ServiceInfo {
-            actor_id,
-            service_name,
-            message_types,
-            capabilities,
-            status,
-            last_heartbeat_time_secs: row.get::<i64, _>("last_heartbeat_at") as u64,
-            service_spec,
-            acl,
-            service_availability_state: row
-                .get::<Option<i64>, _>("service_availability_state")
-                .map(|v| v as i32),
-            power_reserve: row.get::<Option<f64>, _>("power_reserve").map(|v| v as f32),
-            mailbox_backlog: row
-                .get::<Option<f64>, _>("mailbox_backlog")
-                .map(|v| v as f32),
-            worst_dependency_health_state: row
-                .get::<Option<i64>, _>("worst_dependency_health_state")
-                .map(|v| v as i32),
-            protocol_compatibility_score: row
-                .get::<Option<f64>, _>("protocol_compatibility_score")
-                .map(|v| v as f32),
-            geo_location,
-            sticky_client_ids,
-            ws_address: None, // Not persisted in SQLite cache; populated from live RegisterRequest
-        })
-    }
-
     /// 获取统计信息
-    pub async fn get_stats(&self) -> Result<CacheStats> {
+    async fn get_stats(&self) -> Result<CacheStats> {
         let now = current_timestamp();
 
         let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM service_registry")
@@ -551,15 +691,11 @@ impl ServiceRegistryStorage {
         })
     }
 
-    // =========================================================================
-    // service_specs 表方法：存储 Proto 内容用于兼容性协商
-    // =========================================================================
-
     /// 保存 Proto spec（用于兼容性协商）
     ///
     /// 在 Actor 注册时，如果 ServiceSpec 存在，则提取 Proto 并保存到 service_specs 表。
     /// 使用 INSERT OR REPLACE 策略，相同指纹的 proto 会更新时间戳。
-    pub async fn save_proto_spec(
+    async fn save_proto_spec(
         &self,
         actr_type: &actr_protocol::ActrType,
         service_spec: &ServiceSpec,
@@ -573,6 +709,7 @@ impl ServiceRegistryStorage {
         service_spec
             .encode(&mut proto_content)
             .with_context(|| "Failed to encode ServiceSpec")?;
+        let proto_content = self.cipher.seal(proto_content)?;
 
         sqlx::query(
             r#"
@@ -606,7 +743,7 @@ impl ServiceRegistryStorage {
     /// 根据指纹获取 Proto spec
     ///
     /// 查询匹配的 Proto，如果找到则更新访问时间（异步执行，避免阻塞查询）。
-    pub async fn get_proto_by_fingerprint(
+    async fn get_proto_by_fingerprint(
         &self,
         actr_type: &actr_protocol::ActrType,
         fingerprint: &str,
@@ -633,7 +770,7 @@ impl ServiceRegistryStorage {
 
         if let Some(row) = row {
             use sqlx::Row;
-            let proto_content: Vec<u8> = row.get("proto_content");
+            let proto_content = self.cipher.open(row.get("proto_content"))?;
             let service_spec = ServiceSpec::decode(&proto_content[..])
                 .with_context(|| "Failed to decode ServiceSpec")?;
 
@@ -677,7 +814,7 @@ impl ServiceRegistryStorage {
     }
 
     /// 清理过期的 proto specs
-    pub async fn cleanup_expired_proto_specs(&self) -> Result<u64> {
+    async fn cleanup_expired_proto_specs(&self) -> Result<u64> {
         let now = current_timestamp();
 
         let result = sqlx::query("DELETE FROM service_specs WHERE expires_at <= ?1")
@@ -695,14 +832,103 @@ impl ServiceRegistryStorage {
 
         Ok(deleted_count)
     }
-}
 
-/// 缓存统计信息
-#[derive(Debug, Clone)]
-pub struct CacheStats {
-    pub total_services: u64,
-    pub expired_services: u64,
-    pub valid_services: u64,
+    /// 保存（或刷新）一个 ServiceSpec 历史版本
+    async fn save_spec_version(&self, service_name: &str, version: &SpecVersion) -> Result<()> {
+        let mut spec_blob = Vec::new();
+        version
+            .spec
+            .encode(&mut spec_blob)
+            .with_context(|| "Failed to encode ServiceSpec")?;
+        let spec_blob = self.cipher.seal(spec_blob)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO service_spec_history (service_name, service_fingerprint, published_at, first_seen_at, last_seen_at, spec_blob)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(service_name, service_fingerprint)
+            DO UPDATE SET last_seen_at = excluded.last_seen_at
+            "#,
+        )
+        .bind(service_name)
+        .bind(&version.fingerprint)
+        .bind(version.published_at)
+        .bind(version.first_seen_at as i64)
+        .bind(version.last_seen_at as i64)
+        .bind(&spec_blob)
+        .execute(&self.pool)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to save spec version {service_name} fingerprint={}",
+                version.fingerprint
+            )
+        })?;
+
+        Ok(())
+    }
+
+    /// 删除被保留策略淘汰的历史版本
+    async fn delete_spec_versions(
+        &self,
+        service_name: &str,
+        fingerprints: &[String],
+    ) -> Result<()> {
+        for fingerprint in fingerprints {
+            sqlx::query(
+                "DELETE FROM service_spec_history WHERE service_name = ?1 AND service_fingerprint = ?2",
+            )
+            .bind(service_name)
+            .bind(fingerprint)
+            .execute(&self.pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// 加载全部历史版本：(service_name, version)，按首次出现时间升序
+    async fn load_spec_history(&self) -> Result<Vec<(String, SpecVersion)>> {
+        use sqlx::Row;
+
+        let rows = sqlx::query(
+            r#"SELECT service_name, service_fingerprint, published_at, first_seen_at, last_seen_at, spec_blob
+               FROM service_spec_history
+               ORDER BY first_seen_at ASC"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut versions = Vec::with_capacity(rows.len());
+        for row in rows {
+            let service_name: String = row.get("service_name");
+            let spec_blob = match self.cipher.open(row.get("spec_blob")) {
+                Ok(spec_blob) => spec_blob,
+                Err(e) => {
+                    error!("Failed to decrypt spec history of {}: {}", service_name, e);
+                    continue;
+                }
+            };
+            let spec = match ServiceSpec::decode(&spec_blob[..]) {
+                Ok(spec) => spec,
+                Err(e) => {
+                    error!("Failed to decode spec history of {}: {}", service_name, e);
+                    continue;
+                }
+            };
+            versions.push((
+                service_name,
+                SpecVersion {
+                    fingerprint: row.get("service_fingerprint"),
+                    published_at: row.get("published_at"),
+                    first_seen_at: row.get::<i64, _>("first_seen_at") as u64,
+                    last_seen_at: row.get::<i64, _>("last_seen_at") as u64,
+                    spec,
+                },
+            ));
+        }
+
+        Ok(versions)
+    }
 }
 
 fn version_to_storage(version: Option<String>) -> String {
@@ -734,14 +960,6 @@ fn string_to_status(s: &str) -> Result<ServiceStatus> {
     }
 }
 
-/// 获取当前 Unix 时间戳（秒）
-fn current_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -782,7 +1000,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_save_and_load() {
-        let storage = ServiceRegistryStorage::new(":memory:", Some(3600))
+        let storage = SqliteRegistryStorage::new(":memory:", Some(3600))
             .await
             .unwrap();
 
@@ -795,9 +1013,26 @@ mod tests {
         assert_eq!(loaded[0].actor_id.serial_number, 1);
     }
 
+    #[tokio::test]
+    async fn test_in_memory_storage_shares_single_database() {
+        let storage = SqliteRegistryStorage::new_in_memory(Some(3600))
+            .await
+            .unwrap();
+
+        // 并发写入与读取都落在同一个常驻连接上
+        let first = create_test_service(1, "service-a");
+        let second = create_test_service(2, "service-b");
+        let (a, b) = tokio::join!(storage.save_service(&first), storage.save_service(&second));
+        a.unwrap();
+        b.unwrap();
+
+        let loaded = storage.load_all_services().await.unwrap();
+        assert_eq!(loaded.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_ttl_expiration() {
-        let storage = ServiceRegistryStorage::new(":memory:", Some(1))
+        let storage = SqliteRegistryStorage::new(":memory:", Some(1))
             .await
             .unwrap();
 
@@ -818,7 +1053,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_cleanup_expired() {
-        let storage = ServiceRegistryStorage::new(":memory:", Some(1))
+        let storage = SqliteRegistryStorage::new(":memory:", Some(1))
             .await
             .unwrap();
 
@@ -839,7 +1074,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_heartbeat() {
-        let storage = ServiceRegistryStorage::new(":memory:", Some(10))
+        let storage = SqliteRegistryStorage::new(":memory:", Some(10))
             .await
             .unwrap();
 
@@ -859,7 +1094,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_service_specs_can_coexist_with_different_versions() {
-        let storage = ServiceRegistryStorage::new(":memory:", Some(3600))
+        let storage = SqliteRegistryStorage::new(":memory:", Some(3600))
             .await
             .unwrap();
 
@@ -914,4 +1149,92 @@ mod tests {
         assert_eq!(loaded_v1.description.as_deref(), Some("v1"));
         assert_eq!(loaded_v2.description.as_deref(), Some("v2"));
     }
+
+    #[tokio::test]
+    async fn test_encryption_migration() {
+        let storage = SqliteRegistryStorage::new_in_memory(Some(3600))
+            .await
+            .unwrap();
+
+        let spec = ServiceSpec {
+            name: "secure".to_string(),
+            fingerprint: "fp-1".to_string(),
+            description: Some("secret".to_string()),
+            protobufs: vec![],
+            published_at: None,
+            tags: vec![],
+        };
+        let mut service = create_test_service(1, "secure-service");
+        service.service_spec = Some(spec.clone());
+        service.acl = Some(Acl::default());
+        let actr_type = service.actor_id.r#type.clone();
+
+        // 启用加密前写入的明文
+        storage.save_service(&service).await.unwrap();
+        storage.save_proto_spec(&actr_type, &spec).await.unwrap();
+        storage
+            .save_spec_version(
+                "secure-service",
+                &SpecVersion {
+                    fingerprint: "fp-1".to_string(),
+                    published_at: 0,
+                    first_seen_at: 1,
+                    last_seen_at: 1,
+                    spec: spec.clone(),
+                },
+            )
+            .await
+            .unwrap();
+        assert!(storage.migrate_encryption().await.is_err());
+
+        let storage = storage.with_cipher(RegistryCipher::new(
+            ks::KeyEncryptor::from_kek(&ks::KeyEncryptor::generate_kek()).unwrap(),
+        ));
+
+        // 明文仍可读取
+        let loaded = storage.load_all_services().await.unwrap();
+        assert_eq!(loaded[0].service_spec, Some(spec.clone()));
+
+        let stats = storage.migrate_encryption().await.unwrap();
+        assert_eq!(
+            stats,
+            EncryptionMigrationStats {
+                service_specs: 1,
+                acls: 1,
+                proto_specs: 1,
+                spec_history: 1,
+            }
+        );
+
+        // 加密后写入的数据与已迁移的数据不会重复加密
+        let mut second = service.clone();
+        second.actor_id = create_test_actor_id(2);
+        storage.save_service(&second).await.unwrap();
+        assert_eq!(
+            storage.migrate_encryption().await.unwrap(),
+            EncryptionMigrationStats::default()
+        );
+
+        let blob: Vec<u8> = sqlx::query_scalar("SELECT proto_content FROM service_specs")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert!(RegistryCipher::is_sealed(&blob));
+
+        // 加载时透明解密
+        let loaded = storage
+            .load_services_by_actor_id(&service.actor_id)
+            .await
+            .unwrap();
+        assert_eq!(loaded[0].service_spec, Some(spec.clone()));
+        assert_eq!(loaded[0].acl, Some(Acl::default()));
+        assert_eq!(
+            storage
+                .get_proto_by_fingerprint(&actr_type, "fp-1")
+                .await
+                .unwrap(),
+            Some(spec.clone())
+        );
+        assert_eq!(storage.load_spec_history().await.unwrap()[0].1.spec, spec);
+    }
 }
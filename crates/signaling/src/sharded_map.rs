@@ -0,0 +1,262 @@
+//! 分片并发映射表
+//!
+//! 连接表与 ActrId 索引按键的哈希分散到多个分片，每个分片一把读写锁：
+//! 注册、清理等写操作只锁定所在分片，Presence 扇出等按键查找的操作
+//! 不再与全表写锁串行。
+//!
+//! 跨分片操作（[`ShardedMap::for_each`]、[`ShardedMap::collect`]、[`ShardedMap::len`]）
+//! 逐个分片加锁遍历，结果不是整表的原子快照。
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, RandomState};
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// 默认分片数
+pub const DEFAULT_SHARDS: usize = 64;
+
+/// 按键哈希分片、每个分片独立加锁的 HashMap
+#[derive(Debug)]
+pub struct ShardedMap<K, V> {
+    shards: Box<[RwLock<HashMap<K, V>>]>,
+    hasher: RandomState,
+}
+
+impl<K, V> Default for ShardedMap<K, V>
+where
+    K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ShardedMap<K, V>
+where
+    K: Eq + Hash,
+{
+    /// 使用默认分片数创建
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// 指定分片数创建（至少 1 个分片）
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// 分片数
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn shard<Q>(&self, key: &Q) -> &RwLock<HashMap<K, V>>
+    where
+        Q: Hash + ?Sized,
+    {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    /// 获取 `key` 所在分片的读锁
+    pub async fn read<Q>(&self, key: &Q) -> RwLockReadGuard<'_, HashMap<K, V>>
+    where
+        Q: Hash + ?Sized,
+    {
+        self.shard(key).read().await
+    }
+
+    /// 获取 `key` 所在分片的写锁
+    pub async fn write<Q>(&self, key: &Q) -> RwLockWriteGuard<'_, HashMap<K, V>>
+    where
+        Q: Hash + ?Sized,
+    {
+        self.shard(key).write().await
+    }
+
+    /// 插入键值，返回旧值
+    pub async fn insert(&self, key: K, value: V) -> Option<V> {
+        self.shard(&key).write().await.insert(key, value)
+    }
+
+    /// 移除键值
+    pub async fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.shard(key).write().await.remove(key)
+    }
+
+    /// 是否包含 `key`
+    pub async fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.shard(key).read().await.contains_key(key)
+    }
+
+    /// 在分片读锁内读取 `key` 对应的值
+    pub async fn with<Q, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.shard(key).read().await.get(key).map(f)
+    }
+
+    /// 在分片写锁内修改 `key` 对应的值
+    pub async fn update<Q, R>(&self, key: &Q, f: impl FnOnce(&mut V) -> R) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.shard(key).write().await.get_mut(key).map(f)
+    }
+
+    /// 逐个分片遍历全部键值
+    pub async fn for_each(&self, mut f: impl FnMut(&K, &V)) {
+        for shard in self.shards.iter() {
+            for (key, value) in shard.read().await.iter() {
+                f(key, value);
+            }
+        }
+    }
+
+    /// 逐个分片遍历并收集 `f` 返回的 Some 值
+    pub async fn collect<R>(&self, mut f: impl FnMut(&K, &V) -> Option<R>) -> Vec<R> {
+        let mut collected = Vec::new();
+        self.for_each(|key, value| collected.extend(f(key, value)))
+            .await;
+        collected
+    }
+
+    /// 全部分片的条目总数
+    pub async fn len(&self) -> usize {
+        let mut len = 0;
+        for shard in self.shards.iter() {
+            len += shard.read().await.len();
+        }
+        len
+    }
+
+    /// 是否没有任何条目
+    pub async fn is_empty(&self) -> bool {
+        for shard in self.shards.iter() {
+            if !shard.read().await.is_empty() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_basic_operations() {
+        let map: ShardedMap<String, u32> = ShardedMap::with_shards(4);
+        assert!(map.is_empty().await);
+
+        for i in 0..100 {
+            map.insert(format!("key-{i}"), i).await;
+        }
+        assert_eq!(map.len().await, 100);
+        assert!(map.contains_key("key-42").await);
+        assert_eq!(map.with("key-42", |v| *v).await, Some(42));
+
+        map.update("key-42", |v| *v += 1).await;
+        assert_eq!(map.read("key-42").await.get("key-42"), Some(&43));
+
+        assert_eq!(map.remove("key-42").await, Some(43));
+        assert!(!map.contains_key("key-42").await);
+
+        let mut even = map.collect(|_, v| (v % 2 == 0).then_some(*v)).await;
+        even.sort();
+        assert_eq!(even.len(), 49);
+        assert_eq!(even[0], 0);
+    }
+
+    /// 模拟 Presence 扇出：读任务按键查找发送端，同时有写任务持续注册/清理连接
+    async fn fan_out_throughput(shards: usize) -> f64 {
+        const ENTRIES: u64 = 10_000;
+        const READERS: u64 = 8;
+        const WRITERS: u64 = 4;
+        const DURATION: Duration = Duration::from_millis(500);
+
+        let map = Arc::new(ShardedMap::<u64, u64>::with_shards(shards));
+        for i in 0..ENTRIES {
+            map.insert(i, i).await;
+        }
+
+        let deadline = Instant::now() + DURATION;
+        let mut writers = Vec::new();
+        for w in 0..WRITERS {
+            let map = map.clone();
+            writers.push(tokio::spawn(async move {
+                let mut key = ENTRIES + w;
+                while Instant::now() < deadline {
+                    // 写锁内模拟注册时的重复连接判定等少量工作
+                    let mut shard = map.write(&key).await;
+                    shard.insert(key, key);
+                    std::hint::black_box(shard.len());
+                    shard.remove(&key);
+                    drop(shard);
+                    key += WRITERS;
+                    tokio::task::yield_now().await;
+                }
+            }));
+        }
+
+        let mut readers = Vec::new();
+        for r in 0..READERS {
+            let map = map.clone();
+            readers.push(tokio::spawn(async move {
+                let mut lookups = 0u64;
+                while Instant::now() < deadline {
+                    let key = (lookups * READERS + r) % ENTRIES;
+                    std::hint::black_box(map.with(&key, |v| *v).await);
+                    lookups += 1;
+                    if lookups % 64 == 0 {
+                        tokio::task::yield_now().await;
+                    }
+                }
+                lookups
+            }));
+        }
+
+        for writer in writers {
+            writer.await.unwrap();
+        }
+        let mut lookups = 0;
+        for reader in readers {
+            lookups += reader.await.unwrap();
+        }
+        lookups as f64 / DURATION.as_secs_f64()
+    }
+
+    /// 扇出吞吐基准：单分片（等价于旧的全表锁）与默认分片数对比
+    ///
+    /// 运行：`cargo test -p signaling --release sharded_map::tests::bench_fan_out -- --ignored --nocapture`
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    #[ignore = "benchmark"]
+    async fn bench_fan_out_throughput() {
+        let single = fan_out_throughput(1).await;
+        let sharded = fan_out_throughput(DEFAULT_SHARDS).await;
+        println!(
+            "fan-out lookups/s: 1 shard = {single:.0}, {DEFAULT_SHARDS} shards = {sharded:.0} ({:.2}x)",
+            sharded / single
+        );
+        assert!(sharded > single);
+    }
+}
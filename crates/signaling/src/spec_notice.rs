@@ -0,0 +1,277 @@
+//! ServiceSpec 不兼容变更主动通知
+//!
+//! 记录客户端通过 RouteCandidates 依赖了哪些服务类型（以及依赖时的 fingerprint）。
+//! 当服务以新 fingerprint 重新注册且兼容性分析结果为破坏性变更时，
+//! 向仍在线的依赖方推送 [`SpecIncompatibilityNotice`]，避免其在调用时才发现契约已变。
+//!
+//! # 投递方式
+//! actr-protocol 目前没有专用的通知 payload，通知通过 `ErrorResponse` 下发：
+//! `code` 固定为 [`SPEC_INCOMPATIBILITY_NOTICE_CODE`]，`message` 为
+//! [`SPEC_INCOMPATIBILITY_NOTICE_PREFIX`] 加 JSON 编码的通知内容。
+//! 该 envelope 不带 `reply_for`，客户端可据此与请求错误区分。
+
+use crate::tunnel;
+use actr_protocol::{ActrId, ErrorResponse};
+use actrix_common::config::signaling::SpecNoticeConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 通知使用的 ErrorResponse code（426 Upgrade Required）
+pub const SPEC_INCOMPATIBILITY_NOTICE_CODE: u32 = tunnel::SPEC_INCOMPATIBILITY_NOTICE.code;
+
+/// 通知 message 前缀，其后为 JSON 编码的 [`SpecIncompatibilityNotice`]
+pub const SPEC_INCOMPATIBILITY_NOTICE_PREFIX: &str = tunnel::SPEC_INCOMPATIBILITY_NOTICE.prefix;
+
+/// ServiceSpec 不兼容变更通知
+#[derive(Debug, Clone, Serialize)]
+pub struct SpecIncompatibilityNotice {
+    /// 服务类型 key（`manufacturer:name[:version]`）
+    pub service_type: String,
+    /// ServiceSpec 名称
+    pub service_name: String,
+    /// 以新 fingerprint 注册的实例
+    pub provider_serial_number: u64,
+    /// 客户端依赖时的 fingerprint
+    pub previous_fingerprint: String,
+    /// 新注册的 fingerprint
+    pub new_fingerprint: String,
+    /// 破坏性变更描述
+    pub breaking_changes: Vec<String>,
+    /// 通知时间 (Unix 秒)
+    pub issued_at: i64,
+}
+
+impl SpecIncompatibilityNotice {
+    /// 编码为下发给客户端的 ErrorResponse
+    pub fn to_error_response(&self) -> ErrorResponse {
+        tunnel::SPEC_INCOMPATIBILITY_NOTICE.encode(self)
+    }
+}
+
+/// 依赖某服务类型的在线客户端
+#[derive(Debug, Clone)]
+pub struct Dependent {
+    pub client_id: String,
+    pub actor_id: ActrId,
+    /// 依赖时的 ServiceSpec fingerprint
+    pub fingerprint: String,
+    last_routed_at: Instant,
+    /// 已通知过的新 fingerprint，避免重复通知
+    notified_fingerprint: Option<String>,
+}
+
+/// 服务类型依赖关系跟踪器
+#[derive(Debug)]
+pub struct SpecDependencyTracker {
+    ttl: Duration,
+    /// service_type -> client_id -> Dependent
+    dependents: Mutex<HashMap<String, HashMap<String, Dependent>>>,
+}
+
+impl Default for SpecDependencyTracker {
+    fn default() -> Self {
+        Self::new(&SpecNoticeConfig::default())
+    }
+}
+
+impl SpecDependencyTracker {
+    /// 根据配置创建跟踪器
+    pub fn new(config: &SpecNoticeConfig) -> Self {
+        Self {
+            ttl: Duration::from_secs(config.dependent_ttl_secs.max(1)),
+            dependents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 记录一次路由请求：`client_id` 以 `fingerprint` 依赖 `service_type`
+    pub fn record_route(
+        &self,
+        service_type: &str,
+        client_id: &str,
+        actor_id: &ActrId,
+        fingerprint: &str,
+    ) {
+        self.record_route_at(
+            service_type,
+            client_id,
+            actor_id,
+            fingerprint,
+            Instant::now(),
+        );
+    }
+
+    fn record_route_at(
+        &self,
+        service_type: &str,
+        client_id: &str,
+        actor_id: &ActrId,
+        fingerprint: &str,
+        now: Instant,
+    ) {
+        if fingerprint.is_empty() {
+            return;
+        }
+
+        let mut dependents = self.dependents.lock().expect("spec dependents poisoned");
+        let entry = dependents
+            .entry(service_type.to_string())
+            .or_default()
+            .entry(client_id.to_string())
+            .or_insert_with(|| Dependent {
+                client_id: client_id.to_string(),
+                actor_id: actor_id.clone(),
+                fingerprint: fingerprint.to_string(),
+                last_routed_at: now,
+                notified_fingerprint: None,
+            });
+
+        if entry.fingerprint != fingerprint {
+            entry.fingerprint = fingerprint.to_string();
+            entry.notified_fingerprint = None;
+        }
+        entry.actor_id = actor_id.clone();
+        entry.last_routed_at = now;
+    }
+
+    /// 取出需要针对 `new_fingerprint` 检查兼容性的依赖方
+    ///
+    /// 跳过 fingerprint 相同或已针对该 fingerprint 通知过的依赖方，并清理过期条目
+    pub fn pending_dependents(&self, service_type: &str, new_fingerprint: &str) -> Vec<Dependent> {
+        self.pending_dependents_at(service_type, new_fingerprint, Instant::now())
+    }
+
+    fn pending_dependents_at(
+        &self,
+        service_type: &str,
+        new_fingerprint: &str,
+        now: Instant,
+    ) -> Vec<Dependent> {
+        let mut dependents = self.dependents.lock().expect("spec dependents poisoned");
+        let Some(by_client) = dependents.get_mut(service_type) else {
+            return Vec::new();
+        };
+
+        by_client.retain(|_, d| now.saturating_duration_since(d.last_routed_at) < self.ttl);
+        let pending = by_client
+            .values()
+            .filter(|d| {
+                d.fingerprint != new_fingerprint
+                    && d.notified_fingerprint.as_deref() != Some(new_fingerprint)
+            })
+            .cloned()
+            .collect();
+
+        if by_client.is_empty() {
+            dependents.remove(service_type);
+        }
+        pending
+    }
+
+    /// 标记依赖方已针对 `new_fingerprint` 收到通知
+    pub fn mark_notified(&self, service_type: &str, client_id: &str, new_fingerprint: &str) {
+        let mut dependents = self.dependents.lock().expect("spec dependents poisoned");
+        if let Some(dependent) = dependents
+            .get_mut(service_type)
+            .and_then(|by_client| by_client.get_mut(client_id))
+        {
+            dependent.notified_fingerprint = Some(new_fingerprint.to_string());
+        }
+    }
+
+    /// 连接断开时移除该客户端的全部依赖记录
+    pub fn remove_client(&self, client_id: &str) {
+        let mut dependents = self.dependents.lock().expect("spec dependents poisoned");
+        dependents.retain(|_, by_client| {
+            by_client.remove(client_id);
+            !by_client.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actr_protocol::{ActrType, Realm};
+
+    fn actor(serial: u64) -> ActrId {
+        ActrId {
+            serial_number: serial,
+            r#type: ActrType {
+                manufacturer: "test".to_string(),
+                name: "client".to_string(),
+                version: None,
+            },
+            realm: Realm { realm_id: 0 },
+        }
+    }
+
+    #[test]
+    fn test_pending_dependents_skip_same_and_notified_fingerprints() {
+        let tracker = SpecDependencyTracker::default();
+        tracker.record_route("acme:echo", "c1", &actor(1), "fp-old");
+        tracker.record_route("acme:echo", "c2", &actor(2), "fp-new");
+        tracker.record_route("acme:echo", "c3", &actor(3), "");
+
+        let pending = tracker.pending_dependents("acme:echo", "fp-new");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].client_id, "c1");
+        assert_eq!(pending[0].fingerprint, "fp-old");
+
+        tracker.mark_notified("acme:echo", "c1", "fp-new");
+        assert!(tracker.pending_dependents("acme:echo", "fp-new").is_empty());
+
+        // 客户端切换到新的 fingerprint 后重新参与通知
+        tracker.record_route("acme:echo", "c1", &actor(1), "fp-older");
+        assert_eq!(tracker.pending_dependents("acme:echo", "fp-new").len(), 1);
+    }
+
+    #[test]
+    fn test_expired_and_disconnected_dependents_are_dropped() {
+        let tracker = SpecDependencyTracker::new(&SpecNoticeConfig {
+            enabled: true,
+            dependent_ttl_secs: 60,
+        });
+        let start = Instant::now();
+        tracker.record_route_at("acme:echo", "c1", &actor(1), "fp-old", start);
+        tracker.record_route_at(
+            "acme:echo",
+            "c2",
+            &actor(2),
+            "fp-old",
+            start + Duration::from_secs(50),
+        );
+
+        let pending =
+            tracker.pending_dependents_at("acme:echo", "fp-new", start + Duration::from_secs(61));
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].client_id, "c2");
+
+        tracker.remove_client("c2");
+        assert!(tracker.pending_dependents("acme:echo", "fp-new").is_empty());
+    }
+
+    #[test]
+    fn test_notice_encodes_as_error_response() {
+        let notice = SpecIncompatibilityNotice {
+            service_type: "acme:echo".to_string(),
+            service_name: "echo".to_string(),
+            provider_serial_number: 7,
+            previous_fingerprint: "fp-old".to_string(),
+            new_fingerprint: "fp-new".to_string(),
+            breaking_changes: vec!["field removed".to_string()],
+            issued_at: 0,
+        };
+
+        let response = notice.to_error_response();
+        assert_eq!(response.code, SPEC_INCOMPATIBILITY_NOTICE_CODE);
+        let json = response
+            .message
+            .strip_prefix(SPEC_INCOMPATIBILITY_NOTICE_PREFIX)
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(value["new_fingerprint"], "fp-new");
+        assert_eq!(value["breaking_changes"][0], "field removed");
+    }
+}
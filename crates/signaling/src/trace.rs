@@ -20,20 +20,30 @@ impl<'a> Extractor for EnvelopeExtractor<'a> {
 }
 
 /// Extract trace context from SignalingEnvelope.
-/// If no valid context is present, returns the current thread Context as a new root.
-pub fn extract_trace_context(envelope: &SignalingEnvelope) -> Context {
+/// Returns `None` if the envelope carries no valid `traceparent`, so the caller
+/// keeps its local span hierarchy instead of starting from an empty context.
+pub fn extract_trace_context(envelope: &SignalingEnvelope) -> Option<Context> {
     let context = opentelemetry::global::get_text_map_propagator(|propagator| {
         propagator.extract(&EnvelopeExtractor(envelope))
     });
     let span_ref = context.span();
     let span_context = span_ref.span_context();
     if span_context.is_valid() {
-        context
+        Some(context)
     } else {
-        Context::current()
+        None
     }
 }
 
+/// OpenTelemetry context of the current tracing span.
+///
+/// Used when forwarding envelopes so that the receiver's spans become children
+/// of the signaling server span (actor -> signaling -> actor).
+pub fn current_trace_context() -> Context {
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    tracing::Span::current().context()
+}
+
 struct EnvelopeInjector<'a>(&'a mut SignalingEnvelope);
 
 impl<'a> Injector for EnvelopeInjector<'a> {
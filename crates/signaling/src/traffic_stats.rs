@@ -0,0 +1,273 @@
+//! 按 ActrType 聚合的信令流量统计
+//!
+//! 用于容量规划：按目标 ActrType 统计 envelope 数量与字节量，
+//! 帮助运维判断哪些服务类型占据了主要的信令流量。
+//!
+//! # 统计口径
+//! - **累计值**：自进程启动（或条目被跟踪）以来的 envelope 数与字节数
+//! - **近期速率**：基于指数衰减（可配置半衰期）的滑动估计，单位为每秒
+//! - **Top-N**：最多跟踪 `max_tracked_types` 个类型，超出时淘汰近期流量最低的条目
+
+use actrix_common::config::signaling::TrafficStatsConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 单个 ActrType 的流量计数
+#[derive(Debug, Clone)]
+struct TypeTraffic {
+    envelopes_total: u64,
+    bytes_total: u64,
+    /// 指数衰减后的 envelope 计数
+    decayed_envelopes: f64,
+    /// 指数衰减后的字节计数
+    decayed_bytes: f64,
+    last_update: Instant,
+}
+
+impl TypeTraffic {
+    fn new(now: Instant) -> Self {
+        Self {
+            envelopes_total: 0,
+            bytes_total: 0,
+            decayed_envelopes: 0.0,
+            decayed_bytes: 0.0,
+            last_update: now,
+        }
+    }
+
+    /// 计算从 last_update 到 now 的衰减因子
+    fn decay_factor(&self, now: Instant, half_life: Duration) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(self.last_update)
+            .as_secs_f64();
+        0.5_f64.powf(elapsed / half_life.as_secs_f64())
+    }
+
+    /// 将衰减计数推进到 now
+    fn decay_to(&mut self, now: Instant, half_life: Duration) {
+        let factor = self.decay_factor(now, half_life);
+        self.decayed_envelopes *= factor;
+        self.decayed_bytes *= factor;
+        self.last_update = now;
+    }
+
+    /// 在 now 时刻的衰减字节数（不修改状态，用于淘汰比较）
+    fn decayed_bytes_at(&self, now: Instant, half_life: Duration) -> f64 {
+        self.decayed_bytes * self.decay_factor(now, half_life)
+    }
+}
+
+/// 单个 ActrType 的流量快照
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeTrafficSnapshot {
+    /// ActrType key（`manufacturer:name[:version]`）
+    pub actr_type: String,
+    /// 累计 envelope 数
+    pub envelopes_total: u64,
+    /// 累计字节数
+    pub bytes_total: u64,
+    /// 近期 envelope 速率（每秒）
+    pub envelopes_per_sec: f64,
+    /// 近期字节速率（每秒）
+    pub bytes_per_sec: f64,
+}
+
+/// 流量统计快照
+#[derive(Debug, Clone, Serialize)]
+pub struct TrafficStatsSnapshot {
+    /// 当前跟踪的类型数量
+    pub tracked_types: usize,
+    /// 因容量限制被淘汰的类型累计次数
+    pub evicted_types: u64,
+    /// 衰减半衰期（秒）
+    pub half_life_secs: u64,
+    /// 按近期字节速率降序排列的 Top-N 类型
+    pub top: Vec<TypeTrafficSnapshot>,
+}
+
+#[derive(Debug, Default)]
+struct TrafficTable {
+    entries: HashMap<String, TypeTraffic>,
+    evicted_types: u64,
+}
+
+/// 按 ActrType 聚合的流量统计器
+#[derive(Debug)]
+pub struct TrafficStats {
+    max_tracked_types: usize,
+    half_life: Duration,
+    table: Mutex<TrafficTable>,
+}
+
+impl Default for TrafficStats {
+    fn default() -> Self {
+        Self::new(&TrafficStatsConfig::default())
+    }
+}
+
+impl TrafficStats {
+    /// 根据配置创建统计器
+    pub fn new(config: &TrafficStatsConfig) -> Self {
+        Self {
+            max_tracked_types: config.max_tracked_types.max(1),
+            half_life: Duration::from_secs(config.half_life_secs.max(1)),
+            table: Mutex::new(TrafficTable::default()),
+        }
+    }
+
+    /// 记录一条发往 `actr_type` 的 envelope
+    pub fn record(&self, actr_type: &str, bytes: usize) {
+        self.record_at(actr_type, bytes, Instant::now());
+    }
+
+    fn record_at(&self, actr_type: &str, bytes: usize, now: Instant) {
+        let mut table = self.table.lock().expect("traffic stats poisoned");
+
+        if !table.entries.contains_key(actr_type) && table.entries.len() >= self.max_tracked_types {
+            // 淘汰近期流量最低的条目，为新类型腾出空间
+            let victim = table
+                .entries
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    a.decayed_bytes_at(now, self.half_life)
+                        .total_cmp(&b.decayed_bytes_at(now, self.half_life))
+                })
+                .map(|(key, _)| key.clone());
+            if let Some(victim) = victim {
+                table.entries.remove(&victim);
+                table.evicted_types += 1;
+            }
+        }
+
+        let entry = table
+            .entries
+            .entry(actr_type.to_string())
+            .or_insert_with(|| TypeTraffic::new(now));
+        entry.decay_to(now, self.half_life);
+        entry.envelopes_total += 1;
+        entry.bytes_total += bytes as u64;
+        entry.decayed_envelopes += 1.0;
+        entry.decayed_bytes += bytes as f64;
+    }
+
+    /// 获取按近期字节速率排序的 Top-N 快照
+    pub fn snapshot(&self, top_n: usize) -> TrafficStatsSnapshot {
+        self.snapshot_at(top_n, Instant::now())
+    }
+
+    fn snapshot_at(&self, top_n: usize, now: Instant) -> TrafficStatsSnapshot {
+        let table = self.table.lock().expect("traffic stats poisoned");
+
+        // 衰减计数的稳态值 = 速率 / λ，其中 λ = ln2 / 半衰期
+        let lambda = std::f64::consts::LN_2 / self.half_life.as_secs_f64();
+
+        let mut top: Vec<TypeTrafficSnapshot> = table
+            .entries
+            .iter()
+            .map(|(key, traffic)| {
+                let factor = traffic.decay_factor(now, self.half_life);
+                TypeTrafficSnapshot {
+                    actr_type: key.clone(),
+                    envelopes_total: traffic.envelopes_total,
+                    bytes_total: traffic.bytes_total,
+                    envelopes_per_sec: traffic.decayed_envelopes * factor * lambda,
+                    bytes_per_sec: traffic.decayed_bytes * factor * lambda,
+                }
+            })
+            .collect();
+
+        top.sort_by(|a, b| {
+            b.bytes_per_sec
+                .total_cmp(&a.bytes_per_sec)
+                .then_with(|| a.actr_type.cmp(&b.actr_type))
+        });
+        top.truncate(top_n);
+
+        TrafficStatsSnapshot {
+            tracked_types: table.entries.len(),
+            evicted_types: table.evicted_types,
+            half_life_secs: self.half_life.as_secs(),
+            top,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(max_tracked_types: usize, half_life_secs: u64) -> TrafficStats {
+        TrafficStats::new(&TrafficStatsConfig {
+            enabled: true,
+            max_tracked_types,
+            half_life_secs,
+        })
+    }
+
+    #[test]
+    fn test_record_accumulates_totals() {
+        let stats = stats(16, 60);
+        let now = Instant::now();
+        stats.record_at("acme:echo", 100, now);
+        stats.record_at("acme:echo", 50, now);
+        stats.record_at("acme:chat", 10, now);
+
+        let snapshot = stats.snapshot_at(10, now);
+        assert_eq!(snapshot.tracked_types, 2);
+        assert_eq!(snapshot.top[0].actr_type, "acme:echo");
+        assert_eq!(snapshot.top[0].envelopes_total, 2);
+        assert_eq!(snapshot.top[0].bytes_total, 150);
+        assert_eq!(snapshot.top[1].actr_type, "acme:chat");
+    }
+
+    #[test]
+    fn test_rates_decay_over_time() {
+        let stats = stats(16, 10);
+        let now = Instant::now();
+        stats.record_at("acme:echo", 1000, now);
+
+        let fresh = stats.snapshot_at(1, now).top[0].bytes_per_sec;
+        let later = stats.snapshot_at(1, now + Duration::from_secs(10)).top[0].bytes_per_sec;
+
+        assert!(
+            (later - fresh / 2.0).abs() < 1e-6,
+            "one half-life should halve the rate"
+        );
+        // 累计值不受衰减影响
+        assert_eq!(
+            stats.snapshot_at(1, now + Duration::from_secs(10)).top[0].bytes_total,
+            1000
+        );
+    }
+
+    #[test]
+    fn test_eviction_keeps_heaviest_types() {
+        let stats = stats(2, 60);
+        let now = Instant::now();
+        stats.record_at("acme:heavy", 10_000, now);
+        stats.record_at("acme:light", 10, now);
+        stats.record_at("acme:new", 500, now);
+
+        let snapshot = stats.snapshot_at(10, now);
+        let types: Vec<_> = snapshot.top.iter().map(|t| t.actr_type.as_str()).collect();
+        assert_eq!(snapshot.tracked_types, 2);
+        assert_eq!(snapshot.evicted_types, 1);
+        assert_eq!(types, vec!["acme:heavy", "acme:new"]);
+    }
+
+    #[test]
+    fn test_snapshot_truncates_to_top_n() {
+        let stats = stats(16, 60);
+        let now = Instant::now();
+        for i in 0..5 {
+            stats.record_at(&format!("acme:svc{i}"), (i + 1) * 10, now);
+        }
+
+        let snapshot = stats.snapshot_at(3, now);
+        assert_eq!(snapshot.tracked_types, 5);
+        assert_eq!(snapshot.top.len(), 3);
+        assert_eq!(snapshot.top[0].actr_type, "acme:svc4");
+    }
+}
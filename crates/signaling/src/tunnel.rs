@@ -0,0 +1,217 @@
+//! ErrorResponse 隧道消息
+//!
+//! actr-protocol 没有通知、上报与扩展请求专用的 payload，这些消息统一借用 `ErrorResponse`
+//! 传输：`code` 为本模块登记的保留 code，`message` 为类型前缀加 JSON 编码的内容。
+//! 所有保留 code 与前缀集中在此登记，各功能模块通过 [`Tunnel::encode`] / [`Tunnel::decode`]
+//! 编解码，避免 code 冲突或在调用点散落魔数。
+//!
+//! | code | 消息 | 方向 |
+//! |------|------|------|
+//! | 100 | [`crate::server_notice`] 运维广播通知 | 服务器 → 客户端 |
+//! | 101 | [`crate::connection_report`] ICE 连接上报 | 客户端 → 服务器 |
+//! | 102 | [`crate::resumption`] 会话恢复 token | 服务器 → 客户端 |
+//! | 103 | [`crate::acl_update`] 运行时 ACL 更新 | 请求 / 回复 |
+//! | 104 | [`crate::presence_snapshot`] Presence 快照 | 请求 / 回复 |
+//! | 307 | [`crate::drain`] 排空重定向 | 服务器 → 客户端 |
+//! | 426 | [`crate::spec_notice`] ServiceSpec 不兼容通知 | 服务器 → 客户端 |
+//! | 503 | [`crate::load_shed`] 过载或依赖服务不可用（纯文本 message，无前缀） | 服务器 → 客户端 |
+
+use actr_protocol::ErrorResponse;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// 运维广播通知（信息性）
+pub const SERVER_NOTICE_CODE: u32 = 100;
+
+/// ICE 连接质量上报
+pub const CONNECTION_REPORT_CODE: u32 = 101;
+
+/// 会话恢复 token 通知
+pub const RESUMPTION_TOKEN_CODE: u32 = 102;
+
+/// 运行时 ACL 更新请求与回复
+pub const UPDATE_ACL_CODE: u32 = 103;
+
+/// Presence 快照请求与回复
+pub const PRESENCE_SNAPSHOT_CODE: u32 = 104;
+
+/// 节点排空时的注册重定向
+pub const DRAIN_REDIRECT_CODE: u32 = 307;
+
+/// ServiceSpec 不兼容变更通知
+pub const SPEC_INCOMPATIBILITY_NOTICE_CODE: u32 = 426;
+
+/// 过载降级或依赖服务不可用（message 为纯文本）
+pub const SERVICE_UNAVAILABLE_CODE: u32 = 503;
+
+/// 全部保留 code 及其名称（snake_case，用于日志、指标与重放保护配置）
+pub const TUNNEL_CODES: [(u32, &str); 8] = [
+    (SERVER_NOTICE_CODE, "server_notice"),
+    (CONNECTION_REPORT_CODE, "connection_report"),
+    (RESUMPTION_TOKEN_CODE, "resumption_token"),
+    (UPDATE_ACL_CODE, "update_acl"),
+    (PRESENCE_SNAPSHOT_CODE, "presence_snapshot"),
+    (DRAIN_REDIRECT_CODE, "drain_redirect"),
+    (
+        SPEC_INCOMPATIBILITY_NOTICE_CODE,
+        "spec_incompatibility_notice",
+    ),
+    (SERVICE_UNAVAILABLE_CODE, "service_unavailable"),
+];
+
+/// 保留 code 的名称，不是保留 code 时返回 None
+pub fn code_name(code: u32) -> Option<&'static str> {
+    TUNNEL_CODES
+        .iter()
+        .find(|(reserved, _)| *reserved == code)
+        .map(|(_, name)| *name)
+}
+
+/// 一种隧道消息：保留 code 与 message 前缀
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tunnel {
+    pub code: u32,
+    pub prefix: &'static str,
+}
+
+pub const SERVER_NOTICE: Tunnel = Tunnel {
+    code: SERVER_NOTICE_CODE,
+    prefix: "ServerNotice:",
+};
+
+pub const CONNECTION_REPORT: Tunnel = Tunnel {
+    code: CONNECTION_REPORT_CODE,
+    prefix: "ConnectionReport:",
+};
+
+pub const RESUMPTION_TOKEN: Tunnel = Tunnel {
+    code: RESUMPTION_TOKEN_CODE,
+    prefix: "ResumptionToken:",
+};
+
+pub const UPDATE_ACL_REQUEST: Tunnel = Tunnel {
+    code: UPDATE_ACL_CODE,
+    prefix: "UpdateAclRequest:",
+};
+
+pub const UPDATE_ACL_RESPONSE: Tunnel = Tunnel {
+    code: UPDATE_ACL_CODE,
+    prefix: "UpdateAclResponse:",
+};
+
+pub const PRESENCE_SNAPSHOT_REQUEST: Tunnel = Tunnel {
+    code: PRESENCE_SNAPSHOT_CODE,
+    prefix: "GetPresenceSnapshotRequest:",
+};
+
+pub const PRESENCE_SNAPSHOT_RESPONSE: Tunnel = Tunnel {
+    code: PRESENCE_SNAPSHOT_CODE,
+    prefix: "GetPresenceSnapshotResponse:",
+};
+
+pub const DRAIN_REDIRECT: Tunnel = Tunnel {
+    code: DRAIN_REDIRECT_CODE,
+    prefix: "DrainRedirect:",
+};
+
+pub const SPEC_INCOMPATIBILITY_NOTICE: Tunnel = Tunnel {
+    code: SPEC_INCOMPATIBILITY_NOTICE_CODE,
+    prefix: "SpecIncompatibilityNotice:",
+};
+
+/// 全部带 JSON 内容的隧道消息
+pub const TUNNELS: [Tunnel; 9] = [
+    SERVER_NOTICE,
+    CONNECTION_REPORT,
+    RESUMPTION_TOKEN,
+    UPDATE_ACL_REQUEST,
+    UPDATE_ACL_RESPONSE,
+    PRESENCE_SNAPSHOT_REQUEST,
+    PRESENCE_SNAPSHOT_RESPONSE,
+    DRAIN_REDIRECT,
+    SPEC_INCOMPATIBILITY_NOTICE,
+];
+
+impl Tunnel {
+    /// 编码为 ErrorResponse：前缀加 JSON 编码的内容
+    pub fn encode<T: Serialize>(&self, payload: &T) -> ErrorResponse {
+        ErrorResponse {
+            code: self.code,
+            message: format!(
+                "{}{}",
+                self.prefix,
+                serde_json::to_string(payload).unwrap_or_default()
+            ),
+        }
+    }
+
+    /// 从 ErrorResponse 解析
+    ///
+    /// code 或前缀不匹配时返回 None；匹配但内容无效时返回 `Some(Err)`
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        error: &ErrorResponse,
+    ) -> Option<Result<T, serde_json::Error>> {
+        if error.code != self.code {
+            return None;
+        }
+        let json = error.message.strip_prefix(self.prefix)?;
+        Some(serde_json::from_str(json))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let codes: HashSet<_> = TUNNEL_CODES.iter().map(|(code, _)| *code).collect();
+        assert_eq!(codes.len(), TUNNEL_CODES.len());
+        let names: HashSet<_> = TUNNEL_CODES.iter().map(|(_, name)| *name).collect();
+        assert_eq!(names.len(), TUNNEL_CODES.len());
+
+        // 每种消息使用已登记的 code，前缀互不相同（同一 code 的请求与回复靠前缀区分）
+        let prefixes: HashSet<_> = TUNNELS.iter().map(|tunnel| tunnel.prefix).collect();
+        assert_eq!(prefixes.len(), TUNNELS.len());
+        for tunnel in TUNNELS {
+            assert!(
+                code_name(tunnel.code).is_some(),
+                "{tunnel:?} not registered"
+            );
+            assert!(tunnel.prefix.ends_with(':'));
+        }
+        assert_eq!(code_name(0), None);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let error = DRAIN_REDIRECT.encode(&vec!["node-b"]);
+        assert_eq!(error.code, DRAIN_REDIRECT_CODE);
+        assert_eq!(error.message, r#"DrainRedirect:["node-b"]"#);
+        assert_eq!(
+            DRAIN_REDIRECT
+                .decode::<Vec<String>>(&error)
+                .unwrap()
+                .unwrap(),
+            vec!["node-b"]
+        );
+
+        // code 或前缀不匹配
+        assert!(SERVER_NOTICE.decode::<Vec<String>>(&error).is_none());
+        let response = UPDATE_ACL_RESPONSE.encode(&1);
+        assert!(UPDATE_ACL_REQUEST.decode::<u32>(&response).is_none());
+
+        // 内容无效
+        let invalid = ErrorResponse {
+            code: DRAIN_REDIRECT_CODE,
+            message: "DrainRedirect:{".to_string(),
+        };
+        assert!(
+            DRAIN_REDIRECT
+                .decode::<Vec<String>>(&invalid)
+                .unwrap()
+                .is_err()
+        );
+    }
+}
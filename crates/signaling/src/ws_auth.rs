@@ -0,0 +1,346 @@
+//! WebSocket 连接认证
+//!
+//! 处理重连场景下随 WebSocket 升级请求携带的身份凭证：
+//! - 优先从 `Authorization: Bearer <base64 token>` 请求头读取 token，避免泄漏到访问日志/代理
+//! - 兼容旧版 URL 查询参数 `token`（可通过 `allow_query_token = false` 禁用）
+//! - WSS 连接上可选校验 TLS 通道绑定证明 (`X-Actr-Channel-Binding`)
+//!
+//! 未在升级请求中携带凭证的连接，会在收到第一条通过认证的 ActrToSignaling 消息时绑定身份。
+
+use actr_protocol::{AIdCredential, ActrId, ActrIdExt};
+use actrix_common::aid::credential::validator::AIdCredentialValidator;
+use actrix_common::config::signaling::WsAuthConfig;
+use actrix_common::util::TlsChannelBinding;
+use axum::http::{HeaderMap, StatusCode, header::AUTHORIZATION};
+use base64::Engine as _;
+use std::collections::HashMap;
+use thiserror::Error;
+use tracing::warn;
+
+/// 携带 ActorId 的请求头（替代查询参数 `actor_id`）
+pub const ACTR_ID_HEADER: &str = "x-actr-id";
+
+/// 携带 token key_id 的请求头（替代查询参数 `token_key_id`）
+pub const TOKEN_KEY_ID_HEADER: &str = "x-actr-token-key-id";
+
+/// TLS 通道绑定证明请求头：`base64(HMAC-SHA256(psk, tls-exporter))`
+pub const CHANNEL_BINDING_HEADER: &str = "x-actr-channel-binding";
+
+/// WebSocket 连接认证错误
+#[derive(Debug, Error)]
+pub enum WsAuthError {
+    #[error("Invalid actor_id: {0}")]
+    InvalidActorId(String),
+
+    #[error("Invalid token encoding")]
+    InvalidToken,
+
+    #[error("Invalid token_key_id: {0}")]
+    InvalidKeyId(String),
+
+    #[error("Passing token in query string is disabled, use the Authorization header")]
+    QueryTokenDisabled,
+
+    #[error("Invalid channel binding proof encoding")]
+    InvalidChannelBindingEncoding,
+
+    #[error("Credential validation failed: {0}")]
+    CredentialRejected(String),
+
+    #[error("Credential is not issued for actor {0}")]
+    IdentityMismatch(String),
+
+    #[error("TLS channel binding proof is required")]
+    ChannelBindingRequired,
+
+    #[error("TLS channel binding proof mismatch")]
+    ChannelBindingMismatch,
+}
+
+impl WsAuthError {
+    /// 对应的 HTTP 状态码
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            WsAuthError::InvalidActorId(_)
+            | WsAuthError::InvalidToken
+            | WsAuthError::InvalidKeyId(_)
+            | WsAuthError::InvalidChannelBindingEncoding => StatusCode::BAD_REQUEST,
+            WsAuthError::QueryTokenDisabled
+            | WsAuthError::CredentialRejected(_)
+            | WsAuthError::IdentityMismatch(_)
+            | WsAuthError::ChannelBindingRequired
+            | WsAuthError::ChannelBindingMismatch => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// 升级请求中携带的身份
+#[derive(Debug, Clone)]
+pub struct ConnectIdentity {
+    pub actor_id: ActrId,
+    pub credential: AIdCredential,
+    /// 客户端提交的 TLS 通道绑定证明
+    pub channel_binding_proof: Option<Vec<u8>>,
+}
+
+/// 读取请求头，不存在或非 UTF-8 时返回 None
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// 从 `Authorization: Bearer <token>` 中取出 token
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|t| !t.is_empty())
+}
+
+/// 从升级请求（请求头优先，其次查询参数）提取身份
+///
+/// 未携带 actor_id 或 token 时返回 `Ok(None)`，连接将通过后续 envelope 认证。
+pub fn extract_identity(
+    headers: &HeaderMap,
+    params: &HashMap<String, String>,
+    config: &WsAuthConfig,
+) -> Result<Option<ConnectIdentity>, WsAuthError> {
+    // token：Authorization 头优先
+    let token_b64 = match bearer_token(headers) {
+        Some(token) => Some(token),
+        None => match params.get("token") {
+            Some(_) if !config.allow_query_token => return Err(WsAuthError::QueryTokenDisabled),
+            Some(token) => {
+                warn!("⚠️ 通过 URL 参数传递 token 已不推荐，请改用 Authorization 请求头");
+                Some(token.as_str())
+            }
+            None => None,
+        },
+    };
+
+    let actor_str =
+        header_str(headers, ACTR_ID_HEADER).or(params.get("actor_id").map(String::as_str));
+
+    let (actor_str, token_b64) = match (actor_str, token_b64) {
+        (Some(actor_str), Some(token_b64)) => (actor_str, token_b64),
+        (Some(_), None) => {
+            warn!("⚠️ 提供了 actor_id 但缺少 token");
+            return Ok(None);
+        }
+        (None, Some(_)) => {
+            warn!("⚠️ 提供了 token 但缺少 actor_id");
+            return Ok(None);
+        }
+        (None, None) => return Ok(None),
+    };
+
+    let actor_id = ActrId::from_string_repr(actor_str)
+        .map_err(|e| WsAuthError::InvalidActorId(format!("'{actor_str}': {e}")))?;
+
+    let token_bytes = base64::engine::general_purpose::STANDARD
+        .decode(token_b64)
+        .map_err(|_| WsAuthError::InvalidToken)?;
+
+    // 默认 key_id = 0
+    let token_key_id = match header_str(headers, TOKEN_KEY_ID_HEADER)
+        .or(params.get("token_key_id").map(String::as_str))
+    {
+        Some(s) => s
+            .parse::<u32>()
+            .map_err(|_| WsAuthError::InvalidKeyId(s.to_string()))?,
+        None => 0,
+    };
+
+    let channel_binding_proof = header_str(headers, CHANNEL_BINDING_HEADER)
+        .map(|proof| {
+            base64::engine::general_purpose::STANDARD
+                .decode(proof)
+                .map_err(|_| WsAuthError::InvalidChannelBindingEncoding)
+        })
+        .transpose()?;
+
+    Ok(Some(ConnectIdentity {
+        actor_id,
+        credential: AIdCredential {
+            encrypted_token: token_bytes.into(),
+            token_key_id,
+        },
+        channel_binding_proof,
+    }))
+}
+
+/// 校验升级请求携带的身份
+///
+/// - credential 必须有效，且签发对象与声明的 actor_id 一致
+/// - 在 TLS 连接上提交了通道绑定证明时必须校验通过；
+///   `require_channel_binding` 启用时 TLS 连接必须提交证明
+pub async fn authenticate(
+    identity: &ConnectIdentity,
+    channel_binding: Option<&TlsChannelBinding>,
+    config: &WsAuthConfig,
+) -> Result<(), WsAuthError> {
+    let (claims, _in_tolerance) =
+        AIdCredentialValidator::check(&identity.credential, identity.actor_id.realm.realm_id)
+            .await
+            .map_err(|e| WsAuthError::CredentialRejected(e.to_string()))?;
+
+    let actor_repr = identity.actor_id.to_string_repr();
+    if claims.actor_id != actor_repr {
+        return Err(WsAuthError::IdentityMismatch(actor_repr));
+    }
+
+    verify_channel_binding(
+        channel_binding,
+        identity.channel_binding_proof.as_deref(),
+        &claims.psk,
+        config,
+    )
+}
+
+/// 校验 TLS 通道绑定证明
+fn verify_channel_binding(
+    channel_binding: Option<&TlsChannelBinding>,
+    proof: Option<&[u8]>,
+    psk: &[u8],
+    config: &WsAuthConfig,
+) -> Result<(), WsAuthError> {
+    match (channel_binding, proof) {
+        (Some(binding), Some(proof)) => {
+            if binding.verify(psk, proof) {
+                Ok(())
+            } else {
+                Err(WsAuthError::ChannelBindingMismatch)
+            }
+        }
+        (Some(_), None) if config.require_channel_binding => {
+            Err(WsAuthError::ChannelBindingRequired)
+        }
+        (None, Some(_)) => {
+            // 明文连接或 TLS 在前置代理终止，无法校验
+            warn!("⚠️ 非 TLS 连接提交了通道绑定证明，已忽略");
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::util::channel_binding::TLS_EXPORTER_LEN;
+    use axum::http::HeaderValue;
+
+    const ACTOR: &str = "fed02d3f000000@12345/apple:user:1";
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer dG9rZW4="));
+        headers.insert(ACTR_ID_HEADER, HeaderValue::from_static(ACTOR));
+        headers.insert(TOKEN_KEY_ID_HEADER, HeaderValue::from_static("7"));
+
+        let identity = extract_identity(&headers, &HashMap::new(), &WsAuthConfig::default())
+            .unwrap()
+            .expect("identity should be present");
+        assert_eq!(identity.credential.encrypted_token.as_ref(), b"token");
+        assert_eq!(identity.credential.token_key_id, 7);
+        assert!(identity.channel_binding_proof.is_none());
+    }
+
+    #[test]
+    fn test_header_token_takes_precedence_over_query() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("bearer dG9rZW4="));
+        let params = params(&[("actor_id", ACTOR), ("token", "b3RoZXI=")]);
+
+        let identity = extract_identity(&headers, &params, &WsAuthConfig::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.credential.encrypted_token.as_ref(), b"token");
+    }
+
+    #[test]
+    fn test_query_token_can_be_disabled() {
+        let params = params(&[("actor_id", ACTOR), ("token", "dG9rZW4=")]);
+        let config = WsAuthConfig {
+            allow_query_token: false,
+            ..Default::default()
+        };
+
+        assert!(
+            extract_identity(&HeaderMap::new(), &params, &WsAuthConfig::default())
+                .unwrap()
+                .is_some()
+        );
+        assert!(matches!(
+            extract_identity(&HeaderMap::new(), &params, &config),
+            Err(WsAuthError::QueryTokenDisabled)
+        ));
+    }
+
+    #[test]
+    fn test_missing_parts_fall_back_to_envelope_auth() {
+        let config = WsAuthConfig::default();
+        assert!(
+            extract_identity(&HeaderMap::new(), &HashMap::new(), &config)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            extract_identity(&HeaderMap::new(), &params(&[("actor_id", ACTOR)]), &config)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_invalid_encodings_are_rejected() {
+        let config = WsAuthConfig::default();
+        let bad_token = params(&[("actor_id", ACTOR), ("token", "***")]);
+        assert!(matches!(
+            extract_identity(&HeaderMap::new(), &bad_token, &config),
+            Err(WsAuthError::InvalidToken)
+        ));
+
+        let bad_key_id = params(&[
+            ("actor_id", ACTOR),
+            ("token", "dG9rZW4="),
+            ("token_key_id", "x"),
+        ]);
+        let err = extract_identity(&HeaderMap::new(), &bad_key_id, &config).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_verify_channel_binding() {
+        let binding = TlsChannelBinding::new([1u8; TLS_EXPORTER_LEN]);
+        let psk = b"psk";
+        let proof = binding.proof(psk);
+        let lenient = WsAuthConfig::default();
+        let strict = WsAuthConfig {
+            require_channel_binding: true,
+            ..Default::default()
+        };
+
+        assert!(verify_channel_binding(Some(&binding), Some(&proof), psk, &strict).is_ok());
+        assert!(matches!(
+            verify_channel_binding(Some(&binding), Some(b"forged"), psk, &lenient),
+            Err(WsAuthError::ChannelBindingMismatch)
+        ));
+        assert!(verify_channel_binding(Some(&binding), None, psk, &lenient).is_ok());
+        assert!(matches!(
+            verify_channel_binding(Some(&binding), None, psk, &strict),
+            Err(WsAuthError::ChannelBindingRequired)
+        ));
+        // 明文连接无法校验，证明被忽略
+        assert!(verify_channel_binding(None, Some(&proof), psk, &strict).is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+//! STUN 报文缓冲分配基准
+//!
+//! 对比每个报文 `to_vec()` 复制与 [`BufferPool`] 复用两种方式的分配次数与耗时。
+//! 模拟接收循环：报文被移交给处理任务，最多 `IN_FLIGHT` 个报文同时在处理中。
+//!
+//! 运行：`cargo bench -p stun --bench buffer_pool`
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use stun::buffer_pool::{BufferPool, DEFAULT_BUFFER_SIZE, DEFAULT_POOL_CAPACITY};
+
+/// 统计分配次数的全局分配器
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const PACKETS: usize = 1_000_000;
+const IN_FLIGHT: usize = 64;
+/// 典型 STUN Binding Request 大小
+const PACKET_LEN: usize = 20;
+
+/// 运行 `f` 并返回（耗时，分配次数）
+fn measure(f: impl FnOnce()) -> (Duration, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    f();
+    (
+        start.elapsed(),
+        ALLOCATIONS.load(Ordering::Relaxed) - before,
+    )
+}
+
+fn report(name: &str, (elapsed, allocations): (Duration, usize)) {
+    println!(
+        "{name:<10} {:>8.1} ns/packet {:>6.3} allocations/packet",
+        elapsed.as_nanos() as f64 / PACKETS as f64,
+        allocations as f64 / PACKETS as f64
+    );
+}
+
+fn main() {
+    let mut in_flight = Vec::with_capacity(IN_FLIGHT);
+
+    let mut recv_buffer = vec![0u8; DEFAULT_BUFFER_SIZE];
+    let copy = measure(|| {
+        for i in 0..PACKETS {
+            recv_buffer[0] = i as u8;
+            in_flight.push(black_box(recv_buffer[..PACKET_LEN].to_vec()));
+            if in_flight.len() == IN_FLIGHT {
+                in_flight.clear();
+            }
+        }
+        in_flight.clear();
+    });
+
+    let mut in_flight = Vec::with_capacity(IN_FLIGHT);
+    let pool = BufferPool::new(DEFAULT_POOL_CAPACITY, DEFAULT_BUFFER_SIZE);
+    let pooled = measure(|| {
+        for i in 0..PACKETS {
+            let mut buffer = pool.get();
+            buffer[0] = i as u8;
+            buffer.truncate(PACKET_LEN);
+            in_flight.push(black_box(buffer));
+            if in_flight.len() == IN_FLIGHT {
+                in_flight.clear();
+            }
+        }
+        in_flight.clear();
+    });
+
+    println!("{PACKETS} packets, {IN_FLIGHT} in flight");
+    report("to_vec", copy);
+    report("pool", pooled);
+}
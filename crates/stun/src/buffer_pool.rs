@@ -0,0 +1,128 @@
+//! UDP 报文缓冲池
+//!
+//! 接收循环从池中取出缓冲区接收报文，并把整个缓冲区移交给处理任务；
+//! 处理完成后 [`PooledBuffer`] 在 drop 时自动归还，稳定负载下不再为每个报文分配内存。
+//! 空闲缓冲区保存在无锁队列中，接收循环与处理任务之间没有锁竞争。
+
+use crossbeam_queue::ArrayQueue;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// 单个缓冲区大小（标准以太网 MTU）
+pub const DEFAULT_BUFFER_SIZE: usize = 1500;
+
+/// 池中最多保留的空闲缓冲区数量
+pub const DEFAULT_POOL_CAPACITY: usize = 1024;
+
+/// 固定大小的报文缓冲池
+#[derive(Debug)]
+pub struct BufferPool {
+    free: ArrayQueue<Vec<u8>>,
+    buffer_size: usize,
+}
+
+impl BufferPool {
+    /// 创建缓冲池，最多保留 `capacity` 个大小为 `buffer_size` 的空闲缓冲区
+    pub fn new(capacity: usize, buffer_size: usize) -> Arc<Self> {
+        Arc::new(Self {
+            free: ArrayQueue::new(capacity.max(1)),
+            buffer_size,
+        })
+    }
+
+    /// 取出一个缓冲区，池为空时新分配
+    ///
+    /// 返回的缓冲区长度为 `buffer_size`，可直接作为接收缓冲区使用
+    pub fn get(self: &Arc<Self>) -> PooledBuffer {
+        let data = self
+            .free
+            .pop()
+            .unwrap_or_else(|| vec![0u8; self.buffer_size]);
+        PooledBuffer {
+            len: data.len(),
+            data,
+            pool: Arc::clone(self),
+        }
+    }
+
+    /// 当前空闲缓冲区数量
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// 单个缓冲区大小
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+}
+
+/// 从 [`BufferPool`] 借出的缓冲区，drop 时归还
+///
+/// 解引用得到前 `len` 个字节；接收报文后用 [`PooledBuffer::truncate`] 设置有效长度
+#[derive(Debug)]
+pub struct PooledBuffer {
+    data: Vec<u8>,
+    len: usize,
+    pool: Arc<BufferPool>,
+}
+
+impl PooledBuffer {
+    /// 设置有效数据长度（不超过缓冲区大小）
+    pub fn truncate(&mut self, len: usize) {
+        self.len = len.min(self.data.len());
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.data[..self.len]
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        // 池已满时直接释放
+        let data = std::mem::take(&mut self.data);
+        let _ = self.pool.free.push(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffers_are_reused() {
+        let pool = BufferPool::new(2, 64);
+        let mut buffer = pool.get();
+        assert_eq!(buffer.len(), 64);
+        buffer[0] = 0xAB;
+        buffer.truncate(10);
+        assert_eq!(buffer.len(), 10);
+        let ptr = buffer.as_ptr();
+        drop(buffer);
+        assert_eq!(pool.available(), 1);
+
+        // 归还的缓冲区恢复完整长度并被再次借出
+        let buffer = pool.get();
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(buffer.len(), 64);
+        assert_eq!(pool.available(), 0);
+    }
+
+    #[test]
+    fn test_pool_capacity_is_bounded() {
+        let pool = BufferPool::new(2, 64);
+        let buffers: Vec<_> = (0..4).map(|_| pool.get()).collect();
+        drop(buffers);
+        assert_eq!(pool.available(), 2);
+    }
+}
@@ -2,11 +2,15 @@
 //!
 //! 提供 STUN 协议服务器功能，用于 NAT 发现和网络穿越
 
+pub mod buffer_pool;
 pub mod error;
 
+pub use buffer_pool::{BufferPool, PooledBuffer};
 // Re-export error types for convenience
 pub use error::{ErrorSeverity, Result, StunError};
 
+use actrix_common::metrics::STUN_REQUESTS;
+use actrix_common::util::NetworkEmulator;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::UdpSocket;
@@ -16,33 +20,56 @@ use webrtc_stun::xoraddr::XorMappedAddress;
 
 /// Create and run a STUN server with graceful shutdown support
 pub async fn create_stun_server_with_shutdown(
+    socket: Arc<UdpSocket>,
+    shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) -> Result<()> {
+    create_stun_server_with_emulation(socket, shutdown_rx, None).await
+}
+
+/// Create and run a STUN server, optionally delaying/dropping responses
+/// through a [`NetworkEmulator`] (development only)
+pub async fn create_stun_server_with_emulation(
     socket: Arc<UdpSocket>,
     mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    emulator: Option<NetworkEmulator>,
 ) -> Result<()> {
     info!(
         "Starting STUN server with shutdown support on {}",
         socket.local_addr()?
     );
 
-    let mut buffer = vec![0u8; 1500]; // Standard MTU size for UDP packets
+    // Buffers are handed to the spawned handlers and return to the pool when dropped
+    let pool = BufferPool::new(
+        buffer_pool::DEFAULT_POOL_CAPACITY,
+        buffer_pool::DEFAULT_BUFFER_SIZE,
+    );
 
     loop {
+        let mut buffer = pool.get();
         tokio::select! {
             // Handle incoming UDP packets
             result = socket.recv_from(&mut buffer) => {
                 match result {
                     Ok((len, src_addr)) => {
-                        let packet_data = &buffer[..len];
+                        buffer.truncate(len);
 
                         // Check if this might be a STUN message before processing
-                        if is_stun_message(packet_data) {
+                        if is_stun_message(&buffer) {
                             debug!("Received potential STUN packet from {} ({} bytes)", src_addr, len);
 
                             // Process the packet in the background to avoid blocking the receive loop
                             let socket_clone = socket.clone();
-                            let packet_data = packet_data.to_vec();
+                            let packet_data = buffer;
+                            let emulator = emulator.clone();
 
                             tokio::spawn(async move {
+                                if let Some(emulator) = &emulator
+                                    && !emulator.impair().await
+                                {
+                                    debug!("Network emulation dropped STUN packet from {}", src_addr);
+                                    STUN_REQUESTS.with_label_values(&["dropped"]).inc();
+                                    return;
+                                }
                                 if let Err(e) = process_packet(socket_clone, &packet_data, src_addr).await {
                                     error!("Failed to process STUN packet from {}: {}", src_addr, e);
                                 }
@@ -94,15 +121,22 @@ pub async fn process_packet(socket: Arc<UdpSocket>, data: &[u8], src: SocketAddr
             e,
             data.len()
         );
+        STUN_REQUESTS.with_label_values(&["malformed"]).inc();
         return Ok(());
     }
 
     if msg.typ == BINDING_REQUEST {
-        if let Err(e) = handle_binding_request(&socket, &msg, src).await {
-            error!("Failed to handle STUN binding request from {}: {}", src, e);
-            // Even if handling fails, we don't want to kill the server loop, so return Ok.
-        }
+        let result = match handle_binding_request(&socket, &msg, src).await {
+            Ok(()) => "binding_success",
+            Err(e) => {
+                error!("Failed to handle STUN binding request from {}: {}", src, e);
+                // Even if handling fails, we don't want to kill the server loop, so return Ok.
+                "binding_error"
+            }
+        };
+        STUN_REQUESTS.with_label_values(&[result]).inc();
     } else {
+        STUN_REQUESTS.with_label_values(&["non_binding"]).inc();
         debug!(
             "Received non-binding STUN message type {:?} from {}",
             msg.typ, src
@@ -125,8 +159,9 @@ async fn handle_binding_request(
     response_msg.transaction_id = request.transaction_id;
 
     // Add XOR-MAPPED-ADDRESS attribute
+    // IPv4 clients on a dual-stack socket appear as ::ffff:a.b.c.d; report the IPv4 address
     let xor_addr = XorMappedAddress {
-        ip: src.ip(),
+        ip: src.ip().to_canonical(),
         port: src.port(),
     };
 
@@ -1,9 +1,10 @@
 //! Minimal SupervisorService gRPC server for testing supervisord registration/report flows.
 //!
 //! This binary boots a simple SupervisorService implementation that accepts
-//! RegisterNode, Report, and HealthCheck calls. It verifies nonce-auth
+//! RegisterNode, Report, HealthCheck and WatchDirectives calls. It verifies nonce-auth
 //! credentials with a shared secret, records basic node state in memory, and
-//! responds with fixed intervals to drive client-side scheduling.
+//! responds with fixed intervals to drive client-side scheduling. Directive
+//! streams are held open without pushing anything.
 //!
 //! # Usage
 //!
@@ -21,6 +22,7 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -30,13 +32,14 @@ use clap::Parser;
 use nonce_auth::{CredentialVerifier, NonceError, storage::NonceStorage};
 use sha2::{Digest, Sha256};
 use tokio::sync::RwLock;
+use tokio_stream::Stream;
 use tonic::{Request, Response, Status, transport::Server};
 use tracing::{debug, info};
 
 use supervit::{
-    HealthCheckRequest, HealthCheckResponse, NonceCredential, RegisterNodeRequest,
+    Directive, HealthCheckRequest, HealthCheckResponse, NonceCredential, RegisterNodeRequest,
     RegisterNodeResponse, ReportRequest, ReportResponse, SupervisorService,
-    SupervisorServiceServer,
+    SupervisorServiceServer, WatchDirectivesRequest,
 };
 
 /// Default shared secret for testing (hex encoded, 32 bytes)
@@ -397,6 +400,27 @@ impl SupervisorService for SupervisorServiceImpl {
 
         Ok(Response::new(response))
     }
+
+    type WatchDirectivesStream =
+        Pin<Box<dyn Stream<Item = Result<Directive, Status>> + Send + 'static>>;
+
+    async fn watch_directives(
+        &self,
+        request: Request<WatchDirectivesRequest>,
+    ) -> Result<Response<Self::WatchDirectivesStream>, Status> {
+        let req = request.into_inner();
+        let payload = format!("watch_directives:{}", req.node_id);
+
+        self.verify_credential(&req.credential, payload).await?;
+
+        info!(
+            node_id = %req.node_id,
+            last_sequence = ?req.last_sequence,
+            "directive stream opened"
+        );
+
+        Ok(Response::new(Box::pin(tokio_stream::pending())))
+    }
 }
 
 fn default_data_dir() -> PathBuf {
@@ -25,13 +25,14 @@
 //!
 //! # Note
 //!
-//! This example uses SqliteNonceStorage for nonce storage.
+//! This example uses the default (SQLite) nonce storage backend.
 //! A temporary database is created in the system temp directory.
 
 use std::sync::Arc;
 
 use actrix_common::ServiceCollector;
-use actrix_common::storage::{SqliteNonceStorage, db::set_db_path};
+use actrix_common::config::NonceStorageConfig;
+use actrix_common::storage::{NonceStore, db::set_db_path};
 use tonic::transport::Server;
 use tracing::{Level, info};
 use tracing_subscriber::FmtSubscriber;
@@ -60,7 +61,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Main database initialized");
 
     // Create nonce storage
-    let nonce_storage = Arc::new(SqliteNonceStorage::new_async(&temp_dir).await?);
+    let nonce_storage =
+        Arc::new(NonceStore::from_config(&NonceStorageConfig::default(), &temp_dir).await?);
     info!("Nonce storage initialized");
 
     // Create empty service collector for demo
@@ -1,8 +1,13 @@
 use actrix_proto::{
-    CreateRealmRequest, CreateRealmResponse, DeleteRealmRequest, DeleteRealmResponse,
-    GetConfigRequest, GetConfigResponse, GetNodeInfoRequest, GetNodeInfoResponse, GetRealmRequest,
-    GetRealmResponse, ListRealmsRequest, ListRealmsResponse, NonceCredential, ShutdownRequest,
-    ShutdownResponse, SupervisedService, UpdateConfigRequest, UpdateConfigResponse,
+    BroadcastServerNoticeRequest, BroadcastServerNoticeResponse, CreateRealmApiKeyRequest,
+    CreateRealmApiKeyResponse, CreateRealmRequest, CreateRealmResponse, DeleteRealmRequest,
+    DeleteRealmResponse, DisconnectActorRequest, DisconnectActorResponse, GetConfigRequest,
+    GetConfigResponse, GetNodeInfoRequest, GetNodeInfoResponse, GetRealmRequest, GetRealmResponse,
+    GetServiceSpecHistoryRequest, GetServiceSpecHistoryResponse, ListConnectionsRequest,
+    ListConnectionsResponse, ListRealmApiKeysRequest, ListRealmApiKeysResponse, ListRealmsRequest,
+    ListRealmsResponse, NonceCredential, RevokeRealmApiKeyRequest, RevokeRealmApiKeyResponse,
+    SetServiceEnabledRequest, SetServiceEnabledResponse, ShutdownRequest, ShutdownResponse,
+    StreamLogsRequest, SupervisedService, UpdateConfigRequest, UpdateConfigResponse,
     UpdateRealmRequest, UpdateRealmResponse,
 };
 use nonce_auth::{CredentialVerifier, NonceError, storage::NonceStorage};
@@ -143,6 +148,30 @@ where
         self.inner.list_realms(request).await
     }
 
+    async fn create_realm_api_key(
+        &self,
+        request: Request<CreateRealmApiKeyRequest>,
+    ) -> Result<Response<CreateRealmApiKeyResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.create_realm_api_key(request).await
+    }
+
+    async fn list_realm_api_keys(
+        &self,
+        request: Request<ListRealmApiKeysRequest>,
+    ) -> Result<Response<ListRealmApiKeysResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.list_realm_api_keys(request).await
+    }
+
+    async fn revoke_realm_api_key(
+        &self,
+        request: Request<RevokeRealmApiKeyRequest>,
+    ) -> Result<Response<RevokeRealmApiKeyResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.revoke_realm_api_key(request).await
+    }
+
     async fn get_node_info(
         &self,
         request: Request<GetNodeInfoRequest>,
@@ -158,6 +187,56 @@ where
         self.verify_body(request.get_ref()).await?;
         self.inner.shutdown(request).await
     }
+
+    async fn set_service_enabled(
+        &self,
+        request: Request<SetServiceEnabledRequest>,
+    ) -> Result<Response<SetServiceEnabledResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.set_service_enabled(request).await
+    }
+
+    async fn list_connections(
+        &self,
+        request: Request<ListConnectionsRequest>,
+    ) -> Result<Response<ListConnectionsResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.list_connections(request).await
+    }
+
+    async fn disconnect_actor(
+        &self,
+        request: Request<DisconnectActorRequest>,
+    ) -> Result<Response<DisconnectActorResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.disconnect_actor(request).await
+    }
+
+    async fn broadcast_server_notice(
+        &self,
+        request: Request<BroadcastServerNoticeRequest>,
+    ) -> Result<Response<BroadcastServerNoticeResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.broadcast_server_notice(request).await
+    }
+
+    async fn get_service_spec_history(
+        &self,
+        request: Request<GetServiceSpecHistoryRequest>,
+    ) -> Result<Response<GetServiceSpecHistoryResponse>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.get_service_spec_history(request).await
+    }
+
+    type StreamLogsStream = S::StreamLogsStream;
+
+    async fn stream_logs(
+        &self,
+        request: Request<StreamLogsRequest>,
+    ) -> Result<Response<Self::StreamLogsStream>, Status> {
+        self.verify_body(request.get_ref()).await?;
+        self.inner.stream_logs(request).await
+    }
 }
 
 // ========= 请求类型的载荷构造实现 =========
@@ -188,13 +267,23 @@ impl CredentialPayload for GetConfigRequest {
     }
 }
 
+/// Dry-run requests sign a distinct payload so they cannot be replayed as real mutations
+fn dry_run_payload(payload: String, dry_run: Option<bool>) -> String {
+    if dry_run.unwrap_or(false) {
+        format!("{payload}:dry_run")
+    } else {
+        payload
+    }
+}
+
 impl CredentialPayload for CreateRealmRequest {
     fn credential(&self) -> &NonceCredential {
         &self.credential
     }
 
     fn auth_payload(&self, node_id: &str) -> String {
-        format!("create_realm:{node_id}:{}", self.realm_id)
+        let payload = format!("create_realm:{node_id}:{}", self.realm_id);
+        dry_run_payload(payload, self.dry_run)
     }
 }
 
@@ -214,7 +303,8 @@ impl CredentialPayload for UpdateRealmRequest {
     }
 
     fn auth_payload(&self, node_id: &str) -> String {
-        format!("update_realm:{node_id}:{}", self.realm_id)
+        let payload = format!("update_realm:{node_id}:{}", self.realm_id);
+        dry_run_payload(payload, self.dry_run)
     }
 }
 
@@ -224,7 +314,8 @@ impl CredentialPayload for DeleteRealmRequest {
     }
 
     fn auth_payload(&self, node_id: &str) -> String {
-        format!("delete_realm:{node_id}:{}", self.realm_id)
+        let payload = format!("delete_realm:{node_id}:{}", self.realm_id);
+        dry_run_payload(payload, self.dry_run)
     }
 }
 
@@ -238,6 +329,39 @@ impl CredentialPayload for ListRealmsRequest {
     }
 }
 
+impl CredentialPayload for CreateRealmApiKeyRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!("create_realm_api_key:{node_id}:{}", self.realm_id)
+    }
+}
+
+impl CredentialPayload for ListRealmApiKeysRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!("list_realm_api_keys:{node_id}:{}", self.realm_id)
+    }
+}
+
+impl CredentialPayload for RevokeRealmApiKeyRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!(
+            "revoke_realm_api_key:{node_id}:{}:{}",
+            self.realm_id, self.key_id
+        )
+    }
+}
+
 impl CredentialPayload for GetNodeInfoRequest {
     fn credential(&self) -> &NonceCredential {
         &self.credential
@@ -258,6 +382,75 @@ impl CredentialPayload for ShutdownRequest {
     }
 }
 
+impl CredentialPayload for SetServiceEnabledRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!(
+            "set_service_enabled:{node_id}:{}:{}",
+            self.service, self.enabled
+        )
+    }
+}
+
+impl CredentialPayload for ListConnectionsRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        match self.realm_id {
+            Some(realm_id) => format!("list_connections:{node_id}:{realm_id}"),
+            None => format!("list_connections:{node_id}"),
+        }
+    }
+}
+
+impl CredentialPayload for DisconnectActorRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!("disconnect_actor:{node_id}:{}", self.actor_id)
+    }
+}
+
+impl CredentialPayload for BroadcastServerNoticeRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!(
+            "broadcast_server_notice:{node_id}:{}:{}",
+            self.severity, self.category
+        )
+    }
+}
+
+impl CredentialPayload for GetServiceSpecHistoryRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!("get_service_spec_history:{node_id}:{}", self.service_name)
+    }
+}
+
+impl CredentialPayload for StreamLogsRequest {
+    fn credential(&self) -> &NonceCredential {
+        &self.credential
+    }
+
+    fn auth_payload(&self, node_id: &str) -> String {
+        format!("stream_logs:{node_id}")
+    }
+}
+
 fn map_nonce_error(err: NonceError, context: &str) -> Status {
     match err {
         NonceError::DuplicateNonce => {
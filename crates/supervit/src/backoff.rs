@@ -0,0 +1,171 @@
+//! Supervisor 连接重连退避与离线上报缓冲
+//!
+//! - [`Backoff`]：带抖动的指数退避，避免大量节点在 Supervisor 恢复后同时重连
+//! - [`ReportBuffer`]：Supervisor 不可达期间暂存状态报告，重连后按时间顺序补发，
+//!   超出容量时丢弃最早的报告
+
+use crate::ReportRequest;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tracing::warn;
+
+/// 首次重连等待时间
+pub const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// 默认最大重连等待时间
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// 带抖动的指数退避
+///
+/// 每次失败后基准等待时间翻倍（不超过上限），实际等待时间在基准的 50%~100% 之间随机
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(INITIAL_RETRY_DELAY, MAX_RETRY_DELAY)
+    }
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        let max = max.max(initial);
+        Self {
+            initial,
+            max,
+            current: initial,
+        }
+    }
+
+    /// 返回本次等待时间，并将基准翻倍
+    pub fn next_delay(&mut self) -> Duration {
+        let base = self.current;
+        self.current = (self.current * 2).min(self.max);
+        base.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+
+    /// 连接成功后重置
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+/// 离线状态报告缓冲
+#[derive(Debug)]
+pub struct ReportBuffer {
+    capacity: usize,
+    reports: VecDeque<ReportRequest>,
+    dropped: u64,
+}
+
+impl ReportBuffer {
+    /// 创建最多保存 `capacity` 条报告的缓冲，0 表示不缓冲
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            reports: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// 暂存一条报告，超出容量时丢弃最早的报告
+    pub fn push(&mut self, report: ReportRequest) {
+        if self.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.reports.len() >= self.capacity {
+            self.reports.pop_front();
+            self.dropped += 1;
+            warn!(
+                "Offline report buffer full ({}), dropped oldest report ({} dropped in total)",
+                self.capacity, self.dropped
+            );
+        }
+        self.reports.push_back(report);
+    }
+
+    /// 取出最早的报告
+    pub fn pop(&mut self) -> Option<ReportRequest> {
+        self.reports.pop_front()
+    }
+
+    /// 补发失败时放回最早的位置
+    pub fn push_front(&mut self, report: ReportRequest) {
+        if self.reports.len() >= self.capacity {
+            self.dropped += 1;
+            return;
+        }
+        self.reports.push_front(report);
+    }
+
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// 因容量不足丢弃的报告总数
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(timestamp: i64) -> ReportRequest {
+        ReportRequest {
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_is_capped_and_jittered() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(8));
+        let delays: Vec<Duration> = (0..6).map(|_| backoff.next_delay()).collect();
+
+        for (delay, base) in delays.iter().zip([1, 2, 4, 8, 8, 8]) {
+            let base = Duration::from_secs(base);
+            assert!(
+                *delay >= base / 2 && *delay <= base,
+                "{delay:?} vs {base:?}"
+            );
+        }
+
+        backoff.reset();
+        assert!(backoff.next_delay() <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_report_buffer_drops_oldest() {
+        let mut buffer = ReportBuffer::new(2);
+        buffer.push(report(1));
+        buffer.push(report(2));
+        buffer.push(report(3));
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.dropped(), 1);
+
+        let first = buffer.pop().unwrap();
+        assert_eq!(first.timestamp, 2);
+        // 补发失败后放回，仍然最先发送
+        buffer.push_front(first);
+        assert_eq!(buffer.pop().unwrap().timestamp, 2);
+        assert_eq!(buffer.pop().unwrap().timestamp, 3);
+        assert!(buffer.is_empty());
+
+        let mut disabled = ReportBuffer::new(0);
+        disabled.push(report(1));
+        assert!(disabled.is_empty());
+        assert_eq!(disabled.dropped(), 1);
+    }
+}
@@ -1,30 +1,62 @@
 //! gRPC client for supervisor communication
 
+use crate::backoff::{Backoff, INITIAL_RETRY_DELAY, ReportBuffer};
 use crate::config::SupervitConfig;
+use crate::directive::{DirectiveHandler, DirectiveState};
 use crate::error::{Result, SupervitError};
-use crate::metrics::collect_system_metrics;
+use crate::failover::EndpointSet;
+use crate::metrics::MetricsCollector;
 use crate::nonce_auth::generate_credential;
-use crate::realm::get_max_realm_version;
+use crate::realm::{get_max_realm_version, lifecycle_event_to_proto};
 use crate::{
-    HealthCheckRequest, HealthCheckResponse, RegisterNodeRequest, RegisterNodeResponse,
-    ReportRequest, ReportResponse, ServiceAdvertisement, ServiceAdvertisementStatus,
-    SupervisorServiceClient as GrpcSupervisorClient,
+    Directive, HealthCheckRequest, HealthCheckResponse, NodeCapabilities, RegisterNodeRequest,
+    RegisterNodeResponse, ReportRequest, ReportResponse, ServiceAdvertisement,
+    ServiceAdvertisementStatus, SupervisorServiceClient as GrpcSupervisorClient,
+    WatchDirectivesRequest,
 };
 use actrix_common::ServiceCollector;
+use actrix_common::realm::lifecycle;
+use actrix_proto::dns::DnsWatch;
 
 use sha2::{Digest, Sha256};
 use std::time::Duration;
-use tokio::time::interval;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, interval, sleep_until};
+use tonic::Streaming;
 use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
 use tracing::{debug, error, info, warn};
 
+/// 是否为连接层面的失败（报告应缓存并重连），而非 Supervisor 拒绝了请求
+fn is_connection_error(error: &SupervitError) -> bool {
+    match error {
+        SupervitError::Transport(_) | SupervitError::ConnectionClosed => true,
+        SupervitError::Status(status) => matches!(
+            status.code(),
+            tonic::Code::Unavailable
+                | tonic::Code::DeadlineExceeded
+                | tonic::Code::Cancelled
+                | tonic::Code::Unknown
+        ),
+        _ => false,
+    }
+}
+
 /// Supervit gRPC 客户端
+///
+/// endpoint 为域名时在后台按 TTL 重新解析，解析结果变化后在下一次请求前重建连接；
+/// 配置了备用端点时按 [`EndpointSet`] 故障转移
 pub struct SupervitClient {
     config: SupervitConfig,
     client: Option<GrpcSupervisorClient<Channel>>,
     shared_secret: Vec<u8>,    // hex decoded shared secret
     service_tags: Vec<String>, // normalized service tags
     service_collector: ServiceCollector,
+    metrics: MetricsCollector,
+    endpoints: EndpointSet,
+    /// 最近一次成功连接的端点（`endpoints` 的游标在故障转移时会先于连接前进）
+    connected_endpoint: Option<String>,
+    dns: Option<DnsWatch>,
 }
 
 impl SupervitClient {
@@ -46,23 +78,99 @@ impl SupervitClient {
         service_tags.sort();
         service_tags.dedup();
 
+        let metrics =
+            MetricsCollector::new(Duration::from_secs(config.metrics_sample_interval_secs))
+                .with_service_collector(service_collector.clone());
+        let endpoints = EndpointSet::new(
+            &config.endpoint,
+            &config.fallback_endpoints,
+            Duration::from_secs(config.health_check_interval_secs),
+        );
+
         Ok(Self {
             config,
             client: None,
             shared_secret,
             service_tags,
             service_collector,
+            metrics,
+            endpoints,
+            connected_endpoint: None,
+            dns: None,
         })
     }
 
     /// 连接到 supervisor 服务器
+    ///
+    /// 从当前端点开始依次尝试全部端点，返回最后一个端点的错误
     pub async fn connect(&mut self) -> Result<()> {
+        let mut last_error = SupervitError::ConnectionClosed;
+        for (index, endpoint) in self.endpoints.candidates() {
+            match self.connect_endpoint(&endpoint).await {
+                Ok(()) => {
+                    self.use_endpoint(index);
+                    return Ok(());
+                }
+                Err(e) => {
+                    if self.endpoints.len() > 1 {
+                        warn!("Supervisor endpoint {} unavailable: {}", endpoint, e);
+                    }
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    /// 切换到 `index` 对应的端点，与上次连接的端点不同时重新监听 DNS
+    fn use_endpoint(&mut self, index: usize) {
+        self.endpoints.select(index);
+        let current = self.endpoints.current();
+        if self.connected_endpoint.as_deref() == Some(current) {
+            return;
+        }
+        if let Some(previous) = self.connected_endpoint.as_deref() {
+            info!(
+                "Supervisor endpoint switched from {} to {}",
+                previous, current
+            );
+        }
+        self.dns = DnsWatch::spawn(current);
+        self.connected_endpoint = Some(current.to_string());
+    }
+
+    /// 当前连接中断，断开并让下一次连接从下一个端点开始
+    fn fail_over(&mut self) {
+        self.disconnect();
+        self.endpoints.fail_over();
+    }
+
+    /// 运行在备用端点上时探测主端点，主端点通过健康检查后切回（失败时保留现有连接）
+    async fn fail_back_if_due(&mut self) {
+        if self.client.is_none() || !self.endpoints.failback_due() {
+            return;
+        }
+        let primary = self.endpoints.primary().to_string();
+        match self.connect_endpoint(&primary).await {
+            Ok(()) => {
+                info!("Primary supervisor endpoint {} recovered", primary);
+                self.use_endpoint(0);
+            }
+            Err(e) => debug!(
+                "Primary supervisor endpoint {} still unavailable: {}",
+                primary, e
+            ),
+        }
+    }
+
+    /// 连接指定端点，配置了多个端点时须通过健康检查
+    async fn connect_endpoint(&mut self, address: &str) -> Result<()> {
         info!(
             "Connecting to supervisor at: {} (node: {})",
-            self.config.endpoint, self.config.node_id
+            address, self.config.node_id
         );
 
-        let mut endpoint = Endpoint::from_shared(self.config.endpoint.clone())
+        let mut endpoint = Endpoint::from_shared(address.to_string())
             .map_err(|e| SupervitError::Config(format!("Invalid server address: {e}")))?
             .timeout(Duration::from_secs(self.config.connect_timeout_secs))
             .connect_timeout(Duration::from_secs(self.config.connect_timeout_secs));
@@ -77,12 +185,46 @@ impl SupervitClient {
         }
 
         let channel = endpoint.connect().await?;
-        self.client = Some(GrpcSupervisorClient::new(channel));
+        let mut client = GrpcSupervisorClient::new(channel);
+        if self.endpoints.len() > 1 {
+            // 只认连接类错误：未实现 HealthCheck 或认证失败的 Supervisor 仍然在线
+            let request = Self::health_check_request(&self.config.node_id, &self.shared_secret)?;
+            if let Err(status) = client.health_check(request).await {
+                let e = SupervitError::from(status);
+                if is_connection_error(&e) {
+                    return Err(e);
+                }
+                debug!(
+                    "Health check on {} returned {}, accepting endpoint",
+                    address, e
+                );
+            }
+        }
+        self.client = Some(client);
 
         info!("Successfully connected to supervisor");
         Ok(())
     }
 
+    /// endpoint 的 DNS 解析结果变化时重建连接（重连失败时保留现有连接）
+    async fn reconnect_on_dns_change(&mut self) {
+        let Some(dns) = self.dns.as_mut() else {
+            return;
+        };
+        if !dns.take_change() || self.client.is_none() {
+            return;
+        }
+
+        info!(
+            "Supervisor endpoint {} now resolves to {:?}, reconnecting",
+            dns.host(),
+            dns.addresses()
+        );
+        if let Err(e) = self.connect().await {
+            warn!("Failed to reconnect to supervisor after DNS change: {}", e);
+        }
+    }
+
     /// 构建 TLS 配置（支持 mTLS）
     fn build_tls_config(&self) -> Result<ClientTlsConfig> {
         // TLS 域名是必需的
@@ -142,6 +284,7 @@ impl SupervitClient {
 
     /// Execute status report
     pub async fn report(&mut self) -> Result<ReportResponse> {
+        self.reconnect_on_dns_change().await;
         let client = self
             .client
             .as_mut()
@@ -152,18 +295,29 @@ impl SupervitClient {
             std::env::var("NODE_NAME").unwrap_or_else(|_| self.config.node_id.clone())
         });
 
-        let request = Self::create_report_request(
+        let mut request = Self::create_report_request(
             &self.config.node_id,
             &location_tag,
             &name,
             &self.shared_secret,
             self.service_collector.clone(),
+            &self.metrics,
+            &self.config.capabilities,
         )
         .await?;
+        let realm_events = lifecycle::take_events();
+        request.realm_events = realm_events.iter().map(lifecycle_event_to_proto).collect();
 
         debug!("Sending status report for node: {}", self.config.node_id);
 
-        let response = client.report(request).await?.into_inner();
+        let response = match client.report(request).await {
+            Ok(response) => response.into_inner(),
+            Err(e) => {
+                // Keep realm events for the next report
+                lifecycle::requeue_events(realm_events);
+                return Err(e.into());
+            }
+        };
 
         debug!(
             "Status report acknowledged, next interval: {}s",
@@ -199,6 +353,7 @@ impl SupervitClient {
             services,
         };
 
+        self.reconnect_on_dns_change().await;
         let client = self
             .client
             .as_mut()
@@ -218,6 +373,10 @@ impl SupervitClient {
     }
 
     /// 启动状态上报循环
+    ///
+    /// 连接失败或中断后按带抖动的指数退避重连（配置了备用端点时切换到下一个端点）；
+    /// 断线期间的报告缓存在 [`ReportBuffer`] 中（容量 `report_buffer_size`），
+    /// 重连后先补发再继续实时上报
     pub async fn start_status_reporting(&mut self) -> Result<()> {
         let mut interval_secs = self.config.status_report_interval_secs;
         let shared_secret = self.shared_secret.clone();
@@ -230,6 +389,7 @@ impl SupervitClient {
             .name
             .clone()
             .unwrap_or_else(|| std::env::var("NODE_NAME").unwrap_or_else(|_| node_id.clone()));
+        let capabilities = report_config.capabilities.clone();
         let service_collector = self.service_collector.clone();
 
         // 启动状态上报任务
@@ -245,79 +405,272 @@ impl SupervitClient {
                         return;
                     }
                 };
-
-            if let Err(e) = client.connect().await {
-                error!("Failed to connect report client: {}", e);
-                return;
-            }
+            let mut backoff = client.backoff();
+            let mut buffer = ReportBuffer::new(report_config.report_buffer_size);
+            // 断线后下一次重连的时间，连接正常时为 None
+            let mut reconnect_at = Some(Instant::now());
 
             loop {
-                ticker.tick().await;
+                tokio::select! {
+                    _ = sleep_until(reconnect_at.unwrap_or_else(Instant::now)),
+                        if reconnect_at.is_some() =>
+                    {
+                        match client.connect().await {
+                            Ok(()) => {
+                                backoff.reset();
+                                reconnect_at = None;
+                                if !client.flush_report_buffer(&mut buffer).await {
+                                    reconnect_at = Some(Instant::now() + backoff.next_delay());
+                                }
+                            }
+                            Err(e) => {
+                                let delay = backoff.next_delay();
+                                warn!(
+                                    "Failed to connect report client: {}, retrying in {:?}",
+                                    e, delay
+                                );
+                                reconnect_at = Some(Instant::now() + delay);
+                            }
+                        }
+                        continue;
+                    }
+                    _ = ticker.tick() => {}
+                }
 
-                match Self::create_report_request(
+                let mut request = match Self::create_report_request(
                     &node_id,
                     &location_tag,
                     &name,
                     &shared_secret,
                     service_collector.clone(),
+                    &client.metrics,
+                    &capabilities,
                 )
                 .await
                 {
-                    Ok(request) => {
-                        debug!("Sending status report for node: {}", node_id);
-                        match client.client.as_mut() {
-                            Some(grpc_client) => match grpc_client.report(request).await {
-                                Ok(response) => {
-                                    let resp = response.into_inner();
-                                    debug!("Status report acknowledged");
-                                    // 动态调整上报间隔
-                                    if resp.next_report_interval_secs > 0
-                                        && resp.next_report_interval_secs as u64 != interval_secs
-                                    {
-                                        interval_secs = resp.next_report_interval_secs as u64;
-                                        ticker = interval(Duration::from_secs(interval_secs));
-                                        info!("Adjusted report interval to {}s", interval_secs);
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Failed to send status report: {}", e);
-                                }
-                            },
-                            None => {
-                                error!("Client not connected");
-                                break;
-                            }
+                    Ok(request) => request,
+                    Err(e) => {
+                        warn!("Failed to create status report: {}", e);
+                        continue;
+                    }
+                };
+
+                if reconnect_at.is_some() {
+                    // Realm 事件保留在队列中，随恢复后的第一份实时报告上报
+                    debug!(
+                        "Supervisor unreachable, buffering status report ({} buffered)",
+                        buffer.len() + 1
+                    );
+                    buffer.push(request);
+                    continue;
+                }
+
+                let realm_events = lifecycle::take_events();
+                request.realm_events = realm_events.iter().map(lifecycle_event_to_proto).collect();
+                debug!("Sending status report for node: {}", node_id);
+                client.reconnect_on_dns_change().await;
+                client.fail_back_if_due().await;
+                match client.send_report(request.clone()).await {
+                    Ok(resp) => {
+                        debug!("Status report acknowledged");
+                        // 动态调整上报间隔
+                        if resp.next_report_interval_secs > 0
+                            && resp.next_report_interval_secs as u64 != interval_secs
+                        {
+                            interval_secs = resp.next_report_interval_secs as u64;
+                            ticker = interval(Duration::from_secs(interval_secs));
+                            info!("Adjusted report interval to {}s", interval_secs);
                         }
                     }
                     Err(e) => {
-                        warn!("Failed to create status report: {}", e);
+                        error!("Failed to send status report: {}", e);
+                        lifecycle::requeue_events(realm_events);
+                        if is_connection_error(&e) {
+                            request.realm_events.clear();
+                            buffer.push(request);
+                            client.fail_over();
+                            let delay = backoff.next_delay();
+                            warn!("Lost connection to supervisor, reconnecting in {:?}", delay);
+                            reconnect_at = Some(Instant::now() + delay);
+                        }
                     }
                 }
             }
-            info!("Status reporting stopped");
         });
 
         Ok(())
     }
 
-    /// 执行健康检查
-    pub async fn health_check(&mut self) -> Result<HealthCheckResponse> {
+    /// 发送一份状态报告
+    async fn send_report(&mut self, request: ReportRequest) -> Result<ReportResponse> {
         let client = self
             .client
             .as_mut()
             .ok_or(SupervitError::ConnectionClosed)?;
+        Ok(client.report(request).await?.into_inner())
+    }
 
-        // 构造请求负载（用于签名）
-        let payload = format!("health_check:{}", self.config.node_id);
+    /// 按时间顺序补发离线期间缓存的状态报告，连接再次中断时返回 false
+    ///
+    /// 原凭证可能已超出时钟偏差窗口，补发前按报告原有时间戳重新签名
+    async fn flush_report_buffer(&mut self, buffer: &mut ReportBuffer) -> bool {
+        if buffer.is_empty() {
+            return true;
+        }
+        info!("Replaying {} buffered status reports", buffer.len());
+
+        while let Some(mut request) = buffer.pop() {
+            let payload = format!("report:{}:{}", request.node_id, request.timestamp);
+            match generate_credential(&self.shared_secret, payload.as_bytes()) {
+                Ok(credential) => request.credential = credential,
+                Err(e) => {
+                    warn!("Failed to sign buffered status report: {}", e);
+                    continue;
+                }
+            }
 
-        // 生成认证凭证
-        let credential = generate_credential(&self.shared_secret, payload.as_bytes())?;
+            match self.send_report(request.clone()).await {
+                Ok(_) => {}
+                Err(e) if is_connection_error(&e) => {
+                    warn!("Failed to replay buffered status report: {}", e);
+                    buffer.push_front(request);
+                    self.fail_over();
+                    return false;
+                }
+                Err(e) => {
+                    // Supervisor 明确拒绝的报告不再重试
+                    warn!("Buffered status report rejected, dropped: {}", e);
+                }
+            }
+        }
+        true
+    }
 
-        let request = HealthCheckRequest {
+    /// 按配置创建重连退避
+    fn backoff(&self) -> Backoff {
+        Backoff::new(
+            INITIAL_RETRY_DELAY,
+            Duration::from_secs(self.config.max_reconnect_delay_secs),
+        )
+    }
+
+    /// 打开 Supervisor 指令流
+    ///
+    /// `last_sequence` 为最后处理的指令序号，Supervisor 据此补发断线期间的指令
+    pub async fn watch_directives(
+        &mut self,
+        last_sequence: Option<u64>,
+    ) -> Result<Streaming<Directive>> {
+        self.reconnect_on_dns_change().await;
+        let client = self
+            .client
+            .as_mut()
+            .ok_or(SupervitError::ConnectionClosed)?;
+
+        let payload = format!("watch_directives:{}", self.config.node_id);
+        let credential = generate_credential(&self.shared_secret, payload.as_bytes())?;
+        let request = WatchDirectivesRequest {
             node_id: self.config.node_id.clone(),
             credential,
+            last_sequence,
+            realm_sync_version: get_max_realm_version().await.unwrap_or(0),
         };
 
+        debug!(
+            "Opening directive stream for node {} (last sequence {:?})",
+            self.config.node_id, last_sequence
+        );
+        Ok(client.watch_directives(request).await?.into_inner())
+    }
+
+    /// 启动指令流监听，断线后按指数退避重连，收到关闭信号后退出
+    ///
+    /// Supervisor 未实现 WatchDirectives 时停止监听，仅依赖状态上报
+    pub fn start_directive_watch(
+        &self,
+        handler: Option<DirectiveHandler>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) -> Result<JoinHandle<()>> {
+        // 使用独立的客户端连接，与状态上报互不阻塞
+        let mut client = SupervitClient::new(self.config.clone(), self.service_collector.clone())?;
+
+        Ok(tokio::spawn(async move {
+            let mut state = DirectiveState::default();
+            let mut backoff = client.backoff();
+
+            loop {
+                let opened = tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    opened = async {
+                        if client.client.is_none() {
+                            client.connect().await?;
+                        }
+                        client.watch_directives(state.last_sequence).await
+                    } => opened,
+                };
+
+                match opened {
+                    Ok(mut stream) => {
+                        info!("Directive stream established");
+                        backoff.reset();
+                        loop {
+                            let message = tokio::select! {
+                                _ = shutdown_rx.recv() => {
+                                    info!("Directive watch stopped");
+                                    return;
+                                }
+                                message = stream.message() => message,
+                            };
+                            match message {
+                                Ok(Some(directive)) => {
+                                    state.dispatch(directive, handler.as_ref()).await;
+                                }
+                                Ok(None) => {
+                                    info!("Directive stream closed by supervisor");
+                                    break;
+                                }
+                                Err(e) => {
+                                    warn!("Directive stream error: {}", e);
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    Err(SupervitError::Status(status))
+                        if status.code() == tonic::Code::Unimplemented =>
+                    {
+                        warn!(
+                            "Supervisor does not support WatchDirectives, directive watch disabled"
+                        );
+                        return;
+                    }
+                    Err(e) => {
+                        warn!("Failed to open directive stream: {}", e);
+                        client.fail_over();
+                    }
+                }
+
+                let retry_delay = backoff.next_delay();
+                debug!("Reconnecting directive stream in {:?}", retry_delay);
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = tokio::time::sleep(retry_delay) => {}
+                }
+            }
+            info!("Directive watch stopped");
+        }))
+    }
+
+    /// 执行健康检查
+    pub async fn health_check(&mut self) -> Result<HealthCheckResponse> {
+        self.reconnect_on_dns_change().await;
+        let client = self
+            .client
+            .as_mut()
+            .ok_or(SupervitError::ConnectionClosed)?;
+
+        let request = Self::health_check_request(&self.config.node_id, &self.shared_secret)?;
+
         debug!("Sending health check request with nonce-auth credential");
 
         let response = client.health_check(request).await?.into_inner();
@@ -330,6 +683,19 @@ impl SupervitClient {
         Ok(response)
     }
 
+    fn health_check_request(node_id: &str, shared_secret: &[u8]) -> Result<HealthCheckRequest> {
+        // 构造请求负载（用于签名）
+        let payload = format!("health_check:{node_id}");
+
+        // 生成认证凭证
+        let credential = generate_credential(shared_secret, payload.as_bytes())?;
+
+        Ok(HealthCheckRequest {
+            node_id: node_id.to_string(),
+            credential,
+        })
+    }
+
     /// 创建状态报告请求（带认证凭证）
     async fn create_report_request(
         node_id: &str,
@@ -337,8 +703,10 @@ impl SupervitClient {
         name: &str,
         shared_secret: &[u8],
         service_collector: ServiceCollector,
+        metrics: &MetricsCollector,
+        capabilities: &NodeCapabilities,
     ) -> Result<ReportRequest> {
-        let metrics = collect_system_metrics().await?;
+        let metrics = metrics.collect().await?;
 
         // Get service statuses from collector
         let services = service_collector.all_statuses().await;
@@ -381,6 +749,8 @@ impl SupervitClient {
             services,
             credential,
             realm_sync_version,
+            capabilities: Some(capabilities.clone()),
+            realm_events: Vec::new(),
         })
     }
 
@@ -512,6 +882,39 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_fail_over_tracks_connected_endpoint() {
+        let config = SupervitConfig {
+            node_id: "test-node".to_string(),
+            endpoint: "http://127.0.0.1:50051".to_string(),
+            fallback_endpoints: vec!["http://127.0.0.2:50051".to_string()],
+            shared_secret: Some(
+                "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef".to_string(),
+            ),
+            ..Default::default()
+        };
+        let mut client = SupervitClient::new(config, ServiceCollector::new()).unwrap();
+
+        client.use_endpoint(0);
+        assert_eq!(
+            client.connected_endpoint.as_deref(),
+            Some("http://127.0.0.1:50051")
+        );
+
+        // 故障转移先移动游标，连接成功后才记录为新的已连接端点
+        client.fail_over();
+        assert_eq!(
+            client.connected_endpoint.as_deref(),
+            Some("http://127.0.0.1:50051")
+        );
+        let (index, _) = client.endpoints.candidates()[0].clone();
+        client.use_endpoint(index);
+        assert_eq!(
+            client.connected_endpoint.as_deref(),
+            Some("http://127.0.0.2:50051")
+        );
+    }
+
     #[tokio::test]
     async fn test_build_service_advertisements_merges_tags() {
         let config = SupervitConfig {
@@ -564,12 +967,18 @@ mod tests {
             hex::decode("0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef")
                 .unwrap();
         let service_collector = ServiceCollector::new();
+        let capabilities = NodeCapabilities {
+            build_features: vec!["opentelemetry".to_string()],
+            active_subsystems: vec!["ks".to_string()],
+        };
         let report = SupervitClient::create_report_request(
             "test-node",
             "test-location",
             "test-name",
             &secret,
             service_collector,
+            &MetricsCollector::new(Duration::from_secs(1)),
+            &capabilities,
         )
         .await;
         assert!(report.is_ok());
@@ -577,6 +986,7 @@ mod tests {
         assert_eq!(report.node_id, "test-node"); // proto field name unchanged
         assert_eq!(report.location_tag, "test-location");
         assert_eq!(report.name, "test-name");
+        assert_eq!(report.capabilities, Some(capabilities));
         // proto2 required 字段不是 Option
         assert!(report.credential.timestamp > 0);
         assert!(!report.credential.nonce.is_empty());
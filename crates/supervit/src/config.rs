@@ -1,5 +1,6 @@
 //! Configuration for supervit client
 
+use crate::NodeCapabilities;
 use crate::error::{Result, SupervitError};
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +23,10 @@ pub struct SupervitConfig {
     /// 示例: "http://supervisor.example.com:50051"
     pub endpoint: String,
 
+    /// 备用 Supervisor 地址（按顺序尝试，见 [`crate::failover`]）
+    #[serde(default)]
+    pub fallback_endpoints: Vec<String>,
+
     /// Supervisord gRPC advertised address (for Supervisor callback)
     ///
     /// This is the address that Supervisor will use to connect back to this node.
@@ -42,6 +47,24 @@ pub struct SupervitConfig {
     #[serde(default = "default_health_check_interval")]
     pub health_check_interval_secs: u64,
 
+    /// 最大重连等待时间（秒）
+    ///
+    /// 与 Supervisor 断开后按带抖动的指数退避重连，等待时间从 1 秒开始翻倍直至该上限
+    #[serde(default = "default_max_reconnect_delay")]
+    pub max_reconnect_delay_secs: u64,
+
+    /// Supervisor 不可达期间最多缓存的状态报告数（0 表示不缓存）
+    ///
+    /// 重连后按时间顺序补发，超出时丢弃最早的报告
+    #[serde(default = "default_report_buffer_size")]
+    pub report_buffer_size: usize,
+
+    /// 系统指标采样间隔（秒）
+    ///
+    /// 间隔内的多次上报复用同一次采样
+    #[serde(default = "default_metrics_sample_interval")]
+    pub metrics_sample_interval_secs: u64,
+
     /// 是否启用 TLS
     #[serde(default)]
     pub enable_tls: bool,
@@ -74,6 +97,12 @@ pub struct SupervitConfig {
     /// 可选的服务标签（应用于全部服务）
     #[serde(default)]
     pub service_tags: Vec<String>,
+
+    /// 节点能力（构建 feature 与已启用的可选子系统）
+    ///
+    /// 由宿主程序在启动时填充，随状态上报发送给 Supervisor 用于调度，不从配置文件读取
+    #[serde(skip)]
+    pub capabilities: NodeCapabilities,
 }
 
 fn default_connect_timeout() -> u64 {
@@ -88,6 +117,18 @@ fn default_health_check_interval() -> u64 {
     30
 }
 
+fn default_max_reconnect_delay() -> u64 {
+    60
+}
+
+fn default_report_buffer_size() -> usize {
+    120 // 默认上报间隔下约 2 小时
+}
+
+fn default_metrics_sample_interval() -> u64 {
+    15
+}
+
 fn default_max_clock_skew() -> u64 {
     300 // 5 分钟
 }
@@ -103,10 +144,14 @@ impl Default for SupervitConfig {
             name: None,
             location_tag: String::new(),
             endpoint: "http://localhost:50051".to_string(),
+            fallback_endpoints: Vec::new(),
             agent_addr: default_agent_addr(),
             connect_timeout_secs: default_connect_timeout(),
             status_report_interval_secs: default_status_interval(),
             health_check_interval_secs: default_health_check_interval(),
+            max_reconnect_delay_secs: default_max_reconnect_delay(),
+            report_buffer_size: default_report_buffer_size(),
+            metrics_sample_interval_secs: default_metrics_sample_interval(),
             enable_tls: false,
             tls_domain: None,
             client_cert: None,
@@ -116,6 +161,7 @@ impl Default for SupervitConfig {
             max_clock_skew_secs: default_max_clock_skew(),
             location: None,
             service_tags: Vec::new(),
+            capabilities: NodeCapabilities::default(),
         }
     }
 }
@@ -145,6 +191,28 @@ impl SupervitConfig {
             ));
         }
 
+        if let Some(endpoint) = self
+            .fallback_endpoints
+            .iter()
+            .find(|e| !e.starts_with("http://") && !e.starts_with("https://"))
+        {
+            return Err(SupervitError::Config(format!(
+                "fallback endpoint '{endpoint}' must start with http:// or https://"
+            )));
+        }
+
+        if self.max_reconnect_delay_secs == 0 {
+            return Err(SupervitError::Config(
+                "max_reconnect_delay_secs must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.metrics_sample_interval_secs == 0 {
+            return Err(SupervitError::Config(
+                "metrics_sample_interval_secs must be greater than 0".to_string(),
+            ));
+        }
+
         if self.enable_tls && self.tls_domain.is_none() {
             return Err(SupervitError::Config(
                 "tls_domain is required when enable_tls is true".to_string(),
@@ -214,6 +282,16 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_validate_invalid_fallback_endpoint() {
+        let config = SupervitConfig {
+            node_id: "test-node".to_string(),
+            fallback_endpoints: vec!["supervisor-2:50051".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_validate_valid_config() {
         let config = SupervitConfig {
@@ -0,0 +1,153 @@
+//! Supervisor directive stream (WatchDirectives)
+//!
+//! 节点保持一条 server-streaming 连接，实时接收 Supervisor 推送的指令，不再依赖
+//! 状态上报响应中捎带的 directive：
+//! - `REALM_UPDATE`：直接写入本地 Realm 表（版本不高于本地时忽略）
+//! - 全部指令（包括 `REALM_UPDATE`）随后交给宿主程序注册的 [`DirectiveHandler`]，
+//!   由宿主处理配置变更、drain 与关闭等
+//!
+//! 连接断开后按带抖动的指数退避重连（见 [`crate::backoff`]），并携带最后处理的序号，Supervisor 据此补发遗漏的指令；
+//! 序号不大于已处理序号的重复指令会被丢弃。
+
+use crate::realm::apply_realm_info;
+use crate::{Directive, DirectiveType};
+use actrix_common::storage::is_database_initialized;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+pub type DirectiveFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// 宿主程序处理指令的回调
+pub type DirectiveHandler = Arc<dyn Fn(Directive) -> DirectiveFuture + Send + Sync>;
+
+/// 由异步闭包构造 [`DirectiveHandler`]
+pub fn directive_handler<F, Fut>(handler: F) -> DirectiveHandler
+where
+    F: Fn(Directive) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    Arc::new(move |directive| Box::pin(handler(directive)))
+}
+
+/// 指令流的处理状态
+#[derive(Debug, Default)]
+pub(crate) struct DirectiveState {
+    /// 最后处理的指令序号
+    pub last_sequence: Option<u64>,
+}
+
+impl DirectiveState {
+    /// 处理一条指令，重复指令返回 false
+    pub async fn dispatch(
+        &mut self,
+        directive: Directive,
+        handler: Option<&DirectiveHandler>,
+    ) -> bool {
+        if let (Some(sequence), Some(last)) = (directive.sequence, self.last_sequence)
+            && sequence <= last
+        {
+            debug!("Skip duplicate directive #{}", sequence);
+            return false;
+        }
+        if let Some(sequence) = directive.sequence {
+            self.last_sequence = Some(sequence);
+        }
+
+        info!(
+            "Received directive {:?} (sequence {:?})",
+            directive.r#type(),
+            directive.sequence
+        );
+        if directive.r#type() == DirectiveType::RealmUpdate {
+            apply_realm_update(&directive).await;
+        }
+        if let Some(handler) = handler {
+            handler(directive).await;
+        }
+        true
+    }
+}
+
+async fn apply_realm_update(directive: &Directive) {
+    let Some(realm) = &directive.realm else {
+        warn!("REALM_UPDATE directive without realm, ignored");
+        return;
+    };
+    if !is_database_initialized() {
+        warn!(
+            "Database not initialized, cannot apply pushed realm {}",
+            realm.realm_id
+        );
+        return;
+    }
+    match apply_realm_info(realm).await {
+        Ok(true) => info!(
+            "Applied pushed realm {} (version {})",
+            realm.realm_id, realm.version
+        ),
+        Ok(false) => {}
+        Err(e) => warn!("Failed to apply pushed realm {}: {}", realm.realm_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Mutex;
+
+    fn directive(r#type: DirectiveType, sequence: Option<u64>) -> Directive {
+        Directive {
+            r#type: r#type as i32,
+            sequence,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_skips_duplicates() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let handler = directive_handler(move |d: Directive| {
+            let sink = sink.clone();
+            async move { sink.lock().await.push(d.sequence) }
+        });
+
+        let mut state = DirectiveState::default();
+        assert!(
+            state
+                .dispatch(
+                    directive(DirectiveType::ConfigUpdate, Some(1)),
+                    Some(&handler)
+                )
+                .await
+        );
+        assert!(
+            state
+                .dispatch(directive(DirectiveType::Drain, Some(2)), Some(&handler))
+                .await
+        );
+        // 重连后补发的旧指令被丢弃
+        assert!(
+            !state
+                .dispatch(
+                    directive(DirectiveType::ConfigUpdate, Some(1)),
+                    Some(&handler)
+                )
+                .await
+        );
+        // 没有序号的指令总是处理
+        assert!(
+            state
+                .dispatch(
+                    directive(DirectiveType::AdjustInterval, None),
+                    Some(&handler)
+                )
+                .await
+        );
+
+        assert_eq!(state.last_sequence, Some(2));
+        assert_eq!(*received.lock().await, vec![Some(1), Some(2), None]);
+    }
+}
@@ -30,6 +30,9 @@ pub enum SupervitError {
     #[error("nonce-auth error: {0}")]
     NonceAuth(#[from] ::nonce_auth::NonceError),
 
+    #[error("Update error: {0}")]
+    Update(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -0,0 +1,143 @@
+//! Supervisor 多端点故障转移
+//!
+//! 节点可以配置一个主端点（`endpoint`）和若干备用端点（`fallback_endpoints`），
+//! [`EndpointSet`] 记录当前使用的端点：
+//! - 连接时从当前端点开始依次尝试，配置了多个端点时候选端点必须通过 HealthCheck 才会被采用
+//! - 连接正常期间保持在当前端点；当前端点连接中断后，下一次连接从下一个端点开始，
+//!   全部尝试过后回到列表开头
+//! - 运行在备用端点上时，按 `health_check_interval_secs` 探测主端点，主端点通过健康检查后切回，
+//!   主端点未恢复前不会来回切换
+
+use std::time::{Duration, Instant};
+
+/// 有序的 Supervisor 端点列表与当前端点
+#[derive(Debug, Clone)]
+pub struct EndpointSet {
+    endpoints: Vec<String>,
+    current: usize,
+    failback_interval: Duration,
+    next_failback_check: Option<Instant>,
+}
+
+impl EndpointSet {
+    /// 创建端点列表，`primary` 在最前，重复的端点只保留第一次出现
+    pub fn new(primary: &str, fallbacks: &[String], failback_interval: Duration) -> Self {
+        let mut endpoints: Vec<String> = Vec::with_capacity(fallbacks.len() + 1);
+        for endpoint in std::iter::once(primary).chain(fallbacks.iter().map(String::as_str)) {
+            if !endpoints.iter().any(|e| e == endpoint) {
+                endpoints.push(endpoint.to_string());
+            }
+        }
+        Self {
+            endpoints,
+            current: 0,
+            failback_interval,
+            next_failback_check: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// 当前端点
+    pub fn current(&self) -> &str {
+        &self.endpoints[self.current]
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// 主端点
+    pub fn primary(&self) -> &str {
+        &self.endpoints[0]
+    }
+
+    pub fn is_primary(&self) -> bool {
+        self.current == 0
+    }
+
+    /// 按尝试顺序列出端点（从当前端点开始）
+    pub fn candidates(&self) -> Vec<(usize, String)> {
+        (0..self.endpoints.len())
+            .map(|offset| {
+                let index = (self.current + offset) % self.endpoints.len();
+                (index, self.endpoints[index].clone())
+            })
+            .collect()
+    }
+
+    /// 采用 `index` 对应的端点
+    pub fn select(&mut self, index: usize) {
+        self.current = index % self.endpoints.len();
+        self.next_failback_check =
+            (self.current != 0).then(|| Instant::now() + self.failback_interval);
+    }
+
+    /// 当前端点连接中断，下一次连接从下一个端点开始
+    pub fn fail_over(&mut self) {
+        self.current = (self.current + 1) % self.endpoints.len();
+    }
+
+    /// 运行在备用端点上且到了探测主端点的时间
+    pub fn failback_due(&mut self) -> bool {
+        match self.next_failback_check {
+            Some(at) if Instant::now() >= at => {
+                self.next_failback_check = Some(Instant::now() + self.failback_interval);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoints() -> EndpointSet {
+        EndpointSet::new(
+            "http://sup-1:50051",
+            &[
+                "http://sup-2:50051".to_string(),
+                "http://sup-1:50051".to_string(),
+                "http://sup-3:50051".to_string(),
+            ],
+            Duration::ZERO,
+        )
+    }
+
+    #[test]
+    fn test_candidates_start_from_current() {
+        let mut set = endpoints();
+        // 重复的主端点被去除
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.current(), "http://sup-1:50051");
+
+        set.fail_over();
+        let order: Vec<usize> = set.candidates().into_iter().map(|(i, _)| i).collect();
+        assert_eq!(order, vec![1, 2, 0]);
+
+        set.fail_over();
+        set.fail_over();
+        assert!(set.is_primary());
+    }
+
+    #[test]
+    fn test_failback_only_on_fallback() {
+        let mut set = endpoints();
+        assert!(!set.failback_due());
+
+        set.select(2);
+        assert_eq!(set.current(), "http://sup-3:50051");
+        assert!(set.failback_due());
+
+        set.select(0);
+        assert!(!set.failback_due());
+    }
+}
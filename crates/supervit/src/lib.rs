@@ -7,13 +7,18 @@
 //!
 //! - **SupervisorService Client**: For nodes to call the supervisor
 //!   - Node registration
-//!   - Status reporting (unary RPC)
+//!   - Failover across multiple supervisor endpoints (health-checked, sticky)
+//!   - Status reporting (unary RPC, jittered reconnect backoff and offline buffering)
 //!   - Health checks
+//!   - Directive stream (server-streaming RPC: realm updates, config changes, drain)
+//!   - Self-update from signed binary/config bundles (UPDATE directive)
 //!
 //! - **SupervisedService Server**: For supervisor to call nodes
 //!   - Configuration management
 //!   - Realm CRUD operations
-//!   - Node control (info, shutdown)
+//!   - Node control (info, shutdown, runtime service enable/disable)
+//!   - Signaling connection management (list, force-disconnect, server notices)
+//!   - Log tailing from an in-memory ring buffer (server-streaming RPC)
 //!
 //! # Architecture
 //!
@@ -38,55 +43,96 @@
 //! - SupervisedService server (to be called by Supervisor)
 
 pub mod auth;
+pub mod backoff;
 pub mod client;
 pub mod config;
+pub mod directive;
 pub mod error;
+pub mod failover;
+pub mod logs;
 pub mod metrics;
 pub mod nonce_auth;
 pub mod realm;
 pub mod service;
+pub mod update;
 
 // Re-export important types and functions
 pub use auth::AuthService;
+pub use backoff::{Backoff, ReportBuffer};
 pub use client::SupervitClient;
 pub use config::SupervitConfig;
+pub use directive::{DirectiveHandler, directive_handler};
 pub use error::{Result, SupervitError};
+pub use failover::EndpointSet;
+pub use logs::{LogBuffer, LogBufferLayer};
+pub use metrics::MetricsCollector;
 pub use realm::{
     REALM_ENABLED_KEY, REALM_USE_SERVERS_KEY, REALM_VERSION_KEY, RealmMetadata,
     get_max_realm_version,
 };
 pub use service::Supervisord;
+pub use update::{SelfUpdater, StagedUpdate, signing_manifest};
 
 // Re-export commonly used proto types from actrix-proto
 pub use actrix_proto::{
+    // SupervisedService (Supervisor calls Node)
+    BroadcastServerNoticeRequest,
+    BroadcastServerNoticeResponse,
     // Common types
+    ConfigChange,
     ConfigType,
-    // SupervisedService (Supervisor calls Node)
+    ConnectedActor,
+    ConnectedService,
+    CreateRealmApiKeyRequest,
+    CreateRealmApiKeyResponse,
     CreateRealmRequest,
     CreateRealmResponse,
     DeleteRealmRequest,
     DeleteRealmResponse,
     Directive,
     DirectiveType,
+    DisconnectActorRequest,
+    DisconnectActorResponse,
+    DryRunReport,
     GetConfigRequest,
     GetConfigResponse,
     GetNodeInfoRequest,
     GetNodeInfoResponse,
     GetRealmRequest,
     GetRealmResponse,
+    GetServiceSpecHistoryRequest,
+    GetServiceSpecHistoryResponse,
+    ListConnectionsRequest,
+    ListConnectionsResponse,
+    ListRealmApiKeysRequest,
+    ListRealmApiKeysResponse,
     ListRealmsRequest,
     ListRealmsResponse,
+    LogEntry,
+    NodeCapabilities,
     NonceCredential,
+    RealmApiKeyInfo,
+    RealmLifecycleEvent,
+    RealmQuotaInfo,
+    RealmRateLimitInfo,
+    RealmUsage,
     RegisterNodeRequest,
     RegisterNodeResponse,
     ReportRequest,
     ReportResponse,
     ResourceType,
+    RevokeRealmApiKeyRequest,
+    RevokeRealmApiKeyResponse,
     ServiceAdvertisement,
     ServiceAdvertisementStatus,
+    ServiceHealth,
+    ServiceSpecVersion,
     ServiceStatus,
+    SetServiceEnabledRequest,
+    SetServiceEnabledResponse,
     ShutdownRequest,
     ShutdownResponse,
+    StreamLogsRequest,
     SupervisedService,
     SupervisedServiceClient,
     SupervisedServiceServer,
@@ -97,8 +143,12 @@ pub use actrix_proto::{
     SupervisorServiceClient,
     SupervisorServiceServer,
     SystemMetrics,
+    UdpSocketStats,
+    UpdateBundle,
+    UpdateBundleKind,
     UpdateConfigRequest,
     UpdateConfigResponse,
     UpdateRealmRequest,
     UpdateRealmResponse,
+    WatchDirectivesRequest,
 };
@@ -0,0 +1,257 @@
+//! 近期日志环形缓冲（StreamLogs）
+//!
+//! [`LogBufferLayer`] 作为 tracing layer 挂在全局 subscriber 上，把经过全局过滤器的事件
+//! 写入固定容量的 [`LogBuffer`]，Supervisor 通过 `StreamLogs` RPC 读取近期日志并可持续跟随，
+//! 无需登录节点即可做基本排障。
+//!
+//! 缓冲只存在于内存中，进程重启后清空；超出容量时覆盖最早的记录。
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, LazyLock, Mutex};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// 默认保留的日志条数
+pub const DEFAULT_CAPACITY: usize = 2000;
+
+/// 跟随模式下实时日志的广播队列长度
+const FOLLOW_CHANNEL_CAPACITY: usize = 1024;
+
+/// 单条日志消息的最大长度（字节），超出部分截断
+const MAX_MESSAGE_BYTES: usize = 4096;
+
+/// 一条日志记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// 进程内单调递增的序号
+    pub sequence: u64,
+    /// 时间（Unix 毫秒）
+    pub timestamp_ms: i64,
+    pub level: Level,
+    pub target: String,
+    /// 消息与结构化字段（`key=value`）
+    pub message: String,
+}
+
+#[derive(Debug)]
+struct BufferState {
+    records: VecDeque<LogRecord>,
+    next_sequence: u64,
+}
+
+/// 固定容量的日志环形缓冲
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    capacity: usize,
+    state: Arc<Mutex<BufferState>>,
+    live: broadcast::Sender<LogRecord>,
+}
+
+static GLOBAL: LazyLock<LogBuffer> = LazyLock::new(|| LogBuffer::new(DEFAULT_CAPACITY));
+
+/// 进程级日志缓冲（由宿主程序的 tracing subscriber 写入，Supervisord 读取）
+pub fn global() -> &'static LogBuffer {
+    &GLOBAL
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let (live, _) = broadcast::channel(FOLLOW_CHANNEL_CAPACITY);
+        Self {
+            capacity: capacity.max(1),
+            state: Arc::new(Mutex::new(BufferState {
+                records: VecDeque::with_capacity(capacity.max(1)),
+                next_sequence: 1,
+            })),
+            live,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 追加一条日志
+    pub fn push(&self, timestamp_ms: i64, level: Level, target: &str, message: String) {
+        let record = {
+            let mut state = self
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let record = LogRecord {
+                sequence: state.next_sequence,
+                timestamp_ms,
+                level,
+                target: target.to_string(),
+                message,
+            };
+            state.next_sequence += 1;
+            if state.records.len() >= self.capacity {
+                state.records.pop_front();
+            }
+            state.records.push_back(record.clone());
+            record
+        };
+        // 没有跟随者时发送失败，忽略
+        let _ = self.live.send(record);
+    }
+
+    /// 读取最近 `limit` 条不低于 `min_level`、target 以 `target_prefix` 开头的日志（按时间正序）
+    pub fn recent(
+        &self,
+        min_level: Level,
+        target_prefix: Option<&str>,
+        limit: usize,
+    ) -> Vec<LogRecord> {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut records: Vec<LogRecord> = state
+            .records
+            .iter()
+            .rev()
+            .filter(|record| matches(record, min_level, target_prefix))
+            .take(limit)
+            .cloned()
+            .collect();
+        records.reverse();
+        records
+    }
+
+    /// 订阅此后写入的日志
+    pub fn subscribe(&self) -> broadcast::Receiver<LogRecord> {
+        self.live.subscribe()
+    }
+}
+
+/// 日志是否满足级别与 target 过滤条件
+///
+/// tracing 的 Level 越详细越“大”（TRACE > ERROR），不低于 `min_level` 即 `level <= min_level`
+pub fn matches(record: &LogRecord, min_level: Level, target_prefix: Option<&str>) -> bool {
+    record.level <= min_level
+        && target_prefix.is_none_or(|prefix| record.target.starts_with(prefix))
+}
+
+/// 把 tracing 事件写入 [`LogBuffer`] 的 layer
+#[derive(Debug, Clone)]
+pub struct LogBufferLayer {
+    buffer: LogBuffer,
+}
+
+impl LogBufferLayer {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+impl Default for LogBufferLayer {
+    fn default() -> Self {
+        Self::new(global().clone())
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        self.buffer.push(
+            chrono::Utc::now().timestamp_millis(),
+            *metadata.level(),
+            metadata.target(),
+            visitor.finish(),
+        );
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(mut self) -> String {
+        if !self.fields.is_empty() {
+            if !self.message.is_empty() {
+                self.message.push(' ');
+            }
+            self.message.push_str(self.fields.trim_start());
+        }
+        if self.message.len() > MAX_MESSAGE_BYTES {
+            let mut end = MAX_MESSAGE_BYTES;
+            while !self.message.is_char_boundary(end) {
+                end -= 1;
+            }
+            self.message.truncate(end);
+            self.message.push('…');
+        }
+        self.message
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_layer_captures_events_with_fields() {
+        let buffer = LogBuffer::new(2);
+        let subscriber = tracing_subscriber::registry().with(LogBufferLayer::new(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "signaling", "first");
+            tracing::warn!(target: "signaling::server", realm_id = 7, "second {}", 2);
+            tracing::debug!(target: "ks", "third");
+        });
+
+        // 容量为 2，最早的记录被覆盖
+        let all = buffer.recent(Level::TRACE, None, 10);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].message, "second 2 realm_id=7");
+        assert_eq!(all[0].sequence, 2);
+        assert_eq!(all[1].target, "ks");
+
+        let warnings = buffer.recent(Level::WARN, None, 10);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].level, Level::WARN);
+
+        assert_eq!(buffer.recent(Level::TRACE, Some("signaling"), 10).len(), 1);
+        assert_eq!(buffer.recent(Level::TRACE, None, 1)[0].target, "ks");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_receives_new_records() {
+        let buffer = LogBuffer::new(8);
+        let mut live = buffer.subscribe();
+        buffer.push(0, Level::ERROR, "turn", "relay failed".to_string());
+
+        let record = live.recv().await.unwrap();
+        assert_eq!(record.message, "relay failed");
+        assert_eq!(record.level, Level::ERROR);
+    }
+}
@@ -1,44 +1,299 @@
-//! System metrics collection using pwrzv
+//! System metrics collection
+//!
+//! [`MetricsCollector`] 基于 sysinfo 采集主机 CPU、内存、网络与磁盘，并在 Linux 上从 `/proc`
+//! 读取本进程的文件描述符与 UDP 套接字队列深度。系统采样在 `sample_interval` 内复用，
+//! CPU 使用率是相邻两次采样之间的平均值；Realm 用量与服务健康状态每次都实时读取。
 
 use crate::error::{Result, SupervitError};
-use actrix_proto::{ServiceStatus, SystemMetrics};
-use std::sync::Arc;
-use tracing::warn;
+use actrix_common::realm::usage;
+use actrix_common::{ServiceCollector, ServiceState};
+use actrix_proto::{RealmUsage, ServiceHealth, ServiceStatus, SystemMetrics, UdpSocketStats};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Disks, Networks, System};
+use tokio::sync::Mutex;
 
-/// 收集系统指标
+/// 默认系统采样间隔
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+struct Sampler {
+    system: System,
+    networks: Networks,
+    disks: Disks,
+    last: Option<(Instant, SystemMetrics)>,
+}
+
+impl Sampler {
+    fn new() -> Self {
+        let mut system = System::new();
+        // 建立 CPU 使用率的基准，下一次刷新得到区间平均值
+        system.refresh_cpu_usage();
+        Self {
+            system,
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new_with_refreshed_list(),
+            last: None,
+        }
+    }
+
+    fn sample(&mut self) -> SystemMetrics {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        self.networks.refresh(true);
+        self.disks.refresh(true);
+
+        let memory_total = self.system.total_memory();
+        let memory_used = self.system.used_memory();
+        let (network_rx, network_tx) = self
+            .networks
+            .iter()
+            .fold((0u64, 0u64), |(rx, tx), (_, data)| {
+                (rx + data.total_received(), tx + data.total_transmitted())
+            });
+        let (disk_total, disk_available) =
+            self.disks
+                .list()
+                .iter()
+                .fold((0u64, 0u64), |(total, available), disk| {
+                    (
+                        total + disk.total_space(),
+                        available + disk.available_space(),
+                    )
+                });
+        let load = System::load_average();
+
+        SystemMetrics {
+            cpu_usage_percent: self.system.global_cpu_usage() as f64,
+            memory_used_bytes: memory_used,
+            memory_total_bytes: memory_total,
+            memory_usage_percent: if memory_total > 0 {
+                (memory_used as f64 / memory_total as f64) * 100.0
+            } else {
+                0.0
+            },
+            network_rx_bytes: network_rx,
+            network_tx_bytes: network_tx,
+            disk_used_bytes: disk_total.saturating_sub(disk_available),
+            disk_total_bytes: disk_total,
+            load_average_1m: load.one,
+            load_average_5m: Some(load.five), // proto2 optional 字段
+            load_average_15m: Some(load.fifteen), // proto2 optional 字段
+            realm_usage: Vec::new(),
+            open_fds: process::open_fds(),
+            max_fds: process::max_fds(),
+            udp: process::udp_socket_stats(),
+            service_health: Vec::new(),
+        }
+    }
+}
+
+/// 系统指标采集器
+#[derive(Clone)]
+pub struct MetricsCollector {
+    sampler: Arc<Mutex<Sampler>>,
+    sample_interval: Duration,
+    service_collector: Option<ServiceCollector>,
+}
+
+impl MetricsCollector {
+    /// 创建采集器，`sample_interval` 内重复采集时复用上一次的系统采样
+    pub fn new(sample_interval: Duration) -> Self {
+        Self {
+            sampler: Arc::new(Mutex::new(Sampler::new())),
+            sample_interval,
+            service_collector: None,
+        }
+    }
+
+    /// 附带服务收集器，填充 `service_health`
+    pub fn with_service_collector(mut self, service_collector: ServiceCollector) -> Self {
+        self.service_collector = Some(service_collector);
+        self
+    }
+
+    /// 采集系统指标
+    pub async fn collect(&self) -> Result<SystemMetrics> {
+        let sampler = self.sampler.clone();
+        let sample_interval = self.sample_interval;
+        // sysinfo 读取 /proc 等为阻塞调用
+        let mut metrics = tokio::task::spawn_blocking(move || {
+            let mut sampler = sampler.blocking_lock();
+            match &sampler.last {
+                Some((at, metrics)) if at.elapsed() < sample_interval => metrics.clone(),
+                _ => {
+                    let metrics = sampler.sample();
+                    sampler.last = Some((Instant::now(), metrics.clone()));
+                    metrics
+                }
+            }
+        })
+        .await
+        .map_err(|e| SupervitError::Metrics(format!("Metrics sampling task failed: {e}")))?;
+
+        metrics.realm_usage = collect_realm_usage();
+        if let Some(service_collector) = &self.service_collector {
+            metrics.service_health = collect_service_health(service_collector).await;
+        }
+        Ok(metrics)
+    }
+}
+
+static DEFAULT_COLLECTOR: LazyLock<MetricsCollector> =
+    LazyLock::new(|| MetricsCollector::new(DEFAULT_SAMPLE_INTERVAL));
+
+/// 使用默认采集器收集系统指标（不含服务健康状态）
 pub async fn collect_system_metrics() -> Result<SystemMetrics> {
-    let (_, details) = pwrzv::get_power_reserve_level_with_details_direct()
+    DEFAULT_COLLECTOR.collect().await
+}
+
+/// 收集各服务的健康状态
+pub async fn collect_service_health(service_collector: &ServiceCollector) -> Vec<ServiceHealth> {
+    let mut health: Vec<ServiceHealth> = service_collector
+        .values()
         .await
-        .map_err(|e| {
-            warn!("Failed to read system metrics: {}", e);
-            SupervitError::Metrics(e.to_string())
-        })?;
-
-    // 从详细信息中提取指标 (pwrzv 返回 HashMap<String, f32>)
-    let cpu_usage = details.get("cpu_usage").copied().unwrap_or(0.0) as f64;
-    let memory_used = details.get("memory_used").copied().unwrap_or(0.0) as u64;
-    let memory_total = details.get("memory_total").copied().unwrap_or(0.0) as u64;
-    let load_avg_1 = details.get("load_avg_1").copied().unwrap_or(0.0) as f64;
-    let load_avg_5 = details.get("load_avg_5").copied().unwrap_or(0.0) as f64;
-    let load_avg_15 = details.get("load_avg_15").copied().unwrap_or(0.0) as f64;
-
-    Ok(SystemMetrics {
-        cpu_usage_percent: cpu_usage,
-        memory_used_bytes: memory_used,
-        memory_total_bytes: memory_total,
-        memory_usage_percent: if memory_total > 0 {
-            (memory_used as f64 / memory_total as f64) * 100.0
-        } else {
-            0.0
-        },
-        network_rx_bytes: 0, // pwrzv 0.6 不提供网络统计
-        network_tx_bytes: 0,
-        disk_used_bytes: 0, // pwrzv 不提供磁盘统计
-        disk_total_bytes: 0,
-        load_average_1m: load_avg_1,
-        load_average_5m: Some(load_avg_5),   // proto2 optional 字段
-        load_average_15m: Some(load_avg_15), // proto2 optional 字段
-    })
+        .into_iter()
+        .map(|info| {
+            let state = match &info.status {
+                ServiceState::Running(_) => "running".to_string(),
+                ServiceState::Unknown => "unknown".to_string(),
+                ServiceState::Error(reason) => format!("error: {reason}"),
+            };
+            let status = ServiceStatus::from(&info);
+            ServiceHealth {
+                name: info.name,
+                r#type: status.r#type,
+                is_healthy: status.is_healthy,
+                state,
+            }
+        })
+        .collect();
+    health.sort_by(|a, b| a.name.cmp(&b.name));
+    health
+}
+
+/// 本进程资源（Linux 从 /proc 读取，其他平台返回 None）
+mod process {
+    use super::UdpSocketStats;
+
+    #[cfg(target_os = "linux")]
+    pub fn open_fds() -> Option<u64> {
+        std::fs::read_dir("/proc/self/fd")
+            .ok()
+            .map(|entries| entries.count() as u64)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn max_fds() -> Option<u64> {
+        let limits = std::fs::read_to_string("/proc/self/limits").ok()?;
+        parse_max_open_files(&limits)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn udp_socket_stats() -> Option<UdpSocketStats> {
+        let inodes = socket_inodes();
+        let mut stats = UdpSocketStats::default();
+        for table in ["/proc/self/net/udp", "/proc/self/net/udp6"] {
+            if let Ok(content) = std::fs::read_to_string(table) {
+                accumulate_udp_table(&content, &inodes, &mut stats);
+            }
+        }
+        Some(stats)
+    }
+
+    /// 本进程持有的套接字 inode
+    #[cfg(target_os = "linux")]
+    fn socket_inodes() -> std::collections::HashSet<u64> {
+        let Ok(entries) = std::fs::read_dir("/proc/self/fd") else {
+            return Default::default();
+        };
+        entries
+            .filter_map(|entry| std::fs::read_link(entry.ok()?.path()).ok())
+            .filter_map(|target| {
+                let target = target.to_str()?;
+                target
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()
+            })
+            .collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open_fds() -> Option<u64> {
+        None
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn max_fds() -> Option<u64> {
+        None
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn udp_socket_stats() -> Option<UdpSocketStats> {
+        None
+    }
+
+    /// 解析 /proc/self/limits 中 "Max open files" 的软限制
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(super) fn parse_max_open_files(limits: &str) -> Option<u64> {
+        let line = limits
+            .lines()
+            .find(|line| line.starts_with("Max open files"))?;
+        line["Max open files".len()..]
+            .split_whitespace()
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// 累加 /proc/net/udp(6) 中属于 `inodes` 的套接字
+    ///
+    /// 列：sl local rem st tx_queue:rx_queue tr:tm retrnsmt uid timeout inode ref pointer drops
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(super) fn accumulate_udp_table(
+        content: &str,
+        inodes: &std::collections::HashSet<u64>,
+        stats: &mut UdpSocketStats,
+    ) {
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 13 {
+                continue;
+            }
+            let Ok(inode) = fields[9].parse::<u64>() else {
+                continue;
+            };
+            if !inodes.contains(&inode) {
+                continue;
+            }
+            let Some((tx, rx)) = fields[4].split_once(':') else {
+                continue;
+            };
+            let tx = u64::from_str_radix(tx, 16).unwrap_or(0);
+            let rx = u64::from_str_radix(rx, 16).unwrap_or(0);
+
+            stats.sockets += 1;
+            stats.tx_queue_bytes += tx;
+            stats.rx_queue_bytes += rx;
+            stats.max_rx_queue_bytes = stats.max_rx_queue_bytes.max(rx);
+            stats.drops += fields[12].parse::<u64>().unwrap_or(0);
+        }
+    }
+}
+
+/// 收集各 Realm 的累计用量（见 [`actrix_common::realm::usage`]）
+pub fn collect_realm_usage() -> Vec<RealmUsage> {
+    usage::snapshot()
+        .into_iter()
+        .map(|u| RealmUsage {
+            realm_id: u.realm_id,
+            active_actors: u.active_actors,
+            registrations: u.registrations,
+            relay_messages: u.relay_messages,
+            turn_bytes: u.turn_bytes,
+        })
+        .collect()
 }
 
 /// 服务状态提供者类型（用于 ReportRequest）
@@ -71,4 +326,68 @@ mod tests {
             assert!(metrics.cpu_usage_percent >= 0.0);
         }
     }
+
+    #[test]
+    fn test_parse_udp_table() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  187: 00000000:0D96 00000000:0000 07 00000000:00000A00 00:00000000 00000000  1000        0 41001 2 0000000000000000 3
+  188: 00000000:0D97 00000000:0000 07 00000010:00000200 00:00000000 00000000  1000        0 41002 2 0000000000000000 0
+  189: 00000000:0035 00000000:0000 07 00000000:00FFFFFF 00:00000000 00000000     0        0 99999 2 0000000000000000 7
+";
+        let inodes = [41001, 41002].into_iter().collect();
+        let mut stats = UdpSocketStats::default();
+        process::accumulate_udp_table(table, &inodes, &mut stats);
+
+        // 其他进程的套接字（inode 99999）不计入
+        assert_eq!(stats.sockets, 2);
+        assert_eq!(stats.rx_queue_bytes, 0xA00 + 0x200);
+        assert_eq!(stats.tx_queue_bytes, 0x10);
+        assert_eq!(stats.max_rx_queue_bytes, 0xA00);
+        assert_eq!(stats.drops, 3);
+    }
+
+    #[test]
+    fn test_parse_max_open_files() {
+        let limits = "Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 524288               files
+";
+        assert_eq!(process::parse_max_open_files(limits), Some(1024));
+    }
+
+    #[tokio::test]
+    async fn test_collect_service_health() {
+        let service_collector = ServiceCollector::new();
+        service_collector
+            .insert(
+                "turn".to_string(),
+                actrix_common::ServiceInfo {
+                    name: "turn-service".to_string(),
+                    service_type: actrix_common::ServiceType::Turn,
+                    domain_name: "turn:example.com".to_string(),
+                    port_info: "3478".to_string(),
+                    status: ServiceState::Error("bind failed".to_string()),
+                    description: None,
+                },
+            )
+            .await;
+
+        let health = collect_service_health(&service_collector).await;
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0].name, "turn-service");
+        assert!(!health[0].is_healthy);
+        assert_eq!(health[0].state, "error: bind failed");
+    }
+
+    #[test]
+    fn test_collect_realm_usage() {
+        let realm_id = 4_000_000_001;
+        usage::record_registration(realm_id);
+        usage::record_turn_bytes(realm_id, 512);
+
+        let reported = collect_realm_usage();
+        let realm = reported.iter().find(|u| u.realm_id == realm_id).unwrap();
+        assert_eq!(realm.registrations, 1);
+        assert_eq!(realm.turn_bytes, 512);
+    }
 }
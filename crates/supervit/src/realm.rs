@@ -1,7 +1,9 @@
 use crate::error::SupervitError;
-use actrix_common::realm::{Realm, RealmConfig};
+use actrix_common::realm::{
+    Realm, RealmConfig, RealmLifecycleEvent, RealmQuota, RealmRateLimits, RealmStatus,
+};
 use actrix_common::storage::is_database_initialized;
-use actrix_proto::{RealmInfo, ResourceType};
+use actrix_proto::{RealmInfo, RealmQuotaInfo, RealmRateLimitInfo, ResourceType};
 use chrono::Utc;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -21,6 +23,10 @@ pub struct RealmMetadata {
     pub use_servers: Vec<ResourceType>,
     /// Realm version assigned by Boss for sync tracking
     pub version: u64,
+    /// Per-realm signaling rate limits (stored under `ratelimit.*` keys)
+    pub rate_limits: RealmRateLimits,
+    /// Per-realm quotas (stored under `quota.*` keys)
+    pub quota: RealmQuota,
 }
 
 /// Convert a realm record and metadata into proto RealmInfo
@@ -39,19 +45,144 @@ pub fn realm_to_proto(realm: &Realm, metadata: &RealmMetadata) -> RealmInfo {
         version: metadata.version,
         expires_at: realm.expires_at.unwrap_or(0) as u64,
         status: realm.status.clone(),
+        rate_limits: (!metadata.rate_limits.is_empty())
+            .then(|| rate_limits_to_proto(&metadata.rate_limits)),
+        quota: (!metadata.quota.is_empty()).then(|| quota_to_proto(&metadata.quota)),
     }
 }
 
+/// Convert realm rate limits into proto RealmRateLimitInfo
+pub fn rate_limits_to_proto(limits: &RealmRateLimits) -> RealmRateLimitInfo {
+    RealmRateLimitInfo {
+        registrations_per_minute: limits.registrations_per_minute,
+        relays_per_second: limits.relays_per_second,
+        discovery_per_second: limits.discovery_per_second,
+    }
+}
+
+/// Convert proto RealmRateLimitInfo into realm rate limits
+pub fn rate_limits_from_proto(info: &RealmRateLimitInfo) -> RealmRateLimits {
+    RealmRateLimits {
+        registrations_per_minute: info.registrations_per_minute,
+        relays_per_second: info.relays_per_second,
+        discovery_per_second: info.discovery_per_second,
+    }
+}
+
+/// Convert realm quotas into proto RealmQuotaInfo
+pub fn quota_to_proto(quota: &RealmQuota) -> RealmQuotaInfo {
+    RealmQuotaInfo {
+        max_concurrent_actors: quota.max_concurrent_actors,
+        max_registrations: quota.max_registrations,
+    }
+}
+
+/// Convert proto RealmQuotaInfo into realm quotas
+pub fn quota_from_proto(info: &RealmQuotaInfo) -> RealmQuota {
+    RealmQuota {
+        max_concurrent_actors: info.max_concurrent_actors,
+        max_registrations: info.max_registrations,
+    }
+}
+
+/// Convert a realm lifecycle event into its proto form for status reports
+pub fn lifecycle_event_to_proto(event: &RealmLifecycleEvent) -> actrix_proto::RealmLifecycleEvent {
+    actrix_proto::RealmLifecycleEvent {
+        realm_id: event.realm_id,
+        previous_status: event.previous.to_string(),
+        status: event.current.to_string(),
+        reason: event.reason.clone(),
+        timestamp: event.timestamp,
+    }
+}
+
+/// Apply a realm snapshot pushed by the Supervisor (create or update)
+///
+/// Returns false when the local copy is already at the same or a newer version.
+pub async fn apply_realm_info(info: &RealmInfo) -> Result<bool, SupervitError> {
+    let existing = Realm::get_by_realm_id(info.realm_id)
+        .await
+        .map_err(|e| SupervitError::Internal(format!("Failed to load realm: {e}")))?;
+
+    let mut realm = match existing {
+        Some(realm) => {
+            if let Some(rowid) = realm.rowid
+                && info.version > 0
+                && load_version(rowid).await? >= info.version
+            {
+                debug!(
+                    "Skip realm {} update: local version is not older than {}",
+                    info.realm_id, info.version
+                );
+                return Ok(false);
+            }
+            realm
+        }
+        None => Realm::new(info.realm_id, info.name.clone()),
+    };
+
+    realm.set_name(info.name.clone());
+    realm.set_expires_at((info.expires_at > 0).then_some(info.expires_at as i64));
+    match RealmStatus::from_str(&info.status) {
+        Ok(status) => realm.set_status(status),
+        Err(_) => warn!(
+            "Unknown realm status {:?} for realm {}, keeping {}",
+            info.status, info.realm_id, realm.status
+        ),
+    }
+    realm
+        .save()
+        .await
+        .map_err(|e| SupervitError::Internal(format!("Failed to save realm: {e}")))?;
+
+    let rowid = realm.rowid.ok_or_else(|| {
+        SupervitError::Internal(format!(
+            "Realm missing rowid for realm_id {}",
+            info.realm_id
+        ))
+    })?;
+    let metadata = RealmMetadata {
+        enabled: info.enabled,
+        use_servers: info
+            .use_servers
+            .iter()
+            .filter_map(|v| ResourceType::try_from(*v).ok())
+            .collect(),
+        version: info.version,
+        rate_limits: info
+            .rate_limits
+            .as_ref()
+            .map(rate_limits_from_proto)
+            .unwrap_or_default(),
+        quota: info
+            .quota
+            .as_ref()
+            .map(quota_from_proto)
+            .unwrap_or_default(),
+    };
+    persist_realm_metadata(rowid, &metadata).await?;
+
+    Ok(true)
+}
+
 /// Load realm metadata from RealmConfig table
 pub async fn load_realm_metadata(realm_rowid: i64) -> Result<RealmMetadata, SupervitError> {
     let enabled = load_enabled_flag(realm_rowid).await?;
     let use_servers = load_use_servers(realm_rowid).await?;
     let version = load_version(realm_rowid).await?;
+    let rate_limits = RealmRateLimits::load(realm_rowid)
+        .await
+        .map_err(|e| SupervitError::Internal(format!("Failed to load realm rate limits: {e}")))?;
+    let quota = RealmQuota::load(realm_rowid)
+        .await
+        .map_err(|e| SupervitError::Internal(format!("Failed to load realm quota: {e}")))?;
 
     Ok(RealmMetadata {
         enabled,
         use_servers,
         version,
+        rate_limits,
+        quota,
     })
 }
 
@@ -67,6 +198,16 @@ pub async fn persist_realm_metadata(
 
     upsert_config_value(realm_rowid, REALM_VERSION_KEY, metadata.version.to_string()).await?;
 
+    metadata.rate_limits.save(realm_rowid).await.map_err(|e| {
+        SupervitError::Internal(format!("Failed to persist realm rate limits: {e}"))
+    })?;
+
+    metadata
+        .quota
+        .save(realm_rowid)
+        .await
+        .map_err(|e| SupervitError::Internal(format!("Failed to persist realm quota: {e}")))?;
+
     Ok(())
 }
 
@@ -1,32 +1,84 @@
 use crate::error::Result as SupervitResult;
-use crate::metrics::collect_system_metrics;
-use crate::realm::{RealmMetadata, load_realm_metadata, persist_realm_metadata, realm_to_proto};
+use crate::logs::{self, LogBuffer, LogRecord};
+use crate::metrics::{DEFAULT_SAMPLE_INTERVAL, MetricsCollector};
+use crate::realm::{
+    RealmMetadata, load_realm_metadata, persist_realm_metadata, quota_from_proto,
+    rate_limits_from_proto, realm_to_proto,
+};
 use actrix_common::ServiceCollector;
-use actrix_common::realm::{Realm, RealmConfig};
+use actrix_common::realm::{Realm, RealmApiKey, RealmApiScope, RealmConfig, RealmError};
 use actrix_proto::SupervisedService;
 use actrix_proto::{
-    ConfigType, CreateRealmRequest, CreateRealmResponse, DeleteRealmRequest, DeleteRealmResponse,
-    GetConfigRequest, GetConfigResponse, GetNodeInfoRequest, GetNodeInfoResponse, GetRealmRequest,
-    GetRealmResponse, ListRealmsRequest, ListRealmsResponse, RealmInfo, ResourceType,
-    ServiceStatus, ShutdownRequest, ShutdownResponse, SystemMetrics, UpdateConfigRequest,
+    BroadcastServerNoticeRequest, BroadcastServerNoticeResponse, ConfigType, ConnectedActor,
+    CreateRealmApiKeyRequest, CreateRealmApiKeyResponse, CreateRealmRequest, CreateRealmResponse,
+    DeleteRealmRequest, DeleteRealmResponse, DisconnectActorRequest, DisconnectActorResponse,
+    DryRunReport, GetConfigRequest, GetConfigResponse, GetNodeInfoRequest, GetNodeInfoResponse,
+    GetRealmRequest, GetRealmResponse, GetServiceSpecHistoryRequest, GetServiceSpecHistoryResponse,
+    ListConnectionsRequest, ListConnectionsResponse, ListRealmApiKeysRequest,
+    ListRealmApiKeysResponse, ListRealmsRequest, ListRealmsResponse, LogEntry, NodeCapabilities,
+    RealmApiKeyInfo, RealmInfo, ResourceType, RevokeRealmApiKeyRequest, RevokeRealmApiKeyResponse,
+    ServiceSpecVersion, ServiceStatus, SetServiceEnabledRequest, SetServiceEnabledResponse,
+    ShutdownRequest, ShutdownResponse, StreamLogsRequest, SystemMetrics, UpdateConfigRequest,
     UpdateConfigResponse, UpdateRealmRequest, UpdateRealmResponse,
 };
 use chrono::Utc;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast, mpsc};
+use tokio_stream::Stream;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
-use tracing::warn;
+use tracing::{Level, warn};
 
 type MetricsFuture = Pin<Box<dyn Future<Output = SupervitResult<SystemMetrics>> + Send>>;
 type MetricsProvider = Arc<dyn Fn() -> MetricsFuture + Send + Sync>;
 type ShutdownFuture = Pin<Box<dyn Future<Output = SupervitResult<()>> + Send>>;
 type ShutdownHandler =
     Arc<dyn Fn(bool, Option<i32>, Option<String>) -> ShutdownFuture + Send + Sync>;
+type ServiceToggleFuture = Pin<Box<dyn Future<Output = SupervitResult<()>> + Send>>;
+type ServiceToggleHandler = Arc<dyn Fn(ResourceType, bool) -> ServiceToggleFuture + Send + Sync>;
+type ConnectionsFuture = Pin<Box<dyn Future<Output = SupervitResult<Vec<ConnectedActor>>> + Send>>;
+type ConnectionsProvider = Arc<dyn Fn(Option<u32>) -> ConnectionsFuture + Send + Sync>;
+type DisconnectFuture = Pin<Box<dyn Future<Output = SupervitResult<bool>> + Send>>;
+type DisconnectHandler = Arc<dyn Fn(String, Option<String>) -> DisconnectFuture + Send + Sync>;
+type NoticeFuture = Pin<Box<dyn Future<Output = SupervitResult<(String, u32)>> + Send>>;
+type NoticeHandler = Arc<dyn Fn(BroadcastServerNoticeRequest) -> NoticeFuture + Send + Sync>;
+type SpecHistoryFuture =
+    Pin<Box<dyn Future<Output = SupervitResult<Vec<ServiceSpecVersion>>> + Send>>;
+type SpecHistoryProvider =
+    Arc<dyn Fn(String, Option<String>, Option<u32>) -> SpecHistoryFuture + Send + Sync>;
 type GrpcResult<T> = std::result::Result<T, Status>;
+type LogStream = Pin<Box<dyn Stream<Item = GrpcResult<LogEntry>> + Send>>;
+
+/// StreamLogs 未指定 limit 时先发送的近期日志条数
+const DEFAULT_LOG_LIMIT: usize = 200;
+
+fn log_record_to_proto(record: LogRecord) -> LogEntry {
+    LogEntry {
+        sequence: record.sequence,
+        timestamp_ms: record.timestamp_ms,
+        level: record.level.to_string(),
+        target: record.target,
+        message: record.message,
+    }
+}
+
+fn api_key_to_proto(key: RealmApiKey) -> RealmApiKeyInfo {
+    RealmApiKeyInfo {
+        key_id: key.key_id,
+        realm_id: key.realm_id,
+        name: key.name,
+        scopes: key.scopes.iter().map(|s| s.to_string()).collect(),
+        created_at: key.created_at,
+        expires_at: key.expires_at,
+        last_used_at: key.last_used_at,
+        revoked: key.revoked,
+    }
+}
 
 #[derive(Hash, Eq, PartialEq, Clone)]
 struct ConfigKey {
@@ -46,7 +98,14 @@ pub struct Supervisord {
     config_store: Arc<RwLock<HashMap<ConfigKey, String>>>,
     metrics_provider: MetricsProvider,
     shutdown_handler: Option<ShutdownHandler>,
+    service_toggle_handler: Option<ServiceToggleHandler>,
+    connections_provider: Option<ConnectionsProvider>,
+    disconnect_handler: Option<DisconnectHandler>,
+    notice_handler: Option<NoticeHandler>,
+    spec_history_provider: Option<SpecHistoryProvider>,
+    log_buffer: LogBuffer,
     service_collector: ServiceCollector,
+    capabilities: NodeCapabilities,
     started_at: Instant,
 }
 
@@ -59,15 +118,27 @@ impl Supervisord {
         version: impl Into<String>,
         service_collector: ServiceCollector,
     ) -> SupervitResult<Self> {
+        let metrics = MetricsCollector::new(DEFAULT_SAMPLE_INTERVAL)
+            .with_service_collector(service_collector.clone());
         Ok(Self {
             node_id: node_id.into(),
             name: name.into(),
             location_tag: location_tag.into(),
             version: version.into(),
             config_store: Arc::new(RwLock::new(HashMap::new())),
-            metrics_provider: Arc::new(|| Box::pin(async { collect_system_metrics().await })),
+            metrics_provider: Arc::new(move || {
+                let metrics = metrics.clone();
+                Box::pin(async move { metrics.collect().await })
+            }),
             shutdown_handler: None,
+            service_toggle_handler: None,
+            connections_provider: None,
+            disconnect_handler: None,
+            notice_handler: None,
+            spec_history_provider: None,
+            log_buffer: logs::global().clone(),
             service_collector,
+            capabilities: NodeCapabilities::default(),
             started_at: Instant::now(),
         })
     }
@@ -98,6 +169,97 @@ impl Supervisord {
         self
     }
 
+    /// Attach a handler starting or stopping a service for SetServiceEnabled.
+    ///
+    /// The handler receives the service and whether it should run, and returns
+    /// once the service is started (listening / mounted) or stopped.
+    pub fn with_service_toggle_handler<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(ResourceType, bool) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SupervitResult<()>> + Send + 'static,
+    {
+        self.service_toggle_handler = Some(Arc::new(move |service, enabled| {
+            let fut = handler(service, enabled);
+            Box::pin(fut)
+        }));
+        self
+    }
+
+    /// Attach a provider listing signaling connections for ListConnections.
+    ///
+    /// The provider receives the optional realm filter from the request.
+    pub fn with_connections_provider<F, Fut>(mut self, provider: F) -> Self
+    where
+        F: Fn(Option<u32>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SupervitResult<Vec<ConnectedActor>>> + Send + 'static,
+    {
+        self.connections_provider = Some(Arc::new(move |realm_id| {
+            let fut = provider(realm_id);
+            Box::pin(fut)
+        }));
+        self
+    }
+
+    /// Attach a handler force-disconnecting an actor for DisconnectActor.
+    ///
+    /// The handler receives the ActrId string repr and the optional reason, and
+    /// returns whether a live connection was closed.
+    pub fn with_disconnect_handler<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(String, Option<String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SupervitResult<bool>> + Send + 'static,
+    {
+        self.disconnect_handler = Some(Arc::new(move |actor_id, reason| {
+            let fut = handler(actor_id, reason);
+            Box::pin(fut)
+        }));
+        self
+    }
+
+    /// Attach a handler broadcasting a server notice for BroadcastServerNotice.
+    ///
+    /// The handler receives the request and returns the assigned notice id and
+    /// the number of connections notified.
+    pub fn with_notice_handler<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(BroadcastServerNoticeRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SupervitResult<(String, u32)>> + Send + 'static,
+    {
+        self.notice_handler = Some(Arc::new(move |request| {
+            let fut = handler(request);
+            Box::pin(fut)
+        }));
+        self
+    }
+
+    /// Attach a provider returning ServiceSpec history for GetServiceSpecHistory.
+    ///
+    /// The provider receives the service name, the optional fingerprint filter and
+    /// the optional limit from the request, and returns versions newest first.
+    pub fn with_spec_history_provider<F, Fut>(mut self, provider: F) -> Self
+    where
+        F: Fn(String, Option<String>, Option<u32>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SupervitResult<Vec<ServiceSpecVersion>>> + Send + 'static,
+    {
+        self.spec_history_provider = Some(Arc::new(move |service_name, fingerprint, limit| {
+            let fut = provider(service_name, fingerprint, limit);
+            Box::pin(fut)
+        }));
+        self
+    }
+
+    /// Override the log buffer served by StreamLogs (defaults to the process-wide buffer).
+    pub fn with_log_buffer(mut self, buffer: LogBuffer) -> Self {
+        self.log_buffer = buffer;
+        self
+    }
+
+    /// Set the build features and active subsystems reported by GetNodeInfo.
+    pub fn with_capabilities(mut self, capabilities: NodeCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
     fn build_config_key(config_type: ConfigType, key: String) -> ConfigKey {
         ConfigKey {
             config_type: config_type as i32,
@@ -168,6 +330,36 @@ impl Supervisord {
         Ok(())
     }
 
+    /// Count actors currently connected to signaling in the given realm.
+    ///
+    /// Returns None when no connections provider is attached or it fails.
+    async fn count_realm_actors(&self, realm_id: u32) -> Option<u32> {
+        let provider = self.connections_provider.as_ref()?;
+        match provider(Some(realm_id)).await {
+            Ok(connections) => Some(connections.len() as u32),
+            Err(e) => {
+                warn!(
+                    "Failed to count connected actors for dry run (realm_id={}): {}",
+                    realm_id, e
+                );
+                None
+            }
+        }
+    }
+
+    async fn dry_run_report(
+        &self,
+        realm_id: u32,
+        conflicts: Vec<String>,
+        changes: Vec<String>,
+    ) -> DryRunReport {
+        DryRunReport {
+            conflicts,
+            changes,
+            affected_actors: self.count_realm_actors(realm_id).await,
+        }
+    }
+
     async fn collect_metrics(&self) -> GrpcResult<SystemMetrics> {
         (self.metrics_provider)()
             .await
@@ -245,6 +437,42 @@ impl SupervisedService for Supervisord {
         let mut realm =
             Realm::new(req.realm_id, req.name.clone()).with_expires_at(req.expires_at as i64);
 
+        let metadata = RealmMetadata {
+            enabled: req.enabled,
+            use_servers,
+            version: req.version,
+            rate_limits: req
+                .rate_limits
+                .as_ref()
+                .map(rate_limits_from_proto)
+                .unwrap_or_default(),
+            quota: req.quota.as_ref().map(quota_from_proto).unwrap_or_default(),
+        };
+
+        if req.dry_run.unwrap_or(false) {
+            let existing = Realm::get_by_realm_id(req.realm_id)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to load realm: {e}")))?;
+
+            let mut conflicts = Vec::new();
+            if existing.is_some() {
+                conflicts.push(format!("Realm {} already exists", req.realm_id));
+            }
+            let changes = vec![format!(
+                "create realm {} (name={}, enabled={}, version={}, expires_at={})",
+                req.realm_id, req.name, req.enabled, req.version, req.expires_at
+            )];
+
+            let report = self.dry_run_report(req.realm_id, conflicts, changes).await;
+            let response = CreateRealmResponse {
+                success: report.conflicts.is_empty(),
+                error_message: (!report.conflicts.is_empty()).then(|| report.conflicts.join("; ")),
+                realm: Some(realm_to_proto(&realm, &metadata)),
+                dry_run_report: Some(report),
+            };
+            return Ok(Response::new(response));
+        }
+
         let save_result = realm.save().await;
 
         if let Err(err) = save_result {
@@ -252,16 +480,11 @@ impl SupervisedService for Supervisord {
                 success: false,
                 error_message: Some(format!("Failed to create realm: {err}")),
                 realm: None,
+                dry_run_report: None,
             };
             return Ok(Response::new(resp));
         }
 
-        let metadata = RealmMetadata {
-            enabled: req.enabled,
-            use_servers,
-            version: req.version,
-        };
-
         if let Err(status) = self.persist_metadata_for(&realm, &metadata).await {
             let err_msg = status.message().to_string();
             warn!("Realm created but metadata persistence failed: {}", err_msg);
@@ -284,6 +507,7 @@ impl SupervisedService for Supervisord {
                 success: false,
                 error_message: Some(format!("Failed to persist realm metadata: {err_msg}")),
                 realm: None,
+                dry_run_report: None,
             };
             return Ok(Response::new(response));
         }
@@ -294,6 +518,7 @@ impl SupervisedService for Supervisord {
             success: true,
             error_message: None,
             realm: Some(realm_info),
+            dry_run_report: None,
         };
 
         Ok(Response::new(response))
@@ -332,15 +557,26 @@ impl SupervisedService for Supervisord {
         request: Request<UpdateRealmRequest>,
     ) -> GrpcResult<Response<UpdateRealmResponse>> {
         let req = request.into_inner();
+        let dry_run = req.dry_run.unwrap_or(false);
 
         let realm_loaded = self.get_realm(req.realm_id).await;
         let (mut realm, mut metadata) = match realm_loaded {
             Ok(data) => data,
             Err(status) if status.code() == tonic::Code::NotFound => {
+                let message = status.message().to_string();
+                let dry_run_report = if dry_run {
+                    Some(
+                        self.dry_run_report(req.realm_id, vec![message.clone()], vec![])
+                            .await,
+                    )
+                } else {
+                    None
+                };
                 let response = UpdateRealmResponse {
                     success: false,
-                    error_message: Some(status.message().to_string()),
+                    error_message: Some(message),
                     realm: None,
+                    dry_run_report,
                 };
                 return Ok(Response::new(response));
             }
@@ -356,6 +592,45 @@ impl SupervisedService for Supervisord {
         if let Some(enabled) = req.enabled {
             metadata.enabled = enabled;
         }
+        if let Some(rate_limits) = &req.rate_limits {
+            metadata.rate_limits = rate_limits_from_proto(rate_limits);
+        }
+        if let Some(quota) = &req.quota {
+            metadata.quota = quota_from_proto(quota);
+        }
+
+        if dry_run {
+            let mut changes = Vec::new();
+            if realm.name != original_realm.name {
+                changes.push(format!("name: {} -> {}", original_realm.name, realm.name));
+            }
+            if metadata.enabled != original_metadata.enabled {
+                changes.push(format!(
+                    "enabled: {} -> {}",
+                    original_metadata.enabled, metadata.enabled
+                ));
+            }
+            if metadata.rate_limits != original_metadata.rate_limits {
+                changes.push(format!(
+                    "rate_limits: {:?} -> {:?}",
+                    original_metadata.rate_limits, metadata.rate_limits
+                ));
+            }
+            if metadata.quota != original_metadata.quota {
+                changes.push(format!(
+                    "quota: {:?} -> {:?}",
+                    original_metadata.quota, metadata.quota
+                ));
+            }
+
+            let response = UpdateRealmResponse {
+                success: true,
+                error_message: None,
+                realm: Some(realm_to_proto(&realm, &metadata)),
+                dry_run_report: Some(self.dry_run_report(req.realm_id, vec![], changes).await),
+            };
+            return Ok(Response::new(response));
+        }
 
         let save_result = realm.save().await;
         if let Err(err) = save_result {
@@ -363,6 +638,7 @@ impl SupervisedService for Supervisord {
                 success: false,
                 error_message: Some(format!("Failed to update realm: {err}")),
                 realm: None,
+                dry_run_report: None,
             };
             return Ok(Response::new(response));
         }
@@ -393,6 +669,7 @@ impl SupervisedService for Supervisord {
                 success: false,
                 error_message: Some(format!("Failed to persist realm metadata: {err_msg}")),
                 realm: None,
+                dry_run_report: None,
             };
             return Ok(Response::new(response));
         }
@@ -401,6 +678,7 @@ impl SupervisedService for Supervisord {
             success: true,
             error_message: None,
             realm: Some(realm_to_proto(&realm, &metadata)),
+            dry_run_report: None,
         };
 
         Ok(Response::new(response))
@@ -416,14 +694,46 @@ impl SupervisedService for Supervisord {
             .await
             .map_err(|e| Status::internal(format!("Failed to load realm: {e}")))?;
 
+        let dry_run = req.dry_run.unwrap_or(false);
+
         let Some(realm) = realm else {
+            let dry_run_report = if dry_run {
+                Some(
+                    self.dry_run_report(req.realm_id, vec!["Realm not found".to_string()], vec![])
+                        .await,
+                )
+            } else {
+                None
+            };
             let response = DeleteRealmResponse {
                 success: false,
                 error_message: Some("Realm not found".to_string()),
+                dry_run_report,
             };
             return Ok(Response::new(response));
         };
 
+        if dry_run {
+            let config_count = match realm.rowid {
+                Some(rowid) => RealmConfig::get_by_realm(rowid)
+                    .await
+                    .map_err(|e| Status::internal(format!("Failed to load realm configs: {e}")))?
+                    .len(),
+                None => 0,
+            };
+            let changes = vec![
+                format!("delete realm {} ({})", realm.realm_id, realm.name),
+                format!("delete {config_count} realm config entries"),
+            ];
+
+            let response = DeleteRealmResponse {
+                success: true,
+                error_message: None,
+                dry_run_report: Some(self.dry_run_report(req.realm_id, vec![], changes).await),
+            };
+            return Ok(Response::new(response));
+        }
+
         let delete_result = Realm::delete_instance(req.realm_id).await;
 
         match delete_result {
@@ -432,6 +742,7 @@ impl SupervisedService for Supervisord {
                 let response = DeleteRealmResponse {
                     success: true,
                     error_message: None,
+                    dry_run_report: None,
                 };
                 Ok(Response::new(response))
             }
@@ -439,6 +750,7 @@ impl SupervisedService for Supervisord {
                 let response = DeleteRealmResponse {
                     success: false,
                     error_message: Some("Realm not found".to_string()),
+                    dry_run_report: None,
                 };
                 Ok(Response::new(response))
             }
@@ -446,6 +758,7 @@ impl SupervisedService for Supervisord {
                 let response = DeleteRealmResponse {
                     success: false,
                     error_message: Some(format!("Failed to delete realm: {err}")),
+                    dry_run_report: None,
                 };
                 Ok(Response::new(response))
             }
@@ -488,6 +801,95 @@ impl SupervisedService for Supervisord {
         Ok(Response::new(response))
     }
 
+    async fn create_realm_api_key(
+        &self,
+        request: Request<CreateRealmApiKeyRequest>,
+    ) -> GrpcResult<Response<CreateRealmApiKeyResponse>> {
+        let req = request.into_inner();
+
+        let failure = |error_message: String| CreateRealmApiKeyResponse {
+            success: false,
+            error_message: Some(error_message),
+            key: None,
+            token: None,
+        };
+
+        let scopes = match RealmApiScope::parse_list(&req.scopes) {
+            Ok(scopes) => scopes,
+            Err(e) => return Ok(Response::new(failure(e.to_string()))),
+        };
+
+        match RealmApiKey::issue(req.realm_id, &req.name, scopes, req.expires_at).await {
+            Ok((key, token)) => {
+                tracing::info!(
+                    "Issued realm API key {} for realm {}",
+                    key.key_id,
+                    req.realm_id
+                );
+                Ok(Response::new(CreateRealmApiKeyResponse {
+                    success: true,
+                    error_message: None,
+                    key: Some(api_key_to_proto(key)),
+                    token: Some(token),
+                }))
+            }
+            Err(RealmError::NotFound) => Ok(Response::new(failure("Realm not found".to_string()))),
+            Err(RealmError::ValidationError(msg)) => Ok(Response::new(failure(msg))),
+            Err(e) => Err(Status::internal(format!(
+                "Failed to issue realm API key: {e}"
+            ))),
+        }
+    }
+
+    async fn list_realm_api_keys(
+        &self,
+        request: Request<ListRealmApiKeysRequest>,
+    ) -> GrpcResult<Response<ListRealmApiKeysResponse>> {
+        let req = request.into_inner();
+
+        let keys = RealmApiKey::list_by_realm(req.realm_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to load realm API keys: {e}")))?;
+
+        Ok(Response::new(ListRealmApiKeysResponse {
+            success: true,
+            error_message: None,
+            keys: keys.into_iter().map(api_key_to_proto).collect(),
+        }))
+    }
+
+    async fn revoke_realm_api_key(
+        &self,
+        request: Request<RevokeRealmApiKeyRequest>,
+    ) -> GrpcResult<Response<RevokeRealmApiKeyResponse>> {
+        let req = request.into_inner();
+
+        let response = match RealmApiKey::revoke(req.realm_id, &req.key_id).await {
+            Ok(()) => {
+                tracing::info!(
+                    "Revoked realm API key {} for realm {}",
+                    req.key_id,
+                    req.realm_id
+                );
+                RevokeRealmApiKeyResponse {
+                    success: true,
+                    error_message: None,
+                }
+            }
+            Err(RealmError::KeyNotExist) => RevokeRealmApiKeyResponse {
+                success: false,
+                error_message: Some("API key not found".to_string()),
+            },
+            Err(e) => {
+                return Err(Status::internal(format!(
+                    "Failed to revoke realm API key: {e}"
+                )));
+            }
+        };
+
+        Ok(Response::new(response))
+    }
+
     async fn get_node_info(
         &self,
         request: Request<GetNodeInfoRequest>,
@@ -509,6 +911,7 @@ impl SupervisedService for Supervisord {
             uptime_secs,
             current_metrics: Some(metrics),
             services,
+            capabilities: Some(self.capabilities.clone()),
         };
 
         Ok(Response::new(response))
@@ -547,4 +950,238 @@ impl SupervisedService for Supervisord {
 
         Ok(Response::new(response))
     }
+
+    async fn set_service_enabled(
+        &self,
+        request: Request<SetServiceEnabledRequest>,
+    ) -> GrpcResult<Response<SetServiceEnabledResponse>> {
+        let req = request.into_inner();
+        let service = req.service();
+        let action = if req.enabled { "enable" } else { "disable" };
+
+        let Some(handler) = &self.service_toggle_handler else {
+            let response = SetServiceEnabledResponse {
+                success: false,
+                error_message: Some("Runtime service control is not available".to_string()),
+                services: self.service_statuses().await,
+            };
+            return Ok(Response::new(response));
+        };
+
+        warn!("Service {} requested: {:?}", action, service);
+
+        let error_message = handler(service, req.enabled)
+            .await
+            .err()
+            .map(|e| format!("Failed to {action} {service:?}: {e}"));
+        let response = SetServiceEnabledResponse {
+            success: error_message.is_none(),
+            error_message,
+            services: self.service_statuses().await,
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn list_connections(
+        &self,
+        request: Request<ListConnectionsRequest>,
+    ) -> GrpcResult<Response<ListConnectionsResponse>> {
+        let req = request.into_inner();
+
+        let Some(provider) = &self.connections_provider else {
+            let response = ListConnectionsResponse {
+                success: false,
+                error_message: Some("Signaling service is not running on this node".to_string()),
+                connections: vec![],
+            };
+            return Ok(Response::new(response));
+        };
+
+        let response = match provider(req.realm_id).await {
+            Ok(connections) => ListConnectionsResponse {
+                success: true,
+                error_message: None,
+                connections,
+            },
+            Err(e) => ListConnectionsResponse {
+                success: false,
+                error_message: Some(format!("Failed to list connections: {e}")),
+                connections: vec![],
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn disconnect_actor(
+        &self,
+        request: Request<DisconnectActorRequest>,
+    ) -> GrpcResult<Response<DisconnectActorResponse>> {
+        let req = request.into_inner();
+
+        let Some(handler) = &self.disconnect_handler else {
+            let response = DisconnectActorResponse {
+                success: false,
+                error_message: Some("Signaling service is not running on this node".to_string()),
+                disconnected: false,
+            };
+            return Ok(Response::new(response));
+        };
+
+        if let Some(reason) = &req.reason {
+            warn!(
+                "Force disconnect requested for {}: {}",
+                req.actor_id, reason
+            );
+        } else {
+            warn!("Force disconnect requested for {}", req.actor_id);
+        }
+
+        let response = match handler(req.actor_id, req.reason).await {
+            Ok(disconnected) => DisconnectActorResponse {
+                success: true,
+                error_message: None,
+                disconnected,
+            },
+            Err(e) => DisconnectActorResponse {
+                success: false,
+                error_message: Some(format!("Disconnect handler failed: {e}")),
+                disconnected: false,
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn broadcast_server_notice(
+        &self,
+        request: Request<BroadcastServerNoticeRequest>,
+    ) -> GrpcResult<Response<BroadcastServerNoticeResponse>> {
+        let req = request.into_inner();
+
+        let Some(handler) = &self.notice_handler else {
+            let response = BroadcastServerNoticeResponse {
+                success: false,
+                error_message: Some("Signaling service is not running on this node".to_string()),
+                notice_id: None,
+                delivered: 0,
+            };
+            return Ok(Response::new(response));
+        };
+
+        warn!(
+            "Server notice requested: severity={} category={}",
+            req.severity, req.category
+        );
+
+        let response = match handler(req).await {
+            Ok((notice_id, delivered)) => BroadcastServerNoticeResponse {
+                success: true,
+                error_message: None,
+                notice_id: Some(notice_id),
+                delivered,
+            },
+            Err(e) => BroadcastServerNoticeResponse {
+                success: false,
+                error_message: Some(format!("Notice handler failed: {e}")),
+                notice_id: None,
+                delivered: 0,
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_service_spec_history(
+        &self,
+        request: Request<GetServiceSpecHistoryRequest>,
+    ) -> GrpcResult<Response<GetServiceSpecHistoryResponse>> {
+        let req = request.into_inner();
+
+        let Some(provider) = &self.spec_history_provider else {
+            let response = GetServiceSpecHistoryResponse {
+                success: false,
+                error_message: Some("Signaling service is not running on this node".to_string()),
+                versions: vec![],
+            };
+            return Ok(Response::new(response));
+        };
+
+        let response = match provider(req.service_name, req.fingerprint, req.limit).await {
+            Ok(versions) => GetServiceSpecHistoryResponse {
+                success: true,
+                error_message: None,
+                versions,
+            },
+            Err(e) => GetServiceSpecHistoryResponse {
+                success: false,
+                error_message: Some(format!("Failed to get spec history: {e}")),
+                versions: vec![],
+            },
+        };
+
+        Ok(Response::new(response))
+    }
+
+    type StreamLogsStream = LogStream;
+
+    async fn stream_logs(
+        &self,
+        request: Request<StreamLogsRequest>,
+    ) -> GrpcResult<Response<Self::StreamLogsStream>> {
+        let req = request.into_inner();
+        let min_level = match req.min_level.as_deref() {
+            Some(level) => Level::from_str(level)
+                .map_err(|_| Status::invalid_argument(format!("Invalid log level: {level}")))?,
+            None => Level::INFO,
+        };
+        let limit = req
+            .limit
+            .map(|limit| limit as usize)
+            .unwrap_or(DEFAULT_LOG_LIMIT)
+            .min(self.log_buffer.capacity());
+        let target = req.target.filter(|target| !target.is_empty());
+        let follow = req.follow.unwrap_or(false);
+
+        // 先订阅再读取近期日志，避免两者之间的日志丢失；重复的记录按序号跳过
+        let mut live = follow.then(|| self.log_buffer.subscribe());
+        let recent = self.log_buffer.recent(min_level, target.as_deref(), limit);
+        let (tx, rx) = mpsc::channel(256);
+
+        tokio::spawn(async move {
+            let mut last_sequence = 0;
+            for record in recent {
+                last_sequence = record.sequence;
+                if tx.send(Ok(log_record_to_proto(record))).await.is_err() {
+                    return;
+                }
+            }
+            let Some(live) = live.as_mut() else {
+                return;
+            };
+            loop {
+                let record = match live.recv().await {
+                    Ok(record) => record,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("StreamLogs follower lagged, skipped {} entries", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                };
+                if record.sequence <= last_sequence
+                    || !logs::matches(&record, min_level, target.as_deref())
+                {
+                    continue;
+                }
+                last_sequence = record.sequence;
+                if tx.send(Ok(log_record_to_proto(record))).await.is_err() {
+                    // Supervisor 断开
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
 }
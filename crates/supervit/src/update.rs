@@ -0,0 +1,560 @@
+//! 节点自更新（UPDATE 指令）
+//!
+//! Supervisor 通过 `UPDATE` 指令推送签名的二进制或配置包，[`SelfUpdater`] 负责：
+//! 1. 下载（限制大小）
+//! 2. 校验 SHA-256 与 Ed25519 签名：签名覆盖规范清单 `<kind>|<version>|<sha256>`
+//!    （见 [`signing_manifest`]），包类型与版本无法被替换，CONFIG 包不能被重放为 BINARY
+//! 3. 拒绝不高于当前版本的包（BINARY 与运行中的版本及上次安装的版本比较，CONFIG 与上次安装的版本比较），
+//!    防止重放旧包降级
+//! 4. 写入暂存目录，得到 [`StagedUpdate`]
+//!
+//! 安装（替换可执行文件或配置文件）与重启由宿主程序在优雅关闭流程中完成，
+//! 见 [`StagedUpdate::install`]；重启失败时由 [`StagedUpdate::rollback`] 恢复安装前的文件。
+
+use crate::error::{Result, SupervitError};
+use crate::{UpdateBundle, UpdateBundleKind};
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 默认包大小上限（256 MiB）
+pub const DEFAULT_MAX_BUNDLE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// 下载超时
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 已校验并写入暂存目录的更新包
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedUpdate {
+    pub version: String,
+    pub kind: UpdateBundleKind,
+    pub path: PathBuf,
+    /// 记录该类型已安装版本的文件（位于暂存目录）
+    marker: PathBuf,
+}
+
+impl StagedUpdate {
+    /// 用暂存文件替换 `target`，原文件保留为 `<target>.bak`，并记录已安装的版本
+    ///
+    /// 暂存目录与目标不在同一文件系统时退化为复制
+    pub fn install(&self, target: &Path) -> Result<()> {
+        back_up(target)?;
+        if std::fs::rename(&self.path, target).is_err() {
+            std::fs::copy(&self.path, target).map_err(|e| {
+                SupervitError::Update(format!("Failed to install {}: {e}", target.display()))
+            })?;
+            let _ = std::fs::remove_file(&self.path);
+        }
+        back_up(&self.marker)?;
+        std::fs::write(&self.marker, &self.version).map_err(|e| {
+            SupervitError::Update(format!("Failed to record {}: {e}", self.marker.display()))
+        })?;
+        info!(
+            "Installed update {} ({:?}) to {}",
+            self.version,
+            self.kind,
+            target.display()
+        );
+        Ok(())
+    }
+
+    /// 用 `<target>.bak` 恢复安装前的文件与版本记录（重启失败时调用）
+    ///
+    /// 安装前不存在的文件被删除
+    pub fn rollback(&self, target: &Path) -> Result<()> {
+        restore(target)?;
+        restore(&self.marker)?;
+        warn!(
+            "Rolled back update {} ({:?}) at {}",
+            self.version,
+            self.kind,
+            target.display()
+        );
+        Ok(())
+    }
+}
+
+fn backup_path(target: &Path) -> PathBuf {
+    let mut name = target.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// 将 `target` 复制为 `<target>.bak`；`target` 不存在时清除旧备份，回滚时据此删除新文件
+fn back_up(target: &Path) -> Result<()> {
+    let backup = backup_path(target);
+    let result = if target.exists() {
+        std::fs::copy(target, &backup).map(|_| ())
+    } else {
+        match std::fs::remove_file(&backup) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    };
+    result
+        .map_err(|e| SupervitError::Update(format!("Failed to back up {}: {e}", target.display())))
+}
+
+/// 用 `<target>.bak` 替换 `target`，没有备份时删除 `target`
+fn restore(target: &Path) -> Result<()> {
+    let backup = backup_path(target);
+    let result = if backup.exists() {
+        std::fs::rename(&backup, target)
+    } else {
+        match std::fs::remove_file(target) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    };
+    result
+        .map_err(|e| SupervitError::Update(format!("Failed to restore {}: {e}", target.display())))
+}
+
+/// 包类型在签名清单与版本记录文件名中的名称
+fn kind_name(kind: UpdateBundleKind) -> Result<&'static str> {
+    match kind {
+        UpdateBundleKind::Binary => Ok("binary"),
+        UpdateBundleKind::Config => Ok("config"),
+        UpdateBundleKind::Unspecified => Err(SupervitError::Update(
+            "Bundle kind is unspecified".to_string(),
+        )),
+    }
+}
+
+/// 更新包签名覆盖的规范清单：`<kind>|<version>|<sha256>`
+///
+/// `kind` 为 `binary` 或 `config`，`sha256` 为包内容摘要的小写 hex；Supervisor 对同样的字节签名
+pub fn signing_manifest(kind: UpdateBundleKind, version: &str, sha256: &str) -> Result<String> {
+    if version.is_empty() || version.contains('|') {
+        return Err(SupervitError::Update(format!(
+            "Invalid bundle version: {version:?}"
+        )));
+    }
+    Ok(format!(
+        "{}|{version}|{}",
+        kind_name(kind)?,
+        sha256.trim().to_ascii_lowercase()
+    ))
+}
+
+/// 比较点分数字版本号（可带 `v` 前缀、`-pre` 后缀与 `+build` 元数据，正式版高于同号预发布版）
+///
+/// 任一版本无法解析时返回 None
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    fn parse(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+        let version = version.trim().trim_start_matches('v');
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let numbers = core
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some((numbers, pre))
+    }
+
+    let (a_numbers, a_pre) = parse(a)?;
+    let (b_numbers, b_pre) = parse(b)?;
+    let len = a_numbers.len().max(b_numbers.len());
+    let component = |numbers: &[u64], i: usize| numbers.get(i).copied().unwrap_or(0);
+    let numeric = (0..len)
+        .map(|i| component(&a_numbers, i).cmp(&component(&b_numbers, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal);
+    Some(numeric.then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(b),
+    }))
+}
+
+/// 更新包下载与校验
+#[derive(Debug, Clone)]
+pub struct SelfUpdater {
+    public_key: VerifyingKey,
+    /// 运行中的可执行文件版本（BINARY 包必须高于此版本）
+    current_version: String,
+    staging_dir: PathBuf,
+    max_bundle_bytes: u64,
+    http: reqwest::Client,
+}
+
+impl SelfUpdater {
+    /// 创建更新器
+    ///
+    /// `public_key_hex` 为 hex 编码的 32 字节 Ed25519 公钥，`current_version` 为运行中的版本
+    pub fn new(
+        public_key_hex: &str,
+        current_version: &str,
+        staging_dir: impl Into<PathBuf>,
+        max_bundle_bytes: u64,
+    ) -> Result<Self> {
+        let key_bytes: [u8; 32] = hex::decode(public_key_hex.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                SupervitError::Config(
+                    "update public key must be 32 bytes (64 hex characters)".to_string(),
+                )
+            })?;
+        let public_key = VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|e| SupervitError::Config(format!("Invalid update public key: {e}")))?;
+        let http = reqwest::Client::builder()
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()
+            .map_err(|e| SupervitError::Internal(format!("Failed to build HTTP client: {e}")))?;
+
+        Ok(Self {
+            public_key,
+            current_version: current_version.to_string(),
+            staging_dir: staging_dir.into(),
+            max_bundle_bytes,
+            http,
+        })
+    }
+
+    /// 下载、校验并暂存更新包
+    pub async fn stage(&self, bundle: &UpdateBundle) -> Result<StagedUpdate> {
+        if !bundle.url.starts_with("http://") && !bundle.url.starts_with("https://") {
+            return Err(SupervitError::Update(format!(
+                "Unsupported bundle URL: {}",
+                bundle.url
+            )));
+        }
+        info!(
+            "Downloading update {} ({:?}) from {}",
+            bundle.version,
+            bundle.kind(),
+            bundle.url
+        );
+        let bytes = self.download(&bundle.url).await?;
+        self.stage_bytes(bundle, &bytes).await
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let mut response = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| SupervitError::Update(format!("Failed to download bundle: {e}")))?;
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| SupervitError::Update(format!("Failed to download bundle: {e}")))?
+        {
+            if (bytes.len() + chunk.len()) as u64 > self.max_bundle_bytes {
+                return Err(SupervitError::Update(format!(
+                    "Bundle exceeds {} bytes",
+                    self.max_bundle_bytes
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(bytes)
+    }
+
+    /// 校验已下载的包内容并写入暂存目录
+    pub async fn stage_bytes(&self, bundle: &UpdateBundle, bytes: &[u8]) -> Result<StagedUpdate> {
+        self.verify(bundle, bytes)?;
+
+        let kind = bundle.kind();
+        let file_name = match kind {
+            UpdateBundleKind::Binary => format!("actrix-{}", sanitize(&bundle.version)),
+            _ => format!("config-{}.toml", sanitize(&bundle.version)),
+        };
+
+        tokio::fs::create_dir_all(&self.staging_dir)
+            .await
+            .map_err(|e| {
+                SupervitError::Update(format!(
+                    "Failed to create staging directory {}: {e}",
+                    self.staging_dir.display()
+                ))
+            })?;
+        let path = self.staging_dir.join(file_name);
+        tokio::fs::write(&path, bytes).await.map_err(|e| {
+            SupervitError::Update(format!("Failed to stage {}: {e}", path.display()))
+        })?;
+
+        #[cfg(unix)]
+        if kind == UpdateBundleKind::Binary {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .await
+                .map_err(|e| {
+                    SupervitError::Update(format!(
+                        "Failed to mark {} executable: {e}",
+                        path.display()
+                    ))
+                })?;
+        }
+
+        info!("Staged update {} at {}", bundle.version, path.display());
+        Ok(StagedUpdate {
+            version: bundle.version.clone(),
+            kind,
+            path,
+            marker: self.marker_path(kind)?,
+        })
+    }
+
+    /// 校验包内容的 SHA-256、签名清单与版本
+    pub fn verify(&self, bundle: &UpdateBundle, bytes: &[u8]) -> Result<()> {
+        let kind = bundle.kind();
+        let digest = hex::encode(Sha256::digest(bytes));
+        if !digest.eq_ignore_ascii_case(bundle.sha256.trim()) {
+            return Err(SupervitError::Update(format!(
+                "Bundle SHA-256 mismatch: expected {}, got {digest}",
+                bundle.sha256
+            )));
+        }
+        let manifest = signing_manifest(kind, &bundle.version, &digest)?;
+
+        let signature: [u8; 64] = hex::decode(bundle.signature.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                SupervitError::Update("Bundle signature must be 64 bytes hex".to_string())
+            })?;
+        self.public_key
+            .verify_strict(manifest.as_bytes(), &Signature::from_bytes(&signature))
+            .map_err(|e| {
+                warn!("Rejected update {}: invalid signature", bundle.version);
+                SupervitError::Update(format!("Bundle signature verification failed: {e}"))
+            })?;
+
+        self.ensure_newer(kind, &bundle.version)
+    }
+
+    /// 拒绝不高于当前版本的包
+    fn ensure_newer(&self, kind: UpdateBundleKind, version: &str) -> Result<()> {
+        let mut floors = Vec::new();
+        if kind == UpdateBundleKind::Binary {
+            floors.push(self.current_version.clone());
+        }
+        if let Ok(installed) = std::fs::read_to_string(self.marker_path(kind)?) {
+            floors.push(installed.trim().to_string());
+        }
+
+        for floor in floors {
+            match compare_versions(version, &floor) {
+                Some(Ordering::Greater) => {}
+                Some(_) => {
+                    warn!("Rejected update {version}: not newer than {floor}");
+                    return Err(SupervitError::Update(format!(
+                        "Bundle version {version} is not newer than {floor}"
+                    )));
+                }
+                None => {
+                    return Err(SupervitError::Update(format!(
+                        "Cannot compare bundle version {version} with {floor}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 记录该类型已安装版本的文件
+    fn marker_path(&self, kind: UpdateBundleKind) -> Result<PathBuf> {
+        Ok(self
+            .staging_dir
+            .join(format!("installed-{}.version", kind_name(kind)?)))
+    }
+}
+
+/// 版本号用于文件名，只保留安全字符
+fn sanitize(version: &str) -> String {
+    version
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use tempfile::tempdir;
+
+    fn bundle_with_version(
+        key: &SigningKey,
+        kind: UpdateBundleKind,
+        version: &str,
+        bytes: &[u8],
+    ) -> UpdateBundle {
+        let sha256 = hex::encode(Sha256::digest(bytes));
+        let manifest = signing_manifest(kind, version, &sha256).unwrap();
+        UpdateBundle {
+            version: version.to_string(),
+            kind: kind as i32,
+            url: "https://updates.example.com/actrix".to_string(),
+            sha256,
+            signature: hex::encode(key.sign(manifest.as_bytes()).to_bytes()),
+        }
+    }
+
+    fn signed_bundle(key: &SigningKey, kind: UpdateBundleKind, bytes: &[u8]) -> UpdateBundle {
+        bundle_with_version(key, kind, "1.2.3", bytes)
+    }
+
+    fn updater(key: &SigningKey, dir: &Path) -> SelfUpdater {
+        SelfUpdater::new(
+            &hex::encode(key.verifying_key().to_bytes()),
+            "1.0.0",
+            dir,
+            DEFAULT_MAX_BUNDLE_BYTES,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_bundle() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let dir = tempdir().unwrap();
+        let updater = updater(&key, dir.path());
+        let bundle = signed_bundle(&key, UpdateBundleKind::Binary, b"new binary");
+
+        assert!(updater.verify(&bundle, b"new binary").is_ok());
+        // 内容被篡改
+        assert!(updater.verify(&bundle, b"evil binary").is_err());
+
+        // 摘要匹配但签名来自其他密钥
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        let forged = signed_bundle(&other, UpdateBundleKind::Binary, b"new binary");
+        assert!(updater.verify(&forged, b"new binary").is_err());
+
+        // 签名只覆盖包内容的旧格式不再被接受
+        let mut legacy = bundle.clone();
+        legacy.signature = hex::encode(key.sign(b"new binary").to_bytes());
+        assert!(updater.verify(&legacy, b"new binary").is_err());
+    }
+
+    #[test]
+    fn test_verify_binds_kind_and_version() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let dir = tempdir().unwrap();
+        let updater = updater(&key, dir.path());
+
+        // CONFIG 包不能被重放为 BINARY
+        let mut replayed = signed_bundle(&key, UpdateBundleKind::Config, b"enable = 3");
+        replayed.kind = UpdateBundleKind::Binary as i32;
+        assert!(updater.verify(&replayed, b"enable = 3").is_err());
+
+        // 版本号不能被改写
+        let mut bumped = signed_bundle(&key, UpdateBundleKind::Binary, b"new binary");
+        bumped.version = "9.9.9".to_string();
+        assert!(updater.verify(&bumped, b"new binary").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_versions_not_newer() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let dir = tempdir().unwrap();
+        let updater = updater(&key, &dir.path().join("staging"));
+
+        // 不高于运行中的版本（1.0.0）
+        for version in ["1.0.0", "0.9.9", "1.0.0-rc.1"] {
+            let bundle = bundle_with_version(&key, UpdateBundleKind::Binary, version, b"old");
+            assert!(updater.verify(&bundle, b"old").is_err(), "{version}");
+        }
+        let bundle = bundle_with_version(&key, UpdateBundleKind::Binary, "1.0.1", b"new");
+        assert!(updater.verify(&bundle, b"new").is_ok());
+
+        // CONFIG 与上次安装的版本比较
+        let target = dir.path().join("config.toml");
+        let v2 = bundle_with_version(&key, UpdateBundleKind::Config, "2", b"enable = 2");
+        updater
+            .stage_bytes(&v2, b"enable = 2")
+            .await
+            .unwrap()
+            .install(&target)
+            .unwrap();
+        let replay = bundle_with_version(&key, UpdateBundleKind::Config, "2", b"enable = 2");
+        assert!(updater.verify(&replay, b"enable = 2").is_err());
+        let older = bundle_with_version(&key, UpdateBundleKind::Config, "1", b"enable = 1");
+        assert!(updater.verify(&older, b"enable = 1").is_err());
+        let newer = bundle_with_version(&key, UpdateBundleKind::Config, "3", b"enable = 3");
+        assert!(updater.verify(&newer, b"enable = 3").is_ok());
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.10", "1.2.9"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v1.2", "1.2.0"), Some(Ordering::Equal));
+        assert_eq!(
+            compare_versions("1.2.0", "1.2.0-rc.1"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_versions("1.2.0+build.5", "1.2.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare_versions("latest", "1.2.0"), None);
+    }
+
+    #[tokio::test]
+    async fn test_stage_and_install() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let dir = tempdir().unwrap();
+        let updater = updater(&key, &dir.path().join("staging"));
+        let bundle = signed_bundle(&key, UpdateBundleKind::Config, b"enable = 3");
+
+        let staged = updater.stage_bytes(&bundle, b"enable = 3").await.unwrap();
+        assert_eq!(staged.kind, UpdateBundleKind::Config);
+        assert!(staged.path.exists());
+
+        let target = dir.path().join("config.toml");
+        std::fs::write(&target, "enable = 1").unwrap();
+        staged.install(&target).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "enable = 3");
+        assert_eq!(
+            std::fs::read_to_string(backup_path(&target)).unwrap(),
+            "enable = 1"
+        );
+
+        // 重启失败时恢复安装前的文件与版本记录
+        staged.rollback(&target).unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "enable = 1");
+        assert!(!backup_path(&target).exists());
+        assert!(updater.verify(&bundle, b"enable = 3").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_removes_newly_created_target() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let dir = tempdir().unwrap();
+        let updater = updater(&key, &dir.path().join("staging"));
+        let bundle = signed_bundle(&key, UpdateBundleKind::Config, b"enable = 3");
+        let target = dir.path().join("config.toml");
+        // 上一次更新遗留的备份不能在回滚时被恢复
+        std::fs::write(backup_path(&target), "stale").unwrap();
+
+        let staged = updater.stage_bytes(&bundle, b"enable = 3").await.unwrap();
+        staged.install(&target).unwrap();
+        staged.rollback(&target).unwrap();
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        assert!(SelfUpdater::new("abcd", "1.0.0", "/tmp", DEFAULT_MAX_BUNDLE_BYTES).is_err());
+    }
+}
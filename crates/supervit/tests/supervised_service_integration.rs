@@ -7,10 +7,13 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use supervit::{
-    ConfigType, CreateRealmRequest, DeleteRealmRequest, GetConfigRequest, GetNodeInfoRequest,
-    GetRealmRequest, ListRealmsRequest, NonceCredential, ResourceType, ShutdownRequest,
-    SupervisedServiceClient, SupervisedServiceServer, Supervisord, SupervitError, SystemMetrics,
-    UpdateConfigRequest, UpdateRealmRequest,
+    BroadcastServerNoticeRequest, ConfigType, ConnectedActor, CreateRealmApiKeyRequest,
+    CreateRealmRequest, DeleteRealmRequest, DisconnectActorRequest, GetConfigRequest,
+    GetNodeInfoRequest, GetRealmRequest, GetServiceSpecHistoryRequest, ListConnectionsRequest,
+    ListRealmApiKeysRequest, ListRealmsRequest, NodeCapabilities, NonceCredential, RealmQuotaInfo,
+    RealmRateLimitInfo, ResourceType, RevokeRealmApiKeyRequest, ServiceSpecVersion,
+    ShutdownRequest, SupervisedServiceClient, SupervisedServiceServer, Supervisord, SupervitError,
+    SystemMetrics, UpdateConfigRequest, UpdateRealmRequest,
 };
 use tokio::net::TcpListener;
 use tokio::sync::{Mutex, OnceCell};
@@ -159,8 +162,14 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
             load_average_1m: 0.7,
             load_average_5m: Some(0.5),
             load_average_15m: Some(0.3),
+            realm_usage: vec![],
+            ..Default::default()
         })
     })
+    .with_capabilities(NodeCapabilities {
+        build_features: vec!["opentelemetry".to_string()],
+        active_subsystems: vec!["signaling".to_string(), "turn".to_string()],
+    })
     .with_shutdown_handler(move |graceful, timeout_secs, reason| {
         let shutdown_calls_for_handler = Arc::clone(&shutdown_calls_for_handler);
         async move {
@@ -238,6 +247,9 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
             credential: test_credential(),
             version: 4,
             expires_at: (chrono::Utc::now().timestamp() + 1800) as u64,
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("create realm should succeed")
@@ -271,6 +283,16 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
             name: Some("realm-beta".to_string()),
             enabled: Some(false),
             credential: test_credential(),
+            dry_run: None,
+            rate_limits: Some(RealmRateLimitInfo {
+                registrations_per_minute: None,
+                relays_per_second: Some(50),
+                discovery_per_second: Some(0),
+            }),
+            quota: Some(RealmQuotaInfo {
+                max_concurrent_actors: Some(25),
+                max_registrations: None,
+            }),
         })
         .await
         .expect("update realm should succeed")
@@ -281,6 +303,13 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
         .expect("updated realm should be returned");
     assert_eq!(updated.name, "realm-beta");
     assert!(!updated.enabled);
+    let rate_limits = updated.rate_limits.expect("rate limits should be returned");
+    assert_eq!(rate_limits.relays_per_second, Some(50));
+    assert_eq!(rate_limits.discovery_per_second, Some(0));
+    assert_eq!(rate_limits.registrations_per_minute, None);
+    let quota = updated.quota.expect("quota should be returned");
+    assert_eq!(quota.max_concurrent_actors, Some(25));
+    assert_eq!(quota.max_registrations, None);
 
     let update_missing = client
         .update_realm(UpdateRealmRequest {
@@ -288,6 +317,9 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
             name: Some("realm-missing".to_string()),
             enabled: Some(true),
             credential: test_credential(),
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("updating missing realm should return response")
@@ -324,6 +356,11 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
     assert_eq!(node_info.location_tag, "edge-a");
     assert_eq!(node_info.version, "1.0.0");
     assert_eq!(node_info.services.len(), 2);
+    let capabilities = node_info
+        .capabilities
+        .expect("capabilities should be returned");
+    assert_eq!(capabilities.build_features, vec!["opentelemetry"]);
+    assert_eq!(capabilities.active_subsystems, vec!["signaling", "turn"]);
     let metrics = node_info
         .current_metrics
         .expect("metrics should be returned");
@@ -356,6 +393,7 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
         .delete_realm(DeleteRealmRequest {
             realm_id,
             credential: test_credential(),
+            dry_run: None,
         })
         .await
         .expect("delete realm should succeed")
@@ -366,6 +404,7 @@ async fn supervised_service_covers_config_realm_node_info_and_shutdown() {
         .delete_realm(DeleteRealmRequest {
             realm_id,
             credential: test_credential(),
+            dry_run: None,
         })
         .await
         .expect("deleting missing realm should return response")
@@ -483,3 +522,626 @@ async fn supervised_service_shutdown_handler_failure_and_missing_handler_are_cov
     handle_ok.abort();
     let _ = handle_ok.await;
 }
+
+#[tokio::test]
+#[serial]
+async fn supervised_service_connection_management_hooks_are_covered() {
+    let with_hooks = Supervisord::new(
+        "node-connections",
+        "node-connections",
+        "edge-c",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service")
+    .with_connections_provider(|realm_id| async move {
+        Ok(vec![ConnectedActor {
+            client_id: "client-1".to_string(),
+            actor_id: Some("acme:echo@1:7".to_string()),
+            realm_id,
+            client_ip: None,
+            connected_at: 1_700_000_000,
+            services: vec![],
+        }])
+    })
+    .with_disconnect_handler(|actor_id, _reason| async move {
+        if actor_id == "invalid" {
+            return Err(SupervitError::Internal("invalid actor id".to_string()));
+        }
+        Ok(actor_id == "acme:echo@1:7")
+    });
+
+    let (endpoint, handle) = spawn_supervised_service(with_hooks).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let listed = client
+        .list_connections(ListConnectionsRequest {
+            realm_id: Some(7),
+            credential: test_credential(),
+        })
+        .await
+        .expect("list connections should succeed")
+        .into_inner();
+    assert!(listed.success);
+    assert_eq!(listed.connections.len(), 1);
+    assert_eq!(listed.connections[0].realm_id, Some(7));
+
+    let disconnected = client
+        .disconnect_actor(DisconnectActorRequest {
+            actor_id: "acme:echo@1:7".to_string(),
+            reason: Some("incident".to_string()),
+            credential: test_credential(),
+        })
+        .await
+        .expect("disconnect should return response")
+        .into_inner();
+    assert!(disconnected.success);
+    assert!(disconnected.disconnected);
+
+    let not_connected = client
+        .disconnect_actor(DisconnectActorRequest {
+            actor_id: "acme:echo@2:7".to_string(),
+            reason: None,
+            credential: test_credential(),
+        })
+        .await
+        .expect("disconnect should return response")
+        .into_inner();
+    assert!(not_connected.success);
+    assert!(!not_connected.disconnected);
+
+    let failed = client
+        .disconnect_actor(DisconnectActorRequest {
+            actor_id: "invalid".to_string(),
+            reason: None,
+            credential: test_credential(),
+        })
+        .await
+        .expect("disconnect should return response")
+        .into_inner();
+    assert!(!failed.success);
+    assert!(
+        failed
+            .error_message
+            .as_deref()
+            .unwrap_or_default()
+            .contains("Disconnect handler failed")
+    );
+
+    handle.abort();
+    let _ = handle.await;
+
+    let without_hooks = Supervisord::new(
+        "node-connections-default",
+        "node-connections-default",
+        "edge-d",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service");
+
+    let (endpoint, handle) = spawn_supervised_service(without_hooks).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let listed = client
+        .list_connections(ListConnectionsRequest {
+            realm_id: None,
+            credential: test_credential(),
+        })
+        .await
+        .expect("list connections should return response")
+        .into_inner();
+    assert!(!listed.success);
+    assert!(listed.connections.is_empty());
+
+    handle.abort();
+    let _ = handle.await;
+}
+
+#[tokio::test]
+#[serial]
+async fn supervised_service_server_notice_hook_is_covered() {
+    let with_hook = Supervisord::new(
+        "node-notices",
+        "node-notices",
+        "edge-n",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service")
+    .with_notice_handler(|request| async move {
+        if request.severity == "fatal" {
+            return Err(SupervitError::Internal("invalid severity".to_string()));
+        }
+        let delivered = if request.realm_ids.is_empty() { 3 } else { 1 };
+        Ok(("notice-1".to_string(), delivered))
+    });
+
+    let (endpoint, handle) = spawn_supervised_service(with_hook).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let broadcast = client
+        .broadcast_server_notice(BroadcastServerNoticeRequest {
+            severity: "warning".to_string(),
+            category: "maintenance".to_string(),
+            message: "Node restarts in 5 minutes".to_string(),
+            effective_at: Some(1_700_000_300),
+            realm_ids: vec![7],
+            actr_types: vec![],
+            credential: test_credential(),
+        })
+        .await
+        .expect("broadcast should return response")
+        .into_inner();
+    assert!(broadcast.success);
+    assert_eq!(broadcast.notice_id.as_deref(), Some("notice-1"));
+    assert_eq!(broadcast.delivered, 1);
+
+    let failed = client
+        .broadcast_server_notice(BroadcastServerNoticeRequest {
+            severity: "fatal".to_string(),
+            category: "maintenance".to_string(),
+            message: "bad".to_string(),
+            effective_at: None,
+            realm_ids: vec![],
+            actr_types: vec![],
+            credential: test_credential(),
+        })
+        .await
+        .expect("broadcast should return response")
+        .into_inner();
+    assert!(!failed.success);
+    assert!(
+        failed
+            .error_message
+            .as_deref()
+            .unwrap_or_default()
+            .contains("Notice handler failed")
+    );
+
+    handle.abort();
+    let _ = handle.await;
+
+    let without_hook = Supervisord::new(
+        "node-notices-default",
+        "node-notices-default",
+        "edge-o",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service");
+
+    let (endpoint, handle) = spawn_supervised_service(without_hook).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let not_running = client
+        .broadcast_server_notice(BroadcastServerNoticeRequest {
+            severity: "info".to_string(),
+            category: "quota".to_string(),
+            message: "Quota at 90%".to_string(),
+            effective_at: None,
+            realm_ids: vec![],
+            actr_types: vec![],
+            credential: test_credential(),
+        })
+        .await
+        .expect("broadcast should return response")
+        .into_inner();
+    assert!(!not_running.success);
+    assert_eq!(not_running.delivered, 0);
+
+    handle.abort();
+    let _ = handle.await;
+}
+
+#[tokio::test]
+#[serial]
+async fn supervised_service_spec_history_hook_is_covered() {
+    let with_hook = Supervisord::new(
+        "node-spec-history",
+        "node-spec-history",
+        "edge-e",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service")
+    .with_spec_history_provider(|service_name, fingerprint, limit| async move {
+        if service_name == "broken" {
+            return Err(SupervitError::Internal("registry unavailable".to_string()));
+        }
+        let versions: Vec<ServiceSpecVersion> = ["fp-2", "fp-1"]
+            .into_iter()
+            .filter(|fp| fingerprint.as_deref().is_none_or(|wanted| wanted == *fp))
+            .take(limit.unwrap_or(u32::MAX) as usize)
+            .map(|fp| ServiceSpecVersion {
+                fingerprint: fp.to_string(),
+                published_at: 1_700_000_000,
+                first_seen_at: 1_700_000_000,
+                last_seen_at: 1_700_000_100,
+                description: None,
+                tags: vec![],
+                spec: vec![],
+            })
+            .collect();
+        Ok(versions)
+    });
+
+    let (endpoint, handle) = spawn_supervised_service(with_hook).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let history = client
+        .get_service_spec_history(GetServiceSpecHistoryRequest {
+            service_name: "echo".to_string(),
+            fingerprint: None,
+            limit: None,
+            credential: test_credential(),
+        })
+        .await
+        .expect("spec history should succeed")
+        .into_inner();
+    assert!(history.success);
+    assert_eq!(history.versions.len(), 2);
+    assert_eq!(history.versions[0].fingerprint, "fp-2");
+
+    let pinned = client
+        .get_service_spec_history(GetServiceSpecHistoryRequest {
+            service_name: "echo".to_string(),
+            fingerprint: Some("fp-1".to_string()),
+            limit: Some(5),
+            credential: test_credential(),
+        })
+        .await
+        .expect("spec history should succeed")
+        .into_inner();
+    assert_eq!(pinned.versions.len(), 1);
+    assert_eq!(pinned.versions[0].fingerprint, "fp-1");
+
+    let failed = client
+        .get_service_spec_history(GetServiceSpecHistoryRequest {
+            service_name: "broken".to_string(),
+            fingerprint: None,
+            limit: None,
+            credential: test_credential(),
+        })
+        .await
+        .expect("spec history should return response")
+        .into_inner();
+    assert!(!failed.success);
+    assert!(failed.versions.is_empty());
+
+    handle.abort();
+    let _ = handle.await;
+
+    let without_hook = Supervisord::new(
+        "node-spec-history-default",
+        "node-spec-history-default",
+        "edge-f",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service");
+
+    let (endpoint, handle) = spawn_supervised_service(without_hook).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let history = client
+        .get_service_spec_history(GetServiceSpecHistoryRequest {
+            service_name: "echo".to_string(),
+            fingerprint: None,
+            limit: None,
+            credential: test_credential(),
+        })
+        .await
+        .expect("spec history should return response")
+        .into_inner();
+    assert!(!history.success);
+
+    handle.abort();
+    let _ = handle.await;
+}
+
+#[tokio::test]
+#[serial]
+async fn supervised_service_realm_dry_run_does_not_commit() {
+    init_global_test_db().await;
+
+    let service = Supervisord::new(
+        "node-dry-run",
+        "node-dry-run",
+        "edge-e",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service")
+    .with_connections_provider(|realm_id| async move {
+        Ok(vec![ConnectedActor {
+            client_id: "client-1".to_string(),
+            actor_id: Some("acme:echo@1:7".to_string()),
+            realm_id,
+            client_ip: None,
+            connected_at: 1_700_000_000,
+            services: vec![],
+        }])
+    });
+
+    let (endpoint, handle) = spawn_supervised_service(service).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let realm_id = unique_realm_id();
+    let create_request = |dry_run| CreateRealmRequest {
+        realm_id,
+        name: "realm-dry".to_string(),
+        enabled: true,
+        use_servers: vec![ResourceType::Signaling as i32],
+        credential: test_credential(),
+        version: 1,
+        expires_at: (chrono::Utc::now().timestamp() + 1800) as u64,
+        dry_run,
+        rate_limits: None,
+        quota: None,
+    };
+
+    let preview = client
+        .create_realm(create_request(Some(true)))
+        .await
+        .expect("dry-run create should return response")
+        .into_inner();
+    assert!(preview.success);
+    let report = preview
+        .dry_run_report
+        .expect("dry-run report should be returned");
+    assert!(report.conflicts.is_empty());
+    assert_eq!(report.changes.len(), 1);
+    assert_eq!(report.affected_actors, Some(1));
+    assert_eq!(preview.realm.expect("preview realm").name, "realm-dry");
+
+    let missing = client
+        .get_realm(GetRealmRequest {
+            realm_id,
+            credential: test_credential(),
+        })
+        .await
+        .expect("get realm should return response")
+        .into_inner();
+    assert!(
+        !missing.success,
+        "dry-run create must not persist the realm"
+    );
+
+    let created = client
+        .create_realm(create_request(None))
+        .await
+        .expect("create realm should succeed")
+        .into_inner();
+    assert!(created.success);
+    assert!(created.dry_run_report.is_none());
+
+    let conflict = client
+        .create_realm(create_request(Some(true)))
+        .await
+        .expect("dry-run create should return response")
+        .into_inner();
+    assert!(!conflict.success);
+    assert_eq!(
+        conflict
+            .dry_run_report
+            .expect("dry-run report should be returned")
+            .conflicts
+            .len(),
+        1
+    );
+
+    let update_preview = client
+        .update_realm(UpdateRealmRequest {
+            realm_id,
+            name: Some("realm-dry-renamed".to_string()),
+            enabled: Some(true),
+            credential: test_credential(),
+            dry_run: Some(true),
+            rate_limits: None,
+            quota: None,
+        })
+        .await
+        .expect("dry-run update should return response")
+        .into_inner();
+    assert!(update_preview.success);
+    let report = update_preview
+        .dry_run_report
+        .expect("dry-run report should be returned");
+    assert_eq!(report.changes, vec!["name: realm-dry -> realm-dry-renamed"]);
+
+    let update_missing = client
+        .update_realm(UpdateRealmRequest {
+            realm_id: realm_id + 9_999,
+            name: None,
+            enabled: Some(false),
+            credential: test_credential(),
+            dry_run: Some(true),
+            rate_limits: None,
+            quota: None,
+        })
+        .await
+        .expect("dry-run update should return response")
+        .into_inner();
+    assert!(!update_missing.success);
+    assert!(
+        !update_missing
+            .dry_run_report
+            .expect("dry-run report should be returned")
+            .conflicts
+            .is_empty()
+    );
+
+    let delete_preview = client
+        .delete_realm(DeleteRealmRequest {
+            realm_id,
+            credential: test_credential(),
+            dry_run: Some(true),
+        })
+        .await
+        .expect("dry-run delete should return response")
+        .into_inner();
+    assert!(delete_preview.success);
+    let report = delete_preview
+        .dry_run_report
+        .expect("dry-run report should be returned");
+    assert_eq!(report.changes.len(), 2);
+    assert_eq!(report.affected_actors, Some(1));
+
+    let still_there = client
+        .get_realm(GetRealmRequest {
+            realm_id,
+            credential: test_credential(),
+        })
+        .await
+        .expect("get realm should succeed")
+        .into_inner();
+    assert!(
+        still_there.success,
+        "dry-run delete must not remove the realm"
+    );
+    assert_eq!(
+        still_there.realm.expect("realm should exist").name,
+        "realm-dry"
+    );
+
+    let deleted = client
+        .delete_realm(DeleteRealmRequest {
+            realm_id,
+            credential: test_credential(),
+            dry_run: None,
+        })
+        .await
+        .expect("delete realm should succeed")
+        .into_inner();
+    assert!(deleted.success);
+
+    handle.abort();
+    let _ = handle.await;
+}
+
+#[tokio::test]
+#[serial]
+async fn supervised_service_realm_api_keys_lifecycle() {
+    init_global_test_db().await;
+
+    let service = Supervisord::new(
+        "node-api-key",
+        "node-api-key",
+        "edge-e",
+        "1.0.0",
+        ServiceCollector::new(),
+    )
+    .expect("create supervisord service");
+
+    let (endpoint, handle) = spawn_supervised_service(service).await;
+    let mut client = connect_client(&endpoint).await;
+
+    let realm_id = unique_realm_id();
+    let create_key = |realm_id, scopes: Vec<&str>| CreateRealmApiKeyRequest {
+        realm_id,
+        name: "tenant-ci".to_string(),
+        scopes: scopes.into_iter().map(str::to_string).collect(),
+        expires_at: None,
+        credential: test_credential(),
+    };
+
+    let missing_realm = client
+        .create_realm_api_key(create_key(realm_id, vec![]))
+        .await
+        .expect("create key should return response")
+        .into_inner();
+    assert!(!missing_realm.success);
+    assert!(missing_realm.token.is_none());
+
+    let created = client
+        .create_realm(CreateRealmRequest {
+            realm_id,
+            name: "realm-api-key".to_string(),
+            enabled: true,
+            use_servers: vec![ResourceType::Signaling as i32],
+            credential: test_credential(),
+            version: 1,
+            expires_at: (chrono::Utc::now().timestamp() + 1800) as u64,
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
+        })
+        .await
+        .expect("create realm should succeed")
+        .into_inner();
+    assert!(created.success);
+
+    let bad_scope = client
+        .create_realm_api_key(create_key(realm_id, vec!["root"]))
+        .await
+        .expect("create key should return response")
+        .into_inner();
+    assert!(!bad_scope.success);
+
+    let issued = client
+        .create_realm_api_key(create_key(realm_id, vec!["presence", "acl"]))
+        .await
+        .expect("create key should succeed")
+        .into_inner();
+    assert!(issued.success);
+    let key = issued.key.expect("key metadata should be returned");
+    let token = issued.token.expect("token should be returned once");
+    assert!(token.starts_with(&format!("rk_{}_", key.key_id)));
+    assert_eq!(key.scopes, vec!["presence", "acl"]);
+
+    let listed = client
+        .list_realm_api_keys(ListRealmApiKeysRequest {
+            realm_id,
+            credential: test_credential(),
+        })
+        .await
+        .expect("list keys should succeed")
+        .into_inner();
+    assert!(listed.success);
+    assert_eq!(listed.keys.len(), 1);
+    assert!(!listed.keys[0].revoked);
+
+    let revoke = |key_id: String| RevokeRealmApiKeyRequest {
+        realm_id,
+        key_id,
+        credential: test_credential(),
+    };
+    let revoked = client
+        .revoke_realm_api_key(revoke(key.key_id.clone()))
+        .await
+        .expect("revoke key should succeed")
+        .into_inner();
+    assert!(revoked.success);
+
+    let unknown = client
+        .revoke_realm_api_key(revoke("missing".to_string()))
+        .await
+        .expect("revoke key should return response")
+        .into_inner();
+    assert!(!unknown.success);
+
+    let listed = client
+        .list_realm_api_keys(ListRealmApiKeysRequest {
+            realm_id,
+            credential: test_credential(),
+        })
+        .await
+        .expect("list keys should succeed")
+        .into_inner();
+    assert!(listed.keys[0].revoked);
+
+    let deleted = client
+        .delete_realm(DeleteRealmRequest {
+            realm_id,
+            credential: test_credential(),
+            dry_run: None,
+        })
+        .await
+        .expect("delete realm should succeed")
+        .into_inner();
+    assert!(deleted.success);
+
+    handle.abort();
+    let _ = handle.await;
+}
@@ -1,4 +1,5 @@
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,14 +11,16 @@ use tempfile::TempDir;
 use tokio::net::TcpListener;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
+use tokio_stream::Stream;
 use tokio_stream::wrappers::TcpListenerStream;
 use tonic::{Request, Response, Status, transport::Server};
 
 use supervit::{
-    HealthCheckRequest, HealthCheckResponse, NonceCredential, RegisterNodeRequest,
-    RegisterNodeResponse, ReportRequest, ReportResponse, ServiceAdvertisementStatus,
-    SupervisorService, SupervisorServiceClient, SupervisorServiceServer, SupervitClient,
-    SupervitConfig, SupervitError,
+    ConfigChange, ConfigType, Directive, DirectiveType, HealthCheckRequest, HealthCheckResponse,
+    NonceCredential, RegisterNodeRequest, RegisterNodeResponse, ReportRequest, ReportResponse,
+    ServiceAdvertisementStatus, SupervisorService, SupervisorServiceClient,
+    SupervisorServiceServer, SupervitClient, SupervitConfig, SupervitError, WatchDirectivesRequest,
+    directive_handler,
 };
 
 const TEST_SHARED_SECRET: &str = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
@@ -28,6 +31,29 @@ struct NodeState {
     last_report_request: Option<ReportRequest>,
     report_count: u32,
     health_check_count: u32,
+    /// `last_sequence` of every WatchDirectives call
+    watch_sequences: Vec<Option<u64>>,
+}
+
+/// Directives pushed by the test supervisor on every stream
+fn test_directives() -> Vec<Directive> {
+    vec![
+        Directive {
+            r#type: DirectiveType::ConfigUpdate as i32,
+            sequence: Some(1),
+            config: Some(ConfigChange {
+                config_type: ConfigType::LogLevel as i32,
+                config_key: "log.level".to_string(),
+                config_value: "debug".to_string(),
+            }),
+            ..Default::default()
+        },
+        Directive {
+            r#type: DirectiveType::Drain as i32,
+            sequence: Some(2),
+            ..Default::default()
+        },
+    ]
 }
 
 type NodeMap = Arc<RwLock<std::collections::HashMap<String, NodeState>>>;
@@ -207,6 +233,32 @@ impl SupervisorService for TestSupervisorService {
 
         Ok(Response::new(response))
     }
+
+    type WatchDirectivesStream =
+        Pin<Box<dyn Stream<Item = Result<Directive, Status>> + Send + 'static>>;
+
+    async fn watch_directives(
+        &self,
+        request: Request<WatchDirectivesRequest>,
+    ) -> Result<Response<Self::WatchDirectivesStream>, Status> {
+        let req = request.into_inner();
+        let payload = format!("watch_directives:{}", req.node_id);
+
+        self.verify_credential(&req.credential, payload).await?;
+        let mut nodes = self.nodes.write().await;
+        let state = nodes.entry(req.node_id.clone()).or_default();
+        state.watch_sequences.push(req.last_sequence);
+
+        // Resume: only push directives the node has not applied yet, then close the stream
+        let last = req.last_sequence.unwrap_or(0);
+        let pending: Vec<Result<Directive, Status>> = test_directives()
+            .into_iter()
+            .filter(|d| d.sequence.unwrap_or(0) > last)
+            .map(Ok)
+            .collect();
+
+        Ok(Response::new(Box::pin(tokio_stream::iter(pending))))
+    }
 }
 
 async fn spawn_test_supervisor(
@@ -318,6 +370,8 @@ fn build_report_request(node_id: &str, shared_secret: &[u8]) -> ReportRequest {
         services: vec![],
         credential,
         realm_sync_version: 1,
+        capabilities: None,
+        realm_events: vec![],
     }
 }
 
@@ -787,3 +841,68 @@ async fn supervit_client_status_reporting_adjusts_interval_from_server_response(
     let _ = handle.await;
     Ok(())
 }
+
+#[tokio::test]
+async fn supervit_client_directive_watch_resumes_after_stream_end()
+-> Result<(), Box<dyn std::error::Error>> {
+    let shared_secret = hex::decode(TEST_SHARED_SECRET)?;
+    let (addr, _temp_dir, handle, nodes) = spawn_test_supervisor(shared_secret, 300, 5).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let collector = build_service_collector_with_entries().await;
+    let endpoint = format!("http://{addr}");
+    let config = build_supervit_config("directive-watch-node", endpoint, TEST_SHARED_SECRET);
+    let client = SupervitClient::new(config, collector)?;
+
+    let received = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let handler = directive_handler(move |directive: Directive| {
+        let sink = sink.clone();
+        async move {
+            sink.lock()
+                .await
+                .push((directive.r#type(), directive.sequence));
+        }
+    });
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+    let watch = client.start_directive_watch(Some(handler), shutdown_rx)?;
+
+    // The stream ends after two directives; the client reconnects after the
+    // initial retry delay and resumes from the last applied sequence.
+    let mut resumed = false;
+    for _ in 0..40 {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let sequences = {
+            let nodes_read = nodes.read().await;
+            nodes_read
+                .get("directive-watch-node")
+                .map(|state| state.watch_sequences.clone())
+                .unwrap_or_default()
+        };
+        if sequences.len() >= 2 {
+            assert_eq!(sequences[0], None);
+            assert_eq!(sequences[1], Some(2));
+            resumed = true;
+            break;
+        }
+    }
+    assert!(
+        resumed,
+        "directive watch should reconnect with last sequence"
+    );
+    assert_eq!(
+        *received.lock().await,
+        vec![
+            (DirectiveType::ConfigUpdate, Some(1)),
+            (DirectiveType::Drain, Some(2)),
+        ]
+    );
+
+    shutdown_tx.send(())?;
+    tokio::time::timeout(Duration::from_secs(2), watch).await??;
+
+    handle.abort();
+    let _ = handle.await;
+    Ok(())
+}
@@ -5,25 +5,109 @@
 use actr_protocol::AIdCredential;
 use actr_protocol::turn::Claims;
 use actrix_common::aid::credential::validator::AIdCredentialValidator;
+use actrix_common::aid::turn_credential::{ScopedTurnUser, turn_password};
+use actrix_common::metrics::{AUTH_FAILURES, TOKENS_VALIDATED};
 use actrix_common::realm::Realm as RealmEntity;
 use lru::LruCache;
 use once_cell::sync::Lazy;
+use std::collections::HashSet;
 use std::hash::Hasher;
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, warn};
 use turn_crate::Error;
 use turn_crate::auth::AuthHandler;
 use twox_hash::XxHash64;
 
 /// TURN 认证器
-pub struct Authenticator;
+pub struct Authenticator {
+    /// 允许使用中继的 Realm ID 集合（为空表示不限制，可热加载）
+    allowed_realm_ids: RwLock<HashSet<u32>>,
+    /// 校验限定范围 TURN 凭证（见 [`actrix_common::aid::turn_credential`]）的密钥，
+    /// 未设置时只接受 Claims 用户名
+    credential_secret: Option<String>,
+}
+
+/// 进程内的 TURN 认证器（供配置热加载使用）
+static REGISTERED_AUTHENTICATOR: RwLock<Option<Arc<Authenticator>>> = RwLock::new(None);
 
 impl Authenticator {
     pub fn new() -> Result<Self, Error> {
-        tracing::info!("TURN 认证器初始化完成 (启用 LRU 缓存)");
-        Ok(Self)
+        Self::with_allowed_realms(std::iter::empty())
+    }
+
+    /// 创建限定 Realm 范围的认证器
+    ///
+    /// 仅当凭证所属 Realm 在 `allowed_realm_ids` 中时才允许分配中继；
+    /// 传入空集合等价于 [`Authenticator::new`]。
+    pub fn with_allowed_realms(
+        allowed_realm_ids: impl IntoIterator<Item = u32>,
+    ) -> Result<Self, Error> {
+        let allowed_realm_ids: HashSet<u32> = allowed_realm_ids.into_iter().collect();
+        if allowed_realm_ids.is_empty() {
+            tracing::info!("TURN 认证器初始化完成 (启用 LRU 缓存)");
+        } else {
+            tracing::info!(
+                "TURN 认证器初始化完成 (启用 LRU 缓存, 限定 {} 个 Realm)",
+                allowed_realm_ids.len()
+            );
+        }
+        Ok(Self {
+            allowed_realm_ids: RwLock::new(allowed_realm_ids),
+            credential_secret: None,
+        })
+    }
+
+    /// 接受由 AIS 用 `actrix_shared_key` 签发的限定范围 TURN 凭证
+    pub fn with_credential_secret(mut self, secret: impl Into<String>) -> Self {
+        self.credential_secret = Some(secret.into());
+        self
+    }
+
+    /// 运行时替换允许使用中继的 Realm 范围，已分配的中继不受影响
+    ///
+    /// 同时清空认证缓存，新范围对之后的认证请求立即生效
+    pub fn set_allowed_realms(&self, allowed_realm_ids: impl IntoIterator<Item = u32>) {
+        let allowed_realm_ids: HashSet<u32> = allowed_realm_ids.into_iter().collect();
+        tracing::info!(
+            "TURN 允许的 Realm 范围已更新: {}",
+            if allowed_realm_ids.is_empty() {
+                "不限制".to_string()
+            } else {
+                format!("{} 个 Realm", allowed_realm_ids.len())
+            }
+        );
+        *self
+            .allowed_realm_ids
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = allowed_realm_ids;
+        Self::clear_cache();
+    }
+
+    /// 检查 Realm 是否允许使用本 TURN 服务
+    fn is_realm_allowed(&self, realm_id: u32) -> bool {
+        let allowed_realm_ids = self
+            .allowed_realm_ids
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        allowed_realm_ids.is_empty() || allowed_realm_ids.contains(&realm_id)
+    }
+
+    /// 注册进程内的 TURN 认证器，后注册的覆盖先注册的
+    pub fn register(authenticator: Arc<Authenticator>) {
+        *REGISTERED_AUTHENTICATOR
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(authenticator);
+    }
+
+    /// 获取进程内已注册的 TURN 认证器（TURN 服务未启动时为 None）
+    pub fn registered() -> Option<Arc<Authenticator>> {
+        REGISTERED_AUTHENTICATOR
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
     }
 
     /// 获取缓存统计信息（用于监控和调试）
@@ -32,8 +116,7 @@ impl Authenticator {
         (cache.len(), cache.cap().get())
     }
 
-    /// 清空缓存（用于测试或手动重置）
-    #[allow(dead_code)]
+    /// 清空缓存（Realm 范围变更、测试或手动重置）
     pub fn clear_cache() {
         let mut cache = AUTH_KEY_CACHE.lock().expect("auth cache poisoned");
         cache.clear();
@@ -43,12 +126,25 @@ impl Authenticator {
 
 // 全局 LRU 缓存，用于存储认证密钥
 // 缓存键: (username, realm) 的哈希值 (u128)
-// 缓存值: MD5(username:realm:psk) 的结果 (Vec<u8>)
+// 缓存值: [`CachedAuth`]，命中时仍复查 Realm 范围、Realm 状态与凭证吊销
 // 容量: 4096 个条目
 // 策略: LRU (Least Recently Used)
 const AUTH_CACHE_CAPACITY: usize = 4096;
 
-static AUTH_KEY_CACHE: Lazy<Mutex<LruCache<u128, Vec<u8>>>> = Lazy::new(|| {
+/// 缓存的认证结果
+#[derive(Debug, Clone)]
+struct CachedAuth {
+    /// MD5(username:realm:psk) 的结果
+    key: Vec<u8>,
+    /// 凭证过期时间（Unix 秒）
+    expires_at: u64,
+    /// 凭证所属 Realm
+    realm_id: u32,
+    /// 凭证序列号（用于复查吊销，无法解析时为 None）
+    serial_number: Option<u64>,
+}
+
+static AUTH_KEY_CACHE: Lazy<Mutex<LruCache<u128, CachedAuth>>> = Lazy::new(|| {
     let cap = NonZeroUsize::new(AUTH_CACHE_CAPACITY).expect("AUTH_CACHE_CAPACITY must be non-zero");
     Mutex::new(LruCache::new(cap))
 });
@@ -76,6 +172,26 @@ impl AuthHandler for Authenticator {
         username: &str,
         server_realm: &str,
         src_addr: SocketAddr,
+    ) -> Result<Vec<u8>, Error> {
+        let result = self.integrity_key(username, server_realm, src_addr);
+        let status = if result.is_ok() { "success" } else { "failure" };
+        TOKENS_VALIDATED.with_label_values(&["turn", status]).inc();
+        if result.is_err() {
+            AUTH_FAILURES
+                .with_label_values(&["turn", "invalid_credential"])
+                .inc();
+        }
+        result
+    }
+}
+
+impl Authenticator {
+    /// 校验 TURN 用户名中的凭证并计算消息完整性密钥
+    fn integrity_key(
+        &self,
+        username: &str,
+        server_realm: &str,
+        src_addr: SocketAddr,
     ) -> Result<Vec<u8>, Error> {
         debug!(
             "Processing TURN authentication request: username={:?}, realm={}, src={}",
@@ -84,16 +200,36 @@ impl AuthHandler for Authenticator {
             src_addr
         );
 
-        // 1️⃣ 首先尝试缓存命中（仅基于 username + realm，无需解析 Claims）
+        // 1️⃣ 首先尝试缓存命中（仅基于 username + realm，无需解析 Claims），
+        // 命中后仍复查有效期、Realm 范围、Realm 状态与吊销，任一失败则丢弃条目
         let cache_key = compute_cache_key(username, server_realm);
-        if let Some(cached) = AUTH_KEY_CACHE
+        let now = now_secs();
+        let cached = AUTH_KEY_CACHE
             .lock()
             .expect("auth cache poisoned")
             .get(&cache_key)
-            .cloned()
-        {
-            debug!("TURN 认证缓存命中: username={}", username);
-            return Ok(cached);
+            .cloned();
+        if let Some(cached) = cached {
+            match self.recheck_cached(&cached, now, src_addr) {
+                Ok(()) => {
+                    debug!("TURN 认证缓存命中: username={}", username);
+                    return Ok(cached.key);
+                }
+                Err(e) => {
+                    AUTH_KEY_CACHE
+                        .lock()
+                        .expect("auth cache poisoned")
+                        .pop(&cache_key);
+                    // 过期条目按未命中处理，重新解析凭证
+                    if cached.expires_at > now {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        if let Some(user) = ScopedTurnUser::parse(username) {
+            return self.scoped_integrity_key(username, &user, server_realm, src_addr, now);
         }
 
         // 2️⃣ 缓存未命中，解析 Claims 获取 key_id
@@ -106,7 +242,12 @@ impl AuthHandler for Authenticator {
             Error::Other(format!("Failed to parse claims: {e}"))
         })?;
 
+        // 拒绝不在允许范围内的 Realm（在解密之前快速失败）
+        self.ensure_realm_allowed(claims.realm_id, src_addr)?;
+
         // 3️⃣ Use AIdCredentialValidator to decrypt and verify the claims
+        // (also rejects tokens whose realm differs from the claimed realm_id,
+        // so credentials cannot be reused across tenants)
         let credential = AIdCredential {
             encrypted_token: claims.token.clone(),
             token_key_id: claims.key_id,
@@ -122,17 +263,7 @@ impl AuthHandler for Authenticator {
             })?;
 
         // 4️⃣ 验证 Realm 是否存在、未过期、状态正常
-        if let Err(e) = tokio::task::block_in_place(|| {
-            let handle = tokio::runtime::Handle::try_current()
-                .map_err(|_| "Not in tokio runtime context")?;
-            handle.block_on(async { RealmEntity::validate_realm(identity_claims.realm_id).await })
-        }) {
-            warn!(
-                "⚠️  TURN 认证 realm 验证失败: realm_id={}, actor_id={}, error={}",
-                identity_claims.realm_id, identity_claims.actor_id, e
-            );
-            return Err(Error::Other(format!("Realm validation failed: {e}")));
-        }
+        validate_realm(identity_claims.realm_id)?;
 
         let psk = identity_claims.psk;
 
@@ -144,11 +275,16 @@ impl AuthHandler for Authenticator {
         let digest = md5::compute(integrity_text.as_bytes());
         let result = digest.to_vec();
 
-        // 6️⃣ 存入缓存
-        AUTH_KEY_CACHE
-            .lock()
-            .expect("auth cache poisoned")
-            .put(cache_key, result.clone());
+        // 6️⃣ 存入缓存，随 AId Token 一同过期
+        AUTH_KEY_CACHE.lock().expect("auth cache poisoned").put(
+            cache_key,
+            CachedAuth {
+                key: result.clone(),
+                expires_at: identity_claims.expr_time,
+                realm_id: identity_claims.realm_id,
+                serial_number: identity_claims.serial_number(),
+            },
+        );
 
         debug!(
             "TURN authentication successful: realm_id={}, actor_id={}, cache_size={}/{}",
@@ -162,17 +298,185 @@ impl AuthHandler for Authenticator {
     }
 }
 
+impl Authenticator {
+    /// 复查缓存的认证结果：未过期、Realm 仍在允许范围内且状态正常、凭证未被吊销
+    fn recheck_cached(
+        &self,
+        cached: &CachedAuth,
+        now: u64,
+        src_addr: SocketAddr,
+    ) -> Result<(), Error> {
+        if now >= cached.expires_at {
+            return Err(Error::Other("Credential expired".to_string()));
+        }
+        self.ensure_realm_allowed(cached.realm_id, src_addr)?;
+        ensure_not_revoked(cached.serial_number, src_addr)?;
+        validate_realm(cached.realm_id)
+    }
+
+    /// 拒绝不在允许范围内的 Realm
+    fn ensure_realm_allowed(&self, realm_id: u32, src_addr: SocketAddr) -> Result<(), Error> {
+        if self.is_realm_allowed(realm_id) {
+            return Ok(());
+        }
+        warn!(
+            "TURN allocation rejected: realm_id={} is not allowed on this relay, src={}",
+            realm_id, src_addr
+        );
+        Err(Error::Other(format!(
+            "Realm {realm_id} is not allowed to use this TURN relay"
+        )))
+    }
+
+    /// 校验 AIS 签发的限定范围 TURN 凭证：用户名未过期、Realm 允许且有效，
+    /// 密码由共享密钥重新计算
+    fn scoped_integrity_key(
+        &self,
+        username: &str,
+        user: &ScopedTurnUser,
+        server_realm: &str,
+        src_addr: SocketAddr,
+        now: u64,
+    ) -> Result<Vec<u8>, Error> {
+        let Some(ref secret) = self.credential_secret else {
+            return Err(Error::Other(
+                "Scoped TURN credentials are not enabled".to_string(),
+            ));
+        };
+        if user.is_expired(now) {
+            warn!(
+                "TURN credential expired: realm_id={}, serial_number={}, src={}",
+                user.realm_id, user.serial_number, src_addr
+            );
+            return Err(Error::Other("Credential expired".to_string()));
+        }
+        self.ensure_realm_allowed(user.realm_id, src_addr)?;
+        ensure_not_revoked(Some(user.serial_number), src_addr)?;
+        validate_realm(user.realm_id)?;
+
+        let password = turn_password(secret, username);
+        let digest = md5::compute(format!("{username}:{server_realm}:{password}").as_bytes());
+        let result = digest.to_vec();
+        AUTH_KEY_CACHE.lock().expect("auth cache poisoned").put(
+            compute_cache_key(username, server_realm),
+            CachedAuth {
+                key: result.clone(),
+                expires_at: user.expires_at,
+                realm_id: user.realm_id,
+                serial_number: Some(user.serial_number),
+            },
+        );
+
+        debug!(
+            "TURN scoped credential accepted: realm_id={}, serial_number={}",
+            user.realm_id, user.serial_number
+        );
+        Ok(result)
+    }
+}
+
+/// 验证 Realm 是否存在、未过期、状态正常（挂起的 Realm 被拒绝）
+fn validate_realm(realm_id: u32) -> Result<(), Error> {
+    tokio::task::block_in_place(|| {
+        let handle =
+            tokio::runtime::Handle::try_current().map_err(|_| "Not in tokio runtime context")?;
+        handle.block_on(async { RealmEntity::validate_realm(realm_id).await })
+    })
+    .map(|_| ())
+    .map_err(|e| {
+        warn!(
+            "⚠️  TURN 认证 realm 验证失败: realm_id={}, error={}",
+            realm_id, e
+        );
+        Error::Other(format!("Realm validation failed: {e}"))
+    })
+}
+
+/// 拒绝序列号已被 AIS 吊销的凭证
+fn ensure_not_revoked(serial_number: Option<u64>, src_addr: SocketAddr) -> Result<(), Error> {
+    match serial_number {
+        Some(serial_number) if AIdCredentialValidator::is_serial_revoked(serial_number) => {
+            warn!(
+                "TURN allocation rejected: serial_number={} is revoked, src={}",
+                serial_number, src_addr
+            );
+            Err(Error::Other(format!(
+                "Credential {serial_number} has been revoked"
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use actrix_common::realm::RealmStatus;
+    use actrix_common::storage::db::{is_database_initialized, set_in_memory_db};
     use serial_test::serial;
     use std::net::SocketAddr;
 
+    /// 在内存数据库中准备指定状态的 Realm
+    async fn prepare_realm(realm_id: u32, status: RealmStatus) {
+        if !is_database_initialized() {
+            set_in_memory_db().await.expect("in-memory database");
+        }
+        let mut realm = match RealmEntity::get_by_realm_id(realm_id)
+            .await
+            .expect("query realm")
+        {
+            Some(realm) => realm,
+            None => {
+                let mut realm = RealmEntity::new(realm_id, format!("turn-test-{realm_id}"));
+                realm.save().await.expect("save realm");
+                realm
+            }
+        };
+        realm.set_status(status);
+        realm.save().await.expect("update realm status");
+    }
+
+    fn cached(key: Vec<u8>, expires_at: u64, realm_id: u32) -> CachedAuth {
+        CachedAuth {
+            key,
+            expires_at,
+            realm_id,
+            serial_number: None,
+        }
+    }
+
     #[test]
     fn test_authenticator_creation() {
         let _auth = Authenticator::new().expect("Failed to create authenticator");
     }
 
+    #[test]
+    #[serial]
+    fn test_realm_scope() {
+        let unrestricted = Authenticator::new().expect("Failed to create authenticator");
+        assert!(unrestricted.is_realm_allowed(1));
+        assert!(unrestricted.is_realm_allowed(u32::MAX));
+
+        let scoped = Authenticator::with_allowed_realms([1001, 1002])
+            .expect("Failed to create scoped authenticator");
+        assert!(scoped.is_realm_allowed(1001));
+        assert!(scoped.is_realm_allowed(1002));
+        assert!(!scoped.is_realm_allowed(2001));
+
+        scoped.set_allowed_realms([2001]);
+        assert!(scoped.is_realm_allowed(2001));
+        assert!(!scoped.is_realm_allowed(1001));
+        scoped.set_allowed_realms(std::iter::empty());
+        assert!(scoped.is_realm_allowed(1001));
+    }
+
     #[test]
     fn test_cache_key_computation() {
         let key1 = compute_cache_key("user1", "realm1");
@@ -232,9 +536,10 @@ mod tests {
         );
     }
 
-    #[test]
+    #[tokio::test(flavor = "multi_thread")]
     #[serial]
-    fn test_auth_handle_uses_cached_key_before_claim_decode() {
+    async fn test_auth_handle_uses_cached_key_before_claim_decode() {
+        prepare_realm(1001, RealmStatus::Normal).await;
         Authenticator::clear_cache();
         let auth = Authenticator::new().expect("authenticator should initialize");
         let username = "non-decodable-user";
@@ -246,7 +551,7 @@ mod tests {
         AUTH_KEY_CACHE
             .lock()
             .expect("auth cache poisoned")
-            .put(cache_key, expected_key.clone());
+            .put(cache_key, cached(expected_key.clone(), u64::MAX, 1001));
 
         let result = auth
             .auth_handle(username, server_realm, src_addr)
@@ -254,4 +559,119 @@ mod tests {
 
         assert_eq!(result, expected_key);
     }
+
+    #[test]
+    #[serial]
+    fn test_scoped_credential_checks_before_realm_lookup() {
+        Authenticator::clear_cache();
+        let src_addr: SocketAddr = "127.0.0.1:3478".parse().expect("valid socket addr");
+        let valid = ScopedTurnUser {
+            expires_at: u64::MAX,
+            realm_id: 1001,
+            serial_number: 7,
+        }
+        .username();
+        let expired = ScopedTurnUser {
+            expires_at: 1,
+            realm_id: 1001,
+            serial_number: 7,
+        }
+        .username();
+
+        let err = Authenticator::new()
+            .expect("authenticator should initialize")
+            .auth_handle(&valid, "actor-rtc.local", src_addr)
+            .expect_err("scoped credential requires a secret");
+        assert!(
+            err.to_string().contains("not enabled"),
+            "unexpected error: {err}"
+        );
+
+        let auth = Authenticator::with_allowed_realms([2002])
+            .expect("authenticator should initialize")
+            .with_credential_secret("shared-key");
+        let err = auth
+            .auth_handle(&expired, "actor-rtc.local", src_addr)
+            .expect_err("expired credential should be rejected");
+        assert!(
+            err.to_string().contains("expired"),
+            "unexpected error: {err}"
+        );
+
+        let err = auth
+            .auth_handle(&valid, "actor-rtc.local", src_addr)
+            .expect_err("realm outside the allowed set should be rejected");
+        assert!(
+            err.to_string().contains("not allowed"),
+            "unexpected error: {err}"
+        );
+        assert_eq!(Authenticator::cache_stats().0, 0);
+    }
+
+    #[test]
+    #[serial]
+    fn test_expired_cache_entry_is_evicted() {
+        Authenticator::clear_cache();
+        let auth = Authenticator::new().expect("authenticator should initialize");
+        let username = "non-decodable-user";
+        let server_realm = "actor-rtc.local";
+        let src_addr: SocketAddr = "127.0.0.1:3478".parse().expect("valid socket addr");
+
+        AUTH_KEY_CACHE.lock().expect("auth cache poisoned").put(
+            compute_cache_key(username, server_realm),
+            cached(vec![0xAB; 16], 1, 1001),
+        );
+
+        assert!(auth.auth_handle(username, server_realm, src_addr).is_err());
+        assert_eq!(Authenticator::cache_stats().0, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[serial]
+    async fn test_cached_auth_rechecks_scope_and_realm_status() {
+        prepare_realm(1001, RealmStatus::Normal).await;
+        Authenticator::clear_cache();
+        let src_addr: SocketAddr = "127.0.0.1:3478".parse().expect("valid socket addr");
+        let username = ScopedTurnUser {
+            expires_at: u64::MAX,
+            realm_id: 1001,
+            serial_number: 7,
+        }
+        .username();
+        let auth = Authenticator::with_allowed_realms([1001])
+            .expect("authenticator should initialize")
+            .with_credential_secret("shared-key");
+
+        auth.auth_handle(&username, "actor-rtc.local", src_addr)
+            .expect("credential within scope should authenticate");
+        assert_eq!(Authenticator::cache_stats().0, 1);
+
+        // 收窄范围后，同一凭证的第二次认证必须失败
+        auth.set_allowed_realms([2002]);
+        let err = auth
+            .auth_handle(&username, "actor-rtc.local", src_addr)
+            .expect_err("narrowed scope should reject the credential");
+        assert!(
+            err.to_string().contains("not allowed"),
+            "unexpected error: {err}"
+        );
+
+        // 恢复范围后重新缓存，再挂起 Realm：缓存命中也必须被拒绝
+        auth.set_allowed_realms([1001]);
+        auth.auth_handle(&username, "actor-rtc.local", src_addr)
+            .expect("restored scope should authenticate");
+        assert_eq!(Authenticator::cache_stats().0, 1);
+
+        prepare_realm(1001, RealmStatus::Suspended).await;
+        let err = auth
+            .auth_handle(&username, "actor-rtc.local", src_addr)
+            .expect_err("suspended realm should be rejected on cache hit");
+        assert!(
+            err.to_string().contains("Realm validation failed"),
+            "unexpected error: {err}"
+        );
+        assert_eq!(Authenticator::cache_stats().0, 0);
+
+        prepare_realm(1001, RealmStatus::Normal).await;
+    }
 }
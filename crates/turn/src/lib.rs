@@ -5,11 +5,13 @@
 // TURN server implementation modules
 mod authenticator;
 pub mod error;
+pub mod metrics;
 
 // Re-export types for convenience
 pub use actr_protocol::turn::Claims;
 pub use authenticator::Authenticator;
 pub use error::{ErrorSeverity, TurnError};
+pub use metrics::{ALLOCATION_METRICS_INTERVAL, AllocationMetrics};
 
 use std::net::IpAddr;
 use std::str::FromStr;
@@ -22,6 +24,19 @@ use turn_crate::server::config::*;
 use turn_crate::server::*;
 use webrtc_util::vnet::net::*;
 
+/// 默认中继端口范围（IANA 动态端口范围）
+pub const DEFAULT_RELAY_PORT_RANGE: (u16, u16) = (49152, 65535);
+
+/// TURN 监听套接字及其对外宣告的中继地址
+///
+/// 双栈部署时 IPv4 与 IPv6 各一个监听套接字，客户端从哪个地址族接入，
+/// 就在该地址族的宣告地址上分配中继地址
+#[derive(Debug, Clone)]
+pub struct TurnListener {
+    pub socket: Arc<UdpSocket>,
+    pub advertised_ip: String,
+}
+
 // Create and initialize the TURN server
 pub async fn create_turn_server(
     socket: Arc<UdpSocket>,
@@ -29,7 +44,66 @@ pub async fn create_turn_server(
     realm: &str,
     auth_handler: Arc<dyn AuthHandler + Send + Sync>,
 ) -> error::Result<Server> {
-    info!("Creating TURN server with advertised IP: {}", advertised_ip);
+    create_turn_server_with_listeners(
+        vec![TurnListener {
+            socket,
+            advertised_ip: advertised_ip.to_string(),
+        }],
+        realm,
+        DEFAULT_RELAY_PORT_RANGE,
+        auth_handler,
+    )
+    .await
+}
+
+// Create and initialize the TURN server on one or more listeners (e.g. IPv4 + IPv6),
+// allocating relay addresses from `relay_port_range` (inclusive)
+pub async fn create_turn_server_with_listeners(
+    listeners: Vec<TurnListener>,
+    realm: &str,
+    relay_port_range: (u16, u16),
+    auth_handler: Arc<dyn AuthHandler + Send + Sync>,
+) -> error::Result<Server> {
+    let mut conn_configs = Vec::with_capacity(listeners.len());
+    for listener in listeners {
+        conn_configs.push(listener_conn_config(listener, relay_port_range)?);
+    }
+
+    let server_config = ServerConfig {
+        conn_configs,
+        realm: realm.to_string(),
+        auth_handler,
+        channel_bind_timeout: std::time::Duration::from_secs(600), // 10 minutes
+        alloc_close_notify: Some(metrics::close_notifier()),       // Count closed allocations
+    };
+
+    // Create the actual server instance
+    let server = match Server::new(server_config).await {
+        Ok(server) => server,
+        Err(e) => {
+            let err_msg = format!("Failed to create TURN server: {e}");
+            error!("{}", err_msg);
+            return Err(TurnError::ServerStartFailed { reason: err_msg });
+        }
+    };
+
+    info!("TURN server created successfully (includes STUN functionality)");
+    Ok(server)
+}
+
+// Build the connection config (relay address generator) for a single listener
+fn listener_conn_config(
+    listener: TurnListener,
+    relay_port_range: (u16, u16),
+) -> error::Result<ConnConfig> {
+    let TurnListener {
+        socket,
+        advertised_ip,
+    } = listener;
+    info!(
+        "Creating TURN listener with advertised IP: {}",
+        advertised_ip
+    );
 
     // Get the local address of the socket
     let local_addr = match socket.local_addr() {
@@ -42,55 +116,36 @@ pub async fn create_turn_server(
     };
 
     info!(
-        "TURN server will use local address: {} and advertised IP: {}",
+        "TURN listener will use local address: {} and advertised IP: {}",
         local_addr, advertised_ip
     );
 
     // Parse advertised IP
-    let relay_ip = match IpAddr::from_str(advertised_ip) {
+    let relay_ip = match IpAddr::from_str(&advertised_ip) {
         Ok(ip) => ip,
         Err(e) => {
             let err_msg = format!("Invalid advertised IP address: {e}");
             error!("{}", err_msg);
             return Err(TurnError::Configuration {
                 field: "advertised_ip".to_string(),
-                value: advertised_ip.to_string(),
+                value: advertised_ip,
             });
         }
     };
 
-    // Create TURN server configuration with dynamic relay port range
-    // Default ephemeral range: 49152-65535 (IANA recommended)
-    let server_config = ServerConfig {
-        conn_configs: vec![ConnConfig {
-            conn: socket,
-            relay_addr_generator: Box::new(RelayAddressGeneratorRanges {
-                relay_address: relay_ip,
-                min_port: 49152,
-                max_port: 65535,
-                max_retries: 10,
-                address: local_addr,
-                net: Arc::new(Net::new(None)),
-            }),
-        }],
-        realm: realm.to_string(),
-        auth_handler,
-        channel_bind_timeout: std::time::Duration::from_secs(600), // 10 minutes
-        alloc_close_notify: None, // No allocation close notification handler
-    };
-
-    // Create the actual server instance
-    let server = match Server::new(server_config).await {
-        Ok(server) => server,
-        Err(e) => {
-            let err_msg = format!("Failed to create TURN server: {e}");
-            error!("{}", err_msg);
-            return Err(TurnError::ServerStartFailed { reason: err_msg });
-        }
-    };
-
-    info!("TURN server created successfully (includes STUN functionality)");
-    Ok(server)
+    // Create TURN connection configuration with the configured relay port range
+    let (min_port, max_port) = relay_port_range;
+    Ok(ConnConfig {
+        conn: socket,
+        relay_addr_generator: Box::new(RelayAddressGeneratorRanges {
+            relay_address: relay_ip,
+            min_port,
+            max_port,
+            max_retries: 10,
+            address: local_addr,
+            net: Arc::new(Net::new(None)),
+        }),
+    })
 }
 
 // Shutdown the TURN server
@@ -155,4 +210,32 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_create_dual_stack_turn_server() -> anyhow::Result<()> {
+        let v4 = Arc::new(UdpSocket::bind("127.0.0.1:0").await?);
+        let mut listeners = vec![TurnListener {
+            socket: v4,
+            advertised_ip: "127.0.0.1".to_string(),
+        }];
+        // 沙箱等环境可能没有 IPv6 回环地址
+        if let Ok(v6) = UdpSocket::bind("[::1]:0").await {
+            listeners.push(TurnListener {
+                socket: Arc::new(v6),
+                advertised_ip: "::1".to_string(),
+            });
+        }
+        let auth_handler: Arc<dyn AuthHandler + Send + Sync> = Arc::new(MockAuthHandler);
+
+        let server = create_turn_server_with_listeners(
+            listeners,
+            "test.realm",
+            (40000, 40100),
+            auth_handler,
+        )
+        .await?;
+        shutdown_turn_server(&server).await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,119 @@
+//! TURN 分配指标采集
+//!
+//! - 分配关闭通过 `alloc_close_notify` 通道计入 `actrix_turn_allocations_total{status="closed"}`
+//! - 活跃会话数与新建分配由 [`AllocationMetrics::refresh`] 定期轮询服务器的分配表得到，
+//!   存活时间短于轮询间隔的分配不计入 `created`
+//! - 中继字节数在轮询和分配关闭时按增量计入所属 Realm 的用量
+//!   （Realm 取自用户名中的 Claims 或限定范围 TURN 凭证，见 [`actrix_common::realm::usage`]）
+
+use actr_protocol::turn::Claims;
+use actrix_common::aid::ScopedTurnUser;
+use actrix_common::metrics::{TURN_ACTIVE_SESSIONS, TURN_ALLOCATIONS};
+use actrix_common::realm::usage;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+use turn_crate::allocation::AllocationInfo;
+use turn_crate::allocation::five_tuple::FiveTuple;
+use turn_crate::server::Server;
+
+/// 分配表轮询间隔
+pub const ALLOCATION_METRICS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 分配关闭通知通道容量
+const CLOSE_NOTIFY_CAPACITY: usize = 256;
+
+/// 各分配已计入用量的中继字节数（轮询与关闭通知共用）
+static ACCOUNTED_BYTES: LazyLock<Mutex<HashMap<FiveTuple, usize>>> =
+    LazyLock::new(Default::default);
+
+/// 将分配自上次计量以来新增的中继字节数计入所属 Realm
+///
+/// `closed` 为 true 时移除该分配的计量记录
+fn account_relayed_bytes(
+    five_tuple: FiveTuple,
+    username: &str,
+    relayed_bytes: usize,
+    closed: bool,
+) {
+    let previous = {
+        let mut accounted = ACCOUNTED_BYTES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if closed {
+            accounted.remove(&five_tuple)
+        } else {
+            accounted.insert(five_tuple, relayed_bytes)
+        }
+    };
+    let delta = relayed_bytes.saturating_sub(previous.unwrap_or(0));
+    if delta == 0 {
+        return;
+    }
+    if let Some(user) = ScopedTurnUser::parse(username) {
+        usage::record_turn_bytes(user.realm_id, delta as u64);
+        return;
+    }
+    match Claims::decode(username) {
+        Ok(claims) => usage::record_turn_bytes(claims.realm_id, delta as u64),
+        Err(e) => debug!("Skip TURN usage for undecodable username: {e}"),
+    }
+}
+
+/// 创建分配关闭通知通道，并在后台统计关闭的分配（服务器关闭后通道随之结束）
+pub(crate) fn close_notifier() -> mpsc::Sender<AllocationInfo> {
+    let (tx, mut rx) = mpsc::channel::<AllocationInfo>(CLOSE_NOTIFY_CAPACITY);
+    tokio::spawn(async move {
+        while let Some(info) = rx.recv().await {
+            debug!("TURN allocation closed: {:?}", info.five_tuple);
+            TURN_ALLOCATIONS.with_label_values(&["closed"]).inc();
+            account_relayed_bytes(info.five_tuple, &info.username, info.relayed_bytes, true);
+        }
+    });
+    tx
+}
+
+/// 基于分配表快照的 TURN 活跃会话统计
+#[derive(Debug, Default)]
+pub struct AllocationMetrics {
+    known: HashSet<FiveTuple>,
+}
+
+impl AllocationMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 读取服务器当前的分配表，更新活跃会话数并统计新出现的分配
+    pub async fn refresh(&mut self, server: &Server) {
+        let allocations = match server.get_allocations_info(None).await {
+            Ok(allocations) => allocations,
+            Err(e) => {
+                debug!("Failed to read TURN allocations: {e}");
+                return;
+            }
+        };
+
+        let mut current = HashSet::with_capacity(allocations.len());
+        for (five_tuple, info) in allocations {
+            account_relayed_bytes(five_tuple, &info.username, info.relayed_bytes, false);
+            current.insert(five_tuple);
+        }
+        let created = current.difference(&self.known).count();
+        if created > 0 {
+            TURN_ALLOCATIONS
+                .with_label_values(&["created"])
+                .inc_by(created as u64);
+        }
+        TURN_ACTIVE_SESSIONS.set(current.len() as i64);
+        self.known = current;
+    }
+
+    /// 服务器停止后清零活跃会话数
+    pub fn reset(&mut self) {
+        self.known.clear();
+        TURN_ACTIVE_SESSIONS.set(0);
+    }
+}
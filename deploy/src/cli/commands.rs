@@ -1,5 +1,6 @@
 //! CLI command definitions
 
+use crate::docker::Topology;
 use clap::Subcommand;
 use std::path::PathBuf;
 
@@ -44,7 +45,95 @@ pub enum Commands {
         /// Use docker-compose instead of docker compose
         #[arg(long)]
         legacy: bool,
+        /// Container topology: one all-in-one container, or one container per service
+        #[arg(long, value_enum, default_value = "single")]
+        topology: Topology,
+    },
+    /// Generate Kubernetes manifests (Deployment, Service, ConfigMap, Secret)
+    K8s {
+        /// Path to actrix config file
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+        /// Output manifest path
+        #[arg(short, long, default_value = "actrix-k8s.yaml")]
+        output: PathBuf,
+        /// Resource name prefix
+        #[arg(long, default_value = "actrix")]
+        name: String,
+        /// Target namespace (omitted from the manifests when not set)
+        #[arg(short, long)]
+        namespace: Option<String>,
+        /// Container image
+        #[arg(long, default_value = "actrix:latest")]
+        image: String,
+        /// Size of the data PersistentVolumeClaim
+        #[arg(long, default_value = "1Gi")]
+        storage_size: String,
+    },
+    /// Upgrade a config file written for an older actrix version
+    MigrateConfig {
+        /// Path to the config file to upgrade
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+        /// Write the upgraded config here instead of in place (in place keeps a .bak copy)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Print the upgraded config without writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Roll out the binary, config and systemd unit to hosts over SSH, one host at a time
+    Rollout {
+        /// Path to the host inventory
+        #[arg(short, long, default_value = "hosts.toml")]
+        inventory: PathBuf,
+        /// Only deploy to these hosts (comma separated names from the inventory)
+        #[arg(long, value_delimiter = ',')]
+        hosts: Vec<String>,
+        /// Print the ssh/scp commands without running them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Manage realms on a running node through its admin API
+    Realm {
+        #[command(subcommand)]
+        command: RealmCommands,
     },
     /// Run interactive menu
     Menu,
 }
+
+/// Realm subcommands
+#[derive(Subcommand)]
+pub enum RealmCommands {
+    /// Create a realm with ACL rules and optional reserved ActrId serial numbers
+    Create {
+        /// Realm ID
+        #[arg(long)]
+        id: u32,
+        /// Realm name
+        #[arg(long)]
+        name: String,
+        /// Node config file, used for the admin URL and actrix_shared_key
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+        /// Node base URL (default: derived from the config, e.g. https://host:8443)
+        #[arg(long)]
+        url: Option<String>,
+        /// Expiry time as a Unix timestamp in seconds
+        #[arg(long)]
+        expires_at: Option<i64>,
+        /// Allow rule FROM=TO (repeatable); realms without rules deny all access
+        #[arg(long, value_name = "FROM=TO")]
+        allow: Vec<String>,
+        /// Deny rule FROM=TO (repeatable)
+        #[arg(long, value_name = "FROM=TO")]
+        deny: Vec<String>,
+        /// Number of ActrId serial numbers to reserve for pre-provisioned actors
+        #[arg(long, default_value_t = 0)]
+        reserve: u64,
+        /// Note recorded with the serial number reservation
+        #[arg(long)]
+        note: Option<String>,
+    },
+}
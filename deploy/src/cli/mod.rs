@@ -4,4 +4,4 @@ mod args;
 mod commands;
 
 pub use args::Cli;
-pub use commands::Commands;
+pub use commands::{Commands, RealmCommands};
@@ -0,0 +1,253 @@
+//! 旧版本配置文件迁移（`deploy migrate-config`）
+//!
+//! 按规则表把改名或移动的字段搬到新位置，删除已不再读取的字段并给出说明。基于 toml_edit
+//! 修改，保留原文件中的注释与格式；新旧位置同时存在时不做改动，留给运维人员确认。
+
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, TableLike};
+
+/// 改名或移动的字段：(旧路径, 新路径)，按顺序应用
+const MOVED_FIELDS: &[(&str, &str)] = &[
+    ("log_level", "observability.filter_level"),
+    ("log", "observability.log"),
+    ("log_output", "observability.log.output"),
+    ("log_rotate", "observability.log.rotate"),
+    ("log_path", "observability.log.path"),
+    ("tracing", "observability.tracing"),
+    ("services.ks.grpc_tls", "services.ks.grpc.bind.tls"),
+];
+
+/// 已移除的字段：(路径, 说明)
+const REMOVED_FIELDS: &[(&str, &str)] = &[(
+    "acl",
+    "ACL 始终生效，没有匹配规则时拒绝；规则随服务注册写入或通过 Realm 管理 API 维护",
+)];
+
+/// 迁移结果
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// 已移动的字段 (旧路径, 新路径)
+    pub moved: Vec<(&'static str, &'static str)>,
+    /// 已删除的字段 (路径, 说明)
+    pub removed: Vec<(&'static str, &'static str)>,
+    /// 新旧位置同时存在、未处理的字段 (旧路径, 新路径)
+    pub conflicts: Vec<(&'static str, &'static str)>,
+}
+
+impl MigrationReport {
+    /// 是否没有任何需要迁移的字段
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty() && self.removed.is_empty() && self.conflicts.is_empty()
+    }
+
+    fn print(&self) {
+        for (from, to) in &self.moved {
+            println!("   ✏️  {from} → {to}");
+        }
+        for (path, note) in &self.removed {
+            println!("   🗑️  已移除 {path}：{note}");
+        }
+        for (from, to) in &self.conflicts {
+            println!("   ⚠️  {from} 与 {to} 同时存在，未做改动，请确认后手动删除 {from}");
+        }
+    }
+}
+
+/// 就地迁移配置文档
+pub fn migrate_document(doc: &mut DocumentMut) -> MigrationReport {
+    let mut report = MigrationReport::default();
+    let root = doc.as_table_mut();
+
+    for &(from, to) in MOVED_FIELDS {
+        let from_path: Vec<&str> = from.split('.').collect();
+        let to_path: Vec<&str> = to.split('.').collect();
+        if !contains(&*root, &from_path) {
+            continue;
+        }
+        if contains(&*root, &to_path) {
+            report.conflicts.push((from, to));
+            continue;
+        }
+        if let Some(item) = take(root, &from_path)
+            && insert(root, &to_path, item)
+        {
+            report.moved.push((from, to));
+        }
+    }
+
+    for &(path, note) in REMOVED_FIELDS {
+        let path_segments: Vec<&str> = path.split('.').collect();
+        if take(root, &path_segments).is_some() {
+            report.removed.push((path, note));
+        }
+    }
+
+    report
+}
+
+/// 迁移配置文件
+///
+/// 未指定 `output` 时就地改写，原文件备份为 `<config>.bak`；`dry_run` 时只打印结果
+pub fn migrate_config_file(config: &Path, output: Option<&Path>, dry_run: bool) -> Result<()> {
+    let content = std::fs::read_to_string(config)
+        .with_context(|| format!("无法读取配置文件: {}", config.display()))?;
+    let mut doc = content
+        .parse::<DocumentMut>()
+        .with_context(|| format!("解析配置文件失败: {}", config.display()))?;
+
+    let report = migrate_document(&mut doc);
+    if report.is_empty() {
+        println!("✅ {} 无需迁移", config.display());
+        return Ok(());
+    }
+    println!("📝 {} 的迁移内容：", config.display());
+    report.print();
+
+    let migrated = doc.to_string();
+    check_migrated(&migrated);
+
+    if dry_run {
+        println!("\n{migrated}");
+        return Ok(());
+    }
+
+    let target = match output {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let backup = PathBuf::from(format!("{}.bak", config.display()));
+            std::fs::copy(config, &backup)
+                .with_context(|| format!("无法备份配置文件到 {}", backup.display()))?;
+            println!("💾 原文件已备份到: {}", backup.display());
+            config.to_path_buf()
+        }
+    };
+    std::fs::write(&target, migrated)
+        .with_context(|| format!("无法写入配置文件: {}", target.display()))?;
+    println!("✅ 已写入迁移后的配置: {}", target.display());
+    Ok(())
+}
+
+/// 用当前版本的配置结构检查迁移结果，问题只提示不阻止写入
+fn check_migrated(content: &str) {
+    match toml::from_str::<ActrixConfig>(content) {
+        Ok(config) => {
+            if let Err(errors) = config.validate() {
+                println!("⚠️  迁移后的配置仍未通过校验：");
+                for error in errors {
+                    println!("   - {error}");
+                }
+            }
+        }
+        Err(e) => println!("⚠️  迁移后的配置无法被当前版本解析: {e}"),
+    }
+}
+
+fn contains(table: &dyn TableLike, path: &[&str]) -> bool {
+    match path {
+        [] => false,
+        [key] => table.contains_key(key),
+        [key, rest @ ..] => table
+            .get(key)
+            .and_then(Item::as_table_like)
+            .is_some_and(|child| contains(child, rest)),
+    }
+}
+
+/// 取出指定路径的字段，移走后变空的父表一并删除
+fn take(table: &mut dyn TableLike, path: &[&str]) -> Option<Item> {
+    match path {
+        [] => None,
+        [key] => table.remove(key),
+        [key, rest @ ..] => {
+            let child = table.get_mut(key)?.as_table_like_mut()?;
+            let item = take(child, rest);
+            if item.is_some() && child.is_empty() {
+                table.remove(key);
+            }
+            item
+        }
+    }
+}
+
+/// 写入指定路径，缺失的父表按隐式表创建（没有直接字段时不输出表头）
+fn insert(table: &mut dyn TableLike, path: &[&str], item: Item) -> bool {
+    match path {
+        [] => false,
+        [key] => {
+            table.insert(key, item);
+            true
+        }
+        [key, rest @ ..] => {
+            if !table.contains_key(key) {
+                let mut child = Table::new();
+                child.set_implicit(true);
+                table.insert(key, Item::Table(child));
+            }
+            match table.get_mut(key).and_then(Item::as_table_like_mut) {
+                Some(child) => insert(child, rest, item),
+                None => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_legacy_fields() {
+        let mut doc = r#"
+name = "edge-1"
+log_level = "debug"
+log_output = "file"
+
+[tracing]
+enable = true
+endpoint = "http://127.0.0.1:4317"
+
+[acl]
+enabled = true
+default_policy = "deny"
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+
+        let report = migrate_document(&mut doc);
+        assert_eq!(report.moved.len(), 3);
+        assert_eq!(report.removed.len(), 1);
+        assert!(report.conflicts.is_empty());
+
+        let migrated: toml::Table = toml::from_str(&doc.to_string()).unwrap();
+        let observability = &migrated["observability"];
+        assert_eq!(observability["filter_level"].as_str(), Some("debug"));
+        assert_eq!(observability["log"]["output"].as_str(), Some("file"));
+        assert_eq!(observability["tracing"]["enable"].as_bool(), Some(true));
+        assert!(!migrated.contains_key("log_level"));
+        assert!(!migrated.contains_key("tracing"));
+        assert!(!migrated.contains_key("acl"));
+        assert!(migrate_document(&mut doc).is_empty());
+    }
+
+    #[test]
+    fn test_conflicting_fields_left_untouched() {
+        let mut doc = r#"
+log_level = "debug"
+
+[observability]
+filter_level = "info"
+"#
+        .parse::<DocumentMut>()
+        .unwrap();
+
+        let report = migrate_document(&mut doc);
+        assert_eq!(
+            report.conflicts,
+            [("log_level", "observability.filter_level")]
+        );
+        assert_eq!(doc["log_level"].as_str(), Some("debug"));
+        assert_eq!(doc["observability"]["filter_level"].as_str(), Some("info"));
+    }
+}
@@ -2,6 +2,7 @@
 
 mod deployment_config;
 mod install_config;
+mod migration;
 mod network_config;
 mod ssl_config;
 mod system_config;
@@ -10,6 +11,7 @@ mod wizard;
 
 pub use deployment_config::DeploymentConfig;
 pub use install_config::InstallConfig;
+pub use migration::migrate_config_file;
 pub use network_config::NetworkConfig;
 pub use ssl_config::SslConfig;
 pub use system_config::SystemConfig;
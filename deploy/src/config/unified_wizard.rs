@@ -216,8 +216,10 @@ impl UnifiedConfigWizard {
                 config.bind.http = Some(HttpBindConfig {
                     domain_name: "localhost".to_string(),
                     advertised_ip: server_host.clone(),
+                    advertised_ipv6: None,
                     ip: "0.0.0.0".to_string(),
                     port: http_port,
+                    redirect_to_https: false,
                 });
             }
         }
@@ -249,6 +251,7 @@ impl UnifiedConfigWizard {
                 config.bind.https = Some(HttpsBindConfig {
                     domain_name: "localhost".to_string(),
                     advertised_ip: server_host.clone(),
+                    advertised_ipv6: None,
                     ip: "0.0.0.0".to_string(),
                     port: https_port,
                     cert: cert_path,
@@ -350,6 +353,9 @@ impl UnifiedConfigWizard {
             connect_timeout_secs: 30,
             status_report_interval_secs: 60,
             health_check_interval_secs: 30,
+            max_reconnect_delay_secs: 60,
+            report_buffer_size: 120,
+            metrics_sample_interval_secs: 15,
             enable_tls,
             tls_domain,
             client_cert: None,
@@ -365,8 +371,10 @@ impl UnifiedConfigWizard {
             client: SupervisorClientConfig {
                 node_id,
                 endpoint,
+                fallback_endpoints: Vec::new(),
                 shared_secret,
             },
+            update: None,
         });
 
         println!();
@@ -519,6 +527,11 @@ impl UnifiedConfigWizard {
             value(supervisor.status_report_interval_secs as i64);
         supervisor_table["health_check_interval_secs"] =
             value(supervisor.health_check_interval_secs as i64);
+        supervisor_table["max_reconnect_delay_secs"] =
+            value(supervisor.max_reconnect_delay_secs as i64);
+        supervisor_table["report_buffer_size"] = value(supervisor.report_buffer_size as i64);
+        supervisor_table["metrics_sample_interval_secs"] =
+            value(supervisor.metrics_sample_interval_secs as i64);
         supervisor_table["enable_tls"] = value(supervisor.enable_tls);
         if let Some(ref domain) = supervisor.tls_domain {
             supervisor_table["tls_domain"] = value(domain);
@@ -1,26 +1,54 @@
 //! Docker Compose 配置生成器实现
 
-use actrix_common::config::ActrixConfig;
+use actrix_common::config::ks::KsClientConfig;
+use actrix_common::config::signaling::AisClientConfig;
+use actrix_common::config::{
+    ActrixConfig, ENABLE_AIS, ENABLE_KS, ENABLE_SIGNALING, ENABLE_STUN, ENABLE_TURN,
+};
 use anyhow::{Context, Result};
 use serde_json::{Value, json};
 use std::fs;
 use std::path::Path;
 
+/// 拆分拓扑下各服务配置文件所在目录（相对 docker-compose.yml）
+const SPLIT_CONFIG_DIR: &str = "actrix-config";
+
+/// 容器拓扑
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Topology {
+    /// 所有启用的服务运行在同一个容器中
+    #[default]
+    Single,
+    /// KS、AIS、Signaling 与 STUN/TURN 分别运行在独立容器中
+    Split,
+}
+
 /// Docker Compose 配置生成器
+///
+/// 拆分拓扑下从同一份 ActrixConfig 为每个启用的服务派生独立配置：
+/// - 每个容器只设置自己的 `enable` 位，`name` 与 Supervisor `node_id` 追加服务后缀
+/// - KS gRPC 监听 `0.0.0.0`，AIS / Signaling 未显式配置依赖时经共享网络按服务名访问 KS 与 AIS
+/// - `depends_on` 按 KS → AIS → Signaling 排序启动
+/// - 所有容器共享数据卷，Realm 等 SQLite 数据在容器间一致
 pub struct DockerComposeGenerator {
     config: ActrixConfig,
+    topology: Topology,
 }
 
 impl DockerComposeGenerator {
     /// 从配置文件创建生成器
-    pub fn from_config_file(config_path: &Path) -> Result<Self> {
+    pub fn from_config_file(config_path: &Path, topology: Topology) -> Result<Self> {
         let config_content = fs::read_to_string(config_path)
             .with_context(|| format!("无法读取配置文件: {}", config_path.display()))?;
 
         let config: ActrixConfig =
             toml::from_str(&config_content).with_context(|| "解析配置文件失败")?;
 
-        Ok(Self { config })
+        Ok(Self::new(config, topology))
+    }
+
+    pub fn new(config: ActrixConfig, topology: Topology) -> Self {
+        Self { config, topology }
     }
 
     /// 生成 docker-compose.yml 内容
@@ -39,9 +67,18 @@ impl DockerComposeGenerator {
             }
         });
 
-        // 生成主服务
-        let main_service = self.generate_main_service()?;
-        compose["services"]["actrix"] = main_service;
+        match self.topology {
+            Topology::Single => {
+                // 生成主服务
+                let main_service = self.generate_main_service()?;
+                compose["services"]["actrix"] = main_service;
+            }
+            Topology::Split => {
+                for (role, config) in self.split_configs()? {
+                    compose["services"][role] = self.generate_split_service(role, &config);
+                }
+            }
+        }
 
         // 转换为 YAML
         let yaml = serde_yaml::to_string(&compose).with_context(|| "转换为 YAML 失败")?;
@@ -51,22 +88,212 @@ impl DockerComposeGenerator {
 
     /// 生成主 Actrix 服务配置
     fn generate_main_service(&self) -> Result<Value> {
+        let mut ports = self.http_ports();
+        ports.extend(self.ice_ports());
+
+        let service = json!({
+            "image": "actrix:latest",
+            "container_name": "actrix",
+            "restart": "unless-stopped",
+            "ports": ports,
+            "environment": Self::environment(),
+            "volumes": [
+                "./config.toml:/app/config.toml:ro",
+                "actrix-data:/app/data",
+                "actrix-certs:/app/certificates:ro"
+            ],
+            "networks": ["actrix-network"],
+            "command": ["--config", "/app/config.toml"]
+        });
+
+        Ok(service)
+    }
+
+    /// 生成拆分拓扑中单个服务的容器配置
+    fn generate_split_service(&self, role: &str, config: &ActrixConfig) -> Value {
         let mut ports = Vec::new();
-        let mut environment = Vec::new();
+        if self.publishes_http(config) {
+            ports.extend(self.http_ports());
+        }
+        if config.is_ice_enabled() {
+            ports.extend(self.ice_ports());
+        }
+
+        let mut service = json!({
+            "image": "actrix:latest",
+            "container_name": format!("actrix-{role}"),
+            "restart": "unless-stopped",
+            "environment": Self::environment(),
+            "volumes": [
+                format!("./{SPLIT_CONFIG_DIR}/{role}.toml:/app/config.toml:ro"),
+                "actrix-data:/app/data",
+                "actrix-certs:/app/certificates:ro"
+            ],
+            "networks": ["actrix-network"],
+            "command": ["--config", "/app/config.toml"]
+        });
+        if !ports.is_empty() {
+            service["ports"] = json!(ports);
+        }
+        if config.is_ks_enabled() {
+            // KS gRPC 只在共享网络内可达
+            service["expose"] = json!([self.config.ks_grpc_bind().port.to_string()]);
+        }
+
+        let mut depends_on = Vec::new();
+        if (config.is_ais_enabled() || config.is_signaling_enabled()) && self.config.is_ks_enabled()
+        {
+            depends_on.push("ks");
+        }
+        if config.is_signaling_enabled() && self.config.is_ais_enabled() {
+            depends_on.push("ais");
+        }
+        if !depends_on.is_empty() {
+            service["depends_on"] = json!(depends_on);
+        }
+
+        service
+    }
+
+    /// 拆分拓扑下各服务的 (服务名, 配置)，按启动顺序排列
+    ///
+    /// 单容器拓扑返回空列表
+    pub fn split_configs(&self) -> Result<Vec<(&'static str, ActrixConfig)>> {
+        if self.topology == Topology::Single {
+            return Ok(Vec::new());
+        }
+
+        let mut roles = Vec::new();
+        if self.config.is_ks_enabled() {
+            roles.push(("ks", ENABLE_KS));
+        }
+        if self.config.is_ais_enabled() {
+            roles.push(("ais", ENABLE_AIS));
+        }
+        if self.config.is_signaling_enabled() {
+            roles.push(("signaling", ENABLE_SIGNALING));
+        }
+        if self.config.is_ice_enabled() {
+            let role = if self.config.is_turn_enabled() {
+                "turn"
+            } else {
+                "stun"
+            };
+            roles.push((role, self.config.enable & (ENABLE_STUN | ENABLE_TURN)));
+        }
+        if roles.is_empty() {
+            anyhow::bail!("配置中没有启用任何服务（enable = {}）", self.config.enable);
+        }
+
+        roles
+            .into_iter()
+            .map(|(role, enable)| Ok((role, self.split_config(role, enable)?)))
+            .collect()
+    }
 
-        // HTTP/HTTPS 端口
+    /// 从完整配置派生单个服务的配置
+    fn split_config(&self, role: &str, enable: u8) -> Result<ActrixConfig> {
+        let mut config = self.config.clone();
+        config.enable = enable;
+        config.name = format!("{}-{role}", self.config.name);
+        // 每个容器作为独立节点向 Supervisor 注册
+        if let Some(supervisor) = config.supervisor.as_mut() {
+            supervisor.client.node_id = format!("{}-{role}", supervisor.client.node_id);
+        }
+
+        if config.is_ks_enabled() {
+            let ks = config.services.ks.get_or_insert_with(Default::default);
+            ks.grpc.bind.ip = "0.0.0.0".to_string();
+        }
+        if config.is_ais_enabled() && self.config.is_ks_enabled() {
+            let ais = config.services.ais.get_or_insert_with(Default::default);
+            if ais.dependencies.ks.is_none() {
+                ais.dependencies.ks = Some(self.ks_client_config("services.ais.dependencies.ks")?);
+            }
+        }
+        if config.is_signaling_enabled() {
+            let signaling = config
+                .services
+                .signaling
+                .get_or_insert_with(Default::default);
+            if signaling.dependencies.ks.is_none() && self.config.is_ks_enabled() {
+                signaling.dependencies.ks =
+                    Some(self.ks_client_config("services.signaling.dependencies.ks")?);
+            }
+            if signaling.dependencies.ais.is_none() && self.config.is_ais_enabled() {
+                signaling.dependencies.ais = Some(self.ais_client_config()?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// 经共享网络访问 KS 容器的客户端配置
+    fn ks_client_config(&self, field: &str) -> Result<KsClientConfig> {
+        if self
+            .config
+            .services
+            .ks
+            .as_ref()
+            .is_some_and(|ks| ks.grpc_tls_config().is_some())
+        {
+            anyhow::bail!("KS 启用了双向 TLS，拆分部署需在 {field} 中显式配置客户端证书");
+        }
+        Ok(KsClientConfig {
+            endpoint: format!("http://ks:{}", self.config.ks_grpc_bind().port),
+            ..Default::default()
+        })
+    }
+
+    /// 经共享网络访问 AIS 容器的客户端配置（容器间优先使用明文 HTTP 监听器）
+    fn ais_client_config(&self) -> Result<AisClientConfig> {
+        let endpoint = match (&self.config.bind.http, &self.config.bind.https) {
+            (Some(http), _) => format!("http://ais:{}", http.port),
+            (None, Some(https)) => format!("https://ais:{}", https.port),
+            (None, None) => anyhow::bail!("AIS 需要 bind.http 或 bind.https 监听器"),
+        };
+        Ok(AisClientConfig {
+            endpoint,
+            route_prefix: self.config.http.route_prefixes.ais.clone(),
+            timeout_seconds: 30,
+            revocation_refresh_interval_secs: 30,
+        })
+    }
+
+    /// 是否向宿主机发布 HTTP/HTTPS 端口
+    ///
+    /// 拆分拓扑中 AIS 与 Signaling 共用同一端口号，由 Signaling 容器发布；
+    /// 仅当没有 Signaling 时 AIS 才发布端口
+    fn publishes_http(&self, config: &ActrixConfig) -> bool {
+        config.is_signaling_enabled()
+            || (config.is_ais_enabled() && !self.config.is_signaling_enabled())
+    }
+
+    /// 未发布到宿主机的 AIS（仅共享网络内可达）
+    pub fn ais_internal_only(&self) -> bool {
+        self.topology == Topology::Split
+            && self.config.is_ais_enabled()
+            && self.config.is_signaling_enabled()
+    }
+
+    /// HTTP/HTTPS 端口映射
+    fn http_ports(&self) -> Vec<String> {
+        let mut ports = Vec::new();
         if let Some(ref http) = self.config.bind.http {
             ports.push(format!("{}:{}", http.port, http.port));
         }
         if let Some(ref https) = self.config.bind.https {
             ports.push(format!("{}:{}", https.port, https.port));
         }
+        ports
+    }
 
-        // ICE 端口 (STUN/TURN)
+    /// ICE 端口 (STUN/TURN) 与 TURN relay 端口范围映射
+    fn ice_ports(&self) -> Vec<String> {
+        let mut ports = Vec::new();
         let ice = &self.config.bind.ice;
         ports.push(format!("{}:{}/udp", ice.port, ice.port));
 
-        // TURN relay 端口范围
         if self.config.is_turn_enabled() {
             let turn = &self.config.turn;
             // 解析端口范围
@@ -74,28 +301,16 @@ impl DockerComposeGenerator {
                 ports.push(format!("{}-{}:{}-{}/udp", start, end, start, end));
             }
         }
+        ports
+    }
 
-        // 环境变量
+    /// 环境变量
+    fn environment() -> Vec<String> {
+        let mut environment = Vec::new();
         if let Ok(kek) = std::env::var("ACTRIX_KEK") {
             environment.push(format!("ACTRIX_KEK={}", kek));
         }
-
-        let service = json!({
-            "image": "actrix:latest",
-            "container_name": "actrix",
-            "restart": "unless-stopped",
-            "ports": ports,
-            "environment": environment,
-            "volumes": [
-                "./config.toml:/app/config.toml:ro",
-                "actrix-data:/app/data",
-                "actrix-certs:/app/certificates:ro"
-            ],
-            "networks": ["actrix-network"],
-            "command": ["--config", "/app/config.toml"]
-        });
-
-        Ok(service)
+        environment
     }
 
     /// 解析端口范围字符串（如 "49152-65535"）
@@ -110,8 +325,28 @@ impl DockerComposeGenerator {
     }
 
     /// 保存到文件
+    ///
+    /// 拆分拓扑下同时在 docker-compose.yml 所在目录的 `actrix-config/` 中写入各服务配置
     pub fn save_to_file(&self, output_path: &Path) -> Result<()> {
         let content = self.generate()?;
+
+        let split_configs = self.split_configs()?;
+        if !split_configs.is_empty() {
+            let config_dir = output_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join(SPLIT_CONFIG_DIR);
+            fs::create_dir_all(&config_dir)
+                .with_context(|| format!("无法创建目录: {}", config_dir.display()))?;
+            for (role, config) in split_configs {
+                let path = config_dir.join(format!("{role}.toml"));
+                let toml = config.to_toml().with_context(|| "序列化配置失败")?;
+                fs::write(&path, toml)
+                    .with_context(|| format!("无法写入文件: {}", path.display()))?;
+                println!("✅ {role} 服务配置已生成: {}", path.display());
+            }
+        }
+
         fs::write(output_path, content)
             .with_context(|| format!("无法写入文件: {}", output_path.display()))?;
 
@@ -119,3 +354,79 @@ impl DockerComposeGenerator {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compose(generator: &DockerComposeGenerator) -> Value {
+        serde_yaml::from_str(&generator.generate().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_single_topology_keeps_one_container() {
+        let generator = DockerComposeGenerator::new(ActrixConfig::default(), Topology::Single);
+        let compose = compose(&generator);
+        let services = compose["services"].as_object().unwrap();
+        assert_eq!(services.len(), 1);
+        assert!(services.contains_key("actrix"));
+        assert!(generator.split_configs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_split_topology_wires_dependencies() {
+        let config = ActrixConfig {
+            enable: ENABLE_SIGNALING | ENABLE_STUN | ENABLE_TURN | ENABLE_AIS | ENABLE_KS,
+            ..Default::default()
+        };
+        let ks_port = config.ks_grpc_bind().port;
+        let generator = DockerComposeGenerator::new(config, Topology::Split);
+
+        let configs = generator.split_configs().unwrap();
+        let roles: Vec<_> = configs.iter().map(|(role, _)| *role).collect();
+        assert_eq!(roles, ["ks", "ais", "signaling", "turn"]);
+
+        let config = |role: &str| &configs.iter().find(|(r, _)| *r == role).unwrap().1;
+        assert_eq!(config("ks").enable, ENABLE_KS);
+        assert_eq!(config("ks").ks_grpc_bind().ip, "0.0.0.0");
+        assert_eq!(config("turn").enable, ENABLE_STUN | ENABLE_TURN);
+        let ks_endpoint = format!("http://ks:{ks_port}");
+        let ais = config("ais").services.ais.as_ref().unwrap();
+        assert_eq!(ais.dependencies.ks.as_ref().unwrap().endpoint, ks_endpoint);
+        let signaling = config("signaling").services.signaling.as_ref().unwrap();
+        assert_eq!(
+            signaling.dependencies.ks.as_ref().unwrap().endpoint,
+            ks_endpoint
+        );
+        let ais_endpoint = &signaling.dependencies.ais.as_ref().unwrap().endpoint;
+        assert!(ais_endpoint.starts_with("http://ais:"));
+
+        let compose = compose(&generator);
+        let services = &compose["services"];
+        assert_eq!(services["ais"]["depends_on"], json!(["ks"]));
+        assert_eq!(services["signaling"]["depends_on"], json!(["ks", "ais"]));
+        assert!(services["turn"]["depends_on"].is_null());
+        assert!(services["ks"]["ports"].is_null());
+        // AIS 与 Signaling 共用端口号，只由 Signaling 发布
+        assert!(services["ais"]["ports"].is_null());
+        assert!(services["signaling"]["ports"].is_array());
+        assert_eq!(
+            services["signaling"]["volumes"][0],
+            "./actrix-config/signaling.toml:/app/config.toml:ro"
+        );
+        assert!(generator.ais_internal_only());
+    }
+
+    #[test]
+    fn test_split_topology_stun_only() {
+        let config = ActrixConfig {
+            enable: ENABLE_STUN,
+            ..Default::default()
+        };
+        let generator = DockerComposeGenerator::new(config, Topology::Split);
+        let compose = compose(&generator);
+        let services = compose["services"].as_object().unwrap();
+        assert_eq!(services.len(), 1);
+        assert!(services.contains_key("stun"));
+    }
+}
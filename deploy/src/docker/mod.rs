@@ -1,10 +1,10 @@
 //! Docker Compose 配置生成器
 //!
-//! 从 Actrix 配置文件生成 docker-compose.yml
+//! 从 Actrix 配置文件生成 docker-compose.yml，支持单容器与按服务拆分的多容器拓扑
 
 mod composer;
 
-pub use composer::DockerComposeGenerator;
+pub use composer::{DockerComposeGenerator, Topology};
 
 use anyhow::Result;
 use std::path::Path;
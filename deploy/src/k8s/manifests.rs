@@ -0,0 +1,465 @@
+//! Kubernetes 清单生成器实现
+
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result};
+use serde_json::{Map, Value, json};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// 容器内配置文件目录（ConfigMap 卷不能嵌套其他挂载点，因此不直接挂载到 /etc/actrix）
+const CONFIG_DIR: &str = "/etc/actrix/config";
+/// 容器内机密文件目录
+const SECRETS_DIR: &str = "/etc/actrix/secrets";
+/// 容器内 TLS 证书目录
+const TLS_DIR: &str = "/etc/actrix/tls";
+/// 容器工作目录，相对路径（sqlite_path、pid 等）落在持久卷上
+const DATA_DIR: &str = "/var/lib/actrix";
+/// 镜像中 actrix 用户的 UID/GID
+const ACTRIX_UID: u64 = 1000;
+
+/// 生成选项
+#[derive(Debug, Clone)]
+pub struct K8sOptions {
+    /// 资源名称前缀与 `app.kubernetes.io/instance` 标签
+    pub name: String,
+    /// 命名空间（None 时不写入，由 kubectl 决定）
+    pub namespace: Option<String>,
+    /// 容器镜像
+    pub image: String,
+    /// 数据卷容量
+    pub storage_size: String,
+}
+
+/// Kubernetes 清单生成器
+///
+/// 生成 ConfigMap、Secret、PersistentVolumeClaim、Deployment 与 Service：
+/// - 配置中的明文机密移入 Secret，ConfigMap 中改为 `file:` 引用
+/// - 配置了 `bind.https` 时证书引用名为 `<name>-tls` 的 TLS Secret（需预先创建）
+/// - 启用 TURN 时使用 hostNetwork：中继端口范围无法经 Service 暴露
+/// - 就绪 / 存活探针指向主 HTTP 监听器的 `/readyz` 与 `/healthz`
+pub struct K8sManifestGenerator {
+    config: ActrixConfig,
+    options: K8sOptions,
+}
+
+/// 从配置中移出的机密：(Secret 中的键, 明文)
+type Secrets = Vec<(&'static str, String)>;
+
+impl K8sManifestGenerator {
+    /// 从配置文件创建生成器
+    pub fn from_config_file(config_path: &Path, options: K8sOptions) -> Result<Self> {
+        let config_content = fs::read_to_string(config_path)
+            .with_context(|| format!("无法读取配置文件: {}", config_path.display()))?;
+
+        let config: ActrixConfig =
+            toml::from_str(&config_content).with_context(|| "解析配置文件失败")?;
+
+        Ok(Self::new(config, options))
+    }
+
+    pub fn new(config: ActrixConfig, options: K8sOptions) -> Self {
+        Self { config, options }
+    }
+
+    /// 生成多文档 YAML
+    pub fn generate(&self) -> Result<String> {
+        let mut config = self.config.clone();
+        let secrets = extract_secrets(&mut config);
+        if let Some(ref mut https) = config.bind.https {
+            https.cert = format!("{TLS_DIR}/tls.crt");
+            https.key = format!("{TLS_DIR}/tls.key");
+        }
+        let config_toml = config.to_toml().with_context(|| "序列化配置失败")?;
+
+        let mut documents = vec![self.config_map(&config_toml)];
+        if !secrets.is_empty() {
+            documents.push(self.secret(&secrets));
+        }
+        documents.push(self.volume_claim());
+        documents.push(self.deployment(&config_toml, !secrets.is_empty()));
+        documents.extend(self.services());
+
+        let mut yaml = String::new();
+        for document in documents {
+            yaml.push_str("---\n");
+            yaml.push_str(&serde_yaml::to_string(&document).with_context(|| "转换为 YAML 失败")?);
+        }
+        Ok(yaml)
+    }
+
+    /// 保存到文件
+    pub fn save_to_file(&self, output_path: &Path) -> Result<()> {
+        let content = self.generate()?;
+        fs::write(output_path, content)
+            .with_context(|| format!("无法写入文件: {}", output_path.display()))?;
+
+        println!("✅ Kubernetes 清单已生成: {}", output_path.display());
+        Ok(())
+    }
+
+    /// 是否需要 TLS 证书 Secret（`<name>-tls`）
+    pub fn has_tls(&self) -> bool {
+        self.config.bind.https.is_some()
+    }
+
+    /// 是否使用宿主机网络（TURN 中继端口范围需要直接暴露在节点上）
+    pub fn uses_host_network(&self) -> bool {
+        self.config.is_turn_enabled()
+    }
+
+    fn metadata(&self, name: &str) -> Value {
+        let mut metadata = json!({
+            "name": name,
+            "labels": self.labels(),
+        });
+        if let Some(ref namespace) = self.options.namespace {
+            metadata["namespace"] = json!(namespace);
+        }
+        metadata
+    }
+
+    fn labels(&self) -> Value {
+        json!({
+            "app.kubernetes.io/name": "actrix",
+            "app.kubernetes.io/instance": self.options.name,
+        })
+    }
+
+    fn resource_name(&self, suffix: &str) -> String {
+        format!("{}-{suffix}", self.options.name)
+    }
+
+    fn config_map(&self, config_toml: &str) -> Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": self.metadata(&self.resource_name("config")),
+            "data": { "config.toml": config_toml },
+        })
+    }
+
+    fn secret(&self, secrets: &Secrets) -> Value {
+        let data: Map<String, Value> = secrets
+            .iter()
+            .map(|(key, value)| (key.to_string(), json!(value)))
+            .collect();
+        json!({
+            "apiVersion": "v1",
+            "kind": "Secret",
+            "metadata": self.metadata(&self.resource_name("secrets")),
+            "type": "Opaque",
+            "stringData": data,
+        })
+    }
+
+    fn volume_claim(&self) -> Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "PersistentVolumeClaim",
+            "metadata": self.metadata(&self.resource_name("data")),
+            "spec": {
+                "accessModes": ["ReadWriteOnce"],
+                "resources": { "requests": { "storage": self.options.storage_size } },
+            },
+        })
+    }
+
+    fn deployment(&self, config_toml: &str, has_secrets: bool) -> Value {
+        let mut volumes = vec![
+            json!({ "name": "config", "configMap": { "name": self.resource_name("config") } }),
+            json!({ "name": "data", "persistentVolumeClaim": { "claimName": self.resource_name("data") } }),
+        ];
+        let mut mounts = vec![
+            json!({ "name": "config", "mountPath": CONFIG_DIR, "readOnly": true }),
+            json!({ "name": "data", "mountPath": DATA_DIR }),
+        ];
+        if has_secrets {
+            volumes.push(json!({
+                "name": "secrets",
+                "secret": { "secretName": self.resource_name("secrets"), "defaultMode": 0o440 },
+            }));
+            mounts.push(json!({ "name": "secrets", "mountPath": SECRETS_DIR, "readOnly": true }));
+        }
+        if self.has_tls() {
+            volumes.push(json!({
+                "name": "tls",
+                "secret": { "secretName": self.resource_name("tls"), "defaultMode": 0o440 },
+            }));
+            mounts.push(json!({ "name": "tls", "mountPath": TLS_DIR, "readOnly": true }));
+        }
+
+        let container_ports: Vec<Value> = self
+            .ports()
+            .into_iter()
+            .map(|(name, port, protocol)| {
+                json!({ "name": name, "containerPort": port, "protocol": protocol })
+            })
+            .collect();
+
+        let mut container = json!({
+            "name": "actrix",
+            "image": self.options.image,
+            "args": ["--config", format!("{CONFIG_DIR}/config.toml")],
+            "ports": container_ports,
+            "volumeMounts": mounts,
+        });
+        if let Some((port, scheme)) = self.probe_target() {
+            container["readinessProbe"] = json!({
+                "httpGet": { "path": "/readyz", "port": port, "scheme": scheme },
+                "periodSeconds": 5,
+                "failureThreshold": 3,
+            });
+            container["livenessProbe"] = json!({
+                "httpGet": { "path": "/healthz", "port": port, "scheme": scheme },
+                "initialDelaySeconds": 10,
+                "periodSeconds": 10,
+            });
+        }
+
+        let mut pod_spec = json!({
+            "securityContext": {
+                "runAsUser": ACTRIX_UID,
+                "runAsGroup": ACTRIX_UID,
+                "fsGroup": ACTRIX_UID,
+            },
+            // 留出优雅关闭期限之外的余量，避免汇总日志输出前被强制终止
+            "terminationGracePeriodSeconds": self.config.shutdown.grace_period_secs + 10,
+            "containers": [container],
+            "volumes": volumes,
+        });
+        if self.uses_host_network() {
+            pod_spec["hostNetwork"] = json!(true);
+            pod_spec["dnsPolicy"] = json!("ClusterFirstWithHostNet");
+        }
+
+        json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": self.metadata(&self.options.name),
+            "spec": {
+                "replicas": 1,
+                // SQLite 数据卷与宿主机端口不能由新旧 Pod 同时占用
+                "strategy": { "type": "Recreate" },
+                "selector": { "matchLabels": self.labels() },
+                "template": {
+                    "metadata": {
+                        "labels": self.labels(),
+                        // 配置变化时触发滚动更新
+                        "annotations": { "checksum/config": config_checksum(config_toml) },
+                    },
+                    "spec": pod_spec,
+                },
+            },
+        })
+    }
+
+    /// TCP 端口走 ClusterIP Service，STUN/TURN 的 UDP 端口走 LoadBalancer Service
+    fn services(&self) -> Vec<Value> {
+        let (udp, tcp): (Vec<_>, Vec<_>) = self
+            .ports()
+            .into_iter()
+            .partition(|(_, _, protocol)| *protocol == "UDP");
+        let service = |name: String, service_type: &str, ports: Vec<(&str, u16, &str)>| {
+            let ports: Vec<Value> = ports
+                .into_iter()
+                .map(|(name, port, protocol)| {
+                    json!({ "name": name, "port": port, "targetPort": name, "protocol": protocol })
+                })
+                .collect();
+            let mut spec = json!({
+                "type": service_type,
+                "selector": self.labels(),
+                "ports": ports,
+            });
+            if service_type == "LoadBalancer" {
+                // 保留客户端源地址，STUN 返回的映射地址才正确
+                spec["externalTrafficPolicy"] = json!("Local");
+            }
+            json!({
+                "apiVersion": "v1",
+                "kind": "Service",
+                "metadata": self.metadata(&name),
+                "spec": spec,
+            })
+        };
+
+        let mut services = Vec::new();
+        if !tcp.is_empty() {
+            services.push(service(self.options.name.clone(), "ClusterIP", tcp));
+        }
+        // hostNetwork 时客户端直接访问节点地址（turn.advertised_ip），不经过负载均衡
+        if !udp.is_empty() && !self.uses_host_network() {
+            services.push(service(self.resource_name("ice"), "LoadBalancer", udp));
+        }
+        services
+    }
+
+    /// 需要暴露的端口：(名称, 端口, 协议)
+    fn ports(&self) -> Vec<(&'static str, u16, &'static str)> {
+        let mut ports = Vec::new();
+        if let Some(ref http) = self.config.bind.http {
+            ports.push(("http", http.port, "TCP"));
+        }
+        if let Some(ref https) = self.config.bind.https {
+            ports.push(("https", https.port, "TCP"));
+        }
+        if self.config.is_ks_enabled() {
+            let grpc = self.config.ks_grpc_bind();
+            if !is_loopback(&grpc.ip) {
+                ports.push(("ks-grpc", grpc.port, "TCP"));
+            }
+        }
+        if self.config.is_supervisor_enabled()
+            && let Some(ref supervisor) = self.config.supervisor
+            && !is_loopback(&supervisor.supervisord.ip)
+        {
+            ports.push(("supervisord", supervisor.supervisord.port, "TCP"));
+        }
+        if self.config.is_ice_enabled() {
+            ports.push(("ice", self.config.bind.ice.port, "UDP"));
+        }
+        ports
+    }
+
+    /// 探针目标：主 HTTP 监听器（dev 环境优先 `bind.http`，其余环境为 `bind.https`）
+    fn probe_target(&self) -> Option<(&'static str, &'static str)> {
+        let is_dev = self.config.env.to_lowercase() == "dev";
+        match (&self.config.bind.http, &self.config.bind.https) {
+            (Some(_), _) if is_dev => Some(("http", "HTTP")),
+            (_, Some(_)) => Some(("https", "HTTPS")),
+            _ => None,
+        }
+    }
+}
+
+/// 将明文机密移出配置，配置中改为引用挂载的 Secret 文件
+///
+/// 已使用 `file:` 或 `${VAR}` 引用的字段保持不变
+fn extract_secrets(config: &mut ActrixConfig) -> Secrets {
+    let mut secrets = Vec::new();
+    let mut take = |key: &'static str, value: &mut String| {
+        if value.is_empty() || value.starts_with("file:") || value.contains("${") {
+            return;
+        }
+        let literal = std::mem::replace(value, format!("file:{SECRETS_DIR}/{key}"));
+        secrets.push((key, literal));
+    };
+
+    take("actrix_shared_key", &mut config.actrix_shared_key);
+    if let Some(kek) = config.services.ks.as_mut().and_then(|ks| ks.kek.as_mut()) {
+        take("ks_kek", kek);
+    }
+    if let Some(kek) = config
+        .services
+        .signaling
+        .as_mut()
+        .and_then(|signaling| signaling.server.registry_encryption.kek.as_mut())
+    {
+        take("registry_encryption_kek", kek);
+    }
+    if let Some(supervisor) = config.supervisor.as_mut() {
+        take(
+            "supervisor_shared_secret",
+            &mut supervisor.client.shared_secret,
+        );
+    }
+    secrets
+}
+
+fn is_loopback(ip: &str) -> bool {
+    ip.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn config_checksum(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::config::{ENABLE_STUN, ENABLE_TURN};
+
+    fn options() -> K8sOptions {
+        K8sOptions {
+            name: "actrix".to_string(),
+            namespace: Some("rtc".to_string()),
+            image: "actrix:latest".to_string(),
+            storage_size: "1Gi".to_string(),
+        }
+    }
+
+    fn documents(generator: &K8sManifestGenerator) -> Vec<Value> {
+        generator
+            .generate()
+            .unwrap()
+            .split("---\n")
+            .filter(|doc| !doc.trim().is_empty())
+            .map(|doc| serde_yaml::from_str(doc).unwrap())
+            .collect()
+    }
+
+    fn find<'a>(documents: &'a [Value], kind: &str, name: &str) -> Option<&'a Value> {
+        documents
+            .iter()
+            .find(|doc| doc["kind"] == kind && doc["metadata"]["name"] == name)
+    }
+
+    #[test]
+    fn test_secrets_moved_out_of_config_map() {
+        let config = ActrixConfig {
+            enable: ENABLE_STUN,
+            ..Default::default()
+        };
+        let shared_key = config.actrix_shared_key.clone();
+        let documents = documents(&K8sManifestGenerator::new(config, options()));
+
+        let secret = find(&documents, "Secret", "actrix-secrets").unwrap();
+        assert_eq!(secret["stringData"]["actrix_shared_key"], shared_key);
+        assert_eq!(secret["metadata"]["namespace"], "rtc");
+
+        let config_toml =
+            find(&documents, "ConfigMap", "actrix-config").unwrap()["data"]["config.toml"]
+                .as_str()
+                .unwrap();
+        assert!(!config_toml.contains(&shared_key));
+        assert!(config_toml.contains("file:/etc/actrix/secrets/actrix_shared_key"));
+
+        // 只有 STUN 时 UDP 端口经 LoadBalancer 暴露
+        let ice = find(&documents, "Service", "actrix-ice").unwrap();
+        assert_eq!(ice["spec"]["type"], "LoadBalancer");
+        assert_eq!(ice["spec"]["ports"][0]["protocol"], "UDP");
+
+        let deployment = find(&documents, "Deployment", "actrix").unwrap();
+        let pod = &deployment["spec"]["template"]["spec"];
+        assert!(pod["hostNetwork"].is_null());
+        assert_eq!(
+            pod["containers"][0]["readinessProbe"]["httpGet"]["path"],
+            "/readyz"
+        );
+    }
+
+    #[test]
+    fn test_turn_uses_host_network() {
+        let config = ActrixConfig {
+            enable: ENABLE_STUN | ENABLE_TURN,
+            env: "prod".to_string(),
+            ..Default::default()
+        };
+        let documents = documents(&K8sManifestGenerator::new(config, options()));
+
+        let deployment = find(&documents, "Deployment", "actrix").unwrap();
+        let pod = &deployment["spec"]["template"]["spec"];
+        assert_eq!(pod["hostNetwork"], true);
+        assert_eq!(
+            pod["containers"][0]["readinessProbe"]["httpGet"]["scheme"],
+            "HTTPS"
+        );
+        assert!(find(&documents, "Service", "actrix-ice").is_none());
+        assert!(find(&documents, "Service", "actrix").is_some());
+    }
+}
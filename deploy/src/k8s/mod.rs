@@ -0,0 +1,7 @@
+//! Kubernetes 清单生成器
+//!
+//! 从 Actrix 配置文件生成 Deployment / Service / ConfigMap / Secret 清单
+
+mod manifests;
+
+pub use manifests::{K8sManifestGenerator, K8sOptions};
@@ -8,12 +8,15 @@ use std::sync::atomic::{AtomicBool, Ordering};
 mod cli;
 mod config;
 mod docker;
+mod k8s;
 mod menu;
+mod realm;
+mod rollout;
 mod services;
 mod system;
 mod template;
 
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, RealmCommands};
 use config::{InstallConfig, UnifiedConfigWizard};
 use menu::{MenuApplication, framework::screen::Screen};
 
@@ -70,6 +73,7 @@ async fn main() -> Result<()> {
             output,
             run,
             legacy,
+            topology,
         }) => {
             // 检查 Docker 是否可用
             if run {
@@ -85,8 +89,13 @@ async fn main() -> Result<()> {
 
             // 生成 docker-compose.yml
             println!("📝 从配置文件生成 Docker Compose 配置...");
-            let generator = docker::DockerComposeGenerator::from_config_file(&config)?;
+            let generator = docker::DockerComposeGenerator::from_config_file(&config, topology)?;
             generator.save_to_file(&output)?;
+            if generator.ais_internal_only() {
+                println!(
+                    "ℹ️  AIS 与 Signaling 共用 HTTP 端口，AIS 仅在 actrix-network 内可达，对外访问需经反向代理转发"
+                );
+            }
 
             // 可选执行 docker-compose up
             if run {
@@ -102,6 +111,77 @@ async fn main() -> Result<()> {
 
             Ok(())
         }
+        Some(Commands::K8s {
+            config,
+            output,
+            name,
+            namespace,
+            image,
+            storage_size,
+        }) => {
+            println!("📝 从配置文件生成 Kubernetes 清单...");
+            let options = k8s::K8sOptions {
+                name: name.clone(),
+                namespace,
+                image,
+                storage_size,
+            };
+            let generator = k8s::K8sManifestGenerator::from_config_file(&config, options)?;
+            generator.save_to_file(&output)?;
+
+            println!("\n💡 提示：");
+            if generator.has_tls() {
+                println!("   先创建 TLS 证书 Secret：");
+                println!("   kubectl create secret tls {name}-tls --cert=<证书> --key=<私钥>");
+            }
+            if generator.uses_host_network() {
+                println!(
+                    "   TURN 以 hostNetwork 运行，请确认节点地址与 turn.advertised_ip 一致并放通中继端口范围"
+                );
+            }
+            println!("   kubectl apply -f {}", output.display());
+            Ok(())
+        }
+        Some(Commands::MigrateConfig {
+            config,
+            output,
+            dry_run,
+        }) => config::migrate_config_file(&config, output.as_deref(), dry_run),
+        Some(Commands::Rollout {
+            inventory,
+            hosts,
+            dry_run,
+        }) => rollout::rollout(&inventory, &hosts, dry_run).await,
+        Some(Commands::Realm {
+            command:
+                RealmCommands::Create {
+                    id,
+                    name,
+                    config,
+                    url,
+                    expires_at,
+                    allow,
+                    deny,
+                    reserve,
+                    note,
+                },
+        }) => {
+            let acl = allow
+                .iter()
+                .map(|rule| realm::AclRule::parse(rule, true))
+                .chain(deny.iter().map(|rule| realm::AclRule::parse(rule, false)))
+                .collect::<Result<Vec<_>>>()?;
+            let spec = realm::RealmSpec {
+                realm_id: id,
+                name,
+                expires_at,
+                acl,
+                reserve,
+                note,
+            };
+            let client = realm::AdminClient::from_config_file(&config, url.as_deref())?;
+            realm::create_realm(&client, &spec).await
+        }
         Some(Commands::Menu) | None => {
             let mut app = MenuApplication::new(cli.debug, interrupted);
             app.run()
@@ -1,7 +1,8 @@
 //! main page implementation
 
 use super::{
-    ConfigPage, DependenciesPage, InstallPage, SystemdInstallPage, UninstallPage, WizardPage,
+    ConfigPage, DependenciesPage, InstallPage, RealmPage, SystemdInstallPage, UninstallPage,
+    WizardPage,
 };
 use crate::menu::framework::{
     DefaultTheme, EnhancedSelect, Layout, LayoutComponents, Page, PageContext, PageResult,
@@ -44,6 +45,7 @@ impl Page for MainPage {
             "Configuration Wizard",
             "Install Application (Deploy Files)",
             "Deploy as systemd Service",
+            "Create Realm",
             "Uninstall",
             "Exit",
         ];
@@ -63,8 +65,9 @@ impl Page for MainPage {
                 2 => Ok(PageResult::Navigate(Box::new(ConfigPage::new()))),
                 3 => Ok(PageResult::Navigate(Box::new(InstallPage::new()))),
                 4 => Ok(PageResult::Navigate(Box::new(SystemdInstallPage::new()))),
-                5 => Ok(PageResult::Navigate(Box::new(UninstallPage::new()))),
-                6 => {
+                5 => Ok(PageResult::Navigate(Box::new(RealmPage::new()))),
+                6 => Ok(PageResult::Navigate(Box::new(UninstallPage::new()))),
+                7 => {
                     println!("👋 Thank you for using the deployment helper!\n");
                     Ok(PageResult::Exit)
                 }
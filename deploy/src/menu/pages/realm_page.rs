@@ -0,0 +1,102 @@
+//! Realm bootstrap page
+
+use crate::menu::framework::{
+    ContentArea, DefaultTheme, Layout, LayoutComponents, Page, PageContext, PageResult,
+    StandardLayout, Theme,
+};
+use crate::realm::{AclRule, AdminClient, RealmSpec, create_realm};
+use crate::system::press_any_key_to_with_interrupt;
+use anyhow::Result;
+use dialoguer::Input;
+use std::path::PathBuf;
+
+pub struct RealmPage {
+    theme: DefaultTheme,
+    layout: StandardLayout,
+}
+
+impl RealmPage {
+    pub fn new() -> Self {
+        Self {
+            theme: DefaultTheme::default(),
+            layout: StandardLayout,
+        }
+    }
+
+    fn run_bootstrap(&self) -> Result<()> {
+        let theme = self.theme.dialoguer_theme();
+
+        let config_path: String = Input::with_theme(theme)
+            .with_prompt("Node config file (admin URL and shared key)")
+            .default("/etc/actor-rtc-actrix/config.toml".to_string())
+            .interact_text()?;
+        let realm_id: u32 = Input::with_theme(theme)
+            .with_prompt("Realm ID")
+            .default(1001)
+            .interact_text()?;
+        let name: String = Input::with_theme(theme)
+            .with_prompt("Realm name")
+            .interact_text()?;
+        let allow: String = Input::with_theme(theme)
+            .with_prompt("Allowed access rules FROM=TO, comma separated (empty: deny all)")
+            .allow_empty(true)
+            .interact_text()?;
+        let reserve: u64 = Input::with_theme(theme)
+            .with_prompt("ActrId serial numbers to reserve (0: none)")
+            .default(0)
+            .interact_text()?;
+
+        let acl = allow
+            .split(',')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .map(|rule| AclRule::parse(rule, true))
+            .collect::<Result<Vec<_>>>()?;
+        let spec = RealmSpec {
+            realm_id,
+            name,
+            expires_at: None,
+            acl,
+            reserve,
+            note: None,
+        };
+
+        let client = AdminClient::from_config_file(&PathBuf::from(config_path), None)?;
+        // 菜单运行在同步上下文中，借用当前 tokio 运行时执行请求
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(create_realm(&client, &spec))
+        })
+    }
+}
+
+impl Page for RealmPage {
+    fn title(&self) -> &str {
+        "Create Realm"
+    }
+
+    fn render(&mut self, context: &mut PageContext) -> Result<PageResult> {
+        let components = LayoutComponents::new("ActorRTC Auxiliary Services Deployment Helper")
+            .with_page_title("Create Realm")
+            .with_operation_hint("Provision a realm on a running node via its admin API")
+            .add_content(ContentArea::new().add_section(
+                "Bootstrap Steps",
+                vec![
+                    "Create the realm record".to_string(),
+                    "Apply ACL rules".to_string(),
+                    "Reserve ActrId serial numbers (optional)".to_string(),
+                ],
+            ));
+
+        self.layout.render(components);
+
+        if let Err(e) = self.run_bootstrap() {
+            eprintln!("Realm bootstrap failed: {e:#}");
+        }
+        let interrupted = press_any_key_to_with_interrupt("continue", context.interrupted.clone());
+        if interrupted {
+            Ok(PageResult::Stay) // Let MenuApplication handle Ctrl+C
+        } else {
+            Ok(PageResult::Back)
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Realm 初始化（`deploy realm create`）
+//!
+//! 通过运行中节点的管理 API 完成，不直接改动 SQLite：
+//! 1. Signaling `POST /admin/realms` 创建 Realm 记录（已存在时跳过）
+//! 2. Signaling `PUT /admin/realms/{realm_id}/acl` 写入 ACL 规则；没有匹配规则时访问被拒绝，
+//!    因此未指定任何规则的 Realm 默认互不可见
+//! 3. 可选：AIS `POST /reserve-batch` 为出厂预置的 Actor 预留 ActrId 序列号
+//!
+//! 各服务的路由前缀按配置解析（`ws_path` 与 `http.route_prefixes`），
+//! 管理 API 均以配置中的 `actrix_shared_key` 认证。
+
+use actrix_common::config::{ActrixConfig, RoutePrefixes};
+use anyhow::{Context, Result, anyhow, bail};
+use nonce_auth::CredentialBuilder;
+use reqwest::{RequestBuilder, StatusCode};
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// ACL 规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AclRule {
+    /// 来源主体（`manufacturer:name`、`manufacturer:*`、`#tag` 或 `*`）
+    pub from_type: String,
+    /// 目标类型（`manufacturer:name`）
+    pub to_type: String,
+    pub access: bool,
+}
+
+impl AclRule {
+    /// 解析 `FROM=TO`
+    pub fn parse(value: &str, access: bool) -> Result<Self> {
+        match value.split_once('=') {
+            Some((from, to)) if !from.trim().is_empty() && !to.trim().is_empty() => Ok(Self {
+                from_type: from.trim().to_string(),
+                to_type: to.trim().to_string(),
+                access,
+            }),
+            _ => bail!("ACL 规则格式应为 FROM=TO，如 '*=acme:echo': {value}"),
+        }
+    }
+}
+
+/// Realm 初始化参数
+#[derive(Debug, Clone)]
+pub struct RealmSpec {
+    pub realm_id: u32,
+    pub name: String,
+    /// 到期时间（Unix 秒）
+    pub expires_at: Option<i64>,
+    pub acl: Vec<AclRule>,
+    /// 预留的 ActrId 个数，为 0 时不预留
+    pub reserve: u64,
+    /// 预留批次备注
+    pub note: Option<String>,
+}
+
+/// 节点管理 API 客户端
+pub struct AdminClient {
+    base_url: String,
+    prefixes: RoutePrefixes,
+    shared_key: String,
+    http: reqwest::Client,
+}
+
+impl AdminClient {
+    /// 从 actrix 配置读取共享密钥与对外地址，`url` 覆盖由配置推导的地址
+    pub fn from_config_file(config_path: &Path, url: Option<&str>) -> Result<Self> {
+        let config = ActrixConfig::from_file(config_path)
+            .map_err(|e| anyhow!("加载配置文件失败 ({}): {e}", config_path.display()))?;
+        let base_url = match url {
+            Some(url) => url.to_string(),
+            None => config.public_url().map_err(|e| anyhow!(e))?.to_string(),
+        };
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            prefixes: config.route_prefixes(),
+            shared_key: config.actrix_shared_key,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Signaling 管理 API 地址（`path` 相对于 Signaling 路由前缀）
+    fn signaling_url(&self, path: &str) -> String {
+        format!("{}{}{path}", self.base_url, self.prefixes.signaling)
+    }
+
+    /// AIS API 地址（`path` 相对于 AIS 路由前缀）
+    fn ais_url(&self, path: &str) -> String {
+        format!("{}{}{path}", self.base_url, self.prefixes.ais)
+    }
+
+    /// 以管理 token 调用 Signaling 管理 API
+    fn signaling(&self, request: RequestBuilder) -> RequestBuilder {
+        request.bearer_auth(&self.shared_key)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<(StatusCode, Value)> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("无法连接节点 {}", self.base_url))?;
+        let status = response.status();
+        let body = response.json::<Value>().await.unwrap_or(Value::Null);
+        Ok((status, body))
+    }
+}
+
+/// 错误响应中的 `message` 字段
+fn error_message(status: StatusCode, body: &Value) -> String {
+    match body.get("message").and_then(Value::as_str) {
+        Some(message) => format!("{status}: {message}"),
+        None => status.to_string(),
+    }
+}
+
+/// 与 AIS 的 `reserve_batch_payload` 保持一致
+fn reserve_batch_payload(realm_id: u32, count: u64) -> String {
+    format!("reserve_batch:{realm_id}:{count}")
+}
+
+/// 创建 Realm 并写入 ACL 规则与序列号预留
+pub async fn create_realm(client: &AdminClient, spec: &RealmSpec) -> Result<()> {
+    println!("🏠 创建 Realm {} ({})...", spec.realm_id, spec.name);
+    let request = client.signaling(client.http.post(client.signaling_url("/admin/realms")));
+    let (status, body) = client
+        .send(request.json(&json!({
+            "realm_id": spec.realm_id,
+            "name": spec.name,
+            "expires_at": spec.expires_at,
+        })))
+        .await?;
+    match status {
+        StatusCode::CREATED => println!("✅ Realm {} 已创建", spec.realm_id),
+        StatusCode::CONFLICT => println!("ℹ️  Realm {} 已存在，继续后续步骤", spec.realm_id),
+        _ => bail!("创建 Realm 失败: {}", error_message(status, &body)),
+    }
+
+    if spec.acl.is_empty() {
+        println!("🔒 未指定 ACL 规则：Realm 内的 Actor 默认互不可见");
+    }
+    let acl_url = client.signaling_url(&format!("/admin/realms/{}/acl", spec.realm_id));
+    for rule in &spec.acl {
+        let request = client.signaling(client.http.put(&acl_url));
+        let (status, body) = client
+            .send(request.json(&json!({
+                "from_type": rule.from_type,
+                "to_type": rule.to_type,
+                "access": rule.access,
+            })))
+            .await?;
+        if !status.is_success() {
+            bail!(
+                "写入 ACL 规则 {} -> {} 失败: {}",
+                rule.from_type,
+                rule.to_type,
+                error_message(status, &body)
+            );
+        }
+        println!(
+            "✅ ACL {} -> {} : {}",
+            rule.from_type,
+            rule.to_type,
+            if rule.access { "ALLOW" } else { "DENY" }
+        );
+    }
+
+    if spec.reserve > 0 {
+        reserve_serials(client, spec).await?;
+    }
+    Ok(())
+}
+
+/// 经 AIS 预留 ActrId 序列号
+async fn reserve_serials(client: &AdminClient, spec: &RealmSpec) -> Result<()> {
+    println!("🔢 预留 {} 个 ActrId 序列号...", spec.reserve);
+    let credential = CredentialBuilder::new(client.shared_key.as_bytes())
+        .sign(reserve_batch_payload(spec.realm_id, spec.reserve).as_bytes())
+        .map_err(|e| anyhow!("生成认证凭证失败: {e}"))?;
+    let request = client.http.post(client.ais_url("/reserve-batch"));
+    let (status, body) = client
+        .send(request.json(&json!({
+            "realm_id": spec.realm_id,
+            "count": spec.reserve,
+            "note": spec.note,
+            "credential": credential,
+        })))
+        .await?;
+    if !status.is_success() {
+        bail!("预留序列号失败: {}", error_message(status, &body));
+    }
+
+    let serials: Vec<u64> = body
+        .get("serial_numbers")
+        .and_then(Value::as_array)
+        .map(|serials| serials.iter().filter_map(Value::as_u64).collect())
+        .unwrap_or_default();
+    match (serials.first(), serials.last()) {
+        (Some(first), Some(last)) => println!(
+            "✅ 已预留序列号 {first} ~ {last}（共 {} 个），设备注册时使用",
+            serials.len()
+        ),
+        _ => println!("✅ 已预留序列号"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_acl_rule() {
+        let rule = AclRule::parse("* = acme:echo", true).unwrap();
+        assert_eq!(rule.from_type, "*");
+        assert_eq!(rule.to_type, "acme:echo");
+        assert!(rule.access);
+
+        assert!(AclRule::parse("acme:client", true).is_err());
+        assert!(AclRule::parse("=acme:echo", false).is_err());
+    }
+
+    #[test]
+    fn test_admin_urls_follow_route_prefixes() {
+        let client = AdminClient {
+            base_url: "https://node.example.com".to_string(),
+            prefixes: RoutePrefixes {
+                ais: "/api/ais".to_string(),
+                ks: "/ks".to_string(),
+                signaling: "/api/signaling".to_string(),
+            },
+            shared_key: String::new(),
+            http: reqwest::Client::new(),
+        };
+        assert_eq!(
+            client.signaling_url("/admin/realms"),
+            "https://node.example.com/api/signaling/admin/realms"
+        );
+        assert_eq!(
+            client.ais_url("/reserve-batch"),
+            "https://node.example.com/api/ais/reserve-batch"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! 主机清单（hosts.toml）
+//!
+//! ```toml
+//! [defaults]
+//! user = "deploy"
+//! identity_file = "~/.ssh/id_ed25519"
+//! binary = "target/release/actrix"
+//! config = "config.toml"
+//!
+//! [[hosts]]
+//! name = "edge-1"
+//! address = "10.0.0.11"
+//!
+//! [[hosts]]
+//! name = "edge-2"
+//! address = "10.0.0.12"
+//! config = "configs/edge-2.toml"  # 覆盖 defaults 中的同名字段
+//! ```
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_SSH_PORT: u16 = 22;
+const DEFAULT_BINARY: &str = "target/release/actrix";
+const DEFAULT_CONFIG: &str = "config.toml";
+const DEFAULT_INSTALL_DIR: &str = "/opt/actor-rtc-actrix";
+const DEFAULT_REMOTE_CONFIG: &str = "/etc/actor-rtc-actrix/config.toml";
+const DEFAULT_SERVICE_NAME: &str = "actrix";
+const DEFAULT_SERVICE_USER: &str = "actrix";
+const DEFAULT_HEALTH_TIMEOUT_SECS: u64 = 60;
+
+/// 主机清单文件
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Inventory {
+    /// 所有主机的默认设置
+    #[serde(default)]
+    pub defaults: HostSettings,
+    /// 按顺序滚动更新的主机
+    #[serde(default)]
+    pub hosts: Vec<HostEntry>,
+}
+
+// serde 的 deny_unknown_fields 不支持 flatten，主机字段不做未知字段检查
+/// 可在 `defaults` 与单个主机上配置的字段
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct HostSettings {
+    /// SSH 用户（默认使用本机 ssh 配置）
+    pub user: Option<String>,
+    /// SSH 端口
+    pub port: Option<u16>,
+    /// SSH 私钥
+    pub identity_file: Option<PathBuf>,
+    /// 本地 actrix 二进制路径
+    pub binary: Option<PathBuf>,
+    /// 本地配置文件路径
+    pub config: Option<PathBuf>,
+    /// 远程安装目录（二进制位于 `<install_dir>/bin/actrix`）
+    pub install_dir: Option<String>,
+    /// 远程配置文件路径
+    pub remote_config: Option<String>,
+    /// systemd 服务名
+    pub service_name: Option<String>,
+    /// 运行服务的用户（不存在时自动创建）
+    pub service_user: Option<String>,
+    /// 运行服务的用户组（默认同 `service_user`）
+    pub service_group: Option<String>,
+    /// 重启后等待节点就绪的最长时间（秒）
+    pub health_timeout_secs: Option<u64>,
+    /// 远程命令是否经 sudo 执行（默认：SSH 用户不是 root 时启用）
+    pub sudo: Option<bool>,
+}
+
+/// 清单中的单个主机
+#[derive(Debug, Deserialize)]
+pub struct HostEntry {
+    /// 显示名称（默认使用地址）
+    pub name: Option<String>,
+    /// 主机名或 IP 地址
+    pub address: String,
+    #[serde(flatten)]
+    pub settings: HostSettings,
+}
+
+/// 合并默认值后的主机设置
+#[derive(Debug, Clone)]
+pub struct Host {
+    pub name: String,
+    pub address: String,
+    pub user: Option<String>,
+    pub port: u16,
+    pub identity_file: Option<PathBuf>,
+    pub binary: PathBuf,
+    pub config: PathBuf,
+    pub install_dir: String,
+    pub remote_config: String,
+    pub service_name: String,
+    pub service_user: String,
+    pub service_group: String,
+    pub health_timeout: Duration,
+    pub sudo: bool,
+}
+
+impl Inventory {
+    /// 读取清单文件，本地路径相对于清单所在目录解析
+    pub fn from_file(path: &Path) -> Result<Vec<Host>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("无法读取主机清单: {}", path.display()))?;
+        let inventory: Inventory = toml::from_str(&content)
+            .with_context(|| format!("解析主机清单失败: {}", path.display()))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        inventory.resolve(base_dir)
+    }
+
+    /// 合并默认值并校验
+    pub fn resolve(self, base_dir: &Path) -> Result<Vec<Host>> {
+        if self.hosts.is_empty() {
+            bail!("主机清单中没有任何主机");
+        }
+        let mut names = HashSet::new();
+        let mut hosts = Vec::with_capacity(self.hosts.len());
+        for entry in self.hosts {
+            let host = entry.resolve(&self.defaults, base_dir)?;
+            if !names.insert(host.name.clone()) {
+                bail!("主机名称重复: {}", host.name);
+            }
+            hosts.push(host);
+        }
+        Ok(hosts)
+    }
+}
+
+impl HostEntry {
+    fn resolve(self, defaults: &HostSettings, base_dir: &Path) -> Result<Host> {
+        if self.address.trim().is_empty() {
+            bail!("主机地址不能为空");
+        }
+        let settings = self.settings;
+        let local_path = |path: Option<PathBuf>, fallback: Option<&PathBuf>, default: &str| {
+            let path = path
+                .or_else(|| fallback.cloned())
+                .unwrap_or_else(|| PathBuf::from(default));
+            let path = expand_home(&path);
+            if path.is_relative() {
+                base_dir.join(path)
+            } else {
+                path
+            }
+        };
+
+        let user = settings.user.or_else(|| defaults.user.clone());
+        let service_user = settings
+            .service_user
+            .or_else(|| defaults.service_user.clone())
+            .unwrap_or_else(|| DEFAULT_SERVICE_USER.to_string());
+        let sudo = settings
+            .sudo
+            .or(defaults.sudo)
+            .unwrap_or_else(|| user.as_deref() != Some("root"));
+
+        Ok(Host {
+            name: self.name.unwrap_or_else(|| self.address.clone()),
+            address: self.address,
+            port: settings.port.or(defaults.port).unwrap_or(DEFAULT_SSH_PORT),
+            identity_file: settings
+                .identity_file
+                .or_else(|| defaults.identity_file.clone())
+                .map(|path| expand_home(&path)),
+            binary: local_path(settings.binary, defaults.binary.as_ref(), DEFAULT_BINARY),
+            config: local_path(settings.config, defaults.config.as_ref(), DEFAULT_CONFIG),
+            install_dir: settings
+                .install_dir
+                .or_else(|| defaults.install_dir.clone())
+                .unwrap_or_else(|| DEFAULT_INSTALL_DIR.to_string()),
+            remote_config: settings
+                .remote_config
+                .or_else(|| defaults.remote_config.clone())
+                .unwrap_or_else(|| DEFAULT_REMOTE_CONFIG.to_string()),
+            service_name: settings
+                .service_name
+                .or_else(|| defaults.service_name.clone())
+                .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+            service_group: settings
+                .service_group
+                .or_else(|| defaults.service_group.clone())
+                .unwrap_or_else(|| service_user.clone()),
+            service_user,
+            health_timeout: Duration::from_secs(
+                settings
+                    .health_timeout_secs
+                    .or(defaults.health_timeout_secs)
+                    .unwrap_or(DEFAULT_HEALTH_TIMEOUT_SECS),
+            ),
+            sudo,
+            user,
+        })
+    }
+}
+
+/// 展开开头的 `~/`
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_settings_override_defaults() {
+        let inventory: Inventory = toml::from_str(
+            r#"
+            [defaults]
+            user = "deploy"
+            config = "config.toml"
+            health_timeout_secs = 30
+
+            [[hosts]]
+            name = "edge-1"
+            address = "10.0.0.11"
+
+            [[hosts]]
+            address = "10.0.0.12"
+            user = "root"
+            config = "/srv/edge-2.toml"
+            service_user = "rtc"
+            "#,
+        )
+        .unwrap();
+        let hosts = inventory.resolve(Path::new("/work")).unwrap();
+
+        assert_eq!(hosts[0].name, "edge-1");
+        assert_eq!(hosts[0].user.as_deref(), Some("deploy"));
+        assert!(hosts[0].sudo);
+        assert_eq!(hosts[0].config, PathBuf::from("/work/config.toml"));
+        assert_eq!(
+            hosts[0].binary,
+            PathBuf::from("/work/target/release/actrix")
+        );
+        assert_eq!(hosts[0].health_timeout, Duration::from_secs(30));
+
+        assert_eq!(hosts[1].name, "10.0.0.12");
+        assert!(!hosts[1].sudo);
+        assert_eq!(hosts[1].config, PathBuf::from("/srv/edge-2.toml"));
+        assert_eq!(hosts[1].service_group, "rtc");
+        assert_eq!(hosts[1].port, 22);
+    }
+
+    #[test]
+    fn test_duplicate_host_names_rejected() {
+        let inventory: Inventory = toml::from_str(
+            r#"
+            [[hosts]]
+            address = "10.0.0.11"
+
+            [[hosts]]
+            address = "10.0.0.11"
+            "#,
+        )
+        .unwrap();
+        assert!(inventory.resolve(Path::new(".")).is_err());
+    }
+}
@@ -0,0 +1,234 @@
+//! 多节点滚动发布（`deploy rollout --inventory hosts.toml`）
+//!
+//! 按清单顺序逐台执行：上传二进制、配置与 systemd unit → 安装并重启服务 → 轮询
+//! `actrix status`（查询 `/readyz`）直到节点就绪，再处理下一台。任一主机失败时立即停止，
+//! 后续主机保持原状，因此同一时刻最多只有一台节点处于重启中。
+
+mod inventory;
+mod ssh;
+
+use inventory::{Host, Inventory};
+
+use crate::config::InstallConfig;
+use crate::template::SystemdServiceTemplate;
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result, bail};
+use ssh::{SshTarget, shell_quote};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 就绪检查间隔
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 按清单滚动发布，`only` 非空时只处理指定名称的主机（保持清单顺序）
+pub async fn rollout(inventory: &Path, only: &[String], dry_run: bool) -> Result<()> {
+    let mut hosts = Inventory::from_file(inventory)?;
+    if !only.is_empty() {
+        if let Some(unknown) = only
+            .iter()
+            .find(|name| !hosts.iter().any(|host| &host.name == *name))
+        {
+            bail!("主机清单中没有名为 {unknown} 的主机");
+        }
+        hosts.retain(|host| only.contains(&host.name));
+    }
+
+    // 改动任何主机之前先检查全部本地文件，避免发布到一半才发现配置错误
+    for host in &hosts {
+        preflight(host).with_context(|| format!("主机 {} 检查失败", host.name))?;
+    }
+
+    let total = hosts.len();
+    println!(
+        "📦 滚动发布 {total} 台主机{}",
+        if dry_run { "（dry-run）" } else { "" }
+    );
+    for (index, host) in hosts.iter().enumerate() {
+        println!(
+            "\n🚀 [{}/{}] {} ({})",
+            index + 1,
+            total,
+            host.name,
+            host.address
+        );
+        if let Err(e) = deploy_host(host, dry_run).await {
+            println!("❌ {} 发布失败: {e:#}", host.name);
+            let remaining: Vec<&str> = hosts[index + 1..]
+                .iter()
+                .map(|host| host.name.as_str())
+                .collect();
+            if !remaining.is_empty() {
+                println!("⏸️  已停止滚动发布，未处理的主机: {}", remaining.join(", "));
+            }
+            return Err(e.context(format!("主机 {} 发布失败", host.name)));
+        }
+        println!("✅ {} 已就绪", host.name);
+    }
+
+    println!("\n🎉 滚动发布完成，共 {total} 台主机");
+    Ok(())
+}
+
+/// 检查本地二进制与配置文件
+fn preflight(host: &Host) -> Result<()> {
+    if !host.binary.is_file() {
+        bail!("二进制文件不存在: {}", host.binary.display());
+    }
+    let content = std::fs::read_to_string(&host.config)
+        .with_context(|| format!("无法读取配置文件: {}", host.config.display()))?;
+    let config: ActrixConfig = toml::from_str(&content)
+        .with_context(|| format!("解析配置文件失败: {}", host.config.display()))?;
+    if let Err(errors) = config.validate() {
+        bail!(
+            "配置校验失败 ({}):\n  - {}",
+            host.config.display(),
+            errors.join("\n  - ")
+        );
+    }
+    Ok(())
+}
+
+/// 发布到单台主机
+async fn deploy_host(host: &Host, dry_run: bool) -> Result<()> {
+    let ssh = SshTarget::new(host, dry_run);
+
+    // 暂存目录由登录用户创建，scp 才能写入
+    let stage = ssh
+        .run("mktemp -d /tmp/actrix-rollout.XXXXXX")
+        .await?
+        .trim()
+        .to_string();
+    let stage = if stage.is_empty() {
+        "/tmp/actrix-rollout.XXXXXX".to_string()
+    } else {
+        stage
+    };
+
+    println!("📤 上传二进制、配置与 systemd unit...");
+    let unit = render_unit(host)?;
+    ssh.upload(&host.binary, &format!("{stage}/actrix")).await?;
+    ssh.upload(&host.config, &format!("{stage}/config.toml"))
+        .await?;
+    ssh.upload(unit.path(), &format!("{stage}/actrix.service"))
+        .await?;
+
+    println!("🔄 安装并重启 {}...", host.service_name);
+    ssh.run_privileged(&install_script(host, &stage)).await?;
+
+    println!("🩺 等待节点就绪（最长 {:?}）...", host.health_timeout);
+    wait_ready(&ssh, host, dry_run).await
+}
+
+/// 渲染 systemd unit 到本地临时文件
+fn render_unit(host: &Host) -> Result<tempfile::NamedTempFile> {
+    let install_config = InstallConfig {
+        install_dir: PathBuf::from(&host.install_dir),
+        binary_name: host.service_name.clone(),
+        add_to_path: false,
+    };
+    let content = SystemdServiceTemplate::new(install_config, PathBuf::from(&host.remote_config))
+        .create_service_content(&host.service_user, &host.service_group)?;
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(content.as_bytes())?;
+    Ok(file)
+}
+
+/// 远程安装脚本：创建服务用户与目录，安装文件，重启服务
+fn install_script(host: &Host, stage: &str) -> String {
+    let user = shell_quote(&host.service_user);
+    let group = shell_quote(&host.service_group);
+    let install_dir = &host.install_dir;
+    let config_dir = Path::new(&host.remote_config)
+        .parent()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    let service = shell_quote(&host.service_name);
+    let unit_path = shell_quote(&format!(
+        "/etc/systemd/system/{}.service",
+        host.service_name
+    ));
+
+    [
+        "set -e".to_string(),
+        format!("getent group {group} >/dev/null || groupadd --system {group}"),
+        format!(
+            "id -u {user} >/dev/null 2>&1 || useradd --system --gid {group} --no-create-home --shell /usr/sbin/nologin {user}"
+        ),
+        format!(
+            "install -d -o {user} -g {group} {} {} {}",
+            shell_quote(&format!("{install_dir}/bin")),
+            shell_quote(&format!("{install_dir}/logs")),
+            shell_quote(&format!("{install_dir}/db"))
+        ),
+        format!("install -d {}", shell_quote(&config_dir)),
+        format!(
+            "install -m 755 {} {}",
+            shell_quote(&format!("{stage}/actrix")),
+            shell_quote(&format!("{install_dir}/bin/actrix"))
+        ),
+        // 配置可能包含机密，仅服务用户组可读
+        format!(
+            "install -m 640 -g {group} {} {}",
+            shell_quote(&format!("{stage}/config.toml")),
+            shell_quote(&host.remote_config)
+        ),
+        format!(
+            "install -m 644 {} {unit_path}",
+            shell_quote(&format!("{stage}/actrix.service"))
+        ),
+        format!("rm -rf {}", shell_quote(stage)),
+        "systemctl daemon-reload".to_string(),
+        format!("systemctl enable {service}"),
+        // Type=notify：restart 在节点发出 READY=1 或启动失败后返回
+        format!("systemctl restart {service}"),
+    ]
+    .join("\n")
+}
+
+/// 轮询 `actrix status` 直到节点就绪或超时
+async fn wait_ready(ssh: &SshTarget<'_>, host: &Host, dry_run: bool) -> Result<()> {
+    let command = format!(
+        "{} --config {} status",
+        shell_quote(&format!("{}/bin/actrix", host.install_dir)),
+        shell_quote(&host.remote_config)
+    );
+    let started = Instant::now();
+    loop {
+        match ssh.run_privileged(&command).await {
+            Ok(_) => return Ok(()),
+            Err(e) if dry_run || started.elapsed() >= host.health_timeout => {
+                return Err(e.context(format!(
+                    "节点在 {:?} 内未就绪，查看日志: journalctl -u {}",
+                    host.health_timeout, host.service_name
+                )));
+            }
+            Err(_) => tokio::time::sleep(HEALTH_POLL_INTERVAL).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_script_quotes_paths() {
+        let inventory: Inventory = toml::from_str(
+            r#"
+            [[hosts]]
+            address = "10.0.0.11"
+            install_dir = "/opt/actrix edge"
+            "#,
+        )
+        .unwrap();
+        let host = inventory.resolve(Path::new(".")).unwrap().remove(0);
+        let script = install_script(&host, "/tmp/actrix-rollout.abc");
+
+        assert!(script.contains(
+            "install -m 755 '/tmp/actrix-rollout.abc/actrix' '/opt/actrix edge/bin/actrix'"
+        ));
+        assert!(script.contains("install -d '/etc/actor-rtc-actrix'"));
+        assert!(script.ends_with("systemctl restart 'actrix'"));
+    }
+}
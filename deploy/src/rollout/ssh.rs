@@ -0,0 +1,129 @@
+//! 基于系统 `ssh` / `scp` 命令的远程执行
+//!
+//! 使用 BatchMode 禁止交互式密码输入，认证依赖密钥或 ssh-agent；主机密钥校验沿用本机
+//! `~/.ssh/known_hosts` 与 ssh 配置。
+
+use super::inventory::Host;
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use tokio::process::Command;
+
+/// SSH 连接超时（秒）
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// 远程主机
+pub struct SshTarget<'a> {
+    host: &'a Host,
+    /// 只打印命令，不实际执行
+    dry_run: bool,
+}
+
+impl<'a> SshTarget<'a> {
+    pub fn new(host: &'a Host, dry_run: bool) -> Self {
+        Self { host, dry_run }
+    }
+
+    /// `user@address`
+    fn destination(&self) -> String {
+        match self.host.user {
+            Some(ref user) => format!("{user}@{}", self.host.address),
+            None => self.host.address.clone(),
+        }
+    }
+
+    fn common_args(&self, port_flag: &str) -> Vec<String> {
+        let mut args = vec![
+            port_flag.to_string(),
+            self.host.port.to_string(),
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={CONNECT_TIMEOUT_SECS}"),
+        ];
+        if let Some(ref identity) = self.host.identity_file {
+            args.push("-i".to_string());
+            args.push(identity.display().to_string());
+        }
+        args
+    }
+
+    /// 以 SSH 登录用户执行远程 shell 命令
+    pub async fn run(&self, script: &str) -> Result<String> {
+        self.ssh(script, false).await
+    }
+
+    /// 执行需要特权的远程 shell 命令，主机设置 `sudo` 时整体经 `sudo -n` 执行
+    pub async fn run_privileged(&self, script: &str) -> Result<String> {
+        self.ssh(script, self.host.sudo).await
+    }
+
+    async fn ssh(&self, script: &str, sudo: bool) -> Result<String> {
+        let command = if sudo {
+            format!("sudo -n sh -c {}", shell_quote(script))
+        } else {
+            format!("sh -c {}", shell_quote(script))
+        };
+        let mut args = self.common_args("-p");
+        args.push(self.destination());
+        args.push("--".to_string());
+        args.push(command);
+
+        self.execute("ssh", &args).await
+    }
+
+    /// 上传本地文件
+    pub async fn upload(&self, local: &Path, remote: &str) -> Result<()> {
+        // scp 的远程地址中 IPv6 字面量需要加方括号
+        let address = if self.host.address.contains(':') {
+            format!("[{}]", self.host.address)
+        } else {
+            self.host.address.clone()
+        };
+        let destination = match self.host.user {
+            Some(ref user) => format!("{user}@{address}:{remote}"),
+            None => format!("{address}:{remote}"),
+        };
+        let mut args = self.common_args("-P");
+        args.push("-q".to_string());
+        args.push(local.display().to_string());
+        args.push(destination);
+
+        self.execute("scp", &args).await.map(|_| ())
+    }
+
+    async fn execute(&self, program: &str, args: &[String]) -> Result<String> {
+        if self.dry_run {
+            println!("   [dry-run] {program} {}", args.join(" "));
+            return Ok(String::new());
+        }
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .await
+            .with_context(|| format!("无法执行 {program}"))?;
+        if !output.status.success() {
+            bail!(
+                "{program} 执行失败 ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// 按 POSIX shell 规则单引号转义
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/opt/actrix"), "'/opt/actrix'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
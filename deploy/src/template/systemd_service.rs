@@ -56,7 +56,12 @@ impl SystemdServiceTemplate {
         Ok(())
     }
 
-    fn create_service_content(&self, service_user: &str, service_group: &str) -> Result<String> {
+    /// Render the unit file content
+    pub fn create_service_content(
+        &self,
+        service_user: &str,
+        service_group: &str,
+    ) -> Result<String> {
         let template = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tpl/actrix.service"));
 
         let install_dir_str = self.install_config.install_dir.to_string_lossy();
@@ -17,6 +17,11 @@ pub(crate) struct Cli {
     /// Configuration file path (defaults to searching standard locations)
     #[arg(short, long, default_value = "config.toml")]
     pub(crate) config: PathBuf,
+
+    /// Override a configuration field (repeatable), e.g. --set bind.http.port=9000.
+    /// Applied after the file and ACTRIX__SECTION__FIELD environment variables
+    #[arg(long = "set", value_name = "KEY=VALUE", global = true)]
+    pub(crate) overrides: Vec<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -27,4 +32,80 @@ pub(crate) enum Commands {
         #[arg(index = 1)]
         config_file: Option<PathBuf>,
     },
+    /// Check the host environment for conflicts with the configuration
+    Doctor {
+        /// Configuration file path (optional, defaults to config.toml)
+        #[arg(index = 1)]
+        config_file: Option<PathBuf>,
+    },
+    /// Create a consistent snapshot of node state (databases, registry, key metadata)
+    Snapshot {
+        /// Output archive path (.tar.gz)
+        #[arg(index = 1)]
+        output: PathBuf,
+    },
+    /// Restore node state from a snapshot archive (the node must be stopped)
+    Restore {
+        /// Snapshot archive path
+        #[arg(index = 1)]
+        archive: PathBuf,
+        /// Overwrite existing databases
+        #[arg(long)]
+        force: bool,
+    },
+    /// Encrypt plaintext ACLs and service specs in the registry database (requires registry_encryption)
+    EncryptRegistry,
+    /// Show readiness of the running local node (queries /readyz)
+    Status,
+    /// Inspect realms in the local database
+    Realms {
+        #[command(subcommand)]
+        command: ListCommand,
+    },
+    /// Inspect KS keys in the local key store (metadata only)
+    Keys {
+        #[command(subcommand)]
+        command: ListCommand,
+    },
+    /// Inspect signaling connections of the running local node
+    Connections {
+        #[command(subcommand)]
+        command: ConnectionsCommand,
+    },
+    /// Reload the configuration of the running local node (sends SIGHUP)
+    Reload,
+    /// Configuration file utilities
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ConfigCommand {
+    /// Print a fully commented default configuration (or its JSON schema)
+    Generate {
+        /// Emit the JSON schema instead of TOML
+        #[arg(long)]
+        schema: bool,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ListCommand {
+    /// List all entries
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum ConnectionsCommand {
+    /// List current connections
+    List {
+        /// Only show connections registered in this realm
+        #[arg(long)]
+        realm_id: Option<u32>,
+    },
 }
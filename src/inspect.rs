@@ -0,0 +1,288 @@
+//! 本地节点检查命令
+//!
+//! 供运维在节点所在主机上查看运行中的节点，无需手工构造请求：
+//! - `status`：请求本机主 HTTP 服务的 `/readyz`，输出各服务与依赖检查结果
+//! - `realms list`：读取 `actrix.db` 中的 Realm
+//! - `keys list`：读取 KS SQLite 存储（`ks_keys.db`）中的密钥元数据，不输出私钥
+//! - `connections list`：请求 Signaling 管理 API `{ws_path}/admin/connections`
+//! - `reload`：向 PID 文件记录的进程发送 SIGHUP，触发配置热加载
+//!
+//! 数据库以只读方式打开，节点运行时也可执行。
+
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection, Row};
+use std::path::Path;
+use std::time::Duration;
+
+/// HTTP 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const REALM_DB: &str = "actrix.db";
+const KS_KEYS_DB: &str = "ks_keys.db";
+
+/// 本机主 HTTP 服务的基础 URL
+///
+/// 与 ServiceManager 选择绑定的规则一致：开发环境优先 HTTP，否则使用 HTTPS；
+/// 监听所有接口时改为访问回环地址
+pub fn local_base_url(config: &ActrixConfig) -> Result<String> {
+    let is_dev = config.env.to_lowercase() == "dev";
+    let (scheme, ip, port) = match (&config.bind.http, &config.bind.https) {
+        (Some(http), _) if is_dev => ("http", http.ip.as_str(), http.port),
+        (_, Some(https)) => ("https", https.ip.as_str(), https.port),
+        _ => bail!("未配置可用的 HTTP/HTTPS 绑定"),
+    };
+    let host = match ip {
+        "0.0.0.0" | "" => "127.0.0.1".to_string(),
+        "::" => "[::1]".to_string(),
+        ip if ip.contains(':') && !ip.starts_with('[') => format!("[{ip}]"),
+        ip => ip.to_string(),
+    };
+    Ok(format!("{scheme}://{host}:{port}"))
+}
+
+/// 访问本机服务的 HTTP 客户端
+///
+/// 证书签发给对外域名而不是回环地址，本机访问时不校验证书
+fn local_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .danger_accept_invalid_certs(true)
+        .build()
+        .context("Failed to build HTTP client")
+}
+
+/// 输出就绪探针结果，节点未就绪时返回错误
+pub async fn status(config: &ActrixConfig) -> Result<()> {
+    let url = format!("{}/readyz", local_base_url(config)?);
+    let response = local_client()?
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("无法连接本机节点 {url}"))?;
+    let code = response.status();
+    let body: Value = response.json().await.context("就绪探针返回内容无法解析")?;
+
+    println!("{}", serde_json::to_string_pretty(&body)?);
+    if !code.is_success() {
+        bail!("节点未就绪 (HTTP {code})");
+    }
+    Ok(())
+}
+
+/// 列出本地 Realm
+pub async fn list_realms(config: &ActrixConfig) -> Result<()> {
+    let mut conn = open_read_only(&config.sqlite_path.join(REALM_DB)).await?;
+    let rows = sqlx::query(
+        "SELECT realm_id, name, status, expires_at, created_at FROM realm ORDER BY realm_id",
+    )
+    .fetch_all(&mut conn)
+    .await
+    .context("Failed to read realms")?;
+    conn.close().await?;
+
+    println!(
+        "{:<12} {:<24} {:<10} {:<20} {:<20}",
+        "REALM_ID", "NAME", "STATUS", "EXPIRES_AT", "CREATED_AT"
+    );
+    for row in &rows {
+        println!(
+            "{:<12} {:<24} {:<10} {:<20} {:<20}",
+            row.get::<i64, _>("realm_id"),
+            row.get::<String, _>("name"),
+            row.get::<String, _>("status"),
+            format_timestamp(row.get("expires_at")),
+            format_timestamp(row.get("created_at")),
+        );
+    }
+    println!("共 {} 个 Realm", rows.len());
+    Ok(())
+}
+
+/// 列出 KS 密钥元数据
+pub async fn list_keys(config: &ActrixConfig) -> Result<()> {
+    let mut conn = open_read_only(&config.sqlite_path.join(KS_KEYS_DB)).await?;
+    let rows = sqlx::query(
+        "SELECT key_id, algorithm, status, created_at, expires_at, verify_until
+         FROM keys ORDER BY key_id",
+    )
+    .fetch_all(&mut conn)
+    .await
+    .context("Failed to read keys")?;
+    conn.close().await?;
+
+    println!(
+        "{:<8} {:<12} {:<10} {:<20} {:<20} {:<20}",
+        "KEY_ID", "ALGORITHM", "STATUS", "CREATED_AT", "EXPIRES_AT", "VERIFY_UNTIL"
+    );
+    for row in &rows {
+        println!(
+            "{:<8} {:<12} {:<10} {:<20} {:<20} {:<20}",
+            row.get::<i64, _>("key_id"),
+            row.get::<String, _>("algorithm"),
+            row.get::<String, _>("status"),
+            format_timestamp(row.get("created_at")),
+            format_timestamp(row.get("expires_at")),
+            format_timestamp(row.get("verify_until")),
+        );
+    }
+    println!("共 {} 个密钥", rows.len());
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct ConnectionsResponse {
+    total: usize,
+    connections: Vec<ConnectionEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConnectionEntry {
+    client_id: String,
+    actor_id: Option<String>,
+    realm_id: Option<u32>,
+    client_ip: Option<String>,
+    connected_at: i64,
+    #[serde(default)]
+    services: Vec<Value>,
+}
+
+/// 列出当前 Signaling 连接
+pub async fn list_connections(config: &ActrixConfig, realm_id: Option<u32>) -> Result<()> {
+    let mut url = format!(
+        "{}{}/admin/connections",
+        local_base_url(config)?,
+        config.route_prefixes().signaling
+    );
+    if let Some(realm_id) = realm_id {
+        url.push_str(&format!("?realm_id={realm_id}"));
+    }
+    let response = local_client()?
+        .get(&url)
+        .bearer_auth(config.get_actrix_shared_key())
+        .send()
+        .await
+        .with_context(|| format!("无法连接本机节点 {url}"))?;
+    if !response.status().is_success() {
+        bail!("查询连接失败 (HTTP {})", response.status());
+    }
+    let body: ConnectionsResponse = response.json().await.context("连接列表无法解析")?;
+
+    println!(
+        "{:<38} {:<40} {:<10} {:<40} {:<20} {:<8}",
+        "CLIENT_ID", "ACTOR_ID", "REALM_ID", "CLIENT_IP", "CONNECTED_AT", "SERVICES"
+    );
+    for connection in &body.connections {
+        println!(
+            "{:<38} {:<40} {:<10} {:<40} {:<20} {:<8}",
+            connection.client_id,
+            connection.actor_id.as_deref().unwrap_or("-"),
+            connection
+                .realm_id
+                .map_or_else(|| "-".to_string(), |id| id.to_string()),
+            connection.client_ip.as_deref().unwrap_or("-"),
+            format_timestamp(Some(connection.connected_at)),
+            connection.services.len(),
+        );
+    }
+    println!("共 {} 个连接", body.total);
+    Ok(())
+}
+
+/// 向运行中的节点发送 SIGHUP
+pub fn reload(config: &ActrixConfig) -> Result<()> {
+    let pid_path = config
+        .get_pid_path()
+        .context("未配置 PID 文件路径，无法定位运行中的节点")?;
+    let content = std::fs::read_to_string(&pid_path)
+        .with_context(|| format!("无法读取 PID 文件 {pid_path}，节点是否在运行？"))?;
+    let pid = parse_pid(&content).with_context(|| format!("PID 文件内容无效: {pid_path}"))?;
+    send_hangup(pid)?;
+    println!("已向进程 {pid} 发送 SIGHUP，配置将重新加载");
+    Ok(())
+}
+
+#[cfg(unix)]
+fn send_hangup(pid: i32) -> Result<()> {
+    use nix::sys::signal::{Signal, kill};
+    use nix::unistd::Pid;
+
+    kill(Pid::from_raw(pid), Signal::SIGHUP)
+        .with_context(|| format!("向进程 {pid} 发送 SIGHUP 失败"))
+}
+
+#[cfg(not(unix))]
+fn send_hangup(_pid: i32) -> Result<()> {
+    bail!("当前平台不支持通过信号触发配置热加载")
+}
+
+fn parse_pid(content: &str) -> Option<i32> {
+    content.trim().parse::<i32>().ok().filter(|pid| *pid > 0)
+}
+
+async fn open_read_only(path: &Path) -> Result<sqlx::SqliteConnection> {
+    if !path.exists() {
+        bail!("数据库不存在: {}", path.display());
+    }
+    SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+/// Unix 秒格式化为 UTC 时间，空值与 0 显示为 `-`
+fn format_timestamp(timestamp: Option<i64>) -> String {
+    timestamp
+        .filter(|ts| *ts > 0)
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map_or_else(
+            || "-".to_string(),
+            |time| time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::config::bind::{HttpBindConfig, HttpsBindConfig};
+
+    #[test]
+    fn test_local_base_url_prefers_http_in_dev() {
+        let mut config = ActrixConfig {
+            env: "dev".to_string(),
+            ..ActrixConfig::default()
+        };
+        config.bind.http = Some(HttpBindConfig::default());
+        config.bind.https = None;
+        assert_eq!(local_base_url(&config).unwrap(), "http://127.0.0.1:8080");
+
+        config.env = "prod".to_string();
+        assert!(local_base_url(&config).is_err());
+
+        config.bind.https = Some(HttpsBindConfig {
+            ip: "::".to_string(),
+            port: 8443,
+            ..HttpsBindConfig::default()
+        });
+        assert_eq!(local_base_url(&config).unwrap(), "https://[::1]:8443");
+    }
+
+    #[test]
+    fn test_parse_pid() {
+        assert_eq!(parse_pid("4242\n"), Some(4242));
+        assert_eq!(parse_pid("0"), None);
+        assert_eq!(parse_pid("not a pid"), None);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(None), "-");
+        assert_eq!(format_timestamp(Some(0)), "-");
+        assert_eq!(format_timestamp(Some(86_400)), "1970-01-02 00:00:00");
+    }
+}
@@ -3,6 +3,7 @@
 //! WebRTC 辅助服务器集合，包括信令服务、STUN 和 TURN 服务
 
 pub mod service;
+pub mod snapshot;
 
 // Re-export commonly used types
 pub use actrix_common::config::ActrixConfig;
@@ -5,22 +5,28 @@
 mod cli;
 // mod config; // 已迁移到独立的 config crate
 mod error;
+mod inspect;
 mod observability;
 mod process;
 mod service;
+mod snapshot;
 
 use actrix_common::config::ActrixConfig;
+use actrix_common::realm::RealmLifecycleManager;
 use anyhow::Context;
 use clap::Parser;
-use observability::init_observability;
+use observability::{LogFilterHandle, init_observability};
 use service::{
-    AisService, KsGrpcService, KsHttpService, ServiceContainer, ServiceManager, SignalingService,
-    StunService, SupervisordGrpcService, TurnService,
+    AisService, KsHttpService, RestartCoordinator, ServiceContainer, ServiceManager, ServiceTasks,
+    SignalingService, StunService, SupervisordGrpcService, TurnService, node_capabilities,
 };
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
-use supervit::{SupervitClient, SupervitConfig};
-use tokio::task::JoinHandle;
+use std::time::Duration;
+use supervit::{
+    Directive, DirectiveHandler, DirectiveType, SelfUpdater, SupervitClient, SupervitConfig,
+    UpdateBundle, UpdateBundleKind, directive_handler,
+};
 
 use tracing::{error, info, warn};
 
@@ -36,7 +42,7 @@ macro_rules! bootstrap_error {
     };
 }
 
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, ConfigCommand, ConnectionsCommand, ListCommand};
 use error::{Error, Result};
 
 /// Application launcher utilities
@@ -45,12 +51,118 @@ struct ApplicationLauncher;
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    let overrides = cli
+        .overrides
+        .iter()
+        .map(|arg| actrix_common::config::layers::ConfigOverride::parse_set(arg))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(Error::custom)?;
+    actrix_common::config::layers::set_cli_overrides(overrides);
+
     match &cli.command {
         Some(Commands::Test { config_file }) => {
             let config_path =
                 ApplicationLauncher::find_config_file(config_file.as_ref().unwrap_or(&cli.config))?;
             ApplicationLauncher::test_config_file(&Some(config_path.clone()), &config_path)
         }
+        Some(Commands::Doctor { config_file }) => {
+            let config_path =
+                ApplicationLauncher::find_config_file(config_file.as_ref().unwrap_or(&cli.config))?;
+            ApplicationLauncher::run_doctor(&config_path)
+        }
+        Some(Commands::Snapshot { output }) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                let manifest = snapshot::create_snapshot(&config, output).await?;
+                info!(
+                    "✅ 快照完成: {} 个数据库, {} 个服务注册, {} 个密钥 (包含私钥: {})",
+                    manifest.databases.len(),
+                    manifest.registry_services,
+                    manifest.keys.keys.len(),
+                    manifest.keys.secrets_included
+                );
+                Ok(())
+            })
+        }
+        Some(Commands::Restore { archive, force }) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                let manifest = snapshot::restore_snapshot(&config, archive, *force).await?;
+                info!(
+                    "✅ 恢复完成: 节点 {} 于 {} 的快照, {} 个数据库",
+                    manifest.node_name,
+                    manifest.created_at,
+                    manifest.databases.len()
+                );
+                Ok(())
+            })
+        }
+        Some(Commands::EncryptRegistry) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                let db_file = config.sqlite_path.join("signaling_cache.db");
+                if !db_file.exists() {
+                    anyhow::bail!("服务注册表数据库不存在: {}", db_file.display());
+                }
+
+                let cipher = signaling::registry_encryption::RegistryCipher::from_config(&config)?;
+                if !cipher.is_enabled() {
+                    anyhow::bail!(
+                        "未启用 services.signaling.server.registry_encryption，无法加密注册表"
+                    );
+                }
+
+                let storage =
+                    signaling::service_registry_storage::SqliteRegistryStorage::new(&db_file, None)
+                        .await?
+                        .with_cipher(cipher);
+                let stats = storage.migrate_encryption().await?;
+                info!(
+                    "✅ 注册表加密完成: {} 个 ServiceSpec, {} 个 ACL, {} 个 proto spec, {} 个历史版本",
+                    stats.service_specs, stats.acls, stats.proto_specs, stats.spec_history
+                );
+                Ok(())
+            })
+        }
+        Some(Commands::Status) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                inspect::status(&config).await
+            })
+        }
+        Some(Commands::Realms {
+            command: ListCommand::List,
+        }) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                inspect::list_realms(&config).await
+            })
+        }
+        Some(Commands::Keys {
+            command: ListCommand::List,
+        }) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                inspect::list_keys(&config).await
+            })
+        }
+        Some(Commands::Connections {
+            command: ConnectionsCommand::List { realm_id },
+        }) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                inspect::list_connections(&config, *realm_id).await
+            })
+        }
+        Some(Commands::Config {
+            command: ConfigCommand::Generate { schema, output },
+        }) => ApplicationLauncher::generate_config(*schema, output.as_deref()),
+        Some(Commands::Reload) => {
+            let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
+            ApplicationLauncher::run_snapshot_command(&config_path, |config| async move {
+                inspect::reload(&config)
+            })
+        }
         None => {
             let config_path = ApplicationLauncher::find_config_file(&cli.config)?;
 
@@ -158,6 +270,92 @@ impl ApplicationLauncher {
         }
     }
 
+    /// 检查运行环境与配置的冲突
+    fn run_doctor(config_path: &Path) -> Result<()> {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .init();
+
+        let config = ActrixConfig::from_file(config_path)
+            .map_err(|e| Error::custom(format!("配置加载失败: {e}")))?;
+        info!("🩺 检查运行环境: {:?}", config_path);
+
+        let mut problems = 0;
+
+        if config.is_turn_enabled() {
+            let ephemeral = actrix_common::config::turn::system_ephemeral_port_range();
+            match ephemeral {
+                Some((start, end)) => info!("系统临时端口范围: {}-{}", start, end),
+                None => info!("无法读取系统临时端口范围，跳过临时端口冲突检查"),
+            }
+
+            if let Err(e) = config.turn.relay_port_bounds() {
+                error!("❌ {}", e);
+                problems += 1;
+            }
+            for conflict in [
+                config.relay_port_listener_conflict(ephemeral),
+                config.relay_port_ephemeral_conflict(ephemeral),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                error!("❌ {}", conflict);
+                problems += 1;
+            }
+        }
+
+        if problems > 0 {
+            return Err(Error::service_validation(format!(
+                "环境检查发现 {problems} 个问题"
+            )));
+        }
+
+        info!("✅ 环境检查通过");
+        Ok(())
+    }
+
+    /// 加载配置并执行快照/恢复命令
+    /// 输出带注释的默认配置或 JSON Schema
+    fn generate_config(schema: bool, output: Option<&Path>) -> Result<()> {
+        let content = if schema {
+            let schema = actrix_common::config::schema::json_schema();
+            serde_json::to_string_pretty(&schema)
+                .map_err(|e| Error::custom(format!("JSON Schema 序列化失败: {e}")))?
+                + "\n"
+        } else {
+            actrix_common::config::schema::default_config_toml()
+        };
+
+        match output {
+            Some(path) => {
+                std::fs::write(path, content)?;
+                bootstrap_info!("✅ 已写入 {:?}", path);
+            }
+            None => print!("{content}"),
+        }
+        Ok(())
+    }
+
+    fn run_snapshot_command<F, Fut>(config_path: &Path, command: F) -> Result<()>
+    where
+        F: FnOnce(ActrixConfig) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<()>>,
+    {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .init();
+
+        let config = ActrixConfig::from_file(config_path)
+            .map_err(|e| Error::custom(format!("配置加载失败: {e}")))?;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(command(config))?;
+        Ok(())
+    }
+
     /// 运行应用程序的主入口
     async fn run_application(config_path: &Path) -> Result<()> {
         bootstrap_info!("📄 加载配置文件: {:?}", config_path);
@@ -184,6 +382,15 @@ impl ApplicationLauncher {
                     }
                 }
 
+                // 中继端口与系统临时端口重叠会导致分配偶发失败，仅提示不阻止启动
+                if config.is_turn_enabled()
+                    && let Some(conflict) = config.relay_port_ephemeral_conflict(
+                        actrix_common::config::turn::system_ephemeral_port_range(),
+                    )
+                {
+                    bootstrap_info!("⚠️  {}", conflict);
+                }
+
                 config
             }
             Err(e) => {
@@ -193,7 +400,7 @@ impl ApplicationLauncher {
         };
 
         // ensure sqlite_path directory exists
-        if !config.sqlite_path.exists() {
+        if !config.storage.is_memory() && !config.sqlite_path.exists() {
             std::fs::create_dir_all(&config.sqlite_path).with_context(|| {
                 format!(
                     "Failed to create SQLite data directory: {}",
@@ -214,23 +421,49 @@ impl ApplicationLauncher {
         let group = config.group.clone();
 
         // 运行服务
-        Self::run_services_with_privilege_drop(config, user, group).await
+        Self::run_services_with_privilege_drop(
+            config,
+            config_path,
+            _observability_guard.filter_handle(),
+            user,
+            group,
+        )
+        .await
     }
 
     /// 运行服务并在适当时机切换用户权限
     async fn run_services_with_privilege_drop(
         config: ActrixConfig,
+        config_path: &Path,
+        log_filter: Option<LogFilterHandle>,
         user: Option<String>,
         group: Option<String>,
     ) -> Result<()> {
         info!("🚀 启动 WebRTC 辅助服务器集群");
 
+        // 自更新会替换可执行文件，替换后 current_exe 可能指向已删除的文件，需提前记录
+        let current_exe = std::env::current_exe()
+            .map_err(|e| Error::custom(format!("无法获取当前可执行文件路径: {e}")))?;
+
+        if config.is_dev_mock_enabled() && !cfg!(feature = "dev-mock") {
+            return Err(Error::service_startup(
+                "dev.mock_dependencies requires a build with --features dev-mock".to_string(),
+            ));
+        }
+
         // First initialize the database,
         // ensure it is ready before any service that may access it starts
-        actrix_common::storage::db::set_db_path(&config.sqlite_path)
-            .await
-            .map_err(|e| Error::custom(format!("数据库初始化失败: {e}")))?;
-        info!("✅ 数据库初始化完成");
+        let db_result = if config.storage.is_memory() {
+            actrix_common::storage::db::set_in_memory_db().await
+        } else {
+            actrix_common::storage::db::set_db_path(&config.sqlite_path).await
+        };
+        db_result.map_err(|e| Error::custom(format!("数据库初始化失败: {e}")))?;
+        if config.storage.is_memory() {
+            info!("✅ 数据库初始化完成（内存模式，数据不会持久化）");
+        } else {
+            info!("✅ 数据库初始化完成");
+        }
 
         // 初始化全局关闭通道（供所有服务共享）
         let (shutdown_tx, _) = tokio::sync::broadcast::channel::<()>(10);
@@ -238,24 +471,43 @@ impl ApplicationLauncher {
         // 安装 Ctrl-C 处理器，确保任何阶段都能广播关闭
         setup_ctrl_c_handler(shutdown_tx.clone()).await;
 
-        // 如果启用 KS，构建 gRPC 服务 future
-        let mut handle_futs: Vec<JoinHandle<()>> = Vec::new();
+        // 各服务的后台任务，关闭时按 shutdown.grace_period_secs 等待退出
+        let mut tasks = ServiceTasks::new(shutdown_tx.clone());
+
+        // Realm 到期检查：到期的 Realm 置为 Suspended，并随状态报告通知 Supervisor
+        tasks.push(
+            "realm-lifecycle",
+            RealmLifecycleManager::default().spawn(shutdown_tx.subscribe()),
+        );
 
         let mut service_manager =
             Self::create_service_manager(config.clone(), shutdown_tx.clone()).await?;
 
         if config.is_ks_enabled() {
             info!("启动 KS gRPC 服务器...");
-            let grpc_addr = "127.0.0.1:50052".parse().map_err(|e| {
+            // 由服务控制器持有，运行时停用 KS 时一并关闭 gRPC 端口
+            let grpc_future = service_manager
+                .controller()
+                .start_ks_grpc()
+                .await
+                .map_err(|e| Error::service_startup(format!("KS gRPC 初始化失败: {e}")))?;
+
+            tasks.push("ks-grpc", grpc_future);
+        }
+
+        #[cfg(feature = "dev-mock")]
+        if config.is_dev_mock_enabled() && !config.is_ks_enabled() {
+            warn!("⚠️  启动模拟 KS gRPC 服务器（仅限开发，密钥可公开推导）");
+            let grpc_addr = config.ks_grpc_bind().socket_addr().map_err(|e| {
                 Error::service_startup(format!("Failed to parse gRPC address: {e}"))
             })?;
-            let mut grpc_service = KsGrpcService::new(config.clone());
-            let grpc_future = grpc_service
-                .start(grpc_addr, shutdown_tx.clone())
+            let grpc_future = service::KsGrpcService::new(config.clone())
+                .with_service_collector(service_manager.service_collector())
+                .start_mock(grpc_addr, shutdown_tx.clone())
                 .await
-                .map_err(|e| Error::service_startup(format!("KS gRPC 初始化失败: {e}")))?;
+                .map_err(|e| Error::service_startup(format!("模拟 KS gRPC 初始化失败: {e}")))?;
 
-            handle_futs.push(grpc_future);
+            tasks.push("ks-grpc-mock", grpc_future);
         }
 
         if let Some(supervisor_cfg) = &config.supervisor {
@@ -281,23 +533,37 @@ impl ApplicationLauncher {
             let mut grpc_service = SupervisordGrpcService::new(
                 supervisor_cfg.clone(),
                 config.sqlite_path.clone(),
+                config.nonce_storage_config(),
                 config.location_tag.clone(),
                 service_collector,
-            );
+                node_capabilities(&config),
+            )
+            .with_service_controller(service_manager.controller());
             let grpc_future = grpc_service
                 .start(bind_addr, shutdown_tx.clone())
                 .await
                 .map_err(|e| Error::service_startup(format!("Supervisord gRPC 初始化失败: {e}")))?;
-            handle_futs.push(grpc_future);
+            tasks.push("supervisord-grpc", grpc_future);
         }
 
         // wait for gRPC service to start
-        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
 
-        let handle_futures = service_manager.start_all().await?;
-        handle_futs.extend(handle_futures);
+        for (name, handle) in service_manager.start_all().await? {
+            tasks.push(name, handle);
+        }
         info!("启动所有服务...");
 
+        // 配置热加载（SIGHUP / 文件变化）
+        let mut reloader = service_manager.config_reloader(config_path);
+        if let Some(handle) = log_filter {
+            reloader = reloader.with_log_filter(move |level| {
+                observability::reload_filter_level(&handle, level)
+                    .map_err(|e| anyhow::anyhow!("{e}"))
+            });
+        }
+        tasks.push("config-reload", reloader.spawn(shutdown_tx.subscribe()));
+
         // Start supervit after all services are started
         if config.is_supervisor_enabled()
             && let Some(supervisor_cfg) = &config.supervisor
@@ -312,10 +578,14 @@ impl ApplicationLauncher {
                 name: Some(supervisor_cfg.node_name().to_string()),
                 location_tag: config.location_tag.clone(),
                 endpoint: endpoint.to_string(),
+                fallback_endpoints: supervisor_cfg.client.fallback_endpoints.clone(),
                 agent_addr: supervisord_cfg.advertised_addr(),
                 connect_timeout_secs: supervisor_cfg.connect_timeout_secs,
                 status_report_interval_secs: supervisor_cfg.status_report_interval_secs,
                 health_check_interval_secs: supervisor_cfg.health_check_interval_secs,
+                max_reconnect_delay_secs: supervisor_cfg.max_reconnect_delay_secs,
+                report_buffer_size: supervisor_cfg.report_buffer_size,
+                metrics_sample_interval_secs: supervisor_cfg.metrics_sample_interval_secs,
                 enable_tls: supervisor_cfg.enable_tls,
                 tls_domain: supervisor_cfg.tls_domain.clone(),
                 client_cert: supervisor_cfg.client_cert.clone(),
@@ -325,12 +595,35 @@ impl ApplicationLauncher {
                 max_clock_skew_secs: supervisor_cfg.max_clock_skew_secs,
                 location: None,
                 service_tags: Vec::new(),
+                capabilities: node_capabilities(&config),
             };
 
             // Get service collector from service manager
             let service_collector = service_manager.service_collector();
 
-            info!("Starting Supervit client (register and status reporting)...");
+            let self_update = match &supervisor_cfg.update {
+                Some(update_cfg) => match SelfUpdater::new(
+                    &update_cfg.public_key,
+                    env!("CARGO_PKG_VERSION"),
+                    &update_cfg.staging_dir,
+                    update_cfg.max_bundle_bytes,
+                ) {
+                    Ok(updater) => Some(SelfUpdateContext {
+                        updater,
+                        restart: service_manager.restart_coordinator(),
+                        exe_path: current_exe.clone(),
+                        config_path: config_path.to_path_buf(),
+                    }),
+                    Err(e) => {
+                        warn!("Self-update disabled: {}", e);
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            info!("Starting Supervit client (register, status reporting and directive stream)...");
+            let directive_shutdown_tx = shutdown_tx.clone();
             let register_handle = tokio::spawn(async move {
                 // ServiceCollector now uses ServiceInfo internally, so we can pass it directly
                 match SupervitClient::new(client_config.clone(), service_collector) {
@@ -351,12 +644,23 @@ impl ApplicationLauncher {
                         } else {
                             info!("✅ Status reporting started");
                         }
+
+                        let handler = supervisor_directive_handler(
+                            directive_shutdown_tx.clone(),
+                            self_update,
+                        );
+                        match client
+                            .start_directive_watch(Some(handler), directive_shutdown_tx.subscribe())
+                        {
+                            Ok(_) => info!("✅ Directive stream started"),
+                            Err(e) => warn!("Start directive stream failed: {}", e),
+                        }
                     }
                     Err(e) => warn!("Create supervit client failed: {}", e),
                 }
             });
 
-            handle_futs.push(register_handle);
+            tasks.push("supervit-client", register_handle);
         }
 
         // 端口绑定完成后，切换用户和组
@@ -370,15 +674,32 @@ impl ApplicationLauncher {
         // 显示服务信息
         Self::display_service_info(&config);
 
-        for handle in handle_futs {
-            if let Err(e) = handle.await {
-                error!("Service task terminated unexpectedly: {}", e);
-                let _ = shutdown_tx.send(());
-            }
-        }
+        // 以 systemd Type=notify 运行时通知就绪，并由看门狗监督服务状态
+        service::systemd::notify_ready("Running");
+        tasks.push(
+            "systemd-watchdog",
+            service_manager.spawn_systemd_watchdog(shutdown_tx.subscribe()),
+        );
+
+        tasks
+            .wait(Duration::from_secs(config.shutdown.grace_period_secs))
+            .await;
         service_manager.stop_all().await?;
 
         info!("🛑 所有服务已安全关闭");
+
+        let restart = service_manager.restart_coordinator();
+        if let Some(reason) = restart.pending() {
+            info!("🔄 重启以应用更新: {}", reason);
+            if let Err(e) = process::ProcessManager::restart(&current_exe) {
+                error!("重启失败: {}", e);
+                if restart.rollback_update()? {
+                    warn!("已回滚更新，使用原有版本重启");
+                    process::ProcessManager::restart(&current_exe)?;
+                }
+                return Err(e);
+            }
+        }
         Ok(())
     }
 
@@ -400,15 +721,19 @@ impl ApplicationLauncher {
             );
         }
 
-        // 注册各服务的 metrics
-        if config.is_ks_enabled()
-            && let Err(e) = ks::register_ks_metrics(registry)
-        {
+        // 注册各服务的 metrics（不区分是否启用，运行时启用的服务同样可以导出）
+        if let Err(e) = ks::register_ks_metrics(registry) {
             warn!(
                 "KS metrics registration warning (may already be registered): {}",
                 e
             );
         }
+        if let Err(e) = ais::register_ais_metrics(registry) {
+            warn!(
+                "AIS metrics registration warning (may already be registered): {}",
+                e
+            );
+        }
 
         info!("✅ Prometheus metrics registry 初始化成功");
 
@@ -430,19 +755,29 @@ impl ApplicationLauncher {
 
         // 添加HTTP路由服务 - 每个服务独立控制
         if config.is_signaling_enabled() {
-            info!("  - Signaling WebSocket Service (/signaling)");
+            info!(
+                "  - Signaling WebSocket Service ({})",
+                config.route_prefixes().signaling
+            );
             let signaling_service = SignalingService::new(config.clone());
             service_manager.add_service(ServiceContainer::signaling(signaling_service));
         }
 
         if config.is_ais_enabled() {
-            info!("  - AIS Service (/ais)");
+            info!("  - AIS Service ({})", config.http.route_prefixes.ais);
             let ais_service = AisService::new(config.clone());
             service_manager.add_service(ServiceContainer::ais(ais_service));
         }
 
+        #[cfg(feature = "dev-mock")]
+        if config.is_dev_mock_enabled() && !config.is_ais_enabled() {
+            info!("  - Mock AIS Service ({})", config.http.route_prefixes.ais);
+            let ais_service = AisService::mock(config.clone());
+            service_manager.add_service(ServiceContainer::ais(ais_service));
+        }
+
         if config.is_ks_enabled() {
-            info!("  - KS Service (/ks)");
+            info!("  - KS Service ({})", config.http.route_prefixes.ks);
             let ks_service = KsHttpService::new(config.clone());
             service_manager.add_service(ServiceContainer::ks(ks_service));
         }
@@ -471,19 +806,20 @@ impl ApplicationLauncher {
 
         info!("✅ 所有服务已启动");
 
+        let prefixes = config.route_prefixes();
         if !urls.is_empty() {
             for (protocol, http_url, _ws_url) in &urls {
                 info!("📡 {} 服务器监听在: {}", protocol, http_url);
                 info!("🔧 可用的API端点:");
                 if config.is_signaling_enabled() {
-                    info!("  - {}/signaling/ws", _ws_url);
+                    info!("  - {}{}/ws", _ws_url, prefixes.signaling);
                 }
                 if config.is_ks_enabled() {
-                    info!("  - {}/ks/health", http_url);
+                    info!("  - {}{}/health", http_url, prefixes.ks);
                 }
                 if config.is_ais_enabled() {
-                    info!("  - {}/ais/health", http_url);
-                    info!("  - {}/ais/register (POST protobuf)", http_url);
+                    info!("  - {}{}/health", http_url, prefixes.ais);
+                    info!("  - {}{}/register (POST protobuf)", http_url, prefixes.ais);
                 }
             }
         } else {
@@ -493,7 +829,11 @@ impl ApplicationLauncher {
         // 显示 gRPC 服务信息
         if config.is_ks_enabled() {
             info!("🔌 gRPC 服务:");
-            info!("  - KS gRPC Server: 127.0.0.1:50052");
+            let ks_grpc_bind = config.ks_grpc_bind();
+            info!(
+                "  - KS gRPC Server: {}:{}",
+                ks_grpc_bind.ip, ks_grpc_bind.port
+            );
         }
         if config.is_supervisor_enabled()
             && let Some(supervisor_cfg) = &config.supervisor
@@ -508,14 +848,131 @@ impl ApplicationLauncher {
     }
 }
 
+/// UPDATE 指令的下载、安装与重启上下文
+#[derive(Clone)]
+struct SelfUpdateContext {
+    updater: SelfUpdater,
+    restart: RestartCoordinator,
+    /// 启动时记录的可执行文件路径（BINARY 包的安装目标）
+    exe_path: PathBuf,
+    /// 当前配置文件路径（CONFIG 包的安装目标）
+    config_path: PathBuf,
+}
+
+impl SelfUpdateContext {
+    /// 校验并安装更新包，成功后请求优雅重启
+    async fn apply(&self, bundle: &UpdateBundle) -> supervit::Result<()> {
+        let staged = self.updater.stage(bundle).await?;
+        let target = match staged.kind {
+            UpdateBundleKind::Config => {
+                // 安装前确认新配置可以加载，避免重启后无法启动
+                ActrixConfig::from_file(&staged.path).map_err(|e| {
+                    supervit::SupervitError::Update(format!("Invalid config bundle: {e}"))
+                })?;
+                &self.config_path
+            }
+            UpdateBundleKind::Binary => &self.exe_path,
+            UpdateBundleKind::Unspecified => {
+                return Err(supervit::SupervitError::Update(
+                    "Bundle kind is unspecified".to_string(),
+                ));
+            }
+        };
+        staged.install(target)?;
+        self.restart
+            .request_restart_after_update(staged, target.clone());
+        Ok(())
+    }
+}
+
+/// 处理 Supervisor 推送的指令
+///
+/// REALM_UPDATE 已由 supervit 写入本地 Realm 表；DRAIN 使 Signaling 进入排空状态，
+/// 新注册按 payload 中的备选节点重定向（未运行 Signaling 时与 GRACEFUL_SHUTDOWN 一样触发优雅关闭）；
+/// UPDATE 在配置了 `[supervisor.update]` 时安装签名的更新包并重启；
+/// CONFIG_UPDATE 仅记录，配置文件仍是运行时配置的唯一来源
+fn supervisor_directive_handler(
+    shutdown_tx: tokio::sync::broadcast::Sender<()>,
+    self_update: Option<SelfUpdateContext>,
+) -> DirectiveHandler {
+    directive_handler(move |directive: Directive| {
+        let shutdown_tx = shutdown_tx.clone();
+        let self_update = self_update.clone();
+        async move {
+            match directive.r#type() {
+                DirectiveType::Update => match (&directive.update, &self_update) {
+                    (Some(bundle), Some(self_update)) => {
+                        info!("收到 Supervisor 更新指令: {}", bundle.version);
+                        if let Err(e) = self_update.apply(bundle).await {
+                            error!("更新 {} 失败: {}", bundle.version, e);
+                        }
+                    }
+                    (None, _) => warn!("UPDATE 指令缺少更新包，忽略"),
+                    (_, None) => warn!("未配置 [supervisor.update]，忽略 UPDATE 指令"),
+                },
+                DirectiveType::Drain | DirectiveType::GracefulShutdown => {
+                    if directive.r#type() == DirectiveType::Drain
+                        && let Some(server) = signaling::admin::registered_server()
+                    {
+                        info!("收到 Supervisor DRAIN 指令，Signaling 进入排空状态");
+                        server.drain.start(
+                            signaling::drain::DrainRedirect::from_directive_payload(
+                                directive.payload.as_deref(),
+                            ),
+                        );
+                        return;
+                    }
+                    warn!(
+                        "收到 Supervisor 指令 {:?}，开始优雅关闭: {}",
+                        directive.r#type(),
+                        directive.payload.as_deref().unwrap_or("")
+                    );
+                    let _ = shutdown_tx.send(());
+                }
+                DirectiveType::ConfigUpdate => {
+                    if let Some(change) = &directive.config {
+                        warn!(
+                            "收到 Supervisor 配置变更 {:?} {}={}，请更新配置文件后重新加载",
+                            change.config_type(),
+                            change.config_key,
+                            change.config_value
+                        );
+                    }
+                }
+                DirectiveType::RealmUpdate => {}
+                other => info!("忽略 Supervisor 指令 {:?}", other),
+            }
+        }
+    })
+}
+
 /// 设置Ctrl-C信号处理程序
+///
+/// Unix 上同时处理 SIGTERM（systemd / K8s 停止进程时发送），使 shutdown.grace_period_secs 生效
 async fn setup_ctrl_c_handler(shutdown_tx: tokio::sync::broadcast::Sender<()>) {
+    let ctrl_c_tx = shutdown_tx.clone();
     tokio::spawn(async move {
         if let Err(e) = tokio::signal::ctrl_c().await {
             error!("无法监听Ctrl-C信号: {}", e);
             return;
         }
         info!("收到Ctrl-C信号，开始优雅关闭...");
+        let _ = ctrl_c_tx.send(());
+    });
+
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut sigterm = match signal(SignalKind::terminate()) {
+            Ok(sigterm) => sigterm,
+            Err(e) => {
+                error!("无法监听SIGTERM信号: {}", e);
+                return;
+            }
+        };
+        sigterm.recv().await;
+        info!("收到SIGTERM信号，开始优雅关闭...");
         let _ = shutdown_tx.send(());
     });
 }
@@ -1,7 +1,12 @@
+#[cfg(feature = "opentelemetry")]
+mod prometheus_bridge;
+mod rotation;
+
 use actrix_common::config::{ActrixConfig, ObservabilityConfig};
 use std::fs;
+use supervit::LogBufferLayer;
 use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
-use tracing_subscriber::{filter::EnvFilter, fmt, prelude::*};
+use tracing_subscriber::{Registry, filter::EnvFilter, fmt, prelude::*, reload};
 
 #[cfg(feature = "opentelemetry")]
 use crate::error::Error;
@@ -13,14 +18,33 @@ use opentelemetry_otlp::WithExportConfig;
 #[cfg(feature = "opentelemetry")]
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 #[cfg(feature = "opentelemetry")]
-use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+use opentelemetry_sdk::{
+    Resource,
+    metrics::{PeriodicReader, SdkMeterProvider},
+    trace::SdkTracerProvider,
+};
+
+/// Handle for replacing the global log filter at runtime
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
 
-/// Guard for observability resources (tracer provider and log writer)
+/// Guard for observability resources (tracer/meter providers and log writer)
 #[derive(Default)]
 pub struct ObservabilityGuard {
     #[cfg(feature = "opentelemetry")]
     tracer_provider: Option<SdkTracerProvider>,
+    #[cfg(feature = "opentelemetry")]
+    meter_provider: Option<SdkMeterProvider>,
+    #[cfg(feature = "opentelemetry")]
+    metrics_bridge: Option<tokio::task::JoinHandle<()>>,
     log_guard: Option<WorkerGuard>,
+    filter_handle: Option<LogFilterHandle>,
+}
+
+impl ObservabilityGuard {
+    /// Handle for reloading the log filter (None when RUST_LOG overrides the config)
+    pub fn filter_handle(&self) -> Option<LogFilterHandle> {
+        self.filter_handle.clone()
+    }
 }
 
 impl Drop for ObservabilityGuard {
@@ -31,6 +55,19 @@ impl Drop for ObservabilityGuard {
         {
             eprintln!("Failed to shutdown tracer provider: {e:?}");
         }
+
+        #[cfg(feature = "opentelemetry")]
+        if let Some(bridge) = self.metrics_bridge.take() {
+            bridge.abort();
+        }
+
+        // Shutting down the meter provider flushes a final export
+        #[cfg(feature = "opentelemetry")]
+        if let Some(provider) = self.meter_provider.take()
+            && let Err(e) = provider.shutdown()
+        {
+            eprintln!("Failed to shutdown meter provider: {e:?}");
+        }
     }
 }
 
@@ -53,22 +90,58 @@ pub fn init_observability(config: &ActrixConfig) -> Result<ObservabilityGuard> {
         }
     }
 
+    #[cfg(feature = "opentelemetry")]
+    if let Some(provider) = build_meter_provider(config)? {
+        use opentelemetry::metrics::MeterProvider as _;
+        let bridge = prometheus_bridge::PrometheusBridge::new(provider.meter("actrix"));
+        guard.metrics_bridge = Some(bridge.spawn());
+        guard.meter_provider = Some(provider);
+    }
+
+    #[cfg(not(feature = "opentelemetry"))]
+    if observability_config.metrics.otlp.enabled {
+        println!(
+            "⚠️  observability.metrics.otlp is enabled but this build lacks the opentelemetry feature, OTLP metrics export is disabled"
+        );
+    }
+
     Ok(guard)
 }
 
-/// Create an EnvFilter from config, with RUST_LOG taking precedence
-fn create_env_filter(config: &ObservabilityConfig) -> EnvFilter {
-    let directive = std::env::var("RUST_LOG")
+/// RUST_LOG directive, if set and non-empty
+fn rust_log_directive() -> Option<String> {
+    std::env::var("RUST_LOG")
         .ok()
         .map(|v| v.trim().to_string())
         .filter(|v| !v.is_empty())
-        .unwrap_or_else(|| {
-            println!(
-                "RUST_LOG not set, using default filter level: {}",
-                config.filter_level
-            );
-            config.filter_level.clone()
-        });
+}
+
+/// Replace the global log filter with `filter_level`
+///
+/// RUST_LOG is fixed at startup and keeps precedence over the config file
+pub fn reload_filter_level(handle: &LogFilterHandle, filter_level: &str) -> Result<()> {
+    if rust_log_directive().is_some() {
+        return Err(crate::error::Error::custom(
+            "RUST_LOG is set and overrides observability.filter_level".to_string(),
+        ));
+    }
+    let filter = EnvFilter::try_new(filter_level).map_err(|e| {
+        crate::error::Error::custom(format!("Invalid filter directive '{filter_level}': {e}"))
+    })?;
+    handle
+        .reload(filter)
+        .map_err(|e| crate::error::Error::custom(format!("Failed to reload log filter: {e}")))
+}
+
+/// Create an EnvFilter from config, with RUST_LOG taking precedence
+fn create_env_filter(config: &ObservabilityConfig) -> EnvFilter {
+    let directive = rust_log_directive().unwrap_or_else(|| {
+        println!(
+            "RUST_LOG not set, using default filter level: {}",
+            config.filter_level
+        );
+        config.filter_level.clone()
+    });
 
     EnvFilter::try_new(&directive).unwrap_or_else(|_| {
         println!("Failed to parse filter directive: {directive}. Falling back to default: info");
@@ -79,7 +152,6 @@ fn create_env_filter(config: &ObservabilityConfig) -> EnvFilter {
 fn init_subscriber_with_writer<W>(
     writer: W,
     use_ansi: bool,
-    #[cfg_attr(not(feature = "opentelemetry"), allow(unused_variables))]
     guard: &mut ObservabilityGuard,
     config: &ActrixConfig,
 ) -> Result<()>
@@ -96,6 +168,12 @@ where
 
     let observability_config = config.observability_config();
 
+    // Reloadable global filter (not reloadable when RUST_LOG takes precedence)
+    let (filter, filter_handle) = reload::Layer::new(create_env_filter(observability_config));
+    if rust_log_directive().is_none() {
+        guard.filter_handle = Some(filter_handle);
+    }
+
     #[cfg(feature = "opentelemetry")]
     {
         let tracer_provider = build_tracing_provider(config)?;
@@ -107,15 +185,20 @@ where
 
             // Global filter: events are filtered first, then passed to all layers
             tracing_subscriber::registry()
-                .with(create_env_filter(observability_config))
+                .with(filter)
                 .with(fmt_layer)
+                // Events passing the global filter are also kept in the in-memory ring buffer
+                // served to Supervisor by the StreamLogs RPC (supervit::logs)
+                .with(LogBufferLayer::default())
                 .with(tracing_opentelemetry::layer().with_tracer(tracer))
                 .try_init()
                 .ok();
         } else {
             tracing_subscriber::registry()
-                .with(create_env_filter(observability_config))
+                .with(filter)
                 .with(fmt_layer)
+                // StreamLogs ring buffer (see above)
+                .with(LogBufferLayer::default())
                 .try_init()
                 .ok();
         }
@@ -124,8 +207,11 @@ where
     #[cfg(not(feature = "opentelemetry"))]
     {
         tracing_subscriber::registry()
-            .with(create_env_filter(observability_config))
+            .with(filter)
             .with(fmt_layer)
+            // Events passing the global filter are also kept in the in-memory ring buffer
+            // served to Supervisor by the StreamLogs RPC (supervit::logs)
+            .with(LogBufferLayer::default())
             .try_init()
             .ok();
     }
@@ -137,7 +223,38 @@ fn build_file_writer(
     log_config: &actrix_common::config::LogConfig,
     rotate: bool,
 ) -> Result<(NonBlocking, WorkerGuard)> {
-    if rotate {
+    if rotate && log_config.has_retention_policy() {
+        println!("日志写入模式: 文件");
+        println!("  - 路径: {}", log_config.path);
+        println!(
+            "  - 轮转: 开启（按天，单文件上限 {}）",
+            if log_config.max_file_size_mb > 0 {
+                format!("{} MB", log_config.max_file_size_mb)
+            } else {
+                "不限".to_string()
+            }
+        );
+        println!(
+            "  - 保留: 最多 {} 个文件，{} 天，压缩: {}",
+            if log_config.max_files > 0 {
+                log_config.max_files.to_string()
+            } else {
+                "不限".to_string()
+            },
+            if log_config.max_age_days > 0 {
+                log_config.max_age_days.to_string()
+            } else {
+                "不限".to_string()
+            },
+            if log_config.compress { "gzip" } else { "否" }
+        );
+        let writer = rotation::RotatingFileWriter::new(
+            &log_config.path,
+            "actrix.log",
+            rotation::RotationPolicy::from_config(log_config),
+        )?;
+        Ok(tracing_appender::non_blocking(writer))
+    } else if rotate {
         println!("日志写入模式: 文件");
         println!("  - 路径: {}", log_config.path);
         println!(
@@ -185,17 +302,8 @@ fn build_tracing_provider(config: &ActrixConfig) -> Result<Option<SdkTracerProvi
         .build()
         .map_err(|e| Error::custom(format!("Failed to build OTLP exporter: {e}")))?;
 
-    let resource = Resource::builder()
-        .with_service_name(tracing_cfg.service_name().to_string())
-        .with_attributes([
-            KeyValue::new("service.instance.id", config.name.clone()),
-            KeyValue::new("service.environment", config.env.clone()),
-            KeyValue::new("service.location", config.location_tag.clone()),
-        ])
-        .build();
-
     let tracer_provider = SdkTracerProvider::builder()
-        .with_resource(resource)
+        .with_resource(build_resource(config))
         .with_batch_exporter(exporter)
         .build();
 
@@ -206,3 +314,60 @@ fn build_tracing_provider(config: &ActrixConfig) -> Result<Option<SdkTracerProvi
 
     Ok(Some(tracer_provider))
 }
+
+/// Resource attributes shared by traces and metrics
+#[cfg(feature = "opentelemetry")]
+fn build_resource(config: &ActrixConfig) -> Resource {
+    Resource::builder()
+        .with_service_name(config.tracing_config().service_name().to_string())
+        .with_attributes([
+            KeyValue::new("service.instance.id", config.name.clone()),
+            KeyValue::new("service.environment", config.env.clone()),
+            KeyValue::new("service.location", config.location_tag.clone()),
+        ])
+        .build()
+}
+
+/// Build the OTLP meter provider that periodically exports the Prometheus registry
+#[cfg(feature = "opentelemetry")]
+fn build_meter_provider(config: &ActrixConfig) -> Result<Option<SdkMeterProvider>> {
+    let otlp_cfg = &config.observability_config().metrics.otlp;
+    if !otlp_cfg.enabled {
+        return Ok(None);
+    }
+
+    if let Err(e) = otlp_cfg.validate() {
+        return Err(Error::custom(e));
+    }
+
+    // Defaults to the tracing endpoint so one collector receives both signals
+    let endpoint = otlp_cfg
+        .endpoint
+        .as_deref()
+        .unwrap_or_else(|| config.tracing_config().endpoint());
+    println!(
+        "📊 Initializing OpenTelemetry metrics export: endpoint={}, interval={}s",
+        endpoint, otlp_cfg.export_interval_secs
+    );
+
+    let exporter = opentelemetry_otlp::MetricExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| Error::custom(format!("Failed to build OTLP metric exporter: {e}")))?;
+
+    let reader = PeriodicReader::builder(exporter)
+        .with_interval(std::time::Duration::from_secs(
+            otlp_cfg.export_interval_secs,
+        ))
+        .build();
+
+    let meter_provider = SdkMeterProvider::builder()
+        .with_resource(build_resource(config))
+        .with_reader(reader)
+        .build();
+
+    println!("✅ OpenTelemetry metrics export initialized successfully");
+
+    Ok(Some(meter_provider))
+}
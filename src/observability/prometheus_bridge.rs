@@ -0,0 +1,218 @@
+//! Bridge from the Prometheus registry to OpenTelemetry metrics
+//!
+//! Every metric family in [`REGISTRY`](actrix_common::metrics::REGISTRY) is exposed as an
+//! observable instrument on the OTLP meter, so the periodic reader exports the same values
+//! that `/metrics` serves:
+//! - counters → f64 observable counters
+//! - gauges and untyped metrics → f64 observable gauges
+//! - histograms and summaries → `<name>_sum` / `<name>_count` observable counters
+//!   (bucket boundaries and quantiles are not exported)
+//!
+//! Labelled families only appear in the registry after their first label set is used,
+//! so new families are discovered periodically instead of once at startup.
+
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{AsyncInstrument, Meter};
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// How often the registry is checked for new metric families
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A gathered snapshot is shared by all callbacks of one collection cycle
+const SNAPSHOT_TTL: Duration = Duration::from_secs(1);
+
+type Families = Arc<HashMap<String, MetricFamily>>;
+
+#[derive(Default)]
+struct Snapshot {
+    taken_at: Option<Instant>,
+    families: Families,
+}
+
+/// Registry snapshot shared between the discovery task and instrument callbacks
+#[derive(Clone, Default)]
+struct SharedSnapshot(Arc<Mutex<Snapshot>>);
+
+impl SharedSnapshot {
+    fn families(&self) -> Families {
+        let mut snapshot = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if snapshot
+            .taken_at
+            .is_none_or(|taken_at| taken_at.elapsed() >= SNAPSHOT_TTL)
+        {
+            snapshot.families = Arc::new(
+                actrix_common::metrics::REGISTRY
+                    .gather()
+                    .into_iter()
+                    .map(|family| (family.get_name().to_string(), family))
+                    .collect(),
+            );
+            snapshot.taken_at = Some(Instant::now());
+        }
+        snapshot.families.clone()
+    }
+}
+
+/// Which value of a Prometheus metric an instrument reports
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Value,
+    Sum,
+    Count,
+}
+
+fn field_value(family_type: MetricType, metric: &Metric, field: Field) -> f64 {
+    match (family_type, field) {
+        (MetricType::COUNTER, _) => metric.get_counter().get_value(),
+        (MetricType::GAUGE, _) => metric.get_gauge().get_value(),
+        (MetricType::UNTYPED, _) => metric.get_untyped().get_value(),
+        (MetricType::HISTOGRAM, Field::Count) => metric.get_histogram().get_sample_count() as f64,
+        (MetricType::HISTOGRAM, _) => metric.get_histogram().get_sample_sum(),
+        (MetricType::SUMMARY, Field::Count) => metric.get_summary().get_sample_count() as f64,
+        (MetricType::SUMMARY, _) => metric.get_summary().get_sample_sum(),
+    }
+}
+
+fn attributes(metric: &Metric) -> Vec<KeyValue> {
+    metric
+        .get_label()
+        .iter()
+        .map(|label| KeyValue::new(label.get_name().to_string(), label.get_value().to_string()))
+        .collect()
+}
+
+fn observe(family: &MetricFamily, field: Field, observer: &dyn AsyncInstrument<f64>) {
+    for metric in family.get_metric() {
+        observer.observe(
+            field_value(family.get_field_type(), metric, field),
+            &attributes(metric),
+        );
+    }
+}
+
+/// Registers OpenTelemetry instruments for Prometheus metric families
+pub struct PrometheusBridge {
+    meter: Meter,
+    snapshot: SharedSnapshot,
+    known: HashSet<String>,
+}
+
+impl PrometheusBridge {
+    pub fn new(meter: Meter) -> Self {
+        Self {
+            meter,
+            snapshot: SharedSnapshot::default(),
+            known: HashSet::new(),
+        }
+    }
+
+    /// Discover new families in the background until the task is aborted
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DISCOVERY_INTERVAL);
+            loop {
+                interval.tick().await;
+                self.discover();
+            }
+        })
+    }
+
+    /// Register instruments for families that have not been seen before
+    fn discover(&mut self) {
+        let families = self.snapshot.families();
+        for (name, family) in families.iter() {
+            if !self.known.insert(name.clone()) {
+                continue;
+            }
+            let help = family.get_help().to_string();
+            match family.get_field_type() {
+                MetricType::COUNTER => self.register(name, name.clone(), help, Field::Value, true),
+                MetricType::GAUGE | MetricType::UNTYPED => {
+                    self.register(name, name.clone(), help, Field::Value, false)
+                }
+                MetricType::HISTOGRAM | MetricType::SUMMARY => {
+                    self.register(name, format!("{name}_sum"), help.clone(), Field::Sum, true);
+                    self.register(name, format!("{name}_count"), help, Field::Count, true);
+                }
+            }
+        }
+    }
+
+    fn register(
+        &self,
+        family: &str,
+        instrument: String,
+        description: String,
+        field: Field,
+        monotonic: bool,
+    ) {
+        let snapshot = self.snapshot.clone();
+        let family = family.to_string();
+        let callback = move |observer: &dyn AsyncInstrument<f64>| {
+            if let Some(family) = snapshot.families().get(&family) {
+                observe(family, field, observer);
+            }
+        };
+
+        // Callbacks stay registered with the meter provider after the handles are dropped
+        if monotonic {
+            self.meter
+                .f64_observable_counter(instrument)
+                .with_description(description)
+                .with_callback(callback)
+                .build();
+        } else {
+            self.meter
+                .f64_observable_gauge(instrument)
+                .with_description(description)
+                .with_callback(callback)
+                .build();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{Histogram, HistogramOpts, IntCounterVec, Opts};
+
+    fn family(collector: impl prometheus::core::Collector) -> MetricFamily {
+        collector.collect().remove(0)
+    }
+
+    #[test]
+    fn test_field_values() {
+        let counter =
+            IntCounterVec::new(Opts::new("bridge_test_total", "test"), &["kind"]).unwrap();
+        counter.with_label_values(&["a"]).inc_by(3);
+        let counter = family(counter);
+        let metric = &counter.get_metric()[0];
+        assert_eq!(
+            field_value(counter.get_field_type(), metric, Field::Value),
+            3.0
+        );
+        assert_eq!(attributes(metric), vec![KeyValue::new("kind", "a")]);
+
+        let histogram =
+            Histogram::with_opts(HistogramOpts::new("bridge_test_seconds", "test")).unwrap();
+        histogram.observe(0.5);
+        histogram.observe(1.5);
+        let histogram = family(histogram);
+        let metric = &histogram.get_metric()[0];
+        assert_eq!(
+            field_value(histogram.get_field_type(), metric, Field::Sum),
+            2.0
+        );
+        assert_eq!(
+            field_value(histogram.get_field_type(), metric, Field::Count),
+            2.0
+        );
+    }
+}
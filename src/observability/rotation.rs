@@ -0,0 +1,260 @@
+//! Size- and date-based log file rotation with retention
+//!
+//! The active log is always `<dir>/<file_name>`. It is archived as
+//! `<file_name>.<YYYYmmdd-HHMMSS>` when the UTC date changes or the file would exceed
+//! `max_file_size`, optionally gzip-compressed to `<file_name>.<timestamp>.gz`.
+//! After each rotation, archives beyond `max_files` or older than `max_age` are deleted.
+//! Compression and pruning run on a separate thread so log writes are not held up.
+
+use actrix_common::config::LogConfig;
+use chrono::{NaiveDate, Utc};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// When archived log files are created and how long they are kept
+#[derive(Debug, Clone, Default)]
+pub struct RotationPolicy {
+    /// Rotate once the active file reaches this many bytes
+    pub max_file_size: Option<u64>,
+    /// Number of archives to keep
+    pub max_files: Option<usize>,
+    /// Delete archives last modified longer ago than this
+    pub max_age: Option<Duration>,
+    /// Gzip archives after rotation
+    pub compress: bool,
+}
+
+impl RotationPolicy {
+    pub fn from_config(config: &LogConfig) -> Self {
+        Self {
+            max_file_size: (config.max_file_size_mb > 0)
+                .then(|| config.max_file_size_mb * 1024 * 1024),
+            max_files: (config.max_files > 0).then_some(config.max_files),
+            max_age: (config.max_age_days > 0)
+                .then(|| Duration::from_secs(config.max_age_days * 86400)),
+            compress: config.compress,
+        }
+    }
+}
+
+/// Log writer that rotates the active file daily and by size
+pub struct RotatingFileWriter {
+    dir: PathBuf,
+    file_name: String,
+    policy: RotationPolicy,
+    file: File,
+    size: u64,
+    opened_on: NaiveDate,
+}
+
+impl RotatingFileWriter {
+    pub fn new(
+        dir: impl Into<PathBuf>,
+        file_name: impl Into<String>,
+        policy: RotationPolicy,
+    ) -> io::Result<Self> {
+        let dir = dir.into();
+        let file_name = file_name.into();
+        fs::create_dir_all(&dir)?;
+        let file = open_append(&dir.join(&file_name))?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            file_name,
+            policy,
+            file,
+            size,
+            opened_on: Utc::now().date_naive(),
+        })
+    }
+
+    fn should_rotate(&self, incoming: usize) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        if Utc::now().date_naive() != self.opened_on {
+            return true;
+        }
+        self.policy
+            .max_file_size
+            .is_some_and(|max| self.size + incoming as u64 > max)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let active = self.dir.join(&self.file_name);
+        let archive = self.archive_path();
+        fs::rename(&active, &archive)?;
+        self.file = open_append(&active)?;
+        self.size = 0;
+        self.opened_on = Utc::now().date_naive();
+
+        let dir = self.dir.clone();
+        let prefix = format!("{}.", self.file_name);
+        let policy = self.policy.clone();
+        std::thread::spawn(move || {
+            if policy.compress
+                && let Err(e) = compress(&archive)
+            {
+                eprintln!("Failed to compress log file {}: {e}", archive.display());
+            }
+            if let Err(e) = prune(&dir, &prefix, &policy) {
+                eprintln!("Failed to prune log files in {}: {e}", dir.display());
+            }
+        });
+        Ok(())
+    }
+
+    /// Timestamped archive path that does not collide with an existing archive
+    fn archive_path(&self) -> PathBuf {
+        let base = format!("{}.{}", self.file_name, Utc::now().format("%Y%m%d-%H%M%S"));
+        let mut candidate = base.clone();
+        let mut n = 1;
+        while self.dir.join(&candidate).exists()
+            || self.dir.join(format!("{candidate}.gz")).exists()
+        {
+            candidate = format!("{base}.{n}");
+            n += 1;
+        }
+        self.dir.join(candidate)
+    }
+}
+
+impl Write for RotatingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_rotate(buf.len())
+            && let Err(e) = self.rotate()
+        {
+            // Keep logging into the current file rather than dropping lines
+            eprintln!("Failed to rotate log file: {e}");
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Replace `path` with `path.gz`
+fn compress(path: &Path) -> io::Result<()> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&gz_path)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(path)
+}
+
+/// Delete archives (files named `<prefix>*`) beyond the count and age limits
+fn prune(dir: &Path, prefix: &str, policy: &RotationPolicy) -> io::Result<()> {
+    if policy.max_files.is_none() && policy.max_age.is_none() {
+        return Ok(());
+    }
+
+    let mut archives: Vec<(SystemTime, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            Some((metadata.modified().ok()?, entry.path()))
+        })
+        .collect();
+    // Newest first
+    archives.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let now = SystemTime::now();
+    for (index, (modified, path)) in archives.iter().enumerate() {
+        let over_count = policy.max_files.is_some_and(|max| index >= max);
+        let too_old = policy
+            .max_age
+            .is_some_and(|max_age| now.duration_since(*modified).is_ok_and(|age| age > max_age));
+        if over_count || too_old {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archives(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("actrix.log."))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_rotates_by_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = RotationPolicy {
+            max_file_size: Some(64),
+            ..Default::default()
+        };
+        let mut writer = RotatingFileWriter::new(dir.path(), "actrix.log", policy).unwrap();
+
+        writer.write_all(&[b'a'; 48]).unwrap();
+        assert!(archives(dir.path()).is_empty());
+        writer.write_all(&[b'b'; 48]).unwrap();
+        writer.write_all(&[b'c'; 48]).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(archives(dir.path()).len(), 2);
+        let active = fs::read(dir.path().join("actrix.log")).unwrap();
+        assert_eq!(active, vec![b'c'; 48]);
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["actrix.log.1", "actrix.log.2", "actrix.log.3", "other.log"] {
+            fs::write(dir.path().join(name), name).unwrap();
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        let policy = RotationPolicy {
+            max_files: Some(2),
+            ..Default::default()
+        };
+        prune(dir.path(), "actrix.log.", &policy).unwrap();
+        assert_eq!(archives(dir.path()), vec!["actrix.log.2", "actrix.log.3"]);
+        assert!(dir.path().join("other.log").exists());
+    }
+
+    #[test]
+    fn test_compress_replaces_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("actrix.log.20260101-000000");
+        fs::write(&archive, "line\n".repeat(100)).unwrap();
+
+        compress(&archive).unwrap();
+        assert!(!archive.exists());
+
+        let mut decoded = String::new();
+        let file = File::open(dir.path().join("actrix.log.20260101-000000.gz")).unwrap();
+        io::Read::read_to_string(&mut flate2::read::GzDecoder::new(file), &mut decoded).unwrap();
+        assert_eq!(decoded, "line\n".repeat(100));
+    }
+}
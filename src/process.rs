@@ -154,6 +154,33 @@ impl ProcessManager {
     }
 }
 
+impl ProcessManager {
+    /// Re-execute `exe` with the current command line arguments
+    ///
+    /// On Unix the process image is replaced in place (same PID); elsewhere a new
+    /// process is spawned and the current one exits. Only returns on failure.
+    pub fn restart(exe: &Path) -> Result<()> {
+        let args: Vec<_> = std::env::args_os().skip(1).collect();
+        info!("Restarting: {:?} {:?}", exe, args);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            let err = std::process::Command::new(exe).args(&args).exec();
+            Err(err).with_context(|| format!("Failed to exec {exe:?}"))
+        }
+
+        #[cfg(not(unix))]
+        {
+            std::process::Command::new(exe)
+                .args(&args)
+                .spawn()
+                .with_context(|| format!("Failed to spawn {exe:?}"))?;
+            std::process::exit(0);
+        }
+    }
+}
+
 /// Guard to ensure PID file is removed on drop
 pub struct PidFileGuard {
     path: Option<PathBuf>,
@@ -0,0 +1,137 @@
+//! 节点能力（构建 feature 矩阵与运行时子系统）
+//!
+//! 通过 Supervisor 状态上报（Report）与 GetNodeInfo 返回，供 Supervisor 只向
+//! 具备相应能力的节点调度任务。
+//!
+//! - `build_features`: 编译进二进制的可选 cargo feature（与根 crate 的 feature 名一致）
+//! - `active_subsystems`: 当前配置下实际启用的服务与可选子系统，
+//!   服务以服务名表示（如 `"turn"`），子系统以点分名称表示（如 `"ks.storage.etcd"`）
+
+use actrix_common::config::{ActrixConfig, nonce::NonceBackend};
+use ks::KekProviderConfig;
+use ks::storage::StorageBackend;
+use supervit::NodeCapabilities;
+
+/// 根据编译 feature 与配置生成节点能力
+pub fn node_capabilities(config: &ActrixConfig) -> NodeCapabilities {
+    NodeCapabilities {
+        build_features: build_features(),
+        active_subsystems: active_subsystems(config),
+    }
+}
+
+/// 编译进二进制的可选 feature
+pub fn build_features() -> Vec<String> {
+    [
+        ("opentelemetry", cfg!(feature = "opentelemetry")),
+        ("nonce-redis", cfg!(feature = "nonce-redis")),
+        ("kek-pkcs11", cfg!(feature = "kek-pkcs11")),
+        ("ks-postgres", cfg!(feature = "ks-postgres")),
+        ("ks-etcd", cfg!(feature = "ks-etcd")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+/// 当前配置下启用的服务与可选子系统
+pub fn active_subsystems(config: &ActrixConfig) -> Vec<String> {
+    let mut subsystems = Vec::new();
+
+    let services = [
+        ("signaling", config.is_signaling_enabled()),
+        ("stun", config.is_stun_enabled()),
+        ("turn", config.is_turn_enabled()),
+        ("ais", config.is_ais_enabled()),
+        ("ks", config.is_ks_enabled()),
+    ];
+    subsystems.extend(
+        services
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| name.to_string()),
+    );
+
+    if config.is_turn_enabled() && config.turn.advertised_ipv6.is_some() {
+        subsystems.push("turn.ipv6".to_string());
+    }
+
+    if config.is_ks_enabled()
+        && let Some(ks) = &config.services.ks
+    {
+        let backend = match ks.storage.backend {
+            StorageBackend::Sqlite => "sqlite",
+            StorageBackend::Postgres => "postgres",
+            StorageBackend::Etcd => "etcd",
+        };
+        subsystems.push(format!("ks.storage.{backend}"));
+
+        if let Some(KekProviderConfig::Pkcs11(_)) = &ks.kek_provider {
+            subsystems.push("ks.kek.pkcs11".to_string());
+        }
+    }
+
+    let nonce_backend = match config.nonce_storage_config().backend {
+        NonceBackend::Memory => "memory",
+        NonceBackend::Sqlite => "sqlite",
+        NonceBackend::Redis => "redis",
+    };
+    subsystems.push(format!("nonce.{nonce_backend}"));
+
+    if config.storage.is_memory() {
+        subsystems.push("storage.memory".to_string());
+    }
+
+    if config.tracing.enable {
+        subsystems.push("tracing".to_string());
+    }
+
+    subsystems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::config::{ENABLE_KS, ENABLE_SIGNALING, ENABLE_TURN};
+
+    #[test]
+    fn test_active_subsystems_follow_config() {
+        let mut config = ActrixConfig::default();
+        config.enable = ENABLE_SIGNALING | ENABLE_TURN | ENABLE_KS;
+        config.turn.advertised_ipv6 = Some("2001:db8::1".to_string());
+        config.services.ks = Some(Default::default());
+
+        let subsystems = active_subsystems(&config);
+        assert_eq!(
+            subsystems,
+            vec![
+                "signaling",
+                "turn",
+                "ks",
+                "turn.ipv6",
+                "ks.storage.sqlite",
+                "nonce.sqlite",
+            ]
+        );
+
+        config.enable = ENABLE_SIGNALING;
+        assert_eq!(
+            active_subsystems(&config),
+            vec!["signaling", "nonce.sqlite"]
+        );
+    }
+
+    #[test]
+    fn test_build_features_match_cfg() {
+        let features = build_features();
+        assert_eq!(
+            features.contains(&"opentelemetry".to_string()),
+            cfg!(feature = "opentelemetry")
+        );
+        assert_eq!(
+            features.contains(&"ks-etcd".to_string()),
+            cfg!(feature = "ks-etcd")
+        );
+    }
+}
@@ -5,7 +5,8 @@
 
 use super::{AisService, KsHttpService, SignalingService, StunService, TurnService};
 use super::{HttpRouterService, IceService};
-use actrix_common::ServiceInfo;
+use actrix_common::config::ActrixConfig;
+use actrix_common::{ServiceInfo, ServiceType};
 use axum::Router;
 use url::Url;
 
@@ -46,6 +47,21 @@ impl ServiceContainer {
         Self::Turn(service)
     }
 
+    /// 按服务类型创建服务容器（运行时启用服务时使用）
+    pub fn from_config(service_type: &ServiceType, config: &ActrixConfig) -> Self {
+        match service_type {
+            ServiceType::Signaling => Self::signaling(SignalingService::new(config.clone())),
+            #[cfg(feature = "dev-mock")]
+            ServiceType::Ais if config.is_dev_mock_enabled() && config.services.ais.is_none() => {
+                Self::ais(AisService::mock(config.clone()))
+            }
+            ServiceType::Ais => Self::ais(AisService::new(config.clone())),
+            ServiceType::Ks => Self::ks(KsHttpService::new(config.clone())),
+            ServiceType::Stun => Self::stun(StunService::new(config.clone())),
+            ServiceType::Turn => Self::turn(TurnService::new(config.clone())),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn service_type(&self) -> &'static str {
         match self {
@@ -107,4 +123,24 @@ impl ServiceContainer {
             _ => None,
         }
     }
+
+    /// 获取 ICE 服务（仅适用于 STUN / TURN）
+    pub fn as_ice_mut(&mut self) -> Option<&mut dyn IceService> {
+        match self {
+            ServiceContainer::Stun(service) => Some(service),
+            ServiceContainer::Turn(service) => Some(service),
+            _ => None,
+        }
+    }
+
+    /// 服务停止回调
+    pub async fn on_stop(&mut self) -> Result<(), anyhow::Error> {
+        match self {
+            ServiceContainer::Signaling(service) => service.on_stop().await,
+            ServiceContainer::Ais(service) => service.on_stop().await,
+            ServiceContainer::Ks(service) => service.on_stop().await,
+            ServiceContainer::Stun(service) => service.stop().await,
+            ServiceContainer::Turn(service) => service.stop().await,
+        }
+    }
 }
@@ -0,0 +1,605 @@
+//! 运行时服务启停
+//!
+//! 除启动时的 `enable` 位掩码外，Signaling、AIS、KS、STUN、TURN 可通过 Supervisor 的
+//! `SetServiceEnabled` 指令或 `POST /admin/services/{service}/{enable|disable}` 在运行时单独启停：
+//!
+//! - HTTP 路由服务（Signaling / AIS / KS）的路由前缀始终挂载一个 [`RouterSlot`]，
+//!   HTTP 服务器保持监听；启用时重新构建路由器并挂载，停用时卸载，该前缀返回 503。
+//!   Signaling 停用时会断开所有 WebSocket 连接
+//! - ICE 服务（STUN / TURN）各自持有停止通道，停用时关闭监听套接字，启用时重新绑定端口。
+//!   TURN 内置 STUN 支持且与独立 STUN 共用 ICE 端口，启用 TURN 时会先停止独立 STUN
+//! - KS gRPC 监听器同样持有停止通道，随 KS 服务启停：停用 KS 时关闭 gRPC 端口，
+//!   启用时重新绑定
+//!
+//! 运行时变更不写回配置文件，重启后仍以配置为准。启用的服务需要的配置段（如 `[services.ks]`）
+//! 必须已在配置文件中；启动时没有任何 HTTP 路由服务的节点不会监听 HTTP 端口，无法在运行时启用
+//! HTTP 路由服务。
+
+use crate::service::container::ServiceContainer;
+use crate::service::grpc::KsGrpcService;
+use actrix_common::{ServiceCollector, ServiceInfo, ServiceType, config::ActrixConfig};
+use anyhow::{Result, bail};
+use axum::Router;
+use axum::extract::Request;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{MethodRouter, any};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock as StdRwLock};
+use tokio::sync::{Mutex, broadcast, oneshot};
+use tokio::task::JoinHandle;
+use tower::ServiceExt;
+use tracing::{error, info, warn};
+use url::Url;
+
+/// 可在运行时启停的服务（按展示顺序）
+pub const SERVICE_TYPES: [ServiceType; 5] = [
+    ServiceType::Signaling,
+    ServiceType::Ais,
+    ServiceType::Ks,
+    ServiceType::Stun,
+    ServiceType::Turn,
+];
+
+/// HTTP 路由服务
+const HTTP_SERVICE_TYPES: [ServiceType; 3] =
+    [ServiceType::Signaling, ServiceType::Ais, ServiceType::Ks];
+
+/// TURN 内置 STUN 在服务收集器中的名称
+const TURN_STUN_INFO_NAME: &str = "STUN Server";
+
+/// 挂载在固定路由前缀上、可在运行时替换的路由器
+///
+/// HTTP 服务器启动后路由树不可修改，因此由插槽在每个请求时转发到当前挂载的路由器，
+/// 未挂载时返回 503
+#[derive(Debug, Clone, Default)]
+pub struct RouterSlot {
+    router: Arc<StdRwLock<Option<Router>>>,
+}
+
+impl RouterSlot {
+    /// 挂载路由器，替换已挂载的路由器
+    pub fn mount(&self, router: Router) {
+        *self
+            .router
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(router);
+    }
+
+    /// 卸载路由器
+    pub fn unmount(&self) {
+        *self
+            .router
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+
+    /// 是否已挂载路由器
+    pub fn is_mounted(&self) -> bool {
+        self.router
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_some()
+    }
+
+    /// 转发到插槽的服务，用于 [`Router::nest_service`]
+    pub fn service(&self) -> MethodRouter {
+        let slot = self.clone();
+        any(move |request: Request| {
+            let slot = slot.clone();
+            async move { slot.dispatch(request).await }
+        })
+    }
+
+    async fn dispatch(&self, request: Request) -> Response {
+        let router = self
+            .router
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        match router {
+            Some(router) => {
+                let Ok(response) = router.oneshot(request).await;
+                response
+            }
+            None => (StatusCode::SERVICE_UNAVAILABLE, "Service disabled").into_response(),
+        }
+    }
+}
+
+/// 运行中的服务
+#[derive(Debug)]
+enum RunningService {
+    /// HTTP 路由服务，路由器挂载在对应的 [`RouterSlot`] 上
+    Http(ServiceContainer),
+    /// ICE 服务
+    Ice {
+        /// 服务收集器中登记的名称（TURN 同时登记内置 STUN）
+        names: Vec<String>,
+        /// 通知服务停止
+        stop_tx: broadcast::Sender<()>,
+        /// 服务任务退出（监听套接字已关闭）后返回
+        done_rx: oneshot::Receiver<()>,
+    },
+}
+
+/// 运行中的 KS gRPC 监听器
+#[derive(Debug)]
+struct GrpcListener {
+    /// 通知监听器停止
+    stop_tx: broadcast::Sender<()>,
+    /// 由控制器主动停止（区别于监听器自身出错退出）
+    stopping: Arc<AtomicBool>,
+    /// 监听任务退出（端口已关闭）后返回
+    done_rx: oneshot::Receiver<()>,
+}
+
+#[derive(Debug)]
+struct ControllerInner {
+    config: ActrixConfig,
+    shutdown_tx: broadcast::Sender<()>,
+    service_collector: ServiceCollector,
+    /// HTTP 路由服务的路由前缀与插槽
+    slots: HashMap<ServiceType, (String, RouterSlot)>,
+    /// HTTP 服务器的公开地址（HTTP 服务器未启动时为 None）
+    public_url: StdRwLock<Option<Url>>,
+    /// 运行中的服务，同时用于串行化启停操作
+    running: Mutex<HashMap<ServiceType, RunningService>>,
+    /// 运行中的 KS gRPC 监听器（仅在持有 `running` 锁时访问）
+    ks_grpc: Mutex<Option<GrpcListener>>,
+}
+
+/// 运行时服务控制句柄（可克隆，由 Supervisord 与管理端点共享）
+#[derive(Debug, Clone)]
+pub struct ServiceController {
+    inner: Arc<ControllerInner>,
+}
+
+impl ServiceController {
+    pub fn new(
+        config: ActrixConfig,
+        shutdown_tx: broadcast::Sender<()>,
+        service_collector: ServiceCollector,
+    ) -> Self {
+        let slots = HTTP_SERVICE_TYPES
+            .iter()
+            .map(|service_type| {
+                let service = ServiceContainer::from_config(service_type, &config);
+                let prefix = service.route_prefix().unwrap_or_default().to_string();
+                (service_type.clone(), (prefix, RouterSlot::default()))
+            })
+            .collect();
+
+        Self {
+            inner: Arc::new(ControllerInner {
+                config,
+                shutdown_tx,
+                service_collector,
+                slots,
+                public_url: StdRwLock::new(None),
+                running: Mutex::new(HashMap::new()),
+                ks_grpc: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// 在 HTTP 服务器的路由器上挂载所有 HTTP 路由服务的插槽
+    pub fn mount_slots(&self, mut app: Router, public_url: Url) -> Router {
+        *self
+            .inner
+            .public_url
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(public_url);
+        for service_type in &HTTP_SERVICE_TYPES {
+            if let Some((prefix, slot)) = self.inner.slots.get(service_type) {
+                app = app.nest_service(prefix, slot.service());
+            }
+        }
+        app
+    }
+
+    /// 启动时启动服务，ICE 服务返回其任务句柄
+    ///
+    /// 启动后 ICE 服务出错会广播全局关闭，与运行时启用的服务不同
+    pub async fn start(&self, service: ServiceContainer) -> Result<Option<JoinHandle<()>>> {
+        let mut running = self.inner.running.lock().await;
+        if service.is_ice() {
+            let handle = self.start_ice(&mut running, service, true).await?;
+            Ok(Some(handle))
+        } else {
+            self.start_http(&mut running, service).await?;
+            Ok(None)
+        }
+    }
+
+    /// 启动时启动 KS gRPC 监听器，返回其任务句柄
+    ///
+    /// 监听器出错退出会广播全局关闭；运行时停用 KS 时关闭监听器
+    pub async fn start_ks_grpc(&self) -> Result<JoinHandle<()>> {
+        let _running = self.inner.running.lock().await;
+        self.spawn_ks_grpc(true).await
+    }
+
+    /// 在运行时启用或停用服务，已处于目标状态时直接返回
+    pub async fn set_enabled(&self, service_type: ServiceType, enabled: bool) -> Result<()> {
+        let mut running = self.inner.running.lock().await;
+        if enabled {
+            self.enable(&mut running, service_type).await
+        } else {
+            self.disable(&mut running, service_type).await
+        }
+    }
+
+    /// 服务当前是否运行（TURN 运行时 STUN 视为运行）
+    pub async fn is_running(&self, service_type: &ServiceType) -> bool {
+        is_serving(&*self.inner.running.lock().await, service_type)
+    }
+
+    /// 各服务当前是否运行
+    pub async fn statuses(&self) -> Vec<(ServiceType, bool)> {
+        let running = self.inner.running.lock().await;
+        SERVICE_TYPES
+            .iter()
+            .map(|service_type| (service_type.clone(), is_serving(&running, service_type)))
+            .collect()
+    }
+
+    /// 停止所有服务
+    pub async fn stop_all(&self) {
+        let mut running = self.inner.running.lock().await;
+        for service_type in &SERVICE_TYPES {
+            if let Err(e) = self.stop(&mut running, service_type).await {
+                warn!("Failed to stop {} service: {:?}", service_type, e);
+            }
+        }
+    }
+
+    async fn enable(
+        &self,
+        running: &mut HashMap<ServiceType, RunningService>,
+        service_type: ServiceType,
+    ) -> Result<()> {
+        if running.contains_key(&service_type) {
+            info!("{} 服务已在运行", service_type);
+            return Ok(());
+        }
+        match service_type {
+            ServiceType::Stun if running.contains_key(&ServiceType::Turn) => {
+                info!("STUN 由 TURN 服务提供，无需单独启用");
+                return Ok(());
+            }
+            ServiceType::Turn => {
+                // 独立 STUN 与 TURN 共用 ICE 端口
+                self.stop(running, &ServiceType::Stun).await?;
+            }
+            _ => {}
+        }
+
+        let service = ServiceContainer::from_config(&service_type, &self.inner.config);
+        if service.is_ice() {
+            self.start_ice(running, service, false).await?;
+        } else {
+            self.start_http(running, service).await?;
+        }
+        if service_type == ServiceType::Ks
+            && let Err(e) = self.spawn_ks_grpc(false).await
+        {
+            self.stop(running, &service_type).await?;
+            return Err(e);
+        }
+        info!("✅ 已在运行时启用 {} 服务", service_type);
+        Ok(())
+    }
+
+    async fn disable(
+        &self,
+        running: &mut HashMap<ServiceType, RunningService>,
+        service_type: ServiceType,
+    ) -> Result<()> {
+        if service_type == ServiceType::Stun && running.contains_key(&ServiceType::Turn) {
+            bail!("STUN is served by the TURN service, disable TURN instead");
+        }
+        if self.stop(running, &service_type).await? {
+            info!("🛑 已在运行时停用 {} 服务", service_type);
+        } else {
+            info!("{} 服务未运行", service_type);
+        }
+        Ok(())
+    }
+
+    /// 挂载 HTTP 路由服务
+    async fn start_http(
+        &self,
+        running: &mut HashMap<ServiceType, RunningService>,
+        mut service: ServiceContainer,
+    ) -> Result<()> {
+        let service_type = service.info().service_type.clone();
+        let service_name = service.info().name.clone();
+        let Some((prefix, slot)) = self.inner.slots.get(&service_type) else {
+            bail!("Invalid service type for HTTP router service: {service_name}");
+        };
+        let Some(public_url) = self.public_url() else {
+            bail!("HTTP server is not running, cannot mount service '{service_name}'");
+        };
+
+        let router = match service.build_router().await {
+            Some(result) => result.map_err(|e| {
+                anyhow::anyhow!("Failed to build router for service '{service_name}': {e:?}")
+            })?,
+            None => bail!("Invalid service type for HTTP router service: {service_name}"),
+        };
+        info!("Adding route '{}' for service '{}'", prefix, service_name);
+        slot.mount(router);
+
+        if let Some(Err(e)) = service.on_start(public_url).await {
+            error!("Failed to start service '{}': {:?}", service_name, e);
+        }
+        self.inner
+            .service_collector
+            .insert(service_name, service.info().clone())
+            .await;
+        running.insert(service_type, RunningService::Http(service));
+        Ok(())
+    }
+
+    /// 启动 ICE 服务，`exit_on_error` 为 true 时服务出错会广播全局关闭
+    async fn start_ice(
+        &self,
+        running: &mut HashMap<ServiceType, RunningService>,
+        mut service: ServiceContainer,
+        exit_on_error: bool,
+    ) -> Result<JoinHandle<()>> {
+        let service_type = service.info().service_type.clone();
+        let service_name = service.info().name.clone();
+        if !service.is_ice() {
+            bail!("Invalid service type for ICE service: {service_name}");
+        }
+
+        let stop_tx = self.stop_channel();
+        let stop_rx = stop_tx.subscribe();
+
+        let (info_tx, info_rx) = oneshot::channel::<ServiceInfo>();
+        let (done_tx, done_rx) = oneshot::channel::<()>();
+        let shutdown_tx = self.inner.shutdown_tx.clone();
+        let task_name = service_name.clone();
+        let handle = tokio::spawn(async move {
+            let _done = done_tx;
+            if let Some(ice) = service.as_ice_mut()
+                && let Err(e) = ice.start(stop_rx, info_tx).await
+            {
+                error!("Failed to start {}: {:?}", task_name, e);
+                if exit_on_error {
+                    let _ = shutdown_tx.send(());
+                }
+            }
+        });
+
+        let info = info_rx
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to receive {service_name} info: {e}"))?;
+
+        let mut names = vec![info.name.clone()];
+        if service_type == ServiceType::Turn {
+            // TURN 内置 STUN 支持，同时登记 STUN 服务
+            let mut stun_info = ServiceInfo::new(
+                TURN_STUN_INFO_NAME,
+                ServiceType::Stun,
+                None,
+                &self.inner.config,
+            );
+            stun_info.set_running(
+                Url::parse(&self.inner.config.stun_url())
+                    .map_err(|e| anyhow::anyhow!("Failed to parse STUN URL: {e}"))?,
+            );
+            names.push(stun_info.name.clone());
+            self.inner
+                .service_collector
+                .insert(stun_info.name.clone(), stun_info)
+                .await;
+        }
+        self.inner
+            .service_collector
+            .insert(info.name.clone(), info)
+            .await;
+
+        running.insert(
+            service_type,
+            RunningService::Ice {
+                names,
+                stop_tx,
+                done_rx,
+            },
+        );
+        Ok(handle)
+    }
+
+    /// 服务自身的停止通道，全局关闭时同样触发
+    fn stop_channel(&self) -> broadcast::Sender<()> {
+        let (stop_tx, _) = broadcast::channel::<()>(1);
+        let mut global_rx = self.inner.shutdown_tx.subscribe();
+        let mut own_rx = stop_tx.subscribe();
+        let forward_tx = stop_tx.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = global_rx.recv() => {
+                    let _ = forward_tx.send(());
+                }
+                _ = own_rx.recv() => {}
+            }
+        });
+        stop_tx
+    }
+
+    /// 绑定 KS gRPC 端口并启动监听器，`exit_on_error` 为 true 时监听器出错会广播全局关闭
+    ///
+    /// 调用方需持有 `running` 锁
+    async fn spawn_ks_grpc(&self, exit_on_error: bool) -> Result<JoinHandle<()>> {
+        let mut ks_grpc = self.inner.ks_grpc.lock().await;
+        if ks_grpc.is_some() {
+            bail!("KS gRPC listener is already running");
+        }
+
+        let addr = self
+            .inner
+            .config
+            .ks_grpc_bind()
+            .socket_addr()
+            .map_err(|e| anyhow::anyhow!("Failed to parse KS gRPC address: {e}"))?;
+        let stop_tx = self.stop_channel();
+        let grpc = KsGrpcService::new(self.inner.config.clone())
+            .with_service_collector(self.inner.service_collector.clone())
+            .start(addr, stop_tx.clone())
+            .await
+            .inspect_err(|_| {
+                let _ = stop_tx.send(());
+            })?;
+
+        let stopping = Arc::new(AtomicBool::new(false));
+        let (done_tx, done_rx) = oneshot::channel::<()>();
+        let shutdown_tx = self.inner.shutdown_tx.clone();
+        let stopped = stopping.clone();
+        let handle = tokio::spawn(async move {
+            let _done = done_tx;
+            let _ = grpc.await;
+            if exit_on_error && !stopped.load(Ordering::SeqCst) {
+                let _ = shutdown_tx.send(());
+            }
+        });
+
+        *ks_grpc = Some(GrpcListener {
+            stop_tx,
+            stopping,
+            done_rx,
+        });
+        Ok(handle)
+    }
+
+    /// 关闭 KS gRPC 监听器，返回监听器此前是否在运行
+    async fn stop_ks_grpc(&self) -> bool {
+        let Some(listener) = self.inner.ks_grpc.lock().await.take() else {
+            return false;
+        };
+        listener.stopping.store(true, Ordering::SeqCst);
+        let _ = listener.stop_tx.send(());
+        let _ = listener.done_rx.await;
+        info!("KS gRPC 监听器已关闭");
+        true
+    }
+
+    /// 停止服务，返回服务此前是否在运行
+    async fn stop(
+        &self,
+        running: &mut HashMap<ServiceType, RunningService>,
+        service_type: &ServiceType,
+    ) -> Result<bool> {
+        let grpc_stopped = *service_type == ServiceType::Ks && self.stop_ks_grpc().await;
+        let Some(service) = running.remove(service_type) else {
+            return Ok(grpc_stopped);
+        };
+
+        match service {
+            RunningService::Http(mut service) => {
+                if let Some((_, slot)) = self.inner.slots.get(service_type) {
+                    slot.unmount();
+                }
+                self.inner
+                    .service_collector
+                    .remove(&service.info().name)
+                    .await;
+                service.on_stop().await?;
+            }
+            RunningService::Ice {
+                names,
+                stop_tx,
+                done_rx,
+            } => {
+                let _ = stop_tx.send(());
+                let _ = done_rx.await;
+                for name in names {
+                    self.inner.service_collector.remove(&name).await;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn public_url(&self) -> Option<Url> {
+        self.inner
+            .public_url
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+fn is_serving(running: &HashMap<ServiceType, RunningService>, service_type: &ServiceType) -> bool {
+    running.contains_key(service_type)
+        || (*service_type == ServiceType::Stun && running.contains_key(&ServiceType::Turn))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+
+    async fn status(app: &Router, uri: &str) -> StatusCode {
+        let request = axum::http::Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_router_slot_remount() {
+        let slot = RouterSlot::default();
+        let app = Router::new().nest_service("/ks", slot.service());
+        assert_eq!(
+            status(&app, "/ks/health").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        slot.mount(Router::new().route("/health", get(|| async { "ok" })));
+        assert!(slot.is_mounted());
+        assert_eq!(status(&app, "/ks/health").await, StatusCode::OK);
+        assert_eq!(status(&app, "/ks/missing").await, StatusCode::NOT_FOUND);
+
+        slot.unmount();
+        assert_eq!(
+            status(&app, "/ks/health").await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_service_requires_http_server() {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        let controller = ServiceController::new(
+            ActrixConfig::default(),
+            shutdown_tx,
+            ServiceCollector::new(),
+        );
+
+        let err = controller
+            .set_enabled(ServiceType::Ks, true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP server is not running"));
+        assert!(!controller.is_running(&ServiceType::Ks).await);
+
+        // 停用未运行的服务不报错
+        controller
+            .set_enabled(ServiceType::Signaling, false)
+            .await
+            .unwrap();
+        assert!(
+            controller
+                .statuses()
+                .await
+                .iter()
+                .all(|(_, running)| !running)
+        );
+    }
+}
@@ -1,12 +1,22 @@
 //! KS (Key Server) gRPC 服务实现
 //!
-//! 提供椭圆曲线密钥生成和管理的 gRPC API 服务
+//! 提供椭圆曲线密钥生成和管理的 gRPC API 服务，同时挂载健康检查与反射服务（见 [`super::probes`]）
 
-use actrix_common::{config::ActrixConfig, storage::nonce::SqliteNonceStorage};
+use super::probes;
+use actrix_common::{
+    ServiceCollector, ServiceType,
+    config::ActrixConfig,
+    storage::{NonceStore, NonceSweeper},
+};
+use actrix_proto::ks::v1::key_server_server::SERVICE_NAME as KS_SERVICE_NAME;
 use anyhow::Result;
-use ks::{KeyEncryptor, KeyStorage, create_grpc_service};
+use ks::{
+    AuditLog, ClientIdentityInterceptor, KeyEncryptor, KeyScheduler, KeyServerServer, KeyStorage,
+    KsGrpcService,
+};
 use std::net::SocketAddr;
 use tokio::{sync::broadcast, task::JoinHandle};
+use tonic::service::Interceptor;
 use tonic::transport::Server;
 use tracing::{error, info};
 
@@ -14,11 +24,21 @@ use tracing::{error, info};
 #[derive(Debug)]
 pub struct KsGrpcService {
     config: ActrixConfig,
+    service_collector: ServiceCollector,
 }
 
 impl KsGrpcService {
     pub fn new(config: ActrixConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            service_collector: ServiceCollector::default(),
+        }
+    }
+
+    /// 设置健康检查使用的服务状态来源
+    pub fn with_service_collector(mut self, service_collector: ServiceCollector) -> Self {
+        self.service_collector = service_collector;
+        self
     }
 
     /// 启动 gRPC 服务器
@@ -31,16 +51,17 @@ impl KsGrpcService {
 
         let ks_service_config = self
             .config
-            .services
-            .ks
-            .as_ref()
+            .ks_service_config()
             .ok_or_else(|| anyhow::anyhow!("KS service configuration not found"))?;
 
-        // 创建 nonce storage 实例（用于防重放攻击）
-        // 使用 sqlite_path 作为目录路径，内部会自动拼接 nonce.db
-        let nonce_storage = SqliteNonceStorage::new_async(&self.config.sqlite_path)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to create nonce storage: {e}"))?;
+        // 创建 nonce storage 实例（用于防重放攻击），后端由 nonce_storage 配置选择
+        // sqlite 后端使用 sqlite_path 作为目录路径，内部会自动拼接 nonce.db
+        let nonce_storage = NonceStore::from_config(
+            &self.config.nonce_storage_config(),
+            &self.config.sqlite_path,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create nonce storage: {e}"))?;
 
         // 创建密钥加密器
         let encryptor = match ks_service_config.get_kek_source() {
@@ -64,20 +85,79 @@ impl KsGrpcService {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create KS storage: {e}"))?;
 
-        // 创建 gRPC 服务
-        let grpc_service = create_grpc_service(
+        let audit = AuditLog::from_config(&ks_service_config.audit, &self.config.sqlite_path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to open KS audit log: {e}"))?;
+
+        // 后台密钥调度（预生成、定时轮替、过期清理），随 shutdown 信号退出
+        let scheduler = KeyScheduler::new(
+            storage.clone(),
+            &ks_service_config.rotation,
+            ks_service_config.rotation_grace_seconds,
+        )
+        .map_err(|e| anyhow::anyhow!("Invalid KS rotation configuration: {e}"))?;
+        let key_pool = scheduler.pool();
+        scheduler.spawn(shutdown_tx.subscribe());
+
+        // 配置双向 TLS 时按客户端证书 SAN 白名单校验访问方
+        let mut server = Server::builder();
+        let mut identity_interceptor = None;
+        if let Some(tls) = ks_service_config.grpc_tls_config() {
+            let tls_config = ks::mtls::server_tls_config(tls)
+                .map_err(|e| anyhow::anyhow!("Failed to load KS gRPC TLS configuration: {e}"))?;
+            server = server
+                .tls_config(tls_config)
+                .map_err(|e| anyhow::anyhow!("Invalid KS gRPC TLS configuration: {e}"))?;
+            info!(
+                "KS gRPC mutual TLS enabled, allowed client SANs: {:?}",
+                tls.allowed_client_sans
+            );
+            identity_interceptor = Some(ClientIdentityInterceptor::new(&tls.allowed_client_sans));
+        }
+
+        let ks_grpc_service = KsGrpcService::new(
             storage,
             nonce_storage,
             self.config.actrix_shared_key.clone(),
             ks_service_config.tolerance_seconds,
+        )
+        .with_rotation_grace_seconds(ks_service_config.rotation_grace_seconds)
+        .with_audit(audit)
+        .with_key_pool(key_pool);
+
+        // 定期清理过期 nonce，服务释放后自动退出
+        if let Some(interval) = self.config.nonce_storage_config().cleanup_interval() {
+            NonceSweeper::new(&ks_grpc_service.nonce_storage(), interval).spawn();
+        }
+
+        // 创建 gRPC 服务
+        let grpc_service = KeyServerServer::with_interceptor(
+            ks_grpc_service,
+            move |request: tonic::Request<()>| match identity_interceptor.as_mut() {
+                Some(interceptor) => interceptor.call(request),
+                None => Ok(request),
+            },
         );
 
         info!("KS gRPC service created successfully");
 
+        let (health_reporter, health_service) = tonic_health::server::health_reporter();
+        probes::spawn_health_updater(
+            health_reporter,
+            self.service_collector.clone(),
+            vec![(KS_SERVICE_NAME, Some(ServiceType::Ks))],
+            shutdown_tx.subscribe(),
+        );
+        let reflection_v1 = probes::reflection_v1()?;
+        let reflection_v1alpha = probes::reflection_v1alpha()?;
+
         let mut shutdown_rx = shutdown_tx.subscribe();
         let handle = tokio::spawn(async move {
-            Server::builder()
+            server
                 .add_service(grpc_service)
+                .add_service(health_service)
+                .add_service(reflection_v1)
+                .add_service(reflection_v1alpha)
                 .serve_with_shutdown(addr, async move {
                     let _ = shutdown_rx.recv().await;
                     info!("KS gRPC service received shutdown signal");
@@ -92,4 +172,56 @@ impl KsGrpcService {
 
         Ok(handle)
     }
+
+    /// 启动模拟 KS gRPC 服务器（`dev.mock_dependencies`）
+    ///
+    /// 使用确定性密钥且不校验请求凭证，仅用于开发
+    #[cfg(feature = "dev-mock")]
+    pub async fn start_mock(
+        &mut self,
+        addr: SocketAddr,
+        shutdown_tx: broadcast::Sender<()>,
+    ) -> Result<JoinHandle<()>> {
+        info!("Starting mock KS gRPC service on {}", addr);
+
+        let tolerance_seconds = self
+            .config
+            .services
+            .ks
+            .as_ref()
+            .map(|ks| ks.tolerance_seconds)
+            .unwrap_or_else(|| ks::KsServiceConfig::default().tolerance_seconds);
+        let grpc_service = KeyServerServer::new(ks::mock::MockKeyServer::new(tolerance_seconds));
+
+        let (health_reporter, health_service) = tonic_health::server::health_reporter();
+        probes::spawn_health_updater(
+            health_reporter,
+            self.service_collector.clone(),
+            vec![(KS_SERVICE_NAME, Some(ServiceType::Ks))],
+            shutdown_tx.subscribe(),
+        );
+        let reflection_v1 = probes::reflection_v1()?;
+        let reflection_v1alpha = probes::reflection_v1alpha()?;
+
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        let handle = tokio::spawn(async move {
+            Server::builder()
+                .add_service(grpc_service)
+                .add_service(health_service)
+                .add_service(reflection_v1)
+                .add_service(reflection_v1alpha)
+                .serve_with_shutdown(addr, async move {
+                    let _ = shutdown_rx.recv().await;
+                    info!("Mock KS gRPC service received shutdown signal");
+                })
+                .await
+                .map_err(|err| error!("Mock KS gRPC service error: {}", err))
+                .ok();
+            let _ = shutdown_tx.send(());
+        });
+
+        info!("✅ Mock KS gRPC service listening on {}", addr);
+
+        Ok(handle)
+    }
 }
@@ -3,6 +3,7 @@
 //! 管理各种 gRPC 服务的实现
 
 pub mod ks;
+pub mod probes;
 pub mod supervisord;
 
 pub use ks::KsGrpcService;
@@ -0,0 +1,150 @@
+//! gRPC 健康检查与反射服务
+//!
+//! 所有 gRPC 监听器统一挂载：
+//! - `grpc.health.v1.Health`：供 Kubernetes gRPC 探针与 `grpc_health_probe` 使用。
+//!   各服务的状态由 [`ServiceCollector`] 中登记的服务状态定期刷新，
+//!   整体状态（空服务名）取所有已登记服务
+//! - `grpc.reflection.v1` / `grpc.reflection.v1alpha`：`grpcurl` 无需本地 proto 文件即可调用
+//!
+//! 健康与反射服务不经过业务认证（mTLS 监听器仍要求客户端证书）。
+
+use actrix_common::{ServiceCollector, ServiceInfo, ServiceType};
+use anyhow::Result;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tonic_health::ServingStatus;
+use tonic_health::server::HealthReporter;
+use tonic_reflection::server::{v1, v1alpha};
+use tracing::debug;
+
+/// 健康状态刷新间隔
+const HEALTH_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+fn reflection_builder() -> tonic_reflection::server::Builder<'static> {
+    tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(actrix_proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+}
+
+/// 反射服务（`grpc.reflection.v1`）
+pub fn reflection_v1() -> Result<v1::ServerReflectionServer<impl v1::ServerReflection>> {
+    reflection_builder()
+        .build_v1()
+        .map_err(|e| anyhow::anyhow!("Failed to build gRPC reflection service: {e}"))
+}
+
+/// 反射服务（`grpc.reflection.v1alpha`，兼容旧版 grpcurl）
+pub fn reflection_v1alpha()
+-> Result<v1alpha::ServerReflectionServer<impl v1alpha::ServerReflection>> {
+    reflection_builder()
+        .build_v1alpha()
+        .map_err(|e| anyhow::anyhow!("Failed to build gRPC reflection service: {e}"))
+}
+
+/// 根据已登记的服务计算健康状态
+///
+/// `service_type` 为 None 时取所有服务；没有对应服务时视为正常（gRPC 监听器本身可用），
+/// 尚未启动（Unknown）或出错的服务视为不可用
+fn serving_status(infos: &[ServiceInfo], service_type: Option<&ServiceType>) -> ServingStatus {
+    let all_running = infos
+        .iter()
+        .filter(|info| service_type.is_none_or(|t| &info.service_type == t))
+        .all(ServiceInfo::is_running);
+    if all_running {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    }
+}
+
+/// 后台按 [`ServiceCollector`] 刷新健康状态，收到关闭信号后置为 NOT_SERVING 并退出
+///
+/// `services` 为 (gRPC 服务名, 对应的服务类型)，服务类型为 None 时取整体状态
+pub fn spawn_health_updater(
+    reporter: HealthReporter,
+    collector: ServiceCollector,
+    services: Vec<(&'static str, Option<ServiceType>)>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEALTH_REFRESH_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let infos = collector.values().await;
+                    reporter
+                        .set_service_status("", serving_status(&infos, None))
+                        .await;
+                    for (name, service_type) in &services {
+                        reporter
+                            .set_service_status(name, serving_status(&infos, service_type.as_ref()))
+                            .await;
+                    }
+                }
+                _ = shutdown_rx.recv() => {
+                    debug!("gRPC health updater received shutdown signal");
+                    reporter.set_service_status("", ServingStatus::NotServing).await;
+                    for (name, _) in &services {
+                        reporter.set_service_status(name, ServingStatus::NotServing).await;
+                    }
+                    break;
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::ServiceState;
+    use actrix_common::config::ActrixConfig;
+
+    fn info(service_type: ServiceType, status: ServiceState) -> ServiceInfo {
+        let mut info = ServiceInfo::new("test", service_type, None, &ActrixConfig::default());
+        info.status = status;
+        info
+    }
+
+    #[test]
+    fn test_serving_status() {
+        let running = ServiceState::Running("http://localhost".to_string());
+        let infos = vec![
+            info(ServiceType::Ks, running.clone()),
+            info(
+                ServiceType::Signaling,
+                ServiceState::Error("down".to_string()),
+            ),
+        ];
+        assert_eq!(
+            serving_status(&infos, Some(&ServiceType::Ks)),
+            ServingStatus::Serving
+        );
+        assert_eq!(
+            serving_status(&infos, Some(&ServiceType::Signaling)),
+            ServingStatus::NotServing
+        );
+        assert_eq!(serving_status(&infos, None), ServingStatus::NotServing);
+
+        // 未登记对应服务时以监听器本身为准
+        assert_eq!(
+            serving_status(&infos, Some(&ServiceType::Ais)),
+            ServingStatus::Serving
+        );
+        assert_eq!(serving_status(&[], None), ServingStatus::Serving);
+
+        // 尚未启动
+        let starting = vec![info(ServiceType::Ks, ServiceState::Unknown)];
+        assert_eq!(
+            serving_status(&starting, Some(&ServiceType::Ks)),
+            ServingStatus::NotServing
+        );
+    }
+
+    #[test]
+    fn test_reflection_services_build() {
+        assert!(reflection_v1().is_ok());
+        assert!(reflection_v1alpha().is_ok());
+    }
+}
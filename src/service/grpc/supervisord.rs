@@ -1,13 +1,27 @@
+use super::probes;
+use crate::service::control::ServiceController;
+use actr_protocol::{ActrId, ActrIdExt};
 use actrix_common::{
-    ServiceCollector, config::SupervisorConfig, storage::nonce::SqliteNonceStorage,
+    ServiceCollector, ServiceType,
+    config::{NonceStorageConfig, SupervisorConfig},
+    storage::{NonceStore, NonceSweeper},
 };
 use anyhow::Result;
+use nonce_auth::storage::NonceStorage;
+use signaling::admin::{
+    ConnectionSnapshot, SpecVersionSnapshot, connection_snapshots, force_disconnect, spec_history,
+};
+use signaling::server_notice::{NoticeFilter, NoticeSeverity, ServerNotice, broadcast_notice};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use supervit::{AuthService, SupervisedServiceServer, Supervisord};
+use supervit::{
+    AuthService, ConnectedActor, ConnectedService, NodeCapabilities, ServiceSpecVersion,
+    SupervisedServiceServer, Supervisord, SupervitError,
+};
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
+use tonic::Status;
 use tonic::transport::Server;
 use tracing::{error, info, warn};
 
@@ -16,8 +30,11 @@ use tracing::{error, info, warn};
 pub struct SupervisordGrpcService {
     supervisor_config: SupervisorConfig,
     sqlite_path: PathBuf,
+    nonce_storage_config: NonceStorageConfig,
     location_tag: String,
     service_collector: ServiceCollector,
+    capabilities: NodeCapabilities,
+    service_controller: Option<ServiceController>,
 }
 
 impl SupervisordGrpcService {
@@ -25,22 +42,35 @@ impl SupervisordGrpcService {
     ///
     /// - `supervisor_config`: validated supervisor configuration
     /// - `sqlite_path`: base directory for SQLite databases (used for nonce.db)
+    /// - `nonce_storage_config`: nonce backend selection (anti-replay)
     /// - `location_tag`: node location tag reported to supervisor
     /// - `service_collector`: service collector for accessing service statuses
+    /// - `capabilities`: build features and active subsystems returned by GetNodeInfo
     pub fn new(
         supervisor_config: SupervisorConfig,
         sqlite_path: PathBuf,
+        nonce_storage_config: NonceStorageConfig,
         location_tag: String,
         service_collector: ServiceCollector,
+        capabilities: NodeCapabilities,
     ) -> Self {
         Self {
             supervisor_config,
             sqlite_path,
+            nonce_storage_config,
             location_tag,
             service_collector,
+            capabilities,
+            service_controller: None,
         }
     }
 
+    /// Enable SetServiceEnabled: start and stop individual services at runtime
+    pub fn with_service_controller(mut self, controller: ServiceController) -> Self {
+        self.service_controller = Some(controller);
+        self
+    }
+
     /// Start Supervisord gRPC service
     pub async fn start(
         &mut self,
@@ -59,11 +89,15 @@ impl SupervisordGrpcService {
         let node_name = supervisor_cfg.node_name().to_string();
 
         // Initialize nonce storage (anti-replay)
-        let nonce_storage = Arc::new(
-            SqliteNonceStorage::new_async(&self.sqlite_path)
+        let nonce_storage: Arc<dyn NonceStorage + Send + Sync> = Arc::new(
+            NonceStore::from_config(&self.nonce_storage_config, &self.sqlite_path)
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to init nonce storage: {e}"))?,
         );
+        // Sweep expired nonces; the sweeper exits once the auth service drops the store
+        if let Some(interval) = self.nonce_storage_config.cleanup_interval() {
+            NonceSweeper::new(&nonce_storage, interval).spawn();
+        }
 
         // Build supervisord service instance
         // ServiceCollector now uses ServiceInfo internally, so we can pass it directly
@@ -74,7 +108,8 @@ impl SupervisordGrpcService {
             env!("CARGO_PKG_VERSION"),
             self.service_collector.clone(),
         )
-        .map_err(|e| anyhow::anyhow!("Failed to create supervisord service: {e}"))?;
+        .map_err(|e| anyhow::anyhow!("Failed to create supervisord service: {e}"))?
+        .with_capabilities(self.capabilities.clone());
 
         // Shutdown handling: broadcast shutdown signal
         let shutdown_tx_for_handler = shutdown_tx.clone();
@@ -91,6 +126,104 @@ impl SupervisordGrpcService {
             }
         });
 
+        // Runtime service enable/disable
+        if let Some(controller) = self.service_controller.clone() {
+            service = service.with_service_toggle_handler(move |resource_type, enabled| {
+                let controller = controller.clone();
+                async move {
+                    let service_type = ServiceType::try_from(resource_type).map_err(|r| {
+                        SupervitError::Status(Status::invalid_argument(format!(
+                            "Unsupported service type: {r:?}"
+                        )))
+                    })?;
+                    controller
+                        .set_enabled(service_type, enabled)
+                        .await
+                        .map_err(|e| SupervitError::Internal(format!("{e:#}")))
+                }
+            });
+        }
+
+        // Signaling connection management, server notices and spec history: the signaling server is resolved
+        // per request, since the signaling service may start after supervisord (or not run at all)
+        service = service
+            .with_connections_provider(|realm_id| async move {
+                let server =
+                    signaling::admin::registered_server().ok_or_else(signaling_not_running)?;
+                let connections = connection_snapshots(&server, realm_id).await;
+                Ok::<_, SupervitError>(
+                    connections
+                        .into_iter()
+                        .map(connected_actor_to_proto)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .with_disconnect_handler(|actor_id, _reason| async move {
+                let server =
+                    signaling::admin::registered_server().ok_or_else(signaling_not_running)?;
+                let actor_id = ActrId::from_string_repr(&actor_id).map_err(|e| {
+                    SupervitError::Status(Status::invalid_argument(format!(
+                        "Invalid actor_id '{actor_id}': {e}"
+                    )))
+                })?;
+                Ok::<_, SupervitError>(force_disconnect(&server, &actor_id).await)
+            })
+            .with_notice_handler(|request| async move {
+                let server =
+                    signaling::admin::registered_server().ok_or_else(signaling_not_running)?;
+                let severity = NoticeSeverity::parse(&request.severity).ok_or_else(|| {
+                    SupervitError::Status(Status::invalid_argument(format!(
+                        "Invalid severity '{}': expected info, warning or critical",
+                        request.severity
+                    )))
+                })?;
+                if request.message.trim().is_empty() {
+                    return Err(SupervitError::Status(Status::invalid_argument(
+                        "Notice message must not be empty",
+                    )));
+                }
+                let notice = ServerNotice::new(
+                    severity,
+                    request.category,
+                    request.message,
+                    request.effective_at,
+                );
+                let filter = NoticeFilter {
+                    realm_ids: request.realm_ids,
+                    actr_types: request.actr_types,
+                };
+                let delivered = broadcast_notice(&server, &notice, &filter).await;
+                Ok::<_, SupervitError>((notice.notice_id, delivered as u32))
+            })
+            .with_spec_history_provider(|service_name, fingerprint, limit| async move {
+                let server =
+                    signaling::admin::registered_server().ok_or_else(signaling_not_running)?;
+                // Without a limit, return every retained version (bounded by spec_history.max_versions)
+                let limit = limit.map_or(usize::MAX, |limit| limit as usize);
+                let versions =
+                    spec_history(&server, &service_name, fingerprint.as_deref(), limit).await;
+                Ok::<_, SupervitError>(
+                    versions
+                        .into_iter()
+                        .map(spec_version_to_proto)
+                        .collect::<Vec<_>>(),
+                )
+            });
+
+        // Health checking is fed from the service collector; reflection serves all Actrix protos
+        let (health_reporter, health_service) = tonic_health::server::health_reporter();
+        probes::spawn_health_updater(
+            health_reporter,
+            self.service_collector.clone(),
+            vec![(
+                actrix_proto::supervisor::v1::supervised_service_server::SERVICE_NAME,
+                None,
+            )],
+            shutdown_tx.subscribe(),
+        );
+        let reflection_v1 = probes::reflection_v1()?;
+        let reflection_v1alpha = probes::reflection_v1alpha()?;
+
         info!("🚀 Starting Supervisord gRPC service on {}", addr);
         let mut shutdown_rx = shutdown_tx.subscribe();
         let max_clock_skew_secs = supervisor_cfg.max_clock_skew_secs;
@@ -104,6 +237,9 @@ impl SupervisordGrpcService {
             );
             let result = Server::builder()
                 .add_service(SupervisedServiceServer::new(authed_service))
+                .add_service(health_service)
+                .add_service(reflection_v1)
+                .add_service(reflection_v1alpha)
                 .serve_with_shutdown(addr, async move {
                     info!("✅ Supervisord gRPC service listening on {}", addr);
                     let _ = shutdown_rx.recv().await;
@@ -121,3 +257,42 @@ impl SupervisordGrpcService {
         Ok(handle)
     }
 }
+
+fn signaling_not_running() -> SupervitError {
+    SupervitError::Internal("Signaling service is not running on this node".to_string())
+}
+
+fn connected_actor_to_proto(snapshot: ConnectionSnapshot) -> ConnectedActor {
+    ConnectedActor {
+        client_id: snapshot.client_id,
+        actor_id: snapshot.actor_id,
+        realm_id: snapshot.realm_id,
+        client_ip: snapshot.client_ip,
+        connected_at: snapshot.connected_at,
+        services: snapshot
+            .services
+            .into_iter()
+            .map(|service| ConnectedService {
+                service_name: service.service_name,
+                fingerprint: service.fingerprint,
+                status: format!("{:?}", service.status),
+                last_heartbeat_time_secs: service.last_heartbeat_time_secs,
+                availability_state: service.availability_state,
+                power_reserve: service.power_reserve,
+                mailbox_backlog: service.mailbox_backlog,
+            })
+            .collect(),
+    }
+}
+
+fn spec_version_to_proto(snapshot: SpecVersionSnapshot) -> ServiceSpecVersion {
+    ServiceSpecVersion {
+        fingerprint: snapshot.fingerprint,
+        published_at: snapshot.published_at,
+        first_seen_at: snapshot.first_seen_at as i64,
+        last_seen_at: snapshot.last_seen_at as i64,
+        description: snapshot.description,
+        tags: snapshot.tags,
+        spec: snapshot.spec,
+    }
+}
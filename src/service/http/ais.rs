@@ -16,6 +16,9 @@ use tracing::info;
 pub struct AisService {
     info: ServiceInfo,
     config: ActrixConfig,
+    /// 使用开发用模拟 AIS（`dev.mock_dependencies`）
+    #[cfg(feature = "dev-mock")]
+    mock: bool,
 }
 
 impl AisService {
@@ -29,6 +32,23 @@ impl AisService {
                 &config,
             ),
             config,
+            #[cfg(feature = "dev-mock")]
+            mock: false,
+        }
+    }
+
+    /// 创建模拟 AIS 服务（确定性密钥、即时签发凭证，仅用于开发）
+    #[cfg(feature = "dev-mock")]
+    pub fn mock(config: ActrixConfig) -> Self {
+        Self {
+            info: ServiceInfo::new(
+                "AIS Service (mock)",
+                ServiceType::Ais,
+                Some("Mock Actor Identity Service - 开发用模拟凭证签发".to_string()),
+                &config,
+            ),
+            config,
+            mock: true,
         }
     }
 }
@@ -46,6 +66,12 @@ impl HttpRouterService for AisService {
     async fn build_router(&mut self) -> Result<Router> {
         info!("Building AIS router");
 
+        #[cfg(feature = "dev-mock")]
+        if self.mock {
+            let ais_config = self.config.services.ais.clone().unwrap_or_default();
+            return Ok(ais::mock::create_mock_router(&ais_config));
+        }
+
         // 获取 AIS 配置
         let ais_config = self
             .config
@@ -64,6 +90,6 @@ impl HttpRouterService for AisService {
     }
 
     fn route_prefix(&self) -> &str {
-        "/ais"
+        &self.config.http.route_prefixes.ais
     }
 }
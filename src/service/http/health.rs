@@ -0,0 +1,201 @@
+//! Kubernetes 存活与就绪探针端点
+//!
+//! - `GET /healthz`：存活探针，进程能够响应 HTTP 请求即返回 200，不检查任何依赖
+//! - `GET /readyz`：就绪探针，汇总 [`ServiceCollector`] 中登记的服务状态与依赖检查，
+//!   任一检查失败返回 503：
+//!   - 每个已登记服务都处于 Running 状态（STUN/TURN 在监听套接字绑定成功后才进入 Running）
+//!   - AIS：签发器已加载加密与签名密钥，且其依赖的 KS 可达
+//!   - Signaling：节点未处于排空状态（排空中负载均衡不应再分配新连接，见 [`signaling::drain`]）
+//!
+//! 探针端点不需要认证，返回内容不包含敏感信息。
+
+use actrix_common::config::ActrixConfig;
+use actrix_common::{ServiceCollector, ServiceInfo, ServiceState, ServiceType};
+use ais::AIdIssuer;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 存活探针路径
+pub const HEALTHZ_PATH: &str = "/healthz";
+
+/// 就绪探针路径
+pub const READYZ_PATH: &str = "/readyz";
+
+/// 单项依赖检查的超时时间（需小于探针的 timeoutSeconds）
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug)]
+struct HealthState {
+    collector: ServiceCollector,
+    /// AIS 使用开发模拟实现时没有签发器，跳过签发器与 KS 检查
+    mock_ais: bool,
+}
+
+/// 单项就绪检查结果
+#[derive(Debug, Serialize)]
+struct Check {
+    name: String,
+    ready: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Check {
+    fn passed(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ready: true,
+            detail: None,
+        }
+    }
+
+    fn failed(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ready: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// 创建探针端点路由
+pub fn health_router(config: &ActrixConfig, collector: ServiceCollector) -> Router {
+    let mock_ais =
+        cfg!(feature = "dev-mock") && config.is_dev_mock_enabled() && config.services.ais.is_none();
+    Router::new()
+        .route(HEALTHZ_PATH, get(liveness))
+        .route(READYZ_PATH, get(readiness))
+        .with_state(Arc::new(HealthState {
+            collector,
+            mock_ais,
+        }))
+}
+
+async fn liveness() -> Response {
+    Json(json!({ "status": "alive" })).into_response()
+}
+
+async fn readiness(State(state): State<Arc<HealthState>>) -> Response {
+    let infos = state.collector.values().await;
+    let mut checks = service_checks(&infos);
+    if !state.mock_ais
+        && infos
+            .iter()
+            .any(|info| info.service_type == ServiceType::Ais && info.is_running())
+    {
+        checks.extend(ais_checks().await);
+    }
+    if let Some(server) = signaling::admin::registered_server()
+        && server.drain.is_draining()
+    {
+        checks.push(Check::failed(
+            "signaling_drain",
+            "draining, new registrations are redirected",
+        ));
+    }
+
+    // 尚未登记任何服务（仍在启动）时不接收流量
+    let ready = !infos.is_empty() && checks.iter().all(|check| check.ready);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(json!({
+            "status": if ready { "ready" } else { "not_ready" },
+            "checks": checks,
+        })),
+    )
+        .into_response()
+}
+
+/// 各已登记服务的运行状态
+fn service_checks(infos: &[ServiceInfo]) -> Vec<Check> {
+    infos
+        .iter()
+        .map(|info| match &info.status {
+            ServiceState::Running(_) => Check::passed(&info.name),
+            ServiceState::Unknown => Check::failed(&info.name, "not started"),
+            ServiceState::Error(e) => Check::failed(&info.name, e),
+        })
+        .collect()
+}
+
+/// AIS 签发器与 KS 依赖检查
+async fn ais_checks() -> Vec<Check> {
+    let Some(issuer) = AIdIssuer::registered() else {
+        return vec![Check::failed("ais_issuer", "issuer not initialized")];
+    };
+
+    let issuer_check =
+        match tokio::time::timeout(CHECK_TIMEOUT, issuer.check_key_cache_health()).await {
+            Ok(Ok(_)) => Check::passed("ais_issuer"),
+            Ok(Err(e)) => Check::failed("ais_issuer", e.to_string()),
+            Err(_) => Check::failed("ais_issuer", "timed out"),
+        };
+    let ks_check = match tokio::time::timeout(CHECK_TIMEOUT, issuer.check_ks_reachable()).await {
+        Ok(Ok(())) => Check::passed("ks_reachable"),
+        Ok(Err(e)) => Check::failed("ks_reachable", e.to_string()),
+        Err(_) => Check::failed("ks_reachable", "timed out"),
+    };
+    vec![issuer_check, ks_check]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    async fn probe(app: &Router, path: &str) -> StatusCode {
+        app.clone()
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri(path)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_probes_follow_service_status() {
+        let config = ActrixConfig::default();
+        let collector = ServiceCollector::new();
+        let app = health_router(&config, collector.clone());
+
+        assert_eq!(probe(&app, HEALTHZ_PATH).await, StatusCode::OK);
+        // 没有登记服务时未就绪
+        assert_eq!(
+            probe(&app, READYZ_PATH).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let mut turn = ServiceInfo::new("TURN Server", ServiceType::Turn, None, &config);
+        collector
+            .insert("TURN Server".to_string(), turn.clone())
+            .await;
+        assert_eq!(
+            probe(&app, READYZ_PATH).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        turn.status = ServiceState::Running("turn:localhost:3478".to_string());
+        collector.insert("TURN Server".to_string(), turn).await;
+        assert_eq!(probe(&app, READYZ_PATH).await, StatusCode::OK);
+        assert_eq!(probe(&app, HEALTHZ_PATH).await, StatusCode::OK);
+    }
+}
@@ -4,7 +4,7 @@
 
 use crate::service::HttpRouterService;
 use actrix_common::config::ActrixConfig;
-use actrix_common::storage::nonce::SqliteNonceStorage;
+use actrix_common::storage::{NonceStore, NonceSweeper};
 use actrix_common::{ServiceInfo, ServiceType};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -49,20 +49,21 @@ impl HttpRouterService for KsHttpService {
         // 创建 KS 状态
         let ks_service_config = self
             .config
-            .services
-            .ks
-            .as_ref()
+            .ks_service_config()
             .ok_or_else(|| anyhow::anyhow!("KS service configuration not found"))?;
 
-        // 创建 nonce storage 实例（用于防重放攻击）
-        // 使用 sqlite_path 作为目录路径，内部会自动拼接 nonce.db
-        let nonce_storage = SqliteNonceStorage::new_async(&self.config.sqlite_path)
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to create nonce storage: {e}"))?;
+        // 创建 nonce storage 实例（用于防重放攻击），后端由 nonce_storage 配置选择
+        // sqlite 后端使用 sqlite_path 作为目录路径，内部会自动拼接 nonce.db
+        let nonce_storage = NonceStore::from_config(
+            &self.config.nonce_storage_config(),
+            &self.config.sqlite_path,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create nonce storage: {e}"))?;
 
         // 创建 KS state（注入 nonce storage 和 shared key）
         let ks_state = create_ks_state(
-            ks_service_config,
+            &ks_service_config,
             nonce_storage,
             &self.config.actrix_shared_key,
             &self.config.sqlite_path,
@@ -70,6 +71,11 @@ impl HttpRouterService for KsHttpService {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create KS state: {e}"))?;
 
+        // 定期清理过期 nonce，路由释放后自动退出
+        if let Some(interval) = self.config.nonce_storage_config().cleanup_interval() {
+            NonceSweeper::new(&ks_state.nonce_storage, interval).spawn();
+        }
+
         // 获取 KS 路由器
         let router = create_router(ks_state);
 
@@ -78,6 +84,6 @@ impl HttpRouterService for KsHttpService {
     }
 
     fn route_prefix(&self) -> &str {
-        "/ks"
+        &self.config.http.route_prefixes.ks
     }
 }
@@ -0,0 +1,46 @@
+//! HTTP 监听套接字
+//!
+//! 绑定 IPv6 地址时显式关闭 IPV6_V6ONLY，`bind.http.ip = "::"` 等配置在任何系统上都是
+//! 同时接收 IPv4 与 IPv6 连接的双栈监听，不受系统 bindv6only 设置影响。
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::SocketAddr;
+
+/// 监听队列长度
+const LISTEN_BACKLOG: i32 = 1024;
+
+/// 绑定非阻塞的 TCP 监听套接字
+pub fn bind_tcp_listener(addr: SocketAddr) -> std::io::Result<std::net::TcpListener> {
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(false)?;
+    }
+    // 与标准库一致：重启后可立即重新绑定处于 TIME_WAIT 的端口
+    #[cfg(unix)]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    socket.listen(LISTEN_BACKLOG)?;
+    Ok(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dual_stack_listener_accepts_ipv4() {
+        // 沙箱等环境可能没有 IPv6 支持
+        let Ok(listener) = bind_tcp_listener("[::]:0".parse().unwrap()) else {
+            return;
+        };
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let client = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let (_, peer) = listener.accept().await.unwrap();
+        assert_eq!(peer.ip().to_canonical(), client.local_addr().unwrap().ip());
+    }
+}
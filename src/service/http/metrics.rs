@@ -0,0 +1,119 @@
+//! Prometheus 指标端点（默认 `GET /metrics`）
+//!
+//! 导出全局 [`REGISTRY`](actrix_common::metrics::REGISTRY) 中的全部指标，
+//! 包括 STUN/TURN/Signaling/KS/AIS 各服务注册的指标。路径、认证与独立监听地址由
+//! `observability.metrics` 配置；配置 `bind` 时由 [`serve_metrics`] 在独立端口上导出，
+//! 不再挂载到主 HTTP 路由，同一端口还会提供探针端点（见 [`super::health`]）。
+
+use actrix_common::config::MetricsConfig;
+use actrix_common::util::constant_time_eq;
+use axum::{
+    Router,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use signaling::ws_auth;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// Prometheus 文本格式的 Content-Type
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// 创建指标端点路由
+pub fn metrics_router(config: &MetricsConfig) -> Router {
+    Router::new()
+        .route(&config.path, get(export_metrics))
+        .with_state(Arc::new(config.bearer_token.clone()))
+}
+
+/// 在独立的管理端口上导出指标（并挂载 `extra` 路由），收到关闭信号后退出
+pub async fn serve_metrics(
+    config: &MetricsConfig,
+    extra: Router,
+    shutdown_tx: tokio::sync::broadcast::Sender<()>,
+) -> anyhow::Result<JoinHandle<()>> {
+    let addr = config
+        .bind_addr()
+        .ok_or_else(|| anyhow::anyhow!("observability.metrics.bind is not configured"))?;
+    let listener = super::listener::bind_tcp_listener(addr)
+        .and_then(tokio::net::TcpListener::from_std)
+        .map_err(|e| anyhow::anyhow!("Failed to bind metrics listener to '{addr}': {e}"))?;
+    info!("Metrics server listening on http://{}{}", addr, config.path);
+
+    let app = metrics_router(config).merge(extra);
+    Ok(tokio::spawn(async move {
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+            let _ = shutdown_rx.recv().await;
+            info!("Metrics server received shutdown signal");
+        });
+        if let Err(e) = server.await {
+            error!("Metrics server error: {}", e);
+        }
+        info!("Metrics server stopped");
+    }))
+}
+
+async fn export_metrics(
+    State(bearer_token): State<Arc<Option<String>>>,
+    headers: HeaderMap,
+) -> Response {
+    if let Some(token) = bearer_token.as_deref()
+        && !ws_auth::bearer_token(&headers)
+            .is_some_and(|t| constant_time_eq(t.as_bytes(), token.as_bytes()))
+    {
+        warn!("🚫 指标端点认证失败");
+        return (StatusCode::UNAUTHORIZED, "Invalid metrics token").into_response();
+    }
+
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        actrix_common::metrics::export_metrics(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    async fn scrape(config: &MetricsConfig, authorization: Option<&str>) -> StatusCode {
+        let mut request = axum::http::Request::builder().uri(config.path.as_str());
+        if let Some(value) = authorization {
+            request = request.header(header::AUTHORIZATION, value);
+        }
+        metrics_router(config)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_auth() {
+        let _ = actrix_common::metrics::register_metrics();
+
+        let open = MetricsConfig::default();
+        assert_eq!(scrape(&open, None).await, StatusCode::OK);
+
+        let protected = MetricsConfig {
+            path: "/internal/metrics".to_string(),
+            bearer_token: Some("scrape-token".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(scrape(&protected, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            scrape(&protected, Some("Bearer wrong-token!")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            scrape(&protected, Some("Bearer scrape-token")).await,
+            StatusCode::OK
+        );
+    }
+}
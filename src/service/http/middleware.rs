@@ -0,0 +1,430 @@
+//! HTTP 公共中间件：客户端地址解析、请求限制、CORS 与安全响应头
+//!
+//! 由 [`ServiceManager`](crate::service::ServiceManager) 应用到合并后的路由上，按 `http` 配置段生效。
+//! 配置了 `trusted_proxies` 时，来自受信任代理的请求的 `ConnectInfo` 被替换为代理头中的客户端地址，
+//! Signaling 限流、节点发现文档与 KS 审计无需各自处理代理头。
+//! CORS 在完整请求路径上判断来源，因此可以按服务路由前缀使用不同的允许列表。
+//! CORS 位于最外层，被限制拒绝的响应（413/408/503）同样携带 CORS 头，浏览器可以读取错误。
+
+use actrix_common::config::http::{
+    CorsConfig, HttpConfig, RoutePrefixes, SecurityHeadersConfig, TrustedProxies,
+};
+use axum::{
+    Router,
+    error_handling::HandleErrorLayer,
+    extract::{ConnectInfo, DefaultBodyLimit, Request},
+    http::{HeaderName, HeaderValue, StatusCode, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower::load_shed::LoadShedLayer;
+use tower::{BoxError, ServiceBuilder};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::set_header::SetResponseHeaderLayer;
+use tower_http::timeout::RequestBodyTimeoutLayer;
+use tracing::warn;
+
+/// 不受 `request_timeout_secs` 限制的路由（生成耗时与数据量成正比，超时会截断归档）
+const TIMEOUT_EXEMPT_PATHS: &[&str] = &[super::snapshot::SNAPSHOT_PATH];
+
+/// 为路由附加请求限制、CORS 与安全响应头中间件（后添加的层位于外层）
+///
+/// `prefixes` 用于按服务路由前缀选择 CORS 允许列表
+pub fn apply_http_middleware(app: Router, config: &HttpConfig, prefixes: &RoutePrefixes) -> Router {
+    let mut app = apply_request_limits(app, config);
+    match config.trusted_proxies() {
+        Ok(proxies) if !proxies.is_empty() => {
+            app = app.layer(axum::middleware::map_request(move |request| {
+                let proxies = proxies.clone();
+                async move { resolve_client_addr(&proxies, request) }
+            }));
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Ignoring invalid http.trusted_proxies: {}", e),
+    }
+    if config.security_headers.enabled {
+        for (name, value) in security_headers(&config.security_headers) {
+            app = app.layer(SetResponseHeaderLayer::if_not_present(name, value));
+        }
+    }
+    if config.cors.enabled {
+        app = app.layer(cors_layer(&config.cors, prefixes));
+    }
+    app
+}
+
+/// 请求体大小、处理超时与并发上限
+///
+/// 达到并发上限时立即返回 503，而不是在信号量上无限排队
+fn apply_request_limits(mut app: Router, config: &HttpConfig) -> Router {
+    // 同时放宽提取器的默认上限（2 MiB），使配置值对 Bytes/Json 等提取器同样生效
+    app = app
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .layer(RequestBodyLimitLayer::new(config.max_body_bytes));
+    if config.request_timeout_secs > 0 {
+        let timeout = Duration::from_secs(config.request_timeout_secs);
+        app = app
+            .layer(RequestBodyTimeoutLayer::new(timeout))
+            .layer(axum::middleware::from_fn(
+                move |request: Request, next: Next| request_timeout(timeout, request, next),
+            ));
+    }
+    if config.max_concurrent_requests > 0 {
+        // 全局信号量，所有路由共享同一上限；信号量耗尽时由 LoadShed 直接拒绝
+        app = app.layer(
+            ServiceBuilder::new()
+                .layer(HandleErrorLayer::new(|_: BoxError| async {
+                    StatusCode::SERVICE_UNAVAILABLE
+                }))
+                .layer(LoadShedLayer::new())
+                .layer(GlobalConcurrencyLimitLayer::new(
+                    config.max_concurrent_requests,
+                )),
+        );
+    }
+    app
+}
+
+/// 处理超时返回 408，[`TIMEOUT_EXEMPT_PATHS`] 中的路由不受限制
+async fn request_timeout(timeout: Duration, request: Request, next: Next) -> Response {
+    if TIMEOUT_EXEMPT_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    match tokio::time::timeout(timeout, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => StatusCode::REQUEST_TIMEOUT.into_response(),
+    }
+}
+
+/// 对端为受信任代理时，以代理头中的客户端地址替换 `ConnectInfo`（保留对端端口）
+fn resolve_client_addr(proxies: &TrustedProxies, mut request: Request) -> Request {
+    let Some(ConnectInfo(peer)) = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .copied()
+    else {
+        return request;
+    };
+    let headers = request.headers();
+    let forwarded_for = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok());
+    let real_ip = headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok());
+    let client_ip = proxies.client_ip(peer.ip(), forwarded_for, real_ip);
+    if client_ip != peer.ip() {
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(client_ip, peer.port())));
+    }
+    request
+}
+
+/// 按配置构建 CORS 层，允许的来源在请求时按路径判断
+pub fn cors_layer(config: &CorsConfig, prefixes: &RoutePrefixes) -> CorsLayer {
+    let policy = config.clone();
+    let prefixes = prefixes.clone();
+    let layer = CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(
+            move |origin: &HeaderValue, parts: &Parts| {
+                origin.to_str().is_ok_and(|origin| {
+                    policy.is_origin_allowed(&prefixes, parts.uri.path(), origin)
+                })
+            },
+        ))
+        .allow_methods(AllowMethods::mirror_request())
+        .allow_headers(AllowHeaders::mirror_request())
+        .max_age(Duration::from_secs(config.max_age_secs));
+
+    // 允许凭据时不能使用通配的 Expose-Headers
+    if config.allow_credentials {
+        layer.allow_credentials(true)
+    } else {
+        layer.expose_headers(Any)
+    }
+}
+
+/// 需要附加的安全响应头
+fn security_headers(config: &SecurityHeadersConfig) -> Vec<(HeaderName, HeaderValue)> {
+    let mut headers = vec![
+        (
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        ),
+        (header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY")),
+        (
+            header::REFERRER_POLICY,
+            HeaderValue::from_static("no-referrer"),
+        ),
+    ];
+    if !config.content_security_policy.is_empty()
+        && let Ok(value) = HeaderValue::from_str(&config.content_security_policy)
+    {
+        headers.push((header::CONTENT_SECURITY_POLICY, value));
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{Body, Bytes},
+        http::{Request, StatusCode},
+        routing::{get, post},
+    };
+    use std::collections::HashMap;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    fn app(config: &HttpConfig) -> Router {
+        let router = Router::new()
+            .route("/ais/register", get(|| async { "ok" }))
+            .route("/ks/keys", get(|| async { "ok" }))
+            .route(
+                "/ks/upload",
+                post(|body: Bytes| async move { body.len().to_string() }),
+            )
+            .route(
+                "/ks/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "late"
+                }),
+            )
+            .route(
+                super::super::snapshot::SNAPSHOT_PATH,
+                post(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "archive"
+                }),
+            );
+        apply_http_middleware(router, config, &RoutePrefixes::default())
+    }
+
+    fn preflight(path: &str, origin: &str) -> Request<Body> {
+        Request::builder()
+            .method("OPTIONS")
+            .uri(path)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cors_per_service_origins() {
+        let config = HttpConfig {
+            cors: CorsConfig {
+                allowed_origins: Vec::new(),
+                services: HashMap::from([(
+                    "ais".to_string(),
+                    vec!["https://app.example.com".to_string()],
+                )]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let response = app(&config)
+            .oneshot(preflight("/ais/register", "https://app.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS],
+            "POST"
+        );
+
+        let response = app(&config)
+            .oneshot(preflight("/ks/keys", "https://app.example.com"))
+            .await
+            .unwrap();
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_limits() {
+        let config = HttpConfig {
+            max_body_bytes: 16,
+            ..Default::default()
+        };
+        let upload = |len: usize| {
+            Request::builder()
+                .method("POST")
+                .uri("/ks/upload")
+                .body(Body::from(vec![0u8; len]))
+                .unwrap()
+        };
+
+        let response = app(&config).oneshot(upload(16)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app(&config).oneshot(upload(17)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // 大于提取器默认 2 MiB 的上限同样生效
+        let large = HttpConfig {
+            max_body_bytes: 4 * 1024 * 1024,
+            ..Default::default()
+        };
+        let response = app(&large).oneshot(upload(3 * 1024 * 1024)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_request_timeout() {
+        let config = HttpConfig {
+            request_timeout_secs: 1,
+            ..Default::default()
+        };
+        let response = app(&config)
+            .oneshot(
+                Request::builder()
+                    .uri("/ks/slow")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+
+        // 快照导出不受处理超时限制
+        let response = app(&config)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(super::super::snapshot::SNAPSHOT_PATH)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_sheds_load() {
+        let config = HttpConfig {
+            max_concurrent_requests: 1,
+            ..Default::default()
+        };
+        let entered = std::sync::Arc::new(Notify::new());
+        let release = std::sync::Arc::new(Notify::new());
+        let router = Router::new().route(
+            "/hold",
+            get({
+                let entered = entered.clone();
+                let release = release.clone();
+                move || async move {
+                    entered.notify_one();
+                    release.notified().await;
+                    "done"
+                }
+            }),
+        );
+        let app = apply_http_middleware(router, &config, &RoutePrefixes::default());
+        let request = || Request::builder().uri("/hold").body(Body::empty()).unwrap();
+
+        let held = tokio::spawn(app.clone().oneshot(request()));
+        entered.notified().await;
+
+        // 并发已满：立即 503，而不是排队等待
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        release.notify_one();
+        assert_eq!(held.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_security_headers() {
+        let response = app(&HttpConfig::default())
+            .oneshot(
+                Request::builder()
+                    .uri("/ais/register")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let headers = response.headers();
+        assert_eq!(headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[header::X_FRAME_OPTIONS], "DENY");
+        assert_eq!(headers[header::REFERRER_POLICY], "no-referrer");
+        assert!(headers.contains_key(header::CONTENT_SECURITY_POLICY));
+
+        let disabled = HttpConfig {
+            security_headers: SecurityHeadersConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let response = app(&disabled)
+            .oneshot(
+                Request::builder()
+                    .uri("/ais/register")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::X_CONTENT_TYPE_OPTIONS)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trusted_proxy_rewrites_connect_info() {
+        let config = HttpConfig {
+            trusted_proxies: vec!["10.0.0.0/8".to_string()],
+            ..Default::default()
+        };
+        let router = Router::new().route(
+            "/ip",
+            get(|ConnectInfo(addr): ConnectInfo<SocketAddr>| async move { addr.to_string() }),
+        );
+        let app = apply_http_middleware(router, &config, &RoutePrefixes::default());
+
+        let request = |peer: &str| {
+            Request::builder()
+                .uri("/ip")
+                .header("x-forwarded-for", "198.51.100.7, 10.0.0.9")
+                .extension(ConnectInfo(peer.parse::<SocketAddr>().unwrap()))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let body = |response: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            String::from_utf8(bytes.to_vec()).unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request("10.0.0.1:40000"))
+            .await
+            .unwrap();
+        assert_eq!(body(response).await, "198.51.100.7:40000");
+
+        // 非受信任对端的代理头被忽略
+        let response = app.oneshot(request("203.0.113.5:40000")).await.unwrap();
+        assert_eq!(body(response).await, "203.0.113.5:40000");
+    }
+}
@@ -3,8 +3,16 @@
 //! 管理HTTP相关的服务
 
 mod ais;
+pub mod health;
 mod ks;
+pub mod listener;
+pub mod metrics;
+pub mod middleware;
+pub mod redirect;
+pub mod services;
 mod signaling;
+pub mod snapshot;
+pub mod well_known;
 
 pub use ais::AisService;
 pub use ks::KsHttpService;
@@ -0,0 +1,216 @@
+//! HTTP → HTTPS 重定向
+//!
+//! 主服务运行在 HTTPS 上且 `bind.http.redirect_to_https = true` 时，[`serve_redirect`] 在
+//! `bind.http` 上启动一个只做重定向的监听器：所有请求以 308 重定向到 HTTPS 上的同一路径
+//! （保留方法与请求体），不提供任何明文 API。HTTPS 响应同时附带 HSTS 头（见 [`add_hsts`]），
+//! 浏览器此后直接使用 HTTPS。
+
+use actrix_common::config::ActrixConfig;
+use actrix_common::config::bind::{HttpBindConfig, HttpsBindConfig};
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// HSTS 头取值（一年，包含子域名）
+pub const HSTS_VALUE: &str = "max-age=31536000; includeSubDomains";
+
+/// 需要启动重定向监听器时返回 HTTP / HTTPS 绑定配置
+///
+/// dev 环境下主服务优先运行在 HTTP 上，此时不做重定向。
+pub fn redirect_binds(config: &ActrixConfig) -> Option<(&HttpBindConfig, &HttpsBindConfig)> {
+    let http = config
+        .bind
+        .http
+        .as_ref()
+        .filter(|http| http.redirect_to_https)?;
+    let https = config.bind.https.as_ref()?;
+    (config.env.to_lowercase() != "dev").then_some((http, https))
+}
+
+/// 重定向目标
+#[derive(Debug, Clone)]
+struct RedirectTarget {
+    /// 请求缺少 Host 头时使用的域名
+    domain_name: String,
+    https_port: u16,
+}
+
+impl RedirectTarget {
+    /// 拼接 HTTPS URL：沿用请求的主机名（去掉端口），非 443 端口时显式附加
+    fn location(&self, host: Option<&str>, path_and_query: &str) -> String {
+        let host = host
+            .map(strip_port)
+            .filter(|host| !host.is_empty())
+            .unwrap_or(&self.domain_name);
+        if self.https_port == 443 {
+            format!("https://{host}{path_and_query}")
+        } else {
+            format!("https://{host}:{}{path_and_query}", self.https_port)
+        }
+    }
+}
+
+/// 去掉 Host 头中的端口（兼容 `[::1]:8080` 形式的 IPv6 地址）
+fn strip_port(host: &str) -> &str {
+    if let Some(end) = host.strip_prefix('[').and_then(|rest| rest.find(']')) {
+        return &host[..end + 2];
+    }
+    match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+/// 只做重定向的路由
+pub fn redirect_router(https: &HttpsBindConfig) -> Router {
+    Router::new()
+        .fallback(redirect)
+        .with_state(Arc::new(RedirectTarget {
+            domain_name: https.domain_name.clone(),
+            https_port: https.port,
+        }))
+}
+
+async fn redirect(State(target): State<Arc<RedirectTarget>>, request: Request) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok());
+    let path_and_query = request.uri().path_and_query().map_or("/", |pq| pq.as_str());
+    let location = target.location(host, path_and_query);
+
+    (
+        StatusCode::PERMANENT_REDIRECT,
+        [
+            (header::LOCATION, location),
+            (header::STRICT_TRANSPORT_SECURITY, HSTS_VALUE.to_string()),
+        ],
+    )
+        .into_response()
+}
+
+/// 为 HTTPS 响应附加 HSTS 头（用于 `axum::middleware::map_response`）
+pub async fn add_hsts(mut response: Response) -> Response {
+    response.headers_mut().insert(
+        header::STRICT_TRANSPORT_SECURITY,
+        HeaderValue::from_static(HSTS_VALUE),
+    );
+    response
+}
+
+/// 在 `bind.http` 上启动重定向监听器，收到关闭信号后退出
+pub async fn serve_redirect(
+    http: &HttpBindConfig,
+    https: &HttpsBindConfig,
+    shutdown_tx: tokio::sync::broadcast::Sender<()>,
+) -> anyhow::Result<JoinHandle<()>> {
+    let addr = http.socket_addr().map_err(|e| anyhow::anyhow!(e))?;
+    let listener = super::listener::bind_tcp_listener(addr)
+        .and_then(tokio::net::TcpListener::from_std)
+        .map_err(|e| anyhow::anyhow!("Failed to bind HTTP redirect listener to '{addr}': {e}"))?;
+    info!(
+        "HTTP redirect listener on {} -> https://{}:{}",
+        addr, https.domain_name, https.port
+    );
+
+    let app = redirect_router(https);
+    Ok(tokio::spawn(async move {
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+            let _ = shutdown_rx.recv().await;
+            info!("HTTP redirect listener received shutdown signal");
+        });
+        if let Err(e) = server.await {
+            error!("HTTP redirect listener error: {}", e);
+        }
+        info!("HTTP redirect listener stopped");
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_location() {
+        let target = RedirectTarget {
+            domain_name: "actrix.example.com".to_string(),
+            https_port: 8443,
+        };
+        assert_eq!(
+            target.location(Some("edge.example.com:8080"), "/signaling/ws?x=1"),
+            "https://edge.example.com:8443/signaling/ws?x=1"
+        );
+        assert_eq!(
+            target.location(Some("[::1]:8080"), "/"),
+            "https://[::1]:8443/"
+        );
+        assert_eq!(
+            target.location(None, "/readyz"),
+            "https://actrix.example.com:8443/readyz"
+        );
+
+        let standard = RedirectTarget {
+            https_port: 443,
+            ..target
+        };
+        assert_eq!(
+            standard.location(Some("edge.example.com"), "/"),
+            "https://edge.example.com/"
+        );
+    }
+
+    #[test]
+    fn test_redirect_binds() {
+        let mut config = ActrixConfig {
+            env: "prod".to_string(),
+            ..Default::default()
+        };
+        assert!(redirect_binds(&config).is_none());
+
+        config.bind.http.as_mut().unwrap().redirect_to_https = true;
+        assert!(redirect_binds(&config).is_some());
+
+        // dev 环境主服务运行在 HTTP 上
+        config.env = "dev".to_string();
+        assert!(redirect_binds(&config).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_router() {
+        let https = HttpsBindConfig {
+            domain_name: "actrix.example.com".to_string(),
+            port: 443,
+            ..HttpsBindConfig::default()
+        };
+        let response = redirect_router(&https)
+            .oneshot(
+                axum::http::Request::builder()
+                    .method("POST")
+                    .uri("/ais/register")
+                    .header(header::HOST, "actrix.example.com")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "https://actrix.example.com/ais/register"
+        );
+        assert_eq!(
+            response.headers()[header::STRICT_TRANSPORT_SECURITY],
+            HSTS_VALUE
+        );
+    }
+}
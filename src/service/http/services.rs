@@ -0,0 +1,104 @@
+//! 运行时服务启停端点
+//!
+//! - `GET /admin/services`：列出各服务是否运行
+//! - `POST /admin/services/{service}/enable` / `POST /admin/services/{service}/disable`：
+//!   启用或停用单个服务（`signaling` / `ais` / `ks` / `stun` / `turn`）
+//!
+//! 与快照端点相同，使用 `Authorization: Bearer <actrix_shared_key>` 认证；
+//! 启停语义见 [`crate::service::control`]。
+
+use crate::service::control::ServiceController;
+use actrix_common::ServiceType;
+use actrix_common::config::ActrixConfig;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde_json::json;
+use signaling::admin::{AdminAuth, AdminTokenSource};
+use std::sync::Arc;
+use tracing::error;
+
+/// 服务启停端点路径
+pub const SERVICES_PATH: &str = "/admin/services";
+
+#[derive(Debug)]
+struct ServicesState {
+    controller: ServiceController,
+    admin_token: String,
+}
+
+impl AdminTokenSource for ServicesState {
+    fn admin_token(&self) -> Option<&str> {
+        Some(&self.admin_token)
+    }
+}
+
+/// 创建服务启停端点路由
+pub fn services_router(config: &ActrixConfig, controller: ServiceController) -> Router {
+    Router::new()
+        .route(SERVICES_PATH, get(list_services))
+        .route("/admin/services/{service}/enable", post(enable_service))
+        .route("/admin/services/{service}/disable", post(disable_service))
+        .with_state(Arc::new(ServicesState {
+            controller,
+            admin_token: config.actrix_shared_key.clone(),
+        }))
+}
+
+async fn list_services(_auth: AdminAuth, State(state): State<Arc<ServicesState>>) -> Response {
+    statuses_response(&state.controller).await
+}
+
+async fn enable_service(
+    _auth: AdminAuth,
+    State(state): State<Arc<ServicesState>>,
+    Path(service): Path<String>,
+) -> Response {
+    set_service_enabled(&state, &service, true).await
+}
+
+async fn disable_service(
+    _auth: AdminAuth,
+    State(state): State<Arc<ServicesState>>,
+    Path(service): Path<String>,
+) -> Response {
+    set_service_enabled(&state, &service, false).await
+}
+
+async fn set_service_enabled(state: &ServicesState, service: &str, enabled: bool) -> Response {
+    let Ok(service_type) = service.parse::<ServiceType>() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!(
+                    "Unknown service '{service}': expected signaling, ais, ks, stun or turn"
+                )
+            })),
+        )
+            .into_response();
+    };
+
+    if let Err(e) = state.controller.set_enabled(service_type, enabled).await {
+        error!("运行时启停服务 {} 失败: {e:#}", service);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": format!("{e:#}") })),
+        )
+            .into_response();
+    }
+    statuses_response(&state.controller).await
+}
+
+async fn statuses_response(controller: &ServiceController) -> Response {
+    let services: Vec<_> = controller
+        .statuses()
+        .await
+        .into_iter()
+        .map(|(service, running)| json!({ "service": service, "running": running }))
+        .collect();
+    Json(json!({ "services": services })).into_response()
+}
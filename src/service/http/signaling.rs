@@ -2,7 +2,7 @@
 
 use crate::service::HttpRouterService;
 use actrix_common::config::ActrixConfig;
-use actrix_common::{ServiceInfo, ServiceType};
+use actrix_common::{ServiceInfo, ServiceState, ServiceType};
 use anyhow::Result;
 use async_trait::async_trait;
 use axum::{Router, routing::get};
@@ -13,6 +13,7 @@ use tracing::info;
 #[derive(Debug)]
 pub struct SignalingService {
     info: ServiceInfo,
+    route_prefix: String,
     config: ActrixConfig,
 }
 
@@ -25,6 +26,7 @@ impl SignalingService {
                 Some("WebRTC signaling service with WebSocket support".to_string()),
                 &config,
             ),
+            route_prefix: config.route_prefixes().signaling,
             config,
         }
     }
@@ -52,7 +54,18 @@ impl HttpRouterService for SignalingService {
         Ok(router)
     }
 
+    async fn on_stop(&mut self) -> Result<()> {
+        // 停用后旧连接不会再经过路由，需要主动断开，并注销管理接口使用的 SignalingServer
+        if let Some(server) = signaling::admin::registered_server() {
+            signaling::admin::disconnect_all(&server, "Signaling service stopped").await;
+        }
+        signaling::admin::unregister_server();
+        info!("HTTP router service '{}' stopped", self.info.name);
+        self.info.status = ServiceState::Unknown;
+        Ok(())
+    }
+
     fn route_prefix(&self) -> &str {
-        "/signaling"
+        &self.route_prefix
     }
 }
@@ -0,0 +1,124 @@
+//! 节点快照管理端点 (`POST /admin/snapshot`)
+//!
+//! 使用 `Authorization: Bearer <actrix_shared_key>` 认证，返回 `.tar.gz` 快照归档；
+//! 归档格式与恢复流程见 [`crate::snapshot`]。
+
+use crate::snapshot;
+use actrix_common::config::ActrixConfig;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use serde_json::json;
+use signaling::admin::{AdminAuth, AdminTokenSource};
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// 快照端点路径
+pub const SNAPSHOT_PATH: &str = "/admin/snapshot";
+
+/// 创建快照端点路由
+pub fn snapshot_router(config: &ActrixConfig) -> Router {
+    Router::new()
+        .route(SNAPSHOT_PATH, post(create_snapshot))
+        .with_state(Arc::new(SnapshotState {
+            config: config.clone(),
+        }))
+}
+
+struct SnapshotState {
+    config: ActrixConfig,
+}
+
+impl AdminTokenSource for SnapshotState {
+    fn admin_token(&self) -> Option<&str> {
+        Some(&self.config.actrix_shared_key)
+    }
+}
+
+async fn create_snapshot(_auth: AdminAuth, State(state): State<Arc<SnapshotState>>) -> Response {
+    let config = &state.config;
+    let file_name = format!(
+        "actrix-snapshot-{}-{}.tar.gz",
+        config.name,
+        chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    let output = std::env::temp_dir().join(format!("{}.tar.gz", uuid::Uuid::new_v4()));
+
+    let result = match snapshot::create_snapshot(config, &output).await {
+        Ok(_) => tokio::fs::read(&output).await.map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    let _ = tokio::fs::remove_file(&output).await;
+
+    match result {
+        Ok(bytes) => {
+            info!("📦 已通过管理端点导出快照 ({} bytes)", bytes.len());
+            (
+                [
+                    (header::CONTENT_TYPE, "application/gzip".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{file_name}\""),
+                    ),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("快照生成失败: {e:#}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": format!("{e:#}") })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    async fn post_snapshot(config: &ActrixConfig, authorization: Option<&str>) -> StatusCode {
+        let mut request = axum::http::Request::builder()
+            .method("POST")
+            .uri(SNAPSHOT_PATH);
+        if let Some(value) = authorization {
+            request = request.header(header::AUTHORIZATION, value);
+        }
+        snapshot_router(config)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_endpoint_requires_admin_token() {
+        let unconfigured = ActrixConfig {
+            actrix_shared_key: String::new(),
+            ..ActrixConfig::default()
+        };
+        assert_eq!(
+            post_snapshot(&unconfigured, Some("Bearer ")).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        let config = ActrixConfig {
+            actrix_shared_key: "secret".to_string(),
+            ..ActrixConfig::default()
+        };
+        assert_eq!(post_snapshot(&config, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            post_snapshot(&config, Some("Bearer secreT")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
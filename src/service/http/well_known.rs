@@ -0,0 +1,225 @@
+//! 服务发现文档 (`/.well-known/actrix-configuration`)
+//!
+//! 客户端只需一个 HTTPS 地址即可引导：返回 Signaling WebSocket 地址、AIS 注册地址、
+//! STUN/TURN 端点、支持的 envelope 版本以及功能开关。
+//!
+//! 双栈部署时按客户端连接的地址族选择宣告的 TURN 地址与 Signaling 地址，
+//! 因此文档按请求生成。
+
+use actrix_common::config::{
+    ActrixConfig,
+    bind::{advertised_ip_for, url_host},
+};
+use axum::{Json, Router, extract::ConnectInfo, routing::get};
+use serde_json::{Value, json};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use url::Url;
+
+/// 服务发现文档路径
+pub const WELL_KNOWN_PATH: &str = "/.well-known/actrix-configuration";
+
+/// 创建服务发现文档路由
+pub fn well_known_router(config: &ActrixConfig, public_url: &Url) -> Router {
+    let config = Arc::new(config.clone());
+    let public_url = public_url.clone();
+    Router::new().route(
+        WELL_KNOWN_PATH,
+        get(
+            move |ConnectInfo(addr): ConnectInfo<SocketAddr>| async move {
+                Json(discovery_document(&config, &public_url, Some(addr.ip())))
+            },
+        ),
+    )
+}
+
+/// 根据配置、对外地址与客户端地址生成服务发现文档
+///
+/// 未启用的服务对应字段为 `null`
+pub fn discovery_document(
+    config: &ActrixConfig,
+    public_url: &Url,
+    client_ip: Option<IpAddr>,
+) -> Value {
+    let http_base = public_url.as_str().trim_end_matches('/');
+    let is_tls = public_url.scheme() == "https";
+
+    // 与 public_url 对应的 HTTP/HTTPS 绑定的宣告地址
+    let http_advertised = if is_tls {
+        config
+            .bind
+            .https
+            .as_ref()
+            .map(|c| (c.advertised_ip.as_str(), c.advertised_ipv6.as_deref()))
+    } else {
+        config
+            .bind
+            .http
+            .as_ref()
+            .map(|c| (c.advertised_ip.as_str(), c.advertised_ipv6.as_deref()))
+    };
+
+    let signaling_config = config.services.signaling.as_ref();
+    let signaling = (config.is_signaling_enabled() && signaling_config.is_some()).then(|| {
+        json!({
+            "ws_url": config.signaling_ws_url(public_url),
+            "advertised_ip": http_advertised
+                .map(|(ipv4, ipv6)| advertised_ip_for(ipv4, ipv6, client_ip)),
+        })
+    });
+
+    let ais = (config.is_ais_enabled() && config.services.ais.is_some()).then(|| {
+        json!({
+            "register_url": format!("{http_base}{}/register", config.http.route_prefixes.ais),
+        })
+    });
+
+    let stun = config.is_stun_enabled().then(|| {
+        json!({
+            "urls": [config.stun_url()],
+        })
+    });
+
+    let turn = config.is_turn_enabled().then(|| {
+        let port = config.turn.advertised_port;
+        // 主机名端点在前（由客户端解析选择地址族），再附上与客户端地址族匹配的 IP 端点
+        let mut urls = Vec::new();
+        if let Some(ref hostname) = config.turn.advertised_hostname {
+            urls.push(format!("turn:{}:{port}?transport=udp", url_host(hostname)));
+        }
+        urls.push(format!(
+            "turn:{}:{port}?transport=udp",
+            url_host(config.turn.advertised_ip_for(client_ip))
+        ));
+        json!({
+            "urls": urls,
+            "realm": config.turn.realm,
+        })
+    });
+
+    let server_config = signaling_config.map(|c| &c.server);
+    let features = json!({
+        "header_token_auth": true,
+        "query_token_auth": server_config.is_none_or(|s| s.auth.allow_query_token),
+        "tls_channel_binding": is_tls,
+        "channel_binding_required": server_config.is_some_and(|s| s.auth.require_channel_binding),
+        "replay_protection": server_config.is_some_and(|s| s.replay_protection.enabled),
+        "max_envelope_bytes": server_config.map(|s| s.limits.max_envelope_bytes),
+    });
+
+    json!({
+        "issuer": http_base,
+        "version": env!("CARGO_PKG_VERSION"),
+        "envelope_versions": [signaling::ENVELOPE_VERSION],
+        "signaling": signaling,
+        "ais": ais,
+        "stun": stun,
+        "turn": turn,
+        "features": features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actrix_common::config::{ENABLE_SIGNALING, ENABLE_STUN, ENABLE_TURN};
+
+    #[test]
+    fn test_discovery_document_for_tls_deployment() {
+        let mut config = ActrixConfig::default();
+        config.enable = ENABLE_SIGNALING | ENABLE_STUN | ENABLE_TURN;
+        config.services.signaling = Some(Default::default());
+        config.services.ais = None;
+        let public_url = Url::parse("https://actrix.example.com:8443").unwrap();
+
+        let document = discovery_document(&config, &public_url, None);
+
+        assert_eq!(document["issuer"], "https://actrix.example.com:8443");
+        assert_eq!(
+            document["envelope_versions"],
+            json!([signaling::ENVELOPE_VERSION])
+        );
+        assert_eq!(
+            document["signaling"]["ws_url"],
+            "wss://actrix.example.com:8443/signaling/ws"
+        );
+        assert!(document["ais"].is_null());
+        assert_eq!(document["stun"]["urls"][0], "stun:localhost:3478");
+        assert_eq!(
+            document["turn"]["urls"][0],
+            "turn:127.0.0.1:3478?transport=udp"
+        );
+        assert_eq!(document["features"]["tls_channel_binding"], true);
+        assert_eq!(document["features"]["query_token_auth"], true);
+        assert_eq!(document["features"]["max_envelope_bytes"], 1024 * 1024);
+    }
+
+    #[test]
+    fn test_discovery_document_plain_http_uses_ws() {
+        let mut config = ActrixConfig::default();
+        config.enable = ENABLE_SIGNALING;
+        config.services.signaling = Some(Default::default());
+        let public_url = Url::parse("http://localhost:8080/").unwrap();
+
+        let document = discovery_document(&config, &public_url, None);
+
+        assert_eq!(document["issuer"], "http://localhost:8080");
+        assert_eq!(
+            document["signaling"]["ws_url"],
+            "ws://localhost:8080/signaling/ws"
+        );
+        assert!(document["stun"].is_null());
+        assert!(document["turn"].is_null());
+        assert_eq!(document["features"]["tls_channel_binding"], false);
+    }
+
+    #[test]
+    fn test_discovery_document_selects_address_family() {
+        let mut config = ActrixConfig::default();
+        config.enable = ENABLE_SIGNALING | ENABLE_TURN;
+        config.services.signaling = Some(Default::default());
+        config.turn.advertised_ip = "203.0.113.1".to_string();
+        config.turn.advertised_ipv6 = Some("2001:db8::1".to_string());
+        config.turn.advertised_hostname = Some("turn.example.com".to_string());
+        if let Some(ref mut https) = config.bind.https {
+            https.advertised_ip = "203.0.113.2".to_string();
+            https.advertised_ipv6 = Some("2001:db8::2".to_string());
+        }
+        let public_url = Url::parse("https://actrix.example.com").unwrap();
+
+        let v6 = discovery_document(&config, &public_url, Some("2001:db8::7".parse().unwrap()));
+        assert_eq!(
+            v6["turn"]["urls"],
+            json!([
+                "turn:turn.example.com:3478?transport=udp",
+                "turn:[2001:db8::1]:3478?transport=udp"
+            ])
+        );
+        assert_eq!(v6["signaling"]["advertised_ip"], "2001:db8::2");
+
+        let v4 = discovery_document(&config, &public_url, Some("198.51.100.7".parse().unwrap()));
+        assert_eq!(v4["turn"]["urls"][1], "turn:203.0.113.1:3478?transport=udp");
+        assert_eq!(v4["signaling"]["advertised_ip"], "203.0.113.2");
+    }
+
+    #[test]
+    fn test_discovery_document_advertise_overrides() {
+        let mut config = ActrixConfig::default();
+        config.enable = ENABLE_SIGNALING | ENABLE_STUN;
+        config.services.signaling = Some(Default::default());
+        config.advertise.signaling_ws_url = Some("wss://ws.example.com/signaling/ws".to_string());
+        config.advertise.stun_host = Some("64:ff9b::cb00:710a".to_string());
+        config.advertise.stun_port = Some(13478);
+        let public_url = Url::parse("https://edge.example.com").unwrap();
+
+        let document = discovery_document(&config, &public_url, None);
+        assert_eq!(
+            document["signaling"]["ws_url"],
+            "wss://ws.example.com/signaling/ws"
+        );
+        assert_eq!(
+            document["stun"]["urls"][0],
+            "stun:[64:ff9b::cb00:710a]:13478"
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //\! 管理ICE相关的服务
 //! ICE服务模块（STUN/TURN）
 
+mod socket;
 mod stun;
 mod turn;
 
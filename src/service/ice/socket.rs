@@ -0,0 +1,89 @@
+//! ICE UDP 套接字绑定
+//!
+//! `bind.ice.workers` 大于 1 时在同一地址上以 SO_REUSEPORT 打开多个套接字，
+//! 内核按四元组把同一客户端的报文固定分发到其中一个套接字，各套接字的接收循环互不影响。
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::net::SocketAddr;
+use tokio::net::UdpSocket;
+
+/// 在 `addr` 上绑定 `workers` 个 UDP 套接字（`workers` 大于 1 时启用 SO_REUSEPORT）
+///
+/// `v6_only` 为 true 时套接字仅接收 IPv6 流量（IPV6_V6ONLY），避免与 IPv4 通配地址的绑定冲突；
+/// 为 false 时 IPv6 地址显式关闭 IPV6_V6ONLY，`::` 上的套接字不受系统 bindv6only 设置影响，始终为双栈
+pub(super) fn bind_udp_sockets(
+    addr: SocketAddr,
+    workers: usize,
+    v6_only: bool,
+) -> std::io::Result<Vec<UdpSocket>> {
+    let reuse_port = workers > 1;
+    (0..workers.max(1))
+        .map(|_| bind_udp(addr, reuse_port, v6_only))
+        .collect()
+}
+
+fn bind_udp(addr: SocketAddr, reuse_port: bool, v6_only: bool) -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(v6_only)?;
+    }
+    #[cfg(unix)]
+    if reuse_port {
+        socket.set_reuse_port(true)?;
+    }
+    #[cfg(not(unix))]
+    let _ = reuse_port;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_single_socket() {
+        let sockets = bind_udp_sockets("127.0.0.1:0".parse().unwrap(), 1, false).unwrap();
+        assert_eq!(sockets.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_bind_reuse_port_workers() {
+        // 先取得一个空闲端口，再在该端口上绑定多个 SO_REUSEPORT 套接字
+        let port = std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let addr: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+
+        let sockets = bind_udp_sockets(addr, 4, false).unwrap();
+        assert_eq!(sockets.len(), 4);
+        for socket in &sockets {
+            assert_eq!(socket.local_addr().unwrap(), addr);
+        }
+
+        // 未启用 SO_REUSEPORT 的套接字无法再绑定同一端口
+        assert!(bind_udp_sockets(addr, 1, false).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dual_stack_socket_accepts_ipv4() {
+        // 沙箱等环境可能没有 IPv6 支持
+        let Ok(mut sockets) = bind_udp_sockets("[::]:0".parse().unwrap(), 1, false) else {
+            return;
+        };
+        let server = sockets.remove(0);
+        let port = server.local_addr().unwrap().port();
+
+        let client = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.send_to(b"ping", ("127.0.0.1", port)).await.unwrap();
+
+        let mut buf = [0u8; 8];
+        let (n, src) = server.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"ping");
+        assert_eq!(src.ip().to_canonical(), client.local_addr().unwrap().ip());
+    }
+}
@@ -1,15 +1,17 @@
 //! STUN服务实现
 
+use super::socket::bind_udp_sockets;
 use crate::service::IceService;
 use actrix_common::config::ActrixConfig;
 use actrix_common::status::services::ServiceState;
+use actrix_common::util::NetworkEmulator;
 use actrix_common::{ServiceInfo, ServiceType};
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
 use stun;
 use tokio::net::UdpSocket;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use url::Url;
 
 /// STUN服务实现
@@ -51,15 +53,16 @@ impl IceService for StunService {
         oneshot_tx: tokio::sync::oneshot::Sender<ServiceInfo>,
     ) -> Result<()> {
         let ice_bind = &self.config.bind.ice;
-        let addr = format!("{}:{}", ice_bind.ip, ice_bind.port);
+        let addr = ice_bind.socket_addr().map_err(|e| anyhow::anyhow!(e))?;
+        let workers = ice_bind.effective_workers();
 
-        info!("Starting STUN service on {}", addr);
+        info!("Starting STUN service on {} ({} workers)", addr, workers);
 
-        // 绑定UDP套接字
-        let socket = match UdpSocket::bind(&addr).await {
-            Ok(socket) => {
+        // 绑定UDP套接字（多个工作线程时每个线程一个 SO_REUSEPORT 套接字）
+        let mut sockets: Vec<Arc<UdpSocket>> = match bind_udp_sockets(addr, workers, false) {
+            Ok(sockets) => {
                 info!("STUN service listening on: {}", addr);
-                Arc::new(socket)
+                sockets.into_iter().map(Arc::new).collect()
             }
             Err(e) => {
                 let error_msg = format!("Failed to bind STUN service to {addr}: {e}");
@@ -68,19 +71,73 @@ impl IceService for StunService {
             }
         };
 
-        self.socket = Some(socket.clone());
+        self.socket = sockets.first().cloned();
 
-        // 设置运行状态
-        let url = Url::parse(&format!("stun:{}:{}", ice_bind.domain_name, ice_bind.port))?;
+        // 双栈：IPv4 绑定之外配置了 bind.ice.ipv6 时额外监听仅 IPv6 的套接字
+        if ice_bind.ipv6.is_some()
+            && let Some(ipv6_addr) = ice_bind
+                .ipv6_socket_addr()
+                .map_err(|e| anyhow::anyhow!(e))?
+        {
+            match bind_udp_sockets(ipv6_addr, workers, true) {
+                Ok(ipv6_sockets) => {
+                    info!("STUN service listening on: {}", ipv6_addr);
+                    sockets.extend(ipv6_sockets.into_iter().map(Arc::new));
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to bind STUN service to {ipv6_addr}: {e}");
+                    self.info.set_error(&error_msg);
+                    return Err(anyhow::anyhow!(error_msg));
+                }
+            }
+        }
+
+        // 设置运行状态（上报对外宣告的端点）
+        let url = Url::parse(&self.config.stun_url())?;
         self.info.set_running(url);
         oneshot_tx
             .send(self.info.clone())
             .map_err(|e| anyhow::anyhow!("Failed to send STUN service info: {e:?}"))?;
         info!("STUN service started successfully");
 
-        // 启动STUN服务器（带优雅关闭支持）
-        if let Err(e) = stun::create_stun_server_with_shutdown(socket.clone(), shutdown_rx).await {
-            let error_msg = format!("STUN server stopped with error: {e}");
+        // 开发环境弱网模拟（可选）
+        let network_emulation = &self.config.dev.network_emulation;
+        let emulator = network_emulation
+            .stun
+            .then(|| NetworkEmulator::from_config(network_emulation))
+            .flatten();
+        if emulator.is_some() {
+            warn!(
+                "⚠️ STUN network emulation enabled: latency={}ms jitter={}ms drop_rate={}",
+                network_emulation.latency_ms,
+                network_emulation.jitter_ms,
+                network_emulation.drop_rate
+            );
+        }
+
+        // 启动STUN服务器（带优雅关闭支持），每个套接字一个接收循环
+        let mut receive_loops = tokio::task::JoinSet::new();
+        for socket in sockets {
+            receive_loops.spawn(stun::create_stun_server_with_emulation(
+                socket,
+                shutdown_rx.resubscribe(),
+                emulator.clone(),
+            ));
+        }
+        drop(shutdown_rx);
+
+        // 任一接收循环出错时停止整个服务
+        let mut failure = None;
+        while let Some(result) = receive_loops.join_next().await {
+            let error_msg = match result {
+                Ok(Ok(())) => continue,
+                Ok(Err(e)) => format!("STUN server stopped with error: {e}"),
+                Err(e) => format!("STUN receive loop panicked: {e}"),
+            };
+            receive_loops.abort_all();
+            failure.get_or_insert(error_msg);
+        }
+        if let Some(error_msg) = failure {
             self.info.set_error(&error_msg);
             error!("{}", error_msg);
         } else {
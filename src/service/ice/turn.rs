@@ -1,5 +1,6 @@
 //! TURN服务实现
 
+use super::socket::bind_udp_sockets;
 use crate::service::IceService;
 use actrix_common::config::ActrixConfig;
 use actrix_common::status::services::ServiceState;
@@ -50,15 +51,16 @@ impl IceService for TurnService {
         oneshot_tx: tokio::sync::oneshot::Sender<ServiceInfo>,
     ) -> Result<()> {
         let ice_bind = &self.config.bind.ice;
-        let addr = format!("{}:{}", ice_bind.ip, ice_bind.port);
+        let addr = ice_bind.socket_addr().map_err(|e| anyhow::anyhow!(e))?;
+        let workers = ice_bind.effective_workers();
 
-        info!("Starting TURN service on {}", addr);
+        info!("Starting TURN service on {} ({} workers)", addr, workers);
 
-        // 绑定UDP套接字
-        let socket = match UdpSocket::bind(&addr).await {
-            Ok(socket) => {
+        // 绑定UDP套接字（多个工作线程时每个线程一个 SO_REUSEPORT 套接字）
+        let sockets: Vec<Arc<UdpSocket>> = match bind_udp_sockets(addr, workers, false) {
+            Ok(sockets) => {
                 info!("TURN service listening on: {}", addr);
-                Arc::new(socket)
+                sockets.into_iter().map(Arc::new).collect()
             }
             Err(e) => {
                 let error_msg = format!("Failed to bind TURN service to {addr}: {e}");
@@ -67,28 +69,67 @@ impl IceService for TurnService {
             }
         };
 
-        self.socket = Some(socket.clone());
+        self.socket = sockets.first().cloned();
+
+        let mut listeners: Vec<turn::TurnListener> = sockets
+            .into_iter()
+            .map(|socket| turn::TurnListener {
+                socket,
+                advertised_ip: self.config.turn.advertised_ip.clone(),
+            })
+            .collect();
+
+        // 双栈：配置了 IPv6 宣告地址时额外绑定仅 IPv6 的套接字（`ip` 本身为 IPv6 时由配置校验拒绝）
+        if let Some(ref advertised_ipv6) = self.config.turn.advertised_ipv6
+            && let Some(ipv6_addr) = ice_bind
+                .ipv6_socket_addr()
+                .map_err(|e| anyhow::anyhow!(e))?
+        {
+            match bind_udp_sockets(ipv6_addr, workers, true) {
+                Ok(ipv6_sockets) => {
+                    info!(
+                        "TURN service listening on: {} (advertised: {})",
+                        ipv6_addr, advertised_ipv6
+                    );
+                    listeners.extend(ipv6_sockets.into_iter().map(|socket| turn::TurnListener {
+                        socket: Arc::new(socket),
+                        advertised_ip: advertised_ipv6.clone(),
+                    }));
+                }
+                Err(e) => {
+                    let error_msg = format!("Failed to bind TURN service to {ipv6_addr}: {e}");
+                    self.info.set_error(&error_msg);
+                    return Err(anyhow::anyhow!(error_msg));
+                }
+            }
+        }
 
         // 创建TURN服务器
         let realm = self.config.turn.realm.clone();
         let auth_handler = Arc::new(
-            turn::Authenticator::new()
-                .map_err(|e| anyhow::anyhow!("Failed to create TURN authenticator: {e}"))?,
+            turn::Authenticator::with_allowed_realms(self.config.turn.allowed_realm_ids.clone())
+                .map_err(|e| anyhow::anyhow!("Failed to create TURN authenticator: {e}"))?
+                // 接受 AIS 以 AIdCredential 换发的限定范围凭证（/ais/turn-credential）
+                .with_credential_secret(self.config.get_actrix_shared_key()),
         );
+        turn::Authenticator::register(auth_handler.clone());
 
-        let turn_server = match turn::create_turn_server(
-            socket.clone(),
-            &self.config.turn.advertised_ip,
+        let relay_port_range = self
+            .config
+            .turn
+            .relay_port_bounds()
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let turn_server = match turn::create_turn_server_with_listeners(
+            listeners,
             &realm,
+            relay_port_range,
             auth_handler,
         )
         .await
         {
             Ok(server) => {
-                let url = Url::parse(&format!(
-                    "turn:{}:{}?transport=udp",
-                    ice_bind.domain_name, ice_bind.port
-                ))?;
+                let url = Url::parse(&self.config.turn_url())?;
                 self.info.set_running(url);
                 oneshot_tx
                     .send(self.info.clone())
@@ -103,9 +144,17 @@ impl IceService for TurnService {
             }
         };
 
-        // 等待关闭信号
-        let _ = shutdown_rx.recv().await;
+        // 等待关闭信号，期间定期刷新分配指标
+        let mut allocation_metrics = turn::AllocationMetrics::new();
+        let mut metrics_interval = tokio::time::interval(turn::ALLOCATION_METRICS_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = metrics_interval.tick() => allocation_metrics.refresh(&turn_server).await,
+                _ = shutdown_rx.recv() => break,
+            }
+        }
         info!("TURN service received shutdown signal");
+        allocation_metrics.reset();
 
         // 关闭TURN服务器
         if let Err(e) = turn::shutdown_turn_server(&turn_server).await {
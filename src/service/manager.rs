@@ -3,19 +3,27 @@
 //\! 实现了服务的启动、停止和管理逻辑
 //! 服务管理器模块 - 负责管理多个服务的生命周期
 
-use super::{HttpRouterService, IceService};
 use crate::service::container::ServiceContainer;
+use crate::service::control::ServiceController;
+use crate::service::http::{
+    health, listener, metrics, middleware, redirect, services, snapshot, well_known,
+};
+use crate::service::reload::ConfigReloader;
+use crate::service::restart::RestartCoordinator;
+use crate::service::systemd;
+use crate::service::tls::ChannelBindingAcceptor;
 use actrix_common::{
-    ServiceCollector, ServiceInfo, ServiceType, TlsConfigurer, config::ActrixConfig,
+    ServiceCollector, ServiceInfo, ServiceState, TlsConfigurer, config::ActrixConfig,
 };
 use anyhow::Result;
 use axum::Router;
 use axum_server::tls_rustls::RustlsConfig;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Notify;
 use tokio::task::JoinHandle;
 use tracing::{error, info, warn};
-use url::Url;
 
 /// 服务管理器，负责管理多个服务的生命周期
 #[derive(Debug)]
@@ -23,16 +31,26 @@ pub struct ServiceManager {
     services: Vec<ServiceContainer>,
     shutdown_tx: tokio::sync::broadcast::Sender<()>,
     service_collector: ServiceCollector,
+    controller: ServiceController,
+    restart: RestartCoordinator,
     config: ActrixConfig,
 }
 
 impl ServiceManager {
     /// 创建新的服务管理器
     pub fn new(config: ActrixConfig, shutdown_tx: tokio::sync::broadcast::Sender<()>) -> Self {
+        let service_collector = ServiceCollector::new();
+        let controller = ServiceController::new(
+            config.clone(),
+            shutdown_tx.clone(),
+            service_collector.clone(),
+        );
         Self {
             services: Vec::new(),
+            restart: RestartCoordinator::new(shutdown_tx.clone()),
             shutdown_tx,
-            service_collector: ServiceCollector::new(),
+            service_collector,
+            controller,
             config,
         }
     }
@@ -67,8 +85,8 @@ impl ServiceManager {
         Ok(())
     }
 
-    /// 启动所有服务
-    pub async fn start_all(&mut self) -> Result<Vec<JoinHandle<()>>> {
+    /// 启动所有服务，返回以任务名标识的后台任务句柄
+    pub async fn start_all(&mut self) -> Result<Vec<(String, JoinHandle<()>)>> {
         info!(
             "Starting all {} types ({}) services.",
             self.services.len(),
@@ -94,23 +112,55 @@ impl ServiceManager {
         let notify = Arc::new(Notify::new());
         let notify_clone = notify.clone();
         let mut handle_futs = Vec::new();
+        // 独立端口导出 Prometheus 指标，不依赖 HTTP 路由服务（如仅运行 STUN/TURN 的节点）
+        let metrics_config = &self.config.observability.metrics;
+        if metrics_config.enabled && metrics_config.bind.is_some() {
+            let probes = health::health_router(&self.config, self.service_collector.clone());
+            handle_futs.push((
+                "metrics".to_string(),
+                metrics::serve_metrics(metrics_config, probes, self.shutdown_tx.clone()).await?,
+            ));
+        } else if metrics_config.enabled && http_services.is_empty() {
+            warn!(
+                "No HTTP route services enabled, {} is not exposed; set observability.metrics.bind to export metrics",
+                metrics_config.path
+            );
+        }
+
         // 启动HTTP服务器（合并所有HTTP路由服务）
         if !http_services.is_empty() {
             let handle = self
                 .start_http_services(http_services, notify_clone)
                 .await?;
-            handle_futs.push(handle);
+            handle_futs.push(("http-server".to_string(), handle));
             notify.notified().await;
+
+            // HTTP 监听器只做 HTTPS 重定向
+            if let Some((http, https)) = redirect::redirect_binds(&self.config) {
+                handle_futs.push((
+                    "http-redirect".to_string(),
+                    redirect::serve_redirect(http, https, self.shutdown_tx.clone()).await?,
+                ));
+            } else if self
+                .config
+                .bind
+                .http
+                .as_ref()
+                .is_some_and(|http| http.redirect_to_https)
+            {
+                warn!(
+                    "bind.http.redirect_to_https is ignored: the main server is not running on HTTPS (environment: {})",
+                    self.config.env
+                );
+            }
         }
-        let notify_clone = notify.clone();
 
         // 启动ICE服务
         for service in ice_services {
-            let handle = self
-                .start_ice_service(service, notify_clone.clone())
-                .await?;
-            handle_futs.push(handle);
-            notify.notified().await;
+            let service_name = service.info().name.clone();
+            if let Some(handle) = self.controller.start(service).await? {
+                handle_futs.push((service_name, handle));
+            }
         }
 
         let services = self.service_collector.values().await;
@@ -123,7 +173,7 @@ impl ServiceManager {
     /// 启动HTTP服务器，合并所有HTTP路由服务
     async fn start_http_services(
         &mut self,
-        mut services: Vec<ServiceContainer>,
+        services: Vec<ServiceContainer>,
         notify: Arc<Notify>,
     ) -> Result<JoinHandle<()>> {
         let is_dev = self.config.env.to_lowercase() == "dev";
@@ -137,29 +187,19 @@ impl ServiceManager {
         );
 
         // 确定绑定配置
-        let (bind_addr, public_url, tls_config) = if is_dev {
+        let (bind_addr, tls_config) = if is_dev {
             // 开发环境优先使用HTTP，如果没有则使用HTTPS
             if let Some(ref http_config) = self.config.bind.http {
-                let bind_addr = format!("{}:{}", http_config.ip, http_config.port);
-                let public_url = Url::parse(&format!(
-                    "http://{}:{}",
-                    http_config.domain_name, http_config.port
-                ))
-                .map_err(|e| anyhow::anyhow!("Failed to parse HTTP URL: {e}"))?;
-                (bind_addr, public_url, None)
+                let bind_addr = http_config.socket_addr().map_err(|e| anyhow::anyhow!(e))?;
+                (bind_addr, None)
             } else if let Some(ref https_config) = self.config.bind.https {
-                let bind_addr = format!("{}:{}", https_config.ip, https_config.port);
-                let public_url = Url::parse(&format!(
-                    "https://{}:{}",
-                    https_config.domain_name, https_config.port
-                ))
-                .map_err(|e| anyhow::anyhow!("Failed to parse HTTPS URL: {e}"))?;
+                let bind_addr = https_config.socket_addr().map_err(|e| anyhow::anyhow!(e))?;
 
                 // 初始化加密提供程序
                 TlsConfigurer::install_crypto_provider();
                 let tls_config =
                     Some(RustlsConfig::from_pem_file(&https_config.cert, &https_config.key).await?);
-                (bind_addr, public_url, tls_config)
+                (bind_addr, tls_config)
             } else {
                 return Err(anyhow::anyhow!(
                     "No HTTP or HTTPS binding configuration found"
@@ -168,95 +208,90 @@ impl ServiceManager {
         } else {
             // 生产环境必须使用HTTPS
             if let Some(ref https_config) = self.config.bind.https {
-                let bind_addr = format!("{}:{}", https_config.ip, https_config.port);
-                let public_url = Url::parse(&format!(
-                    "https://{}:{}",
-                    https_config.domain_name, https_config.port
-                ))
-                .map_err(|e| anyhow::anyhow!("Failed to parse HTTPS URL: {e}"))?;
+                let bind_addr = https_config.socket_addr().map_err(|e| anyhow::anyhow!(e))?;
 
                 // 初始化加密提供程序
                 TlsConfigurer::install_crypto_provider();
                 let tls_config =
                     Some(RustlsConfig::from_pem_file(&https_config.cert, &https_config.key).await?);
-                (bind_addr, public_url, tls_config)
+                (bind_addr, tls_config)
             } else {
                 return Err(anyhow::anyhow!(
                     "HTTPS binding configuration is required for production environment"
                 ));
             }
         };
+        // 对外发布的地址可能被 advertise.public_url 覆盖（负载均衡、端口转发之后）
+        let public_url = self.config.public_url().map_err(|e| anyhow::anyhow!(e))?;
 
-        // 构建合并的路由器
-        let mut app = Router::new();
-        let mut http_services_info = Vec::new();
+        // 构建合并的路由器：各 HTTP 路由服务挂载在可替换的插槽上，支持运行时启停
+        let mut app = self
+            .controller
+            .mount_slots(Router::new(), public_url.clone());
 
         // 添加 HTTP 追踪层（支持 OpenTelemetry 上下文传播）
         use crate::service::trace::http_trace_layer;
-        use tower_http::cors::CorsLayer;
-
-        for service in &mut services {
-            let route_prefix = match service.route_prefix() {
-                Some(prefix) => prefix.to_string(),
-                None => continue,
-            };
 
+        for service in services {
             let service_name = service.info().name.clone();
-
-            let router_result = match service.build_router().await {
-                Some(result) => result,
-                None => continue,
-            };
-
-            match router_result {
-                Ok(router) => {
-                    info!(
-                        "Adding route '{}' for service '{}'",
-                        route_prefix, service_name
-                    );
-                    app = app.nest(&route_prefix, router);
-
-                    // 记录服务信息用于后续状态更新
-                    http_services_info.push((service_name.clone(), route_prefix.clone()));
-
-                    // 调用 on_start 回调
-                    let start_result = match service.on_start(public_url.clone()).await {
-                        Some(result) => {
-                            // 更新服务信息到收集器
-                            self.service_collector
-                                .insert(service_name.clone(), service.info().clone())
-                                .await;
-                            result
-                        }
-                        None => Ok(()),
-                    };
-
-                    if let Err(e) = start_result {
-                        error!("Failed to start service '{}': {:?}", service_name, e);
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to build router for service '{}': {:?}",
-                        service_name, e
-                    );
-                }
+            if let Err(e) = self.controller.start(service).await {
+                error!("Failed to start service '{}': {:?}", service_name, e);
             }
         }
 
-        // 添加全局 Prometheus metrics 端点
-        info!("Adding /metrics endpoint for Prometheus");
-        app = app.route("/metrics", axum::routing::get(metrics_handler));
+        // 添加全局 Prometheus metrics 端点（配置独立监听地址时不挂载到主路由）
+        let metrics_config = &self.config.observability.metrics;
+        if metrics_config.enabled && metrics_config.bind.is_none() {
+            info!("Adding {} endpoint for Prometheus", metrics_config.path);
+            app = app.merge(metrics::metrics_router(metrics_config));
+        }
+
+        // 添加 Kubernetes 存活/就绪探针端点
+        info!(
+            "Adding {} and {} probe endpoints",
+            health::HEALTHZ_PATH,
+            health::READYZ_PATH
+        );
+        app = app.merge(health::health_router(
+            &self.config,
+            self.service_collector.clone(),
+        ));
+
+        // 添加服务发现文档端点
+        info!("Adding {} discovery document", well_known::WELL_KNOWN_PATH);
+        app = app.merge(well_known::well_known_router(&self.config, &public_url));
+
+        // 添加节点快照管理端点
+        info!("Adding {} snapshot endpoint", snapshot::SNAPSHOT_PATH);
+        app = app.merge(snapshot::snapshot_router(&self.config));
+
+        // 添加运行时服务启停管理端点
+        info!(
+            "Adding {} service control endpoint",
+            services::SERVICES_PATH
+        );
+        app = app.merge(services::services_router(
+            &self.config,
+            self.controller.clone(),
+        ));
 
         // 添加全局中间件层
-        app = app
-            .layer(http_trace_layer()) // HTTP 追踪（包含 OpenTelemetry 上下文传播）
-            .layer(CorsLayer::permissive()); // CORS 支持
+        app = app.layer(http_trace_layer()); // HTTP 追踪（包含 OpenTelemetry 上下文传播）
+        app = middleware::apply_http_middleware(
+            app,
+            &self.config.http,
+            &self.config.route_prefixes(),
+        ); // CORS 与安全响应头
+
+        // HTTP 重定向到 HTTPS 时，要求浏览器此后直接使用 HTTPS
+        if redirect::redirect_binds(&self.config).is_some() {
+            app = app.layer(axum::middleware::map_response(redirect::add_hsts));
+        }
 
-        // 启动服务器
-        let addr: std::net::SocketAddr = bind_addr
-            .parse()
-            .map_err(|e| anyhow::anyhow!("Invalid bind address '{bind_addr}': {e}"))?;
+        // 启动服务器（绑定 `::` 时为双栈监听）
+        let addr = bind_addr;
+        let listener = listener::bind_tcp_listener(addr)
+            .map_err(|e| anyhow::anyhow!("Failed to bind to address '{addr}': {e}"))?;
 
         info!("{} server listening on {}", protocol, addr);
         notify.notify_one();
@@ -264,7 +299,9 @@ impl ServiceManager {
         let shutdown_tx = self.shutdown_tx.clone();
         let fut = if let Some(tls_config) = tls_config {
             // 启动HTTPS服务器
-            let server = axum_server::bind_rustls(addr, tls_config)
+            // 使用携带 TLS 通道绑定的 acceptor，供 Signaling 校验 token 绑定
+            let server = axum_server::from_tcp(listener)
+                .acceptor(ChannelBindingAcceptor::new(tls_config))
                 .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>());
             tokio::spawn(async move {
                 let mut shutdown_rx = shutdown_tx.subscribe();
@@ -283,8 +320,7 @@ impl ServiceManager {
             })
         } else {
             // 启动HTTP服务器
-            let listener = tokio::net::TcpListener::bind(addr)
-                .await
+            let listener = tokio::net::TcpListener::from_std(listener)
                 .map_err(|e| anyhow::anyhow!("Failed to bind to address '{addr}': {e}"))?;
 
             tokio::spawn(async move {
@@ -308,72 +344,19 @@ impl ServiceManager {
         Ok(fut)
     }
 
-    /// 启动单个ICE服务
-    async fn start_ice_service(
-        &mut self,
-        service: ServiceContainer,
-        notify: Arc<Notify>,
-    ) -> Result<JoinHandle<()>> {
-        let shutdown_rx = self.shutdown_tx.subscribe();
-        let shutdown_tx = self.shutdown_tx.clone();
-        let service_name = service.info().name.clone();
-        let bind_addr = self.config.bind.ice.domain_name.clone();
-        let config = self.config.clone();
-
-        match service {
-            ServiceContainer::Stun(mut s) => {
-                let (tx, rx) = tokio::sync::oneshot::channel::<ServiceInfo>();
-                let handle = tokio::spawn(async move {
-                    if let Err(e) = s.start(shutdown_rx, tx).await {
-                        error!("Failed to start STUN service: {:?}", e);
-                        let _ = shutdown_tx.send(());
-                    }
-                });
-                let info = rx
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to receive STUN service info: {e}"))?;
-                self.service_collector.insert(info.name.clone(), info).await;
-                notify.notify_one();
-                Ok(handle)
-            }
-            ServiceContainer::Turn(mut s) => {
-                let (tx, rx) = tokio::sync::oneshot::channel::<ServiceInfo>();
-                let handle = tokio::spawn(async move {
-                    if let Err(e) = s.start(shutdown_rx, tx).await {
-                        error!("Failed to start TURN service: {:?}", e);
-                        let _ = shutdown_tx.send(());
-                    }
-                });
-                let info = rx
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to receive TURN service info: {e}"))?;
-
-                self.service_collector
-                    .insert(info.name.clone(), info.clone())
-                    .await;
-                // turn 服务需要注册两个服务，一个是turn，一个是stun
-
-                let mut stun_info =
-                    ServiceInfo::new("STUN Server", ServiceType::Stun, None, &config);
+    /// 创建配置热加载器，以启动时的配置为基准比较后续的配置文件
+    pub fn config_reloader(&self, config_path: impl Into<PathBuf>) -> ConfigReloader {
+        ConfigReloader::new(config_path.into(), self.config.clone())
+    }
 
-                stun_info.set_running(
-                    Url::parse(&format!("stun:{}:{}", bind_addr, info.port_info))
-                        .map_err(|e| anyhow::anyhow!("Failed to parse STUN URL: {e}"))?,
-                );
+    /// 运行时服务启停句柄（供 Supervisord 与管理端点使用）
+    pub fn controller(&self) -> ServiceController {
+        self.controller.clone()
+    }
 
-                self.service_collector
-                    .insert(stun_info.name.clone(), stun_info)
-                    .await;
-                notify.notify_one();
-                Ok(handle)
-            }
-            _ => {
-                error!("Invalid service type for ICE service: {}", service_name);
-                Err(anyhow::anyhow!(
-                    "Invalid service type for ICE service: {service_name}"
-                ))
-            }
-        }
+    /// 重启请求句柄（自更新安装完成后使用）
+    pub fn restart_coordinator(&self) -> RestartCoordinator {
+        self.restart.clone()
     }
 
     /// Return service registry handle for accessing service statuses
@@ -381,27 +364,73 @@ impl ServiceManager {
         self.service_collector.clone()
     }
 
+    /// systemd 看门狗与关闭通知
+    ///
+    /// 启用看门狗时按周期检查已登记服务的状态，全部正常才发送心跳；
+    /// 收到关闭信号后发送 `STOPPING=1` 并退出；自更新重启（同一 PID 重新执行）时不发送，
+    /// 以免 systemd 进入停止流程并在 `TimeoutStopSec` 后杀掉重新执行的进程
+    pub fn spawn_systemd_watchdog(
+        &self,
+        mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+    ) -> JoinHandle<()> {
+        let collector = self.service_collector.clone();
+        let restart = self.restart.clone();
+        let interval = systemd::watchdog_interval();
+        tokio::spawn(async move {
+            if let Some(interval) = interval {
+                info!("systemd watchdog enabled, pinging every {:?}", interval);
+            }
+            let mut ticker = tokio::time::interval(interval.unwrap_or(Duration::from_secs(3600)));
+            let mut unhealthy = false;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick(), if interval.is_some() => {
+                        let failed: Vec<String> = collector
+                            .values()
+                            .await
+                            .into_iter()
+                            .filter(|info| matches!(info.status, ServiceState::Error(_)))
+                            .map(|info| info.name)
+                            .collect();
+                        if failed.is_empty() {
+                            if unhealthy {
+                                info!("所有服务已恢复，继续发送 systemd 看门狗心跳");
+                                systemd::notify_status("Running");
+                                unhealthy = false;
+                            }
+                            systemd::notify_watchdog();
+                        } else if !unhealthy {
+                            warn!(
+                                "⚠️  服务异常，停止发送 systemd 看门狗心跳: {}",
+                                failed.join(", ")
+                            );
+                            systemd::notify_status(&format!("Unhealthy: {}", failed.join(", ")));
+                            unhealthy = true;
+                        }
+                    }
+                    _ = shutdown_rx.recv() => {
+                        match restart.pending() {
+                            Some(reason) => systemd::notify_status(&format!("Restarting: {reason}")),
+                            None => systemd::notify_stopping(),
+                        }
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
     /// Stop all services
     pub async fn stop_all(&mut self) -> Result<()> {
         info!("Stopping all services");
 
         let _ = self.shutdown_tx.send(());
         for service in &mut self.services {
-            match service {
-                ServiceContainer::Signaling(s) => s.on_stop().await.unwrap(),
-                ServiceContainer::Ais(s) => s.on_stop().await.unwrap(),
-                ServiceContainer::Stun(s) => s.stop().await.unwrap(),
-                ServiceContainer::Turn(s) => s.stop().await.unwrap(),
-                ServiceContainer::Ks(s) => s.on_stop().await.unwrap(),
-            }
+            service.on_stop().await?;
         }
+        self.controller.stop_all().await;
 
         info!("All services stopped");
         Ok(())
     }
 }
-
-/// Prometheus metrics endpoint handler
-async fn metrics_handler() -> String {
-    actrix_common::metrics::export_metrics()
-}
@@ -11,12 +11,22 @@
 //! - `IceService`: ICE服务的核心 trait，独立的 UDP 服务器
 //! - `ServiceInfo`: 服务的基本信息
 //! - `ServiceManager`: 服务管理器，负责管理多个服务的生命周期
+//! - `ServiceController`: 运行时服务启停句柄（Supervisor 指令与管理端点）
+//! - `RestartCoordinator`: 自更新后的优雅重启请求
+//! - `systemd`: sd_notify 就绪、看门狗与关闭通知
 
+pub mod capabilities;
 pub mod container;
+pub mod control;
 pub mod grpc;
 pub mod http;
 pub mod ice;
 pub mod manager;
+pub mod reload;
+pub mod restart;
+pub mod shutdown;
+pub mod systemd;
+pub mod tls;
 pub mod trace;
 
 use actrix_common::{ServiceInfo, ServiceState};
@@ -33,8 +43,13 @@ pub use http::{AisService, KsHttpService, SignalingService};
 pub use ice::{StunService, TurnService};
 
 // 重新导出核心组件
+pub use capabilities::node_capabilities;
 pub use container::ServiceContainer;
+pub use control::ServiceController;
 pub use manager::ServiceManager;
+pub use reload::{ConfigReloader, ReloadReport};
+pub use restart::RestartCoordinator;
+pub use shutdown::{ServiceTasks, ShutdownReport};
 
 /// HTTP路由服务的核心 trait - 为 axum 提供路由器
 #[async_trait]
@@ -0,0 +1,401 @@
+//! 配置热加载
+//!
+//! 收到 SIGHUP 或检测到配置文件修改（`reload.watch = true`）时重新加载配置文件，
+//! 校验通过后只应用可在运行时安全生效的字段：
+//!
+//! - `observability.filter_level`：替换全局日志过滤器（设置了 RUST_LOG 时不生效）
+//! - `services.signaling.server.rate_limit`：更新 Signaling 连接、消息与 Realm 限流配额
+//! - `turn.allowed_realm_ids`：更新 TURN 允许使用中继的 Realm 范围
+//!
+//! 其余字段（监听地址、启用的服务、密钥等）的变化只记录为需要重启，
+//! 在重启前的每次重新加载中都会再次报告。校验失败的配置整体不生效。
+//!
+//! Signaling 速率限制也可通过管理 API `PATCH /admin/rate-limits` 在运行时调整
+//! （见 `signaling::admin::update_rate_limits`），两者经同一路径应用；配置文件中的
+//! `rate_limit` 之后发生变化时，重新加载会以配置文件为准覆盖运行时的调整。
+
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// 日志过滤级别
+const FILTER_LEVEL_FIELD: &str = "observability.filter_level";
+
+/// Signaling 速率限制
+const RATE_LIMIT_FIELD: &str = "services.signaling.server.rate_limit";
+
+/// TURN 允许使用中继的 Realm 范围
+const TURN_REALMS_FIELD: &str = "turn.allowed_realm_ids";
+
+/// 可在运行时生效的字段（包括其子字段）
+const RELOADABLE_FIELDS: &[&str] = &[FILTER_LEVEL_FIELD, RATE_LIMIT_FIELD, TURN_REALMS_FIELD];
+
+/// 替换日志过滤级别的回调
+type LogFilterReloader = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
+/// 一次重新加载的结果
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// 已在运行时生效的字段
+    pub applied: Vec<String>,
+    /// 已变化但需要重启才能生效的字段
+    pub restart_required: Vec<String>,
+}
+
+impl ReloadReport {
+    /// 配置是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.restart_required.is_empty()
+    }
+}
+
+/// 配置热加载器
+pub struct ConfigReloader {
+    config_path: PathBuf,
+    /// 当前生效的配置（需要重启的字段保持启动时的值）
+    current: ActrixConfig,
+    log_filter: Option<LogFilterReloader>,
+}
+
+impl ConfigReloader {
+    pub fn new(config_path: PathBuf, current: ActrixConfig) -> Self {
+        Self {
+            config_path,
+            current,
+            log_filter: None,
+        }
+    }
+
+    /// 设置替换日志过滤级别的回调，未设置时日志级别的变化需要重启
+    pub fn with_log_filter<F>(mut self, reload: F) -> Self
+    where
+        F: Fn(&str) -> Result<()> + Send + Sync + 'static,
+    {
+        self.log_filter = Some(Box::new(reload));
+        self
+    }
+
+    /// 重新加载配置文件并应用可在运行时生效的字段
+    pub async fn reload(&mut self) -> Result<ReloadReport> {
+        let config = ActrixConfig::from_file(&self.config_path)
+            .map_err(|e| anyhow::anyhow!("Failed to load {}: {e}", self.config_path.display()))?;
+
+        if let Err(errors) = config.validate() {
+            let critical: Vec<String> = errors
+                .into_iter()
+                .filter(|e| !e.starts_with("Warning:"))
+                .collect();
+            if !critical.is_empty() {
+                bail!("Configuration validation failed: {}", critical.join("; "));
+            }
+        }
+
+        self.apply(config).await
+    }
+
+    /// 与当前配置比较并应用可在运行时生效的字段
+    pub async fn apply(&mut self, config: ActrixConfig) -> Result<ReloadReport> {
+        let mut report = ReloadReport::default();
+        let (mut reloadable, restart_required): (Vec<String>, Vec<String>) =
+            changed_fields(&self.current, &config)?
+                .into_iter()
+                .partition(|field| is_reloadable(field));
+        report.restart_required = restart_required;
+
+        let fields = take_fields(&mut reloadable, FILTER_LEVEL_FIELD);
+        if !fields.is_empty() {
+            let level = &config.observability.filter_level;
+            match self.log_filter.as_ref().map(|reload| reload(level)) {
+                Some(Ok(())) => {
+                    info!("Log filter level reloaded: {}", level);
+                    self.current.observability.filter_level = level.clone();
+                    report.applied.extend(fields);
+                }
+                Some(Err(e)) => {
+                    warn!("Failed to reload log filter level: {}", e);
+                    report.restart_required.extend(fields);
+                }
+                None => report.restart_required.extend(fields),
+            }
+        }
+
+        let fields = take_fields(&mut reloadable, RATE_LIMIT_FIELD);
+        if !fields.is_empty() {
+            self.apply_rate_limits(&config, fields, &mut report).await;
+        }
+
+        let fields = take_fields(&mut reloadable, TURN_REALMS_FIELD);
+        if !fields.is_empty() {
+            match turn::Authenticator::registered() {
+                Some(authenticator) => {
+                    authenticator.set_allowed_realms(config.turn.allowed_realm_ids.iter().copied());
+                    self.current.turn.allowed_realm_ids = config.turn.allowed_realm_ids.clone();
+                    report.applied.extend(fields);
+                }
+                None => report.restart_required.extend(fields),
+            }
+        }
+
+        report.restart_required.sort();
+        Ok(report)
+    }
+
+    /// 应用 Signaling 速率限制，启动时未创建的限流器需要重启才能启用
+    async fn apply_rate_limits(
+        &mut self,
+        config: &ActrixConfig,
+        fields: Vec<String>,
+        report: &mut ReloadReport,
+    ) {
+        let server = signaling::admin::registered_server();
+        let new = config.services.signaling.as_ref();
+        let (Some(server), Some(new), Some(current)) =
+            (server, new, self.current.services.signaling.as_mut())
+        else {
+            report.restart_required.extend(fields);
+            return;
+        };
+
+        let rate_limit = &new.server.rate_limit;
+        let pending = server.reload_rate_limits(rate_limit).await;
+        for field in fields {
+            let limiter_pending = pending
+                .iter()
+                .any(|name| is_field(&field, &format!("{RATE_LIMIT_FIELD}.{name}")));
+            if limiter_pending {
+                report.restart_required.push(field);
+            } else {
+                report.applied.push(field);
+            }
+        }
+
+        let current = &mut current.server.rate_limit;
+        if !pending.contains(&"connection") {
+            current.connection = rate_limit.connection.clone();
+        }
+        if !pending.contains(&"message") {
+            current.message = rate_limit.message.clone();
+        }
+        if !pending.contains(&"realm") {
+            current.realm = rate_limit.realm.clone();
+        }
+    }
+
+    /// 后台处理 SIGHUP 与配置文件变化，收到关闭信号后退出
+    pub fn spawn(mut self, mut shutdown_rx: broadcast::Receiver<()>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let watch = self.current.reload.watch;
+            let mut interval = tokio::time::interval(Duration::from_secs(
+                self.current.reload.watch_interval_secs.max(1),
+            ));
+            let mut last_modified = modified_time(&self.config_path);
+            let mut hangup = hangup_signal();
+            if watch {
+                info!(
+                    "Watching {} for changes (every {}s)",
+                    self.config_path.display(),
+                    self.current.reload.watch_interval_secs
+                );
+            }
+
+            loop {
+                tokio::select! {
+                    _ = next_hangup(&mut hangup) => {
+                        info!("收到 SIGHUP 信号，重新加载配置");
+                        self.reload_and_log().await;
+                    }
+                    _ = interval.tick(), if watch => {
+                        let modified = modified_time(&self.config_path);
+                        if modified != last_modified {
+                            last_modified = modified;
+                            info!("配置文件已修改，重新加载配置");
+                            self.reload_and_log().await;
+                        }
+                    }
+                    _ = shutdown_rx.recv() => {
+                        info!("Config reloader received shutdown signal");
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+    async fn reload_and_log(&mut self) {
+        match self.reload().await {
+            Ok(report) if report.is_empty() => info!("配置无变化"),
+            Ok(report) => {
+                info!(
+                    "✅ 配置已重新加载: 已生效 {:?}, 需要重启 {:?}",
+                    report.applied, report.restart_required
+                );
+                if !report.restart_required.is_empty() {
+                    warn!(
+                        "⚠️  以下配置项需要重启后生效: {}",
+                        report.restart_required.join(", ")
+                    );
+                }
+            }
+            Err(e) => error!("配置重新加载失败，继续使用当前配置: {:#}", e),
+        }
+    }
+}
+
+/// 比较两份配置，返回发生变化的字段路径（如 `services.signaling.server.rate_limit.message.per_second`）
+fn changed_fields(old: &ActrixConfig, new: &ActrixConfig) -> Result<Vec<String>> {
+    let old = serde_json::to_value(old).context("Failed to serialize current config")?;
+    let new = serde_json::to_value(new).context("Failed to serialize new config")?;
+    let mut changed = Vec::new();
+    diff_values("", &old, &new, &mut changed);
+    Ok(changed)
+}
+
+fn diff_values(path: &str, old: &Value, new: &Value, changed: &mut Vec<String>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_values(
+                    &field,
+                    old.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    changed,
+                );
+            }
+        }
+        _ if old != new => changed.push(path.to_string()),
+        _ => {}
+    }
+}
+
+/// `field` 是 `parent` 本身或其子字段
+fn is_field(field: &str, parent: &str) -> bool {
+    field
+        .strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn is_reloadable(field: &str) -> bool {
+    RELOADABLE_FIELDS
+        .iter()
+        .any(|parent| is_field(field, parent))
+}
+
+/// 取出属于 `parent` 的字段
+fn take_fields(fields: &mut Vec<String>, parent: &str) -> Vec<String> {
+    let (taken, rest): (Vec<String>, Vec<String>) = std::mem::take(fields)
+        .into_iter()
+        .partition(|field| is_field(field, parent));
+    *fields = rest;
+    taken
+}
+
+fn modified_time(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(unix)]
+fn hangup_signal() -> Option<tokio::signal::unix::Signal> {
+    use tokio::signal::unix::{SignalKind, signal};
+    match signal(SignalKind::hangup()) {
+        Ok(signal) => Some(signal),
+        Err(e) => {
+            warn!("无法监听 SIGHUP 信号，配置热加载仅支持文件监视: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn next_hangup(signal: &mut Option<tokio::signal::unix::Signal>) {
+    if let Some(signal) = signal
+        && signal.recv().await.is_some()
+    {
+        return;
+    }
+    std::future::pending::<()>().await
+}
+
+#[cfg(not(unix))]
+fn hangup_signal() -> Option<()> {
+    None
+}
+
+#[cfg(not(unix))]
+async fn next_hangup(_signal: &mut Option<()>) {
+    std::future::pending::<()>().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_changed_fields() {
+        let old = ActrixConfig::default();
+        let mut new = old.clone();
+        new.observability.filter_level = "debug".to_string();
+        new.location_tag = "us-west-1".to_string();
+        new.turn.allowed_realm_ids = vec![1001];
+
+        let changed = changed_fields(&old, &new).unwrap();
+        assert_eq!(
+            changed,
+            vec![
+                "location_tag".to_string(),
+                FILTER_LEVEL_FIELD.to_string(),
+                TURN_REALMS_FIELD.to_string(),
+            ]
+        );
+        assert!(changed_fields(&old, &old).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reloadable_fields() {
+        assert!(is_reloadable(FILTER_LEVEL_FIELD));
+        assert!(is_reloadable(
+            "services.signaling.server.rate_limit.message.per_second"
+        ));
+        assert!(!is_reloadable("services.signaling.server.ws_path"));
+        assert!(!is_reloadable("turn.allowed_realm_ids_extra"));
+        assert!(!is_reloadable("bind.http.port"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_reports_restart_required() {
+        let levels = Arc::new(Mutex::new(Vec::new()));
+        let recorded = levels.clone();
+        let mut reloader =
+            ConfigReloader::new(PathBuf::from("config.toml"), ActrixConfig::default())
+                .with_log_filter(move |level| {
+                    recorded.lock().unwrap().push(level.to_string());
+                    Ok(())
+                });
+
+        let mut config = ActrixConfig::default();
+        config.observability.filter_level = "debug".to_string();
+        config.location_tag = "us-west-1".to_string();
+
+        let report = reloader.apply(config.clone()).await.unwrap();
+        assert_eq!(report.applied, vec![FILTER_LEVEL_FIELD.to_string()]);
+        assert_eq!(report.restart_required, vec!["location_tag".to_string()]);
+        assert_eq!(*levels.lock().unwrap(), vec!["debug".to_string()]);
+
+        // 已生效的字段不再报告，需要重启的字段在重启前持续报告
+        let report = reloader.apply(config).await.unwrap();
+        assert!(report.applied.is_empty());
+        assert_eq!(report.restart_required, vec!["location_tag".to_string()]);
+    }
+}
@@ -0,0 +1,116 @@
+//! 重启协调
+//!
+//! Supervisor 的 UPDATE 指令安装新的可执行文件或配置文件后，通过 [`RestartCoordinator`]
+//! 请求重启：记录待重启状态并广播关闭信号，所有服务按正常流程优雅关闭；
+//! 主流程在 [`ServiceManager::stop_all`](crate::service::ServiceManager::stop_all) 之后
+//! 检查待重启状态，以原有命令行参数重新执行可执行文件；重新执行失败时通过
+//! [`RestartCoordinator::rollback_update`] 恢复安装前的文件。
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use supervit::StagedUpdate;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+/// 重启请求句柄
+#[derive(Debug, Clone)]
+pub struct RestartCoordinator {
+    pending: Arc<Mutex<Option<String>>>,
+    /// 触发重启的已安装更新及其安装目标
+    installed: Arc<Mutex<Option<(StagedUpdate, PathBuf)>>>,
+    shutdown_tx: broadcast::Sender<()>,
+}
+
+impl RestartCoordinator {
+    pub fn new(shutdown_tx: broadcast::Sender<()>) -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(None)),
+            installed: Arc::new(Mutex::new(None)),
+            shutdown_tx,
+        }
+    }
+
+    /// 请求优雅关闭后重启，重复请求只保留第一次的原因
+    pub fn request_restart(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        {
+            let mut pending = self
+                .pending
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if pending.is_some() {
+                warn!("已有待执行的重启请求，忽略: {}", reason);
+                return;
+            }
+            info!("🔄 请求重启: {}", reason);
+            *pending = Some(reason);
+        }
+        let _ = self.shutdown_tx.send(());
+    }
+
+    /// 安装更新后请求重启，重启失败时可回滚该更新
+    pub fn request_restart_after_update(&self, update: StagedUpdate, target: PathBuf) {
+        let reason = format!("update {} ({:?})", update.version, update.kind);
+        {
+            let mut installed = self
+                .installed
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if installed.is_none() {
+                *installed = Some((update, target));
+            }
+        }
+        self.request_restart(reason);
+    }
+
+    /// 回滚触发重启的更新，返回是否执行了回滚
+    pub fn rollback_update(&self) -> anyhow::Result<bool> {
+        let installed = self
+            .installed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        match installed {
+            Some((update, target)) => {
+                update.rollback(&target)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// 待执行的重启原因
+    pub fn pending(&self) -> Option<String> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_restart_broadcasts_shutdown_once() {
+        let (shutdown_tx, mut shutdown_rx) = broadcast::channel(4);
+        let coordinator = RestartCoordinator::new(shutdown_tx);
+        assert!(coordinator.pending().is_none());
+
+        coordinator.request_restart("update 1.2.3");
+        coordinator.clone().request_restart("update 1.2.4");
+
+        assert_eq!(coordinator.pending().as_deref(), Some("update 1.2.3"));
+        assert!(shutdown_rx.try_recv().is_ok());
+        assert!(shutdown_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_rollback_without_update_is_noop() {
+        let (shutdown_tx, _shutdown_rx) = broadcast::channel(4);
+        let coordinator = RestartCoordinator::new(shutdown_tx);
+        coordinator.request_restart("config reload");
+        assert!(!coordinator.rollback_update().unwrap());
+    }
+}
@@ -0,0 +1,188 @@
+//! 优雅关闭期限
+//!
+//! [`ServiceTasks`] 收集各服务的后台任务并等待其退出。收到全局关闭信号后开始计时，
+//! 超过 `shutdown.grace_period_secs` 仍未退出的任务被强制中止，并在日志与
+//! `actrix_shutdown_aborted_tasks_total` 指标中按任务名汇总，使重启耗时可预期。
+
+use actrix_common::metrics::{SHUTDOWN_ABORTED_TASKS, SHUTDOWN_DURATION};
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+/// 关闭结果
+#[derive(Debug, Default)]
+pub struct ShutdownReport {
+    /// 从收到关闭信号到全部任务结束的耗时，未收到关闭信号时为 None
+    pub elapsed: Option<Duration>,
+    /// 超过期限被强制中止的任务
+    pub aborted: Vec<String>,
+}
+
+/// 带名称的服务任务集合
+pub struct ServiceTasks {
+    tasks: Vec<(String, JoinHandle<()>)>,
+    shutdown_tx: broadcast::Sender<()>,
+    // 创建时订阅，等待开始前发出的关闭信号同样会启动计时
+    shutdown_rx: broadcast::Receiver<()>,
+}
+
+impl ServiceTasks {
+    pub fn new(shutdown_tx: broadcast::Sender<()>) -> Self {
+        Self {
+            tasks: Vec::new(),
+            shutdown_rx: shutdown_tx.subscribe(),
+            shutdown_tx,
+        }
+    }
+
+    /// 登记任务
+    pub fn push(&mut self, name: impl Into<String>, handle: JoinHandle<()>) {
+        self.tasks.push((name.into(), handle));
+    }
+
+    /// 等待全部任务结束
+    ///
+    /// 任务异常终止（panic）时广播全局关闭；收到关闭信号后最多再等待 `grace_period`，
+    /// 随后中止剩余任务
+    pub async fn wait(self, grace_period: Duration) -> ShutdownReport {
+        let Self {
+            tasks,
+            shutdown_tx,
+            mut shutdown_rx,
+        } = self;
+
+        let mut aborts: HashMap<usize, (String, AbortHandle)> = HashMap::new();
+        let mut pending = FuturesUnordered::new();
+        for (index, (name, handle)) in tasks.into_iter().enumerate() {
+            aborts.insert(index, (name.clone(), handle.abort_handle()));
+            pending.push(async move { (index, name, handle.await) });
+        }
+
+        let mut report = ShutdownReport::default();
+        let mut shutdown_started: Option<Instant> = None;
+        // 未收到关闭信号前不会触发，仅作为占位
+        let deadline = tokio::time::sleep(Duration::MAX);
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                finished = pending.next() => {
+                    let Some((index, name, result)) = finished else {
+                        break;
+                    };
+                    aborts.remove(&index);
+                    if let Err(e) = result {
+                        error!("Service task '{}' terminated unexpectedly: {}", name, e);
+                        let _ = shutdown_tx.send(());
+                    }
+                }
+                // 自身持有 Sender，通道不会关闭；Lagged 同样表示已发出关闭信号
+                _ = shutdown_rx.recv(), if shutdown_started.is_none() => {
+                    let now = Instant::now();
+                    shutdown_started = Some(now);
+                    deadline.as_mut().reset(now + grace_period);
+                    info!(
+                        "Shutdown requested, waiting up to {:?} for {} task(s)",
+                        grace_period,
+                        aborts.len()
+                    );
+                }
+                _ = &mut deadline, if shutdown_started.is_some() => {
+                    let mut remaining: Vec<(usize, (String, AbortHandle))> = aborts.drain().collect();
+                    remaining.sort_by_key(|(index, _)| *index);
+                    for (_, (name, abort)) in remaining {
+                        abort.abort();
+                        SHUTDOWN_ABORTED_TASKS.with_label_values(&[&name]).inc();
+                        report.aborted.push(name);
+                    }
+                    warn!(
+                        "Shutdown grace period of {:?} exceeded, aborted {} task(s): {}",
+                        grace_period,
+                        report.aborted.len(),
+                        report.aborted.join(", ")
+                    );
+                    break;
+                }
+            }
+        }
+
+        report.elapsed = shutdown_started.map(|started| started.elapsed());
+        if let Some(elapsed) = report.elapsed {
+            SHUTDOWN_DURATION.set(elapsed.as_secs_f64());
+            if report.aborted.is_empty() {
+                info!(
+                    "All service tasks exited {:?} after shutdown signal",
+                    elapsed
+                );
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tasks_exit_within_grace_period() {
+        let (shutdown_tx, _) = broadcast::channel(4);
+        let mut tasks = ServiceTasks::new(shutdown_tx.clone());
+        let mut rx = shutdown_tx.subscribe();
+        tasks.push(
+            "http",
+            tokio::spawn(async move {
+                let _ = rx.recv().await;
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }),
+        );
+
+        let _ = shutdown_tx.send(());
+        let report = tasks.wait(Duration::from_secs(5)).await;
+        assert!(report.aborted.is_empty());
+        assert_eq!(report.elapsed, Some(Duration::from_secs(1)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stuck_tasks_are_aborted() {
+        let (shutdown_tx, _) = broadcast::channel(4);
+        let mut tasks = ServiceTasks::new(shutdown_tx.clone());
+        let mut rx = shutdown_tx.subscribe();
+        tasks.push(
+            "http",
+            tokio::spawn(async move {
+                let _ = rx.recv().await;
+            }),
+        );
+        tasks.push("turn", tokio::spawn(std::future::pending()));
+        tasks.push("ks-grpc", tokio::spawn(std::future::pending()));
+
+        let _ = shutdown_tx.send(());
+        let report = tasks.wait(Duration::from_secs(3)).await;
+        assert_eq!(report.aborted, vec!["turn", "ks-grpc"]);
+        assert_eq!(report.elapsed, Some(Duration::from_secs(3)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panicking_task_triggers_shutdown() {
+        let (shutdown_tx, _) = broadcast::channel(4);
+        let mut tasks = ServiceTasks::new(shutdown_tx.clone());
+        let mut rx = shutdown_tx.subscribe();
+        tasks.push("reload", tokio::spawn(async { panic!("boom") }));
+        tasks.push(
+            "http",
+            tokio::spawn(async move {
+                let _ = rx.recv().await;
+            }),
+        );
+
+        let report = tasks.wait(Duration::from_secs(3)).await;
+        assert!(report.aborted.is_empty());
+        assert!(report.elapsed.is_some());
+    }
+}
@@ -0,0 +1,147 @@
+//! systemd 通知与看门狗（sd_notify）
+//!
+//! 以 `Type=notify` 运行时，systemd 通过 `NOTIFY_SOCKET` 环境变量提供通知套接字：
+//! - 所有服务启动完成后发送 `READY=1`
+//! - 配置了 `WatchdogSec` 时（`WATCHDOG_USEC`），[`ServiceManager`](crate::service::ServiceManager)
+//!   按看门狗超时的一半周期检查服务状态，全部服务正常时发送 `WATCHDOG=1`；
+//!   有服务处于错误状态时停止发送，由 systemd 在超时后重启进程
+//! - 开始优雅关闭时发送 `STOPPING=1`；自更新重启以同一 PID 重新执行，不发送 `STOPPING=1`，
+//!   由新进程启动完成后再次发送 `READY=1`
+//!
+//! 未设置 `NOTIFY_SOCKET`（非 systemd 或 `Type=simple`）时所有通知为空操作。
+
+use std::ffi::OsStr;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// 发送 `READY=1`
+pub fn notify_ready(status: &str) {
+    notify(&format!("READY=1\nSTATUS={status}"));
+}
+
+/// 发送 `STOPPING=1`
+pub fn notify_stopping() {
+    notify("STOPPING=1\nSTATUS=Shutting down");
+}
+
+/// 发送看门狗心跳
+pub fn notify_watchdog() {
+    notify("WATCHDOG=1");
+}
+
+/// 更新 systemctl status 中显示的状态文本
+pub fn notify_status(status: &str) {
+    notify(&format!("STATUS={status}"));
+}
+
+fn notify(state: &str) {
+    let Some(socket) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    match send_to(&socket, state) {
+        Ok(()) => debug!("sd_notify: {}", state.replace('\n', " ")),
+        Err(e) => warn!("sd_notify 发送失败 ({:?}): {}", socket, e),
+    }
+}
+
+#[cfg(unix)]
+fn send_to(socket: &OsStr, state: &str) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixDatagram;
+
+    let datagram = UnixDatagram::unbound()?;
+    match socket.as_bytes().strip_prefix(b"@") {
+        // 以 @ 开头为 Linux 抽象命名空间套接字
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            use std::os::unix::net::SocketAddr;
+
+            let addr = SocketAddr::from_abstract_name(name)?;
+            datagram.send_to_addr(state.as_bytes(), &addr)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "abstract notify socket is only supported on Linux",
+            ));
+        }
+        None => {
+            datagram.send_to(state.as_bytes(), std::path::Path::new(socket))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send_to(_socket: &OsStr, _state: &str) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "sd_notify is only supported on Unix",
+    ))
+}
+
+/// 看门狗心跳周期（看门狗超时的一半），未启用看门狗时返回 None
+pub fn watchdog_interval() -> Option<Duration> {
+    parse_watchdog_interval(
+        std::env::var("WATCHDOG_USEC").ok().as_deref(),
+        std::env::var("WATCHDOG_PID").ok().as_deref(),
+        std::process::id(),
+    )
+}
+
+/// `WATCHDOG_PID` 存在时必须是当前进程，否则看门狗属于其他进程
+fn parse_watchdog_interval(
+    usec: Option<&str>,
+    pid: Option<&str>,
+    own_pid: u32,
+) -> Option<Duration> {
+    if let Some(pid) = pid
+        && pid.trim().parse::<u32>().ok() != Some(own_pid)
+    {
+        return None;
+    }
+    let usec = usec?.trim().parse::<u64>().ok().filter(|usec| *usec > 0)?;
+    Some(Duration::from_micros(usec) / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_watchdog_interval() {
+        assert_eq!(
+            parse_watchdog_interval(Some("30000000"), None, 42),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(
+            parse_watchdog_interval(Some("30000000"), Some("42"), 42),
+            Some(Duration::from_secs(15))
+        );
+        // 看门狗属于其他进程
+        assert_eq!(
+            parse_watchdog_interval(Some("30000000"), Some("7"), 42),
+            None
+        );
+        assert_eq!(parse_watchdog_interval(None, None, 42), None);
+        assert_eq!(parse_watchdog_interval(Some("0"), None, 42), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send_to_socket_path() {
+        use std::os::unix::net::UnixDatagram;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        send_to(path.as_os_str(), "READY=1").unwrap();
+
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+    }
+}
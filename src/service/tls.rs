@@ -0,0 +1,49 @@
+//! HTTPS 接入层
+//!
+//! 在 rustls 握手完成后导出 TLS 通道绑定值 ([`TlsChannelBinding`])，
+//! 并作为请求扩展注入该连接上的所有请求，供 Signaling 等服务做 token 绑定校验。
+
+use actrix_common::util::TlsChannelBinding;
+use axum_server::accept::{Accept, DefaultAcceptor};
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use futures::future::BoxFuture;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::server::TlsStream;
+use tower_http::add_extension::AddExtension;
+use tracing::warn;
+
+/// 注入 TLS 通道绑定的 rustls acceptor
+#[derive(Clone)]
+pub struct ChannelBindingAcceptor {
+    inner: RustlsAcceptor<DefaultAcceptor>,
+}
+
+impl ChannelBindingAcceptor {
+    pub fn new(config: RustlsConfig) -> Self {
+        Self {
+            inner: RustlsAcceptor::new(config),
+        }
+    }
+}
+
+impl<I, S> Accept<I, S> for ChannelBindingAcceptor
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = TlsStream<I>;
+    type Service = AddExtension<S, TlsChannelBinding>;
+    type Future = BoxFuture<'static, std::io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let acceptor = self.inner.clone();
+        Box::pin(async move {
+            let (stream, service) = acceptor.accept(stream, service).await?;
+            let binding = TlsChannelBinding::from_connection(stream.get_ref().1).map_err(|e| {
+                warn!("Failed to export TLS channel binding: {}", e);
+                std::io::Error::other(e)
+            })?;
+            Ok((stream, AddExtension::new(service, binding)))
+        })
+    }
+}
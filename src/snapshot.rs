@@ -0,0 +1,523 @@
+//! 节点状态快照与冷备恢复
+//!
+//! 将节点的持久化状态打包为单个 `.tar.gz` 归档：
+//! - `databases/*.db`：各 SQLite 数据库的一致性备份（`VACUUM INTO`，节点运行时也可执行）
+//! - `registry.json`：服务注册表导出（来自 `signaling_cache.db`）
+//! - `manifest.json`：快照清单，包含各数据库的 SHA-256 与 KS 密钥元数据
+//!
+//! 私钥材料只有在已被 KEK 加密时才进入快照：未配置 KEK 时快照中 `ks_keys.db` 的私钥被清空；
+//! `ks_cache.db`（从 KS 拉取的私钥明文缓存）从不进入快照，恢复后按需重新拉取。
+//!
+//! ## 恢复流程
+//!
+//! 1. 在备用节点上准备与原节点一致的配置（KEK 已加密的私钥需要相同的 KEK 才能解密）
+//! 2. 确保节点已停止
+//! 3. 执行 `actrix --config <config> restore <archive>`（目标目录已有数据库时需加 `--force`）
+//! 4. 启动节点
+
+use actrix_common::config::ActrixConfig;
+use anyhow::{Context, Result, bail};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection, Row};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// 快照格式版本
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const REGISTRY_FILE: &str = "registry.json";
+const DATABASES_DIR: &str = "databases";
+const KS_KEYS_DB: &str = "ks_keys.db";
+const SIGNALING_CACHE_DB: &str = "signaling_cache.db";
+
+/// 进入快照的数据库文件（位于 `sqlite_path` 下，不存在的跳过）
+const SNAPSHOT_DATABASES: &[&str] = &[
+    "actrix.db",
+    SIGNALING_CACHE_DB,
+    "nonce.db",
+    "ais_keys.db",
+    "ais_revocations.db",
+    KS_KEYS_DB,
+    "ks_audit.db",
+];
+
+/// 快照清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format_version: u32,
+    /// 快照时间 (Unix 秒)
+    pub created_at: i64,
+    pub node_name: String,
+    pub actrix_version: String,
+    pub databases: Vec<DatabaseEntry>,
+    /// 导出的服务注册数量
+    pub registry_services: usize,
+    pub keys: KeyManifest,
+}
+
+/// 快照中的数据库文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseEntry {
+    pub file: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// KS 密钥元数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyManifest {
+    /// 快照中的 `ks_keys.db` 是否仍含私钥（仅当私钥已被 KEK 加密时保留）
+    pub secrets_included: bool,
+    pub keys: Vec<KeyMetadata>,
+}
+
+/// 单个 KS 密钥的元数据（不含私钥）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyMetadata {
+    pub key_id: i64,
+    pub public_key: String,
+    pub created_at: i64,
+    /// 过期时间 (Unix 秒，0 表示永不过期)
+    pub expires_at: i64,
+}
+
+/// 服务注册表导出条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    pub realm_id: i64,
+    pub serial_number: i64,
+    pub manufacturer: String,
+    pub name: String,
+    pub version: String,
+    pub service_name: String,
+    pub status: String,
+    pub registered_at: i64,
+    pub last_heartbeat_at: i64,
+}
+
+/// 生成节点状态快照，写入 `output`
+pub async fn create_snapshot(config: &ActrixConfig, output: &Path) -> Result<SnapshotManifest> {
+    let staging = output.with_extension("staging");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(staging.join(DATABASES_DIR))
+        .with_context(|| format!("Failed to create staging dir {}", staging.display()))?;
+
+    let result = build_snapshot(config, &staging, output).await;
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+async fn build_snapshot(
+    config: &ActrixConfig,
+    staging: &Path,
+    output: &Path,
+) -> Result<SnapshotManifest> {
+    let databases_dir = staging.join(DATABASES_DIR);
+    let kek_wrapped = config
+        .services
+        .ks
+        .as_ref()
+        .is_some_and(|ks| ks.get_kek_source().is_some());
+
+    let mut copied = Vec::new();
+    for name in SNAPSHOT_DATABASES {
+        let source = config.sqlite_path.join(name);
+        if !source.exists() {
+            continue;
+        }
+        let target = databases_dir.join(name);
+        backup_database(&source, &target).await?;
+        copied.push(*name);
+    }
+
+    let keys = if copied.contains(&KS_KEYS_DB) {
+        let path = databases_dir.join(KS_KEYS_DB);
+        if !kek_wrapped {
+            strip_secret_keys(&path).await?;
+            warn!("KS 私钥未经 KEK 加密，快照中不包含私钥");
+        }
+        KeyManifest {
+            secrets_included: has_secret_keys(&path).await?,
+            keys: read_key_metadata(&path).await?,
+        }
+    } else {
+        KeyManifest::default()
+    };
+
+    let registry = if copied.contains(&SIGNALING_CACHE_DB) {
+        read_registry(&databases_dir.join(SIGNALING_CACHE_DB)).await?
+    } else {
+        Vec::new()
+    };
+    std::fs::write(
+        staging.join(REGISTRY_FILE),
+        serde_json::to_vec_pretty(&registry)?,
+    )?;
+
+    let mut databases = Vec::with_capacity(copied.len());
+    for name in copied {
+        let path = databases_dir.join(name);
+        databases.push(DatabaseEntry {
+            file: name.to_string(),
+            size: std::fs::metadata(&path)?.len(),
+            sha256: sha256_file(&path)?,
+        });
+    }
+
+    let manifest = SnapshotManifest {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        created_at: chrono::Utc::now().timestamp(),
+        node_name: config.name.clone(),
+        actrix_version: env!("CARGO_PKG_VERSION").to_string(),
+        databases,
+        registry_services: registry.len(),
+        keys,
+    };
+    std::fs::write(
+        staging.join(MANIFEST_FILE),
+        serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    write_archive(staging, output)?;
+    info!(
+        "📦 快照已生成: {} ({} 个数据库, {} 个服务注册, {} 个密钥)",
+        output.display(),
+        manifest.databases.len(),
+        manifest.registry_services,
+        manifest.keys.keys.len()
+    );
+
+    Ok(manifest)
+}
+
+/// 从快照恢复节点状态到 `sqlite_path`（节点必须已停止）
+///
+/// 目标目录已有同名数据库时，除非 `force` 否则拒绝覆盖
+pub async fn restore_snapshot(
+    config: &ActrixConfig,
+    archive: &Path,
+    force: bool,
+) -> Result<SnapshotManifest> {
+    std::fs::create_dir_all(&config.sqlite_path)?;
+    let staging = config.sqlite_path.join(".restore-staging");
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+
+    let result = restore_from_staging(config, archive, &staging, force);
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+fn restore_from_staging(
+    config: &ActrixConfig,
+    archive: &Path,
+    staging: &Path,
+    force: bool,
+) -> Result<SnapshotManifest> {
+    let file = File::open(archive)
+        .with_context(|| format!("Failed to open snapshot {}", archive.display()))?;
+    tar::Archive::new(GzDecoder::new(file))
+        .unpack(staging)
+        .with_context(|| format!("Failed to unpack snapshot {}", archive.display()))?;
+
+    let manifest: SnapshotManifest = serde_json::from_slice(
+        &std::fs::read(staging.join(MANIFEST_FILE)).context("Snapshot has no manifest")?,
+    )?;
+    if manifest.format_version > SNAPSHOT_FORMAT_VERSION {
+        bail!(
+            "Unsupported snapshot format version {} (supported: {})",
+            manifest.format_version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+    }
+
+    // 先校验全部文件，再写入目标目录
+    for entry in &manifest.databases {
+        if Path::new(&entry.file).file_name() != Some(entry.file.as_ref()) {
+            bail!("Invalid database entry in manifest: {}", entry.file);
+        }
+        let staged = staging.join(DATABASES_DIR).join(&entry.file);
+        let checksum =
+            sha256_file(&staged).with_context(|| format!("Snapshot is missing {}", entry.file))?;
+        if checksum != entry.sha256 {
+            bail!("Checksum mismatch for {}", entry.file);
+        }
+        if !force && config.sqlite_path.join(&entry.file).exists() {
+            bail!(
+                "{} already exists in {}; stop the node and pass --force to overwrite",
+                entry.file,
+                config.sqlite_path.display()
+            );
+        }
+    }
+
+    for entry in &manifest.databases {
+        let target = config.sqlite_path.join(&entry.file);
+        // 旧的 WAL/SHM 文件属于被替换的数据库，必须一并删除
+        for suffix in ["-wal", "-shm"] {
+            let _ = std::fs::remove_file(PathBuf::from(format!("{}{suffix}", target.display())));
+        }
+        let temp = target.with_extension("db.restoring");
+        std::fs::copy(staging.join(DATABASES_DIR).join(&entry.file), &temp)?;
+        std::fs::rename(&temp, &target)?;
+        info!("✅ 已恢复 {}", target.display());
+    }
+
+    if !manifest.keys.keys.is_empty() {
+        if manifest.keys.secrets_included {
+            warn!("快照中的 KS 私钥由 KEK 加密，恢复后的节点必须配置相同的 KEK");
+        } else {
+            warn!(
+                "快照不包含 KS 私钥，{} 个已有密钥只能用于查询公钥",
+                manifest.keys.keys.len()
+            );
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// 使用 `VACUUM INTO` 生成数据库的一致性副本
+async fn backup_database(source: &Path, target: &Path) -> Result<()> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(source)
+        .read_only(true)
+        .connect()
+        .await
+        .with_context(|| format!("Failed to open {}", source.display()))?;
+    sqlx::query("VACUUM INTO ?1")
+        .bind(target.display().to_string())
+        .execute(&mut conn)
+        .await
+        .with_context(|| format!("Failed to back up {}", source.display()))?;
+    conn.close().await?;
+    Ok(())
+}
+
+/// 清空快照副本中的 KS 私钥，并 VACUUM 以清除残留页
+async fn strip_secret_keys(path: &Path) -> Result<()> {
+    let mut conn = SqliteConnectOptions::new().filename(path).connect().await?;
+    sqlx::query("UPDATE keys SET secret_key = ''")
+        .execute(&mut conn)
+        .await?;
+    sqlx::query("VACUUM").execute(&mut conn).await?;
+    conn.close().await?;
+    Ok(())
+}
+
+/// 快照副本中是否还有非空私钥
+async fn has_secret_keys(path: &Path) -> Result<bool> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await?;
+    let row = sqlx::query("SELECT EXISTS(SELECT 1 FROM keys WHERE secret_key != '') AS present")
+        .fetch_one(&mut conn)
+        .await?;
+    conn.close().await?;
+    Ok(row.get::<bool, _>("present"))
+}
+
+async fn read_key_metadata(path: &Path) -> Result<Vec<KeyMetadata>> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await?;
+    let rows =
+        sqlx::query("SELECT key_id, public_key, created_at, expires_at FROM keys ORDER BY key_id")
+            .fetch_all(&mut conn)
+            .await?;
+    conn.close().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| KeyMetadata {
+            key_id: row.get("key_id"),
+            public_key: row.get("public_key"),
+            created_at: row.get("created_at"),
+            expires_at: row.get("expires_at"),
+        })
+        .collect())
+}
+
+async fn read_registry(path: &Path) -> Result<Vec<RegistryEntry>> {
+    let mut conn = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await?;
+    let rows = sqlx::query(
+        r#"SELECT actor_realm_id, actor_serial_number, actor_manufacturer, actor_device_name,
+                  actor_type_version, service_name, status, registered_at, last_heartbeat_at
+           FROM service_registry
+           ORDER BY actor_realm_id, service_name, actor_serial_number"#,
+    )
+    .fetch_all(&mut conn)
+    .await
+    .context("Failed to read service registry")?;
+    conn.close().await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RegistryEntry {
+            realm_id: row.get("actor_realm_id"),
+            serial_number: row.get("actor_serial_number"),
+            manufacturer: row.get("actor_manufacturer"),
+            name: row.get("actor_device_name"),
+            version: row.get("actor_type_version"),
+            service_name: row.get("service_name"),
+            status: row.get("status"),
+            registered_at: row.get("registered_at"),
+            last_heartbeat_at: row.get("last_heartbeat_at"),
+        })
+        .collect())
+}
+
+fn write_archive(staging: &Path, output: &Path) -> Result<()> {
+    let temp = output.with_extension("partial");
+    let encoder = GzEncoder::new(File::create(&temp)?, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.append_dir_all(".", staging)?;
+    builder.into_inner()?.finish()?;
+    std::fs::rename(&temp, output)
+        .with_context(|| format!("Failed to write snapshot {}", output.display()))?;
+    Ok(())
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn create_ks_db(dir: &Path) {
+        let mut conn = SqliteConnectOptions::new()
+            .filename(dir.join(KS_KEYS_DB))
+            .create_if_missing(true)
+            .connect()
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE keys (key_id INTEGER PRIMARY KEY, public_key TEXT NOT NULL,
+             secret_key TEXT NOT NULL, created_at INTEGER NOT NULL, expires_at INTEGER NOT NULL)",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO keys VALUES (1, 'pub-1', 'plain-secret', 100, 0)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        conn.close().await.unwrap();
+    }
+
+    async fn secret_of(dir: &Path) -> String {
+        let mut conn = SqliteConnectOptions::new()
+            .filename(dir.join(KS_KEYS_DB))
+            .connect()
+            .await
+            .unwrap();
+        let row = sqlx::query("SELECT secret_key FROM keys WHERE key_id = 1")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        row.get("secret_key")
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_excludes_unwrapped_secrets_and_restores() {
+        let source = tempfile::tempdir().unwrap();
+        create_ks_db(source.path()).await;
+        let config = ActrixConfig {
+            sqlite_path: source.path().to_path_buf(),
+            ..ActrixConfig::default()
+        };
+
+        let archive = source.path().join("node.tar.gz");
+        let manifest = create_snapshot(&config, &archive).await.unwrap();
+        assert_eq!(manifest.databases.len(), 1);
+        assert!(!manifest.keys.secrets_included);
+        assert_eq!(manifest.keys.keys[0].public_key, "pub-1");
+        assert_eq!(manifest.registry_services, 0);
+        // 原数据库不受影响
+        assert_eq!(secret_of(source.path()).await, "plain-secret");
+
+        let target = tempfile::tempdir().unwrap();
+        let restore_config = ActrixConfig {
+            sqlite_path: target.path().to_path_buf(),
+            ..ActrixConfig::default()
+        };
+        restore_snapshot(&restore_config, &archive, false)
+            .await
+            .unwrap();
+        assert_eq!(secret_of(target.path()).await, "");
+
+        // 已有数据库时需要 force
+        assert!(
+            restore_snapshot(&restore_config, &archive, false)
+                .await
+                .is_err()
+        );
+        restore_snapshot(&restore_config, &archive, true)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_reports_secrets_from_archived_keys() {
+        let source = tempfile::tempdir().unwrap();
+        create_ks_db(source.path()).await;
+        let staging = source.path().join("copy.db");
+        backup_database(&source.path().join(KS_KEYS_DB), &staging)
+            .await
+            .unwrap();
+        assert!(has_secret_keys(&staging).await.unwrap());
+
+        strip_secret_keys(&staging).await.unwrap();
+        assert!(!has_secret_keys(&staging).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_includes_audit_and_revocation_databases() {
+        let source = tempfile::tempdir().unwrap();
+        for name in ["ks_audit.db", "ais_revocations.db"] {
+            let mut conn = SqliteConnectOptions::new()
+                .filename(source.path().join(name))
+                .create_if_missing(true)
+                .connect()
+                .await
+                .unwrap();
+            sqlx::query("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            conn.close().await.unwrap();
+        }
+        let config = ActrixConfig {
+            sqlite_path: source.path().to_path_buf(),
+            ..ActrixConfig::default()
+        };
+
+        let archive = source.path().join("node.tar.gz");
+        let manifest = create_snapshot(&config, &archive).await.unwrap();
+        let files: Vec<_> = manifest.databases.iter().map(|d| d.file.as_str()).collect();
+        assert_eq!(files, ["ais_revocations.db", "ks_audit.db"]);
+    }
+}
@@ -679,6 +679,27 @@ async fn actrix_end_to_end_register_and_health() {
         "signaling health text: {sig_text}"
     );
 
+    // Discovery document
+    let discovery: Value = client
+        .get(format!("{base}/.well-known/actrix-configuration"))
+        .send()
+        .await
+        .expect("discovery document")
+        .json()
+        .await
+        .expect("discovery document json");
+    // Discovery URLs use the configured public domain_name
+    assert_eq!(
+        discovery["signaling"]["ws_url"],
+        format!("ws://localhost:{}/signaling/ws", harness.port)
+    );
+    assert_eq!(
+        discovery["ais"]["register_url"],
+        format!("http://localhost:{}/ais/register", harness.port)
+    );
+    assert_eq!(discovery["envelope_versions"][0], 1);
+    assert!(discovery["stun"].is_null());
+
     // Register an actor via AIS HTTP (protobuf body)
     let register_req = RegisterRequest {
         actr_type: ActrType {
@@ -1278,6 +1299,91 @@ async fn signaling_url_identity_reconnect_replaces_stale_connection() {
     harness.shutdown();
 }
 
+#[tokio::test]
+#[serial]
+async fn signaling_header_identity_reconnect_and_forged_identity_rejected() {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    let harness = ActrixHarness::start(DEFAULT_TOKEN_TTL).await;
+
+    let (_old_write, _old_read, register_ok) =
+        ws_register(harness.port, "mfg", "header-id", None).await;
+    let token_b64 =
+        base64::engine::general_purpose::STANDARD.encode(&register_ok.credential.encrypted_token);
+    let ws_url = format!("ws://127.0.0.1:{}/signaling/ws", harness.port);
+
+    // 凭证通过请求头传递，URL 中不含 token
+    let mut request = ws_url
+        .as_str()
+        .into_client_request()
+        .expect("build request");
+    let headers = request.headers_mut();
+    headers.insert(
+        "authorization",
+        format!("Bearer {token_b64}").parse().unwrap(),
+    );
+    headers.insert(
+        "x-actr-id",
+        register_ok.actr_id.to_string_repr().parse().unwrap(),
+    );
+    headers.insert(
+        "x-actr-token-key-id",
+        register_ok
+            .credential
+            .token_key_id
+            .to_string()
+            .parse()
+            .unwrap(),
+    );
+    let (stream, _) = connect_async(request)
+        .await
+        .expect("reconnect with header identity");
+    let (mut write, mut read) = stream.split();
+
+    let ping = actr_protocol::ActrToSignaling {
+        source: register_ok.actr_id.clone(),
+        credential: register_ok.credential.clone(),
+        payload: Some(actr_protocol::actr_to_signaling::Payload::Ping(
+            actr_protocol::Ping {
+                availability: 90,
+                mailbox_backlog: 0.0,
+                power_reserve: 90.0,
+                ..Default::default()
+            },
+        )),
+    };
+    send_envelope(
+        &mut write,
+        make_envelope(signaling_envelope::Flow::ActrToServer(ping)),
+    )
+    .await;
+    match recv_envelope(&mut read).await.flow {
+        Some(signaling_envelope::Flow::ServerToActr(msg)) => {
+            assert!(matches!(
+                msg.payload,
+                Some(signaling_to_actr::Payload::Pong(_))
+            ));
+        }
+        other => panic!("unexpected response flow: {other:?}"),
+    }
+
+    // 声明的 actor_id 与 token 不一致时拒绝升级
+    let mut forged_id = register_ok.actr_id.clone();
+    forged_id.serial_number = forged_id.serial_number.wrapping_add(1);
+    let forged_url = format!(
+        "{ws_url}?actor_id={}&token={}&token_key_id={}",
+        urlencoding::encode(&forged_id.to_string_repr()),
+        urlencoding::encode(&token_b64),
+        register_ok.credential.token_key_id
+    );
+    assert!(
+        connect_async(&forged_url).await.is_err(),
+        "forged url identity should be rejected before upgrade"
+    );
+
+    harness.shutdown();
+}
+
 #[tokio::test]
 #[serial]
 async fn signaling_peer_payload_none_is_ignored_and_connection_remains_usable() {
@@ -3746,3 +3852,134 @@ async fn service_registry_persists_across_restart() {
 
     graceful_shutdown(child2);
 }
+
+fn write_memory_storage_config(dir: &PathBuf, port: u16, grpc_port: u16) -> PathBuf {
+    let data_dir = dir.join("data");
+    fs::create_dir_all(&data_dir).expect("create data dir");
+    let config_path = dir.join("config.toml");
+    let mut f = fs::File::create(&config_path).expect("create config file");
+    writeln!(
+        f,
+        r#"
+name = "actrix-memory-storage-test"
+enable = 24  # ENABLE_AIS | ENABLE_KS
+env = "dev"
+sqlite_path = "{sqlite}"
+storage = "memory"
+actrix_shared_key = "{shared}"
+
+[bind]
+[bind.http]
+domain_name = "localhost"
+advertised_ip = "127.0.0.1"
+ip = "127.0.0.1"
+port = {port}
+
+[services.ks]
+[services.ks.storage]
+backend = "sqlite"
+key_ttl_seconds = 3600
+[services.ks.storage.sqlite]
+[services.ks.audit]
+enabled = true
+[services.ks.grpc.bind]
+port = {grpc_port}
+
+[services.ais]
+
+[observability.log]
+output = "console"
+level = "info"
+
+[process]
+pid = "{pid}"
+"#,
+        sqlite = data_dir.display(),
+        shared = ACTRIX_SHARED_KEY,
+        port = port,
+        grpc_port = grpc_port,
+        pid = dir.join("actrix.pid").display()
+    )
+    .expect("write config");
+    config_path
+}
+
+#[tokio::test]
+#[serial]
+async fn memory_storage_creates_no_files_in_data_dir() {
+    let tmp = tempfile::tempdir().expect("temp dir");
+    let port = choose_port();
+    let config_path = write_memory_storage_config(&tmp.path().to_path_buf(), port, choose_port());
+    let log_path = tmp.path().join("actrix_memory.log");
+    let data_dir = tmp.path().join("data");
+    let mut child = spawn_actrix(&config_path, &log_path);
+
+    let base = format!("http://127.0.0.1:{port}");
+    wait_for_health(&format!("{base}/ks/health"), &mut child, &log_path).await;
+    wait_for_health(&format!("{base}/ais/health"), &mut child, &log_path).await;
+
+    // AIS 密钥与吊销记录、KS 密钥与审计日志均保存在内存中
+    let files: Vec<_> = fs::read_dir(&data_dir)
+        .expect("read data dir")
+        .map(|entry| entry.expect("dir entry").file_name())
+        .collect();
+    graceful_shutdown(child);
+    assert!(
+        files.is_empty(),
+        "memory storage created files in data dir: {files:?}"
+    );
+}
+
+/// 等待端口进入期望的状态（监听器在后台任务中绑定/关闭），返回最终是否可连接
+async fn wait_for_port(addr: &str, open: bool) -> bool {
+    for _ in 0..50 {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() == open {
+            return open;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    !open
+}
+
+#[tokio::test]
+#[serial]
+async fn disabling_ks_at_runtime_closes_grpc_port() {
+    let tmp = tempfile::tempdir().expect("temp dir");
+    let port = choose_port();
+    let grpc_port = choose_port();
+    let config_path = write_memory_storage_config(&tmp.path().to_path_buf(), port, grpc_port);
+    let log_path = tmp.path().join("actrix_ks_toggle.log");
+    let mut child = spawn_actrix(&config_path, &log_path);
+
+    let base = format!("http://127.0.0.1:{port}");
+    wait_for_health(&format!("{base}/ks/health"), &mut child, &log_path).await;
+    let grpc_addr = format!("127.0.0.1:{grpc_port}");
+    assert!(
+        wait_for_port(&grpc_addr, true).await,
+        "KS gRPC port should be open at startup"
+    );
+
+    let client = reqwest::Client::new();
+    let toggle = |action: &'static str| {
+        client
+            .post(format!("{base}/admin/services/ks/{action}"))
+            .bearer_auth(ACTRIX_SHARED_KEY)
+            .send()
+    };
+
+    let resp = toggle("disable").await.expect("disable ks");
+    assert!(resp.status().is_success(), "disable failed: {resp:?}");
+    assert!(
+        !wait_for_port(&grpc_addr, false).await,
+        "KS gRPC port should be closed after disable"
+    );
+
+    let resp = toggle("enable").await.expect("enable ks");
+    assert!(resp.status().is_success(), "enable failed: {resp:?}");
+    assert!(
+        wait_for_port(&grpc_addr, true).await,
+        "KS gRPC port should be re-bound after enable"
+    );
+
+    graceful_shutdown(child);
+}
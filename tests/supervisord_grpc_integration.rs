@@ -1,8 +1,8 @@
 use actrix::service::SupervisordGrpcService;
 use actrix_common::{
     ServiceCollector,
-    config::SupervisorConfig,
     config::supervisor::{SupervisorClientConfig, SupervisordConfig},
+    config::{NonceStorageConfig, SupervisorConfig},
     realm::{Realm as RealmEntity, RealmConfig},
     storage::db::set_db_path,
 };
@@ -16,8 +16,8 @@ use std::{
 use supervit::{
     ConfigType, CreateRealmRequest, DeleteRealmRequest, GetConfigRequest, GetNodeInfoRequest,
     GetRealmRequest, ListRealmsRequest, REALM_ENABLED_KEY, REALM_USE_SERVERS_KEY,
-    REALM_VERSION_KEY, ResourceType, ShutdownRequest, SupervisedServiceClient, UpdateConfigRequest,
-    UpdateRealmRequest,
+    REALM_VERSION_KEY, ResourceType, ShutdownRequest, StreamLogsRequest, SupervisedServiceClient,
+    UpdateConfigRequest, UpdateRealmRequest,
 };
 use tokio::sync::{OnceCell, broadcast};
 use tokio::task::JoinHandle;
@@ -87,6 +87,9 @@ fn build_supervisor_config(port: u16) -> SupervisorConfig {
         connect_timeout_secs: 5,
         status_report_interval_secs: 5,
         health_check_interval_secs: 5,
+        max_reconnect_delay_secs: 5,
+        report_buffer_size: 0,
+        metrics_sample_interval_secs: 1,
         enable_tls: false,
         tls_domain: None,
         client_cert: None,
@@ -102,8 +105,10 @@ fn build_supervisor_config(port: u16) -> SupervisorConfig {
         client: SupervisorClientConfig {
             node_id: TEST_NODE_ID.into(),
             endpoint: "http://127.0.0.1:1".into(),
+            fallback_endpoints: Vec::new(),
             shared_secret: TEST_SHARED_SECRET.into(),
         },
+        update: None,
     }
 }
 
@@ -160,6 +165,7 @@ async fn start_supervisord_service() -> RunningServer {
     let mut service = SupervisordGrpcService::new(
         build_supervisor_config(port),
         temp.path().to_path_buf(),
+        NonceStorageConfig::default(),
         TEST_LOCATION_TAG.to_string(),
         service_collector,
     );
@@ -288,6 +294,9 @@ async fn supervisord_grpc_covers_config_realm_nodeinfo_shutdown_and_auth_rejecti
             ),
             version: 11,
             expires_at: (chrono::Utc::now().timestamp() + 3600) as u64,
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("create realm should succeed")
@@ -310,6 +319,9 @@ async fn supervisord_grpc_covers_config_realm_nodeinfo_shutdown_and_auth_rejecti
             ),
             version: 12,
             expires_at: (chrono::Utc::now().timestamp() + 3600) as u64,
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("duplicate create should still return response")
@@ -342,6 +354,9 @@ async fn supervisord_grpc_covers_config_realm_nodeinfo_shutdown_and_auth_rejecti
                 &shared_secret,
                 &format!("update_realm:{TEST_NODE_ID}:{realm_id}"),
             ),
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("update realm should succeed")
@@ -363,6 +378,9 @@ async fn supervisord_grpc_covers_config_realm_nodeinfo_shutdown_and_auth_rejecti
                 &shared_secret,
                 &format!("update_realm:{TEST_NODE_ID}:{missing_realm_id}"),
             ),
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("update missing realm should return response")
@@ -396,6 +414,7 @@ async fn supervisord_grpc_covers_config_realm_nodeinfo_shutdown_and_auth_rejecti
                 &shared_secret,
                 &format!("delete_realm:{TEST_NODE_ID}:{realm_id}"),
             ),
+            dry_run: None,
         })
         .await
         .expect("delete realm should succeed")
@@ -423,6 +442,7 @@ async fn supervisord_grpc_covers_config_realm_nodeinfo_shutdown_and_auth_rejecti
                 &shared_secret,
                 &format!("delete_realm:{TEST_NODE_ID}:{realm_id}"),
             ),
+            dry_run: None,
         })
         .await
         .expect("delete deleted realm should return response")
@@ -606,6 +626,9 @@ async fn supervisord_grpc_tolerates_corrupted_use_servers_metadata() {
             ),
             version: 42,
             expires_at: (chrono::Utc::now().timestamp() + 3600) as u64,
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("create realm should succeed")
@@ -657,6 +680,7 @@ async fn supervisord_grpc_tolerates_corrupted_use_servers_metadata() {
                 &server.shared_secret,
                 &format!("delete_realm:{TEST_NODE_ID}:{realm_id}"),
             ),
+            dry_run: None,
         })
         .await
         .expect("delete realm should succeed")
@@ -685,6 +709,9 @@ async fn supervisord_grpc_tolerates_corrupted_enabled_and_version_metadata() {
             ),
             version: 77,
             expires_at: (chrono::Utc::now().timestamp() + 3600) as u64,
+            dry_run: None,
+            rate_limits: None,
+            quota: None,
         })
         .await
         .expect("create realm should succeed")
@@ -749,6 +776,7 @@ async fn supervisord_grpc_tolerates_corrupted_enabled_and_version_metadata() {
                 &server.shared_secret,
                 &format!("delete_realm:{TEST_NODE_ID}:{realm_id}"),
             ),
+            dry_run: None,
         })
         .await
         .expect("delete realm should succeed")
@@ -757,3 +785,66 @@ async fn supervisord_grpc_tolerates_corrupted_enabled_and_version_metadata() {
 
     stop_supervisord_service(server).await;
 }
+
+#[tokio::test]
+#[serial]
+async fn supervisord_grpc_streams_recent_logs_with_level_filter() {
+    let mut server = start_supervisord_service().await;
+    let buffer = supervit::logs::global();
+    let now = chrono::Utc::now().timestamp_millis();
+    buffer.push(
+        now,
+        tracing::Level::DEBUG,
+        "stream_logs_test",
+        "debug entry".to_string(),
+    );
+    buffer.push(
+        now,
+        tracing::Level::WARN,
+        "stream_logs_test",
+        "warn entry".to_string(),
+    );
+
+    let credential = build_credential_for_payload(
+        &server.shared_secret,
+        &format!("stream_logs:{TEST_NODE_ID}"),
+    );
+    let mut stream = server
+        .client
+        .stream_logs(StreamLogsRequest {
+            min_level: Some("info".to_string()),
+            target: Some("stream_logs_test".to_string()),
+            limit: Some(10),
+            follow: Some(false),
+            credential,
+        })
+        .await
+        .expect("stream logs")
+        .into_inner();
+
+    let mut entries = Vec::new();
+    while let Some(entry) = stream.message().await.expect("read log entry") {
+        entries.push(entry);
+    }
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].level, "WARN");
+    assert_eq!(entries[0].message, "warn entry");
+
+    let bad_level = server
+        .client
+        .stream_logs(StreamLogsRequest {
+            min_level: Some("verbose".to_string()),
+            target: None,
+            limit: None,
+            follow: None,
+            credential: build_credential_for_payload(
+                &server.shared_secret,
+                &format!("stream_logs:{TEST_NODE_ID}"),
+            ),
+        })
+        .await
+        .expect_err("unknown level should be rejected");
+    assert_eq!(bad_level.code(), Code::InvalidArgument);
+
+    stop_supervisord_service(server).await;
+}